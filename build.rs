@@ -103,9 +103,18 @@ fn main() {
         println!("cargo:rerun-if-changed=proto/dnstap.proto");
         println!("cargo:rerun-if-changed=proto/ddsketch.proto");
         println!("cargo:rerun-if-changed=proto/vector.proto");
+        println!("cargo:rerun-if-changed=proto/google/pubsub/v1/pubsub.proto");
+        println!("cargo:rerun-if-changed=proto/opentelemetry");
+        println!("cargo:rerun-if-changed=proto/spiffe/workload.proto");
 
         let mut prost_build = prost_build::Config::new();
         prost_build.btree_map(&["."]);
+        // The OpenTelemetry protocol has optional fields, which versions of `protoc` before 3.15
+        // only support with this flag.
+        prost_build.protoc_arg("--experimental_allow_proto3_optional");
+        // The comments of the OpenTelemetry protocol have indented examples, which would be run as
+        // doctests.
+        prost_build.disable_comments(&[".opentelemetry"]);
 
         tonic_build::configure()
             .compile_with_config(
@@ -116,6 +125,11 @@ fn main() {
                     "proto/dnstap.proto",
                     "proto/ddsketch.proto",
                     "proto/dd_trace.proto",
+                    "proto/google/pubsub/v1/pubsub.proto",
+                    "proto/opentelemetry/proto/collector/logs/v1/logs_service.proto",
+                    "proto/opentelemetry/proto/collector/metrics/v1/metrics_service.proto",
+                    "proto/opentelemetry/proto/collector/trace/v1/trace_service.proto",
+                    "proto/spiffe/workload.proto",
                 ],
                 &["proto/", "lib/vector-core/proto/"],
             )
@@ -5,7 +5,7 @@ pub mod tables;
 #[cfg(test)]
 mod test_util;
 mod vrl_util;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, net::IpAddr};
 
 use dyn_clone::DynClone;
 pub use tables::{TableRegistry, TableSearch};
@@ -24,6 +24,26 @@ pub enum Condition<'a> {
         from: chrono::DateTime<chrono::Utc>,
         to: chrono::DateTime<chrono::Utc>,
     },
+    /// The number is within the range from the number in the `from` field to the number in the
+    /// `to` field (inclusive).
+    WithinRange {
+        from: &'a str,
+        to: &'a str,
+        value: f64,
+    },
+    /// The address is within the CIDR network in the field. When several networks contain the
+    /// address, only the most specific ones are returned by `find_table_row`, and the others
+    /// follow them in `find_table_rows`.
+    WithinCidr { field: &'a str, address: IpAddr },
+}
+
+/// An index for the conditions which don't match fields exactly.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LookupIndex {
+    /// Indexes the ranges from the `from` field to the `to` field, for `WithinRange` conditions.
+    Range { from: String, to: String },
+    /// Indexes the CIDR networks in the field, for `WithinCidr` conditions.
+    Cidr { field: String },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -69,6 +89,23 @@ pub trait Table: DynClone {
     /// Returns a list of the field names that are in each index
     fn index_fields(&self) -> Vec<(Case, Vec<String>)>;
 
+    /// Hints to the enrichment table that data is going to be searched by range or CIDR
+    /// containment, to allow it to index the data in advance. The table uses the index whenever
+    /// it is searched with the matching condition.
+    ///
+    /// Tables that don't support these indexes search their data without them.
+    ///
+    /// # Errors
+    /// Errors if the fields are not in the table.
+    fn add_lookup_index(&mut self, _index: &LookupIndex) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returns the range and CIDR indexes of the table.
+    fn lookup_indexes(&self) -> Vec<LookupIndex> {
+        Vec::new()
+    }
+
     /// Returns true if the underlying data has changed and the table needs reloading.
     fn needs_reload(&self) -> bool;
 }
@@ -36,7 +36,7 @@ use std::{
 
 use arc_swap::ArcSwap;
 
-use super::{Condition, IndexHandle, Table};
+use super::{Condition, IndexHandle, LookupIndex, Table};
 use crate::Case;
 
 /// A hashmap of name => implementation of an enrichment table.
@@ -150,6 +150,25 @@ impl TableRegistry {
         }
     }
 
+    /// Adds a range or CIDR index to the given Enrichment Table.
+    ///
+    /// If we are in the reading stage, this function will error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Mutex is poisoned.
+    pub fn add_lookup_index(&mut self, table: &str, index: &LookupIndex) -> Result<(), String> {
+        let mut locked = self.loading.lock().unwrap();
+
+        match *locked {
+            None => Err("finish_load has been called".to_string()),
+            Some(ref mut tables) => match tables.get_mut(table) {
+                None => Err(format!("table '{}' not loaded", table)),
+                Some(table) => table.add_lookup_index(index),
+            },
+        }
+    }
+
     /// Returns a cheaply clonable struct through that provides lock free read
     /// access to the enrichment tables.
     pub fn as_readonly(&self) -> TableSearch {
@@ -168,6 +187,18 @@ impl TableRegistry {
         }
     }
 
+    /// Returns the range and CIDR indexes that have been applied to the given table, to reapply
+    /// them to the reloaded table along with the `index_fields`.
+    pub fn lookup_indexes(&self, table: &str) -> Vec<LookupIndex> {
+        match &**self.tables.load() {
+            Some(tables) => tables
+                .get(table)
+                .map(|table| table.lookup_indexes())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
     /// Checks if the table needs reloading.
     /// If in doubt (the table isn't in our list) we return true.
     pub fn needs_reload(&self, table: &str) -> bool {
@@ -6,7 +6,7 @@ use vrl::{
     prelude::*,
 };
 
-use crate::{Case, Condition, IndexHandle, TableRegistry};
+use crate::{Case, Condition, IndexHandle, LookupIndex, TableRegistry};
 
 #[derive(Debug)]
 pub enum Error {
@@ -40,12 +40,60 @@ impl DiagnosticError for Error {
     }
 }
 
+/// Returns the object of a condition given as an object literal, such as `{"from": .., "to": ..}`.
+fn condition_object(value: &expression::Expr) -> Option<&BTreeMap<String, expression::Expr>> {
+    match value {
+        expression::Expr::Container(expression::Container {
+            variant: expression::Variant::Object(map),
+        }) => Some(map),
+        _ => None,
+    }
+}
+
+/// Returns the field ending the range of a `{"contains": .., "end": ".."}` condition. The field
+/// must be a string literal, so that the ranges can be indexed when the program is compiled.
+fn range_end(map: &BTreeMap<String, expression::Expr>) -> Option<std::result::Result<&str, ()>> {
+    map.get("end").map(|end| match end {
+        expression::Expr::Literal(expression::Literal::String(end)) => {
+            std::str::from_utf8(end).map_err(|_| ())
+        }
+        _ => Err(()),
+    })
+}
+
 /// Evaluates the condition object to search the enrichment tables with.
 pub(crate) fn evaluate_condition<'a>(
     ctx: &mut Context,
     key: &'a str,
-    value: &expression::Expr,
+    value: &'a expression::Expr,
 ) -> Result<Condition<'a>> {
+    if let Some(map) = condition_object(value).filter(|map| map.contains_key("contains")) {
+        let contains = map
+            .get("contains")
+            .expect("should contain contains")
+            .resolve(ctx)?;
+
+        return Ok(match range_end(map) {
+            Some(end) => Condition::WithinRange {
+                from: key,
+                to: end.map_err(|_| "end in condition must be a string literal")?,
+                value: match contains {
+                    Value::Integer(value) => value as f64,
+                    Value::Float(value) => value.into_inner(),
+                    _ => return Err("contains in condition must be a number".into()),
+                },
+            },
+            None => Condition::WithinCidr {
+                field: key,
+                address: contains
+                    .as_bytes()
+                    .and_then(|address| std::str::from_utf8(address).ok())
+                    .and_then(|address| address.parse().ok())
+                    .ok_or("contains in condition must be an IP address")?,
+            },
+        });
+    }
+
     let value = value.resolve(ctx)?;
 
     Ok(match value {
@@ -68,23 +116,40 @@ pub(crate) fn evaluate_condition<'a>(
     })
 }
 
-/// Add an index for the given condition to the given enrichment table.
+/// Add an index for the given condition to the given enrichment table, along with the range and
+/// CIDR indexes for its `contains` conditions.
 pub(crate) fn add_index(
     registry: &mut TableRegistry,
     tablename: &str,
     case: Case,
     condition: &BTreeMap<String, expression::Expr>,
 ) -> std::result::Result<IndexHandle, ExpressionError> {
+    let mut lookup_indexes = Vec::new();
     let fields = condition
         .iter()
-        .filter_map(|(field, value)| match value {
-            expression::Expr::Container(expression::Container {
-                variant: expression::Variant::Object(map),
-            }) if map.contains_key("from") && map.contains_key("to") => None,
+        .filter_map(|(field, value)| match condition_object(value) {
+            Some(map) if map.contains_key("from") && map.contains_key("to") => None,
+            Some(map) if map.contains_key("contains") => {
+                match range_end(map) {
+                    Some(Ok(end)) => lookup_indexes.push(LookupIndex::Range {
+                        from: field.clone(),
+                        to: end.to_owned(),
+                    }),
+                    // The condition fails when the program runs.
+                    Some(Err(_)) => (),
+                    None => lookup_indexes.push(LookupIndex::Cidr {
+                        field: field.clone(),
+                    }),
+                }
+                None
+            }
             _ => Some(field.as_ref()),
         })
         .collect::<Vec<_>>();
     let index = registry.add_index(tablename, case, &fields)?;
+    for lookup_index in lookup_indexes {
+        registry.add_lookup_index(tablename, &lookup_index)?;
+    }
 
     Ok(index)
 }
@@ -135,4 +200,44 @@ mod tests {
         let indexes = indexes.lock().unwrap();
         assert_eq!(vec![vec!["field1".to_string()]], *indexes);
     }
+
+    #[test]
+    fn evaluates_contains_conditions() {
+        let tz = vector_common::TimeZone::default();
+        let mut object: Value = BTreeMap::new().into();
+        let mut runtime_state = vrl::state::Runtime::default();
+        let mut ctx = Context::new(&mut object, &mut runtime_state, &tz);
+
+        let range: expression::Expr = expression::Container::new(expression::Variant::Object(
+            btreemap! {
+                "contains" => expression::Literal::from(8080),
+                "end" => expression::Literal::from("port_end"),
+            }
+            .into(),
+        ))
+        .into();
+        assert_eq!(
+            Condition::WithinRange {
+                from: "port_start",
+                to: "port_end",
+                value: 8080.0,
+            },
+            evaluate_condition(&mut ctx, "port_start", &range).unwrap()
+        );
+
+        let cidr: expression::Expr = expression::Container::new(expression::Variant::Object(
+            btreemap! {
+                "contains" => expression::Literal::from("10.1.2.3"),
+            }
+            .into(),
+        ))
+        .into();
+        assert_eq!(
+            Condition::WithinCidr {
+                field: "network",
+                address: "10.1.2.3".parse().unwrap(),
+            },
+            evaluate_condition(&mut ctx, "network", &cidr).unwrap()
+        );
+    }
 }
@@ -10,9 +10,27 @@ use line::{Line, Metric, MetricKind};
 
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
+/// The bit pattern of the NaN value Prometheus writes as the sample of a series that has gone
+/// stale, distinct from the NaN values the series may have themselves.
+pub const STALE_NAN_BITS: u64 = 0x7ff0_0000_0000_0002;
+
+/// The value marking a series as stale.
+pub fn stale_marker() -> f64 {
+    f64::from_bits(STALE_NAN_BITS)
+}
+
+pub fn is_stale_marker(value: f64) -> bool {
+    value.to_bits() == STALE_NAN_BITS
+}
+
 pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
 
+    /// The protobuf exposition format, served by the instrumented applications.
+    pub mod client {
+        include!(concat!(env!("OUT_DIR"), "/io.prometheus.client.rs"));
+    }
+
     pub use metric_metadata::MetricType;
 
     impl MetricType {
@@ -171,7 +189,7 @@ impl GroupKind {
                     let (_, bucket) = line::Metric::parse_value(&bucket)
                         .map_err(Into::into)
                         .context(ParseLabelValueSnafu)?;
-                    let count = try_f64_to_u32(metric.value)?;
+                    let count = try_count(metric.value)?;
                     matching_group(metrics, key)
                         .buckets
                         .push(HistogramBucket { bucket, count });
@@ -181,7 +199,7 @@ impl GroupKind {
                     matching_group(metrics, key).sum = sum;
                 }
                 "_count" => {
-                    let count = try_f64_to_u32(metric.value)?;
+                    let count = try_count(metric.value)?;
                     matching_group(metrics, key).count = count;
                 }
                 _ => {
@@ -212,7 +230,7 @@ impl GroupKind {
                     matching_group(metrics, key).sum = sum;
                 }
                 "_count" => {
-                    let count = try_f64_to_u32(metric.value)?;
+                    let count = try_count(metric.value)?;
                     matching_group(metrics, key).count = count;
                 }
                 _ => {
@@ -243,6 +261,16 @@ fn try_f64_to_u32(f: f64) -> Result<u32, ParserError> {
     }
 }
 
+/// The counts of stale histograms and summaries can't be represented, so they are zero, and only
+/// their sum keeps the stale marker.
+fn try_count(value: f64) -> Result<u32, ParserError> {
+    if is_stale_marker(value) {
+        Ok(0)
+    } else {
+        try_f64_to_u32(value)
+    }
+}
+
 impl MetricGroup {
     fn new(name: String, kind: MetricKind) -> Self {
         let metrics = GroupKind::new(kind);
@@ -838,4 +866,30 @@ mod test {
             assert_eq!(metrics.get_index(0).unwrap(), simple_metric!(Some(1395066367700), labels!(), 24.0));
         });
     }
+
+    #[test]
+    fn parse_request_stale_histogram() {
+        let mut request = write_request!(
+            ["one" = Histogram],
+            [
+                [__name__ => "one_bucket", le => "1"] => [ 0 @ 1395066367700 ],
+                [__name__ => "one_bucket", le => "+Inf"] => [ 0 @ 1395066367700 ],
+                [__name__ => "one_count"] => [ 0 @ 1395066367700 ],
+                [__name__ => "one_sum"] => [ 0 @ 1395066367700 ]
+            ]
+        );
+        for timeseries in &mut request.timeseries {
+            timeseries.samples[0].value = stale_marker();
+        }
+        let parsed = parse_request(request).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        match_group!(parsed[0], "one", Histogram => |metrics: &MetricMap<HistogramMetric>| {
+            let (_, metric) = metrics.get_index(0).unwrap();
+            assert_eq!(metric.buckets.len(), 2);
+            assert!(metric.buckets.iter().all(|bucket| bucket.count == 0));
+            assert_eq!(metric.count, 0);
+            assert!(is_stale_marker(metric.sum));
+        });
+    }
 }
@@ -6,6 +6,8 @@ mod comparison;
 mod conversion;
 pub mod find;
 pub mod insert;
+#[cfg(feature = "json")]
+mod json;
 pub mod merge;
 pub mod nest;
 pub mod remove;
@@ -13,6 +15,8 @@ pub mod remove;
 use crate::Value;
 pub use builder::EmptyKindError;
 pub use collection::{Collection, Field, Index, Unknown};
+#[cfg(feature = "json")]
+pub use json::JsonKindError;
 use std::collections::BTreeMap;
 
 /// The type (kind) of a given value.
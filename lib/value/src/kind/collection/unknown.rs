@@ -123,6 +123,27 @@ impl Unknown {
     }
 }
 
+#[cfg(feature = "json")]
+impl Unknown {
+    /// Get the kind of which the values, and the values nested in them, can only be of the states
+    /// set on `kind`, regardless of its collections.
+    #[must_use]
+    pub(crate) fn recursive(kind: &Kind) -> Kind {
+        Infinite {
+            bytes: kind.bytes,
+            integer: kind.integer,
+            float: kind.float,
+            boolean: kind.boolean,
+            timestamp: kind.timestamp,
+            regex: kind.regex,
+            null: kind.null,
+            array: kind.array.as_ref().map(|_| ()),
+            object: kind.object.as_ref().map(|_| ()),
+        }
+        .into()
+    }
+}
+
 impl From<Unknown> for Kind {
     fn from(unknown: Unknown) -> Self {
         match unknown.0 {
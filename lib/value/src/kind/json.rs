@@ -0,0 +1,306 @@
+//! Conversions of [`Kind`] to and from JSON, used to declare the kind of the events a VRL program
+//! handles, and to print the kind of the events it emits.
+//!
+//! A kind is either `"any"`, `"json"`, or an object with a `true` key for each of its primitive
+//! states (`bytes`, `integer`, `float`, `boolean`, `timestamp`, `regex` and `null`), and a
+//! collection for each of its `array` and `object` states. A collection is an object with the
+//! kinds of its `known` indices or fields, and the kind of its `unknown` ones, `null` or missing
+//! when there are none. `true` is the collection of which all elements are of any kind.
+//!
+//! The kind of the unknown elements can also be `{"recursive": [...]}`, the kind of which the
+//! values, and the values nested in them, can only be of the listed states.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value as JsonValue};
+
+use super::{Collection, Field, Index, Kind, Unknown};
+
+impl Kind {
+    /// Converts the kind to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> JsonValue {
+        if self.is_any()
+            && self.array.as_ref().map_or(false, Collection::is_any)
+            && self.object.as_ref().map_or(false, Collection::is_any)
+        {
+            return JsonValue::from("any");
+        }
+
+        let mut json = Map::new();
+        for state in states(self) {
+            json.insert(state.to_owned(), JsonValue::Bool(true));
+        }
+        if let Some(array) = &self.array {
+            json.insert("array".to_owned(), collection_to_json(array));
+        }
+        if let Some(object) = &self.object {
+            json.insert("object".to_owned(), collection_to_json(object));
+        }
+
+        JsonValue::Object(json)
+    }
+
+    /// Converts the JSON to a kind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON doesn't describe a kind, or describes a kind with no states.
+    pub fn from_json(json: &JsonValue) -> Result<Self, JsonKindError> {
+        let states = match json {
+            JsonValue::String(kind) if kind == "any" => return Ok(Self::any()),
+            JsonValue::String(kind) if kind == "json" => return Ok(Self::json()),
+            JsonValue::Object(states) => states,
+            _ => {
+                return Err(JsonKindError(format!(
+                    "expected \"any\", \"json\" or an object of states, got {}",
+                    json
+                )))
+            }
+        };
+
+        let mut kind = Self::empty();
+        for (state, value) in states {
+            if state != "array" && state != "object" && value != &JsonValue::Bool(true) {
+                return Err(JsonKindError(format!(
+                    "expected state {:?} to be true, got {}",
+                    state, value
+                )));
+            }
+
+            match state.as_str() {
+                "bytes" => kind.add_bytes(),
+                "integer" => kind.add_integer(),
+                "float" => kind.add_float(),
+                "boolean" => kind.add_boolean(),
+                "timestamp" => kind.add_timestamp(),
+                "regex" => kind.add_regex(),
+                "null" => kind.add_null(),
+                "array" => kind.add_array(collection_from_json(value, |index| {
+                    index.parse::<usize>().ok().map(Index::from)
+                })?),
+                "object" => kind.add_object(collection_from_json(value, |field| {
+                    Some(Field::from(field))
+                })?),
+                _ => return Err(JsonKindError(format!("unknown state {:?}", state))),
+            };
+        }
+
+        if kind.is_empty() {
+            return Err(JsonKindError("the kind has no states".to_owned()));
+        }
+
+        Ok(kind)
+    }
+}
+
+/// The primitive states of the kind.
+fn states(kind: &Kind) -> Vec<&'static str> {
+    [
+        ("bytes", kind.contains_bytes()),
+        ("integer", kind.contains_integer()),
+        ("float", kind.contains_float()),
+        ("boolean", kind.contains_boolean()),
+        ("timestamp", kind.contains_timestamp()),
+        ("regex", kind.contains_regex()),
+        ("null", kind.contains_null()),
+    ]
+    .into_iter()
+    .filter_map(|(state, contained)| contained.then(|| state))
+    .collect()
+}
+
+fn collection_to_json<T: Ord + std::fmt::Display>(collection: &Collection<T>) -> JsonValue {
+    if collection.is_any() && collection.known().is_empty() {
+        return JsonValue::Bool(true);
+    }
+
+    let known = collection
+        .known()
+        .iter()
+        .map(|(key, kind)| (key.to_string(), kind.to_json()))
+        .collect::<Map<_, _>>();
+
+    let mut json = Map::new();
+    json.insert("known".to_owned(), JsonValue::Object(known));
+    json.insert(
+        "unknown".to_owned(),
+        collection
+            .unknown()
+            .map_or(JsonValue::Null, unknown_to_json),
+    );
+
+    JsonValue::Object(json)
+}
+
+fn unknown_to_json(unknown: &Unknown) -> JsonValue {
+    if let Some(kind) = unknown.as_exact() {
+        return kind.to_json();
+    }
+    if unknown.is_any() {
+        return JsonValue::from("any");
+    }
+    if unknown.is_json() {
+        return JsonValue::from("json");
+    }
+
+    // The infinite kinds nest themselves, so only their states are listed.
+    let kind = unknown.to_kind();
+    let mut recursive = states(&kind);
+    if kind.contains_array() {
+        recursive.push("array");
+    }
+    if kind.contains_object() {
+        recursive.push("object");
+    }
+
+    let mut json = Map::new();
+    json.insert("recursive".to_owned(), JsonValue::from(recursive));
+    JsonValue::Object(json)
+}
+
+fn collection_from_json<T: Ord>(
+    json: &JsonValue,
+    key: impl Fn(&str) -> Option<T>,
+) -> Result<Collection<T>, JsonKindError> {
+    let collection = match json {
+        JsonValue::Bool(true) => return Ok(Collection::any()),
+        JsonValue::Object(collection) => collection,
+        _ => {
+            return Err(JsonKindError(format!(
+                "expected true or an object of known and unknown kinds, got {}",
+                json
+            )))
+        }
+    };
+
+    let known = match collection.get("known") {
+        None => BTreeMap::new(),
+        Some(JsonValue::Object(known)) => known
+            .iter()
+            .map(|(name, kind)| {
+                let name = key(name)
+                    .ok_or_else(|| JsonKindError(format!("invalid array index {:?}", name)))?;
+                Ok::<_, JsonKindError>((name, Kind::from_json(kind)?))
+            })
+            .collect::<Result<_, _>>()?,
+        Some(known) => {
+            return Err(JsonKindError(format!(
+                "expected an object of known kinds, got {}",
+                known
+            )))
+        }
+    };
+
+    let unknown = match collection.get("unknown") {
+        None | Some(JsonValue::Null) => None,
+        Some(JsonValue::Object(unknown)) if unknown.contains_key("recursive") => {
+            Some(recursive_from_json(&unknown["recursive"])?)
+        }
+        Some(unknown) => Some(Kind::from_json(unknown)?),
+    };
+
+    Ok(Collection::from_parts(known, unknown))
+}
+
+fn recursive_from_json(json: &JsonValue) -> Result<Kind, JsonKindError> {
+    let states = json
+        .as_array()
+        .ok_or_else(|| JsonKindError(format!("expected a list of states, got {}", json)))?
+        .iter()
+        .map(|state| {
+            (
+                state.as_str().unwrap_or_default().to_owned(),
+                JsonValue::Bool(true),
+            )
+        })
+        .collect::<Map<_, _>>();
+
+    Kind::from_json(&JsonValue::Object(states)).map(|kind| Unknown::recursive(&kind))
+}
+
+/// The error returned when converting JSON which doesn't describe a [`Kind`].
+#[derive(Debug)]
+pub struct JsonKindError(String);
+
+impl std::fmt::Display for JsonKindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for JsonKindError {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn converts_kinds() {
+        let kind = Kind::object(BTreeMap::from([
+            (Field::from("message"), Kind::bytes()),
+            (
+                Field::from("tags"),
+                Kind::array(Collection::from_unknown(Kind::bytes())).or_null(),
+            ),
+        ]));
+        let json = json!({
+            "object": {
+                "known": {
+                    "message": { "bytes": true },
+                    "tags": {
+                        "null": true,
+                        "array": { "known": {}, "unknown": { "bytes": true } },
+                    },
+                },
+                "unknown": null,
+            },
+        });
+
+        assert_eq!(kind.to_json(), json);
+        assert_eq!(Kind::from_json(&json).unwrap(), kind);
+    }
+
+    #[test]
+    fn converts_any_kinds() {
+        assert_eq!(Kind::any().to_json(), json!("any"));
+        assert_eq!(Kind::from_json(&json!("any")).unwrap(), Kind::any());
+        assert_eq!(
+            Kind::object(Collection::any()).to_json(),
+            json!({ "object": true })
+        );
+        assert_eq!(
+            Kind::from_json(&json!({ "object": true })).unwrap(),
+            Kind::object(Collection::any())
+        );
+        assert_eq!(
+            Kind::object(Collection::json()).to_json(),
+            json!({ "object": { "known": {}, "unknown": "json" } })
+        );
+    }
+
+    #[test]
+    fn converts_recursive_kinds() {
+        let json = json!({ "array": { "unknown": { "recursive": ["bytes", "array"] } } });
+        let kind = Kind::from_json(&json).unwrap();
+
+        let nested = kind.as_array().unwrap().unknown().unwrap().to_kind();
+        assert!(nested.contains_bytes() && nested.contains_array());
+        assert!(!nested.contains_object());
+        assert_eq!(
+            nested.as_array().unwrap().unknown().unwrap().to_kind(),
+            nested
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_kinds() {
+        assert!(Kind::from_json(&json!({})).is_err());
+        assert!(Kind::from_json(&json!({ "bytes": false })).is_err());
+        assert!(Kind::from_json(&json!({ "string": true })).is_err());
+        assert!(Kind::from_json(&json!({ "array": { "known": { "foo": "any" } } })).is_err());
+        assert!(Kind::from_json(&json!(42)).is_err());
+    }
+}
@@ -1,35 +1,96 @@
 use anyhow::Context;
 use graphql_client::GraphQLQuery;
 use indoc::indoc;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    StatusCode,
+};
 use url::Url;
 
 /// Wrapped `Result` type, that returns deserialized GraphQL response data.
 pub type QueryResult<T> =
     anyhow::Result<graphql_client::Response<<T as GraphQLQuery>::ResponseData>>;
 
+/// Options for connecting to a Vector API server which requires credentials, or which serves TLS
+/// with a certificate the system doesn't trust.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectOptions {
+    /// Value of the `Authorization` header sent with each request, matching the server's
+    /// `api.auth` credentials.
+    pub authorization: Option<String>,
+    /// PEM encoded certificate of an authority to trust, in addition to the system's ones.
+    pub ca_certificate: Option<Vec<u8>>,
+}
+
 /// GraphQL query client over HTTP.
 #[derive(Debug)]
 pub struct Client {
     url: Url,
+    client: reqwest::Client,
 }
 
 impl Client {
     /// Returns a new GraphQL query client, bound to the provided URL.
     pub fn new(url: Url) -> Self {
-        Self { url }
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Returns a new GraphQL query client, bound to the provided URL, which connects with the
+    /// given options.
+    pub fn with_options(url: Url, options: &ConnectOptions) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(authorization) = &options.authorization {
+            let mut headers = HeaderMap::new();
+            let mut value =
+                HeaderValue::from_str(authorization).context("Invalid API credentials")?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+            builder = builder.default_headers(headers);
+        }
+        if let Some(pem) = &options.ca_certificate {
+            let certificate =
+                reqwest::Certificate::from_pem(pem).context("Invalid CA certificate")?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(Self {
+            url,
+            client: builder.build().context("Couldn't create HTTP client")?,
+        })
     }
 
-    pub async fn new_with_healthcheck(url: Url) -> Option<Self> {
+    pub async fn new_with_healthcheck(url: Url, options: &ConnectOptions) -> Option<Self> {
         #![allow(clippy::print_stderr)]
 
         use crate::gql::HealthQueryExt;
 
         // Create a new API client for connecting to the local/remote Vector instance.
-        let client = Self::new(url.clone());
+        let client = match Self::with_options(url.clone(), options) {
+            Ok(client) => client,
+            Err(error) => {
+                eprintln!("Couldn't create Vector API client: {:#}", error);
+                return None;
+            }
+        };
 
         // Check that the GraphQL server is reachable
         match client.health_query().await {
             Ok(_) => Some(client),
+            Err(error)
+                if error
+                    .downcast_ref::<reqwest::Error>()
+                    .and_then(reqwest::Error::status)
+                    == Some(StatusCode::UNAUTHORIZED) =>
+            {
+                eprintln!(
+                    "Vector API server ({}) rejected the request, as it requires credentials matching its `api.auth` settings.",
+                    url
+                );
+                None
+            }
             _ => {
                 eprintln!(
                     indoc! {"
@@ -54,13 +115,12 @@ impl Client {
         &self,
         request_body: &graphql_client::QueryBody<T::Variables>,
     ) -> QueryResult<T> {
-        let client = reqwest::Client::new();
-
-        client
+        self.client
             .post(self.url.clone())
             .json(request_body)
             .send()
             .await
+            .and_then(reqwest::Response::error_for_status)
             .with_context(|| {
                 format!(
                     "Couldn't send '{}' query to {}",
@@ -124,6 +124,45 @@ pub struct ComponentSentEventsThroughputsSubscription;
 )]
 pub struct ComponentSentEventsTotalsSubscription;
 
+/// ComponentErrorsTotalsSubscription contains metrics on the number of errors raised
+/// by a Vector instance, against specific components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_totals.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsTotalsSubscription;
+
+/// ComponentErrorsThroughputsSubscription contains metrics on the number of errors
+/// raised between `interval` samples, against specific components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors_throughputs.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsThroughputsSubscription;
+
+/// ComponentErrorsSubscription streams error messages as they're logged by components.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_errors.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentErrorsSubscription;
+
+/// ComponentBufferUsagesSubscription contains the buffer utilization of sinks, sampled
+/// every `interval`.
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_buffer_usages.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentBufferUsagesSubscription;
+
 impl component_sent_events_totals_subscription::ComponentSentEventsTotalsSubscriptionComponentSentEventsTotals {
     pub fn outputs(&self) -> Vec<(String, i64)> {
         self.outputs
@@ -227,6 +266,29 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentSentEventsThroughputsSubscription>;
+
+    /// Executes a component errors totals subscription.
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
+
+    /// Executes a component errors throughputs subscription.
+    fn component_errors_throughputs_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsThroughputsSubscription>;
+
+    /// Executes a component error messages subscription.
+    fn component_errors_subscription(
+        &self,
+    ) -> crate::BoxedSubscription<ComponentErrorsSubscription>;
+
+    /// Executes a component buffer usages subscription.
+    fn component_buffer_usages_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentBufferUsagesSubscription>;
 }
 
 impl MetricsSubscriptionExt for crate::SubscriptionClient {
@@ -368,4 +430,50 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<ComponentSentEventsThroughputsSubscription>(&request_body)
     }
+
+    /// Executes a component errors totals subscription.
+    fn component_errors_totals_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription> {
+        let request_body = ComponentErrorsTotalsSubscription::build_query(
+            component_errors_totals_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsTotalsSubscription>(&request_body)
+    }
+
+    /// Executes a component errors throughputs subscription.
+    fn component_errors_throughputs_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentErrorsThroughputsSubscription> {
+        let request_body = ComponentErrorsThroughputsSubscription::build_query(
+            component_errors_throughputs_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentErrorsThroughputsSubscription>(&request_body)
+    }
+
+    /// Executes a component error messages subscription.
+    fn component_errors_subscription(
+        &self,
+    ) -> crate::BoxedSubscription<ComponentErrorsSubscription> {
+        let request_body =
+            ComponentErrorsSubscription::build_query(component_errors_subscription::Variables);
+
+        self.start::<ComponentErrorsSubscription>(&request_body)
+    }
+
+    /// Executes a component buffer usages subscription.
+    fn component_buffer_usages_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentBufferUsagesSubscription> {
+        let request_body = ComponentBufferUsagesSubscription::build_query(
+            component_buffer_usages_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentBufferUsagesSubscription>(&request_body)
+    }
 }
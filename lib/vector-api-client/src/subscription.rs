@@ -8,15 +8,27 @@ use futures::SinkExt;
 use graphql_client::GraphQLQuery;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio::sync::{
-    broadcast::{self, Sender},
-    mpsc, oneshot,
+use tokio::{
+    net::TcpStream,
+    sync::{
+        broadcast::{self, Sender},
+        mpsc, oneshot,
+    },
 };
 use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{
+    client_async_tls_with_config,
+    tungstenite::{
+        client::IntoClientRequest,
+        http::{header::AUTHORIZATION, HeaderValue},
+        Error, Message,
+    },
+};
 use url::Url;
 use uuid::Uuid;
 
+use crate::ConnectOptions;
+
 /// Subscription GraphQL response, returned from an active stream.
 pub type BoxedSubscription<T> = Pin<
     Box<
@@ -173,10 +185,47 @@ impl SubscriptionClient {
 /// Connect to a new WebSocket GraphQL server endpoint, and return a `SubscriptionClient`.
 /// This method will a) connect to a ws(s):// endpoint, and perform the initial handshake, and b)
 /// set up channel forwarding to expose just the returned `Payload`s to the client.
-pub async fn connect_subscription_client(
+pub async fn connect_subscription_client(url: Url) -> Result<SubscriptionClient, Error> {
+    connect_subscription_client_with_options(url, &ConnectOptions::default()).await
+}
+
+/// Connect to a new WebSocket GraphQL server endpoint, as [`connect_subscription_client`] does,
+/// with the given options.
+pub async fn connect_subscription_client_with_options(
     url: Url,
-) -> Result<SubscriptionClient, tokio_tungstenite::tungstenite::Error> {
-    let (ws, _) = connect_async(url).await?;
+    options: &ConnectOptions,
+) -> Result<SubscriptionClient, Error> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::Url("no host name in the url".into()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| Error::Url("Url scheme not supported".into()))?;
+    let socket = TcpStream::connect(format!("{}:{}", host, port))
+        .await
+        .map_err(Error::Io)?;
+
+    let mut request = url.as_str().into_client_request()?;
+    if let Some(authorization) = &options.authorization {
+        let mut value = HeaderValue::from_str(authorization)
+            .map_err(|error| Error::HttpFormat(error.into()))?;
+        value.set_sensitive(true);
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+
+    let connector = match &options.ca_certificate {
+        Some(pem) => {
+            let certificate = native_tls::Certificate::from_pem(pem).map_err(Error::Tls)?;
+            let connector = native_tls::TlsConnector::builder()
+                .add_root_certificate(certificate)
+                .build()
+                .map_err(Error::Tls)?;
+            Some(connector)
+        }
+        None => None,
+    };
+
+    let (ws, _) = client_async_tls_with_config(request, socket, None, connector).await?;
     let (mut ws_tx, mut ws_rx) = futures::StreamExt::split(ws);
 
     let (send_tx, mut send_rx) = mpsc::unbounded_channel::<Payload>();
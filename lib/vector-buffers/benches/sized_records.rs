@@ -71,6 +71,7 @@ fn create_disk_v1_variant(_max_events: usize, max_size: u64) -> BufferType {
     BufferType::DiskV1 {
         max_size,
         when_full: WhenFull::DropNewest,
+        encryption: None,
     }
 }
 
@@ -78,6 +79,7 @@ fn create_disk_v2_variant(_max_events: usize, max_size: u64) -> BufferType {
     BufferType::DiskV2 {
         max_size,
         when_full: WhenFull::DropNewest,
+        encryption: None,
     }
 }
 
@@ -254,6 +254,7 @@ where
             BufferType::DiskV1 {
                 max_size: max_size_bytes,
                 when_full,
+                encryption: None,
             }
         }
         "disk-v2" => {
@@ -264,6 +265,7 @@ where
             BufferType::DiskV2 {
                 max_size: max_size_bytes,
                 when_full,
+                encryption: None,
             }
         }
         s => panic!(
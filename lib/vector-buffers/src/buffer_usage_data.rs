@@ -1,17 +1,21 @@
 use std::{
     sync::{
-        atomic::{AtomicU64, AtomicUsize, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Weak,
     },
     time::Duration,
 };
 
+use parking_lot::Mutex;
 use tokio::time::interval;
 use tracing::{Instrument, Span};
 use vector_common::internal_event::emit;
 
 use crate::{
-    internal_events::{BufferCreated, BufferEventsReceived, BufferEventsSent, EventsDropped},
+    inspection::DiskBufferInspector,
+    internal_events::{
+        BufferCreated, BufferDiskUsage, BufferEventsReceived, BufferEventsSent, EventsDropped,
+    },
     WhenFull,
 };
 
@@ -26,7 +30,7 @@ impl BufferUsageHandle {
     /// No usage data is written or stored.
     pub(crate) fn noop(when_full: WhenFull) -> Self {
         BufferUsageHandle {
-            state: Arc::new(BufferUsageData::new(when_full, 0)),
+            state: Arc::new(BufferUsageData::new(when_full, 0, "noop")),
         }
     }
 
@@ -54,6 +58,11 @@ impl BufferUsageHandle {
         }
     }
 
+    /// Sets the disk buffer of this buffer component, whose on-disk usage is exposed as gauges.
+    pub(crate) fn set_disk_inspector(&self, inspector: Weak<dyn DiskBufferInspector>) {
+        *self.state.disk_inspector.lock() = Some(inspector);
+    }
+
     /// Increments the number of events (and their total size) received by this buffer component.
     ///
     /// This represents the events being sent into the buffer.
@@ -82,43 +91,61 @@ impl BufferUsageHandle {
     ///
     /// If the component itself is not configured to drop events, this call does nothing.
     pub fn try_increment_dropped_event_count(&self, count: u64) {
-        if let Some(dropped_event_count) = &self.state.dropped_event_count {
-            dropped_event_count.fetch_add(count, Ordering::Relaxed);
+        if self.state.drops_events.load(Ordering::Relaxed) {
+            self.state
+                .dropped_event_count
+                .fetch_add(count, Ordering::Relaxed);
         }
     }
+
+    /// Marks this buffer component as dropping events, even though its "when full" behavior
+    /// doesn't, such as when it drops low priority events.
+    pub(crate) fn track_dropped_event_count(&self) {
+        self.state.drops_events.store(true, Ordering::Relaxed);
+    }
 }
 
 #[derive(Debug)]
 pub struct BufferUsageData {
     idx: usize,
+    buffer_type: &'static str,
     received_event_count: AtomicU64,
     received_byte_size: AtomicU64,
     sent_event_count: AtomicU64,
     sent_byte_size: AtomicU64,
-    dropped_event_count: Option<AtomicU64>,
+    drops_events: AtomicBool,
+    dropped_event_count: AtomicU64,
     max_size_bytes: AtomicU64,
     max_size_events: AtomicUsize,
+    disk_inspector: Mutex<Option<Weak<dyn DiskBufferInspector>>>,
 }
 
 impl BufferUsageData {
-    pub fn new(mode: WhenFull, idx: usize) -> Self {
-        let dropped_event_count = match mode {
-            WhenFull::Block | WhenFull::Overflow => None,
-            WhenFull::DropNewest => Some(AtomicU64::new(0)),
+    pub fn new(mode: WhenFull, idx: usize, buffer_type: &'static str) -> Self {
+        let drops_events = match mode {
+            WhenFull::Block | WhenFull::Overflow => false,
+            WhenFull::DropNewest => true,
         };
 
         Self {
             idx,
+            buffer_type,
             received_event_count: AtomicU64::new(0),
             received_byte_size: AtomicU64::new(0),
             sent_event_count: AtomicU64::new(0),
             sent_byte_size: AtomicU64::new(0),
-            dropped_event_count,
+            drops_events: AtomicBool::new(drops_events),
+            dropped_event_count: AtomicU64::new(0),
             max_size_bytes: AtomicU64::new(0),
             max_size_events: AtomicUsize::new(0),
+            disk_inspector: Mutex::new(None),
         }
     }
 
+    fn disk_inspector(&self) -> Option<Arc<dyn DiskBufferInspector>> {
+        self.disk_inspector.lock().as_ref().and_then(Weak::upgrade)
+    }
+
     fn snapshot(&self) -> BufferUsageSnapshot {
         BufferUsageSnapshot {
             received_event_count: self.received_event_count.load(Ordering::Relaxed),
@@ -126,9 +153,9 @@ impl BufferUsageData {
             sent_event_count: self.sent_event_count.load(Ordering::Relaxed),
             sent_byte_size: self.sent_byte_size.load(Ordering::Relaxed),
             dropped_event_count: self
-                .dropped_event_count
-                .as_ref()
-                .map(|inner| inner.load(Ordering::Relaxed)),
+                .drops_events
+                .load(Ordering::Relaxed)
+                .then(|| self.dropped_event_count.load(Ordering::Relaxed)),
             max_size_bytes: self.max_size_bytes.load(Ordering::Relaxed),
             max_size_events: self.max_size_events.load(Ordering::Relaxed),
         }
@@ -166,9 +193,14 @@ impl BufferUsage {
     ///
     /// A [`BufferUsageHandle`] is returned that the caller can use to actually update the usage
     /// metrics with.  This handle will only update the usage metrics for the particular stage it
-    /// was added for.
-    pub fn add_stage(&mut self, idx: usize, mode: WhenFull) -> BufferUsageHandle {
-        let data = Arc::new(BufferUsageData::new(mode, idx));
+    /// was added for, tagging them with the stage's index and buffer type.
+    pub fn add_stage(
+        &mut self,
+        idx: usize,
+        mode: WhenFull,
+        buffer_type: &'static str,
+    ) -> BufferUsageHandle {
+        let data = Arc::new(BufferUsageData::new(mode, idx, buffer_type));
         let handle = BufferUsageHandle {
             state: Arc::clone(&data),
         };
@@ -200,28 +232,49 @@ impl BufferUsage {
 
                         emit(&BufferCreated {
                             idx: stage.idx,
+                            buffer_type: stage.buffer_type,
                             max_size_bytes,
                             max_size_events,
                         });
 
                         emit(&BufferEventsReceived {
                             idx: stage.idx,
+                            buffer_type: stage.buffer_type,
                             count: stage.received_event_count.swap(0, Ordering::Relaxed),
                             byte_size: stage.received_byte_size.swap(0, Ordering::Relaxed),
                         });
 
                         emit(&BufferEventsSent {
                             idx: stage.idx,
+                            buffer_type: stage.buffer_type,
                             count: stage.sent_event_count.swap(0, Ordering::Relaxed),
                             byte_size: stage.sent_byte_size.swap(0, Ordering::Relaxed),
                         });
 
-                        if let Some(dropped_event_count) = &stage.dropped_event_count {
+                        if stage.drops_events.load(Ordering::Relaxed) {
                             emit(&EventsDropped {
                                 idx: stage.idx,
-                                count: dropped_event_count.swap(0, Ordering::Relaxed),
+                                buffer_type: stage.buffer_type,
+                                count: stage.dropped_event_count.swap(0, Ordering::Relaxed),
                             });
                         }
+
+                        // Inspecting a disk buffer walks its directory, so it's done on a blocking
+                        // thread rather than on the runtime.
+                        if let Some(inspector) = stage.disk_inspector() {
+                            match tokio::task::spawn_blocking(move || inspector.stats()).await {
+                                Ok(Ok(stats)) => emit(&BufferDiskUsage {
+                                    idx: stage.idx,
+                                    buffer_type: stage.buffer_type,
+                                    stats,
+                                }),
+                                Ok(Err(error)) => {
+                                    debug!(%error, "Failed to inspect disk buffer.");
+                                }
+                                // The inspection panicked, which was already reported.
+                                Err(_) => {}
+                            }
+                        }
                     }
                 }
             }
@@ -10,7 +10,7 @@ use crate::{
         channel::{BufferReceiver, BufferSender},
     },
     variants::{DiskV1Buffer, DiskV2Buffer, MemoryBuffer},
-    Acker, Bufferable, WhenFull,
+    Acker, Bufferable, EncryptionConfig, WhenFull,
 };
 
 #[derive(Debug, Snafu)]
@@ -31,12 +31,18 @@ enum BufferTypeKind {
     DiskV2,
 }
 
-const ALL_FIELDS: [&str; 4] = ["type", "max_events", "max_size", "when_full"];
+const ALL_FIELDS: [&str; 5] = ["type", "max_events", "max_size", "when_full", "encryption"];
+
+/// A buffer stage, along with whether or not its "when full" behavior was explicitly configured.
+struct BufferStage {
+    stage: BufferType,
+    when_full_set: bool,
+}
 
 struct BufferTypeVisitor;
 
 impl BufferTypeVisitor {
-    fn visit_map_impl<'de, A>(mut map: A) -> Result<BufferType, A::Error>
+    fn visit_map_impl<'de, A>(mut map: A) -> Result<BufferStage, A::Error>
     where
         A: de::MapAccess<'de>,
     {
@@ -44,6 +50,7 @@ impl BufferTypeVisitor {
         let mut max_events: Option<usize> = None;
         let mut max_size: Option<u64> = None;
         let mut when_full: Option<WhenFull> = None;
+        let mut encryption: Option<EncryptionConfig> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
                 "type" => {
@@ -70,14 +77,21 @@ impl BufferTypeVisitor {
                     }
                     when_full = Some(map.next_value()?);
                 }
+                "encryption" => {
+                    if encryption.is_some() {
+                        return Err(de::Error::duplicate_field("encryption"));
+                    }
+                    encryption = Some(map.next_value()?);
+                }
                 other => {
                     return Err(de::Error::unknown_field(other, &ALL_FIELDS));
                 }
             }
         }
         let kind = kind.unwrap_or(BufferTypeKind::Memory);
+        let when_full_set = when_full.is_some();
         let when_full = when_full.unwrap_or_default();
-        match kind {
+        let stage = match kind {
             BufferTypeKind::Memory => {
                 if max_size.is_some() {
                     return Err(de::Error::unknown_field(
@@ -85,41 +99,53 @@ impl BufferTypeVisitor {
                         &["type", "max_events", "when_full"],
                     ));
                 }
-                Ok(BufferType::Memory {
+                if encryption.is_some() {
+                    return Err(de::Error::unknown_field(
+                        "encryption",
+                        &["type", "max_events", "when_full"],
+                    ));
+                }
+                BufferType::Memory {
                     max_events: max_events.unwrap_or_else(memory_buffer_default_max_events),
                     when_full,
-                })
+                }
             }
             BufferTypeKind::DiskV1 => {
                 if max_events.is_some() {
                     return Err(de::Error::unknown_field(
                         "max_events",
-                        &["type", "max_size", "when_full"],
+                        &["type", "max_size", "when_full", "encryption"],
                     ));
                 }
-                Ok(BufferType::DiskV1 {
+                BufferType::DiskV1 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
-                })
+                    encryption,
+                }
             }
             BufferTypeKind::DiskV2 => {
                 if max_events.is_some() {
                     return Err(de::Error::unknown_field(
                         "max_events",
-                        &["type", "max_size", "when_full"],
+                        &["type", "max_size", "when_full", "encryption"],
                     ));
                 }
-                Ok(BufferType::DiskV2 {
+                BufferType::DiskV2 {
                     max_size: max_size.ok_or_else(|| de::Error::missing_field("max_size"))?,
                     when_full,
-                })
+                    encryption,
+                }
             }
-        }
+        };
+        Ok(BufferStage {
+            stage,
+            when_full_set,
+        })
     }
 }
 
 impl<'de> de::Visitor<'de> for BufferTypeVisitor {
-    type Value = BufferType;
+    type Value = BufferStage;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.write_str("enum BufferType")
@@ -133,12 +159,21 @@ impl<'de> de::Visitor<'de> for BufferTypeVisitor {
     }
 }
 
+impl<'de> Deserialize<'de> for BufferStage {
+    fn deserialize<D>(deserializer: D) -> Result<BufferStage, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(BufferTypeVisitor)
+    }
+}
+
 impl<'de> Deserialize<'de> for BufferType {
     fn deserialize<D>(deserializer: D) -> Result<BufferType, D::Error>
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(BufferTypeVisitor)
+        BufferStage::deserialize(deserializer).map(|stage| stage.stage)
     }
 }
 
@@ -155,7 +190,7 @@ impl<'de> de::Visitor<'de> for BufferConfigVisitor {
     where
         A: de::MapAccess<'de>,
     {
-        let stage = BufferTypeVisitor::visit_map_impl(map)?;
+        let stage = BufferTypeVisitor::visit_map_impl(map)?.stage;
         Ok(BufferConfig {
             stages: vec![stage],
         })
@@ -165,10 +200,25 @@ impl<'de> de::Visitor<'de> for BufferConfigVisitor {
     where
         A: de::SeqAccess<'de>,
     {
-        let mut stages = Vec::new();
+        let mut parsed: Vec<BufferStage> = Vec::new();
         while let Some(stage) = seq.next_element()? {
-            stages.push(stage);
+            parsed.push(stage);
         }
+
+        // Every stage followed by another one overflows into it unless told otherwise, which lets a
+        // memory stage in front of a disk stage absorb bursts without blocking.
+        let last_idx = parsed.len().saturating_sub(1);
+        let stages = parsed
+            .into_iter()
+            .enumerate()
+            .map(|(idx, parsed)| {
+                if idx < last_idx && !parsed.when_full_set {
+                    parsed.stage.with_when_full(WhenFull::Overflow)
+                } else {
+                    parsed.stage
+                }
+            })
+            .collect();
         Ok(BufferConfig { stages })
     }
 }
@@ -202,7 +252,7 @@ pub const fn memory_buffer_default_max_events() -> usize {
 }
 
 /// A specific type of buffer stage.
-#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum BufferType {
@@ -220,6 +270,8 @@ pub enum BufferType {
         max_size: u64,
         #[serde(default)]
         when_full: WhenFull,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<EncryptionConfig>,
     },
     /// A buffer stage backed by disk.
     #[serde(rename = "disk_v2")]
@@ -227,10 +279,39 @@ pub enum BufferType {
         max_size: u64,
         #[serde(default)]
         when_full: WhenFull,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<EncryptionConfig>,
     },
 }
 
 impl BufferType {
+    fn with_when_full(self, when_full: WhenFull) -> Self {
+        match self {
+            BufferType::Memory { max_events, .. } => BufferType::Memory {
+                max_events,
+                when_full,
+            },
+            BufferType::DiskV1 {
+                max_size,
+                encryption,
+                ..
+            } => BufferType::DiskV1 {
+                max_size,
+                when_full,
+                encryption,
+            },
+            BufferType::DiskV2 {
+                max_size,
+                encryption,
+                ..
+            } => BufferType::DiskV2 {
+                max_size,
+                when_full,
+                encryption,
+            },
+        }
+    }
+
     /// Adds this buffer type as a stage to an existing [`TopologyBuilder`].
     ///
     /// # Errors
@@ -246,27 +327,37 @@ impl BufferType {
     where
         T: Bufferable + Clone,
     {
-        match *self {
+        match self {
             BufferType::Memory {
                 when_full,
                 max_events,
             } => {
-                builder.stage(MemoryBuffer::new(max_events), when_full);
+                builder.stage(MemoryBuffer::new(*max_events), *when_full);
             }
             BufferType::DiskV1 {
                 when_full,
                 max_size,
+                encryption,
             } => {
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV1Buffer::new(id, data_dir, max_size), when_full);
+                let key = encryption.as_ref().map(|encryption| encryption.key.clone());
+                builder.stage(
+                    DiskV1Buffer::new(id, data_dir, *max_size).with_encryption(key),
+                    *when_full,
+                );
             }
             BufferType::DiskV2 {
                 when_full,
                 max_size,
+                encryption,
             } => {
                 warn!("!!!! The `disk_v2` buffer type is not yet stable.  Data loss may be encountered. !!!!");
                 let data_dir = data_dir.ok_or(BufferBuildError::RequiresDataDir)?;
-                builder.stage(DiskV2Buffer::new(id, data_dir, max_size), when_full);
+                let key = encryption.as_ref().map(|encryption| encryption.key.clone());
+                builder.stage(
+                    DiskV2Buffer::new(id, data_dir, *max_size).with_encryption(key),
+                    *when_full,
+                );
             }
         };
 
@@ -318,8 +409,8 @@ impl BufferConfig {
     ///
     /// # Errors
     ///
-    /// If the buffer is configured with anything other than a single stage, an error variant will
-    /// be thrown.
+    /// If a stage followed by another one isn't configured to overflow, if the last stage is, or if
+    /// more than one stage is backed by disk, an error variant will be thrown.
     ///
     /// If a disk buffer stage is configured and the data directory provided is `None`, an error
     /// variant will be thrown.
@@ -348,7 +439,7 @@ impl BufferConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::{BufferConfig, BufferType, WhenFull};
+    use crate::{BufferConfig, BufferType, EncryptionConfig, WhenFull};
 
     fn check_single_stage(source: &str, expected: BufferType) {
         let config: BufferConfig = serde_yaml::from_str(source).unwrap();
@@ -378,7 +469,7 @@ mod test {
         let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
         assert_eq!(
             error.to_string(),
-            "unknown field `foo`, expected one of `type`, `max_events`, `max_size`, `when_full` at line 1 column 4"
+            "unknown field `foo`, expected one of `type`, `max_events`, `max_size`, `when_full`, `encryption` at line 1 column 4"
         );
     }
 
@@ -418,7 +509,7 @@ max_events: 42
             &[
                 BufferType::Memory {
                     max_events: 42,
-                    when_full: WhenFull::Block,
+                    when_full: WhenFull::Overflow,
                 },
                 BufferType::Memory {
                     max_events: 100,
@@ -428,6 +519,83 @@ max_events: 42
         );
     }
 
+    #[test]
+    fn parse_memory_overflow_to_disk() {
+        check_multiple_stages(
+            r#"
+          - type: memory
+            max_events: 1000
+          - type: disk
+            max_size: 1073741824
+          "#,
+            &[
+                BufferType::Memory {
+                    max_events: 1000,
+                    when_full: WhenFull::Overflow,
+                },
+                BufferType::DiskV1 {
+                    max_size: 1_073_741_824,
+                    when_full: WhenFull::Block,
+                    encryption: None,
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_multiple_stages_keeps_explicit_when_full() {
+        check_multiple_stages(
+            r#"
+          - max_events: 42
+            when_full: block
+          - max_events: 100
+          "#,
+            &[
+                BufferType::Memory {
+                    max_events: 42,
+                    when_full: WhenFull::Block,
+                },
+                BufferType::Memory {
+                    max_events: 100,
+                    when_full: WhenFull::Block,
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_disk_encryption() {
+        let key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+        check_single_stage(
+            &format!(
+                r#"
+          type: disk
+          max_size: 1024
+          encryption:
+            key: {}
+          "#,
+                key
+            ),
+            BufferType::DiskV1 {
+                max_size: 1024,
+                when_full: WhenFull::Block,
+                encryption: Some(EncryptionConfig {
+                    key: key.to_owned().try_into().unwrap(),
+                }),
+            },
+        );
+
+        let source = r#"
+          type: memory
+          encryption:
+            key: AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=
+          "#;
+        let error = serde_yaml::from_str::<BufferConfig>(source).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("unknown field `encryption`, expected one of"));
+    }
+
     #[test]
     fn ensure_field_defaults_for_all_types() {
         check_single_stage(
@@ -438,6 +606,7 @@ max_events: 42
             BufferType::DiskV1 {
                 max_size: 1024,
                 when_full: WhenFull::Block,
+                encryption: None,
             },
         );
 
@@ -492,6 +661,7 @@ max_events: 42
             BufferType::DiskV2 {
                 max_size: 1024,
                 when_full: WhenFull::Block,
+                encryption: None,
             },
         );
     }
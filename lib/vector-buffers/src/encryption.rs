@@ -0,0 +1,243 @@
+use std::{fmt, fs, io, path::Path};
+
+use openssl::{
+    error::ErrorStack,
+    rand::rand_bytes,
+    sha::sha256,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use serde::{Deserialize, Serialize, Serializer};
+use snafu::{ResultExt, Snafu};
+
+/// Length, in bytes, of the nonce prepended to each encrypted record.
+const NONCE_LEN: usize = 12;
+
+/// Length, in bytes, of the authentication tag appended to each encrypted record.
+const TAG_LEN: usize = 16;
+
+/// Length, in bytes, of an AES-256 key.
+const KEY_LEN: usize = 32;
+
+/// Name of the file marking, in the directory of a disk buffer, that its records are encrypted.
+const ENCRYPTED_MARKER: &str = "buffer.encrypted";
+
+/// Error that occurred when encrypting or decrypting a record.
+#[derive(Debug, Snafu)]
+pub enum EncryptionError {
+    #[snafu(display(
+        "encrypted record is too short ({} bytes) to hold its nonce and authentication tag",
+        len
+    ))]
+    TooShort { len: usize },
+    #[snafu(display("failed to encrypt or decrypt record: {}", source))]
+    Cipher { source: ErrorStack },
+}
+
+/// Encryption at rest of the records of a disk buffer.
+///
+/// Records are encrypted with AES-256-GCM, each with its own random nonce, before being written to
+/// disk, and decrypted, and authenticated, after being read back.  Records which fail to be
+/// decrypted, whether because they were tampered with or because the key changed, are handled as
+/// corrupted records.
+///
+/// Encryption can only be turned on, or off, while a buffer is empty, as the records already in it
+/// would otherwise all be read as corrupted.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptionConfig {
+    /// The 256-bit key, encoded in base64.
+    pub key: EncryptionKey,
+}
+
+/// A 256-bit AES key.
+///
+/// It's deserialized from base64, but never printed, nor serialized, so that it doesn't end up in
+/// logs or in dumps of the configuration.  Its SHA-256 digest is serialized in its stead, so that
+/// configurations using different keys still compare as different.
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
+pub struct EncryptionKey([u8; KEY_LEN]);
+
+impl TryFrom<String> for EncryptionKey {
+    type Error = String;
+
+    fn try_from(encoded: String) -> Result<Self, Self::Error> {
+        let decoded = base64::decode(encoded.trim())
+            .map_err(|error| format!("encryption key isn't valid base64: {}", error))?;
+        <[u8; KEY_LEN]>::try_from(decoded.as_slice())
+            .map(EncryptionKey)
+            .map_err(|_| {
+                format!(
+                    "encryption key must be {} bytes long, got {} bytes",
+                    KEY_LEN,
+                    decoded.len()
+                )
+            })
+    }
+}
+
+impl Serialize for EncryptionKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let digest = sha256(&self.0)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        serializer.serialize_str(&format!("<redacted sha256:{}>", digest))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+impl EncryptionKey {
+    /// Encrypts the given record, as the nonce followed by the ciphertext and the authentication
+    /// tag.
+    ///
+    /// # Errors
+    ///
+    /// If the nonce can't be generated, or the record encrypted, an error variant will be returned.
+    pub(crate) fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let mut nonce = [0; NONCE_LEN];
+        rand_bytes(&mut nonce).context(CipherSnafu)?;
+        let mut tag = [0; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.0,
+            Some(&nonce),
+            &[],
+            plaintext,
+            &mut tag,
+        )
+        .context(CipherSnafu)?;
+
+        let mut encrypted = Vec::with_capacity(NONCE_LEN + ciphertext.len() + TAG_LEN);
+        encrypted.extend_from_slice(&nonce);
+        encrypted.extend_from_slice(&ciphertext);
+        encrypted.extend_from_slice(&tag);
+        Ok(encrypted)
+    }
+
+    /// Decrypts, and authenticates, a record encrypted by [`EncryptionKey::encrypt`].
+    ///
+    /// # Errors
+    ///
+    /// If the record is truncated, was tampered with, or was encrypted with another key, an error
+    /// variant will be returned.
+    pub(crate) fn decrypt(&self, encrypted: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if encrypted.len() < NONCE_LEN + TAG_LEN {
+            return Err(EncryptionError::TooShort {
+                len: encrypted.len(),
+            });
+        }
+
+        let (nonce, rest) = encrypted.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.0,
+            Some(nonce),
+            &[],
+            ciphertext,
+            tag,
+        )
+        .context(CipherSnafu)
+    }
+}
+
+/// Gets whether the records of the disk buffer in `data_dir` are encrypted.
+pub(crate) fn is_encrypted_buffer(data_dir: &Path) -> bool {
+    data_dir.join(ENCRYPTED_MARKER).exists()
+}
+
+/// Marks the records of the disk buffer in `data_dir` as encrypted, or not.
+///
+/// This must only be changed while the buffer is empty.
+///
+/// # Errors
+///
+/// If the marker can't be written, or removed, an error variant will be returned.
+pub(crate) fn mark_encrypted_buffer(data_dir: &Path, encrypted: bool) -> io::Result<()> {
+    let path = data_dir.join(ENCRYPTED_MARKER);
+    if encrypted {
+        fs::write(path, b"")
+    } else {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncryptionConfig, EncryptionError, EncryptionKey};
+
+    fn key(byte: u8) -> EncryptionKey {
+        EncryptionKey([byte; 32])
+    }
+
+    #[test]
+    fn round_trip() {
+        let key = key(7);
+        let encrypted = key.encrypt(b"some event").unwrap();
+        assert_eq!(encrypted.len(), 12 + 10 + 16);
+        assert!(!encrypted
+            .windows(b"some event".len())
+            .any(|window| window == b"some event"));
+        assert_eq!(key.decrypt(&encrypted).unwrap(), b"some event");
+    }
+
+    #[test]
+    fn nonces_are_unique() {
+        let key = key(7);
+        assert_ne!(key.encrypt(b"same").unwrap(), key.encrypt(b"same").unwrap());
+    }
+
+    #[test]
+    fn wrong_key_fails() {
+        let encrypted = key(7).encrypt(b"some event").unwrap();
+        assert!(matches!(
+            key(8).decrypt(&encrypted),
+            Err(EncryptionError::Cipher { .. })
+        ));
+    }
+
+    #[test]
+    fn tampering_fails() {
+        let key = key(7);
+        let mut encrypted = key.encrypt(b"some event").unwrap();
+        encrypted[14] ^= 1;
+        assert!(key.decrypt(&encrypted).is_err());
+        assert!(matches!(
+            key.decrypt(&encrypted[..20]),
+            Err(EncryptionError::TooShort { len: 20 })
+        ));
+    }
+
+    #[test]
+    fn parse_key() {
+        let config: EncryptionConfig =
+            serde_yaml::from_str("key: AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").unwrap();
+        assert_eq!(config.key, key(0));
+        assert_eq!(format!("{:?}", config.key), "EncryptionKey(<redacted>)");
+
+        let error = EncryptionKey::try_from("AAAA".to_owned()).unwrap_err();
+        assert_eq!(error, "encryption key must be 32 bytes long, got 3 bytes");
+        assert!(EncryptionKey::try_from("not base64!".to_owned()).is_err());
+    }
+
+    #[test]
+    fn serialize_redacts_key() {
+        let config: EncryptionConfig =
+            serde_yaml::from_str("key: AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=").unwrap();
+        let serialized = serde_yaml::to_string(&config).unwrap();
+        assert!(!serialized.contains("AAAAAAAA"));
+        assert!(serialized.contains("<redacted sha256:"));
+
+        let other = EncryptionConfig { key: key(1) };
+        assert_ne!(serialized, serde_yaml::to_string(&other).unwrap());
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::Path,
+    sync::{Arc, Weak},
+    time::{Duration, SystemTime},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Serialize, Serializer};
+
+/// Disk buffers of the running topologies, by buffer ID.
+///
+/// Buffers are tracked weakly, so that a buffer going away, such as when its sink is removed during
+/// a reload, doesn't have to unregister itself.
+static DISK_BUFFERS: Lazy<Mutex<HashMap<String, Weak<dyn DiskBufferInspector>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Point-in-time view of the data a disk buffer holds on disk.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DiskBufferStats {
+    /// Number of segments of the buffer: data files for `disk_v2` buffers, tables for `disk`
+    /// buffers.
+    pub segments: u64,
+    /// Total size of the files of the buffer, in bytes, including the space held by acknowledged
+    /// records that wasn't reclaimed yet.
+    pub disk_bytes: u64,
+    /// Age of the oldest unacknowledged record, zero if the buffer is empty, or `None` if the
+    /// buffer doesn't keep track of it.
+    #[serde(
+        rename = "oldest_record_age_seconds",
+        serialize_with = "serialize_seconds"
+    )]
+    pub oldest_record_age: Option<Duration>,
+}
+
+fn serialize_seconds<S: Serializer>(
+    age: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    age.map(|age| age.as_secs_f64()).serialize(serializer)
+}
+
+/// A disk buffer which can be inspected, and compacted, while it's running.
+pub(crate) trait DiskBufferInspector: fmt::Debug + Send + Sync {
+    /// Gathers the statistics of the buffer.
+    ///
+    /// This walks the directory of the buffer, and so shouldn't be called from asynchronous code.
+    ///
+    /// # Errors
+    ///
+    /// If the directory of the buffer can't be read, an error variant will be returned.
+    fn stats(&self) -> io::Result<DiskBufferStats>;
+
+    /// Asks the buffer to reclaim the space held by acknowledged records.
+    ///
+    /// The compaction itself happens asynchronously, driven by the buffer.
+    fn request_compaction(&self);
+}
+
+/// Registers a disk buffer so that it can be inspected, and compacted, by its ID.
+///
+/// Any previous buffer with the same ID is replaced.
+pub(crate) fn register(id: &str, inspector: Weak<dyn DiskBufferInspector>) {
+    let mut buffers = DISK_BUFFERS.lock();
+    buffers.retain(|_, inspector| inspector.strong_count() > 0);
+    buffers.insert(id.to_owned(), inspector);
+}
+
+fn get(id: &str) -> Option<Arc<dyn DiskBufferInspector>> {
+    DISK_BUFFERS.lock().get(id).and_then(Weak::upgrade)
+}
+
+/// Gets the IDs of the running disk buffers, in order.
+pub fn disk_buffer_ids() -> Vec<String> {
+    let mut buffers = DISK_BUFFERS.lock();
+    buffers.retain(|_, inspector| inspector.strong_count() > 0);
+
+    let mut ids = buffers.keys().cloned().collect::<Vec<_>>();
+    ids.sort();
+    ids
+}
+
+/// Gathers the statistics of the disk buffer with the given ID, if it's running.
+///
+/// This walks the directory of the buffer, and so shouldn't be called from asynchronous code.
+///
+/// # Errors
+///
+/// If the directory of the buffer can't be read, an error variant will be returned.
+pub fn inspect_disk_buffer(id: &str) -> Option<io::Result<DiskBufferStats>> {
+    get(id).map(|inspector| inspector.stats())
+}
+
+/// Asks the disk buffer with the given ID to reclaim the space held by acknowledged records,
+/// returning whether it's running.
+pub fn compact_disk_buffer(id: &str) -> bool {
+    match get(id) {
+        Some(inspector) => {
+            inspector.request_compaction();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sums the size of the files in the given directory, also counting those matching `is_segment`.
+///
+/// Files deleted while the directory is being walked are skipped.
+pub(crate) fn walk_buffer_dir(
+    dir: &Path,
+    is_segment: impl Fn(&str) -> bool,
+) -> io::Result<DiskBufferStats> {
+    let mut stats = DiskBufferStats::default();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+
+        stats.disk_bytes += metadata.len();
+        if is_segment(&entry.file_name().to_string_lossy()) {
+            stats.segments += 1;
+        }
+    }
+    Ok(stats)
+}
+
+/// Gets the time elapsed since the given file was created, or last modified where creation times
+/// aren't available.
+pub(crate) fn file_age(path: &Path) -> Option<Duration> {
+    let metadata = fs::metadata(path).ok()?;
+    let created = metadata.created().or_else(|_| metadata.modified()).ok()?;
+    Some(
+        SystemTime::now()
+            .duration_since(created)
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    };
+
+    use temp_dir::TempDir;
+
+    use super::{
+        compact_disk_buffer, disk_buffer_ids, inspect_disk_buffer, register, walk_buffer_dir,
+        DiskBufferInspector, DiskBufferStats,
+    };
+
+    #[derive(Debug, Default)]
+    struct FakeInspector {
+        compactions: AtomicUsize,
+    }
+
+    impl DiskBufferInspector for FakeInspector {
+        fn stats(&self) -> std::io::Result<DiskBufferStats> {
+            Ok(DiskBufferStats {
+                segments: 2,
+                ..DiskBufferStats::default()
+            })
+        }
+
+        fn request_compaction(&self) {
+            self.compactions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn registered_buffers_can_be_inspected_until_dropped() {
+        let inspector = Arc::new(FakeInspector::default());
+        let weak: Weak<dyn DiskBufferInspector> = Arc::downgrade(&inspector) as _;
+        register("registry_test_sink", weak);
+
+        assert!(disk_buffer_ids().contains(&"registry_test_sink".to_owned()));
+        assert_eq!(
+            inspect_disk_buffer("registry_test_sink")
+                .expect("buffer should be registered")
+                .expect("stats should not fail")
+                .segments,
+            2
+        );
+        assert!(compact_disk_buffer("registry_test_sink"));
+        assert_eq!(inspector.compactions.load(Ordering::Relaxed), 1);
+
+        drop(inspector);
+        assert!(!disk_buffer_ids().contains(&"registry_test_sink".to_owned()));
+        assert!(inspect_disk_buffer("registry_test_sink").is_none());
+        assert!(!compact_disk_buffer("registry_test_sink"));
+    }
+
+    #[test]
+    fn walks_buffer_dir() {
+        let dir = TempDir::new().expect("should create temp dir");
+        std::fs::write(dir.path().join("buffer-data-0.dat"), [0; 10]).unwrap();
+        std::fs::write(dir.path().join("buffer-data-1.dat"), [0; 20]).unwrap();
+        std::fs::write(dir.path().join("buffer.db"), [0; 5]).unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+
+        let stats = walk_buffer_dir(dir.path(), |name| name.ends_with(".dat"))
+            .expect("walk should not fail");
+        assert_eq!(stats.segments, 2);
+        assert_eq!(stats.disk_bytes, 35);
+        assert_eq!(stats.oldest_record_age, None);
+    }
+}
@@ -1,8 +1,11 @@
 use metrics::{counter, decrement_gauge, gauge, increment_gauge};
 use vector_common::internal_event::InternalEvent;
 
+use crate::DiskBufferStats;
+
 pub struct BufferEventsReceived {
     pub idx: usize,
+    pub buffer_type: &'static str,
     pub count: u64,
     pub byte_size: u64,
 }
@@ -10,15 +13,16 @@ pub struct BufferEventsReceived {
 impl InternalEvent for BufferEventsReceived {
     #[allow(clippy::cast_precision_loss)]
     fn emit_metrics(&self) {
-        counter!("buffer_received_events_total", self.count, "stage" => self.idx.to_string());
-        counter!("buffer_received_bytes_total", self.byte_size, "stage" => self.idx.to_string());
-        increment_gauge!("buffer_events", self.count as f64, "stage" => self.idx.to_string());
-        increment_gauge!("buffer_byte_size", self.byte_size as f64, "stage" => self.idx.to_string());
+        counter!("buffer_received_events_total", self.count, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        counter!("buffer_received_bytes_total", self.byte_size, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        increment_gauge!("buffer_events", self.count as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        increment_gauge!("buffer_byte_size", self.byte_size as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
     }
 }
 
 pub struct BufferEventsSent {
     pub idx: usize,
+    pub buffer_type: &'static str,
     pub count: u64,
     pub byte_size: u64,
 }
@@ -26,21 +30,22 @@ pub struct BufferEventsSent {
 impl InternalEvent for BufferEventsSent {
     #[allow(clippy::cast_precision_loss)]
     fn emit_metrics(&self) {
-        counter!("buffer_sent_events_total", self.count, "stage" => self.idx.to_string());
-        counter!("buffer_sent_bytes_total", self.byte_size, "stage" => self.idx.to_string());
-        decrement_gauge!("buffer_events", self.count as f64, "stage" => self.idx.to_string());
-        decrement_gauge!("buffer_byte_size", self.byte_size as f64, "stage" => self.idx.to_string());
+        counter!("buffer_sent_events_total", self.count, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        counter!("buffer_sent_bytes_total", self.byte_size, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        decrement_gauge!("buffer_events", self.count as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        decrement_gauge!("buffer_byte_size", self.byte_size as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
     }
 }
 
 pub struct EventsDropped {
     pub idx: usize,
+    pub buffer_type: &'static str,
     pub count: u64,
 }
 
 impl InternalEvent for EventsDropped {
     fn emit_metrics(&self) {
-        counter!("buffer_discarded_events_total", self.count, "stage" => self.idx.to_string());
+        counter!("buffer_discarded_events_total", self.count, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
     }
 }
 
@@ -56,6 +61,7 @@ impl InternalEvent for EventsCorrupted {
 
 pub struct BufferCreated {
     pub idx: usize,
+    pub buffer_type: &'static str,
     pub max_size_events: Option<usize>,
     pub max_size_bytes: Option<u64>,
 }
@@ -64,10 +70,27 @@ impl InternalEvent for BufferCreated {
     #[allow(clippy::cast_precision_loss)]
     fn emit_metrics(&self) {
         if let Some(max_size) = self.max_size_events {
-            gauge!("buffer_max_event_size", max_size as f64, "stage" => self.idx.to_string());
+            gauge!("buffer_max_event_size", max_size as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
         }
         if let Some(max_size) = self.max_size_bytes {
-            gauge!("buffer_max_byte_size", max_size as f64, "stage" => self.idx.to_string());
+            gauge!("buffer_max_byte_size", max_size as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        }
+    }
+}
+
+pub struct BufferDiskUsage {
+    pub idx: usize,
+    pub buffer_type: &'static str,
+    pub stats: DiskBufferStats,
+}
+
+impl InternalEvent for BufferDiskUsage {
+    #[allow(clippy::cast_precision_loss)]
+    fn emit_metrics(&self) {
+        gauge!("buffer_segments", self.stats.segments as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        gauge!("buffer_disk_bytes", self.stats.disk_bytes as f64, "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
+        if let Some(age) = self.stats.oldest_record_age {
+            gauge!("buffer_oldest_record_age_seconds", age.as_secs_f64(), "stage" => self.idx.to_string(), "buffer_type" => self.buffer_type);
         }
     }
 }
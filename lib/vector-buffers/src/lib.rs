@@ -23,12 +23,19 @@ use encoding::Encodable;
 
 pub mod encoding;
 
+mod encryption;
+pub use encryption::{EncryptionConfig, EncryptionError, EncryptionKey};
+
+mod inspection;
+pub use inspection::{compact_disk_buffer, disk_buffer_ids, inspect_disk_buffer, DiskBufferStats};
+
 mod internal_events;
 #[cfg(test)]
 pub mod test;
 pub mod topology;
 
 pub(crate) mod variants;
+pub use variants::read_disk_v2_buffer;
 
 use std::fmt::Debug;
 
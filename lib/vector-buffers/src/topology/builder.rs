@@ -7,7 +7,7 @@ use tracing::Span;
 use super::channel::{ReceiverAdapter, SenderAdapter};
 use crate::{
     buffer_usage_data::{BufferUsage, BufferUsageHandle},
-    topology::channel::{BufferReceiver, BufferSender},
+    topology::channel::{AckRouter, BufferReceiver, BufferSender},
     variants::MemoryBuffer,
     Acker, Bufferable, WhenFull,
 };
@@ -15,6 +15,10 @@ use crate::{
 /// Value that can be used as a stage in a buffer topology.
 #[async_trait]
 pub trait IntoBuffer<T> {
+    /// Gets the type of this buffer stage, such as `memory` or `disk`, which its metrics are
+    /// tagged with.
+    fn buffer_type(&self) -> &'static str;
+
     /// Gets whether or not this buffer stage provides its own instrumentation, or if it should be
     /// instrumented from the outside.
     ///
@@ -109,6 +113,7 @@ impl<T> TopologyBuilder<T> {
         span: Span,
     ) -> Result<(BufferSender<T>, BufferReceiver<T>, Acker), TopologyError> {
         // We pop stages off in reverse order to build from the inside out.
+        let stage_count = self.stages.len();
         let mut buffer_usage = BufferUsage::from_span(span);
         let mut current_acker = None;
         let mut current_stage = None;
@@ -135,7 +140,11 @@ impl<T> TopologyBuilder<T> {
             // sender/receiver/acker.  This is slightly awkward since we just end up actually giving
             // the handle to the `BufferSender`/`BufferReceiver` wrappers, but that's the price we
             // have to pay for letting each stage function in an opaque way when wrapped.
-            let usage_handle = buffer_usage.add_stage(stage_idx, stage.when_full);
+            let usage_handle = buffer_usage.add_stage(
+                stage_idx,
+                stage.when_full,
+                stage.untransformed.buffer_type(),
+            );
             let provides_instrumentation = stage.untransformed.provides_instrumentation();
             let (sender, receiver, acker) = stage
                 .untransformed
@@ -160,10 +169,12 @@ impl<T> TopologyBuilder<T> {
             // configurations.
             //
             // In the future, we may opt to support such a configuration.
-            if current_acker.is_some() && acker.is_some() {
-                return Err(TopologyError::StackedAcks);
+            if let Some(acker) = acker {
+                if current_acker.is_some() {
+                    return Err(TopologyError::StackedAcks);
+                }
+                current_acker = Some((stage_idx, acker));
             }
-            current_acker = acker;
 
             let (mut sender, mut receiver) = match current_stage.take() {
                 None => (
@@ -184,8 +195,19 @@ impl<T> TopologyBuilder<T> {
             current_stage = Some((sender, receiver));
         }
 
-        let (sender, receiver) = current_stage.ok_or(TopologyError::EmptyTopology)?;
-        let acker = current_acker.unwrap_or_else(Acker::passthrough);
+        let (sender, mut receiver) = current_stage.ok_or(TopologyError::EmptyTopology)?;
+        let acker = match current_acker {
+            // The sink acknowledges the events read from every stage, so when the stage with
+            // segmented acknowledgements overflows into, or out of, other stages, we have to track
+            // which events were read from it to only pass their acknowledgements along.
+            Some((segmented_stage_idx, acker)) if stage_count > 1 => {
+                let router = AckRouter::default();
+                receiver.route_acks(&router, 0, segmented_stage_idx);
+                router.into_acker(acker)
+            }
+            Some((_, acker)) => acker,
+            None => Acker::passthrough(),
+        };
 
         // Install the buffer usage handler since we successfully created the buffer topology.  This
         // spawns it in the background and periodically emits aggregated metrics about each of the
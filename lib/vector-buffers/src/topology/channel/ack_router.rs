@@ -0,0 +1,101 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use parking_lot::Mutex;
+
+use crate::Acker;
+
+/// Routes acknowledgements to the stage of a buffer topology with segmented acknowledgements.
+///
+/// When a stage overflows into another, the sink reads events from either of them and acknowledges
+/// them all through the same [`Acker`], but only the events read out of the stage with segmented
+/// acknowledgements, such as a disk stage, must be acknowledged to that stage.  The receivers record
+/// which stage each event was read from, in read order, which lets the acknowledgements, which are
+/// given in read order as well, be translated into acknowledgements for that stage.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AckRouter {
+    // Runs of events read from the same kind of stage: whether it's the segmented stage, and the
+    // number of events.
+    reads: Arc<Mutex<VecDeque<(bool, usize)>>>,
+}
+
+impl AckRouter {
+    /// Records that `count` events were read, either from the segmented stage or from another one.
+    pub(crate) fn record(&self, segmented: bool, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        let mut reads = self.reads.lock();
+        match reads.back_mut() {
+            Some((last_segmented, total)) if *last_segmented == segmented => *total += count,
+            _ => reads.push_back((segmented, count)),
+        }
+    }
+
+    /// Consumes the acknowledgement of the `amount` oldest read events, returning how many of them
+    /// were read from the segmented stage.
+    pub(crate) fn route(&self, mut amount: usize) -> usize {
+        let mut reads = self.reads.lock();
+        let mut segmented_amount = 0;
+        while amount > 0 {
+            let (segmented, count) = match reads.front_mut() {
+                Some(run) => run,
+                None => break,
+            };
+            let acked = amount.min(*count);
+            if *segmented {
+                segmented_amount += acked;
+            }
+            amount -= acked;
+            *count -= acked;
+            if *count == 0 {
+                reads.pop_front();
+            }
+        }
+        segmented_amount
+    }
+
+    /// Wraps the acker of the segmented stage into an acker for the whole buffer topology.
+    pub(crate) fn into_acker(self, inner: Acker) -> Acker {
+        Acker::segmented(move |amount: usize| inner.ack(self.route(amount)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::AckRouter;
+    use crate::Acker;
+
+    #[test]
+    fn routes_only_segmented_reads() {
+        let router = AckRouter::default();
+        router.record(false, 2);
+        router.record(true, 3);
+        router.record(true, 1);
+        router.record(false, 4);
+        router.record(true, 2);
+
+        assert_eq!(router.route(1), 0);
+        assert_eq!(router.route(3), 2);
+        assert_eq!(router.route(5), 2);
+        assert_eq!(router.route(10), 2);
+        assert_eq!(router.route(1), 0);
+    }
+
+    #[test]
+    fn wrapped_acker_acks_segmented_stage() {
+        let (inner, counter) = Acker::basic();
+        let router = AckRouter::default();
+        router.record(true, 2);
+        router.record(false, 3);
+        router.record(true, 1);
+
+        let acker = router.into_acker(inner);
+        acker.ack(4);
+        assert_eq!(counter.load(Ordering::Relaxed), 2);
+        acker.ack(2);
+        assert_eq!(counter.load(Ordering::Relaxed), 3);
+    }
+}
@@ -1,3 +1,4 @@
+mod ack_router;
 mod limited_queue;
 pub(self) mod poll_notify;
 pub(self) mod poll_semaphore;
@@ -5,6 +6,7 @@ mod receiver;
 mod sender;
 mod strategy;
 
+pub(crate) use ack_router::AckRouter;
 pub use limited_queue::{limited, LimitedReceiver, LimitedSender, SendError};
 pub use receiver::*;
 pub use sender::*;
@@ -7,7 +7,7 @@ use std::{
 use futures::Stream;
 use pin_project::pin_project;
 
-use super::{limited_queue::LimitedReceiver, strategy::StrategyResult, PollStrategy};
+use super::{limited_queue::LimitedReceiver, strategy::StrategyResult, AckRouter, PollStrategy};
 use crate::{buffer_usage_data::BufferUsageHandle, Bufferable};
 
 /// Adapter for papering over various receiver backends by providing a [`Stream`] interface.
@@ -75,6 +75,7 @@ pub struct BufferReceiver<T> {
     overflow: Option<Box<BufferReceiver<T>>>,
     strategy: PollStrategy,
     instrumentation: Option<BufferUsageHandle>,
+    acks: Option<(AckRouter, bool)>,
 }
 
 impl<T> BufferReceiver<T> {
@@ -85,6 +86,7 @@ impl<T> BufferReceiver<T> {
             overflow: None,
             strategy: PollStrategy::default(),
             instrumentation: None,
+            acks: None,
         }
     }
 
@@ -95,6 +97,7 @@ impl<T> BufferReceiver<T> {
             overflow: Some(Box::new(overflow)),
             strategy: PollStrategy::default(),
             instrumentation: None,
+            acks: None,
         }
     }
 
@@ -111,6 +114,23 @@ impl<T> BufferReceiver<T> {
     pub fn with_instrumentation(&mut self, handle: BufferUsageHandle) {
         self.instrumentation = Some(handle);
     }
+
+    /// Configures this receiver, and its overflow receivers, to record the items they read into the
+    /// given router.
+    ///
+    /// `stage_idx` is the index of this receiver's stage in the buffer topology, and
+    /// `segmented_stage_idx` the index of the stage with segmented acknowledgements.
+    pub(crate) fn route_acks(
+        &mut self,
+        router: &AckRouter,
+        stage_idx: usize,
+        segmented_stage_idx: usize,
+    ) {
+        self.acks = Some((router.clone(), stage_idx == segmented_stage_idx));
+        if let Some(overflow) = self.overflow.as_mut() {
+            overflow.route_acks(router, stage_idx + 1, segmented_stage_idx);
+        }
+    }
 }
 
 impl<T: Bufferable> Stream for BufferReceiver<T> {
@@ -140,6 +160,10 @@ impl<T: Bufferable> Stream for BufferReceiver<T> {
                             i.size_of() as u64,
                         );
                     }
+                    // Items from the overflow receiver were already recorded by it.
+                    if let Some((router, segmented)) = this.acks {
+                        router.record(*segmented, i.event_count());
+                    }
                     Some(i)
                 }
                 StrategyResult::Secondary(i) => Some(i),
@@ -1,6 +1,7 @@
 use std::{
     fmt, mem,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -8,7 +9,7 @@ use futures::{ready, Sink};
 use pin_project::pin_project;
 
 use super::limited_queue::LimitedSender;
-use crate::{buffer_usage_data::BufferUsageHandle, Bufferable, WhenFull};
+use crate::{buffer_usage_data::BufferUsageHandle, Bufferable, EventCount, WhenFull};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum SendState {
@@ -18,16 +19,32 @@ enum SendState {
     BaseReady,
     // The overflow sender is ready to be sent an item.
     OverflowReady,
+    // This sender would block, so it should drop the low priority events of the next item it
+    // receives, and hold on to the rest until there's room for them.
+    DropLowPriority,
     // Default state.
     Idle,
 }
 
 impl SendState {
     fn is_ready(self) -> bool {
-        matches!(self, SendState::BaseReady | SendState::OverflowReady)
+        matches!(
+            self,
+            SendState::BaseReady | SendState::OverflowReady | SendState::DropLowPriority
+        )
     }
 }
 
+/// Splits the items sent to a buffer into a high priority lane and a low priority lane.
+///
+/// Low priority events are dropped whenever the buffer would otherwise block, while high priority
+/// events keep waiting for room in the buffer.
+pub trait PriorityClassifier<T>: fmt::Debug + Send + Sync {
+    /// Drops the low priority events of the given item, returning its high priority events, if
+    /// any are left.
+    fn retain_high_priority(&self, item: T) -> Option<T>;
+}
+
 // Some type-level tomfoolery to have a trait that represents a `Sink` that can be cloned.
 /// A [`Sink`] that can be cloned.
 ///
@@ -141,6 +158,9 @@ where
 /// accept the event.  In "drop newest" mode, any event being sent when the channel is full will be
 /// dropped and proceed no further. In "overflow" mode, events will be sent to another buffer
 /// sender.  Callers can specify the overflow sender to use when constructing their buffers initially.
+///
+/// Senders can also split events into priority lanes, in which case the low priority events are
+/// dropped wherever the sender would otherwise block, rather than waited on.
 #[pin_project]
 #[derive(Debug)]
 pub struct BufferSender<T> {
@@ -153,6 +173,9 @@ pub struct BufferSender<T> {
     state: SendState,
     when_full: WhenFull,
     instrumentation: Option<BufferUsageHandle>,
+    priority: Option<Arc<dyn PriorityClassifier<T>>>,
+    // The high priority events of an item whose low priority events were dropped, waiting for room.
+    pending: Option<T>,
 }
 
 impl<T> BufferSender<T> {
@@ -166,6 +189,8 @@ impl<T> BufferSender<T> {
             state: SendState::Idle,
             when_full,
             instrumentation: None,
+            priority: None,
+            pending: None,
         }
     }
 
@@ -179,6 +204,8 @@ impl<T> BufferSender<T> {
             state: SendState::Idle,
             when_full: WhenFull::Overflow,
             instrumentation: None,
+            priority: None,
+            pending: None,
         }
     }
 
@@ -199,6 +226,18 @@ impl<T> BufferSender<T> {
     pub fn with_instrumentation(&mut self, handle: BufferUsageHandle) {
         self.instrumentation = Some(handle);
     }
+
+    /// Configures this sender to split the items sent to it into priority lanes, or not to when
+    /// `classifier` is `None`.
+    ///
+    /// Whenever this sender would otherwise block, the low priority events of the next item are
+    /// dropped, and its high priority events wait for room as usual.
+    pub fn set_priority_lanes(&mut self, classifier: Option<Arc<dyn PriorityClassifier<T>>>) {
+        if let (Some(_), Some(handle)) = (&classifier, &self.instrumentation) {
+            handle.track_dropped_event_count();
+        }
+        self.priority = classifier;
+    }
 }
 
 impl<T: Bufferable> BufferSender<T> {
@@ -211,6 +250,61 @@ impl<T: Bufferable> BufferSender<T> {
     pub(crate) fn get_overflow_ref(&self) -> Option<&BufferSender<T>> {
         self.overflow.as_ref().map(AsRef::as_ref)
     }
+
+    /// Sends the high priority events held from a previous item, if any, to whichever sender can
+    /// take them first.
+    fn poll_send_pending(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        let mut this = self.project();
+        if this.pending.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let to_overflow = match this.base.as_mut().poll_ready(cx) {
+            Poll::Ready(result) => {
+                result?;
+                false
+            }
+            Poll::Pending => match this.overflow.as_mut().as_pin_mut() {
+                Some(overflow) if *this.when_full == WhenFull::Overflow => {
+                    ready!(overflow.poll_ready(cx))?;
+                    true
+                }
+                _ => return Poll::Pending,
+            },
+        };
+
+        let item = this.pending.take().expect("pending item should be present");
+        if to_overflow {
+            this.overflow.as_pin_mut().unwrap().start_send(item)?;
+            *this.overflow_flush = true;
+        } else {
+            let item_sizing = (item.event_count(), item.size_of());
+            this.base.start_send(item)?;
+            *this.base_flush = true;
+            if let Some(handle) = this.instrumentation.as_ref() {
+                handle.increment_received_event_count_and_byte_size(
+                    item_sizing.0 as u64,
+                    item_sizing.1 as u64,
+                );
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Gets the poll result and next state of a sender which can't take an item right away, and so
+/// would block.
+fn blocked<T>(
+    priority: &Option<Arc<dyn PriorityClassifier<T>>>,
+) -> (Poll<Result<(), ()>>, SendState) {
+    if priority.is_some() {
+        // The low priority events of the next item can be dropped right away, while its high
+        // priority events are held until there's room for them.
+        (Poll::Ready(Ok(())), SendState::DropLowPriority)
+    } else {
+        (Poll::Pending, SendState::Idle)
+    }
 }
 
 impl<T> Clone for BufferSender<T> {
@@ -223,6 +317,8 @@ impl<T> Clone for BufferSender<T> {
             state: SendState::Idle,
             when_full: self.when_full,
             instrumentation: self.instrumentation.clone(),
+            priority: self.priority.clone(),
+            pending: None,
         }
     }
 }
@@ -230,7 +326,10 @@ impl<T> Clone for BufferSender<T> {
 impl<T: Bufferable> Sink<T> for BufferSender<T> {
     type Error = ();
 
-    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // High priority events held from a previous item have to be sent before any other item.
+        ready!(self.as_mut().poll_send_pending(cx))?;
+
         let this = self.project();
 
         // For whatever reason, the caller is calling `poll_ready` again after a successful previous
@@ -251,7 +350,7 @@ impl<T: Bufferable> Sink<T> for BufferSender<T> {
             Poll::Pending => match this.when_full {
                 // We need to block.  Nothing else to do, as the base sender will notify us when
                 // there's capacity to do the send.
-                WhenFull::Block => (Poll::Pending, SendState::Idle),
+                WhenFull::Block => blocked(this.priority),
                 // We need to drop the next item.  We have to wait until the caller hands it over to
                 // us in order to drop it, though, so we pretend we're ready and mark ourselves to
                 // drop the next item when `start_send` is called.
@@ -270,7 +369,7 @@ impl<T: Bufferable> Sink<T> for BufferSender<T> {
                         },
                         // Our overflow sender is not ready, either, so there's nothing else to do
                         // here except wait for a wakeup from either the base sender or overflow sender.
-                        Poll::Pending => (Poll::Pending, SendState::Idle),
+                        Poll::Pending => blocked(this.priority),
                     },
                 },
             },
@@ -301,6 +400,24 @@ impl<T: Bufferable> Sink<T> for BufferSender<T> {
                 }
                 Ok(())
             }
+            // We've been instructed to drop the low priority events of the next item, and to hold
+            // on to the rest.
+            SendState::DropLowPriority => {
+                let classifier = this
+                    .priority
+                    .as_ref()
+                    .expect("priority lanes must be configured to drop low priority events");
+                let item_count = item.event_count();
+                let remaining = classifier.retain_high_priority(item);
+                let dropped = item_count - remaining.as_ref().map_or(0, EventCount::event_count);
+                if dropped > 0 {
+                    if let Some(instrumentation) = this.instrumentation.as_ref() {
+                        instrumentation.try_increment_dropped_event_count(dropped as u64);
+                    }
+                }
+                *this.pending = remaining;
+                Ok(())
+            }
             // Base is ready, so send the item there.
             SendState::BaseReady => {
                 let result = this.base.start_send(item);
@@ -336,7 +453,9 @@ impl<T: Bufferable> Sink<T> for BufferSender<T> {
         }
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_send_pending(cx))?;
+
         let this = self.project();
 
         if *this.base_flush {
@@ -352,7 +471,9 @@ impl<T: Bufferable> Sink<T> for BufferSender<T> {
         Poll::Ready(Ok(()))
     }
 
-    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_send_pending(cx))?;
+
         let this = self.project();
 
         if let Some(overflow) = this.overflow.as_pin_mut() {
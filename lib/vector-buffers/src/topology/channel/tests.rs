@@ -8,7 +8,7 @@ use tokio::{pin, sync::Barrier, time::sleep};
 
 use crate::{
     topology::{
-        channel::{BufferReceiver, BufferSender},
+        channel::{BufferReceiver, BufferSender, PriorityClassifier},
         test_util::{assert_current_send_capacity, build_buffer},
     },
     Bufferable, WhenFull,
@@ -239,3 +239,34 @@ async fn test_buffer_metrics_drop_newest() {
     assert_eq!(2, snapshot.sent_event_count);
     assert_eq!(Some(1), snapshot.dropped_event_count);
 }
+
+#[derive(Debug)]
+struct OddIsLowPriority;
+
+impl PriorityClassifier<u64> for OddIsLowPriority {
+    fn retain_high_priority(&self, item: u64) -> Option<u64> {
+        (item % 2 == 0).then(|| item)
+    }
+}
+
+#[tokio::test]
+async fn test_sender_block_priority_lanes() {
+    // Get a non-overflow buffer in blocking mode with a capacity of 2, treating odd items as low
+    // priority.
+    let (mut tx, rx, handle) = build_buffer(2, WhenFull::Block, None).await;
+    tx.set_priority_lanes(Some(Arc::new(OddIsLowPriority)));
+
+    // We should be able to send two messages through unimpeded.
+    assert_send_ok_with_capacities(&mut tx, 2, Some(1), None).await;
+    assert_send_ok_with_capacities(&mut tx, 3, Some(0), None).await;
+
+    // Low priority items are then dropped instead of blocking.
+    assert_send_ok_with_capacities(&mut tx, 5, Some(0), None).await;
+    assert_send_ok_with_capacities(&mut tx, 7, Some(0), None).await;
+    assert_eq!(Some(2), handle.snapshot().dropped_event_count);
+
+    // While high priority items still block until the receiver makes room for them.
+    let mut results = blocking_send_and_drain_receiver(tx, rx, 4).await;
+    results.sort_unstable();
+    assert_eq!(results, vec![2, 3, 4]);
+}
@@ -0,0 +1,57 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use futures::task::AtomicWaker;
+
+use crate::inspection::{walk_buffer_dir, DiskBufferInspector, DiskBufferStats};
+
+/// Inspects a running buffer, and relays compaction requests to its reader.
+#[derive(Debug)]
+pub struct Inspector {
+    path: PathBuf,
+    compaction_requested: AtomicBool,
+    write_notifier: Arc<AtomicWaker>,
+}
+
+impl Inspector {
+    pub(crate) fn new(path: PathBuf, write_notifier: Arc<AtomicWaker>) -> Self {
+        Self {
+            path,
+            compaction_requested: AtomicBool::new(false),
+            write_notifier,
+        }
+    }
+
+    /// Returns whether a compaction was requested and not yet taken.
+    pub(crate) fn is_compaction_requested(&self) -> bool {
+        self.compaction_requested.load(Ordering::Acquire)
+    }
+
+    /// Consumes any pending compaction request, returning whether there was one.
+    pub(crate) fn take_compaction_request(&self) -> bool {
+        self.compaction_requested.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl DiskBufferInspector for Inspector {
+    fn stats(&self) -> io::Result<DiskBufferStats> {
+        // LevelDB keeps its records in a log and in sorted tables, the latter being what compactions
+        // rewrite. Records don't carry their write time, so their age isn't known.
+        walk_buffer_dir(&self.path, |name| {
+            name.ends_with(".ldb") || name.ends_with(".sst")
+        })
+    }
+
+    fn request_compaction(&self) {
+        self.compaction_requested.store(true, Ordering::Release);
+
+        // Wake the reader up, as compactions are run by it.
+        self.write_notifier.wake();
+    }
+}
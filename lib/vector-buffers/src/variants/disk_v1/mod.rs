@@ -1,4 +1,5 @@
 mod acknowledgements;
+mod inspector;
 mod key;
 mod reader;
 mod writer;
@@ -15,7 +16,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicU64, AtomicUsize},
-        Arc,
+        Arc, Weak,
     },
 };
 
@@ -33,16 +34,18 @@ use tokio::time::Instant;
 
 use crate::{
     buffer_usage_data::BufferUsageHandle,
+    encryption::{is_encrypted_buffer, mark_encrypted_buffer},
+    inspection::{self, DiskBufferInspector},
     topology::{
         acks::OrderedAcknowledgements,
         builder::IntoBuffer,
         channel::{ReceiverAdapter, SenderAdapter},
     },
-    Acker, Bufferable,
+    Acker, Bufferable, EncryptionKey,
 };
 
-use self::key::Key;
 pub use self::{acknowledgements::create_disk_v1_acker, reader::Reader, writer::Writer};
+use self::{inspector::Inspector, key::Key};
 
 /// How much of disk buffer needs to be deleted before we trigger compaction.
 const MAX_UNCOMPACTED_DENOMINATOR: u64 = 10;
@@ -63,12 +66,20 @@ pub enum DataDirError {
         data_dir: PathBuf,
         source: leveldb::database::error::Error,
     },
+    #[snafu(display(
+        "The buffer at {:?} holds records written {}, please read them all before turning encryption {}, or move the buffer directory away",
+        data_dir,
+        if *encrypted { "unencrypted" } else { "encrypted" },
+        if *encrypted { "on" } else { "off" }
+    ))]
+    EncryptionChanged { data_dir: PathBuf, encrypted: bool },
 }
 
 pub struct DiskV1Buffer {
     id: String,
     data_dir: PathBuf,
     max_size: u64,
+    encryption: Option<EncryptionKey>,
 }
 
 impl DiskV1Buffer {
@@ -77,8 +88,15 @@ impl DiskV1Buffer {
             id,
             data_dir,
             max_size,
+            encryption: None,
         }
     }
+
+    /// Encrypts the records written to disk with the given key, if any.
+    pub fn with_encryption(mut self, encryption: Option<EncryptionKey>) -> Self {
+        self.encryption = encryption;
+        self
+    }
 }
 
 #[async_trait]
@@ -86,6 +104,10 @@ impl<T> IntoBuffer<T> for DiskV1Buffer
 where
     T: Bufferable + Clone,
 {
+    fn buffer_type(&self) -> &'static str {
+        "disk"
+    }
+
     fn provides_instrumentation(&self) -> bool {
         true
     }
@@ -98,7 +120,18 @@ where
         usage_handle.set_buffer_limits(Some(self.max_size), None);
 
         // Create the actual buffer subcomponents.
-        let (writer, reader, acker) = open(&self.data_dir, &self.id, self.max_size, usage_handle)?;
+        let (writer, reader, acker) = open(
+            &self.data_dir,
+            &self.id,
+            self.max_size,
+            self.encryption,
+            usage_handle.clone(),
+        )?;
+
+        // Expose the on-disk usage of the buffer, and let it be compacted while it's running.
+        let inspector: Weak<dyn DiskBufferInspector> = Arc::downgrade(&reader.inspector);
+        inspection::register(&self.id, Weak::clone(&inspector));
+        usage_handle.set_disk_inspector(inspector);
 
         Ok((
             SenderAdapter::opaque(writer),
@@ -118,6 +151,7 @@ pub(self) fn open<T>(
     data_dir: &Path,
     name: &str,
     max_size: u64,
+    encryption: Option<EncryptionKey>,
     usage_handle: BufferUsageHandle,
 ) -> Result<(Writer<T>, Reader<T>, Acker), DataDirError>
 where
@@ -174,7 +208,7 @@ where
             //
             // If there's no data in the old style path, though, we just delete the directory and move
             // on: no need to emit anything because nothing is being lost.
-            let old_buffer_state = db_initial_state::<T>(&old_path)?;
+            let old_buffer_state = db_initial_state::<T>(&old_path, encryption.as_ref())?;
             if old_buffer_state.total_bytes != 0 || old_buffer_state.total_records != 0 {
                 // The old style path still has some data, so all we're going to do is warn the user
                 // that this is the case, since we don't want to risk reading older records that
@@ -212,7 +246,7 @@ where
         }
     }
 
-    build(&path, max_size, usage_handle)
+    build(&path, max_size, encryption, usage_handle)
 }
 
 #[derive(Default)]
@@ -238,7 +272,10 @@ impl BufferState {
 ///
 /// The state includes the necessary information to adjust buffer metrics (event count and bytes
 /// consumed) as well as information required for the writer to know the next key to write to.
-fn db_initial_state<T>(path: &Path) -> Result<BufferState, DataDirError>
+fn db_initial_state<T>(
+    path: &Path,
+    encryption: Option<&EncryptionKey>,
+) -> Result<BufferState, DataDirError>
 where
     T: Bufferable,
 {
@@ -272,7 +309,7 @@ where
     let read_offset = first_key;
     let write_offset = last_key.map(|key| {
         let value = last_value.expect("can't have a last key without a last value");
-        match T::decode(T::get_metadata(), &value[..]) {
+        match decode_value::<T>(&value, encryption) {
             Ok(record) => {
                 let event_count = record.event_count();
                 total_events += event_count as u64;
@@ -331,6 +368,28 @@ where
     })
 }
 
+/// Checks that the records of the buffer at `path` can be read with the configured encryption,
+/// recording whether records are encrypted if the buffer is empty.
+fn check_encryption(path: &Path, encrypted: bool) -> Result<(), DataDirError> {
+    if is_encrypted_buffer(path) == encrypted {
+        return Ok(());
+    }
+
+    let mut options = Options::new();
+    options.create_if_missing = true;
+    let db: Database<Key> = Database::open(path, options).with_context(|_| OpenSnafu {
+        data_dir: path.parent().expect("always a parent"),
+    })?;
+    if db.iter(ReadOptions::new()).next().is_some() {
+        return Err(DataDirError::EncryptionChanged {
+            data_dir: path.to_path_buf(),
+            encrypted,
+        });
+    }
+
+    mark_encrypted_buffer(path, encrypted).map_err(|e| map_io_error(e, path))
+}
+
 /// Build a new `DiskBuffer` rooted at `path`
 ///
 /// # Errors
@@ -341,6 +400,7 @@ where
 pub fn build<T: Bufferable>(
     path: &Path,
     max_size: u64,
+    encryption: Option<EncryptionKey>,
     usage_handle: BufferUsageHandle,
 ) -> Result<(Writer<T>, Reader<T>, Acker), DataDirError> {
     // New `max_size` of the buffer is used for storing the unacked events.
@@ -348,7 +408,11 @@ pub fn build<T: Bufferable>(
     let max_uncompacted_size = max_size / MAX_UNCOMPACTED_DENOMINATOR;
     let max_size = max_size - max_uncompacted_size;
 
-    let initial_state = db_initial_state::<T>(path)?;
+    // The initial state is read with the configured encryption, which would drop records written
+    // with the other setting as undecodable, so the setting is checked first.
+    check_encryption(path, encryption.is_some())?;
+
+    let initial_state = db_initial_state::<T>(path, encryption.as_ref())?;
     usage_handle.increment_received_event_count_and_byte_size(
         initial_state.total_events,
         initial_state.total_bytes,
@@ -371,6 +435,10 @@ pub fn build<T: Bufferable>(
     let blocked_write_tasks = Arc::new(Mutex::new(Vec::new()));
     let ack_counter = Arc::new(AtomicUsize::new(0));
     let acker = create_disk_v1_acker(&ack_counter, &write_notifier);
+    let inspector = Arc::new(Inspector::new(
+        path.to_path_buf(),
+        Arc::clone(&write_notifier),
+    ));
 
     let writer = Writer {
         db: Some(Arc::clone(&db)),
@@ -383,6 +451,7 @@ pub fn build<T: Bufferable>(
         current_size: Arc::clone(&current_size),
         slot: None,
         usage_handle: usage_handle.clone(),
+        encryption: encryption.clone(),
     };
 
     let reader = Reader {
@@ -402,12 +471,28 @@ pub fn build<T: Bufferable>(
         last_flush: Instant::now(),
         pending_read: None,
         usage_handle,
+        encryption,
+        inspector,
         phantom: PhantomData,
     };
 
     Ok((writer, reader, acker))
 }
 
+/// Decodes a value stored in the database, decrypting it first if the buffer is encrypted.
+pub(self) fn decode_value<T: Bufferable>(
+    value: &[u8],
+    encryption: Option<&EncryptionKey>,
+) -> Result<T, Box<dyn Error + Send + Sync>> {
+    match encryption {
+        Some(key) => {
+            let decrypted = key.decrypt(value)?;
+            Ok(T::decode(T::get_metadata(), &decrypted[..])?)
+        }
+        None => Ok(T::decode(T::get_metadata(), value)?),
+    }
+}
+
 fn map_io_error<P>(e: io::Error, data_dir: P) -> DataDirError
 where
     P: AsRef<Path>,
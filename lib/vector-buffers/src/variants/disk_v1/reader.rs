@@ -1,5 +1,6 @@
 use std::{
     collections::VecDeque,
+    error::Error,
     fmt,
     future::Future,
     marker::PhantomData,
@@ -12,7 +13,6 @@ use std::{
     time::Duration,
 };
 
-use bytes::Bytes;
 use futures::{task::AtomicWaker, Stream};
 use leveldb::database::{
     batch::{Batch, Writebatch},
@@ -24,11 +24,11 @@ use leveldb::database::{
 use parking_lot::Mutex;
 use tokio::{task::JoinHandle, time::Instant};
 
-use super::Key;
+use super::{decode_value, Inspector, Key};
 use crate::{
     buffer_usage_data::BufferUsageHandle,
     topology::acks::{EligibleMarker, EligibleMarkerLength, MarkerError, OrderedAcknowledgements},
-    Bufferable,
+    Bufferable, EncryptionKey,
 };
 
 /// How much time needs to pass between compaction to trigger new one.
@@ -90,6 +90,10 @@ pub struct Reader<T> {
     pub(crate) pending_read: Option<JoinHandle<Vec<(Key, Vec<u8>)>>>,
     // Buffer usage data.
     pub(crate) usage_handle: BufferUsageHandle,
+    // Key decrypting the items after they're read, if any.
+    pub(crate) encryption: Option<EncryptionKey>,
+    // Inspector of the buffer, through which compactions are requested.
+    pub(crate) inspector: Arc<Inspector>,
     pub(crate) phantom: PhantomData<T>,
 }
 
@@ -189,11 +193,13 @@ impl<T> Drop for Reader<T> {
 impl<T: Bufferable> Reader<T> {
     /// Decodes the next buffered record, if one is available.
     #[cfg_attr(test, instrument(skip(self), level = "trace"))]
-    fn decode_next_record(&mut self) -> Option<(Key, usize, Result<T, T::DecodeError>)> {
+    fn decode_next_record(
+        &mut self,
+    ) -> Option<(Key, usize, Result<T, Box<dyn Error + Send + Sync>>)> {
+        let encryption = self.encryption.as_ref();
         self.buffer.pop_front().map(|(key, value)| {
             let item_bytes = value.len();
-            let decode_buf = Bytes::from(value);
-            (key, item_bytes, T::decode(T::get_metadata(), decode_buf))
+            (key, item_bytes, decode_value(&value, encryption))
         })
     }
 }
@@ -248,8 +254,8 @@ impl<T> Reader<T> {
     /// Flushes are driven based on elapsed time to coalsece operations that require modifying the database.
     #[cfg_attr(test, instrument(skip(self), level = "trace"))]
     fn try_flush(&mut self) {
-        // Don't flush unless we've overrun our flush interval.
-        if self.last_flush.elapsed() < FLUSH_INTERVAL {
+        // Don't flush unless we've overrun our flush interval, or a compaction was requested.
+        if self.last_flush.elapsed() < FLUSH_INTERVAL && !self.inspector.is_compaction_requested() {
             trace!("Last flush was too recent to run again.");
             return;
         }
@@ -336,6 +342,19 @@ impl<T> Reader<T> {
     /// Compaction will only be triggered if certain criteria are met, which are specifically
     /// documented below.
     pub(super) fn try_compact(&mut self) {
+        // A requested compaction covers all of the deleted records, including those of previous
+        // compactions, as small compactions can leave LevelDB files behind.
+        if self.inspector.take_compaction_request() {
+            self.uncompacted_size = 0;
+
+            debug!("Compacting disk buffer on request.");
+            self.db.compact(&Key(0), &Key(self.delete_offset));
+
+            self.compacted_offset = self.delete_offset;
+            self.last_compaction = Instant::now();
+            return;
+        }
+
         // Compaction can be triggered in two ways:
         //  1. When size of uncompacted is a percentage of total allowed size.
         //     Managed by MAX_UNCOMPACTED. This is to limit the size of disk buffer
@@ -389,6 +408,7 @@ impl<T: Bufferable> fmt::Debug for Reader<T> {
             .field("last_flush", &self.last_flush)
             .field("pending_read", &self.pending_read)
             .field("usage_handle", &self.usage_handle)
+            .field("inspector", &self.inspector)
             .field("phantom", &self.phantom)
             .finish()
     }
@@ -0,0 +1,138 @@
+use futures::{SinkExt, StreamExt};
+use leveldb::{iterator::Iterable, options::ReadOptions};
+
+use super::{
+    create_default_buffer_v1, create_encrypted_buffer_v1, DEFAULT_DISK_BUFFER_V1_SIZE_BYTES,
+};
+use crate::{
+    buffer_usage_data::BufferUsageHandle,
+    test::common::{with_temp_dir, SizedRecord},
+    variants::disk_v1::{open, DataDirError},
+    EncryptionKey, WhenFull,
+};
+
+fn key(encoded: &str) -> EncryptionKey {
+    EncryptionKey::try_from(encoded.to_owned()).expect("key should be valid")
+}
+
+const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+const OTHER_KEY: &str = "HxwdHhobGBkWFxQVEhMQEQ4PDA0KCwgJBgcEBQIDAAE=";
+
+#[tokio::test]
+async fn encrypted_records_survive_reopening() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let expected_items = (64..96).map(SizedRecord).collect::<Vec<_>>();
+
+            // Write the records and close the buffer without reading them.
+            let (mut writer, reader, _acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(KEY));
+            for item in expected_items.clone() {
+                writer.send(item).await.expect("write should not fail");
+            }
+            writer.flush().await.expect("writer flush should not fail");
+
+            // None of the stored values should contain the plaintext padding of the records.
+            let values = reader
+                .db
+                .iter(ReadOptions::new())
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>();
+            assert_eq!(values.len(), expected_items.len());
+            for value in values {
+                assert!(!value.windows(16).any(|window| window == [0x42; 16]));
+            }
+            drop(writer);
+            drop(reader);
+
+            // Reopening the buffer with the same key reads them all back.
+            let (writer, mut reader, acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(KEY));
+            drop(writer);
+            let mut actual_items = Vec::new();
+            while let Some(item) = reader.next().await {
+                actual_items.push(item);
+                acker.ack(1);
+            }
+            assert_eq!(actual_items, expected_items);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn records_encrypted_with_another_key_are_dropped() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, reader, _acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(KEY));
+            for item in (64..96).map(SizedRecord) {
+                writer.send(item).await.expect("write should not fail");
+            }
+            writer.flush().await.expect("writer flush should not fail");
+            drop(writer);
+            drop(reader);
+
+            let (writer, mut reader, _acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(OTHER_KEY));
+            drop(writer);
+            assert_eq!(reader.next().await, None);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn encryption_only_changes_while_empty() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // An empty buffer can start encrypting its records.
+            let (writer, reader, _acker) = create_default_buffer_v1::<_, SizedRecord>(&data_dir);
+            drop(writer);
+            drop(reader);
+            let (writer, reader, _acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(KEY));
+            drop(writer);
+            drop(reader);
+
+            // Once it holds encrypted records, it can't stop encrypting them.
+            let (mut writer, reader, _acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(KEY));
+            writer
+                .send(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            drop(writer);
+            drop(reader);
+
+            let result = open::<SizedRecord>(
+                &data_dir,
+                "disk_buffer_v1",
+                DEFAULT_DISK_BUFFER_V1_SIZE_BYTES,
+                None,
+                BufferUsageHandle::noop(WhenFull::Block),
+            );
+            assert!(matches!(
+                result,
+                Err(DataDirError::EncryptionChanged {
+                    encrypted: false,
+                    ..
+                })
+            ));
+
+            // The encrypted records are still there to be read.
+            let (writer, mut reader, _acker) =
+                create_encrypted_buffer_v1::<_, SizedRecord>(&data_dir, key(KEY));
+            drop(writer);
+            assert_eq!(reader.next().await, Some(SizedRecord(64)));
+        }
+    })
+    .await;
+}
@@ -6,13 +6,14 @@ use tracing::{Metadata, Span};
 
 use crate::{
     buffer_usage_data::BufferUsageHandle, test::common::install_tracing_helpers,
-    variants::disk_v1::reader::FLUSH_INTERVAL, Acker, Bufferable, WhenFull,
+    variants::disk_v1::reader::FLUSH_INTERVAL, Acker, Bufferable, EncryptionKey, WhenFull,
 };
 
 use super::{open, Reader, Writer};
 
 mod acknowledgements;
 mod basic;
+mod encryption;
 mod event_count;
 mod naming;
 
@@ -29,6 +30,7 @@ where
         data_dir.as_ref(),
         "disk_buffer_v1",
         DEFAULT_DISK_BUFFER_V1_SIZE_BYTES,
+        None,
         usage_handle,
     )
     .expect("should not fail to create buffer")
@@ -46,6 +48,7 @@ where
         data_dir.as_ref(),
         "disk_buffer_v1",
         DEFAULT_DISK_BUFFER_V1_SIZE_BYTES,
+        None,
         usage_handle.clone(),
     )
     .expect("should not fail to create buffer");
@@ -53,6 +56,25 @@ where
     (writer, reader, acker, usage_handle)
 }
 
+pub(crate) fn create_encrypted_buffer_v1<P, R>(
+    data_dir: P,
+    key: EncryptionKey,
+) -> (Writer<R>, Reader<R>, Acker)
+where
+    P: AsRef<Path>,
+    R: Bufferable + Clone,
+{
+    let usage_handle = BufferUsageHandle::noop(WhenFull::Block);
+    open(
+        data_dir.as_ref(),
+        "disk_buffer_v1",
+        DEFAULT_DISK_BUFFER_V1_SIZE_BYTES,
+        Some(key),
+        usage_handle,
+    )
+    .expect("should not fail to create buffer")
+}
+
 async fn drive_reader_to_flush<T: Bufferable>(reader: &mut Reader<T>) {
     tokio::time::advance(FLUSH_INTERVAL).await;
 
@@ -17,7 +17,7 @@ use leveldb::database::{
 use parking_lot::Mutex;
 
 use super::Key;
-use crate::{buffer_usage_data::BufferUsageHandle, Bufferable};
+use crate::{buffer_usage_data::BufferUsageHandle, Bufferable, EncryptionKey};
 
 /// The writer side of N to 1 channel through leveldb.
 pub struct Writer<T>
@@ -49,6 +49,8 @@ where
     pub(crate) slot: Option<T>,
     /// Buffer usage data.
     pub(crate) usage_handle: BufferUsageHandle,
+    /// Key encrypting the items before they're written, if any.
+    pub(crate) encryption: Option<EncryptionKey>,
 }
 
 // Writebatch isn't Send, but the leveldb docs explicitly say that it's okay to
@@ -72,6 +74,7 @@ where
             current_size: Arc::clone(&self.current_size),
             slot: None,
             usage_handle: self.usage_handle.clone(),
+            encryption: self.encryption.clone(),
         }
     }
 }
@@ -142,7 +145,14 @@ where
         // Encode the item.
         let mut buffer: BytesMut = BytesMut::with_capacity(64);
         T::encode(item, &mut buffer).unwrap();
-        let event_size = buffer.len() as u64;
+
+        // If the buffer is encrypted, what's stored, and accounted for, is the encrypted item.
+        let encrypted = self
+            .encryption
+            .as_ref()
+            .map(|key| key.encrypt(&buffer).expect("failed to encrypt item"));
+        let value = encrypted.as_deref().unwrap_or(&buffer[..]);
+        let event_size = value.len() as u64;
 
         // Now that we have the encoded size, see if we can fit this item in the buffer given the
         // current size.  If it won't fit, then give back the item so we can hold on to it and wait
@@ -160,7 +170,7 @@ where
         // quickly calculate the total number of events in the buffer.
         let key = self.offset.fetch_add(event_len, Ordering::Relaxed);
 
-        self.writebatch.put(Key(key), &buffer);
+        self.writebatch.put(Key(key), value);
         self.batch_size += 1;
 
         if self.batch_size >= 100 {
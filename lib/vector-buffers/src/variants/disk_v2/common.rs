@@ -6,6 +6,8 @@ use std::{
 
 use crc32fast::Hasher;
 
+use crate::EncryptionKey;
+
 // We don't want data files to be bigger than 128MB, but we might end up overshooting slightly.
 pub const DEFAULT_MAX_DATA_FILE_SIZE: u64 = 128 * 1024 * 1024;
 // There's no particular reason that _has_ to be 8MB, it's just a simple default we've chosen here.
@@ -62,6 +64,12 @@ pub struct DiskBufferConfig {
     /// In the event that data had not yet been durably written to disk, and Vector crashed, the
     /// amount of data written since the last flush would be lost.
     pub(crate) flush_interval: Duration,
+
+    /// Key encrypting the payload of records written to data files, if any.
+    ///
+    /// The record framing, and checksum, are left in the clear, so that the buffer can still
+    /// detect corrupted records without decrypting them.
+    pub(crate) encryption: Option<EncryptionKey>,
 }
 
 impl DiskBufferConfig {
@@ -75,6 +83,7 @@ impl DiskBufferConfig {
             max_data_file_size: None,
             max_record_size: None,
             flush_interval: None,
+            encryption: None,
         }
     }
 }
@@ -86,6 +95,7 @@ pub struct DiskBufferConfigBuilder {
     max_data_file_size: Option<u64>,
     max_record_size: Option<usize>,
     flush_interval: Option<Duration>,
+    encryption: Option<EncryptionKey>,
 }
 
 impl DiskBufferConfigBuilder {
@@ -148,6 +158,14 @@ impl DiskBufferConfigBuilder {
         self
     }
 
+    /// Sets the key encrypting the payload of records written to data files.
+    ///
+    /// Defaults to no encryption.
+    pub fn encryption(mut self, key: Option<EncryptionKey>) -> Self {
+        self.encryption = key;
+        self
+    }
+
     /// Consumes this builder and constructs a `DiskBufferConfig`.
     pub fn build(self) -> DiskBufferConfig {
         // TODO: Make config building fallible so we can validate our values, as part of satisfying
@@ -184,6 +202,7 @@ impl DiskBufferConfigBuilder {
             max_data_file_size,
             max_record_size,
             flush_interval,
+            encryption: self.encryption,
         }
     }
 }
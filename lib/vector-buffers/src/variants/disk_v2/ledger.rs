@@ -2,7 +2,7 @@ use std::{
     fmt, io,
     path::PathBuf,
     sync::atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use bytecheck::CheckBytes;
@@ -23,7 +23,11 @@ use super::{
     common::{DiskBufferConfig, MAX_FILE_ID},
     ser::SerializeError,
 };
-use crate::buffer_usage_data::BufferUsageHandle;
+use crate::{
+    buffer_usage_data::BufferUsageHandle,
+    encryption::{is_encrypted_buffer, mark_encrypted_buffer},
+    inspection::{file_age, walk_buffer_dir, DiskBufferInspector, DiskBufferStats},
+};
 
 /// Error that occurred during calls to [`Ledger`].
 #[derive(Debug, Snafu)]
@@ -68,6 +72,18 @@ pub enum LedgerLoadCreateError {
     /// buffers required for the serialization step.
     #[snafu(display("failed to serialize ledger to buffer: {}", reason))]
     FailedToSerialize { reason: String },
+
+    /// Encryption was turned on, or off, while the buffer still held records.
+    ///
+    /// The records already in the buffer were written with the previous setting, and so would all
+    /// be read as corrupted with the new one.
+    #[snafu(display(
+        "buffer holds {} records written {}; read them all before turning encryption {}, or move the buffer directory away",
+        records,
+        if *encrypted { "unencrypted" } else { "encrypted" },
+        if *encrypted { "on" } else { "off" }
+    ))]
+    EncryptionChanged { records: u64, encrypted: bool },
 }
 
 /// Ledger state.
@@ -216,6 +232,8 @@ pub struct Ledger {
     writer_notify: Notify,
     // Tracks when writer has fully shutdown.
     writer_done: AtomicBool,
+    // Whether the writer was asked to roll over to the next data file.
+    writer_rollover_requested: AtomicBool,
     // Number of pending record acknowledgements that have yeet to be consumed by the reader.
     pending_acks: AtomicU64,
     // The file ID offset of the reader past the acknowledged reader file ID.
@@ -332,6 +350,11 @@ impl Ledger {
         self.get_data_file_path(self.state().get_next_writer_file_id())
     }
 
+    /// Gets the path of the oldest data file, which holds the oldest unacknowledged records.
+    pub fn get_oldest_data_file_path(&self) -> PathBuf {
+        self.get_data_file_path(self.state().get_current_reader_file_id())
+    }
+
     /// Gets the data file path for an arbitrary file ID.
     pub fn get_data_file_path(&self, file_id: u16) -> PathBuf {
         self.config
@@ -397,6 +420,22 @@ impl Ledger {
         self.writer_done.load(Ordering::Acquire)
     }
 
+    /// Asks the writer to roll over to the next data file before its next write.
+    ///
+    /// Data files are only deleted once the writer has moved on from them, and all of their records
+    /// are acknowledged, so this lets the reader reclaim the space of the current data file without
+    /// waiting for it to be full.
+    pub fn request_writer_rollover(&self) {
+        self.writer_rollover_requested
+            .store(true, Ordering::Release);
+    }
+
+    /// Consumes any pending request for the writer to roll over to the next data file, returning
+    /// whether there was one.
+    pub fn take_writer_rollover_request(&self) -> bool {
+        self.writer_rollover_requested.swap(false, Ordering::AcqRel)
+    }
+
     /// Increments the pending acknowledgement counter by the given amount.
     pub fn increment_pending_acks(&self, amount: u64) {
         self.pending_acks.fetch_add(amount, Ordering::AcqRel);
@@ -607,16 +646,34 @@ impl Ledger {
             reader_notify: Notify::new(),
             writer_notify: Notify::new(),
             writer_done: AtomicBool::new(false),
+            writer_rollover_requested: AtomicBool::new(false),
             pending_acks: AtomicU64::new(0),
             unacked_reader_file_id_offset: AtomicU16::new(0),
             last_flush: AtomicCell::new(Instant::now()),
             usage_handle,
         };
         ledger.update_buffer_size().await?;
+        ledger.check_encryption()?;
 
         Ok(ledger)
     }
 
+    /// Checks that the records of the buffer can be read with the configured encryption, recording
+    /// whether records are encrypted if the buffer is empty.
+    fn check_encryption(&self) -> Result<(), LedgerLoadCreateError> {
+        let encrypted = self.config.encryption.is_some();
+        if is_encrypted_buffer(&self.config.data_dir) == encrypted {
+            return Ok(());
+        }
+
+        let records = self.get_total_records();
+        if records != 0 {
+            return Err(LedgerLoadCreateError::EncryptionChanged { records, encrypted });
+        }
+
+        mark_encrypted_buffer(&self.config.data_dir, encrypted).context(IoSnafu)
+    }
+
     async fn update_buffer_size(&mut self) -> Result<(), LedgerLoadCreateError> {
         // Under normal operation, the reader and writer maintain a consistent state within the
         // ledger.  However, due to the nature of how we update the ledger, process crashes could
@@ -665,6 +722,24 @@ impl Ledger {
     }
 }
 
+impl DiskBufferInspector for Ledger {
+    fn stats(&self) -> io::Result<DiskBufferStats> {
+        let mut stats = walk_buffer_dir(&self.config.data_dir, |name| {
+            name.starts_with("buffer-data-") && name.ends_with(".dat")
+        })?;
+        stats.oldest_record_age = if self.get_total_records() == 0 {
+            Some(Duration::ZERO)
+        } else {
+            file_age(&self.get_oldest_data_file_path())
+        };
+        Ok(stats)
+    }
+
+    fn request_compaction(&self) {
+        self.request_writer_rollover();
+    }
+}
+
 impl fmt::Debug for Ledger {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Ledger")
@@ -683,6 +758,10 @@ impl fmt::Debug for Ledger {
                 &self.unacked_reader_file_id_offset.load(Ordering::Acquire),
             )
             .field("writer_done", &self.writer_done.load(Ordering::Acquire))
+            .field(
+                "writer_rollover_requested",
+                &self.writer_rollover_requested.load(Ordering::Acquire),
+            )
             .field("last_flush", &self.last_flush)
             .finish()
     }
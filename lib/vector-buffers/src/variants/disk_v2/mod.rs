@@ -106,7 +106,7 @@ use std::{
     marker::PhantomData,
     path::PathBuf,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Weak},
     task::{Context, Poll},
 };
 
@@ -138,11 +138,12 @@ pub use self::{
 };
 use crate::{
     buffer_usage_data::BufferUsageHandle,
+    inspection::{self, DiskBufferInspector},
     topology::{
         builder::IntoBuffer,
         channel::{ReceiverAdapter, SenderAdapter},
     },
-    Acker, Bufferable,
+    Acker, Bufferable, EncryptionKey, WhenFull,
 };
 
 /// Error that occurred when creating/loading a disk buffer.
@@ -173,6 +174,17 @@ impl<T> Buffer<T>
 where
     T: Bufferable,
 {
+    /// Creates a new disk buffer from the given [`DiskBufferConfig`].
+    ///
+    /// If successful, a [`Writer`] and [`Reader`] value, representing the write/read sides of the
+    /// buffer, respectively, will be returned.  Additionally, an [`Acker`] will be returned, which
+    /// must be used to indicate when records read from the [`Reader`] can be considered durably
+    /// processed and able to be deleted from the buffer, along with the [`Ledger`] of the buffer.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurred during the creation or loading of the disk buffer, an error variant
+    /// will be returned describing the error.
     #[cfg_attr(test, instrument(skip(config, usage_handle), level = "trace"))]
     pub(crate) async fn from_config_inner(
         config: DiskBufferConfig,
@@ -201,33 +213,37 @@ where
 
         Ok((writer, reader, acker, ledger))
     }
+}
 
-    /// Creates a new disk buffer from the given [`DiskBufferConfig`].
-    ///
-    /// If successful, a [`Writer`] and [`Reader`] value, representing the write/read sides of the
-    /// buffer, respectively, will be returned.  Additionally, an [`Acker`] will be returned, which
-    /// must be used to indicate when records read from the [`Reader`] can be considered durably
-    /// processed and able to be deleted from the buffer.
-    ///
-    /// # Errors
-    ///
-    /// If an error occurred during the creation or loading of the disk buffer, an error variant
-    /// will be returned describing the error.
-    #[cfg_attr(test, instrument(skip(config, usage_handle), level = "trace"))]
-    pub async fn from_config(
-        config: DiskBufferConfig,
-        usage_handle: BufferUsageHandle,
-    ) -> Result<(Writer<T>, Reader<T>, Acker), BufferError<T>> {
-        let (writer, reader, acker, _) = Self::from_config_inner(config, usage_handle).await?;
+/// Opens the disk buffer at the given path to read the records it holds, such as to replay them.
+///
+/// The records are never acknowledged, so the buffer keeps them. As nothing is written to the
+/// buffer, the returned stream ends once all of its records were read.
+///
+/// # Errors
+///
+/// If the buffer can't be loaded, such as when it's in use by a running Vector, an error variant
+/// will be returned.
+pub async fn read_disk_v2_buffer<T>(
+    buffer_path: PathBuf,
+) -> Result<impl Stream<Item = T> + Send, BufferError<T>>
+where
+    T: Bufferable,
+{
+    let config = DiskBufferConfig::from_path(buffer_path).build();
+    let usage_handle = BufferUsageHandle::noop(WhenFull::Block);
+    let (mut writer, reader, _acker, _ledger) =
+        Buffer::from_config_inner(config, usage_handle).await?;
+    writer.close();
 
-        Ok((writer, reader, acker))
-    }
+    Ok(WrappedReader::new(reader))
 }
 
 pub struct DiskV2Buffer {
     id: String,
     data_dir: PathBuf,
     max_size: u64,
+    encryption: Option<EncryptionKey>,
 }
 
 impl DiskV2Buffer {
@@ -236,8 +252,15 @@ impl DiskV2Buffer {
             id,
             data_dir,
             max_size,
+            encryption: None,
         }
     }
+
+    /// Encrypts the records written to disk with the given key, if any.
+    pub fn with_encryption(mut self, encryption: Option<EncryptionKey>) -> Self {
+        self.encryption = encryption;
+        self
+    }
 }
 
 #[async_trait]
@@ -245,6 +268,10 @@ impl<T> IntoBuffer<T> for DiskV2Buffer
 where
     T: Bufferable + Clone,
 {
+    fn buffer_type(&self) -> &'static str {
+        "disk_v2"
+    }
+
     fn provides_instrumentation(&self) -> bool {
         true
     }
@@ -260,8 +287,15 @@ where
         let buffer_path = self.data_dir.join("buffer").join("v2").join(self.id);
         let config = DiskBufferConfig::from_path(buffer_path)
             .max_buffer_size(self.max_size as u64)
+            .encryption(self.encryption)
             .build();
-        let (writer, reader, acker) = Buffer::from_config(config, usage_handle).await?;
+        let (writer, reader, acker, ledger) =
+            Buffer::from_config_inner(config, usage_handle.clone()).await?;
+
+        // Expose the on-disk usage of the buffer, and let it be compacted while it's running.
+        let inspector: Weak<dyn DiskBufferInspector> = Arc::downgrade(&ledger);
+        inspection::register(&self.id, Weak::clone(&inspector));
+        usage_handle.set_disk_inspector(inspector);
 
         let wrapped_reader = WrappedReader::new(reader);
 
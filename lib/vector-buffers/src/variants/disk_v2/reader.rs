@@ -27,7 +27,7 @@ use crate::{
     internal_events::EventsCorrupted,
     topology::acks::{EligibleMarker, EligibleMarkerLength, MarkerError, OrderedAcknowledgements},
     variants::disk_v2::record::try_as_record_archive,
-    Bufferable,
+    Bufferable, EncryptionError, EncryptionKey,
 };
 
 pub(super) struct ReadToken {
@@ -103,6 +103,13 @@ where
         source: <T as Encodable>::DecodeError,
     },
 
+    /// The record payload could not be decrypted.
+    ///
+    /// As the checksum was validated, this indicates that the record was encrypted with another
+    /// key than the one currently configured, or that it was tampered with.
+    #[snafu(display("failed to decrypt record: {}", source))]
+    Decryption { source: EncryptionError },
+
     /// The record is not compatible with this version of Vector.
     ///
     /// This can occur when records written to a buffer in previous versions of Vector are read by
@@ -149,6 +156,7 @@ pub(super) struct RecordReader<R, T> {
     aligned_buf: AlignedVec,
     checksummer: Hasher,
     current_record_id: u64,
+    encryption: Option<EncryptionKey>,
     _t: PhantomData<T>,
 }
 
@@ -167,10 +175,17 @@ where
             aligned_buf: AlignedVec::new(),
             checksummer: create_crc32c_hasher(),
             current_record_id: 0,
+            encryption: None,
             _t: PhantomData,
         }
     }
 
+    /// Decrypts the payload of the records read with the given key, if any.
+    pub fn with_encryption(mut self, encryption: Option<EncryptionKey>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
     /// Gets a reference to the underlying reader.
     pub fn get_ref(&self) -> &R {
         self.reader.get_ref()
@@ -330,7 +345,7 @@ where
         // - `try_next_record` does all the archive checks, checksum validation, etc
         let record = unsafe { archived_root::<Record<'_>>(&self.aligned_buf) };
 
-        decode_record_payload(record)
+        decode_record_payload(record, self.encryption.as_ref())
     }
 }
 
@@ -731,7 +746,10 @@ where
                 "Opened data file for reading."
             );
 
-            self.reader = Some(RecordReader::new(data_file));
+            self.reader = Some(
+                RecordReader::new(data_file)
+                    .with_encryption(self.ledger.config().encryption.clone()),
+            );
             return Ok(());
         }
     }
@@ -801,7 +819,8 @@ where
                 } => {
                     let record = try_as_record_archive(data_file_mmap.as_ref())
                         .expect("record was already validated");
-                    let item = match decode_record_payload::<T>(record) {
+                    let encryption = self.ledger.config().encryption.as_ref();
+                    let item = match decode_record_payload::<T>(record, encryption) {
                         Ok(item) => item,
                         // If there's an error decoding the item, just fall back to the slow path,
                         // because this file might actually be where we left off, so we don't want
@@ -1054,6 +1073,7 @@ where
 
 pub(crate) fn decode_record_payload<T: Bufferable>(
     record: &ArchivedRecord<'_>,
+    encryption: Option<&EncryptionKey>,
 ) -> Result<T, ReaderError<T>> {
     // Try and convert the raw record metadata into the true metadata type used by `T`, and then
     // also verify that `T` is able to decode records with the metadata used for this record in particular.
@@ -1070,6 +1090,12 @@ pub(crate) fn decode_record_payload<T: Bufferable>(
         });
     }
 
-    // Now we can finally try decoding.
-    T::decode(metadata, record.payload()).context(DecodeSnafu)
+    // Now we can finally try decoding, once the payload is decrypted if the buffer is encrypted.
+    match encryption {
+        Some(key) => {
+            let payload = key.decrypt(record.payload()).context(DecryptionSnafu)?;
+            T::decode(metadata, &payload[..]).context(DecodeSnafu)
+        }
+        None => T::decode(metadata, record.payload()).context(DecodeSnafu),
+    }
 }
@@ -0,0 +1,149 @@
+use super::{create_default_buffer_v2, create_encrypted_buffer_v2};
+use crate::{
+    assert_buffer_records,
+    buffer_usage_data::BufferUsageHandle,
+    test::common::{with_temp_dir, SizedRecord},
+    variants::disk_v2::{
+        Buffer, BufferError, DiskBufferConfig, LedgerLoadCreateError, ReaderError,
+    },
+    EncryptionKey, WhenFull,
+};
+
+fn key(encoded: &str) -> EncryptionKey {
+    EncryptionKey::try_from(encoded.to_owned()).expect("key should be valid")
+}
+
+const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+const OTHER_KEY: &str = "HxwdHhobGBkWFxQVEhMQEQ4PDA0KCwgJBgcEBQIDAAE=";
+
+#[tokio::test]
+async fn encrypted_records_survive_reopening() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let expected_items = (64..96).map(SizedRecord).collect::<Vec<_>>();
+
+            // Write the records and close the buffer without reading them.
+            let (mut writer, reader, _acker, ledger) =
+                create_encrypted_buffer_v2::<_, SizedRecord>(&data_dir, key(KEY)).await;
+            for item in expected_items.clone() {
+                writer
+                    .write_record(item)
+                    .await
+                    .expect("write should not fail");
+            }
+            writer.flush().await.expect("writer flush should not fail");
+            assert_buffer_records!(ledger, expected_items.len());
+
+            // The data file shouldn't contain the plaintext padding of the records.
+            let data_file = tokio::fs::read(ledger.get_current_writer_data_file_path())
+                .await
+                .expect("data file should be readable");
+            assert!(!data_file.windows(16).any(|window| window == [0x42; 16]));
+            drop(writer);
+            drop(reader);
+            drop(ledger);
+
+            // Reopening the buffer with the same key reads them all back.
+            let (writer, mut reader, acker, _ledger) =
+                create_encrypted_buffer_v2::<_, SizedRecord>(&data_dir, key(KEY)).await;
+            drop(writer);
+            let mut actual_items = Vec::new();
+            while let Some(item) = reader.next().await.expect("reader should not fail") {
+                actual_items.push(item);
+                acker.ack(1);
+            }
+            assert_eq!(actual_items, expected_items);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn records_encrypted_with_another_key_fail_to_decrypt() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, reader, _acker, ledger) =
+                create_encrypted_buffer_v2::<_, SizedRecord>(&data_dir, key(KEY)).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            drop(writer);
+            drop(reader);
+            drop(ledger);
+
+            let (writer, mut reader, _acker, _ledger) =
+                create_encrypted_buffer_v2::<_, SizedRecord>(&data_dir, key(OTHER_KEY)).await;
+            drop(writer);
+            assert!(matches!(
+                reader.next().await,
+                Err(ReaderError::Decryption { .. })
+            ));
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn encryption_only_changes_while_empty() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            // Once the buffer holds unencrypted records, it can't start encrypting them.
+            let (mut writer, reader, _acker, ledger) =
+                create_default_buffer_v2::<_, SizedRecord>(&data_dir).await;
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            drop(writer);
+            drop(reader);
+            drop(ledger);
+
+            let config = DiskBufferConfig::from_path(&data_dir)
+                .encryption(Some(key(KEY)))
+                .build();
+            let result = Buffer::<SizedRecord>::from_config_inner(
+                config,
+                BufferUsageHandle::noop(WhenFull::Block),
+            )
+            .await;
+            assert!(matches!(
+                result,
+                Err(BufferError::LedgerError {
+                    source: LedgerLoadCreateError::EncryptionChanged {
+                        records: 1,
+                        encrypted: true,
+                    },
+                })
+            ));
+
+            // After the records are read, it can.
+            let (writer, mut reader, acker, ledger) =
+                create_default_buffer_v2::<_, SizedRecord>(&data_dir).await;
+            drop(writer);
+            assert_eq!(
+                reader.next().await.expect("read should not fail"),
+                Some(SizedRecord(64))
+            );
+            acker.ack(1);
+            assert_eq!(reader.next().await.expect("read should not fail"), None);
+            assert_buffer_records!(ledger, 0);
+            drop(reader);
+            drop(ledger);
+
+            let (writer, reader, _acker, _ledger) =
+                create_encrypted_buffer_v2::<_, SizedRecord>(&data_dir, key(KEY)).await;
+            drop(writer);
+            drop(reader);
+        }
+    })
+    .await;
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use super::create_default_buffer_v2;
+use crate::{
+    inspection::DiskBufferInspector,
+    test::common::{with_temp_dir, SizedRecord},
+};
+
+#[tokio::test]
+async fn stats_reflect_data_files() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, acker, ledger) = create_default_buffer_v2(data_dir).await;
+
+            // An empty buffer has no oldest record.
+            let stats = ledger.stats().expect("stats should not fail");
+            assert_eq!(stats.oldest_record_age, Some(Duration::ZERO));
+
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+
+            let stats = ledger.stats().expect("stats should not fail");
+            assert_eq!(stats.segments, 1);
+            assert!(stats.disk_bytes > 64);
+            assert!(stats.oldest_record_age.is_some());
+
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(64)));
+            acker.ack(1);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn compaction_rolls_over_to_next_data_file() {
+    with_temp_dir(|dir| {
+        let data_dir = dir.to_path_buf();
+
+        async move {
+            let (mut writer, mut reader, acker, ledger) = create_default_buffer_v2(data_dir).await;
+
+            writer
+                .write_record(SizedRecord(64))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            let first_data_file = ledger.get_current_writer_data_file_path();
+
+            // Once read and acknowledged, the record is still held by the data file, as the writer
+            // hasn't moved on from it.
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(64)));
+            acker.ack(1);
+
+            // Compacting the buffer makes the next write go to the next data file.
+            ledger.request_compaction();
+            writer
+                .write_record(SizedRecord(65))
+                .await
+                .expect("write should not fail");
+            writer.flush().await.expect("writer flush should not fail");
+            assert_eq!(ledger.get_current_writer_file_id(), 1);
+
+            // Moving on to the next data file, the reader deletes the first one.
+            let record = reader.next().await.expect("read should not fail");
+            assert_eq!(record, Some(SizedRecord(65)));
+            assert!(!first_data_file.exists());
+            assert_eq!(ledger.stats().expect("stats should not fail").segments, 1);
+        }
+    })
+    .await;
+}
@@ -1,10 +1,12 @@
 use std::{path::Path, sync::Arc};
 
 use super::{Buffer, DiskBufferConfig, Ledger, Reader, Writer};
-use crate::{buffer_usage_data::BufferUsageHandle, Acker, Bufferable, WhenFull};
+use crate::{buffer_usage_data::BufferUsageHandle, Acker, Bufferable, EncryptionKey, WhenFull};
 
 mod acknowledgements;
 mod basic;
+mod encryption;
+mod inspection;
 mod invariants;
 mod known_errors;
 mod record;
@@ -206,3 +208,21 @@ where
         .await
         .expect("should not fail to create buffer")
 }
+
+pub(crate) async fn create_encrypted_buffer_v2<P, R>(
+    data_dir: P,
+    key: EncryptionKey,
+) -> (Writer<R>, Reader<R>, Acker, Arc<Ledger>)
+where
+    P: AsRef<Path>,
+    R: Bufferable,
+{
+    let config = DiskBufferConfig::from_path(data_dir)
+        .encryption(Some(key))
+        .build();
+    let usage_handle = BufferUsageHandle::noop(WhenFull::Block);
+
+    Buffer::from_config_inner(config, usage_handle)
+        .await
+        .expect("should not fail to create buffer")
+}
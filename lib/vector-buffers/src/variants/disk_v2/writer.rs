@@ -33,7 +33,7 @@ use super::{
 use crate::{
     encoding::{AsMetadata, Encodable},
     variants::disk_v2::{reader::decode_record_payload, record::try_as_record_archive},
-    Bufferable,
+    Bufferable, EncryptionError, EncryptionKey,
 };
 
 /// Error that occurred during calls to [`Writer`].
@@ -96,6 +96,10 @@ where
         source: <T as Encodable>::EncodeError,
     },
 
+    /// The writer failed to encrypt the encoded record.
+    #[snafu(display("failed to encrypt record: {}", source))]
+    FailedToEncrypt { source: EncryptionError },
+
     /// The writer failed to serialize the record.
     ///
     /// As records are encoded and then wrapped in a container which carries metadata about the size
@@ -154,6 +158,7 @@ pub(super) struct RecordWriter<W, T> {
     max_record_size: usize,
     current_data_file_size: u64,
     max_data_file_size: u64,
+    encryption: Option<EncryptionKey>,
     _t: PhantomData<T>,
 }
 
@@ -181,10 +186,17 @@ where
             max_record_size,
             current_data_file_size,
             max_data_file_size,
+            encryption: None,
             _t: PhantomData,
         }
     }
 
+    /// Encrypts the payload of the records written with the given key, if any.
+    pub fn with_encryption(mut self, encryption: Option<EncryptionKey>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
     /// Gets a reference to the underlying writer.
     pub fn get_ref(&self) -> &W {
         self.writer.get_ref()
@@ -243,9 +255,19 @@ where
             });
         }
 
+        // If the buffer is encrypted, the payload of the record is the encrypted record, which the
+        // checksum then covers.
+        let encrypted = match &self.encryption {
+            Some(key) => Some(
+                key.encrypt(&self.encode_buf)
+                    .context(FailedToEncryptSnafu)?,
+            ),
+            None => None,
+        };
+        let payload = encrypted.as_deref().unwrap_or(&self.encode_buf[..]);
+
         let metadata = T::get_metadata().into_u32();
-        let wrapped_record =
-            Record::with_checksum(id, metadata, &self.encode_buf, &self.checksummer);
+        let wrapped_record = Record::with_checksum(id, metadata, payload, &self.checksummer);
 
         // TODO: This could be a good spot to potentially calculate the on-disk size of the archived
         // record, but to do that correctly, it involves needing a few things:
@@ -496,11 +518,10 @@ where
                 // next writer record ID should be.
                 let record = try_as_record_archive(data_file_mmap.as_ref())
                     .expect("record was already validated");
-                let item = decode_record_payload::<T>(record).map_err(|e| {
-                    WriterError::FailedToValidate {
+                let item = decode_record_payload::<T>(record, self.config.encryption.as_ref())
+                    .map_err(|e| WriterError::FailedToValidate {
                         reason: e.to_string(),
-                    }
-                })?;
+                    })?;
 
                 // Since we have a valid record, checksum and all, see if the writer record ID
                 // in the ledger lines up with the record ID we have here.  Specifically, the record
@@ -624,6 +645,12 @@ where
         // need to skip to the next file, we honor that here.
         let mut should_open_next = self.should_skip();
         if self.writer.is_some() {
+            // If a compaction asked us to move on from the current data file, treat it as full, so
+            // that it can be deleted as soon as all of its records are acknowledged.
+            if self.data_file_size > 0 && self.ledger.take_writer_rollover_request() {
+                self.mark_data_file_full();
+            }
+
             if self.can_write() {
                 return Ok(());
             }
@@ -723,12 +750,15 @@ where
                 // Make sure the file is flushed to disk, especially if we just created it.
                 data_file.sync_all().await?;
 
-                self.writer = Some(RecordWriter::new(
-                    data_file,
-                    data_file_size,
-                    self.config.max_data_file_size,
-                    self.config.max_record_size,
-                ));
+                self.writer = Some(
+                    RecordWriter::new(
+                        data_file,
+                        data_file_size,
+                        self.config.max_data_file_size,
+                        self.config.max_record_size,
+                    )
+                    .with_encryption(self.config.encryption.clone()),
+                );
                 self.data_file_size = data_file_size;
 
                 // If we opened the "next" data file, we need to increment the current writer
@@ -26,6 +26,10 @@ impl<T> IntoBuffer<T> for MemoryBuffer
 where
     T: Bufferable,
 {
+    fn buffer_type(&self) -> &'static str {
+        "memory"
+    }
+
     async fn into_buffer_parts(
         self: Box<Self>,
         usage_handle: BufferUsageHandle,
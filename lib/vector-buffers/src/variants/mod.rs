@@ -2,7 +2,7 @@ mod disk_v1;
 pub use disk_v1::DiskV1Buffer;
 
 mod disk_v2;
-pub use disk_v2::DiskV2Buffer;
+pub use disk_v2::{read_disk_v2_buffer, DiskV2Buffer};
 
 mod in_memory;
 pub use in_memory::MemoryBuffer;
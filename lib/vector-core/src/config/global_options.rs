@@ -47,6 +47,10 @@ pub struct GlobalOptions {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub acknowledgements: AcknowledgementsConfig,
+    /// How long the components replaced by a reload are given to drain their events, before being
+    /// killed.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    pub reload_drain_timeout_secs: Option<u64>,
 }
 
 impl GlobalOptions {
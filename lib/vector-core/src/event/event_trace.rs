@@ -0,0 +1,102 @@
+#![deny(missing_docs)]
+
+use chrono::{DateTime, Utc};
+
+/// The trace of the components traversed by an event sampled by the event tracing mode, with when
+/// it entered and left each of them, from the source which emitted it up to the sink it reached.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct EventTrace {
+    id: u64,
+    hops: Vec<TraceHop>,
+}
+
+/// A component traversed by a traced event.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct TraceHop {
+    /// The ID of the component.
+    pub component_id: String,
+    /// The kind of the component, `source`, `transform` or `sink`.
+    pub component_kind: &'static str,
+    /// When the event was received by the component, or emitted for a source.
+    pub entered_at: DateTime<Utc>,
+    /// When the event was sent by the component, if it left it. Sinks don't send the events on.
+    pub exited_at: Option<DateTime<Utc>>,
+}
+
+impl EventTrace {
+    /// Starts the trace of an event emitted by the given source.
+    pub fn new(id: u64, source_id: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            hops: vec![TraceHop {
+                component_id: source_id.into(),
+                component_kind: "source",
+                entered_at: now,
+                exited_at: Some(now),
+            }],
+        }
+    }
+
+    /// The ID of the trace, shared by the copies of the event made when it's sent to several
+    /// components.
+    pub const fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The components traversed by the event, in order.
+    pub fn hops(&self) -> &[TraceHop] {
+        &self.hops
+    }
+
+    /// Records the event being received by the given component.
+    pub fn enter(&mut self, component_id: &str, component_kind: &'static str) {
+        self.hops.push(TraceHop {
+            component_id: component_id.to_owned(),
+            component_kind,
+            entered_at: Utc::now(),
+            exited_at: None,
+        });
+    }
+
+    /// Records the event being sent by the given component. The events created by a transform,
+    /// rather than received, start no hop in it, so they are left as they are.
+    pub fn exit(&mut self, component_id: &str) {
+        if let Some(hop) = self.hops.last_mut() {
+            if hop.component_id == component_id && hop.exited_at.is_none() {
+                hop.exited_at = Some(Utc::now());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_hops() {
+        let mut trace = EventTrace::new(1, "in");
+        trace.enter("parse", "transform");
+        trace.exit("parse");
+        trace.exit("parse");
+        trace.enter("out", "sink");
+
+        let hops = trace
+            .hops()
+            .iter()
+            .map(|hop| (hop.component_id.as_str(), hop.exited_at.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(hops, vec![("in", true), ("parse", true), ("out", false)]);
+        assert!(trace.hops()[1].exited_at.unwrap() >= trace.hops()[1].entered_at);
+    }
+
+    #[test]
+    fn ignores_exits_of_other_components() {
+        let mut trace = EventTrace::new(1, "in");
+        trace.enter("parse", "transform");
+        trace.exit("route");
+
+        assert_eq!(trace.hops()[1].exited_at, None);
+    }
+}
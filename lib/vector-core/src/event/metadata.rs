@@ -5,7 +5,7 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use vector_common::EventDataEq;
 
-use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
+use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus, EventTrace};
 use crate::{schema, ByteSizeOf};
 
 /// The top-level metadata structure contained by both `struct Metric`
@@ -18,6 +18,12 @@ pub struct EventMetadata {
     /// Used to store the Splunk HEC auth token from sources to sinks
     #[serde(default, skip)]
     splunk_hec_token: Option<Arc<str>>,
+    /// Used to store the description of a metric, as the help of a Prometheus metric family
+    #[serde(default, skip)]
+    metric_description: Option<Arc<str>>,
+    /// Used to store the unit of the values of a metric
+    #[serde(default, skip)]
+    metric_unit: Option<Arc<str>>,
     #[serde(default, skip)]
     finalizers: EventFinalizers,
 
@@ -27,6 +33,10 @@ pub struct EventMetadata {
     /// TODO(Jean): must not skip serialization to track schemas across restarts.
     #[serde(default = "default_schema_definition", skip)]
     schema_definition: Arc<schema::Definition>,
+
+    /// The components traversed by the event, if it's sampled by the event tracing mode.
+    #[serde(default, skip)]
+    trace: Option<Box<EventTrace>>,
 }
 
 impl EventMetadata {
@@ -49,6 +59,26 @@ impl EventMetadata {
     pub fn set_splunk_hec_token(&mut self, token: Option<Arc<str>>) {
         self.splunk_hec_token = token;
     }
+
+    /// Return the metric description, if it exists
+    pub fn metric_description(&self) -> &Option<Arc<str>> {
+        &self.metric_description
+    }
+
+    /// Set the metric description to passed value
+    pub fn set_metric_description(&mut self, description: Option<Arc<str>>) {
+        self.metric_description = description;
+    }
+
+    /// Return the metric unit, if it exists
+    pub fn metric_unit(&self) -> &Option<Arc<str>> {
+        &self.metric_unit
+    }
+
+    /// Set the metric unit to passed value
+    pub fn set_metric_unit(&mut self, unit: Option<Arc<str>>) {
+        self.metric_unit = unit;
+    }
 }
 
 impl Default for EventMetadata {
@@ -56,8 +86,11 @@ impl Default for EventMetadata {
         Self {
             datadog_api_key: Default::default(),
             splunk_hec_token: Default::default(),
+            metric_description: None,
+            metric_unit: None,
             finalizers: Default::default(),
             schema_definition: default_schema_definition(),
+            trace: None,
         }
     }
 }
@@ -108,6 +141,7 @@ impl EventMetadata {
     /// Merge the other `EventMetadata` into this.
     /// If a Datadog API key is not set in `self`, the one from `other` will be used.
     /// If a Splunk HEC token is not set in `self`, the one from `other` will be used.
+    /// If a metric description or unit is not set in `self`, the one from `other` will be used.
     pub fn merge(&mut self, other: Self) {
         self.finalizers.merge(other.finalizers);
         if self.datadog_api_key.is_none() {
@@ -116,6 +150,15 @@ impl EventMetadata {
         if self.splunk_hec_token.is_none() {
             self.splunk_hec_token = other.splunk_hec_token;
         }
+        if self.metric_description.is_none() {
+            self.metric_description = other.metric_description;
+        }
+        if self.metric_unit.is_none() {
+            self.metric_unit = other.metric_unit;
+        }
+        if self.trace.is_none() {
+            self.trace = other.trace;
+        }
     }
 
     /// Update the finalizer(s) status.
@@ -152,6 +195,21 @@ impl EventMetadata {
     pub fn set_schema_definition(&mut self, definition: &Arc<schema::Definition>) {
         self.schema_definition = Arc::clone(definition);
     }
+
+    /// Get the trace of the components traversed by the event, if it's traced.
+    pub fn trace(&self) -> Option<&EventTrace> {
+        self.trace.as_deref()
+    }
+
+    /// Get the trace of the event mutably, if it's traced.
+    pub fn trace_mut(&mut self) -> Option<&mut EventTrace> {
+        self.trace.as_deref_mut()
+    }
+
+    /// Start tracing the event with the given trace.
+    pub fn set_trace(&mut self, trace: EventTrace) {
+        self.trace = Some(Box::new(trace));
+    }
 }
 
 impl EventDataEq for EventMetadata {
@@ -12,6 +12,7 @@ use vector_common::EventDataEq;
 use crate::ByteSizeOf;
 pub use ::value::Value;
 pub use array::{into_event_stream, EventArray, EventContainer, LogArray, MetricArray, TraceArray};
+pub use event_trace::{EventTrace, TraceHop};
 pub use finalization::{
     BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers, EventStatus,
     Finalizable,
@@ -27,6 +28,7 @@ pub use vrl_target::VrlTarget;
 pub mod array;
 pub mod discriminant;
 pub mod error;
+mod event_trace;
 mod finalization;
 mod log_event;
 #[cfg(feature = "lua")]
@@ -11,6 +11,5 @@ impl LabelFilter for VectorLabelFilter {
             || key == "component_type"
             || key == "component_kind"
             || key == "component_name"
-            || key == "buffer_type"
     }
 }
@@ -30,10 +30,19 @@ pub enum Error {
     AlreadyInitialized,
     #[snafu(display("Metrics system was not initialized."))]
     NotInitialized,
+    #[snafu(display("Metrics keys are already rewritten."))]
+    KeyRewriterAlreadySet,
 }
 
 static CONTROLLER: OnceCell<Controller> = OnceCell::new();
 
+/// Rewrites the keys of the metrics as they are recorded, before they reach the registry, such
+/// as to remove some of their labels or to cap the number of distinct values of a label.
+pub trait KeyRewriter: Send + Sync {
+    /// Returns the key to record the metric under, or `None` to record it under `key` as is.
+    fn rewrite(&self, key: &Key) -> Option<Key>;
+}
+
 // Cardinality counter parameters, expose the internal metrics registry
 // cardinality. Useful for the end users to help understand the characteristics
 // of their environment and how vectors acts in it.
@@ -131,6 +140,25 @@ impl Controller {
         CONTROLLER.get().ok_or(Error::NotInitialized)
     }
 
+    /// Rewrite the keys of the metrics recorded from now on with `rewriter`.
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if another key rewriter is set already, there being only one.
+    pub fn set_key_rewriter(&self, rewriter: Arc<dyn KeyRewriter>) -> Result<()> {
+        if self.recorder.set_key_rewriter(rewriter) {
+            Ok(())
+        } else {
+            Err(Error::KeyRewriterAlreadySet)
+        }
+    }
+
+    /// Stop rewriting the keys of the metrics with `rewriter`, unless another key rewriter is
+    /// set.
+    pub fn remove_key_rewriter(&self, rewriter: &Arc<dyn KeyRewriter>) {
+        self.recorder.remove_key_rewriter(rewriter);
+    }
+
     /// Take a snapshot of all gathered metrics and expose them as metric
     /// [`Event`](crate::event::Event)s.
     pub fn capture_metrics(&self) -> Vec<Metric> {
@@ -1,26 +1,77 @@
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
+use arc_swap::ArcSwapOption;
 use metrics::{GaugeValue, Key, Recorder, Unit};
 use metrics_util::MetricKind;
 use once_cell::unsync::OnceCell;
 
-use super::Registry;
+use super::{KeyRewriter, Registry};
 use crate::metrics::handle::Handle;
 
+/// The number of keys whose rewrite is cached. The keys seen past it are rewritten every time
+/// they are recorded.
+const MAX_CACHED_KEYS: usize = 10_000;
+
 thread_local!(static LOCAL_REGISTRY: OnceCell<Registry>=OnceCell::new());
+thread_local!(static LOCAL_KEY_REWRITER: RefCell<Option<Arc<CachedKeyRewriter>>> = RefCell::new(None));
+
+/// A [`KeyRewriter`] along with the keys it has rewritten, so that the metrics updated over and
+/// over again are rewritten only once.
+pub(super) struct CachedKeyRewriter {
+    rewriter: Arc<dyn KeyRewriter>,
+    cache: RwLock<HashMap<Key, Option<Arc<Key>>>>,
+}
+
+impl CachedKeyRewriter {
+    fn new(rewriter: Arc<dyn KeyRewriter>) -> Self {
+        Self {
+            rewriter,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn is(&self, rewriter: &Arc<dyn KeyRewriter>) -> bool {
+        Arc::as_ptr(&self.rewriter).cast::<()>() == Arc::as_ptr(rewriter).cast::<()>()
+    }
+
+    fn rewrite(&self, key: &Key) -> Option<Arc<Key>> {
+        if let Some(rewritten) = self
+            .cache
+            .read()
+            .expect("Couldn't acquire lock on the rewritten keys")
+            .get(key)
+        {
+            return rewritten.clone();
+        }
+
+        let rewritten = self.rewriter.rewrite(key).map(Arc::new);
+        let mut cache = self
+            .cache
+            .write()
+            .expect("Couldn't acquire lock on the rewritten keys");
+        if cache.len() < MAX_CACHED_KEYS {
+            cache.insert(key.clone(), rewritten.clone());
+        }
+        rewritten
+    }
+}
 
 /// [`VectorRecorder`] is a [`metrics::Recorder`] implementation that's suitable
 /// for the advanced usage that we have in Vector.
 #[derive(Clone)]
 pub(super) enum VectorRecorder {
-    Global(Arc<Registry>),
+    Global(Arc<Registry>, Arc<ArcSwapOption<CachedKeyRewriter>>),
     ThreadLocal,
 }
 
 impl VectorRecorder {
     pub(super) fn new_global() -> Self {
         let registry = Arc::new(Registry::untracked());
-        Self::Global(registry)
+        Self::Global(registry, Arc::new(ArcSwapOption::empty()))
     }
 
     pub(super) fn new_test() -> Self {
@@ -30,7 +81,7 @@ impl VectorRecorder {
 
     pub(super) fn with_registry<T>(&self, doit: impl FnOnce(&Registry) -> T) -> T {
         match &self {
-            Self::Global(registry) => doit(registry),
+            Self::Global(registry, _) => doit(registry),
             Self::ThreadLocal => Self::with_thread_local(doit),
         }
     }
@@ -38,15 +89,75 @@ impl VectorRecorder {
     fn with_thread_local<T>(doit: impl FnOnce(&Registry) -> T) -> T {
         LOCAL_REGISTRY.with(|oc| doit(oc.get_or_init(Registry::untracked)))
     }
+
+    /// Sets the key rewriter, unless there is one already. Returns whether it has been set.
+    pub(super) fn set_key_rewriter(&self, rewriter: Arc<dyn KeyRewriter>) -> bool {
+        let rewriter = Arc::new(CachedKeyRewriter::new(rewriter));
+        match &self {
+            Self::Global(_, current) => current
+                .compare_and_swap(&None::<Arc<CachedKeyRewriter>>, Some(rewriter))
+                .is_none(),
+            Self::ThreadLocal => LOCAL_KEY_REWRITER.with(|current| {
+                let mut current = current.borrow_mut();
+                if current.is_some() {
+                    return false;
+                }
+                *current = Some(rewriter);
+                true
+            }),
+        }
+    }
+
+    /// Removes the key rewriter, if it is `rewriter`.
+    pub(super) fn remove_key_rewriter(&self, rewriter: &Arc<dyn KeyRewriter>) {
+        match &self {
+            Self::Global(_, current) => {
+                let guard = current.load();
+                if matches!(&*guard, Some(entry) if entry.is(rewriter)) {
+                    current.compare_and_swap(&guard, None::<Arc<CachedKeyRewriter>>);
+                }
+            }
+            Self::ThreadLocal => LOCAL_KEY_REWRITER.with(|current| {
+                let mut current = current.borrow_mut();
+                if matches!(&*current, Some(entry) if entry.is(rewriter)) {
+                    *current = None;
+                }
+            }),
+        }
+    }
+
+    /// Calls `doit` with the key to record a metric under, as given by the key rewriter, if any.
+    fn with_key<T>(&self, key: &Key, doit: impl FnOnce(&Key) -> T) -> T {
+        let rewritten = match &self {
+            Self::Global(_, rewriter) => match &*rewriter.load() {
+                Some(rewriter) => rewriter.rewrite(key),
+                None => return doit(key),
+            },
+            Self::ThreadLocal => {
+                match LOCAL_KEY_REWRITER.with(|rewriter| rewriter.borrow().clone()) {
+                    Some(rewriter) => rewriter.rewrite(key),
+                    None => return doit(key),
+                }
+            }
+        };
+        match rewritten {
+            Some(rewritten) => doit(&rewritten),
+            None => doit(key),
+        }
+    }
 }
 
 impl Recorder for VectorRecorder {
     fn register_counter(&self, key: &Key, _unit: Option<Unit>, _description: Option<&'static str>) {
-        self.with_registry(|r| r.op(MetricKind::Counter, key, |_| {}, Handle::counter));
+        self.with_key(key, |key| {
+            self.with_registry(|r| r.op(MetricKind::Counter, key, |_| {}, Handle::counter))
+        });
     }
 
     fn register_gauge(&self, key: &Key, _unit: Option<Unit>, _description: Option<&'static str>) {
-        self.with_registry(|r| r.op(MetricKind::Gauge, key, |_| {}, Handle::gauge));
+        self.with_key(key, |key| {
+            self.with_registry(|r| r.op(MetricKind::Gauge, key, |_| {}, Handle::gauge))
+        });
     }
 
     fn register_histogram(
@@ -55,39 +166,47 @@ impl Recorder for VectorRecorder {
         _unit: Option<Unit>,
         _description: Option<&'static str>,
     ) {
-        self.with_registry(|r| r.op(MetricKind::Histogram, key, |_| {}, Handle::histogram));
+        self.with_key(key, |key| {
+            self.with_registry(|r| r.op(MetricKind::Histogram, key, |_| {}, Handle::histogram))
+        });
     }
 
     fn increment_counter(&self, key: &Key, value: u64) {
-        self.with_registry(|r| {
-            r.op(
-                MetricKind::Counter,
-                key,
-                |handle| handle.increment_counter(value),
-                Handle::counter,
-            );
+        self.with_key(key, |key| {
+            self.with_registry(|r| {
+                r.op(
+                    MetricKind::Counter,
+                    key,
+                    |handle| handle.increment_counter(value),
+                    Handle::counter,
+                );
+            });
         });
     }
 
     fn update_gauge(&self, key: &Key, value: GaugeValue) {
-        self.with_registry(|r| {
-            r.op(
-                MetricKind::Gauge,
-                key,
-                |handle| handle.update_gauge(value),
-                Handle::gauge,
-            );
+        self.with_key(key, |key| {
+            self.with_registry(|r| {
+                r.op(
+                    MetricKind::Gauge,
+                    key,
+                    |handle| handle.update_gauge(value),
+                    Handle::gauge,
+                );
+            });
         });
     }
 
     fn record_histogram(&self, key: &Key, value: f64) {
-        self.with_registry(|r| {
-            r.op(
-                MetricKind::Histogram,
-                key,
-                |handle| handle.record_histogram(value),
-                Handle::histogram,
-            );
+        self.with_key(key, |key| {
+            self.with_registry(|r| {
+                r.op(
+                    MetricKind::Histogram,
+                    key,
+                    |handle| handle.record_histogram(value),
+                    Handle::histogram,
+                );
+            });
         });
     }
 }
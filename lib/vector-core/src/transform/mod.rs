@@ -6,7 +6,7 @@ use vector_common::EventDataEq;
 
 use crate::{
     config::Output,
-    event::{into_event_stream, Event, EventArray, EventContainer, EventRef},
+    event::{into_event_stream, Event, EventArray, EventContainer, EventMutRef, EventRef},
     fanout::{self, Fanout},
     ByteSizeOf,
 };
@@ -374,6 +374,16 @@ impl TransformOutputsBuf {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Calls the given update function over each event of all the outputs.
+    pub fn for_each_event(&mut self, mut update: impl FnMut(EventMutRef<'_>)) {
+        if let Some(buf) = self.primary_buffer.as_mut() {
+            buf.for_each_event(&mut update);
+        }
+        for buf in self.named_buffers.values_mut() {
+            buf.for_each_event(&mut update);
+        }
+    }
 }
 
 impl ByteSizeOf for TransformOutputsBuf {
@@ -456,6 +466,12 @@ impl OutputBuffer {
         self.0.iter().flat_map(EventArray::iter_events)
     }
 
+    fn for_each_event(&mut self, mut update: impl FnMut(EventMutRef<'_>)) {
+        for array in &mut self.0 {
+            array.for_each_event(&mut update);
+        }
+    }
+
     pub fn into_events(self) -> impl Iterator<Item = Event> {
         self.0.into_iter().flat_map(EventArray::into_events)
     }
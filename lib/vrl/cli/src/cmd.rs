@@ -7,6 +7,7 @@ use std::{
 };
 
 use clap::Parser;
+use value::{kind::Collection, Kind};
 use vector_common::TimeZone;
 use vrl::{diagnostic::Formatter, state, Program, Runtime, Target, Value, VrlRuntime};
 
@@ -35,6 +36,16 @@ pub struct Opts {
     #[clap(short = 'o', long)]
     print_object: bool,
 
+    /// Type-check the program without running it, and print the kind of the event object it
+    /// emits as JSON.
+    #[clap(long)]
+    print_types: bool,
+
+    /// The kind of the event objects handled by the program, as JSON, when printing types.
+    /// Defaults to an object of any fields.
+    #[clap(long)]
+    input_kind: Option<String>,
+
     /// The timezone used to parse dates.
     #[clap(short = 'z', long)]
     timezone: Option<String>,
@@ -79,6 +90,14 @@ impl Opts {
         }
     }
 
+    fn input_kind(&self) -> Result<Kind, Error> {
+        match self.input_kind.as_ref() {
+            Some(kind) => Kind::from_json(&serde_json::from_str(kind)?)
+                .map_err(|err| Error::Parse(format!("unable to parse input kind: {}", err))),
+            None => Ok(Kind::object(Collection::any())),
+        }
+    }
+
     fn should_open_repl(&self) -> bool {
         self.program.is_none() && self.program_file.is_none()
     }
@@ -99,6 +118,10 @@ pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
 
 fn run(opts: &Opts) -> Result<(), Error> {
     let tz = opts.timezone()?;
+    if opts.print_types {
+        return print_types(opts);
+    }
+
     // Run the REPL if no program or program file is specified
     if opts.should_open_repl() {
         // If an input file is provided, use that for the REPL objects, otherwise provide a
@@ -148,6 +171,21 @@ fn run(opts: &Opts) -> Result<(), Error> {
     }
 }
 
+fn print_types(opts: &Opts) -> Result<(), Error> {
+    let source = opts.read_program()?;
+    let kind =
+        vrl::type_check(&source, &stdlib::all(), opts.input_kind()?).map_err(|diagnostics| {
+            Error::Parse(Formatter::new(&source, diagnostics).colored().to_string())
+        })?;
+
+    #[allow(clippy::print_stdout)]
+    {
+        println!("{}", serde_json::to_string_pretty(&kind.to_json())?);
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "repl")]
 fn repl(objects: Vec<Value>, timezone: &TimeZone, vrl_runtime: VrlRuntime) -> Result<(), Error> {
     repl::run(objects, timezone, vrl_runtime);
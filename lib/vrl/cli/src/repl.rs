@@ -1,6 +1,11 @@
-use std::borrow::Cow::{self, Borrowed, Owned};
+use std::{
+    borrow::Cow::{self, Borrowed, Owned},
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+};
 
-use indoc::indoc;
+use indoc::{indoc, writedoc};
 use once_cell::sync::Lazy;
 use prettytable::{format, Cell, Row, Table};
 use regex::Regex;
@@ -32,6 +37,7 @@ const ERRORS_URL_ROOT: &str = "https://errors.vrl.dev";
 const RESERVED_TERMS: &[&str] = &[
     "next",
     "prev",
+    "save",
     "exit",
     "quit",
     "help",
@@ -45,6 +51,8 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone, vrl_runtime: Vrl
     let mut index = 0;
     let func_docs_regex = Regex::new(r"^help\sdocs\s(\w{1,})$").unwrap();
     let error_docs_regex = Regex::new(r"^help\serror\s(\w{1,})$").unwrap();
+    let save_regex = Regex::new(r"^save\s(.+)$").unwrap();
+    let mut session = Session::new(objects.clone());
 
     let mut compiler_state = state::Compiler::default();
     let mut rt = Runtime::new(state::Runtime::default());
@@ -69,6 +77,8 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone, vrl_runtime: Vrl
             Ok(line) if error_docs_regex.is_match(line) => show_error_docs(line, &error_docs_regex),
             // Capture "help docs <func_name>"
             Ok(line) if func_docs_regex.is_match(line) => show_func_docs(line, &func_docs_regex),
+            // Capture "save <path>"
+            Ok(line) if save_regex.is_match(line) => session.save(&objects, line, &save_regex),
             Ok(line) => {
                 rl.add_history_entry(line);
 
@@ -100,6 +110,7 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone, vrl_runtime: Vrl
                     _ => line,
                 };
 
+                let before = objects.get(index).cloned();
                 let result = resolve(
                     objects.get_mut(index),
                     &mut rt,
@@ -109,6 +120,15 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone, vrl_runtime: Vrl
                     vrl_runtime,
                 );
 
+                // Navigating between the objects only prints them.
+                let changes = match (&result, before) {
+                    (Ok(_), Some(before)) if command == line && line != "." => {
+                        session.statements.push(line.to_owned());
+                        diff(&before, &objects[index])
+                    }
+                    _ => String::new(),
+                };
+
                 let string = match result {
                     Ok(v) => v.to_string(),
                     Err(v) => v.to_string(),
@@ -116,7 +136,7 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone, vrl_runtime: Vrl
 
                 #[allow(clippy::print_stdout)]
                 {
-                    println!("{}\n", string);
+                    println!("{}\n{}", string, changes);
                 }
             }
             Err(ReadlineError::Interrupted) => break,
@@ -174,6 +194,185 @@ fn execute(
     }
 }
 
+/// Describes the changes between the two objects, one changed field per line.
+fn diff(before: &Value, after: &Value) -> String {
+    let before = leaves(before);
+    let after = leaves(after);
+
+    let mut diff = String::new();
+    for (path, value) in &before {
+        match after.get(path) {
+            None => writeln!(diff, "- {}: {}", path, value),
+            Some(new) if new != value => writeln!(diff, "~ {}: {} -> {}", path, value, new),
+            Some(_) => Ok(()),
+        }
+        .expect("writing to a string doesn't fail");
+    }
+    for (path, value) in &after {
+        if !before.contains_key(path) {
+            writeln!(diff, "+ {}: {}", path, value).expect("writing to a string doesn't fail");
+        }
+    }
+
+    diff
+}
+
+/// The values of the object which aren't themselves non-empty objects or arrays, by path.
+fn leaves(value: &Value) -> BTreeMap<String, &Value> {
+    fn walk<'a>(path: String, value: &'a Value, leaves: &mut BTreeMap<String, &'a Value>) {
+        match value {
+            Value::Object(object) if !object.is_empty() => {
+                for (key, value) in object {
+                    walk(format!("{}{}", path, segment(key)), value, leaves);
+                }
+            }
+            Value::Array(array) if !array.is_empty() => {
+                for (index, value) in array.iter().enumerate() {
+                    walk(format!("{}[{}]", path, index), value, leaves);
+                }
+            }
+            _ => {
+                leaves.insert(path, value);
+            }
+        }
+    }
+
+    let mut leaves = BTreeMap::new();
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                walk(segment(key), value, &mut leaves);
+            }
+        }
+        _ => walk(".".to_owned(), value, &mut leaves),
+    }
+    leaves
+}
+
+/// The path segment of the field, quoted when it isn't a plain identifier.
+fn segment(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!(".{}", key)
+    } else {
+        format!(".{}", serde_json::Value::from(key))
+    }
+}
+
+/// The statements run during the session, saved along with the objects they started from as a
+/// unit test skeleton.
+struct Session {
+    inputs: Vec<Value>,
+    statements: Vec<String>,
+}
+
+impl Session {
+    fn new(inputs: Vec<Value>) -> Self {
+        Self {
+            inputs,
+            statements: Vec::new(),
+        }
+    }
+
+    fn save(&self, objects: &[Value], line: &str, pattern: &Regex) {
+        // As in show_func_docs, unwrap is okay here
+        let matches = pattern.captures(line).unwrap();
+        let path = matches.get(1).unwrap().as_str().trim();
+
+        let message = match fs::write(path, self.unit_test(objects)) {
+            Ok(()) => format!("session saved to {}", path),
+            Err(err) => format!("unable to save session to {}: {}", path, err),
+        };
+
+        #[allow(clippy::print_stdout)]
+        {
+            println!("{}\n", message);
+        }
+    }
+
+    /// A Vector configuration running the statements through a `remap` transform, with a test
+    /// for each object the session started from, expecting the object as it is now.
+    fn unit_test(&self, objects: &[Value]) -> String {
+        let mut config = String::from(indoc! {r#"
+            # Unit test skeleton saved from a VRL REPL session. The expected values are those of
+            # the objects at the end of the session, review them before relying on the tests.
+
+            [sources.in]
+            type = "stdin"
+
+            [transforms.remap]
+            type = "remap"
+            inputs = ["in"]
+            source = '''
+        "#});
+        for statement in &self.statements {
+            config.push_str(statement);
+            config.push('\n');
+        }
+        config.push_str("'''\n");
+
+        let tests = self.inputs.iter().zip(objects).enumerate();
+        for (index, (input, output)) in tests {
+            if !matches!(input, Value::Object(_)) {
+                continue;
+            }
+
+            writedoc!(
+                config,
+                r#"
+
+                    [[tests]]
+                    name = "repl session object {}"
+
+                    [[tests.inputs]]
+                    insert_at = "remap"
+                    type = "log"
+
+                    [tests.inputs.log_fields]
+                "#,
+                index + 1
+            )
+            .expect("writing to a string doesn't fail");
+            for (path, value) in leaves(input) {
+                if let Some(value) = toml_value(value) {
+                    let path = serde_json::Value::from(path.trim_start_matches('.'));
+                    writeln!(config, "{} = {}", path, value)
+                        .expect("writing to a string doesn't fail");
+                }
+            }
+
+            config.push_str(indoc! {r#"
+
+                [[tests.outputs]]
+                extract_from = "remap"
+
+                [[tests.outputs.conditions]]
+                type = "vrl"
+                source = '''
+            "#});
+            for (path, value) in leaves(output) {
+                writeln!(config, "assert_eq!({}, {})", path, value)
+                    .expect("writing to a string doesn't fail");
+            }
+            config.push_str("'''\n");
+        }
+
+        config
+    }
+}
+
+/// The value of a test input field, for the values which the test inputs support.
+fn toml_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Bytes(_) | Value::Timestamp(_) | Value::Regex(_) => {
+            Some(serde_json::Value::from(value.to_string_lossy()).to_string())
+        }
+        Value::Integer(integer) => Some(integer.to_string()),
+        Value::Float(float) => Some(format!("{:?}", float.into_inner())),
+        Value::Boolean(boolean) => Some(boolean.to_string()),
+        Value::Null | Value::Object(_) | Value::Array(_) => None,
+    }
+}
+
 struct Repl {
     highlighter: MatchingBracketHighlighter,
     history_hinter: HistoryHinter,
@@ -392,6 +591,7 @@ const HELP_TEXT: &str = indoc! {r#"
       help error <code>  Navigate to the docs for a specific error code
       next               Load the next object or create a new one
       prev               Load the previous object
+      save <path>        Save the session as a unit test skeleton
       exit               Terminate the program
 "#};
 
@@ -426,6 +626,7 @@ const BANNER_TEXT: &str = indoc! {r#"
     >   help              Learn more about VRL
     >   next              Load the next object or create a new one
     >   prev              Load the previous object
+    >   save <path>       Save the session as a unit test skeleton
     >   exit              Terminate the program
     >
     > Any other value is resolved to a VRL expression.
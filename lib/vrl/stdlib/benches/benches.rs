@@ -43,8 +43,11 @@ criterion_group!(
               int,
               ip_aton,
               ip_cidr_contains,
+              ip_cidr_overlap,
               ip_ntoa,
               ip_subnet,
+              ip_subnet_aggregate,
+              ip_to_cidr,
               ip_to_ipv6,
               ipv6_to_ipv4,
               is_array,
@@ -553,6 +556,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_cidr_overlap => vrl_stdlib::IpCidrOverlap;
+
+    ipv4 {
+        args: func_args![cidr_1: "192.168.0.0/16", cidr_2: "192.168.10.0/24"],
+        want: Ok(true),
+    }
+
+    ipv6 {
+        args: func_args![cidr_1: "2001:4f8:3:ba::/64", cidr_2: "2001:4f8::/32"],
+        want: Ok(true),
+    }
+}
+
 bench_function! {
     ip_ntoa => vrl_stdlib::IpNtoa;
 
@@ -586,6 +603,29 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_subnet_aggregate => vrl_stdlib::IpSubnetAggregate;
+
+    ipv4 {
+        args: func_args![value: value!(["10.0.2.0/24", "10.0.0.0/23", "10.0.3.0/24", "10.0.8.0/24"])],
+        want: Ok(value!(["10.0.0.0/22", "10.0.8.0/24"])),
+    }
+}
+
+bench_function! {
+    ip_to_cidr => vrl_stdlib::IpToCidr;
+
+    ipv4 {
+        args: func_args![value: "192.168.10.23", prefix_length: 16],
+        want: Ok("192.168.0.0/16"),
+    }
+
+    ipv6 {
+        args: func_args![value: "2400:6800:4003:c02::64", prefix_length: 16],
+        want: Ok("2400::/16"),
+    }
+}
+
 bench_function! {
     ip_to_ipv6 => vrl_stdlib::IpToIpv6;
 
@@ -0,0 +1,213 @@
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+/// A CIDR block, such as `10.0.0.0/8`, whose address has its host bits cleared. A plain address
+/// is the block made of that single address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Cidr {
+    v6: bool,
+    bits: u128,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// The block of the given length containing the address.
+    pub(crate) fn new(address: IpAddr, prefix_len: i64) -> Result<Self, String> {
+        let (v6, bits) = match address {
+            IpAddr::V4(address) => (false, u128::from(u32::from(address))),
+            IpAddr::V6(address) => (true, u128::from(address)),
+        };
+        let max_len = max_len(v6);
+        if prefix_len < 0 || prefix_len > i64::from(max_len) {
+            return Err(format!(
+                "prefix length must be between 0 and {} for {} addresses",
+                max_len,
+                if v6 { "ipv6" } else { "ipv4" }
+            ));
+        }
+
+        let prefix_len = prefix_len as u8;
+        Ok(Self {
+            v6,
+            bits: bits & !host_mask(u32::from(max_len - prefix_len)),
+            prefix_len,
+        })
+    }
+
+    /// Parses a CIDR block, or a plain address.
+    pub(crate) fn parse(cidr: &str) -> Result<Self, String> {
+        let (address, prefix_len) = match cidr.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (cidr, None),
+        };
+        let address: IpAddr = address
+            .parse()
+            .map_err(|err| format!("unable to parse CIDR: {}", err))?;
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .parse()
+                .map_err(|_| format!("unable to parse CIDR: invalid prefix length in {}", cidr))?,
+            None => i64::from(max_len(address.is_ipv6())),
+        };
+
+        Self::new(address, prefix_len)
+    }
+
+    const fn first(&self) -> u128 {
+        self.bits
+    }
+
+    fn last(&self) -> u128 {
+        self.bits | host_mask(u32::from(max_len(self.v6) - self.prefix_len))
+    }
+
+    /// Whether the blocks share any address.
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        self.v6 == other.v6 && self.first() <= other.last() && other.first() <= self.last()
+    }
+
+    /// Summarizes the blocks into the fewest blocks covering exactly the same addresses, merging
+    /// the overlapping and adjacent ones. The IPv4 blocks come first, each family in order.
+    pub(crate) fn aggregate(cidrs: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        let mut ranges = cidrs
+            .into_iter()
+            .map(|cidr| (cidr.v6, cidr.first(), cidr.last()))
+            .collect::<Vec<_>>();
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(bool, u128, u128)> = Vec::with_capacity(ranges.len());
+        for (v6, first, last) in ranges {
+            match merged.last_mut() {
+                Some((merged_v6, _, merged_last))
+                    if *merged_v6 == v6 && first <= merged_last.saturating_add(1) =>
+                {
+                    *merged_last = (*merged_last).max(last);
+                }
+                _ => merged.push((v6, first, last)),
+            }
+        }
+
+        let mut aggregated = Vec::new();
+        for (v6, first, last) in merged {
+            range_to_cidrs(v6, first, last, &mut aggregated);
+        }
+        aggregated
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = if self.v6 {
+            IpAddr::V6(Ipv6Addr::from(self.bits))
+        } else {
+            IpAddr::V4(Ipv4Addr::from(self.bits as u32))
+        };
+        write!(f, "{}/{}", address, self.prefix_len)
+    }
+}
+
+const fn max_len(v6: bool) -> u8 {
+    if v6 {
+        128
+    } else {
+        32
+    }
+}
+
+/// The mask of the given number of low bits.
+const fn host_mask(host_len: u32) -> u128 {
+    match 1u128.checked_shl(host_len) {
+        Some(size) => size - 1,
+        None => u128::MAX,
+    }
+}
+
+/// Splits the range of addresses into the largest blocks, in order.
+fn range_to_cidrs(v6: bool, mut first: u128, last: u128, cidrs: &mut Vec<Cidr>) {
+    let max_len = max_len(v6);
+    loop {
+        // The largest block starting at `first` which doesn't go past `last`.
+        let mut host_len = first.trailing_zeros().min(u32::from(max_len));
+        while host_len > 0 && first | host_mask(host_len) > last {
+            host_len -= 1;
+        }
+        cidrs.push(Cidr {
+            v6,
+            bits: first,
+            prefix_len: max_len - host_len as u8,
+        });
+
+        let block_last = first | host_mask(host_len);
+        if block_last >= last {
+            break;
+        }
+        first = block_last + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidr(cidr: &str) -> Cidr {
+        Cidr::parse(cidr).unwrap()
+    }
+
+    #[test]
+    fn parses_cidrs() {
+        assert_eq!(cidr("10.1.2.3/16").to_string(), "10.1.0.0/16");
+        assert_eq!(cidr("10.1.2.3").to_string(), "10.1.2.3/32");
+        assert_eq!(cidr("2001:db8::1/32").to_string(), "2001:db8::/32");
+        assert_eq!(cidr("0.0.0.0/0").to_string(), "0.0.0.0/0");
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("10.0.0.0/x").is_err());
+        assert!(Cidr::parse("zork").is_err());
+    }
+
+    #[test]
+    fn checks_overlaps() {
+        assert!(cidr("10.0.0.0/8").overlaps(&cidr("10.1.0.0/16")));
+        assert!(cidr("10.1.0.0/16").overlaps(&cidr("10.0.0.0/8")));
+        assert!(!cidr("10.0.0.0/16").overlaps(&cidr("10.1.0.0/16")));
+        assert!(!cidr("0.0.0.0/0").overlaps(&cidr("::/0")));
+    }
+
+    #[test]
+    fn aggregates_cidrs() {
+        let aggregated = Cidr::aggregate(
+            [
+                "10.0.1.0/24",
+                "10.0.0.0/24",
+                "10.0.2.0/23",
+                "10.0.2.5",
+                "192.168.0.1",
+                "192.168.0.2",
+                "2001:db8::/33",
+                "2001:db8:8000::/33",
+            ]
+            .iter()
+            .map(|value| cidr(value)),
+        )
+        .into_iter()
+        .map(|cidr| cidr.to_string())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            aggregated,
+            vec![
+                "10.0.0.0/22",
+                "192.168.0.1/32",
+                "192.168.0.2/32",
+                "2001:db8::/32"
+            ]
+        );
+    }
+
+    #[test]
+    fn aggregates_whole_ranges() {
+        let aggregated = Cidr::aggregate(vec![cidr("::/1"), cidr("8000::/1")]);
+        assert_eq!(aggregated, vec![cidr("::/0")]);
+    }
+}
@@ -0,0 +1,118 @@
+use vrl::prelude::*;
+
+use crate::cidr_util::Cidr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpCidrOverlap;
+
+impl Function for IpCidrOverlap {
+    fn identifier(&self) -> &'static str {
+        "ip_cidr_overlap"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "cidr_1",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "cidr_2",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "overlapping",
+                source: r#"ip_cidr_overlap!("10.0.0.0/8", "10.20.0.0/16")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "not overlapping",
+                source: r#"ip_cidr_overlap!("10.0.0.0/16", "10.1.0.0/16")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let cidr_1 = arguments.required("cidr_1");
+        let cidr_2 = arguments.required("cidr_2");
+
+        Ok(Box::new(IpCidrOverlapFn { cidr_1, cidr_2 }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpCidrOverlapFn {
+    cidr_1: Box<dyn Expression>,
+    cidr_2: Box<dyn Expression>,
+}
+
+impl Expression for IpCidrOverlapFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let cidr_1 = Cidr::parse(&self.cidr_1.resolve(ctx)?.try_bytes_utf8_lossy()?)?;
+        let cidr_2 = Cidr::parse(&self.cidr_2.resolve(ctx)?.try_bytes_utf8_lossy()?)?;
+
+        Ok(cidr_1.overlaps(&cidr_2).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::boolean().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_cidr_overlap => IpCidrOverlap;
+
+        ipv4_yes {
+            args: func_args![cidr_1: "192.168.0.0/16", cidr_2: "192.168.10.0/24"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        ipv4_no {
+            args: func_args![cidr_1: "192.168.0.0/24", cidr_2: "192.168.1.0/24"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        ipv4_address {
+            args: func_args![cidr_1: "192.168.0.0/24", cidr_2: "192.168.0.32"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        ipv6_yes {
+            args: func_args![cidr_1: "2001:4f8:3:ba::/64", cidr_2: "2001:4f8::/32"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        mixed_families {
+            args: func_args![cidr_1: "0.0.0.0/0", cidr_2: "::/0"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        invalid_cidr {
+            args: func_args![cidr_1: "192.168.0.0/33", cidr_2: "192.168.0.0/24"],
+            want: Err("prefix length must be between 0 and 32 for ipv4 addresses"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+    ];
+}
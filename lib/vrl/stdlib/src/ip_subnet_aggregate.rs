@@ -0,0 +1,118 @@
+use vrl::prelude::*;
+
+use crate::cidr_util::Cidr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpSubnetAggregate;
+
+impl Function for IpSubnetAggregate {
+    fn identifier(&self) -> &'static str {
+        "ip_subnet_aggregate"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::ARRAY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "adjacent subnets",
+                source: r#"ip_subnet_aggregate!(["10.0.0.0/24", "10.0.1.0/24", "10.0.1.7"])"#,
+                result: Ok(r#"["10.0.0.0/23"]"#),
+            },
+            Example {
+                title: "addresses",
+                source: r#"ip_subnet_aggregate!(["192.168.0.3", "192.168.0.2", "2001:db8::1"])"#,
+                result: Ok(r#"["192.168.0.2/31", "2001:db8::1/128"]"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpSubnetAggregateFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpSubnetAggregateFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpSubnetAggregateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let cidrs = self
+            .value
+            .resolve(ctx)?
+            .try_array()?
+            .iter()
+            .map(|value| Ok(Cidr::parse(&value.try_bytes_utf8_lossy()?)?))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Cidr::aggregate(cidrs)
+            .into_iter()
+            .map(|cidr| Value::from(cidr.to_string()))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_subnet_aggregate => IpSubnetAggregate;
+
+        merges_subnets {
+            args: func_args![value: vec!["10.0.2.0/24", "10.0.0.0/23", "10.0.3.0/24", "10.0.8.0/24"]],
+            want: Ok(value!(["10.0.0.0/22", "10.0.8.0/24"])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        keeps_contained_subnets_out {
+            args: func_args![value: vec!["10.0.0.0/8", "10.1.0.0/16", "10.1.2.3"]],
+            want: Ok(value!(["10.0.0.0/8"])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        splits_unaligned_ranges {
+            args: func_args![value: vec!["10.0.1.0/24", "10.0.2.0/24"]],
+            want: Ok(value!(["10.0.1.0/24", "10.0.2.0/24"])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        ipv6 {
+            args: func_args![value: vec!["2001:db8::/33", "2001:db8:8000::/33"]],
+            want: Ok(value!(["2001:db8::/32"])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        empty {
+            args: func_args![value: value!([])],
+            want: Ok(value!([])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        invalid {
+            args: func_args![value: vec!["10.0.0.0/8", "zork"]],
+            want: Err("unable to parse CIDR: invalid IP address syntax"),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+    ];
+}
@@ -0,0 +1,129 @@
+use std::net::IpAddr;
+
+use vrl::prelude::*;
+
+use crate::cidr_util::Cidr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpToCidr;
+
+impl Function for IpToCidr {
+    fn identifier(&self) -> &'static str {
+        "ip_to_cidr"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "prefix_length",
+                kind: kind::INTEGER,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "ipv4",
+                source: r#"ip_to_cidr!("192.168.10.32", 24)"#,
+                result: Ok("192.168.10.0/24"),
+            },
+            Example {
+                title: "ipv6",
+                source: r#"ip_to_cidr!("2001:4f8:3:ba:2e0:81ff:fe22:d1f1", 64)"#,
+                result: Ok("2001:4f8:3:ba::/64"),
+            },
+            Example {
+                title: "invalid prefix length",
+                source: r#"ip_to_cidr!("192.168.10.32", 33)"#,
+                result: Err(
+                    r#"function call error for "ip_to_cidr" at (0:32): prefix length must be between 0 and 32 for ipv4 addresses"#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let prefix_length = arguments.required("prefix_length");
+
+        Ok(Box::new(IpToCidrFn {
+            value,
+            prefix_length,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpToCidrFn {
+    value: Box<dyn Expression>,
+    prefix_length: Box<dyn Expression>,
+}
+
+impl Expression for IpToCidrFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+        let prefix_length = self.prefix_length.resolve(ctx)?.try_integer()?;
+
+        Ok(Cidr::new(value, prefix_length)?.to_string().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_to_cidr => IpToCidr;
+
+        ipv4 {
+            args: func_args![value: "192.168.10.32", prefix_length: 16],
+            want: Ok(value!("192.168.0.0/16")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_host {
+            args: func_args![value: "192.168.10.32", prefix_length: 32],
+            want: Ok(value!("192.168.10.32/32")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6 {
+            args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1", prefix_length: 48],
+            want: Ok(value!("2001:4f8:3::/48")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_prefix_length {
+            args: func_args![value: "2001:4f8:3:ba:2e0:81ff:fe22:d1f1", prefix_length: 129],
+            want: Err("prefix length must be between 0 and 128 for ipv6 addresses"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_address {
+            args: func_args![value: "INVALID", prefix_length: 24],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -7,6 +7,13 @@
 
 mod util;
 
+#[cfg(any(
+    feature = "ip_cidr_overlap",
+    feature = "ip_subnet_aggregate",
+    feature = "ip_to_cidr"
+))]
+mod cidr_util;
+
 #[cfg(feature = "append")]
 mod append;
 #[cfg(feature = "array")]
@@ -73,10 +80,16 @@ mod integer;
 mod ip_aton;
 #[cfg(feature = "ip_cidr_contains")]
 mod ip_cidr_contains;
+#[cfg(feature = "ip_cidr_overlap")]
+mod ip_cidr_overlap;
 #[cfg(feature = "ip_ntoa")]
 mod ip_ntoa;
 #[cfg(feature = "ip_subnet")]
 mod ip_subnet;
+#[cfg(feature = "ip_subnet_aggregate")]
+mod ip_subnet_aggregate;
+#[cfg(feature = "ip_to_cidr")]
+mod ip_to_cidr;
 #[cfg(feature = "ip_to_ipv6")]
 mod ip_to_ipv6;
 #[cfg(feature = "ipv6_to_ipv4")]
@@ -328,10 +341,16 @@ pub use integer::Integer;
 pub use ip_aton::IpAton;
 #[cfg(feature = "ip_cidr_contains")]
 pub use ip_cidr_contains::IpCidrContains;
+#[cfg(feature = "ip_cidr_overlap")]
+pub use ip_cidr_overlap::IpCidrOverlap;
 #[cfg(feature = "ip_ntoa")]
 pub use ip_ntoa::IpNtoa;
 #[cfg(feature = "ip_subnet")]
 pub use ip_subnet::IpSubnet;
+#[cfg(feature = "ip_subnet_aggregate")]
+pub use ip_subnet_aggregate::IpSubnetAggregate;
+#[cfg(feature = "ip_to_cidr")]
+pub use ip_to_cidr::IpToCidr;
 #[cfg(feature = "ip_to_ipv6")]
 pub use ip_to_ipv6::IpToIpv6;
 #[cfg(feature = "ipv6_to_ipv4")]
@@ -569,10 +588,16 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IpAton),
         #[cfg(feature = "ip_cidr_contains")]
         Box::new(IpCidrContains),
+        #[cfg(feature = "ip_cidr_overlap")]
+        Box::new(IpCidrOverlap),
         #[cfg(feature = "ip_ntoa")]
         Box::new(IpNtoa),
         #[cfg(feature = "ip_subnet")]
         Box::new(IpSubnet),
+        #[cfg(feature = "ip_subnet_aggregate")]
+        Box::new(IpSubnetAggregate),
+        #[cfg(feature = "ip_to_cidr")]
+        Box::new(IpToCidr),
         #[cfg(feature = "ip_to_ipv6")]
         Box::new(IpToIpv6),
         #[cfg(feature = "ipv6_to_ipv4")]
@@ -1,4 +1,8 @@
-use vector_common::conversion::Conversion;
+use chrono::{
+    format::{self, Parsed, StrftimeItems},
+    DateTime, Local, TimeZone as _, Utc,
+};
+use vector_common::{conversion::Conversion, TimeZone};
 use vrl::prelude::*;
 
 #[derive(Clone, Copy, Debug)]
@@ -10,11 +14,23 @@ impl Function for ParseTimestamp {
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[Example {
-            title: "valid",
-            source: r#"parse_timestamp!("11-Feb-2021 16:00 +00:00", format: "%v %R %z")"#,
-            result: Ok("t'2021-02-11T16:00:00Z'"),
-        }]
+        &[
+            Example {
+                title: "valid",
+                source: r#"parse_timestamp!("11-Feb-2021 16:00 +00:00", format: "%v %R %z")"#,
+                result: Ok("t'2021-02-11T16:00:00Z'"),
+            },
+            Example {
+                title: "locale",
+                source: r#"parse_timestamp!("11 février 2021 16:00", format: "%d %B %Y %R", locale: "fr", timezone: "Europe/Paris")"#,
+                result: Ok("t'2021-02-11T15:00:00Z'"),
+            },
+            Example {
+                title: "two-digit year pivot",
+                source: r#"parse_timestamp!("11/02/65 16:00 +00:00", format: "%d/%m/%y %R %z", year_pivot: 50)"#,
+                result: Ok("t'1965-02-11T16:00:00Z'"),
+            },
+        ]
     }
 
     fn compile(
@@ -25,8 +41,17 @@ impl Function for ParseTimestamp {
     ) -> Compiled {
         let value = arguments.required("value");
         let format = arguments.required("format");
+        let timezone = arguments.optional("timezone");
+        let locale = arguments.optional("locale");
+        let year_pivot = arguments.optional("year_pivot");
 
-        Ok(Box::new(ParseTimestampFn { value, format }))
+        Ok(Box::new(ParseTimestampFn {
+            value,
+            format,
+            timezone,
+            locale,
+            year_pivot,
+        }))
     }
 
     fn parameters(&self) -> &'static [Parameter] {
@@ -41,6 +66,21 @@ impl Function for ParseTimestamp {
                 kind: kind::BYTES,
                 required: true,
             },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "locale",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "year_pivot",
+                kind: kind::INTEGER,
+                required: false,
+            },
         ]
     }
 }
@@ -49,6 +89,9 @@ impl Function for ParseTimestamp {
 struct ParseTimestampFn {
     value: Box<dyn Expression>,
     format: Box<dyn Expression>,
+    timezone: Option<Box<dyn Expression>>,
+    locale: Option<Box<dyn Expression>>,
+    year_pivot: Option<Box<dyn Expression>>,
 }
 
 impl Expression for ParseTimestampFn {
@@ -59,10 +102,50 @@ impl Expression for ParseTimestampFn {
             Value::Bytes(v) => {
                 let bytes = self.format.resolve(ctx)?;
                 let format = bytes.try_bytes_utf8_lossy()?;
-                Conversion::parse(format!("timestamp|{}", format), ctx.timezone().to_owned())
-                    .map_err(|e| format!("{}", e))?
-                    .convert(v)
-                    .map_err(|e| e.to_string().into())
+
+                let timezone = match &self.timezone {
+                    Some(expr) => {
+                        let timezone = expr.resolve(ctx)?;
+                        let timezone = timezone.try_bytes_utf8_lossy()?;
+                        TimeZone::parse(&timezone)
+                            .ok_or_else(|| format!("unable to parse timezone: {}", timezone))?
+                    }
+                    None => ctx.timezone().to_owned(),
+                };
+
+                let v = match &self.locale {
+                    Some(expr) => {
+                        let locale = expr.resolve(ctx)?;
+                        let locale = locale.try_bytes_utf8_lossy()?;
+                        let locale = Locale::find(&locale)
+                            .ok_or_else(|| format!("unknown locale: {}", locale))?;
+                        locale
+                            .translate(&String::from_utf8_lossy(&v), &format)
+                            .into()
+                    }
+                    None => v,
+                };
+
+                match &self.year_pivot {
+                    Some(expr) => {
+                        let year_pivot = expr.resolve(ctx)?.try_integer()?;
+                        if !(0..=100).contains(&year_pivot) {
+                            return Err("year_pivot must be between 0 and 100".into());
+                        }
+                        parse_with_year_pivot(
+                            &String::from_utf8_lossy(&v),
+                            &format,
+                            year_pivot,
+                            timezone,
+                        )
+                        .map(Into::into)
+                        .map_err(Into::into)
+                    }
+                    None => Conversion::parse(format!("timestamp|{}", format), timezone)
+                        .map_err(|e| format!("{}", e))?
+                        .convert(v)
+                        .map_err(|e| e.to_string().into()),
+                }
             }
             Value::Timestamp(_) => Ok(value),
             _ => Err("unable to convert value to timestamp".into()),
@@ -74,6 +157,355 @@ impl Expression for ParseTimestampFn {
     }
 }
 
+/// Parses the timestamp, placing the two-digit years (`%y`) below `year_pivot` in the 2000s and
+/// the others in the 1900s, rather than chrono's fixed pivot of 70.
+fn parse_with_year_pivot(
+    s: &str,
+    format: &str,
+    year_pivot: i64,
+    timezone: TimeZone,
+) -> std::result::Result<DateTime<Utc>, String> {
+    let error = |err| format!("Invalid timestamp {:?}: {}", s, err);
+
+    let mut parsed = Parsed::new();
+    format::parse(&mut parsed, s, StrftimeItems::new(format)).map_err(error)?;
+    if let (Some(year_mod_100), None) = (parsed.year_mod_100, parsed.year_div_100) {
+        let century = if i64::from(year_mod_100) < year_pivot {
+            20
+        } else {
+            19
+        };
+        parsed.set_year_div_100(century).map_err(error)?;
+    }
+
+    if parsed.offset.is_some() {
+        return parsed
+            .to_datetime()
+            .map(|datetime| datetime.with_timezone(&Utc))
+            .map_err(error);
+    }
+
+    let datetime = parsed.to_naive_datetime_with_offset(0).map_err(error)?;
+    match timezone {
+        TimeZone::Local => Local
+            .from_local_datetime(&datetime)
+            .single()
+            .map(|datetime| datetime.with_timezone(&Utc)),
+        TimeZone::Named(timezone) => timezone
+            .from_local_datetime(&datetime)
+            .single()
+            .map(|datetime| datetime.with_timezone(&Utc)),
+    }
+    .ok_or_else(|| format!("Invalid timestamp {:?}: ambiguous local time", s))
+}
+
+/// The month and day names of a language, in lowercase.
+struct Locale {
+    language: &'static str,
+    months: [&'static str; 12],
+    short_months: [&'static str; 12],
+    weekdays: [&'static str; 7],
+    short_weekdays: [&'static str; 7],
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const LOCALES: &[Locale] = &[
+    Locale {
+        language: "de",
+        months: [
+            "januar",
+            "februar",
+            "märz",
+            "april",
+            "mai",
+            "juni",
+            "juli",
+            "august",
+            "september",
+            "oktober",
+            "november",
+            "dezember",
+        ],
+        short_months: [
+            "jan", "feb", "mär", "apr", "mai", "jun", "jul", "aug", "sep", "okt", "nov", "dez",
+        ],
+        weekdays: [
+            "montag",
+            "dienstag",
+            "mittwoch",
+            "donnerstag",
+            "freitag",
+            "samstag",
+            "sonntag",
+        ],
+        short_weekdays: ["mo", "di", "mi", "do", "fr", "sa", "so"],
+    },
+    Locale {
+        language: "en",
+        months: [
+            "january",
+            "february",
+            "march",
+            "april",
+            "may",
+            "june",
+            "july",
+            "august",
+            "september",
+            "october",
+            "november",
+            "december",
+        ],
+        short_months: [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ],
+        weekdays: [
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ],
+        short_weekdays: ["mon", "tue", "wed", "thu", "fri", "sat", "sun"],
+    },
+    Locale {
+        language: "es",
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        short_months: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+        ],
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        short_weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    },
+    Locale {
+        language: "fr",
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        short_months: [
+            "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov",
+            "déc",
+        ],
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        short_weekdays: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+    },
+    Locale {
+        language: "it",
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        short_months: [
+            "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+        ],
+        weekdays: [
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+            "domenica",
+        ],
+        short_weekdays: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    },
+    Locale {
+        language: "nl",
+        months: [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        short_months: [
+            "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+        ],
+        weekdays: [
+            "maandag",
+            "dinsdag",
+            "woensdag",
+            "donderdag",
+            "vrijdag",
+            "zaterdag",
+            "zondag",
+        ],
+        short_weekdays: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+    },
+];
+
+/// A month or day name expected by the format, and whether it's abbreviated.
+#[derive(Clone, Copy)]
+enum Name {
+    Month(bool),
+    Weekday(bool),
+}
+
+impl Locale {
+    /// Finds the locale of a language, such as `fr`, `fr_FR` or `fr-FR`.
+    fn find(locale: &str) -> Option<&'static Self> {
+        let language = locale.split(|c| c == '_' || c == '-').next()?;
+        LOCALES
+            .iter()
+            .find(|locale| locale.language.eq_ignore_ascii_case(language))
+    }
+
+    /// Translates the month and day names in the timestamp to English, so that chrono can parse
+    /// them. Each name in the timestamp is matched against the names expected by the format, in
+    /// order, as some abbreviations are both a month and a day name.
+    fn translate(&self, s: &str, format: &str) -> String {
+        let mut names = format_names(format).into_iter().peekable();
+        let mut translated = String::with_capacity(s.len());
+        let mut rest = s;
+
+        while let Some(start) = rest.find(char::is_alphabetic) {
+            translated.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            rest = &rest[end..];
+
+            match names.peek().and_then(|name| self.english(word, *name)) {
+                Some(english) => {
+                    translated.push_str(english);
+                    names.next();
+                }
+                None => translated.push_str(word),
+            }
+        }
+        translated.push_str(rest);
+
+        translated
+    }
+
+    /// The English name matching the word, in the form expected by the format.
+    fn english(&self, word: &str, name: Name) -> Option<&'static str> {
+        let word = word.to_lowercase();
+        let position = |long: &[&str], short: &[&str]| {
+            long.iter()
+                .position(|name| *name == word)
+                .or_else(|| short.iter().position(|name| *name == word))
+        };
+
+        match name {
+            Name::Month(short) => position(&self.months, &self.short_months)
+                .map(|index| abbreviate(MONTHS[index], short)),
+            Name::Weekday(short) => position(&self.weekdays, &self.short_weekdays)
+                .map(|index| abbreviate(WEEKDAYS[index], short)),
+        }
+    }
+}
+
+fn abbreviate(name: &'static str, short: bool) -> &'static str {
+    if short {
+        &name[..3]
+    } else {
+        name
+    }
+}
+
+/// The month and day names expected by the format, in order.
+fn format_names(format: &str) -> Vec<Name> {
+    let mut names = Vec::new();
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        let specifier = chars.find(|c| !matches!(c, '-' | '_' | '0' | ':' | '#'));
+        match specifier {
+            Some('a') => names.push(Name::Weekday(true)),
+            Some('A') => names.push(Name::Weekday(false)),
+            Some('b' | 'h' | 'v') => names.push(Name::Month(true)),
+            Some('B') => names.push(Name::Month(false)),
+            Some('c') => names.extend([Name::Weekday(true), Name::Month(true)]),
+            _ => {}
+        }
+    }
+
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{DateTime, Utc};
@@ -126,5 +558,102 @@ mod tests {
             tdef: TypeDef::timestamp().fallible(),
             tz: vector_common::TimeZone::Named(chrono_tz::Europe::Paris),
         }
+
+        parse_text_with_timezone_argument {
+            args: func_args![
+                value: "16/10/2019:12:00:00",
+                format: "%d/%m/%Y:%H:%M:%S",
+                timezone: "America/New_York"
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Wed, 16 Oct 2019 16:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::Named(chrono_tz::Europe::Paris),
+        }
+
+        parse_text_with_invalid_timezone {
+            args: func_args![
+                value: "16/10/2019:12:00:00",
+                format: "%d/%m/%Y:%H:%M:%S",
+                timezone: "Mars/Olympus_Mons"
+            ],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::default(),
+        }
+
+        parse_text_with_locale {
+            args: func_args![
+                value: "mer. 16 oct. 2019 12:00:00 +0000",
+                format: "%a. %d %b. %Y %H:%M:%S %z",
+                locale: "fr_FR"
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Wed, 16 Oct 2019 12:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::default(),
+        }
+
+        parse_text_with_ambiguous_locale_names {
+            args: func_args![
+                value: "martes 5 marzo 2019 12:00:00 +0000",
+                format: "%A %e %B %Y %H:%M:%S %z",
+                locale: "es"
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Tue, 5 Mar 2019 12:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::default(),
+        }
+
+        parse_text_with_unknown_locale {
+            args: func_args![
+                value: "16 oct 2019 12:00:00 +0000",
+                format: "%d %b %Y %H:%M:%S %z",
+                locale: "xx"
+            ],
+            want: Err("unknown locale: xx"),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::default(),
+        }
+
+        parse_text_with_year_pivot {
+            args: func_args![
+                value: "16/10/65 12:00:00",
+                format: "%d/%m/%y %H:%M:%S",
+                year_pivot: 50
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Sat, 16 Oct 1965 11:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::Named(chrono_tz::Europe::Paris),
+        }
+
+        parse_text_with_year_pivot_and_offset {
+            args: func_args![
+                value: "16/10/45 12:00:00 +0000",
+                format: "%d/%m/%y %H:%M:%S %z",
+                year_pivot: 50
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Mon, 16 Oct 2045 12:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: vector_common::TimeZone::default(),
+        }
     ];
 }
@@ -32,3 +32,17 @@ pub fn compile_with_state(
 
     compiler::compile_with_state(ast, fns, state)
 }
+
+/// Type-check a given source against the kind of the target, without running it.
+///
+/// Returns the kind of the target once the program has run.
+pub fn type_check(
+    source: &str,
+    fns: &[Box<dyn Function>],
+    kind: value::Kind,
+) -> Result<value::Kind, diagnostic::DiagnosticList> {
+    let mut state = state::Compiler::new_with_kind(kind.clone());
+    compile_with_state(source, fns, &mut state)?;
+
+    Ok(state.target_kind().cloned().unwrap_or(kind))
+}
@@ -23,6 +23,7 @@ start_podman () {
   podman pod create --replace --name vector_nats_tls -p 4227:4222
   podman pod create --replace --name vector_nats_tls_client_cert -p 4228:4222
   podman pod create --replace --name vector_nats_jwt -p 4229:4222
+  podman pod create --replace --name vector_nats_jetstream -p 4230:4222
 
   podman run -d --pod=vector_nats --name vector_nats_test docker.io/library/nats:latest
   podman run -d --pod=vector_nats_userpass --name vector_nats_userpass_test docker.io/library/nats:latest \
@@ -44,6 +45,9 @@ start_podman () {
   podman run -d --pod=vector_nats_jwt --name vector_nats_jwt_test \
     -v "$(pwd)"/tests/data:/usr/share/nats/config:ro \
     docker.io/library/nats:latest -c /usr/share/nats/config/nats-jwt.conf
+
+  podman run -d --pod=vector_nats_jetstream --name vector_nats_jetstream_test docker.io/library/nats:latest \
+      --jetstream
 }
 
 start_docker () {
@@ -108,6 +112,9 @@ start_docker () {
     -v "$(pwd)"/tests/data:/usr/share/nats/config:ro \
     --name vector_nats_jwt nats \
     -c /usr/share/nats/config/nats-jwt.conf
+
+  docker run -d --network=vector-test-integration-nats -p 4230:4222 --name vector_nats_jetstream nats \
+    --jetstream
 }
 
 stop_podman () {
@@ -131,6 +138,9 @@ stop_podman () {
 
   podman pod stop vector_nats_jwt_test 2>/dev/null; true
   podman pod rm --force vector_nats_jwt 2>/dev/null; true
+
+  podman pod stop vector_nats_jetstream_test 2>/dev/null; true
+  podman pod rm --force vector_nats_jetstream 2>/dev/null; true
 }
 
 stop_docker () {
@@ -141,6 +151,7 @@ stop_docker () {
   docker rm --force vector_nats_tls 2>/dev/null; true
   docker rm --force vector_nats_tls_client_cert 2>/dev/null; true
   docker rm --force vector_nats_jwt 2>/dev/null; true
+  docker rm --force vector_nats_jetstream 2>/dev/null; true
   docker network rm vector-test-integration-nats 2>/dev/null; true
 }
 
@@ -4,7 +4,18 @@ use std::sync::{
 };
 
 use serde_json::json;
-use warp::{reply::json, Rejection, Reply};
+use warp::{
+    http::{header::WWW_AUTHENTICATE, StatusCode},
+    reject::Reject,
+    reply::json,
+    Rejection, Reply,
+};
+
+/// Rejection raised when a request doesn't carry the credentials configured in `api.auth`.
+#[derive(Debug)]
+pub(super) struct Unauthorized;
+
+impl Reject for Unauthorized {}
 
 // Health handler, responds with '{ ok: true }' when running and '{ ok: false}'
 // when shutting down
@@ -21,3 +32,20 @@ pub(super) async fn health(running: Arc<AtomicBool>) -> Result<impl Reply, Rejec
         ))
     }
 }
+
+// Maps rejections raised by the API filters to responses. Unknown rejections are passed
+// through so Warp can render its default response for them.
+pub(super) async fn recover(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(warp::reply::with_header(
+            warp::reply::with_status(
+                json(&json!({"error": "Unauthorized"})),
+                StatusCode::UNAUTHORIZED,
+            ),
+            WWW_AUTHENTICATE,
+            "Basic, Bearer",
+        ))
+    } else {
+        Err(rejection)
+    }
+}
@@ -1,4 +1,5 @@
 mod handler;
+mod rest;
 mod schema;
 mod server;
 pub mod tap;
@@ -0,0 +1,241 @@
+//! Plain HTTP endpoints for environments that can't use GraphQL or WebSockets. These are
+//! backed by the same component state, metrics and tap streams as the GraphQL schema.
+
+use std::convert::Infallible;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use warp::{http::StatusCode, reply::json, sse, Rejection, Reply};
+
+use super::schema::{
+    components::state,
+    events::{create_events_stream, TapPatterns},
+    metrics::{self, ComponentTotals},
+};
+use crate::{config::ComponentKey, topology::WatchRx};
+
+/// Tags a summary shared with the GraphQL schema, or with the buffers, with the component it's
+/// about.
+#[derive(Debug, Serialize)]
+struct ComponentSummary<T> {
+    component_id: String,
+    #[serde(flatten)]
+    summary: T,
+}
+
+impl<T> ComponentSummary<T> {
+    const fn new(component_id: String, summary: T) -> Self {
+        Self {
+            component_id,
+            summary,
+        }
+    }
+}
+
+/// Query parameters for `/tap`. Patterns are comma-separated globs, as with `vector tap`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct TapQuery {
+    #[serde(default)]
+    outputs_of: Option<String>,
+    #[serde(default)]
+    inputs_of: Option<String>,
+    #[serde(default = "default_interval")]
+    interval: u32,
+    #[serde(default = "default_limit")]
+    limit: u32,
+}
+
+const fn default_interval() -> u32 {
+    500
+}
+
+const fn default_limit() -> u32 {
+    100
+}
+
+fn split_patterns(patterns: Option<String>) -> Vec<String> {
+    patterns
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+// Lists configured components, sorted by id.
+pub(super) async fn components() -> Result<impl Reply, Rejection> {
+    let mut components = state::get_components();
+    components.sort_by(|a, b| Ord::cmp(a.get_component_key(), b.get_component_key()));
+
+    Ok(json(&components))
+}
+
+// Summarizes the metrics of a single component, or responds with a 404 if no component
+// exists with the given id.
+pub(super) async fn component_metrics(component_id: String) -> Result<impl Reply, Rejection> {
+    let key = ComponentKey::from(component_id);
+    if state::component_by_component_key(&key).is_none() {
+        return Err(warp::reject::not_found());
+    }
+
+    let totals = ComponentTotals::new(&metrics::by_component_key(&key));
+    Ok(json(&ComponentSummary::new(key.id().to_string(), totals)))
+}
+
+// Lists the disk buffers of running sinks along with their on-disk usage, sorted by id. Buffers
+// whose directory can't be read are left out.
+pub(super) async fn buffers() -> Result<impl Reply, Rejection> {
+    let summaries = tokio::task::spawn_blocking(|| {
+        vector_buffers::disk_buffer_ids()
+            .into_iter()
+            .filter_map(|id| match vector_buffers::inspect_disk_buffer(&id)? {
+                Ok(stats) => Some(ComponentSummary::new(id, stats)),
+                Err(error) => {
+                    warn!(message = "Failed to inspect disk buffer.", component_id = %id, %error);
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|_| warp::reject())?;
+
+    Ok(json(&summaries))
+}
+
+// Summarizes the on-disk usage of the disk buffer of a single sink, or responds with a 404 if
+// no running sink with the given id has a disk buffer.
+pub(super) async fn buffer(component_id: String) -> Result<impl Reply, Rejection> {
+    let id = component_id.clone();
+    let stats = tokio::task::spawn_blocking(move || vector_buffers::inspect_disk_buffer(&id))
+        .await
+        .map_err(|_| warp::reject())?
+        .ok_or_else(warp::reject::not_found)?;
+
+    Ok(match stats {
+        Ok(stats) => json(&ComponentSummary::new(component_id, stats)).into_response(),
+        Err(error) => warp::reply::with_status(
+            json(&serde_json::json!({ "error": error.to_string() })),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .into_response(),
+    })
+}
+
+// Asks the disk buffer of a single sink to reclaim the space held by acknowledged records. As
+// the buffer compacts itself in the background, this responds with a 202 right away, or with a
+// 404 if no running sink with the given id has a disk buffer.
+pub(super) async fn compact_buffer(component_id: String) -> Result<impl Reply, Rejection> {
+    if !vector_buffers::compact_disk_buffer(&component_id) {
+        return Err(warp::reject::not_found());
+    }
+
+    Ok(warp::reply::with_status(
+        json(&serde_json::json!({ "component_id": component_id, "compacting": true })),
+        StatusCode::ACCEPTED,
+    ))
+}
+
+// Streams tap results as server-sent events. Each SSE event is named after the payload type
+// (`log`, `metric`, `trace` or `notification`) and carries a JSON body.
+pub(super) async fn tap(watch_rx: WatchRx, query: TapQuery) -> Result<impl Reply, Rejection> {
+    if !(10..=60_000).contains(&query.interval) || !(1..=10_000).contains(&query.limit) {
+        return Ok(warp::reply::with_status(
+            json(&serde_json::json!({
+                "error": "`interval` must be within 10..=60000 and `limit` within 1..=10000"
+            })),
+            StatusCode::BAD_REQUEST,
+        )
+        .into_response());
+    }
+
+    let mut for_outputs = split_patterns(query.outputs_of);
+    let for_inputs = split_patterns(query.inputs_of);
+
+    // As with `vector tap`, tap all components' outputs if no patterns are provided.
+    if for_outputs.is_empty() && for_inputs.is_empty() {
+        for_outputs.push("*".to_string());
+    }
+
+    let patterns = TapPatterns::new(
+        for_outputs.into_iter().collect(),
+        for_inputs.into_iter().collect(),
+    );
+
+    let events = create_events_stream(
+        watch_rx,
+        patterns,
+        query.interval as u64,
+        query.limit as usize,
+    )
+    .flat_map(|payloads| futures::stream::iter(payloads.into_iter()))
+    .map(|payload| {
+        Ok::<_, Infallible>(
+            sse::Event::default()
+                .event(payload.as_str())
+                .data(payload.to_json().to_string()),
+        )
+    });
+
+    Ok(sse::reply(sse::keep_alive().stream(events)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_buffers::DiskBufferStats;
+
+    use super::*;
+
+    #[test]
+    fn split_tap_patterns() {
+        assert_eq!(
+            split_patterns(Some("in, transform*,,".to_string())),
+            vec!["in".to_string(), "transform*".to_string()]
+        );
+        assert!(split_patterns(None).is_empty());
+    }
+
+    #[test]
+    fn summarize_component_metrics() {
+        let summary = ComponentSummary::new(
+            "in".to_string(),
+            ComponentTotals {
+                received_events_total: Some(10.0),
+                sent_events_total: None,
+                errors_total: 1.0,
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "component_id": "in",
+                "received_events_total": 10.0,
+                "sent_events_total": null,
+                "errors_total": 1.0,
+            })
+        );
+    }
+
+    #[test]
+    fn summarize_buffer() {
+        let summary = ComponentSummary::new(
+            "out".to_string(),
+            DiskBufferStats {
+                segments: 2,
+                disk_bytes: 1024,
+                oldest_record_age: Some(std::time::Duration::from_millis(1500)),
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            serde_json::json!({
+                "component_id": "out",
+                "segments": 2,
+                "disk_bytes": 1024,
+                "oldest_record_age_seconds": 1.5,
+            })
+        );
+    }
+}
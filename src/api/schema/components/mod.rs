@@ -5,6 +5,7 @@ pub mod transform;
 
 use async_graphql::{Enum, InputObject, Interface, Object, Subscription};
 use once_cell::sync::Lazy;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use std::{
     cmp,
     collections::{HashMap, HashSet},
@@ -18,7 +19,7 @@ use crate::{
         filter::{self, filter_items},
         relay, sort,
     },
-    config::{ComponentKey, Config},
+    config::{ComponentKey, Config, OutputId},
     filter_check, schema,
 };
 
@@ -33,7 +34,8 @@ pub enum Component {
     Sink(sink::Sink),
 }
 
-#[derive(Enum, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ComponentKind {
     Source,
     Transform,
@@ -41,7 +43,7 @@ pub enum ComponentKind {
 }
 
 impl Component {
-    const fn get_component_key(&self) -> &ComponentKey {
+    pub const fn get_component_key(&self) -> &ComponentKey {
         match self {
             Component::Source(c) => &c.0.component_key,
             Component::Transform(c) => &c.0.component_key,
@@ -49,13 +51,48 @@ impl Component {
         }
     }
 
-    const fn get_component_kind(&self) -> ComponentKind {
+    pub const fn get_component_kind(&self) -> ComponentKind {
         match self {
             Component::Source(_) => ComponentKind::Source,
             Component::Transform(_) => ComponentKind::Transform,
             Component::Sink(_) => ComponentKind::Sink,
         }
     }
+
+    pub fn get_component_type(&self) -> &str {
+        match self {
+            Component::Source(c) => c.get_component_type(),
+            Component::Transform(c) => c.get_component_type(),
+            Component::Sink(c) => c.get_component_type(),
+        }
+    }
+
+    /// Returns the outputs this component reads from. Sources have no inputs.
+    pub fn get_inputs(&self) -> &[OutputId] {
+        match self {
+            Component::Source(_) => &[],
+            Component::Transform(c) => c.0.inputs.as_ref(),
+            Component::Sink(c) => c.0.inputs.as_ref(),
+        }
+    }
+}
+
+/// Summarizes the component for the REST API, with the same fields as the GraphQL interface.
+impl Serialize for Component {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let inputs = self
+            .get_inputs()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let mut summary = serializer.serialize_struct("Component", 4)?;
+        summary.serialize_field("component_id", self.get_component_key().id())?;
+        summary.serialize_field("component_kind", &self.get_component_kind())?;
+        summary.serialize_field("component_type", self.get_component_type())?;
+        summary.serialize_field("inputs", &inputs)?;
+        summary.end()
+    }
 }
 
 #[derive(Default, InputObject)]
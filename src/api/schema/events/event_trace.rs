@@ -0,0 +1,102 @@
+use async_graphql::Object;
+use chrono::{DateTime, Utc};
+
+use crate::event;
+
+/// Milliseconds between the two instants, as a fraction so that sub-millisecond latencies show.
+fn millis_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from)
+        .num_microseconds()
+        .map_or(f64::MAX, |micros| micros as f64 / 1000.0)
+}
+
+#[derive(Debug, Clone)]
+pub struct EventTrace(event::EventTrace);
+
+impl EventTrace {
+    pub const fn new(trace: event::EventTrace) -> Self {
+        Self(trace)
+    }
+
+    /// Whether the event traversed one of the given components.
+    pub fn traverses_any(&self, component_ids: &[String]) -> bool {
+        self.0
+            .hops()
+            .iter()
+            .any(|hop| component_ids.contains(&hop.component_id))
+    }
+}
+
+#[Object]
+/// The trace of an event sampled by the event tracing mode, from the source which emitted it to
+/// the sink it reached
+impl EventTrace {
+    /// Trace ID, shared by the copies of the event sent to several components
+    async fn id(&self) -> u64 {
+        self.0.id()
+    }
+
+    /// Time from the source emitting the event to the sink receiving it, in milliseconds
+    async fn total_latency_ms(&self) -> f64 {
+        let hops = self.0.hops();
+        match (hops.first(), hops.last()) {
+            (Some(first), Some(last)) => millis_between(first.entered_at, last.entered_at),
+            _ => 0.0,
+        }
+    }
+
+    /// Components traversed by the event, in order
+    async fn hops(&self) -> Vec<EventTraceHop> {
+        let hops = self.0.hops();
+        hops.iter()
+            .enumerate()
+            .map(|(index, hop)| EventTraceHop {
+                hop: hop.clone(),
+                previous_exited_at: index.checked_sub(1).and_then(|index| hops[index].exited_at),
+            })
+            .collect()
+    }
+}
+
+pub struct EventTraceHop {
+    hop: event::TraceHop,
+    previous_exited_at: Option<DateTime<Utc>>,
+}
+
+#[Object]
+/// A component traversed by a traced event
+impl EventTraceHop {
+    /// Component ID
+    async fn component_id(&self) -> &str {
+        &self.hop.component_id
+    }
+
+    /// Component kind, `source`, `transform` or `sink`
+    async fn component_kind(&self) -> &str {
+        self.hop.component_kind
+    }
+
+    /// When the component received the event, or emitted it for a source
+    async fn entered_at(&self) -> DateTime<Utc> {
+        self.hop.entered_at
+    }
+
+    /// When the component sent the event on. Sinks don't send the events on
+    async fn exited_at(&self) -> Option<DateTime<Utc>> {
+        self.hop.exited_at
+    }
+
+    /// Time the event waited in the input buffer of the component, since the previous component
+    /// sent it, in milliseconds
+    async fn queue_latency_ms(&self) -> Option<f64> {
+        self.previous_exited_at
+            .map(|exited_at| millis_between(exited_at, self.hop.entered_at))
+    }
+
+    /// Time the component spent processing the event, in milliseconds
+    async fn processing_latency_ms(&self) -> Option<f64> {
+        self.hop
+            .exited_at
+            .map(|exited_at| millis_between(self.hop.entered_at, exited_at))
+    }
+}
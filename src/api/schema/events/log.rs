@@ -19,6 +19,14 @@ impl Log {
         Self { output, event }
     }
 
+    pub const fn get_output(&self) -> &TapOutput {
+        &self.output
+    }
+
+    pub const fn get_event(&self) -> &event::LogEvent {
+        &self.event
+    }
+
     pub fn get_message(&self) -> Option<String> {
         Some(self.event.get("message")?.to_string_lossy())
     }
@@ -21,6 +21,14 @@ impl Metric {
     pub const fn new(output: TapOutput, event: event::Metric) -> Self {
         Self { output, event }
     }
+
+    pub const fn get_output(&self) -> &TapOutput {
+        &self.output
+    }
+
+    pub const fn get_event(&self) -> &event::Metric {
+        &self.event
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Enum)]
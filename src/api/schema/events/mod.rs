@@ -1,4 +1,5 @@
 mod encoding;
+pub mod event_trace;
 pub mod log;
 pub mod metric;
 pub mod notification;
@@ -9,14 +10,21 @@ use std::collections::HashSet;
 
 use async_graphql::{Context, Subscription};
 use encoding::EventEncodingType;
+use event_trace::EventTrace;
 use futures::Stream;
 use itertools::Itertools;
 use output::OutputEventsPayload;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 use tokio::{select, sync::mpsc, time};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{
+    wrappers::{BroadcastStream, ReceiverStream},
+    StreamExt,
+};
 
-use crate::{api::tap::TapController, topology::WatchRx};
+use crate::{
+    api::tap::TapController,
+    topology::{event_tracing, WatchRx},
+};
 
 /// Patterns (glob) used by tap to match against components and access events
 /// flowing into (for_inputs) or out of (for_outputs) specified components
@@ -67,6 +75,21 @@ impl EventsSubscription {
         // Client input is confined to `u32` to provide sensible bounds.
         create_events_stream(watch_rx, patterns, interval as u64, limit as usize)
     }
+
+    /// A stream of the traces of the events sampled by the event tracing mode, enabled by
+    /// `api.event_tracing_sample_rate`, as they reach a sink. Only the traces of the events
+    /// traversing one of the given components are streamed, if any are given.
+    pub async fn event_trace(
+        &self,
+        component_ids: Option<Vec<String>>,
+    ) -> impl Stream<Item = EventTrace> {
+        let component_ids = component_ids.unwrap_or_default();
+        // The traces missed by a lagging subscriber are skipped.
+        BroadcastStream::new(event_tracing::subscribe()).filter_map(move |trace| {
+            let trace = EventTrace::new(trace.ok()?);
+            (component_ids.is_empty() || trace.traverses_any(&component_ids)).then(|| trace)
+        })
+    }
 }
 
 /// Creates an events stream based on component ids, and a provided interval. Will emit
@@ -61,20 +61,47 @@ impl InvalidMatch {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, SimpleObject)]
+/// Events were skipped because the tap couldn't keep up with the component's output,
+/// so the events received by the client are incomplete
+pub struct EventsSkipped {
+    #[graphql(skip)]
+    message: String,
+    /// Id of the component whose events were skipped
+    component_id: String,
+    /// Number of events skipped since the last notification
+    count: usize,
+}
+
+impl EventsSkipped {
+    pub fn new(component_id: String, count: usize) -> Self {
+        Self {
+            message: format!(
+                "[tap] Warning: skipped {} event(s) from '{}': the tap couldn't keep up.",
+                count, component_id
+            ),
+            component_id,
+            count,
+        }
+    }
+}
+
 #[derive(Union, Debug, Clone, PartialEq)]
 /// A specific kind of notification with additional details
 pub enum Notification {
     Matched(Matched),
     NotMatched(NotMatched),
     InvalidMatch(InvalidMatch),
+    EventsSkipped(EventsSkipped),
 }
 
 impl Notification {
-    fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &str {
         match self {
             Notification::Matched(n) => n.message.as_ref(),
             Notification::NotMatched(n) => n.message.as_ref(),
             Notification::InvalidMatch(n) => n.message.as_ref(),
+            Notification::EventsSkipped(n) => n.message.as_ref(),
         }
     }
 }
@@ -1,8 +1,10 @@
 use async_graphql::Union;
+use serde::Serialize;
+use serde_json::json;
 
 use super::{log::Log, metric::Metric, notification::EventNotification, trace::Trace};
 
-use crate::api::tap::TapPayload;
+use crate::{api::tap::TapPayload, topology::TapOutput};
 
 #[derive(Union, Debug, Clone)]
 /// An event or a notification
@@ -33,3 +35,36 @@ impl From<TapPayload> for OutputEventsPayload {
         }
     }
 }
+
+impl OutputEventsPayload {
+    /// Returns the name of the payload variant, e.g. `log`.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Log(_) => "log",
+            Self::Metric(_) => "metric",
+            Self::Notification(_) => "notification",
+            Self::Trace(_) => "trace",
+        }
+    }
+
+    /// Returns the payload as JSON, for clients that don't use GraphQL.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Log(log) => event_json(log.get_output(), log.get_event()),
+            Self::Metric(metric) => event_json(metric.get_output(), metric.get_event()),
+            Self::Trace(trace) => event_json(trace.get_output(), trace.get_event()),
+            Self::Notification(notification) => json!({
+                "message": notification.notification.as_str(),
+            }),
+        }
+    }
+}
+
+fn event_json(output: &TapOutput, event: impl Serialize) -> serde_json::Value {
+    json!({
+        "component_id": output.output_id.component.id(),
+        "component_kind": output.component_kind,
+        "component_type": output.component_type,
+        "event": event,
+    })
+}
@@ -17,6 +17,14 @@ impl Trace {
     pub const fn new(output: TapOutput, event: event::TraceEvent) -> Self {
         Self { output, event }
     }
+
+    pub const fn get_output(&self) -> &TapOutput {
+        &self.output
+    }
+
+    pub const fn get_event(&self) -> &event::TraceEvent {
+        &self.event
+    }
 }
 
 #[Object]
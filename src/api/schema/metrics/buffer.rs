@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+
+use async_graphql::Object;
+
+use crate::{
+    config::ComponentKey,
+    event::{Metric, MetricValue},
+};
+
+/// Sums the values of the gauges named `name`, across all buffer stages.
+fn sum_gauges(metrics: &[Metric], name: &str) -> Option<f64> {
+    sum_stage_gauges(metrics, name, |_| true)
+}
+
+/// Sums the values of the gauges named `name`, across the buffer stages whose type matches
+/// `stage_type`.
+fn sum_stage_gauges(
+    metrics: &[Metric],
+    name: &str,
+    stage_type: impl Fn(&str) -> bool,
+) -> Option<f64> {
+    metrics
+        .iter()
+        .filter(|m| m.name() == name)
+        .filter(|m| m.tag_value("buffer_type").map_or(false, |t| stage_type(&t)))
+        .filter_map(|m| match m.value() {
+            MetricValue::Gauge { value } => Some(*value),
+            _ => None,
+        })
+        .reduce(|a, b| a + b)
+}
+
+/// Highest buffer fill observed for a component, in events and bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BufferHighWatermark {
+    events: f64,
+    byte_size: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentBufferUsage {
+    component_key: ComponentKey,
+    buffer_type: Option<String>,
+    events: f64,
+    byte_size: f64,
+    disk_byte_size: Option<f64>,
+    max_events: Option<f64>,
+    max_byte_size: Option<f64>,
+    high_watermark: BufferHighWatermark,
+}
+
+impl ComponentBufferUsage {
+    /// Returns a new `ComponentBufferUsage` from the `buffer_*` metrics of a single component,
+    /// raising the component's entry in `watermarks` if the buffer is fuller than seen before.
+    pub fn new(
+        component_key: ComponentKey,
+        metrics: &[Metric],
+        watermarks: &mut BTreeMap<ComponentKey, BufferHighWatermark>,
+    ) -> Self {
+        let events = sum_gauges(metrics, "buffer_events").unwrap_or(0.00);
+        let byte_size = sum_gauges(metrics, "buffer_byte_size").unwrap_or(0.00);
+
+        let high_watermark = watermarks.entry(component_key.clone()).or_default();
+        high_watermark.events = high_watermark.events.max(events);
+        high_watermark.byte_size = high_watermark.byte_size.max(byte_size);
+
+        Self {
+            buffer_type: metrics
+                .iter()
+                .filter(|m| m.tag_value("stage").as_deref() == Some("0"))
+                .find_map(|m| m.tag_value("buffer_type")),
+            events,
+            byte_size,
+            disk_byte_size: sum_stage_gauges(metrics, "buffer_byte_size", |t| {
+                t.starts_with("disk")
+            }),
+            max_events: sum_gauges(metrics, "buffer_max_event_size"),
+            max_byte_size: sum_gauges(metrics, "buffer_max_byte_size"),
+            high_watermark: *high_watermark,
+            component_key,
+        }
+    }
+
+    /// Returns the fill percentage of the buffer, against whichever limit is closest to
+    /// being reached.
+    pub fn get_utilization(&self) -> Option<f64> {
+        let by_events = self.max_events.map(|max| self.events / max);
+        let by_bytes = self.max_byte_size.map(|max| self.byte_size / max);
+
+        match (by_events, by_bytes) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+        .filter(|ratio| ratio.is_finite())
+        .map(|ratio| ratio * 100.00)
+    }
+}
+
+#[Object]
+impl ComponentBufferUsage {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Type of the buffer's first stage (`memory`, `disk` or `disk_v2`)
+    async fn buffer_type(&self) -> Option<&str> {
+        self.buffer_type.as_deref()
+    }
+
+    /// Number of events currently held in the buffer
+    async fn events(&self) -> f64 {
+        self.events
+    }
+
+    /// Size of the events currently held in the buffer, in bytes
+    async fn byte_size(&self) -> f64 {
+        self.byte_size
+    }
+
+    /// Configured maximum number of events, for buffers limited by event count
+    async fn max_events(&self) -> Option<f64> {
+        self.max_events
+    }
+
+    /// Configured maximum size in bytes, for buffers limited by size
+    async fn max_byte_size(&self) -> Option<f64> {
+        self.max_byte_size
+    }
+
+    /// Percentage of the buffer's capacity currently in use
+    async fn utilization(&self) -> Option<f64> {
+        self.get_utilization()
+    }
+
+    /// Highest number of events held in the buffer since the subscription started
+    async fn events_high_watermark(&self) -> f64 {
+        self.high_watermark.events
+    }
+
+    /// Highest size held in the buffer, in bytes, since the subscription started
+    async fn byte_size_high_watermark(&self) -> f64 {
+        self.high_watermark.byte_size
+    }
+
+    /// Size of the events held by the buffer's disk stages, in bytes
+    async fn disk_byte_size(&self) -> Option<f64> {
+        self.disk_byte_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::SinkExt;
+    use tracing::Span;
+    use vector_core::buffers::BufferConfig;
+
+    use super::*;
+    use crate::event::{Event, EventArray, LogEvent, MetricKind};
+
+    fn gauge(name: &str, value: f64, stage: &str) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value }).with_tags(Some(
+            vec![
+                ("component_id".to_owned(), "out".to_owned()),
+                ("buffer_type".to_owned(), "disk".to_owned()),
+                ("stage".to_owned(), stage.to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+    }
+
+    #[test]
+    fn buffer_usage_high_watermark() {
+        let key = ComponentKey::from("out");
+        let mut watermarks = BTreeMap::new();
+
+        let usage = ComponentBufferUsage::new(
+            key.clone(),
+            &[
+                gauge("buffer_events", 30.0, "0"),
+                gauge("buffer_byte_size", 3000.0, "0"),
+                gauge("buffer_max_byte_size", 10000.0, "0"),
+            ],
+            &mut watermarks,
+        );
+        assert_eq!(usage.get_utilization(), Some(30.0));
+        assert_eq!(usage.high_watermark.events, 30.0);
+
+        let usage = ComponentBufferUsage::new(
+            key,
+            &[
+                gauge("buffer_events", 10.0, "0"),
+                gauge("buffer_byte_size", 1000.0, "0"),
+                gauge("buffer_max_byte_size", 10000.0, "0"),
+            ],
+            &mut watermarks,
+        );
+        assert_eq!(usage.get_utilization(), Some(10.0));
+        assert_eq!(usage.high_watermark.events, 30.0);
+        assert_eq!(usage.high_watermark.byte_size, 3000.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn buffer_usage_from_buffer_metrics() {
+        let _ = crate::metrics::init_test();
+
+        let (mut tx, _rx, _acker) = BufferConfig::default()
+            .build::<EventArray>(None, "out".to_owned(), Span::none())
+            .await
+            .unwrap();
+        for _ in 0..3 {
+            tx.send(Event::from(LogEvent::default()).into())
+                .await
+                .unwrap();
+        }
+
+        // The buffer reports its usage every two seconds.
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        let metrics = crate::metrics::Controller::get()
+            .unwrap()
+            .capture_metrics()
+            .into_iter()
+            .filter(|m| m.name().starts_with("buffer_"))
+            .collect::<Vec<_>>();
+        let usage =
+            ComponentBufferUsage::new(ComponentKey::from("out"), &metrics, &mut BTreeMap::new());
+
+        assert_eq!(usage.buffer_type.as_deref(), Some("memory"));
+        assert_eq!(usage.events, 3.0);
+        assert_eq!(usage.max_events, Some(500.0));
+        assert_eq!(usage.disk_byte_size, None);
+    }
+}
@@ -3,7 +3,7 @@ use chrono::{DateTime, Utc};
 
 use crate::{
     config::ComponentKey,
-    event::{Metric, MetricValue},
+    event::{LogEvent, Metric, MetricValue, Value},
 };
 
 pub struct ErrorsTotal(Metric);
@@ -69,3 +69,99 @@ impl ComponentErrorsTotal {
         ErrorsTotal::new(self.metric.clone())
     }
 }
+
+pub struct ComponentErrorsThroughput {
+    component_key: ComponentKey,
+    throughput: i64,
+}
+
+impl ComponentErrorsThroughput {
+    /// Returns a new `ComponentErrorsThroughput`, set to the provided id/throughput values
+    pub const fn new(component_key: ComponentKey, throughput: i64) -> Self {
+        Self {
+            component_key,
+            throughput,
+        }
+    }
+}
+
+#[Object]
+impl ComponentErrorsThroughput {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Errors throughput
+    async fn throughput(&self) -> i64 {
+        self.throughput
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentError {
+    component_key: ComponentKey,
+    timestamp: Option<DateTime<Utc>>,
+    message: String,
+}
+
+impl ComponentError {
+    /// Returns a `ComponentError` from an internal log event emitted by `component_id`, if
+    /// the event was logged at `ERROR` level
+    pub fn from_log(component_id: &str, log: &LogEvent) -> Option<Self> {
+        if log.get("metadata.level")?.to_string_lossy() != "ERROR" {
+            return None;
+        }
+
+        Some(Self {
+            component_key: ComponentKey::from(component_id),
+            timestamp: log.get("timestamp").and_then(Value::as_timestamp).copied(),
+            message: log
+                .get("message")
+                .map(Value::to_string_lossy)
+                .unwrap_or_default(),
+        })
+    }
+
+    pub const fn get_component_key(&self) -> &ComponentKey {
+        &self.component_key
+    }
+}
+
+#[Object]
+impl ComponentError {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Time the error was logged at
+    async fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp
+    }
+
+    /// Error message
+    async fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(level: &str) -> LogEvent {
+        let mut log = LogEvent::from("Request failed.");
+        log.insert("metadata.level", level);
+        log
+    }
+
+    #[test]
+    fn component_error_from_log() {
+        let error = ComponentError::from_log("out", &log("ERROR")).unwrap();
+        assert_eq!(error.component_key, ComponentKey::from("out"));
+        assert_eq!(error.message, "Request failed.");
+
+        assert!(ComponentError::from_log("out", &log("WARN")).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, HashSet};
 
 use async_stream::stream;
+use serde::Serialize;
 use tokio::time::Duration;
 use tokio_stream::{Stream, StreamExt};
 
@@ -54,7 +55,7 @@ pub trait MetricsFilter<'a> {
     fn sent_events_total(&self) -> Option<SentEventsTotal>;
 }
 
-impl<'a> MetricsFilter<'a> for Vec<Metric> {
+impl<'a> MetricsFilter<'a> for [Metric] {
     fn processed_events_total(&self) -> Option<ProcessedEventsTotal> {
         let sum = sum_metrics(self.iter().filter(|m| m.name() == "processed_events_total"))?;
 
@@ -187,6 +188,41 @@ pub fn get_all_metrics(interval: i32) -> impl Stream<Item = Vec<Metric>> {
     }
 }
 
+/// Sums the values of all `*_errors_total` counters in `metrics`.
+pub fn sum_errors_total(metrics: &[Metric]) -> f64 {
+    metrics
+        .iter()
+        .filter(|m| m.name().ends_with("_errors_total"))
+        .map(|m| match m.value() {
+            MetricValue::Counter { value } => *value,
+            _ => 0.00,
+        })
+        .sum()
+}
+
+/// The event and error totals of a component, as reported by both the topology and the REST API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ComponentTotals {
+    pub received_events_total: Option<f64>,
+    pub sent_events_total: Option<f64>,
+    pub errors_total: f64,
+}
+
+impl ComponentTotals {
+    /// Returns the totals of the metrics of a single component.
+    pub fn new(metrics: &[Metric]) -> Self {
+        Self {
+            received_events_total: metrics
+                .received_events_total()
+                .map(|m| m.get_received_events_total()),
+            sent_events_total: metrics
+                .sent_events_total()
+                .map(|m| m.get_sent_events_total()),
+            errors_total: sum_errors_total(metrics),
+        }
+    }
+}
+
 /// Return Vec<Metric> based on a component id tag.
 pub fn by_component_key(component_key: &ComponentKey) -> Vec<Metric> {
     get_controller()
@@ -368,7 +404,7 @@ pub fn component_sent_events_total_throughputs_with_outputs(
 
 /// Returns a map of Component ID to list of metrics where metrics have been
 /// filtered by `filter_fn`
-fn component_to_filtered_metrics(
+pub fn component_to_filtered_metrics(
     interval: i32,
     filter_fn: &'static MetricFilterFn,
 ) -> impl Stream<Item = BTreeMap<String, Vec<Metric>>> {
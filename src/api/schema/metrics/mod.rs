@@ -1,3 +1,4 @@
+mod buffer;
 mod errors;
 mod events_in;
 mod events_out;
@@ -15,9 +16,12 @@ mod uptime;
 #[cfg(feature = "sources-host_metrics")]
 mod host;
 
+use std::collections::BTreeMap;
+
 use async_graphql::{Interface, Object, Subscription};
+pub use buffer::{BufferHighWatermark, ComponentBufferUsage};
 use chrono::{DateTime, Utc};
-pub use errors::{ComponentErrorsTotal, ErrorsTotal};
+pub use errors::{ComponentError, ComponentErrorsThroughput, ComponentErrorsTotal, ErrorsTotal};
 pub use events_in::EventsInTotal;
 pub use events_out::EventsOutTotal;
 pub use filter::*;
@@ -34,11 +38,11 @@ pub use received_events::{
 pub use sent_events::{ComponentSentEventsThroughput, ComponentSentEventsTotal, SentEventsTotal};
 pub use sink::{IntoSinkMetrics, SinkMetrics};
 pub use source::{IntoSourceMetrics, SourceMetrics};
-use tokio_stream::{Stream, StreamExt};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 pub use transform::{IntoTransformMetrics, TransformMetrics};
 pub use uptime::Uptime;
 
-use crate::config::ComponentKey;
+use crate::{config::ComponentKey, trace};
 
 #[derive(Interface)]
 #[graphql(field(name = "timestamp", type = "Option<DateTime<Utc>>"))]
@@ -297,6 +301,58 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component error throughput over `interval`
+    async fn component_errors_throughputs(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentErrorsThroughput>> {
+        component_counter_throughputs(interval, &|m| m.name().ends_with("_errors_total")).map(|m| {
+            m.into_iter()
+                .map(|(m, throughput)| {
+                    ComponentErrorsThroughput::new(
+                        ComponentKey::from(m.tag_value("component_id").unwrap()),
+                        throughput as i64,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    /// Error messages logged by components, as they happen. If `componentId` is provided,
+    /// only errors of that component are returned.
+    async fn component_errors(
+        &self,
+        component_id: Option<String>,
+    ) -> impl Stream<Item = ComponentError> {
+        BroadcastStream::new(trace::subscribe_components()).filter_map(move |event| {
+            let event = event.ok()?;
+            let error = ComponentError::from_log(&event.component_id, &event.log)?;
+            component_id
+                .as_deref()
+                .map_or(true, |id| error.get_component_key().id() == id)
+                .then(|| error)
+        })
+    }
+
+    /// Buffer usage of each sink over `interval`. High-watermarks are tracked from the
+    /// start of the subscription.
+    async fn component_buffer_usages(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentBufferUsage>> {
+        let mut watermarks = BTreeMap::<ComponentKey, BufferHighWatermark>::new();
+
+        component_to_filtered_metrics(interval, &|m| m.name().starts_with("buffer_")).map(
+            move |map| {
+                map.into_iter()
+                    .map(|(id, metrics)| {
+                        ComponentBufferUsage::new(ComponentKey::from(id), &metrics, &mut watermarks)
+                    })
+                    .collect()
+            },
+        )
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
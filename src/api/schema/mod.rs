@@ -3,9 +3,11 @@ pub mod events;
 pub mod filter;
 mod health;
 mod meta;
-mod metrics;
+pub mod metrics;
 mod relay;
+mod reload;
 pub mod sort;
+mod topology;
 
 use async_graphql::{EmptyMutation, MergedObject, MergedSubscription, Schema, SchemaBuilder};
 
@@ -15,6 +17,8 @@ pub struct Query(
     components::ComponentsQuery,
     metrics::MetricsQuery,
     meta::MetaQuery,
+    topology::TopologyQuery,
+    reload::ReloadQuery,
 );
 
 #[derive(MergedSubscription, Default)]
@@ -0,0 +1,137 @@
+use async_graphql::{Enum, Object};
+use chrono::{DateTime, Utc};
+
+use crate::topology;
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReloadStrategy {
+    /// The new components were built while the old ones kept running
+    BlueGreen,
+    /// The old components were shut down before the new ones were built, as they claim the same
+    /// resources
+    InPlace,
+}
+
+impl From<topology::ReloadStrategy> for ReloadStrategy {
+    fn from(strategy: topology::ReloadStrategy) -> Self {
+        match strategy {
+            topology::ReloadStrategy::BlueGreen => Self::BlueGreen,
+            topology::ReloadStrategy::InPlace => Self::InPlace,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReloadState {
+    /// The new components are being built, and healthchecked
+    Building,
+    /// The traffic was cut over to the new components, while the old ones drain their events
+    Draining,
+    /// The new components are running, and the old ones are gone
+    Completed,
+    /// The new config couldn't be applied, so the old config is still running
+    Failed,
+}
+
+impl From<topology::ReloadState> for ReloadState {
+    fn from(state: topology::ReloadState) -> Self {
+        match state {
+            topology::ReloadState::Building => Self::Building,
+            topology::ReloadState::Draining => Self::Draining,
+            topology::ReloadState::Completed => Self::Completed,
+            topology::ReloadState::Failed => Self::Failed,
+        }
+    }
+}
+
+pub struct ReloadStatus(topology::ReloadStatus);
+
+#[Object]
+impl ReloadStatus {
+    /// How the components were replaced
+    async fn strategy(&self) -> ReloadStrategy {
+        self.0.strategy.into()
+    }
+
+    /// Current state of the reload
+    async fn state(&self) -> ReloadState {
+        self.0.state.into()
+    }
+
+    /// IDs of the old components still draining their events
+    async fn draining_component_ids(&self) -> Vec<String> {
+        self.0.draining.iter().map(ToString::to_string).collect()
+    }
+
+    /// Time at which the reload started
+    async fn started_at(&self) -> DateTime<Utc> {
+        self.0.started_at
+    }
+
+    /// Time at which the reload completed or failed
+    async fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.0.finished_at
+    }
+}
+
+#[derive(Default)]
+pub(super) struct ReloadQuery;
+
+#[Object]
+impl ReloadQuery {
+    /// Status of the last reload of the configuration, if any since Vector started
+    async fn reload_status(&self) -> Option<ReloadStatus> {
+        topology::reload_status().map(ReloadStatus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::ComponentKey;
+
+    struct StatusQuery(topology::ReloadStatus);
+
+    #[Object]
+    impl StatusQuery {
+        async fn reload_status(&self) -> ReloadStatus {
+            ReloadStatus(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn reload_status() {
+        let status = topology::ReloadStatus {
+            strategy: topology::ReloadStrategy::BlueGreen,
+            state: topology::ReloadState::Draining,
+            draining: vec![ComponentKey::from("out")],
+            started_at: Utc.ymd(2022, 3, 1).and_hms(12, 0, 0),
+            finished_at: None,
+        };
+        let schema = Schema::new(StatusQuery(status), EmptyMutation, EmptySubscription);
+
+        let response = schema
+            .execute(
+                "{ reloadStatus { strategy state drainingComponentIds startedAt finishedAt } }",
+            )
+            .await;
+
+        assert!(response.errors.is_empty());
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "reloadStatus": {
+                    "strategy": "BLUE_GREEN",
+                    "state": "DRAINING",
+                    "drainingComponentIds": ["out"],
+                    "startedAt": "2022-03-01T12:00:00+00:00",
+                    "finishedAt": null,
+                }
+            })
+        );
+    }
+}
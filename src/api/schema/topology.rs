@@ -0,0 +1,289 @@
+use async_graphql::{Enum, Object, SimpleObject};
+use itertools::Itertools;
+use tokio::time::Duration;
+
+use super::{
+    components::{state, Component, ComponentKind},
+    metrics::{self, ComponentTotals},
+};
+use crate::config::{ComponentKey, OutputId};
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ComponentHealth {
+    /// The component has reported metrics, and no errors
+    Healthy,
+    /// The component has reported one or more errors over the sampling interval
+    Erroring,
+    /// The component hasn't reported any metrics yet
+    Unknown,
+}
+
+/// A component in the running pipeline, with its current health, event counts and throughputs
+pub struct TopologyNode {
+    component: Component,
+    /// Whether the component has reported any metrics
+    has_metrics: bool,
+    totals: ComponentTotals,
+    /// Totals of the component at the start of the sampling interval
+    previous_totals: ComponentTotals,
+    interval: Duration,
+}
+
+/// Returns the per-second rate at which a counter went from `previous` to `current` over
+/// `interval`. A counter that went down was reset, e.g. by a reload, and counts from zero.
+fn rate(previous: Option<f64>, current: Option<f64>, interval: Duration) -> Option<f64> {
+    let current = current?;
+    let previous = previous
+        .filter(|previous| *previous <= current)
+        .unwrap_or(0.00);
+    Some((current - previous) / interval.as_secs_f64())
+}
+
+/// Returns the health of a component from its errors at the start and at the end of the sampling
+/// interval. As for the rates, errors that went down were reset and count from zero.
+fn health(has_metrics: bool, previous_errors: f64, errors: f64) -> ComponentHealth {
+    let previous_errors = if previous_errors <= errors {
+        previous_errors
+    } else {
+        0.00
+    };
+    if !has_metrics {
+        ComponentHealth::Unknown
+    } else if errors > previous_errors {
+        ComponentHealth::Erroring
+    } else {
+        ComponentHealth::Healthy
+    }
+}
+
+impl TopologyNode {
+    fn new(component: Component, previous_totals: ComponentTotals, interval: Duration) -> Self {
+        let metrics = metrics::by_component_key(component.get_component_key());
+        Self {
+            component,
+            has_metrics: !metrics.is_empty(),
+            totals: ComponentTotals::new(&metrics),
+            previous_totals,
+            interval,
+        }
+    }
+}
+
+#[Object]
+impl TopologyNode {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component.get_component_key().id()
+    }
+
+    /// Component kind (source, transform or sink)
+    async fn component_kind(&self) -> ComponentKind {
+        self.component.get_component_kind()
+    }
+
+    /// Component type, e.g. `demo_logs`
+    async fn component_type(&self) -> &str {
+        self.component.get_component_type()
+    }
+
+    /// Health of the component, derived from its internal metrics over the sampling interval
+    async fn health(&self) -> ComponentHealth {
+        health(
+            self.has_metrics,
+            self.previous_totals.errors_total,
+            self.totals.errors_total,
+        )
+    }
+
+    /// Total events received by the component
+    async fn received_events_total(&self) -> Option<f64> {
+        self.totals.received_events_total
+    }
+
+    /// Events received by the component per second, over the sampling interval
+    async fn received_events_throughput(&self) -> Option<f64> {
+        rate(
+            self.previous_totals.received_events_total,
+            self.totals.received_events_total,
+            self.interval,
+        )
+    }
+
+    /// Total events sent by the component
+    async fn sent_events_total(&self) -> Option<f64> {
+        self.totals.sent_events_total
+    }
+
+    /// Events sent by the component per second, over the sampling interval
+    async fn sent_events_throughput(&self) -> Option<f64> {
+        rate(
+            self.previous_totals.sent_events_total,
+            self.totals.sent_events_total,
+            self.interval,
+        )
+    }
+
+    /// Total errors raised by the component
+    async fn errors_total(&self) -> f64 {
+        self.totals.errors_total
+    }
+}
+
+#[derive(SimpleObject)]
+/// A connection from the output of one component to the input of another
+pub struct TopologyEdge {
+    /// Id of the upstream component
+    from_component_id: String,
+    /// Named output of the upstream component the edge reads from, if not the default
+    from_output: Option<String>,
+    /// Id of the downstream component
+    to_component_id: String,
+}
+
+impl TopologyEdge {
+    fn new(from: &OutputId, to: &ComponentKey) -> Self {
+        Self {
+            from_component_id: from.component.id().to_string(),
+            from_output: from.port.clone(),
+            to_component_id: to.id().to_string(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+/// The live pipeline graph. A component with several entries in `edges` as
+/// `fromComponentId` fans out; one with several entries as `toComponentId` fans in.
+pub struct Topology {
+    /// Configured components
+    nodes: Vec<TopologyNode>,
+    /// Input connections between components
+    edges: Vec<TopologyEdge>,
+}
+
+/// Returns an edge for every input of every component
+fn edges(components: &[Component]) -> Vec<TopologyEdge> {
+    components
+        .iter()
+        .flat_map(|component| {
+            let key = component.get_component_key();
+            component
+                .get_inputs()
+                .iter()
+                .map(move |input| TopologyEdge::new(input, key))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct TopologyQuery;
+
+#[Object]
+impl TopologyQuery {
+    /// The running topology, as a graph of components and their input connections.
+    /// Throughputs are sampled over `interval` milliseconds, which the query waits for.
+    async fn topology(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> Topology {
+        let components = state::get_components()
+            .into_iter()
+            .sorted_by(|a, b| Ord::cmp(a.get_component_key(), b.get_component_key()))
+            .collect::<Vec<_>>();
+
+        let interval = Duration::from_millis(interval as u64);
+        let previous_totals = components
+            .iter()
+            .map(|component| {
+                ComponentTotals::new(&metrics::by_component_key(component.get_component_key()))
+            })
+            .collect::<Vec<_>>();
+        tokio::time::sleep(interval).await;
+
+        let edges = edges(&components);
+        let nodes = components
+            .into_iter()
+            .zip(previous_totals)
+            .map(|(component, previous_totals)| {
+                TopologyNode::new(component, previous_totals, interval)
+            })
+            .collect();
+
+        Topology { nodes, edges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::schema::components::{sink, source, transform},
+        config::DataType,
+    };
+
+    #[test]
+    fn counter_rates() {
+        let interval = Duration::from_millis(500);
+
+        assert_eq!(rate(Some(10.0), Some(20.0), interval), Some(20.0));
+        // Counters that only appeared during the interval count from zero, as do reset ones.
+        assert_eq!(rate(None, Some(5.0), interval), Some(10.0));
+        assert_eq!(rate(Some(50.0), Some(5.0), interval), Some(10.0));
+        assert_eq!(rate(Some(10.0), None, interval), None);
+    }
+
+    #[test]
+    fn health_over_interval() {
+        assert_eq!(health(false, 0.0, 0.0), ComponentHealth::Unknown);
+        assert_eq!(health(true, 0.0, 0.0), ComponentHealth::Healthy);
+        // Errors raised before the interval don't count.
+        assert_eq!(health(true, 3.0, 3.0), ComponentHealth::Healthy);
+        assert_eq!(health(true, 3.0, 4.0), ComponentHealth::Erroring);
+        // Reset errors count from zero.
+        assert_eq!(health(true, 3.0, 1.0), ComponentHealth::Erroring);
+        assert_eq!(health(true, 3.0, 0.0), ComponentHealth::Healthy);
+    }
+
+    #[test]
+    fn edges_fan_in_and_fan_out() {
+        let components = vec![
+            Component::Source(source::Source(source::Data {
+                component_key: ComponentKey::from("gen"),
+                component_type: "demo_logs".to_string(),
+                output_type: DataType::Log,
+                outputs: vec![],
+            })),
+            Component::Transform(transform::Transform(transform::Data {
+                component_key: ComponentKey::from("route"),
+                component_type: "route".to_string(),
+                inputs: vec![OutputId::from("gen")],
+                outputs: vec!["errors".to_string()],
+            })),
+            Component::Sink(sink::Sink(sink::Data {
+                component_key: ComponentKey::from("devnull"),
+                component_type: "blackhole".to_string(),
+                inputs: vec![
+                    OutputId::from("gen"),
+                    OutputId::from((&ComponentKey::from("route"), "errors".to_string())),
+                ],
+            })),
+        ];
+
+        let edges = edges(&components)
+            .into_iter()
+            .map(|e| (e.from_component_id, e.from_output, e.to_component_id))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            edges,
+            vec![
+                ("gen".to_string(), None, "route".to_string()),
+                ("gen".to_string(), None, "devnull".to_string()),
+                (
+                    "route".to_string(),
+                    Some("errors".to_string()),
+                    "devnull".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -9,11 +9,18 @@ use async_graphql::{
     Data, Request, Schema,
 };
 use async_graphql_warp::{graphql_protocol, GraphQLResponse, GraphQLWebSocket};
+use futures::FutureExt;
+use openssl::memcmp;
 use tokio::sync::oneshot;
-use warp::{filters::BoxedFilter, http::Response, ws::Ws, Filter, Reply};
+use warp::{
+    filters::BoxedFilter,
+    http::{header::AUTHORIZATION, HeaderMap, Response},
+    ws::Ws,
+    Filter, Reply,
+};
 
-use super::{handler, schema, ShutdownTx};
-use crate::{config, topology};
+use super::{handler, rest, schema, ShutdownTx};
+use crate::{config, http::Auth, tls::MaybeTlsSettings, topology};
 
 pub struct Server {
     _shutdown: ShutdownTx,
@@ -21,30 +28,43 @@ pub struct Server {
 }
 
 impl Server {
-    /// Start the API server. This creates the routes and spawns a Warp server. The server is
-    /// gracefully shut down when Self falls out of scope by way of the oneshot sender closing.
-    pub fn start(
+    /// Start the API server. This creates the routes, binds the server's address and spawns a
+    /// Warp server. The server is gracefully shut down when Self falls out of scope by way of the
+    /// oneshot sender closing.
+    pub async fn start(
         config: &config::Config,
         watch_rx: topology::WatchRx,
         running: Arc<AtomicBool>,
-    ) -> Self {
-        let routes = make_routes(config.api.playground, watch_rx, running);
+    ) -> crate::Result<Self> {
+        let tls = MaybeTlsSettings::from_config(&config.api.tls, true)?;
+        let authorization = config
+            .api
+            .auth
+            .as_ref()
+            .map(authorization_header)
+            .transpose()?;
+        let routes = make_routes(config.api.playground, authorization, watch_rx, running);
 
         let (_shutdown, rx) = oneshot::channel();
-        let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
-            config.api.address.expect("No socket address"),
-            async {
-                rx.await.ok();
-            },
-        );
+        let addr = config.api.address.expect("No socket address");
 
         // Update component schema with the config before starting the server.
         schema::components::update_config(config);
+        topology::event_tracing::set_sample_rate(config.api.event_tracing_sample_rate);
+
+        let listener = tls
+            .bind(&addr)
+            .await
+            .map_err(|error| format!("Unable to bind API server to {}: {}", addr, error))?;
 
         // Spawn the server in the background.
-        tokio::spawn(server);
+        tokio::spawn(async move {
+            warp::serve(routes)
+                .serve_incoming_with_graceful_shutdown(listener.accept_stream(), rx.map(|_| ()))
+                .await;
+        });
 
-        Self { _shutdown, addr }
+        Ok(Self { _shutdown, addr })
     }
 
     /// Returns a copy of the SocketAddr that the server was started on.
@@ -56,12 +76,14 @@ impl Server {
     /// directly involve `self`, it provides a neater API to expose an internal implementation
     /// detail than exposing the function of the sub-mod directly.
     pub fn update_config(&self, config: &config::Config) {
-        schema::components::update_config(config)
+        schema::components::update_config(config);
+        topology::event_tracing::set_sample_rate(config.api.event_tracing_sample_rate);
     }
 }
 
 fn make_routes(
     playground: bool,
+    authorization: Option<String>,
     watch_tx: topology::WatchRx,
     running: Arc<AtomicBool>,
 ) -> BoxedFilter<(impl Reply,)> {
@@ -72,6 +94,34 @@ fn make_routes(
         .and(with_shared(running))
         .and_then(handler::health);
 
+    // REST fallbacks for clients that can't use GraphQL/WebSockets, guarded by the same
+    // credentials as the GraphQL endpoint. The credentials are checked once the route is matched,
+    // so that unknown paths are still not found.
+    let rest_routes = warp::get()
+        .and(
+            warp::path!("components")
+                .and(with_authorization(authorization.clone()))
+                .and_then(rest::components)
+                .or(warp::path!("components" / String / "metrics")
+                    .and(with_authorization(authorization.clone()))
+                    .and_then(rest::component_metrics))
+                .or(warp::path!("tap")
+                    .and(with_authorization(authorization.clone()))
+                    .and(with_watch(watch_tx.clone()))
+                    .and(warp::query::<rest::TapQuery>())
+                    .and_then(rest::tap))
+                .or(warp::path!("buffers")
+                    .and(with_authorization(authorization.clone()))
+                    .and_then(rest::buffers))
+                .or(warp::path!("buffers" / String)
+                    .and(with_authorization(authorization.clone()))
+                    .and_then(rest::buffer)),
+        )
+        .or(warp::post()
+            .and(warp::path!("buffers" / String / "compact"))
+            .and(with_authorization(authorization.clone()))
+            .and_then(rest::compact_buffer));
+
     // 404.
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
@@ -104,14 +154,17 @@ fn make_routes(
 
     // Handle GraphQL queries. Headers will first be parsed to determine whether the query is
     // a subscription and if so, an attempt will be made to upgrade the connection to WebSockets.
-    // All other queries will fall back to the default HTTP handler.
-    let graphql_handler = warp::path("graphql").and(graphql_subscription_handler.or(
-        async_graphql_warp::graphql(schema::build_schema().finish()).and_then(
-            |(schema, request): (Schema<_, _, _>, Request)| async move {
-                Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
-            },
-        ),
-    ));
+    // All other queries will fall back to the default HTTP handler. Both are guarded by the
+    // configured credentials, if any, which are checked against the `Authorization` header.
+    let graphql_handler = warp::path("graphql")
+        .and(with_authorization(authorization))
+        .and(graphql_subscription_handler.or(
+            async_graphql_warp::graphql(schema::build_schema().finish()).and_then(
+                |(schema, request): (Schema<_, _, _>, Request)| async move {
+                    Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
+                },
+            ),
+        ));
 
     // Provide a playground for executing GraphQL queries/mutations/subscriptions.
     let graphql_playground = if playground {
@@ -131,9 +184,11 @@ fn make_routes(
     // Wire up the health + GraphQL endpoints. Provides a permissive CORS policy to allow for
     // cross-origin interaction with the Vector API.
     health
+        .or(rest_routes)
         .or(graphql_handler)
         .or(graphql_playground)
         .or(not_found)
+        .recover(handler::recover)
         .with(
             warp::cors()
                 .allow_any_origin()
@@ -146,6 +201,7 @@ fn make_routes(
                     "Access-Control-Allow-Origin",
                     "Access-Control-Request-Headers",
                     "Content-Type",
+                    "Authorization",
                     "X-Apollo-Tracing", // for Apollo GraphQL clients
                     "Pragma",
                     "Host",
@@ -162,3 +218,100 @@ fn with_shared(
 ) -> impl Filter<Extract = (Arc<AtomicBool>,), Error = Infallible> + Clone {
     warp::any().map(move || Arc::<AtomicBool>::clone(&shared))
 }
+
+fn with_watch(
+    watch_rx: topology::WatchRx,
+) -> impl Filter<Extract = (topology::WatchRx,), Error = Infallible> + Clone {
+    warp::any().map(move || watch_rx.clone())
+}
+
+/// Renders the `Authorization` header value that requests must carry for the given credentials.
+fn authorization_header(auth: &Auth) -> crate::Result<String> {
+    let mut headers = HeaderMap::new();
+    auth.apply_headers_map(&mut headers);
+
+    let value = headers
+        .get(AUTHORIZATION)
+        .ok_or("Invalid `api.auth` credentials.")?;
+    Ok(value.to_str()?.to_owned())
+}
+
+fn with_authorization(authorization: Option<String>) -> BoxedFilter<()> {
+    let authorization = Arc::new(authorization);
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let authorization = Arc::clone(&authorization);
+            async move {
+                match (authorization.as_ref(), header) {
+                    (None, _) => Ok(()),
+                    // Compared in constant time, so as not to reveal how much of it matches.
+                    (Some(expected), Some(header))
+                        if expected.len() == header.len()
+                            && memcmp::eq(expected.as_bytes(), header.as_bytes()) =>
+                    {
+                        Ok(())
+                    }
+                    _ => Err(warp::reject::custom(handler::Unauthorized)),
+                }
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tokio::sync::watch;
+    use warp::http::StatusCode;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_unauthenticated_requests() {
+        let auth = Auth::Bearer {
+            token: "secret".to_owned(),
+        };
+        let (_watch_tx, watch_rx) = watch::channel(topology::TapResource::default());
+        let routes = make_routes(
+            false,
+            Some(authorization_header(&auth).unwrap()),
+            watch_rx,
+            Arc::new(AtomicBool::new(true)),
+        );
+        let query = json!({ "query": "{ health }" });
+
+        for authorization in [None, Some("Bearer other")] {
+            let mut request = warp::test::request().method("POST").path("/graphql");
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            let response = request.json(&query).reply(&routes).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+            let mut request = warp::test::request().path("/components");
+            if let Some(authorization) = authorization {
+                request = request.header("authorization", authorization);
+            }
+            let response = request.reply(&routes).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/graphql")
+            .header("authorization", "Bearer secret")
+            .json(&query)
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The health check stays open, for load balancers and orchestrators.
+        let response = warp::test::request().path("/health").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Unknown paths aren't found, whatever the credentials.
+        let response = warp::test::request().path("/unknown").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
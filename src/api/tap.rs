@@ -16,7 +16,7 @@ use vector_core::event::Metric;
 
 use super::{
     schema::events::{
-        notification::{InvalidMatch, Matched, NotMatched, Notification},
+        notification::{EventsSkipped, InvalidMatch, Matched, NotMatched, Notification},
         TapPatterns,
     },
     ShutdownRx, ShutdownTx,
@@ -24,6 +24,7 @@ use super::{
 use crate::{
     config::ComponentKey,
     event::{Event, EventArray, EventContainer, LogEvent, TraceEvent},
+    internal_events::TapEventSkipped,
     topology::{fanout, fanout::ControlChannel, TapOutput, TapResource, WatchRx},
 };
 
@@ -93,6 +94,14 @@ impl TapPayload {
         Self::Notification(Notification::NotMatched(NotMatched::new(pattern.into())))
     }
 
+    /// Raise an `events_skipped` event for events the tap couldn't keep up with.
+    pub fn events_skipped<T: Into<String>>(component_id: T, count: usize) -> Self {
+        Self::Notification(Notification::EventsSkipped(EventsSkipped::new(
+            component_id.into(),
+            count,
+        )))
+    }
+
     /// Raise an `invalid_match` event against the provided input pattern.
     pub fn invalid_input_pattern_match<T: Into<String>>(
         pattern: T,
@@ -130,11 +139,30 @@ impl TapPayload {
 pub struct TapSink {
     tap_tx: TapSender,
     output: TapOutput,
+    /// Events dropped because the tap channel was full, not yet reported to the client.
+    skipped: usize,
 }
 
 impl TapSink {
     pub const fn new(tap_tx: TapSender, output: TapOutput) -> Self {
-        Self { tap_tx, output }
+        Self {
+            tap_tx,
+            output,
+            skipped: 0,
+        }
+    }
+}
+
+impl TapSink {
+    /// Lets the client know how many events were skipped, if the channel has room.
+    fn report_skipped(&mut self) {
+        if self.skipped > 0 {
+            let notification =
+                TapPayload::events_skipped(self.output.output_id.to_string(), self.skipped);
+            if self.tap_tx.try_send(notification).is_ok() {
+                self.skipped = 0;
+            }
+        }
     }
 }
 
@@ -147,27 +175,44 @@ impl Sink<Event> for TapSink {
         Poll::Ready(Ok(()))
     }
 
-    /// Immediately send the event to the tap_tx, only if it has room. Otherwise just drop it
-    fn start_send(self: Pin<&mut Self>, event: Event) -> Result<(), Self::Error> {
+    /// Immediately send the event to the tap_tx, only if it has room. Otherwise just drop it,
+    /// and let the client know how many events were skipped once the channel has room again.
+    fn start_send(mut self: Pin<&mut Self>, event: Event) -> Result<(), Self::Error> {
+        self.report_skipped();
+
         let payload = match event {
             Event::Log(log) => TapPayload::Log(self.output.clone(), log),
             Event::Metric(metric) => TapPayload::Metric(self.output.clone(), metric),
             Event::Trace(trace) => TapPayload::Trace(self.output.clone(), trace),
         };
 
-        if let Err(TrySendError::Closed(payload)) = self.tap_tx.try_send(payload) {
-            debug!(
-                message = "Couldn't send event.",
-                payload = ?payload,
-                component_id = ?self.output.output_id,
-            );
+        match self.tap_tx.try_send(payload) {
+            Err(TrySendError::Full(_)) => {
+                emit!(&TapEventSkipped {
+                    component_id: self.output.output_id.component.id(),
+                });
+                self.skipped += 1;
+            }
+            Err(TrySendError::Closed(payload)) => {
+                debug!(
+                    message = "Couldn't send event.",
+                    payload = ?payload,
+                    component_id = ?self.output.output_id,
+                );
+            }
+            Ok(()) => {}
         }
 
         Ok(())
     }
 
-    /// Events are immediately flushed, so this doesn't do anything
-    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    /// Events are immediately flushed, so this only reports the events skipped since the last
+    /// notification, if the channel has room for it.
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.report_skipped();
         Poll::Ready(Ok(()))
     }
 
@@ -548,6 +593,79 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    /// A tap sink should report events it had to drop once the channel has room again
+    async fn sink_events_skipped() {
+        let id = OutputId::from(&ComponentKey::from("test"));
+        let output = TapOutput {
+            output_id: id.clone(),
+            component_kind: "source",
+            component_type: "demo".to_string(),
+        };
+
+        let (sink_tx, mut sink_rx) = tokio_mpsc::channel(1);
+        let mut sink = TapSink::new(sink_tx, output);
+
+        // Only the first event fits in the channel; the next two are skipped.
+        for _ in 0..3 {
+            sink.send(Event::from(LogEvent::from("test")))
+                .await
+                .unwrap();
+        }
+
+        assert!(matches!(sink_rx.recv().await, Some(TapPayload::Log(..))));
+
+        // The next event sent reports the skipped events first.
+        sink.send(Event::from(LogEvent::from("test")))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sink_rx.recv().await.map(|payload| match payload {
+                TapPayload::Notification(notification) => Some(notification),
+                _ => None,
+            }),
+            Some(Some(Notification::EventsSkipped(EventsSkipped::new(
+                id.to_string(),
+                2
+            ))))
+        );
+    }
+
+    #[tokio::test]
+    /// A tap sink should report events it had to drop on flush, without waiting for more events
+    async fn sink_events_skipped_on_flush() {
+        let id = OutputId::from(&ComponentKey::from("test"));
+        let output = TapOutput {
+            output_id: id.clone(),
+            component_kind: "source",
+            component_type: "demo".to_string(),
+        };
+
+        let (sink_tx, mut sink_rx) = tokio_mpsc::channel(1);
+        let mut sink = TapSink::new(sink_tx, output);
+
+        sink.feed(Event::from(LogEvent::from("test")))
+            .await
+            .unwrap();
+        sink.feed(Event::from(LogEvent::from("test")))
+            .await
+            .unwrap();
+        assert!(matches!(sink_rx.recv().await, Some(TapPayload::Log(..))));
+
+        sink.flush().await.unwrap();
+        assert_eq!(
+            sink_rx.recv().await.map(|payload| match payload {
+                TapPayload::Notification(notification) => Some(notification),
+                _ => None,
+            }),
+            Some(Some(Notification::EventsSkipped(EventsSkipped::new(
+                id.to_string(),
+                1
+            ))))
+        );
+    }
+
     fn assert_notification(payload: OutputEventsPayload) -> Notification {
         if let OutputEventsPayload::Notification(event_notification) = payload {
             event_notification.notification
@@ -637,6 +755,9 @@ mod tests {
                     namespace: None,
                     tags: None,
                 })],
+                tags: None,
+                all_metrics: false,
+                host_tag: None,
             },
         );
         config.add_sink(
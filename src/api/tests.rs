@@ -184,6 +184,9 @@ async fn integration_test_source_metric() {
                 namespace: None,
                 tags: None,
             })],
+            tags: None,
+            all_metrics: false,
+            host_tag: None,
         },
     );
     config.add_sink(
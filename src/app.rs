@@ -12,15 +12,15 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use crate::service;
 #[cfg(feature = "api")]
 use crate::{api, internal_events::ApiStarted};
+#[cfg(feature = "api-client")]
+use crate::{buffer, tap, top};
 use crate::{
     cli::{handle_config_errors, Color, LogFormat, Opts, RootOpts, SubCommand},
-    config, generate, graph, heartbeat, list, metrics,
+    config, convert_config, generate, graph, heartbeat, list, metrics, replay,
     signal::{self, SignalTo},
     topology::{self, RunningTopology},
     trace, unit_test, validate,
 };
-#[cfg(feature = "api-client")]
-use crate::{tap, top};
 
 pub static WORKER_THREADS: OnceNonZeroUsize = OnceNonZeroUsize::new();
 
@@ -145,14 +145,18 @@ impl Application {
                         SubCommand::Generate(g) => generate::cmd(&g),
                         SubCommand::Graph(g) => graph::cmd(&g),
                         SubCommand::Config(c) => config::cmd(&c, &config_paths),
+                        SubCommand::ConvertConfig(c) => convert_config::cmd(&c),
                         SubCommand::List(l) => list::cmd(&l),
                         SubCommand::Test(t) => unit_test::cmd(&t).await,
+                        SubCommand::Replay(r) => replay::cmd(&r).await,
                         #[cfg(windows)]
                         SubCommand::Service(s) => service::cmd(&s),
                         #[cfg(feature = "api-client")]
                         SubCommand::Top(t) => top::cmd(&t).await,
                         #[cfg(feature = "api-client")]
                         SubCommand::Tap(t) => tap::cmd(&t, signal_rx).await,
+                        #[cfg(feature = "api-client")]
+                        SubCommand::Buffer(b) => buffer::cmd(&b).await,
 
                         SubCommand::Validate(v) => validate::validate(&v, color).await,
                         #[cfg(feature = "vrl-cli")]
@@ -202,7 +206,7 @@ impl Application {
                     .ok_or(exitcode::CONFIG)?;
 
                 #[cfg(feature = "api")]
-                let api = config.api;
+                let api = config.api.clone();
 
                 let result = topology::start_validated(config, diff, pieces).await;
                 let (topology, graceful_crash) = result.ok_or(exitcode::CONFIG)?;
@@ -255,12 +259,26 @@ impl Application {
             // Assigned to prevent the API terminating when falling out of scope.
             let api_server = if api_config.enabled {
                 use std::sync::{Arc, atomic::AtomicBool};
-                emit!(&ApiStarted {
-                    addr: api_config.address.unwrap(),
-                    playground: api_config.playground
-                });
 
-                Some(api::Server::start(topology.config(), topology.watch(), Arc::<AtomicBool>::clone(&topology.running)))
+                match api::Server::start(topology.config(), topology.watch(), Arc::<AtomicBool>::clone(&topology.running)).await {
+                    Ok(api_server) => {
+                        emit!(&ApiStarted {
+                            addr: api_config.address.unwrap(),
+                            playground: api_config.playground,
+                            tls: api_config.tls.as_ref().and_then(|tls| tls.enabled).unwrap_or(false),
+                        });
+
+                        Some(api_server)
+                    }
+                    Err(error) => {
+                        // The API was asked for, so running without it would leave `vector top`
+                        // and `vector tap`, and anything else relying on it, silently broken.
+                        error!(message = "An error occurred while starting the API server.", %error);
+                        emit!(&VectorStopped);
+                        topology.stop().await;
+                        std::process::exit(exitcode::CONFIG);
+                    }
+                }
             } else {
                 info!(message="API is disabled, enable by setting `api.enabled` to `true` and use commands like `vector top`.");
                 None
@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use super::credentials::ExternalCredentialsSource;
+
 /// Configuration for configuring authentication strategy for AWS.
 #[derive(Serialize, Deserialize, Clone, Debug, Derivative)]
 #[derivative(Default)]
@@ -14,8 +18,29 @@ pub enum AwsAuthentication {
         credentials_file: String,
         profile: Option<String>,
     },
+    /// Assumes the role with the token of the file, as provided to the pods of EKS clusters by
+    /// the IAM roles for service accounts.
+    WebIdentity {
+        web_identity_token_file: String,
+        role_arn: String,
+        session_name: Option<String>,
+    },
+    /// Uses the credentials cached by `aws sso login` for the SSO profile.
+    Sso {
+        sso_profile: String,
+        config_file: Option<String>,
+    },
+    /// Runs the command, which prints the credentials as specified for the `credential_process`
+    /// setting of the AWS CLI.
+    Process { credential_process: String },
     Role {
         assume_role: String,
+        external_id: Option<String>,
+        session_name: Option<String>,
+        /// Roles assumed in order before `assume_role`, each with the credentials of the previous
+        /// one, starting from the default credentials.
+        #[serde(default)]
+        role_chain: Vec<AwsAssumeRole>,
     },
     // Default variant is used instead of Option<AWSAuthentication> since even for
     // None we need to build `AwsCredentialsProvider`.
@@ -25,6 +50,33 @@ pub enum AwsAuthentication {
     Default {},
 }
 
+/// A role assumed on the way to the role of the component.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AwsAssumeRole {
+    pub role_arn: String,
+    pub external_id: Option<String>,
+}
+
+impl AwsAuthentication {
+    /// The source of the credentials implemented by Vector, if any.
+    pub(crate) fn external_source(&self) -> Option<ExternalCredentialsSource> {
+        match self {
+            Self::Sso {
+                sso_profile,
+                config_file,
+            } => Some(ExternalCredentialsSource::Sso {
+                profile: sso_profile.clone(),
+                config_file: config_file.as_ref().map(PathBuf::from),
+            }),
+            Self::Process { credential_process } => Some(ExternalCredentialsSource::Process {
+                command: credential_process.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,11 +134,93 @@ mod tests {
         .unwrap();
 
         match config.auth {
-            AwsAuthentication::Role { assume_role } => assert_eq!(&assume_role, "auth.root"),
+            AwsAuthentication::Role { assume_role, .. } => assert_eq!(&assume_role, "auth.root"),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn parsing_role_chain() {
+        let config = toml::from_str::<ComponentConfig>(
+            r#"
+            auth.assume_role = "arn:aws:iam::123456789012:role/target"
+            auth.external_id = "target-id"
+            auth.role_chain = [
+                { role_arn = "arn:aws:iam::123456789012:role/first", external_id = "first-id" },
+                { role_arn = "arn:aws:iam::123456789012:role/second" },
+            ]
+        "#,
+        )
+        .unwrap();
+
+        match config.auth {
+            AwsAuthentication::Role {
+                assume_role,
+                external_id,
+                session_name,
+                role_chain,
+            } => {
+                assert_eq!(&assume_role, "arn:aws:iam::123456789012:role/target");
+                assert_eq!(external_id.as_deref(), Some("target-id"));
+                assert_eq!(session_name, None);
+                assert_eq!(
+                    role_chain,
+                    vec![
+                        AwsAssumeRole {
+                            role_arn: "arn:aws:iam::123456789012:role/first".to_owned(),
+                            external_id: Some("first-id".to_owned()),
+                        },
+                        AwsAssumeRole {
+                            role_arn: "arn:aws:iam::123456789012:role/second".to_owned(),
+                            external_id: None,
+                        },
+                    ]
+                );
+            }
             _ => panic!(),
         }
     }
 
+    #[test]
+    fn parsing_web_identity() {
+        let config = toml::from_str::<ComponentConfig>(
+            r#"
+            auth.web_identity_token_file = "/var/run/secrets/eks.amazonaws.com/serviceaccount/token"
+            auth.role_arn = "arn:aws:iam::123456789012:role/vector"
+        "#,
+        )
+        .unwrap();
+
+        assert!(matches!(config.auth, AwsAuthentication::WebIdentity { .. }));
+    }
+
+    #[test]
+    fn parsing_sso_and_process() {
+        let config = toml::from_str::<ComponentConfig>(
+            r#"
+            auth.sso_profile = "vector"
+        "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.auth.external_source(),
+            Some(ExternalCredentialsSource::Sso { profile, config_file: None }) if profile == "vector"
+        ));
+
+        let config = toml::from_str::<ComponentConfig>(
+            r#"
+            auth.credential_process = "/usr/local/bin/credentials --json"
+        "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.auth.external_source(),
+            Some(ExternalCredentialsSource::Process { .. })
+        ));
+    }
+
     #[test]
     fn parsing_static() {
         let config = toml::from_str::<ComponentConfig>(
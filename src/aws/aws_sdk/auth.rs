@@ -1,7 +1,20 @@
-use aws_config::{default_provider::credentials::default_provider, sts::AssumeRoleProviderBuilder};
-use aws_types::{credentials::SharedCredentialsProvider, Credentials};
+use std::{path::PathBuf, time::SystemTime};
 
-use crate::aws::auth::AwsAuthentication;
+use aws_config::{
+    default_provider::credentials::default_provider,
+    meta::credentials::LazyCachingCredentialsProvider,
+    sts::AssumeRoleProviderBuilder,
+    web_identity_token::{StaticConfiguration, WebIdentityTokenCredentialsProvider},
+};
+use aws_types::{
+    credentials::{future, CredentialsError, ProvideCredentials, SharedCredentialsProvider},
+    Credentials,
+};
+
+use crate::aws::{
+    auth::{AwsAssumeRole, AwsAuthentication},
+    credentials::{record_refresh, ExternalCredentialsSource},
+};
 
 impl AwsAuthentication {
     pub async fn credentials_provider(&self) -> crate::Result<SharedCredentialsProvider> {
@@ -17,13 +30,49 @@ impl AwsAuthentication {
             AwsAuthentication::File { .. } => {
                 Err("Overriding the credentials file is not supported.".into())
             }
-            AwsAuthentication::Role { assume_role } => Ok(SharedCredentialsProvider::new(
-                AssumeRoleProviderBuilder::new(assume_role)
-                    .build(default_credentials_provider().await),
-            )),
-            AwsAuthentication::Default {} => Ok(SharedCredentialsProvider::new(
-                default_credentials_provider().await,
+            AwsAuthentication::WebIdentity {
+                web_identity_token_file,
+                role_arn,
+                session_name,
+            } => Ok(cached(
+                WebIdentityTokenCredentialsProvider::builder()
+                    .static_configuration(StaticConfiguration {
+                        web_identity_token_file: PathBuf::from(web_identity_token_file),
+                        role_arn: role_arn.clone(),
+                        session_name: session_name.clone().unwrap_or_else(|| "vector".to_owned()),
+                    })
+                    .build(),
+                "web_identity",
             )),
+            AwsAuthentication::Sso { .. } | AwsAuthentication::Process { .. } => {
+                let source = self.external_source().expect("credentials are external");
+                let name = source.provider_name();
+                Ok(cached(ExternalProvider(source), name))
+            }
+            AwsAuthentication::Role {
+                assume_role,
+                external_id,
+                session_name,
+                role_chain,
+            } => {
+                let mut provider = default_credentials_provider().await;
+                let last = AwsAssumeRole {
+                    role_arn: assume_role.clone(),
+                    external_id: external_id.clone(),
+                };
+                for role in role_chain.iter().chain(std::iter::once(&last)) {
+                    let mut builder = AssumeRoleProviderBuilder::new(&role.role_arn);
+                    if let Some(external_id) = &role.external_id {
+                        builder = builder.external_id(external_id);
+                    }
+                    if let Some(session_name) = session_name {
+                        builder = builder.session_name(session_name);
+                    }
+                    provider = cached(builder.build(provider), "role");
+                }
+                Ok(provider)
+            }
+            AwsAuthentication::Default {} => Ok(default_credentials_provider().await),
         }
     }
 
@@ -37,5 +86,62 @@ impl AwsAuthentication {
 }
 
 async fn default_credentials_provider() -> SharedCredentialsProvider {
-    SharedCredentialsProvider::new(default_provider().await)
+    cached(default_provider().await, "default")
+}
+
+/// Caches the credentials of the provider until they expire, recording their refreshes.
+fn cached(
+    provider: impl ProvideCredentials + 'static,
+    name: &'static str,
+) -> SharedCredentialsProvider {
+    SharedCredentialsProvider::new(
+        LazyCachingCredentialsProvider::builder()
+            .load(MeteredProvider { provider, name })
+            .build(),
+    )
+}
+
+#[derive(Debug)]
+struct MeteredProvider<P> {
+    provider: P,
+    name: &'static str,
+}
+
+impl<P: ProvideCredentials> ProvideCredentials for MeteredProvider<P> {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(async move {
+            let result = self.provider.provide_credentials().await;
+            record_refresh(self.name, &result);
+            result
+        })
+    }
+}
+
+/// Provides the credentials of the sources implemented by Vector.
+#[derive(Debug)]
+struct ExternalProvider(ExternalCredentialsSource);
+
+impl ProvideCredentials for ExternalProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(async move {
+            let credentials = self
+                .0
+                .fetch()
+                .await
+                .map_err(|error| CredentialsError::ProviderError(error.into()))?;
+            Ok(Credentials::new(
+                credentials.access_key_id,
+                credentials.secret_access_key,
+                credentials.session_token,
+                credentials.expiration.map(SystemTime::from),
+                self.0.provider_name(),
+            ))
+        })
+    }
 }
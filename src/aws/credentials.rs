@@ -0,0 +1,525 @@
+//! The credentials sources implemented by Vector for both AWS clients, namely the
+//! `credential_process` commands and the cached credentials of the SSO profiles.
+
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use http::{Request, StatusCode};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use tokio::process::Command;
+
+use crate::{
+    config::ProxyConfig,
+    http::HttpClient,
+    internal_events::{AwsCredentialsRefreshError, AwsCredentialsRefreshed},
+    tls::TlsSettings,
+};
+
+#[derive(Debug, Snafu)]
+pub enum CredentialsError {
+    #[snafu(display("Failed running credential process: {}", source))]
+    ProcessRun { source: std::io::Error },
+    #[snafu(display("Credential process failed with {}: {}", status, stderr))]
+    ProcessStatus {
+        status: std::process::ExitStatus,
+        stderr: String,
+    },
+    #[snafu(display("Invalid credential process output: {}", source))]
+    ProcessOutput { source: serde_json::Error },
+    #[snafu(display("Unsupported credential process output version {}", version))]
+    ProcessVersion { version: u64 },
+    #[snafu(display("Failed reading {:?}: {}", path, source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Profile {:?} not found in {:?}", profile, path))]
+    ProfileNotFound { profile: String, path: PathBuf },
+    #[snafu(display("Profile {:?} has no {:?} setting", profile, key))]
+    MissingSetting { profile: String, key: &'static str },
+    #[snafu(display("Invalid SSO token cache {:?}: {}", path, source))]
+    SsoToken {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("The SSO token of {:?} expired, run `aws sso login` to renew it", path))]
+    SsoTokenExpired { path: PathBuf },
+    #[snafu(display("SSO credentials request failed: {}", source))]
+    SsoRequest { source: crate::Error },
+    #[snafu(display("SSO credentials request failed with status {}", status))]
+    SsoStatus { status: StatusCode },
+    #[snafu(display("Invalid SSO credentials response: {}", source))]
+    SsoResponse { source: serde_json::Error },
+    #[snafu(display("Unable to locate the home directory"))]
+    NoHomeDirectory,
+}
+
+/// Temporary credentials, expiring at the given time if any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExternalCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+/// A source of credentials the AWS clients don't provide for a single component.
+#[derive(Clone, Debug)]
+pub enum ExternalCredentialsSource {
+    /// Runs the command, which prints the credentials as JSON.
+    Process { command: String },
+    /// Exchanges the token cached by `aws sso login` for the credentials of the role of the
+    /// profile.
+    Sso {
+        profile: String,
+        config_file: Option<PathBuf>,
+    },
+}
+
+impl ExternalCredentialsSource {
+    pub const fn provider_name(&self) -> &'static str {
+        match self {
+            Self::Process { .. } => "process",
+            Self::Sso { .. } => "sso",
+        }
+    }
+
+    pub async fn fetch(&self) -> Result<ExternalCredentials, CredentialsError> {
+        match self {
+            Self::Process { command } => fetch_process(command).await,
+            Self::Sso {
+                profile,
+                config_file,
+            } => fetch_sso(profile, config_file.as_deref()).await,
+        }
+    }
+}
+
+/// Records the outcome of fetching new credentials from the given provider.
+pub fn record_refresh<T, E: std::fmt::Display>(provider: &'static str, result: &Result<T, E>) {
+    match result {
+        Ok(_) => emit!(&AwsCredentialsRefreshed { provider }),
+        Err(error) => emit!(&AwsCredentialsRefreshError {
+            provider,
+            error: &error.to_string(),
+        }),
+    }
+}
+
+/// The output of the credential process, as specified by the AWS CLI.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProcessOutput {
+    version: u64,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    expiration: Option<DateTime<Utc>>,
+}
+
+async fn fetch_process(command: &str) -> Result<ExternalCredentials, CredentialsError> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c");
+        process
+    };
+    let output = process
+        .arg(command)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context(ProcessRunSnafu)?;
+    if !output.status.success() {
+        return Err(CredentialsError::ProcessStatus {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        });
+    }
+    parse_process_output(&output.stdout)
+}
+
+fn parse_process_output(stdout: &[u8]) -> Result<ExternalCredentials, CredentialsError> {
+    let output: ProcessOutput = serde_json::from_slice(stdout).context(ProcessOutputSnafu)?;
+    if output.version != 1 {
+        return Err(CredentialsError::ProcessVersion {
+            version: output.version,
+        });
+    }
+    Ok(ExternalCredentials {
+        access_key_id: output.access_key_id,
+        secret_access_key: output.secret_access_key,
+        session_token: output.session_token,
+        expiration: output.expiration,
+    })
+}
+
+/// The settings of an SSO profile, in the legacy format or referring to an `sso-session`.
+#[derive(Debug, PartialEq)]
+struct SsoProfile {
+    start_url: String,
+    region: String,
+    account_id: String,
+    role_name: String,
+    /// The name of the `sso-session` section, which names the token cache instead of the URL.
+    session_name: Option<String>,
+}
+
+impl SsoProfile {
+    fn cache_key(&self) -> String {
+        let key = self.session_name.as_ref().unwrap_or(&self.start_url);
+        sha1_hex(key.as_bytes())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoToken {
+    access_token: String,
+    expires_at: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoResponse {
+    role_credentials: SsoRoleCredentials,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoRoleCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: String,
+    /// Milliseconds since the epoch.
+    expiration: i64,
+}
+
+fn sha1_hex(data: &[u8]) -> String {
+    openssl::sha::sha1(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn home_dir() -> Result<PathBuf, CredentialsError> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .ok_or(CredentialsError::NoHomeDirectory)
+}
+
+async fn fetch_sso(
+    profile: &str,
+    config_file: Option<&Path>,
+) -> Result<ExternalCredentials, CredentialsError> {
+    let config_file = match config_file {
+        Some(config_file) => config_file.to_owned(),
+        None => match env::var_os("AWS_CONFIG_FILE") {
+            Some(config_file) => PathBuf::from(config_file),
+            None => home_dir()?.join(".aws").join("config"),
+        },
+    };
+    let config = tokio::fs::read_to_string(&config_file)
+        .await
+        .context(ReadFileSnafu { path: &config_file })?;
+    let profile = sso_profile(&parse_ini(&config), profile, &config_file)?;
+
+    let token_path = home_dir()?
+        .join(".aws")
+        .join("sso")
+        .join("cache")
+        .join(format!("{}.json", profile.cache_key()));
+    let token = tokio::fs::read(&token_path)
+        .await
+        .context(ReadFileSnafu { path: &token_path })?;
+    let token: SsoToken =
+        serde_json::from_slice(&token).context(SsoTokenSnafu { path: &token_path })?;
+    if parse_expires_at(&token.expires_at).map_or(true, |expires_at| expires_at <= Utc::now()) {
+        return Err(CredentialsError::SsoTokenExpired { path: token_path });
+    }
+
+    let uri = format!(
+        "https://portal.sso.{}.amazonaws.com/federation/credentials?account_id={}&role_name={}",
+        profile.region,
+        utf8_percent_encode(&profile.account_id, NON_ALPHANUMERIC),
+        utf8_percent_encode(&profile.role_name, NON_ALPHANUMERIC),
+    );
+    let request = Request::get(uri)
+        .header("x-amz-sso_bearer_token", token.access_token)
+        .body(Body::empty())
+        .map_err(|error| CredentialsError::SsoRequest {
+            source: error.into(),
+        })?;
+    let client = sso_client().context(SsoRequestSnafu)?;
+    let response = client
+        .send(request)
+        .await
+        .map_err(|error| CredentialsError::SsoRequest {
+            source: error.into(),
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CredentialsError::SsoStatus { status });
+    }
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .map_err(|error| CredentialsError::SsoRequest {
+            source: error.into(),
+        })?;
+    parse_sso_response(&body)
+}
+
+fn sso_client() -> crate::Result<HttpClient> {
+    let tls = TlsSettings::from_options(&None)?;
+    Ok(HttpClient::new(tls, &ProxyConfig::from_env())?)
+}
+
+fn parse_sso_response(body: &[u8]) -> Result<ExternalCredentials, CredentialsError> {
+    let response: SsoResponse = serde_json::from_slice(body).context(SsoResponseSnafu)?;
+    let credentials = response.role_credentials;
+    Ok(ExternalCredentials {
+        access_key_id: credentials.access_key_id,
+        secret_access_key: credentials.secret_access_key,
+        session_token: Some(credentials.session_token),
+        expiration: Some(Utc.timestamp_millis(credentials.expiration)),
+    })
+}
+
+/// Older versions of the AWS CLI write the expiration as `2022-01-01T00:00:00UTC`.
+fn parse_expires_at(expires_at: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(expires_at)
+        .map(|expires_at| expires_at.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(expires_at, "%Y-%m-%dT%H:%M:%SUTC")
+                .map(|expires_at| Utc.from_utc_datetime(&expires_at))
+        })
+        .ok()
+}
+
+/// Parses the sections of the AWS config file, keyed by their header.
+fn parse_ini(contents: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections = HashMap::<String, HashMap<String, String>>::new();
+    let mut section = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+            sections.entry(header.clone()).or_default();
+            section = Some(header);
+        } else if let (Some(section), Some((key, value))) = (&section, line.split_once('=')) {
+            sections
+                .get_mut(section)
+                .expect("section was inserted")
+                .insert(key.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+    sections
+}
+
+fn sso_profile(
+    sections: &HashMap<String, HashMap<String, String>>,
+    name: &str,
+    path: &Path,
+) -> Result<SsoProfile, CredentialsError> {
+    let profile = sections
+        .get(&format!("profile {}", name))
+        .or_else(|| {
+            (name == "default")
+                .then(|| sections.get("default"))
+                .flatten()
+        })
+        .ok_or_else(|| CredentialsError::ProfileNotFound {
+            profile: name.to_owned(),
+            path: path.to_owned(),
+        })?;
+    let setting = |section: &HashMap<String, String>, section_name: &str, key: &'static str| {
+        section
+            .get(key)
+            .cloned()
+            .ok_or_else(|| CredentialsError::MissingSetting {
+                profile: section_name.to_owned(),
+                key,
+            })
+    };
+
+    let (session, session_name) = match profile.get("sso_session") {
+        Some(session_name) => {
+            let session = sections
+                .get(&format!("sso-session {}", session_name))
+                .ok_or_else(|| CredentialsError::ProfileNotFound {
+                    profile: format!("sso-session {}", session_name),
+                    path: path.to_owned(),
+                })?;
+            (session, Some(session_name.clone()))
+        }
+        None => (profile, None),
+    };
+    let session_section = session_name.as_deref().unwrap_or(name);
+
+    Ok(SsoProfile {
+        start_url: setting(session, session_section, "sso_start_url")?,
+        region: setting(session, session_section, "sso_region")?,
+        account_id: setting(profile, name, "sso_account_id")?,
+        role_name: setting(profile, name, "sso_role_name")?,
+        session_name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_process_output() {
+        let credentials = parse_process_output(
+            br#"{
+                "Version": 1,
+                "AccessKeyId": "key",
+                "SecretAccessKey": "secret",
+                "SessionToken": "token",
+                "Expiration": "2022-05-01T12:00:00Z"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            credentials,
+            ExternalCredentials {
+                access_key_id: "key".to_owned(),
+                secret_access_key: "secret".to_owned(),
+                session_token: Some("token".to_owned()),
+                expiration: Some(Utc.ymd(2022, 5, 1).and_hms(12, 0, 0)),
+            }
+        );
+
+        let credentials = parse_process_output(
+            br#"{"Version": 1, "AccessKeyId": "key", "SecretAccessKey": "secret"}"#,
+        )
+        .unwrap();
+        assert_eq!(credentials.session_token, None);
+        assert_eq!(credentials.expiration, None);
+
+        assert!(matches!(
+            parse_process_output(
+                br#"{"Version": 2, "AccessKeyId": "key", "SecretAccessKey": "secret"}"#
+            ),
+            Err(CredentialsError::ProcessVersion { version: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn runs_credential_process() {
+        let source = ExternalCredentialsSource::Process {
+            command: r#"echo '{"Version": 1, "AccessKeyId": "key", "SecretAccessKey": "secret"}'"#
+                .to_owned(),
+        };
+        assert_eq!(source.fetch().await.unwrap().access_key_id, "key");
+
+        let source = ExternalCredentialsSource::Process {
+            command: "echo failed >&2; exit 1".to_owned(),
+        };
+        assert!(matches!(
+            source.fetch().await,
+            Err(CredentialsError::ProcessStatus { stderr, .. }) if stderr == "failed"
+        ));
+    }
+
+    const CONFIG: &str = r#"
+        [default]
+        region = us-east-1
+
+        [profile legacy]
+        sso_start_url = https://example.awsapps.com/start
+        sso_region = us-east-1
+        sso_account_id = 123456789012
+        sso_role_name = ReadOnly
+
+        [profile session]
+        sso_session = vector
+        sso_account_id = 123456789012
+        sso_role_name = Admin
+
+        [sso-session vector]
+        sso_start_url = https://vector.awsapps.com/start
+        sso_region = eu-west-1
+    "#;
+
+    #[test]
+    fn parses_sso_profiles() {
+        let sections = parse_ini(CONFIG);
+        let path = Path::new("config");
+
+        let profile = sso_profile(&sections, "legacy", path).unwrap();
+        assert_eq!(
+            profile,
+            SsoProfile {
+                start_url: "https://example.awsapps.com/start".to_owned(),
+                region: "us-east-1".to_owned(),
+                account_id: "123456789012".to_owned(),
+                role_name: "ReadOnly".to_owned(),
+                session_name: None,
+            }
+        );
+        assert_eq!(
+            profile.cache_key(),
+            sha1_hex(b"https://example.awsapps.com/start")
+        );
+
+        let profile = sso_profile(&sections, "session", path).unwrap();
+        assert_eq!(profile.region, "eu-west-1");
+        assert_eq!(profile.role_name, "Admin");
+        assert_eq!(profile.cache_key(), sha1_hex(b"vector"));
+
+        assert!(matches!(
+            sso_profile(&sections, "default", path),
+            Err(CredentialsError::MissingSetting { .. })
+        ));
+        assert!(matches!(
+            sso_profile(&sections, "missing", path),
+            Err(CredentialsError::ProfileNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_sso_responses() {
+        let credentials = parse_sso_response(
+            br#"{"roleCredentials": {
+                "accessKeyId": "key",
+                "secretAccessKey": "secret",
+                "sessionToken": "token",
+                "expiration": 1651406400000
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            credentials.expiration,
+            Some(Utc.ymd(2022, 5, 1).and_hms(12, 0, 0))
+        );
+        assert_eq!(credentials.session_token.as_deref(), Some("token"));
+    }
+
+    #[test]
+    fn parses_sso_token_expiration() {
+        let expected = Some(Utc.ymd(2022, 5, 1).and_hms(12, 0, 0));
+        assert_eq!(parse_expires_at("2022-05-01T12:00:00Z"), expected);
+        assert_eq!(parse_expires_at("2022-05-01T12:00:00UTC"), expected);
+        assert_eq!(parse_expires_at("tomorrow"), None);
+    }
+}
@@ -1,7 +1,8 @@
 pub mod auth;
+pub mod credentials;
 pub mod region;
 
-pub use auth::AwsAuthentication;
+pub use auth::{AwsAssumeRole, AwsAuthentication};
 pub use region::RegionOrEndpoint;
 
 #[cfg(feature = "rusoto_core")]
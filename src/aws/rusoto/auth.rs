@@ -1,6 +1,9 @@
 use rusoto_core::Region;
 
-use crate::aws::{auth::AwsAuthentication, rusoto::AwsCredentialsProvider};
+use crate::aws::{
+    auth::{AwsAssumeRole, AwsAuthentication},
+    rusoto::AwsCredentialsProvider,
+};
 
 const AWS_DEFAULT_PROFILE: &str = "default";
 
@@ -43,13 +46,45 @@ impl AwsAuthentication {
                         .as_str(),
                 )
             }
-            Self::Role { assume_role } => {
+            Self::WebIdentity {
+                web_identity_token_file,
+                role_arn,
+                session_name,
+            } => {
+                if old_assume_role.is_some() {
+                    warn!("Ignoring option `assume_role`, instead using option `auth.role_arn`.");
+                }
+                AwsCredentialsProvider::new_with_web_identity(
+                    web_identity_token_file,
+                    role_arn,
+                    session_name.clone(),
+                )
+            }
+            Self::Sso { .. } | Self::Process { .. } => {
+                if old_assume_role.is_some() {
+                    warn!("Ignoring option `assume_role`, instead using the configured credentials source.");
+                }
+                AwsCredentialsProvider::new_with_external_source(
+                    self.external_source().expect("credentials are external"),
+                )
+            }
+            Self::Role {
+                assume_role,
+                external_id,
+                session_name,
+                role_chain,
+            } => {
                 if old_assume_role.is_some() {
                     warn!(
                         "Ignoring option `assume_role`, instead using option `auth.assume_role`."
                     );
                 }
-                AwsCredentialsProvider::new(region, Some(assume_role.clone()))
+                let mut roles = role_chain.clone();
+                roles.push(AwsAssumeRole {
+                    role_arn: assume_role.clone(),
+                    external_id: external_id.clone(),
+                });
+                AwsCredentialsProvider::new_with_roles(region, roles, session_name.clone())
             }
             Self::Default {} => AwsCredentialsProvider::new(region, old_assume_role),
         }
@@ -32,14 +32,15 @@ use rusoto_core::{
 };
 use rusoto_credential::{
     AutoRefreshingProvider, AwsCredentials, ChainProvider, CredentialsError, ProvideAwsCredentials,
-    StaticProvider,
+    Secret, StaticProvider, Variable,
 };
 use rusoto_signature::{SignedRequest, SignedRequestPayload};
 use rusoto_sts::{StsAssumeRoleSessionCredentialsProvider, StsClient, WebIdentityProvider};
 use snafu::{ResultExt, Snafu};
 use tower::{Service, ServiceExt};
 
-pub use super::auth::AwsAuthentication;
+pub use super::auth::{AwsAssumeRole, AwsAuthentication};
+use super::credentials::{record_refresh, ExternalCredentialsSource};
 use crate::{config::ProxyConfig, http::HttpError, tls::MaybeTlsSettings};
 // use crate::http;
 
@@ -111,13 +112,57 @@ impl ProvideAwsCredentials for CustomChainProvider {
     }
 }
 
+/// Records the refreshes of the credentials of the provider, which is only asked for new
+/// credentials once the previous ones expired when wrapped in an [`AutoRefreshingProvider`].
+pub struct MeteredProvider<P> {
+    provider: P,
+    name: &'static str,
+}
+
+impl<P> MeteredProvider<P> {
+    pub const fn new(provider: P, name: &'static str) -> Self {
+        Self { provider, name }
+    }
+}
+
+#[async_trait]
+impl<P: ProvideAwsCredentials + Send + Sync> ProvideAwsCredentials for MeteredProvider<P> {
+    async fn credentials(&self) -> Result<AwsCredentials, CredentialsError> {
+        let result = self.provider.credentials().await;
+        record_refresh(self.name, &result);
+        result
+    }
+}
+
+/// Provides the credentials of the sources implemented by Vector.
+pub struct ExternalProvider(ExternalCredentialsSource);
+
+#[async_trait]
+impl ProvideAwsCredentials for ExternalProvider {
+    async fn credentials(&self) -> Result<AwsCredentials, CredentialsError> {
+        let credentials = self
+            .0
+            .fetch()
+            .await
+            .map_err(|error| CredentialsError::new(error.to_string()))?;
+        Ok(AwsCredentials::new(
+            credentials.access_key_id,
+            credentials.secret_access_key,
+            credentials.session_token,
+            credentials.expiration,
+        ))
+    }
+}
+
 // A place-holder for the types of AWS credentials we support
 #[allow(clippy::large_enum_variant)] // discovered during Rust upgrade to 1.57; just allowing for now since we did previously
 pub enum AwsCredentialsProvider {
-    Default(AutoRefreshingProvider<CustomChainProvider>),
-    Role(AutoRefreshingProvider<StsAssumeRoleSessionCredentialsProvider>),
+    Default(AutoRefreshingProvider<MeteredProvider<CustomChainProvider>>),
+    Role(AutoRefreshingProvider<MeteredProvider<StsAssumeRoleSessionCredentialsProvider>>),
     Static(StaticProvider),
-    File(AutoRefreshingProvider<ProfileProvider>),
+    File(AutoRefreshingProvider<MeteredProvider<ProfileProvider>>),
+    WebIdentity(AutoRefreshingProvider<MeteredProvider<WebIdentityProvider>>),
+    External(AutoRefreshingProvider<MeteredProvider<ExternalProvider>>),
 }
 
 impl fmt::Debug for AwsCredentialsProvider {
@@ -127,6 +172,8 @@ impl fmt::Debug for AwsCredentialsProvider {
             Self::Role(_) => "role",
             Self::Static(_) => "static",
             Self::File(_) => "file",
+            Self::WebIdentity(_) => "web_identity",
+            Self::External(_) => "external",
         };
 
         f.debug_tuple("AwsCredentialsProvider")
@@ -138,40 +185,87 @@ impl fmt::Debug for AwsCredentialsProvider {
 impl AwsCredentialsProvider {
     pub fn new(region: &Region, assume_role: Option<String>) -> crate::Result<Self> {
         if let Some(role) = assume_role {
-            debug!("Using STS assume role credentials for AWS.");
+            Self::new_with_roles(
+                region,
+                vec![AwsAssumeRole {
+                    role_arn: role,
+                    external_id: None,
+                }],
+                None,
+            )
+        } else {
+            debug!("Using default credentials provider for AWS.");
+            let creds = AutoRefreshingProvider::new(MeteredProvider::new(
+                default_chain_provider(),
+                "default",
+            ))
+            .context(InvalidAwsCredentialsSnafu)?;
+
+            Ok(Self::Default(creds))
+        }
+    }
 
+    /// Assumes the roles in order, each with the credentials of the previous one, starting from
+    /// the default credentials.
+    pub fn new_with_roles(
+        region: &Region,
+        roles: Vec<AwsAssumeRole>,
+        session_name: Option<String>,
+    ) -> crate::Result<Self> {
+        debug!("Using STS assume role credentials for AWS.");
+
+        let mut provider = None;
+        for role in roles {
             let dispatcher = rusoto_core::request::HttpClient::new()
                 .map_err(|_| AwsRusotoError::DispatcherError)?;
+            let sts = match provider.take() {
+                Some(previous) => StsClient::new_with(dispatcher, previous, region.clone()),
+                None => StsClient::new_with(dispatcher, default_chain_provider(), region.clone()),
+            };
 
-            let mut credentials = CustomChainProvider::new();
-            credentials.set_timeout(Duration::from_secs(8));
-
-            let sts = StsClient::new_with(dispatcher, credentials, region.clone());
-
-            let provider = StsAssumeRoleSessionCredentialsProvider::new(
+            let role = StsAssumeRoleSessionCredentialsProvider::new(
                 sts,
-                role,
-                "default".to_owned(),
-                None,
+                role.role_arn,
+                session_name.clone().unwrap_or_else(|| "default".to_owned()),
+                role.external_id,
                 None,
                 None,
                 None,
             );
 
-            let creds =
-                AutoRefreshingProvider::new(provider).context(InvalidAwsCredentialsSnafu)?;
-            Ok(Self::Role(creds))
-        } else {
-            debug!("Using default credentials provider for AWS.");
-            let mut chain = CustomChainProvider::new();
-            // 8 seconds because our default healthcheck timeout
-            // is 10 seconds.
-            chain.set_timeout(Duration::from_secs(8));
+            let creds = AutoRefreshingProvider::new(MeteredProvider::new(role, "role"))
+                .context(InvalidAwsCredentialsSnafu)?;
+            provider = Some(Self::Role(creds));
+        }
+        match provider {
+            Some(provider) => Ok(provider),
+            None => Self::new(region, None),
+        }
+    }
 
-            let creds = AutoRefreshingProvider::new(chain).context(InvalidAwsCredentialsSnafu)?;
+    /// Assumes the role with the web identity token of the file, which is read again on each
+    /// refresh as it is rotated.
+    pub fn new_with_web_identity(
+        token_file: &str,
+        role_arn: &str,
+        session_name: Option<String>,
+    ) -> crate::Result<Self> {
+        let provider = WebIdentityProvider::new(
+            Variable::<Secret, CredentialsError>::from_text_file(token_file),
+            role_arn.to_owned(),
+            session_name,
+        );
+        let creds = AutoRefreshingProvider::new(MeteredProvider::new(provider, "web_identity"))
+            .context(InvalidAwsCredentialsSnafu)?;
+        Ok(Self::WebIdentity(creds))
+    }
 
-            Ok(Self::Default(creds))
-        }
+    pub fn new_with_external_source(source: ExternalCredentialsSource) -> crate::Result<Self> {
+        let name = source.provider_name();
+        let creds =
+            AutoRefreshingProvider::new(MeteredProvider::new(ExternalProvider(source), name))
+                .context(InvalidAwsCredentialsSnafu)?;
+        Ok(Self::External(creds))
     }
 
     pub fn new_minimal<A: Into<String>, S: Into<String>>(access_key: A, secret_key: S) -> Self {
@@ -182,9 +276,9 @@ impl AwsCredentialsProvider {
     }
 
     pub fn new_with_credentials_file(credentials_file: &str, profile: &str) -> crate::Result<Self> {
-        let creds = AutoRefreshingProvider::new(ProfileProvider::with_configuration(
-            credentials_file,
-            profile,
+        let creds = AutoRefreshingProvider::new(MeteredProvider::new(
+            ProfileProvider::with_configuration(credentials_file, profile),
+            "file",
         ))
         .context(InvalidAwsCredentialsSnafu)?;
         Ok(Self::File(creds))
@@ -199,11 +293,21 @@ impl ProvideAwsCredentials for AwsCredentialsProvider {
             Self::Role(p) => p.credentials(),
             Self::Static(p) => p.credentials(),
             Self::File(p) => p.credentials(),
+            Self::WebIdentity(p) => p.credentials(),
+            Self::External(p) => p.credentials(),
         };
         fut.await
     }
 }
 
+fn default_chain_provider() -> CustomChainProvider {
+    let mut chain = CustomChainProvider::new();
+    // 8 seconds because our default healthcheck timeout
+    // is 10 seconds.
+    chain.set_timeout(Duration::from_secs(8));
+    chain
+}
+
 #[derive(Debug, Clone)]
 pub struct HttpClient<T> {
     client: T,
@@ -0,0 +1,252 @@
+use clap::Parser;
+use hyper::{body, Body, Method, Request, StatusCode};
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    cli::ApiClientOpts,
+    config,
+    config::ProxyConfig,
+    http::{Auth, HttpClient},
+    tls::{TlsOptions, TlsSettings},
+};
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Vector API server endpoint
+    #[clap(short, long)]
+    url: Option<Url>,
+
+    #[clap(flatten)]
+    api: ApiClientOpts,
+
+    #[clap(subcommand)]
+    sub_command: SubCommand,
+}
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+enum SubCommand {
+    /// Print the on-disk usage of the disk buffers of all running sinks, or of the given sinks
+    Inspect {
+        /// Sink IDs whose disk buffer to inspect
+        component_ids: Vec<String>,
+    },
+
+    /// Reclaim the space held by acknowledged records in the disk buffer of a running sink,
+    /// without restarting it
+    Compact {
+        /// Sink ID whose disk buffer to compact
+        component_id: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct BufferSummary {
+    component_id: String,
+    segments: u64,
+    disk_bytes: u64,
+    oldest_record_age_seconds: Option<f64>,
+}
+
+impl BufferSummary {
+    fn print(&self) {
+        let age = self
+            .oldest_record_age_seconds
+            .map_or_else(|| "-".to_string(), |age| format!("{:.1}s", age));
+        println!(
+            "{:<32} {:>8} {:>16} {:>12}",
+            self.component_id, self.segments, self.disk_bytes, age
+        );
+    }
+}
+
+/// CLI command func for inspecting and compacting the disk buffers of a local/remote Vector
+/// instance, through its API.
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    // As with `vector top` and `vector tap`, default to the local address of the API, whose
+    // config is available even if the `api` feature is disabled.
+    let base = opts.url.clone().unwrap_or_else(|| {
+        let addr = config::api::default_address().unwrap();
+        Url::parse(&*format!("http://{}", addr))
+            .expect("Couldn't parse default API URL. Please report this.")
+    });
+
+    let auth = match opts.api.auth() {
+        Ok(auth) => auth,
+        Err(error) => {
+            eprintln!("{}", error);
+            return exitcode::USAGE;
+        }
+    };
+    let tls = opts
+        .api
+        .api_ca_file
+        .as_ref()
+        .map(|ca_file| {
+            TlsSettings::from_options(&Some(TlsOptions {
+                ca_file: Some(ca_file.clone()),
+                ..TlsOptions::default()
+            }))
+        })
+        .transpose();
+    let tls = match tls {
+        Ok(tls) => tls,
+        Err(error) => {
+            eprintln!("Invalid CA file: {}", error);
+            return exitcode::CONFIG;
+        }
+    };
+
+    let client = match HttpClient::new(tls, &ProxyConfig::default()) {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("Couldn't create HTTP client: {}", error);
+            return exitcode::SOFTWARE;
+        }
+    };
+    let api = Api { client, auth, base };
+
+    match &opts.sub_command {
+        SubCommand::Inspect { component_ids } => inspect(&api, component_ids).await,
+        SubCommand::Compact { component_id } => compact(&api, component_id).await,
+    }
+}
+
+/// Connection to the API of a Vector instance.
+struct Api {
+    client: HttpClient,
+    auth: Option<Auth>,
+    base: Url,
+}
+
+async fn inspect(api: &Api, component_ids: &[String]) -> exitcode::ExitCode {
+    let mut summaries = Vec::new();
+    if component_ids.is_empty() {
+        match request(api, Method::GET, &endpoint(&api.base, &["buffers"])).await {
+            Ok(body) => match serde_json::from_slice::<Vec<BufferSummary>>(&body) {
+                Ok(all) => summaries.extend(all),
+                Err(error) => return invalid_response(&error),
+            },
+            Err(code) => return code,
+        }
+    } else {
+        for id in component_ids {
+            match request(api, Method::GET, &endpoint(&api.base, &["buffers", id])).await {
+                Ok(body) => match serde_json::from_slice::<BufferSummary>(&body) {
+                    Ok(summary) => summaries.push(summary),
+                    Err(error) => return invalid_response(&error),
+                },
+                Err(code) => return code,
+            }
+        }
+    }
+
+    println!(
+        "{:<32} {:>8} {:>16} {:>12}",
+        "COMPONENT", "SEGMENTS", "DISK BYTES", "OLDEST"
+    );
+    for summary in &summaries {
+        summary.print();
+    }
+
+    exitcode::OK
+}
+
+async fn compact(api: &Api, component_id: &str) -> exitcode::ExitCode {
+    let url = endpoint(&api.base, &["buffers", component_id, "compact"]);
+    match request(api, Method::POST, &url).await {
+        Ok(_) => {
+            println!(
+                "Compaction of the disk buffer of {:?} requested.",
+                component_id
+            );
+            exitcode::OK
+        }
+        Err(code) => code,
+    }
+}
+
+fn endpoint(base: &Url, segments: &[&str]) -> Url {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .expect("API URL can't be a base. Please report this.")
+        .pop_if_empty()
+        .extend(segments);
+    url
+}
+
+/// Sends a request to the API, returning the body of successful responses. Failures are reported
+/// to the user, and mapped to the exit code to return with.
+async fn request(api: &Api, method: Method, url: &Url) -> Result<body::Bytes, exitcode::ExitCode> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(url.as_str())
+        .body(Body::empty())
+        .expect("Couldn't build API request. Please report this.");
+    if let Some(auth) = &api.auth {
+        auth.apply(&mut request);
+    }
+
+    let response = api.client.send(request).await.map_err(|error| {
+        eprintln!(
+            "Vector API server isn't reachable at {} ({}). Have you enabled the API?",
+            url, error
+        );
+        exitcode::UNAVAILABLE
+    })?;
+
+    let status = response.status();
+    let body = body::to_bytes(response.into_body())
+        .await
+        .map_err(|error| {
+            eprintln!("Couldn't read API response: {}", error);
+            exitcode::UNAVAILABLE
+        })?;
+
+    match status {
+        status if status.is_success() => Ok(body),
+        StatusCode::UNAUTHORIZED => {
+            eprintln!("Vector API server requires credentials matching its `api.auth` settings.");
+            Err(exitcode::NOPERM)
+        }
+        StatusCode::NOT_FOUND => {
+            eprintln!("No running sink with a disk buffer matches {}.", url);
+            Err(exitcode::DATAERR)
+        }
+        status => {
+            eprintln!(
+                "Vector API server responded with {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            );
+            Err(exitcode::SOFTWARE)
+        }
+    }
+}
+
+fn invalid_response(error: &serde_json::Error) -> exitcode::ExitCode {
+    eprintln!("Couldn't parse API response: {}", error);
+    exitcode::SOFTWARE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_endpoints() {
+        let base = Url::parse("http://127.0.0.1:8686/").unwrap();
+        assert_eq!(
+            endpoint(&base, &["buffers", "out", "compact"]).as_str(),
+            "http://127.0.0.1:8686/buffers/out/compact"
+        );
+
+        let base = Url::parse("http://localhost/vector").unwrap();
+        assert_eq!(
+            endpoint(&base, &["buffers"]).as_str(),
+            "http://localhost/vector/buffers"
+        );
+    }
+}
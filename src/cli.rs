@@ -2,13 +2,17 @@ use std::path::PathBuf;
 
 use clap::{AppSettings, FromArgMatches, IntoApp, Parser};
 
+#[cfg(feature = "api-client")]
+use crate::buffer;
 #[cfg(windows)]
 use crate::service;
 #[cfg(feature = "api-client")]
 use crate::tap;
 #[cfg(feature = "api-client")]
 use crate::top;
-use crate::{config, generate, get_version, graph, list, unit_test, validate};
+use crate::{
+    config, convert_config, generate, get_version, graph, list, replay, unit_test, validate,
+};
 
 #[derive(Parser, Debug)]
 #[clap(rename_all = "kebab-case")]
@@ -35,6 +39,7 @@ impl Opts {
             | Some(SubCommand::Graph(_))
             | Some(SubCommand::Generate(_))
             | Some(SubCommand::List(_))
+            | Some(SubCommand::ConvertConfig(_))
             | Some(SubCommand::Test(_)) => {
                 if self.root.verbose == 0 {
                     (self.root.quiet + 1, self.root.verbose)
@@ -182,6 +187,9 @@ pub enum SubCommand {
     /// Output a provided Vector configuration file/dir as a single JSON object, useful for checking in to version control.
     Config(config::Opts),
 
+    /// Convert a Vector configuration file between the TOML, YAML and JSON formats, after validating it.
+    ConvertConfig(convert_config::Opts),
+
     /// List available components, then exit.
     List(list::Opts),
 
@@ -192,6 +200,9 @@ pub enum SubCommand {
     /// Output the topology as visual representation using the DOT language which can be rendered by GraphViz
     Graph(graph::Opts),
 
+    /// Feed archived events, from NDJSON files, S3 objects or disk buffers, through the pipeline of a config, then exit once they're processed.
+    Replay(replay::Opts),
+
     /// Display topology and metrics in the console, for a local or remote Vector instance
     #[cfg(feature = "api-client")]
     Top(top::Opts),
@@ -200,6 +211,10 @@ pub enum SubCommand {
     #[cfg(feature = "api-client")]
     Tap(tap::Opts),
 
+    /// Inspect the disk buffers of sinks, or reclaim the space held by acknowledged records, for a local or remote Vector instance
+    #[cfg(feature = "api-client")]
+    Buffer(buffer::Opts),
+
     /// Manage the vector service.
     #[cfg(windows)]
     Service(service::Opts),
@@ -253,6 +268,82 @@ impl std::str::FromStr for LogFormat {
     }
 }
 
+/// Options for connecting to a Vector API server which requires credentials, or which serves TLS
+/// with a certificate the system doesn't trust.
+#[cfg(feature = "api-client")]
+#[derive(Parser, Debug, Clone)]
+#[clap(rename_all = "kebab-case")]
+pub struct ApiClientOpts {
+    /// Bearer token matching the `api.auth` settings of the Vector API server
+    #[clap(long, env = "VECTOR_API_TOKEN")]
+    pub api_token: Option<String>,
+
+    /// User matching the basic `api.auth` settings of the Vector API server
+    #[clap(long, env = "VECTOR_API_USER")]
+    pub api_user: Option<String>,
+
+    /// Password matching the basic `api.auth` settings of the Vector API server
+    #[clap(long, env = "VECTOR_API_PASSWORD")]
+    pub api_password: Option<String>,
+
+    /// PEM file of a certificate authority to verify the TLS certificate of the Vector API server with, in addition to the system's ones
+    #[clap(long, env = "VECTOR_API_CA_FILE")]
+    pub api_ca_file: Option<PathBuf>,
+}
+
+#[cfg(feature = "api-client")]
+impl ApiClientOpts {
+    /// Returns the credentials to authenticate to the API with, if any.
+    pub fn auth(&self) -> Result<Option<crate::http::Auth>, String> {
+        use crate::http::Auth;
+
+        match (&self.api_token, &self.api_user, &self.api_password) {
+            (None, None, None) => Ok(None),
+            (Some(token), None, None) => Ok(Some(Auth::Bearer {
+                token: token.clone(),
+            })),
+            (None, Some(user), password) => Ok(Some(Auth::Basic {
+                user: user.clone(),
+                password: password.clone().unwrap_or_default(),
+            })),
+            (None, None, Some(_)) => Err("`--api-password` requires `--api-user`.".to_owned()),
+            (Some(_), _, _) => Err("`--api-token` can't be combined with `--api-user`.".to_owned()),
+        }
+    }
+
+    /// Returns the options to connect the API client with.
+    pub fn connect_options(&self) -> Result<vector_api_client::ConnectOptions, String> {
+        use http::{header::AUTHORIZATION, HeaderMap};
+
+        let authorization = self.auth()?.map(|auth| {
+            let mut headers = HeaderMap::new();
+            auth.apply_headers_map(&mut headers);
+            headers
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        });
+        let authorization = match authorization {
+            Some(None) => return Err("Invalid API credentials.".to_owned()),
+            authorization => authorization.flatten(),
+        };
+
+        let ca_certificate = self
+            .api_ca_file
+            .as_ref()
+            .map(|path| {
+                std::fs::read(path)
+                    .map_err(|error| format!("Couldn't read CA file {:?}: {}", path, error))
+            })
+            .transpose()?;
+
+        Ok(vector_api_client::ConnectOptions {
+            authorization,
+            ca_certificate,
+        })
+    }
+}
+
 pub fn handle_config_errors(errors: Vec<String>) -> exitcode::ExitCode {
     for error in errors {
         error!(message = "Configuration error.", %error);
@@ -0,0 +1,236 @@
+use std::io::{self, Read};
+
+use bytes::BytesMut;
+use serde::{Deserialize, Serialize};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const LZ4_MAGIC: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+
+/// The compression of the byte streams / byte messages, undone before framing.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Decompression {
+    /// The bytes aren't compressed.
+    None,
+    /// The compression is detected from the magic bytes of the payload, which is left as is if
+    /// it doesn't start with those of a supported format.
+    Auto,
+    /// The bytes are gzip members.
+    Gzip,
+    /// The bytes are zstd frames.
+    Zstd,
+    /// The bytes are lz4 frames.
+    Lz4,
+}
+
+impl Default for Decompression {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl Decompression {
+    /// Build the `Decompressor` for this method, if the bytes are compressed.
+    pub fn build(self) -> Option<Decompressor> {
+        match self {
+            Decompression::None => None,
+            method => Some(Decompressor::new(method)),
+        }
+    }
+
+    fn detect(payload: &[u8]) -> Self {
+        if payload.starts_with(GZIP_MAGIC) {
+            Decompression::Gzip
+        } else if payload.starts_with(ZSTD_MAGIC) {
+            Decompression::Zstd
+        } else if payload.starts_with(LZ4_MAGIC) {
+            Decompression::Lz4
+        } else {
+            Decompression::None
+        }
+    }
+}
+
+/// Decompresses the byte streams / byte messages before they are framed.
+///
+/// The compressed bytes are buffered until the end of the input, at which point they are
+/// decompressed at once. This suits the message-based sources, such as `kafka` or `http`, where
+/// every message ends its input.
+#[derive(Debug)]
+pub struct Decompressor {
+    method: Decompression,
+    compressed: BytesMut,
+    decompressed: BytesMut,
+}
+
+impl Clone for Decompressor {
+    /// The clones start with empty buffers.
+    fn clone(&self) -> Self {
+        Self::new(self.method)
+    }
+}
+
+impl Decompressor {
+    /// Creates a new `Decompressor` for the given method.
+    pub fn new(method: Decompression) -> Self {
+        Self {
+            method,
+            compressed: BytesMut::new(),
+            decompressed: BytesMut::new(),
+        }
+    }
+
+    /// Moves the compressed bytes read so far out of the input buffer.
+    pub fn buffer(&mut self, buf: &mut BytesMut) {
+        self.compressed.unsplit(buf.split());
+    }
+
+    /// Decompresses the buffered bytes at the end of the input, returning the buffer of the
+    /// decompressed bytes left to be framed.
+    pub fn finish(&mut self) -> io::Result<&mut BytesMut> {
+        if !self.compressed.is_empty() {
+            // The buffer is emptied even on errors, so that the same payload doesn't fail again.
+            let compressed = self.compressed.split().freeze();
+            let method = match self.method {
+                Decompression::Auto => Decompression::detect(&compressed),
+                method => method,
+            };
+
+            let mut decompressed = Vec::new();
+            match method {
+                Decompression::None | Decompression::Auto => {
+                    decompressed.extend_from_slice(&compressed);
+                }
+                Decompression::Gzip => {
+                    flate2::read::MultiGzDecoder::new(&compressed[..])
+                        .read_to_end(&mut decompressed)?;
+                }
+                Decompression::Zstd => {
+                    zstd::stream::read::Decoder::new(&compressed[..])?
+                        .read_to_end(&mut decompressed)?;
+                }
+                Decompression::Lz4 => {
+                    lz4_flex::frame::FrameDecoder::new(&compressed[..])
+                        .read_to_end(&mut decompressed)?;
+                }
+            }
+            self.decompressed.extend_from_slice(&decompressed);
+        }
+
+        Ok(&mut self.decompressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn decompress(method: Decompression, payload: &[u8]) -> io::Result<Bytes> {
+        let mut decompressor = method.build().unwrap();
+        decompressor.buffer(&mut BytesMut::from(payload));
+        Ok(decompressor.finish()?.split().freeze())
+    }
+
+    fn gzip(payload: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(payload).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zstd(payload: &[u8]) -> Vec<u8> {
+        zstd::stream::encode_all(payload, 0).unwrap()
+    }
+
+    fn lz4(payload: &[u8]) -> Vec<u8> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(payload).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decompress_formats() {
+        let payload = b"foo\nbar\n";
+
+        assert_eq!(
+            decompress(Decompression::Gzip, &gzip(payload)).unwrap(),
+            &payload[..]
+        );
+        assert_eq!(
+            decompress(Decompression::Zstd, &zstd(payload)).unwrap(),
+            &payload[..]
+        );
+        assert_eq!(
+            decompress(Decompression::Lz4, &lz4(payload)).unwrap(),
+            &payload[..]
+        );
+    }
+
+    #[test]
+    fn decompress_auto() {
+        let payload = b"foo\nbar\n";
+
+        for compressed in [gzip(payload), zstd(payload), lz4(payload), payload.to_vec()] {
+            assert_eq!(
+                decompress(Decompression::Auto, &compressed).unwrap(),
+                &payload[..]
+            );
+        }
+    }
+
+    #[test]
+    fn decompress_concatenated_gzip_members() {
+        let mut compressed = gzip(b"foo\n");
+        compressed.extend(gzip(b"bar\n"));
+
+        assert_eq!(
+            decompress(Decompression::Gzip, &compressed).unwrap(),
+            &b"foo\nbar\n"[..]
+        );
+    }
+
+    #[test]
+    fn decode_compressed_message() {
+        use tokio_util::codec::Decoder as _;
+
+        use crate::codecs::decoding::{
+            BytesDeserializerConfig, DecodingConfig, NewlineDelimitedDecoderConfig,
+        };
+
+        let mut decoder = DecodingConfig::new(
+            NewlineDelimitedDecoderConfig::new().into(),
+            BytesDeserializerConfig::new().into(),
+        )
+        .with_decompression(Decompression::Auto)
+        .build()
+        .unwrap();
+
+        let compressed = zstd(b"foo\nbar");
+        let (head, tail) = compressed.split_at(4);
+        let mut buf = BytesMut::from(head);
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(tail);
+
+        let mut messages = Vec::new();
+        while let Some((events, _)) = decoder.decode_eof(&mut buf).unwrap() {
+            messages.extend(
+                events
+                    .into_iter()
+                    .map(|event| event.as_log()["message"].clone()),
+            );
+        }
+        assert_eq!(messages, vec!["foo".into(), "bar".into()]);
+    }
+
+    #[test]
+    fn decompress_invalid() {
+        let mut decompressor = Decompression::Gzip.build().unwrap();
+        decompressor.buffer(&mut BytesMut::from(&b"not gzip"[..]));
+        assert!(decompressor.finish().is_err());
+        assert!(decompressor.finish().unwrap().is_empty());
+    }
+}
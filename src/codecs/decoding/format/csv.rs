@@ -0,0 +1,339 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use value::Kind;
+use vector_common::TimeZone;
+
+use super::Deserializer;
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    schema,
+    types::{parse_conversion_map, Conversion},
+};
+
+/// Config used to build a `CsvDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CsvDeserializerConfig {
+    /// Options for the CSV deserializer.
+    #[serde(default)]
+    pub csv: CsvDeserializerOptions,
+}
+
+impl CsvDeserializerConfig {
+    /// Build the `CsvDeserializer` from this configuration.
+    pub fn build(&self) -> crate::Result<CsvDeserializer> {
+        let options = &self.csv;
+        let byte = |name, character: char| {
+            if character.is_ascii() {
+                Ok(character as u8)
+            } else {
+                Err(format!("`{}` must be an ASCII character.", name))
+            }
+        };
+
+        Ok(CsvDeserializer {
+            delimiter: byte("delimiter", options.delimiter)?,
+            quote: byte("quote", options.quote)?,
+            has_headers: options.has_headers,
+            headers: options.headers.clone(),
+            types: parse_conversion_map(&options.types, TimeZone::default())?,
+            infer_types: options.infer_types,
+            malformed: options.malformed,
+            inferred_headers: Mutex::new(None),
+        })
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        schema::Definition::empty()
+            .required_field(
+                log_schema().timestamp_key(),
+                // The timestamp is only inserted if there is no column of the same name.
+                Kind::any(),
+                Some("timestamp"),
+            )
+            .unknown_fields(Kind::any())
+    }
+}
+
+/// Options for building a `CsvDeserializer`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CsvDeserializerOptions {
+    /// The character separating the columns, such as `\t` for TSV.
+    pub delimiter: char,
+    /// The character quoting the columns holding delimiters or line breaks.
+    pub quote: char,
+    /// Whether the first row read is the header row naming the columns.
+    pub has_headers: bool,
+    /// The names of the columns, overriding those of the header row.
+    pub headers: Vec<String>,
+    /// The types to convert the columns to, by column name.
+    pub types: HashMap<String, String>,
+    /// Whether the columns without a type are converted to integers, floats or booleans when
+    /// they look like ones.
+    pub infer_types: bool,
+    /// What becomes of the rows that don't have as many columns as there are headers.
+    pub malformed: MalformedRowPolicy,
+}
+
+impl Default for CsvDeserializerOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            has_headers: true,
+            headers: Vec::new(),
+            types: HashMap::new(),
+            infer_types: false,
+            malformed: MalformedRowPolicy::Error,
+        }
+    }
+}
+
+/// What becomes of the rows that don't have as many columns as there are headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MalformedRowPolicy {
+    /// The whole frame fails to be decoded.
+    Error,
+    /// The row is dropped.
+    Skip,
+    /// The row is decoded as far as there are headers, the extra columns being ignored.
+    Partial,
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing CSV rows.
+///
+/// With `has_headers`, the first row read by the deserializer names the columns of all the
+/// following ones, even when they come in separate frames, as with newline delimited framing.
+#[derive(Debug)]
+pub struct CsvDeserializer {
+    delimiter: u8,
+    quote: u8,
+    has_headers: bool,
+    headers: Vec<String>,
+    types: HashMap<String, Conversion>,
+    infer_types: bool,
+    malformed: MalformedRowPolicy,
+    inferred_headers: Mutex<Option<Vec<String>>>,
+}
+
+impl Clone for CsvDeserializer {
+    /// The clones read their own header row.
+    fn clone(&self) -> Self {
+        Self {
+            delimiter: self.delimiter,
+            quote: self.quote,
+            has_headers: self.has_headers,
+            headers: self.headers.clone(),
+            types: self.types.clone(),
+            infer_types: self.infer_types,
+            malformed: self.malformed,
+            inferred_headers: Mutex::new(None),
+        }
+    }
+}
+
+impl CsvDeserializer {
+    fn convert(&self, header: &str, column: &str) -> crate::Result<Value> {
+        match self.types.get(header) {
+            Some(conversion) => Ok(conversion
+                .convert(Bytes::copy_from_slice(column.as_bytes()))
+                .map_err(|error| format!("Invalid column {:?}: {}", header, error))?),
+            None if self.infer_types => Ok(infer(column)),
+            None => Ok(Value::from(column)),
+        }
+    }
+}
+
+/// Converts the column to an integer, a float or a boolean if it looks like one.
+fn infer(column: &str) -> Value {
+    if let Ok(integer) = column.parse::<i64>() {
+        Value::from(integer)
+    } else if let Some(float) = column.parse::<f64>().ok().filter(|float| float.is_finite()) {
+        Value::from(float)
+    } else if let Ok(boolean) = column.parse::<bool>() {
+        Value::from(boolean)
+    } else {
+        Value::from(column)
+    }
+}
+
+impl Deserializer for CsvDeserializer {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(bytes.as_ref());
+
+        let mut inferred_headers = self
+            .inferred_headers
+            .lock()
+            .expect("CSV headers lock poisoned");
+        let timestamp = Utc::now();
+        let mut events = SmallVec::new();
+
+        for record in reader.records() {
+            let record = record.map_err(|error| format!("Error parsing CSV: {}", error))?;
+            if self.has_headers && inferred_headers.is_none() {
+                *inferred_headers = Some(record.iter().map(ToOwned::to_owned).collect());
+                continue;
+            }
+
+            let headers = if !self.headers.is_empty() {
+                Some(&self.headers)
+            } else {
+                inferred_headers.as_ref()
+            };
+            if let Some(headers) = headers {
+                if headers.len() != record.len() {
+                    match self.malformed {
+                        MalformedRowPolicy::Error => {
+                            return Err(format!(
+                                "Error parsing CSV: expected {} columns, got {}.",
+                                headers.len(),
+                                record.len()
+                            )
+                            .into())
+                        }
+                        MalformedRowPolicy::Skip => continue,
+                        MalformedRowPolicy::Partial => (),
+                    }
+                }
+            }
+
+            let mut log = LogEvent::default();
+            for (index, column) in record.iter().enumerate() {
+                let header = match headers {
+                    Some(headers) => match headers.get(index) {
+                        Some(header) => header.clone(),
+                        None => break,
+                    },
+                    // Without headers, the columns are named after their position.
+                    None => format!("column_{}", index + 1),
+                };
+                let value = self.convert(&header, column)?;
+                log.insert_flat(header, value);
+            }
+
+            let timestamp_key = log_schema().timestamp_key();
+            if !log.contains(timestamp_key) {
+                log.insert(timestamp_key, timestamp);
+            }
+            events.push(Event::Log(log));
+        }
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(config: &str) -> CsvDeserializer {
+        toml::from_str::<CsvDeserializerConfig>(config)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn deserialize_csv_with_headers() {
+        let deserializer = build(
+            r#"
+            [csv]
+            types.status = "int"
+            "#,
+        );
+
+        let events = deserializer
+            .parse(Bytes::from("path,status\n\"/a,b\",200\n"))
+            .unwrap();
+        assert!(events.is_empty());
+        let events = deserializer.parse(Bytes::from("/c,404\n")).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log["path"], "/c".into());
+        assert_eq!(log["status"], 404.into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_tsv_without_headers() {
+        let deserializer = build(
+            r#"
+            [csv]
+            delimiter = "\t"
+            has_headers = false
+            infer_types = true
+            "#,
+        );
+
+        let events = deserializer
+            .parse(Bytes::from("a\t1\t1.5\ttrue\nb\t2\t2.5\tfalse"))
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        let log = events[0].as_log();
+        assert_eq!(log["column_1"], "a".into());
+        assert_eq!(log["column_2"], 1.into());
+        assert_eq!(log["column_3"], 1.5.into());
+        assert_eq!(log["column_4"], true.into());
+    }
+
+    #[test]
+    fn malformed_rows() {
+        let config = r#"
+            [csv]
+            headers = ["a", "b"]
+            has_headers = false
+            malformed = "%s"
+            "#;
+        let input = || Bytes::from("1,2\n3\n4,5,6\n");
+
+        let error = build(&config.replace("%s", "error"));
+        assert!(error.parse(input()).is_err());
+
+        let skip = build(&config.replace("%s", "skip"));
+        assert_eq!(skip.parse(input()).unwrap().len(), 1);
+
+        let partial = build(&config.replace("%s", "partial"));
+        let events = partial.parse(input()).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1].as_log()["a"], "3".into());
+        assert!(events[1].as_log().get("b").is_none());
+        assert_eq!(events[2].as_log()["b"], "5".into());
+    }
+
+    #[test]
+    fn clones_read_their_own_headers() {
+        let deserializer = build("[csv]");
+        deserializer.parse(Bytes::from("a,b")).unwrap();
+
+        let clone = deserializer.clone();
+        clone.parse(Bytes::from("c,d")).unwrap();
+        let events = clone.parse(Bytes::from("1,2")).unwrap();
+        assert_eq!(events[0].as_log()["c"], "1".into());
+    }
+
+    #[test]
+    fn invalid_delimiter() {
+        let config = toml::from_str::<CsvDeserializerConfig>(
+            r#"
+            [csv]
+            delimiter = "é"
+            "#,
+        )
+        .unwrap();
+        assert!(config.build().is_err());
+    }
+}
@@ -4,14 +4,25 @@
 #![deny(missing_docs)]
 
 mod bytes;
+mod csv;
 mod json;
+mod msgpack;
+mod protobuf;
 #[cfg(feature = "sources-syslog")]
 mod syslog;
 
 pub use self::bytes::{BytesDeserializer, BytesDeserializerConfig};
+pub use self::csv::{
+    CsvDeserializer, CsvDeserializerConfig, CsvDeserializerOptions, MalformedRowPolicy,
+};
+pub use self::protobuf::{
+    BytesHandling, EnumHandling, ProtobufDeserializer, ProtobufDeserializerConfig,
+    ProtobufDeserializerOptions,
+};
 #[cfg(feature = "sources-syslog")]
 pub use self::syslog::{SyslogDeserializer, SyslogDeserializerConfig};
 pub use json::{JsonDeserializer, JsonDeserializerConfig};
+pub use msgpack::{MsgpackDeserializer, MsgpackDeserializerConfig};
 
 use crate::event::Event;
 use ::bytes::Bytes;
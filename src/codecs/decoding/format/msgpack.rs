@@ -0,0 +1,264 @@
+use std::convert::TryInto;
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use value::Kind;
+
+use super::Deserializer;
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+/// The MessagePack extension type of timestamps.
+const TIMESTAMP_EXT: i8 = -1;
+
+/// Config used to build a `MsgpackDeserializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MsgpackDeserializerConfig;
+
+impl MsgpackDeserializerConfig {
+    /// Creates a new `MsgpackDeserializerConfig`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Build the `MsgpackDeserializer` from this configuration.
+    pub fn build(&self) -> MsgpackDeserializer {
+        MsgpackDeserializer
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        schema::Definition::empty()
+            .required_field(
+                log_schema().timestamp_key(),
+                // The timestamp is only inserted if the map doesn't hold one already.
+                Kind::any(),
+                Some("timestamp"),
+            )
+            .unknown_fields(Kind::any())
+    }
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing MessagePack maps.
+///
+/// The frame can hold several concatenated maps, or arrays of maps, each map becoming an event.
+#[derive(Debug, Clone, Default)]
+pub struct MsgpackDeserializer;
+
+impl MsgpackDeserializer {
+    /// Creates a new `MsgpackDeserializer`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Deserializer for MsgpackDeserializer {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let mut input = bytes.as_ref();
+        let mut events = SmallVec::new();
+        let timestamp = Utc::now();
+
+        while !input.is_empty() {
+            let value = rmpv::decode::read_value(&mut input)
+                .map_err(|error| format!("Error parsing MessagePack: {}", error))?;
+
+            let values = match value {
+                rmpv::Value::Array(values) => values,
+                value => vec![value],
+            };
+            for value in values {
+                let mut log = match to_value(value) {
+                    Value::Object(fields) => LogEvent::from(fields),
+                    _ => {
+                        return Err(
+                            "Attempted to convert non-map MessagePack value into an Event.".into(),
+                        )
+                    }
+                };
+
+                let timestamp_key = log_schema().timestamp_key();
+                if !log.contains(timestamp_key) {
+                    log.insert(timestamp_key, timestamp);
+                }
+                events.push(Event::Log(log));
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+/// Converts a MessagePack value to a `Value`.
+///
+/// The strings and binaries become bytes, the timestamp extensions become timestamps and the other
+/// extensions become maps of their type and data.
+fn to_value(value: rmpv::Value) -> Value {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(boolean) => Value::Boolean(boolean),
+        rmpv::Value::Integer(integer) => integer
+            .as_i64()
+            .map(Value::Integer)
+            // Large unsigned integers are kept as strings, as with JSON.
+            .unwrap_or_else(|| Value::Bytes(integer.to_string().into())),
+        rmpv::Value::F32(float) => NotNan::new(f64::from(float))
+            .map(Value::Float)
+            .unwrap_or(Value::Null),
+        rmpv::Value::F64(float) => NotNan::new(float).map(Value::Float).unwrap_or(Value::Null),
+        rmpv::Value::String(string) => Value::Bytes(string.into_bytes().into()),
+        rmpv::Value::Binary(bytes) => Value::Bytes(bytes.into()),
+        rmpv::Value::Array(values) => Value::Array(values.into_iter().map(to_value).collect()),
+        rmpv::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        rmpv::Value::String(key) => {
+                            String::from_utf8_lossy(key.as_bytes()).into_owned()
+                        }
+                        key => key.to_string(),
+                    };
+                    (key, to_value(value))
+                })
+                .collect(),
+        ),
+        rmpv::Value::Ext(TIMESTAMP_EXT, data) => match timestamp(&data) {
+            Some(timestamp) => Value::Timestamp(timestamp),
+            None => extension(TIMESTAMP_EXT, data),
+        },
+        rmpv::Value::Ext(code, data) => extension(code, data),
+    }
+}
+
+fn extension(code: i8, data: Vec<u8>) -> Value {
+    let mut fields = std::collections::BTreeMap::new();
+    fields.insert(
+        String::from("msgpack_extension_code"),
+        Value::Integer(code.into()),
+    );
+    fields.insert(String::from("bytes"), Value::Bytes(data.into()));
+    Value::Object(fields)
+}
+
+/// Decodes the 32, 64 or 96 bits timestamp extension.
+fn timestamp(data: &[u8]) -> Option<DateTime<Utc>> {
+    let (seconds, nanoseconds) = match data.len() {
+        4 => (i64::from(u32::from_be_bytes(data.try_into().ok()?)), 0),
+        8 => {
+            let data = u64::from_be_bytes(data.try_into().ok()?);
+            ((data & 0x3_ffff_ffff) as i64, (data >> 34) as u32)
+        }
+        12 => (
+            i64::from_be_bytes(data[4..].try_into().ok()?),
+            u32::from_be_bytes(data[..4].try_into().ok()?),
+        ),
+        _ => return None,
+    };
+    Utc.timestamp_opt(seconds, nanoseconds).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(value: &rmpv::Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, value).unwrap();
+        bytes
+    }
+
+    fn map(entries: Vec<(&str, rmpv::Value)>) -> rmpv::Value {
+        rmpv::Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn deserialize_msgpack() {
+        let value = map(vec![
+            ("message", "foo".into()),
+            ("count", 42.into()),
+            ("ratio", 0.5.into()),
+            ("data", rmpv::Value::Binary(vec![0, 1])),
+            ("tags", rmpv::Value::Array(vec!["a".into(), "b".into()])),
+            ("missing", rmpv::Value::Nil),
+        ]);
+        let events = MsgpackDeserializer::new()
+            .parse(Bytes::from(encode(&value)))
+            .unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log["message"], "foo".into());
+        assert_eq!(log["count"], 42.into());
+        assert_eq!(log["ratio"], 0.5.into());
+        assert_eq!(log["data"], Value::Bytes(Bytes::from_static(&[0, 1])));
+        assert_eq!(log["tags"], Value::Array(vec!["a".into(), "b".into()]));
+        assert_eq!(log["missing"], Value::Null);
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_concatenated_maps_and_arrays() {
+        let mut bytes = encode(&map(vec![("a", 1.into())]));
+        bytes.extend(encode(&rmpv::Value::Array(vec![
+            map(vec![("b", 2.into())]),
+            map(vec![("c", 3.into())]),
+        ])));
+
+        let events = MsgpackDeserializer::new()
+            .parse(Bytes::from(bytes))
+            .unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[2].as_log()["c"], 3.into());
+    }
+
+    #[test]
+    fn deserialize_timestamp_extension() {
+        for (data, nanoseconds) in [
+            (1_650_000_000_u32.to_be_bytes().to_vec(), 0),
+            (
+                ((123_456_789_u64 << 34) | 1_650_000_000)
+                    .to_be_bytes()
+                    .to_vec(),
+                123_456_789,
+            ),
+            (
+                [
+                    &123_456_789_u32.to_be_bytes()[..],
+                    &1_650_000_000_i64.to_be_bytes()[..],
+                ]
+                .concat(),
+                123_456_789,
+            ),
+        ] {
+            let value = map(vec![("timestamp", rmpv::Value::Ext(TIMESTAMP_EXT, data))]);
+            let events = MsgpackDeserializer::new()
+                .parse(Bytes::from(encode(&value)))
+                .unwrap();
+            assert_eq!(
+                events[0].as_log()["timestamp"],
+                Value::Timestamp(Utc.timestamp(1_650_000_000, nanoseconds))
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_non_map() {
+        assert!(MsgpackDeserializer::new()
+            .parse(Bytes::from(encode(&"foo".into())))
+            .is_err());
+        assert!(MsgpackDeserializer::new()
+            .parse(Bytes::from_static(&[0xc1]))
+            .is_err());
+    }
+}
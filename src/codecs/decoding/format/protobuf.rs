@@ -0,0 +1,588 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use bytes::{Buf, Bytes};
+use chrono::Utc;
+use prost::{
+    encoding::{decode_key, decode_varint, WireType},
+    Message,
+};
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, FileDescriptorSet,
+};
+use serde::{Deserialize, Serialize};
+use smallvec::{smallvec, SmallVec};
+use value::Kind;
+
+use super::Deserializer;
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    schema,
+};
+
+/// Config used to build a `ProtobufDeserializer`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProtobufDeserializerConfig {
+    /// Options for the protobuf deserializer.
+    pub protobuf: ProtobufDeserializerOptions,
+}
+
+impl ProtobufDeserializerConfig {
+    /// Build the `ProtobufDeserializer` from this configuration.
+    pub fn build(&self) -> crate::Result<ProtobufDeserializer> {
+        let descriptor_set = std::fs::read(&self.protobuf.desc_file).map_err(|error| {
+            format!(
+                "Failed to read descriptor set file {:?}: {}",
+                self.protobuf.desc_file, error
+            )
+        })?;
+        let descriptor_set = FileDescriptorSet::decode(descriptor_set.as_slice())
+            .map_err(|error| format!("Invalid descriptor set: {}", error))?;
+        let descriptors = Descriptors::new(&descriptor_set);
+
+        let message_type = format!(".{}", self.protobuf.message_type.trim_start_matches('.'));
+        if !descriptors.messages.contains_key(&message_type) {
+            return Err(format!(
+                "Message type {:?} not found in the descriptor set.",
+                self.protobuf.message_type
+            )
+            .into());
+        }
+
+        Ok(ProtobufDeserializer {
+            descriptors: Arc::new(descriptors),
+            message_type,
+            bytes: self.protobuf.bytes,
+            enums: self.protobuf.enums,
+        })
+    }
+
+    /// The schema produced by the deserializer.
+    pub fn schema_definition(&self) -> schema::Definition {
+        schema::Definition::empty()
+            .required_field(
+                log_schema().timestamp_key(),
+                // The timestamp is only inserted if the message doesn't have a field of the
+                // same name.
+                Kind::any(),
+                Some("timestamp"),
+            )
+            .unknown_fields(Kind::any())
+    }
+}
+
+/// Options for building a `ProtobufDeserializer`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ProtobufDeserializerOptions {
+    /// The path to the descriptor set of the message type, as compiled by
+    /// `protoc --include_imports --descriptor_set_out`.
+    pub desc_file: PathBuf,
+    /// The fully qualified name of the message type, such as `package.Message`.
+    pub message_type: String,
+    /// How the `bytes` fields are represented.
+    #[serde(default)]
+    pub bytes: BytesHandling,
+    /// How the enum fields are represented.
+    #[serde(default)]
+    pub enums: EnumHandling,
+}
+
+/// How the `bytes` fields of messages are represented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BytesHandling {
+    /// The bytes are kept as they are.
+    Raw,
+    /// The bytes are encoded as a base64 string.
+    Base64,
+}
+
+impl Default for BytesHandling {
+    fn default() -> Self {
+        Self::Raw
+    }
+}
+
+/// How the enum fields of messages are represented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnumHandling {
+    /// The name of the value, or its number if it isn't known.
+    Name,
+    /// The number of the value.
+    Number,
+}
+
+impl Default for EnumHandling {
+    fn default() -> Self {
+        Self::Name
+    }
+}
+
+/// The message and enum types of a descriptor set, by fully qualified name.
+#[derive(Debug, Default)]
+struct Descriptors {
+    messages: HashMap<String, MessageDescriptor>,
+    enums: HashMap<String, HashMap<i32, String>>,
+}
+
+#[derive(Debug, Default)]
+struct MessageDescriptor {
+    fields: HashMap<u32, FieldDescriptor>,
+    map_entry: bool,
+}
+
+#[derive(Debug)]
+struct FieldDescriptor {
+    name: String,
+    kind: Type,
+    type_name: String,
+    repeated: bool,
+}
+
+impl Descriptors {
+    fn new(descriptor_set: &FileDescriptorSet) -> Self {
+        let mut descriptors = Self::default();
+        for file in &descriptor_set.file {
+            let scope = match file.package() {
+                "" => String::new(),
+                package => format!(".{}", package),
+            };
+            for message in &file.message_type {
+                descriptors.add_message(&scope, message);
+            }
+            for enumeration in &file.enum_type {
+                descriptors.add_enum(&scope, enumeration);
+            }
+        }
+        descriptors
+    }
+
+    fn add_message(&mut self, scope: &str, message: &DescriptorProto) {
+        let name = format!("{}.{}", scope, message.name());
+        for nested in &message.nested_type {
+            self.add_message(&name, nested);
+        }
+        for enumeration in &message.enum_type {
+            self.add_enum(&name, enumeration);
+        }
+
+        let fields = message
+            .field
+            .iter()
+            .map(|field| {
+                let descriptor = FieldDescriptor {
+                    name: field.name().to_owned(),
+                    kind: field.r#type(),
+                    type_name: field.type_name().to_owned(),
+                    repeated: field.label() == Label::Repeated,
+                };
+                (field.number() as u32, descriptor)
+            })
+            .collect();
+        let map_entry = message
+            .options
+            .as_ref()
+            .map_or(false, |options| options.map_entry());
+        self.messages
+            .insert(name, MessageDescriptor { fields, map_entry });
+    }
+
+    fn add_enum(&mut self, scope: &str, enumeration: &EnumDescriptorProto) {
+        let values = enumeration
+            .value
+            .iter()
+            .map(|value| (value.number(), value.name().to_owned()))
+            .collect();
+        self.enums
+            .insert(format!("{}.{}", scope, enumeration.name()), values);
+    }
+}
+
+/// A field as read from the wire, before it is interpreted with its type.
+enum RawField<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Fixed32(u32),
+    LengthDelimited(&'a [u8]),
+}
+
+fn read_field<'a>(wire_type: WireType, buf: &mut &'a [u8]) -> Result<RawField<'a>, String> {
+    let truncated = || "Truncated message.".to_owned();
+    match wire_type {
+        WireType::Varint => decode_varint(buf)
+            .map(RawField::Varint)
+            .map_err(|error| error.to_string()),
+        WireType::SixtyFourBit if buf.remaining() >= 8 => Ok(RawField::Fixed64(buf.get_u64_le())),
+        WireType::ThirtyTwoBit if buf.remaining() >= 4 => Ok(RawField::Fixed32(buf.get_u32_le())),
+        WireType::LengthDelimited => {
+            let len = decode_varint(buf).map_err(|error| error.to_string())? as usize;
+            let slice: &'a [u8] = *buf;
+            if slice.len() < len {
+                return Err(truncated());
+            }
+            let (field, rest) = slice.split_at(len);
+            *buf = rest;
+            Ok(RawField::LengthDelimited(field))
+        }
+        WireType::StartGroup | WireType::EndGroup => Err("Groups are not supported.".to_owned()),
+        _ => Err(truncated()),
+    }
+}
+
+/// The wire type of the elements of packed repeated fields of the type.
+const fn packed_wire_type(kind: Type) -> Option<WireType> {
+    match kind {
+        Type::Double | Type::Fixed64 | Type::Sfixed64 => Some(WireType::SixtyFourBit),
+        Type::Float | Type::Fixed32 | Type::Sfixed32 => Some(WireType::ThirtyTwoBit),
+        Type::Int64
+        | Type::Uint64
+        | Type::Int32
+        | Type::Uint32
+        | Type::Bool
+        | Type::Enum
+        | Type::Sint32
+        | Type::Sint64 => Some(WireType::Varint),
+        Type::String | Type::Group | Type::Message | Type::Bytes => None,
+    }
+}
+
+/// Deserializer that builds `Event`s from a byte frame containing a protobuf message.
+#[derive(Debug, Clone)]
+pub struct ProtobufDeserializer {
+    descriptors: Arc<Descriptors>,
+    message_type: String,
+    bytes: BytesHandling,
+    enums: EnumHandling,
+}
+
+impl ProtobufDeserializer {
+    /// Decodes a message into an object of its fields. The fields missing from the message
+    /// are left out, rather than set to their default value.
+    fn decode_message(
+        &self,
+        message_type: &str,
+        mut buf: &[u8],
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let message = self
+            .descriptors
+            .messages
+            .get(message_type)
+            .ok_or_else(|| format!("Unknown message type {:?}.", message_type))?;
+
+        let mut fields = BTreeMap::new();
+        while buf.has_remaining() {
+            let (number, wire_type) = decode_key(&mut buf).map_err(|error| error.to_string())?;
+            let raw = read_field(wire_type, &mut buf)?;
+            let field = match message.fields.get(&number) {
+                Some(field) => field,
+                // Unknown fields are skipped.
+                None => continue,
+            };
+
+            let mut values = Vec::new();
+            match (raw, packed_wire_type(field.kind)) {
+                (RawField::LengthDelimited(mut packed), Some(wire_type)) if field.repeated => {
+                    while packed.has_remaining() {
+                        let raw = read_field(wire_type, &mut packed)?;
+                        values.push(self.convert(field, raw)?);
+                    }
+                }
+                (raw, _) => values.push(self.convert(field, raw)?),
+            }
+
+            let entries = self
+                .descriptors
+                .messages
+                .get(&field.type_name)
+                .map_or(false, |message| message.map_entry);
+            if field.repeated && entries {
+                let map = fields
+                    .entry(field.name.clone())
+                    .or_insert_with(|| Value::Object(BTreeMap::new()));
+                for value in values {
+                    if let (Value::Object(map), Value::Object(mut entry)) = (&mut *map, value) {
+                        let key = entry
+                            .remove("key")
+                            .map(|key| key.to_string_lossy())
+                            .unwrap_or_default();
+                        map.insert(key, entry.remove("value").unwrap_or(Value::Null));
+                    }
+                }
+            } else if field.repeated {
+                let array = fields
+                    .entry(field.name.clone())
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(array) = array {
+                    array.extend(values);
+                }
+            } else if let Some(value) = values.pop() {
+                // The last value of a singular field wins.
+                fields.insert(field.name.clone(), value);
+            }
+        }
+        Ok(fields)
+    }
+
+    fn convert(&self, field: &FieldDescriptor, raw: RawField<'_>) -> Result<Value, String> {
+        let value = match (field.kind, raw) {
+            (Type::Double, RawField::Fixed64(value)) => float(f64::from_bits(value)),
+            (Type::Float, RawField::Fixed32(value)) => float(f32::from_bits(value).into()),
+            (Type::Int64 | Type::Uint64, RawField::Varint(value)) => Value::from(value as i64),
+            (Type::Int32, RawField::Varint(value)) => Value::from(value as i32 as i64),
+            (Type::Uint32, RawField::Varint(value)) => Value::from(value as u32 as i64),
+            (Type::Sint32 | Type::Sint64, RawField::Varint(value)) => {
+                Value::from((value >> 1) as i64 ^ -((value & 1) as i64))
+            }
+            (Type::Fixed64 | Type::Sfixed64, RawField::Fixed64(value)) => Value::from(value as i64),
+            (Type::Fixed32, RawField::Fixed32(value)) => Value::from(value as i64),
+            (Type::Sfixed32, RawField::Fixed32(value)) => Value::from(value as i32 as i64),
+            (Type::Bool, RawField::Varint(value)) => Value::from(value != 0),
+            (Type::Enum, RawField::Varint(value)) => {
+                let number = value as i32;
+                let name = match self.enums {
+                    EnumHandling::Name => self
+                        .descriptors
+                        .enums
+                        .get(&field.type_name)
+                        .and_then(|values| values.get(&number)),
+                    EnumHandling::Number => None,
+                };
+                match name {
+                    Some(name) => Value::from(name.as_str()),
+                    None => Value::from(number as i64),
+                }
+            }
+            (Type::String, RawField::LengthDelimited(value)) => {
+                Value::from(String::from_utf8_lossy(value).into_owned())
+            }
+            (Type::Bytes, RawField::LengthDelimited(value)) => match self.bytes {
+                BytesHandling::Raw => Value::from(Bytes::copy_from_slice(value)),
+                BytesHandling::Base64 => Value::from(base64::encode(value)),
+            },
+            (Type::Message, RawField::LengthDelimited(value)) => {
+                Value::Object(self.decode_message(&field.type_name, value)?)
+            }
+            _ => return Err(format!("Unexpected wire type for field {:?}.", field.name)),
+        };
+        Ok(value)
+    }
+}
+
+/// Converts a floating point number, with NaN becoming null as events can't hold it.
+fn float(value: f64) -> Value {
+    if value.is_nan() {
+        Value::Null
+    } else {
+        Value::from(value)
+    }
+}
+
+impl Deserializer for ProtobufDeserializer {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let fields = self
+            .decode_message(&self.message_type, &bytes)
+            .map_err(|error| format!("Error parsing protobuf: {}", error))?;
+
+        let mut log = LogEvent::from(fields);
+        let timestamp_key = log_schema().timestamp_key();
+        if !log.contains(timestamp_key) {
+            log.insert(timestamp_key, Utc::now());
+        }
+
+        Ok(smallvec![Event::Log(log)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::encoding::{encode_key, encode_varint};
+    use prost_types::{
+        EnumValueDescriptorProto, FieldDescriptorProto, FileDescriptorProto, MessageOptions,
+    };
+
+    use super::*;
+
+    fn field(name: &str, number: i32, kind: Type, type_name: Option<&str>) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            number: Some(number),
+            r#type: Some(kind as i32),
+            type_name: type_name.map(ToOwned::to_owned),
+            label: Some(Label::Optional as i32),
+            ..Default::default()
+        }
+    }
+
+    fn repeated(field: FieldDescriptorProto) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            label: Some(Label::Repeated as i32),
+            ..field
+        }
+    }
+
+    fn descriptor_set() -> FileDescriptorSet {
+        let labels_entry = DescriptorProto {
+            name: Some("LabelsEntry".to_owned()),
+            field: vec![
+                field("key", 1, Type::String, None),
+                field("value", 2, Type::String, None),
+            ],
+            options: Some(MessageOptions {
+                map_entry: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let request = DescriptorProto {
+            name: Some("Request".to_owned()),
+            field: vec![
+                field("path", 1, Type::String, None),
+                field("status", 2, Type::Sint32, None),
+                field("method", 3, Type::Enum, Some(".test.Method")),
+                repeated(field("durations", 4, Type::Double, None)),
+                repeated(field(
+                    "labels",
+                    5,
+                    Type::Message,
+                    Some(".test.Request.LabelsEntry"),
+                )),
+                field("body", 6, Type::Bytes, None),
+                field("parent", 7, Type::Message, Some(".test.Request")),
+            ],
+            nested_type: vec![labels_entry],
+            ..Default::default()
+        };
+        let method = EnumDescriptorProto {
+            name: Some("Method".to_owned()),
+            value: vec![EnumValueDescriptorProto {
+                name: Some("GET".to_owned()),
+                number: Some(1),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".to_owned()),
+                package: Some("test".to_owned()),
+                message_type: vec![request],
+                enum_type: vec![method],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn build(bytes: &str, enums: &str) -> ProtobufDeserializer {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = dir.path().join("test.desc");
+        std::fs::write(&desc_file, descriptor_set().encode_to_vec()).unwrap();
+
+        let config: ProtobufDeserializerConfig = toml::from_str(&format!(
+            r#"
+            [protobuf]
+            desc_file = {:?}
+            message_type = "test.Request"
+            bytes = "{}"
+            enums = "{}"
+            "#,
+            desc_file, bytes, enums
+        ))
+        .unwrap();
+        config.build().unwrap()
+    }
+
+    fn length_delimited(number: u32, value: &[u8], buf: &mut Vec<u8>) {
+        encode_key(number, WireType::LengthDelimited, buf);
+        encode_varint(value.len() as u64, buf);
+        buf.extend_from_slice(value);
+    }
+
+    fn message() -> Vec<u8> {
+        let mut parent = Vec::new();
+        length_delimited(1, b"/", &mut parent);
+
+        let mut entry = Vec::new();
+        length_delimited(1, b"env", &mut entry);
+        length_delimited(2, b"prod", &mut entry);
+
+        let mut durations = Vec::new();
+        durations.extend_from_slice(&0.5_f64.to_le_bytes());
+        durations.extend_from_slice(&1.5_f64.to_le_bytes());
+
+        let mut buf = Vec::new();
+        length_delimited(1, b"/index.html", &mut buf);
+        encode_key(2, WireType::Varint, &mut buf);
+        // -2, zigzag encoded
+        encode_varint(3, &mut buf);
+        encode_key(3, WireType::Varint, &mut buf);
+        encode_varint(1, &mut buf);
+        length_delimited(4, &durations, &mut buf);
+        length_delimited(5, &entry, &mut buf);
+        length_delimited(6, b"\x00\x01", &mut buf);
+        length_delimited(7, &parent, &mut buf);
+        // An unknown field, which is skipped.
+        encode_key(15, WireType::Varint, &mut buf);
+        encode_varint(42, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn deserialize_protobuf() {
+        let deserializer = build("raw", "name");
+        let events = deserializer.parse(Bytes::from(message())).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let log = events[0].as_log();
+        assert_eq!(log["path"], "/index.html".into());
+        assert_eq!(log["status"], (-2).into());
+        assert_eq!(log["method"], "GET".into());
+        assert_eq!(log["durations"], vec![0.5, 1.5].into());
+        assert_eq!(log["labels.env"], "prod".into());
+        assert_eq!(log["body"], Value::from(Bytes::from_static(b"\x00\x01")));
+        assert_eq!(log["parent.path"], "/".into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+    }
+
+    #[test]
+    fn deserialize_protobuf_base64_bytes_and_enum_numbers() {
+        let deserializer = build("base64", "number");
+        let events = deserializer.parse(Bytes::from(message())).unwrap();
+
+        let log = events[0].as_log();
+        assert_eq!(log["method"], 1.into());
+        assert_eq!(log["body"], "AAE=".into());
+    }
+
+    #[test]
+    fn deserialize_error_truncated_protobuf() {
+        let deserializer = build("raw", "name");
+        let mut message = message();
+        message.truncate(5);
+
+        assert!(deserializer.parse(Bytes::from(message)).is_err());
+    }
+
+    #[test]
+    fn unknown_message_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let desc_file = dir.path().join("test.desc");
+        std::fs::write(&desc_file, descriptor_set().encode_to_vec()).unwrap();
+
+        let config = ProtobufDeserializerConfig {
+            protobuf: ProtobufDeserializerOptions {
+                desc_file,
+                message_type: "test.Response".to_owned(),
+                bytes: BytesHandling::Raw,
+                enums: EnumHandling::Name,
+            },
+        };
+        assert!(config.build().is_err());
+    }
+}
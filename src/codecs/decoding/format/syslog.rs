@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use bytes::Bytes;
 use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 use syslog_loose::{IncompleteDate, Message, ProcId, Protocol};
-use value::Kind;
+use value::{kind::Collection, Kind};
 
 use super::Deserializer;
 use crate::{
@@ -42,9 +44,9 @@ impl SyslogDeserializerConfig {
             .optional_field("appname", Kind::bytes(), None)
             .optional_field("msgid", Kind::bytes(), None)
             .optional_field("procid", Kind::integer().or_bytes(), None)
-            // "structured data" in a syslog message can be stored in any field, but will always be
-            // a string.
-            .unknown_fields(Kind::bytes())
+            // The elements of the "structured data" in a syslog message can be stored in any
+            // field, but will always be an object of strings.
+            .unknown_fields(Kind::object(Collection::from_unknown(Kind::bytes())))
     }
 }
 
@@ -120,10 +122,66 @@ fn insert_fields_from_syslog(event: &mut Event, parsed: Message<&str>) {
         log.insert("procid", value);
     }
 
+    // The parameters of the elements with the same ID, which may not be repeated, are merged.
+    let mut elements = BTreeMap::<&str, BTreeMap<String, Value>>::new();
     for element in parsed.structured_data.into_iter() {
-        for (name, value) in element.params() {
-            let key = format!("{}.{}", element.id, name);
-            log.insert(key.as_str(), value);
+        let params = element
+            .params()
+            .map(|(name, value)| (name.to_string(), Value::from(unescape_param_value(value))))
+            .collect::<Vec<_>>();
+        if !params.is_empty() {
+            elements.entry(element.id).or_default().extend(params);
         }
     }
+    // The IDs are not paths, as they may contain dots.
+    for (id, params) in elements {
+        log.insert_flat(id, params);
+    }
+}
+
+/// Unescapes the `"`, `\` and `]` characters of the parameter value, the only ones escaped with
+/// a backslash as specified in https://datatracker.ietf.org/doc/html/rfc5424#section-6.3.3.
+fn unescape_param_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next @ ('"' | '\\' | ']'))) => {
+                unescaped.push(next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_structured_data_into_objects() {
+        let line = r#"<13>1 2019-02-13T19:48:34+00:00 host app 8449 - [exampleSDID@32473 iut="3" eventSource="Application"][origin.x ip="192.168.0.1" path="C:\\logs \] \"a\""] message"#;
+        let events = SyslogDeserializer.parse(Bytes::from(line)).unwrap();
+        let log = events[0].as_log();
+
+        let element = log
+            .get_flat("exampleSDID@32473")
+            .unwrap()
+            .as_object()
+            .unwrap();
+        assert_eq!(element["iut"], "3".into());
+        assert_eq!(element["eventSource"], "Application".into());
+
+        let element = log.get_flat("origin.x").unwrap().as_object().unwrap();
+        assert_eq!(element["ip"], "192.168.0.1".into());
+        assert_eq!(element["path"], r#"C:\logs ] "a""#.into());
+    }
+
+    #[test]
+    fn keeps_unknown_escapes() {
+        assert_eq!(unescape_param_value(r#"a\nb\"#), r#"a\nb\"#);
+        assert_eq!(unescape_param_value(r#"\\\""#), r#"\""#);
+    }
 }
@@ -8,6 +8,7 @@ mod character_delimited;
 mod length_delimited;
 mod newline_delimited;
 mod octet_counting;
+mod pattern_delimited;
 
 pub use self::bytes::{BytesDecoder, BytesDecoderConfig};
 pub use character_delimited::{
@@ -19,6 +20,11 @@ pub use newline_delimited::{
 };
 pub use octet_counting::{
     OctetCountingDecoder, OctetCountingDecoderConfig, OctetCountingDecoderOptions,
+    OctetCountingDetection,
+};
+pub use pattern_delimited::{
+    PatternBoundary, PatternDelimitedDecoder, PatternDelimitedDecoderConfig,
+    PatternDelimitedDecoderOptions,
 };
 
 use super::StreamDecodingError;
@@ -36,12 +36,32 @@ pub struct OctetCountingDecoderOptions {
     max_length: Option<usize>,
 }
 
+/// How the octet counted frames are told from the non-transparently framed ones, which are
+/// delimited by newlines.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OctetCountingDetection {
+    /// Each frame is octet counted if it starts with a non-zero digit.
+    #[derivative(Default)]
+    PerFrame,
+    /// All the frames of the stream are framed as its first frame is.
+    PerStream,
+    /// All the frames are octet counted.
+    OctetCounting,
+    /// All the frames are delimited by newlines.
+    NonTransparent,
+}
+
 /// Codec using the `Octet Counting` format as specified in
 /// https://tools.ietf.org/html/rfc6587#section-3.4.1.
 #[derive(Clone, Debug)]
 pub struct OctetCountingDecoder {
     other: LinesCodec,
     octet_decoding: Option<State>,
+    detection: OctetCountingDetection,
+    /// Whether the frames of the stream are octet counted, once detected from its first frame.
+    octet_counted: Option<bool>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -57,6 +77,8 @@ impl OctetCountingDecoder {
         Self {
             other: LinesCodec::new(),
             octet_decoding: None,
+            detection: OctetCountingDetection::default(),
+            octet_counted: None,
         }
     }
 
@@ -65,9 +87,17 @@ impl OctetCountingDecoder {
         Self {
             other: LinesCodec::new_with_max_length(max_length),
             octet_decoding: None,
+            detection: OctetCountingDetection::default(),
+            octet_counted: None,
         }
     }
 
+    /// Sets how the octet counted frames are detected.
+    pub const fn with_detection(mut self, detection: OctetCountingDetection) -> Self {
+        self.detection = detection;
+        self
+    }
+
     /// Decode a frame.
     fn octet_decode(
         &mut self,
@@ -234,9 +264,20 @@ impl OctetCountingDecoder {
         src: &mut BytesMut,
     ) -> Option<Result<Option<Bytes>, LinesCodecError>> {
         if let Some(&first_byte) = src.get(0) {
-            if (49..=57).contains(&first_byte) {
-                // First character is non zero number so we can assume that
-                // octet count framing is used.
+            let octet_counted = match self.detection {
+                OctetCountingDetection::PerFrame => is_octet_count_start(first_byte),
+                // The frames are only detected when a new one starts, not while discarding.
+                _ if self.octet_decoding.is_some() => false,
+                OctetCountingDetection::PerStream => *self
+                    .octet_counted
+                    .get_or_insert_with(|| is_octet_count_start(first_byte)),
+                OctetCountingDetection::OctetCounting => true,
+                OctetCountingDetection::NonTransparent => false,
+            };
+            if octet_counted {
+                // Either the first character is a non zero number so we can
+                // assume that octet count framing is used, or it is known to
+                // be used.
                 trace!("Octet counting encoded event detected.");
                 self.octet_decoding = Some(State::NotDiscarding);
             }
@@ -247,6 +288,11 @@ impl OctetCountingDecoder {
     }
 }
 
+/// Whether the frame starts with a non zero number, as octet counted frames do.
+fn is_octet_count_start(first_byte: u8) -> bool {
+    (b'1'..=b'9').contains(&first_byte)
+}
+
 impl Default for OctetCountingDecoder {
     fn default() -> Self {
         Self::new()
@@ -410,4 +456,53 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(b"32 something valid"[..], buffer);
     }
+
+    #[test]
+    fn detects_framing_per_stream() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128)
+            .with_detection(OctetCountingDetection::PerStream);
+        let mut buffer = BytesMut::with_capacity(32);
+
+        // Starting with a newline delimited frame, the frames starting with digits are not
+        // mistaken for octet counted ones.
+        buffer.put(&b"<13>first\n2 second\n"[..]);
+        assert_eq!(
+            Ok(Some("<13>first".into())),
+            decoder.decode(&mut buffer).map_err(|_| ())
+        );
+        assert_eq!(
+            Ok(Some("2 second".into())),
+            decoder.decode(&mut buffer).map_err(|_| ())
+        );
+
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128)
+            .with_detection(OctetCountingDetection::PerStream);
+        buffer.put(&b"5 first6 second"[..]);
+        assert_eq!(
+            Ok(Some("first".into())),
+            decoder.decode(&mut buffer).map_err(|_| ())
+        );
+        assert_eq!(
+            Ok(Some("second".into())),
+            decoder.decode(&mut buffer).map_err(|_| ())
+        );
+    }
+
+    #[test]
+    fn decodes_configured_framing() {
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128)
+            .with_detection(OctetCountingDetection::NonTransparent);
+        let mut buffer = BytesMut::with_capacity(32);
+
+        buffer.put(&b"5 first\n"[..]);
+        assert_eq!(
+            Ok(Some("5 first".into())),
+            decoder.decode(&mut buffer).map_err(|_| ())
+        );
+
+        let mut decoder = OctetCountingDecoder::new_with_max_length(128)
+            .with_detection(OctetCountingDetection::OctetCounting);
+        buffer.put(&b"<13>first\n"[..]);
+        assert!(decoder.decode(&mut buffer).is_err());
+    }
 }
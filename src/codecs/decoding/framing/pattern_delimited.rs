@@ -0,0 +1,261 @@
+use bytes::{Buf, Bytes, BytesMut};
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Decoder;
+
+use super::BoxedFramingError;
+
+/// Config used to build a `PatternDelimitedDecoder`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PatternDelimitedDecoderConfig {
+    /// Options for the pattern delimited decoder.
+    pub pattern_delimited: PatternDelimitedDecoderOptions,
+}
+
+impl PatternDelimitedDecoderConfig {
+    /// Build the `PatternDelimitedDecoder` from this configuration.
+    pub fn build(&self) -> crate::Result<PatternDelimitedDecoder> {
+        let options = &self.pattern_delimited;
+        let pattern = Regex::new(&options.pattern)?;
+        if pattern.is_match(b"") {
+            return Err(
+                "The pattern of `pattern_delimited` framing must not match empty input.".into(),
+            );
+        }
+
+        Ok(PatternDelimitedDecoder {
+            pattern,
+            boundary: options.boundary,
+            max_length: options.max_length.unwrap_or(usize::MAX),
+        })
+    }
+}
+
+/// Options for building a `PatternDelimitedDecoder`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PatternDelimitedDecoderOptions {
+    /// The regular expression matching the boundaries of the frames.
+    pattern: String,
+    /// What the matches of the pattern are to the frames.
+    #[serde(default)]
+    boundary: PatternBoundary,
+    /// The maximum length of the frames.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    max_length: Option<usize>,
+}
+
+/// What the matches of the pattern of a `PatternDelimitedDecoder` are to the frames.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternBoundary {
+    /// The matches separate the frames, and are discarded.
+    Delimiter,
+    /// The matches start the frames, such as the timestamps starting multi-line log records,
+    /// and are kept. A line break preceding a match is discarded.
+    Start,
+}
+
+impl Default for PatternBoundary {
+    fn default() -> Self {
+        Self::Delimiter
+    }
+}
+
+/// A decoder for handling bytes the frames of which are delimited by the matches of a regular
+/// expression, which can span several lines.
+#[derive(Clone, Debug)]
+pub struct PatternDelimitedDecoder {
+    pattern: Regex,
+    boundary: PatternBoundary,
+    max_length: usize,
+}
+
+impl PatternDelimitedDecoder {
+    /// Finds the end of the next frame and the start of the one after it. Unless at the end of
+    /// the input, a match reaching the end of the buffer could still grow with more bytes, so it
+    /// isn't taken as a boundary yet.
+    fn next_boundary(&self, buf: &[u8], eof: bool) -> Option<(usize, usize)> {
+        let start = match self.boundary {
+            PatternBoundary::Delimiter => 0,
+            // A match at the very start of the buffer starts the current frame.
+            PatternBoundary::Start => 1,
+        };
+        if buf.len() < start {
+            return None;
+        }
+
+        let found = self.pattern.find_at(buf, start)?;
+        if !eof && found.end() == buf.len() {
+            return None;
+        }
+
+        Some(match self.boundary {
+            PatternBoundary::Delimiter => (found.start(), found.end()),
+            PatternBoundary::Start => (trim_line_break(buf, found.start()), found.start()),
+        })
+    }
+
+    fn frame(&self, buf: &mut BytesMut, eof: bool) -> Option<Bytes> {
+        while let Some((end, next)) = self.next_boundary(buf, eof) {
+            if end > self.max_length {
+                warn!(
+                    message = "Discarding frame larger than max_length.",
+                    buf_len = buf.len(),
+                    max_length = self.max_length,
+                    internal_log_rate_secs = 30
+                );
+                buf.advance(next);
+                continue;
+            }
+
+            let frame = buf.split_to(end).freeze();
+            buf.advance(next - end);
+            trace!(
+                message = "Decoding the frame.",
+                bytes_proccesed = frame.len()
+            );
+            return Some(frame);
+        }
+        None
+    }
+}
+
+/// Returns the end of the frame preceding the boundary, without the line break before it.
+fn trim_line_break(buf: &[u8], boundary: usize) -> usize {
+    let frame = &buf[..boundary];
+    if frame.ends_with(b"\r\n") {
+        boundary - 2
+    } else if frame.ends_with(b"\n") {
+        boundary - 1
+    } else {
+        boundary
+    }
+}
+
+impl Decoder for PatternDelimitedDecoder {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Bytes>, Self::Error> {
+        Ok(self.frame(buf, false))
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Bytes>, Self::Error> {
+        if let Some(frame) = self.frame(buf, true) {
+            return Ok(Some(frame));
+        }
+
+        if buf.is_empty() {
+            Ok(None)
+        } else {
+            let end = match self.boundary {
+                PatternBoundary::Delimiter => buf.len(),
+                PatternBoundary::Start => trim_line_break(buf, buf.len()),
+            };
+            let bytes = buf.split_to(buf.len()).freeze().slice(..end);
+            if bytes.len() > self.max_length {
+                warn!(
+                    message = "Discarding frame larger than max_length.",
+                    buf_len = bytes.len(),
+                    max_length = self.max_length,
+                    internal_log_rate_secs = 30
+                );
+                Ok(None)
+            } else {
+                Ok(Some(bytes))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+
+    use super::*;
+
+    fn decoder(
+        pattern: &str,
+        boundary: &str,
+        max_length: Option<usize>,
+    ) -> PatternDelimitedDecoder {
+        let config: PatternDelimitedDecoderConfig = toml::from_str(&format!(
+            r#"
+            [pattern_delimited]
+            pattern = '{}'
+            boundary = "{}"
+            {}
+            "#,
+            pattern,
+            boundary,
+            max_length
+                .map(|max_length| format!("max_length = {}", max_length))
+                .unwrap_or_default()
+        ))
+        .unwrap();
+        config.build().unwrap()
+    }
+
+    #[test]
+    fn decode_delimiter() {
+        let mut codec = decoder(r"\n---+\n", "delimiter", None);
+        let buf = &mut BytesMut::new();
+
+        buf.put_slice(b"abc\n---\ndef\n--");
+        assert_eq!(codec.decode(buf).unwrap(), Some("abc".into()));
+        assert_eq!(codec.decode(buf).unwrap(), None);
+
+        buf.put_slice(b"-\nghi");
+        assert_eq!(codec.decode(buf).unwrap(), Some("def".into()));
+        assert_eq!(codec.decode(buf).unwrap(), None);
+        assert_eq!(codec.decode_eof(buf).unwrap(), Some("ghi".into()));
+        assert_eq!(codec.decode_eof(buf).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_start() {
+        let mut codec = decoder(r"(?m)^\d{4}-\d{2}-\d{2} ", "start", None);
+        let buf = &mut BytesMut::new();
+
+        buf.put_slice(b"2022-01-01 first\n  at a\n  at b\n2022-01-02 second\n");
+        assert_eq!(
+            codec.decode(buf).unwrap(),
+            Some("2022-01-01 first\n  at a\n  at b".into())
+        );
+        assert_eq!(codec.decode(buf).unwrap(), None);
+
+        buf.put_slice(b"  at c\n2022-01-03 third\n");
+        assert_eq!(
+            codec.decode(buf).unwrap(),
+            Some("2022-01-02 second\n  at c".into())
+        );
+        assert_eq!(
+            codec.decode_eof(buf).unwrap(),
+            Some("2022-01-03 third".into())
+        );
+        assert_eq!(codec.decode_eof(buf).unwrap(), None);
+    }
+
+    #[test]
+    fn decode_max_length() {
+        let mut codec = decoder(r"\|", "delimiter", Some(3));
+        let buf = &mut BytesMut::new();
+
+        buf.put_slice(b"abcd|abc|abcdef");
+        assert_eq!(codec.decode(buf).unwrap(), Some("abc".into()));
+        assert_eq!(codec.decode(buf).unwrap(), None);
+        assert_eq!(codec.decode_eof(buf).unwrap(), None);
+    }
+
+    #[test]
+    fn empty_pattern() {
+        let config: PatternDelimitedDecoderConfig = toml::from_str(
+            r#"
+            [pattern_delimited]
+            pattern = 'x*'
+            "#,
+        )
+        .unwrap();
+        assert!(config.build().is_err());
+    }
+}
@@ -1,12 +1,16 @@
 //! A collection of support structures that are used in the process of decoding
 //! bytes into events.
 
+mod decompression;
 pub mod format;
 pub mod framing;
 
+pub use decompression::{Decompression, Decompressor};
 pub use format::{
-    BoxedDeserializer, BytesDeserializer, BytesDeserializerConfig, JsonDeserializer,
-    JsonDeserializerConfig,
+    BoxedDeserializer, BytesDeserializer, BytesDeserializerConfig, CsvDeserializer,
+    CsvDeserializerConfig, CsvDeserializerOptions, JsonDeserializer, JsonDeserializerConfig,
+    MsgpackDeserializer, MsgpackDeserializerConfig, ProtobufDeserializer,
+    ProtobufDeserializerConfig, ProtobufDeserializerOptions,
 };
 #[cfg(feature = "sources-syslog")]
 pub use format::{SyslogDeserializer, SyslogDeserializerConfig};
@@ -15,7 +19,8 @@ pub use framing::{
     CharacterDelimitedDecoderConfig, CharacterDelimitedDecoderOptions, FramingError,
     LengthDelimitedDecoder, LengthDelimitedDecoderConfig, NewlineDelimitedDecoder,
     NewlineDelimitedDecoderConfig, NewlineDelimitedDecoderOptions, OctetCountingDecoder,
-    OctetCountingDecoderConfig, OctetCountingDecoderOptions,
+    OctetCountingDecoderConfig, OctetCountingDecoderOptions, OctetCountingDetection,
+    PatternDelimitedDecoder, PatternDelimitedDecoderConfig, PatternDelimitedDecoderOptions,
 };
 
 use bytes::{Bytes, BytesMut};
@@ -102,6 +107,11 @@ pub enum FramingConfig {
         /// Options for the octet counting decoder.
         octet_counting: OctetCountingDecoderOptions,
     },
+    /// Configures the `PatternDelimitedDecoder`.
+    PatternDelimited {
+        /// Options for the pattern delimited decoder.
+        pattern_delimited: PatternDelimitedDecoderOptions,
+    },
 }
 
 impl From<BytesDecoderConfig> for FramingConfig {
@@ -140,9 +150,17 @@ impl From<OctetCountingDecoderConfig> for FramingConfig {
     }
 }
 
+impl From<PatternDelimitedDecoderConfig> for FramingConfig {
+    fn from(config: PatternDelimitedDecoderConfig) -> Self {
+        Self::PatternDelimited {
+            pattern_delimited: config.pattern_delimited,
+        }
+    }
+}
+
 impl FramingConfig {
-    fn build(self) -> Framer {
-        match self {
+    fn build(self) -> crate::Result<Framer> {
+        Ok(match self {
             FramingConfig::Bytes => Framer::Bytes(BytesDecoderConfig.build()),
             FramingConfig::CharacterDelimited {
                 character_delimited,
@@ -161,7 +179,10 @@ impl FramingConfig {
             FramingConfig::OctetCounting { octet_counting } => {
                 Framer::OctetCounting(OctetCountingDecoderConfig { octet_counting }.build())
             }
-        }
+            FramingConfig::PatternDelimited { pattern_delimited } => Framer::PatternDelimited(
+                PatternDelimitedDecoderConfig { pattern_delimited }.build()?,
+            ),
+        })
     }
 }
 
@@ -178,6 +199,8 @@ pub enum Framer {
     NewlineDelimited(NewlineDelimitedDecoder),
     /// Uses a `OctetCountingDecoder` for framing.
     OctetCounting(OctetCountingDecoder),
+    /// Uses a `PatternDelimitedDecoder` for framing.
+    PatternDelimited(PatternDelimitedDecoder),
     /// Uses an opaque `Framer` implementation for framing.
     Boxed(BoxedFramer),
 }
@@ -193,6 +216,7 @@ impl tokio_util::codec::Decoder for Framer {
             Framer::LengthDelimited(framer) => framer.decode(src),
             Framer::NewlineDelimited(framer) => framer.decode(src),
             Framer::OctetCounting(framer) => framer.decode(src),
+            Framer::PatternDelimited(framer) => framer.decode(src),
             Framer::Boxed(framer) => framer.decode(src),
         }
     }
@@ -204,6 +228,7 @@ impl tokio_util::codec::Decoder for Framer {
             Framer::LengthDelimited(framer) => framer.decode_eof(src),
             Framer::NewlineDelimited(framer) => framer.decode_eof(src),
             Framer::OctetCounting(framer) => framer.decode_eof(src),
+            Framer::PatternDelimited(framer) => framer.decode_eof(src),
             Framer::Boxed(framer) => framer.decode_eof(src),
         }
     }
@@ -218,8 +243,24 @@ impl tokio_util::codec::Decoder for Framer {
 pub enum DeserializerConfig {
     /// Configures the `BytesDeserializer`.
     Bytes,
+    /// Configures the `CsvDeserializer`.
+    Csv {
+        #[serde(
+            default,
+            skip_serializing_if = "crate::serde::skip_serializing_if_default"
+        )]
+        /// Options for the CSV deserializer.
+        csv: CsvDeserializerOptions,
+    },
     /// Configures the `JsonDeserializer`.
     Json,
+    /// Configures the `MsgpackDeserializer`.
+    Msgpack,
+    /// Configures the `ProtobufDeserializer`.
+    Protobuf {
+        /// Options for the protobuf deserializer.
+        protobuf: ProtobufDeserializerOptions,
+    },
     #[cfg(feature = "sources-syslog")]
     /// Configures the `SyslogDeserializer`.
     Syslog,
@@ -231,12 +272,32 @@ impl From<BytesDeserializerConfig> for DeserializerConfig {
     }
 }
 
+impl From<CsvDeserializerConfig> for DeserializerConfig {
+    fn from(config: CsvDeserializerConfig) -> Self {
+        Self::Csv { csv: config.csv }
+    }
+}
+
 impl From<JsonDeserializerConfig> for DeserializerConfig {
     fn from(_: JsonDeserializerConfig) -> Self {
         Self::Json
     }
 }
 
+impl From<MsgpackDeserializerConfig> for DeserializerConfig {
+    fn from(_: MsgpackDeserializerConfig) -> Self {
+        Self::Msgpack
+    }
+}
+
+impl From<ProtobufDeserializerConfig> for DeserializerConfig {
+    fn from(config: ProtobufDeserializerConfig) -> Self {
+        Self::Protobuf {
+            protobuf: config.protobuf,
+        }
+    }
+}
+
 #[cfg(feature = "sources-syslog")]
 impl From<SyslogDeserializerConfig> for DeserializerConfig {
     fn from(_: SyslogDeserializerConfig) -> Self {
@@ -245,20 +306,38 @@ impl From<SyslogDeserializerConfig> for DeserializerConfig {
 }
 
 impl DeserializerConfig {
-    fn build(&self) -> Deserializer {
-        match self {
+    fn build(&self) -> crate::Result<Deserializer> {
+        Ok(match self {
             DeserializerConfig::Bytes => Deserializer::Bytes(BytesDeserializerConfig.build()),
+            DeserializerConfig::Csv { csv } => {
+                Deserializer::Csv(CsvDeserializerConfig { csv: csv.clone() }.build()?)
+            }
             DeserializerConfig::Json => Deserializer::Json(JsonDeserializerConfig.build()),
+            DeserializerConfig::Msgpack => Deserializer::Msgpack(MsgpackDeserializerConfig.build()),
+            DeserializerConfig::Protobuf { protobuf } => Deserializer::Protobuf(
+                ProtobufDeserializerConfig {
+                    protobuf: protobuf.clone(),
+                }
+                .build()?,
+            ),
             #[cfg(feature = "sources-syslog")]
             DeserializerConfig::Syslog => Deserializer::Syslog(SyslogDeserializerConfig.build()),
-        }
+        })
     }
 
     /// The schema produced by the deserializer.
     pub fn schema_definition(&self) -> schema::Definition {
         match self {
             DeserializerConfig::Bytes => BytesDeserializerConfig.schema_definition(),
+            DeserializerConfig::Csv { csv } => {
+                CsvDeserializerConfig { csv: csv.clone() }.schema_definition()
+            }
             DeserializerConfig::Json => JsonDeserializerConfig.schema_definition(),
+            DeserializerConfig::Msgpack => MsgpackDeserializerConfig.schema_definition(),
+            DeserializerConfig::Protobuf { protobuf } => ProtobufDeserializerConfig {
+                protobuf: protobuf.clone(),
+            }
+            .schema_definition(),
             #[cfg(feature = "sources-syslog")]
             DeserializerConfig::Syslog => SyslogDeserializerConfig.schema_definition(),
         }
@@ -270,8 +349,14 @@ impl DeserializerConfig {
 pub enum Deserializer {
     /// Uses a `BytesDeserializer` for deserialization.
     Bytes(BytesDeserializer),
+    /// Uses a `CsvDeserializer` for deserialization.
+    Csv(CsvDeserializer),
     /// Uses a `JsonDeserializer` for deserialization.
     Json(JsonDeserializer),
+    /// Uses a `MsgpackDeserializer` for deserialization.
+    Msgpack(MsgpackDeserializer),
+    /// Uses a `ProtobufDeserializer` for deserialization.
+    Protobuf(ProtobufDeserializer),
     #[cfg(feature = "sources-syslog")]
     /// Uses a `SyslogDeserializer` for deserialization.
     Syslog(SyslogDeserializer),
@@ -283,7 +368,10 @@ impl format::Deserializer for Deserializer {
     fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
         match self {
             Deserializer::Bytes(deserializer) => deserializer.parse(bytes),
+            Deserializer::Csv(deserializer) => deserializer.parse(bytes),
             Deserializer::Json(deserializer) => deserializer.parse(bytes),
+            Deserializer::Msgpack(deserializer) => deserializer.parse(bytes),
+            Deserializer::Protobuf(deserializer) => deserializer.parse(bytes),
             #[cfg(feature = "sources-syslog")]
             Deserializer::Syslog(deserializer) => deserializer.parse(bytes),
             Deserializer::Boxed(deserializer) => deserializer.parse(bytes),
@@ -295,6 +383,7 @@ impl format::Deserializer for Deserializer {
 /// messages.
 #[derive(Debug, Clone)]
 pub struct Decoder {
+    decompressor: Option<Decompressor>,
     framer: Framer,
     deserializer: Deserializer,
 }
@@ -302,6 +391,7 @@ pub struct Decoder {
 impl Default for Decoder {
     fn default() -> Self {
         Self {
+            decompressor: None,
             framer: Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
             deserializer: Deserializer::Bytes(BytesDeserializer::new()),
         }
@@ -314,11 +404,18 @@ impl Decoder {
     /// structured events from a byte frame.
     pub const fn new(framer: Framer, deserializer: Deserializer) -> Self {
         Self {
+            decompressor: None,
             framer,
             deserializer,
         }
     }
 
+    /// Decompresses the byte stream / byte messages with the given method before framing them.
+    pub fn with_decompression(mut self, decompression: Decompression) -> Self {
+        self.decompressor = decompression.build();
+        self
+    }
+
     /// Handles the framing result and parses it into a structured event, if
     /// possible.
     ///
@@ -355,12 +452,28 @@ impl tokio_util::codec::Decoder for Decoder {
     type Error = Error;
 
     fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let frame = self.framer.decode(buf);
+        let frame = match &mut self.decompressor {
+            // The compressed bytes can only be framed once they are all read.
+            Some(decompressor) => {
+                decompressor.buffer(buf);
+                Ok(None)
+            }
+            None => self.framer.decode(buf),
+        };
         self.handle_framing_result(frame)
     }
 
     fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let frame = self.framer.decode_eof(buf);
+        let frame = match &mut self.decompressor {
+            Some(decompressor) => {
+                decompressor.buffer(buf);
+                match decompressor.finish() {
+                    Ok(decompressed) => self.framer.decode_eof(decompressed),
+                    Err(error) => Err(error.into()),
+                }
+            }
+            None => self.framer.decode_eof(buf),
+        };
         self.handle_framing_result(frame)
     }
 }
@@ -372,23 +485,39 @@ pub struct DecodingConfig {
     framing: FramingConfig,
     /// The decoding config.
     decoding: DeserializerConfig,
+    /// The decompression applied before framing.
+    #[serde(default)]
+    decompression: Decompression,
 }
 
 impl DecodingConfig {
     /// Creates a new `DecodingConfig` with the provided `FramingConfig` and
     /// `DeserializerConfig`.
     pub const fn new(framing: FramingConfig, decoding: DeserializerConfig) -> Self {
-        Self { framing, decoding }
+        Self {
+            framing,
+            decoding,
+            decompression: Decompression::None,
+        }
+    }
+
+    /// Decompresses the byte stream / byte messages with the given method before framing them.
+    pub fn with_decompression(mut self, decompression: Decompression) -> Self {
+        self.decompression = decompression;
+        self
     }
 
     /// Builds a `Decoder` from the provided configuration.
-    pub fn build(self) -> Decoder {
+    ///
+    /// Fails if the framer or the deserializer can't be built, e.g. when the pattern of the
+    /// framer is invalid or the protobuf descriptor set can't be read.
+    pub fn build(self) -> crate::Result<Decoder> {
         // Build the framer.
-        let framer = self.framing.build();
+        let framer = self.framing.build()?;
 
         // Build the deserializer.
-        let deserializer = self.decoding.build();
+        let deserializer = self.decoding.build()?;
 
-        Decoder::new(framer, deserializer)
+        Ok(Decoder::new(framer, deserializer).with_decompression(self.decompression))
     }
 }
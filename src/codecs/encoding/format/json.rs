@@ -1,8 +1,11 @@
 use bytes::{BufMut, BytesMut};
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
 use tokio_util::codec::Encoder;
 
-use crate::{event::Event, schema};
+use crate::{
+    event::{Event, LogEvent},
+    schema,
+};
 
 /// Config used to build a `JsonSerializer`.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -16,7 +19,7 @@ impl JsonSerializerConfig {
 
     /// Build the `JsonSerializer` from this configuration.
     pub const fn build(&self) -> JsonSerializer {
-        JsonSerializer
+        JsonSerializer::new()
     }
 
     /// The schema required by the serializer.
@@ -29,12 +32,50 @@ impl JsonSerializerConfig {
 
 /// Serializer that converts an `Event` to bytes using the JSON format.
 #[derive(Debug, Clone)]
-pub struct JsonSerializer;
+pub struct JsonSerializer {
+    field_order: Vec<String>,
+}
 
 impl JsonSerializer {
     /// Creates a new `JsonSerializer`.
     pub const fn new() -> Self {
-        Self
+        Self {
+            field_order: Vec::new(),
+        }
+    }
+
+    /// Writes the given top-level fields of the logs first, in this order, followed by the
+    /// other fields in lexicographic order.
+    pub fn with_field_order(mut self, field_order: Vec<String>) -> Self {
+        self.field_order = field_order;
+        self
+    }
+}
+
+/// A log serialized with some of its top-level fields first.
+struct OrderedLog<'a> {
+    log: &'a LogEvent,
+    field_order: &'a [String],
+}
+
+impl<'a> Serialize for OrderedLog<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let fields = self.log.as_map();
+        let mut map = serializer.serialize_map(None)?;
+        for field in self.field_order {
+            if let Some(value) = fields.get(field) {
+                map.serialize_entry(field, value)?;
+            }
+        }
+        for (field, value) in fields {
+            if !self.field_order.contains(field) {
+                map.serialize_entry(field, value)?;
+            }
+        }
+        map.end()
     }
 }
 
@@ -44,6 +85,13 @@ impl Encoder<Event> for JsonSerializer {
     fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
         let writer = buffer.writer();
         match event {
+            Event::Log(log) if !self.field_order.is_empty() => serde_json::to_writer(
+                writer,
+                &OrderedLog {
+                    log: &log,
+                    field_order: &self.field_order,
+                },
+            ),
             Event::Log(log) => serde_json::to_writer(writer, &log),
             Event::Metric(metric) => serde_json::to_writer(writer, &metric),
             Event::Trace(trace) => serde_json::to_writer(writer, &trace),
@@ -71,4 +119,24 @@ mod tests {
 
         assert_eq!(bytes.freeze(), r#"{"foo":"bar"}"#);
     }
+
+    #[test]
+    fn serialize_json_with_field_order() {
+        let event = Event::from(btreemap! {
+            "a" => Value::from(1),
+            "b" => Value::from(2),
+            "c" => Value::from(3),
+            "d" => Value::from(4),
+        });
+        let mut serializer = JsonSerializer::new().with_field_order(vec![
+            "c".to_owned(),
+            "x".to_owned(),
+            "a".to_owned(),
+        ]);
+        let mut bytes = BytesMut::new();
+
+        serializer.encode(event, &mut bytes).unwrap();
+
+        assert_eq!(bytes.freeze(), r#"{"c":3,"a":1,"b":2,"d":4}"#);
+    }
 }
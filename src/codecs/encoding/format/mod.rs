@@ -4,9 +4,15 @@
 #![deny(missing_docs)]
 
 mod json;
+mod msgpack;
+#[cfg(feature = "codecs-otlp")]
+mod otlp;
 mod raw_message;
 
 pub use json::{JsonSerializer, JsonSerializerConfig};
+pub use msgpack::{MsgpackSerializer, MsgpackSerializerConfig};
+#[cfg(feature = "codecs-otlp")]
+pub use otlp::{OtlpFormat, OtlpSerializer, OtlpSerializerConfig, OtlpSerializerOptions};
 pub use raw_message::{RawMessageSerializer, RawMessageSerializerConfig};
 
 use crate::event::Event;
@@ -0,0 +1,159 @@
+use bytes::{BufMut, BytesMut};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+
+use crate::{
+    event::{Event, Value},
+    schema,
+};
+
+/// The MessagePack extension type of timestamps.
+const TIMESTAMP_EXT: i8 = -1;
+
+/// Config used to build a `MsgpackSerializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MsgpackSerializerConfig;
+
+impl MsgpackSerializerConfig {
+    /// Creates a new `MsgpackSerializerConfig`.
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Build the `MsgpackSerializer` from this configuration.
+    pub const fn build(&self) -> MsgpackSerializer {
+        MsgpackSerializer
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Serializer that converts an `Event` to bytes using the MessagePack format.
+///
+/// Unlike with JSON, the bytes which aren't valid UTF-8 are written as binaries and the timestamps
+/// as timestamp extensions, so that they are decoded back as such by the `msgpack` codec.
+#[derive(Debug, Clone)]
+pub struct MsgpackSerializer;
+
+impl MsgpackSerializer {
+    /// Creates a new `MsgpackSerializer`.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Encoder<Event> for MsgpackSerializer {
+    type Error = crate::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let mut writer = buffer.writer();
+        match event {
+            Event::Log(log) => {
+                let (fields, _) = log.into_parts();
+                rmpv::encode::write_value(&mut writer, &from_value(Value::Object(fields)))?;
+            }
+            Event::Metric(metric) => rmp_serde::encode::write_named(&mut writer, &metric)?,
+            Event::Trace(trace) => {
+                let (fields, _) = trace.into_parts();
+                rmpv::encode::write_value(&mut writer, &from_value(Value::Object(fields)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts a `Value` to a MessagePack value.
+fn from_value(value: Value) -> rmpv::Value {
+    match value {
+        Value::Bytes(bytes) => match String::from_utf8(bytes.to_vec()) {
+            Ok(string) => rmpv::Value::String(string.into()),
+            Err(error) => rmpv::Value::Binary(error.into_bytes()),
+        },
+        Value::Regex(regex) => rmpv::Value::String(regex.as_str().into()),
+        Value::Integer(integer) => rmpv::Value::Integer(integer.into()),
+        Value::Float(float) => rmpv::Value::F64(float.into_inner()),
+        Value::Boolean(boolean) => rmpv::Value::Boolean(boolean),
+        Value::Timestamp(timestamp) => timestamp_extension(timestamp),
+        Value::Object(fields) => rmpv::Value::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.into(), from_value(value)))
+                .collect(),
+        ),
+        Value::Array(values) => rmpv::Value::Array(values.into_iter().map(from_value).collect()),
+        Value::Null => rmpv::Value::Nil,
+    }
+}
+
+/// Encodes the timestamp in the smallest of the 32, 64 or 96 bits timestamp extensions.
+fn timestamp_extension(timestamp: DateTime<Utc>) -> rmpv::Value {
+    let seconds = timestamp.timestamp();
+    let nanoseconds = timestamp.timestamp_subsec_nanos();
+
+    let data = if seconds >> 34 == 0 {
+        let data = (u64::from(nanoseconds) << 34) | seconds as u64;
+        if data >> 32 == 0 {
+            (data as u32).to_be_bytes().to_vec()
+        } else {
+            data.to_be_bytes().to_vec()
+        }
+    } else {
+        let mut data = nanoseconds.to_be_bytes().to_vec();
+        data.extend_from_slice(&seconds.to_be_bytes());
+        data
+    };
+    rmpv::Value::Ext(TIMESTAMP_EXT, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use chrono::TimeZone;
+    use vector_common::btreemap;
+
+    use super::*;
+    use crate::codecs::decoding::format::{Deserializer, MsgpackDeserializer};
+
+    #[test]
+    fn serialize_msgpack() {
+        let event = Event::from(btreemap! {
+            "foo" => Value::from("bar")
+        });
+        let mut serializer = MsgpackSerializer::new();
+        let mut bytes = BytesMut::new();
+
+        serializer.encode(event, &mut bytes).unwrap();
+
+        assert_eq!(
+            bytes.freeze(),
+            &[0x81, 0xa3, b'f', b'o', b'o', 0xa3, b'b', b'a', b'r'][..]
+        );
+    }
+
+    #[test]
+    fn round_trip_msgpack() {
+        let fields = btreemap! {
+            "message" => Value::from("foo"),
+            "data" => Value::Bytes(Bytes::from_static(&[0xff, 0x00])),
+            "count" => Value::from(-42),
+            "nested" => Value::from(btreemap! {
+                "ratio" => Value::from(0.5),
+                "tags" => Value::Array(vec!["a".into(), Value::Null]),
+            }),
+            "timestamp" => Value::from(Utc.timestamp(1_650_000_000, 123_456_789)),
+            "old" => Value::from(Utc.timestamp(1_000, 0)),
+            "far" => Value::from(Utc.timestamp(20_000_000_000, 1)),
+        };
+        let mut bytes = BytesMut::new();
+        MsgpackSerializer::new()
+            .encode(Event::from(fields.clone()), &mut bytes)
+            .unwrap();
+
+        let events = MsgpackDeserializer::new().parse(bytes.freeze()).unwrap();
+        assert_eq!(events[0].as_log().as_map(), &fields);
+    }
+}
@@ -0,0 +1,356 @@
+//! The JSON encoding of the OTLP export requests, following the mapping of protobuf to JSON with
+//! the exceptions of OTLP: the trace and span IDs are hexadecimal strings, and the enumerations
+//! are integers. The fields with default values are omitted.
+
+use serde_json::{json, Map, Value as JsonValue};
+
+use crate::proto::opentelemetry::{
+    collector::{
+        logs::v1::ExportLogsServiceRequest, metrics::v1::ExportMetricsServiceRequest,
+        trace::v1::ExportTraceServiceRequest,
+    },
+    common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+    logs::v1::LogRecord,
+    metrics::v1::{
+        metric::Data, number_data_point, HistogramDataPoint, Metric, NumberDataPoint,
+        SummaryDataPoint,
+    },
+    resource::v1::Resource,
+    trace::v1::{
+        span::{Event as SpanEvent, Link},
+        Span, Status,
+    },
+};
+
+/// A JSON object, its fields being written unless they have default values.
+#[derive(Default)]
+struct Object(Map<String, JsonValue>);
+
+impl Object {
+    fn field(mut self, key: &str, value: JsonValue) -> Self {
+        self.0.insert(key.to_owned(), value);
+        self
+    }
+
+    fn string(self, key: &str, value: &str) -> Self {
+        if value.is_empty() {
+            self
+        } else {
+            self.field(key, value.into())
+        }
+    }
+
+    /// The 64 bits integers are written as strings, as JavaScript numbers can't hold them all.
+    fn u64(self, key: &str, value: u64) -> Self {
+        if value == 0 {
+            self
+        } else {
+            self.field(key, value.to_string().into())
+        }
+    }
+
+    fn u32(self, key: &str, value: u32) -> Self {
+        if value == 0 {
+            self
+        } else {
+            self.field(key, value.into())
+        }
+    }
+
+    fn i32(self, key: &str, value: i32) -> Self {
+        if value == 0 {
+            self
+        } else {
+            self.field(key, value.into())
+        }
+    }
+
+    fn bool(self, key: &str, value: bool) -> Self {
+        if value {
+            self.field(key, value.into())
+        } else {
+            self
+        }
+    }
+
+    fn id(self, key: &str, id: &[u8]) -> Self {
+        self.string(key, &hex::encode(id))
+    }
+
+    fn optional<T>(self, key: &str, value: Option<&T>, map: impl Fn(&T) -> JsonValue) -> Self {
+        match value {
+            Some(value) => self.field(key, map(value)),
+            None => self,
+        }
+    }
+
+    fn array<T>(self, key: &str, values: &[T], map: impl Fn(&T) -> JsonValue) -> Self {
+        if values.is_empty() {
+            self
+        } else {
+            self.field(key, values.iter().map(map).collect())
+        }
+    }
+
+    fn attributes(self, attributes: &[KeyValue], dropped_attributes_count: u32) -> Self {
+        self.array("attributes", attributes, key_value)
+            .u32("droppedAttributesCount", dropped_attributes_count)
+    }
+
+    fn build(self) -> JsonValue {
+        JsonValue::Object(self.0)
+    }
+}
+
+pub(super) fn logs(request: &ExportLogsServiceRequest) -> JsonValue {
+    Object::default()
+        .array("resourceLogs", &request.resource_logs, |resource_logs| {
+            Object::default()
+                .optional("resource", resource_logs.resource.as_ref(), resource)
+                .array("scopeLogs", &resource_logs.scope_logs, |scope_logs| {
+                    Object::default()
+                        .optional("scope", scope_logs.scope.as_ref(), scope)
+                        .array("logRecords", &scope_logs.log_records, log_record)
+                        .string("schemaUrl", &scope_logs.schema_url)
+                        .build()
+                })
+                .string("schemaUrl", &resource_logs.schema_url)
+                .build()
+        })
+        .build()
+}
+
+pub(super) fn metrics(request: &ExportMetricsServiceRequest) -> JsonValue {
+    Object::default()
+        .array(
+            "resourceMetrics",
+            &request.resource_metrics,
+            |resource_metrics| {
+                Object::default()
+                    .optional("resource", resource_metrics.resource.as_ref(), resource)
+                    .array(
+                        "scopeMetrics",
+                        &resource_metrics.scope_metrics,
+                        |scope_metrics| {
+                            Object::default()
+                                .optional("scope", scope_metrics.scope.as_ref(), scope)
+                                .array("metrics", &scope_metrics.metrics, metric)
+                                .string("schemaUrl", &scope_metrics.schema_url)
+                                .build()
+                        },
+                    )
+                    .string("schemaUrl", &resource_metrics.schema_url)
+                    .build()
+            },
+        )
+        .build()
+}
+
+pub(super) fn traces(request: &ExportTraceServiceRequest) -> JsonValue {
+    Object::default()
+        .array("resourceSpans", &request.resource_spans, |resource_spans| {
+            Object::default()
+                .optional("resource", resource_spans.resource.as_ref(), resource)
+                .array("scopeSpans", &resource_spans.scope_spans, |scope_spans| {
+                    Object::default()
+                        .optional("scope", scope_spans.scope.as_ref(), scope)
+                        .array("spans", &scope_spans.spans, span)
+                        .string("schemaUrl", &scope_spans.schema_url)
+                        .build()
+                })
+                .string("schemaUrl", &resource_spans.schema_url)
+                .build()
+        })
+        .build()
+}
+
+fn any_value(value: &AnyValue) -> JsonValue {
+    match &value.value {
+        Some(any_value::Value::StringValue(value)) => json!({ "stringValue": value }),
+        Some(any_value::Value::BoolValue(value)) => json!({ "boolValue": value }),
+        Some(any_value::Value::IntValue(value)) => json!({ "intValue": value.to_string() }),
+        Some(any_value::Value::DoubleValue(value)) => json!({ "doubleValue": value }),
+        Some(any_value::Value::ArrayValue(array)) => {
+            let array = Object::default().array("values", &array.values, any_value);
+            json!({ "arrayValue": array.build() })
+        }
+        Some(any_value::Value::KvlistValue(list)) => {
+            let list = Object::default().array("values", &list.values, key_value);
+            json!({ "kvlistValue": list.build() })
+        }
+        Some(any_value::Value::BytesValue(value)) => {
+            json!({ "bytesValue": base64::encode(value) })
+        }
+        None => json!({}),
+    }
+}
+
+fn key_value(attribute: &KeyValue) -> JsonValue {
+    Object::default()
+        .field("key", attribute.key.as_str().into())
+        .optional("value", attribute.value.as_ref(), any_value)
+        .build()
+}
+
+fn resource(resource: &Resource) -> JsonValue {
+    Object::default()
+        .attributes(&resource.attributes, resource.dropped_attributes_count)
+        .build()
+}
+
+fn scope(scope: &InstrumentationScope) -> JsonValue {
+    Object::default()
+        .string("name", &scope.name)
+        .string("version", &scope.version)
+        .attributes(&scope.attributes, scope.dropped_attributes_count)
+        .build()
+}
+
+fn log_record(record: &LogRecord) -> JsonValue {
+    Object::default()
+        .u64("timeUnixNano", record.time_unix_nano)
+        .u64("observedTimeUnixNano", record.observed_time_unix_nano)
+        .i32("severityNumber", record.severity_number)
+        .string("severityText", &record.severity_text)
+        .optional("body", record.body.as_ref(), any_value)
+        .attributes(&record.attributes, record.dropped_attributes_count)
+        .u32("flags", record.flags)
+        .id("traceId", &record.trace_id)
+        .id("spanId", &record.span_id)
+        .build()
+}
+
+fn metric(metric: &Metric) -> JsonValue {
+    let object = Object::default()
+        .string("name", &metric.name)
+        .string("description", &metric.description)
+        .string("unit", &metric.unit);
+    match &metric.data {
+        Some(Data::Gauge(gauge)) => object.field(
+            "gauge",
+            Object::default()
+                .array("dataPoints", &gauge.data_points, number_data_point)
+                .build(),
+        ),
+        Some(Data::Sum(sum)) => object.field(
+            "sum",
+            Object::default()
+                .array("dataPoints", &sum.data_points, number_data_point)
+                .i32("aggregationTemporality", sum.aggregation_temporality)
+                .bool("isMonotonic", sum.is_monotonic)
+                .build(),
+        ),
+        Some(Data::Histogram(histogram)) => object.field(
+            "histogram",
+            Object::default()
+                .array("dataPoints", &histogram.data_points, histogram_data_point)
+                .i32("aggregationTemporality", histogram.aggregation_temporality)
+                .build(),
+        ),
+        Some(Data::Summary(summary)) => object.field(
+            "summary",
+            Object::default()
+                .array("dataPoints", &summary.data_points, summary_data_point)
+                .build(),
+        ),
+        // The serializer doesn't write exponential histograms.
+        Some(Data::ExponentialHistogram(_)) | None => object,
+    }
+    .build()
+}
+
+fn number_data_point(point: &NumberDataPoint) -> JsonValue {
+    let object = Object::default()
+        .array("attributes", &point.attributes, key_value)
+        .u64("startTimeUnixNano", point.start_time_unix_nano)
+        .u64("timeUnixNano", point.time_unix_nano)
+        .u32("flags", point.flags);
+    match point.value {
+        Some(number_data_point::Value::AsDouble(value)) => object.field("asDouble", value.into()),
+        Some(number_data_point::Value::AsInt(value)) => {
+            object.field("asInt", value.to_string().into())
+        }
+        None => object,
+    }
+    .build()
+}
+
+fn histogram_data_point(point: &HistogramDataPoint) -> JsonValue {
+    Object::default()
+        .array("attributes", &point.attributes, key_value)
+        .u64("startTimeUnixNano", point.start_time_unix_nano)
+        .u64("timeUnixNano", point.time_unix_nano)
+        .field("count", point.count.to_string().into())
+        .optional("sum", point.sum.as_ref(), |sum| (*sum).into())
+        .array("bucketCounts", &point.bucket_counts, |count| {
+            count.to_string().into()
+        })
+        .array("explicitBounds", &point.explicit_bounds, |bound| {
+            (*bound).into()
+        })
+        .u32("flags", point.flags)
+        .optional("min", point.min.as_ref(), |min| (*min).into())
+        .optional("max", point.max.as_ref(), |max| (*max).into())
+        .build()
+}
+
+fn summary_data_point(point: &SummaryDataPoint) -> JsonValue {
+    Object::default()
+        .array("attributes", &point.attributes, key_value)
+        .u64("startTimeUnixNano", point.start_time_unix_nano)
+        .u64("timeUnixNano", point.time_unix_nano)
+        .field("count", point.count.to_string().into())
+        .field("sum", point.sum.into())
+        .array(
+            "quantileValues",
+            &point.quantile_values,
+            |quantile| json!({ "quantile": quantile.quantile, "value": quantile.value }),
+        )
+        .u32("flags", point.flags)
+        .build()
+}
+
+fn span(span: &Span) -> JsonValue {
+    Object::default()
+        .id("traceId", &span.trace_id)
+        .id("spanId", &span.span_id)
+        .string("traceState", &span.trace_state)
+        .id("parentSpanId", &span.parent_span_id)
+        .u32("flags", span.flags)
+        .string("name", &span.name)
+        .i32("kind", span.kind)
+        .u64("startTimeUnixNano", span.start_time_unix_nano)
+        .u64("endTimeUnixNano", span.end_time_unix_nano)
+        .attributes(&span.attributes, span.dropped_attributes_count)
+        .array("events", &span.events, span_event)
+        .u32("droppedEventsCount", span.dropped_events_count)
+        .array("links", &span.links, link)
+        .u32("droppedLinksCount", span.dropped_links_count)
+        .optional("status", span.status.as_ref(), status)
+        .build()
+}
+
+fn span_event(event: &SpanEvent) -> JsonValue {
+    Object::default()
+        .u64("timeUnixNano", event.time_unix_nano)
+        .string("name", &event.name)
+        .attributes(&event.attributes, event.dropped_attributes_count)
+        .build()
+}
+
+fn link(link: &Link) -> JsonValue {
+    Object::default()
+        .id("traceId", &link.trace_id)
+        .id("spanId", &link.span_id)
+        .string("traceState", &link.trace_state)
+        .attributes(&link.attributes, link.dropped_attributes_count)
+        .u32("flags", link.flags)
+        .build()
+}
+
+fn status(status: &Status) -> JsonValue {
+    Object::default()
+        .string("message", &status.message)
+        .i32("code", status.code)
+        .build()
+}
@@ -0,0 +1,74 @@
+use std::convert::TryFrom;
+
+use super::{count, id, resource, scope, string, unix_nano, Fields};
+use crate::{
+    config::log_schema,
+    event::LogEvent,
+    proto::opentelemetry::{
+        collector::logs::v1::ExportLogsServiceRequest,
+        common::v1::AnyValue,
+        logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+    },
+};
+
+/// Converts the log into a log record, its message being the body.
+pub(super) fn encode(log: LogEvent) -> ExportLogsServiceRequest {
+    let (fields, _) = log.into_parts();
+    let mut fields = Fields::new(fields);
+    fields.fields.remove(log_schema().source_type_key());
+
+    let resource = fields.take("resources", resource);
+    let scope = fields.take("scope", scope);
+    let body = fields.take(log_schema().message_key(), |value| {
+        Some(AnyValue::from(value.clone()))
+    });
+    let time_unix_nano = fields
+        .take(log_schema().timestamp_key(), unix_nano)
+        .unwrap_or_default();
+    let observed_time_unix_nano = fields
+        .take("observed_timestamp", unix_nano)
+        .unwrap_or_default();
+    let severity_text = fields.take("severity_text", string).unwrap_or_default();
+    let severity_number = fields
+        .take("severity_number", |value| {
+            i32::try_from(value.as_integer()?)
+                .ok()
+                .filter(|number| (0..=24).contains(number))
+        })
+        .unwrap_or_default();
+    let trace_id = fields
+        .take("trace_id", |value| id(value, 16))
+        .unwrap_or_default();
+    let span_id = fields
+        .take("span_id", |value| id(value, 8))
+        .unwrap_or_default();
+    let flags = fields.take("flags", count).unwrap_or_default();
+    let dropped_attributes_count = fields
+        .take("dropped_attributes_count", count)
+        .unwrap_or_default();
+    fields.take_attributes();
+
+    let record = LogRecord {
+        time_unix_nano,
+        observed_time_unix_nano,
+        severity_number,
+        severity_text,
+        body,
+        attributes: fields.into_attributes(),
+        dropped_attributes_count,
+        flags,
+        trace_id,
+        span_id,
+    };
+    ExportLogsServiceRequest {
+        resource_logs: vec![ResourceLogs {
+            resource,
+            scope_logs: vec![ScopeLogs {
+                scope,
+                log_records: vec![record],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
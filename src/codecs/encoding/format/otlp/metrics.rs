@@ -0,0 +1,163 @@
+use super::timestamp_unix_nano;
+use crate::{
+    event::{
+        metric::{MetricKind, MetricValue},
+        Metric, Value,
+    },
+    proto::opentelemetry::{
+        collector::metrics::v1::ExportMetricsServiceRequest,
+        common::v1::{AnyValue, InstrumentationScope, KeyValue},
+        metrics::v1::{
+            metric::Data, number_data_point, summary_data_point::ValueAtQuantile,
+            AggregationTemporality, Gauge, Histogram, HistogramDataPoint, Metric as OtlpMetric,
+            NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+        },
+        resource::v1::Resource,
+    },
+};
+
+/// Converts the metric into a metric with a single data point.
+///
+/// The counters become monotonic sums, the incremental gauges non-monotonic sums of deltas and the
+/// sets gauges of their number of values. The distributions and sketches aren't supported, and
+/// must be aggregated into histograms or summaries first.
+pub(super) fn encode(metric: Metric) -> crate::Result<ExportMetricsServiceRequest> {
+    let name = match metric.namespace() {
+        Some(namespace) => format!("{}.{}", namespace, metric.name()),
+        None => metric.name().to_owned(),
+    };
+    let time_unix_nano = metric
+        .timestamp()
+        .as_ref()
+        .and_then(timestamp_unix_nano)
+        .unwrap_or_default();
+    let temporality = match metric.kind() {
+        MetricKind::Incremental => AggregationTemporality::Delta,
+        MetricKind::Absolute => AggregationTemporality::Cumulative,
+    } as i32;
+
+    let mut resource = Vec::new();
+    let mut scope = InstrumentationScope::default();
+    let mut attributes = Vec::new();
+    for (key, value) in metric.tags().cloned().unwrap_or_default() {
+        if let Some(key) = key.strip_prefix("resource.") {
+            resource.push(key_value(key.to_owned(), value));
+        } else if key == "scope.name" {
+            scope.name = value;
+        } else if key == "scope.version" {
+            scope.version = value;
+        } else {
+            attributes.push(key_value(key, value));
+        }
+    }
+
+    let number_point = |value: f64| NumberDataPoint {
+        attributes: attributes.clone(),
+        time_unix_nano,
+        value: Some(number_data_point::Value::AsDouble(value)),
+        ..Default::default()
+    };
+    let data = match metric.value() {
+        MetricValue::Counter { value } => Data::Sum(Sum {
+            data_points: vec![number_point(*value)],
+            aggregation_temporality: temporality,
+            is_monotonic: true,
+        }),
+        MetricValue::Gauge { value } if metric.kind() == MetricKind::Incremental => {
+            Data::Sum(Sum {
+                data_points: vec![number_point(*value)],
+                aggregation_temporality: temporality,
+                is_monotonic: false,
+            })
+        }
+        MetricValue::Gauge { value } => Data::Gauge(Gauge {
+            data_points: vec![number_point(*value)],
+        }),
+        MetricValue::Set { values } => Data::Gauge(Gauge {
+            data_points: vec![number_point(values.len() as f64)],
+        }),
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => {
+            // The count of the values above the last bound is implied by the total count.
+            let buckets = buckets
+                .iter()
+                .filter(|bucket| bucket.upper_limit.is_finite())
+                .collect::<Vec<_>>();
+            let mut bucket_counts = buckets
+                .iter()
+                .map(|bucket| u64::from(bucket.count))
+                .collect::<Vec<_>>();
+            bucket_counts.push(u64::from(*count).saturating_sub(bucket_counts.iter().sum()));
+            Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    attributes,
+                    time_unix_nano,
+                    count: u64::from(*count),
+                    sum: Some(*sum),
+                    bucket_counts,
+                    explicit_bounds: buckets.iter().map(|bucket| bucket.upper_limit).collect(),
+                    ..Default::default()
+                }],
+                aggregation_temporality: temporality,
+            })
+        }
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => Data::Summary(Summary {
+            data_points: vec![SummaryDataPoint {
+                attributes,
+                time_unix_nano,
+                count: u64::from(*count),
+                sum: *sum,
+                quantile_values: quantiles
+                    .iter()
+                    .map(|quantile| ValueAtQuantile {
+                        quantile: quantile.quantile,
+                        value: quantile.value,
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+        }),
+        MetricValue::Distribution { .. } | MetricValue::Sketch { .. } => {
+            return Err(format!(
+                "The {} metric {:?} can't be encoded as OTLP, it must be aggregated first.",
+                metric.value().as_name(),
+                name
+            )
+            .into())
+        }
+    };
+
+    Ok(ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            resource: (!resource.is_empty()).then(|| Resource {
+                attributes: resource,
+                dropped_attributes_count: 0,
+            }),
+            scope_metrics: vec![ScopeMetrics {
+                scope: Some(scope),
+                metrics: vec![OtlpMetric {
+                    name,
+                    description: String::new(),
+                    unit: String::new(),
+                    data: Some(data),
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    })
+}
+
+fn key_value(key: String, value: String) -> KeyValue {
+    KeyValue {
+        key,
+        value: Some(AnyValue::from(Value::from(value))),
+    }
+}
@@ -0,0 +1,410 @@
+//! Serializes the events into the export requests of the OpenTelemetry protocol (OTLP), the
+//! inverse of the conversions of the `opentelemetry` source.
+
+mod json;
+mod logs;
+mod metrics;
+mod traces;
+
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use bytes::{BufMut, BytesMut};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Encoder;
+
+use crate::{
+    event::{Event, Value},
+    proto::opentelemetry::{
+        common::v1::{InstrumentationScope, KeyValue},
+        fields_into_key_values,
+        resource::v1::Resource,
+    },
+    schema,
+};
+
+/// Config used to build an `OtlpSerializer`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OtlpSerializerConfig {
+    /// Options for the OTLP serializer.
+    pub otlp: OtlpSerializerOptions,
+}
+
+impl OtlpSerializerConfig {
+    /// Creates a new `OtlpSerializerConfig`.
+    pub const fn new(otlp: OtlpSerializerOptions) -> Self {
+        Self { otlp }
+    }
+
+    /// Build the `OtlpSerializer` from this configuration.
+    pub const fn build(&self) -> OtlpSerializer {
+        OtlpSerializer::new(self.otlp)
+    }
+
+    /// The schema required by the serializer.
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Options for building an `OtlpSerializer`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct OtlpSerializerOptions {
+    /// The encoding of the export requests.
+    #[serde(default)]
+    pub format: OtlpFormat,
+}
+
+/// The encodings of the export requests defined by OTLP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OtlpFormat {
+    /// The binary protobuf encoding, as sent over gRPC and by default over HTTP.
+    Protobuf,
+    /// The JSON encoding of protobuf, with trace and span IDs as hexadecimal strings.
+    Json,
+}
+
+impl Default for OtlpFormat {
+    fn default() -> Self {
+        Self::Protobuf
+    }
+}
+
+/// Serializer that converts each `Event` to the OTLP export request of its signal, holding a
+/// single log record, metric or span along with its resource and instrumentation scope.
+///
+/// The fields of the logs and traces are read from the names used by the `opentelemetry` source,
+/// the other fields being kept as attributes. The tags of the metrics prefixed by `resource.` and
+/// `scope.` are those of their resource and scope.
+///
+/// As protobuf messages are merged when concatenated, the encoded requests of several events can
+/// be concatenated into a single request holding all of them.
+#[derive(Debug, Clone)]
+pub struct OtlpSerializer {
+    format: OtlpFormat,
+}
+
+impl OtlpSerializer {
+    /// Creates a new `OtlpSerializer`.
+    pub const fn new(options: OtlpSerializerOptions) -> Self {
+        Self {
+            format: options.format,
+        }
+    }
+}
+
+impl Encoder<Event> for OtlpSerializer {
+    type Error = crate::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        match (event, self.format) {
+            (Event::Log(log), OtlpFormat::Protobuf) => logs::encode(log).encode(buffer)?,
+            (Event::Log(log), OtlpFormat::Json) => {
+                serde_json::to_writer(buffer.writer(), &json::logs(&logs::encode(log)))?
+            }
+            (Event::Metric(metric), OtlpFormat::Protobuf) => {
+                metrics::encode(metric)?.encode(buffer)?
+            }
+            (Event::Metric(metric), OtlpFormat::Json) => {
+                serde_json::to_writer(buffer.writer(), &json::metrics(&metrics::encode(metric)?))?
+            }
+            (Event::Trace(trace), OtlpFormat::Protobuf) => traces::encode(trace).encode(buffer)?,
+            (Event::Trace(trace), OtlpFormat::Json) => {
+                serde_json::to_writer(buffer.writer(), &json::traces(&traces::encode(trace)))?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The fields of a log or trace being converted, those which aren't fields of the OTLP messages,
+/// or can't be converted into them, being kept as attributes.
+struct Fields {
+    fields: BTreeMap<String, Value>,
+    attributes: BTreeMap<String, Value>,
+}
+
+impl Fields {
+    fn new(fields: BTreeMap<String, Value>) -> Self {
+        Self {
+            fields,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    /// Takes the field, keeping it as an attribute if `convert` doesn't accept it.
+    fn take<T>(&mut self, key: &str, convert: impl FnOnce(&Value) -> Option<T>) -> Option<T> {
+        let value = self.fields.remove(key)?;
+        let converted = convert(&value);
+        if converted.is_none() {
+            self.attributes.insert(key.to_owned(), value);
+        }
+        converted
+    }
+
+    /// Takes the `attributes` object, the attributes written to the messages.
+    fn take_attributes(&mut self) {
+        if let Some(attributes) = self.take("attributes", |value| value.as_object().cloned()) {
+            self.attributes.extend(attributes);
+        }
+    }
+
+    /// The attributes along with the fields left.
+    fn into_attributes(mut self) -> Vec<KeyValue> {
+        self.attributes.extend(self.fields);
+        fields_into_key_values(self.attributes)
+    }
+}
+
+/// Converts a timestamp into nanoseconds since the epoch, the timestamps before it being unknown.
+fn unix_nano(value: &Value) -> Option<u64> {
+    value.as_timestamp().and_then(timestamp_unix_nano)
+}
+
+fn timestamp_unix_nano(timestamp: &DateTime<Utc>) -> Option<u64> {
+    u64::try_from(timestamp.timestamp())
+        .ok()?
+        .checked_mul(1_000_000_000)?
+        .checked_add(u64::from(timestamp.timestamp_subsec_nanos()))
+}
+
+/// Decodes the hexadecimal trace and span IDs, of 16 and 8 bytes.
+fn id(value: &Value, len: usize) -> Option<Vec<u8>> {
+    hex::decode(value.as_bytes()?)
+        .ok()
+        .filter(|id| id.len() == len)
+}
+
+fn string(value: &Value) -> Option<String> {
+    value
+        .as_bytes()
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn count(value: &Value) -> Option<u32> {
+    u32::try_from(value.as_integer()?).ok()
+}
+
+fn resource(value: &Value) -> Option<Resource> {
+    value.as_object().map(|attributes| Resource {
+        attributes: fields_into_key_values(attributes.clone()),
+        dropped_attributes_count: 0,
+    })
+}
+
+/// The instrumentation scope of an object with its `name`, `version` and `attributes`.
+fn scope(value: &Value) -> Option<InstrumentationScope> {
+    let mut fields = value.as_object()?.clone();
+    Some(InstrumentationScope {
+        name: fields
+            .remove("name")
+            .as_ref()
+            .and_then(string)
+            .unwrap_or_default(),
+        version: fields
+            .remove("version")
+            .as_ref()
+            .and_then(string)
+            .unwrap_or_default(),
+        attributes: fields
+            .remove("attributes")
+            .and_then(Value::into_object)
+            .map(fields_into_key_values)
+            .unwrap_or_default(),
+        dropped_attributes_count: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vector_common::btreemap;
+
+    use super::*;
+    use crate::{
+        config::log_schema,
+        event::{LogEvent, Metric, MetricKind, MetricValue},
+        proto::opentelemetry::{
+            collector::{
+                logs::v1::ExportLogsServiceRequest, metrics::v1::ExportMetricsServiceRequest,
+            },
+            common::v1::{any_value, AnyValue},
+            metrics::v1::{metric::Data, number_data_point, AggregationTemporality},
+        },
+    };
+
+    fn serializer(format: OtlpFormat) -> OtlpSerializer {
+        OtlpSerializer::new(OtlpSerializerOptions { format })
+    }
+
+    fn string_value(value: &str) -> Option<AnyValue> {
+        Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.into())),
+        })
+    }
+
+    fn log() -> LogEvent {
+        let mut log = LogEvent::from("foo");
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.timestamp(1_650_000_000, 5),
+        );
+        log.insert("severity_text", "INFO");
+        log.insert("severity_number", 9);
+        log.insert("trace_id", "0123456789abcdef0123456789abcdef");
+        log.insert("span_id", "not hex");
+        log.insert("attributes", btreemap! { "a" => 1 });
+        log.insert("resources", btreemap! { "service.name" => "bar" });
+        log.insert("scope", btreemap! { "name" => "baz", "version" => "1.0" });
+        log.insert("host", "localhost");
+        log
+    }
+
+    #[test]
+    fn serialize_log() {
+        let mut bytes = BytesMut::new();
+        serializer(OtlpFormat::Protobuf)
+            .encode(log().into(), &mut bytes)
+            .unwrap();
+
+        let request = ExportLogsServiceRequest::decode(bytes.freeze()).unwrap();
+        let resource_logs = &request.resource_logs[0];
+        assert_eq!(
+            resource_logs.resource.as_ref().unwrap().attributes[0].key,
+            "service.name"
+        );
+        let scope_logs = &resource_logs.scope_logs[0];
+        assert_eq!(scope_logs.scope.as_ref().unwrap().name, "baz");
+        assert_eq!(scope_logs.scope.as_ref().unwrap().version, "1.0");
+
+        let record = &scope_logs.log_records[0];
+        assert_eq!(record.body, string_value("foo"));
+        assert_eq!(record.time_unix_nano, 1_650_000_000_000_000_005);
+        assert_eq!(record.severity_text, "INFO");
+        assert_eq!(record.severity_number, 9);
+        assert_eq!(
+            record.trace_id,
+            hex::decode("0123456789abcdef0123456789abcdef").unwrap()
+        );
+        assert!(record.span_id.is_empty());
+        // The fields left, including those which can't be converted, are kept as attributes.
+        let attributes = record
+            .attributes
+            .iter()
+            .map(|attribute| attribute.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(attributes, vec!["a", "host", "span_id"]);
+    }
+
+    #[test]
+    fn serialize_concatenated_logs() {
+        let mut bytes = BytesMut::new();
+        let mut serializer = serializer(OtlpFormat::Protobuf);
+        serializer.encode(log().into(), &mut bytes).unwrap();
+        serializer
+            .encode(LogEvent::from("bar").into(), &mut bytes)
+            .unwrap();
+
+        let request = ExportLogsServiceRequest::decode(bytes.freeze()).unwrap();
+        assert_eq!(request.resource_logs.len(), 2);
+        assert_eq!(
+            request.resource_logs[1].scope_logs[0].log_records[0].body,
+            string_value("bar")
+        );
+    }
+
+    #[test]
+    fn serialize_log_json() {
+        let mut log = LogEvent::default();
+        log.insert(log_schema().message_key(), "foo");
+        log.insert("trace_id", "0123456789abcdef0123456789abcdef");
+        log.insert("count", 2);
+
+        let mut bytes = BytesMut::new();
+        serializer(OtlpFormat::Json)
+            .encode(log.into(), &mut bytes)
+            .unwrap();
+
+        let request: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "resourceLogs": [{
+                    "scopeLogs": [{
+                        "logRecords": [{
+                            "body": { "stringValue": "foo" },
+                            "attributes": [{ "key": "count", "value": { "intValue": "2" } }],
+                            "traceId": "0123456789abcdef0123456789abcdef"
+                        }]
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_metric() {
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 2.0 },
+        )
+        .with_namespace(Some("http"))
+        .with_tags(Some(
+            vec![
+                ("resource.service.name".to_owned(), "foo".to_owned()),
+                ("scope.name".to_owned(), "bar".to_owned()),
+                ("code".to_owned(), "200".to_owned()),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+        let mut bytes = BytesMut::new();
+        serializer(OtlpFormat::Protobuf)
+            .encode(metric.into(), &mut bytes)
+            .unwrap();
+
+        let request = ExportMetricsServiceRequest::decode(bytes.freeze()).unwrap();
+        let resource_metrics = &request.resource_metrics[0];
+        assert_eq!(
+            resource_metrics.resource.as_ref().unwrap().attributes[0].key,
+            "service.name"
+        );
+        let scope_metrics = &resource_metrics.scope_metrics[0];
+        assert_eq!(scope_metrics.scope.as_ref().unwrap().name, "bar");
+
+        let metric = &scope_metrics.metrics[0];
+        assert_eq!(metric.name, "http.requests");
+        let sum = match &metric.data {
+            Some(Data::Sum(sum)) => sum,
+            data => panic!("Unexpected data {:?}", data),
+        };
+        assert!(sum.is_monotonic);
+        assert_eq!(
+            sum.aggregation_temporality,
+            AggregationTemporality::Delta as i32
+        );
+        assert_eq!(
+            sum.data_points[0].value,
+            Some(number_data_point::Value::AsDouble(2.0))
+        );
+        assert_eq!(sum.data_points[0].attributes[0].key, "code");
+    }
+
+    #[test]
+    fn serialize_distribution_fails() {
+        let metric = Metric::new(
+            "latency",
+            MetricKind::Incremental,
+            MetricValue::Distribution {
+                samples: Vec::new(),
+                statistic: crate::event::metric::StatisticKind::Histogram,
+            },
+        );
+        assert!(serializer(OtlpFormat::Protobuf)
+            .encode(metric.into(), &mut BytesMut::new())
+            .is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use super::{count, id, resource, scope, string, unix_nano, Fields};
+use crate::{
+    config::log_schema,
+    event::{TraceEvent, Value},
+    proto::opentelemetry::{
+        collector::trace::v1::ExportTraceServiceRequest,
+        trace::v1::{
+            span::{Event as SpanEvent, Link, SpanKind},
+            status::StatusCode,
+            ResourceSpans, ScopeSpans, Span, Status,
+        },
+    },
+};
+
+/// Converts the trace event into a span.
+pub(super) fn encode(trace: TraceEvent) -> ExportTraceServiceRequest {
+    let (fields, _) = trace.into_parts();
+    let mut fields = Fields::new(fields);
+    fields.fields.remove(log_schema().source_type_key());
+
+    let resource = fields.take("resources", resource);
+    let scope = fields.take("scope", scope);
+    let mut span = Span {
+        trace_id: fields
+            .take("trace_id", |value| id(value, 16))
+            .unwrap_or_default(),
+        span_id: fields
+            .take("span_id", |value| id(value, 8))
+            .unwrap_or_default(),
+        trace_state: fields.take("trace_state", string).unwrap_or_default(),
+        parent_span_id: fields
+            .take("parent_span_id", |value| id(value, 8))
+            .unwrap_or_default(),
+        flags: fields.take("flags", count).unwrap_or_default(),
+        name: fields.take("name", string).unwrap_or_default(),
+        kind: fields.take("kind", kind).unwrap_or(SpanKind::Unspecified) as i32,
+        start_time_unix_nano: fields
+            .take("start_timestamp", unix_nano)
+            .unwrap_or_default(),
+        end_time_unix_nano: fields.take("end_timestamp", unix_nano).unwrap_or_default(),
+        dropped_attributes_count: fields
+            .take("dropped_attributes_count", count)
+            .unwrap_or_default(),
+        events: fields
+            .take("events", |value| objects(value, convert_event))
+            .unwrap_or_default(),
+        dropped_events_count: fields
+            .take("dropped_events_count", count)
+            .unwrap_or_default(),
+        links: fields
+            .take("links", |value| objects(value, convert_link))
+            .unwrap_or_default(),
+        dropped_links_count: fields
+            .take("dropped_links_count", count)
+            .unwrap_or_default(),
+        status: fields.take("status", status),
+        attributes: Vec::new(),
+    };
+    fields.take_attributes();
+    span.attributes = fields.into_attributes();
+
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource,
+            scope_spans: vec![ScopeSpans {
+                scope,
+                spans: vec![span],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+/// Converts an array of objects, such as the events and links of the spans.
+fn objects<T>(value: &Value, convert: impl Fn(BTreeMap<String, Value>) -> T) -> Option<Vec<T>> {
+    match value {
+        Value::Array(values) => values
+            .iter()
+            .map(|value| value.as_object().cloned().map(&convert))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn convert_event(fields: BTreeMap<String, Value>) -> SpanEvent {
+    let mut fields = Fields::new(fields);
+    let name = fields.take("name", string).unwrap_or_default();
+    let time_unix_nano = fields.take("timestamp", unix_nano).unwrap_or_default();
+    let dropped_attributes_count = fields
+        .take("dropped_attributes_count", count)
+        .unwrap_or_default();
+    fields.take_attributes();
+    SpanEvent {
+        time_unix_nano,
+        name,
+        attributes: fields.into_attributes(),
+        dropped_attributes_count,
+    }
+}
+
+fn convert_link(fields: BTreeMap<String, Value>) -> Link {
+    let mut fields = Fields::new(fields);
+    let trace_id = fields
+        .take("trace_id", |value| id(value, 16))
+        .unwrap_or_default();
+    let span_id = fields
+        .take("span_id", |value| id(value, 8))
+        .unwrap_or_default();
+    let trace_state = fields.take("trace_state", string).unwrap_or_default();
+    let flags = fields.take("flags", count).unwrap_or_default();
+    let dropped_attributes_count = fields
+        .take("dropped_attributes_count", count)
+        .unwrap_or_default();
+    fields.take_attributes();
+    Link {
+        trace_id,
+        span_id,
+        trace_state,
+        attributes: fields.into_attributes(),
+        dropped_attributes_count,
+        flags,
+    }
+}
+
+fn status(value: &Value) -> Option<Status> {
+    let fields = value.as_object()?;
+    let code = match fields.get("code").and_then(string).as_deref() {
+        Some("ok") => StatusCode::Ok,
+        Some("error") => StatusCode::Error,
+        _ => StatusCode::Unset,
+    };
+    Some(Status {
+        message: fields.get("message").and_then(string).unwrap_or_default(),
+        code: code as i32,
+    })
+}
+
+fn kind(value: &Value) -> Option<SpanKind> {
+    match string(value)?.as_str() {
+        "unspecified" => Some(SpanKind::Unspecified),
+        "internal" => Some(SpanKind::Internal),
+        "server" => Some(SpanKind::Server),
+        "client" => Some(SpanKind::Client),
+        "producer" => Some(SpanKind::Producer),
+        "consumer" => Some(SpanKind::Consumer),
+        _ => None,
+    }
+}
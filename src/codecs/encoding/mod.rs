@@ -5,9 +5,11 @@ pub mod format;
 pub mod framing;
 
 pub use format::{
-    BoxedSerializer, JsonSerializer, JsonSerializerConfig, RawMessageSerializer,
-    RawMessageSerializerConfig,
+    BoxedSerializer, JsonSerializer, JsonSerializerConfig, MsgpackSerializer,
+    MsgpackSerializerConfig, RawMessageSerializer, RawMessageSerializerConfig,
 };
+#[cfg(feature = "codecs-otlp")]
+pub use format::{OtlpFormat, OtlpSerializer, OtlpSerializerConfig, OtlpSerializerOptions};
 pub use framing::{
     BoxedFramer, BoxedFramingError, CharacterDelimitedEncoder, CharacterDelimitedEncoderConfig,
     CharacterDelimitedEncoderOptions, NewlineDelimitedEncoder, NewlineDelimitedEncoderConfig,
@@ -129,6 +131,18 @@ impl tokio_util::codec::Encoder<()> for Framer {
 pub enum SerializerConfig {
     /// Configures the `JsonSerializer`.
     Json,
+    /// Configures the `MsgpackSerializer`.
+    Msgpack,
+    #[cfg(feature = "codecs-otlp")]
+    /// Configures the `OtlpSerializer`.
+    Otlp {
+        #[serde(
+            default,
+            skip_serializing_if = "crate::serde::skip_serializing_if_default"
+        )]
+        /// Options for the OTLP serializer.
+        otlp: OtlpSerializerOptions,
+    },
     /// Configures the `RawMessageSerializer`.
     RawMessage,
 }
@@ -139,6 +153,19 @@ impl From<JsonSerializerConfig> for SerializerConfig {
     }
 }
 
+impl From<MsgpackSerializerConfig> for SerializerConfig {
+    fn from(_: MsgpackSerializerConfig) -> Self {
+        Self::Msgpack
+    }
+}
+
+#[cfg(feature = "codecs-otlp")]
+impl From<OtlpSerializerConfig> for SerializerConfig {
+    fn from(config: OtlpSerializerConfig) -> Self {
+        Self::Otlp { otlp: config.otlp }
+    }
+}
+
 impl From<RawMessageSerializerConfig> for SerializerConfig {
     fn from(_: RawMessageSerializerConfig) -> Self {
         Self::RawMessage
@@ -150,6 +177,11 @@ impl SerializerConfig {
     pub const fn build(&self) -> Serializer {
         match self {
             SerializerConfig::Json => Serializer::Json(JsonSerializerConfig.build()),
+            SerializerConfig::Msgpack => Serializer::Msgpack(MsgpackSerializerConfig.build()),
+            #[cfg(feature = "codecs-otlp")]
+            SerializerConfig::Otlp { otlp } => {
+                Serializer::Otlp(OtlpSerializerConfig::new(*otlp).build())
+            }
             SerializerConfig::RawMessage => {
                 Serializer::RawMessage(RawMessageSerializerConfig.build())
             }
@@ -160,6 +192,11 @@ impl SerializerConfig {
     pub fn schema_requirement(&self) -> schema::Requirement {
         match self {
             SerializerConfig::Json => JsonSerializerConfig.schema_requirement(),
+            SerializerConfig::Msgpack => MsgpackSerializerConfig.schema_requirement(),
+            #[cfg(feature = "codecs-otlp")]
+            SerializerConfig::Otlp { otlp } => {
+                OtlpSerializerConfig::new(*otlp).schema_requirement()
+            }
             SerializerConfig::RawMessage => RawMessageSerializerConfig.schema_requirement(),
         }
     }
@@ -170,16 +207,39 @@ impl SerializerConfig {
 pub enum Serializer {
     /// Uses a `JsonSerializer` for deserialization.
     Json(JsonSerializer),
+    /// Uses a `MsgpackSerializer` for serialization.
+    Msgpack(MsgpackSerializer),
+    #[cfg(feature = "codecs-otlp")]
+    /// Uses an `OtlpSerializer` for serialization.
+    Otlp(OtlpSerializer),
     /// Uses a `RawMessageSerializer` for deserialization.
     RawMessage(RawMessageSerializer),
 }
 
+impl Serializer {
+    /// Orders the top-level fields of the serialized logs, for the serializers supporting it,
+    /// currently the `JsonSerializer`.
+    pub fn with_field_order(self, field_order: Vec<String>) -> Self {
+        match self {
+            Serializer::Json(serializer) => {
+                Serializer::Json(serializer.with_field_order(field_order))
+            }
+            serializer @ (Serializer::Msgpack(_) | Serializer::RawMessage(_)) => serializer,
+            #[cfg(feature = "codecs-otlp")]
+            serializer @ Serializer::Otlp(_) => serializer,
+        }
+    }
+}
+
 impl tokio_util::codec::Encoder<Event> for Serializer {
     type Error = crate::Error;
 
     fn encode(&mut self, item: Event, dst: &mut BytesMut) -> Result<(), Self::Error> {
         match self {
             Serializer::Json(serializer) => serializer.encode(item, dst),
+            Serializer::Msgpack(serializer) => serializer.encode(item, dst),
+            #[cfg(feature = "codecs-otlp")]
+            Serializer::Otlp(serializer) => serializer.encode(item, dst),
             Serializer::RawMessage(serializer) => serializer.encode(item, dst),
         }
     }
@@ -9,16 +9,20 @@ mod ready_frames;
 
 pub use decoding::{
     BytesDecoder, BytesDecoderConfig, BytesDeserializer, BytesDeserializerConfig,
-    CharacterDelimitedDecoder, CharacterDelimitedDecoderConfig, Decoder, JsonDeserializer,
-    JsonDeserializerConfig, LengthDelimitedDecoder, LengthDelimitedDecoderConfig,
-    NewlineDelimitedDecoder, NewlineDelimitedDecoderConfig, OctetCountingDecoder,
-    OctetCountingDecoderConfig,
+    CharacterDelimitedDecoder, CharacterDelimitedDecoderConfig, CsvDeserializer,
+    CsvDeserializerConfig, Decoder, JsonDeserializer, JsonDeserializerConfig,
+    LengthDelimitedDecoder, LengthDelimitedDecoderConfig, MsgpackDeserializer,
+    MsgpackDeserializerConfig, NewlineDelimitedDecoder, NewlineDelimitedDecoderConfig,
+    OctetCountingDecoder, OctetCountingDecoderConfig, OctetCountingDetection, ProtobufDeserializer,
+    ProtobufDeserializerConfig,
 };
 #[cfg(feature = "sources-syslog")]
 pub use decoding::{SyslogDeserializer, SyslogDeserializerConfig};
 pub use encoding::{
     CharacterDelimitedEncoder, CharacterDelimitedEncoderConfig, JsonSerializer,
-    JsonSerializerConfig, NewlineDelimitedEncoder, NewlineDelimitedEncoderConfig,
-    RawMessageSerializer, RawMessageSerializerConfig,
+    JsonSerializerConfig, MsgpackSerializer, MsgpackSerializerConfig, NewlineDelimitedEncoder,
+    NewlineDelimitedEncoderConfig, RawMessageSerializer, RawMessageSerializerConfig,
 };
+#[cfg(feature = "codecs-otlp")]
+pub use encoding::{OtlpFormat, OtlpSerializer, OtlpSerializerConfig, OtlpSerializerOptions};
 pub use ready_frames::ReadyFrames;
@@ -1,8 +1,13 @@
-use std::net::{Ipv4Addr, SocketAddr};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    num::NonZeroU64,
+};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+use crate::{http::Auth, tls::TlsConfig};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct Options {
     #[serde(default = "default_enabled")]
@@ -13,6 +18,19 @@ pub struct Options {
 
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    /// TLS settings for the API server. When enabled, both GraphQL queries and
+    /// WebSocket subscriptions are served over TLS.
+    pub tls: Option<TlsConfig>,
+
+    /// Credentials required in the `Authorization` header of every GraphQL request,
+    /// including the upgrade request of WebSocket subscriptions.
+    pub auth: Option<Auth>,
+
+    /// Traces one in this many events through the components they traverse, with how long they
+    /// spent in each of them, for the `eventTrace` subscription. Disabled by default, as it adds
+    /// some overhead to every component.
+    pub event_tracing_sample_rate: Option<NonZeroU64>,
 }
 
 impl Default for Options {
@@ -21,6 +39,9 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            tls: None,
+            auth: None,
+            event_tracing_sample_rate: None,
         }
     }
 }
@@ -61,10 +82,40 @@ impl Options {
             }
         };
 
+        let tls = match (self.tls.clone(), other.tls) {
+            (Some(a), Some(b)) if a != b => {
+                return Err("Conflicting `api` TLS settings.".to_owned())
+            }
+            (a, b) => a.or(b),
+        };
+
+        let auth = match (self.auth.clone(), other.auth) {
+            (Some(a), Some(b)) if a != b => {
+                return Err("Conflicting `api` auth settings.".to_owned())
+            }
+            (a, b) => a.or(b),
+        };
+
+        let event_tracing_sample_rate = match (
+            self.event_tracing_sample_rate,
+            other.event_tracing_sample_rate,
+        ) {
+            (Some(a), Some(b)) if a != b => {
+                return Err(format!(
+                    "Conflicting `api` event tracing sample rates: {}, {} .",
+                    a, b
+                ))
+            }
+            (a, b) => a.or(b),
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            tls,
+            auth,
+            event_tracing_sample_rate,
         };
 
         *self = options;
@@ -78,6 +129,7 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        ..Options::default()
     };
 
     a.merge(Options::default()).unwrap();
@@ -88,6 +140,7 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            ..Options::default()
         }
     );
 }
@@ -99,6 +152,7 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        ..Options::default()
     };
 
     a.merge(Options::default()).unwrap();
@@ -109,6 +163,7 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            ..Options::default()
         }
     );
 }
@@ -127,3 +182,37 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn auth_merge() {
+    let auth = Auth::Bearer {
+        token: "abc".to_owned(),
+    };
+    let mut a = Options {
+        auth: Some(auth.clone()),
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+    assert_eq!(a.auth, Some(auth));
+}
+
+#[test]
+fn auth_conflict() {
+    let mut a = Options {
+        auth: Some(Auth::Bearer {
+            token: "abc".to_owned(),
+        }),
+        ..Options::default()
+    };
+
+    let b = Options {
+        auth: Some(Auth::Basic {
+            user: "vector".to_owned(),
+            password: "secret".to_owned(),
+        }),
+        ..Options::default()
+    };
+
+    assert!(a.merge(b).is_err());
+}
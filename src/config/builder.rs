@@ -12,9 +12,10 @@ use super::api;
 use super::datadog;
 use super::{
     compiler, provider, schema, ComponentKey, Config, EnrichmentTableConfig, EnrichmentTableOuter,
-    HealthcheckOptions, SinkConfig, SinkOuter, SourceConfig, SourceOuter, TestDefinition,
-    TransformOuter,
+    FragmentOuter, HealthcheckOptions, SinkConfig, SinkOuter, SourceConfig, SourceOuter,
+    TestDefinition, TransformOuter,
 };
+use crate::secrets::SecretBackendOuter;
 
 #[derive(Deserialize, Serialize, Debug, Default)]
 #[serde(deny_unknown_fields)]
@@ -40,6 +41,10 @@ pub struct ConfigBuilder {
     #[serde(default)]
     pub transforms: IndexMap<ComponentKey, TransformOuter<String>>,
     #[serde(default)]
+    pub fragments: IndexMap<String, FragmentOuter>,
+    #[serde(default)]
+    pub secret: IndexMap<ComponentKey, SecretBackendOuter>,
+    #[serde(default)]
     pub tests: Vec<TestDefinition<String>>,
     pub provider: Option<Box<dyn provider::ProviderConfig>>,
 }
@@ -113,6 +118,8 @@ impl From<Config> for ConfigBuilder {
             sources,
             sinks,
             transforms,
+            fragments: IndexMap::new(),
+            secret: IndexMap::new(),
             provider: None,
             tests,
         }
@@ -181,6 +188,7 @@ impl ConfigBuilder {
         let transform = TransformOuter {
             inner: Box::new(transform),
             inputs,
+            limits: Default::default(),
         };
 
         self.transforms
@@ -226,6 +234,16 @@ impl ConfigBuilder {
 
         self.global.proxy = self.global.proxy.merge(&with.global.proxy);
 
+        if self.global.reload_drain_timeout_secs.is_some()
+            && with.global.reload_drain_timeout_secs.is_some()
+        {
+            errors.push("conflicting values for 'reload_drain_timeout_secs' found".to_owned());
+        }
+        self.global.reload_drain_timeout_secs = self
+            .global
+            .reload_drain_timeout_secs
+            .or(with.global.reload_drain_timeout_secs);
+
         if self.global.data_dir.is_none() || self.global.data_dir == default_data_dir() {
             self.global.data_dir = with.global.data_dir;
         } else if with.global.data_dir != default_data_dir()
@@ -264,6 +282,16 @@ impl ConfigBuilder {
                 errors.push(format!("duplicate transform id found: {}", k));
             }
         });
+        with.fragments.keys().for_each(|k| {
+            if self.fragments.contains_key(k) {
+                errors.push(format!("duplicate fragment name found: {}", k));
+            }
+        });
+        with.secret.keys().for_each(|k| {
+            if self.secret.contains_key(k) {
+                errors.push(format!("duplicate secret id found: {}", k));
+            }
+        });
         with.tests.iter().for_each(|wt| {
             if self.tests.iter().any(|t| t.name == wt.name) {
                 errors.push(format!("duplicate test name found: {}", wt.name));
@@ -277,6 +305,8 @@ impl ConfigBuilder {
         self.sources.extend(with.sources);
         self.sinks.extend(with.sinks);
         self.transforms.extend(with.transforms);
+        self.fragments.extend(with.fragments);
+        self.secret.extend(with.secret);
         self.tests.extend(with.tests);
 
         Ok(())
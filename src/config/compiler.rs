@@ -3,7 +3,8 @@ use std::collections::HashSet;
 use indexmap::{IndexMap, IndexSet};
 
 use super::{
-    builder::ConfigBuilder, graph::Graph, schema, validation, ComponentKey, Config, OutputId,
+    builder::ConfigBuilder, fragment, graph::Graph, schema, validation, ComponentKey, Config,
+    OutputId,
 };
 
 pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<String>> {
@@ -22,6 +23,7 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         errors.extend(name_errors);
     }
 
+    fragment::resolve(&builder.fragments, &mut builder.transforms)?;
     let expansions = expand_macros(&mut builder)?;
 
     expand_globs(&mut builder);
@@ -38,6 +40,10 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         errors.extend(output_errors);
     }
 
+    if let Err(timeout_errors) = validation::check_acknowledgement_timeouts(&builder) {
+        errors.extend(timeout_errors);
+    }
+
     #[cfg(feature = "datadog-pipelines")]
     let version = Some(builder.sha256_hash());
 
@@ -56,6 +62,8 @@ pub fn compile(mut builder: ConfigBuilder) -> Result<(Config, Vec<String>), Vec<
         sources,
         sinks,
         transforms,
+        fragments: _,
+        secret: _,
         tests,
         provider: _,
     } = builder;
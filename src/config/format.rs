@@ -38,6 +38,23 @@ impl Format {
     }
 }
 
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    /// Obtain the format from its name, as given on the command line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "toml" => Ok(Format::Toml),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "Format {:?} not supported, expected one of: toml, yaml, json.",
+                s
+            )),
+        }
+    }
+}
+
 /// Parse the string represented in the specified format.
 /// If the format is unknown - fallback to the default format and attempt
 /// parsing using that.
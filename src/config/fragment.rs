@@ -0,0 +1,208 @@
+//! Pipeline fragments: named chains of transforms, defined once and used by any number of
+//! `fragment` transforms, each of them expanding into its own copy of the chain.
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ComponentKey, DataType, ExpandType, Input, Output, TransformConfig, TransformContext,
+    TransformOuter,
+};
+use crate::{schema, transforms::Transform};
+
+/// A named chain of transforms.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FragmentOuter {
+    pub steps: Vec<FragmentStep>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FragmentStep {
+    id: Option<String>,
+
+    #[serde(flatten)]
+    transform: Box<dyn TransformConfig>,
+}
+
+/// A use of a fragment, expanded into the fragment's transforms, each of them being given the ID
+/// of its step under the ID of this transform. The last transform of the chain is aliased to the
+/// ID of this transform, so that other components can consume its output.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FragmentConfig {
+    fragment: String,
+
+    // Resolved from the fragments of the config before expanding.
+    #[serde(skip)]
+    steps: Vec<FragmentStep>,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "fragment")]
+impl TransformConfig for FragmentConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Err("this transform must be expanded".into())
+    }
+
+    fn expand(
+        &mut self,
+    ) -> crate::Result<Option<(IndexMap<String, Box<dyn TransformConfig>>, ExpandType)>> {
+        if self.steps.is_empty() {
+            return Err(format!("fragment \"{}\" has no steps", self.fragment).into());
+        }
+
+        let mut map: IndexMap<String, Box<dyn TransformConfig>> = IndexMap::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            let id = step.id.clone().unwrap_or_else(|| i.to_string());
+            if map.insert(id, step.transform.clone()).is_some() {
+                return Err(format!(
+                    "conflicting step id found in fragment \"{}\"",
+                    self.fragment
+                )
+                .into());
+            }
+        }
+
+        Ok(Some((map, ExpandType::Serial { alias: true })))
+    }
+
+    fn nestable(&self, parents: &HashSet<&'static str>) -> bool {
+        // Only the fragments used at the top level of the config are resolved.
+        !parents.contains("fragment")
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::all())]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "fragment"
+    }
+}
+
+/// Resolves the fragment used by each `fragment` transform, so that they can be expanded.
+pub(super) fn resolve(
+    fragments: &IndexMap<String, FragmentOuter>,
+    transforms: &mut IndexMap<ComponentKey, TransformOuter<String>>,
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    for (key, transform) in transforms.iter_mut() {
+        if transform.inner.transform_type() != "fragment" {
+            continue;
+        }
+
+        // Transforms are only known as trait objects at this point, so the name of the fragment is
+        // read back from their serialized form.
+        let fragment = serde_json::to_value(&transform.inner)
+            .ok()
+            .and_then(|value| value.get("fragment")?.as_str().map(ToOwned::to_owned))
+            .expect("fragment transforms always name their fragment");
+
+        match fragments.get(&fragment) {
+            Some(outer) => {
+                transform.inner = Box::new(FragmentConfig {
+                    fragment,
+                    steps: outer.steps.clone(),
+                });
+            }
+            None => errors.push(format!(
+                "Transform \"{}\" uses unknown fragment \"{}\"",
+                key, fragment
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(all(test, feature = "sources-stdin", feature = "sinks-console"))]
+mod tests {
+    use crate::config::{ComponentKey, ConfigBuilder};
+
+    #[test]
+    fn expands_fragments_for_each_use() {
+        let config = ConfigBuilder::from_toml(
+            r#"
+            [fragments.normalize]
+            [[fragments.normalize.steps]]
+            type = "noop"
+
+            [[fragments.normalize.steps]]
+            id = "last"
+            type = "noop"
+
+            [sources.in1]
+            type = "stdin"
+
+            [sources.in2]
+            type = "stdin"
+
+            [transforms.normalized1]
+            type = "fragment"
+            fragment = "normalize"
+            inputs = ["in1"]
+
+            [transforms.normalized2]
+            type = "fragment"
+            fragment = "normalize"
+            inputs = ["in2"]
+
+            [sinks.out]
+            type = "console"
+            inputs = ["normalized1", "normalized2"]
+            encoding = "json"
+            "#,
+        )
+        .build()
+        .unwrap();
+
+        for id in ["normalized1", "normalized2"] {
+            let key = ComponentKey::from(id);
+            assert!(config.transforms.contains_key(&key.join("0")));
+            assert!(config.transforms.contains_key(&key.join("last")));
+            assert_eq!(
+                config.transforms[&key].inputs,
+                vec![key.join("last").into()]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_fragments() {
+        let errors = ConfigBuilder::from_toml(
+            r#"
+            [sources.in]
+            type = "stdin"
+
+            [transforms.normalized]
+            type = "fragment"
+            fragment = "normalize"
+            inputs = ["in"]
+
+            [sinks.out]
+            type = "console"
+            inputs = ["normalized"]
+            encoding = "json"
+            "#,
+        )
+        .build()
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec!["Transform \"normalized\" uses unknown fragment \"normalize\"".to_owned()]
+        );
+    }
+}
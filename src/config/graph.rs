@@ -4,6 +4,7 @@ use indexmap::{set::IndexSet, IndexMap};
 
 use super::{
     schema, ComponentKey, DataType, Output, OutputId, SinkOuter, SourceOuter, TransformOuter,
+    DEAD_LETTER_OUTPUT,
 };
 
 #[derive(Debug, Clone)]
@@ -107,6 +108,14 @@ impl Graph {
             }
         }
 
+        for (id, config) in sinks.iter() {
+            if let Some(dead_letter) = &config.dead_letter {
+                if let Err(e) = graph.add_dead_letter(id, dead_letter) {
+                    errors.push(e);
+                }
+            }
+        }
+
         if ignore_errors || errors.is_empty() {
             Ok(graph)
         } else {
@@ -139,6 +148,32 @@ impl Graph {
         }
     }
 
+    /// Routes the events a sink fails to deliver to the given component, as if the component had
+    /// the `dead_letter` output of the sink as input.
+    fn add_dead_letter(&mut self, from: &ComponentKey, to: &ComponentKey) -> Result<(), String> {
+        match self.nodes.get(to) {
+            Some(Node::Transform { .. } | Node::Sink { .. }) if from != to => {
+                self.edges.push(Edge {
+                    from: OutputId::from((from, DEAD_LETTER_OUTPUT.to_owned())),
+                    to: to.clone(),
+                });
+                Ok(())
+            }
+            Some(Node::Source { .. }) => Err(format!(
+                "Dead letter \"{}\" for sink \"{}\" is a source.",
+                to, from
+            )),
+            Some(_) => Err(format!(
+                "Dead letter for sink \"{}\" can't be the sink itself.",
+                from
+            )),
+            None => Err(format!(
+                "Dead letter \"{}\" for sink \"{}\" doesn't match any components.",
+                to, from
+            )),
+        }
+    }
+
     /// Return the input type of a given component.
     ///
     /// # Panics
@@ -157,8 +192,8 @@ impl Graph {
     ///
     /// # Panics
     ///
-    /// Will panic if the given id is not present in the graph or identifies a sink, which can't
-    /// have inputs.
+    /// Will panic if the given id is not present in the graph or identifies a sink output other
+    /// than its dead letter.
     fn get_output_type(&self, id: &OutputId) -> DataType {
         match &self.nodes[&id.component] {
             Node::Source { outputs } | Node::Transform { outputs, .. } => outputs
@@ -166,6 +201,8 @@ impl Graph {
                 .find(|output| output.port == id.port)
                 .map(|output| output.ty)
                 .expect("output didn't exist"),
+            // The events a sink fails to deliver are routed unchanged.
+            Node::Sink { ty } if id.port.as_deref() == Some(DEAD_LETTER_OUTPUT) => *ty,
             Node::Sink { .. } => panic!("no outputs on sinks"),
         }
     }
@@ -726,4 +763,57 @@ mod test {
         assert_eq!(paths[0], vec!["source", "t1", "t3", "sink1"]);
         assert_eq!(paths[1], vec!["source", "t1", "t2", "sink1"]);
     }
+
+    #[test]
+    fn dead_letter_outputs() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("primary", DataType::Log, vec!["in"]);
+        graph.add_transform("remap", DataType::Log, DataType::Log, vec![]);
+        graph.add_sink("fallback", DataType::all(), vec![]);
+
+        graph
+            .add_dead_letter(&"primary".into(), &"remap".into())
+            .unwrap();
+        graph
+            .add_dead_letter(&"primary".into(), &"fallback".into())
+            .unwrap();
+        assert_eq!(
+            graph.inputs_for(&"remap".into()),
+            vec![OutputId::from((
+                &ComponentKey::from("primary"),
+                "dead_letter".to_owned()
+            ))]
+        );
+        assert_eq!(Ok(()), graph.typecheck());
+        assert_eq!(Ok(()), graph.check_for_cycles());
+
+        assert_eq!(
+            Err("Dead letter \"in\" for sink \"primary\" is a source.".into()),
+            graph.add_dead_letter(&"primary".into(), &"in".into())
+        );
+        assert_eq!(
+            Err("Dead letter for sink \"primary\" can't be the sink itself.".into()),
+            graph.add_dead_letter(&"primary".into(), &"primary".into())
+        );
+        assert_eq!(
+            Err("Dead letter \"nope\" for sink \"primary\" doesn't match any components.".into()),
+            graph.add_dead_letter(&"primary".into(), &"nope".into())
+        );
+    }
+
+    #[test]
+    fn dead_letter_cycles() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("one", DataType::Log, vec!["in"]);
+        graph.add_transform("remap", DataType::Log, DataType::Log, vec![]);
+        graph.add_sink("two", DataType::Log, vec!["remap"]);
+
+        graph
+            .add_dead_letter(&"one".into(), &"remap".into())
+            .unwrap();
+        graph.add_dead_letter(&"two".into(), &"one".into()).unwrap();
+        assert!(graph.check_for_cycles().is_err());
+    }
 }
@@ -4,26 +4,42 @@ use crate::config::{
     ComponentKey, ConfigBuilder, EnrichmentTableOuter, SinkOuter, SourceOuter, TestDefinition,
     TransformOuter,
 };
+use crate::secrets;
 use indexmap::IndexMap;
-use std::io::Read;
+use std::{collections::HashMap, io::Read};
 use toml::value::Table;
 
 pub struct ConfigBuilderLoader {
     builder: ConfigBuilder,
+    secrets: Option<HashMap<String, String>>,
 }
 
 impl ConfigBuilderLoader {
     pub fn new() -> Self {
         Self {
             builder: ConfigBuilder::default(),
+            secrets: None,
+        }
+    }
+
+    /// Creates a loader replacing the secrets referenced in the config with the given values.
+    pub fn with_secrets(secrets: HashMap<String, String>) -> Self {
+        Self {
+            builder: ConfigBuilder::default(),
+            secrets: Some(secrets),
         }
     }
 }
 
 impl Process for ConfigBuilderLoader {
-    /// Prepares input for a `ConfigBuilder` by interpolating environment variables.
+    /// Prepares input for a `ConfigBuilder` by interpolating environment variables, and then
+    /// secrets, if they were retrieved.
     fn prepare<R: Read>(&self, input: R) -> Result<(String, Vec<String>), Vec<String>> {
-        prepare_input(input)
+        let (config_string, warnings) = prepare_input(input)?;
+        match &self.secrets {
+            Some(secrets) => Ok((secrets::interpolate(&config_string, secrets)?, warnings)),
+            None => Ok((config_string, warnings)),
+        }
     }
 
     /// Merge a TOML `Table` with a `ConfigBuilder`. Component types extend specific keys.
@@ -1,5 +1,6 @@
 mod config_builder;
 mod loader;
+mod secret;
 mod source;
 
 use std::{
@@ -22,6 +23,7 @@ use once_cell::sync::Lazy;
 
 pub use config_builder::*;
 pub use loader::*;
+pub use secret::*;
 pub use source::*;
 
 pub static CONFIG_PATHS: Lazy<Mutex<Vec<ConfigPath>>> = Lazy::new(Mutex::default);
@@ -134,7 +136,7 @@ pub async fn load_from_paths_with_provider(
     config_paths: &[ConfigPath],
     signal_handler: &mut signal::SignalHandler,
 ) -> Result<Config, Vec<String>> {
-    let (mut builder, load_warnings) = load_builder_from_paths(config_paths)?;
+    let (mut builder, load_warnings) = load_builder_and_secrets_from_paths(config_paths).await?;
     validation::check_provider(&builder)?;
     signal_handler.clear();
 
@@ -201,6 +203,21 @@ pub fn load_builder_from_paths(
     loader_from_paths(ConfigBuilderLoader::new(), config_paths)
 }
 
+/// Uses `SecretBackendLoader` to retrieve the secrets referenced in the `ConfigPaths` from their
+/// backends, and then `ConfigBuilderLoader` to deserialize them to a `ConfigBuilder`, replacing
+/// the secrets with their values.
+pub async fn load_builder_and_secrets_from_paths(
+    config_paths: &[ConfigPath],
+) -> Result<(ConfigBuilder, Vec<String>), Vec<String>> {
+    let (secret_loader, _) = loader_from_paths(SecretBackendLoader::new(), config_paths)?;
+    if secret_loader.has_secrets_to_retrieve() {
+        let secrets = secret_loader.retrieve().await?;
+        loader_from_paths(ConfigBuilderLoader::with_secrets(secrets), config_paths)
+    } else {
+        load_builder_from_paths(config_paths)
+    }
+}
+
 /// Uses `SourceLoader` to process `ConfigPaths`, deserializing to a toml `SourceMap`.
 pub fn load_source_from_paths(
     config_paths: &[ConfigPath],
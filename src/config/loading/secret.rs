@@ -0,0 +1,75 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    io::Read,
+};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use toml::value::Table;
+
+use super::{deserialize_table, loader, prepare_input, ComponentHint, Process};
+use crate::{
+    config::ComponentKey,
+    secrets::{self, SecretBackendOuter},
+};
+
+/// Loads the secret backends of a config, along with the keys of the secrets it references, so
+/// that they can be retrieved before the config itself is loaded.
+#[derive(Debug, Default, Deserialize)]
+pub struct SecretBackendLoader {
+    #[serde(default)]
+    backends: IndexMap<ComponentKey, SecretBackendOuter>,
+    #[serde(skip)]
+    secret_keys: RefCell<HashMap<String, HashSet<String>>>,
+}
+
+impl SecretBackendLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn has_secrets_to_retrieve(&self) -> bool {
+        !self.secret_keys.borrow().is_empty()
+    }
+
+    /// Retrieves the referenced secrets from their backends, keyed by `<backend>.<key>`.
+    pub async fn retrieve(self) -> Result<HashMap<String, String>, Vec<String>> {
+        secrets::retrieve(&self.backends, self.secret_keys.into_inner()).await
+    }
+}
+
+impl Process for SecretBackendLoader {
+    /// Prepares input by interpolating environment variables, as secret backends can be
+    /// configured with them, and collecting the secrets it references.
+    fn prepare<R: Read>(&self, input: R) -> Result<(String, Vec<String>), Vec<String>> {
+        let (config_string, warnings) = prepare_input(input)?;
+        secrets::collect_secret_keys(&config_string, &mut self.secret_keys.borrow_mut());
+        Ok((config_string, warnings))
+    }
+
+    /// Merges the secret backends found at the top level of the config, ignoring everything else.
+    fn merge(&mut self, mut table: Table, hint: Option<ComponentHint>) -> Result<(), Vec<String>> {
+        if hint.is_none() {
+            if let Some(toml::Value::Table(backends)) = table.remove("secret") {
+                let backends: IndexMap<ComponentKey, SecretBackendOuter> =
+                    deserialize_table(backends)?;
+                for key in backends.keys() {
+                    if self.backends.contains_key(key) {
+                        return Err(vec![format!("duplicate secret id found: {}", key)]);
+                    }
+                }
+                self.backends.extend(backends);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl loader::Loader<SecretBackendLoader> for SecretBackendLoader {
+    /// Returns the loaded secret backends, along with the secrets to retrieve from them.
+    fn take(self) -> SecretBackendLoader {
+        self
+    }
+}
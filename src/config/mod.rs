@@ -13,7 +13,10 @@ use serde::{Deserialize, Serialize};
 pub use vector_core::config::{AcknowledgementsConfig, DataType, GlobalOptions, Input, Output};
 pub use vector_core::transform::{ExpandType, TransformConfig, TransformContext};
 
-use crate::{conditions, event::Metric};
+use crate::{
+    conditions,
+    event::{Metric, MetricKind},
+};
 
 pub mod api;
 mod builder;
@@ -24,6 +27,7 @@ pub mod component;
 pub mod datadog;
 mod diff;
 pub mod format;
+mod fragment;
 mod graph;
 mod id;
 mod loading;
@@ -41,14 +45,18 @@ pub use builder::ConfigBuilder;
 pub use cmd::{cmd, Opts};
 pub use diff::ConfigDiff;
 pub use format::{Format, FormatHint};
+pub use fragment::FragmentOuter;
 pub use id::{ComponentKey, OutputId};
 pub use loading::{
-    load, load_builder_from_paths, load_from_paths, load_from_paths_with_provider, load_from_str,
-    load_source_from_paths, merge_path_lists, process_paths, CONFIG_PATHS,
+    load, load_builder_and_secrets_from_paths, load_builder_from_paths, load_from_paths,
+    load_from_paths_with_provider, load_from_str, load_source_from_paths, merge_path_lists,
+    prepare_input, process_paths, CONFIG_PATHS,
+};
+pub use sink::{
+    SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter, DEAD_LETTER_OUTPUT,
 };
-pub use sink::{SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter};
 pub use source::{SourceConfig, SourceContext, SourceDescription, SourceOuter};
-pub use transform::{TransformDescription, TransformOuter};
+pub use transform::{ComponentLimits, TransformDescription, TransformOuter};
 pub use unit_test::{build_unit_tests, build_unit_tests_main, UnitTestResult};
 pub use validation::warnings;
 pub use vector_core::config::{log_schema, proxy::ProxyConfig, LogSchema};
@@ -176,6 +184,15 @@ impl Config {
                     .map(|input| (sink.clone(), input.clone()))
                     .collect();
                 self.propagate_acks_rec(inputs);
+            } else if let Some(failing) = self.sinks.get(component) {
+                // The events routed to the dead letter of a sink hold the finalizers of the
+                // events of the sink.
+                let inputs = failing
+                    .inputs
+                    .iter()
+                    .map(|input| (sink.clone(), input.clone()))
+                    .collect();
+                self.propagate_acks_rec(inputs);
             }
         }
     }
@@ -257,6 +274,7 @@ pub enum Resource {
     SystemFdOffset(usize),
     Stdin,
     DiskBuffer(String),
+    InternalMetricsLimits,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Copy)]
@@ -332,6 +350,7 @@ impl Display for Resource {
             Resource::SystemFdOffset(offset) => write!(fmt, "systemd {}th socket", offset + 1),
             Resource::Stdin => write!(fmt, "stdin"),
             Resource::DiskBuffer(name) => write!(fmt, "disk buffer {:?}", name),
+            Resource::InternalMetricsLimits => write!(fmt, "internal metrics tag limits"),
         }
     }
 }
@@ -347,6 +366,7 @@ pub struct TestDefinition<T = OutputId> {
     pub outputs: Vec<TestOutput<T>>,
     #[serde(default)]
     pub no_outputs_from: Vec<T>,
+    pub snapshot: Option<TestSnapshot>,
 }
 
 impl TestDefinition<String> {
@@ -360,6 +380,7 @@ impl TestDefinition<String> {
             inputs,
             outputs,
             no_outputs_from,
+            snapshot,
         } = self;
         let mut errors = Vec::new();
 
@@ -368,18 +389,35 @@ impl TestDefinition<String> {
         let outputs = outputs
             .into_iter()
             .filter_map(|old| {
-                if let Some(output_id) = output_map.get(&old.extract_from) {
-                    Some(TestOutput {
-                        extract_from: output_id.clone(),
-                        conditions: old.conditions,
-                    })
-                } else {
+                if old.extract_from.as_slice().is_empty() {
                     errors.push(format!(
-                        r#"Invalid extract_from target in test '{}': '{}' does not exist"#,
-                        name, old.extract_from
+                        r#"Invalid extract_from target in test '{}': no outputs given"#,
+                        name
                     ));
-                    None
+                    return None;
                 }
+
+                let extract_from = old
+                    .extract_from
+                    .as_slice()
+                    .iter()
+                    .filter_map(|target| {
+                        if let Some(output_id) = output_map.get(target) {
+                            Some(output_id.clone())
+                        } else {
+                            errors.push(format!(
+                                r#"Invalid extract_from target in test '{}': '{}' does not exist"#,
+                                name, target
+                            ));
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                (extract_from.len() == old.extract_from.as_slice().len()).then(|| TestOutput {
+                    extract_from: old.extract_from.map(extract_from),
+                    conditions: old.conditions,
+                    metrics: old.metrics,
+                })
             })
             .collect();
 
@@ -405,6 +443,7 @@ impl TestDefinition<String> {
                 inputs,
                 outputs,
                 no_outputs_from,
+                snapshot,
             })
         } else {
             Err(errors)
@@ -420,13 +459,23 @@ impl TestDefinition<OutputId> {
             inputs,
             outputs,
             no_outputs_from,
+            snapshot,
         } = self;
 
         let outputs = outputs
             .into_iter()
-            .map(|old| TestOutput {
-                extract_from: old.extract_from.to_string(),
-                conditions: old.conditions,
+            .map(|old| {
+                let extract_from = old
+                    .extract_from
+                    .as_slice()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                TestOutput {
+                    extract_from: old.extract_from.map(extract_from),
+                    conditions: old.conditions,
+                    metrics: old.metrics,
+                }
             })
             .collect();
 
@@ -438,6 +487,7 @@ impl TestDefinition<OutputId> {
             inputs,
             outputs,
             no_outputs_from,
+            snapshot,
         }
     }
 }
@@ -469,8 +519,69 @@ fn default_test_input_type() -> String {
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct TestOutput<T = OutputId> {
-    pub extract_from: T,
+    pub extract_from: TestOutputTarget<T>,
     pub conditions: Option<Vec<conditions::AnyCondition>>,
+    #[serde(default)]
+    pub metrics: Vec<TestMetricAssertion>,
+}
+
+/// The outputs a test output extracts events from: either a single one, or several, each of which
+/// must then satisfy the conditions and the metric assertions on its own.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum TestOutputTarget<T> {
+    Single(T),
+    Multiple(Vec<T>),
+}
+
+impl<T> TestOutputTarget<T> {
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            Self::Single(target) => std::slice::from_ref(target),
+            Self::Multiple(targets) => targets,
+        }
+    }
+
+    /// Replaces the targets, keeping the form they were given in.
+    fn map<U>(&self, mut targets: Vec<U>) -> TestOutputTarget<U> {
+        match self {
+            Self::Single(_) => TestOutputTarget::Single(targets.remove(0)),
+            Self::Multiple(_) => TestOutputTarget::Multiple(targets),
+        }
+    }
+}
+
+/// A golden file holding the events output by a test, which they are checked against on each run,
+/// and recorded into when running `vector test --update`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TestSnapshot {
+    /// The path of the golden file, relative to the current directory.
+    pub path: PathBuf,
+    /// The log fields left out of the snapshot, as they change on each run.
+    #[serde(default = "default_snapshot_ignore_fields")]
+    pub ignore_fields: Vec<String>,
+}
+
+fn default_snapshot_ignore_fields() -> Vec<String> {
+    vec![log_schema().timestamp_key().to_owned()]
+}
+
+/// An assertion that at least one of the metric events of an output has the given name, and
+/// optionally the given namespace, type, kind, tags and value. The tags only need to be a subset
+/// of those of the metric.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TestMetricAssertion {
+    pub name: String,
+    pub namespace: Option<String>,
+    #[serde(rename = "type")]
+    pub type_str: Option<String>,
+    pub kind: Option<MetricKind>,
+    #[serde(default)]
+    pub tags: IndexMap<String, String>,
+    /// The value of a counter or a gauge, or the number of values of a set.
+    pub value: Option<f64>,
 }
 
 #[cfg(all(
@@ -5,7 +5,13 @@ use vector_buffers::{Acker, BufferConfig, BufferType};
 use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Input};
 
 use super::{component, ComponentKey, ProxyConfig, Resource};
-use crate::sinks::{self, util::UriSerde};
+use crate::{
+    conditions::AnyCondition,
+    sinks::{self, util::UriSerde},
+};
+
+/// The output of the sinks holding the events they fail to deliver.
+pub const DEAD_LETTER_OUTPUT: &str = "dead_letter";
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SinkOuter<T> {
@@ -22,6 +28,16 @@ pub struct SinkOuter<T> {
     #[serde(default)]
     pub buffer: BufferConfig,
 
+    /// The events classified as low priority, which are dropped whenever the buffer is full rather
+    /// than waited on, while the other events keep blocking as usual.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_priority: Option<AnyCondition>,
+
+    /// The component receiving the events the sink fails to deliver, once their requests exhausted
+    /// their retries or were rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<ComponentKey>,
+
     #[serde(
         default,
         skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
@@ -37,6 +53,8 @@ impl<T> SinkOuter<T> {
         SinkOuter {
             inputs,
             buffer: Default::default(),
+            low_priority: None,
+            dead_letter: None,
             healthcheck: SinkHealthcheckOptions::default(),
             healthcheck_uri: None,
             inner,
@@ -89,6 +107,8 @@ impl<T> SinkOuter<T> {
             inputs,
             inner: self.inner,
             buffer: self.buffer,
+            low_priority: self.low_priority,
+            dead_letter: self.dead_letter,
             healthcheck: self.healthcheck,
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
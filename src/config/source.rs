@@ -6,7 +6,14 @@ use serde::{Deserialize, Serialize};
 use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Output};
 
 use super::{component, schema, ComponentKey, ProxyConfig, Resource};
-use crate::{shutdown::ShutdownSignal, sources, SourceSender};
+use crate::{
+    shutdown::ShutdownSignal,
+    sources::{
+        self,
+        util::{AcknowledgementTimeout, AcknowledgementTimeoutConfig},
+    },
+    SourceSender,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SourceOuter {
@@ -15,6 +22,11 @@ pub struct SourceOuter {
         skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
     )]
     pub proxy: ProxyConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub acknowledgement_timeout: AcknowledgementTimeoutConfig,
     #[serde(flatten)]
     pub(crate) inner: Box<dyn SourceConfig>,
     #[serde(default, skip)]
@@ -26,6 +38,7 @@ impl SourceOuter {
         Self {
             inner: Box::new(source),
             proxy: Default::default(),
+            acknowledgement_timeout: Default::default(),
             sink_acknowledgements: false,
         }
     }
@@ -46,6 +59,17 @@ pub trait SourceConfig: core::fmt::Debug + Send + Sync {
     }
 
     fn can_acknowledge(&self) -> bool;
+
+    /// Whether the events are received again when their delivery errored, as when the messages
+    /// consumed from a queue are not deleted.
+    fn can_redeliver(&self) -> bool {
+        false
+    }
+
+    /// Whether the source applies the `acknowledgement_timeout` settings.
+    fn can_time_out_acknowledgements(&self) -> bool {
+        false
+    }
 }
 
 pub struct SourceContext {
@@ -55,6 +79,7 @@ pub struct SourceContext {
     pub out: SourceSender,
     pub proxy: ProxyConfig,
     pub acknowledgements: bool,
+    pub acknowledgement_timeout: AcknowledgementTimeout,
 
     /// Tracks the schema IDs assigned to schemas exposed by the source.
     ///
@@ -79,6 +104,7 @@ impl SourceContext {
                 out,
                 proxy: Default::default(),
                 acknowledgements: false,
+                acknowledgement_timeout: Default::default(),
                 schema_definitions: HashMap::default(),
             },
             shutdown,
@@ -97,6 +123,7 @@ impl SourceContext {
             out,
             proxy: Default::default(),
             acknowledgements: false,
+            acknowledgement_timeout: Default::default(),
             schema_definitions: schema_definitions.unwrap_or_default(),
         }
     }
@@ -12,15 +12,44 @@ use crate::transforms::noop::Noop;
 pub struct TransformOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
     pub inputs: Vec<T>,
+
+    /// Limits on the resources the transform can use, enforced by the topology.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub limits: ComponentLimits,
+
     #[serde(flatten)]
     pub inner: Box<dyn TransformConfig>,
 }
 
+/// Limits on the resources of a transform, so that a single pathological transform can't starve
+/// the rest of the topology.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentLimits {
+    /// The maximum number of events waiting for, or being processed by, the transform. This sizes
+    /// the input buffer of the transform, and caps the events processed concurrently. Not
+    /// supported by the transforms processing their events as a stream, such as `reduce`.
+    pub max_in_flight_events: Option<usize>,
+
+    /// The maximum estimated size, in bytes, of the events being processed by the transform. No
+    /// more events are taken from the input buffer until enough of them are sent on. Not supported
+    /// by the transforms processing their events as a stream, such as `reduce`.
+    pub max_memory_bytes: Option<usize>,
+
+    /// The number of events the transform can process before yielding to the other components
+    /// running on the same thread.
+    pub task_budget: Option<usize>,
+}
+
 impl<T> TransformOuter<T> {
     #[cfg(test)]
     pub(crate) fn new(transform: impl TransformConfig + 'static) -> Self {
         Self {
             inputs: vec![],
+            limits: ComponentLimits::default(),
             inner: Box::new(transform),
         }
     }
@@ -33,6 +62,7 @@ impl<T> TransformOuter<T> {
     pub(super) fn with_inputs<U>(self, inputs: Vec<U>) -> TransformOuter<U> {
         TransformOuter {
             inputs,
+            limits: self.limits,
             inner: self.inner,
         }
     }
@@ -69,8 +99,10 @@ impl TransformOuter<String> {
             for (name, content) in expanded {
                 let full_name = key.join(name);
 
+                // Each of the expanded transforms is given the limits of the transform itself.
                 let child = TransformOuter {
                     inputs,
+                    limits: self.limits,
                     inner: content,
                 };
                 child.expand(full_name.clone(), &ptypes, transforms, expansions)?;
@@ -87,6 +119,7 @@ impl TransformOuter<String> {
                     key.clone(),
                     TransformOuter {
                         inputs: children.iter().map(ToString::to_string).collect(),
+                        limits: ComponentLimits::default(),
                         inner: Box::new(Noop),
                     },
                 );
@@ -96,6 +129,7 @@ impl TransformOuter<String> {
                     key.clone(),
                     TransformOuter {
                         inputs,
+                        limits: ComponentLimits::default(),
                         inner: Box::new(Noop),
                     },
                 );
@@ -1,3 +1,4 @@
+mod snapshot;
 #[cfg(all(test, feature = "vector-unit-test-tests"))]
 mod tests;
 mod unit_test_components;
@@ -7,6 +8,7 @@ use crate::{
     config::{
         self, compiler::expand_macros, loading, ComponentKey, Config, ConfigBuilder, ConfigPath,
         SinkOuter, SourceOuter, TestDefinition, TestInput, TestInputValue, TestOutput,
+        TestSnapshot,
     },
     event::{Event, Value},
     schema,
@@ -29,7 +31,7 @@ use tokio::sync::{
 use uuid::Uuid;
 
 use self::unit_test_components::{
-    UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkResult, UnitTestSourceConfig,
+    UnitTestCheck, UnitTestSinkCheck, UnitTestSinkConfig, UnitTestSinkResult, UnitTestSourceConfig,
 };
 
 use super::{compiler::expand_globs, graph::Graph, OutputId};
@@ -39,6 +41,8 @@ pub struct UnitTest {
     config: Config,
     pieces: Pieces,
     test_result_rxs: Vec<Receiver<UnitTestSinkResult>>,
+    snapshot: Option<TestSnapshot>,
+    update_snapshot: bool,
 }
 
 pub struct UnitTestResult {
@@ -46,6 +50,11 @@ pub struct UnitTestResult {
 }
 
 impl UnitTest {
+    /// Records the output events into the snapshot of the test, if any, instead of checking them.
+    pub fn set_update_snapshot(&mut self, update: bool) {
+        self.update_snapshot = update;
+    }
+
     pub async fn run(self) -> UnitTestResult {
         let diff = config::ConfigDiff::initial(&self.config);
         let (topology, _) = topology::start_validated(self.config, diff, self.pieces)
@@ -60,11 +69,32 @@ impl UnitTest {
             .collect::<FuturesUnordered<_>>();
 
         let mut errors = Vec::new();
+        let mut snapshot_events = snapshot::SnapshotEvents::new();
         while let Some(partial_result) = in_flight.next().await {
             let partial_result = partial_result.expect(
                 "An unexpected error occurred while executing unit tests. Please try again.",
             );
             errors.extend(partial_result.test_errors);
+            if let Some(snapshot) = &self.snapshot {
+                if !partial_result.output_events.is_empty() {
+                    snapshot_events.insert(
+                        partial_result.transform_id,
+                        partial_result
+                            .output_events
+                            .into_iter()
+                            .map(|event| snapshot::event_to_snapshot(snapshot, event))
+                            .collect(),
+                    );
+                }
+            }
+        }
+
+        if let Some(snapshot) = &self.snapshot {
+            errors.extend(snapshot::check_snapshot(
+                snapshot,
+                &snapshot_events,
+                self.update_snapshot,
+            ));
         }
 
         UnitTestResult { errors }
@@ -417,6 +447,8 @@ async fn build_unit_test(
         config,
         pieces,
         test_result_rxs,
+        snapshot: test.snapshot,
+        update_snapshot: false,
     })
 }
 
@@ -513,8 +545,8 @@ fn build_and_validate_inputs(
 
 fn build_outputs(
     test_outputs: &[TestOutput],
-) -> Result<IndexMap<OutputId, Vec<Vec<Condition>>>, Vec<String>> {
-    let mut outputs: IndexMap<OutputId, Vec<Vec<Condition>>> = IndexMap::new();
+) -> Result<IndexMap<OutputId, Vec<UnitTestCheck>>, Vec<String>> {
+    let mut outputs: IndexMap<OutputId, Vec<UnitTestCheck>> = IndexMap::new();
     let mut errors = Vec::new();
 
     for output in test_outputs {
@@ -535,10 +567,16 @@ fn build_outputs(
             }
         }
 
-        outputs
-            .entry(output.extract_from.clone())
-            .and_modify(|existing_conditions| existing_conditions.push(conditions.clone()))
-            .or_insert(vec![conditions]);
+        let check = UnitTestCheck {
+            conditions,
+            metrics: output.metrics.clone(),
+        };
+        for extract_from in output.extract_from.as_slice() {
+            outputs
+                .entry(extract_from.clone())
+                .or_default()
+                .push(check.clone());
+        }
     }
 
     if errors.is_empty() {
@@ -0,0 +1,103 @@
+use std::{collections::BTreeMap, fs};
+
+use serde_json::Value;
+
+use crate::{config::TestSnapshot, event::Event};
+
+/// The events of a test, keyed by the transform/branch they were received from.
+pub(super) type SnapshotEvents = BTreeMap<String, Vec<Value>>;
+
+pub(super) fn event_to_snapshot(snapshot: &TestSnapshot, event: Event) -> Value {
+    let value = match event {
+        Event::Log(mut log) => {
+            for field in &snapshot.ignore_fields {
+                log.remove(field.as_str());
+            }
+            serde_json::to_value(log)
+        }
+        Event::Metric(metric) => serde_json::to_value(metric),
+        Event::Trace(trace) => serde_json::to_value(trace),
+    };
+    value.unwrap_or_else(|_| Value::Object(Default::default()))
+}
+
+/// Checks the events against the golden file of the snapshot, or records them into it when
+/// updating it, returning the differences as test errors.
+pub(super) fn check_snapshot(
+    snapshot: &TestSnapshot,
+    events: &SnapshotEvents,
+    update: bool,
+) -> Vec<String> {
+    let path = &snapshot.path;
+    if update {
+        let written = serde_json::to_string_pretty(events)
+            .map_err(|error| error.to_string())
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+                }
+                fs::write(path, json + "\n").map_err(|error| error.to_string())
+            });
+        return match written {
+            Ok(()) => Vec::new(),
+            Err(error) => vec![format!("unable to write snapshot {:?}: {}", path, error)],
+        };
+    }
+
+    let expected: SnapshotEvents = match fs::read_to_string(path) {
+        Ok(json) => match serde_json::from_str(&json) {
+            Ok(expected) => expected,
+            Err(error) => return vec![format!("unable to parse snapshot {:?}: {}", path, error)],
+        },
+        Err(error) => {
+            return vec![format!(
+                "unable to read snapshot {:?}: {}. Run `vector test --update` to record it.",
+                path, error
+            )]
+        }
+    };
+
+    let mut errors = diff_snapshots(&expected, events);
+    // If there are errors, add a preamble to the output
+    if !errors.is_empty() {
+        errors.insert(
+            0,
+            format!(
+                "snapshot {:?} differs from the output events, run `vector test --update` to \
+                 record them if the changes are expected:",
+                path
+            ),
+        );
+    }
+    errors
+}
+
+fn diff_snapshots(expected: &SnapshotEvents, received: &SnapshotEvents) -> Vec<String> {
+    let mut errors = Vec::new();
+    let no_events = Vec::new();
+    let transform_ids = expected
+        .keys()
+        .chain(received.keys().filter(|id| !expected.contains_key(*id)));
+    for transform_id in transform_ids {
+        let expected = expected.get(transform_id).unwrap_or(&no_events);
+        let received = received.get(transform_id).unwrap_or(&no_events);
+        if expected.len() != received.len() {
+            errors.push(format!(
+                "  transform {:?}: expected {} events, received {}",
+                transform_id,
+                expected.len(),
+                received.len()
+            ));
+        }
+
+        for (index, (expected, received)) in expected.iter().zip(received).enumerate() {
+            if expected != received {
+                errors.push(format!(
+                    "  transform {:?} event[{}]:\n    expected: {}\n    received: {}",
+                    transform_id, index, expected, received
+                ));
+            }
+        }
+    }
+    errors
+}
@@ -1,6 +1,6 @@
 use super::*;
 use crate::config::ConfigBuilder;
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 
 #[tokio::test]
 async fn parse_no_input() {
@@ -808,6 +808,122 @@ async fn test_metric_input() {
     assert!(tests.remove(0).run().await.errors.is_empty());
 }
 
+#[tokio::test]
+async fn test_metric_assertions() {
+    let config: ConfigBuilder = toml::from_str(indoc! { r#"
+          [transforms.foo]
+            inputs = ["ignored"]
+            type = "add_tags"
+            [transforms.foo.tags]
+              new_tag = "new value added"
+
+          [[tests]]
+            name = "successful test with metric assertions"
+
+            [tests.input]
+              insert_at = "foo"
+              type = "metric"
+              [tests.input.metric]
+                kind = "incremental"
+                name = "foometric"
+                [tests.input.metric.tags]
+                  tagfoo = "valfoo"
+                [tests.input.metric.counter]
+                  value = 100.0
+
+            [[tests.outputs]]
+              extract_from = "foo"
+              [[tests.outputs.metrics]]
+                name = "foometric"
+                type = "counter"
+                kind = "incremental"
+                value = 100.0
+                [tests.outputs.metrics.tags]
+                  new_tag = "new value added"
+
+          [[tests]]
+            name = "failing test with metric assertions"
+
+            [tests.input]
+              insert_at = "foo"
+              type = "metric"
+              [tests.input.metric]
+                kind = "absolute"
+                name = "foometric"
+                [tests.input.metric.gauge]
+                  value = 1.0
+
+            [[tests.outputs]]
+              extract_from = "foo"
+              [[tests.outputs.metrics]]
+                name = "foometric"
+                value = 2.0
+              [[tests.outputs.metrics]]
+                name = "barmetric"
+      "#})
+    .unwrap();
+
+    let mut tests = build_unit_tests(config).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+
+    let errors = tests.remove(0).run().await.errors;
+    assert_eq!(
+        errors[..3],
+        vec![
+            r#"check[0] for transform "foo" failed conditions:"#.to_owned(),
+            r#"  metric[0]: no metric named "foometric" matched: value 1 isn't 2"#.to_owned(),
+            r#"  metric[1]: no metric named "barmetric" received"#.to_owned(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_multiple_extract_from() {
+    let config: ConfigBuilder = toml::from_str(indoc! {r#"
+          [transforms.foo]
+            inputs = ["ignored"]
+            type = "route"
+              [transforms.foo.route]
+              first = 'exists(.message)'
+              second = 'contains(string!(.message), "swimlane")'
+
+          [[tests]]
+            name = "successful route test to both outputs"
+
+            [tests.input]
+              insert_at = "foo"
+              value = "test swimlane"
+
+            [[tests.outputs]]
+              extract_from = ["foo.first", "foo.second"]
+              [[tests.outputs.conditions]]
+                type = "vrl"
+                source = """
+                    assert_eq!(.message, "test swimlane")
+                """
+
+          [[tests]]
+            name = "failing route test to both outputs"
+
+            [tests.input]
+              insert_at = "foo"
+              value = "test lane"
+
+            [[tests.outputs]]
+              extract_from = ["foo.first", "foo.second"]
+              [[tests.outputs.conditions]]
+                type = "vrl"
+                source = """
+                    assert_eq!(.message, "test lane")
+                """
+      "#})
+    .unwrap();
+
+    let mut tests = build_unit_tests(config).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+    assert!(!tests.remove(0).run().await.errors.is_empty());
+}
+
 #[tokio::test]
 async fn test_success_over_gap() {
     let config: ConfigBuilder = toml::from_str(indoc! { r#"
@@ -1287,3 +1403,51 @@ async fn test_glob_input() {
     let mut tests = build_unit_tests(config).await.unwrap();
     assert!(tests.remove(0).run().await.errors.is_empty());
 }
+
+#[tokio::test]
+async fn test_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshots").join("remap.json");
+    let config = |new_field: &str| -> ConfigBuilder {
+        toml::from_str(&formatdoc! {r#"
+            [transforms.foo]
+              type = "remap"
+              inputs = [ "ignored" ]
+              source = '.new_field = "{}"'
+
+            [[tests]]
+              name = "snapshot test"
+
+              [tests.snapshot]
+                path = {:?}
+
+              [[tests.inputs]]
+                insert_at = "foo"
+                value = "test1"
+
+              [[tests.outputs]]
+                extract_from = "foo"
+            "#,
+            new_field,
+            path
+        })
+        .unwrap()
+    };
+
+    let mut tests = build_unit_tests(config("value1")).await.unwrap();
+    assert!(tests.remove(0).run().await.errors[0].starts_with("unable to read snapshot"));
+
+    let mut tests = build_unit_tests(config("value1")).await.unwrap();
+    let mut test = tests.remove(0);
+    test.set_update_snapshot(true);
+    assert!(test.run().await.errors.is_empty());
+    assert!(path.exists());
+
+    let mut tests = build_unit_tests(config("value1")).await.unwrap();
+    assert!(tests.remove(0).run().await.errors.is_empty());
+
+    let mut tests = build_unit_tests(config("value2")).await.unwrap();
+    let errors = tests.remove(0).run().await.errors;
+    assert_eq!(errors.len(), 2);
+    assert!(errors[1].contains(r#""new_field":"value2""#));
+}
@@ -11,7 +11,11 @@ use vector_core::{
 
 use crate::{
     conditions::Condition,
-    config::{AcknowledgementsConfig, SinkConfig, SinkContext, SourceConfig, SourceContext},
+    config::{
+        AcknowledgementsConfig, SinkConfig, SinkContext, SourceConfig, SourceContext,
+        TestMetricAssertion,
+    },
+    event::{Metric, MetricValue},
     sinks::Healthcheck,
     sources,
 };
@@ -51,10 +55,18 @@ impl SourceConfig for UnitTestSourceConfig {
     }
 }
 
+#[derive(Clone)]
+pub struct UnitTestCheck {
+    // Conditions which must each be satisfied by at least one received event
+    pub conditions: Vec<Condition>,
+    // Assertions which must each be satisfied by at least one received metric
+    pub metrics: Vec<TestMetricAssertion>,
+}
+
 #[derive(Clone)]
 pub enum UnitTestSinkCheck {
-    // Check sets of conditions against received events
-    Checks(Vec<Vec<Condition>>),
+    // Check sets of conditions and metric assertions against received events
+    Checks(Vec<UnitTestCheck>),
     // Check that no events were received
     NoOutputs,
     // Do nothing
@@ -71,6 +83,10 @@ impl Default for UnitTestSinkCheck {
 pub struct UnitTestSinkResult {
     pub test_name: String,
     pub test_errors: Vec<String>,
+    // Name of the transform/branch the events were received from
+    pub transform_id: String,
+    // Events received, recorded into the snapshot of the test, if any
+    pub output_events: Vec<Event>,
 }
 
 #[derive(Serialize, Deserialize, Default, Derivative)]
@@ -133,6 +149,8 @@ impl StreamSink<Event> for UnitTestSink {
         let mut result = UnitTestSinkResult {
             test_name: self.test_name,
             test_errors: Vec::new(),
+            transform_id: self.transform_id.clone(),
+            output_events: Vec::new(),
         };
 
         while let Some(event) = input.next().await {
@@ -148,7 +166,7 @@ impl StreamSink<Event> for UnitTestSink {
                 } else {
                     for (i, check) in checks.iter().enumerate() {
                         let mut check_errors = Vec::new();
-                        for (j, condition) in check.iter().enumerate() {
+                        for (j, condition) in check.conditions.iter().enumerate() {
                             let mut condition_errors = Vec::new();
                             for event in output_events.iter() {
                                 match condition.check_with_context(event) {
@@ -164,6 +182,11 @@ impl StreamSink<Event> for UnitTestSink {
                             }
                             check_errors.extend(condition_errors);
                         }
+                        for (j, assertion) in check.metrics.iter().enumerate() {
+                            if let Err(error) = check_metrics(assertion, &output_events) {
+                                check_errors.push(format!("  metric[{}]: {}", j, error));
+                            }
+                        }
                         // If there are errors, add a preamble to the output
                         if !check_errors.is_empty() {
                             check_errors.insert(
@@ -198,6 +221,7 @@ impl StreamSink<Event> for UnitTestSink {
             }
             UnitTestSinkCheck::NoOp => {}
         }
+        result.output_events = output_events;
 
         if let Some(tx) = self.result_tx {
             if tx.send(result).is_err() {
@@ -208,6 +232,91 @@ impl StreamSink<Event> for UnitTestSink {
     }
 }
 
+/// Checks that at least one of the metrics satisfies the assertion, explaining why the metrics with
+/// the asserted name don't otherwise.
+fn check_metrics(assertion: &TestMetricAssertion, events: &[Event]) -> Result<(), String> {
+    let mut mismatches = Vec::new();
+    let metrics = events.iter().filter_map(|event| match event {
+        Event::Metric(metric) => Some(metric),
+        _ => None,
+    });
+    for metric in metrics {
+        if metric.name() != assertion.name {
+            continue;
+        }
+        match check_metric(assertion, metric) {
+            Ok(()) => return Ok(()),
+            Err(mismatch) => mismatches.push(mismatch),
+        }
+    }
+
+    if mismatches.is_empty() {
+        Err(format!("no metric named {:?} received", assertion.name))
+    } else {
+        Err(format!(
+            "no metric named {:?} matched: {}",
+            assertion.name,
+            mismatches.join(", ")
+        ))
+    }
+}
+
+fn check_metric(assertion: &TestMetricAssertion, metric: &Metric) -> Result<(), String> {
+    if let Some(namespace) = &assertion.namespace {
+        if metric.namespace() != Some(namespace.as_str()) {
+            return Err(format!(
+                "namespace {:?} isn't {:?}",
+                metric.namespace(),
+                namespace
+            ));
+        }
+    }
+
+    // Types are given as in the metric inputs of the tests, with underscores.
+    let metric_type = metric.value().as_name().replace(' ', "_");
+    if let Some(type_str) = &assertion.type_str {
+        if metric_type != *type_str {
+            return Err(format!("type {:?} isn't {:?}", metric_type, type_str));
+        }
+    }
+
+    if let Some(kind) = assertion.kind {
+        if metric.kind() != kind {
+            return Err(format!("kind {:?} isn't {:?}", metric.kind(), kind));
+        }
+    }
+
+    for (name, value) in &assertion.tags {
+        match metric.tag_value(name) {
+            Some(tag_value) if tag_value == *value => {}
+            tag_value => {
+                return Err(format!(
+                    "tag {:?} is {:?}, not {:?}",
+                    name, tag_value, value
+                ))
+            }
+        }
+    }
+
+    if let Some(value) = assertion.value {
+        let metric_value = match metric.value() {
+            MetricValue::Counter { value } | MetricValue::Gauge { value } => *value,
+            MetricValue::Set { values } => values.len() as f64,
+            _ => {
+                return Err(format!(
+                    "the value of {} metrics can't be asserted",
+                    metric_type
+                ))
+            }
+        };
+        if (metric_value - value).abs() > f64::EPSILON {
+            return Err(format!("value {} isn't {}", metric_value, value));
+        }
+    }
+
+    Ok(())
+}
+
 fn events_to_string(events: &[Event]) -> String {
     events
         .iter()
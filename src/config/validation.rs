@@ -178,6 +178,32 @@ pub fn check_outputs(config: &ConfigBuilder) -> Result<(), Vec<String>> {
     }
 }
 
+/// Check that the sources configured with an `acknowledgement_timeout` apply it, so that it is not
+/// silently ignored.
+pub fn check_acknowledgement_timeouts(config: &ConfigBuilder) -> Result<(), Vec<String>> {
+    let errors: Vec<_> = config
+        .sources
+        .iter()
+        .filter(|(_, source)| {
+            source.acknowledgement_timeout != Default::default()
+                && !source.inner.can_time_out_acknowledgements()
+        })
+        .map(|(key, source)| {
+            format!(
+                "Source \"{}\" of type \"{}\" does not support `acknowledgement_timeout`",
+                key,
+                source.inner.source_type()
+            )
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 pub fn warnings(config: &Config) -> Vec<String> {
     let mut warnings = vec![];
 
@@ -0,0 +1,238 @@
+use std::{fs, path::PathBuf};
+
+use clap::Parser;
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    cli::handle_config_errors,
+    config::{self, format, ConfigBuilder, Format},
+};
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Opts {
+    /// The Vector config file to convert.
+    input: PathBuf,
+
+    /// The format of the input file. Detected from the file name if not given.
+    #[clap(long)]
+    from: Option<Format>,
+
+    /// The format to convert the config to. Detected from the name of the
+    /// output file if not given.
+    #[clap(long)]
+    to: Option<Format>,
+
+    /// Write the converted config to this file instead of stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Replace the environment variables referenced in the config with their
+    /// values. By default they are kept as they are, so that the converted
+    /// config doesn't leak them.
+    #[clap(long)]
+    interpolate_env: bool,
+
+    /// Only check that the components of the config are valid, not that they
+    /// make up a whole topology, for configs split across several files.
+    #[clap(long)]
+    fragment: bool,
+}
+
+/// Function used by the `vector convert-config` subcommand. The config is validated, then converted
+/// from its parsed form, so keys keep their order but comments are lost.
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let from = match opts.from.map_or_else(|| Format::from_path(&opts.input), Ok) {
+        Ok(from) => from,
+        Err(_) => {
+            return handle_config_errors(vec![format!(
+                "Unable to detect the format of {:?}, use --from to specify it.",
+                opts.input
+            )])
+        }
+    };
+    let to = match opts.to.map_or_else(
+        || {
+            opts.output
+                .as_ref()
+                .and_then(|output| Format::from_path(output).ok())
+        },
+        Some,
+    ) {
+        Some(to) => to,
+        None => {
+            return handle_config_errors(vec![
+                "Unable to detect the format to convert to, use --to to specify it.".to_owned(),
+            ])
+        }
+    };
+
+    let source = match fs::read_to_string(&opts.input) {
+        Ok(source) => source,
+        Err(error) => {
+            return handle_config_errors(vec![format!(
+                "Could not read {:?}: {}",
+                opts.input, error
+            )])
+        }
+    };
+    let (interpolated, warnings) = match config::prepare_input(source.as_bytes()) {
+        Ok(prepared) => prepared,
+        Err(errors) => return handle_config_errors(errors),
+    };
+    for warning in warnings {
+        warn!("{}", warning);
+    }
+
+    if let Err(errors) = validate(&interpolated, from, opts.fragment) {
+        return handle_config_errors(errors);
+    }
+
+    let input = if opts.interpolate_env {
+        &interpolated
+    } else {
+        &source
+    };
+    let converted = match convert(input, from, to) {
+        Ok(converted) => converted,
+        Err(errors) => return handle_config_errors(errors),
+    };
+
+    if let Some(output) = &opts.output {
+        if let Err(error) = fs::write(output, converted) {
+            return handle_config_errors(vec![format!("Could not write {:?}: {}", output, error)]);
+        }
+    } else {
+        #[allow(clippy::print_stdout)]
+        {
+            print!("{}", converted);
+        }
+    }
+
+    exitcode::OK
+}
+
+/// Checks the config against the schema of its components and, unless it's a fragment, that they
+/// make up a valid topology.
+fn validate(input: &str, from: Format, fragment: bool) -> Result<(), Vec<String>> {
+    let builder: ConfigBuilder = format::deserialize(input, from)?;
+    if !fragment {
+        builder.build()?;
+    }
+    Ok(())
+}
+
+/// Converts the config between formats. It's parsed into YAML values, as their mappings keep the
+/// order of their keys whatever the format.
+fn convert(input: &str, from: Format, to: Format) -> Result<String, Vec<String>> {
+    let value: Value = format::deserialize(input, from)?;
+    let converted = match to {
+        Format::Toml => toml::to_string(&tables_last(value)).map_err(|error| error.to_string()),
+        Format::Yaml => serde_yaml::to_string(&value)
+            .map(|yaml| yaml + "\n")
+            .map_err(|error| error.to_string()),
+        Format::Json => serde_json::to_string_pretty(&value)
+            .map(|json| json + "\n")
+            .map_err(|error| error.to_string()),
+    };
+    converted.map_err(|error| vec![format!("Could not convert the config: {}", error)])
+}
+
+/// TOML requires the plain values of a table to come before its tables, so these are moved last,
+/// keeping their order otherwise.
+fn tables_last(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let (tables, values): (Vec<_>, Vec<_>) = mapping
+                .into_iter()
+                .map(|(key, value)| (key, tables_last(value)))
+                .partition(|(_, value)| is_table(value));
+            Value::Mapping(values.into_iter().chain(tables).collect::<Mapping>())
+        }
+        Value::Sequence(values) => Value::Sequence(values.into_iter().map(tables_last).collect()),
+        value => value,
+    }
+}
+
+/// Whether the value is written as a table, or as an array of tables.
+fn is_table(value: &Value) -> bool {
+    match value {
+        Value::Mapping(_) => true,
+        Value::Sequence(values) => !values.is_empty() && values.iter().all(is_table),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn converts_toml_to_yaml_keeping_key_order() {
+        let toml = indoc! {r#"
+            [sources.in]
+            type = "stdin"
+
+            [sinks.out]
+            type = "console"
+            inputs = ["in"]
+            encoding.codec = "json"
+        "#};
+
+        assert_eq!(
+            convert(toml, Format::Toml, Format::Yaml).unwrap(),
+            indoc! {r#"
+                ---
+                sources:
+                  in:
+                    type: stdin
+                sinks:
+                  out:
+                    type: console
+                    inputs:
+                      - in
+                    encoding:
+                      codec: json
+            "#}
+        );
+    }
+
+    #[test]
+    fn converts_yaml_to_toml_with_tables_last() {
+        let yaml = indoc! {r#"
+            sinks:
+              out:
+                encoding:
+                  codec: json
+                inputs: [in]
+                type: console
+            data_dir: /var/lib/vector
+        "#};
+
+        assert_eq!(
+            convert(yaml, Format::Yaml, Format::Toml).unwrap(),
+            indoc! {r#"
+                data_dir = "/var/lib/vector"
+
+                [sinks.out]
+                inputs = ["in"]
+                type = "console"
+
+                [sinks.out.encoding]
+                codec = "json"
+            "#}
+        );
+    }
+
+    #[test]
+    fn reports_invalid_components() {
+        let toml = indoc! {r#"
+            [sources.in]
+            type = "zork"
+        "#};
+
+        assert!(validate(toml, Format::Toml, true).is_err());
+    }
+}
@@ -1,19 +1,37 @@
 use std::{
+    cmp::Reverse,
     collections::{BTreeMap, HashMap},
     fs,
     hash::Hasher,
-    path::PathBuf,
-    time::SystemTime,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{channel, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
+use arc_swap::ArcSwap;
 use bytes::Bytes;
-use enrichment::{Case, Condition, IndexHandle, Table};
+use enrichment::{Case, Condition, IndexHandle, LookupIndex, Table};
+use notify::{raw_watcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use tracing::trace;
 use vector_common::{conversion::Conversion, datetime::TimeZone};
 use vrl::Value;
 
-use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription};
+use super::index::{value_to_f64, CidrIndex, Network, RangeIndex};
+use crate::{
+    config::{EnrichmentTableConfig, EnrichmentTableDescription},
+    internal_events::{EnrichmentTableLoaded, EnrichmentTableReloadError, EnrichmentTableReloaded},
+};
+
+/// How long changes to a watched file are accumulated before it is reloaded.
+const WATCH_DELAY: Duration = Duration::from_secs(1);
+
+/// How often the reloader checks that the table is still in use, when it isn't due to reload it.
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -46,6 +64,15 @@ pub struct FileConfig {
     file: FileC,
     #[serde(default)]
     schema: HashMap<String, String>,
+    /// How often the file is checked for changes, and reloaded if it has been modified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reload_interval_secs: Option<u64>,
+    /// Watches the file for changes, reloading it as soon as it has been modified.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    watch: bool,
 }
 
 const fn default_delimiter() -> char {
@@ -169,9 +196,22 @@ impl EnrichmentTableConfig for FileConfig {
         &self,
         globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        if self.reload_interval_secs == Some(0) {
+            return Err("`reload_interval_secs` must be greater than 0".into());
+        }
+
         let (headers, data, modified) = self.load_file(globals.timezone)?;
+        emit!(&EnrichmentTableLoaded {
+            file: &self.file.path,
+            rows: data.len(),
+        });
+
+        let table = File::new(self.clone(), modified, data, headers);
+        if self.reload_interval_secs.is_some() || self.watch {
+            spawn_reloader(&table, globals.timezone)?;
+        }
 
-        Ok(Box::new(File::new(self.clone(), modified, data, headers)))
+        Ok(Box::new(table))
     }
 }
 
@@ -181,17 +221,38 @@ inventory::submit! {
 
 impl_generate_config_from_default!(FileConfig);
 
+/// The index of the rows, keyed by the hash of the indexed fields.
+type Index = HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>;
+
+/// The index for the conditions which don't match fields exactly.
+enum LookupIndexData {
+    Range(RangeIndex),
+    Cidr(CidrIndex),
+}
+
+/// The data loaded from the file, which is swapped as a whole when the file is reloaded.
 #[derive(Clone)]
-pub struct File {
-    config: FileConfig,
+struct FileData {
     last_modified: SystemTime,
-    data: Vec<Vec<Value>>,
+    data: Arc<Vec<Vec<Value>>>,
     headers: Vec<String>,
-    indexes: Vec<(
-        Case,
-        Vec<usize>,
-        HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>,
-    )>,
+    indexes: Vec<(Case, Vec<usize>, Arc<Index>)>,
+    lookup_indexes: Vec<(LookupIndex, Arc<LookupIndexData>)>,
+}
+
+/// The data shared by all the copies of a table, so that reloading the file in the background
+/// updates the table wherever it is used.
+struct SharedData {
+    current: ArcSwap<FileData>,
+    // Held whilst the data is being replaced, so that an index being added isn't lost to a
+    // concurrent reload.
+    writing: Mutex<()>,
+}
+
+#[derive(Clone)]
+pub struct File {
+    config: FileConfig,
+    shared: Arc<SharedData>,
 }
 
 impl File {
@@ -203,13 +264,140 @@ impl File {
     ) -> Self {
         Self {
             config,
+            shared: Arc::new(SharedData {
+                current: ArcSwap::from_pointee(FileData {
+                    last_modified,
+                    data: Arc::new(data),
+                    headers,
+                    indexes: Vec::new(),
+                    lookup_indexes: Vec::new(),
+                }),
+                writing: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Loads the file again if it has been modified since it was last loaded, rebuilding the
+    /// same indexes so that the handles given out stay valid, and swaps the new data in. The
+    /// current data is kept if any of this fails.
+    fn reload(&self, timezone: TimeZone) -> crate::Result<bool> {
+        if !self.needs_reload() {
+            return Ok(false);
+        }
+
+        let start = Instant::now();
+        let (headers, data, last_modified) = self.config.load_file(timezone)?;
+
+        let _writing = self
+            .shared
+            .writing
+            .lock()
+            .expect("file data writer poisoned");
+        let current = self.shared.current.load_full();
+        let mut next = FileData {
             last_modified,
-            data,
+            data: Arc::new(data),
             headers,
-            indexes: Vec::new(),
+            indexes: Vec::with_capacity(current.indexes.len()),
+            lookup_indexes: Vec::with_capacity(current.lookup_indexes.len()),
+        };
+        for (case, fields) in current.index_fields() {
+            let fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
+            let normalized = next.normalize_index_fields(&fields)?;
+            let index = next.index_data(&normalized, case)?;
+            next.indexes.push((case, normalized, Arc::new(index)));
+        }
+        for (index, _) in &current.lookup_indexes {
+            let data = next.lookup_index_data(index)?;
+            next.lookup_indexes.push((index.clone(), Arc::new(data)));
         }
+
+        emit!(&EnrichmentTableReloaded {
+            file: &self.config.file.path,
+            rows: next.data.len(),
+            elapsed: start.elapsed(),
+        });
+        self.shared.current.store(Arc::new(next));
+
+        Ok(true)
     }
+}
+
+/// Reloads the table in the background, every `reload_interval_secs` and, when `watch` is
+/// enabled, whenever the file changes. The reloader stops once the table is no longer used.
+fn spawn_reloader(table: &File, timezone: TimeZone) -> crate::Result<()> {
+    let config = table.config.clone();
+    let shared = Arc::downgrade(&table.shared);
+    let interval = config.reload_interval_secs.map(Duration::from_secs);
+
+    // The watcher is created upfront, so that failing to watch the file fails the build.
+    let (sender, receiver) = channel();
+    let watcher = if config.watch {
+        let mut watcher = raw_watcher(sender.clone())?;
+        // The directory is watched rather than the file, so that the file being replaced, as
+        // tools updating files atomically do, is noticed too.
+        let directory = match config.file.path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => Path::new("."),
+        };
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+        Some(watcher)
+    } else {
+        None
+    };
+
+    thread::Builder::new()
+        .name("enrichment-table-reload".to_owned())
+        .spawn(move || {
+            let _watcher = watcher;
+            // Keeps the channel open when the file isn't watched, so that the reloader only
+            // wakes up on time.
+            let _sender = sender;
+            let mut next_reload = interval.map(|interval| Instant::now() + interval);
+
+            loop {
+                let timeout = next_reload.map_or(LIVENESS_CHECK_INTERVAL, |at| {
+                    at.saturating_duration_since(Instant::now())
+                        .min(LIVENESS_CHECK_INTERVAL)
+                });
+                match receiver.recv_timeout(timeout) {
+                    Ok(_) => {
+                        // Consume events until the file hasn't changed for a while, as files
+                        // are often written in several steps.
+                        while receiver.recv_timeout(WATCH_DELAY).is_ok() {}
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !next_reload.map_or(false, |at| at <= Instant::now()) {
+                            if shared.strong_count() == 0 {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                next_reload = interval.map(|interval| Instant::now() + interval);
+
+                let table = match shared.upgrade() {
+                    Some(shared) => File {
+                        config: config.clone(),
+                        shared,
+                    },
+                    None => break,
+                };
+                if let Err(error) = table.reload(timezone) {
+                    emit!(&EnrichmentTableReloadError {
+                        file: &config.file.path,
+                        error,
+                    });
+                }
+            }
+        })?;
+
+    Ok(())
+}
 
+impl FileData {
     fn column_index(&self, col: &str) -> Option<usize> {
         self.headers.iter().position(|header| header == col)
     }
@@ -237,6 +425,22 @@ impl File {
                     _ => false,
                 },
             },
+            Condition::WithinRange { from, to, value } => {
+                match (self.column_index(from), self.column_index(to)) {
+                    (Some(from), Some(to)) => {
+                        match (value_to_f64(&row[from]), value_to_f64(&row[to])) {
+                            (Some(from), Some(to)) => from <= *value && *value <= to,
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                }
+            }
+            Condition::WithinCidr { field, address } => match self.column_index(field) {
+                None => false,
+                Some(idx) => Network::from_value(&row[idx])
+                    .map_or(false, |network| network.contains(*address)),
+            },
         })
     }
 
@@ -293,11 +497,7 @@ impl File {
     /// the index of the row in the data.
     ///
     /// Ensure fields that are searched via a comparison are not included in the index!
-    fn index_data(
-        &self,
-        fieldidx: &[usize],
-        case: Case,
-    ) -> Result<HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>, String> {
+    fn index_data(&self, fieldidx: &[usize], case: Case) -> Result<Index, String> {
         let mut index = HashMap::with_capacity_and_hasher(
             self.data.len(),
             hash_hasher::HashBuildHasher::default(),
@@ -365,48 +565,119 @@ impl File {
         let IndexHandle(handle) = handle;
         Ok(self.indexes[handle].2.get(&key))
     }
-}
 
-/// Adds the bytes from the given value to the hash.
-/// Each field is terminated by a `0` value to separate the fields
-fn hash_value(hasher: &mut seahash::SeaHasher, case: Case, value: &Value) -> Result<(), String> {
-    match value {
-        Value::Bytes(bytes) => match case {
-            Case::Sensitive => hasher.write(bytes),
-            Case::Insensitive => hasher.write(
-                std::str::from_utf8(bytes)
-                    .map_err(|_| "column contains invalid utf".to_string())?
-                    .to_lowercase()
-                    .as_bytes(),
-            ),
-        },
-        value => {
-            let bytes: bytes::Bytes = value.encode_as_bytes()?;
-            hasher.write(&bytes);
-        }
+    /// Indexes the ranges or the CIDR networks of the rows.
+    fn lookup_index_data(&self, index: &LookupIndex) -> Result<LookupIndexData, String> {
+        let column = |field: &str| {
+            self.column_index(field)
+                .ok_or_else(|| format!("field(s) '{}' missing from dataset", field))
+        };
+
+        Ok(match index {
+            LookupIndex::Range { from, to } => {
+                let (from, to) = (column(from)?, column(to)?);
+                LookupIndexData::Range(RangeIndex::new(
+                    self.data
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, row)| (idx, &row[from], &row[to])),
+                ))
+            }
+            LookupIndex::Cidr { field } => {
+                let field = column(field)?;
+                LookupIndexData::Cidr(CidrIndex::new(
+                    self.data
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, row)| (idx, &row[field])),
+                ))
+            }
+        })
     }
 
-    hasher.write_u8(0);
+    /// Returns the rows that may match the condition, as found by a range or CIDR index.
+    fn lookup_indexed(&self, condition: &Condition) -> Option<Vec<usize>> {
+        self.lookup_indexes
+            .iter()
+            .find_map(|(index, data)| match (condition, index, &**data) {
+                (
+                    Condition::WithinRange { from, to, value },
+                    LookupIndex::Range {
+                        from: index_from,
+                        to: index_to,
+                    },
+                    LookupIndexData::Range(ranges),
+                ) if *from == index_from.as_str() && *to == index_to.as_str() => {
+                    Some(ranges.find(*value))
+                }
+                (
+                    Condition::WithinCidr { field, address },
+                    LookupIndex::Cidr { field: index_field },
+                    LookupIndexData::Cidr(networks),
+                ) if *field == index_field.as_str() => Some(networks.find(*address)),
+                _ => None,
+            })
+    }
 
-    Ok(())
-}
+    /// Searches the rows for a condition including `WithinRange` or `WithinCidr` conditions,
+    /// returning `None` for the other conditions. The rows are searched through a range or CIDR
+    /// index if there is one for these conditions, or through the exact index passed otherwise.
+    ///
+    /// The rows are ordered from the most specific network for a `WithinCidr` condition, and
+    /// only the rows of the most specific network are kept if `most_specific` is set.
+    fn find_within(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        index: Option<IndexHandle>,
+        most_specific: bool,
+    ) -> Option<Result<Vec<usize>, String>> {
+        if !condition.iter().any(|condition| {
+            matches!(
+                condition,
+                Condition::WithinRange { .. } | Condition::WithinCidr { .. }
+            )
+        }) {
+            return None;
+        }
 
-/// Returns an error if the iterator doesn't yield exactly one result.
-fn single_or_err<I, T>(mut iter: T) -> Result<I, String>
-where
-    T: Iterator<Item = I>,
-{
-    let result = iter.next();
+        let candidates = match condition
+            .iter()
+            .find_map(|condition| self.lookup_indexed(condition))
+        {
+            Some(rows) => rows,
+            None => match index {
+                Some(handle) => match self.indexed(case, condition, handle) {
+                    Ok(rows) => rows.cloned().unwrap_or_default(),
+                    Err(error) => return Some(Err(error)),
+                },
+                None => (0..self.data.len()).collect(),
+            },
+        };
+        let mut rows = candidates
+            .into_iter()
+            .filter(|idx| self.row_equals(case, condition, &self.data[*idx]))
+            .collect::<Vec<_>>();
 
-    if iter.next().is_some() {
-        // More than one row has been found.
-        Err("more than one row found".to_string())
-    } else {
-        result.ok_or_else(|| "no rows found".to_string())
+        let network_field = condition.iter().find_map(|condition| match condition {
+            Condition::WithinCidr { field, .. } => self.column_index(field),
+            _ => None,
+        });
+        if let Some(field) = network_field {
+            let prefix_len = |idx: &usize| {
+                Network::from_value(&self.data[*idx][field]).map(|network| network.prefix_len())
+            };
+            rows.sort_by_key(|idx| Reverse(prefix_len(idx)));
+            if most_specific {
+                if let Some(longest) = rows.first().map(prefix_len) {
+                    rows.retain(|idx| prefix_len(idx) == longest);
+                }
+            }
+        }
+
+        Some(Ok(rows))
     }
-}
 
-impl Table for File {
     fn find_table_row<'a>(
         &self,
         case: Case,
@@ -414,6 +685,14 @@ impl Table for File {
         select: Option<&'a [String]>,
         index: Option<IndexHandle>,
     ) -> Result<BTreeMap<String, Value>, String> {
+        if let Some(rows) = self.find_within(case, condition, index, true) {
+            return single_or_err(
+                rows?
+                    .into_iter()
+                    .map(|idx| self.add_columns(select, &self.data[idx])),
+            );
+        }
+
         match index {
             None => {
                 // No index has been passed so we need to do a Sequential Scan.
@@ -439,6 +718,13 @@ impl Table for File {
         select: Option<&'a [String]>,
         index: Option<IndexHandle>,
     ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        if let Some(rows) = self.find_within(case, condition, index, false) {
+            return Ok(rows?
+                .into_iter()
+                .map(|idx| self.add_columns(select, &self.data[idx]))
+                .collect());
+        }
+
         match index {
             None => {
                 // No index has been passed so we need to do a Sequential Scan.
@@ -462,9 +748,99 @@ impl Table for File {
         }
     }
 
+    /// Returns a list of the field names that are in each index
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes
+            .iter()
+            .map(|index| {
+                let (case, fields, _) = index;
+                (
+                    *case,
+                    fields
+                        .iter()
+                        .map(|idx| self.headers[*idx].clone())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    }
+}
+
+/// Adds the bytes from the given value to the hash.
+/// Each field is terminated by a `0` value to separate the fields
+fn hash_value(hasher: &mut seahash::SeaHasher, case: Case, value: &Value) -> Result<(), String> {
+    match value {
+        Value::Bytes(bytes) => match case {
+            Case::Sensitive => hasher.write(bytes),
+            Case::Insensitive => hasher.write(
+                std::str::from_utf8(bytes)
+                    .map_err(|_| "column contains invalid utf".to_string())?
+                    .to_lowercase()
+                    .as_bytes(),
+            ),
+        },
+        value => {
+            let bytes: bytes::Bytes = value.encode_as_bytes()?;
+            hasher.write(&bytes);
+        }
+    }
+
+    hasher.write_u8(0);
+
+    Ok(())
+}
+
+/// Returns an error if the iterator doesn't yield exactly one result.
+fn single_or_err<I, T>(mut iter: T) -> Result<I, String>
+where
+    T: Iterator<Item = I>,
+{
+    let result = iter.next();
+
+    if iter.next().is_some() {
+        // More than one row has been found.
+        Err("more than one row found".to_string())
+    } else {
+        result.ok_or_else(|| "no rows found".to_string())
+    }
+}
+
+impl Table for File {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        self.shared
+            .current
+            .load()
+            .find_table_row(case, condition, select, index)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.shared
+            .current
+            .load()
+            .find_table_rows(case, condition, select, index)
+    }
+
     fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
-        let normalized = self.normalize_index_fields(fields)?;
-        match self
+        let _writing = self
+            .shared
+            .writing
+            .lock()
+            .expect("file data writer poisoned");
+        let current = self.shared.current.load_full();
+        let normalized = current.normalize_index_fields(fields)?;
+        match current
             .indexes
             .iter()
             .position(|index| index.0 == case && index.1 == normalized)
@@ -474,46 +850,70 @@ impl Table for File {
                 Ok(IndexHandle(pos))
             }
             None => {
-                let index = self.index_data(&normalized, case)?;
-                self.indexes.push((case, normalized, index));
+                let index = current.index_data(&normalized, case)?;
+                let mut next = FileData::clone(&current);
+                next.indexes.push((case, normalized, Arc::new(index)));
                 // The returned index handle is the position of the index in our list of indexes.
-                Ok(IndexHandle(self.indexes.len() - 1))
+                let handle = IndexHandle(next.indexes.len() - 1);
+                self.shared.current.store(Arc::new(next));
+                Ok(handle)
             }
         }
     }
 
     /// Returns a list of the field names that are in each index
     fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
-        self.indexes
+        self.shared.current.load().index_fields()
+    }
+
+    fn add_lookup_index(&mut self, index: &LookupIndex) -> Result<(), String> {
+        let _writing = self
+            .shared
+            .writing
+            .lock()
+            .expect("file data writer poisoned");
+        let current = self.shared.current.load_full();
+        if current
+            .lookup_indexes
             .iter()
-            .map(|index| {
-                let (case, fields, _) = index;
-                (
-                    *case,
-                    fields
-                        .iter()
-                        .map(|idx| self.headers[*idx].clone())
-                        .collect::<Vec<_>>(),
-                )
-            })
-            .collect::<Vec<_>>()
+            .all(|(existing, _)| existing != index)
+        {
+            let data = current.lookup_index_data(index)?;
+            let mut next = FileData::clone(&current);
+            next.lookup_indexes.push((index.clone(), Arc::new(data)));
+            self.shared.current.store(Arc::new(next));
+        }
+
+        Ok(())
+    }
+
+    fn lookup_indexes(&self) -> Vec<LookupIndex> {
+        self.shared
+            .current
+            .load()
+            .lookup_indexes
+            .iter()
+            .map(|(index, _)| index.clone())
+            .collect()
     }
 
     /// Checks the modified timestamp of the data file to see if data has changed.
     fn needs_reload(&self) -> bool {
+        let last_modified = self.shared.current.load().last_modified;
         matches!(fs::metadata(&self.config.file.path)
             .and_then(|metadata| metadata.modified()),
-            Ok(modified) if modified > self.last_modified)
+            Ok(modified) if modified > last_modified)
     }
 }
 
 impl std::fmt::Debug for File {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let current = self.shared.current.load();
         write!(
             f,
             "File {} row(s) {} index(es)",
-            self.data.len(),
-            self.indexes.len()
+            current.data.len(),
+            current.indexes.len()
         )
     }
 }
@@ -538,6 +938,7 @@ mod tests {
         let config = FileConfig {
             file: Default::default(),
             schema,
+            ..Default::default()
         };
 
         assert_eq!(
@@ -649,7 +1050,7 @@ mod tests {
         let handle2 = file.add_index(Case::Sensitive, &["field3", "field2"]);
 
         assert_eq!(handle1, handle2);
-        assert_eq!(1, file.indexes.len());
+        assert_eq!(1, file.shared.current.load().indexes.len());
     }
 
     #[test]
@@ -943,4 +1344,154 @@ mod tests {
             file.find_table_row(Case::Sensitive, &[condition], None, Some(handle))
         );
     }
+
+    #[test]
+    fn finds_rows_within_ranges() {
+        let mut file = File::new(
+            Default::default(),
+            SystemTime::now(),
+            vec![
+                vec!["0".into(), "1023".into(), "system".into()],
+                vec!["8000".into(), "8999".into(), "http".into()],
+                vec!["8080".into(), "8080".into(), "proxy".into()],
+            ],
+            vec![
+                "port_start".to_string(),
+                "port_end".to_string(),
+                "service".to_string(),
+            ],
+        );
+
+        let condition = Condition::WithinRange {
+            from: "port_start",
+            to: "port_end",
+            value: 8080.0,
+        };
+        let select = ["service".to_string()];
+        let expected = vec![
+            btreemap! { "service" => "http" },
+            btreemap! { "service" => "proxy" },
+        ];
+
+        // The rows are scanned until the ranges are indexed.
+        assert_eq!(
+            Ok(expected.clone()),
+            file.find_table_rows(Case::Sensitive, &[condition.clone()], Some(&select), None)
+        );
+
+        file.add_lookup_index(&LookupIndex::Range {
+            from: "port_start".to_string(),
+            to: "port_end".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            Ok(expected),
+            file.find_table_rows(Case::Sensitive, &[condition], Some(&select), None)
+        );
+        assert_eq!(
+            Ok(btreemap! { "service" => "system" }),
+            file.find_table_row(
+                Case::Sensitive,
+                &[Condition::WithinRange {
+                    from: "port_start",
+                    to: "port_end",
+                    value: 22.0,
+                }],
+                Some(&select),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn finds_most_specific_network() {
+        let mut file = File::new(
+            Default::default(),
+            SystemTime::now(),
+            vec![
+                vec!["10.0.0.0/8".into(), "corp".into()],
+                vec!["10.1.0.0/16".into(), "payments".into()],
+                vec!["192.168.0.0/16".into(), "lab".into()],
+            ],
+            vec!["network".to_string(), "owner".to_string()],
+        );
+        file.add_lookup_index(&LookupIndex::Cidr {
+            field: "network".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            vec![LookupIndex::Cidr {
+                field: "network".to_string(),
+            }],
+            file.lookup_indexes()
+        );
+
+        let condition = Condition::WithinCidr {
+            field: "network",
+            address: "10.1.2.3".parse().unwrap(),
+        };
+        let select = ["owner".to_string()];
+
+        assert_eq!(
+            Ok(btreemap! { "owner" => "payments" }),
+            file.find_table_row(Case::Sensitive, &[condition.clone()], Some(&select), None)
+        );
+        assert_eq!(
+            Ok(vec![
+                btreemap! { "owner" => "payments" },
+                btreemap! { "owner" => "corp" },
+            ]),
+            file.find_table_rows(Case::Sensitive, &[condition], Some(&select), None)
+        );
+        assert_eq!(
+            Err("no rows found".to_string()),
+            file.find_table_row(
+                Case::Sensitive,
+                &[Condition::WithinCidr {
+                    field: "network",
+                    address: "172.16.0.1".parse().unwrap(),
+                }],
+                Some(&select),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn reloads_modified_file() {
+        let path = crate::test_util::temp_file();
+        fs::write(&path, "field1,field2\nzip,zup\n").unwrap();
+        let config = FileConfig {
+            file: FileC {
+                path: path.clone(),
+                encoding: Default::default(),
+            },
+            ..Default::default()
+        };
+        let (headers, data, _) = config.load_file(Default::default()).unwrap();
+        // Loaded long before the file was last modified, so that it needs reloading.
+        let mut file = File::new(config, SystemTime::UNIX_EPOCH, data, headers);
+        let handle = file.add_index(Case::Sensitive, &["field1"]).unwrap();
+        let copy = file.clone();
+
+        fs::write(&path, "field2,field1\nzup,zip\nzurp,zirp\n").unwrap();
+        assert!(file.reload(Default::default()).unwrap());
+        assert!(!file.reload(Default::default()).unwrap());
+
+        let condition = Condition::Equals {
+            field: "field1",
+            value: Value::from("zirp"),
+        };
+        assert_eq!(
+            Ok(btreemap! {
+                "field1" => "zirp",
+                "field2" => "zurp",
+            }),
+            copy.find_table_row(Case::Sensitive, &[condition], None, Some(handle))
+        );
+        assert_eq!(
+            vec![(Case::Sensitive, vec!["field1".to_string()])],
+            copy.index_fields()
+        );
+    }
 }
@@ -0,0 +1,560 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use enrichment::{Case, Condition, IndexHandle, Table};
+use http::{Request, StatusCode};
+use hyper::Body;
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+use url::Url;
+use vrl::Value;
+
+use super::lookup::{
+    blocking, row_matches, select_fields, single_or_err, value_to_string, CacheConfig, LookupCache,
+    Row,
+};
+use crate::{
+    config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig, ProxyConfig},
+    http::HttpClient,
+    tls::{TlsOptions, TlsSettings},
+};
+
+/// Lookups are done synchronously, as VRL functions are, so the requests are sent from a runtime
+/// of their own, whichever runtime the lookups are done from, while the lookups wait for them.
+static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("vector-enrichment-http")
+        .enable_all()
+        .build()
+        .expect("Unable to create the runtime of the http enrichment tables.")
+});
+
+const fn default_timeout_secs() -> u64 {
+    5
+}
+
+const fn default_failure_threshold() -> u32 {
+    5
+}
+
+const fn default_reset_timeout_secs() -> u64 {
+    30
+}
+
+/// An enrichment table looking rows up from a JSON API. The fields matched exactly by the lookups
+/// replace their `{field}` placeholders in the URL, or are otherwise sent as query parameters. A
+/// JSON object in the response is a single row, and an array of objects is many rows.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    url: String,
+    #[serde(default)]
+    headers: IndexMap<String, String>,
+    tls: Option<TlsOptions>,
+    /// How long lookups wait for a response, before failing.
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+    #[serde(default)]
+    cache: CacheConfig,
+    #[serde(default)]
+    circuit_breaker: CircuitBreakerConfig,
+}
+
+/// Once the API failed `failure_threshold` lookups in a row, lookups fail right away for
+/// `reset_timeout_secs`, instead of waiting for the API, until a lookup is tried again.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct CircuitBreakerConfig {
+    #[serde(default = "default_failure_threshold")]
+    failure_threshold: u32,
+    #[serde(default = "default_reset_timeout_secs")]
+    reset_timeout_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            reset_timeout_secs: default_reset_timeout_secs(),
+        }
+    }
+}
+
+impl GenerateConfig for HttpConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            url: "https://inventory.example.com/api/assets/{asset_id}".to_owned(),
+            headers: IndexMap::new(),
+            tls: None,
+            timeout_secs: default_timeout_secs(),
+            cache: CacheConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "http")]
+impl EnrichmentTableConfig for HttpConfig {
+    async fn build(
+        &self,
+        globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Http::new(self.clone(), &globals.proxy)?))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<HttpConfig>("http")
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new(config: &CircuitBreakerConfig) -> Self {
+        Self {
+            failure_threshold: config.failure_threshold.max(1),
+            reset_timeout: Duration::from_secs(config.reset_timeout_secs),
+            failures: 0,
+            open_until: None,
+        }
+    }
+
+    /// Returns whether lookups can be tried. Once the reset timeout elapsed, a single lookup is
+    /// let through, the circuit being opened again unless it succeeds.
+    fn allows(&mut self, now: Instant) -> bool {
+        match self.open_until {
+            Some(open_until) if now < open_until => false,
+            Some(_) => {
+                self.open_until = Some(now + self.reset_timeout);
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn succeeded(&mut self) {
+        self.failures = 0;
+        self.open_until = None;
+    }
+
+    fn failed(&mut self, now: Instant) {
+        self.failures += 1;
+        if self.failures >= self.failure_threshold {
+            self.open_until = Some(now + self.reset_timeout);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Http {
+    config: HttpConfig,
+    client: HttpClient,
+    cache: LookupCache,
+    circuit: Arc<Mutex<CircuitBreaker>>,
+    indexes: Vec<(Case, Vec<String>)>,
+}
+
+impl Http {
+    fn new(config: HttpConfig, proxy: &ProxyConfig) -> crate::Result<Self> {
+        let tls_settings = TlsSettings::from_options(&config.tls)?;
+        Url::parse(&config.url)?;
+
+        Ok(Self {
+            client: HttpClient::new(tls_settings, proxy)?,
+            cache: LookupCache::new(&config.cache),
+            circuit: Arc::new(Mutex::new(CircuitBreaker::new(&config.circuit_breaker))),
+            indexes: Vec::new(),
+            config,
+        })
+    }
+
+    /// The URL to look up, along with the conditions left to match against the rows returned.
+    fn lookup_url<'a>(
+        &self,
+        condition: &'a [Condition<'a>],
+    ) -> Result<(String, Vec<&'a Condition<'a>>), String> {
+        let mut url = self.config.url.clone();
+        let mut query = Vec::new();
+        let mut remaining = Vec::new();
+        for condition in condition {
+            match condition {
+                Condition::Equals { field, value } => {
+                    let value = value_to_string(value);
+                    let placeholder = format!("{{{}}}", field);
+                    if url.contains(&placeholder) {
+                        let value = utf8_percent_encode(&value, NON_ALPHANUMERIC).to_string();
+                        url = url.replace(&placeholder, &value);
+                    } else {
+                        query.push((*field, value));
+                    }
+                }
+                condition => remaining.push(condition),
+            }
+        }
+
+        let mut url = Url::parse(&url).map_err(|error| format!("invalid lookup URL: {}", error))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok((url.into(), remaining))
+    }
+
+    fn fetch(&self, url: &str) -> Result<Vec<Row>, String> {
+        let mut builder = Request::get(url).header("Accept", "application/json");
+        for (header, value) in &self.config.headers {
+            builder = builder.header(header.as_str(), value.as_str());
+        }
+        let request = builder
+            .body(Body::empty())
+            .map_err(|error| format!("invalid lookup request: {}", error))?;
+
+        let client = self.client.clone();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        RUNTIME.spawn(async move {
+            let response = async {
+                let response = client
+                    .send(request)
+                    .await
+                    .map_err(|error| format!("lookup failed: {}", error))?;
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body())
+                    .await
+                    .map_err(|error| format!("lookup failed: {}", error))?;
+                Ok::<_, String>((status, body))
+            };
+            let result = tokio::time::timeout(timeout, response)
+                .await
+                .unwrap_or_else(|_| Err("lookup timed out".to_owned()));
+            // The lookup waits for the result, unless it panicked.
+            let _ = tx.send(result);
+        });
+        let (status, body) =
+            blocking(|| rx.recv()).map_err(|_| "lookup was cancelled".to_owned())??;
+
+        match status {
+            StatusCode::NOT_FOUND => Ok(Vec::new()),
+            status if status.is_success() => {
+                match serde_json::from_slice(&body)
+                    .map_err(|error| format!("invalid lookup response: {}", error))?
+                {
+                    serde_json::Value::Array(rows) => rows.into_iter().map(json_row).collect(),
+                    row => Ok(vec![json_row(row)?]),
+                }
+            }
+            status => Err(format!("lookup failed with status {}", status)),
+        }
+    }
+
+    fn lookup(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        select: Option<&[String]>,
+    ) -> Result<Vec<Row>, String> {
+        let (url, remaining) = self.lookup_url(condition)?;
+        let rows = match self.cache.get(&url) {
+            Some(rows) => rows,
+            None => {
+                if !self
+                    .circuit
+                    .lock()
+                    .expect("circuit breaker poisoned")
+                    .allows(Instant::now())
+                {
+                    return Err("lookups are failing, so they are paused".to_owned());
+                }
+
+                let result = self.fetch(&url);
+                let mut circuit = self.circuit.lock().expect("circuit breaker poisoned");
+                match result {
+                    Ok(rows) => {
+                        circuit.succeeded();
+                        self.cache.insert(url, rows.clone());
+                        rows
+                    }
+                    Err(error) => {
+                        circuit.failed(Instant::now());
+                        return Err(error);
+                    }
+                }
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .filter(|row| {
+                remaining
+                    .iter()
+                    .all(|condition| row_matches(case, condition, row))
+            })
+            .map(|row| select_fields(row, select))
+            .collect())
+    }
+}
+
+fn json_row(value: serde_json::Value) -> Result<Row, String> {
+    match Value::from(value) {
+        Value::Object(row) => Ok(row),
+        _ => Err("lookup response rows must be objects".to_owned()),
+    }
+}
+
+impl Table for Http {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        single_or_err(self.lookup(case, condition, select)?)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.lookup(case, condition, select)
+    }
+
+    /// The API does the actual lookups, so indexes are only recorded.
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        let fields = fields.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let position = match self
+            .indexes
+            .iter()
+            .position(|index| index.0 == case && index.1 == fields)
+        {
+            Some(position) => position,
+            None => {
+                self.indexes.push((case, fields));
+                self.indexes.len() - 1
+            }
+        };
+        Ok(IndexHandle(position))
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes.clone()
+    }
+
+    /// Rows are looked up on demand, so the table never needs reloading.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for Http {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Http {}", self.config.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        net::SocketAddr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Response, Server,
+    };
+
+    use super::*;
+    use crate::test_util::next_addr;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HttpConfig>();
+    }
+
+    fn config(url: String) -> HttpConfig {
+        HttpConfig {
+            url,
+            headers: IndexMap::new(),
+            tls: None,
+            timeout_secs: 1,
+            cache: CacheConfig::default(),
+            circuit_breaker: CircuitBreakerConfig {
+                failure_threshold: 2,
+                reset_timeout_secs: 60,
+            },
+        }
+    }
+
+    /// Serves the given body on every request, counting them.
+    fn serve(status: StatusCode, body: &'static str) -> (SocketAddr, Arc<AtomicUsize>) {
+        serve_after(Duration::ZERO, status, body)
+    }
+
+    /// Serves the given body on every request once the delay elapsed, counting them.
+    fn serve_after(
+        delay: Duration,
+        status: StatusCode,
+        body: &'static str,
+    ) -> (SocketAddr, Arc<AtomicUsize>) {
+        let addr = next_addr();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let make_service = make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        tokio::time::sleep(delay).await;
+                        Ok::<_, Infallible>(
+                            Response::builder()
+                                .status(status)
+                                .body(Body::from(body))
+                                .unwrap(),
+                        )
+                    }
+                }))
+            }
+        });
+        // Bound from the runtime of the lookups, which drives it while the tests block on them.
+        let _guard = RUNTIME.enter();
+        RUNTIME.spawn(Server::bind(&addr).serve(make_service));
+        (addr, requests)
+    }
+
+    fn equals<'a>(field: &'a str, value: &str) -> Condition<'a> {
+        Condition::Equals {
+            field,
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn builds_lookup_urls() {
+        let table = Http::new(
+            config("https://example.com/users/{user}".to_owned()),
+            &ProxyConfig::default(),
+        )
+        .unwrap();
+        let condition = [equals("user", "a b"), equals("team", "core")];
+        let (url, remaining) = table.lookup_url(&condition).unwrap();
+        assert_eq!(url, "https://example.com/users/a%20b?team=core");
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn looks_up_and_caches_rows() {
+        let (addr, requests) = serve(
+            StatusCode::OK,
+            r#"[{"user": "alice", "team": "core"}, {"user": "bob", "team": "docs"}]"#,
+        );
+        let table = Http::new(
+            config(format!("http://{}/users", addr)),
+            &Default::default(),
+        )
+        .unwrap();
+
+        let condition = [equals("team", "core")];
+        let rows = table
+            .find_table_rows(Case::Sensitive, &condition, None, None)
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get("user"), Some(&Value::from("alice")));
+
+        table
+            .find_table_rows(Case::Sensitive, &condition, None, None)
+            .unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn opens_circuit_after_failures() {
+        let (addr, requests) = serve(StatusCode::INTERNAL_SERVER_ERROR, "");
+        let table = Http::new(
+            config(format!("http://{}/users/{{user}}", addr)),
+            &Default::default(),
+        )
+        .unwrap();
+
+        for user in ["alice", "bob", "carol"] {
+            assert!(table
+                .find_table_row(Case::Sensitive, &[equals("user", user)], None, None)
+                .is_err());
+        }
+        // The third lookup didn't reach the API.
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn looks_up_concurrently_without_stalling_the_runtime() {
+        let delay = Duration::from_millis(500);
+        let (addr, requests) = serve_after(delay, StatusCode::OK, r#"{"team": "core"}"#);
+        let table = Http::new(
+            config(format!("http://{}/users/{{user}}", addr)),
+            &Default::default(),
+        )
+        .unwrap();
+
+        let start = Instant::now();
+        let lookups = ["alice", "bob", "carol", "dave"].into_iter().map(|user| {
+            let table = table.clone();
+            tokio::spawn(async move {
+                table.find_table_row(Case::Sensitive, &[equals("user", user)], None, None)
+            })
+        });
+        let lookups = futures::future::join_all(lookups);
+        // The lookups blocking both workers don't keep the other tasks from running.
+        let ticker = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            start.elapsed()
+        });
+
+        for lookup in lookups.await {
+            assert_eq!(
+                lookup.unwrap().unwrap().get("team"),
+                Some(&Value::from("core"))
+            );
+        }
+        assert!(ticker.await.unwrap() < delay);
+        // The lookups were sent concurrently, rather than one after the other.
+        assert!(start.elapsed() < delay * 4);
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn circuit_is_tried_again_after_reset_timeout() {
+        let mut circuit = CircuitBreaker::new(&CircuitBreakerConfig {
+            failure_threshold: 1,
+            reset_timeout_secs: 10,
+        });
+        let now = Instant::now();
+
+        assert!(circuit.allows(now));
+        circuit.failed(now);
+        assert!(!circuit.allows(now + Duration::from_secs(5)));
+        assert!(circuit.allows(now + Duration::from_secs(11)));
+        // Only a single lookup is let through.
+        assert!(!circuit.allows(now + Duration::from_secs(12)));
+        circuit.succeeded();
+        assert!(circuit.allows(now + Duration::from_secs(12)));
+    }
+}
@@ -0,0 +1,242 @@
+//! The indexes for the conditions which don't match fields exactly, finding the rows whose range
+//! contains a number, or whose CIDR network contains an address.
+
+#[cfg(feature = "enrichment-tables-file")]
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+use vrl::Value;
+
+/// The number in a field. CSV files loaded without a schema hold strings, which are parsed.
+pub(super) fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(value) => Some(*value as f64),
+        Value::Float(value) => Some(value.into_inner()),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|value: &f64| !value.is_nan())
+}
+
+/// A CIDR network, such as `10.0.0.0/8`. A field holding a plain address is the network made of
+/// that single address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Network {
+    v6: bool,
+    bits: u128,
+    prefix_len: u8,
+}
+
+impl Network {
+    pub(super) fn from_value(value: &Value) -> Option<Self> {
+        let value = std::str::from_utf8(value.as_bytes()?).ok()?.trim();
+        let (address, prefix_len) = match value.split_once('/') {
+            Some((address, prefix_len)) => (address.parse().ok()?, Some(prefix_len.parse().ok()?)),
+            None => (value.parse().ok()?, None),
+        };
+
+        let (v6, bits, max_len) = address_bits(address);
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        (prefix_len <= max_len).then(|| Self {
+            v6,
+            bits: mask(bits, prefix_len, max_len),
+            prefix_len,
+        })
+    }
+
+    pub(super) const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub(super) fn contains(&self, address: IpAddr) -> bool {
+        let (v6, bits, max_len) = address_bits(address);
+        v6 == self.v6 && mask(bits, self.prefix_len, max_len) == self.bits
+    }
+}
+
+/// Returns whether the address is an IPv6 one, its bits and its length.
+fn address_bits(address: IpAddr) -> (bool, u128, u8) {
+    match address {
+        IpAddr::V4(address) => (false, u128::from(u32::from(address)), 32),
+        IpAddr::V6(address) => (true, u128::from(address), 128),
+    }
+}
+
+/// Keeps the first `prefix_len` bits of the address.
+fn mask(bits: u128, prefix_len: u8, max_len: u8) -> u128 {
+    let host_len = u32::from(max_len - prefix_len);
+    // Shifting all the bits out overflows.
+    bits.checked_shr(host_len)
+        .and_then(|bits| bits.checked_shl(host_len))
+        .unwrap_or(0)
+}
+
+/// The ranges of the rows, sorted by their start. The furthest end of the ranges up to each one is
+/// kept too, so that looking for the ranges containing a number, from the last range starting
+/// before it, stops as soon as none of the remaining ranges can reach it.
+#[cfg(feature = "enrichment-tables-file")]
+pub(super) struct RangeIndex {
+    starts: Vec<f64>,
+    ends: Vec<f64>,
+    furthest_ends: Vec<f64>,
+    rows: Vec<usize>,
+}
+
+#[cfg(feature = "enrichment-tables-file")]
+impl RangeIndex {
+    /// Indexes the ranges of the rows, given as the position of the row along with the start and
+    /// the end of its range. The rows whose range isn't made of numbers are skipped.
+    pub(super) fn new<'a>(ranges: impl Iterator<Item = (usize, &'a Value, &'a Value)>) -> Self {
+        let mut ranges = ranges
+            .filter_map(|(row, start, end)| Some((value_to_f64(start)?, value_to_f64(end)?, row)))
+            .collect::<Vec<_>>();
+        ranges.sort_by(|(start1, ..), (start2, ..)| {
+            start1.partial_cmp(start2).expect("ranges aren't NaN")
+        });
+
+        let mut furthest_end = f64::NEG_INFINITY;
+        let mut index = Self {
+            starts: Vec::with_capacity(ranges.len()),
+            ends: Vec::with_capacity(ranges.len()),
+            furthest_ends: Vec::with_capacity(ranges.len()),
+            rows: Vec::with_capacity(ranges.len()),
+        };
+        for (start, end, row) in ranges {
+            furthest_end = furthest_end.max(end);
+            index.starts.push(start);
+            index.ends.push(end);
+            index.furthest_ends.push(furthest_end);
+            index.rows.push(row);
+        }
+
+        index
+    }
+
+    /// Returns the positions of the rows whose range contains the number, in order.
+    pub(super) fn find(&self, value: f64) -> Vec<usize> {
+        let count = self.starts.partition_point(|start| *start <= value);
+        let mut rows = (0..count)
+            .rev()
+            .take_while(|idx| self.furthest_ends[*idx] >= value)
+            .filter(|idx| self.ends[*idx] >= value)
+            .map(|idx| self.rows[idx])
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows
+    }
+}
+
+/// The networks of the rows, grouped by the length of their prefix, so that the networks
+/// containing an address are found with a single lookup for each length.
+#[cfg(feature = "enrichment-tables-file")]
+pub(super) struct CidrIndex {
+    // Ordered from the longest prefix, that is the most specific networks.
+    prefixes: Vec<(bool, u8, HashMap<u128, Vec<usize>>)>,
+}
+
+#[cfg(feature = "enrichment-tables-file")]
+impl CidrIndex {
+    /// Indexes the networks of the rows, given as the position of the row along with its network.
+    /// The rows whose network isn't valid are skipped.
+    pub(super) fn new<'a>(networks: impl Iterator<Item = (usize, &'a Value)>) -> Self {
+        let mut prefixes = BTreeMap::<_, HashMap<_, Vec<_>>>::new();
+        for (row, network) in networks {
+            if let Some(network) = Network::from_value(network) {
+                prefixes
+                    .entry((network.v6, network.prefix_len))
+                    .or_default()
+                    .entry(network.bits)
+                    .or_default()
+                    .push(row);
+            }
+        }
+
+        let mut prefixes = prefixes
+            .into_iter()
+            .map(|((v6, prefix_len), networks)| (v6, prefix_len, networks))
+            .collect::<Vec<_>>();
+        prefixes.sort_by(|(_, prefix_len1, _), (_, prefix_len2, _)| prefix_len2.cmp(prefix_len1));
+
+        Self { prefixes }
+    }
+
+    /// Returns the positions of the rows whose network contains the address, from the most
+    /// specific networks, and in order for the networks of the same length.
+    pub(super) fn find(&self, address: IpAddr) -> Vec<usize> {
+        let (v6, bits, max_len) = address_bits(address);
+        self.prefixes
+            .iter()
+            .filter(|(prefix_v6, ..)| *prefix_v6 == v6)
+            .filter_map(|(_, prefix_len, networks)| networks.get(&mask(bits, *prefix_len, max_len)))
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_networks() {
+        let network = Network::from_value(&"10.1.0.0/16".into()).unwrap();
+        assert_eq!(network.prefix_len(), 16);
+        assert!(network.contains("10.1.2.3".parse().unwrap()));
+        assert!(!network.contains("10.2.0.1".parse().unwrap()));
+        assert!(!network.contains("::ffff:a01:203".parse().unwrap()));
+
+        let network = Network::from_value(&"2001:db8::/32".into()).unwrap();
+        assert!(network.contains("2001:db8::1".parse().unwrap()));
+
+        let network = Network::from_value(&"0.0.0.0/0".into()).unwrap();
+        assert!(network.contains("192.168.0.1".parse().unwrap()));
+
+        let network = Network::from_value(&"192.168.0.1".into()).unwrap();
+        assert_eq!(network.prefix_len(), 32);
+
+        assert_eq!(Network::from_value(&"10.0.0.0/33".into()), None);
+        assert_eq!(Network::from_value(&"zork".into()), None);
+    }
+
+    #[cfg(feature = "enrichment-tables-file")]
+    #[test]
+    fn finds_ranges() {
+        let ranges = vec![
+            (Value::from(0), Value::from(1023)),
+            (Value::from("8000"), Value::from("8999")),
+            (Value::from(8080), Value::from(8080)),
+            (Value::from("zork"), Value::from(10)),
+        ];
+        let index = RangeIndex::new(
+            ranges
+                .iter()
+                .enumerate()
+                .map(|(row, (start, end))| (row, start, end)),
+        );
+
+        assert_eq!(index.find(22.0), vec![0]);
+        assert_eq!(index.find(8080.0), vec![1, 2]);
+        assert_eq!(index.find(8081.0), vec![1]);
+        assert!(index.find(5000.0).is_empty());
+        assert!(index.find(-1.0).is_empty());
+    }
+
+    #[cfg(feature = "enrichment-tables-file")]
+    #[test]
+    fn finds_networks() {
+        let networks = vec![
+            Value::from("10.0.0.0/8"),
+            Value::from("10.1.0.0/16"),
+            Value::from("10.1.2.0/24"),
+            Value::from("2001:db8::/32"),
+            Value::from("0.0.0.0/0"),
+        ];
+        let index = CidrIndex::new(networks.iter().enumerate());
+
+        assert_eq!(index.find("10.1.2.3".parse().unwrap()), vec![2, 1, 0, 4]);
+        assert_eq!(index.find("10.2.0.1".parse().unwrap()), vec![0, 4]);
+        assert_eq!(index.find("192.168.0.1".parse().unwrap()), vec![4]);
+        assert_eq!(index.find("2001:db8::1".parse().unwrap()), vec![3]);
+    }
+}
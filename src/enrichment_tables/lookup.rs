@@ -0,0 +1,241 @@
+//! Shared parts of the enrichment tables backed by remote services, which look rows up on demand
+//! instead of loading their data upfront: caching the rows looked up, and filtering them.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use enrichment::{Case, Condition};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use vrl::Value;
+
+use super::index::{value_to_f64, Network};
+
+pub(super) type Row = BTreeMap<String, Value>;
+
+const fn default_ttl_secs() -> u64 {
+    60
+}
+
+const fn default_max_entries() -> usize {
+    10_000
+}
+
+/// How the rows looked up are cached. The lookups finding no rows are cached as well, so that
+/// events with unknown keys don't hammer the backend.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(super) struct CacheConfig {
+    /// How long the rows are reused before being looked up again. Setting it to 0 disables the
+    /// cache.
+    #[serde(default = "default_ttl_secs")]
+    ttl_secs: u64,
+    /// How many lookups are cached, the least recently used ones being evicted first.
+    #[serde(default = "default_max_entries")]
+    max_entries: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: default_ttl_secs(),
+            max_entries: default_max_entries(),
+        }
+    }
+}
+
+/// The cache of the lookups, shared by all the copies of a table.
+#[derive(Clone)]
+pub(super) struct LookupCache {
+    entries: Option<Arc<Mutex<LruCache<String, (Vec<Row>, Instant)>>>>,
+    ttl: Duration,
+}
+
+impl LookupCache {
+    pub(super) fn new(config: &CacheConfig) -> Self {
+        let enabled = config.ttl_secs > 0 && config.max_entries > 0;
+        Self {
+            entries: enabled.then(|| Arc::new(Mutex::new(LruCache::new(config.max_entries)))),
+            ttl: Duration::from_secs(config.ttl_secs),
+        }
+    }
+
+    pub(super) fn get(&self, key: &str) -> Option<Vec<Row>> {
+        let mut entries = self
+            .entries
+            .as_ref()?
+            .lock()
+            .expect("lookup cache poisoned");
+        match entries.get(key) {
+            Some((rows, expires_at)) if *expires_at > Instant::now() => Some(rows.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(super) fn insert(&self, key: String, rows: Vec<Row>) {
+        if let Some(entries) = &self.entries {
+            entries
+                .lock()
+                .expect("lookup cache poisoned")
+                .put(key, (rows, Instant::now() + self.ttl));
+        }
+    }
+}
+
+/// Does the given row match the condition?
+pub(super) fn row_matches(case: Case, condition: &Condition, row: &Row) -> bool {
+    match condition {
+        Condition::Equals { field, value } => match (case, row.get(*field), value) {
+            (_, None, _) => false,
+            (Case::Insensitive, Some(Value::Bytes(bytes1)), Value::Bytes(bytes2)) => {
+                match (std::str::from_utf8(bytes1), std::str::from_utf8(bytes2)) {
+                    (Ok(s1), Ok(s2)) => s1.to_lowercase() == s2.to_lowercase(),
+                    (Err(_), Err(_)) => bytes1 == bytes2,
+                    _ => false,
+                }
+            }
+            (_, Some(value1), value2) => value1 == value2,
+        },
+        Condition::BetweenDates { field, from, to } => match row.get(*field) {
+            Some(Value::Timestamp(date)) => from <= date && date <= to,
+            _ => false,
+        },
+        Condition::WithinRange { from, to, value } => {
+            match (
+                row.get(*from).and_then(value_to_f64),
+                row.get(*to).and_then(value_to_f64),
+            ) {
+                (Some(from), Some(to)) => from <= *value && *value <= to,
+                _ => false,
+            }
+        }
+        Condition::WithinCidr { field, address } => row
+            .get(*field)
+            .and_then(Network::from_value)
+            .map_or(false, |network| network.contains(*address)),
+    }
+}
+
+/// Keeps the fields of the row in the selection, if any.
+pub(super) fn select_fields(row: Row, select: Option<&[String]>) -> Row {
+    match select {
+        Some(select) => row
+            .into_iter()
+            .filter(|(field, _)| select.contains(field))
+            .collect(),
+        None => row,
+    }
+}
+
+/// The single row looked up, as returned by `find_table_row`.
+pub(super) fn single_or_err(mut rows: Vec<Row>) -> Result<Row, String> {
+    match rows.len() {
+        0 => Err("no rows found".to_string()),
+        1 => Ok(rows.remove(0)),
+        _ => Err("more than one row found".to_string()),
+    }
+}
+
+/// Waits for a remote service. VRL functions are synchronous, so the lookups block the worker of the
+/// runtime they are done from. On the multi-threaded runtime, as transforms run on, the worker
+/// hands its other tasks over to another thread in the meantime so that those aren't stalled by
+/// slow lookups. Outside of any runtime, or on a current-thread one, which has no other thread to
+/// hand its tasks over to, the lookups simply block.
+pub(super) fn blocking<T>(wait: impl FnOnce() -> T) -> T {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(wait)
+        }
+        _ => wait(),
+    }
+}
+
+/// The string form of a value being looked up, as used in keys or URLs.
+pub(super) fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(fields: &[(&str, &str)]) -> Row {
+        fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), Value::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn caches_lookups() {
+        let cache = LookupCache::new(&CacheConfig::default());
+        assert_eq!(cache.get("alice"), None);
+
+        cache.insert("alice".to_owned(), vec![row(&[("team", "core")])]);
+        cache.insert("bob".to_owned(), vec![]);
+        assert_eq!(cache.get("alice"), Some(vec![row(&[("team", "core")])]));
+        assert_eq!(cache.get("bob"), Some(vec![]));
+    }
+
+    #[test]
+    fn expires_lookups() {
+        let cache = LookupCache::new(&CacheConfig {
+            ttl_secs: 0,
+            max_entries: 10,
+        });
+        cache.insert("alice".to_owned(), vec![row(&[("team", "core")])]);
+        assert_eq!(cache.get("alice"), None);
+    }
+
+    #[test]
+    fn matches_rows() {
+        let row = row(&[("name", "Alice"), ("team", "core")]);
+        let equals = |field, value: &str| Condition::Equals {
+            field,
+            value: value.into(),
+        };
+
+        assert!(row_matches(Case::Sensitive, &equals("name", "Alice"), &row));
+        assert!(!row_matches(
+            Case::Sensitive,
+            &equals("name", "alice"),
+            &row
+        ));
+        assert!(row_matches(
+            Case::Insensitive,
+            &equals("name", "alice"),
+            &row
+        ));
+        assert!(!row_matches(
+            Case::Insensitive,
+            &equals("email", "alice"),
+            &row
+        ));
+    }
+
+    #[test]
+    fn blocks_outside_of_runtimes() {
+        assert_eq!(blocking(|| 1), 1);
+    }
+
+    #[tokio::test]
+    async fn blocks_on_current_thread_runtimes() {
+        assert_eq!(blocking(|| 1), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocks_on_multi_threaded_runtimes() {
+        assert_eq!(blocking(|| 1), 1);
+    }
+}
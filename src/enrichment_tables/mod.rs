@@ -2,3 +2,18 @@ pub use enrichment::{Condition, IndexHandle, Table};
 
 #[cfg(feature = "enrichment-tables-file")]
 pub mod file;
+#[cfg(feature = "enrichment-tables-http")]
+pub mod http;
+#[cfg(any(
+    feature = "enrichment-tables-file",
+    feature = "enrichment-tables-http",
+    feature = "enrichment-tables-redis"
+))]
+mod index;
+#[cfg(any(
+    feature = "enrichment-tables-http",
+    feature = "enrichment-tables-redis"
+))]
+mod lookup;
+#[cfg(feature = "enrichment-tables-redis")]
+pub mod redis;
@@ -0,0 +1,406 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use enrichment::{Case, Condition, IndexHandle, Table};
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+use vrl::Value;
+
+use super::lookup::{
+    blocking, row_matches, select_fields, single_or_err, value_to_string, CacheConfig, LookupCache,
+    Row,
+};
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+
+const fn default_timeout_ms() -> u64 {
+    500
+}
+
+/// How many connections are kept open once the lookups using them are done.
+const MAX_IDLE_CONNECTIONS: usize = 16;
+
+/// An enrichment table looking rows up in Redis, each row being stored as a hash, at the key made
+/// of the `key_prefix` and the value of the `key_field` being looked up.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RedisConfig {
+    url: String,
+    /// The field holding the key of the rows, which all lookups must match exactly.
+    key_field: String,
+    #[serde(default)]
+    key_prefix: String,
+    /// How long lookups wait for Redis, before failing.
+    #[serde(default = "default_timeout_ms")]
+    timeout_ms: u64,
+    #[serde(default)]
+    cache: CacheConfig,
+}
+
+impl GenerateConfig for RedisConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            url: "redis://127.0.0.1:6379/0".to_owned(),
+            key_field: "user_id".to_owned(),
+            key_prefix: "user:".to_owned(),
+            timeout_ms: default_timeout_ms(),
+            cache: CacheConfig::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl EnrichmentTableConfig for RedisConfig {
+    async fn build(
+        &self,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Redis::new(self.clone())?))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<RedisConfig>("redis")
+}
+
+#[derive(Clone)]
+pub struct Redis {
+    config: RedisConfig,
+    client: redis::Client,
+    // Lookups are done synchronously, as VRL functions are, so over blocking connections. Each
+    // concurrent lookup takes one of the idle connections, or opens a new one.
+    connections: Arc<Mutex<Vec<redis::Connection>>>,
+    cache: LookupCache,
+    indexes: Vec<(Case, Vec<String>)>,
+}
+
+impl Redis {
+    fn new(config: RedisConfig) -> crate::Result<Self> {
+        Ok(Self {
+            client: redis::Client::open(config.url.as_str())?,
+            connections: Arc::new(Mutex::new(Vec::new())),
+            cache: LookupCache::new(&config.cache),
+            indexes: Vec::new(),
+            config,
+        })
+    }
+
+    fn key(&self, condition: &[Condition]) -> Result<String, String> {
+        condition
+            .iter()
+            .find_map(|condition| match condition {
+                Condition::Equals { field, value } if *field == self.config.key_field => Some(
+                    format!("{}{}", self.config.key_prefix, value_to_string(value)),
+                ),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                format!(
+                    "lookups must match field \"{}\" exactly",
+                    self.config.key_field
+                )
+            })
+    }
+
+    fn connect(&self) -> Result<redis::Connection, String> {
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        self.client
+            .get_connection_with_timeout(timeout)
+            .and_then(|connection| {
+                connection.set_read_timeout(Some(timeout))?;
+                connection.set_write_timeout(Some(timeout))?;
+                Ok(connection)
+            })
+            .map_err(|error| format!("unable to connect to redis: {}", error))
+    }
+
+    fn fetch(&self, key: &str) -> Result<HashMap<String, String>, String> {
+        let idle = self
+            .connections
+            .lock()
+            .expect("redis connections poisoned")
+            .pop();
+        let mut connection = match idle {
+            Some(connection) => connection,
+            None => self.connect()?,
+        };
+
+        let result: redis::RedisResult<HashMap<String, String>> = connection.hgetall(key);
+        match result {
+            Ok(hash) => {
+                let mut connections = self.connections.lock().expect("redis connections poisoned");
+                if connections.len() < MAX_IDLE_CONNECTIONS {
+                    connections.push(connection);
+                }
+                Ok(hash)
+            }
+            // The connection may be broken, so it's dropped instead of being reused.
+            Err(error) => Err(format!("redis lookup failed: {}", error)),
+        }
+    }
+
+    fn lookup(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        select: Option<&[String]>,
+    ) -> Result<Vec<Row>, String> {
+        let key = self.key(condition)?;
+        let rows = match self.cache.get(&key) {
+            Some(rows) => rows,
+            None => {
+                let hash = blocking(|| self.fetch(&key))?;
+                // Missing keys are returned as empty hashes.
+                let rows = if hash.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![hash
+                        .into_iter()
+                        .map(|(field, value)| (field, Value::from(value)))
+                        .collect::<Row>()]
+                };
+                self.cache.insert(key, rows.clone());
+                rows
+            }
+        };
+
+        // The key was matched by the lookup itself, whatever the type of the value looked up, while
+        // the other conditions are matched against the fields of the hash.
+        let other_conditions = condition
+            .iter()
+            .filter(|condition| {
+                !matches!(condition, Condition::Equals { field, .. } if *field == self.config.key_field)
+            })
+            .collect::<Vec<_>>();
+        Ok(rows
+            .into_iter()
+            .filter(|row| {
+                other_conditions
+                    .iter()
+                    .all(|condition| row_matches(case, condition, row))
+            })
+            .map(|row| select_fields(row, select))
+            .collect())
+    }
+}
+
+impl Table for Redis {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        single_or_err(self.lookup(case, condition, select)?)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.lookup(case, condition, select)
+    }
+
+    /// Rows are only ever looked up by key, so the only valid indexes are those including the key.
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        if !fields.contains(&self.config.key_field.as_str()) {
+            return Err(format!(
+                "lookups must match field \"{}\" exactly",
+                self.config.key_field
+            ));
+        }
+
+        let fields = fields.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let position = match self
+            .indexes
+            .iter()
+            .position(|index| index.0 == case && index.1 == fields)
+        {
+            Some(position) => position,
+            None => {
+                self.indexes.push((case, fields));
+                self.indexes.len() - 1
+            }
+        };
+        Ok(IndexHandle(position))
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.indexes.clone()
+    }
+
+    /// Rows are looked up on demand, so the table never needs reloading.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for Redis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Redis {} key field {}",
+            self.config.url, self.config.key_field
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisConfig>();
+    }
+
+    #[test]
+    fn builds_keys() {
+        let config: RedisConfig = toml::from_str(
+            r#"
+            url = "redis://127.0.0.1:6379/0"
+            key_field = "user_id"
+            key_prefix = "user:"
+            "#,
+        )
+        .unwrap();
+        let mut table = Redis::new(config).unwrap();
+
+        let condition = [
+            Condition::Equals {
+                field: "user_id",
+                value: Value::Integer(42),
+            },
+            Condition::Equals {
+                field: "team",
+                value: Value::from("core"),
+            },
+        ];
+        assert_eq!(
+            table.add_index(Case::Sensitive, &["team", "user_id"]),
+            Ok(IndexHandle(0))
+        );
+        assert!(table.add_index(Case::Sensitive, &["team"]).is_err());
+
+        assert_eq!(table.key(&condition), Ok("user:42".to_owned()));
+        assert!(table.key(&condition[1..]).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "redis-integration-tests"))]
+mod integration_tests {
+    use redis::Commands;
+
+    use super::*;
+
+    fn redis_server() -> String {
+        std::env::var("REDIS_SERVER").unwrap_or_else(|_| "redis://127.0.0.1:6379/0".to_owned())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn looks_up_hashes() {
+        let url = redis_server();
+        let mut connection = redis::Client::open(url.as_str())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        let _: () = connection
+            .hset_multiple("enrichment:alice", &[("team", "core"), ("role", "admin")])
+            .unwrap();
+
+        let config = RedisConfig {
+            url,
+            key_field: "user".to_owned(),
+            key_prefix: "enrichment:".to_owned(),
+            timeout_ms: default_timeout_ms(),
+            cache: CacheConfig::default(),
+        };
+        let table = config.build(&Default::default()).await.unwrap();
+
+        let row = table
+            .find_table_row(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "user",
+                    value: "alice".into(),
+                }],
+                Some(&["team".to_owned()]),
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            vec![("team".to_owned(), "core".into())]
+                .into_iter()
+                .collect()
+        );
+
+        let rows = table
+            .find_table_rows(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "user",
+                    value: "bob".into(),
+                }],
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn looks_up_concurrently() {
+        let url = redis_server();
+        let mut connection = redis::Client::open(url.as_str())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        for user in 0..8 {
+            let _: () = connection
+                .hset(format!("concurrent:{}", user), "user", user)
+                .unwrap();
+        }
+
+        let table = Redis::new(RedisConfig {
+            url,
+            key_field: "user".to_owned(),
+            key_prefix: "concurrent:".to_owned(),
+            timeout_ms: default_timeout_ms(),
+            cache: CacheConfig::default(),
+        })
+        .unwrap();
+
+        let lookups = (0..8).map(|user| {
+            let table = table.clone();
+            tokio::spawn(async move {
+                table.find_table_row(
+                    Case::Sensitive,
+                    &[Condition::Equals {
+                        field: "user",
+                        value: Value::Integer(user),
+                    }],
+                    None,
+                    None,
+                )
+            })
+        });
+        for (user, lookup) in futures::future::join_all(lookups)
+            .await
+            .into_iter()
+            .enumerate()
+        {
+            let row = lookup.unwrap().unwrap();
+            assert_eq!(row.get("user"), Some(&Value::from(user.to_string())));
+        }
+        assert!(!table.connections.lock().unwrap().is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 
 use futures::future::BoxFuture;
 use headers::{Authorization, HeaderMapExt};
-use http::{header::HeaderValue, request::Builder, uri::InvalidUri, HeaderMap, Request};
+use http::{header::HeaderValue, request::Builder, HeaderMap, Request};
 use hyper::{
     body::{Body, HttpBody},
     client,
@@ -17,13 +17,17 @@ use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use tower::Service;
 use tracing_futures::Instrument;
+use vector_core::config::proxy::ProxyError;
 
+pub use self::socks::Socks5Connector;
 use crate::{
     config::ProxyConfig,
     internal_events::http_client,
     tls::{tls_connector_builder, MaybeTlsSettings, TlsError},
 };
 
+mod socks;
+
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub(crate)))]
 pub enum HttpError {
@@ -32,17 +36,20 @@ pub enum HttpError {
     #[snafu(display("Failed to build HTTPS connector: {}", source))]
     MakeHttpsConnector { source: openssl::error::ErrorStack },
     #[snafu(display("Failed to build Proxy connector: {}", source))]
-    MakeProxyConnector { source: InvalidUri },
+    MakeProxyConnector { source: ProxyError },
     #[snafu(display("Failed to make HTTP(S) request: {}", source))]
     CallRequest { source: hyper::Error },
     #[snafu(display("Failed to build HTTP request: {}", source))]
     BuildRequest { source: http::Error },
 }
 
+/// The connector of the HTTP clients, through the configured proxies.
+pub type HttpsProxyConnector = ProxyConnector<HttpsConnector<Socks5Connector>>;
+
 pub type HttpClientFuture = <HttpClient as Service<http::Request<Body>>>::Future;
 
 pub struct HttpClient<B = Body> {
-    client: Client<ProxyConnector<HttpsConnector<HttpConnector>>, B>,
+    client: Client<HttpsProxyConnector, B>,
     user_agent: HeaderValue,
 }
 
@@ -127,9 +134,10 @@ where
 pub fn build_proxy_connector(
     tls_settings: MaybeTlsSettings,
     proxy_config: &ProxyConfig,
-) -> Result<ProxyConnector<HttpsConnector<HttpConnector>>, HttpError> {
+) -> Result<HttpsProxyConnector, HttpError> {
     let mut http = HttpConnector::new();
     http.enforce_http(false);
+    let http = Socks5Connector::new(http, proxy_config).context(MakeProxyConnectorSnafu)?;
 
     let tls = tls_connector_builder(&tls_settings).context(BuildTlsConnectorSnafu)?;
     let mut https = HttpsConnector::with_connector(http, tls).context(MakeHttpsConnectorSnafu)?;
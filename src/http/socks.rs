@@ -0,0 +1,303 @@
+//! The connector tunneling the connections through a SOCKS5 proxy, as specified by RFC 1928, with
+//! the user name and password authentication of RFC 1929.
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use http::Uri;
+use hyper::client::HttpConnector;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tower::Service;
+use vector_core::config::proxy::{ProxyError, Socks5Proxy};
+
+use crate::config::ProxyConfig;
+
+const VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+const USER_PASSWORD_AUTHENTICATION: u8 = 0x02;
+const NO_ACCEPTABLE_METHODS: u8 = 0xff;
+const USER_PASSWORD_VERSION: u8 = 0x01;
+const CONNECT: u8 = 0x01;
+const IPV4: u8 = 0x01;
+const DOMAIN_NAME: u8 = 0x03;
+const IPV6: u8 = 0x04;
+
+/// Connects directly, or through the SOCKS5 proxy unless the host matches `no_proxy`.
+#[derive(Clone)]
+pub struct Socks5Connector {
+    http: HttpConnector,
+    socks5: Option<Arc<(Socks5Proxy, ProxyConfig)>>,
+}
+
+impl Socks5Connector {
+    pub fn new(http: HttpConnector, proxy_config: &ProxyConfig) -> Result<Self, ProxyError> {
+        let socks5 = proxy_config
+            .socks5_proxy()?
+            .map(|proxy| Arc::new((proxy, proxy_config.clone())));
+        Ok(Self { http, socks5 })
+    }
+}
+
+impl Service<Uri> for Socks5Connector {
+    type Response = TcpStream;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<TcpStream, crate::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let host = uri.host().unwrap_or_default().to_owned();
+        let port = uri
+            .port_u16()
+            .unwrap_or(if uri.scheme_str() == Some("https") {
+                443
+            } else {
+                80
+            });
+        match &self.socks5 {
+            Some(socks5) if !socks5.1.bypasses(&host, Some(port)) => {
+                let socks5 = Arc::clone(socks5);
+                Box::pin(async move {
+                    connect(&socks5.0, &host, port).await.map_err(|error| {
+                        format!("SOCKS5 proxy connection failed: {}", error).into()
+                    })
+                })
+            }
+            _ => {
+                let connecting = self.http.call(uri);
+                Box::pin(async move { connecting.await.map_err(Into::into) })
+            }
+        }
+    }
+}
+
+fn protocol_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Opens a connection to the host through the proxy.
+pub async fn connect(proxy: &Socks5Proxy, host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port)).await?;
+    stream.set_nodelay(true)?;
+
+    // The brackets of IPv6 addresses are kept by the URIs.
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    let address = match host.parse::<IpAddr>() {
+        Ok(address) => Some(address),
+        Err(_) if proxy.remote_dns => None,
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await?
+            .next()
+            .map(|address: SocketAddr| address.ip()),
+    };
+
+    negotiate(&mut stream, proxy).await?;
+
+    let mut request = vec![VERSION, CONNECT, 0x00];
+    match address {
+        Some(IpAddr::V4(address)) => {
+            request.push(IPV4);
+            request.extend_from_slice(&address.octets());
+        }
+        Some(IpAddr::V6(address)) => {
+            request.push(IPV6);
+            request.extend_from_slice(&address.octets());
+        }
+        None => {
+            let length = u8::try_from(host.len())
+                .map_err(|_| protocol_error("host name is too long for SOCKS5"))?;
+            request.push(DOMAIN_NAME);
+            request.push(length);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        return Err(protocol_error("invalid SOCKS5 reply version"));
+    }
+    if reply[1] != 0x00 {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "SOCKS5 proxy refused the connection: {}",
+                reply_message(reply[1])
+            ),
+        ));
+    }
+    // The address the proxy bound is of no use.
+    let bound_length = match reply[3] {
+        IPV4 => 4,
+        IPV6 => 16,
+        DOMAIN_NAME => usize::from(stream.read_u8().await?),
+        _ => return Err(protocol_error("invalid SOCKS5 address type")),
+    };
+    let mut bound = vec![0; bound_length + 2];
+    stream.read_exact(&mut bound).await?;
+
+    Ok(stream)
+}
+
+/// Agrees on the authentication method, and authenticates.
+async fn negotiate(stream: &mut TcpStream, proxy: &Socks5Proxy) -> io::Result<()> {
+    let greeting: &[u8] = match proxy.credentials {
+        Some(_) => &[VERSION, 2, NO_AUTHENTICATION, USER_PASSWORD_AUTHENTICATION],
+        None => &[VERSION, 1, NO_AUTHENTICATION],
+    };
+    stream.write_all(greeting).await?;
+
+    let mut choice = [0; 2];
+    stream.read_exact(&mut choice).await?;
+    if choice[0] != VERSION {
+        return Err(protocol_error("invalid SOCKS5 reply version"));
+    }
+    match (choice[1], &proxy.credentials) {
+        (NO_AUTHENTICATION, _) => Ok(()),
+        (USER_PASSWORD_AUTHENTICATION, Some((user, password))) => {
+            let user_length = u8::try_from(user.len())
+                .map_err(|_| protocol_error("SOCKS5 user name is too long"))?;
+            let password_length = u8::try_from(password.len())
+                .map_err(|_| protocol_error("SOCKS5 password is too long"))?;
+            let mut request = vec![USER_PASSWORD_VERSION, user_length];
+            request.extend_from_slice(user.as_bytes());
+            request.push(password_length);
+            request.extend_from_slice(password.as_bytes());
+            stream.write_all(&request).await?;
+
+            let mut status = [0; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] == 0x00 {
+                Ok(())
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "SOCKS5 proxy rejected the credentials",
+                ))
+            }
+        }
+        (NO_ACCEPTABLE_METHODS, _) => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "SOCKS5 proxy accepts none of the authentication methods",
+        )),
+        _ => Err(protocol_error("invalid SOCKS5 authentication method")),
+    }
+}
+
+const fn reply_message(reply: u8) -> &'static str {
+    match reply {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Accepts a single connection as a SOCKS5 proxy, checking the request, then echoes.
+    async fn proxy(expected_request: Vec<u8>, credentials: bool) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let mut greeting = [0; 2];
+            stream.read_exact(&mut greeting).await.unwrap();
+            let mut methods = vec![0; usize::from(greeting[1])];
+            stream.read_exact(&mut methods).await.unwrap();
+            if credentials {
+                assert!(methods.contains(&USER_PASSWORD_AUTHENTICATION));
+                stream
+                    .write_all(&[VERSION, USER_PASSWORD_AUTHENTICATION])
+                    .await
+                    .unwrap();
+                let mut authentication = vec![0; 2 + 4 + 1 + 6];
+                stream.read_exact(&mut authentication).await.unwrap();
+                assert_eq!(&authentication, b"\x01\x04user\x06secret");
+                stream.write_all(&[USER_PASSWORD_VERSION, 0]).await.unwrap();
+            } else {
+                stream
+                    .write_all(&[VERSION, NO_AUTHENTICATION])
+                    .await
+                    .unwrap();
+            }
+
+            let mut request = vec![0; expected_request.len()];
+            stream.read_exact(&mut request).await.unwrap();
+            assert_eq!(request, expected_request);
+            stream
+                .write_all(&[VERSION, 0, 0, IPV4, 127, 0, 0, 1, 0, 80])
+                .await
+                .unwrap();
+
+            let mut data = [0; 4];
+            stream.read_exact(&mut data).await.unwrap();
+            stream.write_all(&data).await.unwrap();
+        });
+        port
+    }
+
+    async fn assert_tunnels(mut stream: TcpStream) {
+        stream.write_all(b"ping").await.unwrap();
+        let mut data = [0; 4];
+        stream.read_exact(&mut data).await.unwrap();
+        assert_eq!(&data, b"ping");
+    }
+
+    #[tokio::test]
+    async fn connects_with_remote_dns_and_credentials() {
+        let mut expected = vec![VERSION, CONNECT, 0, DOMAIN_NAME, 11];
+        expected.extend_from_slice(b"example.com");
+        expected.extend_from_slice(&443u16.to_be_bytes());
+        let port = proxy(expected, true).await;
+
+        let proxy = Socks5Proxy {
+            host: "127.0.0.1".to_owned(),
+            port,
+            credentials: Some(("user".to_owned(), "secret".to_owned())),
+            remote_dns: true,
+        };
+        assert_tunnels(connect(&proxy, "example.com", 443).await.unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn connects_to_addresses() {
+        let mut expected = vec![VERSION, CONNECT, 0, IPV6];
+        expected.extend_from_slice(&"::1".parse::<std::net::Ipv6Addr>().unwrap().octets());
+        expected.extend_from_slice(&8080u16.to_be_bytes());
+        let port = proxy(expected, false).await;
+
+        let proxy = Socks5Proxy {
+            host: "127.0.0.1".to_owned(),
+            port,
+            credentials: None,
+            remote_dns: false,
+        };
+        assert_tunnels(connect(&proxy, "[::1]", 8080).await.unwrap()).await;
+    }
+}
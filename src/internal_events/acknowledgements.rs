@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use metrics::{counter, histogram};
+use vector_core::internal_event::InternalEvent;
+
+use crate::event::BatchStatus;
+
+#[derive(Debug)]
+pub struct AcknowledgementReceived {
+    pub latency: Duration,
+    pub status: BatchStatus,
+}
+
+impl InternalEvent for AcknowledgementReceived {
+    fn emit_metrics(&self) {
+        let status = match self.status {
+            BatchStatus::Delivered => "delivered",
+            BatchStatus::Errored => "errored",
+            BatchStatus::Rejected => "rejected",
+        };
+        histogram!("source_acknowledgement_latency_seconds", self.latency, "status" => status);
+    }
+}
+
+#[derive(Debug)]
+pub struct AcknowledgementTimedOut {
+    pub timeout: Duration,
+    pub action: &'static str,
+}
+
+impl InternalEvent for AcknowledgementTimedOut {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Events not acknowledged in time.",
+            timeout_secs = self.timeout.as_secs_f64(),
+            action = self.action,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        histogram!("source_acknowledgement_latency_seconds", self.timeout, "status" => "timed_out");
+        counter!("source_acknowledgement_timeouts_total", 1, "action" => self.action);
+    }
+}
@@ -0,0 +1,85 @@
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::event::BatchStatus;
+
+#[derive(Debug)]
+pub struct AmqpEventsReceived {
+    pub byte_size: usize,
+    pub count: usize,
+}
+
+impl InternalEvent for AmqpEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpConsumerError {
+    pub error: lapin::Error,
+}
+
+impl InternalEvent for AmqpConsumerError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to consume message.",
+            error = %self.error,
+            error_code = "consuming_message",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "consuming_message",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpAckError {
+    pub error: lapin::Error,
+    pub status: BatchStatus,
+}
+
+impl InternalEvent for AmqpAckError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to acknowledge message.",
+            error = %self.error,
+            status = ?self.status,
+            error_code = "acknowledging_message",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "acknowledging_message",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
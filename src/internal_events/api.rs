@@ -7,11 +7,18 @@ use vector_core::internal_event::InternalEvent;
 pub struct ApiStarted {
     pub addr: SocketAddr,
     pub playground: bool,
+    pub tls: bool,
 }
 
 impl InternalEvent for ApiStarted {
     fn emit_logs(&self) {
-        let playground = &*format!("http://{}:{}/playground", self.addr.ip(), self.addr.port());
+        let scheme = if self.tls { "https" } else { "http" };
+        let playground = &*format!(
+            "{}://{}:{}/playground",
+            scheme,
+            self.addr.ip(),
+            self.addr.port()
+        );
         info!(
             message="API server running.",
             address = ?self.addr,
@@ -23,3 +30,25 @@ impl InternalEvent for ApiStarted {
         counter!("api_started_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct TapEventSkipped<'a> {
+    pub component_id: &'a str,
+}
+
+impl<'a> InternalEvent for TapEventSkipped<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Tap couldn't keep up; skipping event.",
+            component_id = %self.component_id,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "tap_events_skipped_total", 1,
+            "component_id" => self.component_id.to_owned(),
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct AwsCredentialsRefreshed {
+    pub provider: &'static str,
+}
+
+impl InternalEvent for AwsCredentialsRefreshed {
+    fn emit_logs(&self) {
+        debug!(message = "Refreshed AWS credentials.", provider = %self.provider);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "aws_credentials_refreshes_total", 1,
+            "provider" => self.provider,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct AwsCredentialsRefreshError<'a> {
+    pub provider: &'static str,
+    pub error: &'a str,
+}
+
+impl InternalEvent for AwsCredentialsRefreshError<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed refreshing AWS credentials.",
+            provider = %self.provider,
+            error = %self.error,
+            error_code = "refreshing_credentials",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "refreshing_credentials",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "aws_credentials_refresh_errors_total", 1,
+            "provider" => self.provider,
+        );
+    }
+}
@@ -229,3 +229,20 @@ impl<'a> InternalEvent for SqsS3EventRecordInvalidEventIgnored<'a> {
         counter!("sqs_s3_event_record_ignored_total", 1, "ignore_type" => "invalid_event_kind");
     }
 }
+
+#[derive(Debug)]
+pub struct SqsS3EventRecordFilteredKeyIgnored<'a> {
+    pub bucket: &'a str,
+    pub key: &'a str,
+}
+
+impl<'a> InternalEvent for SqsS3EventRecordFilteredKeyIgnored<'a> {
+    fn emit_logs(&self) {
+        debug!(message = "Ignored S3 record in SQS message for an object not matching the key filters.",
+            bucket = %self.bucket, key = %self.key);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("sqs_s3_event_record_ignored_total", 1, "ignore_type" => "filtered_key");
+    }
+}
@@ -0,0 +1,57 @@
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct AzureEventHubsEventsReceived {
+    pub byte_size: usize,
+    pub count: usize,
+}
+
+impl InternalEvent for AzureEventHubsEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct AzureEventHubsCheckpointError {
+    pub error: crate::Error,
+    pub partition: i32,
+}
+
+impl InternalEvent for AzureEventHubsCheckpointError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to access partition checkpoint.",
+            error = %self.error,
+            partition = %self.partition,
+            error_code = "checkpoint",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
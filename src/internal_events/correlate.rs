@@ -0,0 +1,17 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct CorrelateUnmatchedEventDiscarded {
+    pub side: &'static str,
+}
+
+impl InternalEvent for CorrelateUnmatchedEventDiscarded {
+    fn emit_logs(&self) {
+        trace!(message = "Discarding event not correlated within its window.", side = %self.side);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1, "side" => self.side);
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use http::StatusCode;
+use metrics::{counter, histogram};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct DatadogLogsInvalidReservedAttribute<'a> {
+    pub attribute: &'static str,
+    pub value: &'a str,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for DatadogLogsInvalidReservedAttribute<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Invalid value for Datadog reserved attribute; discarding value.",
+            attribute = %self.attribute,
+            value = %self.value,
+            reason = %self.reason,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "datadog_logs_invalid_reserved_attributes_total", 1,
+            "attribute" => self.attribute,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct DatadogLogsDeliveryLatency {
+    pub status: StatusCode,
+    pub latency: Duration,
+}
+
+impl InternalEvent for DatadogLogsDeliveryLatency {
+    fn emit_metrics(&self) {
+        histogram!(
+            "datadog_logs_delivery_latency_seconds", self.latency,
+            "status_code" => self.status.as_u16().to_string(),
+        );
+    }
+}
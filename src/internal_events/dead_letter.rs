@@ -0,0 +1,25 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct DeadLetterEventsSent {
+    pub count: usize,
+    pub status: &'static str,
+}
+
+impl InternalEvent for DeadLetterEventsSent {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Events sent to the dead letter output.",
+            count = %self.count,
+            status = %self.status,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "dead_letter_events_total", self.count as u64,
+            "status" => self.status,
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
+use super::prelude::{error_stage, error_type};
+
 #[derive(Debug)]
 pub struct DedupeEventDiscarded {
     pub event: crate::event::Event,
@@ -15,3 +17,53 @@ impl InternalEvent for DedupeEventDiscarded {
         counter!("events_discarded_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct DedupeFieldsError {
+    pub error: String,
+}
+
+impl InternalEvent for DedupeFieldsError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to compute the fields to match; passing the event through.",
+            error = %self.error,
+            error_type = error_type::CONVERSION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::CONVERSION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct DedupeCacheError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for DedupeCacheError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to persist the cache.",
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -51,3 +51,33 @@ impl<'a> InternalEvent for ElasticsearchResponseError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct ElasticsearchDocumentRejected<'a> {
+    pub index: Option<&'a str>,
+    pub status: Option<u16>,
+    pub rejection_type: &'a str,
+    pub reason: &'a str,
+}
+
+impl<'a> InternalEvent for ElasticsearchDocumentRejected<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Document rejected.",
+            index = ?self.index,
+            status = ?self.status,
+            rejection_type = %self.rejection_type,
+            reason = %self.reason,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "elasticsearch_rejected_documents_total", 1,
+            "rejection_type" => self.rejection_type.to_owned(),
+        );
+    }
+}
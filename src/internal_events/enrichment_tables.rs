@@ -0,0 +1,85 @@
+use std::{path::Path, time::Duration};
+
+use metrics::{counter, gauge, histogram};
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct EnrichmentTableLoaded<'a> {
+    pub file: &'a Path,
+    pub rows: usize,
+}
+
+impl InternalEvent for EnrichmentTableLoaded<'_> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Enrichment table loaded.",
+            file = %self.file.display(),
+            rows = %self.rows,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        gauge!(
+            "enrichment_table_rows", self.rows as f64,
+            "file" => self.file.to_string_lossy().into_owned(),
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloaded<'a> {
+    pub file: &'a Path,
+    pub rows: usize,
+    pub elapsed: Duration,
+}
+
+impl InternalEvent for EnrichmentTableReloaded<'_> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Enrichment table reloaded.",
+            file = %self.file.display(),
+            rows = %self.rows,
+            elapsed_ms = %self.elapsed.as_millis(),
+        );
+    }
+
+    fn emit_metrics(&self) {
+        let file = self.file.to_string_lossy().into_owned();
+        counter!("enrichment_table_reloads_total", 1, "file" => file.clone());
+        histogram!(
+            "enrichment_table_reload_duration_seconds", self.elapsed,
+            "file" => file.clone(),
+        );
+        gauge!("enrichment_table_rows", self.rows as f64, "file" => file);
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloadError<'a> {
+    pub file: &'a Path,
+    pub error: crate::Error,
+}
+
+impl InternalEvent for EnrichmentTableReloadError<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload enrichment table, keeping the data previously loaded.",
+            file = %self.file.display(),
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+            "file" => self.file.to_string_lossy().into_owned(),
+        );
+    }
+}
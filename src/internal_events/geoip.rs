@@ -36,3 +36,47 @@ impl<'a> InternalEvent for GeoipIpAddressParseError<'a> {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct GeoipDatabaseReloaded<'a> {
+    pub path: &'a str,
+}
+
+impl<'a> InternalEvent for GeoipDatabaseReloaded<'a> {
+    fn emit_logs(&self) {
+        info!(message = "GeoIP database reloaded.", path = %self.path);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("geoip_database_reloads_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct GeoipDatabaseReloadError<'a> {
+    pub path: &'a str,
+    pub error: maxminddb::MaxMindDBError,
+}
+
+impl<'a> InternalEvent for GeoipDatabaseReloadError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload the GeoIP database, the previous one remains in use.",
+            path = %self.path,
+            error = %self.error,
+            error_code = "reload_database",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "reload_database",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -92,6 +92,50 @@ impl<'a> InternalEvent for GotHttpError<'a> {
     }
 }
 
+#[derive(Debug)]
+pub struct HttpRequestCompressed {
+    pub algorithm: &'static str,
+    pub uncompressed_byte_size: usize,
+    pub compressed_byte_size: usize,
+}
+
+impl InternalEvent for HttpRequestCompressed {
+    fn emit_metrics(&self) {
+        counter!(
+            "http_client_request_uncompressed_bytes_total", self.uncompressed_byte_size as u64,
+            "algorithm" => self.algorithm,
+        );
+        counter!(
+            "http_client_request_compressed_bytes_total", self.compressed_byte_size as u64,
+            "algorithm" => self.algorithm,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpCompressionRejected {
+    pub algorithm: &'static str,
+    pub fallback: Option<&'static str>,
+}
+
+impl InternalEvent for HttpCompressionRejected {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Compression rejected by the endpoint; falling back.",
+            algorithm = %self.algorithm,
+            fallback = %self.fallback.unwrap_or("none"),
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "http_client_compression_rejected_total", 1,
+            "algorithm" => self.algorithm,
+        );
+    }
+}
+
 /// Newtype placeholder to provide a formatter for the request and response body.
 struct FormatBody<'a, B>(&'a B);
 
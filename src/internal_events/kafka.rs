@@ -58,6 +58,31 @@ impl InternalEvent for KafkaOffsetUpdateError {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaRedeliveriesExhausted<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub offset: i64,
+    pub redeliveries: u32,
+}
+
+impl<'a> InternalEvent for KafkaRedeliveriesExhausted<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Message consumed again too many times; committing its offset.",
+            topic = %self.topic,
+            partition = %self.partition,
+            offset = %self.offset,
+            redeliveries = %self.redeliveries,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("kafka_redeliveries_exhausted_total", 1);
+    }
+}
+
 #[derive(Debug)]
 pub struct KafkaReadError {
     pub error: rdkafka::error::KafkaError,
@@ -86,6 +111,33 @@ impl InternalEvent for KafkaReadError {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaKeyDecodeError {
+    pub error: serde_json::Error,
+}
+
+impl InternalEvent for KafkaKeyDecodeError {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode message key, keeping it as a string.",
+            error = %self.error,
+            error_code = "decoding_key",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "decoding_key",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct KafkaStatisticsReceived<'a> {
     pub statistics: &'a rdkafka::Statistics,
@@ -153,3 +205,61 @@ impl InternalEvent for KafkaHeaderExtractionError<'_> {
         counter!("kafka_header_extraction_failures_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaSchemaValidationError<'a> {
+    pub subject: &'a str,
+    pub error: &'a str,
+}
+
+impl InternalEvent for KafkaSchemaValidationError<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Event doesn't match the schema of its subject, rejecting it.",
+            subject = %self.subject,
+            error = %self.error,
+            error_code = "schema_validation",
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "schema_validation",
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaSchemaRegistryError<'a> {
+    pub subject: &'a str,
+    pub error: String,
+}
+
+impl InternalEvent for KafkaSchemaRegistryError<'_> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed fetching schema from the Schema Registry.",
+            subject = %self.subject,
+            error = %self.error,
+            error_code = "fetching_schema",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "fetching_schema",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::io::Error;
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct KubernetesEventsReceived {
+    pub byte_size: usize,
+}
+
+impl InternalEvent for KubernetesEventsReceived {
+    fn emit_logs(&self) {
+        trace!(message = "Received one event.", byte_size = %self.byte_size);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", 1);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        counter!("events_in_total", 1); // deprecated
+    }
+}
+
+#[derive(Debug)]
+pub struct KubernetesEventsCheckpointError {
+    pub error: Error,
+}
+
+impl InternalEvent for KubernetesEventsCheckpointError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reading or writing checkpoint.",
+            error = %self.error,
+            error_code = "checkpoint",
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint",
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
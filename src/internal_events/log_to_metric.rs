@@ -100,3 +100,30 @@ impl InternalEvent for LogToMetricTemplateParseError {
         );
     }
 }
+
+pub struct LogToMetricValueError<'a> {
+    pub name: &'a str,
+    pub error: &'a str,
+}
+
+impl<'a> InternalEvent for LogToMetricValueError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = %format!("Failed to compute the value of metric {:?}: {}", self.name, self.error),
+            metric_name = %self.name,
+            error = "failed_computing_value",
+            error_type = error_type::CONVERSION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error" => "failed_computing_value",
+            "error_type" => error_type::CONVERSION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
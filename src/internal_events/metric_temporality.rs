@@ -0,0 +1,35 @@
+use metrics::counter;
+use vector_core::{event::metric::MetricSeries, internal_event::InternalEvent};
+
+#[derive(Debug)]
+pub struct MetricTemporalityResetDetected<'a> {
+    pub series: &'a MetricSeries,
+}
+
+impl<'a> InternalEvent for MetricTemporalityResetDetected<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Cumulative value went down, counting it from zero.",
+            series = %self.series,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("metric_temporality_resets_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MetricTemporalitySeriesExpired {
+    pub count: usize,
+}
+
+impl InternalEvent for MetricTemporalitySeriesExpired {
+    fn emit_logs(&self) {
+        debug!(message = "Expired stale series.", count = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("metric_temporality_expired_series_total", self.count as u64);
+    }
+}
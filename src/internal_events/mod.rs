@@ -1,14 +1,19 @@
 pub mod prelude;
 
+mod acknowledgements;
 mod adaptive_concurrency;
 mod add_fields;
 mod add_tags;
 mod aggregate;
+#[cfg(feature = "sources-amqp")]
+mod amqp;
 mod ansi_stripper;
 #[cfg(feature = "sources-apache_metrics")]
 mod apache_metrics;
 #[cfg(feature = "api")]
 mod api;
+#[cfg(any(feature = "rusoto_core", feature = "aws-config"))]
+mod aws;
 #[cfg(any(
     feature = "sinks-aws_cloudwatch_logs",
     feature = "transforms-aws_cloudwatch_logs_subscription_parser",
@@ -26,6 +31,8 @@ mod aws_kinesis_streams;
 mod aws_sqs;
 #[cfg(any(feature = "sinks-azure_blob", feature = "sinks-datadog_archives"))]
 pub(crate) mod azure_blob;
+#[cfg(feature = "sources-azure_event_hubs")]
+mod azure_event_hubs;
 mod batch;
 mod blackhole;
 #[cfg(feature = "transforms-coercer")]
@@ -36,10 +43,15 @@ mod concat;
 mod conditions;
 #[cfg(feature = "sinks-console")]
 mod console;
+#[cfg(feature = "transforms-correlate")]
+mod correlate;
 #[cfg(feature = "sinks-datadog_events")]
 mod datadog_events;
+#[cfg(feature = "sinks-datadog_logs")]
+mod datadog_logs;
 #[cfg(feature = "sinks-datadog_metrics")]
 mod datadog_metrics;
+mod dead_letter;
 #[cfg(any(feature = "codecs"))]
 mod decoder;
 #[cfg(feature = "transforms-dedupe")]
@@ -52,6 +64,8 @@ mod dnstap;
 mod docker_logs;
 mod elasticsearch;
 mod encoding_transcode;
+#[cfg(feature = "enrichment-tables-file")]
+mod enrichment_tables;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
@@ -60,8 +74,12 @@ mod exec;
 mod filter;
 #[cfg(feature = "sources-fluent")]
 mod fluent;
+#[cfg(feature = "sources-gcp_pubsub")]
+mod gcp_pubsub;
 #[cfg(feature = "transforms-geoip")]
 mod geoip;
+#[cfg(feature = "sinks-grpc")]
+mod grpc;
 mod heartbeat;
 mod http;
 pub mod http_client;
@@ -71,10 +89,16 @@ mod internal_logs;
 mod journald;
 #[cfg(feature = "transforms-json_parser")]
 mod json_parser;
-#[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
+#[cfg(any(
+    feature = "sources-azure_event_hubs",
+    feature = "sources-kafka",
+    feature = "sinks-kafka"
+))]
 mod kafka;
 #[cfg(feature = "transforms-key_value_parser")]
 mod key_value_parser;
+#[cfg(feature = "sources-kubernetes_events")]
+mod kubernetes_events;
 #[cfg(feature = "sources-kubernetes_logs")]
 mod kubernetes_logs;
 #[cfg(feature = "transforms-log_to_metric")]
@@ -84,12 +108,16 @@ mod logplex;
 mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+#[cfg(feature = "transforms-metric_temporality")]
+mod metric_temporality;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
 mod mongodb_metrics;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 mod nats;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
@@ -106,13 +134,15 @@ mod open;
     feature = "transforms-tokenizer",
 ))]
 mod parser;
+#[cfg(feature = "transforms-pii_scrubber")]
+mod pii_scrubber;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 mod prometheus;
 mod pulsar;
-#[cfg(feature = "sinks-redis")]
+#[cfg(any(feature = "sinks-redis", feature = "sources-redis"))]
 mod redis;
 #[cfg(feature = "transforms-reduce")]
 mod reduce;
@@ -124,26 +154,41 @@ mod rename_fields;
 #[cfg(feature = "transforms-route")]
 mod route;
 mod sample;
+#[cfg(feature = "transforms-sample_adaptive")]
+mod sample_adaptive;
+#[cfg(feature = "transforms-schema_enforce")]
+mod schema_enforce;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sources-snmp_trap")]
+mod snmp_trap;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(feature = "sources-sql_query")]
+mod sql_query;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "sources-statsd")]
 mod statsd_source;
 mod stdin;
 mod syslog;
+#[cfg(feature = "sinks-syslog")]
+mod syslog_sink;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
 mod tcp;
 mod template;
 #[cfg(feature = "transforms-throttle")]
 mod throttle;
+mod tls;
 mod udp;
 mod unix;
 mod vector;
+#[cfg(feature = "sources-webhook")]
+mod webhook;
+#[cfg(all(windows, feature = "sources-windows_event_log"))]
+mod windows_event_log;
 
 #[cfg(any(
     feature = "sources-file",
@@ -164,12 +209,16 @@ pub(crate) use self::add_fields::*;
 pub(crate) use self::add_tags::*;
 #[cfg(feature = "transforms-aggregate")]
 pub(crate) use self::aggregate::*;
+#[cfg(feature = "sources-amqp")]
+pub(crate) use self::amqp::*;
 #[cfg(feature = "transforms-ansi_stripper")]
 pub(crate) use self::ansi_stripper::*;
 #[cfg(feature = "sources-apache_metrics")]
 pub(crate) use self::apache_metrics::*;
 #[cfg(feature = "api")]
 pub(crate) use self::api::*;
+#[cfg(any(feature = "rusoto_core", feature = "aws-config"))]
+pub(crate) use self::aws::*;
 #[cfg(any(
     feature = "sinks-aws_cloudwatch_logs",
     feature = "transforms-aws_cloudwatch_logs_subscription_parser",
@@ -185,6 +234,8 @@ pub(crate) use self::aws_kinesis_firehose::*;
 pub(crate) use self::aws_kinesis_streams::*;
 #[cfg(any(feature = "sinks-aws_sqs", feature = "sources-aws_s3",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(feature = "sources-azure_event_hubs")]
+pub(crate) use self::azure_event_hubs::*;
 #[cfg(feature = "sinks-blackhole")]
 pub(crate) use self::blackhole::*;
 #[cfg(feature = "transforms-coercer")]
@@ -193,8 +244,12 @@ pub(crate) use self::coercer::*;
 pub(crate) use self::concat::*;
 #[cfg(feature = "sinks-console")]
 pub(crate) use self::console::*;
+#[cfg(feature = "transforms-correlate")]
+pub(crate) use self::correlate::*;
 #[cfg(feature = "sinks-datadog_events")]
 pub(crate) use self::datadog_events::*;
+#[cfg(feature = "sinks-datadog_logs")]
+pub(crate) use self::datadog_logs::*;
 #[cfg(feature = "sinks-datadog_metrics")]
 pub(crate) use self::datadog_metrics::*;
 #[cfg(any(feature = "codecs"))]
@@ -209,6 +264,8 @@ pub(crate) use self::dnstap::*;
 pub(crate) use self::docker_logs::*;
 #[cfg(feature = "sinks-elasticsearch")]
 pub(crate) use self::elasticsearch::*;
+#[cfg(feature = "enrichment-tables-file")]
+pub(crate) use self::enrichment_tables::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub(crate) use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
@@ -223,8 +280,12 @@ pub(crate) use self::file::*;
 pub(crate) use self::filter::*;
 #[cfg(feature = "sources-fluent")]
 pub(crate) use self::fluent::*;
+#[cfg(feature = "sources-gcp_pubsub")]
+pub(crate) use self::gcp_pubsub::*;
 #[cfg(feature = "transforms-geoip")]
 pub(crate) use self::geoip::*;
+#[cfg(feature = "sinks-grpc")]
+pub(crate) use self::grpc::*;
 #[cfg(any(
     feature = "sources-utils-http",
     feature = "sources-utils-http-encoding",
@@ -232,6 +293,7 @@ pub(crate) use self::geoip::*;
     feature = "sources-datadog_agent",
     feature = "sources-splunk_hec",
     feature = "sources-aws_ecs_metrics",
+    feature = "sources-webhook",
 ))]
 pub(crate) use self::http::*;
 #[cfg(feature = "sources-internal_logs")]
@@ -240,10 +302,16 @@ pub(crate) use self::internal_logs::*;
 pub(crate) use self::journald::*;
 #[cfg(feature = "transforms-json_parser")]
 pub(crate) use self::json_parser::*;
-#[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
+#[cfg(any(
+    feature = "sources-azure_event_hubs",
+    feature = "sources-kafka",
+    feature = "sinks-kafka"
+))]
 pub(crate) use self::kafka::*;
 #[cfg(feature = "transforms-key_value_parser")]
 pub(crate) use self::key_value_parser::*;
+#[cfg(feature = "sources-kubernetes_events")]
+pub(crate) use self::kubernetes_events::*;
 #[cfg(feature = "sources-kubernetes_logs")]
 pub(crate) use self::kubernetes_logs::*;
 #[cfg(feature = "transforms-log_to_metric")]
@@ -254,10 +322,14 @@ pub(crate) use self::logplex::*;
 pub(crate) use self::loki::*;
 #[cfg(feature = "transforms-lua")]
 pub(crate) use self::lua::*;
+#[cfg(feature = "transforms-metric_temporality")]
+pub(crate) use self::metric_temporality::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 pub(crate) use self::nats::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 #[cfg(any(
@@ -273,13 +345,15 @@ pub(crate) use self::nginx_metrics::*;
     feature = "transforms-tokenizer",
 ))]
 pub(crate) use self::parser::*;
+#[cfg(feature = "transforms-pii_scrubber")]
+pub(crate) use self::pii_scrubber::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
 #[cfg(feature = "sinks-pulsar")]
 pub(crate) use self::pulsar::*;
-#[cfg(feature = "sinks-redis")]
+#[cfg(any(feature = "sinks-redis", feature = "sources-redis"))]
 pub(crate) use self::redis::*;
 #[cfg(feature = "transforms-reduce")]
 pub(crate) use self::reduce::*;
@@ -293,10 +367,18 @@ pub(crate) use self::rename_fields::*;
 pub(crate) use self::route::*;
 #[cfg(feature = "transforms-sample")]
 pub(crate) use self::sample::*;
+#[cfg(feature = "transforms-sample_adaptive")]
+pub(crate) use self::sample_adaptive::*;
+#[cfg(feature = "transforms-schema_enforce")]
+pub(crate) use self::schema_enforce::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
+#[cfg(feature = "sources-snmp_trap")]
+pub(crate) use self::snmp_trap::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(feature = "sources-sql_query")]
+pub(crate) use self::sql_query::*;
 #[cfg(feature = "sinks-statsd")]
 pub(crate) use self::statsd_sink::*;
 #[cfg(feature = "sources-statsd")]
@@ -305,6 +387,8 @@ pub(crate) use self::statsd_source::*;
 pub(crate) use self::stdin::*;
 #[cfg(feature = "sources-syslog")]
 pub(crate) use self::syslog::*;
+#[cfg(feature = "sinks-syslog")]
+pub(crate) use self::syslog_sink::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
 #[cfg(feature = "transforms-throttle")]
@@ -324,11 +408,16 @@ pub(crate) use self::throttle::*;
 pub(crate) use self::unix::*;
 #[cfg(feature = "sources-vector")]
 pub(crate) use self::vector::*;
+#[cfg(feature = "sources-webhook")]
+pub(crate) use self::webhook::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
+#[cfg(all(windows, feature = "sources-windows_event_log"))]
+pub(crate) use self::windows_event_log::*;
 pub(crate) use self::{
-    adaptive_concurrency::*, batch::*, common::*, conditions::*, encoding_transcode::*,
-    heartbeat::*, open::*, process::*, socket::*, tcp::*, template::*, udp::*,
+    acknowledgements::*, adaptive_concurrency::*, batch::*, common::*, conditions::*,
+    dead_letter::*, encoding_transcode::*, heartbeat::*, open::*, process::*, socket::*, tcp::*,
+    template::*, tls::*, udp::*,
 };
 
 // this version won't be needed once all `InternalEvent`s implement `name()`
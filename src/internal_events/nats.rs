@@ -71,3 +71,32 @@ impl InternalEvent for NatsEventSendError {
         counter!("send_errors_total", 1);
     }
 }
+
+#[cfg(feature = "sinks-nats")]
+#[derive(Debug)]
+pub struct NatsJetStreamPublishError {
+    pub error: crate::sinks::nats::JetStreamError,
+}
+
+#[cfg(feature = "sinks-nats")]
+impl InternalEvent for NatsJetStreamPublishError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to publish message to JetStream.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        // deprecated
+        counter!("send_errors_total", 1);
+    }
+}
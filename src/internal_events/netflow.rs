@@ -0,0 +1,91 @@
+use std::net::SocketAddr;
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::sources::netflow::DecodeError;
+
+#[derive(Debug)]
+pub struct NetflowEventsReceived {
+    pub byte_size: usize,
+    pub count: usize,
+}
+
+impl InternalEvent for NetflowEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowDecodeError<'a> {
+    pub error: &'a DecodeError,
+    pub peer: SocketAddr,
+}
+
+impl<'a> InternalEvent for NetflowDecodeError<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to decode flow datagram.",
+            error = %self.error,
+            peer = %self.peer,
+            error_code = "decoding_datagram",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "decoding_datagram",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowTemplateNotFound {
+    pub template_id: u16,
+    pub peer: SocketAddr,
+}
+
+impl InternalEvent for NetflowTemplateNotFound {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Dropping data set whose template wasn't received yet.",
+            template_id = %self.template_id,
+            peer = %self.peer,
+            error_code = "template_not_found",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "template_not_found",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
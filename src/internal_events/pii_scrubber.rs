@@ -0,0 +1,17 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct PiiScrubbed {
+    pub count: usize,
+}
+
+impl InternalEvent for PiiScrubbed {
+    fn emit_logs(&self) {
+        trace!(message = "Scrubbed PII from event.", findings = %self.count);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("pii_findings_total", self.count as u64);
+    }
+}
@@ -240,3 +240,34 @@ impl InternalEvent for PrometheusServerRequestComplete {
         counter!("requests_received_total", 1);
     }
 }
+
+#[cfg(feature = "sources-prometheus")]
+#[derive(Debug)]
+pub struct PrometheusDiscoveryError {
+    pub mechanism: &'static str,
+    pub error: crate::Error,
+}
+
+#[cfg(feature = "sources-prometheus")]
+impl InternalEvent for PrometheusDiscoveryError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed discovering targets.",
+            mechanism = %self.mechanism,
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "mechanism" => self.mechanism,
+            "error" => self.error.to_string(),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
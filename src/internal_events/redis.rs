@@ -2,6 +2,9 @@ use super::prelude::{error_stage, error_type};
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
+#[cfg(feature = "sources-redis")]
+use crate::event::BatchStatus;
+
 #[derive(Debug)]
 pub struct RedisEventsSent {
     pub count: usize,
@@ -50,3 +53,90 @@ impl<'a> InternalEvent for RedisSendEventError<'a> {
         counter!("send_errors_total", 1);
     }
 }
+
+#[cfg(feature = "sources-redis")]
+#[derive(Debug)]
+pub struct RedisEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+#[cfg(feature = "sources-redis")]
+impl InternalEvent for RedisEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[cfg(feature = "sources-redis")]
+#[derive(Debug)]
+pub struct RedisReceiveEventError {
+    pub error: redis::RedisError,
+}
+
+#[cfg(feature = "sources-redis")]
+impl InternalEvent for RedisReceiveEventError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to read entries.",
+            error = %self.error,
+            error_code = %self.error.code().unwrap_or_default(),
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => self.error.code().unwrap_or_default().to_string(),
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[cfg(feature = "sources-redis")]
+#[derive(Debug)]
+pub struct RedisAckError {
+    pub error: redis::RedisError,
+    pub status: BatchStatus,
+}
+
+#[cfg(feature = "sources-redis")]
+impl InternalEvent for RedisAckError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Unable to acknowledge entry.",
+            error = %self.error,
+            status = ?self.status,
+            error_code = "acknowledging_entry",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "acknowledging_entry",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SchemaEnforceViolation {
+    pub count: usize,
+    pub dropped: bool,
+}
+
+impl InternalEvent for SchemaEnforceViolation {
+    fn emit_logs(&self) {
+        if self.dropped {
+            debug!(message = "Event violates the schema; discarding event.", violations = %self.count);
+        } else {
+            debug!(message = "Event violates the schema.", violations = %self.count);
+        }
+    }
+
+    fn emit_metrics(&self) {
+        counter!("schema_violations_total", self.count as u64);
+        if self.dropped {
+            counter!("events_discarded_total", 1);
+        }
+    }
+}
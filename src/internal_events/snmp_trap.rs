@@ -0,0 +1,91 @@
+use std::net::SocketAddr;
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::sources::snmp_trap::TrapError;
+
+#[derive(Debug)]
+pub struct SnmpTrapEventsReceived {
+    pub byte_size: usize,
+    pub count: usize,
+}
+
+impl InternalEvent for SnmpTrapEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapParseError<'a> {
+    pub error: &'a TrapError,
+    pub peer: SocketAddr,
+}
+
+impl<'a> InternalEvent for SnmpTrapParseError<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Failed to parse SNMP message.",
+            error = %self.error,
+            peer = %self.peer,
+            error_code = "parsing_message",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "parsing_message",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapResponseError {
+    pub error: std::io::Error,
+    pub peer: SocketAddr,
+}
+
+impl InternalEvent for SnmpTrapResponseError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to acknowledge inform.",
+            error = %self.error,
+            peer = %self.peer,
+            error_code = "sending_response",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "sending_response",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -163,6 +163,21 @@ mod sink {
         }
     }
 
+    pub struct SplunkIndexerAcknowledgementRequestResent;
+
+    impl InternalEvent for SplunkIndexerAcknowledgementRequestResent {
+        fn emit_logs(&self) {
+            warn!(
+                message = "Ack id expired without being acknowledged. Resending request.",
+                internal_log_rate_secs = 10,
+            );
+        }
+
+        fn emit_metrics(&self) {
+            counter!("splunk_resent_requests_total", 1);
+        }
+    }
+
     pub struct SplunkIndexerAcknowledgementAcksRemoved {
         pub count: f64,
     }
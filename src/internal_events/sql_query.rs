@@ -0,0 +1,111 @@
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::sources::sql_query::QueryError;
+
+#[derive(Debug)]
+pub struct SqlQueryEventsReceived {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for SqlQueryEventsReceived {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Received events.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("component_received_events_total", self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            self.byte_size as u64
+        );
+        // deprecated
+        counter!("events_in_total", self.count as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlQueryError<'a> {
+    pub error: &'a QueryError,
+}
+
+impl<'a> InternalEvent for SqlQueryError<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to run query.",
+            error = %self.error,
+            error_code = "query_failed",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "query_failed",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlQueryCursorMissing<'a> {
+    pub column: &'a str,
+}
+
+impl<'a> InternalEvent for SqlQueryCursorMissing<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Cursor column is missing or null in the last row, cursor not advanced.",
+            column = %self.column,
+            error_code = "cursor_missing",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "cursor_missing",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlQueryCheckpointError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for SqlQueryCheckpointError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed writing cursor checkpoint.",
+            error = %self.error,
+            error_code = "checkpoint",
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "checkpoint",
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
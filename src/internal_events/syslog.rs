@@ -1,5 +1,6 @@
 // ## skip check-events ##
 
+use super::prelude::{error_stage, error_type};
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
@@ -33,3 +34,28 @@ impl InternalEvent for SyslogConvertUtf8Error {
         counter!("utf8_convert_errors_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct SyslogParseError {
+    pub(crate) error: String,
+}
+
+impl InternalEvent for SyslogParseError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to parse message.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
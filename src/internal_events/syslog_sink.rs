@@ -0,0 +1,30 @@
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SyslogInvalidPriorityValue<'a> {
+    pub field: &'static str,
+    pub value: &'a str,
+}
+
+impl<'a> InternalEvent for SyslogInvalidPriorityValue<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Invalid priority value; using the default one.",
+            field = %self.field,
+            value = %self.value,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
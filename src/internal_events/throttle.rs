@@ -1,6 +1,8 @@
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
+use super::prelude::{error_stage, error_type};
+
 #[derive(Debug)]
 pub(crate) struct ThrottleEventDiscarded {
     pub key: String,
@@ -18,3 +20,28 @@ impl InternalEvent for ThrottleEventDiscarded {
         );
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ThrottleBackendError {
+    pub error: String,
+}
+
+impl InternalEvent for ThrottleBackendError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Throttle backend unavailable, rate limiting locally.",
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
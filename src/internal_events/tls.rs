@@ -0,0 +1,55 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct TlsCertificatesReloaded {
+    pub source: &'static str,
+}
+
+impl InternalEvent for TlsCertificatesReloaded {
+    fn emit_logs(&self) {
+        info!(message = "Reloaded TLS certificates.", source = %self.source);
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "tls_certificate_reloads_total", 1,
+            "source" => self.source,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TlsCertificatesReloadError {
+    pub source: &'static str,
+    pub error: String,
+}
+
+impl InternalEvent for TlsCertificatesReloadError {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed reloading TLS certificates, the previous ones remain in use.",
+            source = %self.source,
+            error = %self.error,
+            error_code = "reloading_certificates",
+            error_type = error_type::CONFIGURATION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "reloading_certificates",
+            "error_type" => error_type::CONFIGURATION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "tls_certificate_reload_errors_total", 1,
+            "source" => self.source,
+        );
+    }
+}
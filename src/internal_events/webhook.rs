@@ -0,0 +1,34 @@
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::sources::webhook::SignatureError;
+
+#[derive(Debug)]
+pub struct WebhookSignatureError<'a> {
+    pub error: &'a SignatureError,
+    pub provider: &'static str,
+}
+
+impl<'a> InternalEvent for WebhookSignatureError<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Rejected request with an invalid signature.",
+            error = %self.error,
+            error_code = self.error.error_code(),
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::RECEIVING,
+            provider = self.provider,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => self.error.error_code(),
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -59,6 +59,13 @@ where
             pause_between_requests,
         }
     }
+
+    /// Start watching from `resource_version` instead of from the current
+    /// state. Falls back to the current state if the resource version is too
+    /// old to resume from.
+    pub fn resume_from(&mut self, resource_version: String) {
+        self.resource_version = resource_version::State::resume_from(resource_version);
+    }
 }
 
 impl<W, S> Reflector<W, S>
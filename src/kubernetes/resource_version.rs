@@ -15,6 +15,12 @@ impl State {
         Self(Some("0".to_owned()))
     }
 
+    /// Create a resource version [`State`] that resumes from a resource
+    /// version seen earlier, e.g. before a restart.
+    pub fn resume_from(resource_version: String) -> Self {
+        Self(Some(resource_version))
+    }
+
     /// Update the resource version from a candidate obtained earlier.
     ///
     /// Returns the previous state.
@@ -41,6 +41,8 @@ pub mod generate;
 #[macro_use]
 #[allow(unreachable_pub)]
 pub mod internal_events;
+#[cfg(feature = "sources-amqp")]
+pub(crate) mod amqp;
 #[cfg(feature = "api")]
 #[allow(unreachable_pub)]
 pub mod api;
@@ -48,12 +50,17 @@ pub mod app;
 pub mod async_read;
 #[cfg(any(feature = "rusoto_core", feature = "aws-config"))]
 pub mod aws;
+#[cfg(feature = "api-client")]
+pub mod buffer;
 #[cfg(feature = "codecs")]
 #[allow(unreachable_pub)]
 pub mod codecs;
 pub(crate) mod common;
+pub mod convert_config;
 pub mod encoding_transcode;
 pub mod enrichment_tables;
+#[cfg(feature = "gcp")]
+pub mod gcp;
 pub mod graph;
 pub mod heartbeat;
 pub mod http;
@@ -68,6 +75,8 @@ pub(crate) mod nats;
 #[allow(unreachable_pub)]
 pub(crate) mod proto;
 pub mod providers;
+pub mod replay;
+pub mod secrets;
 pub mod serde;
 #[cfg(windows)]
 pub mod service;
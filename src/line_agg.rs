@@ -106,13 +106,21 @@ pub struct LineAgg<T, K, C> {
     draining: Option<Vec<(K, Bytes, C)>>,
 }
 
+/// Chooses the configuration of a source, by index.
+type SelectConfig<K> = dyn Fn(&K) -> Option<usize> + Send + Sync;
+
 /// Core line aggregation logic.
 ///
 /// Encapsulates the essential state and the core logic for the line
 /// aggregation algorithm.
 pub struct Logic<K, C> {
     /// Configuration parameters to use.
-    config: Config,
+    configs: Vec<Config>,
+
+    /// Chooses the index of the configuration to use for the lines of each
+    /// source. Lines of the sources it doesn't choose any for are passed
+    /// through.
+    select: Box<SelectConfig<K>>,
 
     /// Line per key.
     /// Key is usually a filename or other line source identifier.
@@ -125,8 +133,19 @@ pub struct Logic<K, C> {
 impl<K, C> Logic<K, C> {
     /// Create a new `Logic` using the specified `Config`.
     pub fn new(config: Config) -> Self {
+        Self::with_configs(vec![config], |_| Some(0))
+    }
+
+    /// Create a new `Logic` using one of the specified `configs` per source,
+    /// chosen by index with `select`. Lines of the sources `select` returns
+    /// `None` for are passed through.
+    pub fn with_configs(
+        configs: Vec<Config>,
+        select: impl Fn(&K) -> Option<usize> + Send + Sync + 'static,
+    ) -> Self {
         Self {
-            config,
+            configs,
+            select: Box::new(select),
             buffers: HashMap::new(),
             timeouts: DelayQueue::new(),
         }
@@ -291,11 +310,16 @@ where
         line: Bytes,
         context: C,
     ) -> Option<(K, Emit<(Bytes, C)>)> {
+        let config = match (self.select)(&src) {
+            Some(index) => &self.configs[index],
+            None => return Some((src, Emit::One((line, context)))),
+        };
+
         // Check if we already have the buffered data for the source.
         match self.buffers.entry(src) {
             Entry::Occupied(mut entry) => {
-                let condition_matched = self.config.condition_pattern.is_match(line.as_ref());
-                let decision = match (self.config.mode, condition_matched) {
+                let condition_matched = config.condition_pattern.is_match(line.as_ref());
+                let decision = match (config.mode, condition_matched) {
                     // All consecutive lines matching this pattern are included in
                     // the group.
                     (Mode::ContinueThrough, true) => Decision::Continue,
@@ -317,7 +341,7 @@ where
                 match decision {
                     Decision::Continue => {
                         let buffered = entry.get_mut();
-                        self.timeouts.reset(&buffered.0, self.config.timeout);
+                        self.timeouts.reset(&buffered.0, config.timeout);
                         buffered.1.add_next_line(line);
                         None
                     }
@@ -336,12 +360,10 @@ where
             }
             Entry::Vacant(entry) => {
                 // This line is a candidate for buffering, or passing through.
-                if self.config.start_pattern.is_match(line.as_ref()) {
+                if config.start_pattern.is_match(line.as_ref()) {
                     // It was indeed a new line we need to filter.
                     // Set the timeout and buffer this line.
-                    let key = self
-                        .timeouts
-                        .insert(entry.key().clone(), self.config.timeout);
+                    let key = self.timeouts.insert(entry.key().clone(), config.timeout);
                     entry.insert((key, Aggregate::new(line, context)));
                     None
                 } else {
@@ -718,6 +740,55 @@ mod tests {
         assert_results(results.await.unwrap(), &[expected.as_str()]);
     }
 
+    #[tokio::test]
+    async fn configs_per_source() {
+        let lines = vec![
+            ("a.log", "first part"),
+            ("b.log", "first part"),
+            ("a.log", " last part"),
+            ("b.log", " last part"),
+            ("c.log", "first part"),
+            ("c.log", " last part"),
+        ];
+        let config = Config {
+            start_pattern: Regex::new("^[^\\s]").unwrap(),
+            condition_pattern: Regex::new("^[\\s]+").unwrap(),
+            mode: Mode::ContinueThrough,
+            timeout: Duration::from_millis(10),
+        };
+        let legacy = Config::for_legacy(Regex::new("^first").unwrap(), 10);
+        let logic = Logic::with_configs(vec![config, legacy], |src: &String| match src.as_str() {
+            "a.log" => Some(0),
+            "b.log" => Some(1),
+            _ => None,
+        });
+
+        let stream = futures::stream::iter(
+            lines
+                .into_iter()
+                .map(|(src, line)| (src.to_owned(), Bytes::from_static(line.as_bytes()), ())),
+        );
+        let mut results = LineAgg::new(stream, logic).collect::<Vec<_>>().await;
+        results.sort();
+        assert_eq!(
+            results,
+            vec![
+                (
+                    "a.log".to_owned(),
+                    Bytes::from("first part\n last part"),
+                    ()
+                ),
+                (
+                    "b.log".to_owned(),
+                    Bytes::from("first part\n last part"),
+                    ()
+                ),
+                ("c.log".to_owned(), Bytes::from(" last part"), ()),
+                ("c.log".to_owned(), Bytes::from("first part"), ()),
+            ]
+        );
+    }
+
     // Test helpers.
 
     /// Private type alias to be more expressive in the internal implementation.
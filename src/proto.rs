@@ -3,3 +3,9 @@ use crate::event::proto as event;
 
 #[cfg(any(feature = "sources-vector", feature = "sinks-vector"))]
 pub mod vector;
+
+#[cfg(any(feature = "sources-opentelemetry", feature = "codecs-otlp"))]
+pub mod opentelemetry;
+
+#[cfg(all(unix, feature = "tls-spiffe"))]
+pub mod spiffe;
@@ -0,0 +1,127 @@
+//! The messages and services of the OpenTelemetry protocol (OTLP), the modules
+//! following the packages of the protobuf definitions.
+
+#![allow(clippy::clone_on_ref_ptr)]
+
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use ordered_float::NotNan;
+
+use crate::event::Value;
+
+pub mod collector {
+    pub mod logs {
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.collector.logs.v1");
+        }
+    }
+    pub mod metrics {
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.collector.metrics.v1");
+        }
+    }
+    pub mod trace {
+        pub mod v1 {
+            tonic::include_proto!("opentelemetry.proto.collector.trace.v1");
+        }
+    }
+}
+
+pub mod common {
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.common.v1");
+    }
+}
+
+pub mod logs {
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.logs.v1");
+    }
+}
+
+pub mod metrics {
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.metrics.v1");
+    }
+}
+
+pub mod resource {
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.resource.v1");
+    }
+}
+
+pub mod trace {
+    pub mod v1 {
+        tonic::include_proto!("opentelemetry.proto.trace.v1");
+    }
+}
+
+use common::v1::{any_value, AnyValue, ArrayValue, KeyValue, KeyValueList};
+
+impl From<AnyValue> for Value {
+    fn from(value: AnyValue) -> Self {
+        match value.value {
+            Some(any_value::Value::StringValue(value)) => Self::Bytes(value.into()),
+            Some(any_value::Value::BoolValue(value)) => Self::Boolean(value),
+            Some(any_value::Value::IntValue(value)) => Self::Integer(value),
+            Some(any_value::Value::DoubleValue(value)) => {
+                NotNan::new(value).map_or(Self::Null, Self::Float)
+            }
+            Some(any_value::Value::ArrayValue(array)) => {
+                Self::Array(array.values.into_iter().map(Into::into).collect())
+            }
+            Some(any_value::Value::KvlistValue(list)) => key_values_into_value(list.values),
+            Some(any_value::Value::BytesValue(value)) => Self::Bytes(Bytes::from(value)),
+            None => Self::Null,
+        }
+    }
+}
+
+/// Converts attributes into an object, attributes without values being null.
+pub fn key_values_into_value(key_values: Vec<KeyValue>) -> Value {
+    Value::Object(
+        key_values
+            .into_iter()
+            .map(|KeyValue { key, value }| (key, value.map_or(Value::Null, Into::into)))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+impl From<Value> for AnyValue {
+    fn from(value: Value) -> Self {
+        let value = match value {
+            Value::Bytes(bytes) => match String::from_utf8(bytes.to_vec()) {
+                Ok(string) => Some(any_value::Value::StringValue(string)),
+                Err(error) => Some(any_value::Value::BytesValue(error.into_bytes())),
+            },
+            Value::Regex(regex) => Some(any_value::Value::StringValue(regex.as_str().into())),
+            Value::Integer(integer) => Some(any_value::Value::IntValue(integer)),
+            Value::Float(float) => Some(any_value::Value::DoubleValue(float.into_inner())),
+            Value::Boolean(boolean) => Some(any_value::Value::BoolValue(boolean)),
+            Value::Timestamp(timestamp) => Some(any_value::Value::StringValue(
+                timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            )),
+            Value::Object(fields) => Some(any_value::Value::KvlistValue(KeyValueList {
+                values: fields_into_key_values(fields),
+            })),
+            Value::Array(values) => Some(any_value::Value::ArrayValue(ArrayValue {
+                values: values.into_iter().map(Into::into).collect(),
+            })),
+            Value::Null => None,
+        };
+        Self { value }
+    }
+}
+
+/// Converts the fields of an object into attributes, the inverse of `key_values_into_value`.
+pub fn fields_into_key_values(fields: BTreeMap<String, Value>) -> Vec<KeyValue> {
+    fields
+        .into_iter()
+        .map(|(key, value)| KeyValue {
+            key,
+            value: (!matches!(value, Value::Null)).then(|| value.into()),
+        })
+        .collect()
+}
@@ -0,0 +1,8 @@
+//! The messages and service of the SPIFFE Workload API.
+
+#![allow(clippy::clone_on_ref_ptr)]
+
+// The definitions have no package, so they are generated in the file named after the empty one.
+include!(concat!(env!("OUT_DIR"), "/_.rs"));
+
+pub use spiffe_workload_api_client::SpiffeWorkloadApiClient as Client;
@@ -1,6 +1,107 @@
 #![allow(clippy::clone_on_ref_ptr)]
 
+use std::io::{self, Read};
+
+use prost::Message;
+
+use super::event::EventWrapper;
+
 tonic::include_proto!("vector");
 
 pub use vector_client::VectorClient as Client;
 pub use vector_server::{Vector as Service, VectorServer as Server};
+
+/// The latest version of the protocol, the version 1 having no negotiation nor compression.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+impl PushCompressedEventsRequest {
+    /// Compresses the encoded request of the events.
+    pub fn new(request: &PushEventsRequest, compression: Compression) -> io::Result<Self> {
+        let encoded = request.encode_to_vec();
+        let events = match compression {
+            Compression::None => encoded,
+            Compression::Zstd => zstd::encode_all(encoded.as_slice(), 0)?,
+        };
+        Ok(Self {
+            compression: compression.into(),
+            events,
+        })
+    }
+
+    /// Decompresses the request of the events, refusing to decompress more than `max_size` bytes.
+    pub fn decompress(self, max_size: usize) -> Result<PushEventsRequest, String> {
+        let decompressed = match Compression::from_i32(self.compression) {
+            Some(Compression::None) => self.events,
+            Some(Compression::Zstd) => {
+                let mut decompressed = Vec::new();
+                zstd::Decoder::new(self.events.as_slice())
+                    .and_then(|decoder| {
+                        decoder
+                            .take((max_size as u64).saturating_add(1))
+                            .read_to_end(&mut decompressed)
+                    })
+                    .map_err(|error| format!("invalid zstd compressed events: {}", error))?;
+                decompressed
+            }
+            None => return Err(format!("unsupported compression {}", self.compression)),
+        };
+        if decompressed.len() > max_size {
+            return Err(format!(
+                "compressed events larger than {} bytes once decompressed",
+                max_size
+            ));
+        }
+        PushEventsRequest::decode(decompressed.as_slice())
+            .map_err(|error| format!("invalid compressed events: {}", error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, LogEvent};
+
+    #[test]
+    fn compresses_events() {
+        let request = PushEventsRequest {
+            events: vec![EventWrapper::from(Event::from(LogEvent::from(
+                "hello world",
+            )))],
+        };
+
+        for compression in [Compression::Zstd, Compression::None] {
+            let compressed = PushCompressedEventsRequest::new(&request, compression).unwrap();
+            assert!(!compressed.events.is_empty());
+            assert_eq!(compressed.decompress(1024 * 1024).unwrap(), request);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_compressed_events() {
+        let request = PushCompressedEventsRequest {
+            compression: Compression::Zstd.into(),
+            events: b"zork".to_vec(),
+        };
+        assert!(request.decompress(1024).is_err());
+
+        let request = PushCompressedEventsRequest {
+            compression: 42,
+            events: Vec::new(),
+        };
+        assert!(request.decompress(1024).is_err());
+    }
+
+    #[test]
+    fn rejects_too_large_decompressed_events() {
+        let request = PushEventsRequest {
+            events: vec![EventWrapper::from(Event::from(LogEvent::from(
+                "a".repeat(64 * 1024),
+            )))],
+        };
+        let compressed = PushCompressedEventsRequest::new(&request, Compression::Zstd).unwrap();
+        assert!(compressed.events.len() < 1024);
+
+        assert!(compressed.clone().decompress(1024).is_err());
+        assert_eq!(compressed.decompress(1024 * 1024).unwrap(), request);
+    }
+}
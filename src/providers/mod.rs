@@ -1,4 +1,5 @@
 pub mod http;
+pub mod remote;
 
 use super::config::ConfigBuilder;
 
@@ -0,0 +1,634 @@
+//! A provider fetching the config from an HTTP(S) endpoint or an object storage bucket, and
+//! polling it for changes. The config can be required to be signed, its detached signature being
+//! fetched from alongside it.
+
+use std::path::PathBuf;
+
+use async_stream::stream;
+use bytes::{Buf, Bytes, BytesMut};
+use futures::Stream;
+#[cfg(feature = "gcp")]
+use goauth::scopes::Scope;
+use hyper::{body::HttpBody, Body};
+use indexmap::IndexMap;
+use openssl::{
+    hash::MessageDigest,
+    pkey::{Id, PKey, Public},
+    sign::Verifier,
+};
+#[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+use rusoto_s3::{GetObjectRequest, S3Client, S3};
+use serde::{Deserialize, Serialize};
+use tokio::time;
+use url::Url;
+
+use super::Result;
+#[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+use crate::aws::{rusoto, AwsAuthentication, RegionOrEndpoint};
+#[cfg(feature = "gcp")]
+use crate::gcp::{GcpAuthConfig, GcpCredentials};
+use crate::{
+    config::{
+        self,
+        format::Format,
+        provider::{ProviderConfig, ProviderDescription},
+        GenerateConfig, ProxyConfig,
+    },
+    http::HttpClient,
+    signal,
+    tls::{TlsOptions, TlsSettings},
+};
+
+/// The largest config, or signature, fetched. Anything larger is refused.
+const MAX_OBJECT_SIZE: usize = 10 * 1024 * 1024;
+
+const fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+const fn default_request_timeout_secs() -> u64 {
+    60
+}
+
+fn default_signature_suffix() -> String {
+    ".sig".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RemoteConfig {
+    /// Where the config is fetched from.
+    source: RemoteSource,
+    #[serde(default = "default_poll_interval_secs")]
+    poll_interval_secs: u64,
+    /// How long fetching the config, or its signature, can take before it's given up on.
+    #[serde(default = "default_request_timeout_secs")]
+    request_timeout_secs: u64,
+    /// When set, only configs with a valid signature are applied.
+    signature: Option<SignatureConfig>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    proxy: ProxyConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RemoteSource {
+    Http {
+        url: Url,
+        #[serde(default)]
+        headers: IndexMap<String, String>,
+        tls: Option<TlsOptions>,
+    },
+    #[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+    S3 {
+        bucket: String,
+        key: String,
+        #[serde(flatten)]
+        region: RegionOrEndpoint,
+        #[serde(default)]
+        auth: AwsAuthentication,
+    },
+    #[cfg(feature = "gcp")]
+    Gcs {
+        bucket: String,
+        object: String,
+        #[serde(flatten)]
+        auth: GcpAuthConfig,
+    },
+}
+
+/// The signature object starts with a line holding the version of the config, a number which
+/// increases with every new config, followed by the signature of that line along with the config.
+/// A config whose version isn't newer than the one applied is refused, so that older signed
+/// configs can't be replayed. The applied version is only kept for as long as Vector runs, so any
+/// validly signed config is accepted when it starts.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct SignatureConfig {
+    /// The PEM encoded public key the signatures are verified with. Ed25519 and Ed448 keys verify
+    /// signatures of the signed data itself, other keys signatures of its SHA-256 digest.
+    public_key_file: PathBuf,
+    /// The suffix appended to the location of the config to get that of its signature.
+    #[serde(default = "default_signature_suffix")]
+    suffix: String,
+}
+
+impl GenerateConfig for RemoteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            source: RemoteSource::Http {
+                url: Url::parse("https://config.example.com/vector.toml").unwrap(),
+                headers: IndexMap::new(),
+                tls: None,
+            },
+            poll_interval_secs: default_poll_interval_secs(),
+            request_timeout_secs: default_request_timeout_secs(),
+            signature: Some(SignatureConfig {
+                public_key_file: PathBuf::from("/etc/vector/config.pub"),
+                suffix: default_signature_suffix(),
+            }),
+            proxy: ProxyConfig::default(),
+        })
+        .unwrap()
+    }
+}
+
+/// Fetches objects from the location of the config.
+enum Fetcher {
+    Http {
+        client: HttpClient,
+        url: Url,
+        headers: IndexMap<String, String>,
+    },
+    #[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+    S3 {
+        client: S3Client,
+        bucket: String,
+        key: String,
+    },
+    #[cfg(feature = "gcp")]
+    Gcs {
+        client: HttpClient,
+        bucket: String,
+        object: String,
+        credentials: Option<GcpCredentials>,
+        api_key: Option<String>,
+    },
+}
+
+impl Fetcher {
+    async fn new(source: &RemoteSource, proxy: &ProxyConfig) -> std::result::Result<Self, String> {
+        match source {
+            RemoteSource::Http { url, headers, tls } => {
+                let tls_settings = TlsSettings::from_options(tls)
+                    .map_err(|error| format!("Invalid TLS options: {}", error))?;
+                let client = HttpClient::new(tls_settings, proxy)
+                    .map_err(|error| format!("Couldn't create HTTP client: {}", error))?;
+                Ok(Self::Http {
+                    client,
+                    url: url.clone(),
+                    headers: headers.clone(),
+                })
+            }
+            #[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+            RemoteSource::S3 {
+                bucket,
+                key,
+                region,
+                auth,
+            } => {
+                let region = region
+                    .try_into()
+                    .map_err(|error| format!("Invalid S3 region: {}", error))?;
+                let client = rusoto::client(None, proxy)
+                    .map_err(|error| format!("Couldn't create S3 client: {}", error))?;
+                let creds = auth
+                    .build(&region, None)
+                    .map_err(|error| format!("Invalid AWS credentials: {}", error))?;
+                Ok(Self::S3 {
+                    client: S3Client::new_with(client, creds, region),
+                    bucket: bucket.clone(),
+                    key: key.clone(),
+                })
+            }
+            #[cfg(feature = "gcp")]
+            RemoteSource::Gcs {
+                bucket,
+                object,
+                auth,
+            } => {
+                let credentials = auth
+                    .make_credentials(Scope::DevStorageReadOnly)
+                    .await
+                    .map_err(|error| format!("Invalid GCP credentials: {}", error))?;
+                if let Some(credentials) = &credentials {
+                    credentials.spawn_regenerate_token();
+                }
+                let client = HttpClient::new(None, proxy)
+                    .map_err(|error| format!("Couldn't create HTTP client: {}", error))?;
+                Ok(Self::Gcs {
+                    client,
+                    bucket: bucket.clone(),
+                    object: object.clone(),
+                    credentials,
+                    api_key: auth.api_key.clone(),
+                })
+            }
+        }
+    }
+
+    /// Returns the path of the config, from which its format is guessed.
+    fn path(&self) -> &str {
+        match self {
+            Self::Http { url, .. } => url.path(),
+            #[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+            Self::S3 { key, .. } => key,
+            #[cfg(feature = "gcp")]
+            Self::Gcs { object, .. } => object,
+        }
+    }
+
+    /// Fetches the object at the location of the config, with the given suffix appended to it.
+    async fn fetch(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        match self {
+            Self::Http {
+                client,
+                url,
+                headers,
+            } => {
+                let mut url = url.clone();
+                url.set_path(&format!("{}{}", url.path(), suffix));
+
+                let mut builder = http::Request::get(url.as_str());
+                for (header, value) in headers {
+                    builder = builder.header(header.as_str(), value.as_str());
+                }
+                let request = builder
+                    .body(Body::empty())
+                    .map_err(|error| format!("Couldn't create HTTP request: {}", error))?;
+
+                send(client, request).await
+            }
+            #[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+            Self::S3 {
+                client,
+                bucket,
+                key,
+            } => {
+                use futures::TryStreamExt;
+
+                let object = client
+                    .get_object(GetObjectRequest {
+                        bucket: bucket.clone(),
+                        key: format!("{}{}", key, suffix),
+                        ..Default::default()
+                    })
+                    .await
+                    .map_err(|error| format!("S3 error: {}", error))?;
+                let mut body = object
+                    .body
+                    .ok_or_else(|| "S3 object has no body".to_owned())?;
+                let mut content = BytesMut::new();
+                while let Some(chunk) = body
+                    .try_next()
+                    .await
+                    .map_err(|error| format!("Couldn't read S3 object: {}", error))?
+                {
+                    append(&mut content, &chunk)?;
+                }
+                Ok(content.freeze())
+            }
+            #[cfg(feature = "gcp")]
+            Self::Gcs {
+                client,
+                bucket,
+                object,
+                credentials,
+                api_key,
+            } => {
+                let mut url = Url::parse("https://storage.googleapis.com/storage/v1/b").unwrap();
+                url.path_segments_mut().unwrap().extend(&[
+                    bucket.as_str(),
+                    "o",
+                    &format!("{}{}", object, suffix),
+                ]);
+                url.query_pairs_mut().append_pair("alt", "media");
+                if credentials.is_none() {
+                    if let Some(api_key) = api_key {
+                        url.query_pairs_mut().append_pair("key", api_key);
+                    }
+                }
+
+                let mut request = http::Request::get(url.as_str())
+                    .body(Body::empty())
+                    .map_err(|error| format!("Couldn't create HTTP request: {}", error))?;
+                if let Some(credentials) = credentials {
+                    credentials.apply(&mut request);
+                }
+
+                send(client, request).await
+            }
+        }
+    }
+}
+
+async fn send(
+    client: &HttpClient,
+    request: http::Request<Body>,
+) -> std::result::Result<Bytes, String> {
+    let response = client
+        .send(request)
+        .await
+        .map_err(|error| format!("HTTP error: {}", error))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP status: {}", status));
+    }
+
+    let mut body = response.into_body();
+    let mut content = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|error| format!("Error interpreting response: {}", error))?;
+        append(&mut content, &chunk)?;
+    }
+    Ok(content.freeze())
+}
+
+/// Appends a chunk of a fetched object to its content, unless it gets too large.
+fn append(content: &mut BytesMut, chunk: &[u8]) -> std::result::Result<(), String> {
+    if content.len() + chunk.len() > MAX_OBJECT_SIZE {
+        return Err(format!("Object larger than {} bytes", MAX_OBJECT_SIZE));
+    }
+    content.extend_from_slice(chunk);
+    Ok(())
+}
+
+/// Splits a signature object into the version of the config it signs, and the signature itself.
+/// The signed data is returned along with them, which is the version line followed by the config.
+fn parse_signature(
+    object: &[u8],
+    content: &[u8],
+) -> std::result::Result<(u64, Vec<u8>, Vec<u8>), String> {
+    let version_len = object
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .ok_or_else(|| "Signature has no version line".to_owned())?;
+    let version = std::str::from_utf8(&object[..version_len])
+        .ok()
+        .and_then(|version| version.trim().parse().ok())
+        .ok_or_else(|| "Invalid configuration version in signature".to_owned())?;
+
+    let mut data = object[..=version_len].to_vec();
+    data.extend_from_slice(content);
+    Ok((version, data, object[version_len + 1..].to_vec()))
+}
+
+/// Verifies the signature of some data with the given public key.
+fn verify_signature(
+    public_key: &PKey<Public>,
+    data: &[u8],
+    signature: &[u8],
+) -> std::result::Result<(), String> {
+    let mut verifier = match public_key.id() {
+        Id::ED25519 | Id::ED448 => Verifier::new_without_digest(public_key),
+        _ => Verifier::new(MessageDigest::sha256(), public_key),
+    }
+    .map_err(|error| format!("Unsupported public key: {}", error))?;
+
+    match verifier.verify_oneshot(signature, data) {
+        Ok(true) => Ok(()),
+        Ok(false) | Err(_) => Err("Invalid configuration signature".to_owned()),
+    }
+}
+
+/// The remote config, and how to verify it.
+struct Remote {
+    fetcher: Fetcher,
+    signature: Option<(PKey<Public>, String)>,
+    format: Format,
+    timeout: time::Duration,
+}
+
+/// A fetched config: its raw content, its version if signed, and the config loaded from it.
+struct Fetched {
+    content: Bytes,
+    version: Option<u64>,
+    config_builder: config::ConfigBuilder,
+}
+
+impl Remote {
+    /// Fetches the object at the location of the config, with the given suffix appended to it,
+    /// unless it takes too long.
+    async fn fetch_object(&self, suffix: &str) -> std::result::Result<Bytes, String> {
+        time::timeout(self.timeout, self.fetcher.fetch(suffix))
+            .await
+            .map_err(|_| format!("Timed out after {:?}", self.timeout))?
+    }
+
+    /// Fetches the config, verifying its signature.
+    async fn fetch(&self) -> std::result::Result<Fetched, Vec<String>> {
+        info!(
+            message = "Attempting to retrieve configuration.",
+            path = ?self.fetcher.path()
+        );
+        let content = self.fetch_object("").await.map_err(|error| vec![error])?;
+
+        let version = match &self.signature {
+            Some((public_key, suffix)) => {
+                let signature = self
+                    .fetch_object(suffix)
+                    .await
+                    .map_err(|error| vec![format!("Couldn't fetch signature: {}", error)])?;
+                let (version, data, signature) =
+                    parse_signature(&signature, &content).map_err(|error| vec![error])?;
+                verify_signature(public_key, &data, &signature).map_err(|error| vec![error])?;
+                Some(version)
+            }
+            None => None,
+        };
+
+        let (config_builder, warnings) = config::load(content.chunk(), self.format)?;
+        for warning in warnings.into_iter() {
+            warn!("{}", warning);
+        }
+
+        Ok(Fetched {
+            content,
+            version,
+            config_builder,
+        })
+    }
+}
+
+/// Polls the remote config every `poll_interval_secs`, returning a stream of `ConfigBuilder`, only
+/// yielding those whose content changed, and whose version, if signed, is newer than the applied
+/// one.
+fn poll_remote(
+    poll_interval_secs: u64,
+    remote: Remote,
+    mut content: Bytes,
+    mut version: Option<u64>,
+) -> impl Stream<Item = signal::SignalTo> {
+    let duration = time::Duration::from_secs(poll_interval_secs);
+    let mut interval = time::interval_at(time::Instant::now() + duration, duration);
+
+    stream! {
+        loop {
+            interval.tick().await;
+
+            match remote.fetch().await {
+                Ok(fetched) if fetched.content == content => {}
+                Ok(Fetched { version: Some(new_version), .. })
+                    if version.map_or(false, |version| new_version <= version) =>
+                {
+                    error!(
+                        message = "Remote configuration isn't newer than the current one; keeping the current one.",
+                        version = new_version,
+                        current_version = ?version,
+                    );
+                }
+                Ok(fetched) => {
+                    content = fetched.content;
+                    version = fetched.version;
+                    yield signal::SignalTo::ReloadFromConfigBuilder(fetched.config_builder);
+                }
+                Err(errors) => {
+                    for error in errors {
+                        error!(message = "Couldn't retrieve remote configuration; keeping the current one.", %error);
+                    }
+                }
+            }
+
+            debug!(
+                message = "Remote provider is waiting.",
+                poll_interval_secs = ?poll_interval_secs,
+            );
+        }
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "remote")]
+impl ProviderConfig for RemoteConfig {
+    async fn build(&mut self, signal_handler: &mut signal::SignalHandler) -> Result {
+        let proxy = ProxyConfig::from_env().merge(&self.proxy);
+        let fetcher = Fetcher::new(&self.source, &proxy)
+            .await
+            .map_err(|error| vec![error])?;
+
+        let signature = match &self.signature {
+            Some(signature) => {
+                let pem = std::fs::read(&signature.public_key_file).map_err(|error| {
+                    vec![format!(
+                        "Couldn't read public key file {:?}: {}",
+                        signature.public_key_file, error
+                    )]
+                })?;
+                let public_key = PKey::public_key_from_pem(&pem)
+                    .map_err(|error| vec![format!("Invalid public key: {}", error)])?;
+                Some((public_key, signature.suffix.clone()))
+            }
+            None => None,
+        };
+
+        let format = Format::from_path(fetcher.path()).unwrap_or_default();
+        let remote = Remote {
+            fetcher,
+            signature,
+            format,
+            timeout: time::Duration::from_secs(self.request_timeout_secs),
+        };
+
+        let fetched = remote.fetch().await?;
+
+        // Poll for changes to remote configuration.
+        signal_handler.add(poll_remote(
+            self.poll_interval_secs,
+            remote,
+            fetched.content,
+            fetched.version,
+        ));
+
+        Ok(fetched.config_builder)
+    }
+
+    fn provider_type(&self) -> &'static str {
+        "remote"
+    }
+}
+
+inventory::submit! {
+    ProviderDescription::new::<RemoteConfig>("remote")
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{pkey::Private, rsa::Rsa, sign::Signer};
+
+    use super::*;
+
+    const CONFIG: &[u8] = b"[sources.in]\ntype = \"stdin\"\n";
+
+    fn public_key(key: &PKey<Private>) -> PKey<Public> {
+        PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RemoteConfig>();
+    }
+
+    #[test]
+    fn verifies_ed25519_signatures() {
+        let key = PKey::generate_ed25519().unwrap();
+        let signature = Signer::new_without_digest(&key)
+            .unwrap()
+            .sign_oneshot_to_vec(CONFIG)
+            .unwrap();
+
+        let public_key = public_key(&key);
+        assert!(verify_signature(&public_key, CONFIG, &signature).is_ok());
+        assert!(verify_signature(&public_key, b"[sources.in]\n", &signature).is_err());
+    }
+
+    #[test]
+    fn verifies_rsa_signatures() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+        signer.update(CONFIG).unwrap();
+        let signature = signer.sign_to_vec().unwrap();
+
+        let public_key = public_key(&key);
+        assert!(verify_signature(&public_key, CONFIG, &signature).is_ok());
+        assert!(verify_signature(&public_key, CONFIG, b"not a signature").is_err());
+    }
+
+    #[test]
+    fn verifies_versioned_signatures() {
+        let key = PKey::generate_ed25519().unwrap();
+        let mut signed = b"42\n".to_vec();
+        signed.extend_from_slice(CONFIG);
+        let mut object = b"42\n".to_vec();
+        object.extend(
+            Signer::new_without_digest(&key)
+                .unwrap()
+                .sign_oneshot_to_vec(&signed)
+                .unwrap(),
+        );
+
+        let (version, data, signature) = parse_signature(&object, CONFIG).unwrap();
+        assert_eq!(version, 42);
+        assert!(verify_signature(&public_key(&key), &data, &signature).is_ok());
+
+        // The version is signed along with the config, so it can't be bumped.
+        let mut bumped = b"43\n".to_vec();
+        bumped.extend_from_slice(&object[3..]);
+        let (_, data, signature) = parse_signature(&bumped, CONFIG).unwrap();
+        assert!(verify_signature(&public_key(&key), &data, &signature).is_err());
+
+        assert!(parse_signature(b"not a version", CONFIG).is_err());
+    }
+
+    #[test]
+    fn refuses_too_large_objects() {
+        let mut content = BytesMut::new();
+        assert!(append(&mut content, &vec![0; MAX_OBJECT_SIZE]).is_ok());
+        assert!(append(&mut content, b"1").is_err());
+    }
+
+    #[test]
+    fn rejects_signatures_of_other_keys() {
+        let key = PKey::generate_ed25519().unwrap();
+        let signature = Signer::new_without_digest(&key)
+            .unwrap()
+            .sign_oneshot_to_vec(CONFIG)
+            .unwrap();
+
+        let other = public_key(&PKey::generate_ed25519().unwrap());
+        assert!(verify_signature(&other, CONFIG, &signature).is_err());
+    }
+}
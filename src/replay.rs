@@ -0,0 +1,510 @@
+use std::{collections::HashMap, io::Read, path::PathBuf, sync::Arc};
+
+use bytes::Bytes;
+use clap::Parser;
+use flate2::read::MultiGzDecoder;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Mutex};
+use vector_core::config::{DataType, Output};
+
+use crate::{
+    cli::handle_config_errors,
+    config::{
+        self, ComponentKey, ConfigBuilder, ConfigDiff, SourceConfig, SourceContext, SourceOuter,
+    },
+    event::{Event, EventArray, EventContainer},
+    sources, topology, SourceSender,
+};
+
+/// The number of events sent to the pipeline at once, when replaying NDJSON.
+const BATCH_SIZE: usize = 1000;
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.toml` will be targeted.
+    #[clap(
+        name = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        use_value_delimiter(true)
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format.
+    #[clap(name = "config-toml", long, use_value_delimiter(true))]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[clap(name = "config-json", long, use_value_delimiter(true))]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[clap(name = "config-yaml", long, use_value_delimiter(true))]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[clap(
+        name = "config-dir",
+        short = 'C',
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        use_value_delimiter(true)
+    )]
+    pub config_dirs: Vec<PathBuf>,
+
+    /// The component the events are fed into: either a source, which is
+    /// replaced, or a transform, given the events as an extra input. The other
+    /// sources are left out, along with the components only they feed.
+    #[clap(long)]
+    into: String,
+
+    /// The format of the inputs: `ndjson`, for files of JSON log events, one per
+    /// line, optionally gzipped, or `disk-buffer`, for the directories of sink
+    /// disk buffers.
+    #[clap(long, default_value = "ndjson")]
+    format: ReplayFormat,
+
+    /// The AWS region of the S3 objects replayed. Defaults to the region of
+    /// the environment.
+    #[clap(long)]
+    region: Option<String>,
+
+    /// The files, `s3://<bucket>/<key>` objects, or disk buffer directories
+    /// to replay, in order.
+    #[clap(required = true)]
+    inputs: Vec<String>,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::Toml)),
+            (&self.paths_json, Some(config::Format::Json)),
+            (&self.paths_yaml, Some(config::Format::Yaml)),
+        ])
+        .map(|(path, hint)| config::ConfigPath::File(path, hint))
+        .chain(
+            self.config_dirs
+                .iter()
+                .map(|dir| config::ConfigPath::Dir(dir.to_path_buf())),
+        )
+        .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReplayFormat {
+    Ndjson,
+    DiskBuffer,
+}
+
+impl std::str::FromStr for ReplayFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ndjson" => Ok(ReplayFormat::Ndjson),
+            "disk-buffer" => Ok(ReplayFormat::DiskBuffer),
+            s => Err(format!(
+                "{} is not a valid option, expected `ndjson` or `disk-buffer`",
+                s
+            )),
+        }
+    }
+}
+
+/// CLI command func for replaying archived events through the pipeline of a config, until they
+/// have all been processed.
+pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let paths = opts.paths_with_formats();
+    let paths = match config::process_paths(&paths) {
+        Some(paths) => paths,
+        None => return exitcode::CONFIG,
+    };
+    let mut builder = match config::load_builder_and_secrets_from_paths(&paths).await {
+        Ok((builder, _)) => builder,
+        Err(errors) => return handle_config_errors(errors),
+    };
+
+    let (result_tx, result_rx) = oneshot::channel();
+    let source = ReplaySourceConfig {
+        inputs: opts.inputs.clone(),
+        format: opts.format,
+        region: opts.region.clone(),
+        result_tx: Arc::new(Mutex::new(Some(result_tx))),
+    };
+    if let Err(error) = inject_source(&mut builder, &opts.into, source) {
+        return handle_config_errors(vec![error]);
+    }
+
+    let config = match builder.build() {
+        Ok(config) => config,
+        Err(errors) => return handle_config_errors(errors),
+    };
+    let diff = ConfigDiff::initial(&config);
+    let pieces = match topology::build_or_log_errors(&config, &diff, HashMap::new()).await {
+        Some(pieces) => pieces,
+        None => return exitcode::CONFIG,
+    };
+    let topology = match topology::start_validated(config, diff, pieces).await {
+        Some((topology, _)) => topology,
+        None => return exitcode::CONFIG,
+    };
+
+    // Once the replay is over, the pipeline is shut down, which lets the transforms and sinks
+    // process the events still in flight.
+    topology.sources_finished().await;
+    topology.stop().await;
+
+    match result_rx.await {
+        Ok(Ok(events)) => {
+            info!(message = "Replay complete.", events);
+            exitcode::OK
+        }
+        Ok(Err(error)) => {
+            error!(message = "Replay failed.", %error);
+            exitcode::IOERR
+        }
+        Err(_) => {
+            error!(message = "Replay didn't run.");
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+/// Feeds the events of the source into the given component, leaving out the other sources and the
+/// components they alone feed.
+fn inject_source(
+    builder: &mut ConfigBuilder,
+    into: &str,
+    source: ReplaySourceConfig,
+) -> Result<(), String> {
+    let into = ComponentKey::from(into);
+    let source_key = if builder.sources.contains_key(&into) {
+        into.clone()
+    } else if builder.transforms.contains_key(&into) {
+        ComponentKey::from(format!("replay-{}", uuid::Uuid::new_v4()))
+    } else {
+        return Err(format!(
+            "Can't replay into {:?}, as there's no such source or transform.",
+            into.id()
+        ));
+    };
+
+    builder.sources.clear();
+    builder
+        .sources
+        .insert(source_key.clone(), SourceOuter::new(source));
+    if let Some(transform) = builder.transforms.get_mut(&into) {
+        transform.inputs.push(source_key.id().to_owned());
+    }
+    prune_disconnected(builder);
+
+    Ok(())
+}
+
+/// Removes the inputs referring to components which don't exist anymore, and then the components
+/// left without inputs, until all of the remaining ones are fed.
+fn prune_disconnected(builder: &mut ConfigBuilder) {
+    loop {
+        let components = builder
+            .sources
+            .keys()
+            .chain(builder.transforms.keys())
+            .map(|key| key.id().to_owned())
+            .collect::<Vec<_>>();
+        let exists = |input: &String| {
+            // Globs are only expanded when building the config.
+            input.contains('*')
+                || components.iter().any(|component| {
+                    input == component
+                        || input
+                            .strip_prefix(component.as_str())
+                            .map_or(false, |port| port.starts_with('.'))
+                })
+        };
+
+        for transform in builder.transforms.values_mut() {
+            transform.inputs.retain(|input| exists(input));
+        }
+        for sink in builder.sinks.values_mut() {
+            sink.inputs.retain(|input| exists(input));
+        }
+
+        let transforms = builder.transforms.len();
+        let sinks = builder.sinks.len();
+        builder
+            .transforms
+            .retain(|_, transform| !transform.inputs.is_empty());
+        builder.sinks.retain(|_, sink| !sink.inputs.is_empty());
+        if builder.transforms.len() == transforms && builder.sinks.len() == sinks {
+            break;
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ReplaySourceConfig {
+    inputs: Vec<String>,
+    format: ReplayFormat,
+    region: Option<String>,
+    // Sender used to transmit the number of events replayed, or the error which stopped the replay
+    #[serde(skip)]
+    result_tx: Arc<Mutex<Option<oneshot::Sender<Result<usize, String>>>>>,
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "replay")]
+impl SourceConfig for ReplaySourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
+        let inputs = self.inputs.clone();
+        let format = self.format;
+        let region = self.region.clone();
+        let result_tx = self.result_tx.lock().await.take();
+
+        Ok(Box::pin(async move {
+            let mut out = cx.out;
+            // Shutting down the topology once all the events are replayed relies on this
+            // shutdown trigger being held until then, as it's not listened to otherwise.
+            let _shutdown = cx.shutdown;
+
+            let mut result = Ok(0);
+            for input in &inputs {
+                match replay(input, format, region.as_deref(), &mut out).await {
+                    Ok(events) => {
+                        debug!(message = "Input replayed.", %input, events);
+                        result = result.map(|total| total + events);
+                    }
+                    Err(error) => {
+                        result = Err(format!("Couldn't replay {:?}: {}", input, error));
+                        break;
+                    }
+                }
+            }
+
+            let failed = result.is_err();
+            if let Some(tx) = result_tx {
+                let _ = tx.send(result);
+            }
+            if failed {
+                Err(())
+            } else {
+                Ok(())
+            }
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::all())]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "replay"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Sends the events of the input to the pipeline, returning how many there were.
+async fn replay(
+    input: &str,
+    format: ReplayFormat,
+    region: Option<&str>,
+    out: &mut SourceSender,
+) -> crate::Result<usize> {
+    match format {
+        ReplayFormat::Ndjson => {
+            let data = read_input(input, region).await?;
+            let mut events = 0;
+            let mut batch = Vec::with_capacity(BATCH_SIZE);
+            for (index, line) in decompress(input, data)?.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let value: serde_json::Value = serde_json::from_str(line)
+                    .map_err(|error| format!("invalid JSON on line {}: {}", index + 1, error))?;
+                let event = Event::try_from(value)
+                    .map_err(|error| format!("invalid event on line {}: {}", index + 1, error))?;
+                batch.push(event);
+                if batch.len() == BATCH_SIZE {
+                    events += batch.len();
+                    out.send_batch(std::mem::take(&mut batch)).await?;
+                }
+            }
+            events += batch.len();
+            out.send_batch(batch).await?;
+            Ok(events)
+        }
+        ReplayFormat::DiskBuffer => {
+            let path = PathBuf::from(input);
+            if !path.is_dir() {
+                return Err(format!("{:?} isn't a disk buffer directory", path).into());
+            }
+            let records = vector_buffers::read_disk_v2_buffer::<EventArray>(path)
+                .await
+                .map_err(|error| error.to_string())?;
+            futures::pin_mut!(records);
+
+            let mut events = 0;
+            while let Some(record) = records.next().await {
+                events += record.len();
+                out.send_event(record).await?;
+            }
+            Ok(events)
+        }
+    }
+}
+
+/// Reads the whole file, or S3 object, to replay.
+async fn read_input(input: &str, region: Option<&str>) -> crate::Result<Bytes> {
+    match input.strip_prefix("s3://") {
+        Some(location) => read_s3_object(location, region).await,
+        None => Ok(tokio::fs::read(input).await?.into()),
+    }
+}
+
+#[cfg(all(feature = "rusoto", feature = "rusoto_s3"))]
+async fn read_s3_object(location: &str, region: Option<&str>) -> crate::Result<Bytes> {
+    use futures::TryStreamExt;
+    use rusoto_s3::{GetObjectRequest, S3Client, S3};
+
+    use crate::aws::{rusoto, AwsAuthentication, RegionOrEndpoint};
+
+    let (bucket, key) = location
+        .split_once('/')
+        .ok_or("S3 objects are given as s3://<bucket>/<key>")?;
+    let region = match region {
+        Some(region) => (&RegionOrEndpoint::with_region(region.to_owned())).try_into()?,
+        None => rusoto_core::Region::default(),
+    };
+    let creds = AwsAuthentication::default().build(&region, None)?;
+    let client = S3Client::new_with(rusoto::client(None, &Default::default())?, creds, region);
+
+    let object = client
+        .get_object(GetObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        })
+        .await?;
+    let body = object
+        .body
+        .ok_or("S3 object has no body")?
+        .map_ok(|chunk| chunk.to_vec())
+        .try_concat()
+        .await?;
+    Ok(body.into())
+}
+
+#[cfg(not(all(feature = "rusoto", feature = "rusoto_s3")))]
+async fn read_s3_object(_location: &str, _region: Option<&str>) -> crate::Result<Bytes> {
+    Err("this build of Vector doesn't support reading from S3".into())
+}
+
+/// Decompresses gzipped inputs, as told by their name.
+fn decompress(input: &str, data: Bytes) -> crate::Result<String> {
+    if input.ends_with(".gz") {
+        let mut decompressed = String::new();
+        MultiGzDecoder::new(&data[..]).read_to_string(&mut decompressed)?;
+        Ok(decompressed)
+    } else {
+        Ok(String::from_utf8(data.to_vec())?)
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "sources-stdin",
+    feature = "transforms-remap",
+    feature = "sinks-blackhole"
+))]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn replay_source() -> ReplaySourceConfig {
+        ReplaySourceConfig {
+            inputs: vec!["events.ndjson".to_owned()],
+            format: ReplayFormat::Ndjson,
+            region: None,
+            result_tx: Default::default(),
+        }
+    }
+
+    fn builder() -> ConfigBuilder {
+        config::format::deserialize(
+            indoc! {r#"
+                [sources.in1]
+                type = "stdin"
+
+                [sources.in2]
+                type = "stdin"
+
+                [transforms.parse]
+                type = "remap"
+                inputs = ["in1"]
+                source = ""
+
+                [transforms.other]
+                type = "remap"
+                inputs = ["in2"]
+                source = ""
+
+                [sinks.out]
+                type = "blackhole"
+                inputs = ["parse", "other"]
+
+                [sinks.other_out]
+                type = "blackhole"
+                inputs = ["other"]
+            "#},
+            config::Format::Toml,
+        )
+        .unwrap()
+    }
+
+    fn keys<T>(components: &indexmap::IndexMap<ComponentKey, T>) -> Vec<&str> {
+        components.keys().map(ComponentKey::id).collect()
+    }
+
+    #[test]
+    fn replaces_source() {
+        let mut builder = builder();
+        inject_source(&mut builder, "in1", replay_source()).unwrap();
+
+        assert_eq!(keys(&builder.sources), vec!["in1"]);
+        assert_eq!(keys(&builder.transforms), vec!["parse"]);
+        assert_eq!(keys(&builder.sinks), vec!["out"]);
+        assert_eq!(builder.sinks[0].inputs, vec!["parse".to_owned()]);
+    }
+
+    #[test]
+    fn feeds_transform() {
+        let mut builder = builder();
+        inject_source(&mut builder, "other", replay_source()).unwrap();
+
+        let source = keys(&builder.sources)[0].to_owned();
+        assert_eq!(keys(&builder.transforms), vec!["other"]);
+        assert_eq!(builder.transforms[0].inputs, vec![source]);
+        assert_eq!(keys(&builder.sinks), vec!["out", "other_out"]);
+    }
+
+    #[test]
+    fn rejects_unknown_components() {
+        assert!(inject_source(&mut builder(), "zork", replay_source()).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+};
+
+use rusoto_core::{region::Region, signature::SignedRequest, Client, RusotoError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{read_field, split_field, SecretBackend};
+use crate::{
+    aws::{rusoto, AwsAuthentication, RegionOrEndpoint},
+    config::ProxyConfig,
+};
+
+/// Retrieves secrets from AWS Secrets Manager, the key of each secret being its name or ARN,
+/// optionally followed by the field to read from secrets holding JSON, as in `prod/db#password`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AwsSecretsManagerBackend {
+    #[serde(flatten)]
+    region: RegionOrEndpoint,
+    #[serde(default)]
+    auth: AwsAuthentication,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    proxy: ProxyConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetSecretValueResponse {
+    secret_string: Option<String>,
+}
+
+async fn get_secret_value(
+    client: &Client,
+    region: &Region,
+    secret_id: &str,
+) -> crate::Result<String> {
+    let mut request = SignedRequest::new("POST", "secretsmanager", region, "/");
+    request.set_content_type("application/x-amz-json-1.1".to_owned());
+    request.add_header("x-amz-target", "secretsmanager.GetSecretValue");
+    request.set_payload(Some(serde_json::to_vec(&json!({ "SecretId": secret_id }))?));
+
+    let response = client
+        .sign_and_dispatch(request)
+        .await
+        .map_err(|error| RusotoError::<Infallible>::from(error).to_string())?
+        .buffer()
+        .await?;
+    if !response.status.is_success() {
+        return Err(format!(
+            "AWS Secrets Manager responded with {}: {}",
+            response.status,
+            String::from_utf8_lossy(&response.body)
+        )
+        .into());
+    }
+
+    let response: GetSecretValueResponse = serde_json::from_slice(&response.body)?;
+    response
+        .secret_string
+        .ok_or_else(|| "binary secrets aren't supported".into())
+}
+
+#[async_trait::async_trait]
+impl SecretBackend for AwsSecretsManagerBackend {
+    async fn retrieve(
+        &self,
+        secret_keys: HashSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let region = (&self.region).try_into()?;
+        let dispatcher = rusoto::client(None, &self.proxy)?;
+        let creds = self.auth.build(&region, None)?;
+        let client = Client::new_with(creds, dispatcher);
+
+        let mut secrets = HashMap::new();
+        let mut values = HashMap::new();
+        for key in secret_keys {
+            let (secret_id, field) = split_field(&key);
+
+            if !values.contains_key(secret_id) {
+                let value = get_secret_value(&client, &region, secret_id)
+                    .await
+                    .map_err(|error| format!("secret \"{}\": {}", secret_id, error))?;
+                values.insert(secret_id.to_owned(), value);
+            }
+            let value = read_field(&values[secret_id], field)
+                .map_err(|error| format!("secret \"{}\": {}", key, error))?;
+            secrets.insert(key, value);
+        }
+
+        Ok(secrets)
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command, time};
+
+use super::SecretBackend;
+
+const fn default_timeout_secs() -> u64 {
+    5
+}
+
+/// Retrieves secrets by running a command, which is given the keys of the secrets as JSON on its
+/// standard input, and writes their values as JSON on its standard output.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExecBackend {
+    command: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+}
+
+#[derive(Serialize)]
+struct ExecQuery {
+    version: &'static str,
+    secrets: HashSet<String>,
+}
+
+#[derive(Deserialize)]
+struct ExecResponse {
+    value: Option<String>,
+    error: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl SecretBackend for ExecBackend {
+    async fn retrieve(
+        &self,
+        secret_keys: HashSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or("the command to run must not be empty")?;
+        let query = serde_json::to_vec(&ExecQuery {
+            version: "1.0",
+            secrets: secret_keys,
+        })?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|error| format!("couldn't run {:?}: {}", program, error))?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(&query).await?;
+        // The command is expected to read its whole input before answering.
+        drop(stdin);
+
+        let output = time::timeout(
+            time::Duration::from_secs(self.timeout_secs),
+            child.wait_with_output(),
+        )
+        .await
+        .map_err(|_| format!("command timed out after {}s", self.timeout_secs))??;
+        if !output.status.success() {
+            return Err(format!("command exited with {}", output.status).into());
+        }
+
+        let responses: HashMap<String, ExecResponse> = serde_json::from_slice(&output.stdout)
+            .map_err(|error| format!("invalid command output: {}", error))?;
+        responses
+            .into_iter()
+            .map(|(key, response)| match (response.value, response.error) {
+                (_, Some(error)) => Err(format!("secret \"{}\": {}", key, error).into()),
+                (Some(value), None) => Ok((key, value)),
+                (None, None) => Err(format!("secret \"{}\" has no value", key).into()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn backend(script: &str) -> ExecBackend {
+        ExecBackend {
+            command: vec!["sh".to_owned(), "-c".to_owned(), script.to_owned()],
+            timeout_secs: 1,
+        }
+    }
+
+    fn keys(keys: &[&str]) -> HashSet<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn retrieves_secrets() {
+        let secrets = backend(
+            r#"grep -q '"version":"1.0"' && echo '{"token": {"value": "hunter2", "error": null}}'"#,
+        )
+        .retrieve(keys(&["token"]))
+        .await
+        .unwrap();
+
+        assert_eq!(secrets["token"], "hunter2");
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let error = backend(
+            r#"cat > /dev/null; echo '{"token": {"value": null, "error": "access denied"}}'"#,
+        )
+        .retrieve(keys(&["token"]))
+        .await
+        .unwrap_err();
+        assert_eq!(error.to_string(), "secret \"token\": access denied");
+
+        let error = backend("cat > /dev/null; exit 1")
+            .retrieve(keys(&["token"]))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "command exited with exit status: 1");
+
+        let error = backend("sleep 5")
+            .retrieve(keys(&["token"]))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "command timed out after 1s");
+    }
+}
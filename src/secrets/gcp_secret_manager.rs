@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{read_field, split_field, SecretBackend};
+use crate::{
+    config::ProxyConfig,
+    gcp::{GcpAuthConfig, GcpCredentials, Scope},
+    http::HttpClient,
+};
+
+/// Retrieves secrets from GCP Secret Manager, the key of each secret being its ID, optionally
+/// followed by its version and the field to read from secrets holding JSON, as in
+/// `postgres@3#password`. The latest version is read unless one is given.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GcpSecretManagerBackend {
+    project: String,
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    proxy: ProxyConfig,
+}
+
+#[derive(Deserialize)]
+struct AccessSecretVersionResponse {
+    payload: SecretPayload,
+}
+
+#[derive(Deserialize)]
+struct SecretPayload {
+    data: String,
+}
+
+/// Splits the name of a secret into its ID and version.
+fn split_version(name: &str) -> (&str, &str) {
+    name.split_once('@').unwrap_or((name, "latest"))
+}
+
+impl GcpSecretManagerBackend {
+    async fn access(
+        &self,
+        client: &HttpClient,
+        credentials: Option<&GcpCredentials>,
+        secret: &str,
+        version: &str,
+    ) -> crate::Result<String> {
+        let mut url = Url::parse("https://secretmanager.googleapis.com/v1/projects").unwrap();
+        url.path_segments_mut().unwrap().extend(&[
+            self.project.as_str(),
+            "secrets",
+            secret,
+            "versions",
+            &format!("{}:access", version),
+        ]);
+        if credentials.is_none() {
+            if let Some(api_key) = &self.auth.api_key {
+                url.query_pairs_mut().append_pair("key", api_key);
+            }
+        }
+
+        let mut request = http::Request::get(url.as_str()).body(Body::empty())?;
+        if let Some(credentials) = credentials {
+            credentials.apply(&mut request);
+        }
+
+        let response = client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if !status.is_success() {
+            return Err(format!(
+                "GCP Secret Manager responded with {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            )
+            .into());
+        }
+
+        let response: AccessSecretVersionResponse = serde_json::from_slice(&body)?;
+        let data = base64::decode(response.payload.data)?;
+        String::from_utf8(data).map_err(|_| "secret isn't valid UTF-8".into())
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretBackend for GcpSecretManagerBackend {
+    async fn retrieve(
+        &self,
+        secret_keys: HashSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        // Secrets are only retrieved when the config is loaded, so the token doesn't need to be
+        // regenerated in the background.
+        let credentials = self.auth.make_credentials(Scope::CloudPlatform).await?;
+        let client = HttpClient::new(None, &self.proxy)?;
+
+        let mut secrets = HashMap::new();
+        let mut values = HashMap::new();
+        for key in secret_keys {
+            let (name, field) = split_field(&key);
+
+            if !values.contains_key(name) {
+                let (secret, version) = split_version(name);
+                let value = self
+                    .access(&client, credentials.as_ref(), secret, version)
+                    .await
+                    .map_err(|error| format!("secret \"{}\": {}", name, error))?;
+                values.insert(name.to_owned(), value);
+            }
+            let value = read_field(&values[name], field)
+                .map_err(|error| format!("secret \"{}\": {}", key, error))?;
+            secrets.insert(key, value);
+        }
+
+        Ok(secrets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_versions() {
+        assert_eq!(split_version("postgres"), ("postgres", "latest"));
+        assert_eq!(split_version("postgres@3"), ("postgres", "3"));
+    }
+}
@@ -0,0 +1,330 @@
+//! Secret backends, retrieving the secrets referenced as `SECRET[<backend>.<key>]` in the config
+//! when it's loaded, so that credentials never have to be written in config files.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::config::ComponentKey;
+
+#[cfg(feature = "rusoto")]
+mod aws_secrets_manager;
+mod exec;
+#[cfg(feature = "gcp")]
+mod gcp_secret_manager;
+mod vault;
+
+#[cfg(feature = "rusoto")]
+pub use aws_secrets_manager::AwsSecretsManagerBackend;
+pub use exec::ExecBackend;
+#[cfg(feature = "gcp")]
+pub use gcp_secret_manager::GcpSecretManagerBackend;
+pub use vault::VaultBackend;
+
+// Backend IDs follow the rules of component IDs, while keys can also contain the characters
+// naming secrets in the supported backends, such as the paths of Vault or AWS Secrets Manager,
+// along with the `#` separating the field to read from structured secrets.
+static SECRET_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"SECRET\[([[:word:]]+)\.([[:word:]./#:@+=-]+)\]").unwrap());
+
+/// The values retrieved from the backends with a cache TTL, by backend ID and key.
+static CACHE: Lazy<Mutex<HashMap<(ComponentKey, String), (String, Instant)>>> =
+    Lazy::new(Mutex::default);
+
+#[async_trait::async_trait]
+pub trait SecretBackend {
+    /// Retrieves the values of the given secrets, by key.
+    async fn retrieve(
+        &self,
+        secret_keys: HashSet<String>,
+    ) -> crate::Result<HashMap<String, String>>;
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SecretBackends {
+    Exec(ExecBackend),
+    Vault(VaultBackend),
+    #[cfg(feature = "rusoto")]
+    AwsSecretsManager(AwsSecretsManagerBackend),
+    #[cfg(feature = "gcp")]
+    GcpSecretManager(GcpSecretManagerBackend),
+}
+
+#[async_trait::async_trait]
+impl SecretBackend for SecretBackends {
+    async fn retrieve(
+        &self,
+        secret_keys: HashSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        match self {
+            Self::Exec(backend) => backend.retrieve(secret_keys).await,
+            Self::Vault(backend) => backend.retrieve(secret_keys).await,
+            #[cfg(feature = "rusoto")]
+            Self::AwsSecretsManager(backend) => backend.retrieve(secret_keys).await,
+            #[cfg(feature = "gcp")]
+            Self::GcpSecretManager(backend) => backend.retrieve(secret_keys).await,
+        }
+    }
+}
+
+/// A secret backend, as configured under `secret`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SecretBackendOuter {
+    #[serde(flatten)]
+    pub inner: SecretBackends,
+
+    /// How long the retrieved secrets are reused, across reloads, before being retrieved again.
+    /// Unless set, secrets are retrieved every time the config is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+}
+
+/// Adds the secrets referenced in the given input to the keys of their backends.
+pub fn collect_secret_keys(input: &str, keys: &mut HashMap<String, HashSet<String>>) {
+    for caps in SECRET_RE.captures_iter(input) {
+        keys.entry(caps[1].to_owned())
+            .or_default()
+            .insert(caps[2].to_owned());
+    }
+}
+
+/// Replaces the secrets referenced in the given input with their values, keyed by
+/// `<backend>.<key>`.
+pub fn interpolate(input: &str, secrets: &HashMap<String, String>) -> Result<String, Vec<String>> {
+    let mut errors = Vec::new();
+    let interpolated = SECRET_RE
+        .replace_all(input, |caps: &Captures<'_>| {
+            let name = format!("{}.{}", &caps[1], &caps[2]);
+            secrets.get(&name).cloned().unwrap_or_else(|| {
+                errors.push(format!("Unable to find secret replacement for {}.", name));
+                String::new()
+            })
+        })
+        .into_owned();
+
+    if errors.is_empty() {
+        Ok(interpolated)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Retrieves the secrets referenced in the config from their backends, returning their values
+/// keyed by `<backend>.<key>`. Values still in the cache of their backend aren't retrieved again.
+pub async fn retrieve(
+    backends: &IndexMap<ComponentKey, SecretBackendOuter>,
+    secret_keys: HashMap<String, HashSet<String>>,
+) -> Result<HashMap<String, String>, Vec<String>> {
+    let mut secrets = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (backend_id, keys) in secret_keys {
+        let key = ComponentKey::from(backend_id.as_str());
+        let backend = match backends.get(&key) {
+            Some(backend) => backend,
+            None => {
+                errors.push(format!(
+                    "Secrets are referenced from undefined backend \"{}\".",
+                    backend_id
+                ));
+                continue;
+            }
+        };
+
+        let ttl = backend.cache_ttl_secs.map(Duration::from_secs);
+        let mut missing = keys;
+        if ttl.is_some() {
+            let cache = CACHE
+                .lock()
+                .expect("Secret cache lock poisoned. Please report this.");
+            let now = Instant::now();
+            missing.retain(
+                |secret_key| match cache.get(&(key.clone(), secret_key.clone())) {
+                    Some((value, expires_at)) if *expires_at > now => {
+                        secrets.insert(format!("{}.{}", backend_id, secret_key), value.clone());
+                        false
+                    }
+                    _ => true,
+                },
+            );
+        }
+        if missing.is_empty() {
+            continue;
+        }
+
+        match backend.inner.retrieve(missing.clone()).await {
+            Ok(values) => {
+                for secret_key in &missing {
+                    if !values.contains_key(secret_key) {
+                        errors.push(format!(
+                            "Secret \"{}\" wasn't returned by backend \"{}\".",
+                            secret_key, backend_id
+                        ));
+                    }
+                }
+
+                if let Some(ttl) = ttl {
+                    let mut cache = CACHE
+                        .lock()
+                        .expect("Secret cache lock poisoned. Please report this.");
+                    let expires_at = Instant::now() + ttl;
+                    for (secret_key, value) in &values {
+                        cache.insert(
+                            (key.clone(), secret_key.clone()),
+                            (value.clone(), expires_at),
+                        );
+                    }
+                }
+
+                secrets.extend(
+                    values.into_iter().map(|(secret_key, value)| {
+                        (format!("{}.{}", backend_id, secret_key), value)
+                    }),
+                );
+            }
+            Err(error) => errors.push(format!(
+                "Error retrieving secrets from backend \"{}\": {}",
+                backend_id, error
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(secrets)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Splits the key of a secret into the name of the secret, and the field to read from it, if any.
+fn split_field(key: &str) -> (&str, Option<&str>) {
+    match key.split_once('#') {
+        Some((name, field)) => (name, Some(field)),
+        None => (key, None),
+    }
+}
+
+/// Reads a field from a secret holding a JSON object, or the whole secret if no field is given.
+fn read_field(secret: &str, field: Option<&str>) -> crate::Result<String> {
+    let field = match field {
+        Some(field) => field,
+        None => return Ok(secret.to_owned()),
+    };
+
+    let object: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(secret).map_err(|_| {
+            format!(
+                "secret isn't a JSON object, so field \"{}\" can't be read",
+                field
+            )
+        })?;
+    match object.get(field) {
+        Some(serde_json::Value::String(value)) => Ok(value.clone()),
+        Some(value) => Ok(value.to_string()),
+        None => Err(format!("secret has no field \"{}\"", field).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_secret_keys() {
+        let mut keys = HashMap::new();
+        collect_secret_keys(
+            r#"
+            password = "SECRET[vault.secret/data/app#password]"
+            token = "SECRET[exec.token]"
+            other_token = "SECRET[exec.other_token]"
+            "#,
+            &mut keys,
+        );
+
+        assert_eq!(keys.len(), 2);
+        assert_eq!(
+            keys["vault"],
+            vec!["secret/data/app#password".to_owned()]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(
+            keys["exec"],
+            vec!["token".to_owned(), "other_token".to_owned()]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn interpolates_secrets() {
+        let secrets = vec![("exec.token".to_owned(), "hunter2".to_owned())]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            interpolate("token = \"SECRET[exec.token]\"", &secrets),
+            Ok("token = \"hunter2\"".to_owned())
+        );
+        assert_eq!(
+            interpolate("token = \"SECRET[exec.other_token]\"", &secrets),
+            Err(vec![
+                "Unable to find secret replacement for exec.other_token.".to_owned()
+            ])
+        );
+        // Not a valid reference, so left as is.
+        assert_eq!(
+            interpolate("token = \"SECRET[token]\"", &secrets),
+            Ok("token = \"SECRET[token]\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn reads_fields() {
+        assert_eq!(read_field("hunter2", None).unwrap(), "hunter2");
+        assert_eq!(
+            read_field(r#"{"user": "admin", "port": 5432}"#, Some("user")).unwrap(),
+            "admin"
+        );
+        assert_eq!(
+            read_field(r#"{"user": "admin", "port": 5432}"#, Some("port")).unwrap(),
+            "5432"
+        );
+        assert!(read_field(r#"{"user": "admin"}"#, Some("password")).is_err());
+        assert!(read_field("hunter2", Some("password")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn caches_secrets() {
+        let backends: IndexMap<ComponentKey, SecretBackendOuter> = toml::from_str(
+            r#"
+            [cached]
+            type = "exec"
+            command = ["sh", "-c", "cat > /dev/null; echo \"{\\\"key\\\": {\\\"value\\\": \\\"$(date +%s%N)\\\", \\\"error\\\": null}}\""]
+            cache_ttl_secs = 60
+            "#,
+        )
+        .unwrap();
+        let keys = || {
+            vec![(
+                "cached".to_owned(),
+                vec!["key".to_owned()].into_iter().collect(),
+            )]
+            .into_iter()
+            .collect::<HashMap<_, _>>()
+        };
+
+        let first = retrieve(&backends, keys()).await.unwrap();
+        let second = retrieve(&backends, keys()).await.unwrap();
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use http::{Method, Request};
+use hyper::Body;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{read_field, split_field, SecretBackend};
+use crate::{
+    config::ProxyConfig,
+    http::HttpClient,
+    tls::{TlsOptions, TlsSettings},
+};
+
+/// The tokens obtained from Vault, by address and identity, reused until they have to be renewed.
+static TOKENS: Lazy<Mutex<HashMap<String, VaultToken>>> = Lazy::new(Mutex::default);
+
+fn default_approle_mount() -> String {
+    "approle".to_owned()
+}
+
+/// Retrieves secrets from the KV secrets engine of HashiCorp Vault, the key of each secret being
+/// its path followed by the field to read from it, as in `secret/data/postgres#password`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VaultBackend {
+    /// The address of the Vault server, such as `https://vault.example.com:8200`.
+    address: String,
+    /// The Vault Enterprise namespace the secrets are in.
+    namespace: Option<String>,
+    auth: VaultAuth,
+    tls: Option<TlsOptions>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    proxy: ProxyConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+enum VaultAuth {
+    Token {
+        token: String,
+    },
+    Approle {
+        role_id: String,
+        secret_id: String,
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct VaultToken {
+    token: String,
+    renewable: bool,
+    lease_duration: Duration,
+    // Tokens without a TTL never expire.
+    expires_at: Option<Instant>,
+}
+
+impl VaultToken {
+    fn new(token: String, renewable: bool, ttl_secs: u64) -> Self {
+        let lease_duration = Duration::from_secs(ttl_secs);
+        Self {
+            token,
+            renewable,
+            lease_duration,
+            expires_at: (ttl_secs > 0).then(|| Instant::now() + lease_duration),
+        }
+    }
+
+    /// Parses the `auth` block returned by logins and renewals.
+    fn from_auth(response: &Value) -> crate::Result<Self> {
+        let auth = &response["auth"];
+        let token = auth["client_token"]
+            .as_str()
+            .ok_or("Vault didn't return a token")?;
+        Ok(Self::new(
+            token.to_owned(),
+            auth["renewable"].as_bool().unwrap_or(false),
+            auth["lease_duration"].as_u64().unwrap_or(0),
+        ))
+    }
+
+    /// Returns whether the token should be renewed, once two thirds of its lease have elapsed.
+    fn needs_renewal(&self, now: Instant) -> bool {
+        self.expires_at.map_or(false, |expires_at| {
+            expires_at < now + self.lease_duration / 3
+        })
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+impl VaultBackend {
+    fn cache_key(&self) -> String {
+        match &self.auth {
+            VaultAuth::Token { token } => format!("{}#token#{}", self.address, token),
+            VaultAuth::Approle { role_id, mount, .. } => {
+                format!("{}#{}#{}", self.address, mount, role_id)
+            }
+        }
+    }
+
+    async fn request(
+        &self,
+        client: &HttpClient,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> crate::Result<Value> {
+        let uri = format!("{}/v1/{}", self.address.trim_end_matches('/'), path);
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("X-Vault-Token", token);
+        }
+        if let Some(namespace) = &self.namespace {
+            builder = builder.header("X-Vault-Namespace", namespace);
+        }
+        let request = builder.body(match body {
+            Some(body) => Body::from(serde_json::to_vec(&body)?),
+            None => Body::empty(),
+        })?;
+
+        let response = client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if !status.is_success() {
+            return Err(format!(
+                "Vault responded with {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            )
+            .into());
+        }
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Logs into Vault, or looks up the configured token to know when it has to be renewed.
+    async fn login(&self, client: &HttpClient) -> crate::Result<VaultToken> {
+        match &self.auth {
+            VaultAuth::Token { token } => {
+                let response = self
+                    .request(
+                        client,
+                        Method::GET,
+                        "auth/token/lookup-self",
+                        Some(token),
+                        None,
+                    )
+                    .await?;
+                let data = &response["data"];
+                Ok(VaultToken::new(
+                    token.clone(),
+                    data["renewable"].as_bool().unwrap_or(false),
+                    data["ttl"].as_u64().unwrap_or(0),
+                ))
+            }
+            VaultAuth::Approle {
+                role_id,
+                secret_id,
+                mount,
+            } => {
+                let response = self
+                    .request(
+                        client,
+                        Method::POST,
+                        &format!("auth/{}/login", mount),
+                        None,
+                        Some(json!({ "role_id": role_id, "secret_id": secret_id })),
+                    )
+                    .await?;
+                VaultToken::from_auth(&response)
+            }
+        }
+    }
+
+    /// Returns a valid token, renewing the previous one if it's about to expire, or logging in
+    /// again if it can't be renewed.
+    async fn token(&self, client: &HttpClient) -> crate::Result<String> {
+        let cache_key = self.cache_key();
+        let cached = TOKENS
+            .lock()
+            .expect("Vault token lock poisoned. Please report this.")
+            .get(&cache_key)
+            .cloned();
+
+        let now = Instant::now();
+        let token = match cached {
+            Some(token) if !token.needs_renewal(now) => return Ok(token.token),
+            Some(token) if token.renewable && !token.is_expired(now) => {
+                match self
+                    .request(
+                        client,
+                        Method::POST,
+                        "auth/token/renew-self",
+                        Some(&token.token),
+                        None,
+                    )
+                    .await
+                    .and_then(|response| VaultToken::from_auth(&response))
+                {
+                    Ok(token) => token,
+                    Err(error) => {
+                        warn!(message = "Failed renewing Vault token, logging in again.", %error);
+                        self.login(client).await?
+                    }
+                }
+            }
+            _ => self.login(client).await?,
+        };
+
+        TOKENS
+            .lock()
+            .expect("Vault token lock poisoned. Please report this.")
+            .insert(cache_key, token.clone());
+        Ok(token.token)
+    }
+}
+
+/// Reads the data of a secret, from either version of the KV secrets engine.
+fn secret_data(response: &Value) -> &Value {
+    let data = &response["data"];
+    // Version 2 nests the data along with its metadata.
+    match (&data["data"], &data["metadata"]) {
+        (Value::Object(_), Value::Object(_)) => &data["data"],
+        _ => data,
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretBackend for VaultBackend {
+    async fn retrieve(
+        &self,
+        secret_keys: HashSet<String>,
+    ) -> crate::Result<HashMap<String, String>> {
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings, &self.proxy)?;
+        let token = self.token(&client).await?;
+
+        let mut secrets = HashMap::new();
+        let mut paths = HashMap::new();
+        for key in secret_keys {
+            let (path, field) = split_field(&key);
+            let field =
+                field.ok_or_else(|| format!("secret \"{}\" must name the field to read", key))?;
+
+            if !paths.contains_key(path) {
+                let response = self
+                    .request(&client, Method::GET, path, Some(&token), None)
+                    .await?;
+                paths.insert(path.to_owned(), secret_data(&response).to_string());
+            }
+            let value = read_field(&paths[path], Some(field))
+                .map_err(|error| format!("secret \"{}\": {}", key, error))?;
+            secrets.insert(key, value);
+        }
+
+        Ok(secrets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_auth_strategies() {
+        let backend: VaultBackend = toml::from_str(
+            r#"
+            address = "https://vault.example.com:8200"
+            auth.strategy = "approle"
+            auth.role_id = "role"
+            auth.secret_id = "secret"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            backend.auth,
+            VaultAuth::Approle {
+                role_id: "role".to_owned(),
+                secret_id: "secret".to_owned(),
+                mount: "approle".to_owned(),
+            }
+        );
+
+        let backend: VaultBackend = toml::from_str(
+            r#"
+            address = "https://vault.example.com:8200"
+            auth.strategy = "token"
+            auth.token = "s.token"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            backend.auth,
+            VaultAuth::Token {
+                token: "s.token".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn reads_both_kv_versions() {
+        let v1 = json!({ "data": { "password": "hunter2" } });
+        assert_eq!(secret_data(&v1), &json!({ "password": "hunter2" }));
+
+        let v2 = json!({
+            "data": {
+                "data": { "password": "hunter2" },
+                "metadata": { "version": 3 },
+            }
+        });
+        assert_eq!(secret_data(&v2), &json!({ "password": "hunter2" }));
+    }
+
+    #[test]
+    fn renews_tokens_before_they_expire() {
+        let now = Instant::now();
+        let token = VaultToken::new("s.token".to_owned(), true, 3600);
+        assert!(!token.needs_renewal(now));
+        assert!(token.needs_renewal(now + Duration::from_secs(2401)));
+        assert!(token.is_expired(now + Duration::from_secs(3601)));
+
+        let token = VaultToken::new("s.root".to_owned(), false, 0);
+        assert!(!token.needs_renewal(now + Duration::from_secs(1_000_000)));
+    }
+}
@@ -14,7 +14,7 @@ use crate::{
             retry::CloudwatchRetryLogic, service::CloudwatchLogsPartitionSvc, sink::CloudwatchSink,
         },
         util::{
-            encoding::{EncodingConfig, StandardEncodings},
+            encoding::{EncodingConfig, EncodingConfiguration, StandardEncodings},
             BatchConfig, Compression, SinkBatchSettings, TowerRequestConfig,
         },
         Healthcheck, VectorSink,
@@ -69,6 +69,11 @@ impl CloudwatchLogsSinkConfig {
 #[typetag::serde(name = "aws_cloudwatch_logs")]
 impl SinkConfig for CloudwatchLogsSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.encoding.codec().is_binary() {
+            return Err(
+                "The `aws_cloudwatch_logs` sink sends text and can't use a binary encoding.".into(),
+            );
+        }
         let batcher_settings = self.batch.into_batcher_settings()?;
         let request = self.request.unwrap_with(&TowerRequestConfig::default());
         let client = self.create_client(cx.proxy())?;
@@ -165,7 +165,7 @@ impl CloudWatchMetricsSvc {
 
         let cloudwatch_metrics = CloudWatchMetricsSvc { client };
 
-        let svc = request.service(CloudWatchMetricsRetryLogic, cloudwatch_metrics);
+        let svc = request.partitioned_service(CloudWatchMetricsRetryLogic, cloudwatch_metrics);
 
         let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
         let mut normalizer = MetricNormalizer::<AwsCloudwatchMetricNormalize>::default();
@@ -17,7 +17,7 @@ use crate::{
             sink::S3Sink,
         },
         util::{
-            encoding::{EncodingConfig, StandardEncodings},
+            encoding::{BatchEncoder, EncodingConfig, ParquetConfig, StandardEncodings},
             partitioner::KeyPartitioner,
             BatchConfig, BulkSizeBasedDefaultBatchSettings, Compression, ServiceBuilderExt,
             TowerRequestConfig,
@@ -44,6 +44,12 @@ pub struct S3SinkConfig {
     #[serde(flatten)]
     pub region: RegionOrEndpoint,
     pub encoding: EncodingConfig<StandardEncodings>,
+    /// Writes the objects as Parquet files, compressing their columns rather than the objects.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub parquet: Option<ParquetConfig>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(default)]
@@ -74,6 +80,7 @@ impl GenerateConfig for S3SinkConfig {
             options: S3Options::default(),
             region: RegionOrEndpoint::default(),
             encoding: StandardEncodings::Text.into(),
+            parquet: None,
             compression: Compression::gzip_default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
@@ -144,13 +151,19 @@ impl S3SinkConfig {
             .filename_append_uuid
             .unwrap_or(DEFAULT_FILENAME_APPEND_UUID);
 
+        let encoding = BatchEncoder::new(&self.encoding, self.parquet.as_ref());
+        let mut api_options = self.options.clone();
+        if encoding.is_parquet() && api_options.content_type.is_none() {
+            api_options.content_type = Some(encoding.content_type().to_owned());
+        }
+
         let request_options = S3RequestOptions {
             bucket: self.bucket.clone(),
-            api_options: self.options.clone(),
+            api_options,
             filename_extension: self.filename_extension.clone(),
             filename_time_format,
             filename_append_uuid,
-            encoding: self.encoding.clone(),
+            encoding,
             compression: self.compression,
         };
 
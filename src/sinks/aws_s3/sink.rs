@@ -12,10 +12,7 @@ use crate::{
             config::S3Options,
             service::{S3Metadata, S3Request},
         },
-        util::{
-            encoding::{EncodingConfig, StandardEncodings},
-            Compression, RequestBuilder,
-        },
+        util::{encoding::BatchEncoder, Compression, RequestBuilder},
     },
 };
 
@@ -26,20 +23,25 @@ pub struct S3RequestOptions {
     pub filename_append_uuid: bool,
     pub filename_extension: Option<String>,
     pub api_options: S3Options,
-    pub encoding: EncodingConfig<StandardEncodings>,
+    pub encoding: BatchEncoder,
     pub compression: Compression,
 }
 
 impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
     type Metadata = S3Metadata;
     type Events = Vec<Event>;
-    type Encoder = EncodingConfig<StandardEncodings>;
+    type Encoder = BatchEncoder;
     type Payload = Bytes;
     type Request = S3Request;
     type Error = io::Error; // TODO: this is ugly.
 
     fn compression(&self) -> Compression {
-        self.compression
+        // Parquet files compress their columns themselves.
+        if self.encoding.is_parquet() {
+            Compression::None
+        } else {
+            self.compression
+        }
     }
 
     fn encoder(&self) -> &Self::Encoder {
@@ -72,7 +74,13 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
             .filename_extension
             .as_ref()
             .cloned()
-            .unwrap_or_else(|| self.compression.extension().into());
+            .unwrap_or_else(|| {
+                if self.encoding.is_parquet() {
+                    "parquet".into()
+                } else {
+                    self.compression.extension().into()
+                }
+            });
         metadata.partition_key = format!("{}{}.{}", metadata.partition_key, filename, extension);
 
         // TODO: move this into `.request_builder(...)` closure?
@@ -88,7 +96,7 @@ impl RequestBuilder<(String, Vec<Event>)> for S3RequestOptions {
             body: payload,
             bucket: self.bucket.clone(),
             metadata,
-            content_encoding: self.compression.content_encoding(),
+            content_encoding: self.compression().content_encoding(),
             options: self.api_options.clone(),
         }
     }
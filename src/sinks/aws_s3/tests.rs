@@ -325,6 +325,7 @@ mod integration_tests {
             options: S3Options::default(),
             region: RegionOrEndpoint::with_endpoint(s3_address()),
             encoding: StandardEncodings::Text.into(),
+            parquet: None,
             compression: Compression::None,
             batch,
             request: TowerRequestConfig::default(),
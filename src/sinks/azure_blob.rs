@@ -19,7 +19,10 @@ use crate::{
             sink::AzureBlobSink,
         },
         util::{
-            encoding::{EncodingConfig, StandardEncodings},
+            encoding::{
+                BatchEncoder, EncodingConfig, ParquetConfig, StandardEncodings,
+                PARQUET_CONTENT_TYPE,
+            },
             partitioner::KeyPartitioner,
             BatchConfig, BulkSizeBasedDefaultBatchSettings, Compression, RequestBuilder,
             ServiceBuilderExt, TowerRequestConfig,
@@ -38,6 +41,12 @@ pub struct AzureBlobSinkConfig {
     pub blob_time_format: Option<String>,
     pub blob_append_uuid: Option<bool>,
     pub encoding: EncodingConfig<StandardEncodings>,
+    /// Writes the blobs as Parquet files, compressing their columns rather than the blobs.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub parquet: Option<ParquetConfig>,
     #[serde(default = "Compression::gzip_default")]
     pub compression: Compression,
     #[serde(default)]
@@ -61,6 +70,7 @@ impl GenerateConfig for AzureBlobSinkConfig {
             blob_time_format: Some(String::from("%s")),
             blob_append_uuid: Some(true),
             encoding: StandardEncodings::Ndjson.into(),
+            parquet: None,
             compression: Compression::gzip_default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
@@ -133,7 +143,7 @@ impl AzureBlobSinkConfig {
             container_name: self.container_name.clone(),
             blob_time_format,
             blob_append_uuid,
-            encoding: self.encoding.clone(),
+            encoding: BatchEncoder::new(&self.encoding, self.parquet.as_ref()),
             compression: self.compression,
         };
 
@@ -164,20 +174,25 @@ pub struct AzureBlobRequestOptions {
     pub container_name: String,
     pub blob_time_format: String,
     pub blob_append_uuid: bool,
-    pub encoding: EncodingConfig<StandardEncodings>,
+    pub encoding: BatchEncoder,
     pub compression: Compression,
 }
 
 impl RequestBuilder<(String, Vec<Event>)> for AzureBlobRequestOptions {
     type Metadata = AzureBlobMetadata;
     type Events = Vec<Event>;
-    type Encoder = EncodingConfig<StandardEncodings>;
+    type Encoder = BatchEncoder;
     type Payload = Bytes;
     type Request = AzureBlobRequest;
     type Error = io::Error;
 
     fn compression(&self) -> Compression {
-        self.compression
+        // Parquet files compress their columns themselves.
+        if self.encoding.is_parquet() {
+            Compression::None
+        } else {
+            self.compression
+        }
     }
 
     fn encoder(&self) -> &Self::Encoder {
@@ -206,7 +221,11 @@ impl RequestBuilder<(String, Vec<Event>)> for AzureBlobRequestOptions {
                 .unwrap_or_else(|| formatted_ts.to_string())
         };
 
-        let extension = self.compression.extension();
+        let extension = if self.encoding.is_parquet() {
+            "parquet"
+        } else {
+            self.compression.extension()
+        };
         metadata.partition_key = format!("{}{}.{}", metadata.partition_key, blob_name, extension);
 
         debug!(
@@ -219,8 +238,12 @@ impl RequestBuilder<(String, Vec<Event>)> for AzureBlobRequestOptions {
 
         AzureBlobRequest {
             blob_data: payload,
-            content_encoding: self.compression.content_encoding(),
-            content_type: self.compression.content_type(),
+            content_encoding: self.compression().content_encoding(),
+            content_type: if self.encoding.is_parquet() {
+                PARQUET_CONTENT_TYPE
+            } else {
+                self.compression.content_type()
+            },
             metadata,
         }
     }
@@ -244,6 +267,7 @@ fn default_config(e: StandardEncodings) -> AzureBlobSinkConfig {
         blob_time_format: Default::default(),
         blob_append_uuid: Default::default(),
         encoding: e.into(),
+        parquet: None,
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
@@ -404,6 +428,38 @@ mod tests {
         assert_eq!(request.content_encoding, None);
         assert_eq!(request.content_type, "text/plain");
     }
+
+    #[test]
+    fn azure_blob_build_parquet_request() {
+        let log = Event::from("test message");
+        let sink_config = AzureBlobSinkConfig {
+            blob_prefix: Some("blob".into()),
+            parquet: Some(ParquetConfig::default()),
+            ..default_config(StandardEncodings::Text)
+        };
+
+        let key = sink_config
+            .key_partitioner()
+            .unwrap()
+            .partition(&log)
+            .expect("key wasn't provided");
+
+        let request_options = AzureBlobRequestOptions {
+            container_name: String::from("logs"),
+            blob_time_format: String::from(""),
+            blob_append_uuid: false,
+            encoding: BatchEncoder::new(&sink_config.encoding, sink_config.parquet.as_ref()),
+            compression: sink_config.compression,
+        };
+        assert_eq!(request_options.compression(), Compression::None);
+
+        let (metadata, _events) = request_options.split_input((key, vec![log]));
+        let request = request_options.build_request(metadata, Bytes::new());
+
+        assert_eq!(request.metadata.partition_key, "blob.parquet".to_string());
+        assert_eq!(request.content_encoding, None);
+        assert_eq!(request.content_type, PARQUET_CONTENT_TYPE);
+    }
 }
 
 #[cfg(feature = "azure-blob-integration-tests")]
@@ -616,6 +672,7 @@ mod integration_tests {
                 blob_time_format: None,
                 blob_append_uuid: None,
                 encoding: StandardEncodings::Text.into(),
+                parquet: None,
                 compression: Compression::None,
                 batch: Default::default(),
                 request: TowerRequestConfig::default(),
@@ -0,0 +1,157 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use http::Request;
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+
+use super::{service::send_json, AzureDataExplorerError, BuildRequestSnafu};
+use crate::http::HttpClient;
+
+const IMDS_TOKEN_URL: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+/// Tokens are renewed this long before they expire.
+const RENEWAL_MARGIN: Duration = Duration::from_secs(300);
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum AzureDataExplorerAuth {
+    /// The managed identity of the Azure resource Vector runs on, or the user-assigned identity
+    /// with the given client ID.
+    ManagedIdentity { client_id: Option<String> },
+    /// An application registered in Azure Active Directory, authenticating with a client secret.
+    ClientSecret {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+        #[serde(default = "default_authority_host")]
+        authority_host: String,
+    },
+}
+
+impl Default for AzureDataExplorerAuth {
+    fn default() -> Self {
+        Self::ManagedIdentity { client_id: None }
+    }
+}
+
+fn default_authority_host() -> String {
+    "https://login.microsoftonline.com".into()
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Seconds,
+}
+
+/// The instance metadata service returns the lifetime of the tokens as a string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Seconds {
+    Number(u64),
+    String(String),
+}
+
+impl Seconds {
+    fn as_duration(&self) -> Duration {
+        Duration::from_secs(match self {
+            Self::Number(seconds) => *seconds,
+            Self::String(seconds) => seconds.parse().unwrap_or_default(),
+        })
+    }
+}
+
+struct Token {
+    access_token: String,
+    renew_at: Instant,
+}
+
+/// Fetches and caches the Azure Active Directory tokens used to access the cluster.
+#[derive(Clone)]
+pub(super) struct TokenProvider {
+    auth: AzureDataExplorerAuth,
+    resource: String,
+    client: HttpClient,
+    token: Arc<Mutex<Option<Token>>>,
+}
+
+impl TokenProvider {
+    pub(super) fn new(auth: AzureDataExplorerAuth, resource: String, client: HttpClient) -> Self {
+        Self {
+            auth,
+            resource,
+            client,
+            token: Arc::default(),
+        }
+    }
+
+    /// The value of the `Authorization` header, renewing the token if it's about to expire.
+    pub(super) async fn authorization(&self) -> Result<String, AzureDataExplorerError> {
+        // Holding the lock while renewing keeps concurrent requests from all renewing the token.
+        let mut token = self.token.lock().await;
+        match token.as_ref() {
+            Some(token) if token.renew_at > Instant::now() => {}
+            _ => *token = Some(self.fetch().await?),
+        }
+        Ok(format!(
+            "Bearer {}",
+            token.as_ref().expect("just fetched").access_token
+        ))
+    }
+
+    async fn fetch(&self) -> Result<Token, AzureDataExplorerError> {
+        let (endpoint, request) = match &self.auth {
+            AzureDataExplorerAuth::ManagedIdentity { client_id } => {
+                let mut query = url::form_urlencoded::Serializer::new(String::new());
+                query.append_pair("api-version", "2018-02-01");
+                query.append_pair("resource", &self.resource);
+                if let Some(client_id) = client_id {
+                    query.append_pair("client_id", client_id);
+                }
+                let request = Request::get(format!("{}?{}", IMDS_TOKEN_URL, query.finish()))
+                    .header("Metadata", "true")
+                    .body(Body::empty());
+                (IMDS_TOKEN_URL.to_owned(), request)
+            }
+            AzureDataExplorerAuth::ClientSecret {
+                tenant_id,
+                client_id,
+                client_secret,
+                authority_host,
+            } => {
+                let endpoint = format!(
+                    "{}/{}/oauth2/v2.0/token",
+                    authority_host.trim_end_matches('/'),
+                    tenant_id
+                );
+                let body = url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair("grant_type", "client_credentials")
+                    .append_pair("client_id", client_id)
+                    .append_pair("client_secret", client_secret)
+                    .append_pair("scope", &format!("{}/.default", self.resource))
+                    .finish();
+                let request = Request::post(&endpoint)
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body(Body::from(body));
+                (endpoint, request)
+            }
+        };
+
+        debug!(message = "Fetching Azure Active Directory token.", %endpoint);
+        let response: TokenResponse =
+            send_json(&self.client, request.context(BuildRequestSnafu)?, &endpoint).await?;
+        Ok(Token {
+            access_token: response.access_token,
+            renew_at: Instant::now()
+                + response
+                    .expires_in
+                    .as_duration()
+                    .saturating_sub(RENEWAL_MARGIN),
+        })
+    }
+}
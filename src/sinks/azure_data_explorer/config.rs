@@ -0,0 +1,148 @@
+use std::num::NonZeroU64;
+
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    auth::{AzureDataExplorerAuth, TokenProvider},
+    resources::ResourceManager,
+    service::{AzureDataExplorerRetryLogic, AzureDataExplorerService, IngestionSettings},
+    sink::AzureDataExplorerSink,
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    http::HttpClient,
+    sinks::{
+        util::{
+            encoding::EncodingConfigWithDefault, BatchConfig, ServiceBuilderExt, SinkBatchSettings,
+            TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+
+/// Queued ingestion favors fewer, larger blobs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AzureDataExplorerDefaultBatchSettings;
+
+impl SinkBatchSettings for AzureDataExplorerDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = None;
+    const MAX_BYTES: Option<usize> = Some(100_000_000);
+    const TIMEOUT_SECS: NonZeroU64 = unsafe { NonZeroU64::new_unchecked(30) };
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AzureDataExplorerConfig {
+    /// The data ingestion URI of the cluster, such as
+    /// `https://ingest-<cluster>.<region>.kusto.windows.net`.
+    pub ingestion_endpoint: String,
+    pub database: String,
+    pub table: Template,
+    /// The name of an ingestion mapping of the table, mapping the fields of the events to its
+    /// columns.
+    pub mapping_reference: Option<String>,
+    /// Asks the cluster to ingest the blobs without aggregating them with others, at the expense
+    /// of the efficiency of the ingestion.
+    #[serde(default)]
+    pub flush_immediately: bool,
+    #[serde(default)]
+    pub auth: AzureDataExplorerAuth,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+    #[serde(default)]
+    pub batch: BatchConfig<AzureDataExplorerDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsOptions>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    #[derivative(Default)]
+    Default,
+}
+
+inventory::submit! {
+    SinkDescription::new::<AzureDataExplorerConfig>("azure_data_explorer")
+}
+
+impl GenerateConfig for AzureDataExplorerConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"ingestion_endpoint = "https://ingest-mycluster.westeurope.kusto.windows.net"
+            database = "mydatabase"
+            table = "logs""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_data_explorer")]
+impl SinkConfig for AzureDataExplorerConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
+
+        let endpoint = self.ingestion_endpoint.trim_end_matches('/').to_owned();
+        let tokens = TokenProvider::new(self.auth.clone(), endpoint.clone(), client.clone());
+        let resources = ResourceManager::new(endpoint, client.clone(), tokens);
+        let service = AzureDataExplorerService::new(
+            client,
+            resources,
+            IngestionSettings {
+                database: self.database.clone(),
+                mapping_reference: self.mapping_reference.clone(),
+                flush_immediately: self.flush_immediately,
+            },
+        );
+
+        let healthcheck = service.clone().healthcheck();
+
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, AzureDataExplorerRetryLogic)
+            .service(service);
+
+        let sink = AzureDataExplorerSink {
+            table: self.table.clone(),
+            encoding: self.encoding.clone(),
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+
+        Ok((
+            VectorSink::from_event_streamsink(sink),
+            Box::pin(healthcheck),
+        ))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "azure_data_explorer"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
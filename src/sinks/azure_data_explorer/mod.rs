@@ -0,0 +1,55 @@
+//! A sink ingesting events into Azure Data Explorer (Kusto) through queued ingestion.
+//!
+//! Each batch is uploaded as a compressed blob to one of the temporary storage containers of the
+//! cluster, and an ingestion message referencing it is then posted to one of its ingestion
+//! queues, from which the cluster ingests the blob into the table on its own schedule.
+
+use http::StatusCode;
+use snafu::Snafu;
+
+mod auth;
+mod config;
+mod resources;
+mod service;
+mod sink;
+
+pub use config::AzureDataExplorerConfig;
+
+#[derive(Debug, Snafu)]
+pub enum AzureDataExplorerError {
+    #[snafu(display("HTTP error: {}", source))]
+    Http { source: crate::http::HttpError },
+    #[snafu(display("Failed to read the response: {}", source))]
+    ReadResponse { source: hyper::Error },
+    #[snafu(display("Unexpected status {} from {}: {}", status, endpoint, body))]
+    UnexpectedStatus {
+        status: StatusCode,
+        endpoint: String,
+        body: String,
+    },
+    #[snafu(display("Invalid response from {}: {}", endpoint, source))]
+    InvalidResponse {
+        endpoint: String,
+        source: serde_json::Error,
+    },
+    #[snafu(display("The cluster returned no {} resources.", resource))]
+    MissingResource { resource: &'static str },
+    #[snafu(display("Failed to build the request: {}", source))]
+    BuildRequest { source: http::Error },
+}
+
+impl AzureDataExplorerError {
+    /// Whether the error is due to the configuration or the data, and would happen again.
+    fn is_permanent(&self) -> bool {
+        match self {
+            Self::UnexpectedStatus { status, .. } => {
+                status.is_client_error() && status.as_u16() != 429
+            }
+            Self::BuildRequest { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
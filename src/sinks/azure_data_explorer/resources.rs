@@ -0,0 +1,233 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use http::Request;
+use hyper::Body;
+use serde::Deserialize;
+use serde_json::json;
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+
+use super::{auth::TokenProvider, service::send_json, AzureDataExplorerError, BuildRequestSnafu};
+use crate::http::HttpClient;
+
+/// How long the ingestion resources are used before being looked up again, as the cluster rotates
+/// the SAS tokens of its storage accounts.
+const RESOURCES_TTL: Duration = Duration::from_secs(3600);
+
+/// A storage container or queue, along with the SAS token granting access to it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct StorageUri {
+    pub(super) base: String,
+    pub(super) sas: String,
+}
+
+impl StorageUri {
+    fn parse(uri: &str) -> Self {
+        let (base, sas) = uri.split_once('?').unwrap_or((uri, ""));
+        Self {
+            base: base.trim_end_matches('/').to_owned(),
+            sas: sas.to_owned(),
+        }
+    }
+
+    /// The URI of a path under the container or queue.
+    pub(super) fn join(&self, path: &str) -> String {
+        format!("{}/{}?{}", self.base, path, self.sas)
+    }
+}
+
+/// The storage used for queued ingestion, which is spread over all of the containers and queues.
+#[derive(Debug)]
+pub(super) struct IngestionResources {
+    pub(super) containers: Vec<StorageUri>,
+    pub(super) queues: Vec<StorageUri>,
+    /// Passed along with the ingestion messages to identify the identity ingesting the data.
+    pub(super) authorization_context: String,
+    next: AtomicUsize,
+}
+
+impl IngestionResources {
+    pub(super) fn next(&self) -> (&StorageUri, &StorageUri) {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        (
+            &self.containers[next % self.containers.len()],
+            &self.queues[next % self.queues.len()],
+        )
+    }
+}
+
+/// The v1 response of management commands.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CommandResponse {
+    tables: Vec<ResultTable>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ResultTable {
+    columns: Vec<ResultColumn>,
+    rows: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ResultColumn {
+    column_name: String,
+}
+
+impl ResultTable {
+    /// The string values of the given column.
+    fn column<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let index = self
+            .columns
+            .iter()
+            .position(|column| column.column_name == name);
+        self.rows.iter().filter_map(move |row| {
+            index
+                .and_then(|index| row.get(index))
+                .and_then(serde_json::Value::as_str)
+        })
+    }
+}
+
+/// Looks up the ingestion resources of the cluster, caching them for `RESOURCES_TTL`.
+#[derive(Clone)]
+pub(super) struct ResourceManager {
+    endpoint: String,
+    client: HttpClient,
+    tokens: TokenProvider,
+    resources: Arc<Mutex<Option<(Arc<IngestionResources>, Instant)>>>,
+}
+
+impl ResourceManager {
+    pub(super) fn new(endpoint: String, client: HttpClient, tokens: TokenProvider) -> Self {
+        Self {
+            endpoint,
+            client,
+            tokens,
+            resources: Arc::default(),
+        }
+    }
+
+    pub(super) async fn get(&self) -> Result<Arc<IngestionResources>, AzureDataExplorerError> {
+        let mut resources = self.resources.lock().await;
+        if let Some((resources, fetched_at)) = resources.as_ref() {
+            if fetched_at.elapsed() < RESOURCES_TTL {
+                return Ok(Arc::clone(resources));
+            }
+        }
+
+        let fetched = Arc::new(self.fetch().await?);
+        debug!(
+            message = "Looked up ingestion resources.",
+            containers = fetched.containers.len(),
+            queues = fetched.queues.len(),
+        );
+        *resources = Some((Arc::clone(&fetched), Instant::now()));
+        Ok(fetched)
+    }
+
+    async fn fetch(&self) -> Result<IngestionResources, AzureDataExplorerError> {
+        let resources = self.command(".get ingestion resources").await?;
+        let storage = |resource| {
+            resources
+                .column("ResourceTypeName")
+                .zip(resources.column("StorageRoot"))
+                .filter(|(name, _)| *name == resource)
+                .map(|(_, uri)| StorageUri::parse(uri))
+                .collect::<Vec<_>>()
+        };
+        let containers = storage("TempStorage");
+        if containers.is_empty() {
+            return Err(AzureDataExplorerError::MissingResource {
+                resource: "TempStorage",
+            });
+        }
+        let queues = storage("SecuredReadyForAggregationQueue");
+        if queues.is_empty() {
+            return Err(AzureDataExplorerError::MissingResource {
+                resource: "SecuredReadyForAggregationQueue",
+            });
+        }
+
+        let identity = self.command(".get kusto identity token").await?;
+        let authorization_context = identity
+            .column("AuthorizationContext")
+            .next()
+            .ok_or(AzureDataExplorerError::MissingResource {
+                resource: "AuthorizationContext",
+            })?
+            .to_owned();
+
+        Ok(IngestionResources {
+            containers,
+            queues,
+            authorization_context,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    async fn command(&self, command: &str) -> Result<ResultTable, AzureDataExplorerError> {
+        let endpoint = format!("{}/v1/rest/mgmt", self.endpoint);
+        let body = json!({ "db": "NetDefaultDB", "csl": command });
+        let request = Request::post(&endpoint)
+            .header("Authorization", self.tokens.authorization().await?)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .body(Body::from(body.to_string()))
+            .context(BuildRequestSnafu)?;
+
+        let response: CommandResponse = send_json(&self.client, request, &endpoint).await?;
+        response
+            .tables
+            .into_iter()
+            .next()
+            .ok_or(AzureDataExplorerError::MissingResource { resource: "table" })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_storage_uris() {
+        let uri = StorageUri::parse("https://account.blob.core.windows.net/container?sv=1&sig=2");
+        assert_eq!(uri.base, "https://account.blob.core.windows.net/container");
+        assert_eq!(
+            uri.join("blob.gz"),
+            "https://account.blob.core.windows.net/container/blob.gz?sv=1&sig=2"
+        );
+    }
+
+    #[test]
+    fn reads_result_columns() {
+        let response: CommandResponse = serde_json::from_value(json!({
+            "Tables": [{
+                "TableName": "Table_0",
+                "Columns": [
+                    { "ColumnName": "ResourceTypeName", "DataType": "String" },
+                    { "ColumnName": "StorageRoot", "DataType": "String" },
+                ],
+                "Rows": [
+                    ["TempStorage", "https://a.blob.core.windows.net/c?sas"],
+                    ["SecuredReadyForAggregationQueue", "https://a.queue.core.windows.net/q?sas"],
+                ],
+            }],
+        }))
+        .unwrap();
+        let table = &response.tables[0];
+        assert_eq!(
+            table.column("ResourceTypeName").collect::<Vec<_>>(),
+            vec!["TempStorage", "SecuredReadyForAggregationQueue"]
+        );
+        assert_eq!(table.column("Missing").count(), 0);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::Request;
+use hyper::Body;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use snafu::ResultExt;
+use uuid::Uuid;
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{
+    resources::ResourceManager, AzureDataExplorerError, BuildRequestSnafu, HttpSnafu,
+    InvalidResponseSnafu, ReadResponseSnafu,
+};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    http::HttpClient,
+    internal_events::EndpointBytesSent,
+    sinks::util::retries::RetryLogic,
+};
+
+/// Sends the request, failing unless the response has a successful status.
+pub(super) async fn send(
+    client: &HttpClient,
+    request: Request<Body>,
+    endpoint: &str,
+) -> Result<Bytes, AzureDataExplorerError> {
+    let response = client.send(request).await.context(HttpSnafu)?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .context(ReadResponseSnafu)?;
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(AzureDataExplorerError::UnexpectedStatus {
+            status,
+            endpoint: endpoint.to_owned(),
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}
+
+pub(super) async fn send_json<T: DeserializeOwned>(
+    client: &HttpClient,
+    request: Request<Body>,
+    endpoint: &str,
+) -> Result<T, AzureDataExplorerError> {
+    let body = send(client, request, endpoint).await?;
+    serde_json::from_slice(&body).context(InvalidResponseSnafu { endpoint })
+}
+
+/// The properties of the ingestion, passed along with each blob.
+#[derive(Debug)]
+pub(super) struct IngestionSettings {
+    pub(super) database: String,
+    pub(super) mapping_reference: Option<String>,
+    pub(super) flush_immediately: bool,
+}
+
+#[derive(Clone)]
+pub(super) struct AzureDataExplorerService {
+    client: HttpClient,
+    resources: ResourceManager,
+    settings: Arc<IngestionSettings>,
+}
+
+impl AzureDataExplorerService {
+    pub(super) fn new(
+        client: HttpClient,
+        resources: ResourceManager,
+        settings: IngestionSettings,
+    ) -> Self {
+        Self {
+            client,
+            resources,
+            settings: Arc::new(settings),
+        }
+    }
+
+    async fn ingest(
+        &self,
+        request: &AzureDataExplorerRequest,
+    ) -> Result<(), AzureDataExplorerError> {
+        let resources = self.resources.get().await?;
+        let (container, queue) = resources.next();
+        let id = Uuid::new_v4();
+
+        // The compression of the blob is deduced from its extension.
+        let blob = container.join(&format!(
+            "{}__{}__{}.multijson.gz",
+            self.settings.database, request.table, id
+        ));
+        let upload = Request::put(&blob)
+            .header("x-ms-blob-type", "BlockBlob")
+            .header("x-ms-version", "2019-12-12")
+            .header("Content-Length", request.payload.len())
+            .body(Body::from(request.payload.clone()))
+            .context(BuildRequestSnafu)?;
+        send(&self.client, upload, &container.base).await?;
+        emit!(&EndpointBytesSent {
+            byte_size: request.payload.len(),
+            protocol: "https",
+            endpoint: &container.base,
+        });
+
+        let mut properties = serde_json::Map::new();
+        properties.insert(
+            "authorizationContext".into(),
+            json!(resources.authorization_context),
+        );
+        properties.insert("format".into(), json!("multijson"));
+        if let Some(mapping_reference) = &self.settings.mapping_reference {
+            properties.insert("ingestionMappingReference".into(), json!(mapping_reference));
+            properties.insert("ingestionMappingType".into(), json!("Json"));
+        }
+        let message = json!({
+            "Id": id,
+            "BlobPath": blob,
+            "RawDataSize": request.raw_size,
+            "DatabaseName": self.settings.database,
+            "TableName": request.table,
+            "RetainBlobOnSuccess": false,
+            "FlushImmediately": self.settings.flush_immediately,
+            "ReportLevel": 0,
+            "ReportMethod": 0,
+            "AdditionalProperties": properties,
+        });
+
+        let enqueue = Request::post(queue.join("messages"))
+            .header("Content-Type", "application/xml")
+            .body(Body::from(format!(
+                "<QueueMessage><MessageText>{}</MessageText></QueueMessage>",
+                base64::encode(message.to_string())
+            )))
+            .context(BuildRequestSnafu)?;
+        send(&self.client, enqueue, &queue.base).await?;
+        Ok(())
+    }
+
+    pub(super) async fn healthcheck(self) -> crate::Result<()> {
+        self.resources.get().await?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(super) struct AzureDataExplorerRequest {
+    pub(super) table: String,
+    /// The events as gzipped JSON lines.
+    pub(super) payload: Bytes,
+    /// The size of the payload before compression, which the cluster uses to plan the ingestion.
+    pub(super) raw_size: usize,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) events_count: usize,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for AzureDataExplorerRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for AzureDataExplorerRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub(super) struct AzureDataExplorerResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for AzureDataExplorerResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+impl tower::Service<AzureDataExplorerRequest> for AzureDataExplorerService {
+    type Response = AzureDataExplorerResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: AzureDataExplorerRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            service.ingest(&request).await?;
+            Ok(AzureDataExplorerResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct AzureDataExplorerRetryLogic;
+
+impl RetryLogic for AzureDataExplorerRetryLogic {
+    type Error = AzureDataExplorerError;
+    type Response = AzureDataExplorerResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        !error.is_permanent()
+    }
+}
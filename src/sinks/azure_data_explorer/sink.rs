@@ -0,0 +1,104 @@
+use std::io::Write;
+
+use async_trait::async_trait;
+use flate2::{write::GzEncoder, Compression};
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::util::BoxService;
+use vector_core::{buffers::Acker, partition::Partitioner, stream::BatcherSettings, ByteSizeOf};
+
+use super::{
+    config::Encoding,
+    service::{AzureDataExplorerRequest, AzureDataExplorerResponse},
+};
+use crate::{
+    event::{Event, EventFinalizers, Finalizable},
+    internal_events::TemplateRenderingError,
+    sinks::util::{
+        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        SinkBuilderExt, StreamSink,
+    },
+    template::Template,
+};
+
+/// Batches the events by the table they are ingested into.
+struct TablePartitioner(Template);
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.0
+            .render_string(item)
+            .map_err(|error| {
+                emit!(&TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
+
+pub(super) struct AzureDataExplorerSink {
+    pub(super) table: Template,
+    pub(super) encoding: EncodingConfigWithDefault<Encoding>,
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) service:
+        BoxService<AzureDataExplorerRequest, AzureDataExplorerResponse, crate::Error>,
+    pub(super) acker: Acker,
+}
+
+impl AzureDataExplorerSink {
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoding = self.encoding;
+        input
+            .batched_partitioned(TablePartitioner(self.table), self.batch_settings)
+            .filter_map(|(table, events)| future::ready(table.map(|table| (table, events))))
+            .map(|(table, events)| build_request(&encoding, table, events))
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+/// Encodes the events as JSON lines, compressed with gzip.
+fn build_request(
+    encoding: &EncodingConfigWithDefault<Encoding>,
+    table: String,
+    events: Vec<Event>,
+) -> AzureDataExplorerRequest {
+    let mut request = AzureDataExplorerRequest {
+        table,
+        events_count: events.len(),
+        ..Default::default()
+    };
+
+    let mut lines = Vec::new();
+    for mut event in events {
+        request.events_byte_size += event.size_of();
+        request.finalizers.merge(event.take_finalizers());
+        encoding.apply_rules(&mut event);
+        serde_json::to_writer(&mut lines, event.as_log()).expect("logs serialize to JSON");
+        lines.push(b'\n');
+    }
+    request.raw_size = lines.len();
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&lines)
+        .expect("writing to a Vec can't fail");
+    request.payload = encoder
+        .finish()
+        .expect("writing to a Vec can't fail")
+        .into();
+    request
+}
+
+#[async_trait]
+impl StreamSink<Event> for AzureDataExplorerSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,256 @@
+use std::{convert::Infallible, io::Read, net::SocketAddr};
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use futures::{channel::mpsc, StreamExt};
+use http::{Method, Request, Response, StatusCode};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Server,
+};
+use serde_json::{json, Value};
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::{service::AzureDataExplorerRetryLogic, *};
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, LogEvent},
+    sinks::util::retries::RetryLogic,
+    test_util::next_addr,
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<AzureDataExplorerConfig>();
+}
+
+#[test]
+fn retriable_errors() {
+    let status = |status| AzureDataExplorerError::UnexpectedStatus {
+        status,
+        endpoint: "https://ingest-cluster.kusto.windows.net".into(),
+        body: String::new(),
+    };
+    let logic = AzureDataExplorerRetryLogic;
+
+    assert!(logic.is_retriable_error(&status(StatusCode::SERVICE_UNAVAILABLE)));
+    assert!(logic.is_retriable_error(&status(StatusCode::TOO_MANY_REQUESTS)));
+    assert!(!logic.is_retriable_error(&status(StatusCode::FORBIDDEN)));
+    assert!(
+        logic.is_retriable_error(&AzureDataExplorerError::MissingResource {
+            resource: "TempStorage"
+        })
+    );
+}
+
+/// A request received by the mock cluster, other than those for tokens and resources.
+#[derive(Debug)]
+struct Received {
+    method: Method,
+    uri: String,
+    body: Bytes,
+}
+
+/// Serves the token, management, blob and queue endpoints of a cluster, answering the uploads
+/// with the given status.
+fn mock_cluster(upload_status: StatusCode) -> (SocketAddr, mpsc::UnboundedReceiver<Received>) {
+    let addr = next_addr();
+    let (tx, rx) = mpsc::unbounded();
+    let service = make_service_fn(move |_| {
+        let tx = tx.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let tx = tx.clone();
+                async move {
+                    let (parts, body) = request.into_parts();
+                    let body = hyper::body::to_bytes(body).await.unwrap();
+                    let response = match parts.uri.path() {
+                        "/tenant/oauth2/v2.0/token" => {
+                            let form = String::from_utf8_lossy(&body);
+                            assert!(form.contains("client_secret=secret"));
+                            json_response(json!({
+                                "token_type": "Bearer",
+                                "expires_in": 3599,
+                                "access_token": "token",
+                            }))
+                        }
+                        "/v1/rest/mgmt" => {
+                            assert_eq!(parts.headers["Authorization"], "Bearer token");
+                            let command: Value = serde_json::from_slice(&body).unwrap();
+                            json_response(management_response(addr, &command["csl"]))
+                        }
+                        _ => {
+                            tx.unbounded_send(Received {
+                                method: parts.method,
+                                uri: parts.uri.to_string(),
+                                body,
+                            })
+                            .unwrap();
+                            Response::builder()
+                                .status(upload_status)
+                                .body(Body::empty())
+                                .unwrap()
+                        }
+                    };
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+    tokio::spawn(Server::bind(&addr).serve(service));
+    (addr, rx)
+}
+
+fn json_response(body: Value) -> Response<Body> {
+    Response::new(Body::from(body.to_string()))
+}
+
+fn management_response(addr: SocketAddr, command: &Value) -> Value {
+    let (columns, rows) = match command.as_str().unwrap() {
+        ".get ingestion resources" => (
+            vec!["ResourceTypeName", "StorageRoot"],
+            json!([
+                ["TempStorage", format!("http://{}/container?sig=blob", addr)],
+                [
+                    "SecuredReadyForAggregationQueue",
+                    format!("http://{}/queue?sig=queue", addr)
+                ],
+                [
+                    "FailedIngestionsQueue",
+                    format!("http://{}/failed?sig=failed", addr)
+                ],
+            ]),
+        ),
+        ".get kusto identity token" => (vec!["AuthorizationContext"], json!([["context"]])),
+        command => panic!("Unexpected command {}", command),
+    };
+    let columns = columns
+        .into_iter()
+        .map(|name| json!({ "ColumnName": name, "DataType": "String" }))
+        .collect::<Vec<_>>();
+    json!({
+        "Tables": [{ "TableName": "Table_0", "Columns": columns, "Rows": rows }],
+    })
+}
+
+fn config(addr: SocketAddr) -> AzureDataExplorerConfig {
+    toml::from_str(&format!(
+        r#"
+            ingestion_endpoint = "http://{}"
+            database = "db"
+            table = "{{{{ table }}}}"
+            mapping_reference = "vector_mapping"
+            auth.strategy = "client_secret"
+            auth.tenant_id = "tenant"
+            auth.client_id = "client"
+            auth.client_secret = "secret"
+            auth.authority_host = "http://{}"
+        "#,
+        addr, addr
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn healthcheck() {
+    let (addr, _rx) = mock_cluster(StatusCode::CREATED);
+    let (_, healthcheck) = config(addr).build(SinkContext::new_test()).await.unwrap();
+    healthcheck.await.unwrap();
+}
+
+#[tokio::test]
+async fn ingests_events_by_table() {
+    let (addr, mut rx) = mock_cluster(StatusCode::CREATED);
+    let (sink, _) = config(addr).build(SinkContext::new_test()).await.unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = ["first", "second", "third"]
+        .iter()
+        .zip(["logs", "audit", "logs"])
+        .map(|(message, table)| {
+            let mut log = LogEvent::from(*message).with_batch_notifier(&batch);
+            log.insert("table", table);
+            Event::from(log)
+        })
+        .collect::<Vec<_>>();
+    drop(batch);
+    sink.run_events(events).await.unwrap();
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    // The batches of both tables may be ingested concurrently.
+    let mut uploads = Vec::new();
+    let mut messages = Vec::new();
+    for _ in 0..4 {
+        let received = rx.next().await.unwrap();
+        if received.method == Method::PUT {
+            uploads.push(received);
+        } else {
+            messages.push(received);
+        }
+    }
+
+    let mut ingested = Vec::new();
+    for message in messages {
+        assert_eq!(message.uri, "/queue/messages?sig=queue");
+        let message = String::from_utf8(message.body.to_vec()).unwrap();
+        let message = message
+            .strip_prefix("<QueueMessage><MessageText>")
+            .and_then(|message| message.strip_suffix("</MessageText></QueueMessage>"))
+            .unwrap();
+        let message: Value = serde_json::from_slice(&base64::decode(message).unwrap()).unwrap();
+
+        let upload = uploads
+            .iter()
+            .find(|upload| message["BlobPath"] == format!("http://{}{}", addr, upload.uri))
+            .unwrap();
+        assert!(upload.uri.starts_with("/container/db__"));
+        assert!(upload.uri.ends_with(".multijson.gz?sig=blob"));
+        let mut lines = String::new();
+        GzDecoder::new(&upload.body[..])
+            .read_to_string(&mut lines)
+            .unwrap();
+
+        assert_eq!(message["RawDataSize"], lines.len());
+        assert_eq!(message["DatabaseName"], "db");
+        assert_eq!(
+            message["AdditionalProperties"],
+            json!({
+                "authorizationContext": "context",
+                "format": "multijson",
+                "ingestionMappingReference": "vector_mapping",
+                "ingestionMappingType": "Json",
+            })
+        );
+
+        let messages = lines
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["message"].clone())
+            .collect::<Vec<_>>();
+        ingested.push((message["TableName"].as_str().unwrap().to_owned(), messages));
+    }
+    ingested.sort();
+    assert_eq!(
+        ingested,
+        vec![
+            ("audit".to_owned(), vec![json!("second")]),
+            ("logs".to_owned(), vec![json!("first"), json!("third")]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rejects_forbidden_uploads() {
+    let (addr, mut rx) = mock_cluster(StatusCode::FORBIDDEN);
+    let (sink, _) = config(addr).build(SinkContext::new_test()).await.unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let mut log = LogEvent::from("message").with_batch_notifier(&batch);
+    log.insert("table", "logs");
+    drop(batch);
+    sink.run_events(vec![log.into()]).await.unwrap();
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+
+    // The upload isn't retried, and no message is posted to the queue.
+    assert_eq!(rx.next().await.unwrap().method, Method::PUT);
+    assert!(rx.try_next().is_err());
+}
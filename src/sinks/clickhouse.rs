@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bytes::{BufMut, Bytes, BytesMut};
 use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
@@ -251,6 +253,10 @@ impl RetryLogic for ClickhouseRetryLogic {
             _ => self.inner.should_retry_response(response),
         }
     }
+
+    fn retry_after(&self, response: &Self::Response) -> Option<Duration> {
+        self.inner.retry_after(response)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,583 @@
+//! Encoding of events into the columns of native blocks, following the types of the columns
+//! reported by the server.
+
+use std::{collections::HashMap, convert::TryFrom};
+
+use bytes::{BufMut, BytesMut};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::{
+    protocol::{put_block_info, put_string, put_varint, ColumnHeader},
+    ClickhouseNativeError,
+};
+use crate::event::{LogEvent, Value};
+
+const LOW_CARDINALITY_KEYS_VERSION: u64 = 1;
+const LOW_CARDINALITY_HAS_ADDITIONAL_KEYS: u64 = 1 << 9;
+const LOW_CARDINALITY_NEED_UPDATE_DICTIONARY: u64 = 1 << 10;
+
+/// The column types the sink is able to encode.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum ColumnType {
+    String,
+    FixedString(usize),
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    Date,
+    Date32,
+    DateTime,
+    DateTime64(u32),
+    Uuid,
+    Nullable(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+}
+
+impl ColumnType {
+    pub(super) fn parse(type_name: &str) -> Option<Self> {
+        let type_name = type_name.trim();
+        let (name, args) = match type_name.split_once('(') {
+            Some((name, args)) => (name, Some(args.strip_suffix(')')?)),
+            None => (type_name, None),
+        };
+        let ty = match (name, args) {
+            ("String", None) => Self::String,
+            ("FixedString", Some(length)) => Self::FixedString(length.trim().parse().ok()?),
+            ("UInt8", None) => Self::UInt8,
+            ("UInt16", None) => Self::UInt16,
+            ("UInt32", None) => Self::UInt32,
+            ("UInt64", None) => Self::UInt64,
+            ("Int8", None) => Self::Int8,
+            ("Int16", None) => Self::Int16,
+            ("Int32", None) => Self::Int32,
+            ("Int64", None) => Self::Int64,
+            ("Float32", None) => Self::Float32,
+            ("Float64", None) => Self::Float64,
+            ("Bool", None) => Self::Bool,
+            ("Date", None) => Self::Date,
+            ("Date32", None) => Self::Date32,
+            // The time zone only matters to the representation of the values.
+            ("DateTime", _) => Self::DateTime,
+            ("DateTime64", Some(args)) => {
+                let precision = args.split(',').next()?.trim().parse().ok()?;
+                if precision > 9 {
+                    return None;
+                }
+                Self::DateTime64(precision)
+            }
+            ("UUID", None) => Self::Uuid,
+            ("Nullable", Some(inner)) => Self::Nullable(Box::new(Self::parse(inner)?)),
+            ("Array", Some(inner)) => Self::Array(Box::new(Self::parse(inner)?)),
+            ("LowCardinality", Some(inner)) => {
+                let inner = Self::parse(inner)?;
+                match inner.non_nullable() {
+                    Self::Nullable(_) | Self::Array(_) | Self::LowCardinality(_) => return None,
+                    _ => Self::LowCardinality(Box::new(inner)),
+                }
+            }
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    fn non_nullable(&self) -> &Self {
+        match self {
+            Self::Nullable(inner) => inner,
+            ty => ty,
+        }
+    }
+}
+
+/// A value which can't be converted to the type of its column.
+#[derive(Debug)]
+struct InvalidValue;
+
+/// Encodes the events into a block holding the given columns, or returns `None` if there are no
+/// events.
+pub(super) fn encode_block(
+    headers: &[ColumnHeader],
+    events: &[LogEvent],
+) -> Result<Option<BytesMut>, ClickhouseNativeError> {
+    if events.is_empty() {
+        return Ok(None);
+    }
+
+    let mut buf = BytesMut::new();
+    put_block_info(&mut buf);
+    put_varint(&mut buf, headers.len() as u64);
+    put_varint(&mut buf, events.len() as u64);
+    for header in headers {
+        let ty = ColumnType::parse(&header.type_name).ok_or_else(|| {
+            ClickhouseNativeError::UnsupportedColumnType {
+                column: header.name.clone(),
+                ty: header.type_name.clone(),
+            }
+        })?;
+        let values = events
+            .iter()
+            .map(|log| log.get(header.name.as_str()))
+            .collect::<Vec<_>>();
+
+        put_string(&mut buf, &header.name);
+        put_string(&mut buf, &header.type_name);
+        encode_column(&mut buf, &ty, &values).map_err(|InvalidValue| {
+            ClickhouseNativeError::InvalidValue {
+                column: header.name.clone(),
+                ty: header.type_name.clone(),
+            }
+        })?;
+    }
+    Ok(Some(buf))
+}
+
+fn encode_column(
+    buf: &mut BytesMut,
+    ty: &ColumnType,
+    values: &[Option<&Value>],
+) -> Result<(), InvalidValue> {
+    put_prefix(buf, ty);
+    put_column(buf, ty, values)
+}
+
+/// The state of the low cardinality columns is written ahead of all of the data of a column.
+fn put_prefix(buf: &mut BytesMut, ty: &ColumnType) {
+    match ty {
+        ColumnType::Nullable(inner) | ColumnType::Array(inner) => put_prefix(buf, inner),
+        ColumnType::LowCardinality(_) => buf.put_u64_le(LOW_CARDINALITY_KEYS_VERSION),
+        _ => (),
+    }
+}
+
+fn put_column(
+    buf: &mut BytesMut,
+    ty: &ColumnType,
+    values: &[Option<&Value>],
+) -> Result<(), InvalidValue> {
+    match ty {
+        ColumnType::Nullable(inner) => {
+            let values = values
+                .iter()
+                .map(|value| value.filter(|value| !value.is_null()))
+                .collect::<Vec<_>>();
+            for value in &values {
+                buf.put_u8(value.is_none() as u8);
+            }
+            put_column(buf, inner, &values)
+        }
+        ColumnType::Array(inner) => {
+            let mut items = Vec::new();
+            for value in values {
+                match value {
+                    None | Some(Value::Null) => (),
+                    Some(Value::Array(array)) => items.extend(array.iter().map(Some)),
+                    Some(value) => items.push(Some(*value)),
+                }
+                buf.put_u64_le(items.len() as u64);
+            }
+            put_column(buf, inner, &items)
+        }
+        ColumnType::LowCardinality(inner) => put_low_cardinality(buf, inner, values),
+        ty => values
+            .iter()
+            .try_for_each(|value| put_value(buf, ty, value.filter(|value| !value.is_null()))),
+    }
+}
+
+/// Low cardinality columns are sent as a dictionary of the distinct values and the index of each
+/// value in the dictionary.
+fn put_low_cardinality(
+    buf: &mut BytesMut,
+    ty: &ColumnType,
+    values: &[Option<&Value>],
+) -> Result<(), InvalidValue> {
+    let (nullable, ty) = match ty {
+        ColumnType::Nullable(inner) => (true, inner.as_ref()),
+        ty => (false, ty),
+    };
+
+    // The dictionary starts with the default value, preceded by a placeholder for nulls in
+    // nullable columns.
+    let mut default = BytesMut::new();
+    put_value(&mut default, ty, None)?;
+    let mut keys = vec![default.clone()];
+    if nullable {
+        keys.push(default.clone());
+    }
+    let mut positions = HashMap::new();
+    positions.insert(default, keys.len() - 1);
+
+    let mut indexes = Vec::with_capacity(values.len());
+    for value in values {
+        let value = value.filter(|value| !value.is_null());
+        if nullable && value.is_none() {
+            indexes.push(0);
+            continue;
+        }
+        let mut key = BytesMut::new();
+        put_value(&mut key, ty, value)?;
+        let index = *positions.entry(key.clone()).or_insert_with(|| {
+            keys.push(key);
+            keys.len() - 1
+        });
+        indexes.push(index);
+    }
+
+    let key_type = match keys.len() - 1 {
+        len if len <= usize::from(u8::MAX) => 0,
+        len if len <= usize::from(u16::MAX) => 1,
+        len if u32::try_from(len).is_ok() => 2,
+        _ => 3,
+    };
+    buf.put_u64_le(
+        key_type | LOW_CARDINALITY_HAS_ADDITIONAL_KEYS | LOW_CARDINALITY_NEED_UPDATE_DICTIONARY,
+    );
+    buf.put_u64_le(keys.len() as u64);
+    for key in keys {
+        buf.extend_from_slice(&key);
+    }
+    buf.put_u64_le(indexes.len() as u64);
+    for index in indexes {
+        match key_type {
+            0 => buf.put_u8(index as u8),
+            1 => buf.put_u16_le(index as u16),
+            2 => buf.put_u32_le(index as u32),
+            _ => buf.put_u64_le(index as u64),
+        }
+    }
+    Ok(())
+}
+
+/// Writes a single value of a scalar type, missing values being written as the default value of
+/// the type.
+fn put_value(
+    buf: &mut BytesMut,
+    ty: &ColumnType,
+    value: Option<&Value>,
+) -> Result<(), InvalidValue> {
+    match ty {
+        ColumnType::String => match value {
+            None => put_string(buf, ""),
+            Some(Value::Bytes(bytes)) => put_string(buf, bytes),
+            Some(value) => put_string(buf, value.to_string_lossy()),
+        },
+        ColumnType::FixedString(length) => {
+            let bytes = match value {
+                None => Vec::new(),
+                Some(Value::Bytes(bytes)) => bytes.to_vec(),
+                Some(value) => value.to_string_lossy().into_bytes(),
+            };
+            if bytes.len() > *length {
+                return Err(InvalidValue);
+            }
+            buf.put_slice(&bytes);
+            buf.put_bytes(0, length - bytes.len());
+        }
+        ColumnType::UInt8 => buf.put_u8(integer(value)?),
+        ColumnType::UInt16 => buf.put_u16_le(integer(value)?),
+        ColumnType::UInt32 => buf.put_u32_le(integer(value)?),
+        ColumnType::UInt64 => buf.put_u64_le(integer(value)?),
+        ColumnType::Int8 => buf.put_i8(integer(value)?),
+        ColumnType::Int16 => buf.put_i16_le(integer(value)?),
+        ColumnType::Int32 => buf.put_i32_le(integer(value)?),
+        ColumnType::Int64 => buf.put_i64_le(integer(value)?),
+        ColumnType::Float32 => buf.put_f32_le(float(value)? as f32),
+        ColumnType::Float64 => buf.put_f64_le(float(value)?),
+        ColumnType::Bool => buf.put_u8(boolean(value)? as u8),
+        ColumnType::Date => buf.put_u16_le(u16::try_from(days(value)?).map_err(|_| InvalidValue)?),
+        ColumnType::Date32 => {
+            buf.put_i32_le(i32::try_from(days(value)?).map_err(|_| InvalidValue)?)
+        }
+        ColumnType::DateTime => buf.put_u32_le(
+            u32::try_from(timestamp(value)?.map_or(0, |timestamp| timestamp.timestamp()))
+                .map_err(|_| InvalidValue)?,
+        ),
+        ColumnType::DateTime64(precision) => {
+            let ticks = match timestamp(value)? {
+                None => 0,
+                Some(timestamp) => {
+                    let scale = 10i64.pow(*precision);
+                    let subsec =
+                        i64::from(timestamp.timestamp_subsec_nanos()) / 10i64.pow(9 - precision);
+                    timestamp
+                        .timestamp()
+                        .checked_mul(scale)
+                        .and_then(|ticks| ticks.checked_add(subsec))
+                        .ok_or(InvalidValue)?
+                }
+            };
+            buf.put_i64_le(ticks);
+        }
+        ColumnType::Uuid => {
+            let uuid = match value {
+                None => 0,
+                Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|uuid| uuid::Uuid::parse_str(uuid).ok())
+                    .ok_or(InvalidValue)?
+                    .as_u128(),
+                Some(_) => return Err(InvalidValue),
+            };
+            // Both halves are written as little endian integers, the most significant one first.
+            buf.put_u64_le((uuid >> 64) as u64);
+            buf.put_u64_le(uuid as u64);
+        }
+        ColumnType::Nullable(_) | ColumnType::Array(_) | ColumnType::LowCardinality(_) => {
+            // Only nested in low cardinality columns, which are rejected when parsing such types.
+            return Err(InvalidValue);
+        }
+    }
+    Ok(())
+}
+
+/// Integers, booleans, whole floats and strings holding integers can be written to integer
+/// columns.
+fn integer<T: TryFrom<i128> + Default>(value: Option<&Value>) -> Result<T, InvalidValue> {
+    let integer = match value {
+        None => return Ok(T::default()),
+        Some(Value::Integer(integer)) => i128::from(*integer),
+        Some(Value::Boolean(boolean)) => i128::from(*boolean),
+        Some(Value::Float(float)) if float.fract() == 0.0 && float.abs() < 2f64.powi(64) => {
+            float.into_inner() as i128
+        }
+        Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|integer| integer.trim().parse().ok())
+            .ok_or(InvalidValue)?,
+        Some(_) => return Err(InvalidValue),
+    };
+    T::try_from(integer).map_err(|_| InvalidValue)
+}
+
+fn float(value: Option<&Value>) -> Result<f64, InvalidValue> {
+    match value {
+        None => Ok(0.0),
+        Some(Value::Float(float)) => Ok(float.into_inner()),
+        Some(Value::Integer(integer)) => Ok(*integer as f64),
+        Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|float| float.trim().parse().ok())
+            .ok_or(InvalidValue),
+        Some(_) => Err(InvalidValue),
+    }
+}
+
+fn boolean(value: Option<&Value>) -> Result<bool, InvalidValue> {
+    match value {
+        None => Ok(false),
+        Some(Value::Boolean(boolean)) => Ok(*boolean),
+        Some(Value::Integer(0)) => Ok(false),
+        Some(Value::Integer(1)) => Ok(true),
+        Some(Value::Bytes(bytes)) => match bytes.as_ref() {
+            b"true" => Ok(true),
+            b"false" => Ok(false),
+            _ => Err(InvalidValue),
+        },
+        Some(_) => Err(InvalidValue),
+    }
+}
+
+/// Timestamps, integers as seconds since the epoch and RFC 3339 strings can be written to time
+/// columns.
+fn timestamp(value: Option<&Value>) -> Result<Option<DateTime<Utc>>, InvalidValue> {
+    match value {
+        None => Ok(None),
+        Some(Value::Timestamp(timestamp)) => Ok(Some(*timestamp)),
+        Some(Value::Integer(seconds)) => Ok(Some(DateTime::from_utc(
+            chrono::NaiveDateTime::from_timestamp_opt(*seconds, 0).ok_or(InvalidValue)?,
+            Utc,
+        ))),
+        Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| Some(timestamp.with_timezone(&Utc)))
+            .ok_or(InvalidValue),
+        Some(_) => Err(InvalidValue),
+    }
+}
+
+/// The number of days since the epoch, strings being either dates or RFC 3339 timestamps.
+fn days(value: Option<&Value>) -> Result<i64, InvalidValue> {
+    let epoch = NaiveDate::from_ymd(1970, 1, 1);
+    match value {
+        Some(Value::Bytes(bytes)) => {
+            if let Some(date) = std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            {
+                return Ok((date - epoch).num_days());
+            }
+        }
+        Some(Value::Integer(days)) => return Ok(*days),
+        _ => (),
+    }
+    Ok(timestamp(value)?.map_or(0, |timestamp| {
+        (timestamp.date().naive_utc() - epoch).num_days()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn encode(type_name: &str, values: &[Option<Value>]) -> Vec<u8> {
+        let ty = ColumnType::parse(type_name).unwrap();
+        let values = values.iter().map(Option::as_ref).collect::<Vec<_>>();
+        let mut buf = BytesMut::new();
+        encode_column(&mut buf, &ty, &values).unwrap();
+        buf.to_vec()
+    }
+
+    #[test]
+    fn parse_types() {
+        assert_eq!(
+            ColumnType::parse("LowCardinality(Nullable(String))"),
+            Some(ColumnType::LowCardinality(Box::new(ColumnType::Nullable(
+                Box::new(ColumnType::String)
+            ))))
+        );
+        assert_eq!(
+            ColumnType::parse("Array(Nullable(Int32))"),
+            Some(ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(
+                ColumnType::Int32
+            )))))
+        );
+        assert_eq!(
+            ColumnType::parse("DateTime64(3, 'Europe/Paris')"),
+            Some(ColumnType::DateTime64(3))
+        );
+        assert_eq!(
+            ColumnType::parse("DateTime('UTC')"),
+            Some(ColumnType::DateTime)
+        );
+        assert_eq!(
+            ColumnType::parse("FixedString(4)"),
+            Some(ColumnType::FixedString(4))
+        );
+        assert_eq!(ColumnType::parse("Map(String, String)"), None);
+        assert_eq!(ColumnType::parse("LowCardinality(Array(String))"), None);
+        assert_eq!(ColumnType::parse("Nullable(Decimal(9, 2))"), None);
+    }
+
+    #[test]
+    fn encode_scalars() {
+        assert_eq!(
+            encode("String", &[Some(Value::from("foo")), None]),
+            b"\x03foo\x00"
+        );
+        assert_eq!(
+            encode("UInt16", &[Some(Value::from(258)), Some(Value::from("3"))]),
+            [2, 1, 3, 0]
+        );
+        assert_eq!(
+            encode("FixedString(3)", &[Some(Value::from("ab"))]),
+            b"ab\x00"
+        );
+        assert_eq!(encode("Bool", &[Some(Value::from(true)), None]), [1, 0]);
+        assert_eq!(encode("Date", &[Some(Value::from("1970-01-03"))]), [2, 0]);
+
+        let timestamp = Utc.ymd(1970, 1, 1).and_hms_milli(0, 0, 1, 500);
+        assert_eq!(
+            encode("DateTime", &[Some(Value::from(timestamp))]),
+            [1, 0, 0, 0]
+        );
+        assert_eq!(
+            encode("DateTime64(3)", &[Some(Value::from(timestamp))]),
+            1500i64.to_le_bytes()
+        );
+
+        let mut uuid = Vec::new();
+        uuid.extend(0x0011_2233_4455_6677u64.to_le_bytes());
+        uuid.extend(0x8899_aabb_ccdd_eeffu64.to_le_bytes());
+        assert_eq!(
+            encode(
+                "UUID",
+                &[Some(Value::from("00112233-4455-6677-8899-aabbccddeeff"))]
+            ),
+            uuid
+        );
+    }
+
+    #[test]
+    fn encode_invalid_values() {
+        let mut buf = BytesMut::new();
+        for (type_name, value) in [
+            ("UInt8", Value::from(256)),
+            ("Int32", Value::from("foo")),
+            ("Float64", Value::from(true)),
+            ("FixedString(1)", Value::from("ab")),
+            ("UUID", Value::from(1)),
+        ] {
+            let ty = ColumnType::parse(type_name).unwrap();
+            assert!(encode_column(&mut buf, &ty, &[Some(&value)]).is_err());
+        }
+    }
+
+    #[test]
+    fn encode_nullable() {
+        assert_eq!(
+            encode(
+                "Nullable(UInt8)",
+                &[Some(Value::from(1)), Some(Value::Null), None]
+            ),
+            [0, 1, 1, 1, 0, 0]
+        );
+    }
+
+    #[test]
+    fn encode_array() {
+        let mut expected = Vec::new();
+        for offset in [2u64, 2, 3] {
+            expected.extend(offset.to_le_bytes());
+        }
+        expected.extend([1, 2, 3]);
+        assert_eq!(
+            encode(
+                "Array(UInt8)",
+                &[
+                    Some(Value::from(vec![Value::from(1), Value::from(2)])),
+                    None,
+                    Some(Value::from(3)),
+                ]
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn encode_low_cardinality() {
+        let mut expected = Vec::new();
+        expected.extend(1u64.to_le_bytes());
+        expected.extend(
+            (LOW_CARDINALITY_HAS_ADDITIONAL_KEYS | LOW_CARDINALITY_NEED_UPDATE_DICTIONARY)
+                .to_le_bytes(),
+        );
+        expected.extend(4u64.to_le_bytes());
+        expected.extend(b"\x00\x00\x01a\x01b");
+        expected.extend(5u64.to_le_bytes());
+        expected.extend([2, 3, 2, 0, 1]);
+        assert_eq!(
+            encode(
+                "LowCardinality(Nullable(String))",
+                &[
+                    Some(Value::from("a")),
+                    Some(Value::from("b")),
+                    Some(Value::from("a")),
+                    None,
+                    Some(Value::from("")),
+                ]
+            ),
+            expected
+        );
+    }
+}
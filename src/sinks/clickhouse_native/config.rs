@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    protocol::Login,
+    service::{ClickhouseNativeRetryLogic, ClickhouseNativeService, Connector},
+    sink::ClickhouseNativeSink,
+    ClickhouseNativeError,
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    sinks::{
+        util::{
+            BatchConfig, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsConfig},
+};
+
+const DEFAULT_PORT: u16 = 9000;
+const DEFAULT_TLS_PORT: u16 = 9440;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ClickhouseNativeConfig {
+    /// The `host:port` of the native interface of the server.
+    pub address: String,
+    pub database: Option<String>,
+    pub table: String,
+    pub auth: Option<ClickhouseNativeAuth>,
+    /// Lets the server buffer the inserted rows, to write them along with those of other inserts.
+    #[serde(default)]
+    pub async_insert: bool,
+    /// Whether asynchronous inserts are only acknowledged once written to the table.
+    #[serde(default = "crate::serde::default_true")]
+    pub wait_for_async_insert: bool,
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ClickhouseNativeAuth {
+    pub user: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+inventory::submit! {
+    SinkDescription::new::<ClickhouseNativeConfig>("clickhouse_native")
+}
+
+impl GenerateConfig for ClickhouseNativeConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "127.0.0.1:9000"
+            table = "logs""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "clickhouse_native")]
+impl SinkConfig for ClickhouseNativeConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let uri = self.address.parse::<http::Uri>()?;
+        let host = uri
+            .host()
+            .ok_or(ClickhouseNativeError::MissingHost)?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if tls.is_tls() {
+            DEFAULT_TLS_PORT
+        } else {
+            DEFAULT_PORT
+        });
+        let login = Login {
+            database: self.database.clone().unwrap_or_else(|| "default".into()),
+            user: self
+                .auth
+                .as_ref()
+                .map_or_else(|| "default".into(), |auth| auth.user.clone()),
+            password: self
+                .auth
+                .as_ref()
+                .map(|auth| auth.password.clone())
+                .unwrap_or_default(),
+        };
+        let connector = Connector::new(host, port, tls, login);
+
+        let mut settings = Vec::new();
+        if self.async_insert {
+            settings.push(("async_insert", "1".to_owned()));
+            settings.push((
+                "wait_for_async_insert",
+                (self.wait_for_async_insert as u8).to_string(),
+            ));
+        }
+
+        let healthcheck = healthcheck(connector.clone());
+
+        let service = ClickhouseNativeService::new(connector, &self.table, settings);
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, ClickhouseNativeRetryLogic)
+            .service(service);
+
+        let sink = ClickhouseNativeSink {
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+
+        Ok((
+            VectorSink::from_event_streamsink(sink),
+            Box::pin(healthcheck),
+        ))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "clickhouse_native"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+async fn healthcheck(connector: Connector) -> crate::Result<()> {
+    connector.connect().await?.ping().await?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! A sink inserting events into ClickHouse over its native TCP protocol.
+//!
+//! Events are batched into columnar blocks, the columns of which are encoded following the types
+//! of the columns of the table, as reported by the server ahead of each insert.
+
+use snafu::Snafu;
+
+mod block;
+mod config;
+mod protocol;
+mod service;
+mod sink;
+
+pub use config::ClickhouseNativeConfig;
+
+#[derive(Debug, Snafu)]
+pub enum ClickhouseNativeError {
+    #[snafu(display("Missing host in address field"))]
+    MissingHost,
+    #[snafu(display("Connection error: {}", source))]
+    Io { source: std::io::Error },
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: crate::dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("Connect error: {}", source))]
+    Connect { source: crate::tls::TlsError },
+    #[snafu(display("Server error {} ({}): {}", code, name, message))]
+    Server {
+        code: i32,
+        name: String,
+        message: String,
+    },
+    #[snafu(display("Unexpected packet of type {} from the server.", packet))]
+    UnexpectedPacket { packet: u64 },
+    #[snafu(display("Unexpected header block with {} rows.", rows))]
+    UnexpectedRows { rows: u64 },
+    #[snafu(display("Unsupported server revision {}.", revision))]
+    UnsupportedRevision { revision: u64 },
+    #[snafu(display("Unsupported type {} of column {:?}.", ty, column))]
+    UnsupportedColumnType { column: String, ty: String },
+    #[snafu(display("Invalid value for column {:?} of type {}.", column, ty))]
+    InvalidValue { column: String, ty: String },
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,375 @@
+//! The parts of the ClickHouse native protocol needed to insert blocks.
+//!
+//! The revision advertised to the server is pinned. Since servers answer with the lowest of their
+//! revision and the one of the client, packets only ever have to be written and read in a single
+//! layout.
+
+use std::io;
+
+use bytes::{BufMut, BytesMut};
+use snafu::ResultExt;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use super::{ClickhouseNativeError, IoSnafu};
+use crate::tls::MaybeTlsStream;
+
+/// The first revision serializing settings as strings, which makes them simple to send.
+pub(super) const REVISION: u64 = 54429;
+
+const CLIENT_NAME: &str = "Vector";
+
+pub(super) const CLIENT_HELLO: u64 = 0;
+pub(super) const CLIENT_QUERY: u64 = 1;
+pub(super) const CLIENT_DATA: u64 = 2;
+pub(super) const CLIENT_PING: u64 = 4;
+
+pub(super) const SERVER_HELLO: u64 = 0;
+pub(super) const SERVER_DATA: u64 = 1;
+pub(super) const SERVER_EXCEPTION: u64 = 2;
+pub(super) const SERVER_PROGRESS: u64 = 3;
+pub(super) const SERVER_PONG: u64 = 4;
+pub(super) const SERVER_END_OF_STREAM: u64 = 5;
+pub(super) const SERVER_PROFILE_INFO: u64 = 6;
+pub(super) const SERVER_TABLE_COLUMNS: u64 = 11;
+
+const QUERY_KIND_INITIAL: u8 = 1;
+const INTERFACE_TCP: u8 = 1;
+const STAGE_COMPLETE: u64 = 2;
+const COMPRESSION_DISABLED: u64 = 0;
+const SETTING_FLAG_IMPORTANT: u64 = 1;
+
+/// Strings longer than this are not expected from the server, and are likely due to a framing
+/// error.
+const MAX_STRING_LENGTH: u64 = 1 << 30;
+
+pub(super) fn put_varint(buf: &mut BytesMut, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.put_u8(byte);
+            return;
+        }
+        buf.put_u8(byte | 0x80);
+    }
+}
+
+pub(super) fn put_string(buf: &mut BytesMut, value: impl AsRef<[u8]>) {
+    let value = value.as_ref();
+    put_varint(buf, value.len() as u64);
+    buf.put_slice(value);
+}
+
+/// Written before the columns of every block, with the default values of its fields.
+pub(super) fn put_block_info(buf: &mut BytesMut) {
+    // is_overflows
+    put_varint(buf, 1);
+    buf.put_u8(0);
+    // bucket_num
+    put_varint(buf, 2);
+    buf.put_i32_le(-1);
+    put_varint(buf, 0);
+}
+
+/// An empty data block marks the end of the data sent by the client.
+pub(super) fn put_empty_data(buf: &mut BytesMut) {
+    put_data(buf, |buf| {
+        put_block_info(buf);
+        put_varint(buf, 0);
+        put_varint(buf, 0);
+    });
+}
+
+pub(super) fn put_data(buf: &mut BytesMut, put_block: impl FnOnce(&mut BytesMut)) {
+    put_varint(buf, CLIENT_DATA);
+    // The name of the table, only used for external tables.
+    put_string(buf, "");
+    put_block(buf);
+}
+
+pub(super) async fn read_varint<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = reader.read_u8().await?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Varint is too long",
+    ))
+}
+
+pub(super) async fn read_bytes<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_varint(reader).await?;
+    if len > MAX_STRING_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("String of {} bytes is too long", len),
+        ));
+    }
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes).await?;
+    Ok(bytes)
+}
+
+pub(super) async fn read_string<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<String> {
+    read_bytes(reader)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The name and type of a column, as sent by the server ahead of an insert.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct ColumnHeader {
+    pub(super) name: String,
+    pub(super) type_name: String,
+}
+
+/// Reads a block without rows, such as the one holding the structure of the inserted columns.
+pub(super) async fn read_header_block<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<Vec<ColumnHeader>, ClickhouseNativeError> {
+    read_block_info(reader).await.context(IoSnafu)?;
+    let columns = read_varint(reader).await.context(IoSnafu)?;
+    let rows = read_varint(reader).await.context(IoSnafu)?;
+    if rows != 0 {
+        return Err(ClickhouseNativeError::UnexpectedRows { rows });
+    }
+    let mut headers = Vec::new();
+    for _ in 0..columns {
+        let name = read_string(reader).await.context(IoSnafu)?;
+        let type_name = read_string(reader).await.context(IoSnafu)?;
+        headers.push(ColumnHeader { name, type_name });
+    }
+    Ok(headers)
+}
+
+pub(super) async fn read_block_info<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<()> {
+    loop {
+        match read_varint(reader).await? {
+            0 => return Ok(()),
+            1 => {
+                reader.read_u8().await?;
+            }
+            2 => {
+                reader.read_i32_le().await?;
+            }
+            field => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown block info field {}", field),
+                ))
+            }
+        }
+    }
+}
+
+/// Reads an exception, keeping the outermost one of a chain of nested exceptions.
+async fn read_exception<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<ClickhouseNativeError> {
+    let mut exception = None;
+    loop {
+        let code = reader.read_i32_le().await?;
+        let name = read_string(reader).await?;
+        let message = read_string(reader).await?;
+        let _stack_trace = read_string(reader).await?;
+        let has_nested = reader.read_u8().await? != 0;
+        exception.get_or_insert(ClickhouseNativeError::Server {
+            code,
+            name,
+            message,
+        });
+        if !has_nested {
+            return Ok(exception.expect("exception was just read"));
+        }
+    }
+}
+
+/// Skips the packets the server may send at any time during a query, returning the type of the
+/// first other packet.
+async fn read_packet_type<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<u64, ClickhouseNativeError> {
+    loop {
+        match read_varint(reader).await.context(IoSnafu)? {
+            SERVER_PROGRESS => {
+                // Read, total and written rows and bytes.
+                for _ in 0..5 {
+                    read_varint(reader).await.context(IoSnafu)?;
+                }
+            }
+            SERVER_PROFILE_INFO => {
+                for _ in 0..3 {
+                    read_varint(reader).await.context(IoSnafu)?;
+                }
+                reader.read_u8().await.context(IoSnafu)?;
+                read_varint(reader).await.context(IoSnafu)?;
+                reader.read_u8().await.context(IoSnafu)?;
+            }
+            SERVER_TABLE_COLUMNS => {
+                read_string(reader).await.context(IoSnafu)?;
+                read_string(reader).await.context(IoSnafu)?;
+            }
+            SERVER_EXCEPTION => return Err(read_exception(reader).await.context(IoSnafu)?),
+            packet => return Ok(packet),
+        }
+    }
+}
+
+/// Credentials and database used for the connections.
+#[derive(Clone, Debug)]
+pub(super) struct Login {
+    pub(super) database: String,
+    pub(super) user: String,
+    pub(super) password: String,
+}
+
+pub(super) struct Connection {
+    stream: BufReader<MaybeTlsStream<TcpStream>>,
+    server_name: String,
+}
+
+impl Connection {
+    pub(super) async fn handshake(
+        stream: MaybeTlsStream<TcpStream>,
+        login: &Login,
+    ) -> Result<Self, ClickhouseNativeError> {
+        let mut stream = BufReader::new(stream);
+
+        let mut buf = BytesMut::new();
+        put_varint(&mut buf, CLIENT_HELLO);
+        put_string(&mut buf, CLIENT_NAME);
+        put_varint(&mut buf, version_part(env!("CARGO_PKG_VERSION_MAJOR")));
+        put_varint(&mut buf, version_part(env!("CARGO_PKG_VERSION_MINOR")));
+        put_varint(&mut buf, REVISION);
+        put_string(&mut buf, &login.database);
+        put_string(&mut buf, &login.user);
+        put_string(&mut buf, &login.password);
+        stream.write_all(&buf).await.context(IoSnafu)?;
+        stream.flush().await.context(IoSnafu)?;
+
+        match read_packet_type(&mut stream).await? {
+            SERVER_HELLO => (),
+            packet => return Err(ClickhouseNativeError::UnexpectedPacket { packet }),
+        }
+        let server_name = read_string(&mut stream).await.context(IoSnafu)?;
+        let _major = read_varint(&mut stream).await.context(IoSnafu)?;
+        let _minor = read_varint(&mut stream).await.context(IoSnafu)?;
+        let revision = read_varint(&mut stream).await.context(IoSnafu)?;
+        if revision < REVISION {
+            return Err(ClickhouseNativeError::UnsupportedRevision { revision });
+        }
+        let _timezone = read_string(&mut stream).await.context(IoSnafu)?;
+        let _display_name = read_string(&mut stream).await.context(IoSnafu)?;
+        let _patch = read_varint(&mut stream).await.context(IoSnafu)?;
+
+        debug!(message = "Connected to ClickHouse.", server = %server_name);
+
+        Ok(Self {
+            stream,
+            server_name,
+        })
+    }
+
+    pub(super) fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    pub(super) async fn ping(&mut self) -> Result<(), ClickhouseNativeError> {
+        let mut buf = BytesMut::new();
+        put_varint(&mut buf, CLIENT_PING);
+        self.write(&buf).await?;
+
+        match read_packet_type(&mut self.stream).await? {
+            SERVER_PONG => Ok(()),
+            packet => Err(ClickhouseNativeError::UnexpectedPacket { packet }),
+        }
+    }
+
+    /// Runs an insert query, the block of which is encoded once the server sent the structure of
+    /// the inserted columns. Returns the number of bytes of the block.
+    ///
+    /// Once an error is returned, the connection is left in an unknown state and must be dropped.
+    pub(super) async fn insert<F>(
+        &mut self,
+        query: &str,
+        settings: &[(&str, String)],
+        encode_block: F,
+    ) -> Result<usize, ClickhouseNativeError>
+    where
+        F: FnOnce(&[ColumnHeader]) -> Result<Option<BytesMut>, ClickhouseNativeError>,
+    {
+        let mut buf = BytesMut::new();
+        put_query(&mut buf, query, settings);
+        put_empty_data(&mut buf);
+        self.write(&buf).await?;
+
+        match read_packet_type(&mut self.stream).await? {
+            SERVER_DATA => read_string(&mut self.stream).await.context(IoSnafu)?,
+            packet => return Err(ClickhouseNativeError::UnexpectedPacket { packet }),
+        };
+        let headers = read_header_block(&mut self.stream).await?;
+
+        let mut buf = BytesMut::new();
+        let mut byte_size = 0;
+        if let Some(block) = encode_block(&headers)? {
+            byte_size = block.len();
+            put_data(&mut buf, |buf| buf.extend_from_slice(&block));
+        }
+        put_empty_data(&mut buf);
+        self.write(&buf).await?;
+
+        match read_packet_type(&mut self.stream).await? {
+            SERVER_END_OF_STREAM => Ok(byte_size),
+            packet => Err(ClickhouseNativeError::UnexpectedPacket { packet }),
+        }
+    }
+
+    async fn write(&mut self, buf: &[u8]) -> Result<(), ClickhouseNativeError> {
+        self.stream.write_all(buf).await.context(IoSnafu)?;
+        self.stream.flush().await.context(IoSnafu)
+    }
+}
+
+fn put_query(buf: &mut BytesMut, query: &str, settings: &[(&str, String)]) {
+    put_varint(buf, CLIENT_QUERY);
+    // Query ID, generated by the server.
+    put_string(buf, "");
+
+    // Client info, the initial query fields being overwritten by the server.
+    buf.put_u8(QUERY_KIND_INITIAL);
+    put_string(buf, "");
+    put_string(buf, "");
+    put_string(buf, "0.0.0.0:0");
+    buf.put_u8(INTERFACE_TCP);
+    // OS user
+    put_string(buf, "");
+    put_string(buf, crate::get_hostname().unwrap_or_default());
+    put_string(buf, CLIENT_NAME);
+    put_varint(buf, version_part(env!("CARGO_PKG_VERSION_MAJOR")));
+    put_varint(buf, version_part(env!("CARGO_PKG_VERSION_MINOR")));
+    put_varint(buf, REVISION);
+    // Quota key
+    put_string(buf, "");
+    put_varint(buf, version_part(env!("CARGO_PKG_VERSION_PATCH")));
+
+    for (name, value) in settings {
+        put_string(buf, name);
+        put_varint(buf, SETTING_FLAG_IMPORTANT);
+        put_string(buf, value);
+    }
+    put_string(buf, "");
+
+    put_varint(buf, STAGE_COMPLETE);
+    put_varint(buf, COMPRESSION_DISABLED);
+    put_string(buf, query);
+}
+
+fn version_part(part: &str) -> u64 {
+    part.parse().unwrap_or_default()
+}
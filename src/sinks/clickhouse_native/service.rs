@@ -0,0 +1,266 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use snafu::ResultExt;
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{
+    block::encode_block,
+    protocol::{Connection, Login},
+    ClickhouseNativeError, ConnectSnafu, DnsSnafu,
+};
+use crate::{
+    dns,
+    event::{EventFinalizers, EventStatus, Finalizable, LogEvent},
+    internal_events::EndpointBytesSent,
+    sinks::util::retries::RetryLogic,
+    tls::MaybeTlsSettings,
+};
+
+/// Server errors due to the inserted data or to the credentials, which would fail again when
+/// retried.
+const NON_RETRIABLE_CODES: &[i32] = &[
+    53,  // TYPE_MISMATCH
+    62,  // SYNTAX_ERROR
+    117, // INCORRECT_DATA
+    192, // UNKNOWN_USER
+    193, // WRONG_PASSWORD
+    194, // REQUIRED_PASSWORD
+    497, // ACCESS_DENIED
+    516, // AUTHENTICATION_FAILED
+];
+
+#[derive(Clone, Debug)]
+pub(super) struct Connector {
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+    login: Login,
+}
+
+impl Connector {
+    pub(super) const fn new(host: String, port: u16, tls: MaybeTlsSettings, login: Login) -> Self {
+        Self {
+            host,
+            port,
+            tls,
+            login,
+        }
+    }
+
+    pub(super) async fn connect(&self) -> Result<Connection, ClickhouseNativeError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(DnsSnafu)?
+            .next()
+            .ok_or(ClickhouseNativeError::NoAddresses)?;
+
+        let addr = SocketAddr::new(ip, self.port);
+        let stream = self
+            .tls
+            .connect(&self.host, &addr)
+            .await
+            .context(ConnectSnafu)?;
+        Connection::handshake(stream, &self.login).await
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// A connection along with the columns of the table, which are looked up when connecting.
+struct TableConnection {
+    connection: Connection,
+    columns: Vec<String>,
+}
+
+#[derive(Clone)]
+pub(super) struct ClickhouseNativeService {
+    connector: Connector,
+    table: String,
+    settings: Arc<Vec<(&'static str, String)>>,
+    idle_connections: Arc<Mutex<Vec<TableConnection>>>,
+}
+
+impl ClickhouseNativeService {
+    pub(super) fn new(
+        connector: Connector,
+        table: &str,
+        settings: Vec<(&'static str, String)>,
+    ) -> Self {
+        Self {
+            table: format!(
+                "{}.{}",
+                quote_identifier(&connector.login.database),
+                quote_identifier(table)
+            ),
+            connector,
+            settings: Arc::new(settings),
+            idle_connections: Arc::default(),
+        }
+    }
+
+    async fn connect(&self) -> Result<TableConnection, ClickhouseNativeError> {
+        let mut connection = self.connector.connect().await?;
+
+        // Inserting no rows still makes the server send the structure of the table.
+        let mut columns = Vec::new();
+        connection
+            .insert(
+                &format!("INSERT INTO {} VALUES", self.table),
+                &[],
+                |headers| {
+                    columns = headers.iter().map(|header| header.name.clone()).collect();
+                    Ok(None)
+                },
+            )
+            .await?;
+        debug!(
+            message = "Looked up the columns of the table.",
+            table = %self.table,
+            server = %connection.server_name(),
+            columns = ?columns,
+        );
+
+        Ok(TableConnection {
+            connection,
+            columns,
+        })
+    }
+
+    async fn insert(
+        &self,
+        connection: &mut TableConnection,
+        events: &[LogEvent],
+    ) -> Result<usize, ClickhouseNativeError> {
+        // Only the columns present in the events are inserted, letting the server fill in the
+        // default values of the others.
+        let mut columns = connection
+            .columns
+            .iter()
+            .filter(|column| events.iter().any(|log| log.contains(column.as_str())))
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            columns = connection
+                .columns
+                .iter()
+                .map(|column| quote_identifier(column))
+                .collect();
+        }
+        let query = format!("INSERT INTO {} ({}) VALUES", self.table, columns.join(", "));
+
+        connection
+            .connection
+            .insert(&query, &self.settings, |headers| {
+                encode_block(headers, events)
+            })
+            .await
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!(
+        "\"{}\"",
+        identifier.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+#[derive(Default)]
+pub(super) struct ClickhouseNativeRequest {
+    pub(super) events: Vec<LogEvent>,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for ClickhouseNativeRequest {
+    fn ack_size(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl Finalizable for ClickhouseNativeRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub(super) struct ClickhouseNativeResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for ClickhouseNativeResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+impl tower::Service<ClickhouseNativeRequest> for ClickhouseNativeService {
+    type Response = ClickhouseNativeResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: ClickhouseNativeRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            let idle_connection = service.idle_connections.lock().unwrap().pop();
+            let mut connection = match idle_connection {
+                Some(connection) => connection,
+                None => service.connect().await?,
+            };
+
+            // On error, the connection is dropped as it may be in the middle of a query.
+            let byte_size = service.insert(&mut connection, &request.events).await?;
+            service.idle_connections.lock().unwrap().push(connection);
+
+            emit!(&EndpointBytesSent {
+                byte_size,
+                protocol: "tcp",
+                endpoint: &service.connector.endpoint(),
+            });
+            Ok(ClickhouseNativeResponse {
+                events_count: request.events.len(),
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ClickhouseNativeRetryLogic;
+
+impl RetryLogic for ClickhouseNativeRetryLogic {
+    type Error = ClickhouseNativeError;
+    type Response = ClickhouseNativeResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            ClickhouseNativeError::Server { code, .. } => !NON_RETRIABLE_CODES.contains(code),
+            ClickhouseNativeError::MissingHost
+            | ClickhouseNativeError::UnsupportedRevision { .. }
+            | ClickhouseNativeError::UnsupportedColumnType { .. }
+            | ClickhouseNativeError::InvalidValue { .. } => false,
+            _ => true,
+        }
+    }
+}
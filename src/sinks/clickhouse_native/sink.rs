@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use tower::util::BoxService;
+use vector_core::{buffers::Acker, stream::BatcherSettings, ByteSizeOf};
+
+use super::service::{ClickhouseNativeRequest, ClickhouseNativeResponse};
+use crate::{
+    event::{Event, EventFinalizers, Finalizable, LogEvent},
+    sinks::util::{SinkBuilderExt, StreamSink},
+};
+
+struct EventData {
+    byte_size: usize,
+    finalizers: EventFinalizers,
+    log: LogEvent,
+}
+
+pub(super) struct ClickhouseNativeSink {
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) service: BoxService<ClickhouseNativeRequest, ClickhouseNativeResponse, crate::Error>,
+    pub(super) acker: Acker,
+}
+
+impl ClickhouseNativeSink {
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        input
+            .map(|mut event| EventData {
+                byte_size: event.size_of(),
+                finalizers: event.take_finalizers(),
+                log: event.into_log(),
+            })
+            .batched(self.batch_settings.into_reducer_config(
+                |data: &EventData| data.byte_size,
+                |request: &mut ClickhouseNativeRequest, data: EventData| {
+                    request.events_byte_size += data.byte_size;
+                    request.finalizers.merge(data.finalizers);
+                    request.events.push(data.log);
+                },
+            ))
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl StreamSink<Event> for ClickhouseNativeSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,334 @@
+use bytes::{BufMut, BytesMut};
+use futures::{channel::mpsc, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::{protocol::*, service::ClickhouseNativeRetryLogic, *};
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, LogEvent},
+    sinks::util::retries::RetryLogic,
+    test_util::next_addr,
+};
+
+const TABLE_COLUMNS: &[(&str, &str)] = &[
+    ("message", "String"),
+    ("count", "Nullable(Int64)"),
+    ("extra", "String"),
+];
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<ClickhouseNativeConfig>();
+}
+
+#[test]
+fn retriable_errors() {
+    let server_error = |code| ClickhouseNativeError::Server {
+        code,
+        name: "DB::Exception".into(),
+        message: String::new(),
+    };
+    let logic = ClickhouseNativeRetryLogic;
+
+    assert!(logic.is_retriable_error(&server_error(16)));
+    assert!(!logic.is_retriable_error(&server_error(117)));
+    assert!(!logic.is_retriable_error(&server_error(516)));
+    assert!(logic.is_retriable_error(&ClickhouseNativeError::NoAddresses));
+    assert!(
+        !logic.is_retriable_error(&ClickhouseNativeError::InvalidValue {
+            column: "count".into(),
+            ty: "Int64".into(),
+        })
+    );
+}
+
+/// An insert received by the mock server.
+#[derive(Debug)]
+struct Insert {
+    query: String,
+    settings: Vec<(String, String)>,
+    columns: Vec<(String, Vec<String>)>,
+}
+
+/// Serves the native protocol for a table of `TABLE_COLUMNS`, failing the inserts with the given
+/// exception code if any.
+async fn mock_server(exception_code: Option<i32>) -> (String, mpsc::UnboundedReceiver<Insert>) {
+    let addr = next_addr();
+    let listener = TcpListener::bind(addr).await.unwrap();
+    let (tx, rx) = mpsc::unbounded();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(serve(stream, tx.clone(), exception_code));
+        }
+    });
+    (addr.to_string(), rx)
+}
+
+async fn serve(stream: TcpStream, tx: mpsc::UnboundedSender<Insert>, exception_code: Option<i32>) {
+    let mut stream = BufReader::new(stream);
+
+    assert_eq!(read_varint(&mut stream).await.unwrap(), CLIENT_HELLO);
+    read_string(&mut stream).await.unwrap();
+    read_varint(&mut stream).await.unwrap();
+    read_varint(&mut stream).await.unwrap();
+    assert_eq!(read_varint(&mut stream).await.unwrap(), REVISION);
+    assert_eq!(read_string(&mut stream).await.unwrap(), "default");
+    assert_eq!(read_string(&mut stream).await.unwrap(), "vector");
+    assert_eq!(read_string(&mut stream).await.unwrap(), "secret");
+
+    let mut buf = BytesMut::new();
+    put_varint(&mut buf, SERVER_HELLO);
+    put_string(&mut buf, "ClickHouse");
+    put_varint(&mut buf, 22);
+    put_varint(&mut buf, 3);
+    put_varint(&mut buf, 54460);
+    put_string(&mut buf, "UTC");
+    put_string(&mut buf, "mock");
+    put_varint(&mut buf, 1);
+    write(&mut stream, &buf).await;
+
+    while let Ok(packet) = read_varint(&mut stream).await {
+        let mut buf = BytesMut::new();
+        match packet {
+            CLIENT_PING => put_varint(&mut buf, SERVER_PONG),
+            CLIENT_QUERY => {
+                let (query, settings) = read_query(&mut stream).await;
+                assert!(read_data(&mut stream).await.is_empty());
+
+                let columns = query_columns(&query);
+                put_varint(&mut buf, SERVER_DATA);
+                put_string(&mut buf, "");
+                put_block_info(&mut buf);
+                put_varint(&mut buf, columns.len() as u64);
+                put_varint(&mut buf, 0);
+                for (name, type_name) in &columns {
+                    put_string(&mut buf, name);
+                    put_string(&mut buf, type_name);
+                }
+                write(&mut stream, &buf).await;
+                buf.clear();
+
+                let mut inserted = false;
+                loop {
+                    let block = read_data(&mut stream).await;
+                    if block.is_empty() {
+                        break;
+                    }
+                    inserted = true;
+                    tx.unbounded_send(Insert {
+                        query: query.clone(),
+                        settings: settings.clone(),
+                        columns: block,
+                    })
+                    .unwrap();
+                }
+
+                match exception_code.filter(|_| inserted) {
+                    Some(code) => {
+                        put_varint(&mut buf, SERVER_EXCEPTION);
+                        buf.put_i32_le(code);
+                        put_string(&mut buf, "DB::Exception");
+                        put_string(&mut buf, "Cannot parse input");
+                        put_string(&mut buf, "");
+                        buf.put_u8(0);
+                    }
+                    None => put_varint(&mut buf, SERVER_END_OF_STREAM),
+                }
+            }
+            packet => panic!("Unexpected packet {}", packet),
+        }
+        write(&mut stream, &buf).await;
+    }
+}
+
+async fn write(stream: &mut BufReader<TcpStream>, buf: &[u8]) {
+    stream.write_all(buf).await.unwrap();
+    stream.flush().await.unwrap();
+}
+
+async fn read_query(stream: &mut BufReader<TcpStream>) -> (String, Vec<(String, String)>) {
+    read_string(stream).await.unwrap();
+    stream.read_u8().await.unwrap();
+    for _ in 0..3 {
+        read_string(stream).await.unwrap();
+    }
+    stream.read_u8().await.unwrap();
+    for _ in 0..3 {
+        read_string(stream).await.unwrap();
+    }
+    for _ in 0..3 {
+        read_varint(stream).await.unwrap();
+    }
+    read_string(stream).await.unwrap();
+    read_varint(stream).await.unwrap();
+
+    let mut settings = Vec::new();
+    loop {
+        let name = read_string(stream).await.unwrap();
+        if name.is_empty() {
+            break;
+        }
+        read_varint(stream).await.unwrap();
+        settings.push((name, read_string(stream).await.unwrap()));
+    }
+
+    read_varint(stream).await.unwrap();
+    assert_eq!(read_varint(stream).await.unwrap(), 0);
+    (read_string(stream).await.unwrap(), settings)
+}
+
+/// The columns listed in the query, or all of the columns of the table.
+fn query_columns(query: &str) -> Vec<(String, String)> {
+    let listed = query
+        .split_once('(')
+        .and_then(|(_, columns)| columns.split_once(')'))
+        .map(|(columns, _)| {
+            columns
+                .split(", ")
+                .map(|column| column.trim_matches('"').to_owned())
+                .collect::<Vec<_>>()
+        });
+    TABLE_COLUMNS
+        .iter()
+        .filter(|(name, _)| {
+            listed
+                .as_ref()
+                .map_or(true, |listed| listed.iter().any(|column| column == name))
+        })
+        .map(|(name, type_name)| (name.to_string(), type_name.to_string()))
+        .collect()
+}
+
+/// Reads a data block of the types of `TABLE_COLUMNS`, rendering the values as strings.
+async fn read_data(stream: &mut BufReader<TcpStream>) -> Vec<(String, Vec<String>)> {
+    assert_eq!(read_varint(stream).await.unwrap(), CLIENT_DATA);
+    read_string(stream).await.unwrap();
+    read_block_info(stream).await.unwrap();
+    let columns = read_varint(stream).await.unwrap();
+    let rows = read_varint(stream).await.unwrap();
+
+    let mut block = Vec::new();
+    for _ in 0..columns {
+        let name = read_string(stream).await.unwrap();
+        let mut values = Vec::new();
+        match read_string(stream).await.unwrap().as_str() {
+            "String" => {
+                for _ in 0..rows {
+                    values.push(read_string(stream).await.unwrap());
+                }
+            }
+            "Nullable(Int64)" => {
+                let mut nulls = Vec::new();
+                for _ in 0..rows {
+                    nulls.push(stream.read_u8().await.unwrap() == 1);
+                }
+                for null in nulls {
+                    let value = stream.read_i64_le().await.unwrap();
+                    values.push(if null {
+                        "NULL".to_owned()
+                    } else {
+                        value.to_string()
+                    });
+                }
+            }
+            type_name => panic!("Unexpected type {}", type_name),
+        }
+        block.push((name, values));
+    }
+    block
+}
+
+fn config(address: &str, async_insert: bool) -> ClickhouseNativeConfig {
+    toml::from_str(&format!(
+        r#"
+            address = "{}"
+            table = "logs"
+            async_insert = {}
+            auth.user = "vector"
+            auth.password = "secret"
+        "#,
+        address, async_insert
+    ))
+    .unwrap()
+}
+
+fn events(batch: &std::sync::Arc<BatchNotifier>) -> Vec<Event> {
+    let mut first = LogEvent::from("first");
+    first.insert("count", 1);
+    let second = LogEvent::from("second");
+    vec![
+        first.with_batch_notifier(batch).into(),
+        second.with_batch_notifier(batch).into(),
+    ]
+}
+
+#[tokio::test]
+async fn healthcheck() {
+    let (address, _rx) = mock_server(None).await;
+    let (_, healthcheck) = config(&address, false)
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+    healthcheck.await.unwrap();
+}
+
+#[tokio::test]
+async fn insert_events() {
+    let (address, mut rx) = mock_server(None).await;
+    let (sink, _) = config(&address, true)
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    sink.run_events(events(&batch)).await.unwrap();
+    drop(batch);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let insert = rx.next().await.unwrap();
+    assert_eq!(
+        insert.query,
+        r#"INSERT INTO "default"."logs" ("message", "count") VALUES"#
+    );
+    assert_eq!(
+        insert.settings,
+        vec![
+            ("async_insert".to_owned(), "1".to_owned()),
+            ("wait_for_async_insert".to_owned(), "1".to_owned()),
+        ]
+    );
+    assert_eq!(
+        insert.columns,
+        vec![
+            (
+                "message".to_owned(),
+                vec!["first".to_owned(), "second".to_owned()]
+            ),
+            ("count".to_owned(), vec!["1".to_owned(), "NULL".to_owned()]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_data() {
+    let (address, mut rx) = mock_server(Some(117)).await;
+    let (sink, _) = config(&address, false)
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    sink.run_events(events(&batch)).await.unwrap();
+    drop(batch);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+
+    // The insert isn't retried.
+    let insert = rx.next().await.unwrap();
+    assert!(insert.settings.is_empty());
+    assert!(rx.try_next().is_err());
+}
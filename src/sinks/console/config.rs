@@ -6,7 +6,7 @@ use crate::{
     config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
     sinks::{
         console::sink::WriterSink,
-        util::encoding::{EncodingConfig, StandardEncodings},
+        util::encoding::{EncodingConfig, EncodingConfiguration, StandardEncodings},
         Healthcheck, VectorSink,
     },
 };
@@ -42,6 +42,9 @@ impl GenerateConfig for ConsoleSinkConfig {
 #[typetag::serde(name = "console")]
 impl SinkConfig for ConsoleSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.encoding.codec().is_binary() {
+            return Err("The `console` sink writes text and can't use a binary encoding.".into());
+        }
         let encoding = self.encoding.clone();
 
         let sink: VectorSink = match self.target {
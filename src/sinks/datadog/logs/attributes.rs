@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use vector_core::event::LogEvent;
+
+use crate::{
+    internal_events::{DatadogLogsInvalidReservedAttribute, TemplateRenderingError},
+    template::Template,
+};
+
+/// The maximum length of the tags accepted by Datadog, which also applies to the other reserved
+/// attributes, as they are indexed as tags.
+const MAX_VALUE_LENGTH: usize = 200;
+
+/// Templates rendered into the reserved attributes of the logs, overwriting the fields of the
+/// events with the same names.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ReservedAttributes {
+    service: Option<Template>,
+    ddsource: Option<Template>,
+    ddtags: Option<Template>,
+    hostname: Option<Template>,
+}
+
+impl ReservedAttributes {
+    pub(super) const fn is_empty(&self) -> bool {
+        self.service.is_none()
+            && self.ddsource.is_none()
+            && self.ddtags.is_none()
+            && self.hostname.is_none()
+    }
+
+    /// Renders the attributes of the event, leaving the fields of the event unchanged when their
+    /// template can't be rendered or when the rendered value isn't valid.
+    pub(super) fn apply(&self, log: &mut LogEvent) {
+        let attributes = [
+            ("service", &self.service),
+            ("ddsource", &self.ddsource),
+            ("ddtags", &self.ddtags),
+            ("hostname", &self.hostname),
+        ];
+        for (attribute, template) in attributes {
+            let template = match template {
+                Some(template) => template,
+                None => continue,
+            };
+            let rendered = match template.render_string(&*log) {
+                Ok(rendered) => rendered,
+                Err(error) => {
+                    emit!(&TemplateRenderingError {
+                        error,
+                        field: Some(attribute),
+                        drop_event: false,
+                    });
+                    continue;
+                }
+            };
+            let value = if attribute == "ddtags" {
+                validate_tags(&rendered)
+            } else {
+                validate(attribute, rendered.trim()).map(str::to_owned)
+            };
+            if let Some(value) = value {
+                log.insert_flat(attribute, value);
+            }
+        }
+    }
+}
+
+fn validate<'a>(attribute: &'static str, value: &'a str) -> Option<&'a str> {
+    let reason = if value.is_empty() {
+        "Value is empty."
+    } else if value.chars().count() > MAX_VALUE_LENGTH {
+        "Value is longer than 200 characters."
+    } else if value.contains(|c: char| c.is_whitespace() || c.is_control()) {
+        "Value contains whitespace."
+    } else {
+        return Some(value);
+    };
+    emit!(&DatadogLogsInvalidReservedAttribute {
+        attribute,
+        value,
+        reason,
+    });
+    None
+}
+
+/// Keeps the valid tags of a comma separated list, which must start with a letter.
+fn validate_tags(tags: &str) -> Option<String> {
+    let valid = tags
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .filter_map(|tag| {
+            if tag.starts_with(char::is_alphabetic) {
+                validate("ddtags", tag)
+            } else {
+                emit!(&DatadogLogsInvalidReservedAttribute {
+                    attribute: "ddtags",
+                    value: tag,
+                    reason: "Tag doesn't start with a letter.",
+                });
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    (!valid.is_empty()).then(|| valid.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(config: &str) -> ReservedAttributes {
+        toml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn renders_attributes() {
+        let attributes = attributes(
+            r#"service = "{{ app }}"
+            ddsource = "vector"
+            ddtags = "env:{{ env }}, team:{{ team }}"
+            hostname = "{{ pod }}""#,
+        );
+        let mut log = LogEvent::from("hello");
+        log.insert("app", "checkout");
+        log.insert("env", "prod");
+        log.insert("team", "payments");
+        log.insert("pod", "checkout-1");
+        log.insert("service", "unknown");
+
+        attributes.apply(&mut log);
+        assert_eq!(log["service"], "checkout".into());
+        assert_eq!(log["ddsource"], "vector".into());
+        assert_eq!(log["ddtags"], "env:prod,team:payments".into());
+        assert_eq!(log["hostname"], "checkout-1".into());
+    }
+
+    #[test]
+    fn skips_invalid_attributes() {
+        let attributes = attributes(
+            r#"service = "{{ app }}"
+            ddsource = "{{ source }}"
+            ddtags = "1bad, env:prod,,{{ missing }}"
+            hostname = "{{ pod }}""#,
+        );
+        let mut log = LogEvent::from("hello");
+        log.insert("service", "unknown");
+        log.insert("source", " ");
+        log.insert("pod", "checkout 1");
+
+        attributes.apply(&mut log);
+        // The template of the service can't be rendered, so the field is left unchanged.
+        assert_eq!(log["service"], "unknown".into());
+        assert!(!log.contains("ddsource"));
+        assert!(!log.contains("ddtags"));
+        assert!(!log.contains("hostname"));
+
+        let attributes = self::attributes(r#"ddtags = "1bad, env:prod""#);
+        attributes.apply(&mut log);
+        assert_eq!(log["ddtags"], "env:prod".into());
+    }
+
+    #[test]
+    fn rejects_long_values() {
+        let mut log = LogEvent::from("hello");
+        log.insert("app", "a".repeat(201));
+        attributes(r#"service = "{{ app }}""#).apply(&mut log);
+        assert!(!log.contains("service"));
+    }
+}
@@ -7,6 +7,7 @@ use tower::ServiceBuilder;
 use vector_core::config::proxy::ProxyConfig;
 
 use super::{
+    attributes::ReservedAttributes,
     service::LogApiRetry,
     sink::{DatadogLogsJsonEncoding, LogSinkBuilder},
 };
@@ -63,6 +64,10 @@ pub(crate) struct DatadogLogsConfig {
     encoding: EncodingConfigFixed<DatadogLogsJsonEncoding>,
     tls: Option<TlsConfig>,
 
+    /// Templates rendered into the `service`, `ddsource`, `ddtags` and `hostname` attributes.
+    #[serde(default)]
+    reserved_attributes: ReservedAttributes,
+
     #[serde(default)]
     compression: Option<Compression>,
 
@@ -139,6 +144,7 @@ impl DatadogLogsConfig {
         let sink = LogSinkBuilder::new(service, cx, default_api_key, batch)
             .encoding(self.encoding.clone())
             .compression(self.compression.unwrap_or_default())
+            .reserved_attributes(self.reserved_attributes.clone())
             .build();
 
         Ok(VectorSink::from_event_streamsink(sink))
@@ -24,6 +24,7 @@ mod integration_tests;
 #[cfg(test)]
 mod tests;
 
+mod attributes;
 mod config;
 mod service;
 mod sink;
@@ -1,6 +1,7 @@
 use std::{
     sync::Arc,
     task::{Context, Poll},
+    time::Instant,
 };
 
 use bytes::Bytes;
@@ -22,6 +23,7 @@ use vector_core::{
 
 use crate::{
     http::HttpClient,
+    internal_events::DatadogLogsDeliveryLatency,
     sinks::util::{retries::RetryLogic, Compression},
 };
 
@@ -50,6 +52,8 @@ pub struct LogApiRequest {
     pub body: Bytes,
     pub finalizers: EventFinalizers,
     pub events_byte_size: usize,
+    /// When the request was built, so that the latency of its delivery includes its retries.
+    pub built_at: Instant,
 }
 
 impl Ackable for LogApiRequest {
@@ -156,10 +160,15 @@ impl Service<LogApiRequest> for LogApiService {
 
         let count = request.batch_size;
         let events_byte_size = request.events_byte_size;
+        let built_at = request.built_at;
         Box::pin(async move {
             match client.call(http_request).in_current_span().await {
                 Ok(response) => {
                     let status = response.status();
+                    emit!(&DatadogLogsDeliveryLatency {
+                        status,
+                        latency: built_at.elapsed(),
+                    });
                     // From https://docs.datadoghq.com/api/latest/logs/:
                     //
                     // The status codes answered by the HTTP API are:
@@ -3,6 +3,7 @@ use std::{
     io::{self, Write},
     num::NonZeroUsize,
     sync::Arc,
+    time::Instant,
 };
 
 use async_trait::async_trait;
@@ -20,7 +21,7 @@ use vector_core::{
     ByteSizeOf,
 };
 
-use super::{config::MAX_PAYLOAD_BYTES, service::LogApiRequest};
+use super::{attributes::ReservedAttributes, config::MAX_PAYLOAD_BYTES, service::LogApiRequest};
 use crate::{
     config::SinkContext,
     sinks::util::{
@@ -48,6 +49,7 @@ pub struct LogSinkBuilder<S> {
     batch_settings: BatcherSettings,
     compression: Option<Compression>,
     default_api_key: Arc<str>,
+    reserved_attributes: ReservedAttributes,
 }
 
 impl<S> LogSinkBuilder<S> {
@@ -64,6 +66,7 @@ impl<S> LogSinkBuilder<S> {
             default_api_key,
             batch_settings,
             compression: None,
+            reserved_attributes: Default::default(),
         }
     }
 
@@ -78,6 +81,12 @@ impl<S> LogSinkBuilder<S> {
         self
     }
 
+    #[allow(clippy::missing_const_for_fn)] // const cannot run destructor
+    pub fn reserved_attributes(mut self, reserved_attributes: ReservedAttributes) -> Self {
+        self.reserved_attributes = reserved_attributes;
+        self
+    }
+
     pub fn build(self) -> LogSink<S> {
         LogSink {
             default_api_key: self.default_api_key,
@@ -86,6 +95,7 @@ impl<S> LogSinkBuilder<S> {
             service: self.service,
             batch_settings: self.batch_settings,
             compression: self.compression.unwrap_or_default(),
+            reserved_attributes: self.reserved_attributes,
         }
     }
 }
@@ -108,6 +118,8 @@ pub struct LogSink<S> {
     compression: Compression,
     /// Batch settings: timeout, max events, max bytes, etc.
     batch_settings: BatcherSettings,
+    /// The templates of the reserved attributes rendered into the events
+    reserved_attributes: ReservedAttributes,
 }
 
 /// Customized encoding specific to the Datadog Logs sink, as the logs API only accepts JSON encoded
@@ -214,6 +226,7 @@ impl RequestBuilder<(Option<Arc<str>>, Vec<Event>)> for LogRequestBuilder {
             body: payload,
             finalizers,
             events_byte_size,
+            built_at: Instant::now(),
         }
     }
 }
@@ -237,7 +250,14 @@ where
             compression: self.compression,
         };
 
+        let reserved_attributes = self.reserved_attributes;
         let sink = input
+            .map(move |mut event| {
+                if !reserved_attributes.is_empty() {
+                    reserved_attributes.apply(event.as_mut_log());
+                }
+                event
+            })
             .batched_partitioned(partitioner, self.batch_settings)
             .request_builder(builder_limit, request_builder)
             .filter_map(|request| async move {
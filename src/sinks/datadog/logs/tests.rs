@@ -434,3 +434,52 @@ async fn no_enterprise_headers_inner(api_status: ApiStatus) {
     assert_eq!(parts.headers.get("DD-EVP-ORIGIN").unwrap(), "vector");
     assert!(parts.headers.get("DD-EVP-ORIGIN-VERSION").is_some());
 }
+
+#[tokio::test]
+/// Assert that the reserved attributes are rendered into the payloads
+///
+/// The `reserved_attributes` templates are rendered from the fields of the
+/// events into the attributes of the same names, overwriting them.
+async fn reserved_attributes() {
+    let (mut config, cx) = load_sink::<DatadogLogsConfig>(indoc! {r#"
+            default_api_key = "atoken"
+            compression = "none"
+
+            [reserved_attributes]
+            service = "{{ app }}"
+            ddsource = "vector"
+            ddtags = "env:{{ env }},{{ env }}"
+        "#})
+    .unwrap();
+
+    let addr = next_addr();
+    // Swap out the endpoint so we can force send it to our local server
+    let endpoint = format!("http://{}", addr);
+    config.endpoint = Some(endpoint.clone());
+
+    let (sink, _) = config.build(cx).await.unwrap();
+
+    let (rx, _trigger, server) = test_server(addr, ApiStatus::OKv2);
+    tokio::spawn(server);
+
+    let (expected_messages, events) = random_lines_with_stream(100, 10, None);
+    let events = events.map(|mut e| {
+        e.for_each_log(|log| {
+            log.insert("app", "checkout");
+            log.insert("env", "1prod");
+            log.insert("service", "unknown");
+        });
+        e
+    });
+
+    let () = sink.run(events).await.unwrap();
+    let output: (Parts, Bytes) = rx.take(1).collect::<Vec<_>>().await.pop().unwrap();
+    let payload_array: Vec<serde_json::Value> = serde_json::from_slice(&output.1[..]).unwrap();
+    assert_eq!(payload_array.len(), expected_messages.len());
+    for obj in payload_array {
+        assert_eq!(obj["service"], "checkout");
+        assert_eq!(obj["ddsource"], "vector");
+        // The tag which doesn't start with a letter is dropped.
+        assert_eq!(obj["ddtags"], "env:1prod");
+    }
+}
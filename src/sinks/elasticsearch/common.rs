@@ -21,6 +21,7 @@ use crate::{
         },
         HealthcheckError,
     },
+    template::Template,
     tls::TlsSettings,
     transforms::metric_to_log::MetricToLog,
 };
@@ -40,11 +41,18 @@ pub struct ElasticsearchCommon {
     pub region: Region,
     pub request: RequestConfig,
     pub query_params: HashMap<String, String>,
+    /// The template of the pipeline of each document, when it depends on the events, static
+    /// pipelines being set for the whole request in the query parameters instead.
+    pub pipeline: Option<Template>,
     pub metric_to_log: MetricToLog,
 }
 
 impl ElasticsearchCommon {
     pub fn parse_config(config: &ElasticsearchConfig) -> crate::Result<Self> {
+        if config.request.compression.is_some() {
+            return Err(ParseError::RequestCompression.into());
+        }
+
         // Test the configured host, but ignore the result
         let uri = format!("{}/_test", &config.endpoint);
         let uri = uri.parse::<Uri>().with_context(|_| InvalidHostSnafu {
@@ -94,9 +102,13 @@ impl ElasticsearchCommon {
             format!("{}s", tower_request.timeout.as_secs()),
         );
 
-        if let Some(pipeline) = &config.pipeline {
-            query_params.insert("pipeline".into(), pipeline.into());
-        }
+        let pipeline = match config.pipeline()? {
+            Some(pipeline) if !pipeline.is_dynamic() => {
+                query_params.insert("pipeline".into(), pipeline.get_ref().into());
+                None
+            }
+            pipeline => pipeline,
+        };
 
         let mut query = url::form_urlencoded::Serializer::new(String::new());
         for (p, v) in &query_params {
@@ -126,6 +138,7 @@ impl ElasticsearchCommon {
             encoding: config.encoding,
             mode,
             query_params,
+            pipeline,
             request,
             region,
             tls_settings,
@@ -21,8 +21,9 @@ use crate::{
             retry::ElasticsearchRetryLogic,
             service::{ElasticsearchService, HttpRequestBuilder},
             sink::ElasticsearchSink,
-            BatchActionTemplateSnafu, ElasticsearchAuth, ElasticsearchCommon,
-            ElasticsearchCommonMode, ElasticsearchMode, IndexTemplateSnafu,
+            BatchActionTemplateSnafu, BulkAction, ElasticsearchAuth, ElasticsearchCommon,
+            ElasticsearchCommonMode, ElasticsearchMode, IndexTemplateSnafu, ParseError,
+            PipelineTemplateSnafu,
         },
         util::{
             encoding::EncodingConfigFixed, http::RequestConfig, BatchConfig, Compression,
@@ -109,6 +110,14 @@ impl ElasticsearchConfig {
         Ok(Template::try_from(index.as_str()).context(IndexTemplateSnafu)?)
     }
 
+    pub fn pipeline(&self) -> crate::Result<Option<Template>> {
+        Ok(self
+            .pipeline
+            .as_deref()
+            .map(|value| Template::try_from(value).context(PipelineTemplateSnafu))
+            .transpose()?)
+    }
+
     pub fn common_mode(&self) -> crate::Result<ElasticsearchCommonMode> {
         match self.mode {
             ElasticsearchMode::Bulk => {
@@ -119,9 +128,21 @@ impl ElasticsearchConfig {
                     action: bulk_action,
                 })
             }
-            ElasticsearchMode::DataStream => Ok(ElasticsearchCommonMode::DataStream(
-                self.data_stream.clone().unwrap_or_default(),
-            )),
+            ElasticsearchMode::DataStream => {
+                // Data streams are append only, so documents are always sent with the `create`
+                // action, whatever the bulk action.
+                if let Some(action) = self.bulk.as_ref().and_then(|bulk| bulk.action.as_ref()) {
+                    if action != BulkAction::Create.as_str() {
+                        return Err(ParseError::DataStreamBulkAction {
+                            action: action.clone(),
+                        }
+                        .into());
+                    }
+                }
+                Ok(ElasticsearchCommonMode::DataStream(
+                    self.data_stream.clone().unwrap_or_default(),
+                ))
+            }
         }
     }
 }
@@ -339,6 +360,7 @@ impl SinkConfig for ElasticsearchConfig {
             metric_to_log: common.metric_to_log,
             mode: common.mode,
             id_key_field: self.id_key.clone(),
+            pipeline: common.pipeline,
         };
 
         let common = ElasticsearchCommon::parse_config(self)?;
@@ -403,4 +425,40 @@ mod tests {
         assert!(matches!(config.mode, ElasticsearchMode::DataStream));
         assert!(config.data_stream.is_some());
     }
+
+    #[test]
+    fn data_stream_mode_rejects_index_action() {
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoint = ""
+            mode = "data_stream"
+            bulk.action = "index"
+        "#,
+        )
+        .unwrap();
+        assert!(config.common_mode().is_err());
+
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoint = ""
+            mode = "data_stream"
+            bulk.action = "create"
+        "#,
+        )
+        .unwrap();
+        assert!(config.common_mode().is_ok());
+    }
+
+    #[test]
+    fn rejects_request_compression() {
+        let config = toml::from_str::<ElasticsearchConfig>(
+            r#"
+            endpoint = "http://localhost:9200"
+            request.compression.algorithms = ["gzip"]
+        "#,
+        )
+        .unwrap();
+        let error = ElasticsearchCommon::parse_config(&config).err().unwrap();
+        assert!(error.to_string().contains("request.compression"));
+    }
 }
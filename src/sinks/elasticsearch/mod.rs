@@ -171,6 +171,17 @@ pub enum ParseError {
     IndexTemplate { source: TemplateParseError },
     #[snafu(display("Batch action template parse error: {}", source))]
     BatchActionTemplate { source: TemplateParseError },
+    #[snafu(display("Pipeline template parse error: {}", source))]
+    PipelineTemplate { source: TemplateParseError },
+    #[snafu(display(
+        "Data streams only accept the \"create\" action, {:?} can't be used as the bulk action",
+        action
+    ))]
+    DataStreamBulkAction { action: String },
+    #[snafu(display(
+        "`request.compression` is only supported by the `http` sink, use `compression` instead"
+    ))]
+    RequestCompression,
 }
 
 async fn finish_signer(
@@ -10,12 +10,12 @@ use crate::{
 };
 
 #[derive(Deserialize, Debug)]
-struct EsResultResponse {
-    items: Vec<EsResultItem>,
+pub(super) struct EsResultResponse {
+    pub(super) items: Vec<EsResultItem>,
 }
 
 #[derive(Deserialize, Debug)]
-enum EsResultItem {
+pub(super) enum EsResultItem {
     #[serde(rename = "index")]
     Index(EsIndexResult),
     #[serde(rename = "create")]
@@ -24,7 +24,7 @@ enum EsResultItem {
 
 impl EsResultItem {
     #[allow(clippy::missing_const_for_fn)] // const cannot run destructor
-    fn result(self) -> EsIndexResult {
+    pub(super) fn result(self) -> EsIndexResult {
         match self {
             EsResultItem::Index(r) => r,
             EsResultItem::Create(r) => r,
@@ -33,15 +33,18 @@ impl EsResultItem {
 }
 
 #[derive(Deserialize, Debug)]
-struct EsIndexResult {
-    error: Option<EsErrorDetails>,
+pub(super) struct EsIndexResult {
+    #[serde(rename = "_index")]
+    pub(super) index: Option<String>,
+    pub(super) status: Option<u16>,
+    pub(super) error: Option<EsErrorDetails>,
 }
 
 #[derive(Deserialize, Debug)]
-struct EsErrorDetails {
-    reason: String,
+pub(super) struct EsErrorDetails {
+    pub(super) reason: String,
     #[serde(rename = "type")]
-    err_type: String,
+    pub(super) err_type: String,
 }
 
 #[derive(Clone)]
@@ -137,6 +140,24 @@ mod tests {
         assert_eq!(reason, "error type: illegal_argument_exception, reason: mapper [message] of different type, current_type [long], merged_type [text]");
     }
 
+    #[test]
+    fn parses_rejected_documents() {
+        let json = "{\"took\":3,\"errors\":true,\"items\":[{\"create\":{\"_index\":\".ds-logs-generic-default-000001\",\"_id\":\"1\",\"status\":201}},{\"create\":{\"_index\":\"logs-generic-default\",\"status\":400,\"error\":{\"type\":\"mapper_parsing_exception\",\"reason\":\"failed to parse field [status]\"}}}]}";
+        let results = serde_json::from_str::<EsResultResponse>(json)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(EsResultItem::result)
+            .collect::<Vec<_>>();
+
+        assert!(results[0].error.is_none());
+        assert_eq!(results[1].index.as_deref(), Some("logs-generic-default"));
+        assert_eq!(results[1].status, Some(400));
+        let error = results[1].error.as_ref().unwrap();
+        assert_eq!(error.err_type, "mapper_parsing_exception");
+        assert_eq!(error.reason, "failed to parse field [status]");
+    }
+
     #[test]
     fn get_create_error_reason() {
         let json = "{\"took\":3,\"errors\":true,\"items\":[{\"create\":{\"_index\":\"test-hgw28jv10u\",\"_type\":\"_doc\",\"_id\":\"aBLq1HcBWD7eBWkW2nj4\",\"status\":400,\"error\":{\"type\":\"mapper_parsing_exception\",\"reason\":\"object mapping for [host] tried to parse field [host] as object, but found a concrete value\"}}}]}";
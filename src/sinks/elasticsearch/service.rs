@@ -22,10 +22,13 @@ use crate::{
     aws::rusoto::AwsCredentialsProvider,
     event::{EventFinalizers, EventStatus, Finalizable},
     http::{Auth, HttpClient},
-    internal_events::ElasticsearchResponseError,
-    sinks::util::{
-        http::{HttpBatchService, RequestConfig},
-        Compression, ElementCount,
+    internal_events::{ElasticsearchDocumentRejected, ElasticsearchResponseError},
+    sinks::{
+        elasticsearch::retry::{EsResultItem, EsResultResponse},
+        util::{
+            http::{HttpBatchService, RequestConfig},
+            Compression, ElementCount,
+        },
     },
 };
 
@@ -239,6 +242,7 @@ fn get_event_status(response: &Response<Bytes>) -> EventStatus {
                 response,
                 message: "Response containerd errors.",
             });
+            emit_rejected_documents(&body);
             EventStatus::Rejected
         } else {
             EventStatus::Delivered
@@ -257,3 +261,22 @@ fn get_event_status(response: &Response<Bytes>) -> EventStatus {
         EventStatus::Rejected
     }
 }
+
+/// Emits the errors of the documents the bulk request was partially rejected for.
+fn emit_rejected_documents(body: &str) {
+    let response = match serde_json::from_str::<EsResultResponse>(body) {
+        Ok(response) => response,
+        // The reason of the first error is logged by the retry logic regardless.
+        Err(_) => return,
+    };
+    for result in response.items.into_iter().map(EsResultItem::result) {
+        if let Some(error) = &result.error {
+            emit!(&ElasticsearchDocumentRejected {
+                index: result.index.as_deref(),
+                status: result.status,
+                rejection_type: &error.err_type,
+                reason: &error.reason,
+            });
+        }
+    }
+}
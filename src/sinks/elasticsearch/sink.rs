@@ -7,6 +7,7 @@ use vector_core::{buffers::Acker, stream::BatcherSettings, ByteSizeOf};
 
 use crate::{
     event::{Event, LogEvent, Value},
+    internal_events::TemplateRenderingError,
     sinks::{
         elasticsearch::{
             encoder::ProcessedEvent,
@@ -16,6 +17,7 @@ use crate::{
         },
         util::{Compression, SinkBuilderExt, StreamSink},
     },
+    template::Template,
     transforms::metric_to_log::MetricToLog,
     Error,
 };
@@ -46,6 +48,7 @@ pub struct ElasticsearchSink {
     pub metric_to_log: MetricToLog,
     pub mode: ElasticsearchCommonMode,
     pub id_key_field: Option<String>,
+    pub pipeline: Option<Template>,
 }
 
 impl ElasticsearchSink {
@@ -54,6 +57,7 @@ impl ElasticsearchSink {
 
         let mode = self.mode;
         let id_key_field = self.id_key_field;
+        let pipeline = self.pipeline;
 
         let sink = input
             .scan(self.metric_to_log, |metric_to_log, event| {
@@ -64,7 +68,7 @@ impl ElasticsearchSink {
                 }))
             })
             .filter_map(|x| async move { x })
-            .filter_map(move |log| future::ready(process_log(log, &mode, &id_key_field)))
+            .filter_map(move |log| future::ready(process_log(log, &mode, &id_key_field, &pipeline)))
             .batched(self.batch_settings.into_byte_size_config())
             .request_builder(request_builder_concurrency_limit, self.request_builder)
             .filter_map(|request| async move {
@@ -86,9 +90,25 @@ pub fn process_log(
     mut log: LogEvent,
     mode: &ElasticsearchCommonMode,
     id_key_field: &Option<String>,
+    pipeline: &Option<Template>,
 ) -> Option<ProcessedEvent> {
     let index = mode.index(&log)?;
     let bulk_action = mode.bulk_action(&log)?;
+    let pipeline = match pipeline {
+        Some(template) => Some(
+            template
+                .render_string(&log)
+                .map_err(|error| {
+                    emit!(&TemplateRenderingError {
+                        error,
+                        field: Some("pipeline"),
+                        drop_event: true,
+                    });
+                })
+                .ok()?,
+        ),
+        None => None,
+    };
 
     if let Some(cfg) = mode.as_data_stream_config() {
         cfg.sync_fields(&mut log);
@@ -107,6 +127,7 @@ pub fn process_log(
         bulk_action,
         log,
         id,
+        pipeline,
     })
 }
 
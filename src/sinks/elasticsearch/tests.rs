@@ -47,7 +47,7 @@ fn sets_create_action_when_configured() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -94,7 +94,7 @@ fn encode_datastream_mode() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -138,7 +138,7 @@ fn encode_datastream_mode_no_routing() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -172,7 +172,7 @@ fn handle_metrics() {
     let mut encoded = vec![];
     es.encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -260,7 +260,7 @@ fn encode_datastream_mode_no_sync() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -296,7 +296,7 @@ fn allows_using_excepted_fields() {
     let encoded_size = es
         .encoding
         .encode_input(
-            vec![process_log(log, &es.mode, &None).unwrap()],
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
             &mut encoded,
         )
         .unwrap();
@@ -308,6 +308,57 @@ fn allows_using_excepted_fields() {
     assert_eq!(encoded.len(), encoded_size);
 }
 
+#[test]
+fn sets_pipeline_of_documents() {
+    let config = ElasticsearchConfig {
+        bulk: Some(BulkConfig {
+            action: None,
+            index: Some(String::from("vector")),
+        }),
+        pipeline: Some(String::from("{{ service }}-pipeline")),
+        encoding: EncodingConfigFixed {
+            except_fields: Some(vec!["timestamp".to_string()]),
+            ..Default::default()
+        },
+        endpoint: String::from("https://example.com"),
+        ..Default::default()
+    };
+    let es = ElasticsearchCommon::parse_config(&config).unwrap();
+    assert!(!es.query_params.contains_key("pipeline"));
+
+    let mut log = LogEvent::from("hello there");
+    log.insert("service", "nginx");
+
+    let mut encoded = vec![];
+    es.encoding
+        .encode_input(
+            vec![process_log(log, &es.mode, &None, &es.pipeline).unwrap()],
+            &mut encoded,
+        )
+        .unwrap();
+
+    let expected = r#"{"index":{"_index":"vector","_type":"","pipeline":"nginx-pipeline"}}
+{"message":"hello there","service":"nginx"}
+"#;
+    assert_eq!(std::str::from_utf8(&encoded).unwrap(), expected);
+
+    // Events the pipeline can't be rendered for are dropped.
+    let log = LogEvent::from("hello there");
+    assert!(process_log(log, &es.mode, &None, &es.pipeline).is_none());
+}
+
+#[test]
+fn sets_static_pipeline_in_query() {
+    let config = ElasticsearchConfig {
+        pipeline: Some(String::from("my-pipeline")),
+        endpoint: String::from("https://example.com"),
+        ..Default::default()
+    };
+    let es = ElasticsearchCommon::parse_config(&config).unwrap();
+    assert_eq!(es.query_params["pipeline"], "my-pipeline");
+    assert!(es.pipeline.is_none());
+}
+
 #[test]
 fn validate_host_header_on_aws_requests() {
     let mut batch = BatchConfig::default();
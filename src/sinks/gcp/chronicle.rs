@@ -0,0 +1,507 @@
+use std::num::NonZeroU64;
+
+use bytes::Bytes;
+use futures::{FutureExt, SinkExt};
+use http::{Request, Uri};
+use hyper::Body;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    config::{log_schema, AcknowledgementsConfig, Input, SinkConfig, SinkContext, SinkDescription},
+    event::{self, Event},
+    gcp::{GcpAuthConfig, GcpCredentials, Scope},
+    http::HttpClient,
+    internal_events::TemplateRenderingError,
+    sinks::{
+        gcs_common::config::healthcheck_response,
+        util::{
+            encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+            http::{HttpEventEncoder, HttpSink, PartitionHttpSink},
+            BatchConfig, BoxedRawValue, JsonArrayBuffer, PartitionBuffer, PartitionInnerBuffer,
+            SinkBatchSettings, TowerRequestConfig,
+        },
+        Healthcheck, UriParseSnafu, VectorSink,
+    },
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+
+#[derive(Debug, Snafu)]
+enum ChronicleError {
+    #[snafu(display("The unstructured API requires a `log_type`"))]
+    MissingLogType,
+    #[snafu(display("Chronicle ingestion API not found"))]
+    NotFound,
+}
+
+// 1MB maximum request size: https://cloud.google.com/chronicle/docs/reference/ingestion-api
+const MAX_BATCH_PAYLOAD_SIZE: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChronicleDefaultBatchSettings;
+
+impl SinkBatchSettings for ChronicleDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = None;
+    const MAX_BYTES: Option<usize> = Some(1_000_000);
+    const TIMEOUT_SECS: NonZeroU64 = unsafe { NonZeroU64::new_unchecked(15) };
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ChronicleConfig {
+    pub customer_id: String,
+    #[serde(default)]
+    pub api: ChronicleApi,
+    /// The log type of the unstructured entries, which selects the parser applied by Chronicle.
+    pub log_type: Option<Template>,
+    #[serde(default)]
+    pub region: ChronicleRegion,
+    /// Overrides the endpoint of the region.
+    pub endpoint: Option<String>,
+    #[serde(flatten)]
+    pub auth: GcpAuthConfig,
+    #[serde(
+        skip_serializing_if = "crate::serde::skip_serializing_if_default",
+        default
+    )]
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+
+    #[serde(default)]
+    pub batch: BatchConfig<ChronicleDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    pub tls: Option<TlsOptions>,
+
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ChronicleApi {
+    /// Raw log lines, parsed by Chronicle according to their log type.
+    #[derivative(Default)]
+    Unstructured,
+    /// Events already in the Unified Data Model.
+    Udm,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum ChronicleRegion {
+    #[derivative(Default)]
+    Us,
+    Eu,
+    Uk,
+    Asia,
+}
+
+impl ChronicleRegion {
+    const fn endpoint(self) -> &'static str {
+        match self {
+            Self::Us => "https://malachiteingestion-pa.googleapis.com",
+            Self::Eu => "https://europe-malachiteingestion-pa.googleapis.com",
+            Self::Uk => "https://europe-west2-malachiteingestion-pa.googleapis.com",
+            Self::Asia => "https://asia-southeast1-malachiteingestion-pa.googleapis.com",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum Encoding {
+    /// Sends the message of the unstructured entries.
+    #[derivative(Default)]
+    Text,
+    /// Sends the whole event as JSON in the unstructured entries.
+    Json,
+}
+
+inventory::submit! {
+    SinkDescription::new::<ChronicleConfig>("gcp_chronicle")
+}
+
+impl_generate_config_from_default!(ChronicleConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_chronicle")]
+impl SinkConfig for ChronicleConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.api == ChronicleApi::Unstructured && self.log_type.is_none() {
+            return Err(ChronicleError::MissingLogType.into());
+        }
+
+        let sink = ChronicleSink::from_config(self).await?;
+        let batch_settings = self
+            .batch
+            .validate()?
+            .limit_max_bytes(MAX_BATCH_PAYLOAD_SIZE)?
+            .into_batch_settings()?;
+        let request_settings = self.request.unwrap_with(&Default::default());
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?;
+
+        let healthcheck = healthcheck(
+            client.clone(),
+            sink.uri("/v2/logtypes")?,
+            sink.creds.clone(),
+        )
+        .boxed();
+
+        let sink = PartitionHttpSink::new(
+            sink,
+            PartitionBuffer::new(JsonArrayBuffer::new(batch_settings.size)),
+            request_settings,
+            batch_settings.timeout,
+            client,
+            cx.acker(),
+        )
+        .sink_map_err(|error| error!(message = "Fatal gcp_chronicle sink error.", %error));
+
+        Ok((VectorSink::from_event_sink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "gcp_chronicle"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+struct ChronicleSink {
+    api_key: Option<String>,
+    creds: Option<GcpCredentials>,
+    uri_base: String,
+    customer_id: String,
+    api: ChronicleApi,
+    log_type: Option<Template>,
+    encoding: EncodingConfigWithDefault<Encoding>,
+}
+
+impl ChronicleSink {
+    async fn from_config(config: &ChronicleConfig) -> crate::Result<Self> {
+        // The ingestion API isn't covered by a narrower scope that goauth knows about.
+        let creds = config.auth.make_credentials(Scope::CloudPlatform).await?;
+
+        let uri_base = match config.endpoint.as_ref() {
+            Some(host) => host.trim_end_matches('/').to_string(),
+            None => config.region.endpoint().into(),
+        };
+
+        Ok(Self {
+            api_key: config.auth.api_key.clone(),
+            creds,
+            uri_base,
+            customer_id: config.customer_id.clone(),
+            api: config.api,
+            log_type: config.log_type.clone(),
+            encoding: config.encoding.clone(),
+        })
+    }
+
+    fn uri(&self, path: &str) -> crate::Result<Uri> {
+        let mut uri = format!("{}{}", self.uri_base, path);
+        if let Some(key) = &self.api_key {
+            uri = format!("{}?key={}", uri, key);
+        }
+        uri.parse::<Uri>()
+            .context(UriParseSnafu)
+            .map_err(Into::into)
+    }
+}
+
+struct ChronicleEventEncoder {
+    api: ChronicleApi,
+    log_type: Option<Template>,
+    encoding: EncodingConfigWithDefault<Encoding>,
+}
+
+impl HttpEventEncoder<PartitionInnerBuffer<Value, Option<String>>> for ChronicleEventEncoder {
+    fn encode_event(
+        &mut self,
+        mut event: Event,
+    ) -> Option<PartitionInnerBuffer<Value, Option<String>>> {
+        if self.api == ChronicleApi::Udm {
+            self.encoding.apply_rules(&mut event);
+            return Some(PartitionInnerBuffer::new(json!(event.into_log()), None));
+        }
+
+        // Entries of each log type are sent in their own requests.
+        let log_type = self
+            .log_type
+            .as_ref()
+            .expect("validated when building")
+            .render_string(&event)
+            .map_err(|error| {
+                emit!(&TemplateRenderingError {
+                    error,
+                    field: Some("log_type"),
+                    drop_event: true,
+                });
+            })
+            .ok()?;
+
+        self.encoding.apply_rules(&mut event);
+        let log = event.into_log();
+
+        let log_text = match self.encoding.codec() {
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|message| message.to_string_lossy())
+                .unwrap_or_default(),
+            Encoding::Json => serde_json::to_string(&log).unwrap(),
+        };
+        let mut entry = serde_json::Map::with_capacity(2);
+        entry.insert("log_text".into(), json!(log_text));
+        if let Some(event::Value::Timestamp(timestamp)) = log.get(log_schema().timestamp_key()) {
+            entry.insert(
+                "ts_rfc3339".into(),
+                json!(timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)),
+            );
+        }
+
+        Some(PartitionInnerBuffer::new(entry.into(), Some(log_type)))
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpSink for ChronicleSink {
+    type Input = PartitionInnerBuffer<Value, Option<String>>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, Option<String>>;
+    type Encoder = ChronicleEventEncoder;
+
+    fn build_encoder(&self) -> Self::Encoder {
+        ChronicleEventEncoder {
+            api: self.api,
+            log_type: self.log_type.clone(),
+            encoding: self.encoding.clone(),
+        }
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<Request<Bytes>> {
+        let (events, log_type) = output.into_parts();
+        let (path, body) = match log_type {
+            Some(log_type) => (
+                "/v2/unstructuredlogentries:batchCreate",
+                json!({
+                    "customer_id": self.customer_id,
+                    "log_type": log_type,
+                    "entries": events,
+                }),
+            ),
+            None => (
+                "/v2/udmevents:batchCreate",
+                json!({
+                    "customer_id": self.customer_id,
+                    "events": events,
+                }),
+            ),
+        };
+        let body = crate::serde::json::to_bytes(&body).unwrap().freeze();
+
+        let mut request = Request::post(self.uri(path)?)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .unwrap();
+        if let Some(creds) = &self.creds {
+            creds.apply(&mut request);
+        }
+
+        Ok(request)
+    }
+}
+
+async fn healthcheck(
+    client: HttpClient,
+    uri: Uri,
+    creds: Option<GcpCredentials>,
+) -> crate::Result<()> {
+    let mut request = Request::get(uri).body(Body::empty()).unwrap();
+    if let Some(creds) = creds.as_ref() {
+        creds.apply(&mut request);
+    }
+
+    let response = client.send(request).await?;
+    healthcheck_response(creds, ChronicleError::NotFound.into())(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use futures::StreamExt;
+    use http::StatusCode;
+    use indoc::indoc;
+    use vector_core::event::{BatchNotifier, BatchStatus, LogEvent};
+
+    use super::*;
+    use crate::{
+        sinks::util::test::{build_test_server_status, load_sink},
+        test_util::next_addr,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ChronicleConfig>();
+    }
+
+    #[tokio::test]
+    async fn requires_log_type() {
+        let config: ChronicleConfig = toml::from_str(indoc! {r#"
+                customer_id = "customer"
+                api_key = "key"
+            "#})
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
+    #[test]
+    fn encodes_unstructured_entries() {
+        let (config, _) = load_sink::<ChronicleConfig>(indoc! {r#"
+                customer_id = "customer"
+                log_type = "{{ source }}"
+            "#})
+        .unwrap();
+        let mut encoder = ChronicleEventEncoder {
+            api: config.api,
+            log_type: config.log_type,
+            encoding: config.encoding,
+        };
+
+        let mut log = LogEvent::from("login failed");
+        log.insert("source", "LINUX");
+        log.insert(
+            log_schema().timestamp_key(),
+            chrono::Utc.ymd(2022, 3, 1).and_hms_milli(12, 30, 0, 250),
+        );
+        let (entry, log_type) = encoder.encode_event(log.into()).unwrap().into_parts();
+        assert_eq!(log_type.as_deref(), Some("LINUX"));
+        assert_eq!(
+            entry,
+            json!({
+                "log_text": "login failed",
+                "ts_rfc3339": "2022-03-01T12:30:00.250Z",
+            })
+        );
+
+        // Events whose log type can't be rendered are dropped.
+        assert!(encoder
+            .encode_event(LogEvent::from("no source").into())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn routes_log_types() {
+        let addr = next_addr();
+        let (mut config, cx) = load_sink::<ChronicleConfig>(indoc! {r#"
+                customer_id = "customer"
+                log_type = "{{ source }}"
+                api_key = "key"
+                encoding.codec = "json"
+                encoding.only_fields = ["message"]
+            "#})
+        .unwrap();
+        config.endpoint = Some(format!("http://{}", addr));
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (rx, _trigger, server) = build_test_server_status(addr, StatusCode::OK);
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let events = ["LINUX", "WINEVTLOG", "LINUX"]
+            .iter()
+            .enumerate()
+            .map(|(i, source)| {
+                let mut log = LogEvent::from(format!("line {}", i)).with_batch_notifier(&batch);
+                log.insert("source", *source);
+                log.into()
+            })
+            .collect::<Vec<Event>>();
+        drop(batch);
+        sink.run_events(events).await.unwrap();
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let mut bodies = rx
+            .take(2)
+            .map(|(parts, body)| {
+                assert_eq!(
+                    parts.uri.path_and_query().unwrap().as_str(),
+                    "/v2/unstructuredlogentries:batchCreate?key=key"
+                );
+                serde_json::from_slice::<Value>(&body).unwrap()
+            })
+            .collect::<Vec<_>>()
+            .await;
+        bodies.sort_by_key(|body| body["log_type"].to_string());
+        assert_eq!(
+            bodies,
+            vec![
+                json!({
+                    "customer_id": "customer",
+                    "log_type": "LINUX",
+                    "entries": [
+                        { "log_text": r#"{"message":"line 0"}"# },
+                        { "log_text": r#"{"message":"line 2"}"# },
+                    ],
+                }),
+                json!({
+                    "customer_id": "customer",
+                    "log_type": "WINEVTLOG",
+                    "entries": [{ "log_text": r#"{"message":"line 1"}"# }],
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_udm_events() {
+        let addr = next_addr();
+        let (mut config, cx) = load_sink::<ChronicleConfig>(indoc! {r#"
+                customer_id = "customer"
+                api = "udm"
+                api_key = "key"
+                encoding.except_fields = ["timestamp"]
+            "#})
+        .unwrap();
+        config.endpoint = Some(format!("http://{}/", addr));
+        let (sink, _) = config.build(cx).await.unwrap();
+
+        let (mut rx, _trigger, server) = build_test_server_status(addr, StatusCode::OK);
+        tokio::spawn(server);
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let mut log = LogEvent::from("user login").with_batch_notifier(&batch);
+        log.insert("metadata.event_type", "USER_LOGIN");
+        drop(batch);
+        sink.run_events(vec![log.into()]).await.unwrap();
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let (parts, body) = rx.next().await.unwrap();
+        assert_eq!(parts.uri.path(), "/v2/udmevents:batchCreate");
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!({
+                "customer_id": "customer",
+                "events": [{
+                    "message": "user login",
+                    "metadata": { "event_type": "USER_LOGIN" },
+                }],
+            })
+        );
+    }
+}
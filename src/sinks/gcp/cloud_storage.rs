@@ -14,12 +14,12 @@ use tower::ServiceBuilder;
 use uuid::Uuid;
 use vector_core::{event::Finalizable, ByteSizeOf};
 
-use super::{GcpAuthConfig, GcpCredentials, Scope};
 use crate::{
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
     },
     event::Event,
+    gcp::{GcpAuthConfig, GcpCredentials, Scope},
     http::HttpClient,
     serde::json::to_string,
     sinks::{
@@ -33,7 +33,7 @@ use crate::{
         },
         util::{
             batch::BatchConfig,
-            encoding::{EncodingConfig, EncodingConfiguration, StandardEncodings},
+            encoding::{BatchEncoder, EncodingConfig, ParquetConfig, StandardEncodings},
             partitioner::KeyPartitioner,
             BulkSizeBasedDefaultBatchSettings, Compression, RequestBuilder, ServiceBuilderExt,
             TowerRequestConfig,
@@ -58,6 +58,11 @@ pub struct GcsSinkConfig {
     filename_append_uuid: Option<bool>,
     filename_extension: Option<String>,
     encoding: EncodingConfig<StandardEncodings>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    parquet: Option<ParquetConfig>,
     #[serde(default)]
     compression: Compression,
     #[serde(default)]
@@ -87,6 +92,7 @@ fn default_config(e: StandardEncodings) -> GcsSinkConfig {
         filename_append_uuid: Default::default(),
         filename_extension: Default::default(),
         encoding: e.into(),
+        parquet: Default::default(),
         compression: Compression::gzip_default(),
         batch: Default::default(),
         request: Default::default(),
@@ -195,14 +201,14 @@ struct RequestSettings {
     extension: String,
     time_format: String,
     append_uuid: bool,
-    encoding: EncodingConfig<StandardEncodings>,
+    encoding: BatchEncoder,
     compression: Compression,
 }
 
 impl RequestBuilder<(String, Vec<Event>)> for RequestSettings {
     type Metadata = GcsMetadata;
     type Events = Vec<Event>;
-    type Encoder = EncodingConfig<StandardEncodings>;
+    type Encoder = BatchEncoder;
     type Payload = Bytes;
     type Request = GcsRequest;
     type Error = io::Error; // TODO: this is ugly.
@@ -264,9 +270,15 @@ impl RequestSettings {
         let acl = config
             .acl
             .map(|acl| HeaderValue::from_str(&to_string(acl)).unwrap());
-        let content_type = HeaderValue::from_str(config.encoding.codec().content_type()).unwrap();
-        let content_encoding = config
-            .compression
+        let encoding = BatchEncoder::new(&config.encoding, config.parquet.as_ref());
+        // Parquet files compress their columns themselves.
+        let compression = if encoding.is_parquet() {
+            Compression::None
+        } else {
+            config.compression
+        };
+        let content_type = HeaderValue::from_str(encoding.content_type()).unwrap();
+        let content_encoding = compression
             .content_encoding()
             .map(|ce| HeaderValue::from_str(&to_string(ce)).unwrap());
         let storage_class = config.storage_class.unwrap_or_default();
@@ -281,10 +293,13 @@ impl RequestSettings {
                     .collect::<Result<Vec<_>, _>>()
             })
             .unwrap_or_else(|| Ok(vec![]))?;
-        let extension = config
-            .filename_extension
-            .clone()
-            .unwrap_or_else(|| config.compression.extension().into());
+        let extension = config.filename_extension.clone().unwrap_or_else(|| {
+            if encoding.is_parquet() {
+                "parquet".into()
+            } else {
+                compression.extension().into()
+            }
+        });
         let time_format = config
             .filename_time_format
             .clone()
@@ -299,8 +314,8 @@ impl RequestSettings {
             extension,
             time_format,
             append_uuid,
-            compression: config.compression,
-            encoding: config.encoding.clone(),
+            compression,
+            encoding,
         })
     }
 }
@@ -318,6 +333,7 @@ mod tests {
     use vector_core::partition::Partitioner;
 
     use super::*;
+    use crate::sinks::util::encoding::PARQUET_CONTENT_TYPE;
 
     #[test]
     fn generate_config() {
@@ -383,4 +399,24 @@ mod tests {
         let req = build_request(None, true, Compression::gzip_default());
         assert_ne!(req.metadata.key, "key/date.log.gz".to_string());
     }
+
+    #[test]
+    fn gcs_build_parquet_request() {
+        let log = Event::new_empty_log();
+        let sink_config = GcsSinkConfig {
+            key_prefix: Some("key/".into()),
+            filename_time_format: Some("date".into()),
+            filename_append_uuid: Some(false),
+            parquet: Some(ParquetConfig::default()),
+            ..default_config(StandardEncodings::Ndjson)
+        };
+        let request_settings = request_settings(&sink_config);
+        assert_eq!(request_settings.compression(), Compression::None);
+
+        let (metadata, _events) = request_settings.split_input(("key/".into(), vec![log]));
+        let req = request_settings.build_request(metadata, Bytes::new());
+        assert_eq!(req.metadata.key, "key/date.parquet".to_string());
+        assert_eq!(req.settings.content_type, PARQUET_CONTENT_TYPE);
+        assert_eq!(req.settings.content_encoding, None);
+    }
 }
@@ -0,0 +1,213 @@
+use std::{convert::TryFrom, path::PathBuf, sync::Arc};
+
+use bytes::BytesMut;
+use http::{header::HeaderName, uri::PathAndQuery, HeaderMap, HeaderValue, Uri};
+use indexmap::IndexMap;
+use prost::Message;
+use prost_types::FileDescriptorSet;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tower::ServiceBuilder;
+
+use super::{
+    descriptor::Descriptors,
+    encoding::{put_length_delimited, MessageEncoder},
+    service::{GrpcRetryLogic, GrpcService, HttpsClient, HyperSvc},
+    sink::GrpcSink,
+    DecodeDescriptorSetSnafu, GrpcError, ReadDescriptorSetSnafu,
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext,
+        SinkDescription,
+    },
+    http::build_proxy_connector,
+    sinks::{
+        util::{
+            BatchConfig, RealtimeEventBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    tls::{MaybeTlsSettings, TlsConfig},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcConfig {
+    /// The address of the server, such as `http://localhost:50051`.
+    pub address: String,
+    /// A file descriptor set holding the service of the method and the types of its messages,
+    /// as written by `protoc --include_imports --descriptor_set_out`.
+    pub descriptor_set_file: PathBuf,
+    /// The method called, such as `package.Service/Method`.
+    pub method: String,
+    /// The paths of the fields of the messages, and the fields of the events they are read from.
+    /// The fields of the events with the names of the fields of the messages are used otherwise.
+    #[serde(default)]
+    pub fields: IndexMap<String, String>,
+    /// The metadata sent along with each request.
+    #[serde(default)]
+    pub headers: IndexMap<String, String>,
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeEventBasedDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    pub tls: Option<TlsConfig>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+inventory::submit! {
+    SinkDescription::new::<GrpcConfig>("grpc")
+}
+
+impl GenerateConfig for GrpcConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "http://127.0.0.1:50051"
+            descriptor_set_file = "/etc/vector/service.desc"
+            method = "package.Service/Method""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "grpc")]
+impl SinkConfig for GrpcConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let bytes =
+            std::fs::read(&self.descriptor_set_file).with_context(|_| ReadDescriptorSetSnafu {
+                path: self.descriptor_set_file.clone(),
+            })?;
+        let set = FileDescriptorSet::decode(&bytes[..]).context(DecodeDescriptorSetSnafu)?;
+        let descriptors = Arc::new(Descriptors::new(set));
+
+        let method_path = format!("/{}", self.method.trim_start_matches('/'));
+        let path = PathAndQuery::try_from(method_path.as_str())
+            .ok()
+            .filter(|path| path.path().matches('/').count() == 2)
+            .ok_or_else(|| GrpcError::InvalidMethod {
+                method: self.method.clone(),
+            })?;
+        let method = descriptors
+            .method(&method_path)
+            .ok_or_else(|| GrpcError::UnknownMethod {
+                method: self.method.clone(),
+            })?;
+        if method.server_streaming() {
+            return Err(GrpcError::ServerStreaming {
+                method: self.method.clone(),
+            }
+            .into());
+        }
+        let client_streaming = method.client_streaming();
+        let encoder = MessageEncoder::new(
+            Arc::clone(&descriptors),
+            method.input_type().to_owned(),
+            self.fields.clone(),
+        )?;
+
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let uri = address_uri(&self.address, tls.is_tls())?;
+        let client = HyperSvc {
+            uri,
+            headers: self.headers()?,
+            client: new_client(&tls, cx.proxy())?,
+        };
+
+        let service = GrpcService::new(client, path, client_streaming);
+        let service_name = self
+            .method
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+        let healthcheck = healthcheck(service.clone(), service_name);
+
+        let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request_settings, GrpcRetryLogic)
+            .service(service);
+
+        let sink = GrpcSink {
+            encoder,
+            client_streaming,
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+
+        Ok((
+            VectorSink::from_event_streamsink(sink),
+            Box::pin(healthcheck),
+        ))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "grpc"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+impl GrpcConfig {
+    fn headers(&self) -> Result<HeaderMap, GrpcError> {
+        self.headers
+            .iter()
+            .map(|(name, value)| {
+                let invalid = || GrpcError::InvalidHeader { name: name.clone() };
+                Ok((
+                    HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+                    HeaderValue::from_str(value).map_err(|_| invalid())?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Defaults the scheme of the address to `http`, or `https` with TLS.
+fn address_uri(address: &str, tls: bool) -> crate::Result<Uri> {
+    let uri: Uri = address.parse()?;
+    if uri.scheme().is_some() {
+        return Ok(uri);
+    }
+    let scheme = if tls { "https" } else { "http" };
+    Ok(format!("{}://{}", scheme, address).parse()?)
+}
+
+fn new_client(
+    tls_settings: &MaybeTlsSettings,
+    proxy_config: &ProxyConfig,
+) -> crate::Result<HttpsClient> {
+    let proxy = build_proxy_connector(tls_settings.clone(), proxy_config)?;
+    Ok(hyper::Client::builder().http2_only(true).build(proxy))
+}
+
+/// Checks the service with the standard health checking protocol, servers which don't
+/// implement it being considered healthy.
+async fn healthcheck(mut service: GrpcService, service_name: String) -> crate::Result<()> {
+    let mut request = BytesMut::new();
+    put_length_delimited(&mut request, 1, service_name.as_bytes());
+    let path = PathAndQuery::from_static("/grpc.health.v1.Health/Check");
+
+    match service.call_method(path, request.freeze()).await {
+        // The response only holds the `status` field, `SERVING` being 1.
+        Ok(response) if response[..] == [0x08, 0x01] => Ok(()),
+        Ok(_) => Err(GrpcError::Unhealthy.into()),
+        Err(GrpcError::Request { source }) if source.code() == tonic::Code::Unimplemented => Ok(()),
+        Err(error) => Err(error.into()),
+    }
+}
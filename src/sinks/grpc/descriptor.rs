@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use prost_types::{DescriptorProto, EnumDescriptorProto, FileDescriptorSet, MethodDescriptorProto};
+
+/// The messages, enums and methods of a file descriptor set, by their fully qualified names.
+#[derive(Debug, Default)]
+pub(super) struct Descriptors {
+    messages: HashMap<String, DescriptorProto>,
+    enums: HashMap<String, EnumDescriptorProto>,
+    /// The methods, by their paths such as `package.Service/Method`.
+    methods: HashMap<String, MethodDescriptorProto>,
+}
+
+impl Descriptors {
+    pub(super) fn new(set: FileDescriptorSet) -> Self {
+        let mut descriptors = Self::default();
+        for file in set.file {
+            let package = file.package().to_owned();
+            for message in file.message_type {
+                descriptors.add_message(&package, message);
+            }
+            for descriptor in file.enum_type {
+                let name = qualified_name(&package, descriptor.name());
+                descriptors.enums.insert(name, descriptor);
+            }
+            for service in file.service {
+                let service_name = qualified_name(&package, service.name());
+                for method in service.method {
+                    let path = format!("{}/{}", service_name, method.name());
+                    descriptors.methods.insert(path, method);
+                }
+            }
+        }
+        descriptors
+    }
+
+    fn add_message(&mut self, scope: &str, mut message: DescriptorProto) {
+        let name = qualified_name(scope, message.name());
+        for nested in std::mem::take(&mut message.nested_type) {
+            self.add_message(&name, nested);
+        }
+        for descriptor in std::mem::take(&mut message.enum_type) {
+            self.enums
+                .insert(qualified_name(&name, descriptor.name()), descriptor);
+        }
+        self.messages.insert(name, message);
+    }
+
+    /// Looks up a message by its name, as given in the type names of fields and methods, with or
+    /// without their leading dot.
+    pub(super) fn message(&self, name: &str) -> Option<&DescriptorProto> {
+        self.messages.get(name.trim_start_matches('.'))
+    }
+
+    pub(super) fn enum_value(&self, name: &str, value: &str) -> Option<i32> {
+        self.enums
+            .get(name.trim_start_matches('.'))?
+            .value
+            .iter()
+            .find(|descriptor| descriptor.name() == value)
+            .map(|descriptor| descriptor.number())
+    }
+
+    /// Looks up a method by its path, such as `package.Service/Method`, with or without a leading
+    /// slash.
+    pub(super) fn method(&self, path: &str) -> Option<&MethodDescriptorProto> {
+        self.methods.get(path.trim_start_matches('/'))
+    }
+}
+
+fn qualified_name(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
@@ -0,0 +1,361 @@
+use std::{collections::BTreeMap, convert::TryFrom, sync::Arc};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto,
+};
+use snafu::{OptionExt, Snafu};
+
+use super::{descriptor::Descriptors, GrpcError};
+use crate::event::{LogEvent, Value};
+
+const TIMESTAMP: &str = ".google.protobuf.Timestamp";
+
+#[derive(Debug, Snafu)]
+pub(super) enum EncodeError {
+    #[snafu(display("Field {:?} can't be encoded as {}", field, kind))]
+    InvalidValue { field: String, kind: &'static str },
+    #[snafu(display("Field {:?} has no enum value named {:?}", field, value))]
+    UnknownEnumValue { field: String, value: String },
+    #[snafu(display("Message type {:?} isn't in the descriptor set", name))]
+    UnknownMessage { name: String },
+}
+
+#[derive(Clone, Copy)]
+enum WireType {
+    Varint = 0,
+    Fixed64 = 1,
+    LengthDelimited = 2,
+    Fixed32 = 5,
+}
+
+fn put_varint(buf: &mut BytesMut, mut value: u64) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+fn put_key(buf: &mut BytesMut, number: u32, wire_type: WireType) {
+    put_varint(buf, (u64::from(number) << 3) | wire_type as u64);
+}
+
+pub(super) fn put_length_delimited(buf: &mut BytesMut, number: u32, value: &[u8]) {
+    put_key(buf, number, WireType::LengthDelimited);
+    put_varint(buf, value.len() as u64);
+    buf.put_slice(value);
+}
+
+/// Encodes events as messages of the input type of the method, reading each field of the
+/// messages from the mapped field of the events, or the field with the same name.
+#[derive(Debug)]
+pub(super) struct MessageEncoder {
+    descriptors: Arc<Descriptors>,
+    message: String,
+    /// The paths of the fields of the messages, and the paths of the fields of the events they
+    /// are read from.
+    fields: IndexMap<String, String>,
+}
+
+impl MessageEncoder {
+    pub(super) fn new(
+        descriptors: Arc<Descriptors>,
+        message: String,
+        fields: IndexMap<String, String>,
+    ) -> Result<Self, GrpcError> {
+        let descriptor =
+            descriptors
+                .message(&message)
+                .ok_or_else(|| GrpcError::UnknownMessage {
+                    name: message.clone(),
+                })?;
+        for path in fields.keys() {
+            let name = path.split('.').next().unwrap_or_default();
+            if find_field(descriptor, name).is_none() {
+                return Err(GrpcError::UnknownField {
+                    field: path.clone(),
+                    message: message.clone(),
+                });
+            }
+        }
+
+        Ok(Self {
+            descriptors,
+            message,
+            fields,
+        })
+    }
+
+    pub(super) fn encode(&self, log: &LogEvent) -> Result<Bytes, EncodeError> {
+        let mut mapped = LogEvent::default();
+        let values = if self.fields.is_empty() {
+            log.as_map()
+        } else {
+            for (field, source) in &self.fields {
+                if let Some(value) = log.get(source.as_str()) {
+                    mapped.insert(field.as_str(), value.clone());
+                }
+            }
+            mapped.as_map()
+        };
+
+        let mut buf = BytesMut::new();
+        self.encode_message(&self.message, values, &mut buf)?;
+        Ok(buf.freeze())
+    }
+
+    fn descriptor(&self, name: &str) -> Result<&DescriptorProto, EncodeError> {
+        self.descriptors
+            .message(name)
+            .context(UnknownMessageSnafu { name })
+    }
+
+    fn encode_message(
+        &self,
+        name: &str,
+        values: &BTreeMap<String, Value>,
+        buf: &mut BytesMut,
+    ) -> Result<(), EncodeError> {
+        for field in &self.descriptor(name)?.field {
+            let value = values.get(field.name()).or_else(|| {
+                field
+                    .json_name
+                    .as_ref()
+                    .and_then(|json_name| values.get(json_name))
+            });
+            match value {
+                None | Some(Value::Null) => {}
+                Some(value) => self.encode_field(field, value, buf)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        field: &FieldDescriptorProto,
+        value: &Value,
+        buf: &mut BytesMut,
+    ) -> Result<(), EncodeError> {
+        if field.label() != Label::Repeated {
+            return self.encode_value(field, value, buf);
+        }
+
+        let entry = match field.r#type() {
+            Type::Message => Some(self.descriptor(field.type_name())?).filter(|entry| {
+                entry
+                    .options
+                    .as_ref()
+                    .map_or(false, |options| options.map_entry())
+            }),
+            _ => None,
+        };
+        match (entry, value) {
+            // Maps are encoded as repeated entries, with the key as field 1 and the value as 2.
+            (Some(entry), Value::Object(object)) => {
+                let key_field = entry.field.iter().find(|field| field.number() == 1);
+                let value_field = entry.field.iter().find(|field| field.number() == 2);
+                for (key, value) in object {
+                    let mut entry_buf = BytesMut::new();
+                    if let Some(key_field) = key_field {
+                        self.encode_value(key_field, &Value::from(key.as_str()), &mut entry_buf)?;
+                    }
+                    if let (Some(value_field), false) = (value_field, value.is_null()) {
+                        self.encode_value(value_field, value, &mut entry_buf)?;
+                    }
+                    put_length_delimited(buf, field.number() as u32, &entry_buf);
+                }
+                Ok(())
+            }
+            (Some(_), _) => invalid(field, "a map"),
+            (None, Value::Array(items)) => items
+                .iter()
+                .filter(|item| !item.is_null())
+                .try_for_each(|item| self.encode_value(field, item, buf)),
+            (None, value) => self.encode_value(field, value, buf),
+        }
+    }
+
+    fn encode_value(
+        &self,
+        field: &FieldDescriptorProto,
+        value: &Value,
+        buf: &mut BytesMut,
+    ) -> Result<(), EncodeError> {
+        let number = field.number() as u32;
+        match field.r#type() {
+            Type::Double => {
+                put_key(buf, number, WireType::Fixed64);
+                buf.put_f64_le(float(field, value)?);
+            }
+            Type::Float => {
+                put_key(buf, number, WireType::Fixed32);
+                buf.put_f32_le(float(field, value)? as f32);
+            }
+            Type::Int64 => {
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, integer(field, value)? as u64);
+            }
+            Type::Int32 => {
+                // Negative values are sign extended to 64 bits.
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, i64::from(int32(field, value)?) as u64);
+            }
+            Type::Uint64 => {
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, uint64(field, value)?);
+            }
+            Type::Uint32 => {
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, u64::from(uint32(field, value)?));
+            }
+            Type::Sint64 => {
+                let value = integer(field, value)?;
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, ((value << 1) ^ (value >> 63)) as u64);
+            }
+            Type::Sint32 => {
+                let value = int32(field, value)?;
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, u64::from(((value << 1) ^ (value >> 31)) as u32));
+            }
+            Type::Fixed64 => {
+                put_key(buf, number, WireType::Fixed64);
+                buf.put_u64_le(uint64(field, value)?);
+            }
+            Type::Fixed32 => {
+                put_key(buf, number, WireType::Fixed32);
+                buf.put_u32_le(uint32(field, value)?);
+            }
+            Type::Sfixed64 => {
+                put_key(buf, number, WireType::Fixed64);
+                buf.put_i64_le(integer(field, value)?);
+            }
+            Type::Sfixed32 => {
+                put_key(buf, number, WireType::Fixed32);
+                buf.put_i32_le(int32(field, value)?);
+            }
+            Type::Bool => {
+                let value = match value {
+                    Value::Boolean(value) => *value,
+                    Value::Bytes(bytes) if &bytes[..] == b"true" => true,
+                    Value::Bytes(bytes) if &bytes[..] == b"false" => false,
+                    _ => return invalid(field, "a boolean"),
+                };
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, u64::from(value));
+            }
+            Type::Enum => {
+                let value = match value {
+                    Value::Bytes(bytes) => {
+                        let name = String::from_utf8_lossy(bytes);
+                        self.descriptors
+                            .enum_value(field.type_name(), &name)
+                            .with_context(|| UnknownEnumValueSnafu {
+                                field: field.name(),
+                                value: name.clone(),
+                            })?
+                    }
+                    value => int32(field, value)?,
+                };
+                put_key(buf, number, WireType::Varint);
+                put_varint(buf, i64::from(value) as u64);
+            }
+            Type::String | Type::Bytes => match value {
+                Value::Bytes(bytes) => put_length_delimited(buf, number, bytes),
+                Value::Object(_) | Value::Array(_) => return invalid(field, "a string"),
+                value => put_length_delimited(buf, number, value.to_string_lossy().as_bytes()),
+            },
+            Type::Message => {
+                let mut message = BytesMut::new();
+                match value {
+                    Value::Object(object) => {
+                        self.encode_message(field.type_name(), object, &mut message)?
+                    }
+                    // Timestamps are encoded without requiring their descriptor, as they are
+                    // often left out of descriptor sets.
+                    value if field.type_name() == TIMESTAMP => {
+                        let timestamp = timestamp(field, value)?;
+                        put_key(&mut message, 1, WireType::Varint);
+                        put_varint(&mut message, timestamp.timestamp() as u64);
+                        put_key(&mut message, 2, WireType::Varint);
+                        put_varint(&mut message, u64::from(timestamp.timestamp_subsec_nanos()));
+                    }
+                    _ => return invalid(field, "a message"),
+                }
+                put_length_delimited(buf, number, &message);
+            }
+            Type::Group => return invalid(field, "a group"),
+        }
+        Ok(())
+    }
+}
+
+fn find_field<'a>(descriptor: &'a DescriptorProto, name: &str) -> Option<&'a FieldDescriptorProto> {
+    descriptor
+        .field
+        .iter()
+        .find(|field| field.name() == name || field.json_name.as_deref() == Some(name))
+}
+
+fn invalid<T>(field: &FieldDescriptorProto, kind: &'static str) -> Result<T, EncodeError> {
+    Err(EncodeError::InvalidValue {
+        field: field.name().to_owned(),
+        kind,
+    })
+}
+
+fn parse<T: std::str::FromStr>(bytes: &[u8]) -> Option<T> {
+    std::str::from_utf8(bytes).ok()?.trim().parse().ok()
+}
+
+fn integer(field: &FieldDescriptorProto, value: &Value) -> Result<i64, EncodeError> {
+    match value {
+        Value::Integer(value) => Ok(*value),
+        Value::Float(value) if value.fract() == 0.0 => Ok(value.into_inner() as i64),
+        Value::Boolean(value) => Ok(i64::from(*value)),
+        // Timestamps are encoded as seconds since the Unix epoch.
+        Value::Timestamp(timestamp) => Ok(timestamp.timestamp()),
+        Value::Bytes(bytes) => parse(bytes).map_or_else(|| invalid(field, "an integer"), Ok),
+        _ => invalid(field, "an integer"),
+    }
+}
+
+fn int32(field: &FieldDescriptorProto, value: &Value) -> Result<i32, EncodeError> {
+    i32::try_from(integer(field, value)?).or_else(|_| invalid(field, "a 32 bit integer"))
+}
+
+fn uint64(field: &FieldDescriptorProto, value: &Value) -> Result<u64, EncodeError> {
+    u64::try_from(integer(field, value)?).or_else(|_| invalid(field, "an unsigned integer"))
+}
+
+fn uint32(field: &FieldDescriptorProto, value: &Value) -> Result<u32, EncodeError> {
+    u32::try_from(integer(field, value)?).or_else(|_| invalid(field, "an unsigned 32 bit integer"))
+}
+
+fn float(field: &FieldDescriptorProto, value: &Value) -> Result<f64, EncodeError> {
+    match value {
+        Value::Integer(value) => Ok(*value as f64),
+        Value::Float(value) => Ok(value.into_inner()),
+        Value::Bytes(bytes) => parse(bytes).map_or_else(|| invalid(field, "a number"), Ok),
+        _ => invalid(field, "a number"),
+    }
+}
+
+fn timestamp(field: &FieldDescriptorProto, value: &Value) -> Result<DateTime<Utc>, EncodeError> {
+    match value {
+        Value::Timestamp(timestamp) => Ok(*timestamp),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map_or_else(
+                || invalid(field, "a timestamp"),
+                |timestamp| Ok(timestamp.into()),
+            ),
+        _ => invalid(field, "a timestamp"),
+    }
+}
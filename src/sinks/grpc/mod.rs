@@ -0,0 +1,48 @@
+//! A sink calling a method of an arbitrary gRPC service with the events.
+//!
+//! The types of the messages are read from a file descriptor set, and each event is encoded as
+//! a message of the input type of the method, from the fields of the event with the names of
+//! the fields of the message or the configured mapping. Unary methods are called once per
+//! event, while client streaming methods are called once per batch, streaming its messages.
+
+use std::path::PathBuf;
+
+use snafu::Snafu;
+
+mod config;
+mod descriptor;
+mod encoding;
+mod service;
+mod sink;
+
+pub use config::GrpcConfig;
+
+#[derive(Debug, Snafu)]
+pub enum GrpcError {
+    #[snafu(display("Failed to read descriptor set {:?}: {}", path, source))]
+    ReadDescriptorSet {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[snafu(display("Failed to decode descriptor set: {}", source))]
+    DecodeDescriptorSet { source: prost::DecodeError },
+    #[snafu(display("Method {:?} isn't of the form `package.Service/Method`", method))]
+    InvalidMethod { method: String },
+    #[snafu(display("Method {:?} isn't in the descriptor set", method))]
+    UnknownMethod { method: String },
+    #[snafu(display("Method {:?} is server streaming, which isn't supported", method))]
+    ServerStreaming { method: String },
+    #[snafu(display("Message type {:?} isn't in the descriptor set", name))]
+    UnknownMessage { name: String },
+    #[snafu(display("Field {:?} isn't a field of message type {:?}", field, message))]
+    UnknownField { field: String, message: String },
+    #[snafu(display("Invalid header {:?}", name))]
+    InvalidHeader { name: String },
+    #[snafu(display("Request failed: {}", source))]
+    Request { source: tonic::Status },
+    #[snafu(display("Service isn't serving"))]
+    Unhealthy,
+}
+
+#[cfg(test)]
+mod tests;
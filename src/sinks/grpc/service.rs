@@ -0,0 +1,252 @@
+use std::task::{Context, Poll};
+
+use bytes::{Buf, BufMut, Bytes};
+use futures::{future::BoxFuture, stream};
+use http::{uri::PathAndQuery, HeaderMap, Uri};
+use tonic::{
+    body::BoxBody,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    Status,
+};
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::GrpcError;
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    http::HttpsProxyConnector,
+    internal_events::EndpointBytesSent,
+    sinks::util::{retries::RetryLogic, uri},
+};
+
+/// Passes messages encoded beforehand through, and the bytes of the responses back, as their
+/// types are only known from the descriptor set.
+#[derive(Debug, Default)]
+pub(super) struct RawCodec;
+
+impl Codec for RawCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        Self
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        Self
+    }
+}
+
+impl Encoder for RawCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn encode(&mut self, item: Bytes, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl Decoder for RawCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Bytes>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
+
+pub(super) type HttpsClient = hyper::Client<HttpsProxyConnector, BoxBody>;
+
+/// Sends the requests of the gRPC client to the address of the server, with the configured
+/// headers.
+#[derive(Clone, Debug)]
+pub(super) struct HyperSvc {
+    pub(super) uri: Uri,
+    pub(super) headers: HeaderMap,
+    pub(super) client: HttpsClient,
+}
+
+impl tower::Service<hyper::Request<BoxBody>> for HyperSvc {
+    type Response = hyper::Response<hyper::Body>;
+    type Error = hyper::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: hyper::Request<BoxBody>) -> Self::Future {
+        let mut parts = self.uri.clone().into_parts();
+        parts.path_and_query = req.uri().path_and_query().cloned();
+        *req.uri_mut() = Uri::from_parts(parts).expect("the scheme and authority are valid");
+        for (name, value) in &self.headers {
+            req.headers_mut().insert(name.clone(), value.clone());
+        }
+
+        Box::pin(self.client.request(req))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct GrpcService {
+    client: tonic::client::Grpc<HyperSvc>,
+    path: PathAndQuery,
+    client_streaming: bool,
+    protocol: String,
+    endpoint: String,
+}
+
+impl GrpcService {
+    pub(super) fn new(client: HyperSvc, path: PathAndQuery, client_streaming: bool) -> Self {
+        let (protocol, endpoint) = uri::protocol_endpoint(client.uri.clone());
+        Self {
+            client: tonic::client::Grpc::new(client),
+            path,
+            client_streaming,
+            protocol,
+            endpoint,
+        }
+    }
+
+    /// Calls a method of the server, returning the bytes of its response.
+    pub(super) async fn call_method(
+        &mut self,
+        path: PathAndQuery,
+        message: Bytes,
+    ) -> Result<Bytes, GrpcError> {
+        self.client
+            .ready()
+            .await
+            .map_err(|error| GrpcError::Request {
+                source: Status::unavailable(error.to_string()),
+            })?;
+        self.client
+            .unary(tonic::Request::new(message), path, RawCodec)
+            .await
+            .map(tonic::Response::into_inner)
+            .map_err(|source| GrpcError::Request { source })
+    }
+
+    async fn send(&mut self, messages: Vec<Bytes>) -> Result<(), GrpcError> {
+        if self.client_streaming {
+            self.client
+                .ready()
+                .await
+                .map_err(|error| GrpcError::Request {
+                    source: Status::unavailable(error.to_string()),
+                })?;
+            self.client
+                .client_streaming(
+                    tonic::Request::new(stream::iter(messages)),
+                    self.path.clone(),
+                    RawCodec,
+                )
+                .await
+                .map_err(|source| GrpcError::Request { source })?;
+        } else {
+            // Requests of unary methods hold a single message.
+            for message in messages {
+                self.call_method(self.path.clone(), message).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(super) struct GrpcRequest {
+    /// The encoded messages, sent in a single stream by client streaming methods.
+    pub(super) messages: Vec<Bytes>,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for GrpcRequest {
+    fn ack_size(&self) -> usize {
+        self.messages.len()
+    }
+}
+
+impl Finalizable for GrpcRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub(super) struct GrpcResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for GrpcResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+impl tower::Service<GrpcRequest> for GrpcService {
+    type Response = GrpcResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The readiness of the client is awaited when sending the request.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: GrpcRequest) -> Self::Future {
+        let mut service = self.clone();
+
+        Box::pin(async move {
+            let events_count = request.messages.len();
+            let byte_size = request.messages.iter().map(Bytes::len).sum();
+            service.send(request.messages).await?;
+
+            emit!(&EndpointBytesSent {
+                byte_size,
+                protocol: &service.protocol,
+                endpoint: &service.endpoint,
+            });
+            Ok(GrpcResponse {
+                events_count,
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct GrpcRetryLogic;
+
+impl RetryLogic for GrpcRetryLogic {
+    type Error = GrpcError;
+    type Response = GrpcResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        use tonic::Code::*;
+
+        match error {
+            GrpcError::Request { source } => !matches!(
+                source.code(),
+                NotFound
+                    | InvalidArgument
+                    | AlreadyExists
+                    | PermissionDenied
+                    | OutOfRange
+                    | Unimplemented
+                    | Unauthenticated
+            ),
+            _ => false,
+        }
+    }
+}
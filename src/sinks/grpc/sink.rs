@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::util::BoxService;
+use vector_core::{buffers::Acker, stream::BatcherSettings, ByteSizeOf};
+
+use super::{
+    encoding::MessageEncoder,
+    service::{GrpcRequest, GrpcResponse},
+};
+use crate::{
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    internal_events::GrpcMessageEncodingError,
+    sinks::util::{SinkBuilderExt, StreamSink},
+};
+
+struct EventData {
+    byte_size: usize,
+    finalizers: EventFinalizers,
+    message: Bytes,
+}
+
+pub(super) struct GrpcSink {
+    pub(super) encoder: MessageEncoder,
+    /// Whether the method is client streaming, sending the messages of a batch in a single
+    /// request, rather than each message in its own request.
+    pub(super) client_streaming: bool,
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) service: BoxService<GrpcRequest, GrpcResponse, crate::Error>,
+    pub(super) acker: Acker,
+}
+
+impl GrpcSink {
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+        let input = input.filter_map(move |event| future::ready(encode_event(&encoder, event)));
+
+        if self.client_streaming {
+            input
+                .batched(self.batch_settings.into_reducer_config(
+                    |data: &EventData| data.message.len(),
+                    |request: &mut GrpcRequest, data: EventData| {
+                        request.events_byte_size += data.byte_size;
+                        request.finalizers.merge(data.finalizers);
+                        request.messages.push(data.message);
+                    },
+                ))
+                .into_driver(self.service, self.acker)
+                .run()
+                .await
+        } else {
+            input
+                .map(|data| GrpcRequest {
+                    messages: vec![data.message],
+                    finalizers: data.finalizers,
+                    events_byte_size: data.byte_size,
+                })
+                .into_driver(self.service, self.acker)
+                .run()
+                .await
+        }
+    }
+}
+
+/// Encodes the event as a message, rejecting it if its fields don't match the message type.
+fn encode_event(encoder: &MessageEncoder, mut event: Event) -> Option<EventData> {
+    let byte_size = event.size_of();
+    let finalizers = event.take_finalizers();
+    match encoder.encode(event.as_log()) {
+        Ok(message) => Some(EventData {
+            byte_size,
+            finalizers,
+            message,
+        }),
+        Err(error) => {
+            emit!(&GrpcMessageEncodingError { error: &error });
+            finalizers.update_status(EventStatus::Rejected);
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSink<Event> for GrpcSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,427 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use futures::{stream, StreamExt};
+use prost::Message;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, FileDescriptorSet, MessageOptions, MethodDescriptorProto,
+    ServiceDescriptorProto,
+};
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::{
+    descriptor::Descriptors,
+    encoding::{EncodeError, MessageEncoder},
+    *,
+};
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, LogEvent, Value},
+    sinks::util::test::build_test_server_generic,
+    test_util::{components, next_addr, temp_file},
+};
+
+fn field(name: &str, number: i32, r#type: Type, type_name: Option<&str>) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.into()),
+        number: Some(number),
+        label: Some(Label::Optional as i32),
+        r#type: Some(r#type as i32),
+        type_name: type_name.map(Into::into),
+        json_name: Some(name.into()),
+        ..Default::default()
+    }
+}
+
+fn repeated(field: FieldDescriptorProto) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        label: Some(Label::Repeated as i32),
+        ..field
+    }
+}
+
+fn method(name: &str, client_streaming: bool, server_streaming: bool) -> MethodDescriptorProto {
+    MethodDescriptorProto {
+        name: Some(name.into()),
+        input_type: Some(".test.Log".into()),
+        output_type: Some(".test.Empty".into()),
+        client_streaming: Some(client_streaming),
+        server_streaming: Some(server_streaming),
+        ..Default::default()
+    }
+}
+
+/// The descriptor set of:
+///
+/// ```protobuf
+/// package test;
+///
+/// enum Level { INFO = 0; WARN = 1; }
+///
+/// message Log {
+///   message Inner { bool ok = 1; }
+///
+///   string message = 1;
+///   int64 count = 2;
+///   repeated string tags = 3;
+///   map<string, int32> labels = 4;
+///   Level level = 5;
+///   google.protobuf.Timestamp timestamp = 6;
+///   Inner inner = 7;
+///   sint32 delta = 8;
+///   double ratio = 9;
+/// }
+///
+/// message Empty {}
+///
+/// service Logs {
+///   rpc Push(Log) returns (Empty);
+///   rpc Stream(stream Log) returns (Empty);
+///   rpc Tail(Log) returns (stream Empty);
+/// }
+/// ```
+fn descriptor_set() -> FileDescriptorSet {
+    let labels_entry = DescriptorProto {
+        name: Some("LabelsEntry".into()),
+        field: vec![
+            field("key", 1, Type::String, None),
+            field("value", 2, Type::Int32, None),
+        ],
+        options: Some(MessageOptions {
+            map_entry: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let inner = DescriptorProto {
+        name: Some("Inner".into()),
+        field: vec![field("ok", 1, Type::Bool, None)],
+        ..Default::default()
+    };
+    let log = DescriptorProto {
+        name: Some("Log".into()),
+        field: vec![
+            field("message", 1, Type::String, None),
+            field("count", 2, Type::Int64, None),
+            repeated(field("tags", 3, Type::String, None)),
+            repeated(field(
+                "labels",
+                4,
+                Type::Message,
+                Some(".test.Log.LabelsEntry"),
+            )),
+            field("level", 5, Type::Enum, Some(".test.Level")),
+            field(
+                "timestamp",
+                6,
+                Type::Message,
+                Some(".google.protobuf.Timestamp"),
+            ),
+            field("inner", 7, Type::Message, Some(".test.Log.Inner")),
+            field("delta", 8, Type::Sint32, None),
+            field("ratio", 9, Type::Double, None),
+        ],
+        nested_type: vec![inner, labels_entry],
+        ..Default::default()
+    };
+    let level = EnumDescriptorProto {
+        name: Some("Level".into()),
+        value: ["INFO", "WARN"]
+            .iter()
+            .enumerate()
+            .map(|(number, name)| EnumValueDescriptorProto {
+                name: Some((*name).into()),
+                number: Some(number as i32),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("test.proto".into()),
+            package: Some("test".into()),
+            message_type: vec![
+                log,
+                DescriptorProto {
+                    name: Some("Empty".into()),
+                    ..Default::default()
+                },
+            ],
+            enum_type: vec![level],
+            service: vec![ServiceDescriptorProto {
+                name: Some("Logs".into()),
+                method: vec![
+                    method("Push", false, false),
+                    method("Stream", true, false),
+                    method("Tail", false, true),
+                ],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".into()),
+            ..Default::default()
+        }],
+    }
+}
+
+fn encoder(fields: &[(&str, &str)]) -> MessageEncoder {
+    let fields = fields
+        .iter()
+        .map(|(field, source)| ((*field).to_owned(), (*source).to_owned()))
+        .collect();
+    MessageEncoder::new(
+        Arc::new(Descriptors::new(descriptor_set())),
+        ".test.Log".into(),
+        fields,
+    )
+    .unwrap()
+}
+
+fn log(fields: &[(&str, Value)]) -> LogEvent {
+    let mut log = LogEvent::default();
+    for (field, value) in fields {
+        log.insert(*field, value.clone());
+    }
+    log
+}
+
+fn write_descriptor_set() -> std::path::PathBuf {
+    let path = temp_file();
+    std::fs::write(&path, descriptor_set().encode_to_vec()).unwrap();
+    path
+}
+
+fn config(method: &str, extra: &str) -> GrpcConfig {
+    toml::from_str(&format!(
+        r#"address = "http://{}"
+        descriptor_set_file = {:?}
+        method = "{}"
+        {}"#,
+        next_addr(),
+        write_descriptor_set(),
+        method,
+        extra
+    ))
+    .unwrap()
+}
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<GrpcConfig>();
+}
+
+#[test]
+fn encodes_scalars() {
+    let message = encoder(&[])
+        .encode(&log(&[
+            ("message", "hi".into()),
+            ("count", 300.into()),
+            ("level", "WARN".into()),
+            ("delta", (-1).into()),
+            ("ratio", "1.5".into()),
+            ("unknown", true.into()),
+        ]))
+        .unwrap();
+
+    let mut expected = vec![
+        0x0a, 0x02, b'h', b'i', 0x10, 0xac, 0x02, 0x28, 0x01, 0x40, 0x01,
+    ];
+    expected.push(0x49);
+    expected.extend_from_slice(&1.5f64.to_le_bytes());
+    assert_eq!(&message[..], &expected[..]);
+}
+
+#[test]
+fn encodes_repeated_and_nested_fields() {
+    let mut labels = BTreeMap::<String, Value>::new();
+    labels.insert("x".to_owned(), 1.into());
+    let mut inner = BTreeMap::<String, Value>::new();
+    inner.insert("ok".to_owned(), true.into());
+
+    let message = encoder(&[])
+        .encode(&log(&[
+            ("tags", vec!["a", "b"].into()),
+            ("labels", labels.into()),
+            ("timestamp", Utc.timestamp(1, 500_000_000).into()),
+            ("inner", inner.into()),
+        ]))
+        .unwrap();
+
+    assert_eq!(
+        &message[..],
+        &[
+            0x1a, 0x01, b'a', 0x1a, 0x01, b'b', // tags
+            0x22, 0x05, 0x0a, 0x01, b'x', 0x10, 0x01, // labels
+            0x32, 0x08, 0x08, 0x01, 0x10, 0x80, 0xca, 0xb5, 0xee, 0x01, // timestamp
+            0x3a, 0x02, 0x08, 0x01, // inner
+        ][..]
+    );
+}
+
+#[test]
+fn maps_fields() {
+    let message = encoder(&[("message", "msg.text"), ("inner.ok", "flag")])
+        .encode(&log(&[
+            ("msg.text", "hi".into()),
+            ("flag", "true".into()),
+            ("count", 1.into()),
+        ]))
+        .unwrap();
+
+    assert_eq!(
+        &message[..],
+        &[0x0a, 0x02, b'h', b'i', 0x3a, 0x02, 0x08, 0x01][..]
+    );
+}
+
+#[test]
+fn rejects_invalid_values() {
+    let encoder = encoder(&[]);
+
+    assert!(matches!(
+        encoder.encode(&log(&[("count", "many".into())])),
+        Err(EncodeError::InvalidValue { field, .. }) if field == "count"
+    ));
+    assert!(matches!(
+        encoder.encode(&log(&[("level", "DEBUG".into())])),
+        Err(EncodeError::UnknownEnumValue { value, .. }) if value == "DEBUG"
+    ));
+    assert!(matches!(
+        encoder.encode(&log(&[("delta", i64::MAX.into())])),
+        Err(EncodeError::InvalidValue { field, .. }) if field == "delta"
+    ));
+}
+
+#[tokio::test]
+async fn validates_config() {
+    let error = |method: &'static str, extra: &'static str| async move {
+        config(method, extra)
+            .build(SinkContext::new_test())
+            .await
+            .map(|_| ())
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        error("test.Logs/Tail", "").await,
+        r#"Method "test.Logs/Tail" is server streaming, which isn't supported"#
+    );
+    assert_eq!(
+        error("test.Logs/Pull", "").await,
+        r#"Method "test.Logs/Pull" isn't in the descriptor set"#
+    );
+    assert_eq!(
+        error("Push", "").await,
+        r#"Method "Push" isn't of the form `package.Service/Method`"#
+    );
+    assert_eq!(
+        error("test.Logs/Push", r#"fields.body = "message""#).await,
+        r#"Field "body" isn't a field of message type ".test.Log""#
+    );
+}
+
+async fn run(method: &str, count: usize) -> Vec<(http::request::Parts, Bytes)> {
+    // The timestamps of the events are left out.
+    let config = config(
+        method,
+        r#"fields.message = "message"
+        headers.authorization = "Bearer token""#,
+    );
+    let address = config
+        .address
+        .trim_start_matches("http://")
+        .parse()
+        .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (rx, trigger, server) = build_test_server_generic(address, || {
+        hyper::Response::builder()
+            .header("grpc-status", "0")
+            .header("content-type", "application/grpc")
+            // An empty message, with no compression.
+            .body(hyper::Body::from(&[0u8; 5][..]))
+            .unwrap()
+    });
+    tokio::spawn(server);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = (0..count)
+        .map(|index| {
+            Event::from(
+                LogEvent::from(format!("line {}", index).as_str()).with_batch_notifier(&batch),
+            )
+        })
+        .collect::<Vec<_>>();
+    drop(batch);
+    components::run_sink_events(sink, stream::iter(events), &components::HTTP_SINK_TAGS).await;
+    drop(trigger);
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    rx.collect().await
+}
+
+/// Splits a gRPC body into its messages.
+fn messages(mut body: Bytes) -> Vec<Bytes> {
+    let mut messages = Vec::new();
+    while !body.is_empty() {
+        let length = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+        messages.push(body.slice(5..5 + length));
+        body = body.slice(5 + length..);
+    }
+    messages
+}
+
+#[tokio::test]
+async fn calls_unary_methods() {
+    let requests = run("test.Logs/Push", 2).await;
+
+    assert_eq!(requests.len(), 2);
+    let mut bodies = Vec::new();
+    for (parts, body) in requests {
+        assert_eq!(parts.uri.path(), "/test.Logs/Push");
+        assert_eq!(parts.headers["authorization"], "Bearer token");
+        bodies.extend(messages(body));
+    }
+    bodies.sort();
+    assert_eq!(
+        bodies,
+        vec![
+            Bytes::from_static(b"\x0a\x06line 0"),
+            Bytes::from_static(b"\x0a\x06line 1")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn calls_client_streaming_methods() {
+    let requests = run("/test.Logs/Stream", 2).await;
+
+    assert_eq!(requests.len(), 1);
+    let (parts, body) = requests.into_iter().next().unwrap();
+    assert_eq!(parts.uri.path(), "/test.Logs/Stream");
+    assert_eq!(
+        messages(body),
+        vec![
+            Bytes::from_static(b"\x0a\x06line 0"),
+            Bytes::from_static(b"\x0a\x06line 1")
+        ]
+    );
+}
+
+#[test]
+fn retries_request_errors() {
+    use super::service::GrpcRetryLogic;
+    use crate::sinks::util::retries::RetryLogic;
+
+    let request = |code| GrpcError::Request {
+        source: tonic::Status::new(code, ""),
+    };
+    assert!(GrpcRetryLogic.is_retriable_error(&request(tonic::Code::Unavailable)));
+    assert!(!GrpcRetryLogic.is_retriable_error(&request(tonic::Code::InvalidArgument)));
+    assert!(!GrpcRetryLogic.is_retriable_error(&GrpcError::Unhealthy));
+}
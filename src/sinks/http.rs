@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::{convert::TryFrom, io::Write, sync::Arc};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use flate2::write::GzEncoder;
@@ -9,10 +9,24 @@ use http::{
 };
 use hyper::Body;
 use indexmap::IndexMap;
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
+use rusoto_core::Region;
+use rusoto_credential::{AwsCredentials, ProvideAwsCredentials};
+use rusoto_signature::SignedRequest;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
+use tokio_util::codec::Encoder as _;
 
 use crate::{
+    aws::{
+        rusoto::{AwsAuthentication, AwsCredentialsProvider},
+        RegionOrEndpoint,
+    },
+    codecs::{OtlpSerializer, OtlpSerializerOptions},
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
     },
@@ -22,6 +36,7 @@ use crate::{
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
         http::{BatchedHttpSink, HttpEventEncoder, HttpSink, RequestConfig},
+        http_compression::HttpCompressionConfig,
         BatchConfig, Buffer, Compression, RealtimeSizeBasedDefaultBatchSettings,
         TowerRequestConfig, UriSerde,
     },
@@ -58,6 +73,7 @@ pub struct HttpSinkConfig {
     #[serde(default)]
     pub request: RequestConfig,
     pub tls: Option<TlsOptions>,
+    pub signing: Option<HttpSigning>,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -72,6 +88,7 @@ fn default_config(e: Encoding) -> HttpSinkConfig {
         uri: Default::default(),
         method: Default::default(),
         auth: Default::default(),
+        signing: Default::default(),
         headers: Default::default(),
         compression: Default::default(),
         batch: Default::default(),
@@ -103,6 +120,207 @@ pub enum Encoding {
     Text,
     Ndjson,
     Json,
+    /// OTLP export requests encoded as protobuf, the requests of the events of a batch being
+    /// merged into one as they are concatenated.
+    Otlp,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+pub enum HttpSigning {
+    /// Signs the requests with AWS Signature Version 4.
+    Aws {
+        /// The name of the service the requests are signed for, such as `aoss` or `execute-api`.
+        service: String,
+        region: String,
+        #[serde(default)]
+        auth: AwsAuthentication,
+    },
+    /// Signs the bodies of the requests with an HMAC keyed by a shared secret.
+    Hmac {
+        secret: String,
+        /// The header carrying the signature.
+        #[serde(default = "default_signature_header")]
+        header: String,
+        /// Prepended to the signature in its header, such as `sha256=`.
+        #[serde(default)]
+        prefix: String,
+        /// When set, the current UNIX timestamp is sent in this header and signed along with
+        /// the body, as `<timestamp>.<body>`.
+        timestamp_header: Option<String>,
+        #[serde(default)]
+        algorithm: HmacAlgorithm,
+        #[serde(default)]
+        signature_encoding: SignatureEncoding,
+    },
+}
+
+fn default_signature_header() -> String {
+    "X-Signature".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum HmacAlgorithm {
+    #[derivative(Default)]
+    Sha256,
+    Sha512,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum SignatureEncoding {
+    #[derivative(Default)]
+    Hex,
+    Base64,
+}
+
+impl HttpSigning {
+    fn build(&self) -> crate::Result<RequestSigner> {
+        match self {
+            Self::Aws {
+                service,
+                region,
+                auth,
+            } => {
+                let region = Region::try_from(RegionOrEndpoint::with_region(region.clone()))?;
+                let credentials_provider = auth.build(&region, None)?;
+                Ok(RequestSigner::Aws(AwsSigner {
+                    service: service.clone(),
+                    region,
+                    credentials_provider,
+                }))
+            }
+            Self::Hmac {
+                secret,
+                header,
+                prefix,
+                timestamp_header,
+                algorithm,
+                signature_encoding,
+            } => {
+                let header_name = |name: &String| {
+                    HeaderName::from_bytes(name.as_bytes())
+                        .with_context(|_| InvalidHeaderNameSnafu { name })
+                };
+                Ok(RequestSigner::Hmac(HmacSigner {
+                    key: PKey::hmac(secret.as_bytes())?,
+                    header: header_name(header)?,
+                    prefix: prefix.clone(),
+                    timestamp_header: timestamp_header.as_ref().map(header_name).transpose()?,
+                    algorithm: *algorithm,
+                    signature_encoding: *signature_encoding,
+                }))
+            }
+        }
+    }
+}
+
+enum RequestSigner {
+    Aws(AwsSigner),
+    Hmac(HmacSigner),
+}
+
+impl RequestSigner {
+    async fn sign(&self, request: &mut Request<Bytes>) -> crate::Result<()> {
+        match self {
+            Self::Aws(signer) => {
+                let credentials = signer.credentials_provider.credentials().await?;
+                signer.sign(request, &credentials)
+            }
+            Self::Hmac(signer) => signer.sign(request, chrono::Utc::now().timestamp()),
+        }
+    }
+}
+
+struct AwsSigner {
+    service: String,
+    region: Region,
+    credentials_provider: AwsCredentialsProvider,
+}
+
+impl AwsSigner {
+    fn sign(
+        &self,
+        request: &mut Request<Bytes>,
+        credentials: &AwsCredentials,
+    ) -> crate::Result<()> {
+        let uri = request.uri();
+        let mut signed = SignedRequest::new(
+            request.method().as_str(),
+            &self.service,
+            &self.region,
+            uri.path(),
+        );
+        // The port is part of the signed `Host` header when not the default one.
+        signed.set_hostname(
+            uri.authority()
+                .map(|authority| authority.as_str().to_owned()),
+        );
+        if let Some(query) = uri.query() {
+            for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+                signed.add_param(key, value);
+            }
+        }
+        for (name, value) in request.headers() {
+            signed.add_header(name.as_str(), value.to_str()?);
+        }
+        signed.set_payload(Some(request.body().clone()));
+        signed.sign(credentials);
+
+        let headers = request.headers_mut();
+        for (name, values) in signed.headers() {
+            let name = HeaderName::from_bytes(name.as_bytes())?;
+            headers.remove(&name);
+            for value in values {
+                headers.append(&name, HeaderValue::from_bytes(value)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+struct HmacSigner {
+    key: PKey<Private>,
+    header: HeaderName,
+    prefix: String,
+    timestamp_header: Option<HeaderName>,
+    algorithm: HmacAlgorithm,
+    signature_encoding: SignatureEncoding,
+}
+
+impl HmacSigner {
+    /// Signs the request, `timestamp` being the current UNIX time.
+    fn sign(&self, request: &mut Request<Bytes>, timestamp: i64) -> crate::Result<()> {
+        let digest = match self.algorithm {
+            HmacAlgorithm::Sha256 => MessageDigest::sha256(),
+            HmacAlgorithm::Sha512 => MessageDigest::sha512(),
+        };
+        let timestamp = timestamp.to_string();
+        let mut signer = Signer::new(digest, &self.key)?;
+        if self.timestamp_header.is_some() {
+            signer.update(timestamp.as_bytes())?;
+            signer.update(b".")?;
+        }
+        signer.update(request.body())?;
+        let signature = signer.sign_to_vec()?;
+        let signature = match self.signature_encoding {
+            SignatureEncoding::Hex => hex::encode(signature),
+            SignatureEncoding::Base64 => base64::encode(signature),
+        };
+
+        let headers = request.headers_mut();
+        headers.insert(
+            &self.header,
+            HeaderValue::from_str(&format!("{}{}", self.prefix, signature))?,
+        );
+        if let Some(timestamp_header) = &self.timestamp_header {
+            headers.insert(timestamp_header, HeaderValue::from_str(&timestamp)?);
+        }
+        Ok(())
+    }
 }
 
 inventory::submit! {
@@ -134,11 +352,21 @@ impl SinkConfig for HttpSinkConfig {
         cx: SinkContext,
     ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
         let client = self.build_http_client(&cx)?;
+        let signer = self
+            .signing
+            .as_ref()
+            .map(HttpSigning::build)
+            .transpose()?
+            .map(Arc::new);
 
         let healthcheck = match cx.healthcheck.uri.clone() {
-            Some(healthcheck_uri) => {
-                healthcheck(healthcheck_uri, self.auth.clone(), client.clone()).boxed()
-            }
+            Some(healthcheck_uri) => healthcheck(
+                healthcheck_uri,
+                self.auth.clone(),
+                signer.clone(),
+                client.clone(),
+            )
+            .boxed(),
             None => future::ok(()).boxed(),
         };
 
@@ -150,6 +378,8 @@ impl SinkConfig for HttpSinkConfig {
 
         config.request.add_old_option(config.headers.take());
         validate_headers(&config.request.headers, &config.auth)?;
+        validate_signing(&config)?;
+        validate_compression(&config)?;
 
         let batch = config.batch.into_batch_settings()?;
         let request = config
@@ -157,7 +387,7 @@ impl SinkConfig for HttpSinkConfig {
             .tower
             .unwrap_with(&TowerRequestConfig::default());
         let sink = BatchedHttpSink::new(
-            config,
+            HttpSinkRequestBuilder { config, signer },
             Buffer::new(batch.size, Compression::None),
             request,
             batch.timeout,
@@ -219,6 +449,14 @@ impl HttpEventEncoder<BytesMut> for HttpSinkEventEncoder {
                 body.put_u8(b',');
                 body
             }
+            Encoding::Otlp => {
+                let mut body = BytesMut::new();
+                OtlpSerializer::new(OtlpSerializerOptions::default())
+                    .encode(event.into(), &mut body)
+                    .map_err(|error| panic!("Unable to encode into OTLP: {}", error))
+                    .ok()?;
+                body
+            }
         };
 
         emit!(&HttpEventEncoded {
@@ -229,19 +467,14 @@ impl HttpEventEncoder<BytesMut> for HttpSinkEventEncoder {
     }
 }
 
-#[async_trait::async_trait]
-impl HttpSink for HttpSinkConfig {
-    type Input = BytesMut;
-    type Output = BytesMut;
-    type Encoder = HttpSinkEventEncoder;
-
-    fn build_encoder(&self) -> Self::Encoder {
+impl HttpSinkConfig {
+    fn build_encoder(&self) -> HttpSinkEventEncoder {
         HttpSinkEventEncoder {
             encoding: self.encoding.clone(),
         }
     }
 
-    async fn build_request(&self, mut body: Self::Output) -> crate::Result<http::Request<Bytes>> {
+    fn build_request(&self, mut body: BytesMut) -> crate::Result<http::Request<Bytes>> {
         let method = match &self.method.clone().unwrap_or(HttpMethod::Post) {
             HttpMethod::Get => Method::GET,
             HttpMethod::Head => Method::HEAD,
@@ -271,6 +504,7 @@ impl HttpSink for HttpSinkConfig {
                 body.put_u8(b']');
                 "application/json"
             }
+            Encoding::Otlp => "application/x-protobuf",
         };
 
         let mut builder = Request::builder()
@@ -304,16 +538,53 @@ impl HttpSink for HttpSinkConfig {
     }
 }
 
-async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> crate::Result<()> {
+/// Builds the requests of the sink, signing them once built when `signing` is set.
+struct HttpSinkRequestBuilder {
+    config: HttpSinkConfig,
+    signer: Option<Arc<RequestSigner>>,
+}
+
+#[async_trait::async_trait]
+impl HttpSink for HttpSinkRequestBuilder {
+    type Input = BytesMut;
+    type Output = BytesMut;
+    type Encoder = HttpSinkEventEncoder;
+
+    fn build_encoder(&self) -> Self::Encoder {
+        self.config.build_encoder()
+    }
+
+    fn request_compression(&self) -> Option<&HttpCompressionConfig> {
+        self.config.request.compression.as_ref()
+    }
+
+    async fn build_request(&self, body: Self::Output) -> crate::Result<http::Request<Bytes>> {
+        let mut request = self.config.build_request(body)?;
+        if let Some(signer) = &self.signer {
+            signer.sign(&mut request).await?;
+        }
+        Ok(request)
+    }
+}
+
+async fn healthcheck(
+    uri: UriSerde,
+    auth: Option<Auth>,
+    signer: Option<Arc<RequestSigner>>,
+    client: HttpClient,
+) -> crate::Result<()> {
     let auth = auth.choose_one(&uri.auth)?;
     let uri = uri.with_default_parts();
-    let mut request = Request::head(&uri.uri).body(Body::empty()).unwrap();
+    let mut request = Request::head(&uri.uri).body(Bytes::new()).unwrap();
 
     if let Some(auth) = auth {
         auth.apply(&mut request);
     }
+    if let Some(signer) = signer {
+        signer.sign(&mut request).await?;
+    }
 
-    let response = client.send(request).await?;
+    let response = client.send(request.map(Body::from)).await?;
 
     match response.status() {
         StatusCode::OK => Ok(()),
@@ -321,6 +592,40 @@ async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> c
     }
 }
 
+/// Checks that nothing else sets the `Authorization` header of requests signed with AWS.
+fn validate_signing(config: &HttpSinkConfig) -> crate::Result<()> {
+    if let Some(HttpSigning::Aws { .. }) = config.signing {
+        if config.auth.is_some()
+            || config
+                .request
+                .headers
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case("Authorization"))
+        {
+            return Err(
+                "Neither auth options nor an Authorization header can be used with AWS signing"
+                    .into(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_compression(config: &HttpSinkConfig) -> crate::Result<()> {
+    if config.request.compression.is_some() {
+        if config.compression.is_compressed() {
+            return Err("`compression` can not be used with `request.compression`".into());
+        }
+        // The negotiated compression applies once the requests are signed.
+        if config.signing.is_some() {
+            return Err("`request.compression` can not be used with signing".into());
+        }
+    }
+
+    Ok(())
+}
+
 fn validate_headers(map: &IndexMap<String, String>, auth: &Option<Auth>) -> crate::Result<()> {
     for (name, value) in map {
         if auth.is_some() && name.eq_ignore_ascii_case("Authorization") {
@@ -404,6 +709,33 @@ mod tests {
         assert_eq!(output.message, "hello world".to_string());
     }
 
+    #[test]
+    fn http_encode_event_otlp() {
+        use prost::Message;
+
+        use crate::{
+            event::Value, proto::opentelemetry::collector::logs::v1::ExportLogsServiceRequest,
+        };
+
+        let mut config = default_config(Encoding::Otlp);
+        config.encoding = EncodingConfig::from(Encoding::Otlp);
+        let mut encoder = config.build_encoder();
+        let mut bytes = encoder.encode_event(Event::from("hello")).unwrap();
+        bytes.unsplit(encoder.encode_event(Event::from("world")).unwrap());
+
+        let request = ExportLogsServiceRequest::decode(bytes.freeze()).unwrap();
+        assert_eq!(request.resource_logs.len(), 2);
+        assert_eq!(
+            Value::from(
+                request.resource_logs[1].scope_logs[0].log_records[0]
+                    .body
+                    .clone()
+                    .unwrap()
+            ),
+            Value::from("world")
+        );
+    }
+
     #[test]
     fn http_validates_normal_headers() {
         let config = r#"
@@ -435,6 +767,116 @@ mod tests {
         );
     }
 
+    fn signing_config(signing: &str) -> HttpSinkConfig {
+        toml::from_str(&format!(
+            r#"
+            uri = "https://example.com:8443/ingest?source=vector"
+            encoding = "json"
+            {}
+            "#,
+            signing
+        ))
+        .unwrap()
+    }
+
+    fn signed_request(config: &HttpSinkConfig) -> Request<Bytes> {
+        config
+            .build_request(BytesMut::from(&b"{\"message\":\"hello\"},"[..]))
+            .unwrap()
+    }
+
+    #[test]
+    fn http_signs_with_hmac() {
+        let config = signing_config(
+            r#"
+            signing.strategy = "hmac"
+            signing.secret = "secret"
+            signing.header = "X-Hub-Signature-256"
+            signing.prefix = "sha256="
+            signing.timestamp_header = "X-Timestamp"
+            "#,
+        );
+        let signer = match config.signing.as_ref().unwrap().build().unwrap() {
+            RequestSigner::Hmac(signer) => signer,
+            RequestSigner::Aws(_) => panic!("Expected an HMAC signer"),
+        };
+        let mut request = signed_request(&config);
+        signer.sign(&mut request, 1_650_000_000).unwrap();
+
+        let key = PKey::hmac(b"secret").unwrap();
+        let mut expected = Signer::new(MessageDigest::sha256(), &key).unwrap();
+        expected.update(b"1650000000.").unwrap();
+        expected.update(request.body()).unwrap();
+        let expected = format!("sha256={}", hex::encode(expected.sign_to_vec().unwrap()));
+
+        assert_eq!(request.headers()["X-Hub-Signature-256"], expected.as_str());
+        assert_eq!(request.headers()["X-Timestamp"], "1650000000");
+    }
+
+    #[tokio::test]
+    async fn http_signs_with_aws() {
+        let config = signing_config(
+            r#"
+            signing.strategy = "aws"
+            signing.service = "aoss"
+            signing.region = "us-east-1"
+            signing.auth.access_key_id = "AKIDEXAMPLE"
+            signing.auth.secret_access_key = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY"
+            "#,
+        );
+        let signer = config.signing.as_ref().unwrap().build().unwrap();
+        let mut request = signed_request(&config);
+        signer.sign(&mut request).await.unwrap();
+
+        let authorization = request.headers()["authorization"].to_str().unwrap();
+        assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        assert!(authorization.contains("/us-east-1/aoss/aws4_request"));
+        assert!(request.headers().contains_key("x-amz-date"));
+        assert_eq!(request.headers()["host"], "example.com:8443");
+        assert_eq!(request.headers()["content-type"], "application/json");
+    }
+
+    #[test]
+    fn http_aws_signing_auth_conflict() {
+        let mut config = signing_config(
+            r#"
+            signing.strategy = "aws"
+            signing.service = "execute-api"
+            signing.region = "us-east-1"
+            "#,
+        );
+        assert!(super::validate_signing(&config).is_ok());
+
+        config
+            .request
+            .headers
+            .insert("Authorization".to_owned(), "Bearer token".to_owned());
+        assert!(super::validate_signing(&config).is_err());
+    }
+
+    #[test]
+    fn http_request_compression_conflicts() {
+        let mut config = signing_config(
+            r#"
+            request.compression.algorithms = ["gzip"]
+            "#,
+        );
+        assert!(super::validate_compression(&config).is_ok());
+
+        config.compression = Compression::gzip_default();
+        assert!(super::validate_compression(&config).is_err());
+
+        config.compression = Compression::None;
+        config.signing = signing_config(
+            r#"
+            signing.strategy = "hmac"
+            signing.secret = "secret"
+            "#,
+        )
+        .signing;
+        assert!(super::validate_compression(&config).is_err());
+    }
+
     // TODO: Fix failure on GH Actions using macos-latest image.
     #[cfg(not(target_os = "macos"))]
     #[tokio::test]
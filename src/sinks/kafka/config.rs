@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroUsize};
 
 use futures::FutureExt;
 use rdkafka::ClientConfig;
@@ -9,9 +9,12 @@ use crate::{
     kafka::{KafkaAuthConfig, KafkaCompression},
     serde::json::to_string,
     sinks::{
-        kafka::sink::{healthcheck, KafkaSink},
+        kafka::{
+            schema_registry::KafkaSchemaValidationConfig,
+            sink::{healthcheck, KafkaSink},
+        },
         util::{
-            encoding::{EncodingConfig, StandardEncodings},
+            encoding::{EncodingConfig, EncodingConfiguration, StandardEncodings},
             BatchConfig, NoDefaultsBatchSettings,
         },
         Healthcheck, VectorSink,
@@ -41,6 +44,11 @@ pub(crate) struct KafkaSinkConfig {
     pub librdkafka_options: HashMap<String, String>,
     #[serde(alias = "headers_field")] // accidentally released as `headers_field` in 0.18
     pub headers_key: Option<String>,
+    /// Makes the producer idempotent, so that its retries can't duplicate messages.
+    #[serde(default)]
+    pub idempotence: bool,
+    pub transaction: Option<KafkaTransactionConfig>,
+    pub schema_validation: Option<KafkaSchemaValidationConfig>,
     #[serde(
         default,
         deserialize_with = "crate::serde::bool_or_struct",
@@ -49,6 +57,33 @@ pub(crate) struct KafkaSinkConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Sends the events in transactions, the events of which are only acknowledged once committed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct KafkaTransactionConfig {
+    /// Identifies the producer across restarts, letting the brokers abort the transactions left
+    /// open by a previous instance.
+    pub transactional_id: String,
+    #[serde(default = "default_transaction_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_transaction_max_events")]
+    pub max_events: NonZeroUsize,
+    #[serde(default = "default_transaction_commit_interval_ms")]
+    pub commit_interval_ms: u64,
+}
+
+const fn default_transaction_timeout_ms() -> u64 {
+    60000 // default in librdkafka
+}
+
+fn default_transaction_max_events() -> NonZeroUsize {
+    NonZeroUsize::new(1000).unwrap()
+}
+
+const fn default_transaction_commit_interval_ms() -> u64 {
+    1000
+}
+
 const fn default_socket_timeout_ms() -> u64 {
     60000 // default in librdkafka
 }
@@ -66,6 +101,17 @@ pub enum KafkaRole {
 }
 
 impl KafkaSinkConfig {
+    /// The payloads are parsed as JSON to be validated against their schema.
+    pub(crate) fn validate_schema_encoding(&self) -> crate::Result<()> {
+        match (&self.schema_validation, self.encoding.codec()) {
+            (Some(_), StandardEncodings::Json | StandardEncodings::Ndjson) | (None, _) => Ok(()),
+            (Some(_), _) => Err(
+                "The `schema_validation` option requires the `json` or `ndjson` encoding codec."
+                    .into(),
+            ),
+        }
+    }
+
     pub(crate) fn to_rdkafka(&self, kafka_role: KafkaRole) -> crate::Result<ClientConfig> {
         let mut client_config = ClientConfig::new();
         client_config
@@ -141,6 +187,42 @@ impl KafkaSinkConfig {
                     );
                     client_config.set(key, &value.to_string());
                 }
+
+                let mut options = Vec::new();
+                if self.idempotence || self.transaction.is_some() {
+                    options.push(("enable.idempotence", "idempotence", "true".to_owned()));
+                }
+                if let Some(transaction) = &self.transaction {
+                    if transaction.commit_interval_ms >= transaction.timeout_ms {
+                        return Err(format!(
+                            "The `transaction.commit_interval_ms` of {} must be lower than the `transaction.timeout_ms` of {}.",
+                            transaction.commit_interval_ms, transaction.timeout_ms
+                        )
+                        .into());
+                    }
+                    options.push((
+                        "transactional.id",
+                        "transaction.transactional_id",
+                        transaction.transactional_id.clone(),
+                    ));
+                    options.push((
+                        "transaction.timeout.ms",
+                        "transaction.timeout_ms",
+                        transaction.timeout_ms.to_string(),
+                    ));
+                }
+                for (key, option, value) in options {
+                    if let Some(val) = self.librdkafka_options.get(key) {
+                        return Err(format!(
+                            "Setting `{}` sets `librdkafka_options.{}={}`.\
+                                        The config already sets this as `librdkafka_options.{}={}`.\
+                                        Please delete one.",
+                            option, key, value, key, val
+                        )
+                        .into());
+                    }
+                    client_config.set(key, &value);
+                }
             }
 
             KafkaRole::Consumer => {
@@ -171,6 +253,9 @@ impl GenerateConfig for KafkaSinkConfig {
             message_timeout_ms: default_message_timeout_ms(),
             librdkafka_options: Default::default(),
             headers_key: None,
+            idempotence: false,
+            transaction: None,
+            schema_validation: None,
             acknowledgements: Default::default(),
         })
         .unwrap()
@@ -181,7 +266,7 @@ impl GenerateConfig for KafkaSinkConfig {
 #[typetag::serde(name = "kafka")]
 impl SinkConfig for KafkaSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let sink = KafkaSink::new(self.clone(), cx.acker())?;
+        let sink = KafkaSink::new(self.clone(), cx.acker(), cx.proxy())?;
         let hc = healthcheck(self.clone()).boxed();
         Ok((VectorSink::from_event_streamsink(sink), hc))
     }
@@ -207,4 +292,81 @@ mod tests {
     fn generate_config() {
         KafkaSinkConfig::generate_config();
     }
+
+    fn config(extra: &str) -> KafkaSinkConfig {
+        toml::from_str(&format!(
+            r#"
+                bootstrap_servers = "localhost:9092"
+                topic = "topic"
+                encoding.codec = "json"
+                {}
+            "#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn transaction_options() {
+        let client_config = config(
+            r#"
+                transaction.transactional_id = "vector"
+                transaction.timeout_ms = 30000
+            "#,
+        )
+        .to_rdkafka(KafkaRole::Producer)
+        .unwrap();
+        assert_eq!(client_config.get("enable.idempotence"), Some("true"));
+        assert_eq!(client_config.get("transactional.id"), Some("vector"));
+        assert_eq!(client_config.get("transaction.timeout.ms"), Some("30000"));
+
+        let client_config = config("idempotence = true")
+            .to_rdkafka(KafkaRole::Producer)
+            .unwrap();
+        assert_eq!(client_config.get("enable.idempotence"), Some("true"));
+        assert_eq!(client_config.get("transactional.id"), None);
+
+        let client_config = config(r#"transaction.transactional_id = "vector""#)
+            .to_rdkafka(KafkaRole::Consumer)
+            .unwrap();
+        assert_eq!(client_config.get("transactional.id"), None);
+    }
+
+    #[test]
+    fn schema_validation_options() {
+        let config = config(
+            r#"
+                schema_validation.url = "http://localhost:8081/"
+                schema_validation.subject = "events-value"
+            "#,
+        );
+        let schema_validation = config.schema_validation.as_ref().unwrap();
+        assert_eq!(schema_validation.url, "http://localhost:8081/");
+        assert_eq!(schema_validation.refresh_secs, 300);
+        assert!(config.validate_schema_encoding().is_ok());
+
+        let mut config = config;
+        config.encoding = StandardEncodings::Text.into();
+        assert!(config.validate_schema_encoding().is_err());
+    }
+
+    #[test]
+    fn transaction_options_errors() {
+        assert!(config(
+            r#"
+                transaction.transactional_id = "vector"
+                transaction.commit_interval_ms = 60000
+            "#
+        )
+        .to_rdkafka(KafkaRole::Producer)
+        .is_err());
+        assert!(config(
+            r#"
+                idempotence = true
+                librdkafka_options."enable.idempotence" = "false"
+            "#
+        )
+        .to_rdkafka(KafkaRole::Producer)
+        .is_err());
+    }
 }
@@ -2,6 +2,7 @@ use crate::config::SinkDescription;
 
 pub(crate) mod config;
 pub(crate) mod request_builder;
+pub(crate) mod schema_registry;
 pub(crate) mod service;
 pub(crate) mod sink;
 pub(crate) mod tests;
@@ -0,0 +1,324 @@
+//! Validation of the encoded payloads against the latest schema of their subject in a Schema
+//! Registry, before producing them.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::{header, Request};
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    config::ProxyConfig,
+    http::{Auth, HttpClient},
+    internal_events::{KafkaSchemaRegistryError, KafkaSchemaValidationError},
+    tls::{TlsOptions, TlsSettings},
+};
+
+const SCHEMA_REGISTRY_CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+/// Validates the payloads against the latest schema of their subject, the events failing it
+/// being rejected, and routed to the `dead_letter` output of the sink if configured.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct KafkaSchemaValidationConfig {
+    /// The base URL of the Schema Registry.
+    pub url: String,
+    /// The subject of the schema, defaulting to `<topic>-value` with the topic of each event.
+    pub subject: Option<String>,
+    pub auth: Option<Auth>,
+    /// How often the latest schema of a subject is fetched again.
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,
+    pub tls: Option<TlsOptions>,
+}
+
+const fn default_refresh_secs() -> u64 {
+    300
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum SchemaError {
+    #[snafu(display("Schema Registry request failed: {}", source))]
+    Request { source: crate::Error },
+    #[snafu(display("Schema Registry responded with status {}", status))]
+    Status { status: http::StatusCode },
+    #[snafu(display("invalid Schema Registry response: {}", source))]
+    Response { source: serde_json::Error },
+    #[snafu(display("invalid Avro schema: {}", source))]
+    Avro { source: avro_rs::Error },
+    #[snafu(display("invalid JSON schema: {}", message))]
+    JsonSchema { message: String },
+}
+
+/// A schema as returned by the Schema Registry, which omits the type of Avro schemas.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubjectVersion {
+    schema: String,
+    #[serde(default)]
+    schema_type: SchemaType,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+enum SchemaType {
+    Avro,
+    Json,
+    Protobuf,
+}
+
+impl Default for SchemaType {
+    fn default() -> Self {
+        Self::Avro
+    }
+}
+
+pub(super) enum Schema {
+    Avro(avro_rs::Schema),
+    Json(jsonschema::JSONSchema),
+    /// Protobuf payloads are encoded by Vector as JSON, so there is nothing to check them against.
+    Protobuf,
+}
+
+impl Schema {
+    fn parse(version: SubjectVersion) -> Result<Self, SchemaError> {
+        match version.schema_type {
+            SchemaType::Avro => avro_rs::Schema::parse_str(&version.schema)
+                .map(Self::Avro)
+                .context(AvroSnafu),
+            SchemaType::Json => {
+                let schema = serde_json::from_str(&version.schema).context(ResponseSnafu)?;
+                jsonschema::JSONSchema::compile(&schema)
+                    .map(Self::Json)
+                    .map_err(|error| SchemaError::JsonSchema {
+                        message: error.to_string(),
+                    })
+            }
+            SchemaType::Protobuf => Ok(Self::Protobuf),
+        }
+    }
+
+    /// Checks the JSON encoded payload against the schema.
+    pub(super) fn validate(&self, payload: &[u8]) -> Result<(), String> {
+        let payload: serde_json::Value = serde_json::from_slice(payload)
+            .map_err(|error| format!("payload is not valid JSON: {}", error))?;
+        match self {
+            Self::Avro(schema) => avro_rs::types::Value::from(payload)
+                .resolve(schema)
+                .map(|_| ())
+                .map_err(|error| error.to_string()),
+            Self::Json(schema) => schema.validate(&payload).map_err(|errors| {
+                errors
+                    .map(|error| error.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+            Self::Protobuf => Ok(()),
+        }
+    }
+}
+
+struct CachedSchema {
+    schema: Arc<Schema>,
+    fetched_at: Instant,
+}
+
+/// Fetches and caches the latest schema of each subject.
+pub(super) struct SchemaValidator {
+    client: HttpClient,
+    url: String,
+    subject: Option<String>,
+    auth: Option<Auth>,
+    refresh: Duration,
+    schemas: Mutex<HashMap<String, CachedSchema>>,
+}
+
+impl SchemaValidator {
+    pub(super) fn new(
+        config: &KafkaSchemaValidationConfig,
+        proxy: &ProxyConfig,
+    ) -> crate::Result<Self> {
+        let client = HttpClient::new(TlsSettings::from_options(&config.tls)?, proxy)?;
+        Ok(Self {
+            client,
+            url: config.url.trim_end_matches('/').to_owned(),
+            subject: config.subject.clone(),
+            auth: config.auth.clone(),
+            refresh: Duration::from_secs(config.refresh_secs),
+            schemas: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Validates the payload to be produced to the topic, returning why it is invalid. The
+    /// payloads can't be validated while their schema can't be fetched, in which case they are
+    /// let through.
+    pub(super) async fn validate(&self, topic: &str, payload: &[u8]) -> Result<(), String> {
+        let subject = match &self.subject {
+            Some(subject) => subject.clone(),
+            None => format!("{}-value", topic),
+        };
+        match self.schema(&subject).await {
+            Some(schema) => schema.validate(payload).map_err(|error| {
+                emit!(&KafkaSchemaValidationError {
+                    subject: &subject,
+                    error: &error,
+                });
+                error
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// The latest schema of the subject, falling back to the schema fetched last when the
+    /// Schema Registry can't be reached.
+    async fn schema(&self, subject: &str) -> Option<Arc<Schema>> {
+        let cached = self.schemas.lock().unwrap().get(subject).map(|cached| {
+            (
+                Arc::clone(&cached.schema),
+                cached.fetched_at.elapsed() < self.refresh,
+            )
+        });
+        if let Some((schema, true)) = cached {
+            return Some(schema);
+        }
+
+        match self.fetch(subject).await {
+            Ok(schema) => {
+                if matches!(schema, Schema::Protobuf) {
+                    warn!(
+                        message = "Protobuf schemas are not validated.",
+                        %subject,
+                        internal_log_rate_secs = 60,
+                    );
+                }
+                let schema = Arc::new(schema);
+                self.schemas.lock().unwrap().insert(
+                    subject.to_owned(),
+                    CachedSchema {
+                        schema: Arc::clone(&schema),
+                        fetched_at: Instant::now(),
+                    },
+                );
+                Some(schema)
+            }
+            Err(error) => {
+                emit!(&KafkaSchemaRegistryError {
+                    subject,
+                    error: error.to_string(),
+                });
+                cached.map(|(schema, _)| schema)
+            }
+        }
+    }
+
+    async fn fetch(&self, subject: &str) -> Result<Schema, SchemaError> {
+        let uri = format!(
+            "{}/subjects/{}/versions/latest",
+            self.url,
+            utf8_percent_encode(subject, NON_ALPHANUMERIC)
+        );
+        let mut request = Request::get(uri)
+            .header(header::ACCEPT, SCHEMA_REGISTRY_CONTENT_TYPE)
+            .body(Body::empty())
+            .map_err(|error| SchemaError::Request {
+                source: error.into(),
+            })?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self
+            .client
+            .send(request)
+            .await
+            .map_err(|error| SchemaError::Request {
+                source: error.into(),
+            })?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SchemaError::Status { status });
+        }
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|error| SchemaError::Request {
+                source: error.into(),
+            })?;
+        let version = serde_json::from_slice(&body).context(ResponseSnafu)?;
+        Schema::parse(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(schema_type: &str, schema: &str) -> Schema {
+        Schema::parse(SubjectVersion {
+            schema: schema.to_owned(),
+            schema_type: serde_json::from_value(serde_json::json!(schema_type)).unwrap(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_subject_versions() {
+        let version: SubjectVersion =
+            serde_json::from_str(r#"{"subject":"topic-value","version":1,"schema":"\"string\""}"#)
+                .unwrap();
+        assert_eq!(version.schema_type, SchemaType::Avro);
+
+        let version: SubjectVersion =
+            serde_json::from_str(r#"{"schema":"{}","schemaType":"JSON"}"#).unwrap();
+        assert_eq!(version.schema_type, SchemaType::Json);
+    }
+
+    #[test]
+    fn validates_avro_payloads() {
+        let schema = schema(
+            "AVRO",
+            r#"{
+                "type": "record",
+                "name": "event",
+                "fields": [
+                    {"name": "message", "type": "string"},
+                    {"name": "count", "type": ["null", "long"], "default": null}
+                ]
+            }"#,
+        );
+
+        assert!(schema.validate(br#"{"message":"hello","count":1}"#).is_ok());
+        assert!(schema.validate(br#"{"message":"hello"}"#).is_ok());
+        assert!(schema.validate(br#"{"message":1}"#).is_err());
+        assert!(schema.validate(b"hello").is_err());
+    }
+
+    #[test]
+    fn validates_json_payloads() {
+        let schema = schema(
+            "JSON",
+            r#"{
+                "type": "object",
+                "properties": {"message": {"type": "string"}},
+                "required": ["message"]
+            }"#,
+        );
+
+        assert!(schema.validate(b"{\"message\":\"hello\"}\n").is_ok());
+        assert!(schema.validate(br#"{"count":1}"#).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(Schema::parse(SubjectVersion {
+            schema: "{".to_owned(),
+            schema_type: SchemaType::Avro,
+        })
+        .is_err());
+    }
+}
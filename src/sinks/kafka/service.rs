@@ -75,6 +75,10 @@ impl KafkaService {
     ) -> KafkaService {
         KafkaService { kafka_producer }
     }
+
+    pub(crate) const fn producer(&self) -> &FutureProducer<KafkaStatisticsContext> {
+        &self.kafka_producer
+    }
 }
 
 impl Service<KafkaRequest> for KafkaService {
@@ -1,26 +1,33 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, num::NonZeroUsize, sync::Arc};
 
 use async_trait::async_trait;
-use futures::{future, stream::BoxStream, StreamExt};
+use futures::{future, stream::BoxStream, Stream, StreamExt};
 use rdkafka::{
     consumer::{BaseConsumer, Consumer},
-    error::KafkaError,
-    producer::FutureProducer,
+    error::{KafkaError, KafkaResult},
+    producer::{FutureProducer, Producer},
     ClientConfig,
 };
 use snafu::{ResultExt, Snafu};
 use tokio::time::Duration;
-use tower::limit::ConcurrencyLimit;
-use vector_core::{buffers::Acker, config::log_schema};
+use tower::{limit::ConcurrencyLimit, Service};
+use vector_core::{
+    buffers::Acker,
+    config::log_schema,
+    stream::{BatcherSettings, DriverResponse},
+};
 
-use super::config::{KafkaRole, KafkaSinkConfig};
+use super::config::{KafkaRole, KafkaSinkConfig, KafkaTransactionConfig};
 use crate::{
-    event::Event,
+    config::ProxyConfig,
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
     kafka::KafkaStatisticsContext,
     sinks::{
         kafka::{
-            config::QUEUED_MIN_MESSAGES, request_builder::KafkaRequestBuilder,
-            service::KafkaService,
+            config::QUEUED_MIN_MESSAGES,
+            request_builder::KafkaRequestBuilder,
+            schema_registry::SchemaValidator,
+            service::{KafkaRequest, KafkaResponse, KafkaService},
         },
         util::{
             builder::SinkBuilderExt,
@@ -46,6 +53,8 @@ pub struct KafkaSink {
     topic: Template,
     key_field: Option<String>,
     headers_key: Option<String>,
+    transaction: Option<KafkaTransactionConfig>,
+    schema_validator: Option<Arc<SchemaValidator>>,
 }
 
 pub(crate) fn create_producer(
@@ -58,9 +67,19 @@ pub(crate) fn create_producer(
 }
 
 impl KafkaSink {
-    pub(crate) fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
+    pub(crate) fn new(
+        config: KafkaSinkConfig,
+        acker: Acker,
+        proxy: &ProxyConfig,
+    ) -> crate::Result<Self> {
+        config.validate_schema_encoding()?;
         let producer_config = config.to_rdkafka(KafkaRole::Producer)?;
         let producer = create_producer(producer_config)?;
+        let schema_validator = config
+            .schema_validation
+            .as_ref()
+            .map(|schema_validation| SchemaValidator::new(schema_validation, proxy).map(Arc::new))
+            .transpose()?;
 
         Ok(KafkaSink {
             headers_key: config.headers_key,
@@ -69,12 +88,12 @@ impl KafkaSink {
             service: KafkaService::new(producer),
             topic: Template::try_from(config.topic).context(TopicTemplateSnafu)?,
             key_field: config.key_field,
+            transaction: config.transaction,
+            schema_validator,
         })
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
-        let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
         let request_builder = KafkaRequestBuilder {
             key_field: self.key_field,
             headers_key: self.headers_key,
@@ -82,13 +101,171 @@ impl KafkaSink {
             encoder: self.encoding,
             log_schema: log_schema(),
         };
-        let sink = input
+        let schema_validator = self.schema_validator;
+        let acker = self.acker.clone();
+        let requests = input
             .filter_map(|event| future::ready(request_builder.build_request(event)))
-            .into_driver(service, self.acker);
+            .filter_map(move |request| {
+                let schema_validator = schema_validator.clone();
+                let acker = acker.clone();
+                async move {
+                    match schema_validator {
+                        Some(schema_validator) => {
+                            validate_request(&schema_validator, &acker, request).await
+                        }
+                        None => Some(request),
+                    }
+                }
+            });
+
+        if let Some(transaction) = self.transaction {
+            return run_transactions(self.service, self.acker, transaction, requests).await;
+        }
+
+        // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
+        let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
+        let sink = requests.into_driver(service, self.acker);
         sink.run().await
     }
 }
 
+/// Rejects the requests whose payload doesn't match the schema of their subject, for their events
+/// to be routed to the dead letter output of the sink.
+async fn validate_request(
+    schema_validator: &SchemaValidator,
+    acker: &Acker,
+    mut request: KafkaRequest,
+) -> Option<KafkaRequest> {
+    match schema_validator
+        .validate(&request.metadata.topic, &request.body)
+        .await
+    {
+        Ok(()) => Some(request),
+        Err(_) => {
+            request
+                .take_finalizers()
+                .update_status(EventStatus::Rejected);
+            acker.ack(1);
+            None
+        }
+    }
+}
+
+/// Sends the requests in transactions, the events of which are only acknowledged once their
+/// transaction is committed. The events of aborted transactions are marked as errored, letting
+/// sources supporting acknowledgements deliver them again.
+async fn run_transactions(
+    mut service: KafkaService,
+    acker: Acker,
+    config: KafkaTransactionConfig,
+    requests: impl Stream<Item = KafkaRequest>,
+) -> Result<(), ()> {
+    let producer = service.producer().clone();
+    let timeout = Duration::from_millis(config.timeout_ms);
+    if let Err(error) = blocking(&producer, move |producer| {
+        producer.init_transactions(timeout)
+    })
+    .await
+    {
+        error!(message = "Failed initializing Kafka transactions.", %error);
+        return Err(());
+    }
+
+    let settings = BatcherSettings::new(
+        Duration::from_millis(config.commit_interval_ms),
+        NonZeroUsize::new(usize::MAX).unwrap(),
+        config.max_events,
+    );
+    let batches = requests.batched(settings.into_item_size_config(|_: &KafkaRequest| 1));
+    tokio::pin!(batches);
+
+    while let Some(batch) = batches.next().await {
+        let count = batch.len();
+        let mut finalizers = EventFinalizers::default();
+        let requests = batch
+            .into_iter()
+            .map(|mut request| {
+                finalizers.merge(request.take_finalizers());
+                request
+            })
+            .collect();
+
+        match send_transaction(&mut service, &producer, requests, timeout).await {
+            Ok(responses) => {
+                finalizers.update_status(EventStatus::Delivered);
+                for response in responses {
+                    emit!(&response.events_sent());
+                }
+            }
+            Err(error) => {
+                finalizers.update_status(EventStatus::Errored);
+                if is_fatal(&error) {
+                    error!(message = "Fatal Kafka transaction error.", %error);
+                    acker.ack(count);
+                    return Err(());
+                }
+                error!(message = "Kafka transaction aborted.", %error, events = count);
+            }
+        }
+        acker.ack(count);
+    }
+
+    Ok(())
+}
+
+async fn send_transaction(
+    service: &mut KafkaService,
+    producer: &FutureProducer<KafkaStatisticsContext>,
+    requests: Vec<KafkaRequest>,
+    timeout: Duration,
+) -> KafkaResult<Vec<KafkaResponse>> {
+    producer.begin_transaction()?;
+
+    let result =
+        match future::try_join_all(requests.into_iter().map(|request| service.call(request))).await
+        {
+            Ok(responses) => blocking(producer, move |producer| {
+                producer.commit_transaction(timeout)
+            })
+            .await
+            .map(|()| responses),
+            Err(error) => Err(error),
+        };
+
+    if let Err(error) = &result {
+        if !is_fatal(error) {
+            if let Err(error) = blocking(producer, move |producer| {
+                producer.abort_transaction(timeout)
+            })
+            .await
+            {
+                error!(message = "Failed aborting Kafka transaction.", %error);
+                return Err(error);
+            }
+        }
+    }
+    result
+}
+
+/// Runs a transactional operation of the producer, which blocks until completed.
+async fn blocking<F>(
+    producer: &FutureProducer<KafkaStatisticsContext>,
+    operation: F,
+) -> KafkaResult<()>
+where
+    F: FnOnce(&FutureProducer<KafkaStatisticsContext>) -> KafkaResult<()> + Send + 'static,
+{
+    let producer = producer.clone();
+    tokio::task::spawn_blocking(move || operation(&producer))
+        .await
+        .expect("Kafka transaction operation panicked")
+}
+
+/// Fatal errors leave the producer unable to run further transactions.
+fn is_fatal(error: &KafkaError) -> bool {
+    matches!(error, KafkaError::Transaction(error) if error.is_fatal())
+}
+
 pub(crate) async fn healthcheck(config: KafkaSinkConfig) -> crate::Result<()> {
     trace!("Healthcheck started.");
     let client = config.to_rdkafka(KafkaRole::Consumer).unwrap();
@@ -6,6 +6,7 @@ mod integration_test {
     use std::{
         collections::{BTreeMap, HashMap},
         future::ready,
+        num::NonZeroUsize,
         thread,
         time::Duration,
     };
@@ -23,11 +24,12 @@ mod integration_test {
     };
 
     use crate::{
+        config::ProxyConfig,
         event::Value,
         kafka::{KafkaAuthConfig, KafkaCompression, KafkaSaslConfig, KafkaTlsConfig},
         sinks::{
             kafka::{
-                config::{KafkaRole, KafkaSinkConfig},
+                config::{KafkaRole, KafkaSinkConfig, KafkaTransactionConfig},
                 sink::KafkaSink,
                 *,
             },
@@ -67,6 +69,9 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_key: None,
+            idempotence: false,
+            transaction: None,
+            schema_validation: None,
             acknowledgements: Default::default(),
         };
         self::sink::healthcheck(config).await.unwrap();
@@ -122,13 +127,16 @@ mod integration_test {
             batch,
             librdkafka_options,
             headers_key: None,
+            idempotence: false,
+            transaction: None,
+            schema_validation: None,
             acknowledgements: Default::default(),
         };
         let (acker, _ack_counter) = Acker::basic();
         config.clone().to_rdkafka(KafkaRole::Consumer)?;
         config.clone().to_rdkafka(KafkaRole::Producer)?;
         self::sink::healthcheck(config.clone()).await?;
-        KafkaSink::new(config, acker)
+        KafkaSink::new(config, acker, &ProxyConfig::default())
     }
 
     #[tokio::test]
@@ -244,6 +252,79 @@ mod integration_test {
         .await;
     }
 
+    #[tokio::test]
+    async fn kafka_transactions() {
+        crate::test_util::trace_init();
+
+        let topic = format!("test-{}", random_string(10));
+        let config = KafkaSinkConfig {
+            bootstrap_servers: kafka_address(9091),
+            topic: topic.clone(),
+            key_field: None,
+            encoding: EncodingConfig::from(StandardEncodings::Text),
+            batch: BatchConfig::default(),
+            compression: KafkaCompression::None,
+            auth: KafkaAuthConfig::default(),
+            socket_timeout_ms: 60000,
+            message_timeout_ms: 300000,
+            librdkafka_options: HashMap::new(),
+            headers_key: None,
+            idempotence: false,
+            schema_validation: None,
+            transaction: Some(KafkaTransactionConfig {
+                transactional_id: random_string(10),
+                timeout_ms: 60000,
+                max_events: NonZeroUsize::new(100).unwrap(),
+                commit_interval_ms: 1000,
+            }),
+            acknowledgements: Default::default(),
+        };
+        let (acker, ack_counter) = Acker::basic();
+        let sink = VectorSink::from_event_streamsink(
+            KafkaSink::new(config, acker, &ProxyConfig::default()).unwrap(),
+        );
+
+        let num_events = 1000;
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(100, num_events, Some(batch));
+        sink.run(events).await.unwrap();
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+        assert_eq!(
+            ack_counter.load(std::sync::atomic::Ordering::Relaxed),
+            num_events
+        );
+
+        // Only committed messages are read.
+        let mut client_config = rdkafka::ClientConfig::new();
+        client_config.set("bootstrap.servers", kafka_address(9091).as_str());
+        client_config.set("group.id", &random_string(10));
+        client_config.set("isolation.level", "read_committed");
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition(&topic, 0)
+            .set_offset(Offset::Beginning)
+            .unwrap();
+
+        let consumer: BaseConsumer = client_config.create().unwrap();
+        consumer.assign(&tpl).unwrap();
+
+        let mut failures = 0;
+        let mut out = Vec::new();
+        while failures < 100 && out.len() < input.len() {
+            match consumer.poll(Duration::from_secs(3)) {
+                Some(Ok(msg)) => {
+                    let s: &str = msg.payload_view().unwrap().unwrap();
+                    out.push(s.to_owned());
+                }
+                _ => {
+                    failures += 1;
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+        assert_eq!(out, input);
+    }
+
     async fn kafka_happy_path(
         server: String,
         sasl: Option<KafkaSaslConfig>,
@@ -265,12 +346,15 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_key: Some(headers_key.clone()),
+            idempotence: false,
+            transaction: None,
+            schema_validation: None,
             acknowledgements: Default::default(),
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
         println!("Topic name generated in test: {:?}", topic);
         let (acker, ack_counter) = Acker::basic();
-        let sink = KafkaSink::new(config, acker).unwrap();
+        let sink = KafkaSink::new(config, acker, &ProxyConfig::default()).unwrap();
         let sink = VectorSink::from_event_streamsink(sink);
 
         let num_events = 1000;
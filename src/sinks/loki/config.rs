@@ -26,9 +26,13 @@ pub struct LokiConfig {
 
     pub tenant_id: Option<Template>,
     pub labels: HashMap<Template, Template>,
+    #[serde(default)]
+    pub structured_metadata: HashMap<Template, Template>,
 
     #[serde(default = "crate::serde::default_false")]
     pub remove_label_fields: bool,
+    #[serde(default = "crate::serde::default_false")]
+    pub remove_structured_metadata_fields: bool,
     #[serde(default = "crate::serde::default_true")]
     pub remove_timestamp: bool,
     #[serde(default)]
@@ -70,6 +74,9 @@ pub enum OutOfOrderAction {
     #[derivative(Default)]
     Drop,
     RewriteTimestamp,
+    /// Sends the out-of-order events as they are, for Loki to ingest them within its
+    /// out-of-order window.
+    Accept,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -1,5 +1,9 @@
-use std::{collections::HashMap, io};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
 
+use indexmap::IndexMap;
 use serde::{ser::SerializeSeq, Serialize};
 use vector_core::{
     event::{EventFinalizers, Finalizable},
@@ -19,8 +23,8 @@ impl Encoder<Vec<LokiRecord>> for LokiBatchEncoder {
         input: Vec<LokiRecord>,
         writer: &mut dyn io::Write,
     ) -> io::Result<usize> {
-        let batch = LokiBatch::from(input);
-        let body = serde_json::json!({ "streams": [batch] });
+        let streams = LokiBatch::streams(input);
+        let body = serde_json::json!({ "streams": streams });
         let body = serde_json::to_vec(&body)?;
         writer.write(&body)
     }
@@ -34,6 +38,20 @@ pub struct LokiBatch {
     finalizers: EventFinalizers,
 }
 
+impl LokiBatch {
+    /// Groups the records of a batch, which all belong to the same tenant, by their streams.
+    fn streams(records: Vec<LokiRecord>) -> Vec<Self> {
+        let mut streams = IndexMap::<PartitionKey, Vec<LokiRecord>>::new();
+        for record in records {
+            streams
+                .entry(record.partition.clone())
+                .or_default()
+                .push(record);
+        }
+        streams.into_values().map(Self::from).collect()
+    }
+}
+
 impl From<Vec<LokiRecord>> for LokiBatch {
     fn from(events: Vec<LokiRecord>) -> Self {
         let mut result = events
@@ -53,11 +71,15 @@ impl From<Vec<LokiRecord>> for LokiBatch {
 pub struct LokiEvent {
     pub timestamp: i64,
     pub event: String,
+    /// The structured metadata of the line, supported since Loki 3.0.
+    pub structured_metadata: Labels,
 }
 
 impl ByteSizeOf for LokiEvent {
     fn allocated_bytes(&self) -> usize {
-        self.timestamp.allocated_bytes() + self.event.allocated_bytes()
+        self.timestamp.allocated_bytes()
+            + self.event.allocated_bytes()
+            + labels_allocated_bytes(&self.structured_metadata)
     }
 }
 
@@ -66,9 +88,24 @@ impl Serialize for LokiEvent {
     where
         S: serde::Serializer,
     {
-        let mut seq = serializer.serialize_seq(Some(2))?;
+        // The structured metadata is left out when empty, for older versions of Loki to
+        // accept the lines.
+        let len = if self.structured_metadata.is_empty() {
+            2
+        } else {
+            3
+        };
+        let mut seq = serializer.serialize_seq(Some(len))?;
         seq.serialize_element(&self.timestamp.to_string())?;
         seq.serialize_element(&self.event)?;
+        if !self.structured_metadata.is_empty() {
+            let metadata = self
+                .structured_metadata
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect::<BTreeMap<_, _>>();
+            seq.serialize_element(&metadata)?;
+        }
         seq.end()
     }
 }
@@ -84,13 +121,17 @@ pub struct LokiRecord {
 impl ByteSizeOf for LokiRecord {
     fn allocated_bytes(&self) -> usize {
         self.partition.allocated_bytes()
-            + self.labels.iter().fold(0, |res, item| {
-                res + item.0.allocated_bytes() + item.1.allocated_bytes()
-            })
+            + labels_allocated_bytes(&self.labels)
             + self.event.allocated_bytes()
     }
 }
 
+fn labels_allocated_bytes(labels: &Labels) -> usize {
+    labels.iter().fold(0, |res, item| {
+        res + item.0.allocated_bytes() + item.1.allocated_bytes()
+    })
+}
+
 impl Finalizable for LokiRecord {
     fn take_finalizers(&mut self) -> EventFinalizers {
         std::mem::take(&mut self.finalizers)
@@ -6,7 +6,8 @@
 //! <https://github.com/grafana/loki/tree/v1.6.1/docs>
 //!
 //! This sink uses `PartitionBatching` to partition events
-//! by streams. There must be at least one valid set of labels.
+//! by tenant, each request holding the streams of a single
+//! tenant. There must be at least one valid set of labels.
 //!
 //! If an event produces no labels, this can happen if the template
 //! does not match, we will add a default label `{agent="vector"}`.
@@ -15,7 +15,7 @@ use vector_core::{
 
 use super::{
     config::{Encoding, LokiConfig, OutOfOrderAction},
-    event::{LokiBatchEncoder, LokiEvent, LokiRecord, PartitionKey},
+    event::{Labels, LokiBatchEncoder, LokiEvent, LokiRecord, PartitionKey},
     service::{LokiRequest, LokiService},
 };
 use crate::{
@@ -66,10 +66,11 @@ struct RecordPartitioner;
 
 impl Partitioner for RecordPartitioner {
     type Item = LokiRecord;
-    type Key = PartitionKey;
+    type Key = Option<String>;
 
+    /// Batches the records by tenant, each request holding the streams of a single tenant.
     fn partition(&self, item: &Self::Item) -> Self::Key {
-        item.partition.clone()
+        item.partition.tenant_id.clone()
     }
 }
 
@@ -93,7 +94,7 @@ impl From<std::io::Error> for RequestBuildError {
     }
 }
 
-impl RequestBuilder<(PartitionKey, Vec<LokiRecord>)> for LokiRequestBuilder {
+impl RequestBuilder<(Option<String>, Vec<LokiRecord>)> for LokiRequestBuilder {
     type Metadata = (Option<String>, usize, EventFinalizers, usize);
     type Events = Vec<LokiRecord>;
     type Encoder = LokiBatchEncoder;
@@ -111,9 +112,9 @@ impl RequestBuilder<(PartitionKey, Vec<LokiRecord>)> for LokiRequestBuilder {
 
     fn split_input(
         &self,
-        input: (PartitionKey, Vec<LokiRecord>),
+        input: (Option<String>, Vec<LokiRecord>),
     ) -> (Self::Metadata, Self::Events) {
-        let (key, mut events) = input;
+        let (tenant_id, mut events) = input;
         let batch_size = events.len();
         let events_byte_size = events.size_of();
         let finalizers = events
@@ -124,7 +125,7 @@ impl RequestBuilder<(PartitionKey, Vec<LokiRecord>)> for LokiRequestBuilder {
             });
 
         (
-            (key.tenant_id, batch_size, finalizers, events_byte_size),
+            (tenant_id, batch_size, finalizers, events_byte_size),
             events,
         )
     }
@@ -152,13 +153,15 @@ pub(super) struct EventEncoder {
     key_partitioner: KeyPartitioner,
     encoding: EncodingConfig<Encoding>,
     labels: HashMap<Template, Template>,
+    structured_metadata: HashMap<Template, Template>,
     remove_label_fields: bool,
+    remove_structured_metadata_fields: bool,
     remove_timestamp: bool,
 }
 
 impl EventEncoder {
-    fn build_labels(&self, event: &Event) -> Vec<(String, String)> {
-        self.labels
+    fn render_pairs(templates: &HashMap<Template, Template>, event: &Event) -> Labels {
+        templates
             .iter()
             .filter_map(|(key_template, value_template)| {
                 if let (Ok(key), Ok(value)) = (
@@ -173,13 +176,11 @@ impl EventEncoder {
             .collect()
     }
 
-    fn remove_label_fields(&self, event: &mut Event) {
-        if self.remove_label_fields {
-            for template in self.labels.values() {
-                if let Some(fields) = template.get_fields() {
-                    for field in fields {
-                        event.as_mut_log().remove(field.as_str());
-                    }
+    fn remove_fields(templates: &HashMap<Template, Template>, event: &mut Event) {
+        for template in templates.values() {
+            if let Some(fields) = template.get_fields() {
+                for field in fields {
+                    event.as_mut_log().remove(field.as_str());
                 }
             }
         }
@@ -188,8 +189,14 @@ impl EventEncoder {
     pub(super) fn encode_event(&self, mut event: Event) -> LokiRecord {
         let tenant_id = self.key_partitioner.partition(&event);
         let finalizers = event.take_finalizers();
-        let mut labels = self.build_labels(&event);
-        self.remove_label_fields(&mut event);
+        let mut labels = Self::render_pairs(&self.labels, &event);
+        let structured_metadata = Self::render_pairs(&self.structured_metadata, &event);
+        if self.remove_label_fields {
+            Self::remove_fields(&self.labels, &mut event);
+        }
+        if self.remove_structured_metadata_fields {
+            Self::remove_fields(&self.structured_metadata, &mut event);
+        }
 
         let schema = log_schema();
         let timestamp_key = schema.timestamp_key();
@@ -231,7 +238,11 @@ impl EventEncoder {
 
         LokiRecord {
             labels,
-            event: LokiEvent { timestamp, event },
+            event: LokiEvent {
+                timestamp,
+                event,
+                structured_metadata,
+            },
             partition,
             finalizers,
         }
@@ -254,6 +265,10 @@ impl RecordFilter {
 
 impl RecordFilter {
     pub fn filter_record(&mut self, mut record: LokiRecord) -> Option<LokiRecord> {
+        if let OutOfOrderAction::Accept = self.out_of_order_action {
+            return Some(record);
+        }
+
         if let Some(latest) = self.timestamps.get_mut(&record.partition) {
             if record.event.timestamp < *latest {
                 match self.out_of_order_action {
@@ -266,6 +281,7 @@ impl RecordFilter {
                         record.event.timestamp = *latest;
                         Some(record)
                     }
+                    OutOfOrderAction::Accept => Some(record),
                 }
             } else {
                 *latest = record.event.timestamp;
@@ -304,7 +320,9 @@ impl LokiSink {
                 key_partitioner: KeyPartitioner::new(config.tenant_id),
                 encoding: config.encoding,
                 labels: config.labels,
+                structured_metadata: config.structured_metadata,
                 remove_label_fields: config.remove_label_fields,
+                remove_structured_metadata_fields: config.remove_structured_metadata_fields,
                 remove_timestamp: config.remove_timestamp,
             },
             batch_settings: config.batch.into_batcher_settings()?,
@@ -356,14 +374,17 @@ mod tests {
     use std::{collections::HashMap, convert::TryFrom};
 
     use futures::stream::StreamExt;
-    use vector_core::event::Event;
+    use vector_core::{event::Event, partition::Partitioner};
 
-    use super::{EventEncoder, KeyPartitioner, RecordFilter};
+    use super::{EventEncoder, KeyPartitioner, RecordFilter, RecordPartitioner};
     use crate::{
         config::log_schema,
         sinks::{
-            loki::config::{Encoding, OutOfOrderAction},
-            util::encoding::EncodingConfig,
+            loki::{
+                config::{Encoding, OutOfOrderAction},
+                event::LokiBatchEncoder,
+            },
+            util::encoding::{Encoder, EncodingConfig},
         },
         template::Template,
         test_util::random_lines,
@@ -375,7 +396,9 @@ mod tests {
             key_partitioner: KeyPartitioner::new(None),
             encoding: EncodingConfig::from(Encoding::Json),
             labels: HashMap::default(),
+            structured_metadata: HashMap::default(),
             remove_label_fields: false,
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -405,7 +428,9 @@ mod tests {
             key_partitioner: KeyPartitioner::new(None),
             encoding: EncodingConfig::from(Encoding::Json),
             labels,
+            structured_metadata: HashMap::default(),
             remove_label_fields: false,
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -427,7 +452,9 @@ mod tests {
             key_partitioner: KeyPartitioner::new(None),
             encoding: EncodingConfig::from(Encoding::Json),
             labels: HashMap::default(),
+            structured_metadata: HashMap::default(),
             remove_label_fields: false,
+            remove_structured_metadata_fields: false,
             remove_timestamp: true,
         };
         let mut event = Event::from("hello world");
@@ -452,7 +479,9 @@ mod tests {
             key_partitioner: KeyPartitioner::new(None),
             encoding: EncodingConfig::from(Encoding::Json),
             labels,
+            structured_metadata: HashMap::default(),
             remove_label_fields: true,
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -470,7 +499,9 @@ mod tests {
             key_partitioner: KeyPartitioner::new(None),
             encoding: EncodingConfig::from(Encoding::Json),
             labels: HashMap::default(),
+            structured_metadata: HashMap::default(),
             remove_label_fields: false,
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let base = chrono::Utc::now();
@@ -503,4 +534,99 @@ mod tests {
         }
         assert_eq!(result.len(), 17);
     }
+
+    #[test]
+    fn encoder_with_structured_metadata() {
+        let mut structured_metadata = HashMap::default();
+        structured_metadata.insert(
+            Template::try_from("trace_id").unwrap(),
+            Template::try_from("{{ trace_id }}").unwrap(),
+        );
+        let encoder = EventEncoder {
+            key_partitioner: KeyPartitioner::new(None),
+            encoding: EncodingConfig::from(Encoding::Json),
+            labels: HashMap::default(),
+            structured_metadata,
+            remove_label_fields: false,
+            remove_structured_metadata_fields: true,
+            remove_timestamp: true,
+        };
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("trace_id", "abc");
+        let record = encoder.encode_event(event);
+        assert!(!record.event.event.contains("trace_id"));
+        assert_eq!(
+            record.event.structured_metadata,
+            vec![("trace_id".to_string(), "abc".to_string())]
+        );
+        assert_eq!(
+            serde_json::to_value(&record.event).unwrap()[2],
+            serde_json::json!({ "trace_id": "abc" })
+        );
+    }
+
+    #[test]
+    fn filter_accept() {
+        let encoder = EventEncoder {
+            key_partitioner: KeyPartitioner::new(None),
+            encoding: EncodingConfig::from(Encoding::Json),
+            labels: HashMap::default(),
+            structured_metadata: HashMap::default(),
+            remove_label_fields: false,
+            remove_structured_metadata_fields: false,
+            remove_timestamp: false,
+        };
+        let base = chrono::Utc::now();
+        let mut filter = RecordFilter::new(OutOfOrderAction::Accept);
+        for i in 0..10 {
+            let mut event = Event::from("hello world");
+            let ts = base - chrono::Duration::seconds(i);
+            event.as_mut_log().insert(log_schema().timestamp_key(), ts);
+            let record = filter.filter_record(encoder.encode_event(event)).unwrap();
+            assert_eq!(record.event.timestamp, ts.timestamp_nanos());
+        }
+    }
+
+    #[test]
+    fn encodes_streams_of_tenant() {
+        let mut labels = HashMap::default();
+        labels.insert(
+            Template::try_from("app").unwrap(),
+            Template::try_from("{{ app }}").unwrap(),
+        );
+        let encoder = EventEncoder {
+            key_partitioner: KeyPartitioner::new(Some(Template::try_from("{{ tenant }}").unwrap())),
+            encoding: EncodingConfig::from(Encoding::Text),
+            labels,
+            structured_metadata: HashMap::default(),
+            remove_label_fields: false,
+            remove_structured_metadata_fields: false,
+            remove_timestamp: false,
+        };
+        let records = ["a", "b", "a"]
+            .iter()
+            .map(|app| {
+                let mut event = Event::from("hello world");
+                event.as_mut_log().insert("app", *app);
+                event.as_mut_log().insert("tenant", "team");
+                encoder.encode_event(event)
+            })
+            .collect::<Vec<_>>();
+        let partitioner = RecordPartitioner::default();
+        assert!(records
+            .iter()
+            .all(|record| partitioner.partition(record) == Some("team".to_string())));
+
+        let mut body = Vec::new();
+        LokiBatchEncoder::default()
+            .encode_input(records, &mut body)
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let streams = body["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0]["stream"], serde_json::json!({ "app": "a" }));
+        assert_eq!(streams[0]["values"].as_array().unwrap().len(), 2);
+        assert_eq!(streams[1]["stream"], serde_json::json!({ "app": "b" }));
+        assert_eq!(streams[1]["values"].as_array().unwrap().len(), 1);
+    }
 }
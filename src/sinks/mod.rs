@@ -19,12 +19,16 @@ pub mod aws_sqs;
 pub mod azure_blob;
 #[cfg(any(feature = "sinks-azure_blob", feature = "sinks-datadog_archives"))]
 pub mod azure_common;
+#[cfg(feature = "sinks-azure_data_explorer")]
+pub mod azure_data_explorer;
 #[cfg(feature = "sinks-azure_monitor_logs")]
 pub mod azure_monitor_logs;
 #[cfg(feature = "sinks-blackhole")]
 pub mod blackhole;
 #[cfg(feature = "sinks-clickhouse")]
 pub mod clickhouse;
+#[cfg(feature = "sinks-clickhouse_native")]
+pub mod clickhouse_native;
 #[cfg(feature = "sinks-console")]
 pub mod console;
 #[cfg(any(
@@ -43,6 +47,8 @@ pub mod file;
 pub mod gcp;
 #[cfg(any(feature = "sinks-gcp"))]
 pub mod gcs_common;
+#[cfg(feature = "sinks-grpc")]
+pub mod grpc;
 #[cfg(feature = "sinks-honeycomb")]
 pub mod honeycomb;
 #[cfg(feature = "sinks-http")]
@@ -65,6 +71,8 @@ pub mod new_relic;
 pub mod new_relic_logs;
 #[cfg(feature = "sinks-papertrail")]
 pub mod papertrail;
+#[cfg(feature = "sinks-postgres")]
+pub mod postgres;
 #[cfg(feature = "sinks-prometheus")]
 pub mod prometheus;
 #[cfg(feature = "sinks-pulsar")]
@@ -75,12 +83,16 @@ pub mod redis;
 pub mod s3_common;
 #[cfg(feature = "sinks-sematext")]
 pub mod sematext;
+#[cfg(feature = "sinks-smtp")]
+pub mod smtp;
 #[cfg(feature = "sinks-socket")]
 pub mod socket;
 #[cfg(feature = "sinks-splunk_hec")]
 pub mod splunk_hec;
 #[cfg(feature = "sinks-statsd")]
 pub mod statsd;
+#[cfg(feature = "sinks-syslog")]
+pub mod syslog;
 #[cfg(feature = "sinks-vector")]
 pub mod vector;
 
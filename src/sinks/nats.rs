@@ -1,7 +1,8 @@
-use std::convert::TryFrom;
+use std::{borrow::Cow, convert::TryFrom, time::Duration};
 
 use async_trait::async_trait;
 use futures::{stream::BoxStream, FutureExt, StreamExt, TryFutureExt};
+use nats::header::HeaderMap;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use vector_buffers::Acker;
@@ -10,8 +11,10 @@ use crate::{
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
     },
-    event::Event,
-    internal_events::{NatsEventSendError, NatsEventSendSuccess, TemplateRenderingError},
+    event::{Event, EventStatus, Finalizable},
+    internal_events::{
+        NatsEventSendError, NatsEventSendSuccess, NatsJetStreamPublishError, TemplateRenderingError,
+    },
     nats::{from_tls_auth_config, NatsAuthConfig, NatsConfigError},
     sinks::util::{
         encoding::{EncodingConfig, EncodingConfiguration},
@@ -29,6 +32,36 @@ enum BuildError {
     Config { source: NatsConfigError },
     #[snafu(display("NATS Connect Error: {}", source))]
     Connect { source: std::io::Error },
+    #[snafu(display("Failed to subscribe to the JetStream replies: {}", source))]
+    Subscribe { source: std::io::Error },
+}
+
+#[derive(Debug, Snafu)]
+pub enum JetStreamError {
+    #[snafu(display("Failed to publish: {}", source))]
+    Publish { source: std::io::Error },
+    #[snafu(display("Timed out waiting for the acknowledgement of the stream"))]
+    Timeout,
+    #[snafu(display("The subscription to the replies was closed"))]
+    Closed,
+    #[snafu(display("No stream stores messages published to the subject"))]
+    NoResponders,
+    #[snafu(display("Invalid reply from the stream: {}", source))]
+    InvalidReply { source: serde_json::Error },
+    #[snafu(display("The stream rejected the message: {} ({})", description, code))]
+    Rejected { code: u16, description: String },
+}
+
+impl JetStreamError {
+    /// Whether the message may be stored when published again, the server or stream being
+    /// unavailable rather than refusing it.
+    const fn is_transient(&self) -> bool {
+        match self {
+            Self::Rejected { code, .. } => *code == 503,
+            Self::InvalidReply { .. } => false,
+            _ => true,
+        }
+    }
 }
 
 /**
@@ -45,12 +78,39 @@ pub struct NatsSinkConfig {
     tls: Option<TlsConfig>,
     #[serde(flatten)]
     auth: Option<NatsAuthConfig>,
+    #[serde(default)]
+    jetstream: NatsJetStreamConfig,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
 }
 
 fn default_name() -> String {
     String::from("vector")
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NatsJetStreamConfig {
+    /// Publishes the messages to JetStream, waiting for each of them to be acknowledged by the
+    /// stream storing it.
+    #[serde(default)]
+    enabled: bool,
+    /// Rendered as the `Nats-Msg-Id` header, by which the streams deduplicate the messages.
+    message_id: Option<Template>,
+    /// The stream expected to store the messages, which are rejected by the other ones.
+    expected_stream: Option<String>,
+    #[serde(default = "default_ack_timeout_secs")]
+    ack_timeout_secs: u64,
+}
+
+const fn default_ack_timeout_secs() -> u64 {
+    5
+}
+
 #[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum Encoding {
@@ -96,7 +156,7 @@ impl SinkConfig for NatsSinkConfig {
     }
 
     fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
-        None
+        Some(&self.acknowledgements)
     }
 }
 
@@ -124,32 +184,64 @@ pub struct NatsSink {
     encoding: EncodingConfig<Encoding>,
     connection: nats::asynk::Connection,
     subject: Template,
+    jetstream: Option<JetStreamPublisher>,
     acker: Acker,
 }
 
 impl NatsSink {
     async fn new(config: NatsSinkConfig, acker: Acker) -> Result<Self, BuildError> {
         let connection = config.connect().await?;
+        let jetstream = if config.jetstream.enabled {
+            Some(JetStreamPublisher::new(&connection, config.jetstream).await?)
+        } else {
+            None
+        };
 
         Ok(NatsSink {
             connection,
             encoding: config.encoding,
             subject: Template::try_from(config.subject).context(SubjectTemplateSnafu)?,
+            jetstream,
             acker,
         })
     }
+
+    fn render(
+        &self,
+        event: &Event,
+    ) -> Result<(String, Option<String>), (crate::template::TemplateRenderingError, &'static str)>
+    {
+        let subject = self
+            .subject
+            .render_string(event)
+            .map_err(|error| (error, "subject"))?;
+        let subject = if self.subject.is_dynamic() {
+            sanitize_subject(&subject).into_owned()
+        } else {
+            subject
+        };
+
+        let message_id = self
+            .jetstream
+            .as_ref()
+            .and_then(|jetstream| jetstream.message_id.as_ref())
+            .map(|template| template.render_string(event))
+            .transpose()
+            .map_err(|error| (error, "jetstream.message_id"))?;
+        Ok((subject, message_id))
+    }
 }
 
 #[async_trait]
 impl StreamSink<Event> for NatsSink {
-    async fn run(self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
-        while let Some(event) = input.next().await {
-            let subject = match self.subject.render_string(&event) {
-                Ok(subject) => subject,
-                Err(error) => {
+    async fn run(mut self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        while let Some(mut event) = input.next().await {
+            let (subject, message_id) = match self.render(&event) {
+                Ok(rendered) => rendered,
+                Err((error, field)) => {
                     emit!(&TemplateRenderingError {
                         error,
-                        field: Some("subject"),
+                        field: Some(field),
                         drop_event: true,
                     });
                     self.acker.ack(1);
@@ -157,20 +249,54 @@ impl StreamSink<Event> for NatsSink {
                 }
             };
 
+            let finalizers = event.take_finalizers();
             let log = encode_event(event, &self.encoding);
             let message_len = log.len();
 
-            match self.connection.publish(&subject, log).await {
-                Ok(_) => {
-                    emit!(&NatsEventSendSuccess {
-                        byte_size: message_len,
-                    });
-                }
-                Err(error) => {
-                    emit!(&NatsEventSendError { error });
+            let status = match &mut self.jetstream {
+                None => match self.connection.publish(&subject, log).await {
+                    Ok(_) => {
+                        emit!(&NatsEventSendSuccess {
+                            byte_size: message_len,
+                        });
+                        EventStatus::Delivered
+                    }
+                    Err(error) => {
+                        emit!(&NatsEventSendError { error });
+                        EventStatus::Errored
+                    }
+                },
+                Some(jetstream) => {
+                    let published = jetstream
+                        .publish(&self.connection, &subject, message_id.as_deref(), log)
+                        .await;
+                    match published {
+                        Ok(ack) => {
+                            trace!(
+                                message = "Message acknowledged by JetStream.",
+                                stream = %ack.stream,
+                                sequence = ack.seq,
+                                duplicate = ack.duplicate,
+                            );
+                            emit!(&NatsEventSendSuccess {
+                                byte_size: message_len,
+                            });
+                            EventStatus::Delivered
+                        }
+                        Err(error) => {
+                            let status = if error.is_transient() {
+                                EventStatus::Errored
+                            } else {
+                                EventStatus::Rejected
+                            };
+                            emit!(&NatsJetStreamPublishError { error });
+                            status
+                        }
+                    }
                 }
-            }
+            };
 
+            finalizers.update_status(status);
             self.acker.ack(1);
         }
 
@@ -178,6 +304,141 @@ impl StreamSink<Event> for NatsSink {
     }
 }
 
+const MSG_ID_HEADER: &str = "Nats-Msg-Id";
+const EXPECTED_STREAM_HEADER: &str = "Nats-Expected-Stream";
+
+/// Publishes the messages to JetStream, the streams acknowledging them by replying to the
+/// subjects of an inbox, one for each message.
+struct JetStreamPublisher {
+    inbox: String,
+    replies: nats::asynk::Subscription,
+    published: u64,
+    message_id: Option<Template>,
+    expected_stream: Option<String>,
+    ack_timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublishAck {
+    stream: String,
+    seq: u64,
+    /// Whether the stream already stored a message with the same identifier.
+    #[serde(default)]
+    duplicate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: u16,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PublishReply {
+    Error { error: ApiError },
+    Ack(PublishAck),
+}
+
+impl JetStreamPublisher {
+    async fn new(
+        connection: &nats::asynk::Connection,
+        config: NatsJetStreamConfig,
+    ) -> Result<Self, BuildError> {
+        let inbox = connection.new_inbox();
+        let replies = connection
+            .subscribe(&format!("{}.*", inbox))
+            .await
+            .context(SubscribeSnafu)?;
+        Ok(Self {
+            inbox,
+            replies,
+            published: 0,
+            message_id: config.message_id,
+            expected_stream: config.expected_stream,
+            ack_timeout: Duration::from_secs(config.ack_timeout_secs),
+        })
+    }
+
+    async fn publish(
+        &mut self,
+        connection: &nats::asynk::Connection,
+        subject: &str,
+        message_id: Option<&str>,
+        payload: String,
+    ) -> Result<PublishAck, JetStreamError> {
+        self.published += 1;
+        let reply_subject = format!("{}.{}", self.inbox, self.published);
+
+        let mut headers = HeaderMap::new();
+        if let Some(message_id) = message_id {
+            headers.insert(MSG_ID_HEADER, message_id);
+        }
+        if let Some(stream) = &self.expected_stream {
+            headers.insert(EXPECTED_STREAM_HEADER, stream.as_str());
+        }
+        let headers = (message_id.is_some() || self.expected_stream.is_some()).then(|| headers);
+
+        connection
+            .publish_with_reply_or_headers(
+                subject,
+                Some(reply_subject.as_str()),
+                headers.as_ref(),
+                payload,
+            )
+            .await
+            .context(PublishSnafu)?;
+
+        let timeout = tokio::time::sleep(self.ack_timeout);
+        tokio::pin!(timeout);
+        loop {
+            let reply = tokio::select! {
+                reply = self.replies.next() => reply.ok_or(JetStreamError::Closed)?,
+                _ = &mut timeout => return Err(JetStreamError::Timeout),
+            };
+            // The late replies to the messages which timed out are skipped.
+            if reply.subject == reply_subject {
+                return parse_reply(&reply.data);
+            }
+        }
+    }
+}
+
+fn parse_reply(data: &[u8]) -> Result<PublishAck, JetStreamError> {
+    // The server replies with an empty status message when no stream listens on the subject.
+    if data.is_empty() {
+        return Err(JetStreamError::NoResponders);
+    }
+    match serde_json::from_slice(data).context(InvalidReplySnafu)? {
+        PublishReply::Ack(ack) => Ok(ack),
+        PublishReply::Error { error } => Err(JetStreamError::Rejected {
+            code: error.code,
+            description: error.description,
+        }),
+    }
+}
+
+/// Replaces the characters which aren't allowed in the subjects messages are published to, and
+/// fills their empty tokens, so that the rendered values of fields can't make subjects invalid.
+fn sanitize_subject(subject: &str) -> Cow<'_, str> {
+    let valid = |c: char| !(c.is_whitespace() || c.is_control() || c == '*' || c == '>');
+    if subject.chars().all(valid) && !subject.split('.').any(str::is_empty) {
+        return Cow::Borrowed(subject);
+    }
+    let tokens = subject
+        .split('.')
+        .map(|token| match token {
+            "" => "_".to_owned(),
+            token => token
+                .chars()
+                .map(|c| if valid(c) { c } else { '_' })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    Cow::Owned(tokens.join("."))
+}
+
 fn encode_event(mut event: Event, encoding: &EncodingConfig<Encoding>) -> String {
     encoding.apply_rules(&mut event);
 
@@ -222,6 +483,56 @@ mod tests {
         let expected = r#"{"a":"0","x":"23","z":25}"#;
         assert_eq!(encoded, expected);
     }
+
+    #[test]
+    fn sanitizes_subjects() {
+        assert_eq!(sanitize_subject("logs.web"), "logs.web");
+        assert_eq!(sanitize_subject("logs.my host.*"), "logs.my_host._");
+        assert_eq!(sanitize_subject("logs..web."), "logs._.web._");
+        assert_eq!(sanitize_subject("logs.>"), "logs._");
+    }
+
+    #[test]
+    fn parses_publish_replies() {
+        let ack = parse_reply(br#"{"stream":"logs","seq":3,"duplicate":true}"#).unwrap();
+        assert_eq!(
+            (ack.stream.as_str(), ack.seq, ack.duplicate),
+            ("logs", 3, true)
+        );
+
+        let error = parse_reply(
+            br#"{"error":{"code":400,"err_code":10060,"description":"expected stream does not match"}}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(error, JetStreamError::Rejected { code: 400, .. }));
+        assert!(!error.is_transient());
+
+        let error =
+            parse_reply(br#"{"error":{"code":503,"description":"unavailable"}}"#).unwrap_err();
+        assert!(error.is_transient());
+
+        assert!(matches!(
+            parse_reply(b""),
+            Err(JetStreamError::NoResponders)
+        ));
+    }
+
+    #[test]
+    fn parses_jetstream_config() {
+        let config: NatsSinkConfig = toml::from_str(
+            r#"
+            encoding.codec = "json"
+            subject = "logs.{{ host }}"
+            url = "nats://127.0.0.1:4222"
+            jetstream.enabled = true
+            jetstream.message_id = "{{ id }}""#,
+        )
+        .unwrap();
+
+        assert!(config.jetstream.enabled);
+        assert_eq!(config.jetstream.message_id.unwrap().get_ref(), "{{ id }}");
+        assert_eq!(config.jetstream.ack_timeout_secs, 5);
+    }
 }
 
 #[cfg(feature = "nats-integration-tests")]
@@ -229,6 +540,9 @@ mod tests {
 mod integration_tests {
     use std::{thread, time::Duration};
 
+    use futures::stream;
+    use vector_core::event::{BatchNotifier, BatchStatus, LogEvent};
+
     use super::*;
     use crate::nats::{
         NatsAuthCredentialsFile, NatsAuthNKey, NatsAuthStrategy, NatsAuthToken,
@@ -297,6 +611,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4222".to_owned(),
             tls: None,
             auth: None,
@@ -320,6 +636,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4223".to_owned(),
             tls: None,
             auth: Some(NatsAuthConfig {
@@ -350,6 +668,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4224".to_owned(),
             tls: None,
             auth: Some(NatsAuthConfig {
@@ -380,6 +700,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4224".to_owned(),
             tls: None,
             auth: Some(NatsAuthConfig {
@@ -409,6 +731,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4224".to_owned(),
             tls: None,
             auth: Some(NatsAuthConfig {
@@ -438,6 +762,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4225".to_owned(),
             tls: None,
             auth: Some(NatsAuthConfig {
@@ -468,6 +794,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://127.0.0.1:4225".to_owned(),
             tls: None,
             auth: Some(NatsAuthConfig {
@@ -498,6 +826,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://localhost:4227".to_owned(),
             tls: Some(TlsConfig {
                 enabled: Some(true),
@@ -527,6 +857,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://localhost:4227".to_owned(),
             tls: None,
             auth: None,
@@ -550,6 +882,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://localhost:4228".to_owned(),
             tls: Some(TlsConfig {
                 enabled: Some(true),
@@ -581,6 +915,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://localhost:4228".to_owned(),
             tls: Some(TlsConfig {
                 enabled: Some(true),
@@ -610,6 +946,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://localhost:4229".to_owned(),
             tls: Some(TlsConfig {
                 enabled: Some(true),
@@ -645,6 +983,8 @@ mod integration_tests {
             encoding: EncodingConfig::from(Encoding::Text),
             connection_name: "".to_owned(),
             subject: subject.clone(),
+            jetstream: Default::default(),
+            acknowledgements: Default::default(),
             url: "nats://localhost:4229".to_owned(),
             tls: Some(TlsConfig {
                 enabled: Some(true),
@@ -669,4 +1009,82 @@ mod integration_tests {
             r
         );
     }
+
+    async fn publish_to_jetstream(expected_stream: Option<&str>) -> (BatchStatus, u64) {
+        let stream_name = format!("test-{}", random_string(10));
+        let subject = format!("{}.events", stream_name);
+
+        let conf = NatsSinkConfig {
+            encoding: EncodingConfig::from(Encoding::Text),
+            connection_name: "".to_owned(),
+            subject: subject.clone(),
+            jetstream: NatsJetStreamConfig {
+                enabled: true,
+                message_id: Some(Template::try_from("{{ id }}").unwrap()),
+                expected_stream: Some(expected_stream.unwrap_or(&stream_name).to_owned()),
+                ack_timeout_secs: 5,
+            },
+            acknowledgements: Default::default(),
+            url: "nats://127.0.0.1:4230".to_owned(),
+            tls: None,
+            auth: None,
+        };
+
+        let connection = conf.connect().await.unwrap();
+        let create = serde_json::json!({ "name": stream_name, "subjects": [subject] });
+        connection
+            .request(
+                &format!("$JS.API.STREAM.CREATE.{}", stream_name),
+                create.to_string(),
+            )
+            .await
+            .unwrap();
+
+        // Each identifier is used by two events, the second of which is a duplicate.
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let events = (0..4)
+            .map(|index| {
+                let mut log =
+                    LogEvent::from(format!("line {}", index).as_str()).with_batch_notifier(&batch);
+                log.insert("id", index / 2);
+                Event::from(log)
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        let (acker, _) = Acker::basic();
+        let sink = NatsSink::new(conf, acker).await.unwrap();
+        VectorSink::from_event_streamsink(sink)
+            .run(stream::iter(events).map(Into::into))
+            .await
+            .unwrap();
+
+        let info = connection
+            .request(&format!("$JS.API.STREAM.INFO.{}", stream_name), "")
+            .await
+            .unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&info.data).unwrap();
+        (
+            receiver.try_recv().unwrap(),
+            info["state"]["messages"].as_u64().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn nats_jetstream_deduplicates_messages() {
+        trace_init();
+
+        let (status, messages) = publish_to_jetstream(None).await;
+        assert_eq!(status, BatchStatus::Delivered);
+        assert_eq!(messages, 2);
+    }
+
+    #[tokio::test]
+    async fn nats_jetstream_rejects_unexpected_streams() {
+        trace_init();
+
+        let (status, messages) = publish_to_jetstream(Some("other")).await;
+        assert_eq!(status, BatchStatus::Rejected);
+        assert_eq!(messages, 0);
+    }
 }
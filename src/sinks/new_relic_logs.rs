@@ -158,7 +158,11 @@ impl NewRelicLogsConfig {
 
         let tower = TowerRequestConfig { ..self.request };
 
-        let request = RequestConfig { tower, headers };
+        let request = RequestConfig {
+            tower,
+            headers,
+            compression: None,
+        };
 
         Ok(HttpSinkConfig {
             uri: uri.into(),
@@ -170,6 +174,7 @@ impl NewRelicLogsConfig {
             batch: batch_settings.into(),
             request,
             tls: None,
+            signing: None,
             acknowledgements: self.acknowledgements,
         })
     }
@@ -171,6 +171,7 @@ mod tests {
                 schema: None,
                 only_fields: None,
                 except_fields: Some(vec!["magic".into()]),
+                rename_fields: None,
                 timestamp_format: None,
             },
         );
@@ -0,0 +1,168 @@
+use bytes::{BufMut, BytesMut};
+
+use super::config::{ColumnConfig, ConflictAction, ConflictConfig};
+use crate::event::{LogEvent, Value};
+
+/// The temporary table batches are copied into when handling conflicts.
+const STAGING_TABLE: &str = "vector_staging";
+
+pub(super) fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// The statements run against the database, built from the configuration once.
+#[derive(Debug)]
+pub(super) struct Statements {
+    /// Creates the schema and the table, run once per connection.
+    pub(super) create: Option<String>,
+    /// Creates the temporary staging table, run once per connection as it is only visible to
+    /// the session creating it.
+    pub(super) staging: Option<String>,
+    pub(super) copy: String,
+    /// Moves the rows of a batch from the staging table into the table.
+    pub(super) insert: Option<String>,
+}
+
+impl Statements {
+    pub(super) fn new(
+        schema: Option<&str>,
+        table: &str,
+        columns: &[ColumnConfig],
+        conflict: Option<&ConflictConfig>,
+        create_table: bool,
+    ) -> Self {
+        let table = match schema {
+            Some(schema) => format!("{}.{}", quote_identifier(schema), quote_identifier(table)),
+            None => quote_identifier(table),
+        };
+        let names = columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let create = create_table.then(|| {
+            let mut definitions = columns
+                .iter()
+                .map(|column| format!("{} {}", quote_identifier(&column.name), column.r#type))
+                .collect::<Vec<_>>();
+            // `ON CONFLICT` requires a unique index on the conflict columns.
+            if let Some(conflict) = conflict {
+                definitions.push(format!("UNIQUE ({})", quoted_list(&conflict.columns)));
+            }
+            let create = format!(
+                "CREATE TABLE IF NOT EXISTS {} ({})",
+                table,
+                definitions.join(", ")
+            );
+            match schema {
+                Some(schema) => format!(
+                    "CREATE SCHEMA IF NOT EXISTS {}; {}",
+                    quote_identifier(schema),
+                    create
+                ),
+                None => create,
+            }
+        });
+
+        match conflict {
+            None => Self {
+                create,
+                staging: None,
+                copy: format!("COPY {} ({}) FROM STDIN", table, names),
+                insert: None,
+            },
+            Some(conflict) => {
+                let staging = format!(
+                    "CREATE TEMPORARY TABLE IF NOT EXISTS {} ON COMMIT DELETE ROWS AS SELECT {} FROM {} WITH NO DATA",
+                    STAGING_TABLE, names, table
+                );
+                let keys = quoted_list(&conflict.columns);
+                let updates = columns
+                    .iter()
+                    .filter(|column| !conflict.columns.contains(&column.name))
+                    .map(|column| {
+                        let name = quote_identifier(&column.name);
+                        format!("{} = EXCLUDED.{}", name, name)
+                    })
+                    .collect::<Vec<_>>();
+                let insert = match conflict.action {
+                    ConflictAction::Update if !updates.is_empty() => {
+                        // A row can only be updated once per statement, so only the last of the
+                        // rows of the batch with the same keys is kept.
+                        format!(
+                            "INSERT INTO {} ({}) SELECT DISTINCT ON ({}) {} FROM {} ORDER BY {}, ctid DESC ON CONFLICT ({}) DO UPDATE SET {}",
+                            table,
+                            names,
+                            keys,
+                            names,
+                            STAGING_TABLE,
+                            keys,
+                            keys,
+                            updates.join(", ")
+                        )
+                    }
+                    _ => format!(
+                        "INSERT INTO {} ({}) SELECT {} FROM {} ON CONFLICT ({}) DO NOTHING",
+                        table, names, names, STAGING_TABLE, keys
+                    ),
+                };
+                Self {
+                    create,
+                    staging: Some(staging),
+                    copy: format!("COPY {} ({}) FROM STDIN", STAGING_TABLE, names),
+                    insert: Some(insert),
+                }
+            }
+        }
+    }
+}
+
+fn quoted_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| quote_identifier(name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Encodes the events as rows in the text format of `COPY`. Missing and null fields are `NULL`,
+/// objects and arrays are encoded as JSON.
+pub(super) fn encode_rows(columns: &[ColumnConfig], events: &[LogEvent]) -> BytesMut {
+    let mut buf = BytesMut::new();
+    for log in events {
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                buf.put_u8(b'\t');
+            }
+            encode_value(&mut buf, log.get(column.field()));
+        }
+        buf.put_u8(b'\n');
+    }
+    buf
+}
+
+fn encode_value(buf: &mut BytesMut, value: Option<&Value>) {
+    let text = match value {
+        None | Some(Value::Null) => return buf.put_slice(b"\\N"),
+        Some(Value::Float(value)) if value.is_infinite() => {
+            let infinity = if value.is_sign_positive() {
+                "Infinity"
+            } else {
+                "-Infinity"
+            };
+            return buf.put_slice(infinity.as_bytes());
+        }
+        Some(value) => value.to_string_lossy(),
+    };
+
+    for byte in text.bytes() {
+        match byte {
+            b'\\' => buf.put_slice(b"\\\\"),
+            b'\t' => buf.put_slice(b"\\t"),
+            b'\n' => buf.put_slice(b"\\n"),
+            b'\r' => buf.put_slice(b"\\r"),
+            byte => buf.put_u8(byte),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use futures::stream;
+use tokio_postgres::{Client, NoTls};
+use vector_core::event::{BatchNotifier, BatchStatus, BatchStatusReceiver};
+
+use super::*;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, LogEvent},
+    test_util::{
+        components::{self, HTTP_SINK_TAGS},
+        random_string, trace_init,
+    },
+};
+
+fn pg_url() -> String {
+    std::env::var("PG_URL").unwrap_or_else(|_| {
+        let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".into());
+        format!("postgres://vector:vector@{}/postgres", host)
+    })
+}
+
+fn gen_table() -> String {
+    format!("test_{}", random_string(10).to_lowercase())
+}
+
+async fn connect() -> Client {
+    let (client, connection) = tokio_postgres::connect(&pg_url(), NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client
+}
+
+fn config(table: &str, extra: &str) -> PostgresConfig {
+    toml::from_str(&format!(
+        r#"endpoint = "{}"
+        table = "{}"
+        create_table = true
+        columns = [
+            {{ name = "id", type = "bigint" }},
+            {{ name = "message" }},
+        ]
+        {}"#,
+        pg_url(),
+        table,
+        extra
+    ))
+    .unwrap()
+}
+
+fn make_events(rows: &[(i64, &str)]) -> (Vec<Event>, BatchStatusReceiver) {
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let events = rows
+        .iter()
+        .map(|(id, message)| {
+            let mut log = LogEvent::from(*message).with_batch_notifier(&batch);
+            log.insert("id", *id);
+            Event::from(log)
+        })
+        .collect();
+    (events, receiver)
+}
+
+async fn select_all(client: &Client, table: &str) -> Vec<(i64, String)> {
+    client
+        .query(
+            format!("SELECT id, message FROM {} ORDER BY id", table).as_str(),
+            &[],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect()
+}
+
+#[tokio::test]
+async fn inserts_events() {
+    trace_init();
+
+    let table = gen_table();
+    let (sink, healthcheck) = config(&table, "")
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+    healthcheck.await.unwrap();
+
+    let (events, mut receiver) = make_events(&[(1, "one"), (2, "two\tand\na half")]);
+    components::run_sink_events(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let client = connect().await;
+    assert_eq!(
+        select_all(&client, &table).await,
+        vec![(1, "one".into()), (2, "two\tand\na half".into())]
+    );
+}
+
+#[tokio::test]
+async fn handles_conflicts() {
+    trace_init();
+
+    let client = connect().await;
+    for (action, expected) in [("ignore", "first"), ("update", "last")] {
+        let table = gen_table();
+        let conflict = format!(
+            r#"conflict = {{ columns = ["id"], action = "{}" }}"#,
+            action
+        );
+
+        for batch in [&[(1, "first")][..], &[(1, "second"), (1, "last")][..]] {
+            let (sink, _) = config(&table, &conflict)
+                .build(SinkContext::new_test())
+                .await
+                .unwrap();
+            let (events, mut receiver) = make_events(batch);
+            components::run_sink_events(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+            assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+        }
+
+        assert_eq!(
+            select_all(&client, &table).await,
+            vec![(1, expected.into())],
+            "action {}",
+            action
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_invalid_rows() {
+    trace_init();
+
+    let table = gen_table();
+    let (sink, _) = config(&table, "")
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let mut log = LogEvent::from("not a number").with_batch_notifier(&batch);
+    log.insert("id", "one");
+    drop(batch);
+    sink.run(stream::once(async { Event::from(log).into() }))
+        .await
+        .unwrap();
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
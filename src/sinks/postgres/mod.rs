@@ -0,0 +1,39 @@
+//! A sink inserting events into a PostgreSQL table with `COPY`.
+//!
+//! Each batch is streamed to the server in the text format of `COPY`, the columns of which are
+//! rendered from the configured fields of the events and parsed by the server according to the
+//! types of the columns. When conflicts are handled, the batch is copied into a temporary table
+//! first, and then inserted from it with an `ON CONFLICT` clause, which `COPY` doesn't support.
+
+use snafu::Snafu;
+
+mod config;
+mod copy;
+mod service;
+mod sink;
+
+pub use config::PostgresConfig;
+
+#[derive(Debug, Snafu)]
+pub enum PostgresError {
+    #[snafu(display("Invalid endpoint: {}", source))]
+    InvalidEndpoint { source: tokio_postgres::Error },
+    #[snafu(display("Failed to create TLS connector: {}", source))]
+    Tls { source: openssl::error::ErrorStack },
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: tokio_postgres::Error },
+    #[snafu(display("Query failed: {}", source))]
+    Query { source: tokio_postgres::Error },
+    #[snafu(display("At least one column is required"))]
+    MissingColumns,
+    #[snafu(display("Conflict handling requires at least one conflict column"))]
+    MissingConflictColumns,
+    #[snafu(display("Conflict column {:?} isn't one of the columns", column))]
+    UnknownConflictColumn { column: String },
+}
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(all(test, feature = "postgres-integration-tests"))]
+mod integration_tests;
@@ -0,0 +1,248 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, SinkExt};
+use openssl::ssl::{SslConnector, SslMethod};
+use postgres_openssl::MakeTlsConnector;
+use snafu::ResultExt;
+use tokio::sync::Mutex;
+use tokio_postgres::{config::Host, error::SqlState, Client, Config, NoTls};
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{copy::Statements, ConnectSnafu, PostgresError, QuerySnafu, TlsSnafu};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    internal_events::EndpointBytesSent,
+    sinks::util::retries::RetryLogic,
+};
+
+#[derive(Debug, Clone)]
+pub(super) struct Connector {
+    config: Arc<Config>,
+    ca_file: Option<PathBuf>,
+    endpoint: Arc<str>,
+}
+
+impl Connector {
+    pub(super) fn new(config: Config, ca_file: Option<PathBuf>) -> Self {
+        let endpoint = config_to_endpoint(&config).into();
+        Self {
+            config: Arc::new(config),
+            ca_file,
+            endpoint,
+        }
+    }
+
+    pub(super) async fn connect(&self) -> Result<Client, PostgresError> {
+        let client = match &self.ca_file {
+            Some(ca_file) => {
+                let mut builder =
+                    SslConnector::builder(SslMethod::tls_client()).context(TlsSnafu)?;
+                builder.set_ca_file(ca_file).context(TlsSnafu)?;
+                let connector = MakeTlsConnector::new(builder.build());
+
+                let (client, connection) =
+                    self.config.connect(connector).await.context(ConnectSnafu)?;
+                tokio::spawn(connection);
+                client
+            }
+            None => {
+                let (client, connection) =
+                    self.config.connect(NoTls).await.context(ConnectSnafu)?;
+                tokio::spawn(connection);
+                client
+            }
+        };
+        Ok(client)
+    }
+}
+
+/// The hosts and ports of the endpoint, without the credentials.
+fn config_to_endpoint(config: &Config) -> String {
+    let ports = config.get_ports();
+    config
+        .get_hosts()
+        .iter()
+        .enumerate()
+        .map(|(index, host)| {
+            let host = match host {
+                Host::Tcp(host) => host.clone(),
+                #[cfg(unix)]
+                Host::Unix(path) => path.to_string_lossy().into_owned(),
+            };
+            // A single port applies to all the hosts.
+            match ports.get(index).or_else(|| ports.first()) {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Clone)]
+pub(super) struct PostgresService {
+    connector: Connector,
+    statements: Arc<Statements>,
+    /// The connections not used by an in-flight request, reused by the next ones.
+    idle_clients: Arc<Mutex<Vec<Client>>>,
+}
+
+impl PostgresService {
+    pub(super) fn new(connector: Connector, statements: Statements) -> Self {
+        Self {
+            connector,
+            statements: Arc::new(statements),
+            idle_clients: Arc::default(),
+        }
+    }
+
+    async fn client(&self) -> Result<Client, PostgresError> {
+        while let Some(client) = self.idle_clients.lock().await.pop() {
+            if !client.is_closed() {
+                return Ok(client);
+            }
+        }
+
+        let client = self.connector.connect().await?;
+        for statement in self
+            .statements
+            .create
+            .iter()
+            .chain(&self.statements.staging)
+        {
+            client.batch_execute(statement).await.context(QuerySnafu)?;
+        }
+        Ok(client)
+    }
+
+    async fn insert(&self, client: &mut Client, rows: Bytes) -> Result<(), PostgresError> {
+        match &self.statements.insert {
+            None => {
+                let sink = client
+                    .copy_in(self.statements.copy.as_str())
+                    .await
+                    .context(QuerySnafu)?;
+                futures::pin_mut!(sink);
+                sink.send(rows).await.context(QuerySnafu)?;
+                sink.finish().await.context(QuerySnafu)?;
+            }
+            Some(insert) => {
+                // The staging table is emptied when the transaction commits.
+                let transaction = client.transaction().await.context(QuerySnafu)?;
+                let sink = transaction
+                    .copy_in(self.statements.copy.as_str())
+                    .await
+                    .context(QuerySnafu)?;
+                futures::pin_mut!(sink);
+                sink.send(rows).await.context(QuerySnafu)?;
+                sink.finish().await.context(QuerySnafu)?;
+                transaction
+                    .execute(insert.as_str(), &[])
+                    .await
+                    .context(QuerySnafu)?;
+                transaction.commit().await.context(QuerySnafu)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(super) struct PostgresRequest {
+    /// The events as rows in the text format of `COPY`.
+    pub(super) rows: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) events_count: usize,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for PostgresRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for PostgresRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+pub(super) struct PostgresResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for PostgresResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+impl tower::Service<PostgresRequest> for PostgresService {
+    type Response = PostgresResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PostgresRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            let mut client = service.client().await?;
+            let byte_size = request.rows.len();
+            // The connection is dropped on errors, as it may be left in the middle of a `COPY`.
+            service.insert(&mut client, request.rows).await?;
+            service.idle_clients.lock().await.push(client);
+
+            emit!(&EndpointBytesSent {
+                byte_size,
+                protocol: "tcp",
+                endpoint: &service.connector.endpoint,
+            });
+            Ok(PostgresResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PostgresRetryLogic;
+
+impl RetryLogic for PostgresRetryLogic {
+    type Error = PostgresError;
+    type Response = PostgresResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            PostgresError::Connect { source } | PostgresError::Query { source } => {
+                !source.code().map_or(false, is_permanent)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether the error is caused by the data or the configuration, such as invalid values,
+/// violated constraints, failed authentication or missing tables, and would fail again.
+pub(super) fn is_permanent(code: &SqlState) -> bool {
+    matches!(code.code().get(..2), Some("22" | "23" | "28" | "42"))
+}
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt};
+use tower::util::BoxService;
+use vector_core::{buffers::Acker, stream::BatcherSettings, ByteSizeOf};
+
+use super::{
+    config::ColumnConfig,
+    copy,
+    service::{PostgresRequest, PostgresResponse},
+};
+use crate::{
+    event::{Event, Finalizable, LogEvent},
+    sinks::util::{SinkBuilderExt, StreamSink},
+};
+
+pub(super) struct PostgresSink {
+    pub(super) columns: Vec<ColumnConfig>,
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) service: BoxService<PostgresRequest, PostgresResponse, crate::Error>,
+    pub(super) acker: Acker,
+}
+
+impl PostgresSink {
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let columns = self.columns;
+        input
+            .batched(self.batch_settings.into_byte_size_config())
+            .map(|events| build_request(&columns, events))
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+fn build_request(columns: &[ColumnConfig], events: Vec<Event>) -> PostgresRequest {
+    let mut request = PostgresRequest {
+        events_count: events.len(),
+        ..Default::default()
+    };
+
+    let logs = events
+        .into_iter()
+        .map(|mut event| {
+            request.events_byte_size += event.size_of();
+            request.finalizers.merge(event.take_finalizers());
+            event.into_log()
+        })
+        .collect::<Vec<LogEvent>>();
+    request.rows = copy::encode_rows(columns, &logs).freeze();
+    request
+}
+
+#[async_trait]
+impl StreamSink<Event> for PostgresSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
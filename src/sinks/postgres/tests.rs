@@ -0,0 +1,172 @@
+use tokio_postgres::error::SqlState;
+
+use super::{
+    config::{ColumnConfig, ConflictAction, ConflictConfig},
+    copy::{encode_rows, Statements},
+    service::is_permanent,
+    *,
+};
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{LogEvent, Value},
+};
+
+fn columns() -> Vec<ColumnConfig> {
+    vec![
+        ColumnConfig {
+            name: "id".into(),
+            field: Some("request.id".into()),
+            r#type: "bigint".into(),
+        },
+        ColumnConfig {
+            name: "message".into(),
+            field: None,
+            r#type: "text".into(),
+        },
+    ]
+}
+
+fn conflict(action: ConflictAction) -> ConflictConfig {
+    ConflictConfig {
+        columns: vec!["id".into()],
+        action,
+    }
+}
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<PostgresConfig>();
+}
+
+#[test]
+fn builds_copy_statements() {
+    let statements = Statements::new(None, "logs", &columns(), None, false);
+
+    assert_eq!(statements.create, None);
+    assert_eq!(statements.staging, None);
+    assert_eq!(
+        statements.copy,
+        r#"COPY "logs" ("id", "message") FROM STDIN"#
+    );
+    assert_eq!(statements.insert, None);
+}
+
+#[test]
+fn builds_create_statements() {
+    let statements = Statements::new(
+        Some("vector"),
+        "lo\"gs",
+        &columns(),
+        Some(&conflict(ConflictAction::Ignore)),
+        true,
+    );
+
+    assert_eq!(
+        statements.create.as_deref(),
+        Some(
+            r#"CREATE SCHEMA IF NOT EXISTS "vector"; CREATE TABLE IF NOT EXISTS "vector"."lo""gs" ("id" bigint, "message" text, UNIQUE ("id"))"#
+        )
+    );
+}
+
+#[test]
+fn builds_conflict_statements() {
+    let statements = Statements::new(
+        None,
+        "logs",
+        &columns(),
+        Some(&conflict(ConflictAction::Ignore)),
+        false,
+    );
+    assert_eq!(
+        statements.staging.as_deref(),
+        Some(
+            r#"CREATE TEMPORARY TABLE IF NOT EXISTS vector_staging ON COMMIT DELETE ROWS AS SELECT "id", "message" FROM "logs" WITH NO DATA"#
+        )
+    );
+    assert_eq!(
+        statements.copy,
+        r#"COPY vector_staging ("id", "message") FROM STDIN"#
+    );
+    assert_eq!(
+        statements.insert.as_deref(),
+        Some(
+            r#"INSERT INTO "logs" ("id", "message") SELECT "id", "message" FROM vector_staging ON CONFLICT ("id") DO NOTHING"#
+        )
+    );
+
+    let statements = Statements::new(
+        None,
+        "logs",
+        &columns(),
+        Some(&conflict(ConflictAction::Update)),
+        false,
+    );
+    assert_eq!(
+        statements.insert.as_deref(),
+        Some(
+            r#"INSERT INTO "logs" ("id", "message") SELECT DISTINCT ON ("id") "id", "message" FROM vector_staging ORDER BY "id", ctid DESC ON CONFLICT ("id") DO UPDATE SET "message" = EXCLUDED."message""#
+        )
+    );
+}
+
+#[test]
+fn encodes_rows() {
+    let mut first = LogEvent::from("tab\tline\nback\\slash");
+    first.insert("request.id", 1);
+    let mut second = LogEvent::default();
+    second.insert("request.id", Value::Null);
+    let mut third = LogEvent::from("infinite");
+    third.insert("request.id", f64::NEG_INFINITY);
+
+    let rows = encode_rows(&columns(), &[first, second, third]);
+
+    assert_eq!(
+        &rows[..],
+        &b"1\ttab\\tline\\nback\\\\slash\n\\N\t\\N\n-Infinity\tinfinite\n"[..]
+    );
+}
+
+#[tokio::test]
+async fn validates_columns() {
+    let build = |config: &str| async move {
+        let config = toml::from_str::<PostgresConfig>(config).unwrap();
+        config.build(SinkContext::new_test()).await.map(|_| ())
+    };
+
+    let error = build(
+        r#"endpoint = "postgresql://localhost/logs"
+        table = "logs"
+        columns = []"#,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<PostgresError>(),
+        Some(PostgresError::MissingColumns)
+    ));
+
+    let error = build(
+        r#"endpoint = "postgresql://localhost/logs"
+        table = "logs"
+        columns = [{ name = "message" }]
+        conflict.columns = ["id"]"#,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<PostgresError>(),
+        Some(PostgresError::UnknownConflictColumn { column }) if column == "id"
+    ));
+}
+
+#[test]
+fn permanent_errors() {
+    assert!(is_permanent(&SqlState::INVALID_TEXT_REPRESENTATION));
+    assert!(is_permanent(&SqlState::UNIQUE_VIOLATION));
+    assert!(is_permanent(&SqlState::INVALID_PASSWORD));
+    assert!(is_permanent(&SqlState::UNDEFINED_TABLE));
+    assert!(!is_permanent(&SqlState::T_R_SERIALIZATION_FAILURE));
+    assert!(!is_permanent(&SqlState::TOO_MANY_CONNECTIONS));
+    assert!(!is_permanent(&SqlState::ADMIN_SHUTDOWN));
+}
@@ -2,8 +2,11 @@ use std::{collections::BTreeMap, fmt::Write as _};
 
 use chrono::Utc;
 use indexmap::map::IndexMap;
-use prometheus_parser::{proto, METRIC_NAME_LABEL};
-use vector_core::event::metric::{samples_to_buckets, MetricSketch, Quantile};
+use prometheus_parser::{is_stale_marker, proto, stale_marker, METRIC_NAME_LABEL};
+use vector_core::event::{
+    metric::{samples_to_buckets, MetricSketch, Quantile},
+    EventMetadata,
+};
 
 use crate::{
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
@@ -15,7 +18,13 @@ pub(super) trait MetricCollector {
 
     fn new() -> Self;
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue);
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        metadata: &EventMetadata,
+    );
 
     fn emit_value(
         &mut self,
@@ -42,7 +51,7 @@ pub(super) trait MetricCollector {
 
         if metric.kind() == MetricKind::Absolute {
             let tags = metric.tags();
-            self.emit_metadata(metric.name(), name, metric.value());
+            self.emit_metadata(metric.name(), name, metric.value(), metric.metadata());
 
             match metric.value() {
                 MetricValue::Counter { value } => {
@@ -121,6 +130,8 @@ pub(super) trait MetricCollector {
                     count,
                     sum,
                 } => {
+                    // The series of a stale histogram are all stale, as marked by its sum.
+                    let stale = is_stale_marker(*sum);
                     let mut bucket_count = 0.0;
                     for bucket in buckets {
                         // Aggregated histograms are cumulative in Prometheus.  This means that the
@@ -145,7 +156,7 @@ pub(super) trait MetricCollector {
                             timestamp,
                             name,
                             "_bucket",
-                            bucket_count,
+                            stale_or(stale, bucket_count),
                             tags,
                             Some(("le", bucket.upper_limit.to_string())),
                         );
@@ -154,12 +165,13 @@ pub(super) trait MetricCollector {
                         timestamp,
                         name,
                         "_bucket",
-                        *count as f64,
+                        stale_or(stale, *count as f64),
                         tags,
                         Some(("le", "+Inf".to_string())),
                     );
                     self.emit_value(timestamp, name, "_sum", *sum, tags, None);
-                    self.emit_value(timestamp, name, "_count", *count as f64, tags, None);
+                    let count = stale_or(stale, *count as f64);
+                    self.emit_value(timestamp, name, "_count", count, tags, None);
                 }
                 MetricValue::AggregatedSummary {
                     quantiles,
@@ -177,7 +189,8 @@ pub(super) trait MetricCollector {
                         );
                     }
                     self.emit_value(timestamp, name, "_sum", *sum, tags, None);
-                    self.emit_value(timestamp, name, "_count", *count as f64, tags, None);
+                    let count = stale_or(is_stale_marker(*sum), *count as f64);
+                    self.emit_value(timestamp, name, "_count", count, tags, None);
                 }
                 MetricValue::Sketch { sketch } => match sketch {
                     MetricSketch::AgentDDSketch(ddsketch) => {
@@ -231,7 +244,13 @@ impl MetricCollector for StringCollector {
         Self { processed }
     }
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        _metadata: &EventMetadata,
+    ) {
         if !self.processed.contains_key(fullname) {
             let header = Self::encode_header(name, fullname, value);
             self.processed.insert(fullname.into(), header);
@@ -354,14 +373,24 @@ impl MetricCollector for TimeSeries {
         }
     }
 
-    fn emit_metadata(&mut self, name: &str, fullname: &str, value: &MetricValue) {
+    fn emit_metadata(
+        &mut self,
+        name: &str,
+        fullname: &str,
+        value: &MetricValue,
+        metadata: &EventMetadata,
+    ) {
         if !self.metadata.contains_key(name) {
             let r#type = prometheus_metric_type(value);
             let metadata = proto::MetricMetadata {
                 r#type: r#type as i32,
                 metric_family_name: fullname.into(),
-                help: name.into(),
-                unit: String::new(),
+                help: metadata
+                    .metric_description()
+                    .as_deref()
+                    .unwrap_or(name)
+                    .into(),
+                unit: metadata.metric_unit().as_deref().unwrap_or_default().into(),
             };
             self.metadata.insert(name.into(), metadata);
         }
@@ -401,6 +430,15 @@ impl MetricCollector for TimeSeries {
     }
 }
 
+/// The stale marker in place of the value, if the metric is stale.
+fn stale_or(stale: bool, value: f64) -> f64 {
+    if stale {
+        stale_marker()
+    } else {
+        value
+    }
+}
+
 const fn prometheus_metric_type(metric_value: &MetricValue) -> proto::MetricType {
     use proto::MetricType;
     match metric_value {
@@ -899,6 +937,44 @@ mod tests {
         assert!(encoded.timeseries[0].samples[0].timestamp >= now);
     }
 
+    #[test]
+    fn encodes_description_and_unit_request() {
+        let mut metric = Metric::new(
+            "temperature".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 2.0 },
+        )
+        .with_timestamp(Some(timestamp()));
+        let metadata = metric.metadata_mut();
+        metadata.set_metric_description(Some("The temperature outside.".into()));
+        metadata.set_metric_unit(Some("celsius".into()));
+
+        let encoded = encode_one::<TimeSeries>(None, &[], &[], &metric);
+        assert_eq!(encoded.metadata[0].help, "The temperature outside.");
+        assert_eq!(encoded.metadata[0].unit, "celsius");
+    }
+
+    #[test]
+    fn encodes_stale_histogram_request() {
+        let metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![ 1.0 => 0, 2.1 => 0 ],
+                count: 0,
+                sum: stale_marker(),
+            },
+        )
+        .with_timestamp(Some(timestamp()));
+
+        let encoded = encode_one::<TimeSeries>(None, &[], &[], &metric);
+        assert_eq!(encoded.timeseries.len(), 5);
+        assert!(encoded
+            .timeseries
+            .iter()
+            .all(|timeseries| is_stale_marker(timeseries.samples[0].value)));
+    }
+
     fn timestamp() -> DateTime<Utc> {
         Utc.ymd(2021, 2, 3).and_hms_milli(4, 5, 6, 789)
     }
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     convert::Infallible,
     hash::Hash,
     mem::{discriminant, Discriminant},
@@ -8,9 +9,10 @@ use std::{
 };
 
 use async_trait::async_trait;
+use chrono::Utc;
 use futures::{future, stream::BoxStream, FutureExt, StreamExt};
 use hyper::{
-    header::HeaderValue,
+    header::{HeaderValue, ACCEPT},
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
@@ -22,12 +24,15 @@ use stream_cancel::{Trigger, Tripwire};
 use tracing_futures::Instrument;
 use vector_core::{
     buffers::Acker,
-    event::metric::MetricSeries,
+    event::metric::{MetricSeries, Sample, StatisticKind},
     internal_event::{BytesSent, EventsSent},
     ByteSizeOf,
 };
 
-use super::collector::{MetricCollector, StringCollector};
+use super::{
+    collector::{MetricCollector, StringCollector},
+    exposition::{self, Exemplar, FamilyCollector, HistogramDetails, NativeHistogram},
+};
 use crate::{
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, Resource, SinkConfig, SinkContext,
@@ -55,6 +60,13 @@ const MIN_FLUSH_PERIOD_SECS: u64 = 1;
 enum BuildError {
     #[snafu(display("Flush period for sets must be greater or equal to {} secs", min))]
     FlushPeriodTooShort { min: u64 },
+    #[snafu(display(
+        "Native histogram schema must be between {} and {}, got {}",
+        min,
+        max,
+        schema
+    ))]
+    InvalidNativeHistogramSchema { min: i32, max: i32, schema: i32 },
 }
 
 #[serde_as]
@@ -75,6 +87,12 @@ pub struct PrometheusExporterConfig {
     #[serde(default = "default_flush_period_secs")]
     #[serde_as(as = "serde_with::DurationSeconds<u64>")]
     pub flush_period_secs: Duration,
+    #[serde(default)]
+    pub exemplar_tags: Vec<String>,
+    #[serde(default)]
+    pub native_histograms: bool,
+    #[serde(default = "default_native_histogram_schema")]
+    pub native_histogram_schema: i32,
 }
 
 impl Default for PrometheusExporterConfig {
@@ -87,6 +105,9 @@ impl Default for PrometheusExporterConfig {
             quantiles: super::default_summary_quantiles(),
             distributions_as_summaries: default_distributions_as_summaries(),
             flush_period_secs: default_flush_period_secs(),
+            exemplar_tags: Vec::new(),
+            native_histograms: false,
+            native_histogram_schema: default_native_histogram_schema(),
         }
     }
 }
@@ -105,6 +126,10 @@ const fn default_flush_period_secs() -> Duration {
     Duration::from_secs(60)
 }
 
+const fn default_native_histogram_schema() -> i32 {
+    3
+}
+
 inventory::submit! {
     SinkDescription::new::<PrometheusExporterConfig>("prometheus")
 }
@@ -131,6 +156,17 @@ impl SinkConfig for PrometheusExporterConfig {
 
         validate_quantiles(&self.quantiles)?;
 
+        if self.native_histograms
+            && !(exposition::MIN_SCHEMA..=exposition::MAX_SCHEMA)
+                .contains(&self.native_histogram_schema)
+        {
+            return Err(Box::new(BuildError::InvalidNativeHistogramSchema {
+                min: exposition::MIN_SCHEMA,
+                max: exposition::MAX_SCHEMA,
+                schema: self.native_histogram_schema,
+            }));
+        }
+
         let sink = PrometheusExporter::new(self.clone(), cx.acker());
         let healthcheck = future::ok(()).boxed();
 
@@ -193,11 +229,13 @@ struct PrometheusExporter {
     acker: Acker,
 }
 
-/// Expiration metadata for a metric.
-#[derive(Clone, Copy, Debug)]
+/// Expiration metadata for a metric, along with the details of histograms that the metric itself
+/// doesn't hold.
+#[derive(Clone, Debug)]
 struct MetricMetadata {
     expiration_window: Duration,
     expires_at: Instant,
+    histogram: Option<HistogramDetails>,
 }
 
 impl MetricMetadata {
@@ -205,6 +243,7 @@ impl MetricMetadata {
         Self {
             expiration_window,
             expires_at: Instant::now() + expiration_window,
+            histogram: None,
         }
     }
 
@@ -319,16 +358,120 @@ impl MetricNormalize for PrometheusExporterMetricNormalizer {
     }
 }
 
+/// The samples and exemplar of a distribution, which are lost once it is aggregated into the
+/// buckets of a histogram.
+struct Observation {
+    kind: MetricKind,
+    samples: Vec<Sample>,
+    exemplar: Option<Exemplar>,
+}
+
+impl Observation {
+    /// Takes the exemplar tags out of the histogram distributions, so that their observations
+    /// are in the same series whatever their trace.
+    fn take(config: &PrometheusExporterConfig, metric: &mut Metric) -> Option<Self> {
+        if config.distributions_as_summaries
+            || (!config.native_histograms && config.exemplar_tags.is_empty())
+        {
+            return None;
+        }
+        let samples = match metric.value() {
+            MetricValue::Distribution {
+                samples,
+                statistic: StatisticKind::Histogram,
+            } => samples.clone(),
+            _ => return None,
+        };
+
+        let labels = config
+            .exemplar_tags
+            .iter()
+            .filter_map(|tag| metric.remove_tag(tag).map(|value| (tag.clone(), value)))
+            .collect::<BTreeMap<_, _>>();
+        let timestamp = metric.timestamp().unwrap_or_else(Utc::now);
+        let exemplar = samples
+            .last()
+            .filter(|_| !labels.is_empty())
+            .map(|sample| Exemplar {
+                labels,
+                value: sample.value,
+                timestamp,
+            });
+
+        Some(Self {
+            kind: metric.kind(),
+            samples,
+            exemplar,
+        })
+    }
+
+    fn record(self, details: &mut HistogramDetails, buckets: &[f64], native_schema: Option<i32>) {
+        if let Some(exemplar) = self.exemplar {
+            // The same bucket as the sample when aggregated, or the `+Inf` one.
+            let index = buckets
+                .iter()
+                .position(|bound| *bound >= exemplar.value)
+                .unwrap_or_else(|| buckets.len());
+            details.exemplars.insert(index, exemplar);
+        }
+        if let Some(schema) = native_schema {
+            if self.kind == MetricKind::Absolute || details.native.is_none() {
+                details.native = Some(NativeHistogram::new(schema));
+            }
+            if let Some(native) = &mut details.native {
+                native.observe(&self.samples);
+            }
+        }
+    }
+}
+
 fn handle(
     req: Request<Body>,
     default_namespace: Option<&str>,
     buckets: &[f64],
     quantiles: &[f64],
+    protobuf: bool,
     metrics: &IndexMap<MetricRef, (Metric, MetricMetadata)>,
 ) -> Response<Body> {
     let mut response = Response::new(Body::empty());
 
     match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics")
+            if protobuf
+                && req
+                    .headers()
+                    .get_all(ACCEPT)
+                    .iter()
+                    .filter_map(|accept| accept.to_str().ok())
+                    .any(exposition::accepts_protobuf) =>
+        {
+            let mut collector = FamilyCollector::new();
+
+            for (_, (metric, metadata)) in metrics {
+                collector.encode_metric(
+                    default_namespace,
+                    buckets,
+                    quantiles,
+                    metric,
+                    metadata.histogram.as_ref(),
+                );
+            }
+
+            let body = collector.finish();
+            let body_size = body.len();
+
+            *response.body_mut() = body.into();
+
+            response.headers_mut().insert(
+                "Content-Type",
+                HeaderValue::from_static(exposition::CONTENT_TYPE),
+            );
+
+            emit!(&BytesSent {
+                byte_size: body_size,
+                protocol: "http",
+            });
+        }
         (&Method::GET, "/metrics") => {
             let mut collector = StringCollector::new();
 
@@ -379,6 +522,8 @@ impl PrometheusExporter {
         let default_namespace = self.config.default_namespace.clone();
         let buckets = self.config.buckets.clone();
         let quantiles = self.config.quantiles.clone();
+        // Only the protobuf exposition format holds native histograms and exemplars.
+        let protobuf = self.config.native_histograms || !self.config.exemplar_tags.is_empty();
 
         let new_service = make_service_fn(move |_| {
             let span = crate::trace::current_span();
@@ -403,6 +548,7 @@ impl PrometheusExporter {
                             default_namespace.as_deref(),
                             &buckets,
                             &quantiles,
+                            protobuf,
                             &metrics,
                         );
 
@@ -491,7 +637,8 @@ impl StreamSink<Event> for PrometheusExporter {
             }
 
             // Now process the metric we got.
-            let metric = event.into_metric();
+            let mut metric = event.into_metric();
+            let observation = Observation::take(&self.config, &mut metric);
             if let Some(normalized) = normalizer.apply(metric) {
                 // We have a normalized metric, in absolute form.  If we're already aware of this
                 // metric, update its expiration deadline, otherwise, start tracking it.
@@ -504,9 +651,24 @@ impl StreamSink<Event> for PrometheusExporter {
                         metadata.refresh();
                     }
                     None => {
-                        metrics.insert(metric_ref, (normalized, MetricMetadata::new(flush_period)));
+                        metrics.insert(
+                            metric_ref.clone(),
+                            (normalized, MetricMetadata::new(flush_period)),
+                        );
                     }
                 }
+
+                if let (Some(observation), Some((_, metadata))) =
+                    (observation, metrics.get_mut(&metric_ref))
+                {
+                    observation.record(
+                        metadata.histogram.get_or_insert_with(Default::default),
+                        &self.config.buckets,
+                        self.config
+                            .native_histograms
+                            .then(|| self.config.native_histogram_schema),
+                    );
+                }
             }
 
             self.acker.ack(1);
@@ -521,6 +683,8 @@ mod tests {
     use chrono::{Duration, Utc};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use prometheus_parser::proto::client;
+    use prost::Message;
     use tokio::{sync::mpsc, time};
     use tokio_stream::wrappers::UnboundedReceiverStream;
     use vector_core::{event::StatisticKind, samples};
@@ -578,18 +742,123 @@ mod tests {
         );
     }
 
-    async fn export_and_fetch(tls_config: Option<TlsConfig>, events: Vec<Event>) -> String {
-        trace_init();
+    #[tokio::test]
+    async fn serves_native_histograms_with_exemplars() {
+        let distribution = |value, trace_id: &str| {
+            Event::from(
+                Metric::new(
+                    "latency",
+                    MetricKind::Incremental,
+                    MetricValue::Distribution {
+                        samples: samples![value => 1],
+                        statistic: StatisticKind::Histogram,
+                    },
+                )
+                .with_tags(Some(
+                    vec![
+                        ("code".to_owned(), "200".to_owned()),
+                        ("trace_id".to_owned(), trace_id.to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                )),
+            )
+        };
+        let config = PrometheusExporterConfig {
+            buckets: vec![1.0, 2.0],
+            exemplar_tags: vec!["trace_id".to_owned()],
+            native_histograms: true,
+            native_histogram_schema: 0,
+            ..Default::default()
+        };
+        let events = vec![
+            distribution(0.5, "a"),
+            distribution(1.5, "b"),
+            distribution(1.8, "c"),
+        ];
 
-        let client_settings = MaybeTlsSettings::from_config(&tls_config, false).unwrap();
-        let proto = client_settings.http_protocol_name();
+        let body = export_and_fetch_with(
+            config,
+            events,
+            Some("application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited"),
+        )
+        .await;
+        let family = client::MetricFamily::decode_length_delimited(body.as_slice()).unwrap();
 
-        let address = next_addr();
+        // The traces are exemplars, not labels of several series.
+        assert_eq!(family.metric.len(), 1);
+        assert_eq!(
+            family.metric[0].label,
+            vec![client::LabelPair {
+                name: Some("code".into()),
+                value: Some("200".into()),
+            }]
+        );
+        let histogram = family.metric[0].histogram.as_ref().unwrap();
+        assert_eq!(histogram.sample_count, Some(3));
+        let exemplars = histogram
+            .bucket
+            .iter()
+            .map(|bucket| {
+                bucket
+                    .exemplar
+                    .as_ref()
+                    .map(|exemplar| (exemplar.label[0].value.clone().unwrap(), exemplar.value))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exemplars,
+            vec![Some(("a".into(), Some(0.5))), Some(("c".into(), Some(1.8)))]
+        );
+        // The buckets (0.25, 0.5] and (1, 2] of schema 0.
+        assert_eq!(histogram.schema, Some(0));
+        assert_eq!(
+            histogram.positive_span,
+            vec![
+                client::BucketSpan {
+                    offset: Some(-1),
+                    length: Some(1),
+                },
+                client::BucketSpan {
+                    offset: Some(1),
+                    length: Some(1),
+                },
+            ]
+        );
+        assert_eq!(histogram.positive_delta, vec![1, 1]);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_native_histogram_schema() {
+        let config = PrometheusExporterConfig {
+            native_histograms: true,
+            native_histogram_schema: 9,
+            ..Default::default()
+        };
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
+    async fn export_and_fetch(tls_config: Option<TlsConfig>, events: Vec<Event>) -> String {
         let config = PrometheusExporterConfig {
-            address,
             tls: tls_config,
             ..Default::default()
         };
+        let body = export_and_fetch_with(config, events, None).await;
+        String::from_utf8(body).unwrap()
+    }
+
+    async fn export_and_fetch_with(
+        config: PrometheusExporterConfig,
+        events: Vec<Event>,
+        accept: Option<&str>,
+    ) -> Vec<u8> {
+        trace_init();
+
+        let client_settings = MaybeTlsSettings::from_config(&config.tls, false).unwrap();
+        let proto = client_settings.http_protocol_name();
+
+        let address = next_addr();
+        let config = PrometheusExporterConfig { address, ..config };
         let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
         let (tx, rx) = mpsc::unbounded_channel();
         tokio::spawn(sink.run(Box::pin(UnboundedReceiverStream::new(rx))));
@@ -600,7 +869,11 @@ mod tests {
 
         time::sleep(time::Duration::from_millis(100)).await;
 
-        let request = Request::get(format!("{}://{}/metrics", proto, address))
+        let mut request = Request::get(format!("{}://{}/metrics", proto, address));
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        let request = request
             .body(Body::empty())
             .expect("Error creating request.");
         let proxy = ProxyConfig::default();
@@ -616,7 +889,7 @@ mod tests {
         let bytes = hyper::body::to_bytes(body)
             .await
             .expect("Reading body failed");
-        String::from_utf8(bytes.to_vec()).unwrap()
+        bytes.to_vec()
     }
 
     async fn export_and_fetch_simple(tls_config: Option<TlsConfig>) {
@@ -0,0 +1,533 @@
+//! Encoding of the metrics in the protobuf exposition format, which unlike the text one also
+//! holds native histograms and the exemplars of the histogram buckets.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use prometheus_parser::proto::client;
+use prost::Message;
+use vector_core::event::metric::{MetricSketch, Sample, StatisticKind};
+
+use crate::{
+    event::metric::{Metric, MetricKind, MetricValue},
+    sinks::util::encode_namespace,
+};
+
+pub(super) const CONTENT_TYPE: &str =
+    "application/vnd.google.protobuf; proto=io.prometheus.client.MetricFamily; encoding=delimited";
+
+/// The default breadth of the zero bucket of the Prometheus client libraries.
+const ZERO_THRESHOLD: f64 = 2.938_735_877_055_719e-39;
+
+/// The range of the bucket schemas of native histograms.
+pub(super) const MIN_SCHEMA: i32 = -4;
+pub(super) const MAX_SCHEMA: i32 = 8;
+
+/// Whether the client asked for the protobuf exposition format, as Prometheus does when scraping
+/// native histograms.
+pub(super) fn accepts_protobuf(accept: &str) -> bool {
+    accept.split(',').any(|media_type| {
+        let mut parameters = media_type.split(';').map(str::trim);
+        parameters.next() == Some("application/vnd.google.protobuf")
+            && parameters.any(|parameter| parameter == "proto=io.prometheus.client.MetricFamily")
+    })
+}
+
+/// An observation of a histogram, with the labels identifying its trace.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Exemplar {
+    pub(super) labels: BTreeMap<String, String>,
+    pub(super) value: f64,
+    pub(super) timestamp: DateTime<Utc>,
+}
+
+impl Exemplar {
+    fn encode(&self) -> client::Exemplar {
+        client::Exemplar {
+            label: encode_labels(Some(&self.labels)),
+            value: Some(self.value),
+            timestamp: Some(prost_types::Timestamp {
+                seconds: self.timestamp.timestamp(),
+                nanos: self.timestamp.timestamp_subsec_nanos() as i32,
+            }),
+        }
+    }
+}
+
+/// The buckets of a native histogram, whose boundaries grow exponentially so that they keep the
+/// same relative precision for any value, without having to be configured.
+#[derive(Clone, Debug)]
+pub(super) struct NativeHistogram {
+    schema: i32,
+    zero_count: u64,
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+}
+
+impl NativeHistogram {
+    pub(super) fn new(schema: i32) -> Self {
+        Self {
+            schema,
+            zero_count: 0,
+            positive: BTreeMap::new(),
+            negative: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn observe(&mut self, samples: &[Sample]) {
+        for sample in samples {
+            let count = u64::from(sample.rate);
+            if sample.value.is_nan() {
+                continue;
+            }
+            if sample.value.abs() <= ZERO_THRESHOLD {
+                self.zero_count += count;
+                continue;
+            }
+            let buckets = if sample.value > 0.0 {
+                &mut self.positive
+            } else {
+                &mut self.negative
+            };
+            *buckets
+                .entry(bucket_index(sample.value.abs(), self.schema))
+                .or_default() += count;
+        }
+    }
+
+    fn encode_into(&self, histogram: &mut client::Histogram) {
+        histogram.schema = Some(self.schema);
+        histogram.zero_threshold = Some(ZERO_THRESHOLD);
+        histogram.zero_count = Some(self.zero_count);
+        let (negative_span, negative_delta) = encode_buckets(&self.negative);
+        let (mut positive_span, positive_delta) = encode_buckets(&self.positive);
+        if negative_span.is_empty() && positive_span.is_empty() {
+            // Tells it apart from a classic histogram when there are no observations yet.
+            positive_span.push(client::BucketSpan {
+                offset: Some(0),
+                length: Some(0),
+            });
+        }
+        histogram.negative_span = negative_span;
+        histogram.negative_delta = negative_delta;
+        histogram.positive_span = positive_span;
+        histogram.positive_delta = positive_delta;
+    }
+}
+
+/// The index of the bucket holding the positive value: the bucket `i` holds the values greater
+/// than `base^(i - 1)` and up to `base^i`, where `base` is `2^(2^-schema)`.
+fn bucket_index(value: f64, schema: i32) -> i32 {
+    (value.log2() * 2f64.powi(schema)).ceil() as i32
+}
+
+/// The spans of consecutive buckets, and the deltas between the counts of the buckets.
+fn encode_buckets(buckets: &BTreeMap<i32, u64>) -> (Vec<client::BucketSpan>, Vec<i64>) {
+    let mut spans = Vec::<client::BucketSpan>::new();
+    let mut deltas = Vec::with_capacity(buckets.len());
+    let mut next_index = 0;
+    let mut previous_count = 0;
+    for (&index, &count) in buckets {
+        match spans.last_mut() {
+            Some(span) if index == next_index => {
+                span.length = span.length.map(|length| length + 1);
+            }
+            _ => spans.push(client::BucketSpan {
+                offset: Some(index - next_index),
+                length: Some(1),
+            }),
+        }
+        deltas.push(count as i64 - previous_count);
+        previous_count = count as i64;
+        next_index = index + 1;
+    }
+    (spans, deltas)
+}
+
+/// The details of a histogram that its metric doesn't hold.
+#[derive(Clone, Debug, Default)]
+pub(super) struct HistogramDetails {
+    /// The last exemplar of each bucket, the last one being the `+Inf` bucket.
+    pub(super) exemplars: BTreeMap<usize, Exemplar>,
+    pub(super) native: Option<NativeHistogram>,
+}
+
+pub(super) struct FamilyCollector {
+    // BTreeMap ensures we get sorted output, like the text format
+    families: BTreeMap<String, client::MetricFamily>,
+}
+
+impl FamilyCollector {
+    pub(super) fn new() -> Self {
+        Self {
+            families: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn encode_metric(
+        &mut self,
+        default_namespace: Option<&str>,
+        buckets: &[f64],
+        quantiles: &[f64],
+        metric: &Metric,
+        details: Option<&HistogramDetails>,
+    ) {
+        if metric.kind() != MetricKind::Absolute {
+            return;
+        }
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let mut encoded = client::Metric {
+            label: encode_labels(metric.tags()),
+            timestamp_ms: metric.timestamp().map(|t| t.timestamp_millis()),
+            ..Default::default()
+        };
+
+        let r#type = match metric.value() {
+            MetricValue::Counter { value } => {
+                encoded.counter = Some(client::Counter {
+                    value: Some(*value),
+                    ..Default::default()
+                });
+                client::MetricType::Counter
+            }
+            MetricValue::Gauge { value } => {
+                encoded.gauge = Some(client::Gauge {
+                    value: Some(*value),
+                });
+                client::MetricType::Gauge
+            }
+            MetricValue::Set { values } => {
+                encoded.gauge = Some(client::Gauge {
+                    value: Some(values.len() as f64),
+                });
+                client::MetricType::Gauge
+            }
+            MetricValue::Distribution { statistic, .. } => {
+                // Encoded like the histograms and summaries they are converted to.
+                let value = match statistic {
+                    StatisticKind::Histogram => {
+                        metric.value().distribution_to_agg_histogram(buckets)
+                    }
+                    StatisticKind::Summary => metric.value().distribution_to_sketch(),
+                };
+                if let Some(value) = value {
+                    let metric = metric.clone().with_value(value);
+                    self.encode_metric(default_namespace, buckets, quantiles, &metric, details);
+                }
+                return;
+            }
+            MetricValue::AggregatedHistogram {
+                buckets,
+                count,
+                sum,
+            } => {
+                let mut histogram = client::Histogram {
+                    sample_count: Some(u64::from(*count)),
+                    sample_sum: Some(*sum),
+                    ..Default::default()
+                };
+                let exemplar = |index| {
+                    details
+                        .and_then(|details| details.exemplars.get(&index))
+                        .map(Exemplar::encode)
+                };
+                let mut cumulative_count = 0;
+                for (index, bucket) in buckets.iter().enumerate() {
+                    // As in the text format, the `+Inf` bucket is computed rather than taken from
+                    // the metric.
+                    if bucket.upper_limit.is_infinite() {
+                        continue;
+                    }
+                    cumulative_count += u64::from(bucket.count);
+                    histogram.bucket.push(client::Bucket {
+                        cumulative_count: Some(cumulative_count),
+                        upper_bound: Some(bucket.upper_limit),
+                        exemplar: exemplar(index),
+                        ..Default::default()
+                    });
+                }
+                // The `+Inf` bucket is implied by the count, unless it has an exemplar.
+                if let Some(exemplar) = exemplar(buckets.len()) {
+                    histogram.bucket.push(client::Bucket {
+                        cumulative_count: Some(u64::from(*count)),
+                        upper_bound: Some(f64::INFINITY),
+                        exemplar: Some(exemplar),
+                        ..Default::default()
+                    });
+                }
+                if let Some(native) = details.and_then(|details| details.native.as_ref()) {
+                    native.encode_into(&mut histogram);
+                    histogram.exemplars = details
+                        .map(|details| details.exemplars.values().map(Exemplar::encode).collect())
+                        .unwrap_or_default();
+                }
+                encoded.histogram = Some(histogram);
+                client::MetricType::Histogram
+            }
+            MetricValue::AggregatedSummary {
+                quantiles,
+                count,
+                sum,
+            } => {
+                encoded.summary = Some(client::Summary {
+                    sample_count: Some(u64::from(*count)),
+                    sample_sum: Some(*sum),
+                    quantile: quantiles
+                        .iter()
+                        .map(|quantile| client::Quantile {
+                            quantile: Some(quantile.quantile),
+                            value: Some(quantile.value),
+                        })
+                        .collect(),
+                    ..Default::default()
+                });
+                client::MetricType::Summary
+            }
+            MetricValue::Sketch { sketch } => match sketch {
+                MetricSketch::AgentDDSketch(ddsketch) => {
+                    encoded.summary = Some(client::Summary {
+                        sample_count: Some(u64::from(ddsketch.count())),
+                        sample_sum: Some(ddsketch.sum().unwrap_or(0.0)),
+                        quantile: quantiles
+                            .iter()
+                            .map(|quantile| client::Quantile {
+                                quantile: Some(*quantile),
+                                value: Some(ddsketch.quantile(*quantile).unwrap_or(0.0)),
+                            })
+                            .collect(),
+                        ..Default::default()
+                    });
+                    client::MetricType::Summary
+                }
+            },
+        };
+
+        self.families
+            .entry(name.clone())
+            .or_insert_with(|| client::MetricFamily {
+                help: Some(metric.name().to_owned()),
+                r#type: Some(r#type as i32),
+                name: Some(name),
+                ..Default::default()
+            })
+            .metric
+            .push(encoded);
+    }
+
+    /// The length delimited metric families.
+    pub(super) fn finish(self) -> Vec<u8> {
+        let mut body = Vec::new();
+        for family in self.families.into_values() {
+            family
+                .encode_length_delimited(&mut body)
+                .expect("the buffer grows as needed");
+        }
+        body
+    }
+}
+
+fn encode_labels(tags: Option<&BTreeMap<String, String>>) -> Vec<client::LabelPair> {
+    tags.into_iter()
+        .flatten()
+        .map(|(name, value)| client::LabelPair {
+            name: Some(name.clone()),
+            value: Some(value.clone()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::{event::metric::Bucket, samples};
+
+    use super::*;
+
+    fn decode(body: &[u8]) -> Vec<client::MetricFamily> {
+        let mut body = body;
+        let mut families = Vec::new();
+        while !body.is_empty() {
+            families.push(client::MetricFamily::decode_length_delimited(&mut body).unwrap());
+        }
+        families
+    }
+
+    #[test]
+    fn detects_protobuf_requests() {
+        assert!(accepts_protobuf(
+            "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited;q=0.7,text/plain;version=0.0.4;q=0.3"
+        ));
+        assert!(accepts_protobuf(
+            "text/plain, application/vnd.google.protobuf; proto=io.prometheus.client.MetricFamily"
+        ));
+        assert!(!accepts_protobuf("text/plain;version=0.0.4"));
+        assert!(!accepts_protobuf("application/vnd.google.protobuf"));
+    }
+
+    #[test]
+    fn computes_bucket_indexes() {
+        // The boundaries are 1, 2, 4, ... at schema 0.
+        assert_eq!(bucket_index(1.0, 0), 0);
+        assert_eq!(bucket_index(1.5, 0), 1);
+        assert_eq!(bucket_index(2.0, 0), 1);
+        assert_eq!(bucket_index(0.3, 0), -1);
+        // And 1, 2^(1/2), 2, ... at schema 1.
+        assert_eq!(bucket_index(1.2, 1), 1);
+        assert_eq!(bucket_index(1.5, 1), 2);
+        // And 1, 4, 16, ... at schema -1.
+        assert_eq!(bucket_index(3.0, -1), 1);
+        assert_eq!(bucket_index(5.0, -1), 2);
+    }
+
+    #[test]
+    fn encodes_native_histograms() {
+        let mut native = NativeHistogram::new(0);
+        native.observe(&samples![1.0 => 1, 1.5 => 2, 2.0 => 1, 7.0 => 1, 0.0 => 3, -1.5 => 1]);
+
+        let mut histogram = client::Histogram::default();
+        native.encode_into(&mut histogram);
+        assert_eq!(histogram.schema, Some(0));
+        assert_eq!(histogram.zero_count, Some(3));
+        // Buckets 0 and 1 with counts 1 and 3, then bucket 3 with a count of 1.
+        assert_eq!(
+            histogram.positive_span,
+            vec![
+                client::BucketSpan {
+                    offset: Some(0),
+                    length: Some(2),
+                },
+                client::BucketSpan {
+                    offset: Some(1),
+                    length: Some(1),
+                },
+            ]
+        );
+        assert_eq!(histogram.positive_delta, vec![1, 2, -2]);
+        assert_eq!(
+            histogram.negative_span,
+            vec![client::BucketSpan {
+                offset: Some(1),
+                length: Some(1),
+            }]
+        );
+        assert_eq!(histogram.negative_delta, vec![1]);
+    }
+
+    #[test]
+    fn marks_empty_native_histograms() {
+        let mut histogram = client::Histogram::default();
+        NativeHistogram::new(3).encode_into(&mut histogram);
+        assert_eq!(
+            histogram.positive_span,
+            vec![client::BucketSpan {
+                offset: Some(0),
+                length: Some(0),
+            }]
+        );
+    }
+
+    #[test]
+    fn encodes_families() {
+        let counter = Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 3.0 },
+        )
+        .with_tags(Some(
+            vec![("code".to_owned(), "200".to_owned())]
+                .into_iter()
+                .collect(),
+        ));
+        let incremental = Metric::new(
+            "ignored",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        );
+
+        let mut collector = FamilyCollector::new();
+        collector.encode_metric(Some("vector"), &[], &[], &counter, None);
+        collector.encode_metric(Some("vector"), &[], &[], &counter, None);
+        collector.encode_metric(Some("vector"), &[], &[], &incremental, None);
+        let families = decode(&collector.finish());
+
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].name.as_deref(), Some("vector_requests"));
+        assert_eq!(families[0].r#type, Some(client::MetricType::Counter as i32));
+        assert_eq!(families[0].metric.len(), 2);
+        assert_eq!(
+            families[0].metric[0].label,
+            vec![client::LabelPair {
+                name: Some("code".into()),
+                value: Some("200".into()),
+            }]
+        );
+        assert_eq!(
+            families[0].metric[0].counter.as_ref().unwrap().value,
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn encodes_histograms_with_exemplars() {
+        let histogram = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: 2,
+                    },
+                    Bucket {
+                        upper_limit: 2.0,
+                        count: 1,
+                    },
+                ],
+                count: 4,
+                sum: 9.0,
+            },
+        );
+        let exemplar = |value| Exemplar {
+            labels: vec![("trace_id".to_owned(), "abc".to_owned())]
+                .into_iter()
+                .collect(),
+            value,
+            timestamp: Utc::now(),
+        };
+        let mut native = NativeHistogram::new(1);
+        native.observe(&samples![0.5 => 2, 1.5 => 1, 5.0 => 1]);
+        let details = HistogramDetails {
+            exemplars: vec![(1, exemplar(1.5)), (2, exemplar(5.0))]
+                .into_iter()
+                .collect(),
+            native: Some(native),
+        };
+
+        let mut collector = FamilyCollector::new();
+        collector.encode_metric(None, &[], &[], &histogram, Some(&details));
+        let families = decode(&collector.finish());
+        let encoded = families[0].metric[0].histogram.as_ref().unwrap();
+
+        assert_eq!(encoded.sample_count, Some(4));
+        let buckets = encoded
+            .bucket
+            .iter()
+            .map(|bucket| {
+                (
+                    bucket.upper_bound.unwrap(),
+                    bucket.cumulative_count.unwrap(),
+                    bucket.exemplar.as_ref().and_then(|exemplar| exemplar.value),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                (1.0, 2, None),
+                (2.0, 3, Some(1.5)),
+                (f64::INFINITY, 4, Some(5.0)),
+            ]
+        );
+        assert_eq!(encoded.schema, Some(1));
+        assert_eq!(encoded.exemplars.len(), 2);
+    }
+}
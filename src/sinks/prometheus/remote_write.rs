@@ -104,7 +104,7 @@ impl SinkConfig for RemoteWriteConfig {
         };
 
         let sink = {
-            let service = request.service(HttpRetryLogic, service);
+            let service = request.partitioned_service(HttpRetryLogic, service);
             let service = ServiceBuilder::new().service(service);
             let buffer = PartitionBuffer::new(MetricsBuffer::new(batch.size));
             let mut normalizer = MetricNormalizer::<PrometheusMetricNormalize>::default();
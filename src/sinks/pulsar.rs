@@ -386,6 +386,7 @@ mod tests {
                 schema: None,
                 only_fields: None,
                 except_fields: Some(vec!["key".into()]),
+                rename_fields: None,
                 timestamp_format: None,
             },
             &None,
@@ -1,11 +1,16 @@
 use std::{
     convert::TryFrom,
     num::NonZeroU64,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
 };
 
 use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
-use redis::{aio::ConnectionManager, RedisError, RedisResult};
+use redis::{
+    aio::ConnectionManager,
+    cluster::{ClusterClient, ClusterConnection},
+    RedisError, RedisResult,
+};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use tower::{Service, ServiceBuilder};
@@ -40,6 +45,10 @@ enum RedisSinkError {
     RedisCreateFailed { source: RedisError },
     #[snafu(display("Invalid key template: {}", source))]
     KeyTemplate { source: TemplateParseError },
+    #[snafu(display("Invalid key hash tag template: {}", source))]
+    KeyHashTagTemplate { source: TemplateParseError },
+    #[snafu(display("Atomic pipelines are not supported in cluster mode"))]
+    AtomicCluster,
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize)]
@@ -49,6 +58,7 @@ pub enum DataTypeConfig {
     #[derivative(Default)]
     List,
     Channel,
+    Stream,
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -57,13 +67,45 @@ pub struct ListOption {
     method: Method,
 }
 
-#[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct StreamOption {
+    /// The field of the entries holding the encoded events.
+    #[serde(default = "default_stream_field")]
+    #[derivative(Default(value = "default_stream_field()"))]
+    field: String,
+    /// The length the streams are trimmed to as entries are added, if set.
+    #[serde(default)]
+    maxlen: Option<u64>,
+    /// Whether the streams may be trimmed to slightly more entries than `maxlen`, which is
+    /// much more efficient.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    approximate: bool,
+}
+
+fn default_stream_field() -> String {
+    "message".into()
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineConfig {
+    /// Whether the commands of a batch are run in a `MULTI`/`EXEC` transaction, by default only
+    /// when not in cluster mode.
+    #[serde(default)]
+    atomic: Option<bool>,
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(rename_all = "lowercase")]
 pub enum DataType {
     #[derivative(Default)]
     List(Method),
     Channel,
+    Stream(StreamOption),
 }
 
 #[derive(Copy, Clone, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
@@ -99,8 +141,18 @@ pub struct RedisSinkConfig {
     data_type: DataTypeConfig,
     #[serde(alias = "list")]
     list_option: Option<ListOption>,
+    #[serde(alias = "stream")]
+    stream_option: Option<StreamOption>,
     url: String,
     key: String,
+    /// The hash tag the keys are prefixed with, as `{tag}`, so that the keys with the same tag
+    /// are stored on the same node in cluster mode.
+    key_hash_tag: Option<String>,
+    /// Whether `url` is one of the nodes of a cluster, the others being discovered from it.
+    #[serde(default)]
+    cluster: bool,
+    #[serde(default)]
+    pipeline: PipelineConfig,
     #[serde(default)]
     batch: BatchConfig<RedisDefaultBatchSettings>,
     #[serde(default)]
@@ -139,7 +191,13 @@ impl SinkConfig for RedisSinkConfig {
         if self.key.is_empty() {
             return Err("`key` cannot be empty.".into());
         }
-        let conn = self.build_client().await.context(RedisCreateFailedSnafu)?;
+        if self.cluster && self.pipeline.atomic == Some(true) {
+            return Err(RedisSinkError::AtomicCluster.into());
+        }
+        let conn = self
+            .build_connection()
+            .await
+            .context(RedisCreateFailedSnafu)?;
         let healthcheck = RedisSinkConfig::healthcheck(conn.clone()).boxed();
         let sink = self.new(conn, cx)?;
         Ok((sink, healthcheck))
@@ -159,17 +217,19 @@ impl SinkConfig for RedisSinkConfig {
 }
 
 impl RedisSinkConfig {
-    pub fn new(
-        &self,
-        conn: ConnectionManager,
-        cx: SinkContext,
-    ) -> crate::Result<super::VectorSink> {
+    pub fn new(&self, conn: RedisConnection, cx: SinkContext) -> crate::Result<super::VectorSink> {
         let request = self.request.unwrap_with(&TowerRequestConfig {
             concurrency: Concurrency::Fixed(1),
             ..Default::default()
         });
 
         let key = Template::try_from(self.key.clone()).context(KeyTemplateSnafu)?;
+        let key_hash_tag = self
+            .key_hash_tag
+            .clone()
+            .map(Template::try_from)
+            .transpose()
+            .context(KeyHashTagTemplateSnafu)?;
         let encoding = self.encoding.clone();
 
         let method = self.list_option.map(|option| option.method);
@@ -177,20 +237,30 @@ impl RedisSinkConfig {
         let data_type = match self.data_type {
             DataTypeConfig::Channel => DataType::Channel,
             DataTypeConfig::List => DataType::List(method.unwrap_or_default()),
+            DataTypeConfig::Stream => {
+                DataType::Stream(self.stream_option.clone().unwrap_or_default())
+            }
         };
+        let atomic = self.pipeline.atomic.unwrap_or(!self.cluster);
 
         let batch = self.batch.into_batch_settings()?;
 
         let buffer = VecBuffer::new(batch.size);
 
-        let redis = RedisSink { conn, data_type };
+        let redis = RedisSink {
+            conn,
+            data_type,
+            atomic,
+        };
 
         let svc = ServiceBuilder::new()
             .settings(request, RedisRetryLogic)
             .service(redis);
 
         let sink = BatchSink::new(svc, buffer, batch.timeout, cx.acker())
-            .with_flat_map(move |e| stream::iter(encode_event(e, &key, &encoding)).map(Ok))
+            .with_flat_map(move |e| {
+                stream::iter(encode_event(e, &key, key_hash_tag.as_ref(), &encoding)).map(Ok)
+            })
             .sink_map_err(|error| error!(message = "Sink failed to flush.", %error));
 
         Ok(super::VectorSink::from_event_sink(sink))
@@ -205,14 +275,72 @@ impl RedisSinkConfig {
         trace!("Get Redis connection success.");
         conn
     }
-    async fn healthcheck(mut conn: ConnectionManager) -> crate::Result<()> {
-        redis::cmd("PING")
-            .query_async(&mut conn)
+
+    async fn build_connection(&self) -> RedisResult<RedisConnection> {
+        if !self.cluster {
+            return self.build_client().await.map(RedisConnection::Single);
+        }
+        trace!("Open Redis cluster client.");
+        let client = ClusterClient::open(vec![self.url.as_str()])?;
+        // The cluster connections are blocking, the slots of the nodes being fetched here.
+        let conn = tokio::task::spawn_blocking(move || client.get_connection())
             .await
+            .expect("Redis cluster connection panicked")?;
+        trace!("Open Redis cluster client success.");
+        Ok(RedisConnection::Cluster(Arc::new(Mutex::new(conn))))
+    }
+
+    async fn healthcheck(conn: RedisConnection) -> crate::Result<()> {
+        conn.query(vec![redis::cmd("PING")], false)
+            .await
+            .map(|_| ())
             .map_err(Into::into)
     }
 }
 
+/// A connection to a single server, or to the nodes of a cluster.
+#[derive(Clone)]
+pub enum RedisConnection {
+    Single(ConnectionManager),
+    Cluster(Arc<Mutex<ClusterConnection>>),
+}
+
+impl RedisConnection {
+    /// Runs the commands in a pipeline, in a transaction if `atomic`, returning their replies.
+    async fn query(
+        &self,
+        commands: Vec<redis::Cmd>,
+        atomic: bool,
+    ) -> RedisResult<Vec<redis::Value>> {
+        match self {
+            Self::Single(conn) => {
+                let mut conn = conn.clone();
+                let mut pipe = redis::pipe();
+                if atomic {
+                    pipe.atomic();
+                }
+                for command in commands {
+                    pipe.add_command(command);
+                }
+                pipe.query_async(&mut conn).await
+            }
+            Self::Cluster(conn) => {
+                let conn = Arc::clone(conn);
+                tokio::task::spawn_blocking(move || {
+                    // The commands are sent to the nodes of their keys.
+                    let mut pipe = redis::cluster::cluster_pipe();
+                    for command in commands {
+                        pipe.add_command(command);
+                    }
+                    pipe.query(&mut conn.lock().expect("Redis cluster connection poisoned"))
+                })
+                .await
+                .expect("Redis cluster pipeline panicked")
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct RedisKvEntry {
     key: String,
@@ -231,21 +359,30 @@ impl ByteSizeOf for RedisKvEntry {
     }
 }
 
-fn encode_event(
-    mut event: Event,
-    key: &Template,
-    encoding: &EncodingConfig<Encoding>,
-) -> Option<EncodedEvent<RedisKvEntry>> {
-    let key = key
-        .render_string(&event)
+fn render(template: &Template, event: &Event, field: &'static str) -> Option<String> {
+    template
+        .render_string(event)
         .map_err(|error| {
             emit!(&TemplateRenderingError {
                 error,
-                field: Some("key"),
+                field: Some(field),
                 drop_event: true,
             });
         })
-        .ok()?;
+        .ok()
+}
+
+fn encode_event(
+    mut event: Event,
+    key: &Template,
+    key_hash_tag: Option<&Template>,
+    encoding: &EncodingConfig<Encoding>,
+) -> Option<EncodedEvent<RedisKvEntry>> {
+    let mut key = render(key, &event, "key")?;
+    if let Some(key_hash_tag) = key_hash_tag {
+        let tag = render(key_hash_tag, &event, "key_hash_tag")?;
+        key = format!("{{{}}}{}", tag, key);
+    }
 
     let byte_size = event.size_of();
     encoding.apply_rules(&mut event);
@@ -267,6 +404,37 @@ fn encode_event(
 
 type RedisPipeResult = RedisResult<Vec<bool>>;
 
+fn command(data_type: &DataType, kv: RedisKvEntry) -> redis::Cmd {
+    match data_type {
+        DataType::List(Method::LPush) => redis::Cmd::lpush(kv.key, kv.value),
+        DataType::List(Method::RPush) => redis::Cmd::rpush(kv.key, kv.value),
+        DataType::Channel => redis::Cmd::publish(kv.key, kv.value),
+        DataType::Stream(option) => {
+            let mut command = redis::cmd("XADD");
+            command.arg(kv.key);
+            if let Some(maxlen) = option.maxlen {
+                command.arg("MAXLEN");
+                if option.approximate {
+                    command.arg("~");
+                }
+                command.arg(maxlen);
+            }
+            command.arg("*").arg(&option.field).arg(kv.value);
+            command
+        }
+    }
+}
+
+/// Whether the command succeeded, the events published to channels without subscribers being
+/// sent again.
+const fn succeeded(reply: &redis::Value) -> bool {
+    match reply {
+        redis::Value::Int(count) => *count != 0,
+        redis::Value::Nil => false,
+        _ => true,
+    }
+}
+
 impl Response for Vec<bool> {
     fn is_successful(&self) -> bool {
         self.iter().all(|x| *x)
@@ -294,8 +462,9 @@ impl RetryLogic for RedisRetryLogic {
 
 #[derive(Clone)]
 pub struct RedisSink {
-    conn: ConnectionManager,
+    conn: RedisConnection,
     data_type: DataType,
+    atomic: bool,
 }
 
 impl Service<Vec<RedisKvEntry>> for RedisSink {
@@ -311,40 +480,22 @@ impl Service<Vec<RedisKvEntry>> for RedisSink {
         let count = kvs.len();
         let mut byte_size = 0;
 
-        let mut conn = self.conn.clone();
-        let mut pipe = redis::pipe();
-
-        for kv in kvs {
-            byte_size += kv.encoded_length();
-            match self.data_type {
-                DataType::List(method) => match method {
-                    Method::LPush => {
-                        if count > 1 {
-                            pipe.atomic().lpush(kv.key, kv.value);
-                        } else {
-                            pipe.lpush(kv.key, kv.value);
-                        }
-                    }
-                    Method::RPush => {
-                        if count > 1 {
-                            pipe.atomic().rpush(kv.key, kv.value);
-                        } else {
-                            pipe.rpush(kv.key, kv.value);
-                        }
-                    }
-                },
-                DataType::Channel => {
-                    if count > 1 {
-                        pipe.atomic().publish(kv.key, kv.value);
-                    } else {
-                        pipe.publish(kv.key, kv.value);
-                    }
-                }
-            }
-        }
+        let conn = self.conn.clone();
+        let atomic = self.atomic && count > 1;
+
+        let commands = kvs
+            .into_iter()
+            .map(|kv| {
+                byte_size += kv.encoded_length();
+                command(&self.data_type, kv)
+            })
+            .collect();
 
         Box::pin(async move {
-            let result: RedisPipeResult = pipe.query_async(&mut conn).await;
+            let result: RedisPipeResult = conn
+                .query(commands, atomic)
+                .await
+                .map(|replies| replies.iter().map(succeeded).collect());
             match &result {
                 Ok(res) => {
                     if res.is_successful() {
@@ -379,6 +530,7 @@ mod tests {
         let result = encode_event(
             evt,
             &Template::try_from("key").unwrap(),
+            None,
             &EncodingConfig::from(Encoding::Json),
         )
         .unwrap()
@@ -395,6 +547,7 @@ mod tests {
         let event = encode_event(
             evt,
             &Template::try_from("key").unwrap(),
+            None,
             &EncodingConfig::from(Encoding::Text),
         )
         .unwrap()
@@ -412,11 +565,13 @@ mod tests {
         let result = encode_event(
             evt,
             &Template::try_from("key").unwrap(),
+            None,
             &EncodingConfig {
                 codec: Encoding::Json,
                 schema: None,
                 only_fields: None,
                 except_fields: Some(vec!["key".into()]),
+                rename_fields: None,
                 timestamp_format: None,
             },
         )
@@ -427,11 +582,69 @@ mod tests {
         let map: HashMap<String, String> = serde_json::from_slice(&result[..]).unwrap();
         assert!(!map.contains_key("key"));
     }
+
+    #[test]
+    fn redis_key_hash_tag() {
+        let mut evt = Event::from("hello_world");
+        evt.as_mut_log().insert("app", "api");
+
+        let key = encode_event(
+            evt,
+            &Template::try_from("logs-{{ app }}").unwrap(),
+            Some(&Template::try_from("{{ app }}").unwrap()),
+            &EncodingConfig::from(Encoding::Text),
+        )
+        .unwrap()
+        .item
+        .key;
+        assert_eq!(key, "{api}logs-api");
+    }
+
+    #[test]
+    fn redis_stream_command() {
+        let kv = RedisKvEntry {
+            key: "logs".into(),
+            value: b"hello_world".to_vec(),
+        };
+        let data_type = DataType::Stream(StreamOption {
+            maxlen: Some(1000),
+            ..Default::default()
+        });
+        assert_eq!(
+            command(&data_type, kv.clone()).get_packed_command(),
+            redis::cmd("XADD")
+                .arg("logs")
+                .arg("MAXLEN")
+                .arg("~")
+                .arg(1000)
+                .arg("*")
+                .arg("message")
+                .arg("hello_world")
+                .get_packed_command()
+        );
+
+        let data_type = DataType::Stream(StreamOption {
+            field: "event".into(),
+            maxlen: None,
+            approximate: false,
+        });
+        assert_eq!(
+            command(&data_type, kv).get_packed_command(),
+            redis::cmd("XADD")
+                .arg("logs")
+                .arg("*")
+                .arg("event")
+                .arg("hello_world")
+                .get_packed_command()
+        );
+    }
 }
 
 #[cfg(feature = "redis-integration-tests")]
 #[cfg(test)]
 mod integration_tests {
+    use std::collections::HashMap;
+
     use rand::Rng;
     use redis::AsyncCommands;
 
@@ -460,6 +673,10 @@ mod integration_tests {
             list_option: Some(ListOption {
                 method: Method::LPush,
             }),
+            stream_option: None,
+            key_hash_tag: None,
+            cluster: false,
+            pipeline: PipelineConfig::default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -469,7 +686,7 @@ mod integration_tests {
         };
 
         // Publish events.
-        let conn = cnf.build_client().await.unwrap();
+        let conn = cnf.build_connection().await.unwrap();
         let cx = SinkContext::new_test();
 
         let sink = cnf.new(conn, cx).unwrap();
@@ -519,6 +736,10 @@ mod integration_tests {
             list_option: Some(ListOption {
                 method: Method::RPush,
             }),
+            stream_option: None,
+            key_hash_tag: None,
+            cluster: false,
+            pipeline: PipelineConfig::default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -528,7 +749,7 @@ mod integration_tests {
         };
 
         // Publish events.
-        let conn = cnf.build_client().await.unwrap();
+        let conn = cnf.build_connection().await.unwrap();
         let cx = SinkContext::new_test();
 
         let sink = cnf.new(conn, cx).unwrap();
@@ -591,6 +812,10 @@ mod integration_tests {
             encoding: Encoding::Json.into(),
             data_type: DataTypeConfig::Channel,
             list_option: None,
+            stream_option: None,
+            key_hash_tag: None,
+            cluster: false,
+            pipeline: PipelineConfig::default(),
             batch: BatchConfig::default(),
             request: TowerRequestConfig {
                 rate_limit_num: Option::from(u64::MAX),
@@ -600,7 +825,7 @@ mod integration_tests {
         };
 
         // Publish events.
-        let conn = cnf.build_client().await.unwrap();
+        let conn = cnf.build_connection().await.unwrap();
         let cx = SinkContext::new_test();
 
         let sink = cnf.new(conn, cx).unwrap();
@@ -619,4 +844,64 @@ mod integration_tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn redis_sink_stream() {
+        trace_init();
+
+        let key = format!("test-{}", random_string(10));
+        debug!("Test key name: {}.", key);
+        let num_events = 1000;
+
+        let cnf = RedisSinkConfig {
+            url: redis_server(),
+            key: key.clone(),
+            encoding: Encoding::Text.into(),
+            data_type: DataTypeConfig::Stream,
+            list_option: None,
+            stream_option: Some(StreamOption {
+                maxlen: Some(100),
+                approximate: false,
+                ..Default::default()
+            }),
+            key_hash_tag: None,
+            cluster: false,
+            pipeline: PipelineConfig::default(),
+            batch: BatchConfig::default(),
+            request: TowerRequestConfig {
+                rate_limit_num: Option::from(u64::MAX),
+                ..Default::default()
+            },
+            acknowledgements: Default::default(),
+        };
+
+        // Publish events.
+        let conn = cnf.build_connection().await.unwrap();
+        let cx = SinkContext::new_test();
+
+        let sink = cnf.new(conn, cx).unwrap();
+        let events = (0..num_events)
+            .map(|i| Event::from(i.to_string()))
+            .collect::<Vec<_>>();
+        sink.run_events(events).await.unwrap();
+
+        let mut conn = cnf.build_client().await.unwrap();
+
+        let len: usize = redis::cmd("XLEN")
+            .arg(&key)
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(len, 100);
+
+        let entries: Vec<(String, HashMap<String, String>)> = redis::cmd("XRANGE")
+            .arg(&key)
+            .arg("-")
+            .arg("+")
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(entries[0].1["message"], (num_events - 100).to_string());
+        assert_eq!(entries[99].1["message"], (num_events - 1).to_string());
+    }
 }
@@ -0,0 +1,291 @@
+use std::net::SocketAddr;
+
+use snafu::ResultExt;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use super::{
+    config::{SmtpAuth, StartTls},
+    ConnectSnafu, DnsSnafu, IoSnafu, SmtpError, TlsSnafu,
+};
+use crate::{
+    dns,
+    tls::{MaybeTls, MaybeTlsSettings, MaybeTlsStream},
+};
+
+/// Opens the connections to the server and sends the emails over them.
+pub(super) struct SmtpClient {
+    pub(super) host: String,
+    pub(super) port: u16,
+    pub(super) implicit_tls: bool,
+    pub(super) tls: MaybeTlsSettings,
+    pub(super) starttls: StartTls,
+    pub(super) auth: Option<SmtpAuth>,
+    pub(super) helo_name: String,
+    pub(super) sender: String,
+    pub(super) recipients: Vec<String>,
+}
+
+impl SmtpClient {
+    pub(super) fn helo_name(&self) -> &str {
+        &self.helo_name
+    }
+
+    pub(super) fn endpoint(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// Connects to the server, negotiating TLS and authenticating as configured.
+    pub(super) async fn open(&self) -> Result<Connection<MaybeTlsStream<TcpStream>>, SmtpError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(DnsSnafu)?
+            .next()
+            .ok_or(SmtpError::NoAddresses)?;
+        let stream = TcpStream::connect(SocketAddr::new(ip, self.port))
+            .await
+            .context(ConnectSnafu)?;
+
+        let mut connection = if self.implicit_tls {
+            let stream = self.upgrade(stream).await?;
+            let mut connection = Connection::new(MaybeTls::Tls(stream));
+            connection.greeting().await?;
+            connection.ehlo(&self.helo_name).await?;
+            connection
+        } else {
+            let mut connection = Connection::new(stream);
+            connection.greeting().await?;
+            connection.ehlo(&self.helo_name).await?;
+
+            let supported = connection.extension("STARTTLS").is_some();
+            match (self.starttls, supported) {
+                (StartTls::Required | StartTls::Opportunistic, true) => {
+                    connection.command("STARTTLS", "STARTTLS", 2).await?;
+                    let stream = self.upgrade(connection.into_inner()?).await?;
+                    // The extensions are discarded, and asked for again over TLS.
+                    let mut connection = Connection::new(MaybeTls::Tls(stream));
+                    connection.ehlo(&self.helo_name).await?;
+                    connection
+                }
+                (StartTls::Required, false) => return Err(SmtpError::StartTlsUnsupported),
+                (StartTls::Opportunistic | StartTls::Disabled, _) => connection.map(MaybeTls::Raw),
+            }
+        };
+
+        if let Some(auth) = &self.auth {
+            connection.authenticate(auth).await?;
+        }
+        Ok(connection)
+    }
+
+    async fn upgrade(&self, stream: TcpStream) -> Result<MaybeTlsStream<TcpStream>, SmtpError> {
+        self.tls
+            .upgrade(&self.host, stream)
+            .await
+            .map(MaybeTls::Tls)
+            .context(TlsSnafu)
+    }
+
+    /// Sends an email, as formatted for the `DATA` command.
+    pub(super) async fn send(&self, message: &[u8]) -> Result<(), SmtpError> {
+        let mut connection = self.open().await?;
+
+        let mut mail = format!("MAIL FROM:<{}>", self.sender);
+        if !message.is_ascii() && connection.extension("8BITMIME").is_some() {
+            mail.push_str(" BODY=8BITMIME");
+        }
+        connection.command("MAIL", &mail, 2).await?;
+        for recipient in &self.recipients {
+            let rcpt = format!("RCPT TO:<{}>", recipient);
+            connection.command("RCPT", &rcpt, 2).await?;
+        }
+        connection.command("DATA", "DATA", 3).await?;
+        connection.write(message).await?;
+        connection.reply("DATA", 2).await?;
+
+        connection.quit().await;
+        Ok(())
+    }
+}
+
+struct Reply {
+    code: u16,
+    lines: Vec<String>,
+}
+
+impl Reply {
+    fn message(&self) -> String {
+        self.lines.join(" ")
+    }
+}
+
+/// A connection to the server, along with the extensions it supports.
+pub(super) struct Connection<S> {
+    stream: BufReader<S>,
+    /// The keywords of the extensions, in upper case, and their parameters.
+    extensions: Vec<(String, String)>,
+}
+
+impl<S> Connection<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Wraps the underlying stream, which has nothing left to read as the server only sends
+    /// replies to the commands.
+    fn map<T>(self, f: impl FnOnce(S) -> T) -> Connection<T> {
+        Connection {
+            stream: BufReader::new(f(self.stream.into_inner())),
+            extensions: self.extensions,
+        }
+    }
+
+    /// Returns the underlying stream, failing if the server sent more than what was read, which
+    /// would otherwise be taken as sent over TLS after an upgrade.
+    fn into_inner(self) -> Result<S, SmtpError> {
+        if self.stream.buffer().is_empty() {
+            Ok(self.stream.into_inner())
+        } else {
+            Err(SmtpError::InvalidReply {
+                line: String::from_utf8_lossy(self.stream.buffer()).into_owned(),
+            })
+        }
+    }
+
+    fn extension(&self, keyword: &str) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find(|(name, _)| name == keyword)
+            .map(|(_, parameters)| parameters.as_str())
+    }
+
+    async fn greeting(&mut self) -> Result<(), SmtpError> {
+        self.reply("greeting", 2).await.map(drop)
+    }
+
+    async fn ehlo(&mut self, helo_name: &str) -> Result<(), SmtpError> {
+        let reply = self
+            .command("EHLO", &format!("EHLO {}", helo_name), 2)
+            .await?;
+        // The first line greets the client, the next ones list the extensions.
+        self.extensions = reply
+            .lines
+            .iter()
+            .skip(1)
+            .map(|line| {
+                let (keyword, parameters) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+                (keyword.to_ascii_uppercase(), parameters.to_owned())
+            })
+            .collect();
+        Ok(())
+    }
+
+    async fn authenticate(&mut self, auth: &SmtpAuth) -> Result<(), SmtpError> {
+        let mechanisms = self
+            .extension("AUTH")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_ascii_uppercase)
+            .collect::<Vec<_>>();
+
+        if mechanisms.iter().any(|mechanism| mechanism == "PLAIN") {
+            let credentials = format!("\0{}\0{}", auth.user, auth.password);
+            let command = format!("AUTH PLAIN {}", base64::encode(credentials));
+            self.command("AUTH", &command, 2).await.map(drop)
+        } else if mechanisms.iter().any(|mechanism| mechanism == "LOGIN") {
+            self.command("AUTH", "AUTH LOGIN", 3).await?;
+            self.command("AUTH", &base64::encode(&auth.user), 3).await?;
+            self.command("AUTH", &base64::encode(&auth.password), 2)
+                .await
+                .map(drop)
+        } else {
+            Err(SmtpError::AuthUnsupported)
+        }
+    }
+
+    /// Ends the session, the errors being ignored as the emails were already accepted.
+    pub(super) async fn quit(mut self) {
+        if let Err(error) = self.command("QUIT", "QUIT", 2).await {
+            debug!(message = "Failed to end the SMTP session.", %error);
+        }
+    }
+
+    /// Sends a command, expecting a reply of the given class, such as 2 for positive completion.
+    async fn command(
+        &mut self,
+        command: &'static str,
+        line: &str,
+        class: u16,
+    ) -> Result<Reply, SmtpError> {
+        self.write(format!("{}\r\n", line).as_bytes()).await?;
+        self.reply(command, class).await
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> Result<(), SmtpError> {
+        let stream = self.stream.get_mut();
+        stream.write_all(bytes).await.context(IoSnafu)?;
+        stream.flush().await.context(IoSnafu)
+    }
+
+    async fn reply(&mut self, command: &'static str, class: u16) -> Result<Reply, SmtpError> {
+        let reply = self.read_reply().await?;
+        if reply.code / 100 == class {
+            Ok(reply)
+        } else {
+            Err(SmtpError::UnexpectedReply {
+                command,
+                code: reply.code,
+                message: reply.message(),
+            })
+        }
+    }
+
+    /// Reads a reply, the lines of which but the last one follow their code with a dash.
+    async fn read_reply(&mut self) -> Result<Reply, SmtpError> {
+        let mut code = None;
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            let read = self.stream.read_line(&mut line).await.context(IoSnafu)?;
+            if read == 0 {
+                return Err(SmtpError::Io {
+                    source: std::io::ErrorKind::UnexpectedEof.into(),
+                });
+            }
+            let line = line.trim_end_matches(&['\r', '\n'][..]);
+
+            let invalid = || SmtpError::InvalidReply {
+                line: line.to_owned(),
+            };
+            let line_code = line
+                .get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .ok_or_else(invalid)?;
+            if *code.get_or_insert(line_code) != line_code {
+                return Err(invalid());
+            }
+            let last = match line.as_bytes().get(3) {
+                None | Some(b' ') => true,
+                Some(b'-') => false,
+                Some(_) => return Err(invalid()),
+            };
+            lines.push(line.get(4..).unwrap_or_default().to_owned());
+
+            if last {
+                return Ok(Reply {
+                    code: line_code,
+                    lines,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::{num::NonZeroU64, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tower::ServiceBuilder;
+
+use super::{
+    client::SmtpClient,
+    message::{envelope_address, MessageFormat},
+    service::{SmtpRetryLogic, SmtpService},
+    sink::SmtpSink,
+    SmtpError,
+};
+use crate::{
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    sinks::{
+        util::{
+            BatchConfig, Concurrency, ServiceBuilderExt, SinkBatchSettings, TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+    tls::{MaybeTlsSettings, TlsConfig, TlsSettings},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpSinkConfig {
+    pub host: String,
+    /// Defaults to 465 when TLS is negotiated on connect, and to 587 otherwise.
+    pub port: Option<u16>,
+    /// Negotiates TLS on connect when enabled. The options also apply to `STARTTLS`.
+    pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub starttls: StartTls,
+    pub auth: Option<SmtpAuth>,
+    /// The name the sink introduces itself with, which defaults to the hostname.
+    pub helo_name: Option<String>,
+    /// The sender, such as `Vector <vector@example.com>`.
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: Template,
+    /// Rendered for each event of an email, the events of digests being separated by lines.
+    #[serde(default = "default_body")]
+    pub body: Template,
+    /// Partitions the batches, so that each digest only holds events with the same key.
+    pub digest_key: Option<Template>,
+    #[serde(default)]
+    pub batch: BatchConfig<SmtpDefaultBatchSettings>,
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Eq, PartialEq, Derivative)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum StartTls {
+    /// Fails to send the emails when the server doesn't support `STARTTLS`.
+    #[derivative(Default)]
+    Required,
+    /// Sends the emails in plain text when the server doesn't support `STARTTLS`.
+    Opportunistic,
+    Disabled,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpAuth {
+    pub user: String,
+    pub password: String,
+}
+
+/// Each event is sent in its own email unless the batches are made larger.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmtpDefaultBatchSettings;
+
+impl SinkBatchSettings for SmtpDefaultBatchSettings {
+    const MAX_EVENTS: Option<usize> = Some(1);
+    const MAX_BYTES: Option<usize> = None;
+    const TIMEOUT_SECS: NonZeroU64 = unsafe { NonZeroU64::new_unchecked(1) };
+}
+
+/// Emails are sent one at a time, and at most ten per minute, as mail servers often limit the
+/// rate of their senders.
+const REQUEST_DEFAULTS: TowerRequestConfig = TowerRequestConfig::new(Concurrency::Fixed(1))
+    .rate_limit_duration_secs(60)
+    .rate_limit_num(10);
+
+fn default_body() -> Template {
+    Template::try_from("{{ message }}").unwrap()
+}
+
+inventory::submit! {
+    SinkDescription::new::<SmtpSinkConfig>("smtp")
+}
+
+impl GenerateConfig for SmtpSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"host = "smtp.example.com"
+            from = "Vector <vector@example.com>"
+            to = ["oncall@example.com"]
+            subject = "Alert from {{ host }}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "smtp")]
+impl SinkConfig for SmtpSinkConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let client = Arc::new(self.build_client()?);
+        let healthcheck = healthcheck(Arc::clone(&client));
+
+        let format = MessageFormat::new(self.from.clone(), self.to.clone(), client.helo_name());
+        let request_settings = self.request.unwrap_with(&REQUEST_DEFAULTS);
+        let service = ServiceBuilder::new()
+            .settings(request_settings, SmtpRetryLogic)
+            .service(SmtpService::new(client));
+
+        let sink = SmtpSink {
+            subject: self.subject.clone(),
+            body: self.body.clone(),
+            digest_key: self.digest_key.clone(),
+            format,
+            batch_settings: self.batch.into_batcher_settings()?,
+            service,
+            acker: cx.acker(),
+        };
+
+        Ok((
+            VectorSink::from_event_streamsink(sink),
+            Box::pin(healthcheck),
+        ))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+impl SmtpSinkConfig {
+    pub(super) fn build_client(&self) -> crate::Result<SmtpClient> {
+        if self.to.is_empty() {
+            return Err(SmtpError::MissingRecipients.into());
+        }
+        let sender = envelope_address(&self.from)?;
+        let recipients = self
+            .to
+            .iter()
+            .map(|address| envelope_address(address))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let implicit_tls = self
+            .tls
+            .as_ref()
+            .and_then(|tls| tls.enabled)
+            .unwrap_or(false);
+        // The options of the connections upgraded with `STARTTLS` apply even when TLS isn't
+        // enabled on connect.
+        let tls = MaybeTlsSettings::from(TlsSettings::from_options(
+            &self.tls.as_ref().map(|tls| tls.options.clone()),
+        )?);
+        let helo_name = match &self.helo_name {
+            Some(name) => name.clone(),
+            None => crate::get_hostname().unwrap_or_else(|_| "localhost".into()),
+        };
+
+        Ok(SmtpClient {
+            host: self.host.clone(),
+            port: self.port.unwrap_or(if implicit_tls { 465 } else { 587 }),
+            implicit_tls,
+            tls,
+            starttls: self.starttls,
+            auth: self.auth.clone(),
+            helo_name,
+            sender,
+            recipients,
+        })
+    }
+}
+
+async fn healthcheck(client: Arc<SmtpClient>) -> crate::Result<()> {
+    client.open().await?.quit().await;
+    Ok(())
+}
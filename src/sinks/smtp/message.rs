@@ -0,0 +1,153 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::SmtpError;
+
+/// The headers shared by all the emails of the sink.
+#[derive(Debug, Clone)]
+pub(super) struct MessageFormat {
+    from: String,
+    to: String,
+    /// The domain of the identifiers of the messages.
+    domain: String,
+}
+
+impl MessageFormat {
+    pub(super) fn new(from: String, to: Vec<String>, domain: &str) -> Self {
+        Self {
+            from: header_value(&from),
+            to: header_value(&to.join(", ")),
+            domain: domain.to_owned(),
+        }
+    }
+
+    /// Formats an email as sent after the `DATA` command, with its lines ending with CRLF,
+    /// dot-stuffed and terminated by a line holding a single dot.
+    pub(super) fn format(&self, subject: &str, body: &str, date: DateTime<Utc>, id: Uuid) -> Bytes {
+        let mut message = BytesMut::with_capacity(body.len() + 512);
+        let mut header = |name: &str, value: &str| {
+            message.put_slice(name.as_bytes());
+            message.put_slice(b": ");
+            message.put_slice(value.as_bytes());
+            message.put_slice(b"\r\n");
+        };
+        header("From", &self.from);
+        header("To", &self.to);
+        header("Subject", &encode_header(subject));
+        header("Date", &date.to_rfc2822());
+        header("Message-ID", &format!("<{}@{}>", id, self.domain));
+        header("MIME-Version", "1.0");
+        header("Content-Type", "text/plain; charset=utf-8");
+        let encoding = if body.is_ascii() { "7bit" } else { "8bit" };
+        header("Content-Transfer-Encoding", encoding);
+        message.put_slice(b"\r\n");
+
+        for line in body.split('\n') {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.starts_with('.') {
+                message.put_u8(b'.');
+            }
+            message.put_slice(line.as_bytes());
+            message.put_slice(b"\r\n");
+        }
+        message.put_slice(b".\r\n");
+        message.freeze()
+    }
+}
+
+/// Extracts the address sent in the envelope from an address such as `Name <user@example.com>`.
+pub(super) fn envelope_address(address: &str) -> Result<String, SmtpError> {
+    let address = address.trim();
+    let bare = match (address.rfind('<'), address.strip_suffix('>')) {
+        (Some(start), Some(address)) => &address[start + 1..],
+        _ => address,
+    };
+    let valid = bare.contains('@')
+        && !bare.contains(|c: char| c.is_whitespace() || c.is_control() || c == '<' || c == '>');
+    if valid {
+        Ok(bare.to_owned())
+    } else {
+        Err(SmtpError::InvalidAddress {
+            address: address.to_owned(),
+        })
+    }
+}
+
+/// Replaces the line breaks and other control characters, which could inject headers.
+fn header_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// Encodes the values holding non-ASCII characters as MIME encoded words.
+fn encode_header(value: &str) -> String {
+    let value = header_value(value);
+    if value.is_ascii() {
+        value
+    } else {
+        format!("=?utf-8?B?{}?=", base64::encode(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn extracts_envelope_addresses() {
+        assert_eq!(
+            envelope_address("Vector <vector@example.com>").unwrap(),
+            "vector@example.com"
+        );
+        assert_eq!(
+            envelope_address(" oncall@example.com ").unwrap(),
+            "oncall@example.com"
+        );
+        assert!(envelope_address("oncall").is_err());
+        assert!(envelope_address("on call@example.com").is_err());
+        assert!(envelope_address("Vector <vector@example.com").is_err());
+    }
+
+    #[test]
+    fn formats_messages() {
+        let format = MessageFormat::new(
+            "Vector <vector@example.com>".into(),
+            vec!["a@example.com".into(), "b@example.com".into()],
+            "vector.local",
+        );
+        let message = format.format(
+            "Disk full\r\nBcc: x@example.com",
+            "first\n.second\r\n..third",
+            Utc.ymd(2022, 3, 1).and_hms(12, 30, 0),
+            Uuid::nil(),
+        );
+
+        assert_eq!(
+            String::from_utf8(message.to_vec()).unwrap(),
+            "From: Vector <vector@example.com>\r\n\
+             To: a@example.com, b@example.com\r\n\
+             Subject: Disk full  Bcc: x@example.com\r\n\
+             Date: Tue, 01 Mar 2022 12:30:00 +0000\r\n\
+             Message-ID: <00000000-0000-0000-0000-000000000000@vector.local>\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             Content-Transfer-Encoding: 7bit\r\n\
+             \r\n\
+             first\r\n\
+             ..second\r\n\
+             ...third\r\n\
+             .\r\n"
+        );
+    }
+
+    #[test]
+    fn encodes_non_ascii_subjects() {
+        assert_eq!(encode_header("Disk full"), "Disk full");
+        assert_eq!(encode_header("Déjà vu"), "=?utf-8?B?RMOpasOgIHZ1?=");
+    }
+}
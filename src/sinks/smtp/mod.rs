@@ -0,0 +1,49 @@
+//! A sink sending events as emails to an SMTP server.
+//!
+//! The events are rendered through the `subject` and `body` templates, each batch being sent as a
+//! single email, which digests its events when a batch holds more than one. Batches are partitioned
+//! by the rendered `digest_key`, so that unrelated events are sent in separate emails. Each email is
+//! sent over its own connection, upgraded with `STARTTLS` unless TLS is negotiated on connect.
+
+use snafu::Snafu;
+
+mod client;
+mod config;
+mod message;
+mod service;
+mod sink;
+
+pub use config::SmtpSinkConfig;
+
+#[derive(Debug, Snafu)]
+pub enum SmtpError {
+    #[snafu(display("At least one recipient is required"))]
+    MissingRecipients,
+    #[snafu(display("Invalid address: {:?}", address))]
+    InvalidAddress { address: String },
+    #[snafu(display("Failed to resolve the host: {}", source))]
+    Dns { source: crate::dns::DnsError },
+    #[snafu(display("No addresses returned for the host"))]
+    NoAddresses,
+    #[snafu(display("Failed to connect: {}", source))]
+    Connect { source: std::io::Error },
+    #[snafu(display("Failed to negotiate TLS: {}", source))]
+    Tls { source: crate::tls::TlsError },
+    #[snafu(display("Connection failed: {}", source))]
+    Io { source: std::io::Error },
+    #[snafu(display("Invalid reply from the server: {:?}", line))]
+    InvalidReply { line: String },
+    #[snafu(display("The server replied {} to {}: {}", code, command, message))]
+    UnexpectedReply {
+        command: &'static str,
+        code: u16,
+        message: String,
+    },
+    #[snafu(display("The server doesn't support STARTTLS"))]
+    StartTlsUnsupported,
+    #[snafu(display("The server doesn't support the PLAIN or LOGIN authentication mechanisms"))]
+    AuthUnsupported,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,117 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use vector_core::{buffers::Ackable, internal_event::EventsSent, stream::DriverResponse};
+
+use super::{client::SmtpClient, SmtpError};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    internal_events::EndpointBytesSent,
+    sinks::util::retries::RetryLogic,
+};
+
+#[derive(Clone)]
+pub(super) struct SmtpService {
+    client: Arc<SmtpClient>,
+    endpoint: Arc<str>,
+}
+
+impl SmtpService {
+    pub(super) fn new(client: Arc<SmtpClient>) -> Self {
+        let endpoint = client.endpoint().into();
+        Self { client, endpoint }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct SmtpRequest {
+    /// The email, as sent after the `DATA` command.
+    pub(super) message: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) events_count: usize,
+    pub(super) events_byte_size: usize,
+}
+
+impl Ackable for SmtpRequest {
+    fn ack_size(&self) -> usize {
+        self.events_count
+    }
+}
+
+impl Finalizable for SmtpRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+pub(super) struct SmtpResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for SmtpResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+impl tower::Service<SmtpRequest> for SmtpService {
+    type Response = SmtpResponse;
+    type Error = SmtpError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SmtpRequest) -> Self::Future {
+        let service = self.clone();
+
+        Box::pin(async move {
+            service.client.send(&request.message).await?;
+
+            emit!(&EndpointBytesSent {
+                byte_size: request.message.len(),
+                protocol: "smtp",
+                endpoint: &service.endpoint,
+            });
+            Ok(SmtpResponse {
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SmtpRetryLogic;
+
+impl RetryLogic for SmtpRetryLogic {
+    type Error = SmtpError;
+    type Response = SmtpResponse;
+
+    /// Retries the transient failures, and the replies of the server in the 4xx range, which
+    /// are transient negative completions such as exceeded rates or unavailable mailboxes.
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            SmtpError::Dns { .. }
+            | SmtpError::NoAddresses
+            | SmtpError::Connect { .. }
+            | SmtpError::Io { .. } => true,
+            SmtpError::UnexpectedReply { code, .. } => (400..500).contains(code),
+            _ => false,
+        }
+    }
+}
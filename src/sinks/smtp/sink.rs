@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::util::BoxService;
+use uuid::Uuid;
+use vector_core::{buffers::Acker, partition::Partitioner, stream::BatcherSettings, ByteSizeOf};
+
+use super::{
+    message::MessageFormat,
+    service::{SmtpRequest, SmtpResponse},
+};
+use crate::{
+    event::{Event, EventFinalizers, Finalizable},
+    internal_events::TemplateRenderingError,
+    sinks::util::{SinkBuilderExt, StreamSink},
+    template::Template,
+};
+
+pub(super) struct SmtpSink {
+    pub(super) subject: Template,
+    pub(super) body: Template,
+    pub(super) digest_key: Option<Template>,
+    pub(super) format: MessageFormat,
+    pub(super) batch_settings: BatcherSettings,
+    pub(super) service: BoxService<SmtpRequest, SmtpResponse, crate::Error>,
+    pub(super) acker: Acker,
+}
+
+/// Partitions the events by their rendered digest keys, the events which can't be rendered being
+/// digested together.
+struct DigestPartitioner(Option<Template>);
+
+impl Partitioner for DigestPartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        self.0.as_ref().and_then(|template| {
+            template
+                .render_string(item)
+                .map_err(|error| {
+                    emit!(&TemplateRenderingError {
+                        error,
+                        field: Some("digest_key"),
+                        drop_event: false,
+                    });
+                })
+                .ok()
+        })
+    }
+}
+
+impl SmtpSink {
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let subject = self.subject;
+        let body = self.body;
+        let format = self.format;
+        input
+            .batched_partitioned(DigestPartitioner(self.digest_key), self.batch_settings)
+            .filter_map(|(_, events)| {
+                future::ready(build_request(&subject, &body, &format, events))
+            })
+            .into_driver(self.service, self.acker)
+            .run()
+            .await
+    }
+}
+
+/// Builds a single email from the events, the subject of which is rendered from the first one,
+/// and the body of which holds the rendered bodies of all of them.
+fn build_request(
+    subject_template: &Template,
+    body_template: &Template,
+    format: &MessageFormat,
+    events: Vec<Event>,
+) -> Option<SmtpRequest> {
+    let mut subject = None;
+    let mut bodies = Vec::with_capacity(events.len());
+    let mut finalizers = EventFinalizers::default();
+    let mut events_byte_size = 0;
+
+    for mut event in events {
+        let rendered = subject_template
+            .render_string(&event)
+            .map_err(|error| (error, "subject"))
+            .and_then(|subject| {
+                body_template
+                    .render_string(&event)
+                    .map(|body| (subject, body))
+                    .map_err(|error| (error, "body"))
+            });
+        match rendered {
+            Ok((event_subject, body)) => {
+                subject.get_or_insert(event_subject);
+                bodies.push(body);
+                events_byte_size += event.size_of();
+                finalizers.merge(event.take_finalizers());
+            }
+            Err((error, field)) => emit!(&TemplateRenderingError {
+                error,
+                field: Some(field),
+                drop_event: true,
+            }),
+        }
+    }
+
+    let subject = subject?;
+    Some(SmtpRequest {
+        message: format.format(&subject, &bodies.join("\n"), Utc::now(), Uuid::new_v4()),
+        finalizers,
+        events_count: bodies.len(),
+        events_byte_size,
+    })
+}
+
+#[async_trait]
+impl StreamSink<Event> for SmtpSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
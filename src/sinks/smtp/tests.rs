@@ -0,0 +1,343 @@
+use std::{net::SocketAddr, pin::Pin, sync::Arc};
+
+use futures::{stream, StreamExt};
+use openssl::ssl::{Ssl, SslAcceptor};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+use tokio_openssl::SslStream;
+use vector_core::event::{BatchNotifier, BatchStatus};
+
+use super::*;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    event::{Event, LogEvent},
+    sinks::util::retries::RetryLogic,
+    test_util::{components, next_addr},
+    tls::{self, MaybeTls, TlsOptions, TlsSettings},
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<SmtpSinkConfig>();
+}
+
+/// What the fake server received in a session, for each email.
+#[derive(Debug, Clone, Default)]
+struct Received {
+    tls: bool,
+    auth: Option<String>,
+    sender: String,
+    recipients: Vec<String>,
+    data: String,
+}
+
+/// Runs a fake server, which supports `STARTTLS` when given an acceptor, and replies to `RCPT`
+/// commands with the given reply.
+async fn serve(
+    acceptor: Option<SslAcceptor>,
+    rcpt_reply: &'static str,
+) -> (SocketAddr, UnboundedReceiver<Received>) {
+    let address = next_addr();
+    let listener = TcpListener::bind(address).await.unwrap();
+    let acceptor = acceptor.map(Arc::new);
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(session(stream, acceptor.clone(), rcpt_reply, tx.clone()));
+        }
+    });
+    (address, rx)
+}
+
+async fn session(
+    stream: TcpStream,
+    acceptor: Option<Arc<SslAcceptor>>,
+    rcpt_reply: &'static str,
+    tx: UnboundedSender<Received>,
+) {
+    let mut stream = BufReader::new(MaybeTls::<_, SslStream<TcpStream>>::Raw(stream));
+    let mut received = Received::default();
+    reply(&mut stream, "220 localhost ESMTP").await;
+
+    while let Some(line) = read_line(&mut stream).await {
+        let command = line.split(' ').next().unwrap().to_ascii_uppercase();
+        match command.as_str() {
+            "EHLO" => {
+                let starttls = if acceptor.is_some() && !received.tls {
+                    "250-STARTTLS\r\n"
+                } else {
+                    ""
+                };
+                let ehlo = format!("250-localhost\r\n{}250 AUTH PLAIN LOGIN", starttls);
+                reply(&mut stream, &ehlo).await;
+            }
+            "STARTTLS" => {
+                reply(&mut stream, "220 Ready to start TLS").await;
+                let stream_raw = match stream.into_inner() {
+                    MaybeTls::Raw(stream) => stream,
+                    MaybeTls::Tls(_) => panic!("TLS negotiated twice"),
+                };
+                let ssl = Ssl::new(acceptor.as_ref().unwrap().context()).unwrap();
+                let mut stream_tls = SslStream::new(ssl, stream_raw).unwrap();
+                Pin::new(&mut stream_tls).accept().await.unwrap();
+                stream = BufReader::new(MaybeTls::Tls(stream_tls));
+                received.tls = true;
+            }
+            "AUTH" => {
+                received.auth = Some(line[5..].to_owned());
+                reply(&mut stream, "235 Authenticated").await;
+            }
+            "MAIL" => {
+                received.sender = line;
+                reply(&mut stream, "250 OK").await;
+            }
+            "RCPT" => {
+                received.recipients.push(line);
+                reply(&mut stream, rcpt_reply).await;
+            }
+            "DATA" => {
+                reply(&mut stream, "354 Go ahead").await;
+                while let Some(line) = read_line(&mut stream).await {
+                    if line == "." {
+                        break;
+                    }
+                    received.data.push_str(&line);
+                    received.data.push('\n');
+                }
+                reply(&mut stream, "250 Queued").await;
+                tx.send(received.clone()).unwrap();
+            }
+            "QUIT" => {
+                reply(&mut stream, "221 Bye").await;
+                return;
+            }
+            _ => reply(&mut stream, "500 Unknown command").await,
+        }
+    }
+}
+
+async fn read_line<S: AsyncRead + Unpin>(stream: &mut BufReader<S>) -> Option<String> {
+    let mut line = String::new();
+    match stream.read_line(&mut line).await.unwrap() {
+        0 => None,
+        _ => Some(line.trim_end_matches("\r\n").to_owned()),
+    }
+}
+
+async fn reply<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut BufReader<S>, line: &str) {
+    let stream = stream.get_mut();
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .await
+        .unwrap();
+    stream.flush().await.unwrap();
+}
+
+fn config(address: SocketAddr, extra: &str) -> SmtpSinkConfig {
+    toml::from_str(&format!(
+        r#"host = "{}"
+        port = {}
+        helo_name = "vector.local"
+        from = "Vector <vector@example.com>"
+        to = ["a@example.com", "B <b@example.com>"]
+        subject = "Alert from {{{{ host }}}}"
+        {}"#,
+        address.ip(),
+        address.port(),
+        extra
+    ))
+    .unwrap()
+}
+
+fn events(batch: &BatchNotifier, fields: &[(&str, &str)]) -> Vec<Event> {
+    fields
+        .iter()
+        .map(|(service, message)| {
+            let mut log = LogEvent::from(*message).with_batch_notifier(batch);
+            log.insert("host", "host-a");
+            log.insert("service", *service);
+            Event::from(log)
+        })
+        .collect()
+}
+
+async fn run(config: SmtpSinkConfig, fields: &[(&str, &str)]) -> BatchStatus {
+    let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = events(&batch, fields);
+    drop(batch);
+    components::run_sink_events(sink, stream::iter(events), &components::HTTP_SINK_TAGS).await;
+    receiver.try_recv().unwrap()
+}
+
+/// Reads the emails, which were all received once the events are delivered, ordered by their
+/// bodies.
+fn received(mut rx: UnboundedReceiver<Received>) -> Vec<Received> {
+    let mut received = Vec::new();
+    while let Ok(email) = rx.try_recv() {
+        received.push(email);
+    }
+    received.sort_by(|a, b| body(a).cmp(body(b)));
+    received
+}
+
+/// The body of an email, following its headers.
+fn body(received: &Received) -> &str {
+    received.data.split_once("\n\n").unwrap().1
+}
+
+#[tokio::test]
+async fn sends_emails() {
+    let (address, rx) = serve(None, "250 OK").await;
+    let config = config(
+        address,
+        r#"starttls = "disabled"
+        auth.user = "alice"
+        auth.password = "secret""#,
+    );
+
+    let status = run(config, &[("api", "disk full"), ("api", ".hidden")]).await;
+    assert_eq!(status, BatchStatus::Delivered);
+
+    let received = received(rx);
+    assert_eq!(received.len(), 2);
+    for email in &received {
+        assert!(!email.tls);
+        assert_eq!(email.auth.as_deref(), Some("PLAIN AGFsaWNlAHNlY3JldA=="));
+        assert_eq!(email.sender, "MAIL FROM:<vector@example.com>");
+        assert_eq!(
+            email.recipients,
+            vec!["RCPT TO:<a@example.com>", "RCPT TO:<b@example.com>"]
+        );
+        assert!(email.data.starts_with(
+            "From: Vector <vector@example.com>\n\
+             To: a@example.com, B <b@example.com>\n\
+             Subject: Alert from host-a\n"
+        ));
+        assert!(email.data.contains("\nMessage-ID: <"));
+        assert!(email.data.contains("@vector.local>\n"));
+    }
+    // The lines starting with a dot are sent dot-stuffed.
+    assert_eq!(body(&received[0]), "..hidden\n");
+    assert_eq!(body(&received[1]), "disk full\n");
+}
+
+#[tokio::test]
+async fn digests_events_by_key() {
+    let (address, rx) = serve(None, "250 OK").await;
+    let config = config(
+        address,
+        r#"starttls = "disabled"
+        body = "[{{ service }}] {{ message }}"
+        digest_key = "{{ service }}"
+        batch.max_events = 10
+        batch.timeout_secs = 1"#,
+    );
+
+    let status = run(config, &[("api", "one"), ("db", "two"), ("api", "three")]).await;
+    assert_eq!(status, BatchStatus::Delivered);
+
+    let received = received(rx);
+    assert_eq!(received.len(), 2);
+    let mut bodies = received.iter().map(body).collect::<Vec<_>>();
+    bodies.sort_unstable();
+    assert_eq!(bodies, vec!["[api] one\n[api] three\n", "[db] two\n"]);
+}
+
+#[tokio::test]
+async fn upgrades_with_starttls() {
+    let settings = TlsSettings::from_options(&Some(TlsOptions {
+        crt_file: Some(tls::TEST_PEM_CRT_PATH.into()),
+        key_file: Some(tls::TEST_PEM_KEY_PATH.into()),
+        ..Default::default()
+    }))
+    .unwrap();
+    let (address, rx) = serve(Some(settings.acceptor().unwrap()), "250 OK").await;
+    let config = config(
+        address,
+        &format!(
+            r#"tls.ca_file = "{}"
+            tls.verify_hostname = false
+            auth.user = "alice"
+            auth.password = "secret""#,
+            tls::TEST_PEM_CA_PATH
+        ),
+    );
+
+    let status = run(config, &[("api", "disk full")]).await;
+    assert_eq!(status, BatchStatus::Delivered);
+
+    let received = received(rx);
+    assert_eq!(received.len(), 1);
+    assert!(received[0].tls);
+    assert_eq!(
+        received[0].auth.as_deref(),
+        Some("PLAIN AGFsaWNlAHNlY3JldA==")
+    );
+}
+
+#[tokio::test]
+async fn healthcheck_fails_without_required_starttls() {
+    let (address, _rx) = serve(None, "250 OK").await;
+    let (_, healthcheck) = config(address, "")
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+
+    let error = healthcheck.await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SmtpError>(),
+        Some(SmtpError::StartTlsUnsupported)
+    ));
+}
+
+#[tokio::test]
+async fn rejects_events_refused_by_server() {
+    let (address, _rx) = serve(None, "550 No such user").await;
+    let (sink, _) = config(address, r#"starttls = "disabled""#)
+        .build(SinkContext::new_test())
+        .await
+        .unwrap();
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let events = events(&batch, &[("api", "disk full")]);
+    drop(batch);
+    sink.run(stream::iter(events).map(Into::into))
+        .await
+        .unwrap();
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[test]
+fn retries_transient_failures() {
+    let logic = service::SmtpRetryLogic;
+    let reply = |code| SmtpError::UnexpectedReply {
+        command: "RCPT",
+        code,
+        message: String::new(),
+    };
+
+    assert!(logic.is_retriable_error(&reply(421)));
+    assert!(logic.is_retriable_error(&reply(451)));
+    assert!(!logic.is_retriable_error(&reply(550)));
+    assert!(!logic.is_retriable_error(&SmtpError::StartTlsUnsupported));
+    assert!(logic.is_retriable_error(&SmtpError::Io {
+        source: std::io::ErrorKind::ConnectionReset.into(),
+    }));
+}
+
+#[test]
+fn rejects_invalid_recipients() {
+    let address = next_addr();
+    let mut config = config(address, "");
+    config.to = vec!["oncall".into()];
+    assert!(config.build_client().is_err());
+    config.to = Vec::new();
+    assert!(config.build_client().is_err());
+}
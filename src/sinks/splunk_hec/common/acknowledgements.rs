@@ -5,18 +5,19 @@ use std::{
     time::Duration,
 };
 
+use bytes::Bytes;
 use hyper::Body;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc::Receiver, oneshot::Sender};
 use vector_core::event::EventStatus;
 
-use super::service::HttpRequestBuilder;
+use super::service::{HecAckResponseBody, HttpRequestBuilder};
 use crate::{
     config::AcknowledgementsConfig,
     http::HttpClient,
     internal_events::{
         SplunkIndexerAcknowledgementAPIError, SplunkIndexerAcknowledgementAckAdded,
-        SplunkIndexerAcknowledgementAcksRemoved,
+        SplunkIndexerAcknowledgementAcksRemoved, SplunkIndexerAcknowledgementRequestResent,
     },
 };
 
@@ -26,6 +27,7 @@ pub struct HecClientAcknowledgementsConfig {
     pub indexer_acknowledgements_enabled: bool,
     pub query_interval: NonZeroU8,
     pub retry_limit: NonZeroU8,
+    pub resend_limit: u8,
     pub max_pending_acks: NonZeroU64,
     #[serde(
         default,
@@ -42,6 +44,7 @@ impl Default for HecClientAcknowledgementsConfig {
             indexer_acknowledgements_enabled: true,
             query_interval: NonZeroU8::new(10).unwrap(),
             retry_limit: NonZeroU8::new(30).unwrap(),
+            resend_limit: 3,
             max_pending_acks: NonZeroU64::new(1_000_000).unwrap(),
             inner: Default::default(),
         }
@@ -66,9 +69,24 @@ pub enum HecAckApiError {
     ServerSendQuery,
 }
 
+/// The payload of a request sent to Splunk HEC, kept to be resent if its ack id expires
+#[derive(Clone, Debug)]
+pub struct HecSentRequest {
+    pub body: Bytes,
+    pub passthrough_token: Option<Arc<str>>,
+}
+
+struct PendingAck {
+    retries: u8,
+    resends: u8,
+    request: Option<HecSentRequest>,
+    ack_event_status_sender: Sender<EventStatus>,
+}
+
 struct HecAckClient {
-    acks: HashMap<u64, (u8, Sender<EventStatus>)>,
+    acks: HashMap<u64, PendingAck>,
     retry_limit: u8,
+    resend_limit: u8,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
 }
@@ -76,21 +94,34 @@ struct HecAckClient {
 impl HecAckClient {
     fn new(
         retry_limit: u8,
+        resend_limit: u8,
         client: HttpClient,
         http_request_builder: Arc<HttpRequestBuilder>,
     ) -> Self {
         Self {
             acks: HashMap::new(),
             retry_limit,
+            resend_limit,
             client,
             http_request_builder,
         }
     }
 
     /// Adds an ack id to be queried
-    fn add(&mut self, ack_id: u64, ack_event_status_sender: Sender<EventStatus>) {
-        self.acks
-            .insert(ack_id, (self.retry_limit, ack_event_status_sender));
+    fn add(
+        &mut self,
+        ack_id: u64,
+        request: HecSentRequest,
+        ack_event_status_sender: Sender<EventStatus>,
+    ) {
+        let pending_ack = PendingAck {
+            retries: self.retry_limit,
+            resends: self.resend_limit,
+            // The payload is only retained if it may be resent
+            request: (self.resend_limit > 0).then(|| request),
+            ack_event_status_sender,
+        };
+        self.acks.insert(ack_id, pending_ack);
         emit!(&SplunkIndexerAcknowledgementAckAdded);
     }
 
@@ -109,6 +140,9 @@ impl HecAckClient {
                         .filter_map(|(ack_id, ack_status)| ack_status.then(|| *ack_id))
                         .collect::<Vec<u64>>();
                     self.finalize_delivered_ack_ids(acked_ack_ids.as_slice());
+                    // Ack ids which are never acked may have been lost by Splunk, such as on
+                    // restarts, so their requests are resent before giving up on them.
+                    self.resend_expired_ack_ids().await;
                     self.expire_ack_ids_with_status(EventStatus::Rejected);
                 }
                 Err(error) => {
@@ -145,8 +179,10 @@ impl HecAckClient {
     fn finalize_delivered_ack_ids(&mut self, ack_ids: &[u64]) {
         let mut removed_count = 0.0;
         for ack_id in ack_ids {
-            if let Some((_, ack_event_status_sender)) = self.acks.remove(ack_id) {
-                let _ = ack_event_status_sender.send(EventStatus::Delivered);
+            if let Some(pending_ack) = self.acks.remove(ack_id) {
+                let _ = pending_ack
+                    .ack_event_status_sender
+                    .send(EventStatus::Delivered);
                 removed_count += 1.0;
                 debug!(message = "Finalized ack id", ?ack_id);
             }
@@ -165,23 +201,63 @@ impl HecAckClient {
 
     /// Decrements retry count on all stored ack ids by 1
     fn decrement_retries(&mut self) {
-        for (retries, _) in self.acks.values_mut() {
-            *retries = retries.checked_sub(1).unwrap_or(0);
+        for pending_ack in self.acks.values_mut() {
+            pending_ack.retries = pending_ack.retries.checked_sub(1).unwrap_or(0);
+        }
+    }
+
+    /// Returns the expired ack ids (those with a retry count of 0)
+    fn expired_ack_ids(&self) -> Vec<u64> {
+        self.acks
+            .iter()
+            .filter_map(|(ack_id, pending_ack)| (pending_ack.retries == 0).then(|| *ack_id))
+            .collect()
+    }
+
+    /// Resends the requests of the expired ack ids with resends left, storing the ack ids of
+    /// the new requests in their place
+    async fn resend_expired_ack_ids(&mut self) {
+        for ack_id in self.expired_ack_ids() {
+            let request = match self.acks.get(&ack_id) {
+                Some(PendingAck {
+                    resends,
+                    request: Some(request),
+                    ..
+                }) if *resends > 0 => request.clone(),
+                _ => continue,
+            };
+
+            match self.send_request(request).await {
+                Ok(new_ack_id) => {
+                    if let Some(mut pending_ack) = self.acks.remove(&ack_id) {
+                        pending_ack.retries = self.retry_limit;
+                        pending_ack.resends -= 1;
+                        self.acks.insert(new_ack_id, pending_ack);
+                        emit!(&SplunkIndexerAcknowledgementRequestResent);
+                        debug!(
+                            message = "Resent request of expired ack id",
+                            ?ack_id,
+                            ?new_ack_id
+                        );
+                    }
+                }
+                Err(error) => {
+                    emit!(&SplunkIndexerAcknowledgementAPIError {
+                        message: "Unable to resend request of expired ack id.",
+                        error,
+                    });
+                }
+            }
         }
     }
 
     /// Removes all expired ack ids (those with a retry count of 0) and
     /// finalizes associated events with the given status
     fn expire_ack_ids_with_status(&mut self, status: EventStatus) {
-        let expired_ack_ids = self
-            .acks
-            .iter()
-            .filter_map(|(ack_id, (retries, _))| (*retries == 0).then(|| *ack_id))
-            .collect::<Vec<_>>();
         let mut removed_count = 0.0;
-        for ack_id in expired_ack_ids {
-            if let Some((_, ack_event_status_sender)) = self.acks.remove(&ack_id) {
-                let _ = ack_event_status_sender.send(status);
+        for ack_id in self.expired_ack_ids() {
+            if let Some(pending_ack) = self.acks.remove(&ack_id) {
+                let _ = pending_ack.ack_event_status_sender.send(status);
                 removed_count += 1.0;
             }
         }
@@ -223,10 +299,43 @@ impl HecAckClient {
             Err(HecAckApiError::ServerSendQuery)
         }
     }
+
+    /// Resends a request to Splunk HEC, returning its new ack id
+    async fn send_request(&self, request: HecSentRequest) -> Result<u64, HecAckApiError> {
+        let request = self
+            .http_request_builder
+            .build_request(
+                request.body,
+                "/services/collector/event",
+                request.passthrough_token,
+            )
+            .map_err(|_| HecAckApiError::ClientBuildRequest)?;
+
+        let response = self
+            .client
+            .send(request.map(Body::from))
+            .await
+            .map_err(|_| HecAckApiError::ServerSendQuery)?;
+
+        let status = response.status();
+        if status.is_success() {
+            let response_body = hyper::body::to_bytes(response.into_body())
+                .await
+                .map_err(|_| HecAckApiError::ClientParseResponse)?;
+            serde_json::from_slice::<HecAckResponseBody>(&response_body)
+                .ok()
+                .and_then(|body| body.ack_id)
+                .ok_or(HecAckApiError::ClientParseResponse)
+        } else if status.is_client_error() {
+            Err(HecAckApiError::ClientSendQuery)
+        } else {
+            Err(HecAckApiError::ServerSendQuery)
+        }
+    }
 }
 
 pub async fn run_acknowledgements(
-    mut receiver: Receiver<(u64, Sender<EventStatus>)>,
+    mut receiver: Receiver<(u64, HecSentRequest, Sender<EventStatus>)>,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
     indexer_acknowledgements: HecClientAcknowledgementsConfig,
@@ -236,6 +345,7 @@ pub async fn run_acknowledgements(
     ));
     let mut ack_client = HecAckClient::new(
         indexer_acknowledgements.retry_limit.get(),
+        indexer_acknowledgements.resend_limit,
         client,
         http_request_builder,
     );
@@ -247,8 +357,8 @@ pub async fn run_acknowledgements(
             },
             ack_info = receiver.recv() => {
                 match ack_info {
-                    Some((ack_id, tx)) => {
-                        ack_client.add(ack_id, tx);
+                    Some((ack_id, request, tx)) => {
+                        ack_client.add(ack_id, request, tx);
                         debug!(message = "Stored ack id", ?ack_id);
                     },
                     None => break,
@@ -262,11 +372,12 @@ pub async fn run_acknowledgements(
 mod tests {
     use std::sync::Arc;
 
+    use bytes::Bytes;
     use futures_util::{stream::FuturesUnordered, StreamExt};
     use tokio::sync::oneshot::{self, Receiver};
     use vector_core::{config::proxy::ProxyConfig, event::EventStatus};
 
-    use super::HecAckClient;
+    use super::{HecAckClient, HecSentRequest};
     use crate::{
         http::HttpClient,
         sinks::{
@@ -281,7 +392,7 @@ mod tests {
         let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
         let http_request_builder =
             HttpRequestBuilder::new(String::from(""), String::from(""), Compression::default());
-        HecAckClient::new(retry_limit, client, Arc::new(http_request_builder))
+        HecAckClient::new(retry_limit, 0, client, Arc::new(http_request_builder))
     }
 
     fn populate_ack_client(
@@ -291,7 +402,11 @@ mod tests {
         let mut ack_status_rxs = Vec::new();
         for ack_id in ack_ids {
             let (tx, rx) = oneshot::channel();
-            ack_client.add(*ack_id, tx);
+            let request = HecSentRequest {
+                body: Bytes::from("test-message"),
+                passthrough_token: None,
+            };
+            ack_client.add(*ack_id, request, tx);
             ack_status_rxs.push(rx);
         }
         ack_status_rxs
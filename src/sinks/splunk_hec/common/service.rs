@@ -18,7 +18,9 @@ use tower::Service;
 use uuid::Uuid;
 use vector_core::event::EventStatus;
 
-use super::acknowledgements::{run_acknowledgements, HecClientAcknowledgementsConfig};
+use super::acknowledgements::{
+    run_acknowledgements, HecClientAcknowledgementsConfig, HecSentRequest,
+};
 use crate::{
     http::HttpClient,
     internal_events::{SplunkIndexerAcknowledgementUnavailableError, SplunkResponseParseError},
@@ -31,15 +33,15 @@ use crate::{
 
 pub struct HecService<S> {
     pub inner: S,
-    ack_finalizer_tx: Option<mpsc::Sender<(u64, oneshot::Sender<EventStatus>)>>,
+    ack_finalizer_tx: Option<mpsc::Sender<(u64, HecSentRequest, oneshot::Sender<EventStatus>)>>,
     ack_slots: PollSemaphore,
     current_ack_slot: Option<OwnedSemaphorePermit>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
-struct HecAckResponseBody {
+pub(super) struct HecAckResponseBody {
     #[serde(alias = "ackId")]
-    ack_id: Option<u64>,
+    pub(super) ack_id: Option<u64>,
 }
 
 impl<S> HecService<S>
@@ -114,6 +116,10 @@ where
 
         let events_count = req.events_count;
         let events_byte_size = req.events_byte_size;
+        let sent_request = HecSentRequest {
+            body: req.body.clone(),
+            passthrough_token: req.passthrough_token.clone(),
+        };
         let response = self.inner.call(req);
 
         Box::pin(async move {
@@ -126,7 +132,7 @@ where
                         Ok(body) => {
                             if let Some(ack_id) = body.ack_id {
                                 let (tx, rx) = oneshot::channel();
-                                match ack_finalizer_tx.send((ack_id, tx)).await {
+                                match ack_finalizer_tx.send((ack_id, sent_request, tx)).await {
                                     Ok(_) => rx.await.unwrap_or(EventStatus::Rejected),
                                     // If we cannot send ack ids to the ack client, fall back to default behavior
                                     Err(_) => {
@@ -227,7 +233,7 @@ mod tests {
         collections::HashMap,
         num::{NonZeroU64, NonZeroU8},
         sync::{
-            atomic::{AtomicU64, Ordering},
+            atomic::{AtomicBool, AtomicU64, Ordering},
             Arc,
         },
         task::Poll,
@@ -410,6 +416,7 @@ mod tests {
         let acknowledgements_config = HecClientAcknowledgementsConfig {
             query_interval: NonZeroU8::new(1).unwrap(),
             retry_limit: NonZeroU8::new(1).unwrap(),
+            resend_limit: 0,
             ..Default::default()
         };
         let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
@@ -419,6 +426,78 @@ mod tests {
         assert_eq!(EventStatus::Rejected, response.event_status)
     }
 
+    #[tokio::test]
+    async fn acknowledgements_enabled_on_server_resend_limit_exceeded() {
+        let mock_server = get_hec_mock_server(true, ack_response_always_fail).await;
+
+        let acknowledgements_config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(1).unwrap(),
+            retry_limit: NonZeroU8::new(1).unwrap(),
+            resend_limit: 1,
+            ..Default::default()
+        };
+        let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
+
+        let request = get_hec_request();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(EventStatus::Rejected, response.event_status);
+
+        let event_requests = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|request| request.url.path() == "/services/collector/event")
+            .count();
+        assert_eq!(event_requests, 2);
+    }
+
+    #[tokio::test]
+    async fn acknowledgements_resent_after_ack_ids_lost() {
+        // Simulates a restart of Splunk, which forgets about the ack ids of the requests
+        // received before it.
+        let restarted = Arc::new(AtomicBool::new(false));
+        let ack_response = {
+            let restarted = Arc::clone(&restarted);
+            move |req: &Request| {
+                let req =
+                    serde_json::from_slice::<HecAckStatusRequest>(req.body.as_slice()).unwrap();
+                let acked = restarted.swap(true, Ordering::Relaxed);
+                ResponseTemplate::new(200).set_body_json(HecAckStatusResponse {
+                    acks: req
+                        .acks
+                        .into_iter()
+                        .map(|ack_id| (ack_id, acked))
+                        .collect::<HashMap<_, _>>(),
+                })
+            }
+        };
+        let mock_server = get_hec_mock_server(true, ack_response).await;
+
+        let acknowledgements_config = HecClientAcknowledgementsConfig {
+            query_interval: NonZeroU8::new(1).unwrap(),
+            retry_limit: NonZeroU8::new(1).unwrap(),
+            resend_limit: 1,
+            ..Default::default()
+        };
+        let mut service = get_hec_service(mock_server.uri(), acknowledgements_config);
+
+        let request = get_hec_request();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(EventStatus::Delivered, response.event_status);
+
+        let event_requests = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|request| {
+                request.url.path() == "/services/collector/event" && request.body == b"test-message"
+            })
+            .count();
+        assert_eq!(event_requests, 2);
+    }
+
     #[tokio::test]
     async fn acknowledgements_server_changed_ack_response_format() {
         let ack_response = |_: &Request| {
@@ -512,6 +591,7 @@ mod tests {
         let acknowledgements_config = HecClientAcknowledgementsConfig {
             query_interval: NonZeroU8::new(1).unwrap(),
             retry_limit: NonZeroU8::new(5).unwrap(),
+            resend_limit: 0,
             // Allow a single pending ack
             max_pending_acks: NonZeroU64::new(1).unwrap(),
             ..Default::default()
@@ -0,0 +1,616 @@
+use std::fmt::Write;
+
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext,
+        SinkDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{SyslogInvalidPriorityValue, TemplateRenderingError},
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfiguration},
+        tcp::TcpSinkConfig,
+        udp::UdpSinkConfig,
+        Encoding,
+    },
+    template::Template,
+};
+
+/// The names of the facilities, by code, as parsed by the `syslog` source.
+const FACILITIES: [&str; 24] = [
+    "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv",
+    "ftp", "ntp", "audit", "alert", "clockd", "local0", "local1", "local2", "local3", "local4",
+    "local5", "local6", "local7",
+];
+
+/// The names of the severities, by code, as parsed by the `syslog` source.
+const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+const DEFAULT_FACILITY: u8 = 1;
+const DEFAULT_SEVERITY: u8 = 6;
+const DEFAULT_APP_NAME: &str = "vector";
+
+const NIL_VALUE: &str = "-";
+
+#[derive(Deserialize, Serialize, Debug)]
+// TODO: add back when serde-rs/serde#1358 is addressed
+// #[serde(deny_unknown_fields)]
+pub struct SyslogSinkConfig {
+    #[serde(flatten)]
+    mode: Mode,
+    #[serde(default)]
+    format: Format,
+    #[serde(default)]
+    framing: Framing,
+    #[serde(default = "default_facility")]
+    facility: Template,
+    #[serde(default = "default_severity")]
+    severity: Template,
+    #[serde(default = "default_app_name")]
+    app_name: Template,
+    proc_id: Option<Template>,
+    msg_id: Option<Template>,
+    #[serde(default)]
+    structured_data: Vec<String>,
+    encoding: EncodingConfig<Encoding>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Mode {
+    Tcp(TcpSinkConfig),
+    Udp(UdpSinkConfig),
+}
+
+/// The format of the messages.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    #[derivative(Default)]
+    Rfc5424,
+    Rfc3164,
+}
+
+/// How the messages are framed over TCP, as specified in RFC 6587.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// Each message starts with its length.
+    #[derivative(Default)]
+    OctetCounting,
+    /// Each message ends with a newline.
+    NonTransparent,
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid {} {:?}", field, value))]
+    InvalidPriority { field: &'static str, value: String },
+}
+
+fn default_facility() -> Template {
+    Template::try_from(FACILITIES[usize::from(DEFAULT_FACILITY)]).unwrap()
+}
+
+fn default_severity() -> Template {
+    Template::try_from(SEVERITIES[usize::from(DEFAULT_SEVERITY)]).unwrap()
+}
+
+fn default_app_name() -> Template {
+    Template::try_from(DEFAULT_APP_NAME).unwrap()
+}
+
+inventory::submit! {
+    SinkDescription::new::<SyslogSinkConfig>("syslog")
+}
+
+impl GenerateConfig for SyslogSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "127.0.0.1:514"
+            mode = "tcp"
+            encoding.codec = "text""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "syslog")]
+impl SinkConfig for SyslogSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        validate_priority(&self.facility, "facility", facility_code)?;
+        validate_priority(&self.severity, "severity", severity_code)?;
+
+        let encoder = SyslogEncoder {
+            format: self.format,
+            framing: None,
+            facility: self.facility.clone(),
+            severity: self.severity.clone(),
+            app_name: self.app_name.clone(),
+            proc_id: self.proc_id.clone(),
+            msg_id: self.msg_id.clone(),
+            structured_data: self.structured_data.clone(),
+            hostname: crate::get_hostname().unwrap_or_else(|_| NIL_VALUE.into()),
+            encoding: self.encoding.clone(),
+        };
+        match &self.mode {
+            Mode::Tcp(config) => {
+                let encoder = SyslogEncoder {
+                    framing: Some(self.framing),
+                    ..encoder
+                };
+                config.build(cx, move |event| encoder.encode(event))
+            }
+            // Each datagram holds a single message.
+            Mode::Udp(config) => config.build(cx, move |event| encoder.encode(event)),
+        }
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "syslog"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        None
+    }
+}
+
+/// Fails if the template is static but not a valid facility or severity.
+fn validate_priority(
+    template: &Template,
+    field: &'static str,
+    code: fn(&str) -> Option<u8>,
+) -> Result<(), BuildError> {
+    if template.is_dynamic() || code(template.get_ref()).is_some() {
+        Ok(())
+    } else {
+        Err(BuildError::InvalidPriority {
+            field,
+            value: template.get_ref().to_owned(),
+        })
+    }
+}
+
+/// Parses a facility from either its name or its code.
+fn facility_code(value: &str) -> Option<u8> {
+    priority_code(value, &FACILITIES, &[("security", 4)])
+}
+
+/// Parses a severity from either its name or its code.
+fn severity_code(value: &str) -> Option<u8> {
+    priority_code(
+        value,
+        &SEVERITIES,
+        &[
+            ("emergency", 0),
+            ("panic", 0),
+            ("critical", 2),
+            ("error", 3),
+            ("warn", 4),
+            ("informational", 6),
+        ],
+    )
+}
+
+fn priority_code(value: &str, names: &[&str], aliases: &[(&str, u8)]) -> Option<u8> {
+    let value = value.trim().to_lowercase();
+    if let Ok(code) = value.parse::<u8>() {
+        return (usize::from(code) < names.len()).then(|| code);
+    }
+    names
+        .iter()
+        .position(|name| *name == value)
+        .map(|code| code as u8)
+        .or_else(|| {
+            aliases
+                .iter()
+                .find(|(alias, _)| *alias == value)
+                .map(|(_, code)| *code)
+        })
+}
+
+struct SyslogEncoder {
+    format: Format,
+    /// The framing of the messages, if sent over a stream.
+    framing: Option<Framing>,
+    facility: Template,
+    severity: Template,
+    app_name: Template,
+    proc_id: Option<Template>,
+    msg_id: Option<Template>,
+    structured_data: Vec<String>,
+    /// The hostname used for the events without one.
+    hostname: String,
+    encoding: EncodingConfig<Encoding>,
+}
+
+impl SyslogEncoder {
+    fn encode(&self, mut event: Event) -> Option<Bytes> {
+        let facility = render_priority(
+            &self.facility,
+            "facility",
+            facility_code,
+            DEFAULT_FACILITY,
+            &event,
+        );
+        let severity = render_priority(
+            &self.severity,
+            "severity",
+            severity_code,
+            DEFAULT_SEVERITY,
+            &event,
+        );
+        let priority = u16::from(facility) * 8 + u16::from(severity);
+        let app_name = render(&self.app_name, "app_name", &event)
+            .unwrap_or_else(|| DEFAULT_APP_NAME.to_owned());
+        let proc_id = self
+            .proc_id
+            .as_ref()
+            .and_then(|template| render(template, "proc_id", &event));
+
+        let log = event.as_log();
+        let hostname = log
+            .get(log_schema().host_key())
+            .map(|host| host.to_string_lossy())
+            .unwrap_or_else(|| self.hostname.clone());
+        let timestamp = log
+            .get(log_schema().timestamp_key())
+            .and_then(Value::as_timestamp)
+            .copied()
+            .unwrap_or_else(Utc::now);
+
+        let mut line = match self.format {
+            Format::Rfc5424 => {
+                let msg_id = self
+                    .msg_id
+                    .as_ref()
+                    .and_then(|template| render(template, "msg_id", &event));
+                format!(
+                    "<{}>1 {} {} {} {} {} {}",
+                    priority,
+                    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    header_field(&hostname, 255),
+                    header_field(&app_name, 48),
+                    header_field(proc_id.as_deref().unwrap_or_default(), 128),
+                    header_field(msg_id.as_deref().unwrap_or_default(), 32),
+                    self.structured_data(log),
+                )
+            }
+            Format::Rfc3164 => {
+                let mut tag = header_field(&app_name, 32);
+                if let Some(proc_id) = proc_id {
+                    let _ = write!(tag, "[{}]", header_field(&proc_id, 128));
+                }
+                format!(
+                    "<{}>{} {} {}:",
+                    priority,
+                    timestamp.format("%b %e %H:%M:%S"),
+                    header_field(&hostname, 255),
+                    tag,
+                )
+            }
+        };
+
+        self.encoding.apply_rules(&mut event);
+        let log = event.into_log();
+        let message = match self.encoding.codec() {
+            Encoding::Json => serde_json::to_string(&log)
+                .map_err(|error| error!(message = "Unable to encode.", %error))
+                .ok()?,
+            Encoding::Text => log
+                .get(log_schema().message_key())
+                .map(|message| message.to_string_lossy())
+                .unwrap_or_default(),
+        };
+        if !message.is_empty() {
+            line.push(' ');
+            line.push_str(&message);
+        }
+
+        let bytes = match self.framing {
+            Some(Framing::OctetCounting) => format!("{} {}", line.len(), line),
+            Some(Framing::NonTransparent) => {
+                line.push('\n');
+                line
+            }
+            None => line,
+        };
+        Some(bytes.into())
+    }
+
+    /// The structured data elements, one for each of the object fields, named after them.
+    fn structured_data(&self, log: &LogEvent) -> String {
+        let mut data = String::new();
+        for field in &self.structured_data {
+            let params = match log.get_flat(field) {
+                Some(Value::Object(params)) => params,
+                _ => continue,
+            };
+            let id = sd_name(field);
+            if id.is_empty() {
+                continue;
+            }
+            data.push('[');
+            data.push_str(&id);
+            for (name, value) in params {
+                let name = sd_name(name);
+                if !name.is_empty() {
+                    let value = escape_param_value(&value.to_string_lossy());
+                    let _ = write!(data, " {}=\"{}\"", name, value);
+                }
+            }
+            data.push(']');
+        }
+        if data.is_empty() {
+            NIL_VALUE.to_owned()
+        } else {
+            data
+        }
+    }
+}
+
+fn render(template: &Template, field: &'static str, event: &Event) -> Option<String> {
+    template
+        .render_string(event)
+        .map_err(|error| {
+            emit!(&TemplateRenderingError {
+                error,
+                field: Some(field),
+                drop_event: false,
+            })
+        })
+        .ok()
+}
+
+/// Renders the facility or severity, falling back to the default one.
+fn render_priority(
+    template: &Template,
+    field: &'static str,
+    code: fn(&str) -> Option<u8>,
+    default: u8,
+    event: &Event,
+) -> u8 {
+    match render(template, field, event) {
+        Some(value) => code(&value).unwrap_or_else(|| {
+            emit!(&SyslogInvalidPriorityValue {
+                field,
+                value: &value
+            });
+            default
+        }),
+        None => default,
+    }
+}
+
+/// Keeps the printable ASCII characters of the header field, up to its maximum length, as
+/// required by RFC 5424.
+fn header_field(value: &str, max_length: usize) -> String {
+    let field = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_length)
+        .collect::<String>();
+    if field.is_empty() {
+        NIL_VALUE.to_owned()
+    } else {
+        field
+    }
+}
+
+/// Keeps the characters allowed in the structured data IDs and parameter names.
+fn sd_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+fn escape_param_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, net::UdpSocket};
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::test_util::{next_addr, trace_init, CountReceiver};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SyslogSinkConfig>();
+    }
+
+    fn encoder(format: Format, framing: Option<Framing>) -> SyslogEncoder {
+        SyslogEncoder {
+            format,
+            framing,
+            facility: Template::try_from("{{ facility }}").unwrap(),
+            severity: Template::try_from("{{ severity }}").unwrap(),
+            app_name: Template::try_from("{{ appname }}").unwrap(),
+            proc_id: Some(Template::try_from("{{ procid }}").unwrap()),
+            msg_id: Some(Template::try_from("{{ msgid }}").unwrap()),
+            structured_data: vec!["exampleSDID@32473".into()],
+            hostname: "vector-host".into(),
+            encoding: Encoding::Text.into(),
+        }
+    }
+
+    fn event() -> Event {
+        let mut event = Event::from("hello world");
+        let log = event.as_mut_log();
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.ymd(2020, 3, 5).and_hms_milli(20, 45, 38, 119),
+        );
+        log.insert(log_schema().host_key(), "dynamicwireless.example.com");
+        log.insert("facility", "local0");
+        log.insert("severity", "notice");
+        log.insert("appname", "non");
+        log.insert("procid", 2426);
+        log.insert("msgid", "ID931");
+        let params = vec![
+            ("iut".to_owned(), Value::from("3")),
+            ("eventSource".to_owned(), Value::from("App\"lic]ation")),
+        ]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+        log.insert_flat("exampleSDID@32473", params);
+        event
+    }
+
+    fn encode(encoder: &SyslogEncoder, event: Event) -> String {
+        String::from_utf8(encoder.encode(event).unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn encodes_rfc5424() {
+        assert_eq!(
+            encode(&encoder(Format::Rfc5424, None), event()),
+            "<133>1 2020-03-05T20:45:38.119Z dynamicwireless.example.com non 2426 ID931 \
+             [exampleSDID@32473 eventSource=\"App\\\"lic\\]ation\" iut=\"3\"] hello world"
+        );
+    }
+
+    #[test]
+    fn encodes_rfc3164() {
+        assert_eq!(
+            encode(&encoder(Format::Rfc3164, None), event()),
+            "<133>Mar  5 20:45:38 dynamicwireless.example.com non[2426]: hello world"
+        );
+    }
+
+    #[test]
+    fn encodes_missing_fields() {
+        let mut encoder = encoder(Format::Rfc5424, None);
+        encoder.proc_id = None;
+        let mut event = Event::from("");
+        event.as_mut_log().insert("facility", "not a facility");
+        event.as_mut_log().insert("severity", 3);
+
+        let line = encode(&encoder, event);
+        assert!(line.starts_with("<11>1 "), "{}", line);
+        assert!(line.ends_with(" vector-host vector - - -"), "{}", line);
+    }
+
+    #[test]
+    fn frames_messages() {
+        let line = encode(&encoder(Format::Rfc3164, None), event());
+        assert_eq!(
+            encode(
+                &encoder(Format::Rfc3164, Some(Framing::OctetCounting)),
+                event()
+            ),
+            format!("71 {}", line)
+        );
+        assert_eq!(
+            encode(
+                &encoder(Format::Rfc3164, Some(Framing::NonTransparent)),
+                event()
+            ),
+            format!("{}\n", line)
+        );
+    }
+
+    #[test]
+    fn parses_priorities() {
+        assert_eq!(facility_code("local7"), Some(23));
+        assert_eq!(facility_code("4"), Some(4));
+        assert_eq!(facility_code("24"), None);
+        assert_eq!(severity_code("Informational"), Some(6));
+        assert_eq!(severity_code("err"), Some(3));
+        assert_eq!(severity_code("verbose"), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_static_priorities() {
+        let config = toml::from_str::<SyslogSinkConfig>(
+            r#"address = "127.0.0.1:514"
+            mode = "udp"
+            severity = "verbose"
+            encoding.codec = "text""#,
+        )
+        .unwrap();
+        let error = config.build(SinkContext::new_test()).await.err().unwrap();
+        assert_eq!(error.to_string(), "Invalid severity \"verbose\"");
+    }
+
+    #[tokio::test]
+    async fn sends_over_tcp() {
+        trace_init();
+
+        let addr = next_addr();
+        let config = toml::from_str::<SyslogSinkConfig>(&format!(
+            r#"address = "{}"
+            mode = "tcp"
+            framing = "non_transparent"
+            format = "rfc3164"
+            encoding.codec = "text""#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+        let mut receiver = CountReceiver::receive_lines(addr);
+
+        sink.run_events(vec![event()]).await.unwrap();
+        receiver.connected().await;
+
+        let output = receiver.await;
+        assert_eq!(
+            output,
+            vec!["<133>Mar  5 20:45:38 dynamicwireless.example.com non[2426]: hello world"]
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_over_udp() {
+        trace_init();
+
+        let addr = next_addr();
+        let receiver = UdpSocket::bind(addr).unwrap();
+        let config = toml::from_str::<SyslogSinkConfig>(&format!(
+            r#"address = "{}"
+            mode = "udp"
+            app_name = "{{{{ appname }}}}"
+            encoding.codec = "text""#,
+            addr
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        sink.run_events(vec![event()]).await.unwrap();
+
+        let mut buf = [0; 256];
+        let (size, _) = receiver.recv_from(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..size]).unwrap(),
+            "<14>1 2020-03-05T20:45:38.119Z dynamicwireless.example.com non - - - hello world"
+        );
+    }
+}
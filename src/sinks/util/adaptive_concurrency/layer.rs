@@ -1,6 +1,10 @@
+use std::marker::PhantomData;
+
 use tower::Layer;
 
-use super::{AdaptiveConcurrencyLimit, AdaptiveConcurrencySettings};
+use super::{
+    AdaptiveConcurrencyLimit, AdaptiveConcurrencySettings, PartitionedAdaptiveConcurrencyLimit,
+};
 use crate::sinks::util::retries::RetryLogic;
 
 /// Enforces a limit on the concurrent number of requests the underlying
@@ -34,3 +38,48 @@ impl<S, L: RetryLogic> Layer<S> for AdaptiveConcurrencyLimitLayer<L> {
         AdaptiveConcurrencyLimit::new(service, self.logic.clone(), self.concurrency, self.options)
     }
 }
+
+/// Enforces a limit on the concurrent number of requests of each partition
+/// the underlying service can handle.
+#[derive(Debug)]
+pub struct PartitionedAdaptiveConcurrencyLimitLayer<L, K> {
+    concurrency: Option<usize>,
+    options: AdaptiveConcurrencySettings,
+    logic: L,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<L, K> PartitionedAdaptiveConcurrencyLimitLayer<L, K> {
+    /// Create a new partitioned concurrency limit layer.
+    pub const fn new(
+        concurrency: Option<usize>,
+        options: AdaptiveConcurrencySettings,
+        logic: L,
+    ) -> Self {
+        PartitionedAdaptiveConcurrencyLimitLayer {
+            concurrency,
+            options,
+            logic,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<L: Clone, K> Clone for PartitionedAdaptiveConcurrencyLimitLayer<L, K> {
+    fn clone(&self) -> Self {
+        Self::new(self.concurrency, self.options, self.logic.clone())
+    }
+}
+
+impl<S, L: RetryLogic, K> Layer<S> for PartitionedAdaptiveConcurrencyLimitLayer<L, K> {
+    type Service = PartitionedAdaptiveConcurrencyLimit<S, L, K>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        PartitionedAdaptiveConcurrencyLimit::new(
+            service,
+            self.logic.clone(),
+            self.concurrency,
+            self.options,
+        )
+    }
+}
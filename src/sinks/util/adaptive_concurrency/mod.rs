@@ -5,13 +5,15 @@ use serde::{Deserialize, Serialize};
 mod controller;
 mod future;
 mod layer;
+mod partitioned;
 mod semaphore;
 mod service;
 mod tests;
 
 pub(super) const MAX_CONCURRENCY: usize = 200;
 
-pub(crate) use layer::AdaptiveConcurrencyLimitLayer;
+pub(crate) use layer::{AdaptiveConcurrencyLimitLayer, PartitionedAdaptiveConcurrencyLimitLayer};
+pub(crate) use partitioned::PartitionedAdaptiveConcurrencyLimit;
 pub(crate) use service::AdaptiveConcurrencyLimit;
 
 pub(self) fn instant_now() -> std::time::Instant {
@@ -35,6 +37,10 @@ pub struct AdaptiveConcurrencySettings {
     // This value avoided changing concurrency too aggressively when
     // there is fluctuation in the RTT measurements.
     pub(super) rtt_deviation_scale: f64,
+
+    // Tracks the limits of the partitions of the requests separately, for
+    // the services they are partitioned by.
+    pub(super) per_partition: bool,
 }
 
 impl AdaptiveConcurrencySettings {
@@ -43,6 +49,7 @@ impl AdaptiveConcurrencySettings {
             decrease_ratio: 0.9,
             ewma_alpha: 0.4,
             rtt_deviation_scale: 2.5,
+            per_partition: false,
         }
     }
 }
@@ -0,0 +1,362 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    hash::Hash,
+    mem,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use futures::{
+    future::{BoxFuture, Either},
+    ready,
+};
+use tokio::sync::Semaphore;
+use tower::{Service, ServiceExt};
+
+use super::{
+    controller::Controller, future::ResponseFuture, service::State, AdaptiveConcurrencyLimit,
+    AdaptiveConcurrencySettings, MAX_CONCURRENCY,
+};
+use crate::sinks::util::{retries::RetryLogic, Partition};
+
+/// The number of partitions above which the controllers of those without
+/// pending requests are dropped, their limits being learned again.
+const MAX_PARTITIONS: usize = 1024;
+
+/// Enforces a limit on the concurrent number of requests of each partition
+/// the underlying service can handle, when `per_partition` is set. The
+/// limits of the partitions expand and contract separately, so that a slow
+/// partition doesn't throttle the requests of the others.
+///
+/// Otherwise, the requests all share the same limit, exactly like with
+/// `AdaptiveConcurrencyLimit`.
+pub enum PartitionedAdaptiveConcurrencyLimit<S, L, K> {
+    Shared(AdaptiveConcurrencyLimit<S, L>),
+    PerPartition(PerPartitionLimit<S, L, K>),
+}
+
+impl<S, L, K> PartitionedAdaptiveConcurrencyLimit<S, L, K> {
+    /// Create a new partitioned automated concurrency limiter.
+    pub(crate) fn new(
+        inner: S,
+        logic: L,
+        concurrency: Option<usize>,
+        settings: AdaptiveConcurrencySettings,
+    ) -> Self {
+        if settings.per_partition {
+            Self::PerPartition(PerPartitionLimit::new(inner, logic, concurrency, settings))
+        } else {
+            Self::Shared(AdaptiveConcurrencyLimit::new(
+                inner,
+                logic,
+                concurrency,
+                settings,
+            ))
+        }
+    }
+}
+
+/// As the partition of a request is only known once it is called, the
+/// service is ready as long as fewer than `MAX_CONCURRENCY` requests are
+/// pending, and the requests wait for a permit of their partition in their
+/// future. The back-pressure on the callers is then only applied by that
+/// maximum, and not by the limits of the partitions.
+pub struct PerPartitionLimit<S, L, K> {
+    inner: S,
+    logic: L,
+    concurrency: Option<usize>,
+    settings: AdaptiveConcurrencySettings,
+    pub(super) controllers: Arc<Mutex<HashMap<K, Arc<Controller<L>>>>>,
+    pending: Arc<Semaphore>,
+    state: State,
+}
+
+impl<S, L, K> PerPartitionLimit<S, L, K> {
+    fn new(
+        inner: S,
+        logic: L,
+        concurrency: Option<usize>,
+        settings: AdaptiveConcurrencySettings,
+    ) -> Self {
+        PerPartitionLimit {
+            inner,
+            logic,
+            concurrency,
+            settings,
+            controllers: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Semaphore::new(MAX_CONCURRENCY)),
+            state: State::Empty,
+        }
+    }
+}
+
+impl<S, L, K> PerPartitionLimit<S, L, K>
+where
+    L: Clone,
+    K: Hash + Eq,
+{
+    fn controller(&self, key: K) -> Arc<Controller<L>> {
+        let mut controllers = self
+            .controllers
+            .lock()
+            .expect("Controllers mutex is poisoned");
+        if controllers.len() >= MAX_PARTITIONS && !controllers.contains_key(&key) {
+            controllers.retain(|_, controller| Arc::strong_count(controller) > 1);
+        }
+        let controller = controllers.entry(key).or_insert_with(|| {
+            Arc::new(Controller::new(
+                self.concurrency,
+                self.settings,
+                self.logic.clone(),
+            ))
+        });
+        Arc::clone(controller)
+    }
+}
+
+impl<S, L, K, Request> Service<Request> for PartitionedAdaptiveConcurrencyLimit<S, L, K>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<crate::Error>,
+    L: RetryLogic<Response = S::Response>,
+    K: Hash + Eq,
+    Request: Partition<K> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = Either<
+        ResponseFuture<S::Future, L>,
+        BoxFuture<'static, Result<Self::Response, Self::Error>>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            Self::Shared(limit) => Service::<Request>::poll_ready(limit, cx),
+            Self::PerPartition(limit) => limit.poll_ready(cx),
+        }
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        match self {
+            Self::Shared(limit) => Either::Left(limit.call(request)),
+            Self::PerPartition(limit) => Either::Right(limit.call(request)),
+        }
+    }
+}
+
+impl<S, L, K> PerPartitionLimit<S, L, K>
+where
+    L: RetryLogic,
+    K: Hash + Eq,
+{
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), crate::Error>> {
+        loop {
+            self.state = match self.state {
+                State::Ready(_) => return Poll::Ready(Ok(())),
+                State::Waiting(ref mut fut) => {
+                    tokio::pin!(fut);
+                    let permit = ready!(fut.poll(cx));
+                    State::Ready(permit)
+                }
+                State::Empty => {
+                    let pending = Arc::clone(&self.pending);
+                    State::Waiting(Box::pin(async move {
+                        pending
+                            .acquire_owned()
+                            .await
+                            .expect("Pending requests semaphore is closed")
+                    }))
+                }
+            };
+        }
+    }
+
+    fn call<Request>(
+        &mut self,
+        request: Request,
+    ) -> BoxFuture<'static, Result<S::Response, crate::Error>>
+    where
+        S: Service<Request> + Clone + Send + 'static,
+        S::Future: Send,
+        S::Error: Into<crate::Error>,
+        L: RetryLogic<Response = S::Response>,
+        Request: Partition<K> + Send + 'static,
+    {
+        // Make sure a pending permit has been acquired
+        let pending = match mem::replace(&mut self.state, State::Empty) {
+            State::Ready(permit) => permit,
+            _ => panic!("Maximum requests pending; poll_ready must be called first"),
+        };
+
+        let controller = self.controller(request.partition());
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let _pending = pending;
+            let permit = controller.acquire().await;
+            controller.start_request();
+            ResponseFuture::new(inner.oneshot(request), permit, controller).await
+        })
+    }
+}
+
+impl<S, L, K> Clone for PartitionedAdaptiveConcurrencyLimit<S, L, K>
+where
+    S: Clone,
+    L: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Shared(limit) => Self::Shared(limit.clone()),
+            Self::PerPartition(limit) => Self::PerPartition(limit.clone()),
+        }
+    }
+}
+
+impl<S, L, K> Clone for PerPartitionLimit<S, L, K>
+where
+    S: Clone,
+    L: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            logic: self.logic.clone(),
+            concurrency: self.concurrency,
+            settings: self.settings,
+            controllers: Arc::clone(&self.controllers),
+            pending: Arc::clone(&self.pending),
+            state: State::Empty,
+        }
+    }
+}
+
+impl<S, L, K> fmt::Debug for PartitionedAdaptiveConcurrencyLimit<S, L, K>
+where
+    S: fmt::Debug,
+    L: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shared(limit) => f.debug_tuple("Shared").field(limit).finish(),
+            Self::PerPartition(limit) => f.debug_tuple("PerPartition").field(limit).finish(),
+        }
+    }
+}
+
+impl<S, L, K> fmt::Debug for PerPartitionLimit<S, L, K>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PerPartitionLimit")
+            .field("inner", &self.inner)
+            .field("concurrency", &self.concurrency)
+            .field("settings", &self.settings)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_test::{assert_pending, assert_ready, assert_ready_ok, task};
+    use tower_test::mock::{self, Handle, Spawn};
+
+    use super::{super::PartitionedAdaptiveConcurrencyLimitLayer, *};
+
+    #[derive(Clone, Copy, Debug)]
+    struct TestRetryLogic;
+    impl RetryLogic for TestRetryLogic {
+        type Error = std::io::Error;
+        type Response = String;
+        fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+            true
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestRequest(&'static str, usize);
+
+    impl Partition<&'static str> for TestRequest {
+        fn partition(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    type TestInner = PartitionedAdaptiveConcurrencyLimit<
+        mock::Mock<TestRequest, String>,
+        TestRetryLogic,
+        &'static str,
+    >;
+
+    fn start(per_partition: bool) -> (Spawn<TestInner>, Handle<TestRequest, String>) {
+        mock::spawn_layer(PartitionedAdaptiveConcurrencyLimitLayer::new(
+            None,
+            AdaptiveConcurrencySettings {
+                per_partition,
+                ..Default::default()
+            },
+            TestRetryLogic,
+        ))
+    }
+
+    fn send(
+        service: &mut Spawn<TestInner>,
+        request: TestRequest,
+    ) -> task::Spawn<<TestInner as Service<TestRequest>>::Future> {
+        assert_ready_ok!(service.poll_ready());
+        let mut future = task::spawn(service.call(request));
+        assert_pending!(future.poll());
+        future
+    }
+
+    #[tokio::test]
+    async fn limits_partitions_separately() {
+        let (mut service, mut handle) = start(true);
+
+        // The concurrency of each partition starts at 1
+        let mut first = send(&mut service, TestRequest("a", 1));
+        let (request, response) = handle.next_request().await.unwrap();
+        assert_eq!(request, TestRequest("a", 1));
+
+        // The requests of the saturated partition wait for a permit ...
+        let mut second = send(&mut service, TestRequest("a", 2));
+        // ... while those of the other partitions are sent
+        let _third = send(&mut service, TestRequest("b", 3));
+        let (request, _) = handle.next_request().await.unwrap();
+        assert_eq!(request, TestRequest("b", 3));
+        match service.get_ref() {
+            PartitionedAdaptiveConcurrencyLimit::PerPartition(limit) => {
+                assert_eq!(limit.controllers.lock().unwrap().len(), 2)
+            }
+            PartitionedAdaptiveConcurrencyLimit::Shared(_) => unreachable!(),
+        }
+
+        response.send_response("done".into());
+        assert_eq!(assert_ready!(first.poll()).unwrap(), "done");
+        assert_pending!(second.poll());
+        let (request, _) = handle.next_request().await.unwrap();
+        assert_eq!(request, TestRequest("a", 2));
+    }
+
+    #[tokio::test]
+    async fn shares_limit_without_per_partition() {
+        let (mut service, mut handle) = start(false);
+
+        assert_ready_ok!(service.poll_ready());
+        let mut first = task::spawn(service.call(TestRequest("a", 1)));
+        assert_pending!(first.poll());
+        let (request, response) = handle.next_request().await.unwrap();
+        assert_eq!(request, TestRequest("a", 1));
+
+        // The service isn't ready until a permit of the shared limit is
+        // released, whatever the partition of the next request
+        assert_pending!(service.poll_ready());
+        response.send_response("done".into());
+        assert_eq!(assert_ready!(first.poll()).unwrap(), "done");
+        assert_ready_ok!(service.poll_ready());
+    }
+}
@@ -23,7 +23,7 @@ pub struct AdaptiveConcurrencyLimit<S, L> {
     state: State,
 }
 
-enum State {
+pub(super) enum State {
     Waiting(BoxFuture<'static, OwnedSemaphorePermit>),
     Ready(OwnedSemaphorePermit),
     Empty,
@@ -37,6 +37,11 @@ impl<S, L> AdaptiveConcurrencyLimit<S, L> {
         concurrency: Option<usize>,
         options: AdaptiveConcurrencySettings,
     ) -> Self {
+        if options.per_partition {
+            warn!(
+                message = "The `adaptive_concurrency.per_partition` option is ignored, as this sink doesn't partition its requests."
+            );
+        }
         AdaptiveConcurrencyLimit {
             inner,
             controller: Arc::new(Controller::new(concurrency, options, logic)),
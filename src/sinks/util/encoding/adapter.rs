@@ -6,6 +6,7 @@ use crate::{
     event::Event,
 };
 use core::fmt::Debug;
+use indexmap::IndexMap;
 use lookup::lookup_v2::OwnedSegment;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -53,6 +54,8 @@ where
             encoding: EncodingWithTransformationConfig {
                 encoding,
                 filter: None,
+                rename_fields: None,
+                field_order: None,
                 timestamp_format: None,
             },
         })
@@ -99,11 +102,13 @@ where
                             }
                             _ => None,
                         });
+                let rename_fields = config.encoding.rename_fields.clone();
                 let timestamp_format = config.encoding.timestamp_format;
 
                 Transformer {
                     only_fields,
                     except_fields,
+                    rename_fields,
                     timestamp_format,
                 }
             }
@@ -114,6 +119,7 @@ where
                     .as_ref()
                     .map(|fields| fields.iter().map(|field| field.to_vec()).collect()),
                 except_fields: config.encoding.except_fields().clone(),
+                rename_fields: config.encoding.rename_fields().clone(),
                 timestamp_format: *config.encoding.timestamp_format(),
             },
         }
@@ -125,6 +131,10 @@ where
             Self::Encoding(config) => {
                 let framer = config.framing.clone().map(FramingConfig::build);
                 let serializer = config.encoding.encoding.build();
+                let serializer = match config.encoding.field_order {
+                    Some(field_order) => serializer.with_field_order(field_order),
+                    None => serializer,
+                };
 
                 (framer, serializer)
             }
@@ -160,6 +170,8 @@ pub struct EncodingWithTransformationConfig {
     encoding: SerializerConfig,
     #[serde(flatten)]
     filter: Option<OnlyOrExceptFieldsConfig>,
+    rename_fields: Option<IndexMap<String, String>>,
+    field_order: Option<Vec<String>>,
     timestamp_format: Option<TimestampFormat>,
 }
 
@@ -185,6 +197,7 @@ pub struct ExceptFieldsConfig {
 pub struct Transformer {
     only_fields: Option<Vec<Vec<OwnedSegment>>>,
     except_fields: Option<Vec<String>>,
+    rename_fields: Option<IndexMap<String, String>>,
     timestamp_format: Option<TimestampFormat>,
 }
 
@@ -214,6 +227,10 @@ impl EncodingConfiguration for Transformer {
         &self.except_fields
     }
 
+    fn rename_fields(&self) -> &Option<IndexMap<String, String>> {
+        &self.rename_fields
+    }
+
     fn timestamp_format(&self) -> &Option<TimestampFormat> {
         &self.timestamp_format
     }
@@ -248,6 +265,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_with_renamed_and_ordered_fields() {
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+        #[serde(rename_all = "snake_case")]
+        enum LegacyEncoding {
+            Foo,
+        }
+
+        #[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+        struct Migrator;
+
+        impl EncodingConfigMigrator for Migrator {
+            type Codec = LegacyEncoding;
+
+            fn migrate(_: &Self::Codec) -> (Option<FramingConfig>, SerializerConfig) {
+                panic!()
+            }
+        }
+
+        let string = r#"
+            {
+                "encoding": {
+                    "codec": "json",
+                    "except_fields": ["ignore_me"],
+                    "rename_fields": { "msg": "message", "lvl": "level" },
+                    "field_order": ["level", "message"]
+                }
+            }
+        "#;
+
+        let config = serde_json::from_str::<
+            EncodingConfigAdapter<crate::sinks::util::EncodingConfig<LegacyEncoding>, Migrator>,
+        >(string)
+        .unwrap();
+
+        let mut event = Event::from(vector_common::btreemap! {
+            "msg" => crate::event::Value::from("foo"),
+            "lvl" => crate::event::Value::from("info"),
+            "host" => crate::event::Value::from("bar"),
+            "ignore_me" => crate::event::Value::from("baz"),
+        });
+        config.transformer().transform(&mut event);
+
+        let (_, mut serializer) = config.encoding();
+        let mut bytes = bytes::BytesMut::new();
+        tokio_util::codec::Encoder::encode(&mut serializer, event, &mut bytes).unwrap();
+
+        assert_eq!(
+            bytes.freeze(),
+            r#"{"level":"info","message":"foo","host":"bar"}"#
+        );
+    }
+
     #[test]
     fn deserialize_new_config() {
         #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
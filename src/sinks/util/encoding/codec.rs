@@ -1,5 +1,7 @@
 use std::io;
 
+#[cfg(feature = "codecs")]
+use bytes::BytesMut;
 use serde::{Deserialize, Serialize};
 use vector_core::config::log_schema;
 use vector_core::event::{Event, LogEvent, TraceEvent};
@@ -8,6 +10,8 @@ use super::Encoder;
 
 static DEFAULT_TEXT_ENCODER: StandardTextEncoding = StandardTextEncoding;
 static DEFAULT_JSON_ENCODER: StandardJsonEncoding = StandardJsonEncoding;
+#[cfg(feature = "codecs")]
+static DEFAULT_MSGPACK_ENCODER: StandardMsgpackEncoding = StandardMsgpackEncoding;
 
 /// A standardized set of encodings with common sense behavior.
 ///
@@ -24,6 +28,8 @@ pub enum StandardEncodings {
     Text,
     Json,
     Ndjson,
+    #[cfg(feature = "codecs")]
+    Msgpack,
 }
 
 impl StandardEncodings {
@@ -32,6 +38,18 @@ impl StandardEncodings {
             StandardEncodings::Text => "text/plain",
             StandardEncodings::Json => "application/json",
             StandardEncodings::Ndjson => "application/x-ndjson",
+            #[cfg(feature = "codecs")]
+            StandardEncodings::Msgpack => "application/msgpack",
+        }
+    }
+
+    /// Whether the encoded events can hold bytes which aren't valid UTF-8, in which case the
+    /// encoding can't be used by the sinks sending text.
+    pub const fn is_binary(&self) -> bool {
+        match self {
+            #[cfg(feature = "codecs")]
+            StandardEncodings::Msgpack => true,
+            _ => false,
         }
     }
 }
@@ -112,6 +130,8 @@ impl Encoder<Event> for StandardEncodings {
             StandardEncodings::Text => DEFAULT_TEXT_ENCODER.encode_input(input, writer),
             StandardEncodings::Json => DEFAULT_JSON_ENCODER.encode_input(input, writer),
             StandardEncodings::Ndjson => DEFAULT_JSON_ENCODER.encode_input(input, writer),
+            #[cfg(feature = "codecs")]
+            StandardEncodings::Msgpack => DEFAULT_MSGPACK_ENCODER.encode_input(input, writer),
         }?;
         written += n;
 
@@ -135,6 +155,8 @@ impl Encoder<Vec<Event>> for StandardEncodings {
                 StandardEncodings::Text => DEFAULT_TEXT_ENCODER.encode_input(event, writer),
                 StandardEncodings::Json => DEFAULT_JSON_ENCODER.encode_input(event, writer),
                 StandardEncodings::Ndjson => DEFAULT_JSON_ENCODER.encode_input(event, writer),
+                #[cfg(feature = "codecs")]
+                StandardEncodings::Msgpack => DEFAULT_MSGPACK_ENCODER.encode_input(event, writer),
             }?;
             written += n;
 
@@ -189,6 +211,29 @@ impl Encoder<TraceEvent> for StandardJsonEncoding {
     }
 }
 
+/// Standard implementation for encoding events as MessagePack.
+///
+/// Each event is written as a MessagePack map, the batches being the concatenation of their maps.
+/// Uses the [`MsgpackSerializer`](crate::codecs::MsgpackSerializer) of the `msgpack` codec under the
+/// hood, so that the events are decoded back as they were by the `msgpack` decoding codec.
+#[cfg(feature = "codecs")]
+#[derive(PartialEq, Debug, Default)]
+pub struct StandardMsgpackEncoding;
+
+#[cfg(feature = "codecs")]
+impl Encoder<Event> for StandardMsgpackEncoding {
+    fn encode_input(&self, event: Event, writer: &mut dyn io::Write) -> io::Result<usize> {
+        let mut buffer = BytesMut::new();
+        tokio_util::codec::Encoder::encode(
+            &mut crate::codecs::MsgpackSerializer::new(),
+            event,
+            &mut buffer,
+        )
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        writer.write_all(&buffer).map(|()| buffer.len())
+    }
+}
+
 /// Standard implementation for encoding events as text.
 ///
 /// If given a log event, the value used in the field matching the global lob schema's "message" key
@@ -559,4 +604,27 @@ mod tests {
         let expected = format!("{}\n{}\n", expected1, expected2);
         assert_eq!(expected, encoded);
     }
+
+    #[cfg(feature = "codecs")]
+    #[test]
+    fn test_standard_msgpack_log_multiple() {
+        use bytes::Bytes;
+
+        use crate::codecs::decoding::format::{Deserializer, MsgpackDeserializer};
+
+        let encoding = StandardEncodings::Msgpack;
+
+        let event1 = Event::from("log event1".to_string());
+        let event2 = Event::from("log event2".to_string());
+
+        let result = encode_events(vec![event1.clone(), event2.clone()], encoding)
+            .expect("should not have failed");
+
+        let events = MsgpackDeserializer::new()
+            .parse(Bytes::from(result))
+            .expect("result should be valid MessagePack");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].as_log().as_map(), event1.as_log().as_map());
+        assert_eq!(events[1].as_log().as_map(), event2.as_log().as_map());
+    }
 }
@@ -3,6 +3,7 @@ use std::{
     marker::PhantomData,
 };
 
+use indexmap::IndexMap;
 use lookup::lookup_v2::{parse_path, OwnedSegment};
 use serde::{
     de::{self, DeserializeOwned, IntoDeserializer, MapAccess, Visitor},
@@ -32,6 +33,8 @@ pub struct EncodingConfig<E> {
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) except_fields: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
+    pub(crate) rename_fields: Option<IndexMap<String, String>>,
+    #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) timestamp_format: Option<TimestampFormat>,
 }
 
@@ -55,6 +58,10 @@ impl<E> EncodingConfiguration for EncodingConfig<E> {
         &self.except_fields
     }
 
+    fn rename_fields(&self) -> &Option<IndexMap<String, String>> {
+        &self.rename_fields
+    }
+
     fn timestamp_format(&self) -> &Option<TimestampFormat> {
         &self.timestamp_format
     }
@@ -70,6 +77,7 @@ where
             schema: encoding.schema,
             only_fields: encoding.only_fields,
             except_fields: encoding.except_fields,
+            rename_fields: encoding.rename_fields,
             timestamp_format: encoding.timestamp_format,
         }
     }
@@ -86,6 +94,7 @@ impl<E> EncodingConfig<E> {
             schema: self.schema,
             only_fields: self.only_fields,
             except_fields: self.except_fields,
+            rename_fields: self.rename_fields,
             timestamp_format: self.timestamp_format,
         }
     }
@@ -98,6 +107,7 @@ impl<E> From<E> for EncodingConfig<E> {
             schema: Default::default(),
             only_fields: Default::default(),
             except_fields: Default::default(),
+            rename_fields: Default::default(),
             timestamp_format: Default::default(),
         }
     }
@@ -139,6 +149,7 @@ where
                     schema: Default::default(),
                     only_fields: Default::default(),
                     except_fields: Default::default(),
+                    rename_fields: Default::default(),
                     timestamp_format: Default::default(),
                 })
             }
@@ -168,6 +179,7 @@ where
                     .collect()
             }),
             except_fields: inner.except_fields,
+            rename_fields: inner.rename_fields,
             timestamp_format: inner.timestamp_format,
         };
 
@@ -186,5 +198,7 @@ struct Inner<E> {
     #[serde(default)]
     except_fields: Option<Vec<String>>,
     #[serde(default)]
+    rename_fields: Option<IndexMap<String, String>>,
+    #[serde(default)]
     timestamp_format: Option<TimestampFormat>,
 }
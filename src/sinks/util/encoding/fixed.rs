@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 
+use indexmap::IndexMap;
 use lookup::lookup_v2::OwnedSegment;
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +32,9 @@ pub struct EncodingConfigFixed<E: Default + PartialEq> {
     /// Remove the following fields of the message. (Items mutually exclusive with `only_fields`)
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) except_fields: Option<Vec<String>>,
+    /// Rename the following fields of the message, after `only_fields` and `except_fields` are applied.
+    #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
+    pub(crate) rename_fields: Option<IndexMap<String, String>>,
     /// Format for outgoing timestamps.
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) timestamp_format: Option<TimestampFormat>,
@@ -56,6 +60,10 @@ impl<E: Default + PartialEq> EncodingConfiguration for EncodingConfigFixed<E> {
         &self.except_fields
     }
 
+    fn rename_fields(&self) -> &Option<IndexMap<String, String>> {
+        &self.rename_fields
+    }
+
     fn timestamp_format(&self) -> &Option<TimestampFormat> {
         &self.timestamp_format
     }
@@ -71,6 +79,7 @@ where
             schema: Default::default(),
             only_fields: Default::default(),
             except_fields: Default::default(),
+            rename_fields: Default::default(),
             timestamp_format: Default::default(),
         }
     }
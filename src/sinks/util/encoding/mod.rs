@@ -62,10 +62,13 @@ mod adapter;
 mod codec;
 mod config;
 mod fixed;
+#[cfg(feature = "parquet")]
+mod parquet;
 mod with_default;
 
 use std::{fmt::Debug, io, sync::Arc};
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -73,8 +76,12 @@ use crate::{
     Result,
 };
 
+#[cfg(feature = "parquet")]
+pub use self::parquet::{BatchEncoder, ParquetConfig, PARQUET_CONTENT_TYPE};
 #[cfg(feature = "codecs")]
 pub use adapter::{EncodingConfigAdapter, EncodingConfigMigrator, Transformer};
+#[cfg(feature = "codecs")]
+pub use codec::StandardMsgpackEncoding;
 pub use codec::{as_tracked_write, StandardEncodings, StandardJsonEncoding, StandardTextEncoding};
 pub use config::EncodingConfig;
 pub use fixed::EncodingConfigFixed;
@@ -120,6 +127,7 @@ pub trait EncodingConfiguration {
     // TODO(2410): Using PathComponents here is a hack for #2407, #2410 should fix this fully.
     fn only_fields(&self) -> &Option<Vec<Vec<OwnedSegment>>>;
     fn except_fields(&self) -> &Option<Vec<String>>;
+    fn rename_fields(&self) -> &Option<IndexMap<String, String>>;
     fn timestamp_format(&self) -> &Option<TimestampFormat>;
 
     fn apply_only_fields(&self, log: &mut LogEvent) {
@@ -152,6 +160,18 @@ pub trait EncodingConfiguration {
             }
         }
     }
+    fn apply_rename_fields(&self, log: &mut LogEvent) {
+        if let Some(rename_fields) = &self.rename_fields() {
+            // The fields are all removed before being inserted back, so that they can be swapped.
+            let renamed = rename_fields
+                .iter()
+                .filter_map(|(from, to)| log.remove(from.as_str()).map(|value| (to, value)))
+                .collect::<Vec<_>>();
+            for (to, value) in renamed {
+                log.insert(to.as_str(), value);
+            }
+        }
+    }
     fn apply_timestamp_format(&self, log: &mut LogEvent) {
         if let Some(timestamp_format) = &self.timestamp_format() {
             match timestamp_format {
@@ -195,16 +215,18 @@ pub trait EncodingConfiguration {
 
     /// Apply the EncodingConfig rules to the provided event.
     ///
-    /// Currently, this is idempotent.
+    /// Currently, this is idempotent, unless fields are renamed to the names of other renamed
+    /// fields.
     fn apply_rules<T>(&self, event: &mut T)
     where
         T: MaybeAsLogMut,
     {
         // No rules are currently applied to metrics
         if let Some(log) = event.maybe_as_log_mut() {
-            // Ordering in here should not matter.
+            // The fields are filtered by their original names before being renamed.
             self.apply_except_fields(log);
             self.apply_only_fields(log);
+            self.apply_rename_fields(log);
             self.apply_timestamp_format(log);
         }
     }
@@ -474,4 +496,31 @@ mod tests {
             ),
         }
     }
+
+    const TOML_RENAME_FIELD: &str = indoc! {r#"
+        encoding.codec = "Snoot"
+        encoding.except_fields = ["c"]
+        encoding.rename_fields = { a = "b", b = "a", c = "d", "e.f" = "g" }
+    "#};
+
+    #[test]
+    fn test_rename() {
+        let config: TestConfig = toml::from_str(TOML_RENAME_FIELD).unwrap();
+        config.encoding.validate().unwrap();
+        let mut event = Event::new_empty_log();
+        {
+            let log = event.as_mut_log();
+            log.insert("a", 1);
+            log.insert("b", 2);
+            log.insert("c", 3);
+            log.insert("e.f", 4);
+        }
+        config.encoding.apply_rules(&mut event);
+        let log = event.as_log();
+        assert_eq!(log["a"], Value::from(2));
+        assert_eq!(log["b"], Value::from(1));
+        assert!(!log.contains("d"));
+        assert_eq!(log["g"], Value::from(4));
+        assert!(!log.contains("e.f"));
+    }
 }
@@ -0,0 +1,612 @@
+//! Parquet encoding of the batches of the sinks writing objects, such as `aws_s3`.
+//!
+//! Each batch becomes a Parquet file, whose columns are either declared in the configuration or
+//! inferred from the top level fields of the events of the batch.
+use std::{collections::BTreeMap, io, num::NonZeroUsize, sync::Arc};
+
+use indexmap::IndexMap;
+use parquet::{
+    basic::{Compression as ParquetCodec, ConvertedType, Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    data_type::ByteArray,
+    errors::ParquetError,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, RowGroupWriter, SerializedFileWriter},
+    },
+    schema::types::Type,
+    util::cursor::InMemoryWriteableCursor,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Encoder, EncodingConfig, EncodingConfiguration, StandardEncodings};
+use crate::event::{Event, LogEvent, Value};
+
+/// The content type of Parquet files.
+pub const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetColumnType {
+    Boolean,
+    Integer,
+    Float,
+    String,
+    /// Timestamps with a precision of microseconds.
+    Timestamp,
+    /// Objects and arrays, written as JSON.
+    Json,
+}
+
+impl ParquetColumnType {
+    const fn physical_type(self) -> PhysicalType {
+        match self {
+            Self::Boolean => PhysicalType::BOOLEAN,
+            Self::Integer | Self::Timestamp => PhysicalType::INT64,
+            Self::Float => PhysicalType::DOUBLE,
+            Self::String | Self::Json => PhysicalType::BYTE_ARRAY,
+        }
+    }
+
+    const fn converted_type(self) -> ConvertedType {
+        match self {
+            Self::Boolean | Self::Integer | Self::Float => ConvertedType::NONE,
+            Self::Timestamp => ConvertedType::TIMESTAMP_MICROS,
+            Self::String => ConvertedType::UTF8,
+            Self::Json => ConvertedType::JSON,
+        }
+    }
+
+    const fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Boolean(_) => Some(Self::Boolean),
+            Value::Integer(_) => Some(Self::Integer),
+            Value::Float(_) => Some(Self::Float),
+            Value::Bytes(_) | Value::Regex(_) => Some(Self::String),
+            Value::Timestamp(_) => Some(Self::Timestamp),
+            Value::Object(_) | Value::Array(_) => Some(Self::Json),
+            Value::Null => None,
+        }
+    }
+
+    /// The type of a column holding the values of both types, integers being widened to floats
+    /// and any other conflict falling back to strings.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Integer, Self::Float) | (Self::Float, Self::Integer) => Self::Float,
+            (kind, other) if kind == other => kind,
+            _ => Self::String,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    None,
+    #[derivative(Default)]
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl From<ParquetCompression> for ParquetCodec {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Self::UNCOMPRESSED,
+            ParquetCompression::Snappy => Self::SNAPPY,
+            ParquetCompression::Gzip => Self::GZIP,
+            ParquetCompression::Zstd => Self::ZSTD,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetConfig {
+    /// The columns of the files and their types, by the paths of the fields of the events they
+    /// are read from. They are inferred from the top level fields of the events of each batch
+    /// otherwise.
+    #[serde(default)]
+    pub schema: Option<IndexMap<String, ParquetColumnType>>,
+    /// The maximum number of rows of each row group, the events of a batch being written in a
+    /// single row group otherwise.
+    #[serde(default)]
+    pub row_group_size: Option<NonZeroUsize>,
+    /// The compression of the pages of the columns.
+    #[serde(default)]
+    pub compression: ParquetCompression,
+}
+
+#[derive(Clone, Debug)]
+struct Column {
+    name: String,
+    kind: ParquetColumnType,
+    /// Whether the name is the path of a field, rather than the name of a top level field.
+    path: bool,
+}
+
+impl Column {
+    fn value<'a>(&self, log: &'a LogEvent) -> Option<&'a Value> {
+        if self.path {
+            log.get(self.name.as_str())
+        } else {
+            log.as_map().get(&self.name)
+        }
+    }
+}
+
+/// Writes batches of events as Parquet files, after applying the rules of the encoding.
+#[derive(Clone, Debug)]
+pub struct ParquetEncoder {
+    encoding: EncodingConfig<StandardEncodings>,
+    columns: Option<Vec<Column>>,
+    row_group_size: Option<NonZeroUsize>,
+    properties: Arc<WriterProperties>,
+}
+
+impl ParquetEncoder {
+    pub fn new(config: &ParquetConfig, encoding: EncodingConfig<StandardEncodings>) -> Self {
+        let columns = config.schema.as_ref().map(|schema| {
+            schema
+                .iter()
+                .map(|(name, kind)| Column {
+                    name: name.clone(),
+                    kind: *kind,
+                    path: true,
+                })
+                .collect()
+        });
+        let properties = WriterProperties::builder()
+            .set_compression(config.compression.into())
+            .build();
+
+        Self {
+            encoding,
+            columns,
+            row_group_size: config.row_group_size,
+            properties: Arc::new(properties),
+        }
+    }
+
+    fn write_file(&self, columns: &[Column], logs: &[LogEvent]) -> Result<Vec<u8>, ParquetError> {
+        let cursor = InMemoryWriteableCursor::default();
+        let mut writer = SerializedFileWriter::new(
+            cursor.clone(),
+            Arc::new(message_type(columns)?),
+            Arc::clone(&self.properties),
+        )?;
+
+        let row_group_size = self
+            .row_group_size
+            .map_or(logs.len(), NonZeroUsize::get)
+            .max(1);
+        for rows in logs.chunks(row_group_size) {
+            let mut row_group = writer.next_row_group()?;
+            for column in columns {
+                let mut column_writer = row_group.next_column()?.ok_or_else(|| {
+                    ParquetError::General(format!("No writer for column {:?}.", column.name))
+                })?;
+                write_column(&mut column_writer, column, rows)?;
+                row_group.close_column(column_writer)?;
+            }
+            writer.close_row_group(row_group)?;
+        }
+        writer.close()?;
+
+        Ok(cursor.data())
+    }
+}
+
+impl Encoder<Vec<Event>> for ParquetEncoder {
+    fn encode_input(&self, events: Vec<Event>, writer: &mut dyn io::Write) -> io::Result<usize> {
+        let logs = events
+            .into_iter()
+            .filter_map(|mut event| {
+                self.encoding.apply_rules(&mut event);
+                event.try_into_log()
+            })
+            .collect::<Vec<_>>();
+
+        let file = match &self.columns {
+            Some(columns) => self.write_file(columns, &logs),
+            None => self.write_file(&infer_columns(&logs), &logs),
+        }
+        .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+
+        writer.write_all(&file)?;
+        Ok(file.len())
+    }
+}
+
+/// Encodes the batches of the sinks writing objects with the codec of their encoding, or as
+/// Parquet files.
+#[derive(Clone, Debug)]
+pub enum BatchEncoder {
+    Codec(EncodingConfig<StandardEncodings>),
+    Parquet(ParquetEncoder),
+}
+
+impl BatchEncoder {
+    pub fn new(
+        encoding: &EncodingConfig<StandardEncodings>,
+        parquet: Option<&ParquetConfig>,
+    ) -> Self {
+        match parquet {
+            Some(config) => Self::Parquet(ParquetEncoder::new(config, encoding.clone())),
+            None => Self::Codec(encoding.clone()),
+        }
+    }
+
+    pub const fn is_parquet(&self) -> bool {
+        matches!(self, Self::Parquet(_))
+    }
+
+    pub fn content_type(&self) -> &str {
+        match self {
+            Self::Codec(encoding) => encoding.codec().content_type(),
+            Self::Parquet(_) => PARQUET_CONTENT_TYPE,
+        }
+    }
+}
+
+impl From<StandardEncodings> for BatchEncoder {
+    fn from(codec: StandardEncodings) -> Self {
+        Self::Codec(codec.into())
+    }
+}
+
+impl Encoder<Vec<Event>> for BatchEncoder {
+    fn encode_input(&self, events: Vec<Event>, writer: &mut dyn io::Write) -> io::Result<usize> {
+        match self {
+            Self::Codec(encoding) => encoding.encode_input(events, writer),
+            Self::Parquet(encoder) => encoder.encode_input(events, writer),
+        }
+    }
+}
+
+/// Infers the columns of the top level fields of the events, fields holding only nulls being
+/// written as strings.
+fn infer_columns(logs: &[LogEvent]) -> Vec<Column> {
+    let mut kinds = BTreeMap::<&str, Option<ParquetColumnType>>::new();
+    for log in logs {
+        for (name, value) in log.as_map() {
+            let kind = kinds.entry(name.as_str()).or_default();
+            *kind = match (*kind, ParquetColumnType::of(value)) {
+                (Some(kind), Some(other)) => Some(kind.merge(other)),
+                (kind, other) => kind.or(other),
+            };
+        }
+    }
+
+    kinds
+        .into_iter()
+        .map(|(name, kind)| Column {
+            name: name.to_owned(),
+            kind: kind.unwrap_or(ParquetColumnType::String),
+            path: false,
+        })
+        .collect()
+}
+
+fn message_type(columns: &[Column]) -> Result<Type, ParquetError> {
+    let mut fields = columns
+        .iter()
+        .map(|column| {
+            Type::primitive_type_builder(&column.name, column.kind.physical_type())
+                .with_repetition(Repetition::OPTIONAL)
+                .with_converted_type(column.kind.converted_type())
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Type::group_type_builder("vector")
+        .with_fields(&mut fields)
+        .build()
+}
+
+/// Collects the values of the rows converted to the type of a column, along with the
+/// definition levels telling which of them are null, values that can't be converted being
+/// written as nulls.
+fn collect_values<T>(
+    column: &Column,
+    logs: &[LogEvent],
+    convert: impl Fn(&Value) -> Option<T>,
+) -> (Vec<T>, Vec<i16>) {
+    let mut values = Vec::with_capacity(logs.len());
+    let mut levels = Vec::with_capacity(logs.len());
+    for log in logs {
+        match column.value(log).and_then(&convert) {
+            Some(value) => {
+                values.push(value);
+                levels.push(1);
+            }
+            None => levels.push(0),
+        }
+    }
+    (values, levels)
+}
+
+fn write_column(
+    writer: &mut ColumnWriter,
+    column: &Column,
+    logs: &[LogEvent],
+) -> Result<(), ParquetError> {
+    match (column.kind, writer) {
+        (ParquetColumnType::Boolean, ColumnWriter::BoolColumnWriter(writer)) => {
+            let (values, levels) = collect_values(column, logs, |value| match value {
+                Value::Boolean(value) => Some(*value),
+                _ => None,
+            });
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ParquetColumnType::Integer, ColumnWriter::Int64ColumnWriter(writer)) => {
+            let (values, levels) = collect_values(column, logs, |value| match value {
+                Value::Integer(value) => Some(*value),
+                _ => None,
+            });
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ParquetColumnType::Timestamp, ColumnWriter::Int64ColumnWriter(writer)) => {
+            let (values, levels) = collect_values(column, logs, |value| match value {
+                Value::Timestamp(timestamp) => Some(
+                    timestamp.timestamp() * 1_000_000
+                        + i64::from(timestamp.timestamp_subsec_micros()),
+                ),
+                _ => None,
+            });
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ParquetColumnType::Float, ColumnWriter::DoubleColumnWriter(writer)) => {
+            let (values, levels) = collect_values(column, logs, |value| match value {
+                Value::Float(value) => Some(value.into_inner()),
+                Value::Integer(value) => Some(*value as f64),
+                _ => None,
+            });
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ParquetColumnType::String, ColumnWriter::ByteArrayColumnWriter(writer)) => {
+            let (values, levels) = collect_values(column, logs, |value| match value {
+                Value::Null => None,
+                value => Some(ByteArray::from(value.coerce_to_bytes().to_vec())),
+            });
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        (ParquetColumnType::Json, ColumnWriter::ByteArrayColumnWriter(writer)) => {
+            let (values, levels) = collect_values(column, logs, |value| match value {
+                Value::Null => None,
+                value => serde_json::to_vec(value).ok().map(ByteArray::from),
+            });
+            writer.write_batch(&values, Some(&levels), None)?;
+        }
+        _ => {
+            return Err(ParquetError::General(format!(
+                "Unexpected writer for column {:?}.",
+                column.name
+            )))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use chrono::{TimeZone, Utc};
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::RowAccessor,
+        util::cursor::SliceableCursor,
+    };
+
+    use super::*;
+
+    fn config(toml: &str) -> ParquetConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn encode(config: &ParquetConfig, events: Vec<Event>) -> SerializedFileReader<SliceableCursor> {
+        let encoder = BatchEncoder::new(&StandardEncodings::Json.into(), Some(config));
+        let mut file = Vec::new();
+        let written = encoder.encode_input(events, &mut file).unwrap();
+        assert_eq!(written, file.len());
+        assert_eq!(&file[..4], b"PAR1");
+
+        SerializedFileReader::new(SliceableCursor::new(file)).unwrap()
+    }
+
+    fn log(fields: &[(&str, Value)]) -> Event {
+        let mut log = LogEvent::default();
+        for (name, value) in fields {
+            log.insert(*name, value.clone());
+        }
+        log.into()
+    }
+
+    #[test]
+    fn parses_config() {
+        let parsed = config(
+            r#"
+            row_group_size = 1000
+            compression = "zstd"
+
+            [schema]
+            message = "string"
+            "http.status" = "integer"
+            "#,
+        );
+
+        assert_eq!(parsed.row_group_size, NonZeroUsize::new(1000));
+        assert_eq!(parsed.compression, ParquetCompression::Zstd);
+        let schema = parsed.schema.unwrap();
+        assert_eq!(
+            schema.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("message".to_owned(), ParquetColumnType::String),
+                ("http.status".to_owned(), ParquetColumnType::Integer),
+            ]
+        );
+
+        assert_eq!(config("").compression, ParquetCompression::Snappy);
+        assert!(toml::from_str::<ParquetConfig>("row_group_size = 0").is_err());
+    }
+
+    #[test]
+    fn infers_columns() {
+        let logs = vec![
+            log(&[
+                ("count", Value::Integer(1)),
+                ("message", "one".into()),
+                ("ratio", Value::Integer(1)),
+            ])
+            .into_log(),
+            log(&[
+                ("count", "two".into()),
+                ("nothing", Value::Null),
+                ("ratio", Value::from(0.5)),
+                ("tags", Value::Array(vec!["a".into()])),
+            ])
+            .into_log(),
+        ];
+
+        let columns = infer_columns(&logs)
+            .into_iter()
+            .map(|column| (column.name, column.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("count".to_owned(), ParquetColumnType::String),
+                ("message".to_owned(), ParquetColumnType::String),
+                ("nothing".to_owned(), ParquetColumnType::String),
+                ("ratio".to_owned(), ParquetColumnType::Float),
+                ("tags".to_owned(), ParquetColumnType::Json),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_inferred_columns() {
+        let timestamp = Utc.ymd(2022, 3, 1).and_hms_micro(12, 0, 0, 250);
+        let events = vec![
+            log(&[
+                ("message", "first".into()),
+                ("status", Value::Integer(200)),
+                ("timestamp", Value::Timestamp(timestamp)),
+            ]),
+            log(&[("message", "second".into()), ("ok", Value::Boolean(true))]),
+        ];
+
+        let reader = encode(&ParquetConfig::default(), events);
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 1);
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+        assert_eq!(
+            metadata.row_group(0).column(0).compression(),
+            ParquetCodec::SNAPPY
+        );
+
+        let names = metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| column.name().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["message", "ok", "status", "timestamp"]);
+
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        assert_eq!(rows[0].get_string(0).unwrap(), "first");
+        assert!(rows[0].get_bool(1).is_err());
+        assert_eq!(rows[0].get_long(2).unwrap(), 200);
+        assert_eq!(
+            rows[0].get_timestamp_micros(3).unwrap(),
+            u64::try_from(timestamp.timestamp_nanos() / 1_000).unwrap()
+        );
+        assert_eq!(rows[1].get_string(0).unwrap(), "second");
+        assert!(rows[1].get_bool(1).unwrap());
+    }
+
+    #[test]
+    fn writes_declared_columns() {
+        let config = config(
+            r#"
+            row_group_size = 2
+
+            [schema]
+            "http.status" = "integer"
+            message = "string"
+            "#,
+        );
+        let events = (0..5)
+            .map(|index| {
+                log(&[
+                    ("message", Value::Integer(index)),
+                    ("http.status", Value::Integer(200 + index)),
+                    ("ignored", Value::Boolean(true)),
+                ])
+            })
+            .collect();
+
+        let reader = encode(&config, events);
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+        assert_eq!(metadata.file_metadata().num_rows(), 5);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 2);
+
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[4].get_long(0).unwrap(), 204);
+        assert_eq!(rows[4].get_string(1).unwrap(), "4");
+    }
+
+    #[test]
+    fn writes_mismatched_values_as_nulls() {
+        let config = config(
+            r#"
+            [schema]
+            count = "integer"
+            "#,
+        );
+        let events = vec![
+            log(&[("count", "many".into())]),
+            log(&[("count", Value::Integer(3))]),
+        ];
+
+        let reader = encode(&config, events);
+        let rows = reader.get_row_iter(None).unwrap().collect::<Vec<_>>();
+        assert!(rows[0].get_long(0).is_err());
+        assert_eq!(rows[1].get_long(0).unwrap(), 3);
+    }
+
+    #[test]
+    fn applies_encoding_rules() {
+        let mut encoding: EncodingConfig<StandardEncodings> = StandardEncodings::Json.into();
+        encoding.except_fields = Some(vec!["secret".to_owned()]);
+        let encoder = BatchEncoder::new(&encoding, Some(&ParquetConfig::default()));
+
+        let mut file = Vec::new();
+        encoder
+            .encode_input(
+                vec![log(&[
+                    ("message", "hello".into()),
+                    ("secret", "hunter2".into()),
+                ])],
+                &mut file,
+            )
+            .unwrap();
+
+        let reader = SerializedFileReader::new(SliceableCursor::new(file)).unwrap();
+        assert_eq!(
+            reader
+                .metadata()
+                .file_metadata()
+                .schema_descr()
+                .num_columns(),
+            1
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     marker::PhantomData,
 };
 
+use indexmap::IndexMap;
 use lookup::lookup_v2::OwnedSegment;
 use serde::{
     de::{self, DeserializeOwned, Deserializer, IntoDeserializer, MapAccess, Visitor},
@@ -32,6 +33,9 @@ pub struct EncodingConfigWithDefault<E: Default + PartialEq> {
     /// Remove the following fields of the message. (Items mutually exclusive with `only_fields`)
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) except_fields: Option<Vec<String>>,
+    /// Rename the following fields of the message, after `only_fields` and `except_fields` are applied.
+    #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
+    pub(crate) rename_fields: Option<IndexMap<String, String>>,
     /// Format for outgoing timestamps.
     #[serde(default, skip_serializing_if = "skip_serializing_if_default")]
     pub(crate) timestamp_format: Option<TimestampFormat>,
@@ -57,6 +61,10 @@ impl<E: Default + PartialEq> EncodingConfiguration for EncodingConfigWithDefault
         &self.except_fields
     }
 
+    fn rename_fields(&self) -> &Option<IndexMap<String, String>> {
+        &self.rename_fields
+    }
+
     fn timestamp_format(&self) -> &Option<TimestampFormat> {
         &self.timestamp_format
     }
@@ -72,6 +80,7 @@ where
             schema: Default::default(),
             only_fields: Default::default(),
             except_fields: Default::default(),
+            rename_fields: Default::default(),
             timestamp_format: Default::default(),
         }
     }
@@ -115,6 +124,7 @@ where
                     schema: Default::default(),
                     only_fields: Default::default(),
                     except_fields: Default::default(),
+                    rename_fields: Default::default(),
                     timestamp_format: Default::default(),
                 })
             }
@@ -138,6 +148,7 @@ where
             schema: inner.schema,
             only_fields: inner.only_fields,
             except_fields: inner.except_fields,
+            rename_fields: inner.rename_fields,
             timestamp_format: inner.timestamp_format,
         };
 
@@ -157,5 +168,7 @@ pub struct InnerWithDefault<E: Default> {
     #[serde(default)]
     except_fields: Option<Vec<String>>,
     #[serde(default)]
+    rename_fields: Option<IndexMap<String, String>>,
+    #[serde(default)]
     timestamp_format: Option<TimestampFormat>,
 }
@@ -20,6 +20,7 @@ use tower::Service;
 use vector_core::{buffers::Acker, ByteSizeOf};
 
 use super::{
+    http_compression::{HttpCompressionConfig, HttpCompressor},
     retries::{RetryAction, RetryLogic},
     sink, uri, Batch, EncodedEvent, Partition, TowerBatchedSink, TowerPartitionSink,
     TowerRequestConfig, TowerRequestSettings,
@@ -42,6 +43,11 @@ pub trait HttpSink: Send + Sync + 'static {
 
     fn build_encoder(&self) -> Self::Encoder;
     async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Bytes>>;
+
+    /// The compression of the bodies of the built requests, negotiated with the endpoint.
+    fn request_compression(&self) -> Option<&HttpCompressionConfig> {
+        None
+    }
 }
 
 /// Provides a simple wrapper around internal tower and
@@ -129,7 +135,10 @@ where
             Box::pin(async move { sink.build_request(b).await })
         };
 
-        let svc = HttpBatchService::new(client, request_builder);
+        let mut svc = HttpBatchService::new(client, request_builder);
+        if let Some(compression) = sink.request_compression() {
+            svc = svc.with_compression(HttpCompressor::new(compression));
+        }
         let inner = request_settings.batch_sink(retry_logic, svc, batch, batch_timeout, acker);
         let encoder = sink.build_encoder();
 
@@ -201,7 +210,7 @@ where
 pub struct PartitionHttpSink<T, B, K, RL = HttpRetryLogic>
 where
     B: Batch,
-    B::Output: ByteSizeOf + Clone + Send + 'static,
+    B::Output: ByteSizeOf + Partition<K> + Clone + Send + 'static,
     B::Input: Partition<K>,
     K: Hash + Eq + Clone + Send + 'static,
     T: HttpSink<Input = B::Input, Output = B::Output>,
@@ -222,7 +231,7 @@ where
 impl<T, B, K> PartitionHttpSink<T, B, K, HttpRetryLogic>
 where
     B: Batch,
-    B::Output: ByteSizeOf + Clone + Send + 'static,
+    B::Output: ByteSizeOf + Partition<K> + Clone + Send + 'static,
     B::Input: Partition<K>,
     K: Hash + Eq + Clone + Send + 'static,
     T: HttpSink<Input = B::Input, Output = B::Output>,
@@ -250,7 +259,7 @@ where
 impl<T, B, K, RL> PartitionHttpSink<T, B, K, RL>
 where
     B: Batch,
-    B::Output: ByteSizeOf + Clone + Send + 'static,
+    B::Output: ByteSizeOf + Partition<K> + Clone + Send + 'static,
     B::Input: Partition<K>,
     K: Hash + Eq + Clone + Send + 'static,
     T: HttpSink<Input = B::Input, Output = B::Output>,
@@ -273,7 +282,10 @@ where
             Box::pin(async move { sink.build_request(b).await })
         };
 
-        let svc = HttpBatchService::new(client, request_builder);
+        let mut svc = HttpBatchService::new(client, request_builder);
+        if let Some(compression) = sink.request_compression() {
+            svc = svc.with_compression(HttpCompressor::new(compression));
+        }
         let inner = request_settings.partition_sink(retry_logic, svc, batch, batch_timeout, acker);
         let encoder = sink.build_encoder();
 
@@ -295,7 +307,7 @@ where
 impl<T, B, K, RL> Sink<Event> for PartitionHttpSink<T, B, K, RL>
 where
     B: Batch,
-    B::Output: ByteSizeOf + Clone + Send + 'static,
+    B::Output: ByteSizeOf + Partition<K> + Clone + Send + 'static,
     B::Input: Partition<K>,
     K: Hash + Eq + Clone + Send + 'static,
     T: HttpSink<Input = B::Input, Output = B::Output>,
@@ -352,6 +364,7 @@ where
 pub struct HttpBatchService<F, B = Bytes> {
     inner: HttpClient<Body>,
     request_builder: Arc<dyn Fn(B) -> F + Send + Sync>,
+    compressor: Option<HttpCompressor>,
 }
 
 impl<F, B> HttpBatchService<F, B> {
@@ -362,8 +375,42 @@ impl<F, B> HttpBatchService<F, B> {
         HttpBatchService {
             inner,
             request_builder: Arc::new(Box::new(request_builder)),
+            compressor: None,
         }
     }
+
+    /// Compresses the bodies of the built requests, falling back to the next algorithm of the
+    /// compressor when the endpoint rejects one.
+    pub fn with_compression(mut self, compressor: HttpCompressor) -> Self {
+        self.compressor = Some(compressor);
+        self
+    }
+}
+
+async fn send_request(
+    http_client: &mut HttpClient,
+    request: http::Request<Bytes>,
+) -> crate::Result<http::Response<Bytes>> {
+    let byte_size = request.body().len();
+    let request = request.map(Body::from);
+    let (protocol, endpoint) = uri::protocol_endpoint(request.uri().clone());
+
+    let response = http_client.call(request).await?;
+
+    if response.status().is_success() {
+        emit!(&EndpointBytesSent {
+            byte_size,
+            protocol: &protocol,
+            endpoint: &endpoint
+        });
+    }
+
+    let (parts, body) = response.into_parts();
+    let mut body = body::aggregate(body).await?;
+    Ok(hyper::Response::from_parts(
+        parts,
+        body.copy_to_bytes(body.remaining()),
+    ))
 }
 
 impl<F, B> Service<B> for HttpBatchService<F, B>
@@ -382,29 +429,25 @@ where
     fn call(&mut self, body: B) -> Self::Future {
         let request_builder = Arc::clone(&self.request_builder);
         let mut http_client = self.inner.clone();
+        let compressor = self.compressor.clone();
 
         Box::pin(async move {
             let request = request_builder(body).await?;
-            let byte_size = request.body().len();
-            let request = request.map(Body::from);
-            let (protocol, endpoint) = uri::protocol_endpoint(request.uri().clone());
-
-            let response = http_client.call(request).await?;
-
-            if response.status().is_success() {
-                emit!(&EndpointBytesSent {
-                    byte_size,
-                    protocol: &protocol,
-                    endpoint: &endpoint
-                });
+            match compressor {
+                Some(compressor) => loop {
+                    let (compressed, algorithm) = compressor.compress(&request)?;
+                    let response = send_request(&mut http_client, compressed).await?;
+                    match algorithm {
+                        Some(algorithm)
+                            if response.status() == StatusCode::UNSUPPORTED_MEDIA_TYPE =>
+                        {
+                            compressor.reject(algorithm);
+                        }
+                        _ => break Ok(response),
+                    }
+                },
+                None => send_request(&mut http_client, request).await,
             }
-
-            let (parts, body) = response.into_parts();
-            let mut body = body::aggregate(body).await?;
-            Ok(hyper::Response::from_parts(
-                parts,
-                body.copy_to_bytes(body.remaining()),
-            ))
         })
     }
 }
@@ -414,6 +457,7 @@ impl<F, B> Clone for HttpBatchService<F, B> {
         Self {
             inner: self.inner.clone(),
             request_builder: Arc::clone(&self.request_builder),
+            compressor: self.compressor.clone(),
         }
     }
 }
@@ -454,6 +498,39 @@ impl RetryLogic for HttpRetryLogic {
             _ => RetryAction::DontRetry(format!("response status: {}", status).into()),
         }
     }
+
+    fn retry_after(&self, response: &Self::Response) -> Option<Duration> {
+        retry_after(response.headers())
+    }
+}
+
+/// Parses the delay the server asks to wait for before retrying, from the `Retry-After` header,
+/// either in seconds or as an HTTP date, or else from the `RateLimit-Reset` header, in seconds.
+pub fn retry_after(headers: &http::HeaderMap) -> Option<Duration> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    header(http::header::RETRY_AFTER)
+        .and_then(|value| {
+            value
+                .trim()
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .ok()
+                .or_else(|| {
+                    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+                    // A date in the past lets the request be retried right away.
+                    Some(
+                        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                            .to_std()
+                            .unwrap_or_default(),
+                    )
+                })
+        })
+        .or_else(|| {
+            header("ratelimit-reset")
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs)
+        })
 }
 
 /// A more generic version of `HttpRetryLogic` that accepts anything that can be converted
@@ -526,6 +603,8 @@ pub struct RequestConfig {
     pub tower: TowerRequestConfig,
     #[serde(default)]
     pub headers: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<HttpCompressionConfig>,
 }
 
 impl RequestConfig {
@@ -569,6 +648,39 @@ mod test {
             .is_not_retryable());
     }
 
+    #[test]
+    fn util_http_retry_after() {
+        let logic = HttpRetryLogic;
+
+        let response = Response::builder()
+            .status(429)
+            .header("Retry-After", "120")
+            .body(Bytes::new())
+            .unwrap();
+        assert_eq!(Some(Duration::from_secs(120)), logic.retry_after(&response));
+
+        let response = Response::builder()
+            .status(503)
+            .header("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .body(Bytes::new())
+            .unwrap();
+        assert_eq!(Some(Duration::from_secs(0)), logic.retry_after(&response));
+
+        let response = Response::builder()
+            .status(429)
+            .header("RateLimit-Reset", "30")
+            .body(Bytes::new())
+            .unwrap();
+        assert_eq!(Some(Duration::from_secs(30)), logic.retry_after(&response));
+
+        let response = Response::builder()
+            .status(429)
+            .header("Retry-After", "soon")
+            .body(Bytes::new())
+            .unwrap();
+        assert_eq!(None, logic.retry_after(&response));
+    }
+
     #[tokio::test]
     async fn util_http_it_makes_http_requests() {
         let addr = next_addr();
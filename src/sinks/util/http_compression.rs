@@ -0,0 +1,252 @@
+//! Compression of the bodies of HTTP requests, negotiated with the endpoint.
+//!
+//! The algorithms are tried in order of preference: once the endpoint rejects one with a
+//! `415 Unsupported Media Type` response, the request is sent again with the next one, which is
+//! then used for all the following requests of the sink.
+
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use flate2::write::GzEncoder;
+use http::{
+    header::{HeaderValue, CONTENT_ENCODING},
+    Request,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::internal_events::http_client::{HttpCompressionRejected, HttpRequestCompressed};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpCompressionAlgorithm {
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// The block format of Snappy, without framing.
+    #[cfg(feature = "snap")]
+    Snappy,
+}
+
+impl HttpCompressionAlgorithm {
+    pub const fn content_encoding(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            #[cfg(feature = "zstd")]
+            Self::Zstd => "zstd",
+            #[cfg(feature = "snap")]
+            Self::Snappy => "snappy",
+        }
+    }
+
+    fn compress(self, body: &[u8], level: Option<u32>) -> crate::Result<Bytes> {
+        Ok(match self {
+            Self::Gzip => {
+                let level = level.map_or_else(flate2::Compression::default, |level| {
+                    flate2::Compression::new(level.min(9))
+                });
+                let mut writer = GzEncoder::new(BytesMut::new().writer(), level);
+                writer.write_all(body).expect("Writing to Vec can't fail");
+                writer
+                    .finish()
+                    .expect("Writing to Vec can't fail")
+                    .into_inner()
+                    .freeze()
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let level = level.map_or(zstd::DEFAULT_COMPRESSION_LEVEL, |level| {
+                    level.min(22) as i32
+                });
+                zstd::stream::encode_all(body, level)
+                    .expect("Writing to Vec can't fail")
+                    .into()
+            }
+            #[cfg(feature = "snap")]
+            Self::Snappy => snap::raw::Encoder::new().compress_vec(body)?.into(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpCompressionConfig {
+    /// The algorithms, in order of preference.
+    pub algorithms: Vec<HttpCompressionAlgorithm>,
+    /// The compression level, if the algorithm has levels.
+    pub level: Option<u32>,
+}
+
+/// Compresses the bodies of the requests of a sink with the algorithm negotiated with the
+/// endpoint. Its clones share the negotiated algorithm.
+#[derive(Clone, Debug)]
+pub struct HttpCompressor {
+    algorithms: Arc<[HttpCompressionAlgorithm]>,
+    level: Option<u32>,
+    /// The index of the algorithm in use, past the last one once the endpoint rejected them all.
+    negotiated: Arc<AtomicUsize>,
+}
+
+impl HttpCompressor {
+    pub fn new(config: &HttpCompressionConfig) -> Self {
+        Self {
+            algorithms: config.algorithms.clone().into(),
+            level: config.level,
+            negotiated: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn algorithm(&self) -> Option<HttpCompressionAlgorithm> {
+        self.algorithms
+            .get(self.negotiated.load(Ordering::Relaxed))
+            .copied()
+    }
+
+    /// Returns a copy of the request, the body of which is compressed with the negotiated
+    /// algorithm, along with that algorithm. Requests the body of which is already encoded, or
+    /// once the endpoint rejected all the algorithms, are copied unchanged. Fails if the body
+    /// can't be compressed, such as when it is too large for Snappy.
+    pub fn compress(
+        &self,
+        request: &Request<Bytes>,
+    ) -> crate::Result<(Request<Bytes>, Option<HttpCompressionAlgorithm>)> {
+        let algorithm = self
+            .algorithm()
+            .filter(|_| !request.headers().contains_key(CONTENT_ENCODING));
+        let body = match algorithm {
+            Some(algorithm) => {
+                let body = algorithm.compress(request.body(), self.level)?;
+                emit!(&HttpRequestCompressed {
+                    algorithm: algorithm.content_encoding(),
+                    uncompressed_byte_size: request.body().len(),
+                    compressed_byte_size: body.len(),
+                });
+                body
+            }
+            None => request.body().clone(),
+        };
+
+        let mut builder = Request::builder()
+            .method(request.method().clone())
+            .uri(request.uri().clone())
+            .version(request.version());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = request.headers().clone();
+            if let Some(algorithm) = algorithm {
+                headers.insert(
+                    CONTENT_ENCODING,
+                    HeaderValue::from_static(algorithm.content_encoding()),
+                );
+            }
+        }
+        let compressed = builder
+            .body(body)
+            .expect("Copy of a valid request can't fail");
+        Ok((compressed, algorithm))
+    }
+
+    /// Records that the endpoint rejected the algorithm, moving on to the next one unless another
+    /// request already did.
+    pub fn reject(&self, algorithm: HttpCompressionAlgorithm) {
+        let current = self.negotiated.load(Ordering::Relaxed);
+        if self.algorithms.get(current) == Some(&algorithm)
+            && self
+                .negotiated
+                .compare_exchange(current, current + 1, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            emit!(&HttpCompressionRejected {
+                algorithm: algorithm.content_encoding(),
+                fallback: self
+                    .algorithm()
+                    .map(HttpCompressionAlgorithm::content_encoding),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    fn compressor(algorithms: Vec<HttpCompressionAlgorithm>) -> HttpCompressor {
+        HttpCompressor::new(&HttpCompressionConfig {
+            algorithms,
+            level: None,
+        })
+    }
+
+    #[test]
+    fn compresses_with_gzip() {
+        let compressor = compressor(vec![HttpCompressionAlgorithm::Gzip]);
+        let request = Request::post("http://localhost")
+            .header("Content-Type", "application/json")
+            .body(Bytes::from("hello world"))
+            .unwrap();
+
+        let (compressed, algorithm) = compressor.compress(&request).unwrap();
+        assert_eq!(algorithm, Some(HttpCompressionAlgorithm::Gzip));
+        assert_eq!(compressed.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(compressed.headers()["Content-Type"], "application/json");
+
+        let mut body = String::new();
+        GzDecoder::new(&compressed.body()[..])
+            .read_to_string(&mut body)
+            .unwrap();
+        assert_eq!(body, "hello world");
+    }
+
+    #[test]
+    fn leaves_encoded_bodies() {
+        let compressor = compressor(vec![HttpCompressionAlgorithm::Gzip]);
+        let request = Request::post("http://localhost")
+            .header(CONTENT_ENCODING, "deflate")
+            .body(Bytes::from("hello world"))
+            .unwrap();
+
+        let (compressed, algorithm) = compressor.compress(&request).unwrap();
+        assert_eq!(algorithm, None);
+        assert_eq!(compressed.headers()[CONTENT_ENCODING], "deflate");
+        assert_eq!(compressed.body(), "hello world");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn falls_back_on_rejection() {
+        let compressor = compressor(vec![
+            HttpCompressionAlgorithm::Zstd,
+            HttpCompressionAlgorithm::Gzip,
+        ]);
+        let shared = compressor.clone();
+        let request = Request::post("http://localhost")
+            .body(Bytes::from("hello world"))
+            .unwrap();
+
+        assert_eq!(
+            compressor.compress(&request).unwrap().1,
+            Some(HttpCompressionAlgorithm::Zstd)
+        );
+
+        compressor.reject(HttpCompressionAlgorithm::Zstd);
+        assert_eq!(shared.algorithm(), Some(HttpCompressionAlgorithm::Gzip));
+
+        // A late rejection of the previous algorithm doesn't skip the next one.
+        shared.reject(HttpCompressionAlgorithm::Zstd);
+        assert_eq!(compressor.algorithm(), Some(HttpCompressionAlgorithm::Gzip));
+
+        compressor.reject(HttpCompressionAlgorithm::Gzip);
+        let (uncompressed, algorithm) = compressor.compress(&request).unwrap();
+        assert_eq!(algorithm, None);
+        assert!(!uncompressed.headers().contains_key(CONTENT_ENCODING));
+        assert_eq!(uncompressed.body(), "hello world");
+    }
+}
@@ -3,13 +3,19 @@ use std::{
     cmp,
     future::Future,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
 
 use futures::FutureExt;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 use tokio::time::{sleep, Sleep};
-use tower::{retry::Policy, timeout::error::Elapsed};
+use tower::{
+    retry::{budget::Budget, Policy},
+    timeout::error::Elapsed,
+};
 
 use crate::Error;
 
@@ -32,6 +38,28 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
         // Treat the default as the request is successful
         RetryAction::Successful
     }
+
+    /// The delay the response asks to wait for before retrying the request, if any.
+    fn retry_after(&self, _response: &Self::Response) -> Option<Duration> {
+        None
+    }
+}
+
+/// How the delays between the retries of a request are randomized.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JitterMode {
+    /// The delays follow the back-off exactly.
+    None,
+    /// The delays are drawn uniformly between zero and the back-off, so that the requests
+    /// failing together aren't retried together.
+    Full,
+}
+
+impl Default for JitterMode {
+    fn default() -> Self {
+        Self::None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +68,11 @@ pub struct FixedRetryPolicy<L> {
     previous_duration: Duration,
     current_duration: Duration,
     max_duration: Duration,
+    jitter_mode: JitterMode,
+    honor_retry_after: bool,
+    budget: Option<Arc<Budget>>,
+    /// Whether this policy is that of a retry, rather than of the original request.
+    retrying: bool,
     logic: L,
 }
 
@@ -60,10 +93,34 @@ impl<L: RetryLogic> FixedRetryPolicy<L> {
             previous_duration: Duration::from_secs(0),
             current_duration: initial_backoff,
             max_duration,
+            jitter_mode: JitterMode::None,
+            honor_retry_after: false,
+            budget: None,
+            retrying: false,
             logic,
         }
     }
 
+    /// Randomizes the delays between the retries as given by `jitter_mode`.
+    pub const fn with_jitter_mode(mut self, jitter_mode: JitterMode) -> Self {
+        self.jitter_mode = jitter_mode;
+        self
+    }
+
+    /// Waits for the delay the responses ask for, as given by `RetryLogic::retry_after`, instead
+    /// of the back-off, up to the maximum back-off.
+    pub const fn with_retry_after(mut self, honor_retry_after: bool) -> Self {
+        self.honor_retry_after = honor_retry_after;
+        self
+    }
+
+    /// Only retries the requests while the budget, shared by all the clones of this policy,
+    /// allows it. Each request deposits to the budget once, whatever its outcome.
+    pub fn with_budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     fn advance(&self) -> FixedRetryPolicy<L> {
         let next_duration: Duration = self.previous_duration + self.current_duration;
 
@@ -72,6 +129,10 @@ impl<L: RetryLogic> FixedRetryPolicy<L> {
             previous_duration: self.current_duration,
             current_duration: cmp::min(next_duration, self.max_duration),
             max_duration: self.max_duration,
+            jitter_mode: self.jitter_mode,
+            honor_retry_after: self.honor_retry_after,
+            budget: self.budget.clone(),
+            retrying: true,
             logic: self.logic.clone(),
         }
     }
@@ -80,12 +141,44 @@ impl<L: RetryLogic> FixedRetryPolicy<L> {
         self.current_duration
     }
 
-    fn build_retry(&self) -> RetryPolicyFuture<L> {
+    fn delay(&self, retry_after: Option<Duration>) -> Duration {
+        match retry_after.filter(|_| self.honor_retry_after) {
+            Some(retry_after) => cmp::min(retry_after, self.max_duration),
+            None => match self.jitter_mode {
+                JitterMode::None => self.backoff(),
+                JitterMode::Full => {
+                    let max = self.backoff().as_millis() as u64;
+                    Duration::from_millis(thread_rng().gen_range(0..=max))
+                }
+            },
+        }
+    }
+
+    fn build_retry(&self, retry_after: Option<Duration>) -> Option<RetryPolicyFuture<L>> {
+        if let Some(budget) = &self.budget {
+            if budget.withdraw().is_err() {
+                error!(message = "Retry budget exhausted; dropping the request.");
+                return None;
+            }
+        }
+
         let policy = self.advance();
-        let delay = Box::pin(sleep(self.backoff()));
+        let delay = self.delay(retry_after);
 
-        debug!(message = "Retrying request.", delay_ms = %self.backoff().as_millis());
-        RetryPolicyFuture { delay, policy }
+        debug!(message = "Retrying request.", delay_ms = %delay.as_millis());
+        Some(RetryPolicyFuture {
+            delay: Box::pin(sleep(delay)),
+            policy,
+        })
+    }
+
+    fn deposit(&self) {
+        if self.retrying {
+            return;
+        }
+        if let Some(budget) = &self.budget {
+            budget.deposit();
+        }
     }
 }
 
@@ -97,6 +190,8 @@ where
     type Future = RetryPolicyFuture<L>;
 
     fn retry(&self, _: &Req, result: Result<&Res, &Error>) -> Option<Self::Future> {
+        self.deposit();
+
         match result {
             Ok(response) => match self.logic.should_retry_response(response) {
                 RetryAction::Retry(reason) => {
@@ -109,7 +204,7 @@ where
                     }
 
                     warn!(message = "Retrying after response.", reason = %reason);
-                    Some(self.build_retry())
+                    self.build_retry(self.logic.retry_after(response))
                 }
 
                 RetryAction::DontRetry(reason) => {
@@ -128,7 +223,7 @@ where
                 if let Some(expected) = error.downcast_ref::<L::Error>() {
                     if self.logic.is_retriable_error(expected) {
                         warn!(message = "Retrying after error.", error = %expected);
-                        Some(self.build_retry())
+                        self.build_retry(None)
                     } else {
                         error!(
                             message = "Non-retriable error; dropping the request.",
@@ -138,7 +233,7 @@ where
                     }
                 } else if error.downcast_ref::<Elapsed>().is_some() {
                     warn!("Request timed out. If this happens often while the events are actually reaching their destination, try decreasing `batch.max_bytes` and/or using `compression` if applicable. Alternatively `request.timeout_secs` can be increased.");
-                    Some(self.build_retry())
+                    self.build_retry(None)
                 } else {
                     error!(
                         message = "Unexpected error type; dropping the request.",
@@ -380,6 +475,141 @@ mod tests {
         assert_eq!(Duration::from_secs(10), policy.backoff());
     }
 
+    #[test]
+    fn full_jitter_stays_below_backoff() {
+        let policy = FixedRetryPolicy::new(
+            10,
+            Duration::from_secs(4),
+            Duration::from_secs(10),
+            SvcRetryLogic,
+        )
+        .with_jitter_mode(JitterMode::Full);
+
+        for _ in 0..100 {
+            assert!(policy.delay(None) <= Duration::from_secs(4));
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_after_response() {
+        time::pause();
+
+        let policy = FixedRetryPolicy::new(
+            5,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            RetryAfterLogic,
+        )
+        .with_retry_after(true);
+
+        let retry = Policy::<&str, &str, crate::Error>::retry(&policy, &"hello", Ok(&"slow down"))
+            .expect("response should be retried");
+        assert_eq!(
+            Duration::from_secs(5),
+            retry.delay.deadline() - time::Instant::now()
+        );
+
+        assert!(
+            Policy::<&str, &str, crate::Error>::retry(&policy, &"hello", Ok(&"world")).is_none()
+        );
+    }
+
+    #[test]
+    fn retry_after_capped_by_max_duration() {
+        let policy = FixedRetryPolicy::new(
+            5,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            RetryAfterLogic,
+        );
+        assert_eq!(
+            Duration::from_secs(1),
+            policy.delay(Some(Duration::from_secs(60)))
+        );
+
+        let policy = policy.with_retry_after(true);
+        assert_eq!(
+            Duration::from_secs(10),
+            policy.delay(Some(Duration::from_secs(60)))
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_budget_exhausted() {
+        trace_init();
+
+        let policy = FixedRetryPolicy::new(
+            5,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            SvcRetryLogic,
+        )
+        .with_budget(Arc::new(Budget::new(Duration::from_secs(10), 0, 0.0)));
+
+        let (mut svc, mut handle) = mock::spawn_layer(RetryLayer::new(policy));
+
+        assert_ready_ok!(svc.poll_ready());
+
+        let mut fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_error(Error(true));
+        assert_ready_err!(fut.poll());
+    }
+
+    #[tokio::test]
+    async fn retry_budget_deposits_failed_requests() {
+        trace_init();
+
+        time::pause();
+
+        // Each request allows for a single retry.
+        let policy = FixedRetryPolicy::new(
+            5,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            SvcRetryLogic,
+        )
+        .with_budget(Arc::new(Budget::new(Duration::from_secs(10), 0, 1.0)));
+
+        let (mut svc, mut handle) = mock::spawn_layer(RetryLayer::new(policy));
+
+        assert_ready_ok!(svc.poll_ready());
+
+        let mut fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_error(Error(true));
+        assert_pending!(fut.poll());
+
+        time::advance(Duration::from_secs(2)).await;
+        assert_pending!(fut.poll());
+
+        // The retry doesn't deposit to the budget itself.
+        assert_request_eq!(handle, "hello").send_error(Error(true));
+        assert_ready_err!(fut.poll());
+    }
+
+    #[derive(Debug, Clone)]
+    struct RetryAfterLogic;
+
+    impl RetryLogic for RetryAfterLogic {
+        type Error = Error;
+        type Response = &'static str;
+
+        fn is_retriable_error(&self, error: &Self::Error) -> bool {
+            error.0
+        }
+
+        fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+            if *response == "slow down" {
+                RetryAction::Retry("slow down".into())
+            } else {
+                RetryAction::Successful
+            }
+        }
+
+        fn retry_after(&self, _response: &Self::Response) -> Option<Duration> {
+            Some(Duration::from_secs(5))
+        }
+    }
+
     #[derive(Debug, Clone)]
     struct SvcRetryLogic;
 
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use tower::{
     layer::{util::Stack, Layer},
     limit::RateLimit,
-    retry::Retry,
+    retry::{budget::Budget, Retry},
     timeout::Timeout,
     util::BoxService,
     Service, ServiceBuilder,
@@ -18,8 +18,9 @@ pub use crate::sinks::util::service::{
 use crate::sinks::util::{
     adaptive_concurrency::{
         AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
+        PartitionedAdaptiveConcurrencyLimit, PartitionedAdaptiveConcurrencyLimitLayer,
     },
-    retries::{FixedRetryPolicy, RetryLogic},
+    retries::{FixedRetryPolicy, JitterMode, RetryLogic},
     service::map::MapLayer,
     sink::Response,
     Batch, BatchSink, Partition, PartitionBatchSink,
@@ -29,8 +30,10 @@ mod concurrency;
 mod map;
 
 pub type Svc<S, L> = RateLimit<AdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L>>;
+pub type PartitionedSvc<S, L, K> =
+    RateLimit<PartitionedAdaptiveConcurrencyLimit<Retry<FixedRetryPolicy<L>, Timeout<S>>, L, K>>;
 pub type TowerBatchedSink<S, B, RL> = BatchSink<Svc<S, RL>, B>;
-pub type TowerPartitionSink<S, B, RL, K> = PartitionBatchSink<Svc<S, RL>, B, K>;
+pub type TowerPartitionSink<S, B, RL, K> = PartitionBatchSink<PartitionedSvc<S, RL, K>, B, K>;
 
 pub trait ServiceBuilderExt<L> {
     fn map<R1, R2, F>(self, f: F) -> ServiceBuilder<Stack<MapLayer<R1, R2>, L>>
@@ -77,6 +80,9 @@ pub struct TowerRequestConfig {
     pub retry_attempts: Option<usize>,         // isize::MAX
     pub retry_max_duration_secs: Option<u64>,
     pub retry_initial_backoff_secs: Option<u64>, // 1
+    pub retry_jitter_mode: Option<JitterMode>,   // none
+    pub retry_after: Option<bool>,               // false
+    pub retry_budget_percent: Option<f32>,       // unlimited
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
 }
@@ -87,6 +93,10 @@ pub const RATE_LIMIT_NUM_DEFAULT: u64 = i64::max_value() as u64; // i64 avoids T
 pub const RETRY_ATTEMPTS_DEFAULT: usize = isize::max_value() as usize; // isize avoids TOML deserialize issue
 pub const RETRY_MAX_DURATION_SECONDS_DEFAULT: u64 = 3_600; // one hour
 pub const RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT: u64 = 1; // one second
+pub const RETRY_JITTER_MODE_DEFAULT: JitterMode = JitterMode::None;
+pub const RETRY_AFTER_DEFAULT: bool = false;
+pub const RETRY_BUDGET_TTL_SECONDS: u64 = 10;
+pub const RETRY_BUDGET_MIN_PER_SECOND: u32 = 10;
 pub const TIMEOUT_SECONDS_DEFAULT: u64 = 60; // one minute
 
 impl Default for TowerRequestConfig {
@@ -105,6 +115,9 @@ impl TowerRequestConfig {
             retry_attempts: Some(RETRY_ATTEMPTS_DEFAULT),
             retry_max_duration_secs: Some(RETRY_MAX_DURATION_SECONDS_DEFAULT),
             retry_initial_backoff_secs: Some(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
+            retry_jitter_mode: Some(RETRY_JITTER_MODE_DEFAULT),
+            retry_after: Some(RETRY_AFTER_DEFAULT),
+            retry_budget_percent: None,
             adaptive_concurrency: AdaptiveConcurrencySettings::const_default(),
         }
     }
@@ -143,6 +156,21 @@ impl TowerRequestConfig {
         self
     }
 
+    pub const fn retry_jitter_mode(mut self, retry_jitter_mode: JitterMode) -> Self {
+        self.retry_jitter_mode = Some(retry_jitter_mode);
+        self
+    }
+
+    pub const fn retry_after(mut self, retry_after: bool) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    pub const fn retry_budget_percent(mut self, retry_budget_percent: f32) -> Self {
+        self.retry_budget_percent = Some(retry_budget_percent);
+        self
+    }
+
     pub fn unwrap_with(&self, defaults: &Self) -> TowerRequestSettings {
         TowerRequestSettings {
             concurrency: self.concurrency.parse_concurrency(defaults.concurrency),
@@ -174,6 +202,25 @@ impl TowerRequestConfig {
                     .or(defaults.retry_initial_backoff_secs)
                     .unwrap_or(RETRY_INITIAL_BACKOFF_SECONDS_DEFAULT),
             ),
+            retry_jitter_mode: self
+                .retry_jitter_mode
+                .or(defaults.retry_jitter_mode)
+                .unwrap_or(RETRY_JITTER_MODE_DEFAULT),
+            retry_after: self
+                .retry_after
+                .or(defaults.retry_after)
+                .unwrap_or(RETRY_AFTER_DEFAULT),
+            // The budget is shared by all the requests of the sink.
+            retry_budget: self
+                .retry_budget_percent
+                .or(defaults.retry_budget_percent)
+                .map(|percent| {
+                    Arc::new(Budget::new(
+                        Duration::from_secs(RETRY_BUDGET_TTL_SECONDS),
+                        RETRY_BUDGET_MIN_PER_SECOND,
+                        percent / 100.0,
+                    ))
+                }),
             adaptive_concurrency: self.adaptive_concurrency,
         }
     }
@@ -188,17 +235,26 @@ pub struct TowerRequestSettings {
     pub retry_attempts: usize,
     pub retry_max_duration_secs: Duration,
     pub retry_initial_backoff_secs: Duration,
+    pub retry_jitter_mode: JitterMode,
+    pub retry_after: bool,
+    pub retry_budget: Option<Arc<Budget>>,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
 }
 
 impl TowerRequestSettings {
     pub fn retry_policy<L: RetryLogic>(&self, logic: L) -> FixedRetryPolicy<L> {
-        FixedRetryPolicy::new(
+        let policy = FixedRetryPolicy::new(
             self.retry_attempts,
             self.retry_initial_backoff_secs,
             self.retry_max_duration_secs,
             logic,
         )
+        .with_jitter_mode(self.retry_jitter_mode)
+        .with_retry_after(self.retry_after);
+        match &self.retry_budget {
+            Some(budget) => policy.with_budget(Arc::clone(budget)),
+            None => policy,
+        }
     }
 
     pub fn partition_sink<B, RL, S, K>(
@@ -217,11 +273,11 @@ impl TowerRequestSettings {
         S::Future: Send + 'static,
         B: Batch,
         B::Input: Partition<K>,
-        B::Output: Send + Clone + 'static,
+        B::Output: Partition<K> + Send + Clone + 'static,
         K: Hash + Eq + Clone + Send + 'static,
     {
         PartitionBatchSink::new(
-            self.service(retry_logic, service),
+            self.partitioned_service(retry_logic, service),
             batch,
             batch_timeout,
             acker,
@@ -274,6 +330,37 @@ impl TowerRequestSettings {
             .timeout(self.timeout)
             .service(service)
     }
+
+    /// Builds a service like `service`, the concurrency limits of which are
+    /// tracked for each partition of the requests when
+    /// `adaptive_concurrency.per_partition` is set. Otherwise, the service
+    /// is limited exactly like the one of `service`.
+    pub fn partitioned_service<RL, S, Request, K>(
+        &self,
+        retry_logic: RL,
+        service: S,
+    ) -> PartitionedSvc<S, RL, K>
+    where
+        RL: RetryLogic<Response = S::Response>,
+        S: Service<Request> + Clone + Send + 'static,
+        S::Error: Into<crate::Error> + Send + Sync + 'static,
+        S::Response: Send + Response,
+        S::Future: Send + 'static,
+        Request: Partition<K> + Send + Clone + 'static,
+        K: Hash + Eq,
+    {
+        let policy = self.retry_policy(retry_logic.clone());
+        ServiceBuilder::new()
+            .rate_limit(self.rate_limit_num, self.rate_limit_duration)
+            .layer(PartitionedAdaptiveConcurrencyLimitLayer::new(
+                self.concurrency,
+                self.adaptive_concurrency,
+                retry_logic,
+            ))
+            .retry(policy)
+            .timeout(self.timeout)
+            .service(service)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -356,6 +443,31 @@ mod tests {
             .expect_err("Invalid concurrency setting didn't fail on negative number");
     }
 
+    #[test]
+    fn retry_params_work() {
+        let cfg = toml::from_str::<TowerRequestConfig>("").expect("Empty config failed");
+        let settings = cfg.unwrap_with(&TowerRequestConfig::default());
+        assert_eq!(settings.retry_jitter_mode, JitterMode::None);
+        assert!(!settings.retry_after);
+        assert!(settings.retry_budget.is_none());
+
+        let cfg = toml::from_str::<TowerRequestConfig>(
+            r#"
+            retry_jitter_mode = "full"
+            retry_after = true
+            retry_budget_percent = 20.0
+            "#,
+        )
+        .expect("Retry settings failed");
+        let settings = cfg.unwrap_with(&TowerRequestConfig::default());
+        assert_eq!(settings.retry_jitter_mode, JitterMode::Full);
+        assert!(settings.retry_after);
+        assert!(settings.retry_budget.is_some());
+
+        toml::from_str::<TowerRequestConfig>(r#"retry_jitter_mode = "broken""#)
+            .expect_err("Invalid jitter mode didn't fail");
+    }
+
     #[test]
     fn config_merging_defaults_concurrency_to_none_if_unset() {
         let cfg = TowerRequestConfig::default().unwrap_with(&TowerRequestConfig::default());
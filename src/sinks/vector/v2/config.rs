@@ -1,7 +1,4 @@
 use http::Uri;
-use hyper::client::HttpConnector;
-use hyper_openssl::HttpsConnector;
-use hyper_proxy::ProxyConnector;
 use serde::{Deserialize, Serialize};
 use tonic::body::BoxBody;
 use tower::ServiceBuilder;
@@ -10,6 +7,7 @@ use crate::{
     config::{
         AcknowledgementsConfig, GenerateConfig, ProxyConfig, SinkContext, SinkHealthcheckOptions,
     },
+    http::{build_proxy_connector, HttpsProxyConnector},
     proto::vector as proto,
     sinks::{
         util::{
@@ -23,7 +21,7 @@ use crate::{
         },
         Healthcheck, VectorSink as VectorSinkType,
     },
-    tls::{tls_connector_builder, MaybeTlsSettings, TlsConfig},
+    tls::{MaybeTlsSettings, TlsConfig},
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -42,6 +40,16 @@ pub struct VectorConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub(in crate::sinks::vector) acknowledgements: AcknowledgementsConfig,
+    /// Compresses the events with zstd, if the source supports it.
+    #[serde(default)]
+    compression: bool,
+    /// The number of HTTP/2 connections the requests are spread over, round-robin.
+    #[serde(default = "default_connections")]
+    connections: usize,
+}
+
+const fn default_connections() -> usize {
+    1
 }
 
 impl GenerateConfig for VectorConfig {
@@ -57,6 +65,8 @@ fn default_config(address: &str) -> VectorConfig {
         request: TowerRequestConfig::default(),
         tls: None,
         acknowledgements: Default::default(),
+        compression: false,
+        connections: default_connections(),
     }
 }
 
@@ -68,7 +78,12 @@ impl VectorConfig {
         let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
         let uri = with_default_scheme(&self.address, tls.is_tls())?;
 
-        let client = new_client(&tls, cx.proxy())?;
+        if self.connections == 0 {
+            return Err("`connections` must be greater than zero.".into());
+        }
+        let clients = (0..self.connections)
+            .map(|_| new_client(&tls, cx.proxy()))
+            .collect::<crate::Result<Vec<_>>>()?;
 
         let healthcheck_uri = cx
             .healthcheck
@@ -76,9 +91,9 @@ impl VectorConfig {
             .clone()
             .map(|uri| uri.uri)
             .unwrap_or_else(|| uri.clone());
-        let healthcheck_client = VectorService::new(client.clone(), healthcheck_uri);
+        let healthcheck_client = VectorService::new(clients.clone(), healthcheck_uri, false);
         let healthcheck = healthcheck(healthcheck_client, cx.healthcheck.clone());
-        let service = VectorService::new(client, uri);
+        let service = VectorService::new(clients, uri, self.compression);
         let request_settings = self.request.unwrap_with(&TowerRequestConfig::default());
         let batch_settings = self.batch.into_batcher_settings()?;
         //
@@ -100,15 +115,12 @@ impl VectorConfig {
 }
 
 /// Check to see if the remote service accepts new events.
-async fn healthcheck(
-    mut service: VectorService,
-    options: SinkHealthcheckOptions,
-) -> crate::Result<()> {
+async fn healthcheck(service: VectorService, options: SinkHealthcheckOptions) -> crate::Result<()> {
     if !options.enabled {
         return Ok(());
     }
 
-    let request = service.client.health_check(proto::HealthCheckRequest {});
+    let request = service.client().health_check(proto::HealthCheckRequest {});
 
     if let Ok(response) = request.await {
         let status = proto::ServingStatus::from_i32(response.into_inner().status);
@@ -158,26 +170,12 @@ pub fn with_default_scheme(address: &str, tls: bool) -> crate::Result<Uri> {
 fn new_client(
     tls_settings: &MaybeTlsSettings,
     proxy_config: &ProxyConfig,
-) -> crate::Result<hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>> {
-    let mut http = HttpConnector::new();
-    http.enforce_http(false);
-
-    let tls = tls_connector_builder(tls_settings)?;
-    let mut https = HttpsConnector::with_connector(http, tls)?;
-
-    let settings = tls_settings.tls().cloned();
-    https.set_callback(move |c, _uri| {
-        if let Some(settings) = &settings {
-            settings.apply_connect_configuration(c);
-        }
-
-        Ok(())
-    });
-
-    let mut proxy = ProxyConnector::new(https).unwrap();
-    proxy_config.configure(&mut proxy)?;
-
-    Ok(hyper::Client::builder().http2_only(true).build(proxy))
+) -> crate::Result<hyper::Client<HttpsProxyConnector, BoxBody>> {
+    let proxy = build_proxy_connector(tls_settings.clone(), proxy_config)?;
+    Ok(hyper::Client::builder()
+        .http2_only(true)
+        .http2_adaptive_window(true)
+        .build(proxy))
 }
 
 #[derive(Debug, Clone)]
@@ -12,6 +12,9 @@ pub enum VectorSinkError {
     #[snafu(display("Request failed: {}", source))]
     Request { source: tonic::Status },
 
+    #[snafu(display("Compression failed: {}", source))]
+    Compress { source: std::io::Error },
+
     #[snafu(display("Vector source unhealthy"))]
     Health,
 
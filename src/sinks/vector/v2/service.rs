@@ -1,10 +1,13 @@
-use std::task::{Context, Poll};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
 
-use futures::{future::BoxFuture, TryFutureExt};
+use futures::future::BoxFuture;
 use http::Uri;
-use hyper::client::HttpConnector;
-use hyper_openssl::HttpsConnector;
-use hyper_proxy::ProxyConnector;
 use prost::Message;
 use proto_event::EventWrapper;
 use tonic::{body::BoxBody, IntoRequest};
@@ -15,6 +18,7 @@ use vector_core::{
 
 use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
+    http::HttpsProxyConnector,
     internal_events::EndpointBytesSent,
     proto::vector as proto_vector,
     sinks::{util::uri, vector::v2::VectorSinkError},
@@ -23,11 +27,23 @@ use crate::{
 
 #[derive(Clone, Debug)]
 pub struct VectorService {
-    pub client: proto_vector::Client<HyperSvc>,
+    connections: Arc<[Connection]>,
+    /// The connection of the next request, round-robin.
+    next: Arc<AtomicUsize>,
+    /// Whether to compress the events, if negotiated.
+    compression: bool,
     pub protocol: String,
     pub endpoint: String,
 }
 
+/// A client with its own HTTP/2 connection, and the compression negotiated on it.
+#[derive(Debug)]
+struct Connection {
+    client: proto_vector::Client<HyperSvc>,
+    /// Forgotten when a request fails, so that it's negotiated again with the source reconnected to.
+    compression: Mutex<Option<proto_vector::Compression>>,
+}
+
 pub struct VectorResponse {
     events_count: usize,
     events_byte_size: usize,
@@ -67,21 +83,131 @@ impl Finalizable for VectorRequest {
 }
 
 impl VectorService {
+    /// The requests are spread over the clients, each with its own HTTP/2 connection.
     pub fn new(
-        hyper_client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+        hyper_clients: Vec<hyper::Client<HttpsProxyConnector, BoxBody>>,
         uri: Uri,
+        compression: bool,
     ) -> Self {
         let (protocol, endpoint) = uri::protocol_endpoint(uri.clone());
-        let proto_client = proto_vector::Client::new(HyperSvc {
-            uri,
-            client: hyper_client,
-        });
+        let connections = hyper_clients
+            .into_iter()
+            .map(|client| Connection {
+                client: proto_vector::Client::new(HyperSvc {
+                    uri: uri.clone(),
+                    client,
+                }),
+                compression: Mutex::default(),
+            })
+            .collect();
         Self {
-            client: proto_client,
+            connections,
+            next: Arc::default(),
+            compression,
             protocol,
             endpoint,
         }
     }
+
+    /// The client of the first connection.
+    pub fn client(&self) -> proto_vector::Client<HyperSvc> {
+        self.connections[0].client.clone()
+    }
+}
+
+impl Connection {
+    /// Sends the events, returning the size of the request sent.
+    async fn push_events(
+        &self,
+        request: proto_vector::PushEventsRequest,
+        compression: bool,
+    ) -> Result<usize, VectorSinkError> {
+        let result = self.try_push_events(request, compression).await;
+        if result.is_err() {
+            *self.compression.lock().unwrap() = None;
+        }
+        result
+    }
+
+    async fn try_push_events(
+        &self,
+        request: proto_vector::PushEventsRequest,
+        compression: bool,
+    ) -> Result<usize, VectorSinkError> {
+        let mut client = self.client.clone();
+        let compression = if compression {
+            self.compression().await?
+        } else {
+            proto_vector::Compression::None
+        };
+        if compression != proto_vector::Compression::None {
+            let compressed = proto_vector::PushCompressedEventsRequest::new(&request, compression)
+                .map_err(|source| VectorSinkError::Compress { source })?;
+            let byte_size = compressed.encoded_len();
+            match client.push_compressed_events(compressed).await {
+                Ok(_) => return Ok(byte_size),
+                // Behind a load balancer, the source receiving the events can be another than the
+                // one which negotiated, and not able to decompress them.
+                Err(status) if status.code() == tonic::Code::Unimplemented => {
+                    debug!(
+                        message = "Source can't decompress the events, sending them uncompressed."
+                    );
+                    *self.compression.lock().unwrap() = Some(proto_vector::Compression::None);
+                }
+                Err(source) => return Err(VectorSinkError::Request { source }),
+            }
+        }
+
+        let byte_size = request.encoded_len();
+        client
+            .push_events(request.into_request())
+            .await
+            .map(|_response| byte_size)
+            .map_err(|source| VectorSinkError::Request { source })
+    }
+
+    async fn compression(&self) -> Result<proto_vector::Compression, VectorSinkError> {
+        let negotiated = *self.compression.lock().unwrap();
+        match negotiated {
+            Some(compression) => Ok(compression),
+            None => {
+                let compression = negotiate(self.client.clone()).await?;
+                *self.compression.lock().unwrap() = Some(compression);
+                Ok(compression)
+            }
+        }
+    }
+}
+
+/// Agrees with the source on the protocol version and the compression of the events.
+async fn negotiate(
+    mut client: proto_vector::Client<HyperSvc>,
+) -> Result<proto_vector::Compression, VectorSinkError> {
+    let request = proto_vector::NegotiateRequest {
+        protocol_version: proto_vector::PROTOCOL_VERSION,
+        compressions: vec![proto_vector::Compression::Zstd.into()],
+    };
+    let (protocol_version, compression) = match client.negotiate(request).await {
+        Ok(response) => {
+            let response = response.into_inner();
+            (
+                response.protocol_version,
+                proto_vector::Compression::from_i32(response.compression)
+                    .unwrap_or(proto_vector::Compression::None),
+            )
+        }
+        // The sources of the first version of the protocol can't negotiate nor decompress.
+        Err(status) if status.code() == tonic::Code::Unimplemented => {
+            (1, proto_vector::Compression::None)
+        }
+        Err(source) => return Err(VectorSinkError::Request { source }),
+    };
+    debug!(
+        message = "Negotiated the protocol with the source.",
+        protocol_version,
+        ?compression
+    );
+    Ok(compression)
 }
 
 impl tower::Service<VectorRequest> for VectorService {
@@ -99,31 +225,27 @@ impl tower::Service<VectorRequest> for VectorService {
     }
 
     fn call(&mut self, list: VectorRequest) -> Self::Future {
-        let mut service = self.clone();
+        let service = self.clone();
+        let connection = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
         let events_count = list.events.len();
         let events_byte_size = list.events_byte_size;
 
-        let request = proto_vector::PushEventsRequest {
-            events: list.events,
-        };
-        let byte_size = request.encoded_len();
         let future = async move {
-            service
-                .client
-                .push_events(request.into_request())
-                .map_ok(|_response| {
-                    emit!(&EndpointBytesSent {
-                        byte_size,
-                        protocol: &service.protocol,
-                        endpoint: &service.endpoint,
-                    });
-                    VectorResponse {
-                        events_count,
-                        events_byte_size,
-                    }
-                })
-                .map_err(|source| VectorSinkError::Request { source }.into())
-                .await
+            let request = proto_vector::PushEventsRequest {
+                events: list.events,
+            };
+            let byte_size = service.connections[connection]
+                .push_events(request, service.compression)
+                .await?;
+            emit!(&EndpointBytesSent {
+                byte_size,
+                protocol: &service.protocol,
+                endpoint: &service.endpoint,
+            });
+            Ok::<_, Error>(VectorResponse {
+                events_count,
+                events_byte_size,
+            })
         };
 
         Box::pin(future)
@@ -133,7 +255,7 @@ impl tower::Service<VectorRequest> for VectorService {
 #[derive(Clone, Debug)]
 pub struct HyperSvc {
     uri: Uri,
-    client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+    client: hyper::Client<HttpsProxyConnector, BoxBody>,
 }
 
 impl tower::Service<hyper::Request<BoxBody>> for HyperSvc {
@@ -0,0 +1,395 @@
+//! The `amqp` source consumes messages from an AMQP 0.9.1 queue, such as one hosted by
+//! RabbitMQ. Messages are acknowledged once their events are delivered, and rejected
+//! without requeueing when they can't be decoded or a sink rejects them, so the broker can
+//! route them to the queue's dead letter exchange.
+
+use std::collections::BTreeMap;
+
+use bytes::{Bytes, BytesMut};
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use lapin::{
+    acker::Acker,
+    message::Delivery,
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicQosOptions, BasicRejectOptions,
+    },
+    types::{AMQPValue, FieldTable},
+};
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+use tokio_util::codec::Decoder as _;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    amqp::AmqpConfig,
+    codecs::{
+        self,
+        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+    },
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    internal_events::{
+        AmqpAckError, AmqpConsumerError, AmqpEventsReceived, BytesReceived, StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sources::util::AcknowledgementTimeout,
+    SourceSender,
+};
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpSourceConfig {
+    #[serde(flatten)]
+    connection: AmqpConfig,
+    #[serde(default = "default_queue")]
+    #[derivative(Default(value = "default_queue()"))]
+    queue: String,
+    #[serde(default = "default_consumer")]
+    #[derivative(Default(value = "default_consumer()"))]
+    consumer: String,
+    /// The number of unacknowledged messages the server delivers before waiting for
+    /// acknowledgements.
+    #[serde(default = "default_prefetch_count")]
+    #[derivative(Default(value = "default_prefetch_count()"))]
+    prefetch_count: u16,
+    #[serde(default = "default_routing_key_field")]
+    #[derivative(Default(value = "default_routing_key_field()"))]
+    routing_key_field: String,
+    #[serde(default = "default_exchange_key")]
+    #[derivative(Default(value = "default_exchange_key()"))]
+    exchange_key: String,
+    #[serde(default = "default_headers_key")]
+    #[derivative(Default(value = "default_headers_key()"))]
+    headers_key: String,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_queue() -> String {
+    "vector".into()
+}
+
+fn default_consumer() -> String {
+    "vector".into()
+}
+
+const fn default_prefetch_count() -> u16 {
+    100
+}
+
+fn default_routing_key_field() -> String {
+    "routing".into()
+}
+
+fn default_exchange_key() -> String {
+    "exchange".into()
+}
+
+fn default_headers_key() -> String {
+    "headers".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<AmqpSourceConfig>("amqp")
+}
+
+impl_generate_config_from_default!(AmqpSourceConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "amqp")]
+impl SourceConfig for AmqpSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let (connection, channel) = self.connection.connect().await?;
+        channel
+            .basic_qos(self.prefetch_count, BasicQosOptions::default())
+            .await?;
+        let consumer = channel
+            .basic_consume(
+                &self.queue,
+                &self.consumer,
+                BasicConsumeOptions::default(),
+                FieldTable::default(),
+            )
+            .await?;
+
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        Ok(Box::pin(amqp_source(
+            connection,
+            consumer,
+            Keys::from(self),
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+            cx.acknowledgement_timeout,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "amqp"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+
+    fn can_redeliver(&self) -> bool {
+        true
+    }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
+}
+
+/// The fields the delivery metadata is inserted at.
+struct Keys {
+    routing_key_field: String,
+    exchange_key: String,
+    headers_key: String,
+}
+
+impl From<&AmqpSourceConfig> for Keys {
+    fn from(config: &AmqpSourceConfig) -> Self {
+        Self {
+            routing_key_field: config.routing_key_field.clone(),
+            exchange_key: config.exchange_key.clone(),
+            headers_key: config.headers_key.clone(),
+        }
+    }
+}
+
+async fn amqp_source(
+    connection: lapin::Connection,
+    consumer: lapin::Consumer,
+    keys: Keys,
+    decoder: codecs::Decoder,
+    shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
+) -> Result<(), ()> {
+    let mut stream = consumer.take_until(shutdown);
+    let mut result = Ok(());
+
+    while let Some(delivery) = stream.next().await {
+        let delivery = match delivery {
+            Ok(delivery) => delivery,
+            Err(error) => {
+                emit!(&AmqpConsumerError { error });
+                result = Err(());
+                break;
+            }
+        };
+
+        emit!(&BytesReceived {
+            byte_size: delivery.data.len(),
+            protocol: "amqp",
+        });
+
+        let events = match decode_delivery(decoder.clone(), &delivery, &keys) {
+            Some(events) => events,
+            None => {
+                // The payload won't become decodable by redelivering it.
+                finalize(delivery.acker, BatchStatus::Rejected).await;
+                continue;
+            }
+        };
+        let count = events.len();
+        emit!(&AmqpEventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let acker = delivery.acker;
+        if acknowledgements {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            let events = events
+                .into_iter()
+                .map(|event| event.with_batch_notifier(&batch));
+            match out.send_batch(events).await {
+                Ok(()) => {
+                    drop(batch);
+                    tokio::spawn(async move {
+                        finalize(acker, acknowledgement_timeout.wait(receiver).await).await
+                    });
+                }
+                Err(error) => {
+                    emit!(&StreamClosedError { error, count });
+                    finalize(acker, BatchStatus::Errored).await;
+                }
+            }
+        } else {
+            match out.send_batch(events).await {
+                Ok(()) => finalize(acker, BatchStatus::Delivered).await,
+                Err(error) => {
+                    emit!(&StreamClosedError { error, count });
+                    finalize(acker, BatchStatus::Errored).await;
+                }
+            }
+        }
+    }
+
+    // Unacknowledged messages are redelivered to other consumers once the connection is closed.
+    if let Err(error) = connection.close(0, "Shutting down").await {
+        debug!(message = "Failed to close AMQP connection.", %error);
+    }
+
+    result
+}
+
+/// Acknowledges a message once its events were delivered. Errored messages are requeued,
+/// rejected ones are dead-lettered if the queue has a dead letter exchange, and dropped
+/// otherwise.
+async fn finalize(acker: Acker, status: BatchStatus) {
+    let result = match status {
+        BatchStatus::Delivered => acker.ack(BasicAckOptions::default()).await,
+        BatchStatus::Errored => {
+            acker
+                .nack(BasicNackOptions {
+                    requeue: true,
+                    ..BasicNackOptions::default()
+                })
+                .await
+        }
+        BatchStatus::Rejected => acker.reject(BasicRejectOptions { requeue: false }).await,
+    };
+
+    if let Err(error) = result {
+        emit!(&AmqpAckError { error, status });
+    }
+}
+
+/// Decodes the payload of a delivery into log events annotated with its metadata. Returns
+/// `None` if the payload can't be decoded.
+fn decode_delivery(
+    mut decoder: codecs::Decoder,
+    delivery: &Delivery,
+    keys: &Keys,
+) -> Option<Vec<Event>> {
+    let mut events = Vec::new();
+    let mut bytes = BytesMut::from(delivery.data.as_slice());
+    loop {
+        match decoder.decode_eof(&mut bytes) {
+            Ok(Some((next, _))) => events.extend(next),
+            Ok(None) => break,
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    return None;
+                }
+            }
+        }
+    }
+
+    let timestamp = delivery
+        .properties
+        .timestamp()
+        .and_then(|secs| Utc.timestamp_opt(secs as i64, 0).latest())
+        .unwrap_or_else(Utc::now);
+    let headers = delivery
+        .properties
+        .headers()
+        .as_ref()
+        .map(|headers| headers_to_value(headers))
+        .unwrap_or_else(|| Value::Object(BTreeMap::new()));
+
+    for event in &mut events {
+        if let Event::Log(log) = event {
+            log.insert(log_schema().source_type_key(), Bytes::from("amqp"));
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert(
+                keys.routing_key_field.as_str(),
+                delivery.routing_key.as_str().to_owned(),
+            );
+            log.insert(
+                keys.exchange_key.as_str(),
+                delivery.exchange.as_str().to_owned(),
+            );
+            log.insert(keys.headers_key.as_str(), headers.clone());
+        }
+    }
+
+    Some(events)
+}
+
+fn headers_to_value(headers: &FieldTable) -> Value {
+    Value::Object(
+        headers
+            .inner()
+            .iter()
+            .filter_map(|(name, value)| {
+                amqp_to_value(value).map(|value| (name.as_str().to_owned(), value))
+            })
+            .collect(),
+    )
+}
+
+/// Converts a header value, skipping types without an equivalent `Value`.
+fn amqp_to_value(value: &AMQPValue) -> Option<Value> {
+    Some(match value {
+        AMQPValue::Boolean(value) => Value::from(*value),
+        AMQPValue::ShortShortInt(value) => Value::from(*value as i64),
+        AMQPValue::ShortShortUInt(value) => Value::from(*value as i64),
+        AMQPValue::ShortInt(value) => Value::from(*value as i64),
+        AMQPValue::ShortUInt(value) => Value::from(*value as i64),
+        AMQPValue::LongInt(value) => Value::from(*value as i64),
+        AMQPValue::LongUInt(value) => Value::from(*value as i64),
+        AMQPValue::LongLongInt(value) => Value::from(*value),
+        AMQPValue::Float(value) => Value::from(NotNan::new(*value as f64).ok()?),
+        AMQPValue::Double(value) => Value::from(NotNan::new(*value).ok()?),
+        AMQPValue::ShortString(value) => Value::from(value.as_str().to_owned()),
+        AMQPValue::LongString(value) => Value::from(Bytes::copy_from_slice(value.as_bytes())),
+        AMQPValue::Timestamp(secs) => Value::from(Utc.timestamp_opt(*secs as i64, 0).latest()?),
+        AMQPValue::FieldTable(table) => headers_to_value(table),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AmqpSourceConfig>();
+    }
+
+    #[test]
+    fn converts_headers() {
+        let mut nested = FieldTable::default();
+        nested.insert("retries".into(), AMQPValue::LongInt(3));
+
+        let mut headers = FieldTable::default();
+        headers.insert("service".into(), AMQPValue::LongString("api".into()));
+        headers.insert("sampled".into(), AMQPValue::Boolean(true));
+        headers.insert("nested".into(), AMQPValue::FieldTable(nested));
+        headers.insert("nothing".into(), AMQPValue::Void);
+
+        let value = headers_to_value(&headers);
+        let object = value.as_object().unwrap();
+        assert_eq!(object["service"], Value::from("api"));
+        assert_eq!(object["sampled"], Value::from(true));
+        assert_eq!(object["nested"].as_object().unwrap()["retries"], 3.into());
+        assert!(!object.contains_key("nothing"));
+    }
+}
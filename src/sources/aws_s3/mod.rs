@@ -102,6 +102,14 @@ impl SourceConfig for AwsS3Config {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_redeliver(&self) -> bool {
+        true
+    }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
 impl AwsS3Config {
@@ -501,7 +509,7 @@ mod integration_tests {
             compression: Compression::Auto,
             multiline,
             sqs: Some(sqs::Config {
-                queue_url: queue_url.to_string(),
+                queue_url: Some(queue_url.to_string()),
                 poll_secs: 1,
                 visibility_timeout_secs: 0,
                 client_concurrency: 1,
@@ -4,6 +4,7 @@ use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
 use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
 use once_cell::sync::Lazy;
+use regex::Regex;
 use rusoto_core::{Region, RusotoError};
 use rusoto_s3::{GetObjectError, GetObjectRequest, S3Client, S3};
 use rusoto_sqs::{
@@ -24,11 +25,12 @@ use crate::{
     internal_events::{
         BytesReceived, SqsMessageDeleteBatchError, SqsMessageDeletePartialError,
         SqsMessageDeleteSucceeded, SqsMessageProcessingError, SqsMessageProcessingSucceeded,
-        SqsMessageReceiveError, SqsMessageReceiveSucceeded, SqsS3EventRecordInvalidEventIgnored,
-        SqsS3EventsReceived, StreamClosedError,
+        SqsMessageReceiveError, SqsMessageReceiveSucceeded, SqsS3EventRecordFilteredKeyIgnored,
+        SqsS3EventRecordInvalidEventIgnored, SqsS3EventsReceived, StreamClosedError,
     },
     line_agg::{self, LineAgg},
     shutdown::ShutdownSignal,
+    sources::util::AcknowledgementTimeout,
     SourceSender,
 };
 
@@ -39,7 +41,12 @@ static SUPPORTED_S3S_EVENT_VERSION: Lazy<semver::VersionReq> =
 #[derivative(Default)]
 #[serde(deny_unknown_fields)]
 pub(super) struct Config {
-    pub(super) queue_url: String,
+    #[serde(default)]
+    pub(super) queue_url: Option<String>,
+
+    // more queues to receive the notifications from, along with `queue_url`
+    #[serde(default)]
+    pub(super) queue_urls: Vec<String>,
 
     // restricted to u32 for safe conversion to i64 later
     #[serde(default = "default_poll_secs")]
@@ -59,6 +66,30 @@ pub(super) struct Config {
     #[serde(default = "default_client_concurrency")]
     #[derivative(Default(value = "default_client_concurrency()"))]
     pub(super) client_concurrency: u32,
+
+    // the objects of the notifications are only fetched if their keys match these
+    #[serde(default)]
+    pub(super) key_prefixes: Vec<String>,
+    #[serde(default)]
+    pub(super) key_suffixes: Vec<String>,
+    #[serde(default)]
+    pub(super) key_pattern: Option<String>,
+}
+
+impl Config {
+    fn all_queue_urls(&self) -> Result<Vec<String>, IngestorNewError> {
+        let queue_urls: Vec<String> = self
+            .queue_url
+            .iter()
+            .chain(self.queue_urls.iter())
+            .cloned()
+            .collect();
+        if queue_urls.is_empty() {
+            Err(IngestorNewError::MissingQueue)
+        } else {
+            Ok(queue_urls)
+        }
+    }
 }
 
 const fn default_poll_secs() -> u32 {
@@ -84,6 +115,48 @@ pub(super) enum IngestorNewError {
         source: std::num::TryFromIntError,
         timeout: u64,
     },
+    #[snafu(display("At least one of `queue_url` or `queue_urls` must be set"))]
+    MissingQueue,
+    #[snafu(display("Invalid key pattern {:?}: {}", pattern, source))]
+    InvalidKeyPattern {
+        source: regex::Error,
+        pattern: String,
+    },
+}
+
+/// Filters the objects by their keys, the ones not matching being skipped without fetching them.
+#[derive(Debug, Default)]
+struct KeyFilter {
+    prefixes: Vec<String>,
+    suffixes: Vec<String>,
+    pattern: Option<Regex>,
+}
+
+impl KeyFilter {
+    fn new(config: &Config) -> Result<Self, IngestorNewError> {
+        let pattern = config
+            .key_pattern
+            .as_ref()
+            .map(|pattern| Regex::new(pattern).context(InvalidKeyPatternSnafu { pattern }))
+            .transpose()?;
+        Ok(Self {
+            prefixes: config.key_prefixes.clone(),
+            suffixes: config.key_suffixes.clone(),
+            pattern,
+        })
+    }
+
+    /// The key has to start with one of the prefixes and end with one of the suffixes, if there
+    /// are any, and to match the pattern if set.
+    fn matches(&self, key: &str) -> bool {
+        (self.prefixes.is_empty() || self.prefixes.iter().any(|prefix| key.starts_with(prefix)))
+            && (self.suffixes.is_empty()
+                || self.suffixes.iter().any(|suffix| key.ends_with(suffix)))
+            && self
+                .pattern
+                .as_ref()
+                .map_or(true, |pattern| pattern.is_match(key))
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -141,7 +214,8 @@ pub struct State {
     multiline: Option<line_agg::Config>,
     compression: super::Compression,
 
-    queue_url: String,
+    queue_urls: Vec<String>,
+    key_filter: KeyFilter,
     poll_secs: u32,
     client_concurrency: u32,
     visibility_timeout_secs: i64,
@@ -163,6 +237,9 @@ impl Ingestor {
     ) -> Result<Ingestor, IngestorNewError> {
         let visibility_timeout_secs: i64 = config.visibility_timeout_secs.into();
 
+        let queue_urls = config.all_queue_urls()?;
+        let key_filter = KeyFilter::new(&config)?;
+
         let state = Arc::new(State {
             region,
 
@@ -172,7 +249,8 @@ impl Ingestor {
             compression,
             multiline,
 
-            queue_url: config.queue_url,
+            queue_urls,
+            key_filter,
             poll_secs: config.poll_secs,
             client_concurrency: config.client_concurrency,
             visibility_timeout_secs,
@@ -189,16 +267,20 @@ impl Ingestor {
     ) -> Result<(), ()> {
         let acknowledgements = cx.do_acknowledgements(&acknowledgements);
         let mut handles = Vec::new();
-        for _ in 0..self.state.client_concurrency {
-            let process = IngestorProcess::new(
-                Arc::clone(&self.state),
-                cx.out.clone(),
-                cx.shutdown.clone(),
-                acknowledgements,
-            );
-            let fut = process.run();
-            let handle = tokio::spawn(fut.in_current_span());
-            handles.push(handle);
+        for queue_url in &self.state.queue_urls {
+            for _ in 0..self.state.client_concurrency {
+                let process = IngestorProcess::new(
+                    Arc::clone(&self.state),
+                    queue_url.clone(),
+                    cx.out.clone(),
+                    cx.shutdown.clone(),
+                    acknowledgements,
+                    cx.acknowledgement_timeout,
+                );
+                let fut = process.run();
+                let handle = tokio::spawn(fut.in_current_span());
+                handles.push(handle);
+            }
         }
 
         // Wait for all of the processes to finish.  If any one of them panics, we resume
@@ -217,23 +299,29 @@ impl Ingestor {
 
 pub struct IngestorProcess {
     state: Arc<State>,
+    queue_url: String,
     out: SourceSender,
     shutdown: ShutdownSignal,
     acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
 }
 
 impl IngestorProcess {
     pub fn new(
         state: Arc<State>,
+        queue_url: String,
         out: SourceSender,
         shutdown: ShutdownSignal,
         acknowledgements: bool,
+        acknowledgement_timeout: AcknowledgementTimeout,
     ) -> Self {
         Self {
             state,
+            queue_url,
             out,
             shutdown,
             acknowledgements,
+            acknowledgement_timeout,
         }
     }
 
@@ -379,6 +467,14 @@ impl IngestorProcess {
             });
         }
 
+        if !self.state.key_filter.matches(&s3_event.s3.object.key) {
+            emit!(&SqsS3EventRecordFilteredKeyIgnored {
+                bucket: &s3_event.s3.bucket.name,
+                key: &s3_event.s3.object.key,
+            });
+            return Ok(());
+        }
+
         let object = self
             .state
             .s3_client
@@ -513,9 +609,10 @@ impl IngestorProcess {
                         key: s3_event.s3.object.key.clone(),
                     })
                 } else {
+                    let acknowledgement_timeout = self.acknowledgement_timeout;
                     match receiver {
                         None => Ok(()),
-                        Some(receiver) => match receiver.await {
+                        Some(receiver) => match acknowledgement_timeout.wait(receiver).await {
                             BatchStatus::Delivered => Ok(()),
                             BatchStatus::Errored => Err(ProcessingError::ErrorAcknowledgement),
                             BatchStatus::Rejected => {
@@ -538,7 +635,7 @@ impl IngestorProcess {
         self.state
             .sqs_client
             .receive_message(ReceiveMessageRequest {
-                queue_url: self.state.queue_url.clone(),
+                queue_url: self.queue_url.clone(),
                 max_number_of_messages: Some(10),
                 visibility_timeout: Some(self.state.visibility_timeout_secs),
                 wait_time_seconds: Some(i64::from(self.state.poll_secs)),
@@ -555,7 +652,7 @@ impl IngestorProcess {
         self.state
             .sqs_client
             .delete_message_batch(DeleteMessageBatchRequest {
-                queue_url: self.state.queue_url.clone(),
+                queue_url: self.queue_url.clone(),
                 entries,
             })
             .await
@@ -726,3 +823,61 @@ mod urlencoded_string {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key_filter(config: &str) -> KeyFilter {
+        let config: Config = toml::from_str(config).unwrap();
+        KeyFilter::new(&config).unwrap()
+    }
+
+    #[test]
+    fn key_filter_matches_everything_by_default() {
+        let filter = key_filter(r#"queue_url = "queue""#);
+        assert!(filter.matches("logs/app.log.gz"));
+        assert!(filter.matches(""));
+    }
+
+    #[test]
+    fn key_filter_matches_prefixes_suffixes_and_pattern() {
+        let filter = key_filter(
+            r#"
+            queue_url = "queue"
+            key_prefixes = ["logs/", "audit/"]
+            key_suffixes = [".gz"]
+            key_pattern = "/20[0-9]{2}/"
+            "#,
+        );
+        assert!(filter.matches("logs/2022/app.log.gz"));
+        assert!(filter.matches("audit/2021/trail.gz"));
+        assert!(!filter.matches("metrics/2022/app.log.gz"));
+        assert!(!filter.matches("logs/2022/app.log"));
+        assert!(!filter.matches("logs/latest/app.log.gz"));
+    }
+
+    #[test]
+    fn merges_queue_urls() {
+        let config: Config = toml::from_str(
+            r#"
+            queue_url = "first"
+            queue_urls = ["second", "third"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.all_queue_urls().unwrap(),
+            vec!["first", "second", "third"]
+        );
+
+        let config: Config = toml::from_str(r#"queue_urls = ["second"]"#).unwrap();
+        assert_eq!(config.all_queue_urls().unwrap(), vec!["second"]);
+
+        let config: Config = toml::from_str("").unwrap();
+        assert!(matches!(
+            config.all_queue_urls(),
+            Err(IngestorNewError::MissingQueue)
+        ));
+    }
+}
@@ -45,7 +45,7 @@ pub struct AwsSqsConfig {
 impl SourceConfig for AwsSqsConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<crate::sources::Source> {
         let client = self.build_client(&cx).await?;
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
         Ok(Box::pin(
@@ -56,6 +56,7 @@ impl SourceConfig for AwsSqsConfig {
                 poll_secs: self.poll_secs,
                 concurrency: self.client_concurrency,
                 acknowledgements,
+                acknowledgement_timeout: cx.acknowledgement_timeout,
             }
             .run(cx.out, cx.shutdown),
         ))
@@ -72,6 +73,14 @@ impl SourceConfig for AwsSqsConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_redeliver(&self) -> bool {
+        true
+    }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
 impl AwsSqsConfig {
@@ -19,7 +19,7 @@ use crate::{
     config::log_schema,
     event::{BatchNotifier, BatchStatus, Event},
     shutdown::ShutdownSignal,
-    sources::util::StreamDecodingError,
+    sources::util::{AcknowledgementTimeout, StreamDecodingError},
     SourceSender,
 };
 
@@ -34,6 +34,7 @@ pub struct SqsSource {
     pub poll_secs: u32,
     pub concurrency: u32,
     pub(super) acknowledgements: bool,
+    pub(super) acknowledgement_timeout: AcknowledgementTimeout,
 }
 
 impl SqsSource {
@@ -126,8 +127,9 @@ impl SqsSource {
             if let Some(receiver) = batch_receiver {
                 let client = self.client.clone();
                 let queue_url = self.queue_url.clone();
+                let acknowledgement_timeout = self.acknowledgement_timeout;
                 tokio::spawn(async move {
-                    let batch_status = receiver.await;
+                    let batch_status = acknowledgement_timeout.wait(receiver).await;
                     if batch_status == BatchStatus::Delivered {
                         delete_messages(&client, &receipts_to_ack, &queue_url).await;
                     }
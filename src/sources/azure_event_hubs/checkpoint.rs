@@ -0,0 +1,216 @@
+use std::{collections::HashMap, sync::Arc};
+
+use azure_core::{new_http_client, HttpError};
+use azure_storage::prelude::*;
+use azure_storage_blobs::prelude::*;
+use bytes::Bytes;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::internal_events::AzureEventHubsCheckpointError;
+
+/// Where partition checkpoints are kept, instead of the offset store of the consumer group.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckpointStoreConfig {
+    pub connection_string: String,
+    pub container_name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Checkpoint {
+    offset: i64,
+}
+
+#[derive(Debug, Default)]
+struct PartitionState {
+    /// Offset of the last message whose events were delivered.
+    offset: Option<i64>,
+    /// Whether `offset` hasn't been written out yet.
+    dirty: bool,
+}
+
+/// Checkpoints of the partitions owned by this consumer, kept in a blob container with one
+/// blob per partition under `<namespace>/<event hub>/<consumer group>/checkpoint/`. Blobs are
+/// named like those of the Event Hubs SDKs, but hold the offset as a JSON body rather than as
+/// blob metadata, so they can't be shared with consumers built on those SDKs.
+pub(super) struct CheckpointStore {
+    client: Arc<ContainerClient>,
+    prefix: String,
+    partitions: std::sync::Mutex<HashMap<i32, PartitionState>>,
+}
+
+impl CheckpointStore {
+    pub(super) fn new(
+        config: &CheckpointStoreConfig,
+        namespace: &str,
+        event_hub: &str,
+        consumer_group: &str,
+    ) -> crate::Result<Self> {
+        let client = StorageAccountClient::new_connection_string(
+            new_http_client(),
+            config.connection_string.as_str(),
+        )?
+        .as_storage_client()
+        .as_container_client(config.container_name.clone());
+
+        Ok(Self {
+            client,
+            prefix: format!(
+                "{}/{}/{}/checkpoint/",
+                namespace,
+                event_hub,
+                consumer_group.to_lowercase()
+            ),
+            partitions: Default::default(),
+        })
+    }
+
+    fn blob_name(&self, partition: i32) -> String {
+        format!("{}{}", self.prefix, partition)
+    }
+
+    /// Takes ownership of newly assigned partitions, returning the offsets of the last
+    /// checkpointed message of those that have a checkpoint.
+    pub(super) async fn acquire(&self, partitions: Vec<i32>) -> HashMap<i32, i64> {
+        let mut offsets = HashMap::new();
+        for partition in partitions {
+            match self.load(partition).await {
+                Ok(Some(offset)) => {
+                    offsets.insert(partition, offset);
+                }
+                Ok(None) => (),
+                Err(error) => emit!(&AzureEventHubsCheckpointError { error, partition }),
+            }
+            self.partitions
+                .lock()
+                .unwrap()
+                .entry(partition)
+                .or_default()
+                .offset = offsets.get(&partition).copied();
+        }
+        offsets
+    }
+
+    async fn load(&self, partition: i32) -> crate::Result<Option<i64>> {
+        let response = self
+            .client
+            .as_blob_client(self.blob_name(partition).as_str())
+            .get()
+            .execute()
+            .await;
+
+        match response {
+            Ok(response) => {
+                let checkpoint: Checkpoint = serde_json::from_slice(&response.data)?;
+                Ok(Some(checkpoint.offset))
+            }
+            Err(error) => match error.downcast_ref::<HttpError>() {
+                Some(HttpError::StatusCode {
+                    status: StatusCode::NOT_FOUND,
+                    ..
+                }) => Ok(None),
+                _ => Err(error),
+            },
+        }
+    }
+
+    /// Records the events of a message as delivered. Messages of partitions that were revoked
+    /// in the meantime are ignored, their new owner resumes from the last checkpoint.
+    pub(super) fn update(&self, partition: i32, offset: i64) {
+        if let Some(state) = self.partitions.lock().unwrap().get_mut(&partition) {
+            if state.offset.map_or(true, |current| current < offset) {
+                state.offset = Some(offset);
+                state.dirty = true;
+            }
+        }
+    }
+
+    /// Writes out the checkpoints that changed since the last flush.
+    pub(super) async fn flush(&self) {
+        let dirty = self
+            .partitions
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|(_, state)| state.dirty)
+            .filter_map(|(partition, state)| {
+                state.dirty = false;
+                state.offset.map(|offset| (*partition, offset))
+            })
+            .collect::<Vec<_>>();
+
+        for (partition, offset) in dirty {
+            if let Err(error) = self.store(partition, offset).await {
+                emit!(&AzureEventHubsCheckpointError { error, partition });
+                // Retry on the next flush, unless a newer checkpoint is pending by then.
+                if let Some(state) = self.partitions.lock().unwrap().get_mut(&partition) {
+                    state.dirty = true;
+                }
+            }
+        }
+    }
+
+    async fn store(&self, partition: i32, offset: i64) -> crate::Result<()> {
+        let body = serde_json::to_vec(&Checkpoint { offset })?;
+        self.client
+            .as_blob_client(self.blob_name(partition).as_str())
+            .put_block_blob(Bytes::from(body))
+            .content_type("application/json")
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Writes out pending checkpoints and gives up ownership of all partitions.
+    pub(super) async fn release(&self) {
+        self.flush().await;
+        self.partitions.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> CheckpointStore {
+        let config = CheckpointStoreConfig {
+            connection_string: "DefaultEndpointsProtocol=https;AccountName=devstoreaccount1;AccountKey=Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==;".to_owned(),
+            container_name: "checkpoints".to_owned(),
+        };
+        CheckpointStore::new(
+            &config,
+            "example.servicebus.windows.net",
+            "logs",
+            "$Default",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn blob_names() {
+        assert_eq!(
+            store().blob_name(3),
+            "example.servicebus.windows.net/logs/$default/checkpoint/3"
+        );
+    }
+
+    #[test]
+    fn updates_owned_partitions_only() {
+        let store = store();
+        store
+            .partitions
+            .lock()
+            .unwrap()
+            .insert(0, PartitionState::default());
+
+        store.update(0, 10);
+        store.update(0, 5);
+        store.update(1, 10);
+
+        let partitions = store.partitions.lock().unwrap();
+        assert_eq!(partitions[&0].offset, Some(10));
+        assert!(partitions[&0].dirty);
+        assert!(!partitions.contains_key(&1));
+    }
+}
@@ -0,0 +1,567 @@
+//! The `azure_event_hubs` source consumes events from an Azure Event Hub over its
+//! Kafka-compatible endpoint. Partitions are balanced across all consumers of the consumer
+//! group by the group protocol, and their checkpoints are either committed to the consumer
+//! group, or kept in Azure Blob Storage.
+
+use std::{collections::BTreeMap, future::Future, sync::Arc, time::Duration};
+
+use bytes::{Bytes, BytesMut};
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use rdkafka::{
+    config::ClientConfig,
+    consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer},
+    message::{BorrowedMessage, Headers, Message},
+    ClientContext, Offset, Statistics,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio_util::codec::Decoder as _;
+use vector_core::ByteSizeOf;
+
+use super::util::finalizer::OrderedFinalizer;
+use crate::{
+    codecs::{
+        self,
+        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+    },
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, Event, Value},
+    internal_events::{
+        AzureEventHubsEventsReceived, BytesReceived, KafkaOffsetUpdateError, KafkaReadError,
+        KafkaStatisticsReceived, StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+mod checkpoint;
+
+use checkpoint::{CheckpointStore, CheckpointStoreConfig};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Connection string is missing the `Endpoint` of the namespace"))]
+    MissingEndpoint,
+    #[snafu(display(
+        "Either `event_hub` or the `EntityPath` of the connection string must be set"
+    ))]
+    MissingEventHub,
+    #[snafu(display("Could not create Kafka consumer: {}", source))]
+    KafkaCreateError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not subscribe to the event hub: {}", source))]
+    KafkaSubscribeError { source: rdkafka::error::KafkaError },
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+enum StartPosition {
+    Earliest,
+    #[derivative(Default)]
+    Latest,
+}
+
+impl StartPosition {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Earliest => "earliest",
+            Self::Latest => "latest",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct AzureEventHubsConfig {
+    connection_string: String,
+    event_hub: Option<String>,
+    #[serde(default = "default_consumer_group")]
+    #[derivative(Default(value = "default_consumer_group()"))]
+    consumer_group: String,
+    /// Where to start reading partitions without a checkpoint.
+    #[serde(default)]
+    start_position: StartPosition,
+    checkpoint_store: Option<CheckpointStoreConfig>,
+    #[serde(default = "default_checkpoint_interval_secs")]
+    #[derivative(Default(value = "default_checkpoint_interval_secs()"))]
+    checkpoint_interval_secs: u64,
+    librdkafka_options: Option<BTreeMap<String, String>>,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_consumer_group() -> String {
+    "$Default".into()
+}
+
+const fn default_checkpoint_interval_secs() -> u64 {
+    10
+}
+
+inventory::submit! {
+    SourceDescription::new::<AzureEventHubsConfig>("azure_event_hubs")
+}
+
+impl_generate_config_from_default!(AzureEventHubsConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "azure_event_hubs")]
+impl SourceConfig for AzureEventHubsConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let connection = ConnectionString::parse(&self.connection_string)?;
+        let event_hub = self
+            .event_hub
+            .clone()
+            .or(connection.entity_path)
+            .ok_or(BuildError::MissingEventHub)?;
+
+        let store = self
+            .checkpoint_store
+            .as_ref()
+            .map(|config| {
+                CheckpointStore::new(
+                    config,
+                    &connection.namespace,
+                    &event_hub,
+                    &self.consumer_group,
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
+
+        let consumer = self.create_consumer(&connection.namespace, &event_hub, store.clone())?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+
+        Ok(Box::pin(event_hubs_source(
+            consumer,
+            store,
+            Duration::from_secs(self.checkpoint_interval_secs),
+            decoder,
+            cx.shutdown,
+            cx.out,
+            acknowledgements,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "azure_event_hubs"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+impl AzureEventHubsConfig {
+    fn create_consumer(
+        &self,
+        namespace: &str,
+        event_hub: &str,
+        store: Option<Arc<CheckpointStore>>,
+    ) -> crate::Result<StreamConsumer<EventHubsContext>> {
+        let mut client_config = ClientConfig::new();
+        client_config
+            .set("group.id", &self.consumer_group)
+            .set("bootstrap.servers", format!("{}:9093", namespace))
+            .set("security.protocol", "sasl_ssl")
+            .set("sasl.mechanism", "PLAIN")
+            .set("sasl.username", "$ConnectionString")
+            .set("sasl.password", &self.connection_string)
+            .set("auto.offset.reset", self.start_position.as_str())
+            .set("enable.partition.eof", "false")
+            // Offsets are only committed to the consumer group if checkpoints aren't kept in
+            // the blob store.
+            .set(
+                "enable.auto.commit",
+                if store.is_some() { "false" } else { "true" },
+            )
+            .set("enable.auto.offset.store", "false")
+            .set("statistics.interval.ms", "1000")
+            .set("client.id", "vector");
+
+        if let Some(librdkafka_options) = &self.librdkafka_options {
+            for (key, value) in librdkafka_options {
+                client_config.set(key.as_str(), value.as_str());
+            }
+        }
+
+        let context = EventHubsContext {
+            store,
+            handle: tokio::runtime::Handle::current(),
+        };
+        let consumer = client_config
+            .create_with_context::<_, StreamConsumer<_>>(context)
+            .context(KafkaCreateSnafu)?;
+        consumer
+            .subscribe(&[event_hub])
+            .context(KafkaSubscribeSnafu)?;
+
+        Ok(consumer)
+    }
+}
+
+/// The parts of an Event Hubs connection string needed to set up the consumer, such as
+/// `Endpoint=sb://<namespace>.servicebus.windows.net/;SharedAccessKeyName=<name>;SharedAccessKey=<key>;EntityPath=<event hub>`.
+#[derive(Debug, PartialEq)]
+struct ConnectionString {
+    namespace: String,
+    entity_path: Option<String>,
+}
+
+impl ConnectionString {
+    fn parse(connection_string: &str) -> Result<Self, BuildError> {
+        let mut namespace = None;
+        let mut entity_path = None;
+        for part in connection_string.split(';') {
+            // Shared access keys may contain `=`.
+            let mut pair = part.trim().splitn(2, '=');
+            match (pair.next(), pair.next()) {
+                (Some("Endpoint"), Some(endpoint)) => {
+                    let endpoint = endpoint.trim_start_matches("sb://").trim_end_matches('/');
+                    namespace = (!endpoint.is_empty()).then(|| endpoint.to_owned());
+                }
+                (Some("EntityPath"), Some(path)) if !path.is_empty() => {
+                    entity_path = Some(path.to_owned())
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            namespace: namespace.ok_or(BuildError::MissingEndpoint)?,
+            entity_path,
+        })
+    }
+}
+
+/// Seeds newly assigned partitions with their checkpoint from the blob store, and writes out
+/// the checkpoints of revoked partitions before another consumer picks them up.
+struct EventHubsContext {
+    store: Option<Arc<CheckpointStore>>,
+    handle: tokio::runtime::Handle,
+}
+
+impl EventHubsContext {
+    /// Rebalances are run by librdkafka while polling for messages, which needs to wait for
+    /// the checkpoints before the partitions are assigned. The store is driven on a separate
+    /// thread so this also works from within the runtime.
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let handle = self.handle.clone();
+        std::thread::spawn(move || handle.block_on(future))
+            .join()
+            .expect("Checkpoint store thread panicked")
+    }
+}
+
+impl ClientContext for EventHubsContext {
+    fn stats(&self, statistics: Statistics) {
+        emit!(&KafkaStatisticsReceived {
+            statistics: &statistics
+        });
+    }
+}
+
+impl ConsumerContext for EventHubsContext {
+    fn pre_rebalance<'a>(&self, rebalance: &Rebalance<'a>) {
+        let store = match &self.store {
+            Some(store) => Arc::clone(store),
+            None => return,
+        };
+
+        match rebalance {
+            Rebalance::Assign(partitions) => {
+                let assigned = partitions
+                    .elements()
+                    .iter()
+                    .map(|element| element.partition())
+                    .collect();
+                let checkpoints = self.block_on(async move { store.acquire(assigned).await });
+
+                for mut element in partitions.elements() {
+                    if let Some(offset) = checkpoints.get(&element.partition()) {
+                        debug!(
+                            message = "Resuming partition from checkpoint.",
+                            partition = element.partition(),
+                            offset,
+                        );
+                        // Resume after the last delivered message.
+                        if let Err(error) = element.set_offset(Offset::Offset(offset + 1)) {
+                            emit!(&KafkaOffsetUpdateError { error });
+                        }
+                    }
+                }
+            }
+            Rebalance::Revoke => self.block_on(async move { store.release().await }),
+            Rebalance::Error(_) => (),
+        }
+    }
+}
+
+async fn event_hubs_source(
+    consumer: StreamConsumer<EventHubsContext>,
+    store: Option<Arc<CheckpointStore>>,
+    checkpoint_interval: Duration,
+    decoder: codecs::Decoder,
+    shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    acknowledgements: bool,
+) -> Result<(), ()> {
+    let consumer = Arc::new(consumer);
+    let shutdown = shutdown.shared();
+    let finalizer = acknowledgements.then(|| {
+        OrderedFinalizer::new(
+            shutdown.clone(),
+            mark_done(Arc::clone(&consumer), store.clone()),
+        )
+    });
+
+    if let Some(store) = &store {
+        let store = Arc::clone(store);
+        let mut shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(checkpoint_interval);
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = interval.tick() => store.flush().await,
+                }
+            }
+        });
+    }
+
+    let mut stream = consumer.stream().take_until(shutdown);
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                emit!(&KafkaReadError { error });
+                continue;
+            }
+        };
+
+        emit!(&BytesReceived {
+            byte_size: message.payload_len(),
+            protocol: "tcp",
+        });
+
+        let entry = FinalizerEntry::from(&message);
+        let events = decode_message(decoder.clone(), &message);
+        drop(message);
+        let count = events.len();
+        emit!(&AzureEventHubsEventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        match &finalizer {
+            Some(finalizer) => {
+                let (batch, receiver) = BatchNotifier::new_with_receiver();
+                let events = events
+                    .into_iter()
+                    .map(|event| event.with_batch_notifier(&batch));
+                match out.send_batch(events).await {
+                    Ok(()) => finalizer.add(entry, receiver),
+                    Err(error) => emit!(&StreamClosedError { error, count }),
+                }
+            }
+            None => match out.send_batch(events).await {
+                Ok(()) => store_offset(&consumer, store.as_deref(), entry),
+                Err(error) => emit!(&StreamClosedError { error, count }),
+            },
+        }
+    }
+
+    if let Some(store) = &store {
+        store.flush().await;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct FinalizerEntry {
+    event_hub: String,
+    partition: i32,
+    offset: i64,
+}
+
+impl<'a> From<&BorrowedMessage<'a>> for FinalizerEntry {
+    fn from(message: &BorrowedMessage<'a>) -> Self {
+        Self {
+            event_hub: message.topic().into(),
+            partition: message.partition(),
+            offset: message.offset(),
+        }
+    }
+}
+
+fn mark_done(
+    consumer: Arc<StreamConsumer<EventHubsContext>>,
+    store: Option<Arc<CheckpointStore>>,
+) -> impl Fn(FinalizerEntry) {
+    move |entry| store_offset(&consumer, store.as_deref(), entry)
+}
+
+fn store_offset(
+    consumer: &StreamConsumer<EventHubsContext>,
+    store: Option<&CheckpointStore>,
+    entry: FinalizerEntry,
+) {
+    match store {
+        Some(store) => store.update(entry.partition, entry.offset),
+        None => {
+            if let Err(error) =
+                consumer.store_offset(&entry.event_hub, entry.partition, entry.offset)
+            {
+                emit!(&KafkaOffsetUpdateError { error });
+            }
+        }
+    }
+}
+
+/// Decodes the body of an event into log events annotated with its partition, offset and
+/// properties. Events whose body can't be decoded are skipped.
+fn decode_message<M: Message>(mut decoder: codecs::Decoder, message: &M) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut bytes = BytesMut::from(message.payload().unwrap_or_default());
+    loop {
+        match decoder.decode_eof(&mut bytes) {
+            Ok(Some((next, _))) => events.extend(next),
+            Ok(None) => break,
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    let timestamp = message
+        .timestamp()
+        .to_millis()
+        .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
+        .unwrap_or_else(Utc::now);
+    let partition_key = message
+        .key()
+        .map(|key| Value::from(String::from_utf8_lossy(key).into_owned()));
+
+    let mut properties = BTreeMap::new();
+    if let Some(headers) = message.headers() {
+        for i in 0..headers.count() {
+            if let Some((name, value)) = headers.get(i) {
+                properties.insert(name.to_owned(), Value::from(Bytes::copy_from_slice(value)));
+            }
+        }
+    }
+    let properties = Value::Object(properties);
+
+    for event in &mut events {
+        if let Event::Log(log) = event {
+            log.insert(
+                log_schema().source_type_key(),
+                Bytes::from("azure_event_hubs"),
+            );
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert("event_hub", message.topic().to_owned());
+            log.insert("partition", message.partition() as i64);
+            log.insert("offset", message.offset());
+            if let Some(partition_key) = &partition_key {
+                log.insert("partition_key", partition_key.clone());
+            }
+            log.insert("properties", properties.clone());
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::message::{OwnedHeaders, OwnedMessage, Timestamp};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AzureEventHubsConfig>();
+    }
+
+    #[test]
+    fn parses_connection_string() {
+        assert_eq!(
+            ConnectionString::parse(
+                "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=abc=;EntityPath=logs"
+            )
+            .unwrap(),
+            ConnectionString {
+                namespace: "example.servicebus.windows.net".to_owned(),
+                entity_path: Some("logs".to_owned()),
+            }
+        );
+        assert_eq!(
+            ConnectionString::parse(
+                "Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=abc="
+            )
+            .unwrap()
+            .entity_path,
+            None
+        );
+        assert!(matches!(
+            ConnectionString::parse("SharedAccessKeyName=listen;SharedAccessKey=abc="),
+            Err(BuildError::MissingEndpoint)
+        ));
+    }
+
+    #[test]
+    fn decodes_message() {
+        let decoder = DecodingConfig::new(default_framing_message_based(), default_decoding())
+            .build()
+            .unwrap();
+        let message = OwnedMessage::new(
+            Some(b"hello".to_vec()),
+            Some(b"device-1".to_vec()),
+            "logs".to_owned(),
+            Timestamp::CreateTime(1_600_000_000_000),
+            2,
+            42,
+            Some(OwnedHeaders::new().add("region", "eu")),
+        );
+
+        let events = decode_message(decoder, &message);
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_600_000_000, 0).into()
+        );
+        assert_eq!(log["event_hub"], "logs".into());
+        assert_eq!(log["partition"], 2.into());
+        assert_eq!(log["offset"], 42.into());
+        assert_eq!(log["partition_key"], "device-1".into());
+        assert_eq!(log["properties.region"], "eu".into());
+    }
+}
@@ -99,7 +99,7 @@ impl SourceConfig for DatadogAgentConfig {
             .expect("registered metrics schema required")
             .clone();
 
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
         let source = DatadogAgentSource::new(
             self.store_api_key,
@@ -161,6 +161,11 @@ impl SourceConfig for DatadogAgentConfig {
             // details provided by the generic JSON schema definition.
             DeserializerConfig::Json => self.decoding.schema_definition(),
 
+            // CSV columns, MessagePack maps and protobuf messages can hold fields of any name.
+            DeserializerConfig::Csv { .. }
+            | DeserializerConfig::Msgpack
+            | DeserializerConfig::Protobuf { .. } => self.decoding.schema_definition(),
+
             // Syslog deserializer allows for arbritrary "structured data" that can overwrite
             // existing fields, similar to the JSON deserializer.
             //
@@ -212,7 +212,7 @@ impl_generate_config_from_default!(DemoLogsConfig);
 impl SourceConfig for DemoLogsConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         self.format.validate()?;
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         Ok(Box::pin(demo_logs_source(
             self.interval,
             self.count,
@@ -277,8 +277,9 @@ mod tests {
     async fn runit(config: &str) -> impl Stream<Item = Event> {
         let (tx, rx) = SourceSender::new_test();
         let config: DemoLogsConfig = toml::from_str(config).unwrap();
-        let decoder =
-            DecodingConfig::new(default_framing_message_based(), default_decoding()).build();
+        let decoder = DecodingConfig::new(default_framing_message_based(), default_decoding())
+            .build()
+            .unwrap();
         demo_logs_source(
             config.interval,
             config.count,
@@ -1,5 +1,10 @@
 use std::{
-    collections::HashMap, convert::TryFrom, future::ready, pin::Pin, sync::Arc, time::Duration,
+    collections::HashMap,
+    convert::TryFrom,
+    future::ready,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use bollard::{
@@ -10,7 +15,7 @@ use bollard::{
     Docker,
 };
 use bytes::{Buf, Bytes};
-use chrono::{DateTime, FixedOffset, Local, ParseError, Utc};
+use chrono::{DateTime, FixedOffset, Local, ParseError, TimeZone, Utc};
 use futures::{Stream, StreamExt};
 use lookup::lookup_v2::{parse_path, OwnedSegment};
 use once_cell::sync::Lazy;
@@ -22,7 +27,7 @@ use super::util::MultilineConfig;
 use crate::{
     config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
     docker::{docker, DockerTlsConfig},
-    event::{self, merge_state::LogEventMergeState, LogEvent, Value},
+    event::{self, merge_state::LogEventMergeState, Event, LogEvent, Value},
     internal_events::{
         BytesReceived, DockerLogsCommunicationError, DockerLogsContainerEventReceived,
         DockerLogsContainerMetadataFetchError, DockerLogsContainerUnwatch,
@@ -39,6 +44,14 @@ const CREATED_AT: &str = "container_created_at";
 const NAME: &str = "container_name";
 const STREAM: &str = "stream";
 const CONTAINER: &str = "container_id";
+const HEALTH: &str = "container_health";
+const COMPOSE_PROJECT: &str = "compose_project";
+const COMPOSE_SERVICE: &str = "compose_service";
+const CONTAINER_EVENT: &str = "container_event";
+const EXIT_CODE: &str = "exit_code";
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+const HEALTH_STATUS_ACTION: &str = "health_status: ";
 // Prevent short hostname from being wrongly regconized as a container's short ID.
 const MIN_HOSTNAME_LENGTH: usize = 6;
 
@@ -61,6 +74,7 @@ pub(super) struct DockerLogsConfig {
     auto_partial_merge: bool,
     multiline: Option<MultilineConfig>,
     retry_backoff_secs: u64,
+    container_events: bool,
 }
 
 impl Default for DockerLogsConfig {
@@ -77,6 +91,7 @@ impl Default for DockerLogsConfig {
             auto_partial_merge: true,
             multiline: None,
             retry_backoff_secs: 2,
+            container_events: false,
         }
     }
 }
@@ -248,15 +263,21 @@ impl DockerLogsSourceCore {
         // unpause | docker unpause
         // die    | docker restart, docker stop, docker kill, process exited, oom
         // pause  | docker pause
-        filters.insert(
-            "event".to_owned(),
-            vec![
-                "start".to_owned(),
-                "unpause".to_owned(),
-                "die".to_owned(),
-                "pause".to_owned(),
-            ],
-        );
+        // health_status | healthcheck status changed
+        let mut actions = vec![
+            "start".to_owned(),
+            "unpause".to_owned(),
+            "die".to_owned(),
+            "pause".to_owned(),
+            "health_status".to_owned(),
+        ];
+        // stop   | docker stop
+        // oom    | container ran out of memory
+        if self.config.container_events {
+            actions.push("stop".to_owned());
+            actions.push("oom".to_owned());
+        }
+        filters.insert("event".to_owned(), actions);
         filters.insert("type".to_owned(), vec!["container".to_owned()]);
 
         // Apply include filters
@@ -453,6 +474,42 @@ impl DockerLogsSource {
 
                             let id = ContainerId::new(id);
 
+                            // Health status changes are only tracked for watched containers.
+                            if let Some(status) = action.strip_prefix(HEALTH_STATUS_ACTION) {
+                                if let Some(state) = self.containers.get(&id) {
+                                    state.health.set(Some(status));
+                                }
+                                continue;
+                            }
+
+                            let included = self.containers.contains_key(&id)
+                                || (self.esb.core.config.container_name_or_id_included(
+                                    id.as_str(),
+                                    attributes.get("name").map(|s| s.as_str()),
+                                ) && !self.exclude_self(id.as_str()));
+
+                            if self.esb.core.config.container_events
+                                && included
+                                && ContainerEvent::is_lifecycle(&action)
+                            {
+                                let container_event = ContainerEvent {
+                                    id: &id,
+                                    action: &action,
+                                    attributes: &attributes,
+                                    time: event.time,
+                                    time_nano: event.time_nano,
+                                };
+                                let log_event = add_hostname(
+                                    container_event.into_log_event(),
+                                    &self.esb.host_key,
+                                    &self.hostname,
+                                );
+                                if let Err(error) = self.esb.out.send_event(Event::Log(log_event)).await {
+                                    emit!(&StreamClosedError { error, count: 1 });
+                                    return;
+                                }
+                            }
+
                             // Update container status
                             match action.as_str() {
                                 "die" | "pause" => {
@@ -464,18 +521,8 @@ impl DockerLogsSource {
                                     if let Some(state) = self.containers.get_mut(&id) {
                                         state.running();
                                         self.esb.restart(state);
-                                    } else {
-                                        let include_name =
-                                            self.esb.core.config.container_name_or_id_included(
-                                                id.as_str(),
-                                                attributes.get("name").map(|s| s.as_str()),
-                                            );
-
-                                        let exclude_self = self.exclude_self(id.as_str());
-
-                                        if include_name && !exclude_self {
-                                            self.containers.insert(id.clone(), self.esb.start(id, None));
-                                        }
+                                    } else if included {
+                                        self.containers.insert(id.clone(), self.esb.start(id, None));
                                     }
                                 }
                                 _ => {},
@@ -526,6 +573,8 @@ impl EventStreamBuilder {
     /// Spawn a task to runs event stream until shutdown.
     fn start(&self, id: ContainerId, backoff: Option<Duration>) -> ContainerState {
         let this = self.clone();
+        let health = ContainerHealth::default();
+        let state = ContainerState::new_running(health.clone());
         tokio::spawn(async move {
             if let Some(duration) = backoff {
                 tokio::time::sleep(duration).await;
@@ -536,7 +585,7 @@ impl EventStreamBuilder {
                 .inspect_container(id.as_str(), None::<InspectContainerOptions>)
                 .await
             {
-                Ok(details) => match ContainerMetadata::from_details(details) {
+                Ok(details) => match ContainerMetadata::from_details(details, health) {
                     Ok(metadata) => {
                         let info = ContainerLogInfo::new(id, metadata, this.core.now_timestamp);
                         this.run_event_stream(info).await;
@@ -556,7 +605,7 @@ impl EventStreamBuilder {
             this.finish(Err((id, ErrorPersistence::Transient)));
         });
 
-        ContainerState::new_running()
+        state
     }
 
     /// If info is present, restarts event stream which will run until shutdown.
@@ -681,6 +730,83 @@ fn add_hostname(mut event: LogEvent, host_key: &str, hostname: &Option<String>)
     event
 }
 
+/// A container lifecycle event, emitted as a log event alongside the logs of the containers.
+struct ContainerEvent<'a> {
+    id: &'a ContainerId,
+    action: &'a str,
+    /// Attributes of the actor, holding the labels of the container along with its name, image
+    /// and, once it died, exit code.
+    attributes: &'a HashMap<String, String>,
+    time: Option<i64>,
+    time_nano: Option<i64>,
+}
+
+impl<'a> ContainerEvent<'a> {
+    fn is_lifecycle(action: &str) -> bool {
+        matches!(action, "start" | "stop" | "die" | "oom")
+    }
+
+    fn into_log_event(self) -> LogEvent {
+        let mut log_event = LogEvent::default();
+
+        log_event.insert(log_schema().source_type_key(), Bytes::from("docker"));
+
+        let name = self
+            .attributes
+            .get("name")
+            .map(String::as_str)
+            .unwrap_or_else(|| self.id.as_str());
+        let description = match self.action {
+            "start" => "started",
+            "stop" => "stopped",
+            "die" => "died",
+            "oom" => "ran out of memory",
+            action => action,
+        };
+        log_event.insert(
+            log_schema().message_key(),
+            format!("Container {} {}.", name, description),
+        );
+        log_event.insert(CONTAINER_EVENT, self.action.to_owned());
+
+        let timestamp = self
+            .time_nano
+            .map(|nanos| Utc.timestamp_nanos(nanos))
+            .or_else(|| self.time.map(|secs| Utc.timestamp(secs, 0)))
+            .unwrap_or_else(Utc::now);
+        log_event.insert(log_schema().timestamp_key(), timestamp);
+
+        log_event.insert(CONTAINER, self.id.0.clone());
+        if let Some(name) = self.attributes.get("name") {
+            log_event.insert(NAME, name.clone());
+        }
+        if let Some(image) = self.attributes.get("image") {
+            log_event.insert(IMAGE, image.clone());
+        }
+        if let Some(exit_code) = self
+            .attributes
+            .get("exitCode")
+            .and_then(|code| code.parse::<i64>().ok())
+        {
+            log_event.insert(EXIT_CODE, exit_code);
+        }
+        if let Some(project) = self.attributes.get(COMPOSE_PROJECT_LABEL) {
+            log_event.insert(COMPOSE_PROJECT, project.clone());
+        }
+        if let Some(service) = self.attributes.get(COMPOSE_SERVICE_LABEL) {
+            log_event.insert(COMPOSE_SERVICE, service.clone());
+        }
+
+        emit!(&DockerLogsEventsReceived {
+            byte_size: log_event.size_of(),
+            container_id: self.id.as_str(),
+            container_name: name,
+        });
+
+        log_event
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum ErrorPersistence {
     Transient,
@@ -702,6 +828,23 @@ impl ContainerId {
     }
 }
 
+/// Health status of a container, shared between main, which follows its changes, and the
+/// event_stream adding it to the log events.
+#[derive(Clone, Default)]
+struct ContainerHealth(Arc<Mutex<Option<Bytes>>>);
+
+impl ContainerHealth {
+    fn set(&self, status: Option<&str>) {
+        *self.0.lock().expect("health mutex poisoned") = status
+            .filter(|status| !status.is_empty() && *status != "none")
+            .map(|status| Bytes::copy_from_slice(status.as_bytes()));
+    }
+
+    fn get(&self) -> Option<Bytes> {
+        self.0.lock().expect("health mutex poisoned").clone()
+    }
+}
+
 /// Kept by main to keep track of container state
 struct ContainerState {
     /// None if there is a event_stream of this container.
@@ -710,15 +853,17 @@ struct ContainerState {
     running: bool,
     /// Of running
     generation: u64,
+    health: ContainerHealth,
 }
 
 impl ContainerState {
     /// It's ContainerLogInfo pair must be created exactly once.
-    const fn new_running() -> Self {
+    const fn new_running(health: ContainerHealth) -> Self {
         ContainerState {
             info: None,
             running: true,
             generation: 0,
+            health,
         }
     }
 
@@ -909,6 +1054,19 @@ impl ContainerLogInfo {
                 }
             }
 
+            // Compose project and service.
+            if let Some(project) = &self.metadata.compose_project {
+                log_event.insert(COMPOSE_PROJECT, project.clone());
+            }
+            if let Some(service) = &self.metadata.compose_service {
+                log_event.insert(COMPOSE_SERVICE, service.clone());
+            }
+
+            // Health status, for containers with a healthcheck.
+            if let Some(health) = self.metadata.health.get() {
+                log_event.insert(HEALTH, health);
+            }
+
             // Container name.
             log_event.insert(NAME, self.metadata.name.clone());
 
@@ -988,17 +1146,36 @@ struct ContainerMetadata {
     image: Value,
     /// created_at
     created_at: DateTime<Utc>,
+    /// com.docker.compose.project label
+    compose_project: Option<Value>,
+    /// com.docker.compose.service label
+    compose_service: Option<Value>,
+    /// health status, kept up to date by main
+    health: ContainerHealth,
 }
 
 impl ContainerMetadata {
-    fn from_details(details: ContainerInspectResponse) -> Result<Self, ParseError> {
+    fn from_details(
+        details: ContainerInspectResponse,
+        health: ContainerHealth,
+    ) -> Result<Self, ParseError> {
         let config = details.config.unwrap();
         let name = details.name.unwrap();
         let created = details.created.unwrap();
 
         let labels = config.labels.unwrap_or_default();
 
+        let status = details
+            .state
+            .and_then(|state| state.health)
+            .and_then(|health| health.status)
+            .map(|status| status.to_string());
+        health.set(status.as_deref());
+
         Ok(ContainerMetadata {
+            compose_project: labels.get(COMPOSE_PROJECT_LABEL).cloned().map(Into::into),
+            compose_service: labels.get(COMPOSE_SERVICE_LABEL).cloned().map(Into::into),
+            health,
             labels,
             name: name.as_str().trim_start_matches('/').to_owned().into(),
             name_str: name,
@@ -1054,6 +1231,90 @@ mod tests {
         source.hostname = Some("a".to_owned());
         assert!(!source.exclude_self("a29d569bd46c"));
     }
+
+    #[test]
+    fn container_event() {
+        let id = ContainerId::new("a29d569bd46c".to_owned());
+        let attributes = vec![
+            ("name", "vector_test_event"),
+            ("image", "busybox"),
+            ("exitCode", "137"),
+            (COMPOSE_PROJECT_LABEL, "shop"),
+            (COMPOSE_SERVICE_LABEL, "web"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+
+        let log = ContainerEvent {
+            id: &id,
+            action: "die",
+            attributes: &attributes,
+            time: Some(1_600_000_000),
+            time_nano: Some(1_600_000_000_123_000_000),
+        }
+        .into_log_event();
+
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Container vector_test_event died.".into()
+        );
+        assert_eq!(log[CONTAINER_EVENT], "die".into());
+        assert_eq!(log[CONTAINER], "a29d569bd46c".into());
+        assert_eq!(log[NAME], "vector_test_event".into());
+        assert_eq!(log[IMAGE], "busybox".into());
+        assert_eq!(log[EXIT_CODE], 137.into());
+        assert_eq!(log[COMPOSE_PROJECT], "shop".into());
+        assert_eq!(log[COMPOSE_SERVICE], "web".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_600_000_000, 123_000_000).into()
+        );
+        assert_eq!(log[log_schema().source_type_key()], "docker".into());
+    }
+
+    #[test]
+    fn container_metadata_compose_and_health() {
+        use bollard::service::{ContainerConfig, Health, HealthStatusEnum};
+
+        let labels = vec![
+            (COMPOSE_PROJECT_LABEL, "shop"),
+            (COMPOSE_SERVICE_LABEL, "web"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+        let details = ContainerInspectResponse {
+            name: Some("/shop_web_1".to_owned()),
+            created: Some("2020-10-03T16:11:29.443232Z".to_owned()),
+            config: Some(ContainerConfig {
+                image: Some("busybox".to_owned()),
+                labels: Some(labels),
+                ..Default::default()
+            }),
+            state: Some(bollard::service::ContainerState {
+                health: Some(Health {
+                    status: Some(HealthStatusEnum::STARTING),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let health = ContainerHealth::default();
+        let metadata = ContainerMetadata::from_details(details, health.clone()).unwrap();
+        assert_eq!(metadata.compose_project, Some("shop".into()));
+        assert_eq!(metadata.compose_service, Some("web".into()));
+        assert_eq!(metadata.health.get(), Some(Bytes::from("starting")));
+
+        // Updates made by main are seen by the event stream.
+        health.set(Some("healthy"));
+        assert_eq!(metadata.health.get(), Some(Bytes::from("healthy")));
+
+        health.set(Some("none"));
+        assert_eq!(metadata.health.get(), None);
+    }
 }
 
 #[cfg(all(test, feature = "docker-logs-integration-tests"))]
@@ -1362,6 +1623,47 @@ mod integration_tests {
         );
     }
 
+    #[tokio::test]
+    async fn container_events() {
+        trace_init();
+
+        let message = "log container_events";
+        let name = "vector_test_container_events";
+
+        let out = source_with_config(DockerLogsConfig {
+            include_containers: Some(vec![name.to_owned()]),
+            container_events: true,
+            ..DockerLogsConfig::default()
+        });
+
+        let docker = docker(None, None).unwrap();
+
+        let id = container_log_n(1, name, None, message, &docker).await;
+        // The log, and the start and death of the container.
+        let events = collect_n(out, 3).await;
+        container_remove(&id, &docker).await;
+
+        let (lifecycle, logs): (Vec<_>, Vec<_>) = events
+            .iter()
+            .map(Event::as_log)
+            .partition(|log| log.contains(super::CONTAINER_EVENT));
+
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0][log_schema().message_key()], message.into());
+
+        let mut actions = lifecycle
+            .iter()
+            .map(|log| log[super::CONTAINER_EVENT].to_string_lossy())
+            .collect::<Vec<_>>();
+        actions.sort();
+        assert_eq!(actions, vec!["die".to_owned(), "start".to_owned()]);
+        for log in lifecycle {
+            assert_eq!(log[super::CONTAINER], id.as_str().into());
+            assert_eq!(log[super::NAME], name.into());
+            assert_eq!(log[super::IMAGE], "busybox".into());
+        }
+    }
+
     #[tokio::test]
     async fn restart() {
         trace_init();
@@ -12,7 +12,7 @@ use smallvec::SmallVec;
 use snafu::Snafu;
 use tokio::{
     io::{AsyncRead, BufReader},
-    process::Command,
+    process::{Child, Command},
     sync::mpsc::{channel, Sender},
     time::{self, sleep, Duration, Instant},
 };
@@ -50,6 +50,8 @@ pub struct ExecConfig {
     pub working_directory: Option<PathBuf>,
     #[serde(default = "default_include_stderr")]
     pub include_stderr: bool,
+    pub separate_stderr: bool,
+    pub include_exit_events: bool,
     #[serde(default = "default_maximum_buffer_size")]
     pub maximum_buffer_size_bytes: usize,
     #[serde(default = "default_framing_stream_based")]
@@ -81,6 +83,10 @@ pub struct StreamingConfig {
     respawn_on_exit: bool,
     #[serde(default = "default_respawn_interval_secs")]
     respawn_interval_secs: u64,
+    #[serde(default)]
+    max_respawn_interval_secs: Option<u64>,
+    #[serde(default)]
+    graceful_shutdown_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Snafu)]
@@ -89,6 +95,12 @@ pub enum ExecConfigError {
     CommandEmpty,
     #[snafu(display("The maximum buffer size must be greater than zero"))]
     ZeroBuffer,
+    #[snafu(display(
+        "The maximum respawn interval must be greater than or equal to the respawn interval"
+    ))]
+    MaxRespawnIntervalTooShort,
+    #[snafu(display("Stderr can only be separated when it is included"))]
+    SeparateStderrNotIncluded,
 }
 
 impl Default for ExecConfig {
@@ -102,6 +114,8 @@ impl Default for ExecConfig {
             command: vec!["echo".to_owned(), "Hello World!".to_owned()],
             working_directory: None,
             include_stderr: default_include_stderr(),
+            separate_stderr: false,
+            include_exit_events: false,
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
@@ -140,6 +154,9 @@ const STDERR: &str = "stderr";
 const STREAM_KEY: &str = "stream";
 const PID_KEY: &str = "pid";
 const COMMAND_KEY: &str = "command";
+const EXIT_CODE_KEY: &str = "exit_code";
+const SIGNAL_KEY: &str = "signal";
+const EXIT_STATUS_TIMEOUT: Duration = Duration::from_secs(1);
 
 inventory::submit! {
     SourceDescription::new::<ExecConfig>("exec")
@@ -153,6 +170,13 @@ impl ExecConfig {
             Err(ExecConfigError::CommandEmpty)
         } else if self.maximum_buffer_size_bytes == 0 {
             Err(ExecConfigError::ZeroBuffer)
+        } else if self
+            .max_respawn_interval_secs_or_default()
+            .map_or(false, |max| max < self.respawn_interval_secs_or_default())
+        {
+            Err(ExecConfigError::MaxRespawnIntervalTooShort)
+        } else if self.separate_stderr && !self.include_stderr {
+            Err(ExecConfigError::SeparateStderrNotIncluded)
         } else {
             Ok(())
         }
@@ -182,6 +206,20 @@ impl ExecConfig {
             Some(config) => config.respawn_interval_secs,
         }
     }
+
+    const fn max_respawn_interval_secs_or_default(&self) -> Option<u64> {
+        match &self.streaming {
+            None => None,
+            Some(config) => config.max_respawn_interval_secs,
+        }
+    }
+
+    fn graceful_shutdown_timeout(&self) -> Option<Duration> {
+        self.streaming
+            .as_ref()
+            .and_then(|config| config.graceful_shutdown_timeout_secs)
+            .map(Duration::from_secs)
+    }
 }
 
 #[async_trait::async_trait]
@@ -190,7 +228,7 @@ impl SourceConfig for ExecConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         self.validate()?;
         let hostname = get_hostname();
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         match &self.mode {
             Mode::Scheduled => {
                 let exec_interval_secs = self.exec_interval_secs_or_default();
@@ -222,7 +260,11 @@ impl SourceConfig for ExecConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(DataType::Log)]
+        let mut outputs = vec![Output::default(DataType::Log)];
+        if self.separate_stderr {
+            outputs.push(Output::from((STDERR, DataType::Log)));
+        }
+        outputs
     }
 
     fn source_type(&self) -> &'static str {
@@ -294,27 +336,31 @@ async fn run_streaming(
     out: SourceSender,
 ) -> Result<(), ()> {
     if respawn_on_exit {
-        let duration = Duration::from_secs(respawn_interval_secs);
+        let initial_duration = Duration::from_secs(respawn_interval_secs);
+        let max_duration = config
+            .max_respawn_interval_secs_or_default()
+            .map(Duration::from_secs);
+        let mut duration = initial_duration;
 
         // Continue to loop while not shutdown
         loop {
-            tokio::select! {
-                _ = shutdown.clone() => break, // will break early if a shutdown is started
-                output = run_command(
-                    config.clone(),
-                    hostname.clone(),
-                    decoder.clone(),
-                    shutdown.clone(),
-                    out.clone()
-                ) => {
-                    // handle command finished
-                    if let Err(command_error) = output {
-                        emit!(&ExecFailedError {
-                            command: config.command_line().as_str(),
-                            error: command_error,
-                        });
-                    }
-                }
+            let start = Instant::now();
+            // The command ends on its own once a shutdown is started, so that it can be stopped
+            // gracefully.
+            let output = run_command(
+                config.clone(),
+                hostname.clone(),
+                decoder.clone(),
+                shutdown.clone(),
+                out.clone(),
+            )
+            .await;
+            // handle command finished
+            if let Err(command_error) = output {
+                emit!(&ExecFailedError {
+                    command: config.command_line().as_str(),
+                    error: command_error,
+                });
             }
 
             let mut poll_shutdown = shutdown.clone();
@@ -322,10 +368,22 @@ async fn run_streaming(
                 warn!("Streaming process ended before shutdown.");
             }
 
+            // A process which ran for longer than the maximum interval is considered to have been
+            // healthy, and is respawned without delay build up from earlier exits.
+            if let Some(max_duration) = max_duration {
+                if start.elapsed() >= max_duration {
+                    duration = initial_duration;
+                }
+            }
+
             tokio::select! {
                 _ = &mut poll_shutdown => break, // will break early if a shutdown is started
                 _ = sleep(duration) => debug!("Restarting streaming process."),
             }
+
+            if let Some(max_duration) = max_duration {
+                duration = next_respawn_interval(duration, max_duration);
+            }
         }
     } else {
         let output = run_command(config.clone(), hostname, decoder, shutdown, out).await;
@@ -341,6 +399,11 @@ async fn run_streaming(
     Ok(())
 }
 
+/// Doubles the respawn interval after each exit, up to the maximum.
+fn next_respawn_interval(duration: Duration, max_duration: Duration) -> Duration {
+    duration.saturating_mul(2).min(max_duration)
+}
+
 async fn run_command(
     config: ExecConfig,
     hostname: Option<String>,
@@ -397,7 +460,12 @@ async fn run_command(
         for event in &mut events {
             handle_event(&config, &hostname, &Some(stream.to_string()), pid, event);
         }
-        if let Err(error) = out.send_batch(events).await {
+        let sent = if config.separate_stderr && stream == STDERR {
+            out.send_batch_named(STDERR, events).await
+        } else {
+            out.send_batch(events).await
+        };
+        if let Err(error) = sent {
             emit!(&StreamClosedError { count, error });
             break;
         }
@@ -405,7 +473,19 @@ async fn run_command(
 
     let elapsed = start.elapsed();
 
-    let result = match child.try_wait() {
+    let mut poll_shutdown = shutdown.clone();
+    let exit_status = if futures::poll!(&mut poll_shutdown).is_ready() {
+        stop_child(&mut child, config.graceful_shutdown_timeout()).await
+    } else {
+        // The process is most likely exiting since it closed its output, so give it a moment to
+        // do so.
+        match time::timeout(EXIT_STATUS_TIMEOUT, child.wait()).await {
+            Ok(exit_status) => exit_status.map(Some),
+            Err(_) => child.try_wait(),
+        }
+    };
+
+    let result = match exit_status {
         Ok(Some(exit_status)) => {
             handle_exit_status(&config, exit_status.code(), elapsed);
             Ok(Some(exit_status))
@@ -422,11 +502,90 @@ async fn run_command(
         }
     };
 
+    if config.include_exit_events {
+        let exit_status = result.as_ref().ok().copied().flatten();
+        let event = exit_event(&config, &hostname, pid, exit_status);
+        if let Err(error) = out.send_event(event).await {
+            emit!(&StreamClosedError { count: 1, error });
+        }
+    }
+
     debug!("Finished command run.");
 
     result
 }
 
+/// Stops the process once a shutdown is started. If a timeout is given, the process is first sent a
+/// `SIGTERM` and given that long to exit, otherwise it is killed when dropped.
+async fn stop_child(
+    child: &mut Child,
+    graceful_shutdown_timeout: Option<Duration>,
+) -> std::io::Result<Option<ExitStatus>> {
+    if let Some(exit_status) = child.try_wait()? {
+        return Ok(Some(exit_status));
+    }
+
+    if let Some(timeout) = graceful_shutdown_timeout {
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+            use nix::{
+                sys::signal::{kill, Signal},
+                unistd::Pid,
+            };
+
+            debug!(message = "Sending SIGTERM to process.", %pid);
+            if let Err(error) = kill(Pid::from_raw(pid as i32), Signal::SIGTERM) {
+                warn!(message = "Unable to send SIGTERM to process.", %pid, %error);
+            }
+        }
+
+        match time::timeout(timeout, child.wait()).await {
+            Ok(exit_status) => return exit_status.map(Some),
+            Err(_) => warn!(
+                message = "Process did not exit before the graceful shutdown timeout, killing it.",
+                timeout_secs = %timeout.as_secs()
+            ),
+        }
+    }
+
+    Ok(None)
+}
+
+fn exit_event(
+    config: &ExecConfig,
+    hostname: &Option<String>,
+    pid: Option<u32>,
+    exit_status: Option<ExitStatus>,
+) -> Event {
+    let code = exit_status.and_then(|exit_status| exit_status.code());
+    #[cfg(unix)]
+    let signal = exit_status.and_then(|exit_status| {
+        use std::os::unix::process::ExitStatusExt;
+        exit_status.signal()
+    });
+    #[cfg(not(unix))]
+    let signal: Option<i32> = None;
+
+    let message = match (code, signal) {
+        (Some(code), _) => format!("Command exited with code {}.", code),
+        (None, Some(signal)) => format!("Command was terminated by signal {}.", signal),
+        (None, None) => "Command exited with an unknown status.".to_owned(),
+    };
+
+    let mut event = Event::from(Bytes::from(message));
+    handle_event(config, hostname, &None, pid, &mut event);
+
+    let log = event.as_mut_log();
+    if let Some(code) = code {
+        log.insert_flat(EXIT_CODE_KEY, i64::from(code));
+    }
+    if let Some(signal) = signal {
+        log.insert_flat(SIGNAL_KEY, i64::from(signal));
+    }
+
+    event
+}
+
 fn handle_exit_status(config: &ExecConfig, exit_status: Option<i32>, exec_duration: Duration) {
     emit!(&ExecCommandExecuted {
         command: config.command_line().as_str(),
@@ -544,7 +703,10 @@ mod tests {
     use futures::task::Poll;
 
     use super::*;
-    use crate::test_util::trace_init;
+    use crate::{
+        event::{into_event_stream, EventStatus},
+        test_util::trace_init,
+    };
 
     #[test]
     fn test_generate_config() {
@@ -591,6 +753,52 @@ mod tests {
         assert!(log.get(log_schema().timestamp_key()).is_some());
     }
 
+    #[test]
+    fn test_validate() {
+        let mut config = standard_streaming_test_config();
+        config.streaming = Some(StreamingConfig {
+            respawn_on_exit: true,
+            respawn_interval_secs: 5,
+            max_respawn_interval_secs: Some(1),
+            graceful_shutdown_timeout_secs: None,
+        });
+        assert_eq!(
+            config.validate(),
+            Err(ExecConfigError::MaxRespawnIntervalTooShort)
+        );
+
+        let mut config = standard_streaming_test_config();
+        config.include_stderr = false;
+        config.separate_stderr = true;
+        assert_eq!(
+            config.validate(),
+            Err(ExecConfigError::SeparateStderrNotIncluded)
+        );
+    }
+
+    #[test]
+    fn test_outputs() {
+        let mut config = standard_streaming_test_config();
+        assert_eq!(config.outputs().len(), 1);
+
+        config.separate_stderr = true;
+        let outputs = config.outputs();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[1].port, Some(STDERR.to_owned()));
+    }
+
+    #[test]
+    fn test_next_respawn_interval() {
+        let max = Duration::from_secs(60);
+        let intervals = std::iter::successors(Some(Duration::from_secs(5)), |&duration| {
+            Some(next_respawn_interval(duration, max))
+        })
+        .take(6)
+        .map(|duration| duration.as_secs())
+        .collect::<Vec<_>>();
+        assert_eq!(intervals, vec![5, 10, 20, 40, 60, 60]);
+    }
+
     #[test]
     fn test_build_command() {
         let config = ExecConfig {
@@ -599,10 +807,14 @@ mod tests {
             streaming: Some(StreamingConfig {
                 respawn_on_exit: default_respawn_on_exit(),
                 respawn_interval_secs: default_respawn_interval_secs(),
+                max_respawn_interval_secs: None,
+                graceful_shutdown_timeout_secs: None,
             }),
             command: vec!["./runner".to_owned(), "arg1".to_owned(), "arg2".to_owned()],
             working_directory: Some(PathBuf::from("/tmp")),
             include_stderr: default_include_stderr(),
+            separate_stderr: false,
+            include_exit_events: false,
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
@@ -700,6 +912,92 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn test_run_command_exit_event_and_separate_stderr() {
+        trace_init();
+        let mut config = standard_scheduled_test_config();
+        config.command = vec![
+            "sh".to_owned(),
+            "-c".to_owned(),
+            "echo out; echo err >&2; exit 3".to_owned(),
+        ];
+        config.separate_stderr = true;
+        config.include_exit_events = true;
+        let (mut tx, rx) = SourceSender::new_test();
+        let mut stderr_rx = tx
+            .add_outputs(EventStatus::Delivered, STDERR.to_owned())
+            .flat_map(into_event_stream);
+
+        let exit_status = tokio::time::timeout(
+            time::Duration::from_secs(5),
+            run_command(
+                config.clone(),
+                None,
+                Default::default(),
+                ShutdownSignal::noop(),
+                tx,
+            ),
+        )
+        .await
+        .expect("command timed out")
+        .expect("command error");
+        assert_eq!(Some(3), exit_status.unwrap().code());
+
+        let events = rx.collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].as_log()[log_schema().message_key()], "out".into());
+        assert_eq!(events[0].as_log()[STREAM_KEY], STDOUT.into());
+
+        let exit_log = events[1].as_log();
+        assert_eq!(
+            exit_log[log_schema().message_key()],
+            "Command exited with code 3.".into()
+        );
+        assert_eq!(exit_log[EXIT_CODE_KEY], 3.into());
+        assert_eq!(exit_log[COMMAND_KEY], config.command.into());
+        assert!(exit_log.get(PID_KEY).is_some());
+        assert!(exit_log.get(STREAM_KEY).is_none());
+
+        let stderr_event = stderr_rx.next().await.expect("no stderr event");
+        let log = stderr_event.as_log();
+        assert_eq!(log[log_schema().message_key()], "err".into());
+        assert_eq!(log[STREAM_KEY], STDERR.into());
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_run_command_graceful_shutdown() {
+        trace_init();
+        let mut config = standard_streaming_test_config();
+        config.command = vec![
+            "sh".to_owned(),
+            "-c".to_owned(),
+            "trap 'exit 7' TERM; echo ready; while true; do sleep 0.1; done".to_owned(),
+        ];
+        config.streaming = Some(StreamingConfig {
+            respawn_on_exit: false,
+            respawn_interval_secs: default_respawn_interval_secs(),
+            max_respawn_interval_secs: None,
+            graceful_shutdown_timeout_secs: Some(5),
+        });
+        let (trigger, shutdown, _tripwire) = ShutdownSignal::new_wired();
+        let (tx, mut rx) = SourceSender::new_test();
+
+        let command = tokio::spawn(run_command(config, None, Default::default(), shutdown, tx));
+
+        let event = rx.next().await.expect("no event");
+        assert_eq!(event.as_log()[log_schema().message_key()], "ready".into());
+        drop(trigger);
+
+        let exit_status = tokio::time::timeout(time::Duration::from_secs(5), command)
+            .await
+            .expect("command timed out")
+            .unwrap()
+            .expect("command error");
+        assert_eq!(Some(7), exit_status.unwrap().code());
+    }
+
     fn standard_scheduled_test_config() -> ExecConfig {
         Default::default()
     }
@@ -711,10 +1009,14 @@ mod tests {
             streaming: Some(StreamingConfig {
                 respawn_on_exit: default_respawn_on_exit(),
                 respawn_interval_secs: default_respawn_interval_secs(),
+                max_respawn_interval_secs: None,
+                graceful_shutdown_timeout_secs: None,
             }),
             command: vec!["yes".to_owned()],
             working_directory: None,
             include_stderr: default_include_stderr(),
+            separate_stderr: false,
+            include_exit_events: false,
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
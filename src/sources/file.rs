@@ -1,4 +1,4 @@
-use std::{convert::TryInto, path::PathBuf, time::Duration};
+use std::{collections::HashMap, convert::TryInto, path::PathBuf, time::Duration};
 
 use bytes::Bytes;
 use chrono::Utc;
@@ -11,12 +11,15 @@ use futures::{
     stream::{Stream, StreamExt},
     FutureExt,
 };
+use glob::Pattern;
 use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use tokio::task::spawn_blocking;
 
-use super::util::{finalizer::OrderedFinalizer, EncodingConfig, MultilineConfig};
+use super::util::{
+    finalizer::OrderedFinalizer, multiline_config::MultilinePreset, EncodingConfig, MultilineConfig,
+};
 use crate::{
     config::{
         log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
@@ -61,6 +64,15 @@ enum BuildError {
         indicator: String,
         source: regex::Error,
     },
+    #[snafu(display(
+        "multiline_presets include {:?} is not a valid glob pattern: {}",
+        pattern,
+        source
+    ))]
+    InvalidMultilinePresetInclude {
+        pattern: PathBuf,
+        source: glob::PatternError,
+    },
 }
 
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
@@ -88,6 +100,7 @@ pub struct FileConfig {
     pub message_start_indicator: Option<String>,
     pub multi_line_timeout: u64, // millis
     pub multiline: Option<MultilineConfig>,
+    pub multiline_presets: Vec<MultilinePresetConfig>,
     pub max_read_bytes: usize,
     pub oldest_first: bool,
     #[serde(alias = "remove_after")]
@@ -98,6 +111,20 @@ pub struct FileConfig {
     acknowledgements: AcknowledgementsConfig,
 }
 
+/// Aggregates the lines of the files matching `include` with a preset, instead of `multiline`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MultilinePresetConfig {
+    pub include: Vec<PathBuf>,
+    pub preset: MultilinePreset,
+    #[serde(default = "default_multiline_preset_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+const fn default_multiline_preset_timeout_ms() -> u64 {
+    1000
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(tag = "strategy", rename_all = "snake_case")]
 pub enum FingerprintConfig {
@@ -192,6 +219,7 @@ impl Default for FileConfig {
             message_start_indicator: None,
             multi_line_timeout: 1000, // millis
             multiline: None,
+            multiline_presets: Vec::new(),
             max_read_bytes: 2048,
             oldest_first: false,
             remove_after_secs: None,
@@ -232,6 +260,13 @@ impl SourceConfig for FileConfig {
                 Regex::new(indicator)
                     .with_context(|_| InvalidMessageStartIndicatorSnafu { indicator })?;
             }
+
+            for preset in &self.multiline_presets {
+                for pattern in &preset.include {
+                    Pattern::new(&pattern.to_string_lossy())
+                        .with_context(|_| InvalidMultilinePresetIncludeSnafu { pattern })?;
+                }
+            }
         }
 
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
@@ -324,6 +359,7 @@ pub fn file_source(
     let include = config.include.clone();
     let exclude = config.exclude.clone();
     let multiline_config = config.multiline.clone();
+    let multiline_presets = config.multiline_presets.clone();
     let message_start_indicator = config.message_start_indicator.clone();
     let multi_line_timeout = config.multi_line_timeout;
     let checkpoints = checkpointer.view();
@@ -358,22 +394,21 @@ pub fn file_source(
                 line
             });
 
-        let messages: Box<dyn Stream<Item = Line> + Send + std::marker::Unpin> =
-            if let Some(ref multiline_config) = multiline_config {
-                wrap_with_line_agg(
-                    rx,
-                    multiline_config.try_into().unwrap(), // validated in build
-                )
-            } else if let Some(msi) = message_start_indicator {
-                wrap_with_line_agg(
-                    rx,
-                    line_agg::Config::for_legacy(
-                        Regex::new(&msi).unwrap(), // validated in build
-                        multi_line_timeout,
-                    ),
+        let fallback = if let Some(ref multiline_config) = multiline_config {
+            Some(multiline_config.try_into().unwrap()) // validated in build
+        } else {
+            message_start_indicator.map(|msi| {
+                line_agg::Config::for_legacy(
+                    Regex::new(&msi).unwrap(), // validated in build
+                    multi_line_timeout,
                 )
-            } else {
+            })
+        };
+        let messages: Box<dyn Stream<Item = Line> + Send + std::marker::Unpin> =
+            if multiline_presets.is_empty() && fallback.is_none() {
                 Box::new(rx)
+            } else {
+                wrap_with_line_agg(rx, &multiline_presets, fallback)
             };
 
         // Once file server ends this will run until it has finished processing remaining
@@ -436,21 +471,73 @@ fn reconcile_position_options(
     }
 }
 
+/// Aggregates the lines of the files matching the include patterns of one of the presets with
+/// the first one, and those of the other files with `fallback`, if any.
 fn wrap_with_line_agg(
     rx: impl Stream<Item = Line> + Send + std::marker::Unpin + 'static,
-    config: line_agg::Config,
+    presets: &[MultilinePresetConfig],
+    fallback: Option<line_agg::Config>,
 ) -> Box<dyn Stream<Item = Line> + Send + std::marker::Unpin + 'static> {
-    let logic = line_agg::Logic::new(config);
-    Box::new(
-        LineAgg::new(
-            rx.map(|line| (line.filename, line.text, (line.file_id, line.offset))),
-            logic,
+    let patterns = presets
+        .iter()
+        .map(|preset| {
+            preset
+                .include
+                .iter()
+                .map(|pattern| Pattern::new(&pattern.to_string_lossy()).unwrap()) // validated in build
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let fallback_index = fallback.as_ref().map(|_| presets.len());
+    let mut configs = presets
+        .iter()
+        .map(|preset| preset.preset.config(preset.timeout_ms))
+        .collect::<Vec<_>>();
+    configs.extend(fallback);
+    let merges = presets
+        .iter()
+        .map(|preset| preset.preset)
+        .collect::<Vec<_>>();
+
+    // The configuration of each file is chosen once, and carried along with its lines.
+    let mut selected = HashMap::<String, Option<usize>>::new();
+    let rx = rx.map(move |line| {
+        let index = match selected.get(&line.filename) {
+            Some(index) => *index,
+            None => {
+                let index = patterns
+                    .iter()
+                    .position(|patterns| {
+                        patterns
+                            .iter()
+                            .any(|pattern| pattern.matches(&line.filename))
+                    })
+                    .or(fallback_index);
+                selected.insert(line.filename.clone(), index);
+                index
+            }
+        };
+        (
+            (line.filename, index),
+            line.text,
+            (line.file_id, line.offset),
         )
-        .map(|(filename, text, (file_id, offset))| Line {
-            text,
-            filename,
-            file_id,
-            offset,
+    });
+
+    let logic =
+        line_agg::Logic::with_configs(configs, |(_, index): &(String, Option<usize>)| *index);
+    Box::new(
+        LineAgg::new(rx, logic).map(move |((filename, index), text, (file_id, offset))| {
+            let text = match index.and_then(|index| merges.get(index)) {
+                Some(preset) => preset.merge(text),
+                None => text,
+            };
+            Line {
+                text,
+                filename,
+                file_id,
+                offset,
+            }
         }),
     )
 }
@@ -1378,6 +1465,65 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_multi_line_presets() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*")],
+            multiline_presets: vec![
+                MultilinePresetConfig {
+                    include: vec![dir.path().join("*-json.log")],
+                    preset: MultilinePreset::DockerJsonFile,
+                    timeout_ms: 25,
+                },
+                MultilinePresetConfig {
+                    include: vec![dir.path().join("java-*")],
+                    preset: MultilinePreset::Java,
+                    timeout_ms: 25,
+                },
+            ],
+            ..test_default_file_config(&dir)
+        };
+
+        let java_path = dir.path().join("java-app.log");
+        let docker_path = dir.path().join("abc-json.log");
+        let other_path = dir.path().join("other.log");
+        let received = run_file_source(&config, false, NoAcks, async {
+            let mut java_file = File::create(&java_path).unwrap();
+            let mut docker_file = File::create(&docker_path).unwrap();
+            let mut other_file = File::create(&other_path).unwrap();
+
+            sleep_500_millis().await; // The files must be observed at their original lengths before writing to them
+
+            writeln!(&mut java_file, "ERROR Request failed").unwrap();
+            writeln!(&mut java_file, "java.lang.IllegalStateException: boom").unwrap();
+            writeln!(&mut java_file, "\tat com.example.Foo.bar(Foo.java:10)").unwrap();
+            writeln!(&mut java_file, "INFO Next request").unwrap();
+            writeln!(&mut docker_file, r#"{{"log":"first ","stream":"stdout"}}"#).unwrap();
+            writeln!(&mut docker_file, r#"{{"log":"part\n","stream":"stdout"}}"#).unwrap();
+            writeln!(&mut docker_file, r#"{{"log":"whole\n","stream":"stdout"}}"#).unwrap();
+            writeln!(&mut other_file, "ERROR Request failed").unwrap();
+            writeln!(&mut other_file, "\tat com.example.Foo.bar(Foo.java:10)").unwrap();
+
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let mut received = extract_messages_string(received);
+        received.sort();
+        assert_eq!(
+            received,
+            vec![
+                "\tat com.example.Foo.bar(Foo.java:10)",
+                "ERROR Request failed",
+                "ERROR Request failed\njava.lang.IllegalStateException: boom\n\tat com.example.Foo.bar(Foo.java:10)",
+                "INFO Next request",
+                r#"{"log":"first part\n","stream":"stdout"}"#,
+                r#"{"log":"whole\n","stream":"stdout"}"#,
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_fair_reads() {
         let dir = tempdir().unwrap();
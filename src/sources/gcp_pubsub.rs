@@ -0,0 +1,528 @@
+//! The `gcp_pubsub` source pulls messages from a Google Cloud Pub/Sub subscription over a
+//! `StreamingPull` stream. Acknowledgement deadlines of messages still in flight are extended
+//! while they are pending, and messages are acknowledged once their events are delivered.
+
+use std::{collections::HashSet, time::Duration};
+
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{stream::FuturesUnordered, StreamExt};
+use http::uri::InvalidUri;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::codec::Decoder as _;
+use tonic::{
+    metadata::MetadataValue,
+    transport::{Channel, ClientTlsConfig, Endpoint},
+    Request, Status,
+};
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{
+        self,
+        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+    },
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, Value},
+    gcp::{GcpAuthConfig, GcpCredentials, Scope},
+    internal_events::{
+        BytesReceived, GcpPubsubConnectError, GcpPubsubEventsReceived, GcpPubsubStreamingPullError,
+        StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sources::util::AcknowledgementTimeout,
+    SourceSender,
+};
+
+#[allow(clippy::clone_on_ref_ptr)]
+mod proto {
+    tonic::include_proto!("google.pubsub.v1");
+}
+
+// The bounds Pub/Sub accepts for `stream_ack_deadline_seconds`.
+const MIN_ACK_DEADLINE_SECS: u16 = 10;
+const MAX_ACK_DEADLINE_SECS: u16 = 600;
+
+#[derive(Debug, Snafu)]
+enum PubsubError {
+    #[snafu(display("Invalid endpoint URI: {}", source))]
+    Uri { source: InvalidUri },
+    #[snafu(display("Could not set up endpoint TLS settings: {}", source))]
+    EndpointTls { source: tonic::transport::Error },
+    #[snafu(display(
+        "`ack_deadline_secs` must be between {} and {}, got {}",
+        MIN_ACK_DEADLINE_SECS,
+        MAX_ACK_DEADLINE_SECS,
+        ack_deadline_secs
+    ))]
+    InvalidAckDeadline { ack_deadline_secs: u16 },
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct PubsubConfig {
+    project: String,
+    subscription: String,
+    #[serde(default = "default_endpoint")]
+    #[derivative(Default(value = "default_endpoint()"))]
+    endpoint: String,
+    #[serde(default)]
+    skip_authentication: bool,
+    #[serde(flatten)]
+    auth: GcpAuthConfig,
+    /// How long the server waits for an acknowledgement before redelivering a message. The
+    /// deadline of messages whose events are still being delivered is extended at half this
+    /// interval.
+    #[serde(default = "default_ack_deadline_secs")]
+    #[derivative(Default(value = "default_ack_deadline_secs()"))]
+    ack_deadline_secs: u16,
+    #[serde(default = "default_retry_delay_secs")]
+    #[derivative(Default(value = "default_retry_delay_secs()"))]
+    retry_delay_secs: u64,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_endpoint() -> String {
+    "https://pubsub.googleapis.com".into()
+}
+
+const fn default_ack_deadline_secs() -> u16 {
+    60
+}
+
+const fn default_retry_delay_secs() -> u64 {
+    1
+}
+
+inventory::submit! {
+    SourceDescription::new::<PubsubConfig>("gcp_pubsub")
+}
+
+impl_generate_config_from_default!(PubsubConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "gcp_pubsub")]
+impl SourceConfig for PubsubConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if !(MIN_ACK_DEADLINE_SECS..=MAX_ACK_DEADLINE_SECS).contains(&self.ack_deadline_secs) {
+            return Err(PubsubError::InvalidAckDeadline {
+                ack_deadline_secs: self.ack_deadline_secs,
+            }
+            .into());
+        }
+
+        // We only need to load the credentials if we are not targeting an emulator.
+        let credentials = if self.skip_authentication {
+            None
+        } else {
+            self.auth.make_credentials(Scope::PubSub).await?
+        };
+        if let Some(credentials) = &credentials {
+            credentials.spawn_regenerate_token();
+        }
+
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone()).context(UriSnafu)?;
+        if endpoint.uri().scheme_str() == Some("https") {
+            let host = endpoint.uri().host().unwrap_or_default().to_owned();
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().domain_name(host))
+                .context(EndpointTlsSnafu)?;
+        }
+
+        let source = PubsubSource {
+            endpoint,
+            credentials,
+            api_key: self.auth.api_key.clone(),
+            subscription: format!(
+                "projects/{}/subscriptions/{}",
+                self.project, self.subscription
+            ),
+            client_id: uuid::Uuid::new_v4().to_string(),
+            decoder: DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+            acknowledgement_timeout: cx.acknowledgement_timeout,
+            ack_deadline_secs: self.ack_deadline_secs,
+            retry_delay: Duration::from_secs(self.retry_delay_secs),
+        };
+
+        Ok(Box::pin(source.run(cx.out, cx.shutdown)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "gcp_pubsub"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+
+    fn can_redeliver(&self) -> bool {
+        true
+    }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
+}
+
+/// What to do once a streaming pull ends.
+enum State {
+    RetryNow,
+    RetryDelay,
+    Shutdown,
+}
+
+struct PubsubSource {
+    endpoint: Endpoint,
+    credentials: Option<GcpCredentials>,
+    api_key: Option<String>,
+    subscription: String,
+    client_id: String,
+    decoder: codecs::Decoder,
+    acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
+    ack_deadline_secs: u16,
+    retry_delay: Duration,
+}
+
+impl PubsubSource {
+    async fn run(self, mut out: SourceSender, shutdown: ShutdownSignal) -> Result<(), ()> {
+        loop {
+            match self.run_once(&mut out, shutdown.clone()).await {
+                State::RetryNow => debug!("Retrying immediately."),
+                State::RetryDelay => {
+                    info!(
+                        timeout_secs = self.retry_delay.as_secs_f64(),
+                        "Retrying after timeout."
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(self.retry_delay) => (),
+                        _ = shutdown.clone() => break,
+                    }
+                }
+                State::Shutdown => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_once(&self, out: &mut SourceSender, shutdown: ShutdownSignal) -> State {
+        let channel = match self.endpoint.connect().await {
+            Ok(channel) => channel,
+            Err(error) => {
+                emit!(&GcpPubsubConnectError { error });
+                return State::RetryDelay;
+            }
+        };
+        let mut client = self.client(channel);
+
+        // The first request sets up the stream, later ones only carry acknowledgements and
+        // deadline modifications.
+        let (request_tx, request_rx) = mpsc::channel(16);
+        let initial = proto::StreamingPullRequest {
+            subscription: self.subscription.clone(),
+            stream_ack_deadline_seconds: self.ack_deadline_secs as i32,
+            client_id: self.client_id.clone(),
+            ..Default::default()
+        };
+        let requests =
+            futures::stream::once(async move { initial }).chain(ReceiverStream::new(request_rx));
+
+        let mut responses = match client.streaming_pull(requests).await {
+            Ok(response) => response.into_inner(),
+            Err(error) => {
+                emit!(&GcpPubsubStreamingPullError { error });
+                return State::RetryDelay;
+            }
+        };
+
+        // Ack IDs of the messages whose events haven't been delivered yet.
+        let mut outstanding = HashSet::new();
+        let mut pending = FuturesUnordered::new();
+        let mut extend_deadlines =
+            tokio::time::interval(Duration::from_secs(self.ack_deadline_secs as u64 / 2));
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => return State::Shutdown,
+                Some((status, ack_ids)) = pending.next() => {
+                    for ack_id in &ack_ids {
+                        outstanding.remove(ack_id);
+                    }
+                    finalize(&request_tx, status, ack_ids).await;
+                }
+                _ = extend_deadlines.tick(), if !outstanding.is_empty() => {
+                    let ack_ids = outstanding.iter().cloned().collect::<Vec<_>>();
+                    let seconds = vec![self.ack_deadline_secs as i32; ack_ids.len()];
+                    send_request(&request_tx, proto::StreamingPullRequest {
+                        modify_deadline_ack_ids: ack_ids,
+                        modify_deadline_seconds: seconds,
+                        ..Default::default()
+                    })
+                    .await;
+                }
+                response = responses.next() => match response {
+                    Some(Ok(response)) => {
+                        if let Some(properties) = &response.subscription_properties {
+                            trace!(
+                                message = "Received subscription properties.",
+                                message_ordering_enabled = properties.message_ordering_enabled,
+                                exactly_once_delivery_enabled = properties.exactly_once_delivery_enabled,
+                            );
+                        }
+
+                        let (events, ack_ids) = self.decode_response(response);
+                        if events.is_empty() {
+                            finalize(&request_tx, BatchStatus::Delivered, ack_ids).await;
+                            continue;
+                        }
+                        let count = events.len();
+
+                        if self.acknowledgements {
+                            let (batch, receiver) = BatchNotifier::new_with_receiver();
+                            let events = events
+                                .into_iter()
+                                .map(|event| event.with_batch_notifier(&batch));
+                            drop(batch);
+                            if let Err(error) = out.send_batch(events).await {
+                                emit!(&StreamClosedError { error, count });
+                                return State::Shutdown;
+                            }
+                            outstanding.extend(ack_ids.iter().cloned());
+                            let acknowledgement_timeout = self.acknowledgement_timeout;
+                            pending.push(async move {
+                                (acknowledgement_timeout.wait(receiver).await, ack_ids)
+                            });
+                        } else {
+                            if let Err(error) = out.send_batch(events).await {
+                                emit!(&StreamClosedError { error, count });
+                                return State::Shutdown;
+                            }
+                            finalize(&request_tx, BatchStatus::Delivered, ack_ids).await;
+                        }
+                    }
+                    Some(Err(error)) => {
+                        emit!(&GcpPubsubStreamingPullError { error });
+                        return State::RetryDelay;
+                    }
+                    // The server regularly closes streams, this isn't an error.
+                    None => return State::RetryNow,
+                },
+            }
+        }
+    }
+
+    fn client(
+        &self,
+        channel: Channel,
+    ) -> proto::subscriber_client::SubscriberClient<
+        tonic::codegen::InterceptedService<
+            Channel,
+            impl FnMut(Request<()>) -> Result<Request<()>, Status>,
+        >,
+    > {
+        let credentials = self.credentials.clone();
+        let api_key = self.api_key.clone();
+        proto::subscriber_client::SubscriberClient::with_interceptor(
+            channel,
+            move |mut request: Request<()>| {
+                if let Some(credentials) = &credentials {
+                    let token = MetadataValue::from_str(&credentials.make_token())
+                        .map_err(|_| Status::unauthenticated("Invalid authentication token."))?;
+                    request.metadata_mut().insert("authorization", token);
+                } else if let Some(api_key) = &api_key {
+                    let key = MetadataValue::from_str(api_key)
+                        .map_err(|_| Status::unauthenticated("Invalid API key."))?;
+                    request.metadata_mut().insert("x-goog-api-key", key);
+                }
+                Ok(request)
+            },
+        )
+    }
+
+    /// Decodes the messages of a response into log events, and returns them along with the
+    /// ack IDs of the messages. Messages that can't be decoded are acknowledged along with
+    /// the rest, as redelivering them wouldn't make them decodable.
+    fn decode_response(&self, response: proto::StreamingPullResponse) -> (Vec<Event>, Vec<String>) {
+        let mut events = Vec::new();
+        let mut ack_ids = Vec::with_capacity(response.received_messages.len());
+
+        // Messages sharing an ordering key are delivered in publish order, and are sent on in
+        // the same order.
+        for received in response.received_messages {
+            ack_ids.push(received.ack_id);
+            if let Some(message) = received.message {
+                emit!(&BytesReceived {
+                    byte_size: message.data.len(),
+                    protocol: "http",
+                });
+                if let Some(decoded) = decode_message(self.decoder.clone(), message) {
+                    emit!(&GcpPubsubEventsReceived {
+                        count: decoded.len(),
+                        byte_size: decoded.size_of(),
+                    });
+                    events.extend(decoded);
+                }
+            }
+        }
+
+        (events, ack_ids)
+    }
+}
+
+async fn send_request(
+    request_tx: &mpsc::Sender<proto::StreamingPullRequest>,
+    request: proto::StreamingPullRequest,
+) {
+    // The stream is only gone once its responses ended, which is handled there.
+    if request_tx.send(request).await.is_err() {
+        debug!("Streaming pull closed before the request could be sent.");
+    }
+}
+
+/// Acknowledges messages once their events were delivered. Other messages are made available
+/// for redelivery right away by setting their deadline to zero, after which Pub/Sub also
+/// redelivers the messages following them with the same ordering key. Subscriptions with a
+/// dead letter policy forward them to the dead letter topic once the maximum number of
+/// delivery attempts is reached.
+async fn finalize(
+    request_tx: &mpsc::Sender<proto::StreamingPullRequest>,
+    status: BatchStatus,
+    ack_ids: Vec<String>,
+) {
+    if ack_ids.is_empty() {
+        return;
+    }
+    let request = match status {
+        BatchStatus::Delivered => proto::StreamingPullRequest {
+            ack_ids,
+            ..Default::default()
+        },
+        BatchStatus::Errored | BatchStatus::Rejected => proto::StreamingPullRequest {
+            modify_deadline_seconds: vec![0; ack_ids.len()],
+            modify_deadline_ack_ids: ack_ids,
+            ..Default::default()
+        },
+    };
+    send_request(request_tx, request).await;
+}
+
+/// Decodes the data of a message into log events annotated with its metadata. Returns `None`
+/// if the data can't be decoded.
+fn decode_message(
+    mut decoder: codecs::Decoder,
+    message: proto::PubsubMessage,
+) -> Option<Vec<Event>> {
+    let mut events = Vec::new();
+    let mut bytes = BytesMut::from(message.data.as_slice());
+    loop {
+        match decoder.decode_eof(&mut bytes) {
+            Ok(Some((next, _))) => events.extend(next),
+            Ok(None) => break,
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    return None;
+                }
+            }
+        }
+    }
+
+    let timestamp = message
+        .publish_time
+        .and_then(publish_time)
+        .unwrap_or_else(Utc::now);
+    let attributes = Value::Object(
+        message
+            .attributes
+            .into_iter()
+            .map(|(key, value)| (key, Value::from(value)))
+            .collect(),
+    );
+
+    for event in &mut events {
+        if let Event::Log(log) = event {
+            log.insert(log_schema().source_type_key(), Bytes::from("gcp_pubsub"));
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert("message_id", message.message_id.clone());
+            log.insert("attributes", attributes.clone());
+            if !message.ordering_key.is_empty() {
+                log.insert("ordering_key", message.ordering_key.clone());
+            }
+        }
+    }
+
+    Some(events)
+}
+
+fn publish_time(timestamp: prost_types::Timestamp) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(timestamp.seconds, timestamp.nanos as u32)
+        .single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codecs::decoding::BytesDeserializerConfig;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PubsubConfig>();
+    }
+
+    #[test]
+    fn decodes_message() {
+        let decoder = DecodingConfig::new(
+            default_framing_message_based(),
+            BytesDeserializerConfig::new().into(),
+        )
+        .build()
+        .unwrap();
+        let message = proto::PubsubMessage {
+            data: b"hello".to_vec(),
+            attributes: vec![("service".to_owned(), "api".to_owned())]
+                .into_iter()
+                .collect(),
+            message_id: "1234".to_owned(),
+            publish_time: Some(prost_types::Timestamp {
+                seconds: 1_600_000_000,
+                nanos: 0,
+            }),
+            ordering_key: "user-1".to_owned(),
+        };
+
+        let events = decode_message(decoder, message).unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1_600_000_000, 0).into()
+        );
+        assert_eq!(log["message_id"], "1234".into());
+        assert_eq!(log["attributes.service"], "api".into());
+        assert_eq!(log["ordering_key"], "user-1".into());
+    }
+}
@@ -92,7 +92,7 @@ impl HttpSource for LogplexSource {
 #[typetag::serde(name = "heroku_logs")]
 impl SourceConfig for LogplexConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
         let source = LogplexSource {
             query_parameters: self.query_parameters.clone(),
             decoder,
@@ -123,6 +123,10 @@ impl SourceConfig for LogplexConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
 // Add a compatibility alias to avoid breaking existing configs
@@ -151,6 +155,10 @@ impl SourceConfig for LogplexCompatConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
 fn decode_message(
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     io::{self, Read},
     num::ParseIntError,
     path::{Path, PathBuf},
@@ -20,6 +21,13 @@ use crate::event::metric::Metric;
 
 const MICROSECONDS: f64 = 1.0 / 1_000_000.0;
 
+/// The period of the CPU quota when `cpu.max` doesn't specify one, in microseconds.
+const DEFAULT_CPU_PERIOD: u64 = 100_000;
+
+/// The suffixes of the names of the cgroups created by systemd for its units.
+const SYSTEMD_UNIT_SUFFIXES: [&str; 6] =
+    [".service", ".scope", ".slice", ".socket", ".mount", ".swap"];
+
 #[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
 #[derivative(Default)]
 #[serde(default)]
@@ -28,6 +36,8 @@ pub(super) struct CGroupsConfig {
     levels: usize,
     pub(super) base: Option<PathBuf>,
     groups: FilterList,
+    units: FilterList,
+    pub(super) container_limits: bool,
 }
 
 #[derive(Debug, Snafu)]
@@ -72,64 +82,17 @@ impl HostMetrics {
         buffer: &'a mut String,
     ) -> BoxFuture<'a, ()> {
         Box::pin(async move {
-            let tags = btreemap! {
-                "cgroup" => cgroup.name.to_string_lossy(),
-                "collector" => "cgroups",
-            };
-            if let Some(cpu) = filter_result_sync(
-                cgroup.load_cpu(buffer).await,
-                "Failed to load cgroups CPU statistics.",
-            ) {
-                result.push(self.counter(
-                    "cgroup_cpu_usage_seconds_total",
-                    now,
-                    cpu.usage_usec as f64 * MICROSECONDS,
-                    tags.clone(),
-                ));
-                result.push(self.counter(
-                    "cgroup_cpu_user_seconds_total",
-                    now,
-                    cpu.user_usec as f64 * MICROSECONDS,
-                    tags.clone(),
-                ));
-                result.push(self.counter(
-                    "cgroup_cpu_system_seconds_total",
-                    now,
-                    cpu.system_usec as f64 * MICROSECONDS,
-                    tags.clone(),
-                ));
-            }
-
-            if cgroup.has_memory_controller && !cgroup.is_root() {
-                if let Some(current) = filter_result_sync(
-                    cgroup.load_memory_current(buffer).await,
-                    "Failed to load cgroups current memory.",
-                ) {
-                    result.push(self.gauge(
-                        "cgroup_memory_current_bytes",
-                        now,
-                        current as f64,
-                        tags.clone(),
-                    ));
-                }
-
-                if let Some(stat) = filter_result_sync(
-                    cgroup.load_memory_stat(buffer).await,
-                    "Failed to load cgroups memory statistics.",
-                ) {
-                    result.push(self.gauge(
-                        "cgroup_memory_anon_bytes",
-                        now,
-                        stat.anon as f64,
-                        tags.clone(),
-                    ));
-                    result.push(self.gauge(
-                        "cgroup_memory_file_bytes",
-                        now,
-                        stat.file as f64,
-                        tags,
-                    ));
+            let unit = cgroup.unit();
+            if self.config.cgroups.units.contains_str(unit) {
+                let mut tags = btreemap! {
+                    "cgroup" => cgroup.name.to_string_lossy(),
+                    "collector" => "cgroups",
+                };
+                if let Some(unit) = unit {
+                    tags.insert("unit".into(), unit.into());
                 }
+                self.cgroup_metrics(result, now, &cgroup, tags, buffer)
+                    .await;
             }
 
             if level < self.config.cgroups.levels {
@@ -146,6 +109,156 @@ impl HostMetrics {
             }
         })
     }
+
+    async fn cgroup_metrics(
+        &self,
+        result: &mut Vec<Metric>,
+        now: DateTime<Utc>,
+        cgroup: &CGroup,
+        tags: BTreeMap<String, String>,
+        buffer: &mut String,
+    ) {
+        if let Some(cpu) = filter_result_sync(
+            cgroup.load_cpu(buffer).await,
+            "Failed to load cgroups CPU statistics.",
+        ) {
+            result.push(self.counter(
+                "cgroup_cpu_usage_seconds_total",
+                now,
+                cpu.usage_usec as f64 * MICROSECONDS,
+                tags.clone(),
+            ));
+            result.push(self.counter(
+                "cgroup_cpu_user_seconds_total",
+                now,
+                cpu.user_usec as f64 * MICROSECONDS,
+                tags.clone(),
+            ));
+            result.push(self.counter(
+                "cgroup_cpu_system_seconds_total",
+                now,
+                cpu.system_usec as f64 * MICROSECONDS,
+                tags.clone(),
+            ));
+            // The periods are only counted for the cgroups with a quota.
+            if cpu.nr_periods > 0 {
+                result.push(self.counter(
+                    "cgroup_cpu_throttled_periods_total",
+                    now,
+                    cpu.nr_throttled as f64,
+                    tags.clone(),
+                ));
+                result.push(self.counter(
+                    "cgroup_cpu_throttled_seconds_total",
+                    now,
+                    cpu.throttled_usec as f64 * MICROSECONDS,
+                    tags.clone(),
+                ));
+            }
+        }
+
+        if let Some(cores) = cgroup.load_cpu_limit(buffer).await {
+            result.push(self.gauge("cgroup_cpu_limit_cores", now, cores, tags.clone()));
+        }
+
+        if cgroup.has_memory_controller && !cgroup.is_root() {
+            if let Some(current) = filter_result_sync(
+                cgroup.load_memory_current(buffer).await,
+                "Failed to load cgroups current memory.",
+            ) {
+                result.push(self.gauge(
+                    "cgroup_memory_current_bytes",
+                    now,
+                    current as f64,
+                    tags.clone(),
+                ));
+            }
+
+            if let Some(stat) = filter_result_sync(
+                cgroup.load_memory_stat(buffer).await,
+                "Failed to load cgroups memory statistics.",
+            ) {
+                result.push(self.gauge(
+                    "cgroup_memory_anon_bytes",
+                    now,
+                    stat.anon as f64,
+                    tags.clone(),
+                ));
+                result.push(self.gauge(
+                    "cgroup_memory_file_bytes",
+                    now,
+                    stat.file as f64,
+                    tags.clone(),
+                ));
+            }
+        }
+
+        if let Some(limit) = cgroup.load_memory_limit(buffer).await {
+            result.push(self.gauge("cgroup_memory_limit_bytes", now, limit as f64, tags.clone()));
+        }
+
+        if let Some(io) = filter_result_sync(
+            cgroup
+                .open_read_parse_optional::<IoStat>("io.stat", buffer)
+                .await,
+            "Failed to load cgroups I/O statistics.",
+        )
+        .flatten()
+        {
+            for stat in io.0 {
+                let mut tags = tags.clone();
+                tags.insert("device".into(), device_name(&stat.device));
+                result.push(self.counter(
+                    "cgroup_io_read_bytes_total",
+                    now,
+                    stat.rbytes as f64,
+                    tags.clone(),
+                ));
+                result.push(self.counter(
+                    "cgroup_io_written_bytes_total",
+                    now,
+                    stat.wbytes as f64,
+                    tags.clone(),
+                ));
+                result.push(self.counter(
+                    "cgroup_io_reads_total",
+                    now,
+                    stat.rios as f64,
+                    tags.clone(),
+                ));
+                result.push(self.counter("cgroup_io_writes_total", now, stat.wios as f64, tags));
+            }
+        }
+    }
+
+    /// The memory limit of the cgroup Vector runs in, with the memory it currently uses, if
+    /// `container_limits` is enabled.
+    pub(super) async fn container_memory(&self) -> Option<(u64, u64)> {
+        let cgroup = self.own_cgroup.as_ref()?;
+        let mut buffer = String::new();
+        let limit = cgroup.limits(&mut buffer).await.memory_bytes?;
+        let current = filter_result_sync(
+            cgroup
+                .open_read_parse_optional::<u64>("memory.current", &mut buffer)
+                .await,
+            "Failed to load cgroups current memory.",
+        )
+        .flatten()?;
+        Some((limit, current))
+    }
+
+    /// The CPU limit of the cgroup Vector runs in, if `container_limits` is enabled.
+    pub(super) async fn container_cpu_metrics(&self) -> Vec<Metric> {
+        let mut buffer = String::new();
+        let cores = match &self.own_cgroup {
+            Some(cgroup) => cgroup.limits(&mut buffer).await.cpu_cores,
+            None => None,
+        };
+        cores
+            .map(|cores| self.gauge("cpu_limit_cores", Utc::now(), cores, BTreeMap::default()))
+            .into_iter()
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -158,7 +271,7 @@ pub(super) struct CGroup {
 const CGROUP_CONTROLLERS: &str = "cgroup.controllers";
 
 impl CGroup {
-    pub(super) fn root<P: AsRef<Path>>(base_group: Option<P>) -> Option<CGroup> {
+    pub(super) fn root() -> Option<CGroup> {
         // There are three standard possibilities for cgroups setups
         // (`BASE` below is normally `/sys/fs/cgroup`, but containers
         // sometimes have `/sys` mounted elsewhere):
@@ -211,28 +324,115 @@ impl CGroup {
             );
         }
 
-        match base_group {
-            Some(group) => {
-                let group = group.as_ref();
-                let root = join_path(base_dir, group);
+        Some(CGroup {
+            root: base_dir,
+            name: "/".into(),
+            has_memory_controller,
+        })
+    }
+
+    /// The cgroup with the given name, relative to this one, or this one without any name.
+    pub(super) fn group(&self, name: Option<&Path>) -> Option<CGroup> {
+        match name {
+            Some(name) => {
+                let root = join_path(&self.root, name);
                 is_dir(&root).then(|| CGroup {
                     root,
-                    name: group.into(),
-                    has_memory_controller,
+                    name: join_name(&self.name, name),
+                    has_memory_controller: self.has_memory_controller,
                 })
             }
-            None => Some(CGroup {
-                root: base_dir,
-                name: "/".into(),
-                has_memory_controller,
-            }),
+            None => Some(self.clone()),
         }
     }
 
+    /// The cgroup the Vector process runs in, as listed in `/proc/self/cgroup`, relative to this
+    /// root. Within a cgroup namespace, as in most containers, this is the root itself.
+    pub(super) fn own(&self) -> Option<CGroup> {
+        let filename = join_path(heim::os::linux::procfs_root(), "self/cgroup");
+        let content = std::fs::read_to_string(&filename)
+            .map_err(
+                |error| error!(message = "Could not load the cgroup of the process.", %error, ?filename),
+            )
+            .ok()?;
+        let name = content
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))?
+            .trim()
+            .trim_start_matches('/');
+        if name.is_empty() {
+            Some(self.clone())
+        } else {
+            self.group(Some(Path::new(name)))
+        }
+    }
+
+    fn parent(&self) -> Option<CGroup> {
+        if self.is_root() {
+            return None;
+        }
+        let name = self
+            .name
+            .parent()
+            .filter(|name| !name.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("/"));
+        Some(CGroup {
+            root: self.root.parent()?.into(),
+            name: name.into(),
+            has_memory_controller: self.has_memory_controller,
+        })
+    }
+
     fn is_root(&self) -> bool {
         self.name == Path::new("/")
     }
 
+    /// The name of the systemd unit, if the cgroup was created for one.
+    fn unit(&self) -> Option<&str> {
+        let name = self.name.file_name()?.to_str()?;
+        SYSTEMD_UNIT_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+            .then(|| name)
+    }
+
+    /// The limits of the cgroup, lowered by those of its ancestors.
+    async fn limits(&self, buffer: &mut String) -> Limits {
+        let mut limits = Limits::default();
+        let mut cgroup = Some(self.clone());
+        while let Some(current) = cgroup {
+            if let Some(cores) = current.load_cpu_limit(buffer).await {
+                limits.cpu_cores = Some(limits.cpu_cores.map_or(cores, |limit| limit.min(cores)));
+            }
+            if let Some(bytes) = current.load_memory_limit(buffer).await {
+                limits.memory_bytes =
+                    Some(limits.memory_bytes.map_or(bytes, |limit| limit.min(bytes)));
+            }
+            cgroup = current.parent();
+        }
+        limits
+    }
+
+    async fn load_cpu_limit(&self, buffer: &mut String) -> Option<f64> {
+        filter_result_sync(
+            self.open_read_parse_optional::<CpuMax>("cpu.max", buffer)
+                .await,
+            "Failed to load cgroups CPU limit.",
+        )
+        .flatten()
+        .and_then(CpuMax::cores)
+    }
+
+    async fn load_memory_limit(&self, buffer: &mut String) -> Option<u64> {
+        filter_result_sync(
+            self.open_read_parse_optional::<Max>("memory.max", buffer)
+                .await,
+            "Failed to load cgroups memory limit.",
+        )
+        .flatten()
+        .and_then(|max| max.0)
+    }
+
     async fn load_cpu(&self, buffer: &mut String) -> CGroupsResult<CpuStat> {
         self.open_read_parse("cpu.stat", buffer).await
     }
@@ -273,6 +473,23 @@ impl CGroup {
             .with_context(|_| ParsingSnafu { filename })
     }
 
+    /// Parses the file, unless it doesn't exist, as when its controller isn't enabled.
+    async fn open_read_parse_optional<T: FromStr<Err = ParseIntError>>(
+        &self,
+        filename: impl AsRef<Path>,
+        buffer: &mut String,
+    ) -> CGroupsResult<Option<T>> {
+        match self.open_read_parse(filename, buffer).await {
+            Ok(value) => Ok(Some(value)),
+            Err(CGroupsError::Opening { source, .. })
+                if source.kind() == io::ErrorKind::NotFound =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
     async fn load_memory_current(&self, buffer: &mut String) -> CGroupsResult<u64> {
         self.open_read_parse("memory.current", buffer).await
     }
@@ -340,6 +557,9 @@ define_stat_struct! { CpuStat(
     usage_usec,
     user_usec,
     system_usec,
+    nr_periods,
+    nr_throttled,
+    throttled_usec,
 )}
 
 define_stat_struct! { MemoryStat(
@@ -352,6 +572,116 @@ define_stat_struct! { MemoryStat(
     file,
 )}
 
+#[derive(Clone, Copy, Debug, Default)]
+struct Limits {
+    cpu_cores: Option<f64>,
+    memory_bytes: Option<u64>,
+}
+
+/// The contents of `cpu.max`, the quota of CPU time available in each period, in microseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CpuMax {
+    quota: Option<u64>,
+    period: u64,
+}
+
+impl CpuMax {
+    fn cores(self) -> Option<f64> {
+        self.quota
+            .filter(|_| self.period > 0)
+            .map(|quota| quota as f64 / self.period as f64)
+    }
+}
+
+impl FromStr for CpuMax {
+    type Err = ParseIntError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut fields = text.split_whitespace();
+        let quota = parse_max(fields.next().unwrap_or_default())?;
+        let period = fields.next().map_or(Ok(DEFAULT_CPU_PERIOD), str::parse)?;
+        Ok(Self { quota, period })
+    }
+}
+
+/// The contents of a limit file, such as `memory.max`, which is `max` when unlimited.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Max(Option<u64>);
+
+impl FromStr for Max {
+    type Err = ParseIntError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse_max(text).map(Self)
+    }
+}
+
+fn parse_max(text: &str) -> Result<Option<u64>, ParseIntError> {
+    if text == "max" {
+        Ok(None)
+    } else {
+        text.parse().map(Some)
+    }
+}
+
+/// The contents of `io.stat`, the I/O statistics of each device.
+#[derive(Clone, Debug, Default)]
+struct IoStat(Vec<IoDeviceStat>);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct IoDeviceStat {
+    /// The `MAJOR:MINOR` numbers of the device.
+    device: String,
+    rbytes: u64,
+    wbytes: u64,
+    rios: u64,
+    wios: u64,
+}
+
+impl FromStr for IoStat {
+    type Err = ParseIntError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        text.lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                fields.next().map(|device| (device, fields))
+            })
+            .map(|(device, fields)| {
+                let mut stat = IoDeviceStat {
+                    device: device.into(),
+                    ..IoDeviceStat::default()
+                };
+                for (key, value) in fields.filter_map(|field| field.split_once('=')) {
+                    let counter = match key {
+                        "rbytes" => &mut stat.rbytes,
+                        "wbytes" => &mut stat.wbytes,
+                        "rios" => &mut stat.rios,
+                        "wios" => &mut stat.wios,
+                        _ => continue,
+                    };
+                    *counter = value.parse()?;
+                }
+                Ok(stat)
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// The name of the block device, such as `sda`, falling back to its `MAJOR:MINOR` numbers.
+fn device_name(device: &str) -> String {
+    let link = join_path(heim::os::linux::sysfs_root(), "dev/block").join(device);
+    std::fs::read_link(link)
+        .ok()
+        .and_then(|target| {
+            target
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| device.to_owned())
+}
+
 fn is_dir(path: impl AsRef<Path>) -> bool {
     std::fs::metadata(path.as_ref())
         .map(|metadata| metadata.is_dir())
@@ -390,7 +720,10 @@ fn join_name(base_name: &Path, filename: impl AsRef<Path>) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use std::path::{Path, PathBuf};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
 
     use pretty_assertions::assert_eq;
 
@@ -399,9 +732,23 @@ mod tests {
             tests::{count_name, count_tag},
             HostMetrics, HostMetricsConfig,
         },
-        join_name, join_path,
+        join_name, join_path, CGroup, CpuMax, IoDeviceStat, IoStat, Max,
     };
 
+    /// Writes the files of the cgroups, relative to the base directory.
+    fn fake_cgroups(base: &Path, files: &[(&str, &str)]) -> CGroup {
+        for (filename, content) in files {
+            let path = base.join(filename);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        CGroup {
+            root: base.into(),
+            name: "/".into(),
+            has_memory_controller: true,
+        }
+    }
+
     #[test]
     fn joins_names_and_paths() {
         assert_eq!(join_name(Path::new("/"), "foo"), PathBuf::from("foo"));
@@ -423,4 +770,158 @@ mod tests {
         assert!(count_name(&metrics, "cgroup_cpu_user_seconds_total") > 0);
         assert!(count_name(&metrics, "cgroup_cpu_system_seconds_total") > 0);
     }
+
+    #[test]
+    fn parses_limits() {
+        assert_eq!(
+            "max 100000".parse(),
+            Ok(CpuMax {
+                quota: None,
+                period: 100_000
+            })
+        );
+        assert_eq!(
+            "150000 100000".parse::<CpuMax>().unwrap().cores(),
+            Some(1.5)
+        );
+        assert_eq!("50000".parse::<CpuMax>().unwrap().cores(), Some(0.5));
+        assert!("".parse::<CpuMax>().is_err());
+
+        assert_eq!("max".parse(), Ok(Max(None)));
+        assert_eq!("1073741824".parse(), Ok(Max(Some(1_073_741_824))));
+    }
+
+    #[test]
+    fn parses_io_stat() {
+        let stat = "8:0 rbytes=1024 wbytes=2048 rios=3 wios=4 dbytes=0 dios=0\n259:0 rbytes=1"
+            .parse::<IoStat>()
+            .unwrap();
+        assert_eq!(
+            stat.0,
+            vec![
+                IoDeviceStat {
+                    device: "8:0".into(),
+                    rbytes: 1024,
+                    wbytes: 2048,
+                    rios: 3,
+                    wios: 4,
+                },
+                IoDeviceStat {
+                    device: "259:0".into(),
+                    rbytes: 1,
+                    ..IoDeviceStat::default()
+                },
+            ]
+        );
+        assert!("8:0 rbytes=many".parse::<IoStat>().is_err());
+    }
+
+    #[test]
+    fn detects_systemd_units() {
+        let root = fake_cgroups(Path::new("/"), &[]);
+        let unit = |name: &str| {
+            let cgroup = CGroup {
+                name: name.into(),
+                ..root.clone()
+            };
+            cgroup.unit().map(Into::into)
+        };
+        assert_eq!(
+            unit("system.slice/nginx.service"),
+            Some("nginx.service".to_owned())
+        );
+        assert_eq!(
+            unit("user.slice/user-1000.slice/session-2.scope"),
+            Some("session-2.scope".to_owned())
+        );
+        assert_eq!(unit("docker/4b1a2c"), None);
+        assert_eq!(unit("/"), None);
+    }
+
+    #[tokio::test]
+    async fn lowers_limits_by_ancestors() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fake_cgroups(
+            dir.path(),
+            &[
+                ("kubepods/cpu.max", "200000 100000"),
+                ("kubepods/memory.max", "max"),
+                ("kubepods/pod1/cpu.max", "max 100000"),
+                ("kubepods/pod1/memory.max", "1073741824"),
+                ("kubepods/pod1/memory.current", "268435456"),
+            ],
+        );
+        let own = root.group(Some(Path::new("kubepods/pod1"))).unwrap();
+        assert_eq!(own.name, PathBuf::from("kubepods/pod1"));
+
+        let limits = own.limits(&mut String::new()).await;
+        assert_eq!(limits.cpu_cores, Some(2.0));
+        assert_eq!(limits.memory_bytes, Some(1_073_741_824));
+
+        let host_metrics = HostMetrics {
+            config: HostMetricsConfig::default(),
+            root_cgroup: None,
+            own_cgroup: Some(own),
+        };
+        assert_eq!(
+            host_metrics.container_memory().await,
+            Some((1_073_741_824, 268_435_456))
+        );
+        let metrics = host_metrics.container_cpu_metrics().await;
+        assert_eq!(count_name(&metrics, "cpu_limit_cores"), 1);
+    }
+
+    #[tokio::test]
+    async fn generates_metrics_of_selected_units() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fake_cgroups(
+            dir.path(),
+            &[
+                ("cpu.stat", "usage_usec 100\nuser_usec 60\nsystem_usec 40\n"),
+                ("system.slice/cpu.stat", "usage_usec 10\n"),
+                (
+                    "system.slice/nginx.service/cpu.stat",
+                    "usage_usec 10\nnr_periods 5\nnr_throttled 2\nthrottled_usec 3000\n",
+                ),
+                ("system.slice/nginx.service/cpu.max", "50000 100000"),
+                ("system.slice/nginx.service/memory.current", "4096"),
+                (
+                    "system.slice/nginx.service/memory.stat",
+                    "anon 1024\nfile 2048\n",
+                ),
+                ("system.slice/nginx.service/memory.max", "8192"),
+                (
+                    "system.slice/nginx.service/io.stat",
+                    "8:0 rbytes=1024 wbytes=2048 rios=3 wios=4",
+                ),
+            ],
+        );
+        let config: HostMetricsConfig = toml::from_str(
+            r#"collectors = ["cgroups"]
+            cgroups.units.includes = ["*.service"]"#,
+        )
+        .unwrap();
+        let metrics = HostMetrics {
+            config,
+            root_cgroup: Some(root),
+            own_cgroup: None,
+        }
+        .cgroups_metrics()
+        .await;
+
+        assert_eq!(metrics.len(), 14);
+        assert_eq!(count_tag(&metrics, "unit"), metrics.len());
+        assert_eq!(
+            count_name(&metrics, "cgroup_cpu_throttled_periods_total"),
+            1
+        );
+        assert_eq!(
+            count_name(&metrics, "cgroup_cpu_throttled_seconds_total"),
+            1
+        );
+        assert_eq!(count_name(&metrics, "cgroup_cpu_limit_cores"), 1);
+        assert_eq!(count_name(&metrics, "cgroup_memory_limit_bytes"), 1);
+        assert_eq!(count_name(&metrics, "cgroup_io_read_bytes_total"), 1);
+        assert_eq!(count_tag(&metrics, "device"), 4);
+    }
 }
@@ -10,7 +10,8 @@ use crate::event::metric::Metric;
 
 impl HostMetrics {
     pub async fn cpu_metrics(&self) -> Vec<Metric> {
-        match heim::cpu::times().await {
+        #[allow(unused_mut)]
+        let mut metrics = match heim::cpu::times().await {
             Ok(times) => {
                 times
                     .filter_map(|result| filter_result(result, "Failed to load/parse CPU time."))
@@ -57,7 +58,10 @@ impl HostMetrics {
                 error!(message = "Failed to load CPU times.", %error, internal_log_rate_secs = 60);
                 vec![]
             }
-        }
+        };
+        #[cfg(target_os = "linux")]
+        metrics.extend(self.container_cpu_metrics().await);
+        metrics
     }
 }
 
@@ -16,25 +16,26 @@ impl HostMetrics {
         match heim::memory::memory().await {
             Ok(memory) => {
                 let timestamp = Utc::now();
-                vec![
-                    self.gauge(
-                        "memory_total_bytes",
-                        timestamp,
-                        memory.total().get::<byte>() as f64,
-                        btreemap! {},
+                let total = memory.total().get::<byte>() as f64;
+                let available = memory.available().get::<byte>() as f64;
+                // Inside of a container, the memory is limited by its cgroup.
+                #[cfg(target_os = "linux")]
+                let (total, available) = match self.container_memory().await {
+                    Some((limit, current)) => (
+                        total.min(limit as f64),
+                        available.min(limit.saturating_sub(current) as f64),
                     ),
+                    None => (total, available),
+                };
+                vec![
+                    self.gauge("memory_total_bytes", timestamp, total, btreemap! {}),
                     self.gauge(
                         "memory_free_bytes",
                         timestamp,
                         memory.free().get::<byte>() as f64,
                         btreemap! {},
                     ),
-                    self.gauge(
-                        "memory_available_bytes",
-                        timestamp,
-                        memory.available().get::<byte>() as f64,
-                        btreemap! {},
-                    ),
+                    self.gauge("memory_available_bytes", timestamp, available, btreemap! {}),
                     #[cfg(any(target_os = "linux", target_os = "macos"))]
                     self.gauge(
                         "memory_active_bytes",
@@ -175,6 +175,9 @@ pub struct HostMetrics {
     config: HostMetricsConfig,
     #[cfg(target_os = "linux")]
     root_cgroup: Option<cgroups::CGroup>,
+    /// The cgroup Vector runs in, if its limits are used.
+    #[cfg(target_os = "linux")]
+    own_cgroup: Option<cgroups::CGroup>,
 }
 
 impl HostMetrics {
@@ -185,10 +188,18 @@ impl HostMetrics {
 
     #[cfg(target_os = "linux")]
     pub fn new(config: HostMetricsConfig) -> Self {
-        let root_cgroup = cgroups::CGroup::root(config.cgroups.base.as_deref());
+        let base_cgroup = cgroups::CGroup::root();
+        let root_cgroup = base_cgroup
+            .as_ref()
+            .and_then(|base| base.group(config.cgroups.base.as_deref()));
+        let own_cgroup = base_cgroup
+            .as_ref()
+            .filter(|_| config.cgroups.container_limits)
+            .and_then(cgroups::CGroup::own);
         Self {
             config,
             root_cgroup,
+            own_cgroup,
         }
     }
 
@@ -10,7 +10,7 @@ use warp::http::{HeaderMap, HeaderValue};
 use crate::{
     codecs::{
         self,
-        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+        decoding::{DecodingConfig, Decompression, DeserializerConfig, FramingConfig},
         BytesDecoderConfig, BytesDeserializerConfig, JsonDeserializerConfig,
         NewlineDelimitedDecoderConfig,
     },
@@ -45,6 +45,14 @@ pub(super) struct SimpleHttpConfig {
     path_key: String,
     framing: Option<FramingConfig>,
     decoding: Option<DeserializerConfig>,
+    /// The compression of the request bodies, undone once they are decompressed as per their
+    /// `Content-Encoding`, and before framing.
+    #[serde(default)]
+    decompression: Decompression,
+    /// The largest size in bytes of the request bodies once decompressed as per their
+    /// `Content-Encoding`, larger requests being rejected.
+    #[serde(default)]
+    max_decompressed_size: Option<usize>,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
 }
@@ -67,6 +75,8 @@ impl GenerateConfig for SimpleHttpConfig {
             strict_path: true,
             framing: Some(default_framing_stream_based()),
             decoding: Some(default_decoding()),
+            decompression: Decompression::None,
+            max_decompressed_size: None,
             acknowledgements: AcknowledgementsConfig::default(),
         })
         .unwrap()
@@ -87,6 +97,7 @@ struct SimpleHttpSource {
     query_parameters: Vec<String>,
     path_key: String,
     decoder: codecs::Decoder,
+    max_decompressed_size: Option<usize>,
 }
 
 impl HttpSource for SimpleHttpSource {
@@ -131,6 +142,10 @@ impl HttpSource for SimpleHttpSource {
 
         Ok(events)
     }
+
+    fn max_decompressed_size(&self) -> Option<usize> {
+        self.max_decompressed_size
+    }
 }
 
 #[async_trait::async_trait]
@@ -173,12 +188,15 @@ impl SourceConfig for SimpleHttpConfig {
             )
         };
 
-        let decoder = DecodingConfig::new(framing, decoding).build();
+        let decoder = DecodingConfig::new(framing, decoding)
+            .with_decompression(self.decompression)
+            .build()?;
         let source = SimpleHttpSource {
             headers: self.headers.clone(),
             query_parameters: self.query_parameters.clone(),
             path_key: self.path_key.clone(),
             decoder,
+            max_decompressed_size: self.max_decompressed_size,
         };
         source.run(
             self.address,
@@ -206,6 +224,10 @@ impl SourceConfig for SimpleHttpConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
 fn add_path(events: &mut [Event], key: &str, path: &str) {
@@ -288,6 +310,8 @@ mod tests {
                 path,
                 framing,
                 decoding,
+                decompression: Decompression::None,
+                max_decompressed_size: None,
                 acknowledgements: acknowledgements.into(),
             }
             .build(context)
@@ -300,6 +324,28 @@ mod tests {
         (recv, address)
     }
 
+    async fn source_with_max_decompressed_size(
+        max_decompressed_size: usize,
+    ) -> (impl Stream<Item = Event>, SocketAddr) {
+        components::init_test();
+        let (sender, recv) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let address = next_addr();
+        let context = SourceContext::new_test(sender, None);
+        let config: SimpleHttpConfig = toml::from_str(&format!(
+            r#"
+            address = "{}"
+            max_decompressed_size = {}
+            "#,
+            address, max_decompressed_size
+        ))
+        .unwrap();
+        tokio::spawn(async move {
+            config.build(context).await.unwrap().await.unwrap();
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
     async fn send(address: SocketAddr, body: &str) -> u16 {
         reqwest::Client::new()
             .post(&format!("http://{}/", address))
@@ -792,6 +838,61 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn http_zstd_max_decompressed_size() {
+        let (rx, addr) = source_with_max_decompressed_size(9).await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("Content-Encoding", "zstd".parse().unwrap());
+
+        let body = zstd::stream::encode_all("test body too large".as_bytes(), 3).unwrap();
+        assert_eq!(413, send_bytes(addr, body, headers.clone()).await);
+
+        let body = zstd::stream::encode_all("test body".as_bytes(), 3).unwrap();
+        let mut events = spawn_ok_collect_n(send_bytes(addr, body, headers), rx, 1).await;
+
+        let event = events.remove(0);
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "test body".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn http_decompression_auto() {
+        components::init_test();
+        let (sender, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let addr = next_addr();
+        let context = SourceContext::new_test(sender, None);
+        let config: SimpleHttpConfig = toml::from_str(&format!(
+            r#"
+            address = "{}"
+            decompression = "auto"
+            "#,
+            addr
+        ))
+        .unwrap();
+        tokio::spawn(async move {
+            config.build(context).await.unwrap().await.unwrap();
+        });
+        wait_for_tcp(addr).await;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"test body\nother body").unwrap();
+        let body = encoder.finish().unwrap();
+
+        let events = spawn_ok_collect_n(send_bytes(addr, body, HeaderMap::new()), rx, 2).await;
+
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "test body".into()
+        );
+        assert_eq!(
+            events[1].as_log()[log_schema().message_key()],
+            "other body".into()
+        );
+    }
+
     #[tokio::test]
     async fn http_path() {
         let (rx, addr) = source(
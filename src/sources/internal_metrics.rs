@@ -1,13 +1,21 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
 use futures::StreamExt;
+use metrics::{Key, Label};
 use serde::{Deserialize, Serialize};
 use tokio::time;
 use tokio_stream::wrappers::IntervalStream;
 use vector_core::ByteSizeOf;
 
 use crate::{
-    config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    config::{
+        log_schema, DataType, Output, Resource, SourceConfig, SourceContext, SourceDescription,
+    },
     internal_events::{EventsReceived, StreamClosedError},
-    metrics::Controller,
+    metrics::{Controller, KeyRewriter},
     shutdown::ShutdownSignal,
     SourceSender,
 };
@@ -20,6 +28,8 @@ pub struct InternalMetricsConfig {
     scrape_interval_secs: f64,
     tags: TagsConfig,
     namespace: Option<String>,
+    cardinality: CardinalityConfig,
+    tag_filters: Vec<TagFilterConfig>,
     #[serde(skip)]
     version: Option<String>,
     #[serde(skip)]
@@ -51,6 +61,37 @@ pub struct TagsConfig {
     pid_key: Option<String>,
 }
 
+/// Caps the number of distinct values of each tag of each metric, such as the file paths or the
+/// peer addresses, so that the number of series exposed stays bounded. The limits are applied
+/// as the metrics are recorded, so they hold for all of Vector's internal metrics, not only those
+/// this source sends.
+#[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct CardinalityConfig {
+    /// The number of distinct values kept for each tag of a metric. The values seen after them
+    /// are replaced by `overflow_value`, the series bucketed together being added up. Unlimited
+    /// by default.
+    max_tag_values: Option<usize>,
+    #[derivative(Default(value = "default_overflow_value()"))]
+    overflow_value: String,
+}
+
+fn default_overflow_value() -> String {
+    "overflow".to_owned()
+}
+
+/// The tags kept for the metrics whose name matches one of the patterns. All the filters matching
+/// a metric are applied, in order.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TagFilterConfig {
+    metrics: Vec<String>,
+    allow: Option<Vec<String>>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
 inventory::submit! {
     SourceDescription::new::<InternalMetricsConfig>("internal_metrics")
 }
@@ -83,12 +124,15 @@ impl SourceConfig for InternalMetricsConfig {
                 .pid_key
                 .as_deref()
                 .and_then(|tag| if tag.is_empty() { None } else { Some("pid") });
+        let limiter = TagLimiter::new(&self.cardinality, &self.tag_filters)?
+            .map(|limiter| Arc::new(limiter) as Arc<dyn KeyRewriter>);
         Ok(Box::pin(run(
             namespace,
             version,
             configuration_key,
             host_key,
             pid_key,
+            limiter,
             Controller::get()?,
             interval,
             cx.out,
@@ -104,6 +148,15 @@ impl SourceConfig for InternalMetricsConfig {
         "internal_metrics"
     }
 
+    /// The limits apply to all of the internal metrics, so only one source can set them.
+    fn resources(&self) -> Vec<Resource> {
+        if self.tag_filters.is_empty() && self.cardinality.max_tag_values.is_none() {
+            Vec::new()
+        } else {
+            vec![Resource::InternalMetricsLimits]
+        }
+    }
+
     fn can_acknowledge(&self) -> bool {
         false
     }
@@ -115,11 +168,31 @@ async fn run(
     configuration_key: Option<String>,
     host_key: Option<&str>,
     pid_key: Option<&str>,
+    limiter: Option<Arc<dyn KeyRewriter>>,
     controller: &Controller,
     interval: time::Duration,
     mut out: SourceSender,
     shutdown: ShutdownSignal,
 ) -> Result<(), ()> {
+    // The limits hold for as long as the source runs.
+    struct LimiterGuard<'a>(&'a Controller, Option<Arc<dyn KeyRewriter>>);
+
+    impl Drop for LimiterGuard<'_> {
+        fn drop(&mut self) {
+            if let Some(limiter) = &self.1 {
+                self.0.remove_key_rewriter(limiter);
+            }
+        }
+    }
+
+    if let Some(limiter) = &limiter {
+        if let Err(error) = controller.set_key_rewriter(Arc::clone(limiter)) {
+            error!(message = "Couldn't apply the tag limits.", %error);
+            return Err(());
+        }
+    }
+    let _guard = LimiterGuard(controller, limiter);
+
     let mut interval = IntervalStream::new(time::interval(interval)).take_until(shutdown);
     while interval.next().await.is_some() {
         let hostname = crate::get_hostname();
@@ -165,6 +238,150 @@ async fn run(
     Ok(())
 }
 
+struct TagFilter {
+    metrics: Vec<glob::Pattern>,
+    allow: Option<Vec<String>>,
+    deny: Vec<String>,
+}
+
+impl TagFilter {
+    fn matches(&self, name: &str) -> bool {
+        self.metrics.iter().any(|pattern| pattern.matches(name))
+    }
+
+    fn keeps(&self, label: &Label) -> bool {
+        let key = label.key();
+        !self.deny.iter().any(|deny| deny == key)
+            && self
+                .allow
+                .as_ref()
+                .map_or(true, |allow| allow.iter().any(|allow| allow == key))
+    }
+}
+
+#[derive(Default)]
+struct TagValues {
+    values: HashSet<String>,
+    overflowed: bool,
+}
+
+/// Filters the tags of the metrics and caps their values, as they are recorded. The values seen
+/// are kept for as long as the source runs, so that each of them keeps its own series.
+struct TagLimiter {
+    filters: Vec<TagFilter>,
+    max_tag_values: Option<usize>,
+    overflow_value: String,
+    seen: Mutex<HashMap<String, HashMap<String, TagValues>>>,
+}
+
+impl TagLimiter {
+    /// Returns the limiter for the given limits, or `None` if there are none.
+    fn new(
+        cardinality: &CardinalityConfig,
+        filters: &[TagFilterConfig],
+    ) -> crate::Result<Option<Self>> {
+        let filters = filters
+            .iter()
+            .map(|filter| -> Result<_, String> {
+                let metrics = filter
+                    .metrics
+                    .iter()
+                    .map(|pattern| {
+                        glob::Pattern::new(pattern).map_err(|error| {
+                            format!("Invalid metric name pattern {:?}: {}", pattern, error)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(TagFilter {
+                    metrics,
+                    allow: filter.allow.clone(),
+                    deny: filter.deny.clone(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        if filters.is_empty() && cardinality.max_tag_values.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            filters,
+            max_tag_values: cardinality.max_tag_values,
+            overflow_value: cardinality.overflow_value.clone(),
+            seen: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Returns whether the value of the tag is over the cardinality limit of the metric.
+    fn overflows(&self, name: &str, label: &Label, max_tag_values: usize) -> bool {
+        let mut seen = self
+            .seen
+            .lock()
+            .expect("Couldn't acquire lock on tag values");
+        let seen = entry(entry(&mut seen, name), label.key());
+        if seen.values.contains(label.value()) {
+            return false;
+        }
+        if seen.values.len() < max_tag_values {
+            seen.values.insert(label.value().to_owned());
+            return false;
+        }
+
+        if !seen.overflowed {
+            seen.overflowed = true;
+            warn!(
+                message = "Tag over the cardinality limit, its next values are bucketed.",
+                metric = %name,
+                tag = %label.key(),
+                max_tag_values,
+                overflow_value = %self.overflow_value,
+            );
+        }
+        true
+    }
+}
+
+/// Returns the value for `key`, inserting the default one if there is none, allocating the key only
+/// then.
+fn entry<'a, V: Default>(map: &'a mut HashMap<String, V>, key: &str) -> &'a mut V {
+    if !map.contains_key(key) {
+        map.insert(key.to_owned(), V::default());
+    }
+    map.get_mut(key).expect("value inserted above")
+}
+
+/// The series left with the same tags, once filtered or bucketed, are recorded as one: counters
+/// and histograms add up, while gauges keep the last value set.
+impl KeyRewriter for TagLimiter {
+    fn rewrite(&self, key: &Key) -> Option<Key> {
+        let name = key.name();
+        let mut changed = false;
+        let mut labels = Vec::new();
+        for label in key.labels() {
+            let kept = self
+                .filters
+                .iter()
+                .all(|filter| !filter.matches(name) || filter.keeps(label));
+            if !kept {
+                changed = true;
+                continue;
+            }
+            match self.max_tag_values {
+                Some(max_tag_values) if self.overflows(name, label, max_tag_values) => {
+                    changed = true;
+                    labels.push(Label::new(
+                        label.key().to_owned(),
+                        self.overflow_value.clone(),
+                    ));
+                }
+                _ => labels.push(label.clone()),
+            }
+        }
+
+        changed.then(|| Key::from_parts(name.to_owned(), labels))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -276,6 +493,140 @@ mod tests {
             .expect("failed to get metrics from a stream")
     }
 
+    #[test]
+    fn limits_tag_cardinality() {
+        let limiter = TagLimiter::new(
+            &CardinalityConfig {
+                max_tag_values: Some(2),
+                ..CardinalityConfig::default()
+            },
+            &[],
+        )
+        .unwrap()
+        .unwrap();
+        let key = |file: &'static str| {
+            Key::from_parts("files_read_total", vec![Label::new("file", file)])
+        };
+
+        assert_eq!(limiter.rewrite(&key("a.log")), None);
+        assert_eq!(limiter.rewrite(&key("b.log")), None);
+        assert_eq!(limiter.rewrite(&key("c.log")), Some(key("overflow")));
+        assert_eq!(limiter.rewrite(&key("d.log")), Some(key("overflow")));
+
+        // The values seen first keep their own series.
+        assert_eq!(limiter.rewrite(&key("b.log")), None);
+    }
+
+    #[test]
+    fn filters_tags() {
+        let filter = |metrics: &str, allow: Option<&[&str]>, deny: &[&str]| TagFilterConfig {
+            metrics: vec![metrics.to_owned()],
+            allow: allow.map(|allow| allow.iter().map(|tag| tag.to_string()).collect()),
+            deny: deny.iter().map(|tag| tag.to_string()).collect(),
+        };
+        let limiter = TagLimiter::new(
+            &CardinalityConfig::default(),
+            &[
+                filter("component_*", Some(&["component_id", "peer_addr"]), &[]),
+                filter("*", None, &["peer_addr"]),
+            ],
+        )
+        .unwrap()
+        .unwrap();
+        let key = |name: &'static str, labels: &[(&'static str, &'static str)]| {
+            Key::from_parts(name, labels.iter().map(Label::from).collect::<Vec<_>>())
+        };
+
+        assert_eq!(
+            limiter.rewrite(&key(
+                "component_received_events_total",
+                &[
+                    ("component_id", "in"),
+                    ("peer_addr", "10.0.0.1"),
+                    ("file", "a"),
+                ],
+            )),
+            Some(key(
+                "component_received_events_total",
+                &[("component_id", "in")]
+            ))
+        );
+        assert_eq!(
+            limiter.rewrite(&key(
+                "open_connections",
+                &[("peer_addr", "10.0.0.1"), ("mode", "tcp")],
+            )),
+            Some(key("open_connections", &[("mode", "tcp")]))
+        );
+        assert_eq!(
+            limiter.rewrite(&key("open_connections", &[("mode", "tcp")])),
+            None
+        );
+    }
+
+    #[test]
+    fn limits_recorded_metrics() {
+        let _ = crate::metrics::init_test();
+        let controller = Controller::get().expect("no controller");
+
+        let limiter: Arc<dyn KeyRewriter> = Arc::new(
+            TagLimiter::new(
+                &CardinalityConfig {
+                    max_tag_values: Some(1),
+                    ..CardinalityConfig::default()
+                },
+                &[],
+            )
+            .unwrap()
+            .unwrap(),
+        );
+        controller.set_key_rewriter(Arc::clone(&limiter)).unwrap();
+        assert!(controller.set_key_rewriter(Arc::clone(&limiter)).is_err());
+        counter!("limited_files_read_total", 1, "file" => "a.log");
+        counter!("limited_files_read_total", 2, "file" => "b.log");
+        counter!("limited_files_read_total", 3, "file" => "c.log");
+        controller.remove_key_rewriter(&limiter);
+        counter!("limited_files_read_total", 4, "file" => "d.log");
+
+        let values = controller
+            .capture_metrics()
+            .into_iter()
+            .filter(|metric| metric.name() == "limited_files_read_total")
+            .map(|metric| {
+                (
+                    metric.tag_value("file").unwrap(),
+                    match metric.value() {
+                        MetricValue::Counter { value } => *value,
+                        _ => panic!("wrong type"),
+                    },
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            values,
+            vec![
+                ("a.log".to_owned(), 1.0),
+                ("d.log".to_owned(), 4.0),
+                ("overflow".to_owned(), 5.0),
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let filter = TagFilterConfig {
+            metrics: vec!["component_[".to_owned()],
+            allow: None,
+            deny: vec!["peer_addr".to_owned()],
+        };
+        assert!(TagLimiter::new(&CardinalityConfig::default(), &[filter]).is_err());
+        assert!(TagLimiter::new(&CardinalityConfig::default(), &[])
+            .unwrap()
+            .is_none());
+    }
+
     #[tokio::test]
     async fn default_namespace() {
         let event = event_from_config(InternalMetricsConfig::default()).await;
@@ -12,6 +12,7 @@ use std::{
 use bytes::Bytes;
 use chrono::TimeZone;
 use futures::{future, stream::BoxStream, StreamExt};
+use glob::Pattern;
 use nix::{
     sys::signal::{kill, Signal},
     unistd::Pid,
@@ -74,17 +75,31 @@ enum BuildError {
         value,
     ))]
     DuplicatedMatches { field: String, value: String },
+    #[snafu(display("Cannot use both `boot_id` and `current_boot_only`"))]
+    BothBootIdAndCurrentBootOnly,
+    #[snafu(display("Invalid field pattern {:?}: {}", pattern, source))]
+    InvalidFieldPattern {
+        pattern: String,
+        source: glob::PatternError,
+    },
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct JournaldConfig {
     pub current_boot_only: Option<bool>,
+    /// Read only the entries of this boot, instead of the current one.
+    pub boot_id: Option<String>,
+    /// Where to start reading if there is no checkpoint, in any format accepted by the `--since`
+    /// option of `journalctl`.
+    pub since: Option<String>,
     pub units: Vec<String>,
     pub include_units: Vec<String>,
     pub exclude_units: Vec<String>,
     pub include_matches: HashMap<String, HashSet<String>>,
     pub exclude_matches: HashMap<String, HashSet<String>>,
+    pub include_fields: Vec<String>,
+    pub exclude_fields: Vec<String>,
     pub data_dir: Option<PathBuf>,
     pub batch_size: Option<usize>,
     pub journalctl_path: Option<PathBuf>,
@@ -162,6 +177,12 @@ impl SourceConfig for JournaldConfig {
             return Err(BuildError::DuplicatedMatches { field, value }.into());
         }
 
+        if self.boot_id.is_some() && self.current_boot_only == Some(true) {
+            return Err(BuildError::BothBootIdAndCurrentBootOnly.into());
+        }
+
+        let fields = FieldFilter::new(&self.include_fields, &self.exclude_fields)?;
+
         let mut checkpoint_path = data_dir;
         checkpoint_path.push(CHECKPOINT_FILENAME);
 
@@ -172,6 +193,8 @@ impl SourceConfig for JournaldConfig {
 
         let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
         let current_boot_only = self.current_boot_only.unwrap_or(true);
+        let boot_id = self.boot_id.clone();
+        let since = self.since.clone();
         let journal_dir = self.journal_directory.clone();
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
@@ -180,6 +203,8 @@ impl SourceConfig for JournaldConfig {
                 &journalctl_path,
                 journal_dir.as_ref(),
                 current_boot_only,
+                boot_id.as_deref(),
+                since.as_deref(),
                 cursor,
             );
             start_journalctl(&mut command)
@@ -189,6 +214,7 @@ impl SourceConfig for JournaldConfig {
             JournaldSource {
                 include_matches,
                 exclude_matches,
+                fields,
                 checkpoint_path,
                 batch_size,
                 remap_priority: self.remap_priority,
@@ -215,6 +241,7 @@ impl SourceConfig for JournaldConfig {
 struct JournaldSource {
     include_matches: Matches,
     exclude_matches: Matches,
+    fields: FieldFilter,
     checkpoint_path: PathBuf,
     batch_size: usize,
     remap_priority: bool,
@@ -365,7 +392,7 @@ impl<'a> Batch<'a> {
                             &self.source.exclude_matches,
                         ) {
                             self.record_size += bytes.len();
-                            let event = create_event(record, &self.batch);
+                            let event = create_event(record, &self.source.fields, &self.batch);
                             self.events.push(event);
                         }
                     }
@@ -464,6 +491,8 @@ fn create_command(
     path: &Path,
     journal_dir: Option<&PathBuf>,
     current_boot_only: bool,
+    boot_id: Option<&str>,
+    since: Option<&str>,
     cursor: &Option<String>,
 ) -> Command {
     let mut command = Command::new(path);
@@ -477,7 +506,9 @@ fn create_command(
         command.arg(format!("--directory={}", dir.display()));
     }
 
-    if current_boot_only {
+    if let Some(boot_id) = boot_id {
+        command.arg(format!("--boot={}", boot_id));
+    } else if current_boot_only {
         command.arg("--boot");
     }
 
@@ -485,13 +516,25 @@ fn create_command(
         command.arg(format!("--after-cursor={}", cursor));
     } else {
         // journalctl --follow only outputs a few lines without a starting point
-        command.arg("--since=2000-01-01");
+        command.arg(format!("--since={}", since.unwrap_or("2000-01-01")));
     }
 
     command
 }
 
-fn create_event(record: Record, batch: &Option<Arc<BatchNotifier>>) -> LogEvent {
+fn create_event(
+    mut record: Record,
+    fields: &FieldFilter,
+    batch: &Option<Arc<BatchNotifier>>,
+) -> LogEvent {
+    // The timestamp is read before filtering the fields, so that it's kept even if the journal
+    // fields holding it are excluded.
+    let timestamp = record
+        .get(SOURCE_TIMESTAMP)
+        .or_else(|| record.get(RECEIVED_TIMESTAMP))
+        .and_then(|timestamp| timestamp.parse::<u64>().ok());
+    fields.apply(&mut record);
+
     let mut log = LogEvent::from_iter(record).with_batch_notifier_option(batch);
 
     // Convert some journald-specific field names into Vector standard ones.
@@ -502,17 +545,12 @@ fn create_event(record: Record, batch: &Option<Arc<BatchNotifier>>) -> LogEvent
         log.insert(log_schema().host_key(), host);
     }
     // Translate the timestamp, and so leave both old and new names.
-    if let Some(Value::Bytes(timestamp)) = log
-        .get(&*SOURCE_TIMESTAMP)
-        .or_else(|| log.get(RECEIVED_TIMESTAMP))
-    {
-        if let Ok(timestamp) = String::from_utf8_lossy(timestamp).parse::<u64>() {
-            let timestamp = chrono::Utc.timestamp(
-                (timestamp / 1_000_000) as i64,
-                (timestamp % 1_000_000) as u32 * 1_000,
-            );
-            log.insert(log_schema().timestamp_key(), Value::Timestamp(timestamp));
-        }
+    if let Some(timestamp) = timestamp {
+        let timestamp = chrono::Utc.timestamp(
+            (timestamp / 1_000_000) as i64,
+            (timestamp % 1_000_000) as u32 * 1_000,
+        );
+        log.insert(log_schema().timestamp_key(), Value::Timestamp(timestamp));
     }
     // Add source type
     log.try_insert(log_schema().source_type_key(), Bytes::from("journald"));
@@ -520,6 +558,41 @@ fn create_event(record: Record, batch: &Option<Arc<BatchNotifier>>) -> LogEvent
     log
 }
 
+/// Filters the fields of the journal entries by patterns of their names, such as `_SYSTEMD_*`.
+#[derive(Debug, Default)]
+struct FieldFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl FieldFilter {
+    fn new(include: &[String], exclude: &[String]) -> Result<Self, BuildError> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| Pattern::new(pattern).context(InvalidFieldPatternSnafu { pattern }))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether the field is kept: it has to match one of the included patterns if there are any,
+    /// and none of the excluded ones.
+    fn retains(&self, field: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches(field)))
+            && !self.exclude.iter().any(|pattern| pattern.matches(field))
+    }
+
+    fn apply(&self, record: &mut Record) {
+        if !self.include.is_empty() || !self.exclude.is_empty() {
+            record.retain(|field, _| self.retains(field));
+        }
+    }
+}
+
 /// Map the given unit name into a valid systemd unit
 /// by appending ".service" if no extension is present.
 fn fixup_unit(unit: &str) -> String {
@@ -822,6 +895,21 @@ mod tests {
         include_matches: Matches,
         exclude_matches: Matches,
         cursor: Option<&str>,
+    ) -> Vec<Event> {
+        run_journal_with_fields(
+            include_matches,
+            exclude_matches,
+            FieldFilter::default(),
+            cursor,
+        )
+        .await
+    }
+
+    async fn run_journal_with_fields(
+        include_matches: Matches,
+        exclude_matches: Matches,
+        fields: FieldFilter,
+        cursor: Option<&str>,
     ) -> Vec<Event> {
         components::init_test();
         let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
@@ -845,6 +933,7 @@ mod tests {
         let source = JournaldSource {
             include_matches,
             exclude_matches,
+            fields,
             checkpoint_path,
             batch_size: DEFAULT_BATCH_SIZE,
             remap_priority: true,
@@ -967,6 +1056,38 @@ mod tests {
         assert_eq!(timestamp(&received[4]), value_ts(1578529839, 140006000));
     }
 
+    #[tokio::test]
+    async fn filters_fields() {
+        let fields = FieldFilter::new(
+            &["_SYSTEMD_*".into(), "MESSAGE".into(), "*TIMESTAMP".into()],
+            &["__REALTIME_TIMESTAMP".into()],
+        )
+        .unwrap();
+        let matches = create_matches(vec![("_SYSTEMD_UNIT", "stdout")]);
+        let received = run_journal_with_fields(matches, HashMap::new(), fields, None).await;
+        assert_eq!(received.len(), 2);
+
+        let log = received[0].as_log();
+        assert_eq!(
+            message(&received[0]),
+            Value::Bytes("Missing timestamp".into())
+        );
+        assert_eq!(log["_SYSTEMD_UNIT"], Value::Bytes("stdout".into()));
+        assert!(!log.contains("PRIORITY"));
+        assert!(!log.contains(RECEIVED_TIMESTAMP));
+        // The timestamp is still derived from the excluded field.
+        assert_eq!(timestamp(&received[0]), value_ts(1578529839, 140004000));
+
+        let log = received[1].as_log();
+        assert!(log.contains(SOURCE_TIMESTAMP));
+        assert!(!log.contains(RECEIVED_TIMESTAMP));
+    }
+
+    #[test]
+    fn rejects_invalid_field_patterns() {
+        assert!(FieldFilter::new(&["[".into()], &[]).is_err());
+    }
+
     #[tokio::test]
     async fn handles_checkpoint() {
         let received = run_with_units(&[], &[], Some("1")).await;
@@ -1025,6 +1146,7 @@ mod tests {
         let mut source = JournaldSource {
             include_matches: Default::default(),
             exclude_matches: Default::default(),
+            fields: Default::default(),
             checkpoint_path,
             batch_size: DEFAULT_BATCH_SIZE,
             remap_priority: true,
@@ -1144,7 +1266,7 @@ mod tests {
         let current_boot_only = false;
         let cursor = None;
 
-        let command = create_command(&path, journal_dir, current_boot_only, &cursor);
+        let command = create_command(&path, journal_dir, current_boot_only, None, None, &cursor);
         let cmd_line = format!("{:?}", command);
         assert!(!cmd_line.contains("--directory="));
         assert!(!cmd_line.contains("--boot"));
@@ -1154,11 +1276,31 @@ mod tests {
         let current_boot_only = true;
         let cursor = Some(String::from("2021-01-01"));
 
-        let command = create_command(&path, journal_dir.as_ref(), current_boot_only, &cursor);
+        let command = create_command(
+            &path,
+            journal_dir.as_ref(),
+            current_boot_only,
+            None,
+            Some("-1h"),
+            &cursor,
+        );
         let cmd_line = format!("{:?}", command);
         assert!(cmd_line.contains("--directory=/tmp/journal-dir"));
         assert!(cmd_line.contains("--boot"));
         assert!(cmd_line.contains("--after-cursor="));
+        assert!(!cmd_line.contains("--since"));
+
+        let command = create_command(
+            &path,
+            None,
+            true,
+            Some("4ba1e7d4ab1f4b8e9f1e0f4c2f8a6e3b"),
+            Some("2022-03-01 12:00:00"),
+            &None,
+        );
+        let cmd_line = format!("{:?}", command);
+        assert!(cmd_line.contains("--boot=4ba1e7d4ab1f4b8e9f1e0f4c2f8a6e3b"));
+        assert!(cmd_line.contains("--since=2022-03-01 12:00:00"));
     }
 
     fn message(event: &Event) -> Value {
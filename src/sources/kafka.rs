@@ -1,7 +1,8 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{btree_map::Entry, BTreeMap, HashMap},
     io::Cursor,
     sync::Arc,
+    time::Duration,
 };
 
 use bytes::Bytes;
@@ -11,6 +12,7 @@ use rdkafka::{
     config::ClientConfig,
     consumer::{Consumer, StreamConsumer},
     message::{BorrowedMessage, Headers, Message},
+    Offset, TopicPartitionList,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -21,21 +23,24 @@ use super::util::finalizer::OrderedFinalizer;
 use crate::{
     codecs::{
         self,
-        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+        decoding::{DecodingConfig, Decompression, DeserializerConfig, FramingConfig},
     },
     config::{
         log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
         SourceDescription,
     },
-    event::{BatchNotifier, Event, Value},
+    event::{BatchNotifier, BatchStatus, Event, Value},
     internal_events::{
-        BytesReceived, KafkaEventsReceived, KafkaOffsetUpdateError, KafkaReadError,
-        StreamClosedError,
+        BytesReceived, KafkaEventsReceived, KafkaKeyDecodeError, KafkaOffsetUpdateError,
+        KafkaReadError, KafkaRedeliveriesExhausted, StreamClosedError,
     },
     kafka::{KafkaAuthConfig, KafkaStatisticsContext},
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
-    sources::util::StreamDecodingError,
+    sources::util::{
+        AcknowledgementOutcome, AcknowledgementTimeout, AcknowledgementTimeoutAction,
+        StreamDecodingError,
+    },
     SourceSender,
 };
 use async_stream::stream;
@@ -46,6 +51,10 @@ enum BuildError {
     KafkaCreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     KafkaSubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not assign Kafka partitions: {}", source))]
+    KafkaAssignError { source: rdkafka::error::KafkaError },
+    #[snafu(display("At least one of `topics` or `assignments` must be set"))]
+    NoTopics,
 }
 
 #[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
@@ -53,7 +62,11 @@ enum BuildError {
 #[serde(deny_unknown_fields)]
 pub struct KafkaSourceConfig {
     bootstrap_servers: String,
+    #[serde(default)]
     topics: Vec<String>,
+    /// Partitions to consume from, instead of subscribing to `topics`.
+    #[serde(default)]
+    assignments: Vec<PartitionAssignment>,
     group_id: String,
     #[serde(default = "default_auto_offset_reset")]
     auto_offset_reset: String,
@@ -67,6 +80,8 @@ pub struct KafkaSourceConfig {
     commit_interval_ms: u64,
     #[serde(default = "default_key_field")]
     key_field: String,
+    #[serde(default)]
+    key_decoding: KeyDecoding,
     #[serde(default = "default_topic_key")]
     topic_key: String,
     #[serde(default = "default_partition_key")]
@@ -84,8 +99,68 @@ pub struct KafkaSourceConfig {
     #[serde(default = "default_decoding")]
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
+    /// The compression of the message payloads, undone before framing.
+    #[serde(default)]
+    decompression: Decompression,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
+    /// Whether the messages whose delivery errored are consumed again, like those whose
+    /// acknowledgement timed out, instead of having their offset committed.
+    #[serde(default)]
+    redeliver_errored: bool,
+    /// How many times a message is consumed again before its offset is committed anyway.
+    #[serde(default = "default_max_redeliveries")]
+    #[derivative(Default(value = "default_max_redeliveries()"))]
+    max_redeliveries: u32,
+}
+
+/// A partition to consume from, starting at `offset` if set or at the committed offset of the
+/// consumer group otherwise. Negative offsets are relative to the end of the partition.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartitionAssignment {
+    topic: String,
+    partition: i32,
+    #[serde(default)]
+    offset: Option<i64>,
+}
+
+impl PartitionAssignment {
+    const fn offset(&self) -> Offset {
+        match self.offset {
+            None => Offset::Stored,
+            Some(offset) if offset < 0 => Offset::OffsetTail(-offset),
+            Some(offset) => Offset::Offset(offset),
+        }
+    }
+}
+
+/// How the message keys are decoded.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Eq, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(tag = "codec", rename_all = "snake_case")]
+pub enum KeyDecoding {
+    /// The key as a string, invalid UTF-8 sequences being replaced.
+    #[derivative(Default)]
+    Bytes,
+    /// The key parsed as JSON, keys which aren't valid JSON being kept as strings.
+    Json,
+}
+
+impl KeyDecoding {
+    fn decode(self, key: &[u8]) -> Value {
+        let string = || Value::from(String::from_utf8_lossy(key).to_string());
+        match self {
+            Self::Bytes => string(),
+            Self::Json => match serde_json::from_slice::<serde_json::Value>(key) {
+                Ok(json) => json.into(),
+                Err(error) => {
+                    emit!(&KafkaKeyDecodeError { error });
+                    string()
+                }
+            },
+        }
+    }
 }
 
 const fn default_session_timeout_ms() -> u64 {
@@ -104,6 +179,10 @@ const fn default_commit_interval_ms() -> u64 {
     5000 // default in librdkafka
 }
 
+const fn default_max_redeliveries() -> u32 {
+    3
+}
+
 fn default_auto_offset_reset() -> String {
     "largest".into() // default in librdkafka
 }
@@ -139,12 +218,15 @@ impl_generate_config_from_default!(KafkaSourceConfig);
 impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let consumer = create_consumer(self)?;
-        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone())
+            .with_decompression(self.decompression)
+            .build()?;
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
         Ok(Box::pin(kafka_source(
             consumer,
             self.key_field.clone(),
+            self.key_decoding,
             self.topic_key.clone(),
             self.partition_key.clone(),
             self.offset_key.clone(),
@@ -153,6 +235,8 @@ impl SourceConfig for KafkaSourceConfig {
             cx.shutdown,
             cx.out,
             acknowledgements,
+            cx.acknowledgement_timeout,
+            Redeliveries::new(self.redeliver_errored, self.max_redeliveries),
         )))
     }
 
@@ -167,11 +251,20 @@ impl SourceConfig for KafkaSourceConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_redeliver(&self) -> bool {
+        true
+    }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
 async fn kafka_source(
     consumer: StreamConsumer<KafkaStatisticsContext>,
     key_field: String,
+    key_decoding: KeyDecoding,
     topic_key: String,
     partition_key: String,
     offset_key: String,
@@ -180,11 +273,18 @@ async fn kafka_source(
     shutdown: ShutdownSignal,
     mut out: SourceSender,
     acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
+    redeliveries: Redeliveries,
 ) -> Result<(), ()> {
     let consumer = Arc::new(consumer);
     let shutdown = shutdown.shared();
-    let mut finalizer = acknowledgements
-        .then(|| OrderedFinalizer::new(shutdown.clone(), mark_done(Arc::clone(&consumer))));
+    let mut finalizer = acknowledgements.then(|| {
+        OrderedFinalizer::with_timeout(
+            shutdown.clone(),
+            acknowledgement_timeout,
+            mark_done(Arc::clone(&consumer), redeliveries),
+        )
+    });
     let mut stream = consumer.stream().take_until(shutdown);
     let schema = log_schema();
 
@@ -213,22 +313,10 @@ async fn kafka_source(
 
                 let msg_key = msg
                     .key()
-                    .map(|key| Value::from(String::from_utf8_lossy(key).to_string()))
+                    .map(|key| key_decoding.decode(key))
                     .unwrap_or(Value::Null);
 
-                let mut headers_map = BTreeMap::new();
-                if let Some(headers) = msg.headers() {
-                    // Using index-based for loop because rdkafka's `Headers` trait
-                    // does not provide Iterator-based API
-                    for i in 0..headers.count() {
-                        if let Some(header) = headers.get(i) {
-                            headers_map.insert(
-                                header.0.to_string(),
-                                Bytes::from(header.1.to_owned()).into(),
-                            );
-                        }
-                    }
-                }
+                let headers_map = msg.headers().map(collect_headers).unwrap_or_default();
 
                 let msg_topic = Bytes::copy_from_slice(msg.topic().as_bytes());
                 let msg_partition = msg.partition();
@@ -314,6 +402,32 @@ async fn kafka_source(
     Ok(())
 }
 
+/// Collects the headers into a map, the values of a header repeated in the message being
+/// collected into an array, in order.
+fn collect_headers<H: Headers>(headers: &H) -> BTreeMap<String, Value> {
+    let mut map = BTreeMap::new();
+    // Using index-based for loop because rdkafka's `Headers` trait
+    // does not provide Iterator-based API
+    for i in 0..headers.count() {
+        if let Some((name, value)) = headers.get(i) {
+            let value = Value::from(Bytes::copy_from_slice(value));
+            match map.entry(name.to_string()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+                Entry::Occupied(mut entry) => match entry.get_mut() {
+                    Value::Array(values) => values.push(value),
+                    previous => {
+                        let first = std::mem::replace(previous, Value::Null);
+                        *previous = Value::Array(vec![first, value]);
+                    }
+                },
+            }
+        }
+    }
+    map
+}
+
 #[derive(Debug)]
 struct FinalizerEntry {
     topic: String,
@@ -331,9 +445,104 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
     }
 }
 
-fn mark_done(consumer: Arc<StreamConsumer<KafkaStatisticsContext>>) -> impl Fn(FinalizerEntry) {
-    move |entry| {
-        if let Err(error) = consumer.store_offset(&entry.topic, entry.partition, entry.offset) {
+/// How long to wait for the consumer to seek back to the messages to redeliver.
+const SEEK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// What to do with the offset of a finalized message.
+#[derive(Debug, PartialEq, Eq)]
+enum Finalization {
+    /// The offset is stored, to be committed.
+    Store,
+    /// The partition is rewound to the offset, so that the message is consumed again.
+    Seek,
+    /// The offset is left as is, the message being consumed again after an earlier one.
+    Skip,
+}
+
+/// Tracks the messages consumed again, so that those failing over and over are eventually
+/// committed, and that the offsets of the messages after them are not committed before they are
+/// delivered.
+#[derive(Debug)]
+struct Redeliveries {
+    redeliver_errored: bool,
+    max_redeliveries: u32,
+    /// The number of times the messages were consumed again, by topic, partition and offset.
+    counts: HashMap<(String, i32, i64), u32>,
+    /// The offset each partition was rewound to, by topic and partition.
+    rewound: HashMap<(String, i32), i64>,
+}
+
+impl Redeliveries {
+    fn new(redeliver_errored: bool, max_redeliveries: u32) -> Self {
+        Self {
+            redeliver_errored,
+            max_redeliveries,
+            counts: HashMap::new(),
+            rewound: HashMap::new(),
+        }
+    }
+
+    fn finalize(
+        &mut self,
+        outcome: AcknowledgementOutcome,
+        entry: &FinalizerEntry,
+    ) -> Finalization {
+        let partition = (entry.topic.clone(), entry.partition);
+        if let Some(&offset) = self.rewound.get(&partition) {
+            // The messages after the one the partition was rewound to are consumed again after it,
+            // so finalizing those consumed before the seek would commit them too early.
+            if entry.offset > offset {
+                return Finalization::Skip;
+            }
+            self.rewound.remove(&partition);
+        }
+
+        let redeliver = match outcome {
+            AcknowledgementOutcome::TimedOut(action) => {
+                action == AcknowledgementTimeoutAction::Redeliver
+            }
+            AcknowledgementOutcome::Acknowledged(status) => {
+                status == BatchStatus::Errored && self.redeliver_errored
+            }
+        };
+        let key = (entry.topic.clone(), entry.partition, entry.offset);
+        let count = self.counts.remove(&key).unwrap_or(0);
+        if !redeliver {
+            return Finalization::Store;
+        }
+        if count >= self.max_redeliveries {
+            emit!(&KafkaRedeliveriesExhausted {
+                topic: &entry.topic,
+                partition: entry.partition,
+                offset: entry.offset,
+                redeliveries: count,
+            });
+            return Finalization::Store;
+        }
+        self.counts.insert(key, count + 1);
+        self.rewound.insert(partition, entry.offset);
+        Finalization::Seek
+    }
+}
+
+fn mark_done(
+    consumer: Arc<StreamConsumer<KafkaStatisticsContext>>,
+    mut redeliveries: Redeliveries,
+) -> impl FnMut(AcknowledgementOutcome, FinalizerEntry) {
+    move |outcome, entry| {
+        let result = match redeliveries.finalize(outcome, &entry) {
+            Finalization::Store => {
+                consumer.store_offset(&entry.topic, entry.partition, entry.offset)
+            }
+            Finalization::Seek => consumer.seek(
+                &entry.topic,
+                entry.partition,
+                Offset::Offset(entry.offset),
+                SEEK_TIMEOUT,
+            ),
+            Finalization::Skip => Ok(()),
+        };
+        if let Err(error) = result {
             emit!(&KafkaOffsetUpdateError { error });
         }
     }
@@ -371,14 +580,29 @@ fn create_consumer(
     let consumer = client_config
         .create_with_context::<_, StreamConsumer<_>>(KafkaStatisticsContext)
         .context(KafkaCreateSnafu)?;
-    let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
-    consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
+    if config.assignments.is_empty() {
+        if config.topics.is_empty() {
+            return Err(BuildError::NoTopics.into());
+        }
+        let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
+    } else {
+        let mut partitions = TopicPartitionList::new();
+        for assignment in &config.assignments {
+            partitions
+                .add_partition_offset(&assignment.topic, assignment.partition, assignment.offset())
+                .context(KafkaAssignSnafu)?;
+        }
+        consumer.assign(&partitions).context(KafkaAssignSnafu)?;
+    }
 
     Ok(consumer)
 }
 
 #[cfg(test)]
 mod test {
+    use rdkafka::message::OwnedHeaders;
+
     use super::*;
 
     pub fn kafka_host() -> String {
@@ -427,6 +651,154 @@ mod test {
         };
         assert!(create_consumer(&config).is_err());
     }
+
+    #[tokio::test]
+    async fn consumer_create_assignments_ok() {
+        let config: KafkaSourceConfig = toml::from_str(&format!(
+            r#"
+            bootstrap_servers = "{}"
+            group_id = "group"
+
+            [[assignments]]
+            topic = "topic"
+            partition = 0
+            offset = 42
+
+            [[assignments]]
+            topic = "topic"
+            partition = 1
+            offset = -10
+            "#,
+            kafka_address(9091)
+        ))
+        .unwrap();
+        assert_eq!(config.assignments[0].offset(), Offset::Offset(42));
+        assert_eq!(config.assignments[1].offset(), Offset::OffsetTail(10));
+        assert!(create_consumer(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_without_topics() {
+        let config = KafkaSourceConfig {
+            topics: vec![],
+            ..make_config("topic", "group")
+        };
+        assert!(create_consumer(&config).is_err());
+    }
+
+    #[test]
+    fn decodes_keys() {
+        assert_eq!(KeyDecoding::Bytes.decode(b"{\"a\":1}"), "{\"a\":1}".into());
+        let mut expected = BTreeMap::new();
+        expected.insert("a".to_string(), Value::from(1));
+        assert_eq!(
+            KeyDecoding::Json.decode(b"{\"a\":1}"),
+            Value::from(expected)
+        );
+        assert_eq!(KeyDecoding::Json.decode(b"not json"), "not json".into());
+    }
+
+    #[test]
+    fn collects_repeated_headers() {
+        let headers = OwnedHeaders::new()
+            .add("a", "1")
+            .add("b", "2")
+            .add("a", "3");
+        let mut expected = BTreeMap::new();
+        expected.insert(
+            "a".to_string(),
+            Value::from(vec![Value::from("1"), "3".into()]),
+        );
+        expected.insert("b".to_string(), Value::from("2"));
+        assert_eq!(collect_headers(&headers), expected);
+    }
+
+    fn entry(partition: i32, offset: i64) -> FinalizerEntry {
+        FinalizerEntry {
+            topic: "topic".into(),
+            partition,
+            offset,
+        }
+    }
+
+    #[test]
+    fn redelivers_only_when_asked_to() {
+        let errored = AcknowledgementOutcome::Acknowledged(BatchStatus::Errored);
+        let timed_out = AcknowledgementOutcome::TimedOut(AcknowledgementTimeoutAction::Redeliver);
+        let dropped = AcknowledgementOutcome::TimedOut(AcknowledgementTimeoutAction::Drop);
+
+        let mut redeliveries = Redeliveries::new(false, 3);
+        assert_eq!(
+            redeliveries.finalize(errored, &entry(0, 1)),
+            Finalization::Store
+        );
+        assert_eq!(
+            redeliveries.finalize(dropped, &entry(0, 2)),
+            Finalization::Store
+        );
+        assert_eq!(
+            redeliveries.finalize(timed_out, &entry(0, 3)),
+            Finalization::Seek
+        );
+
+        let mut redeliveries = Redeliveries::new(true, 3);
+        assert_eq!(
+            redeliveries.finalize(errored, &entry(0, 1)),
+            Finalization::Seek
+        );
+    }
+
+    #[test]
+    fn skips_messages_after_rewinding() {
+        let delivered = AcknowledgementOutcome::Acknowledged(BatchStatus::Delivered);
+        let errored = AcknowledgementOutcome::Acknowledged(BatchStatus::Errored);
+        let mut redeliveries = Redeliveries::new(true, 3);
+
+        assert_eq!(
+            redeliveries.finalize(errored, &entry(0, 1)),
+            Finalization::Seek
+        );
+        // Consumed before the seek, and consumed again after the message redelivered.
+        assert_eq!(
+            redeliveries.finalize(delivered, &entry(0, 2)),
+            Finalization::Skip
+        );
+        // Other partitions are not rewound.
+        assert_eq!(
+            redeliveries.finalize(delivered, &entry(1, 2)),
+            Finalization::Store
+        );
+        // Consumed again.
+        assert_eq!(
+            redeliveries.finalize(delivered, &entry(0, 1)),
+            Finalization::Store
+        );
+        assert_eq!(
+            redeliveries.finalize(delivered, &entry(0, 2)),
+            Finalization::Store
+        );
+    }
+
+    #[test]
+    fn commits_after_too_many_redeliveries() {
+        let errored = AcknowledgementOutcome::Acknowledged(BatchStatus::Errored);
+        let mut redeliveries = Redeliveries::new(true, 2);
+
+        assert_eq!(
+            redeliveries.finalize(errored, &entry(0, 1)),
+            Finalization::Seek
+        );
+        assert_eq!(
+            redeliveries.finalize(errored, &entry(0, 1)),
+            Finalization::Seek
+        );
+        assert_eq!(
+            redeliveries.finalize(errored, &entry(0, 1)),
+            Finalization::Store
+        );
+        assert!(redeliveries.counts.is_empty());
+        assert!(redeliveries.rewound.is_empty());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -522,6 +894,7 @@ mod integration_test {
         tokio::spawn(kafka_source(
             create_consumer(&config).unwrap(),
             config.key_field,
+            config.key_decoding,
             config.topic_key,
             config.partition_key,
             config.offset_key,
@@ -530,6 +903,8 @@ mod integration_test {
             shutdown,
             tx,
             acknowledgements,
+            AcknowledgementTimeout::default(),
+            Redeliveries::new(false, default_max_redeliveries()),
         ));
         let events = collect_n(rx, 10).await;
         drop(trigger_shutdown);
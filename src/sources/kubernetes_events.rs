@@ -0,0 +1,528 @@
+//! This mod implements `kubernetes_events` source.
+//! The scope of this source is to watch the Kubernetes Events API, and to
+//! forward each `Event` as a structured log event. Unlike `kubernetes_logs`,
+//! this source doesn't need to run on every node, a single instance per
+//! cluster is enough.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use k8s_openapi::{
+    api::core::v1::{Event as K8sEvent, ObjectReference},
+    http::{Request, StatusCode},
+    RequestError, ResponseBody, WatchOptional, WatchResponse,
+};
+use serde::{Deserialize, Serialize};
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent},
+    internal_events::{
+        KubernetesEventsCheckpointError, KubernetesEventsReceived, StreamClosedError,
+    },
+    kubernetes as k8s,
+    kubernetes::{state, watch_request_builder::WatchRequestBuilder},
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+/// The name of the file holding the last resource version seen.
+const CHECKPOINT_FILENAME: &str = "checkpoint.txt";
+
+/// Configuration for the `kubernetes_events` source.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    /// The namespace to watch `Event`s in. `Event`s of all namespaces are
+    /// watched if not set.
+    namespace: Option<String>,
+
+    /// Specifies the label selector to filter `Event`s with.
+    label_selector: Option<String>,
+
+    /// Specifies the field selector to filter `Event`s with, e.g.
+    /// `type=Warning`.
+    field_selector: Option<String>,
+
+    /// Override global data_dir
+    data_dir: Option<PathBuf>,
+
+    /// Optional path to a kubeconfig file readable by Vector. If not set,
+    /// Vector will try to connect to Kubernetes using in-cluster configuration.
+    kube_config_file: Option<PathBuf>,
+
+    /// How often to checkpoint the resource version of the last `Event`
+    /// seen, so the watch can resume from it after a restart.
+    checkpoint_interval_secs: u64,
+}
+
+inventory::submit! {
+    SourceDescription::new::<Config>(COMPONENT_ID)
+}
+
+impl GenerateConfig for Config {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(&Self::default()).unwrap()
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            namespace: None,
+            label_selector: None,
+            field_selector: None,
+            data_dir: None,
+            kube_config_file: None,
+            checkpoint_interval_secs: 5,
+        }
+    }
+}
+
+const COMPONENT_ID: &str = "kubernetes_events";
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "kubernetes_events")]
+impl SourceConfig for Config {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let k8s_config = match &self.kube_config_file {
+            Some(kc) => k8s::client::config::Config::kubeconfig(kc)?,
+            None => k8s::client::config::Config::in_cluster()?,
+        };
+        let client = k8s::client::Client::new(k8s_config, &cx.proxy)?;
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+
+        let request_builder = match &self.namespace {
+            Some(namespace) => EventsRequestBuilder::Namespaced(namespace.clone()),
+            None => EventsRequestBuilder::AllNamespaces,
+        };
+
+        Ok(Box::pin(run(
+            client,
+            request_builder,
+            self.field_selector.clone(),
+            self.label_selector.clone(),
+            data_dir.join(CHECKPOINT_FILENAME),
+            Duration::from_secs(self.checkpoint_interval_secs.max(1)),
+            cx.out,
+            cx.shutdown,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        COMPONENT_ID
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Builds watch requests for `Event`s, either of a single namespace or of all of them.
+enum EventsRequestBuilder {
+    AllNamespaces,
+    Namespaced(String),
+}
+
+impl WatchRequestBuilder for EventsRequestBuilder {
+    type Object = K8sEvent;
+
+    fn build(&self, watch_optional: WatchOptional<'_>) -> Result<Request<Vec<u8>>, RequestError> {
+        type Built = (
+            Request<Vec<u8>>,
+            fn(StatusCode) -> ResponseBody<WatchResponse<K8sEvent>>,
+        );
+
+        let (request, _): Built = match self {
+            Self::AllNamespaces => K8sEvent::watch_event_for_all_namespaces(watch_optional)?,
+            Self::Namespaced(namespace) => {
+                K8sEvent::watch_namespaced_event(namespace, watch_optional)?
+            }
+        };
+        Ok(request)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run(
+    client: k8s::client::Client,
+    request_builder: EventsRequestBuilder,
+    field_selector: Option<String>,
+    label_selector: Option<String>,
+    checkpoint_path: PathBuf,
+    checkpoint_interval: Duration,
+    out: SourceSender,
+    shutdown: ShutdownSignal,
+) -> Result<(), ()> {
+    let mut checkpointer = Checkpointer::new(checkpoint_path);
+    let resource_version = Arc::new(Mutex::new(None));
+
+    let watcher = k8s::api_watcher::ApiWatcher::new(client, request_builder);
+    let watcher = k8s::instrumenting_watcher::InstrumentingWatcher::new(watcher);
+    let writer = EventsWriter::new(out, Arc::clone(&resource_version));
+
+    let mut reflector = k8s::reflector::Reflector::new(
+        watcher,
+        writer,
+        field_selector,
+        label_selector,
+        Duration::from_secs(1),
+    );
+    match checkpointer.read().await {
+        Ok(Some(checkpoint)) => reflector.resume_from(checkpoint),
+        Ok(None) => {}
+        Err(error) => emit!(&KubernetesEventsCheckpointError { error }),
+    }
+
+    let reflector_process = reflector.run();
+    tokio::pin!(reflector_process);
+    tokio::pin!(shutdown);
+
+    let mut interval = tokio::time::interval(checkpoint_interval);
+    let result = loop {
+        tokio::select! {
+            result = &mut reflector_process => {
+                let error = match result {
+                    Ok(infallible) => match infallible {},
+                    Err(error) => error,
+                };
+                error!(message = "Reflector process exited with an error.", %error);
+                break Err(());
+            }
+            _ = interval.tick() => {
+                checkpointer.sync(&resource_version).await;
+            }
+            _ = &mut shutdown => break Ok(()),
+        }
+    };
+
+    checkpointer.sync(&resource_version).await;
+    result
+}
+
+/// Forwards watched `Event`s as log events, in place of maintaining a local
+/// copy of the state.
+struct EventsWriter {
+    out: SourceSender,
+    /// The highest `count` forwarded for each `Event`, by uid.
+    seen: HashMap<String, i32>,
+    /// The resource version of the last `Event` forwarded.
+    resource_version: Arc<Mutex<Option<String>>>,
+}
+
+impl EventsWriter {
+    fn new(out: SourceSender, resource_version: Arc<Mutex<Option<String>>>) -> Self {
+        Self {
+            out,
+            seen: HashMap::new(),
+            resource_version,
+        }
+    }
+
+    /// Returns `false` for an `Event` that was already forwarded with the same
+    /// `count`, as happens when the watch is resumed or the list is resynced.
+    /// Repeated occurrences of an `Event` increase its `count`, and are
+    /// forwarded.
+    fn is_new(&mut self, event: &K8sEvent) -> bool {
+        let uid = match &event.metadata.uid {
+            Some(uid) => uid.clone(),
+            None => return true,
+        };
+        let count = event.count.unwrap_or(1);
+
+        let seen = self.seen.entry(uid).or_insert(0);
+        if *seen >= count {
+            false
+        } else {
+            *seen = count;
+            true
+        }
+    }
+
+    async fn forward(&mut self, event: K8sEvent) {
+        if self.is_new(&event) {
+            let log = create_log(&event);
+            emit!(&KubernetesEventsReceived {
+                byte_size: log.size_of(),
+            });
+
+            if let Err(error) = self.out.send_event(Event::from(log)).await {
+                emit!(&StreamClosedError { error, count: 1 });
+                return;
+            }
+        }
+
+        if let Some(resource_version) = event.metadata.resource_version {
+            *self.resource_version.lock().unwrap() = Some(resource_version);
+        }
+    }
+}
+
+#[async_trait]
+impl state::Write for EventsWriter {
+    type Item = K8sEvent;
+
+    async fn add(&mut self, item: Self::Item) {
+        self.forward(item).await;
+    }
+
+    async fn update(&mut self, item: Self::Item) {
+        self.forward(item).await;
+    }
+
+    async fn delete(&mut self, item: Self::Item) {
+        // `Event`s are deleted once they expire, so they won't be seen again.
+        if let Some(uid) = &item.metadata.uid {
+            self.seen.remove(uid);
+        }
+    }
+
+    async fn resync(&mut self) {
+        // The list that follows a resync is deduplicated against `seen`.
+    }
+}
+
+#[async_trait]
+impl state::MaintainedWrite for EventsWriter {
+    fn maintenance_request(&mut self) -> Option<BoxFuture<'_, ()>> {
+        None
+    }
+
+    async fn perform_maintenance(&mut self) {}
+}
+
+fn insert_object_reference(log: &mut LogEvent, prefix: &str, object: &ObjectReference) {
+    let fields = [
+        ("api_version", &object.api_version),
+        ("field_path", &object.field_path),
+        ("kind", &object.kind),
+        ("name", &object.name),
+        ("namespace", &object.namespace),
+        ("resource_version", &object.resource_version),
+        ("uid", &object.uid),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            log.insert(format!("{}.{}", prefix, key).as_str(), value.clone());
+        }
+    }
+}
+
+/// Creates a log event from a Kubernetes `Event`. The timestamp is the time of
+/// the most recent occurrence of the `Event`.
+fn create_log(event: &K8sEvent) -> LogEvent {
+    let mut log = LogEvent::default();
+
+    if let Some(message) = &event.message {
+        log.insert(log_schema().message_key(), message.clone());
+    }
+
+    let timestamp = event
+        .last_timestamp
+        .as_ref()
+        .map(|time| time.0)
+        .or_else(|| event.event_time.as_ref().map(|time| time.0))
+        .or_else(|| event.first_timestamp.as_ref().map(|time| time.0))
+        .unwrap_or_else(Utc::now);
+    log.insert(log_schema().timestamp_key(), timestamp);
+    log.insert(log_schema().source_type_key(), Bytes::from(COMPONENT_ID));
+
+    let fields = [
+        ("reason", &event.reason),
+        ("type", &event.type_),
+        ("action", &event.action),
+        ("reporting_component", &event.reporting_component),
+        ("reporting_instance", &event.reporting_instance),
+        ("metadata.name", &event.metadata.name),
+        ("metadata.namespace", &event.metadata.namespace),
+        ("metadata.uid", &event.metadata.uid),
+        (
+            "metadata.resource_version",
+            &event.metadata.resource_version,
+        ),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            log.insert(key, value.clone());
+        }
+    }
+
+    log.insert("count", event.count.unwrap_or(1));
+    if let Some(time) = &event.first_timestamp {
+        log.insert("first_timestamp", time.0);
+    }
+    if let Some(time) = &event.last_timestamp {
+        log.insert("last_timestamp", time.0);
+    }
+
+    if let Some(source) = &event.source {
+        if let Some(component) = &source.component {
+            log.insert("source.component", component.clone());
+        }
+        if let Some(host) = &source.host {
+            log.insert("source.host", host.clone());
+        }
+    }
+
+    insert_object_reference(&mut log, "involved_object", &event.involved_object);
+    if let Some(related) = &event.related {
+        insert_object_reference(&mut log, "related", related);
+    }
+
+    log
+}
+
+/// Keeps the resource version of the last `Event` forwarded in a file.
+struct Checkpointer {
+    path: PathBuf,
+    /// The last resource version written, to skip redundant writes.
+    written: Option<String>,
+}
+
+impl Checkpointer {
+    const fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            written: None,
+        }
+    }
+
+    async fn read(&mut self) -> io::Result<Option<String>> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => {
+                let resource_version = contents.trim();
+                self.written = (!resource_version.is_empty()).then(|| resource_version.to_owned());
+                Ok(self.written.clone())
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Writes the current resource version, if it changed since the last write.
+    async fn sync(&mut self, resource_version: &Mutex<Option<String>>) {
+        let resource_version = resource_version.lock().unwrap().clone();
+        if resource_version.is_none() || resource_version == self.written {
+            return;
+        }
+
+        let resource_version = resource_version.unwrap();
+        match write_atomic(&self.path, &resource_version).await {
+            Ok(()) => self.written = Some(resource_version),
+            Err(error) => emit!(&KubernetesEventsCheckpointError { error }),
+        }
+    }
+}
+
+/// Writes to a temporary file first, so a crash never leaves a truncated checkpoint.
+async fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, format!("{}\n", contents)).await?;
+    tokio::fs::rename(&tmp_path, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+
+    use super::*;
+
+    fn make_event(uid: &str, count: i32) -> K8sEvent {
+        K8sEvent {
+            count: Some(count),
+            message: Some("Back-off restarting failed container".to_owned()),
+            reason: Some("BackOff".to_owned()),
+            type_: Some("Warning".to_owned()),
+            last_timestamp: Some(Time(Utc.ymd(2022, 3, 1).and_hms(12, 0, 0))),
+            metadata: ObjectMeta {
+                name: Some("app.16d7e4b3".to_owned()),
+                namespace: Some("default".to_owned()),
+                uid: Some(uid.to_owned()),
+                resource_version: Some("1234".to_owned()),
+                ..ObjectMeta::default()
+            },
+            involved_object: ObjectReference {
+                kind: Some("Pod".to_owned()),
+                name: Some("app".to_owned()),
+                namespace: Some("default".to_owned()),
+                ..ObjectReference::default()
+            },
+            ..K8sEvent::default()
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<Config>();
+    }
+
+    #[test]
+    fn creates_log_with_involved_object() {
+        let log = create_log(&make_event("a", 3));
+
+        assert_eq!(
+            log[log_schema().message_key()],
+            "Back-off restarting failed container".into()
+        );
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.ymd(2022, 3, 1).and_hms(12, 0, 0).into()
+        );
+        assert_eq!(log["reason"], "BackOff".into());
+        assert_eq!(log["type"], "Warning".into());
+        assert_eq!(log["count"], 3.into());
+        assert_eq!(log["involved_object.kind"], "Pod".into());
+        assert_eq!(log["involved_object.name"], "app".into());
+        assert_eq!(log["metadata.resource_version"], "1234".into());
+        assert!(log.get("related").is_none());
+    }
+
+    #[test]
+    fn deduplicates_by_uid_and_count() {
+        let (out, _rx) = SourceSender::new_test();
+        let mut writer = EventsWriter::new(out, Arc::new(Mutex::new(None)));
+
+        assert!(writer.is_new(&make_event("a", 1)));
+        assert!(!writer.is_new(&make_event("a", 1)));
+        assert!(writer.is_new(&make_event("a", 2)));
+        assert!(!writer.is_new(&make_event("a", 1)));
+        assert!(writer.is_new(&make_event("b", 1)));
+    }
+
+    #[tokio::test]
+    async fn checkpoints_resource_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CHECKPOINT_FILENAME);
+        let resource_version = Mutex::new(None);
+
+        let mut checkpointer = Checkpointer::new(path.clone());
+        assert_eq!(checkpointer.read().await.unwrap(), None);
+
+        *resource_version.lock().unwrap() = Some("42".to_owned());
+        checkpointer.sync(&resource_version).await;
+
+        let mut checkpointer = Checkpointer::new(path);
+        assert_eq!(checkpointer.read().await.unwrap(), Some("42".to_owned()));
+    }
+}
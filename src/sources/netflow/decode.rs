@@ -0,0 +1,166 @@
+//! Types shared by the decoders of the flow protocols.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use chrono::{DateTime, Utc};
+use snafu::Snafu;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum DecodeError {
+    #[snafu(display("Unexpected end of datagram"))]
+    Truncated,
+    #[snafu(display("Unsupported version {}", version))]
+    UnsupportedVersion { version: u32 },
+    #[snafu(display("Unknown address type {}", address_type))]
+    UnknownAddressType { address_type: u32 },
+    #[snafu(display("Invalid length {} of {}", length, what))]
+    InvalidLength { what: &'static str, length: usize },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Integer(u64),
+    Text(String),
+}
+
+impl From<u64> for FieldValue {
+    fn from(value: u64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<u32> for FieldValue {
+    fn from(value: u32) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<u16> for FieldValue {
+    fn from(value: u16) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<u8> for FieldValue {
+    fn from(value: u8) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_owned())
+    }
+}
+
+impl From<Ipv4Addr> for FieldValue {
+    fn from(value: Ipv4Addr) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<Ipv6Addr> for FieldValue {
+    fn from(value: Ipv6Addr) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+pub type Fields = Vec<(String, FieldValue)>;
+
+/// The flow records of a datagram, along with the fields of its header that apply to all of
+/// them.
+#[derive(Debug, Default)]
+pub struct Packet {
+    pub flow_type: &'static str,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub header: Fields,
+    pub records: Vec<Fields>,
+    /// The IDs of data sets that couldn't be decoded, as their template wasn't received yet.
+    pub missing_templates: Vec<u16>,
+}
+
+/// A big-endian cursor over a datagram.
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub const fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.data.len() < len {
+            return Err(DecodeError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Splits off the next `len` bytes into a reader of their own.
+    pub fn sub(&mut self, len: usize) -> Result<Reader<'a>, DecodeError> {
+        self.bytes(len).map(Reader::new)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, DecodeError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, DecodeError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from(self.u32()?) << 32 | u64::from(self.u32()?))
+    }
+
+    pub fn ipv4(&mut self) -> Result<Ipv4Addr, DecodeError> {
+        self.u32().map(Ipv4Addr::from)
+    }
+
+    pub fn ipv6(&mut self) -> Result<Ipv6Addr, DecodeError> {
+        let mut octets = [0; 16];
+        octets.copy_from_slice(self.bytes(16)?);
+        Ok(Ipv6Addr::from(octets))
+    }
+}
+
+/// Decodes an unsigned integer of up to eight bytes, as used by the templated protocols for
+/// reduced-size encoding.
+pub fn decode_unsigned(bytes: &[u8]) -> Option<u64> {
+    (!bytes.is_empty() && bytes.len() <= 8).then(|| {
+        bytes
+            .iter()
+            .fold(0u64, |value, &byte| (value << 8) | u64::from(byte))
+    })
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub fn timestamp(secs: u32, nanos: u32) -> Option<DateTime<Utc>> {
+    use chrono::TimeZone;
+    Utc.timestamp_opt(secs.into(), nanos).single()
+}
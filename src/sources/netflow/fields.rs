@@ -0,0 +1,195 @@
+//! Names and types of the information elements registered with IANA, which NetFlow v9 shares
+//! with IPFIX for the IDs it defines.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::decode::{decode_unsigned, hex, FieldValue};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Unsigned,
+    Ipv4,
+    Ipv6,
+    Mac,
+    Text,
+}
+
+use Kind::*;
+
+const ELEMENTS: &[(u16, &str, Kind)] = &[
+    (1, "octet_delta_count", Unsigned),
+    (2, "packet_delta_count", Unsigned),
+    (3, "delta_flow_count", Unsigned),
+    (4, "protocol_identifier", Unsigned),
+    (5, "ip_class_of_service", Unsigned),
+    (6, "tcp_control_bits", Unsigned),
+    (7, "source_transport_port", Unsigned),
+    (8, "source_ipv4_address", Ipv4),
+    (9, "source_ipv4_prefix_length", Unsigned),
+    (10, "ingress_interface", Unsigned),
+    (11, "destination_transport_port", Unsigned),
+    (12, "destination_ipv4_address", Ipv4),
+    (13, "destination_ipv4_prefix_length", Unsigned),
+    (14, "egress_interface", Unsigned),
+    (15, "ip_next_hop_ipv4_address", Ipv4),
+    (16, "bgp_source_as_number", Unsigned),
+    (17, "bgp_destination_as_number", Unsigned),
+    (18, "bgp_next_hop_ipv4_address", Ipv4),
+    (19, "post_mcast_packet_delta_count", Unsigned),
+    (20, "post_mcast_octet_delta_count", Unsigned),
+    (21, "flow_end_sys_up_time", Unsigned),
+    (22, "flow_start_sys_up_time", Unsigned),
+    (23, "post_octet_delta_count", Unsigned),
+    (24, "post_packet_delta_count", Unsigned),
+    (25, "minimum_ip_total_length", Unsigned),
+    (26, "maximum_ip_total_length", Unsigned),
+    (27, "source_ipv6_address", Ipv6),
+    (28, "destination_ipv6_address", Ipv6),
+    (29, "source_ipv6_prefix_length", Unsigned),
+    (30, "destination_ipv6_prefix_length", Unsigned),
+    (31, "flow_label_ipv6", Unsigned),
+    (32, "icmp_type_code_ipv4", Unsigned),
+    (33, "igmp_type", Unsigned),
+    (34, "sampling_interval", Unsigned),
+    (35, "sampling_algorithm", Unsigned),
+    (36, "flow_active_timeout", Unsigned),
+    (37, "flow_idle_timeout", Unsigned),
+    (38, "engine_type", Unsigned),
+    (39, "engine_id", Unsigned),
+    (40, "exported_octet_total_count", Unsigned),
+    (41, "exported_message_total_count", Unsigned),
+    (42, "exported_flow_record_total_count", Unsigned),
+    (44, "source_ipv4_prefix", Ipv4),
+    (45, "destination_ipv4_prefix", Ipv4),
+    (46, "mpls_top_label_type", Unsigned),
+    (47, "mpls_top_label_ipv4_address", Ipv4),
+    (48, "sampler_id", Unsigned),
+    (49, "sampler_mode", Unsigned),
+    (50, "sampler_random_interval", Unsigned),
+    (52, "minimum_ttl", Unsigned),
+    (53, "maximum_ttl", Unsigned),
+    (54, "fragment_identification", Unsigned),
+    (55, "post_ip_class_of_service", Unsigned),
+    (56, "source_mac_address", Mac),
+    (57, "post_destination_mac_address", Mac),
+    (58, "vlan_id", Unsigned),
+    (59, "post_vlan_id", Unsigned),
+    (60, "ip_version", Unsigned),
+    (61, "flow_direction", Unsigned),
+    (62, "ip_next_hop_ipv6_address", Ipv6),
+    (63, "bgp_next_hop_ipv6_address", Ipv6),
+    (64, "ipv6_extension_headers", Unsigned),
+    (80, "destination_mac_address", Mac),
+    (81, "post_source_mac_address", Mac),
+    (82, "interface_name", Text),
+    (83, "interface_description", Text),
+    (85, "octet_total_count", Unsigned),
+    (86, "packet_total_count", Unsigned),
+    (88, "fragment_offset", Unsigned),
+    (89, "forwarding_status", Unsigned),
+    (136, "flow_end_reason", Unsigned),
+    (148, "flow_id", Unsigned),
+    (150, "flow_start_seconds", Unsigned),
+    (151, "flow_end_seconds", Unsigned),
+    (152, "flow_start_milliseconds", Unsigned),
+    (153, "flow_end_milliseconds", Unsigned),
+    (160, "system_init_time_milliseconds", Unsigned),
+    (176, "icmp_type_ipv4", Unsigned),
+    (177, "icmp_code_ipv4", Unsigned),
+    (178, "icmp_type_ipv6", Unsigned),
+    (179, "icmp_code_ipv6", Unsigned),
+    (180, "udp_source_port", Unsigned),
+    (181, "udp_destination_port", Unsigned),
+    (182, "tcp_source_port", Unsigned),
+    (183, "tcp_destination_port", Unsigned),
+    (225, "post_nat_source_ipv4_address", Ipv4),
+    (226, "post_nat_destination_ipv4_address", Ipv4),
+    (227, "post_napt_source_transport_port", Unsigned),
+    (228, "post_napt_destination_transport_port", Unsigned),
+    (234, "ingress_vrf_id", Unsigned),
+    (235, "egress_vrf_id", Unsigned),
+];
+
+/// Names and decodes the value of an information element. Elements that aren't known, or
+/// that don't have the length their type requires, are named after their ID, and decoded as
+/// integers if they fit, or as hexadecimal bytes.
+pub fn decode(enterprise: Option<u32>, id: u16, bytes: &[u8]) -> (String, FieldValue) {
+    let known = match enterprise {
+        None => ELEMENTS
+            .binary_search_by_key(&id, |(element, _, _)| *element)
+            .ok()
+            .map(|index| ELEMENTS[index]),
+        Some(_) => None,
+    };
+
+    if let Some((_, name, kind)) = known {
+        if let Some(value) = decode_kind(kind, bytes) {
+            return (name.to_owned(), value);
+        }
+    }
+
+    let name = match enterprise {
+        Some(enterprise) => format!("enterprise_{}_{}", enterprise, id),
+        None => format!("field_{}", id),
+    };
+    (name, decode_raw(bytes))
+}
+
+fn decode_kind(kind: Kind, bytes: &[u8]) -> Option<FieldValue> {
+    match kind {
+        Unsigned => decode_unsigned(bytes).map(FieldValue::Integer),
+        Ipv4 => <[u8; 4]>::try_from(bytes)
+            .ok()
+            .map(|octets| Ipv4Addr::from(octets).into()),
+        Ipv6 => <[u8; 16]>::try_from(bytes)
+            .ok()
+            .map(|octets| Ipv6Addr::from(octets).into()),
+        Mac => (bytes.len() == 6).then(|| hex(bytes).into()),
+        Text => Some(String::from_utf8_lossy(bytes).trim_end_matches('\0').into()),
+    }
+}
+
+pub fn decode_raw(bytes: &[u8]) -> FieldValue {
+    decode_unsigned(bytes)
+        .map(FieldValue::Integer)
+        .unwrap_or_else(|| hex(bytes).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elements_are_sorted() {
+        assert!(ELEMENTS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn decodes_elements() {
+        assert_eq!(
+            decode(None, 8, &[10, 0, 0, 1]),
+            ("source_ipv4_address".to_owned(), "10.0.0.1".into())
+        );
+        // Reduced-size encoding.
+        assert_eq!(
+            decode(None, 1, &[0x01, 0x00]),
+            ("octet_delta_count".to_owned(), 256u64.into())
+        );
+        assert_eq!(
+            decode(None, 56, &[0, 0x1b, 0x21, 0x3c, 0x4d, 0x5e]),
+            ("source_mac_address".to_owned(), "00:1b:21:3c:4d:5e".into())
+        );
+        assert_eq!(
+            decode(Some(9), 12, &[0xff; 9]),
+            (
+                "enterprise_9_12".to_owned(),
+                "ff:ff:ff:ff:ff:ff:ff:ff:ff".into()
+            )
+        );
+        // An address with the wrong length.
+        assert_eq!(
+            decode(None, 12, &[1, 2]),
+            ("field_12".to_owned(), 258u64.into())
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! The `netflow` source collects flow telemetry over UDP. NetFlow v5, NetFlow v9, IPFIX and
+//! sFlow datagrams are told apart by their version, and each of their flow records becomes an
+//! event. The templates of NetFlow v9 and IPFIX are cached per exporter.
+
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        BytesReceived, NetflowDecodeError, NetflowEventsReceived, NetflowTemplateNotFound,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+mod decode;
+mod fields;
+mod sflow;
+mod template;
+mod v5;
+
+pub use self::decode::DecodeError;
+use self::{
+    decode::{FieldValue, Packet},
+    template::TemplateCache,
+};
+
+/// Large enough for any datagram.
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    #[serde(default = "default_address")]
+    #[derivative(Default(value = "default_address()"))]
+    address: SocketAddr,
+    host_key: Option<String>,
+    receive_buffer_bytes: Option<usize>,
+}
+
+fn default_address() -> SocketAddr {
+    SocketAddr::new([0, 0, 0, 0].into(), 2055)
+}
+
+inventory::submit! {
+    SourceDescription::new::<NetflowConfig>("netflow")
+}
+
+impl_generate_config_from_default!(NetflowConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let socket = UdpSocket::bind(self.address).await?;
+        if let Some(receive_buffer_bytes) = self.receive_buffer_bytes {
+            if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+                warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+            }
+        }
+
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_owned());
+
+        Ok(Box::pin(netflow_source(
+            socket,
+            host_key,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "netflow"
+    }
+}
+
+async fn netflow_source(
+    socket: UdpSocket,
+    host_key: String,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    info!(message = "Listening.", address = ?socket.local_addr().ok());
+
+    let mut templates = TemplateCache::default();
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (byte_size, peer) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(recv) => recv,
+                Err(error) => {
+                    error!(message = "Error receiving flow datagram.", %error);
+                    return Err(());
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+
+        emit!(&BytesReceived {
+            byte_size,
+            protocol: "udp",
+        });
+
+        let packet = match decode(&buf[..byte_size], peer, &mut templates) {
+            Ok(packet) => packet,
+            Err(error) => {
+                emit!(&NetflowDecodeError {
+                    error: &error,
+                    peer
+                });
+                continue;
+            }
+        };
+        for template_id in &packet.missing_templates {
+            emit!(&NetflowTemplateNotFound {
+                template_id: *template_id,
+                peer,
+            });
+        }
+
+        let events = to_events(packet, peer, &host_key);
+        if events.is_empty() {
+            continue;
+        }
+
+        let count = events.len();
+        emit!(&NetflowEventsReceived {
+            byte_size: events.size_of(),
+            count,
+        });
+
+        if let Err(error) = out.send_batch(events).await {
+            emit!(&StreamClosedError { error, count });
+            return Ok(());
+        }
+    }
+}
+
+fn decode(
+    data: &[u8],
+    exporter: SocketAddr,
+    templates: &mut TemplateCache,
+) -> Result<Packet, DecodeError> {
+    match data {
+        [0, 5, ..] => v5::decode(data),
+        [0, 9, ..] => templates.decode_v9(data, exporter),
+        [0, 10, ..] => templates.decode_ipfix(data, exporter),
+        // sFlow has a 32-bit version.
+        [0, 0, 0, 5, ..] => sflow::decode(data),
+        [a, b, ..] => Err(DecodeError::UnsupportedVersion {
+            version: u16::from_be_bytes([*a, *b]).into(),
+        }),
+        _ => Err(DecodeError::Truncated),
+    }
+}
+
+fn to_events(packet: Packet, exporter: SocketAddr, host_key: &str) -> Vec<Event> {
+    let timestamp = packet.timestamp.unwrap_or_else(Utc::now);
+
+    packet
+        .records
+        .into_iter()
+        .map(|record| {
+            let mut log = LogEvent::default();
+            for (name, value) in packet.header.iter().cloned().chain(record) {
+                log.insert_flat(name, to_value(value));
+            }
+            log.try_insert_flat("record_type", "flow");
+            log.insert(log_schema().source_type_key(), Bytes::from("netflow"));
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert(host_key, exporter.ip().to_string());
+            log.insert("flow_type", packet.flow_type);
+            Event::Log(log)
+        })
+        .collect()
+}
+
+fn to_value(value: FieldValue) -> Value {
+    match value {
+        FieldValue::Integer(value) => Value::from(value),
+        FieldValue::Text(text) => Value::from(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    fn exporter() -> SocketAddr {
+        "192.0.2.1:2055".parse().unwrap()
+    }
+
+    #[test]
+    fn netflow_v5_events() {
+        let mut data = vec![0, 5, 0, 2, 0, 0, 0, 1];
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&[10, 0, 0, 1]);
+        data.extend_from_slice(&[0; 44]);
+        data.extend_from_slice(&[10, 0, 0, 2]);
+        data.extend_from_slice(&[0; 44]);
+
+        let packet = decode(&data, exporter(), &mut TemplateCache::default()).unwrap();
+        let events = to_events(packet, exporter(), "host");
+        assert_eq!(events.len(), 2);
+
+        let log = events[1].as_log();
+        assert_eq!(log["flow_type"], "netflow_v5".into());
+        assert_eq!(log["record_type"], "flow".into());
+        assert_eq!(log["host"], "192.0.2.1".into());
+        assert_eq!(log["source_type"], "netflow".into());
+        assert_eq!(log["source_ipv4_address"], "10.0.0.2".into());
+        assert_eq!(log["sys_uptime"], 1.into());
+        assert_eq!(
+            log[log_schema().timestamp_key()]
+                .as_timestamp()
+                .unwrap()
+                .timestamp(),
+            1_600_000_000
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut templates = TemplateCache::default();
+        assert_eq!(
+            decode(&[0, 7, 0, 0], exporter(), &mut templates).unwrap_err(),
+            DecodeError::UnsupportedVersion { version: 7 }
+        );
+        assert_eq!(
+            decode(&[0], exporter(), &mut templates).unwrap_err(),
+            DecodeError::Truncated
+        );
+    }
+}
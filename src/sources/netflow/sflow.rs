@@ -0,0 +1,380 @@
+//! sFlow version 5. Flow samples are decoded down to the transport ports of their sampled
+//! packet headers, and counter samples to their generic interface counters. Other records,
+//! such as those of vendor extensions, are skipped.
+
+use super::decode::{hex, DecodeError, FieldValue, Fields, Packet, Reader};
+
+const FLOW_SAMPLE: u32 = 1;
+const COUNTERS_SAMPLE: u32 = 2;
+const EXPANDED_FLOW_SAMPLE: u32 = 3;
+const EXPANDED_COUNTERS_SAMPLE: u32 = 4;
+
+const RAW_PACKET_HEADER: u32 = 1;
+const EXTENDED_SWITCH: u32 = 1001;
+const GENERIC_INTERFACE_COUNTERS: u32 = 1;
+
+const HEADER_PROTOCOL_ETHERNET: u32 = 1;
+const HEADER_PROTOCOL_IPV4: u32 = 11;
+const HEADER_PROTOCOL_IPV6: u32 = 12;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+
+const PROTOCOL_TCP: u8 = 6;
+const PROTOCOL_UDP: u8 = 17;
+
+pub fn decode(data: &[u8]) -> Result<Packet, DecodeError> {
+    let mut reader = Reader::new(data);
+    let version = reader.u32()?;
+    if version != 5 {
+        return Err(DecodeError::UnsupportedVersion { version });
+    }
+    let agent_address = match reader.u32()? {
+        1 => FieldValue::from(reader.ipv4()?),
+        2 => FieldValue::from(reader.ipv6()?),
+        address_type => return Err(DecodeError::UnknownAddressType { address_type }),
+    };
+    let sub_agent_id = reader.u32()?;
+    let sequence = reader.u32()?;
+    let sys_uptime = reader.u32()?;
+    let sample_count = reader.u32()?;
+
+    let mut records = Vec::new();
+    for _ in 0..sample_count {
+        let format = reader.u32()?;
+        let length = reader.u32()? as usize;
+        let mut sample = reader.sub(length)?;
+        let record = match format {
+            FLOW_SAMPLE => decode_flow_sample(&mut sample, false)?,
+            EXPANDED_FLOW_SAMPLE => decode_flow_sample(&mut sample, true)?,
+            COUNTERS_SAMPLE => decode_counters_sample(&mut sample, false)?,
+            EXPANDED_COUNTERS_SAMPLE => decode_counters_sample(&mut sample, true)?,
+            // Including the samples of enterprises other than the standard one.
+            _ => continue,
+        };
+        records.push(record);
+    }
+
+    Ok(Packet {
+        flow_type: "sflow",
+        timestamp: None,
+        header: vec![
+            ("agent_address".to_owned(), agent_address),
+            ("sub_agent_id".to_owned(), sub_agent_id.into()),
+            ("sequence".to_owned(), sequence.into()),
+            ("sys_uptime".to_owned(), sys_uptime.into()),
+        ],
+        records,
+        missing_templates: Vec::new(),
+    })
+}
+
+fn push(fields: &mut Fields, name: &str, value: impl Into<FieldValue>) {
+    fields.push((name.to_owned(), value.into()));
+}
+
+fn decode_source_id(
+    sample: &mut Reader<'_>,
+    expanded: bool,
+    fields: &mut Fields,
+) -> Result<(), DecodeError> {
+    let (source_id_type, source_id_index) = if expanded {
+        (sample.u32()?, sample.u32()?)
+    } else {
+        let source_id = sample.u32()?;
+        (source_id >> 24, source_id & 0x00ff_ffff)
+    };
+    push(fields, "source_id_type", source_id_type);
+    push(fields, "source_id_index", source_id_index);
+    Ok(())
+}
+
+fn decode_flow_sample(sample: &mut Reader<'_>, expanded: bool) -> Result<Fields, DecodeError> {
+    let mut fields = vec![("record_type".to_owned(), "flow".into())];
+    push(&mut fields, "sample_sequence", sample.u32()?);
+    decode_source_id(sample, expanded, &mut fields)?;
+    push(&mut fields, "sampling_rate", sample.u32()?);
+    push(&mut fields, "sample_pool", sample.u32()?);
+    push(&mut fields, "drops", sample.u32()?);
+    let (input, output) = if expanded {
+        // The format of the interfaces is ignored, their values are kept as they are.
+        let (_, input) = (sample.u32()?, sample.u32()?);
+        let (_, output) = (sample.u32()?, sample.u32()?);
+        (input, output)
+    } else {
+        (sample.u32()?, sample.u32()?)
+    };
+    push(&mut fields, "ingress_interface", input);
+    push(&mut fields, "egress_interface", output);
+
+    let record_count = sample.u32()?;
+    for _ in 0..record_count {
+        let format = sample.u32()?;
+        let length = sample.u32()? as usize;
+        let mut record = sample.sub(length)?;
+        match format {
+            RAW_PACKET_HEADER => {
+                let protocol = record.u32()?;
+                push(&mut fields, "frame_length", record.u32()?);
+                let _stripped = record.u32()?;
+                let header_length = record.u32()? as usize;
+                let header = record.bytes(header_length)?;
+                decode_packet_header(protocol, header, &mut fields);
+            }
+            EXTENDED_SWITCH => {
+                push(&mut fields, "vlan_id", record.u32()?);
+                let _priority = record.u32()?;
+                push(&mut fields, "post_vlan_id", record.u32()?);
+            }
+            _ => (),
+        }
+    }
+    Ok(fields)
+}
+
+fn decode_counters_sample(sample: &mut Reader<'_>, expanded: bool) -> Result<Fields, DecodeError> {
+    let mut fields = vec![("record_type".to_owned(), "counters".into())];
+    push(&mut fields, "sample_sequence", sample.u32()?);
+    decode_source_id(sample, expanded, &mut fields)?;
+
+    let record_count = sample.u32()?;
+    for _ in 0..record_count {
+        let format = sample.u32()?;
+        let length = sample.u32()? as usize;
+        let mut record = sample.sub(length)?;
+        if format == GENERIC_INTERFACE_COUNTERS {
+            push(&mut fields, "if_index", record.u32()?);
+            push(&mut fields, "if_type", record.u32()?);
+            push(&mut fields, "if_speed", record.u64()?);
+            push(&mut fields, "if_direction", record.u32()?);
+            push(&mut fields, "if_status", record.u32()?);
+            push(&mut fields, "if_in_octets", record.u64()?);
+            push(&mut fields, "if_in_ucast_pkts", record.u32()?);
+            push(&mut fields, "if_in_multicast_pkts", record.u32()?);
+            push(&mut fields, "if_in_broadcast_pkts", record.u32()?);
+            push(&mut fields, "if_in_discards", record.u32()?);
+            push(&mut fields, "if_in_errors", record.u32()?);
+            push(&mut fields, "if_in_unknown_protos", record.u32()?);
+            push(&mut fields, "if_out_octets", record.u64()?);
+            push(&mut fields, "if_out_ucast_pkts", record.u32()?);
+            push(&mut fields, "if_out_multicast_pkts", record.u32()?);
+            push(&mut fields, "if_out_broadcast_pkts", record.u32()?);
+            push(&mut fields, "if_out_discards", record.u32()?);
+            push(&mut fields, "if_out_errors", record.u32()?);
+            push(&mut fields, "if_promiscuous_mode", record.u32()?);
+        }
+    }
+    Ok(fields)
+}
+
+/// Decodes what is available of a sampled packet header. Headers are usually truncated, so
+/// decoding just stops where they do.
+fn decode_packet_header(protocol: u32, header: &[u8], fields: &mut Fields) {
+    let mut reader = Reader::new(header);
+    let _ = match protocol {
+        HEADER_PROTOCOL_ETHERNET => decode_ethernet(&mut reader, fields),
+        HEADER_PROTOCOL_IPV4 => decode_ipv4(&mut reader, fields),
+        HEADER_PROTOCOL_IPV6 => decode_ipv6(&mut reader, fields),
+        _ => Ok(()),
+    };
+}
+
+fn decode_ethernet(reader: &mut Reader<'_>, fields: &mut Fields) -> Result<(), DecodeError> {
+    push(fields, "destination_mac_address", hex(reader.bytes(6)?));
+    push(fields, "source_mac_address", hex(reader.bytes(6)?));
+    let mut ethertype = reader.u16()?;
+    if ethertype == ETHERTYPE_VLAN {
+        push(fields, "dot1q_vlan_id", reader.u16()? & 0x0fff);
+        ethertype = reader.u16()?;
+    }
+    match ethertype {
+        ETHERTYPE_IPV4 => decode_ipv4(reader, fields),
+        ETHERTYPE_IPV6 => decode_ipv6(reader, fields),
+        _ => Ok(()),
+    }
+}
+
+fn decode_ipv4(reader: &mut Reader<'_>, fields: &mut Fields) -> Result<(), DecodeError> {
+    let version_ihl = reader.u8()?;
+    let header_length = (version_ihl & 0x0f) as usize * 4;
+    if version_ihl >> 4 != 4 || header_length < 20 {
+        return Ok(());
+    }
+    push(fields, "ip_version", 4u8);
+    push(fields, "ip_class_of_service", reader.u8()?);
+    push(fields, "ip_total_length", reader.u16()?);
+    let _identification = reader.u16()?;
+    let fragment = reader.u16()?;
+    push(fields, "ip_ttl", reader.u8()?);
+    let protocol = reader.u8()?;
+    push(fields, "protocol_identifier", protocol);
+    let _checksum = reader.u16()?;
+    push(fields, "source_ipv4_address", reader.ipv4()?);
+    push(fields, "destination_ipv4_address", reader.ipv4()?);
+    reader.bytes(header_length - 20)?;
+
+    // Only the first fragment has the transport header.
+    if fragment & 0x1fff == 0 {
+        decode_transport(reader, protocol, fields)?;
+    }
+    Ok(())
+}
+
+fn decode_ipv6(reader: &mut Reader<'_>, fields: &mut Fields) -> Result<(), DecodeError> {
+    let first = reader.u32()?;
+    if first >> 28 != 6 {
+        return Ok(());
+    }
+    push(fields, "ip_version", 6u8);
+    push(fields, "ip_class_of_service", ((first >> 20) & 0xff) as u8);
+    push(fields, "flow_label_ipv6", first & 0x000f_ffff);
+    let _payload_length = reader.u16()?;
+    let next_header = reader.u8()?;
+    push(fields, "protocol_identifier", next_header);
+    push(fields, "ip_ttl", reader.u8()?);
+    push(fields, "source_ipv6_address", reader.ipv6()?);
+    push(fields, "destination_ipv6_address", reader.ipv6()?);
+    // Extension headers aren't followed.
+    decode_transport(reader, next_header, fields)
+}
+
+fn decode_transport(
+    reader: &mut Reader<'_>,
+    protocol: u8,
+    fields: &mut Fields,
+) -> Result<(), DecodeError> {
+    if protocol == PROTOCOL_TCP || protocol == PROTOCOL_UDP {
+        push(fields, "source_transport_port", reader.u16()?);
+        push(fields, "destination_transport_port", reader.u16()?);
+    }
+    if protocol == PROTOCOL_TCP {
+        reader.bytes(9)?;
+        push(fields, "tcp_control_bits", reader.u8()?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use super::*;
+
+    fn octets(address: &str) -> Vec<u8> {
+        match address.parse().unwrap() {
+            IpAddr::V4(address) => address.octets().to_vec(),
+            IpAddr::V6(address) => address.octets().to_vec(),
+        }
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    fn get<'a>(record: &'a Fields, name: &str) -> Option<&'a FieldValue> {
+        record
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    fn datagram(samples: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut data = u32s(&[5, 1]);
+        data.extend(octets("192.0.2.10"));
+        data.extend(u32s(&[0, 12, 60_000, samples.len() as u32]));
+        for (format, sample) in samples {
+            data.extend(u32s(&[*format, sample.len() as u32]));
+            data.extend(sample);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_flow_sample() {
+        let mut header = vec![
+            0x00, 0x1b, 0x21, 0x3c, 0x4d, 0x5e, 0x00, 0x1b, 0x21, 0x3c, 0x4d, 0x5f,
+        ];
+        header.extend([0x81, 0x00, 0x00, 0x0a, 0x08, 0x00]);
+        header.extend([0x45, 0x00, 0x00, 0x3c, 0, 0, 0x40, 0, 64, 6, 0, 0]);
+        header.extend(octets("10.0.0.1"));
+        header.extend(octets("10.0.0.2"));
+        header.extend([0x30, 0x39, 0x01, 0xbb, 0, 0, 0, 0, 0, 0, 0, 0, 0x50, 0x02]);
+
+        let mut record = u32s(&[HEADER_PROTOCOL_ETHERNET, 1514, 4, header.len() as u32]);
+        record.extend(&header);
+        record.resize(record.len() + (4 - header.len() % 4) % 4, 0);
+
+        let mut sample = u32s(&[3, 0x0000_0007, 512, 8192, 0, 7, 8, 2]);
+        sample.extend(u32s(&[RAW_PACKET_HEADER, record.len() as u32]));
+        sample.extend(&record);
+        // An unknown record, which is skipped.
+        sample.extend(u32s(&[(9 << 12) | 1, 4, 0]));
+
+        let packet = decode(&datagram(&[(FLOW_SAMPLE, sample)])).unwrap();
+        assert_eq!(packet.flow_type, "sflow");
+        assert!(packet
+            .header
+            .contains(&("agent_address".to_owned(), "192.0.2.10".into())));
+
+        let record = &packet.records[0];
+        assert_eq!(get(record, "record_type"), Some(&"flow".into()));
+        assert_eq!(get(record, "sampling_rate"), Some(&512u32.into()));
+        assert_eq!(get(record, "source_id_index"), Some(&7u32.into()));
+        assert_eq!(get(record, "ingress_interface"), Some(&7u32.into()));
+        assert_eq!(get(record, "frame_length"), Some(&1514u32.into()));
+        assert_eq!(
+            get(record, "source_mac_address"),
+            Some(&"00:1b:21:3c:4d:5f".into())
+        );
+        assert_eq!(get(record, "dot1q_vlan_id"), Some(&10u16.into()));
+        assert_eq!(
+            get(record, "destination_ipv4_address"),
+            Some(&"10.0.0.2".into())
+        );
+        assert_eq!(get(record, "protocol_identifier"), Some(&6u8.into()));
+        assert_eq!(get(record, "source_transport_port"), Some(&12345u16.into()));
+        assert_eq!(
+            get(record, "destination_transport_port"),
+            Some(&443u16.into())
+        );
+        assert_eq!(get(record, "tcp_control_bits"), Some(&2u8.into()));
+    }
+
+    #[test]
+    fn decodes_truncated_header() {
+        let mut fields = Vec::new();
+        let mut header = vec![0x60, 0, 0, 0, 0, 0, 17, 64];
+        header.extend(octets("2001:db8::1"));
+        header.extend(octets("2001:db8::2"));
+        header.extend([0x30]);
+        decode_packet_header(HEADER_PROTOCOL_IPV6, &header, &mut fields);
+
+        assert_eq!(
+            get(&fields, "source_ipv6_address"),
+            Some(&"2001:db8::1".into())
+        );
+        assert_eq!(get(&fields, "protocol_identifier"), Some(&17u8.into()));
+        assert_eq!(get(&fields, "source_transport_port"), None);
+    }
+
+    #[test]
+    fn decodes_counters_sample() {
+        let mut counters = u32s(&[3, 6, 0, 1_000_000_000, 1, 1, 0, 1000, 10, 0, 0, 0, 0, 0]);
+        counters.extend(u32s(&[0, 2000, 20, 0, 0, 0, 1, 0]));
+        let mut sample = u32s(&[9, 3, 1, GENERIC_INTERFACE_COUNTERS, counters.len() as u32]);
+        sample.extend(&counters);
+
+        let packet = decode(&datagram(&[(COUNTERS_SAMPLE, sample)])).unwrap();
+        let record = &packet.records[0];
+        assert_eq!(get(record, "record_type"), Some(&"counters".into()));
+        assert_eq!(get(record, "if_index"), Some(&3u32.into()));
+        assert_eq!(get(record, "if_speed"), Some(&1_000_000_000u64.into()));
+        assert_eq!(get(record, "if_in_octets"), Some(&1000u64.into()));
+        assert_eq!(get(record, "if_out_octets"), Some(&2000u64.into()));
+        assert_eq!(get(record, "if_promiscuous_mode"), Some(&0u32.into()));
+    }
+}
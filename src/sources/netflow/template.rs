@@ -0,0 +1,437 @@
+//! NetFlow v9 (RFC 3954) and IPFIX (RFC 7011), whose data records are described by templates
+//! the exporters send periodically.
+
+use std::{collections::HashMap, net::SocketAddr};
+
+use super::{
+    decode::{timestamp, DecodeError, Fields, Packet, Reader},
+    fields,
+};
+
+const V9_TEMPLATE_SET: u16 = 0;
+const V9_OPTIONS_TEMPLATE_SET: u16 = 1;
+const IPFIX_TEMPLATE_SET: u16 = 2;
+const IPFIX_OPTIONS_TEMPLATE_SET: u16 = 3;
+const MIN_DATA_SET: u16 = 256;
+
+const ENTERPRISE_BIT: u16 = 0x8000;
+const VARIABLE_LENGTH: u16 = 0xffff;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Protocol {
+    V9,
+    Ipfix,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct TemplateField {
+    enterprise: Option<u32>,
+    id: u16,
+    length: u16,
+    scope: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Template {
+    fields: Vec<TemplateField>,
+    options: bool,
+}
+
+impl Template {
+    /// The length of the shortest record, used to tell records from the padding at the end of
+    /// a set.
+    fn min_record_len(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => length as usize,
+            })
+            .sum()
+    }
+}
+
+type TemplateKey = (SocketAddr, Protocol, u32, u16);
+
+/// The templates received from each exporter, keyed by the address they are sent from, the
+/// observation domain, or source ID, and the template ID.
+#[derive(Debug, Default)]
+pub struct TemplateCache {
+    templates: HashMap<TemplateKey, Template>,
+}
+
+impl TemplateCache {
+    pub fn decode_v9(&mut self, data: &[u8], exporter: SocketAddr) -> Result<Packet, DecodeError> {
+        let mut reader = Reader::new(data);
+        let _version = reader.u16()?;
+        let _count = reader.u16()?;
+        let sys_uptime = reader.u32()?;
+        let unix_secs = reader.u32()?;
+        let sequence = reader.u32()?;
+        let source_id = reader.u32()?;
+
+        let mut packet = Packet {
+            flow_type: "netflow_v9",
+            timestamp: timestamp(unix_secs, 0),
+            header: vec![
+                ("sys_uptime".to_owned(), sys_uptime.into()),
+                ("sequence".to_owned(), sequence.into()),
+                ("source_id".to_owned(), source_id.into()),
+            ],
+            ..Default::default()
+        };
+        self.decode_sets(reader, (exporter, Protocol::V9, source_id), &mut packet)?;
+        Ok(packet)
+    }
+
+    pub fn decode_ipfix(
+        &mut self,
+        data: &[u8],
+        exporter: SocketAddr,
+    ) -> Result<Packet, DecodeError> {
+        let mut reader = Reader::new(data);
+        let _version = reader.u16()?;
+        let length = reader.u16()? as usize;
+        if length < 16 || length > data.len() {
+            return Err(DecodeError::InvalidLength {
+                what: "IPFIX message",
+                length,
+            });
+        }
+        let mut reader = Reader::new(&data[..length]);
+        reader.bytes(4)?;
+        let export_time = reader.u32()?;
+        let sequence = reader.u32()?;
+        let observation_domain_id = reader.u32()?;
+
+        let mut packet = Packet {
+            flow_type: "ipfix",
+            timestamp: timestamp(export_time, 0),
+            header: vec![
+                ("sequence".to_owned(), sequence.into()),
+                (
+                    "observation_domain_id".to_owned(),
+                    observation_domain_id.into(),
+                ),
+            ],
+            ..Default::default()
+        };
+        self.decode_sets(
+            reader,
+            (exporter, Protocol::Ipfix, observation_domain_id),
+            &mut packet,
+        )?;
+        Ok(packet)
+    }
+
+    fn decode_sets(
+        &mut self,
+        mut reader: Reader<'_>,
+        (exporter, protocol, domain): (SocketAddr, Protocol, u32),
+        packet: &mut Packet,
+    ) -> Result<(), DecodeError> {
+        // Some exporters pad the end of the datagram.
+        while reader.remaining() >= 4 {
+            let id = reader.u16()?;
+            let length = reader.u16()? as usize;
+            if length < 4 {
+                return Err(DecodeError::InvalidLength {
+                    what: "set",
+                    length,
+                });
+            }
+            let mut set = reader.sub(length - 4)?;
+
+            match (protocol, id) {
+                (Protocol::V9, V9_TEMPLATE_SET) | (Protocol::Ipfix, IPFIX_TEMPLATE_SET) => {
+                    // Anything shorter than a template header is padding.
+                    while set.remaining() >= 4 {
+                        let template_id = set.u16()?;
+                        let field_count = set.u16()?;
+                        let key = (exporter, protocol, domain, template_id);
+                        if field_count == 0 {
+                            // An IPFIX template withdrawal.
+                            self.templates.remove(&key);
+                            continue;
+                        }
+                        let fields = decode_template_fields(&mut set, protocol, field_count, 0)?;
+                        self.templates.insert(
+                            key,
+                            Template {
+                                fields,
+                                options: false,
+                            },
+                        );
+                    }
+                }
+                (Protocol::V9, V9_OPTIONS_TEMPLATE_SET) => {
+                    while set.remaining() >= 6 {
+                        let template_id = set.u16()?;
+                        let scope_length = set.u16()? as usize;
+                        let option_length = set.u16()? as usize;
+                        let scope_count = scope_length / 4;
+                        let field_count = scope_count + option_length / 4;
+                        let fields = decode_template_fields(
+                            &mut set,
+                            protocol,
+                            field_count as u16,
+                            scope_count,
+                        )?;
+                        self.templates.insert(
+                            (exporter, protocol, domain, template_id),
+                            Template {
+                                fields,
+                                options: true,
+                            },
+                        );
+                    }
+                }
+                (Protocol::Ipfix, IPFIX_OPTIONS_TEMPLATE_SET) => {
+                    while set.remaining() >= 6 {
+                        let template_id = set.u16()?;
+                        let field_count = set.u16()?;
+                        let key = (exporter, protocol, domain, template_id);
+                        if field_count == 0 {
+                            self.templates.remove(&key);
+                            continue;
+                        }
+                        let scope_count = set.u16()? as usize;
+                        let fields =
+                            decode_template_fields(&mut set, protocol, field_count, scope_count)?;
+                        self.templates.insert(
+                            key,
+                            Template {
+                                fields,
+                                options: true,
+                            },
+                        );
+                    }
+                }
+                (_, id) if id >= MIN_DATA_SET => {
+                    let template = match self.templates.get(&(exporter, protocol, domain, id)) {
+                        Some(template) => template,
+                        None => {
+                            packet.missing_templates.push(id);
+                            continue;
+                        }
+                    };
+                    let min_len = template.min_record_len().max(1);
+                    while set.remaining() >= min_len {
+                        packet
+                            .records
+                            .push(decode_record(&mut set, protocol, template)?);
+                    }
+                }
+                // Reserved set IDs are ignored.
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decode_template_fields(
+    set: &mut Reader<'_>,
+    protocol: Protocol,
+    field_count: u16,
+    scope_count: usize,
+) -> Result<Vec<TemplateField>, DecodeError> {
+    (0..field_count as usize)
+        .map(|index| {
+            let id = set.u16()?;
+            let length = set.u16()?;
+            let (enterprise, id) = match protocol {
+                Protocol::Ipfix if id & ENTERPRISE_BIT != 0 => {
+                    (Some(set.u32()?), id & !ENTERPRISE_BIT)
+                }
+                _ => (None, id),
+            };
+            Ok(TemplateField {
+                enterprise,
+                id,
+                length,
+                scope: index < scope_count,
+            })
+        })
+        .collect()
+}
+
+fn decode_record(
+    set: &mut Reader<'_>,
+    protocol: Protocol,
+    template: &Template,
+) -> Result<Fields, DecodeError> {
+    let mut record = Vec::with_capacity(template.fields.len() + 1);
+    if template.options {
+        record.push(("record_type".to_owned(), "options".into()));
+    }
+
+    for field in &template.fields {
+        let length = match (protocol, field.length) {
+            (Protocol::Ipfix, VARIABLE_LENGTH) => match set.u8()? {
+                255 => set.u16()? as usize,
+                length => length as usize,
+            },
+            (_, length) => length as usize,
+        };
+        let bytes = set.bytes(length)?;
+
+        record.push(match protocol {
+            // The scope fields of NetFlow v9 have types of their own.
+            Protocol::V9 if field.scope => (v9_scope_name(field.id), fields::decode_raw(bytes)),
+            _ => fields::decode(field.enterprise, field.id, bytes),
+        });
+    }
+    Ok(record)
+}
+
+fn v9_scope_name(id: u16) -> String {
+    match id {
+        1 => "scope_system".to_owned(),
+        2 => "scope_interface".to_owned(),
+        3 => "scope_line_card".to_owned(),
+        4 => "scope_cache".to_owned(),
+        5 => "scope_template".to_owned(),
+        id => format!("scope_{}", id),
+    }
+}
+
+#[cfg(test)]
+impl TemplateCache {
+    fn len(&self) -> usize {
+        self.templates.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decode::FieldValue;
+    use super::*;
+
+    fn exporter() -> SocketAddr {
+        "192.0.2.1:2055".parse().unwrap()
+    }
+
+    fn set(id: u16, contents: &[u8]) -> Vec<u8> {
+        let mut set = id.to_be_bytes().to_vec();
+        set.extend_from_slice(&(contents.len() as u16 + 4).to_be_bytes());
+        set.extend_from_slice(contents);
+        set
+    }
+
+    fn ipfix(sets: &[Vec<u8>]) -> Vec<u8> {
+        let body = sets.concat();
+        let mut message = vec![0, 10];
+        message.extend_from_slice(&(body.len() as u16 + 16).to_be_bytes());
+        message.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        message.extend_from_slice(&7u32.to_be_bytes());
+        message.extend_from_slice(&1u32.to_be_bytes());
+        message.extend_from_slice(&body);
+        message
+    }
+
+    fn get<'a>(record: &'a Fields, name: &str) -> Option<&'a FieldValue> {
+        record
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    #[test]
+    fn ipfix_templates_and_data() {
+        let mut cache = TemplateCache::default();
+        let template = set(
+            IPFIX_TEMPLATE_SET,
+            &[
+                1, 0, 0, 4, // template 256 with 4 fields
+                0, 8, 0, 4, // sourceIPv4Address
+                0, 2, 0, 4, // packetDeltaCount, with reduced size
+                0, 82, 0xff, 0xff, // interfaceName, variable length
+                0x80, 1, 0, 2, 0, 0, 0x0b, 0xe3, // enterprise field
+            ],
+        );
+        let data = set(
+            256,
+            &[
+                10, 0, 0, 1, 0, 0, 0, 5, 4, b'e', b't', b'h', b'0', 0, 1, //
+                10, 0, 0, 2, 0, 0, 0, 6, 0, 0, 2, //
+                0, 0, // padding
+            ],
+        );
+
+        // Data sets whose template wasn't received yet are skipped.
+        let packet = cache
+            .decode_ipfix(&ipfix(std::slice::from_ref(&data)), exporter())
+            .unwrap();
+        assert!(packet.records.is_empty());
+        assert_eq!(packet.missing_templates, vec![256]);
+
+        let packet = cache
+            .decode_ipfix(&ipfix(&[template, data]), exporter())
+            .unwrap();
+        assert_eq!(packet.timestamp.unwrap().timestamp(), 1_600_000_000);
+        assert_eq!(packet.records.len(), 2);
+        let record = &packet.records[0];
+        assert_eq!(get(record, "source_ipv4_address"), Some(&"10.0.0.1".into()));
+        assert_eq!(get(record, "packet_delta_count"), Some(&5u32.into()));
+        assert_eq!(get(record, "interface_name"), Some(&"eth0".into()));
+        assert_eq!(get(record, "enterprise_3043_1"), Some(&1u16.into()));
+        assert_eq!(get(&packet.records[1], "interface_name"), Some(&"".into()));
+
+        // Templates are kept per exporter.
+        let other = "192.0.2.2:2055".parse().unwrap();
+        let packet = cache
+            .decode_ipfix(&ipfix(&[set(256, &[0; 11])]), other)
+            .unwrap();
+        assert_eq!(packet.missing_templates, vec![256]);
+    }
+
+    #[test]
+    fn ipfix_template_withdrawal() {
+        let mut cache = TemplateCache::default();
+        let template = set(IPFIX_TEMPLATE_SET, &[1, 0, 0, 1, 0, 8, 0, 4]);
+        cache.decode_ipfix(&ipfix(&[template]), exporter()).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        let withdrawal = set(IPFIX_TEMPLATE_SET, &[1, 0, 0, 0]);
+        cache
+            .decode_ipfix(&ipfix(&[withdrawal]), exporter())
+            .unwrap();
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn v9_options() {
+        let mut cache = TemplateCache::default();
+        let options = set(
+            V9_OPTIONS_TEMPLATE_SET,
+            &[
+                1, 4, 0, 4, 0, 8, // template 260, one scope field, two option fields
+                0, 2, 0, 4, // interface scope
+                0, 34, 0, 4, // samplingInterval
+                0, 35, 0, 1, // samplingAlgorithm
+                0, 0, // padding
+            ],
+        );
+        let data = set(260, &[0, 0, 0, 3, 0, 0, 0, 100, 2, 0, 0, 0]);
+
+        let mut message = vec![0, 9, 0, 2];
+        message.extend_from_slice(&[0, 0, 0, 1]);
+        message.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        message.extend_from_slice(&[0, 0, 0, 1]);
+        message.extend_from_slice(&[0, 0, 0, 9]);
+        message.extend_from_slice(&options);
+        message.extend_from_slice(&data);
+
+        let packet = cache.decode_v9(&message, exporter()).unwrap();
+        assert_eq!(packet.records.len(), 1);
+        let record = &packet.records[0];
+        assert_eq!(get(record, "record_type"), Some(&"options".into()));
+        assert_eq!(get(record, "scope_interface"), Some(&3u32.into()));
+        assert_eq!(get(record, "sampling_interval"), Some(&100u32.into()));
+        assert_eq!(get(record, "sampling_algorithm"), Some(&2u8.into()));
+        assert!(packet
+            .header
+            .contains(&("source_id".to_owned(), 9u32.into())));
+    }
+}
@@ -0,0 +1,123 @@
+//! NetFlow v5, whose records have a fixed layout. Its fields are named after the matching
+//! information elements, so that records look the same as those of the templated versions.
+
+use super::decode::{timestamp, DecodeError, FieldValue, Packet, Reader};
+
+const HEADER_LEN: usize = 24;
+const RECORD_LEN: usize = 48;
+
+pub fn decode(data: &[u8]) -> Result<Packet, DecodeError> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let count = reader.u16()? as usize;
+    let sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let flow_sequence = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    let sampling = reader.u16()?;
+
+    if data.len() != HEADER_LEN + count * RECORD_LEN {
+        return Err(DecodeError::InvalidLength {
+            what: "NetFlow v5 datagram",
+            length: data.len(),
+        });
+    }
+
+    let header = vec![
+        ("sys_uptime".to_owned(), sys_uptime.into()),
+        ("flow_sequence".to_owned(), flow_sequence.into()),
+        ("engine_type".to_owned(), engine_type.into()),
+        ("engine_id".to_owned(), engine_id.into()),
+        // The two most significant bits are the sampling mode.
+        ("sampling_algorithm".to_owned(), (sampling >> 14).into()),
+        ("sampling_interval".to_owned(), (sampling & 0x3fff).into()),
+    ];
+
+    let mut records = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut fields = Vec::with_capacity(18);
+        let mut field = |name: &str, value: FieldValue| fields.push((name.to_owned(), value));
+        field("source_ipv4_address", reader.ipv4()?.into());
+        field("destination_ipv4_address", reader.ipv4()?.into());
+        field("ip_next_hop_ipv4_address", reader.ipv4()?.into());
+        field("ingress_interface", reader.u16()?.into());
+        field("egress_interface", reader.u16()?.into());
+        field("packet_delta_count", reader.u32()?.into());
+        field("octet_delta_count", reader.u32()?.into());
+        field("flow_start_sys_up_time", reader.u32()?.into());
+        field("flow_end_sys_up_time", reader.u32()?.into());
+        field("source_transport_port", reader.u16()?.into());
+        field("destination_transport_port", reader.u16()?.into());
+        let _pad = reader.u8()?;
+        field("tcp_control_bits", reader.u8()?.into());
+        field("protocol_identifier", reader.u8()?.into());
+        field("ip_class_of_service", reader.u8()?.into());
+        field("bgp_source_as_number", reader.u16()?.into());
+        field("bgp_destination_as_number", reader.u16()?.into());
+        field("source_ipv4_prefix_length", reader.u8()?.into());
+        field("destination_ipv4_prefix_length", reader.u8()?.into());
+        let _pad = reader.u16()?;
+        records.push(fields);
+    }
+
+    Ok(Packet {
+        flow_type: "netflow_v5",
+        timestamp: timestamp(unix_secs, unix_nsecs),
+        header,
+        records,
+        missing_templates: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_records() {
+        let mut data = vec![0, 5, 0, 1];
+        data.extend_from_slice(&1000u32.to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&42u32.to_be_bytes());
+        data.extend_from_slice(&[1, 2, 0x40, 0x0a]);
+
+        data.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0, 1, 0, 2]);
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&180u32.to_be_bytes());
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&[0x30, 0x39, 0, 80, 0, 0x12, 6, 0]);
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&[24, 16, 0, 0]);
+
+        let packet = decode(&data).unwrap();
+        assert_eq!(packet.timestamp.unwrap().timestamp(), 1_600_000_000);
+        assert!(packet
+            .header
+            .contains(&("sampling_interval".to_owned(), 10u16.into())));
+        assert!(packet
+            .header
+            .contains(&("sampling_algorithm".to_owned(), 1u16.into())));
+
+        let record = &packet.records[0];
+        assert_eq!(record.len(), 18);
+        assert!(record.contains(&("source_ipv4_address".to_owned(), "10.0.0.1".into())));
+        assert!(record.contains(&("source_transport_port".to_owned(), 12345u16.into())));
+        assert!(record.contains(&("protocol_identifier".to_owned(), 6u8.into())));
+        assert!(record.contains(&("octet_delta_count".to_owned(), 180u32.into())));
+        assert!(record.contains(&("destination_ipv4_prefix_length".to_owned(), 16u8.into())));
+    }
+
+    #[test]
+    fn rejects_wrong_count() {
+        let mut data = vec![0, 5, 0, 2];
+        data.extend_from_slice(&[0; 20]);
+        assert!(matches!(
+            decode(&data),
+            Err(DecodeError::InvalidLength { .. })
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+use std::net::SocketAddr;
+
+use futures::{FutureExt, StreamExt};
+use tonic::{
+    transport::{server::Connected, Server},
+    Request, Response, Status,
+};
+use tracing_futures::Instrument;
+use vector_core::event::BatchStatus;
+
+use super::{logs, metrics, traces, EventSender, LOGS, METRICS, TRACES};
+use crate::{
+    event::Event,
+    internal_events::TcpBytesReceived,
+    proto::opentelemetry::collector::{
+        logs::v1::{
+            logs_service_server::{LogsService, LogsServiceServer},
+            ExportLogsServiceRequest, ExportLogsServiceResponse,
+        },
+        metrics::v1::{
+            metrics_service_server::{MetricsService, MetricsServiceServer},
+            ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+        },
+        trace::v1::{
+            trace_service_server::{TraceService, TraceServiceServer},
+            ExportTraceServiceRequest, ExportTraceServiceResponse,
+        },
+    },
+    shutdown::{ShutdownSignal, ShutdownSignalToken},
+    sources::util::AfterReadExt as _,
+    tls::MaybeTlsSettings,
+};
+
+#[derive(Clone)]
+struct Service {
+    sender: EventSender,
+}
+
+impl Service {
+    async fn send(&self, output: &'static str, events: Vec<Event>) -> Result<(), Status> {
+        match self.sender.send(output, events).await {
+            Ok(BatchStatus::Delivered) => Ok(()),
+            Ok(BatchStatus::Errored) => Err(Status::internal("Delivery error")),
+            Ok(BatchStatus::Rejected) => Err(Status::data_loss("Delivery failed")),
+            Err(error) => Err(Status::unavailable(error.to_string())),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl LogsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportLogsServiceRequest>,
+    ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+        self.send(LOGS, logs::decode(request.into_inner())).await?;
+        Ok(Response::new(ExportLogsServiceResponse {
+            partial_success: None,
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        self.send(METRICS, metrics::decode(request.into_inner()))
+            .await?;
+        Ok(Response::new(ExportMetricsServiceResponse {
+            partial_success: None,
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl TraceService for Service {
+    async fn export(
+        &self,
+        request: Request<ExportTraceServiceRequest>,
+    ) -> Result<Response<ExportTraceServiceResponse>, Status> {
+        self.send(TRACES, traces::decode(request.into_inner()))
+            .await?;
+        Ok(Response::new(ExportTraceServiceResponse {
+            partial_success: None,
+        }))
+    }
+}
+
+pub(super) async fn run(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    sender: EventSender,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let span = crate::trace::current_span();
+
+    let service = Service { sender };
+    let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
+
+    let listener = tls_settings.bind(&address).await?;
+    let stream = listener.accept_stream().map(|result| {
+        result.map(|socket| {
+            let peer_addr = socket.connect_info().remote_addr.ip();
+            socket.after_read(move |byte_size| {
+                emit!(&TcpBytesReceived {
+                    byte_size,
+                    peer_addr,
+                })
+            })
+        })
+    });
+
+    Server::builder()
+        .trace_fn(move |_| span.clone())
+        .add_service(LogsServiceServer::new(service.clone()))
+        .add_service(MetricsServiceServer::new(service.clone()))
+        .add_service(TraceServiceServer::new(service))
+        .serve_with_incoming_shutdown(stream, shutdown.map(|token| tx.send(token).unwrap()))
+        .in_current_span()
+        .await?;
+
+    drop(rx.await);
+
+    Ok(())
+}
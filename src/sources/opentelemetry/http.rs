@@ -0,0 +1,164 @@
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use futures::FutureExt;
+use prost::Message;
+use vector_core::event::BatchStatus;
+use warp::{
+    filters::BoxedFilter, http::StatusCode, path::FullPath, reject::Rejection, reply::Response,
+    Filter, Reply,
+};
+
+use super::{logs, metrics, traces, EventSender, LOGS, METRICS, TRACES};
+use crate::{
+    event::Event,
+    internal_events::{HttpBadRequest, HttpBytesReceived},
+    proto::opentelemetry::collector::{
+        logs::v1::{ExportLogsServiceRequest, ExportLogsServiceResponse},
+        metrics::v1::{ExportMetricsServiceRequest, ExportMetricsServiceResponse},
+        trace::v1::{ExportTraceServiceRequest, ExportTraceServiceResponse},
+    },
+    shutdown::ShutdownSignal,
+    sources::util::{decode, ErrorMessage},
+    tls::MaybeTlsSettings,
+};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+pub(super) async fn run(
+    address: SocketAddr,
+    tls_settings: MaybeTlsSettings,
+    sender: EventSender,
+    shutdown: ShutdownSignal,
+) -> crate::Result<()> {
+    let protocol = tls_settings.http_protocol_name();
+    let listener = tls_settings.bind(&address).await?;
+
+    let span = crate::trace::current_span();
+    let routes = build_warp_filter::<ExportLogsServiceRequest, ExportLogsServiceResponse>(
+        LOGS,
+        logs::decode,
+        sender.clone(),
+        protocol,
+    )
+    .or(build_warp_filter::<
+        ExportMetricsServiceRequest,
+        ExportMetricsServiceResponse,
+    >(METRICS, metrics::decode, sender.clone(), protocol))
+    .unify()
+    .or(build_warp_filter::<
+        ExportTraceServiceRequest,
+        ExportTraceServiceResponse,
+    >(TRACES, traces::decode, sender, protocol))
+    .unify()
+    .with(warp::trace(move |_info| span.clone()))
+    .recover(|r: Rejection| async move {
+        if let Some(e_msg) = r.find::<ErrorMessage>() {
+            let json = warp::reply::json(e_msg);
+            Ok(warp::reply::with_status(json, e_msg.status_code()))
+        } else {
+            // other internal error - will return 500 internal server error
+            Err(r)
+        }
+    });
+
+    warp::serve(routes)
+        .serve_incoming_with_graceful_shutdown(listener.accept_stream(), shutdown.map(|_| ()))
+        .await;
+
+    Ok(())
+}
+
+/// Receives the requests of a signal on `/v1/<output>`, the path of the signal in OTLP being the
+/// name of its output.
+fn build_warp_filter<Req, Resp>(
+    output: &'static str,
+    decode_request: fn(Req) -> Vec<Event>,
+    sender: EventSender,
+    protocol: &'static str,
+) -> BoxedFilter<(Response,)>
+where
+    Req: Message + Default + 'static,
+    Resp: Message + Default + 'static,
+{
+    warp::post()
+        .and(warp::path("v1"))
+        .and(warp::path(output))
+        .and(warp::path::end())
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("content-type"))
+        .and(warp::header::optional::<String>("content-encoding"))
+        .and(warp::body::bytes())
+        .and_then(
+            move |path: FullPath,
+                  content_type: Option<String>,
+                  encoding_header: Option<String>,
+                  body: Bytes| {
+                let sender = sender.clone();
+                async move {
+                    emit!(&HttpBytesReceived {
+                        byte_size: body.len(),
+                        http_path: path.as_str(),
+                        protocol,
+                    });
+                    let request = decode_body::<Req>(content_type, &encoding_header, body)
+                        .map_err(warp::reject::custom)?;
+
+                    match sender.send(output, decode_request(request)).await {
+                        Ok(BatchStatus::Delivered) => Ok(protobuf_response(Resp::default())),
+                        Ok(BatchStatus::Errored) => Err(warp::reject::custom(ErrorMessage::new(
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            "Error delivering contents to sink".into(),
+                        ))),
+                        Ok(BatchStatus::Rejected) => Err(warp::reject::custom(ErrorMessage::new(
+                            StatusCode::BAD_REQUEST,
+                            "Contents failed to deliver to sink".into(),
+                        ))),
+                        Err(error) => Err(warp::reject::custom(ErrorMessage::new(
+                            StatusCode::SERVICE_UNAVAILABLE,
+                            error.to_string(),
+                        ))),
+                    }
+                }
+            },
+        )
+        .boxed()
+}
+
+fn decode_body<Req: Message + Default>(
+    content_type: Option<String>,
+    encoding_header: &Option<String>,
+    body: Bytes,
+) -> Result<Req, ErrorMessage> {
+    // OTLP also allows JSON payloads, which are not supported.
+    if let Some(content_type) = content_type {
+        if content_type != PROTOBUF_CONTENT_TYPE {
+            return Err(ErrorMessage::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!(
+                    "Unsupported content type {}, expected {}",
+                    content_type, PROTOBUF_CONTENT_TYPE
+                ),
+            ));
+        }
+    }
+
+    let body = decode(encoding_header, body)?;
+    Req::decode(body).map_err(|error| {
+        let message = format!("Error decoding request: {}", error);
+        emit!(&HttpBadRequest {
+            code: StatusCode::BAD_REQUEST.as_u16(),
+            message: &message,
+        });
+        ErrorMessage::new(StatusCode::BAD_REQUEST, message)
+    })
+}
+
+fn protobuf_response(message: impl Message) -> Response {
+    warp::reply::with_header(
+        message.encode_to_vec(),
+        "content-type",
+        PROTOBUF_CONTENT_TYPE,
+    )
+    .into_response()
+}
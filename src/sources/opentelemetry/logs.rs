@@ -0,0 +1,79 @@
+use bytes::Bytes;
+use chrono::Utc;
+
+use super::{id_value, resource_attributes, scope_value, timestamp};
+use crate::{
+    config::log_schema,
+    event::{Event, LogEvent, Value},
+    proto::opentelemetry::{
+        collector::logs::v1::ExportLogsServiceRequest, key_values_into_value, logs::v1::LogRecord,
+    },
+};
+
+/// Converts each log record into a log event, its body being the message.
+pub(super) fn decode(request: ExportLogsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = resource_attributes(resource_logs.resource);
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_value(scope_logs.scope);
+            events.extend(
+                scope_logs.log_records.into_iter().map(|record| {
+                    Event::Log(convert_record(record, resource.clone(), scope.clone()))
+                }),
+            );
+        }
+    }
+    events
+}
+
+fn convert_record(record: LogRecord, resource: Option<Value>, scope: Option<Value>) -> LogEvent {
+    let mut log = LogEvent::default();
+    if let Some(body) = record.body {
+        log.insert_flat(log_schema().message_key(), Value::from(body));
+    }
+    if !record.attributes.is_empty() {
+        log.insert_flat("attributes", key_values_into_value(record.attributes));
+    }
+    if let Some(resource) = resource {
+        log.insert_flat("resources", resource);
+    }
+    if let Some(scope) = scope {
+        log.insert_flat("scope", scope);
+    }
+
+    let observed_timestamp = timestamp(record.observed_time_unix_nano);
+    log.insert_flat(
+        log_schema().timestamp_key(),
+        timestamp(record.time_unix_nano)
+            .or(observed_timestamp)
+            .unwrap_or_else(Utc::now),
+    );
+    if let Some(observed_timestamp) = observed_timestamp {
+        log.insert_flat("observed_timestamp", observed_timestamp);
+    }
+
+    if !record.severity_text.is_empty() {
+        log.insert_flat("severity_text", record.severity_text);
+    }
+    if record.severity_number != 0 {
+        log.insert_flat("severity_number", i64::from(record.severity_number));
+    }
+    if let Some(trace_id) = id_value(&record.trace_id) {
+        log.insert_flat("trace_id", trace_id);
+    }
+    if let Some(span_id) = id_value(&record.span_id) {
+        log.insert_flat("span_id", span_id);
+    }
+    if record.flags != 0 {
+        log.insert_flat("flags", i64::from(record.flags));
+    }
+    if record.dropped_attributes_count != 0 {
+        log.insert_flat(
+            "dropped_attributes_count",
+            i64::from(record.dropped_attributes_count),
+        );
+    }
+    log.insert_flat(log_schema().source_type_key(), Bytes::from("opentelemetry"));
+    log
+}
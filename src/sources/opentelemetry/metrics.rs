@@ -0,0 +1,262 @@
+use std::convert::TryFrom;
+
+use super::timestamp;
+use crate::{
+    event::{
+        metric::{Bucket, MetricKind, MetricTags, MetricValue, Quantile},
+        Event, Metric, Value,
+    },
+    proto::opentelemetry::{
+        collector::metrics::v1::ExportMetricsServiceRequest,
+        common::v1::{InstrumentationScope, KeyValue},
+        metrics::v1::{
+            exponential_histogram_data_point::Buckets, metric::Data, number_data_point,
+            AggregationTemporality, DataPointFlags, ExponentialHistogramDataPoint,
+            HistogramDataPoint, Metric as OtlpMetric, NumberDataPoint, SummaryDataPoint,
+        },
+        resource::v1::Resource,
+    },
+};
+
+/// Converts each data point into a metric, tagged with its attributes and those of its resource
+/// and instrumentation scope.
+pub(super) fn decode(request: ExportMetricsServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_metrics in request.resource_metrics {
+        let resource_tags = resource_tags(resource_metrics.resource);
+        for scope_metrics in resource_metrics.scope_metrics {
+            let mut tags = resource_tags.clone();
+            insert_scope_tags(&mut tags, scope_metrics.scope);
+            for metric in scope_metrics.metrics {
+                events.extend(convert_metric(metric, &tags).into_iter().map(Event::Metric));
+            }
+        }
+    }
+    events
+}
+
+fn resource_tags(resource: Option<Resource>) -> MetricTags {
+    resource
+        .map(|resource| {
+            resource
+                .attributes
+                .into_iter()
+                .filter_map(|KeyValue { key, value }| {
+                    value.map(|value| {
+                        (
+                            format!("resource.{}", key),
+                            Value::from(value).to_string_lossy(),
+                        )
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn insert_scope_tags(tags: &mut MetricTags, scope: Option<InstrumentationScope>) {
+    if let Some(scope) = scope {
+        if !scope.name.is_empty() {
+            tags.insert("scope.name".into(), scope.name);
+        }
+        if !scope.version.is_empty() {
+            tags.insert("scope.version".into(), scope.version);
+        }
+    }
+}
+
+fn convert_metric(metric: OtlpMetric, tags: &MetricTags) -> Vec<Metric> {
+    let name = metric.name;
+    match metric.data {
+        Some(Data::Gauge(gauge)) => number_metrics(&name, gauge.data_points, tags, |value| {
+            (MetricKind::Absolute, MetricValue::Gauge { value })
+        }),
+        Some(Data::Sum(sum)) => {
+            let kind = kind(sum.aggregation_temporality);
+            let is_monotonic = sum.is_monotonic;
+            number_metrics(&name, sum.data_points, tags, |value| {
+                if is_monotonic {
+                    (kind, MetricValue::Counter { value })
+                } else {
+                    (kind, MetricValue::Gauge { value })
+                }
+            })
+        }
+        Some(Data::Histogram(histogram)) => {
+            let kind = kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .filter(|point| has_value(point.flags))
+                .map(|point| histogram_metric(&name, kind, point, tags))
+                .collect()
+        }
+        Some(Data::ExponentialHistogram(histogram)) => {
+            let kind = kind(histogram.aggregation_temporality);
+            histogram
+                .data_points
+                .into_iter()
+                .filter(|point| has_value(point.flags))
+                .map(|point| exponential_histogram_metric(&name, kind, point, tags))
+                .collect()
+        }
+        Some(Data::Summary(summary)) => summary
+            .data_points
+            .into_iter()
+            .filter(|point| has_value(point.flags))
+            .map(|point| summary_metric(&name, point, tags))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Deltas are incremental, while cumulative values, and those whose temporality is unknown, are
+/// absolute.
+fn kind(aggregation_temporality: i32) -> MetricKind {
+    match AggregationTemporality::from_i32(aggregation_temporality) {
+        Some(AggregationTemporality::Delta) => MetricKind::Incremental,
+        _ => MetricKind::Absolute,
+    }
+}
+
+/// Points flagged as having no recorded value only mark the end of a series.
+const fn has_value(flags: u32) -> bool {
+    flags & DataPointFlags::NoRecordedValueMask as u32 == 0
+}
+
+fn saturating_count(count: u64) -> u32 {
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+fn point_tags(tags: &MetricTags, attributes: Vec<KeyValue>) -> Option<MetricTags> {
+    let mut tags = tags.clone();
+    tags.extend(
+        attributes
+            .into_iter()
+            .filter_map(|KeyValue { key, value }| {
+                value.map(|value| (key, Value::from(value).to_string_lossy()))
+            }),
+    );
+    (!tags.is_empty()).then(|| tags)
+}
+
+fn number_metrics(
+    name: &str,
+    points: Vec<NumberDataPoint>,
+    tags: &MetricTags,
+    value: impl Fn(f64) -> (MetricKind, MetricValue),
+) -> Vec<Metric> {
+    points
+        .into_iter()
+        .filter(|point| has_value(point.flags))
+        .filter_map(|point| {
+            let number = match point.value? {
+                number_data_point::Value::AsDouble(value) => value,
+                number_data_point::Value::AsInt(value) => value as f64,
+            };
+            let (kind, value) = value(number);
+            Some(
+                Metric::new(name, kind, value)
+                    .with_tags(point_tags(tags, point.attributes))
+                    .with_timestamp(timestamp(point.time_unix_nano)),
+            )
+        })
+        .collect()
+}
+
+fn histogram_metric(
+    name: &str,
+    kind: MetricKind,
+    point: HistogramDataPoint,
+    tags: &MetricTags,
+) -> Metric {
+    // The last count is that of the values above the last bound, which is implied by the total
+    // count as for the histograms of the other sources.
+    let buckets = point
+        .explicit_bounds
+        .iter()
+        .zip(&point.bucket_counts)
+        .map(|(&upper_limit, &count)| Bucket {
+            upper_limit,
+            count: saturating_count(count),
+        })
+        .collect();
+    Metric::new(
+        name,
+        kind,
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count: saturating_count(point.count),
+            sum: point.sum.unwrap_or_default(),
+        },
+    )
+    .with_tags(point_tags(tags, point.attributes))
+    .with_timestamp(timestamp(point.time_unix_nano))
+}
+
+/// The buckets of exponential histograms are converted to explicit ones, the first bucket holding
+/// the values in the zero bucket and the negative buckets.
+fn exponential_histogram_metric(
+    name: &str,
+    kind: MetricKind,
+    point: ExponentialHistogramDataPoint,
+    tags: &MetricTags,
+) -> Metric {
+    let negative_count = point
+        .negative
+        .as_ref()
+        .map_or(0, |negative| negative.bucket_counts.iter().sum::<u64>());
+    let mut buckets = vec![Bucket {
+        upper_limit: point.zero_threshold,
+        count: saturating_count(point.zero_count.saturating_add(negative_count)),
+    }];
+    if let Some(Buckets {
+        offset,
+        bucket_counts,
+    }) = point.positive
+    {
+        // The bucket of index `i` holds the values in `(base^i, base^(i + 1)]`.
+        let base = 2f64.powf(2f64.powi(-point.scale));
+        buckets.extend(
+            bucket_counts
+                .into_iter()
+                .enumerate()
+                .map(|(i, count)| Bucket {
+                    upper_limit: base.powf(f64::from(offset) + i as f64 + 1.0),
+                    count: saturating_count(count),
+                }),
+        );
+    }
+    Metric::new(
+        name,
+        kind,
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count: saturating_count(point.count),
+            sum: point.sum.unwrap_or_default(),
+        },
+    )
+    .with_tags(point_tags(tags, point.attributes))
+    .with_timestamp(timestamp(point.time_unix_nano))
+}
+
+fn summary_metric(name: &str, point: SummaryDataPoint, tags: &MetricTags) -> Metric {
+    Metric::new(
+        name,
+        MetricKind::Absolute,
+        MetricValue::AggregatedSummary {
+            quantiles: point
+                .quantile_values
+                .into_iter()
+                .map(|quantile| Quantile {
+                    quantile: quantile.quantile,
+                    value: quantile.value,
+                })
+                .collect(),
+            count: saturating_count(point.count),
+            sum: point.sum,
+        },
+    )
+    .with_tags(point_tags(tags, point.attributes))
+    .with_timestamp(timestamp(point.time_unix_nano))
+}
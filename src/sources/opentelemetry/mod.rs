@@ -0,0 +1,218 @@
+//! Receives logs, metrics and traces from OpenTelemetry SDKs and collectors, implementing the
+//! receivers of the OpenTelemetry protocol (OTLP) over gRPC and over HTTP with protobuf payloads.
+
+mod grpc;
+mod http;
+mod logs;
+mod metrics;
+#[cfg(test)]
+mod tests;
+mod traces;
+
+use std::{collections::BTreeMap, net::SocketAddr};
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{future, TryFutureExt};
+use serde::{Deserialize, Serialize};
+use vector_core::{
+    event::{BatchNotifier, BatchStatus},
+    ByteSizeOf,
+};
+
+use crate::{
+    config::{
+        AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource, SourceConfig,
+        SourceContext, SourceDescription,
+    },
+    event::{Event, Value},
+    internal_events::{EventsReceived, StreamClosedError},
+    proto::opentelemetry::{
+        common::v1::InstrumentationScope, key_values_into_value,
+        resource::v1::Resource as OtlpResource,
+    },
+    serde::bool_or_struct,
+    source_sender::ClosedError,
+    tls::{MaybeTlsSettings, TlsConfig},
+    SourceSender,
+};
+
+pub const LOGS: &str = "logs";
+pub const METRICS: &str = "metrics";
+pub const TRACES: &str = "traces";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpentelemetryConfig {
+    #[serde(default = "default_grpc")]
+    grpc: ListenerConfig,
+    #[serde(default = "default_http")]
+    http: ListenerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ListenerConfig {
+    address: SocketAddr,
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+}
+
+fn default_grpc() -> ListenerConfig {
+    ListenerConfig {
+        address: "0.0.0.0:4317".parse().unwrap(),
+        tls: None,
+    }
+}
+
+fn default_http() -> ListenerConfig {
+    ListenerConfig {
+        address: "0.0.0.0:4318".parse().unwrap(),
+        tls: None,
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<OpentelemetryConfig>("opentelemetry")
+}
+
+impl GenerateConfig for OpentelemetryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            grpc: default_grpc(),
+            http: default_http(),
+            acknowledgements: AcknowledgementsConfig::default(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "opentelemetry")]
+impl SourceConfig for OpentelemetryConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
+        let sender = EventSender {
+            out: cx.out,
+            acknowledgements,
+        };
+
+        let grpc_tls_settings = MaybeTlsSettings::from_config(&self.grpc.tls, true)?;
+        let grpc = grpc::run(
+            self.grpc.address,
+            grpc_tls_settings,
+            sender.clone(),
+            cx.shutdown.clone(),
+        );
+
+        let http_tls_settings = MaybeTlsSettings::from_config(&self.http.tls, true)?;
+        let http = http::run(self.http.address, http_tls_settings, sender, cx.shutdown);
+
+        Ok(Box::pin(
+            future::try_join(grpc, http)
+                .map_ok(|_| ())
+                .map_err(|error| {
+                    error!(message = "Source future failed.", %error);
+                }),
+        ))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![
+            Output::from((LOGS, DataType::Log)),
+            Output::from((METRICS, DataType::Metric)),
+            Output::from((TRACES, DataType::Trace)),
+        ]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "opentelemetry"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![
+            Resource::tcp(self.grpc.address),
+            Resource::tcp(self.http.address),
+        ]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// Sends the events decoded from a request to their output, shared by both receivers.
+#[derive(Clone)]
+struct EventSender {
+    out: SourceSender,
+    acknowledgements: bool,
+}
+
+impl EventSender {
+    /// Returns the status of the delivery of the events, once delivered if acknowledging them.
+    async fn send(
+        &self,
+        output: &'static str,
+        mut events: Vec<Event>,
+    ) -> Result<BatchStatus, ClosedError> {
+        let count = events.len();
+        emit!(&EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to_events(self.acknowledgements, &mut events);
+        self.out
+            .clone()
+            .send_batch_named(output, events)
+            .await
+            .map_err(|error| {
+                emit!(&StreamClosedError {
+                    error: error.clone(),
+                    count
+                });
+                error
+            })?;
+
+        Ok(match receiver {
+            Some(receiver) => receiver.await,
+            None => BatchStatus::Delivered,
+        })
+    }
+}
+
+/// Converts a time in nanoseconds since the epoch, zero meaning that it is unknown.
+fn timestamp(unix_nano: u64) -> Option<DateTime<Utc>> {
+    (unix_nano != 0).then(|| Utc.timestamp_nanos(unix_nano as i64))
+}
+
+fn resource_attributes(resource: Option<OtlpResource>) -> Option<Value> {
+    resource
+        .filter(|resource| !resource.attributes.is_empty())
+        .map(|resource| key_values_into_value(resource.attributes))
+}
+
+/// The instrumentation scope as an object with its `name`, `version` and `attributes`.
+fn scope_value(scope: Option<InstrumentationScope>) -> Option<Value> {
+    let scope = scope?;
+    let mut fields = BTreeMap::new();
+    if !scope.name.is_empty() {
+        fields.insert("name".to_owned(), Value::from(scope.name));
+    }
+    if !scope.version.is_empty() {
+        fields.insert("version".to_owned(), Value::from(scope.version));
+    }
+    if !scope.attributes.is_empty() {
+        fields.insert(
+            "attributes".to_owned(),
+            key_values_into_value(scope.attributes),
+        );
+    }
+    (!fields.is_empty()).then(|| Value::Object(fields))
+}
+
+/// Trace and span IDs are encoded as lowercase hexadecimal, as in the W3C trace context.
+fn id_value(id: &[u8]) -> Option<Value> {
+    (!id.is_empty()).then(|| Value::from(hex::encode(id)))
+}
@@ -0,0 +1,572 @@
+use std::net::SocketAddr;
+
+use chrono::{TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+use prost::Message;
+use vector_common::btreemap;
+
+use super::{logs, metrics, traces, OpentelemetryConfig, LOGS, METRICS, TRACES};
+use crate::{
+    config::{log_schema, SourceConfig, SourceContext},
+    event::{
+        into_event_stream,
+        metric::{Bucket, MetricKind, MetricValue, Quantile},
+        Event, EventStatus, Value,
+    },
+    proto::opentelemetry::{
+        collector::{
+            logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
+            metrics::v1::ExportMetricsServiceRequest,
+            trace::v1::ExportTraceServiceRequest,
+        },
+        common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+        logs::v1::{LogRecord, ResourceLogs, ScopeLogs},
+        metrics::v1::{
+            exponential_histogram_data_point::Buckets, metric::Data, number_data_point,
+            summary_data_point::ValueAtQuantile, AggregationTemporality, ExponentialHistogram,
+            ExponentialHistogramDataPoint, Gauge, Histogram, HistogramDataPoint, Metric,
+            NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+        },
+        resource::v1::Resource,
+        trace::v1::{span::SpanKind, status::StatusCode, ResourceSpans, ScopeSpans, Span, Status},
+    },
+    test_util::{next_addr, trace_init, wait_for_tcp},
+    SourceSender,
+};
+
+const TIME_UNIX_NANO: u64 = 1_650_000_000_123_456_789;
+
+fn string_value(value: &str) -> Option<AnyValue> {
+    Some(AnyValue {
+        value: Some(any_value::Value::StringValue(value.into())),
+    })
+}
+
+fn attribute(key: &str, value: any_value::Value) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue { value: Some(value) }),
+    }
+}
+
+fn resource() -> Option<Resource> {
+    Some(Resource {
+        attributes: vec![attribute(
+            "service.name",
+            any_value::Value::StringValue("checkout".into()),
+        )],
+        dropped_attributes_count: 0,
+    })
+}
+
+fn scope() -> Option<InstrumentationScope> {
+    Some(InstrumentationScope {
+        name: "io.opentelemetry.http".into(),
+        version: "1.2.0".into(),
+        attributes: Vec::new(),
+        dropped_attributes_count: 0,
+    })
+}
+
+fn logs_request() -> ExportLogsServiceRequest {
+    ExportLogsServiceRequest {
+        resource_logs: vec![ResourceLogs {
+            resource: resource(),
+            scope_logs: vec![ScopeLogs {
+                scope: scope(),
+                log_records: vec![LogRecord {
+                    time_unix_nano: TIME_UNIX_NANO,
+                    severity_number: 9,
+                    severity_text: "INFO".into(),
+                    body: string_value("User logged in"),
+                    attributes: vec![
+                        attribute("user.id", any_value::Value::IntValue(42)),
+                        attribute("http.secure", any_value::Value::BoolValue(true)),
+                    ],
+                    trace_id: vec![0x4b; 16],
+                    span_id: vec![0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7],
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+fn number_point(value: number_data_point::Value) -> NumberDataPoint {
+    NumberDataPoint {
+        attributes: vec![attribute(
+            "http.method",
+            any_value::Value::StringValue("GET".into()),
+        )],
+        time_unix_nano: TIME_UNIX_NANO,
+        value: Some(value),
+        ..Default::default()
+    }
+}
+
+fn metric(name: &str, data: Data) -> Metric {
+    Metric {
+        name: name.into(),
+        description: String::new(),
+        unit: String::new(),
+        data: Some(data),
+    }
+}
+
+fn metrics_request(metrics: Vec<Metric>) -> ExportMetricsServiceRequest {
+    ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            resource: resource(),
+            scope_metrics: vec![ScopeMetrics {
+                scope: scope(),
+                metrics,
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+fn traces_request() -> ExportTraceServiceRequest {
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: resource(),
+            scope_spans: vec![ScopeSpans {
+                scope: scope(),
+                spans: vec![Span {
+                    trace_id: vec![0x4b; 16],
+                    span_id: vec![0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7],
+                    name: "GET /checkout".into(),
+                    kind: SpanKind::Server as i32,
+                    start_time_unix_nano: TIME_UNIX_NANO,
+                    end_time_unix_nano: TIME_UNIX_NANO + 1_000_000,
+                    attributes: vec![attribute(
+                        "http.status_code",
+                        any_value::Value::IntValue(200),
+                    )],
+                    status: Some(Status {
+                        message: String::new(),
+                        code: StatusCode::Ok as i32,
+                    }),
+                    ..Default::default()
+                }],
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+fn metric_tags(metric: &crate::event::Metric) -> Vec<(&str, &str)> {
+    metric
+        .tags()
+        .unwrap()
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect()
+}
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<OpentelemetryConfig>();
+}
+
+#[test]
+fn decodes_logs() {
+    let events = logs::decode(logs_request());
+    assert_eq!(events.len(), 1);
+    let log = events[0].as_log();
+
+    assert_eq!(log[log_schema().message_key()], "User logged in".into());
+    assert_eq!(
+        log[log_schema().timestamp_key()],
+        Utc.timestamp_nanos(TIME_UNIX_NANO as i64).into()
+    );
+    assert_eq!(log["severity_text"], "INFO".into());
+    assert_eq!(log["severity_number"], 9.into());
+    assert_eq!(log["trace_id"], "4b".repeat(16).into());
+    assert_eq!(log["span_id"], "00f067aa0ba902b7".into());
+    assert_eq!(
+        log.as_map()["attributes"],
+        Value::Object(btreemap! {
+            "user.id" => 42,
+            "http.secure" => true,
+        })
+    );
+    assert_eq!(
+        log.as_map()["resources"],
+        Value::Object(btreemap! { "service.name" => "checkout" })
+    );
+    assert_eq!(
+        log.as_map()["scope"],
+        Value::Object(btreemap! {
+            "name" => "io.opentelemetry.http",
+            "version" => "1.2.0",
+        })
+    );
+    assert_eq!(log[log_schema().source_type_key()], "opentelemetry".into());
+    assert!(log.get("observed_timestamp").is_none());
+}
+
+#[test]
+fn falls_back_to_observed_timestamp() {
+    let mut request = logs_request();
+    let record = &mut request.resource_logs[0].scope_logs[0].log_records[0];
+    record.time_unix_nano = 0;
+    record.observed_time_unix_nano = TIME_UNIX_NANO;
+
+    let events = logs::decode(request);
+    let log = events[0].as_log();
+    let timestamp = Value::from(Utc.timestamp_nanos(TIME_UNIX_NANO as i64));
+    assert_eq!(log[log_schema().timestamp_key()], timestamp);
+    assert_eq!(log["observed_timestamp"], timestamp);
+}
+
+#[test]
+fn decodes_sums_and_gauges() {
+    let events = metrics::decode(metrics_request(vec![
+        metric(
+            "http.server.requests",
+            Data::Sum(Sum {
+                data_points: vec![number_point(number_data_point::Value::AsInt(7))],
+                aggregation_temporality: AggregationTemporality::Delta as i32,
+                is_monotonic: true,
+            }),
+        ),
+        metric(
+            "http.server.active_requests",
+            Data::Sum(Sum {
+                data_points: vec![number_point(number_data_point::Value::AsInt(3))],
+                aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                is_monotonic: false,
+            }),
+        ),
+        metric(
+            "process.memory.usage",
+            Data::Gauge(Gauge {
+                data_points: vec![number_point(number_data_point::Value::AsDouble(1.5))],
+            }),
+        ),
+    ]));
+    let metrics = events.iter().map(Event::as_metric).collect::<Vec<_>>();
+    assert_eq!(metrics.len(), 3);
+
+    assert_eq!(metrics[0].name(), "http.server.requests");
+    assert_eq!(metrics[0].kind(), MetricKind::Incremental);
+    assert_eq!(metrics[0].value(), &MetricValue::Counter { value: 7.0 });
+    assert_eq!(
+        metrics[0].timestamp(),
+        Some(Utc.timestamp_nanos(TIME_UNIX_NANO as i64))
+    );
+    assert_eq!(
+        metric_tags(metrics[0]),
+        vec![
+            ("http.method", "GET"),
+            ("resource.service.name", "checkout"),
+            ("scope.name", "io.opentelemetry.http"),
+            ("scope.version", "1.2.0"),
+        ]
+    );
+
+    assert_eq!(metrics[1].kind(), MetricKind::Absolute);
+    assert_eq!(metrics[1].value(), &MetricValue::Gauge { value: 3.0 });
+
+    assert_eq!(metrics[2].kind(), MetricKind::Absolute);
+    assert_eq!(metrics[2].value(), &MetricValue::Gauge { value: 1.5 });
+}
+
+#[test]
+fn decodes_histograms_and_summaries() {
+    let events = metrics::decode(metrics_request(vec![
+        metric(
+            "http.server.duration",
+            Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    time_unix_nano: TIME_UNIX_NANO,
+                    count: 10,
+                    sum: Some(3.5),
+                    bucket_counts: vec![2, 5, 3],
+                    explicit_bounds: vec![0.1, 0.5],
+                    ..Default::default()
+                }],
+                aggregation_temporality: AggregationTemporality::Cumulative as i32,
+            }),
+        ),
+        metric(
+            "rpc.duration",
+            Data::ExponentialHistogram(ExponentialHistogram {
+                data_points: vec![ExponentialHistogramDataPoint {
+                    time_unix_nano: TIME_UNIX_NANO,
+                    count: 7,
+                    sum: Some(10.0),
+                    scale: 0,
+                    zero_count: 1,
+                    positive: Some(Buckets {
+                        offset: 1,
+                        bucket_counts: vec![2, 3],
+                    }),
+                    negative: Some(Buckets {
+                        offset: 0,
+                        bucket_counts: vec![1],
+                    }),
+                    ..Default::default()
+                }],
+                aggregation_temporality: AggregationTemporality::Delta as i32,
+            }),
+        ),
+        metric(
+            "rpc.latency",
+            Data::Summary(Summary {
+                data_points: vec![SummaryDataPoint {
+                    time_unix_nano: TIME_UNIX_NANO,
+                    count: 4,
+                    sum: 2.0,
+                    quantile_values: vec![ValueAtQuantile {
+                        quantile: 0.5,
+                        value: 0.4,
+                    }],
+                    ..Default::default()
+                }],
+            }),
+        ),
+    ]));
+    let metrics = events.iter().map(Event::as_metric).collect::<Vec<_>>();
+    assert_eq!(metrics.len(), 3);
+
+    assert_eq!(metrics[0].kind(), MetricKind::Absolute);
+    assert_eq!(
+        metrics[0].value(),
+        &MetricValue::AggregatedHistogram {
+            buckets: vec![
+                Bucket {
+                    upper_limit: 0.1,
+                    count: 2
+                },
+                Bucket {
+                    upper_limit: 0.5,
+                    count: 5
+                },
+            ],
+            count: 10,
+            sum: 3.5,
+        }
+    );
+
+    // With a scale of 0 the base is 2, the positive buckets from the offset of 1 being (2, 4]
+    // and (4, 8].
+    assert_eq!(metrics[1].kind(), MetricKind::Incremental);
+    assert_eq!(
+        metrics[1].value(),
+        &MetricValue::AggregatedHistogram {
+            buckets: vec![
+                Bucket {
+                    upper_limit: 0.0,
+                    count: 2
+                },
+                Bucket {
+                    upper_limit: 4.0,
+                    count: 2
+                },
+                Bucket {
+                    upper_limit: 8.0,
+                    count: 3
+                },
+            ],
+            count: 7,
+            sum: 10.0,
+        }
+    );
+
+    assert_eq!(
+        metrics[2].value(),
+        &MetricValue::AggregatedSummary {
+            quantiles: vec![Quantile {
+                quantile: 0.5,
+                value: 0.4
+            }],
+            count: 4,
+            sum: 2.0,
+        }
+    );
+}
+
+#[test]
+fn skips_points_without_recorded_values() {
+    let mut point = number_point(number_data_point::Value::AsInt(1));
+    point.flags = 1;
+    let events = metrics::decode(metrics_request(vec![metric(
+        "process.memory.usage",
+        Data::Gauge(Gauge {
+            data_points: vec![point],
+        }),
+    )]));
+    assert!(events.is_empty());
+}
+
+#[test]
+fn decodes_traces() {
+    let events = traces::decode(traces_request());
+    assert_eq!(events.len(), 1);
+    let trace = events[0].as_trace();
+
+    assert_eq!(trace.get("trace_id"), Some(&"4b".repeat(16).into()));
+    assert_eq!(trace.get("span_id"), Some(&"00f067aa0ba902b7".into()));
+    assert_eq!(trace.get("parent_span_id"), None);
+    assert_eq!(trace.get("name"), Some(&"GET /checkout".into()));
+    assert_eq!(trace.get("kind"), Some(&"server".into()));
+    assert_eq!(
+        trace.get("start_timestamp"),
+        Some(&Utc.timestamp_nanos(TIME_UNIX_NANO as i64).into())
+    );
+    assert_eq!(
+        trace.get("end_timestamp"),
+        Some(
+            &Utc.timestamp_nanos((TIME_UNIX_NANO + 1_000_000) as i64)
+                .into()
+        )
+    );
+    assert_eq!(
+        trace.get_flat("attributes"),
+        Some(&Value::Object(btreemap! { "http.status_code" => 200 }))
+    );
+    assert_eq!(
+        trace.get_flat("status"),
+        Some(&Value::Object(btreemap! { "code" => "ok" }))
+    );
+    assert_eq!(
+        trace.get_flat("resources"),
+        Some(&Value::Object(btreemap! { "service.name" => "checkout" }))
+    );
+}
+
+async fn source() -> (
+    impl Stream<Item = Event> + Unpin,
+    impl Stream<Item = Event> + Unpin,
+    impl Stream<Item = Event> + Unpin,
+    SocketAddr,
+    SocketAddr,
+) {
+    let (mut sender, _) = SourceSender::new_test();
+    let logs = sender
+        .add_outputs(EventStatus::Delivered, LOGS.to_string())
+        .flat_map(into_event_stream);
+    let metrics = sender
+        .add_outputs(EventStatus::Delivered, METRICS.to_string())
+        .flat_map(into_event_stream);
+    let traces = sender
+        .add_outputs(EventStatus::Delivered, TRACES.to_string())
+        .flat_map(into_event_stream);
+
+    let grpc_address = next_addr();
+    let http_address = next_addr();
+    let config = toml::from_str::<OpentelemetryConfig>(&format!(
+        indoc! { r#"
+            [grpc]
+            address = "{}"
+
+            [http]
+            address = "{}"
+        "#},
+        grpc_address, http_address
+    ))
+    .unwrap();
+    let context = SourceContext::new_test(sender, None);
+    tokio::spawn(async move {
+        config.build(context).await.unwrap().await.unwrap();
+    });
+    wait_for_tcp(grpc_address).await;
+    wait_for_tcp(http_address).await;
+    (logs, metrics, traces, grpc_address, http_address)
+}
+
+async fn post(address: SocketAddr, path: &str, content_type: &str, body: Vec<u8>) -> u16 {
+    reqwest::Client::new()
+        .post(&format!("http://{}{}", address, path))
+        .header("content-type", content_type)
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn receives_over_grpc() {
+    trace_init();
+    let (mut logs, _, _, grpc_address, _) = source().await;
+
+    let mut client = LogsServiceClient::connect(format!("http://{}", grpc_address))
+        .await
+        .unwrap();
+    client.export(logs_request()).await.unwrap();
+
+    let event = logs.next().await.unwrap();
+    assert_eq!(
+        event.as_log()[log_schema().message_key()],
+        "User logged in".into()
+    );
+}
+
+#[tokio::test]
+async fn receives_over_http() {
+    trace_init();
+    let (_, mut metrics, mut traces, _, http_address) = source().await;
+
+    let request = metrics_request(vec![metric(
+        "process.memory.usage",
+        Data::Gauge(Gauge {
+            data_points: vec![number_point(number_data_point::Value::AsDouble(1.5))],
+        }),
+    )]);
+    assert_eq!(
+        post(
+            http_address,
+            "/v1/metrics",
+            "application/x-protobuf",
+            request.encode_to_vec()
+        )
+        .await,
+        200
+    );
+    let event = metrics.next().await.unwrap();
+    assert_eq!(event.as_metric().name(), "process.memory.usage");
+
+    assert_eq!(
+        post(
+            http_address,
+            "/v1/traces",
+            "application/x-protobuf",
+            traces_request().encode_to_vec()
+        )
+        .await,
+        200
+    );
+    let event = traces.next().await.unwrap();
+    assert_eq!(event.as_trace().get("name"), Some(&"GET /checkout".into()));
+}
+
+#[tokio::test]
+async fn rejects_invalid_http_requests() {
+    trace_init();
+    let (_, _, _, _, http_address) = source().await;
+
+    assert_eq!(
+        post(http_address, "/v1/logs", "application/json", b"{}".to_vec()).await,
+        415
+    );
+    assert_eq!(
+        post(
+            http_address,
+            "/v1/logs",
+            "application/x-protobuf",
+            b"not protobuf".to_vec()
+        )
+        .await,
+        400
+    );
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+
+use super::{id_value, resource_attributes, scope_value, timestamp};
+use crate::{
+    config::log_schema,
+    event::{Event, TraceEvent, Value},
+    proto::opentelemetry::{
+        collector::trace::v1::ExportTraceServiceRequest,
+        common::v1::KeyValue,
+        key_values_into_value,
+        trace::v1::{
+            span::{Event as SpanEvent, Link, SpanKind},
+            status::StatusCode,
+            Span, Status,
+        },
+    },
+};
+
+/// Converts each span into a trace event.
+pub(super) fn decode(request: ExportTraceServiceRequest) -> Vec<Event> {
+    let mut events = Vec::new();
+    for resource_spans in request.resource_spans {
+        let resource = resource_attributes(resource_spans.resource);
+        for scope_spans in resource_spans.scope_spans {
+            let scope = scope_value(scope_spans.scope);
+            events.extend(
+                scope_spans
+                    .spans
+                    .into_iter()
+                    .map(|span| Event::Trace(convert_span(span, resource.clone(), scope.clone()))),
+            );
+        }
+    }
+    events
+}
+
+fn convert_span(span: Span, resource: Option<Value>, scope: Option<Value>) -> TraceEvent {
+    let mut fields = BTreeMap::<String, Value>::new();
+    insert_id(&mut fields, "trace_id", &span.trace_id);
+    insert_id(&mut fields, "span_id", &span.span_id);
+    insert_id(&mut fields, "parent_span_id", &span.parent_span_id);
+    if !span.trace_state.is_empty() {
+        fields.insert("trace_state".into(), span.trace_state.into());
+    }
+    fields.insert("name".into(), span.name.into());
+    fields.insert("kind".into(), kind_name(span.kind).into());
+    insert_timestamp(&mut fields, "start_timestamp", span.start_time_unix_nano);
+    insert_timestamp(&mut fields, "end_timestamp", span.end_time_unix_nano);
+    insert_attributes(&mut fields, span.attributes, span.dropped_attributes_count);
+    if !span.events.is_empty() {
+        fields.insert(
+            "events".into(),
+            Value::Array(span.events.into_iter().map(convert_event).collect()),
+        );
+    }
+    insert_count(
+        &mut fields,
+        "dropped_events_count",
+        span.dropped_events_count,
+    );
+    if !span.links.is_empty() {
+        fields.insert(
+            "links".into(),
+            Value::Array(span.links.into_iter().map(convert_link).collect()),
+        );
+    }
+    insert_count(&mut fields, "dropped_links_count", span.dropped_links_count);
+    if let Some(status) = span.status {
+        fields.insert("status".into(), convert_status(status));
+    }
+    if let Some(resource) = resource {
+        fields.insert("resources".into(), resource);
+    }
+    if let Some(scope) = scope {
+        fields.insert("scope".into(), scope);
+    }
+    fields.insert(
+        log_schema().source_type_key().into(),
+        Bytes::from("opentelemetry").into(),
+    );
+    TraceEvent::from(fields)
+}
+
+fn convert_event(event: SpanEvent) -> Value {
+    let mut fields = BTreeMap::<String, Value>::new();
+    fields.insert("name".into(), event.name.into());
+    insert_timestamp(&mut fields, "timestamp", event.time_unix_nano);
+    insert_attributes(
+        &mut fields,
+        event.attributes,
+        event.dropped_attributes_count,
+    );
+    Value::Object(fields)
+}
+
+fn convert_link(link: Link) -> Value {
+    let mut fields = BTreeMap::<String, Value>::new();
+    insert_id(&mut fields, "trace_id", &link.trace_id);
+    insert_id(&mut fields, "span_id", &link.span_id);
+    if !link.trace_state.is_empty() {
+        fields.insert("trace_state".into(), link.trace_state.into());
+    }
+    insert_attributes(&mut fields, link.attributes, link.dropped_attributes_count);
+    Value::Object(fields)
+}
+
+fn convert_status(status: Status) -> Value {
+    let code = match StatusCode::from_i32(status.code) {
+        Some(StatusCode::Ok) => "ok",
+        Some(StatusCode::Error) => "error",
+        Some(StatusCode::Unset) | None => "unset",
+    };
+    let mut fields = BTreeMap::<String, Value>::new();
+    fields.insert("code".into(), code.into());
+    if !status.message.is_empty() {
+        fields.insert("message".into(), status.message.into());
+    }
+    Value::Object(fields)
+}
+
+fn kind_name(kind: i32) -> &'static str {
+    match SpanKind::from_i32(kind) {
+        Some(SpanKind::Internal) => "internal",
+        Some(SpanKind::Server) => "server",
+        Some(SpanKind::Client) => "client",
+        Some(SpanKind::Producer) => "producer",
+        Some(SpanKind::Consumer) => "consumer",
+        Some(SpanKind::Unspecified) | None => "unspecified",
+    }
+}
+
+fn insert_id(fields: &mut BTreeMap<String, Value>, key: &str, id: &[u8]) {
+    if let Some(id) = id_value(id) {
+        fields.insert(key.into(), id);
+    }
+}
+
+fn insert_timestamp(fields: &mut BTreeMap<String, Value>, key: &str, unix_nano: u64) {
+    if let Some(timestamp) = timestamp(unix_nano) {
+        fields.insert(key.into(), timestamp.into());
+    }
+}
+
+fn insert_count(fields: &mut BTreeMap<String, Value>, key: &str, count: u32) {
+    if count != 0 {
+        fields.insert(key.into(), i64::from(count).into());
+    }
+}
+
+fn insert_attributes(
+    fields: &mut BTreeMap<String, Value>,
+    attributes: Vec<KeyValue>,
+    dropped_attributes_count: u32,
+) {
+    if !attributes.is_empty() {
+        fields.insert("attributes".into(), key_values_into_value(attributes));
+    }
+    insert_count(fields, "dropped_attributes_count", dropped_attributes_count);
+}
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use super::{default_refresh_interval_secs, Discoverer, Labels, ADDRESS_LABEL};
+
+const FILEPATH_LABEL: &str = "__meta_filepath";
+
+#[derive(Debug, Snafu)]
+enum FileSdError {
+    #[snafu(display("Invalid file pattern {:?}: {}", pattern, source))]
+    InvalidPattern {
+        pattern: String,
+        source: glob::PatternError,
+    },
+    #[snafu(display("No file patterns are set"))]
+    NoFiles,
+}
+
+/// Discovers targets from JSON files in the format of Prometheus' `file_sd`,
+/// for example `[{"targets": ["10.0.0.1:9100"], "labels": {"job": "node"}}]`.
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct FileSdConfig {
+    /// Glob patterns of the files to read the targets from.
+    files: Vec<String>,
+    #[derivative(Default(value = "default_refresh_interval_secs()"))]
+    pub(super) refresh_interval_secs: u64,
+}
+
+impl FileSdConfig {
+    pub(super) fn build(&self) -> crate::Result<FileSd> {
+        if self.files.is_empty() {
+            return Err(FileSdError::NoFiles.into());
+        }
+        for pattern in &self.files {
+            glob::Pattern::new(pattern).context(InvalidPatternSnafu { pattern })?;
+        }
+        Ok(FileSd {
+            patterns: self.files.clone(),
+        })
+    }
+}
+
+pub(super) struct FileSd {
+    patterns: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TargetGroup {
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: Labels,
+}
+
+fn parse(path: &Path, contents: &[u8]) -> serde_json::Result<Vec<Labels>> {
+    let groups: Vec<TargetGroup> = serde_json::from_slice(contents)?;
+    Ok(groups
+        .into_iter()
+        .flat_map(|group| {
+            let labels = group.labels;
+            group.targets.into_iter().map(move |target| {
+                let mut labels = labels.clone();
+                labels.insert(ADDRESS_LABEL.into(), target);
+                labels.insert(FILEPATH_LABEL.into(), path.to_string_lossy().into_owned());
+                labels
+            })
+        })
+        .collect())
+}
+
+#[async_trait]
+impl Discoverer for FileSd {
+    fn mechanism(&self) -> &'static str {
+        "file_sd"
+    }
+
+    async fn discover(&mut self) -> crate::Result<Vec<Labels>> {
+        let mut targets = Vec::new();
+        for pattern in &self.patterns {
+            for path in glob::glob(pattern)? {
+                let path = path?;
+                let contents = tokio::fs::read(&path).await?;
+                targets.extend(parse(&path, &contents)?);
+            }
+        }
+        Ok(targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn discovers_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("node.json"),
+            r#"[
+                {"targets": ["10.0.0.1:9100", "10.0.0.2:9100"], "labels": {"job": "node"}},
+                {"targets": ["10.0.0.3:9100"]}
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("ignored.yml"), "- targets: []").unwrap();
+
+        let mut discoverer = FileSdConfig {
+            files: vec![format!("{}/*.json", dir.path().display())],
+            refresh_interval_secs: 1,
+        }
+        .build()
+        .unwrap();
+        let targets = discoverer.discover().await.unwrap();
+
+        let path = dir.path().join("node.json").display().to_string();
+        let addresses = targets
+            .iter()
+            .map(|labels| labels[ADDRESS_LABEL].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            addresses,
+            vec!["10.0.0.1:9100", "10.0.0.2:9100", "10.0.0.3:9100"]
+        );
+        assert!(targets.iter().all(|labels| labels[FILEPATH_LABEL] == path));
+        assert_eq!(targets[1].get("job").map(String::as_str), Some("node"));
+        assert_eq!(targets[2].get("job"), None);
+    }
+
+    #[tokio::test]
+    async fn fails_on_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("targets.json"), "{").unwrap();
+
+        let mut discoverer = FileSdConfig {
+            files: vec![format!("{}/*.json", dir.path().display())],
+            refresh_interval_secs: 1,
+        }
+        .build()
+        .unwrap();
+        assert!(discoverer.discover().await.is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let config = FileSdConfig {
+            files: vec!["/etc/targets/[.json".into()],
+            refresh_interval_secs: 1,
+        };
+        assert!(config.build().is_err());
+        assert!(FileSdConfig::default().build().is_err());
+    }
+}
@@ -0,0 +1,483 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use async_trait::async_trait;
+use k8s_openapi::{
+    api::core::v1::{Endpoints, Pod, Service},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+    http::{Request, StatusCode},
+    List, ListOptional, ListResponse, ListableResource, RequestError, ResponseBody,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    default_refresh_interval_secs, sanitize_label_name, Discoverer, Labels, ADDRESS_LABEL,
+    METRICS_PATH_LABEL, SCHEME_LABEL,
+};
+use crate::{config::ProxyConfig, kubernetes as k8s};
+
+const SCRAPE_ANNOTATION: &str = "prometheus.io/scrape";
+const PORT_ANNOTATION: &str = "prometheus.io/port";
+const PATH_ANNOTATION: &str = "prometheus.io/path";
+const SCHEME_ANNOTATION: &str = "prometheus.io/scheme";
+
+const META_PREFIX: &str = "__meta_kubernetes_";
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Eq, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Scrapes the annotated pods.
+    #[derivative(Default)]
+    Pod,
+    /// Scrapes the endpoints of the annotated services.
+    Endpoints,
+}
+
+/// Discovers the targets to scrape from the `prometheus.io/*` annotations of
+/// pods, or of services for their endpoints.
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct KubernetesSdConfig {
+    role: Role,
+    /// The namespaces to discover targets in, all of them if empty.
+    namespaces: Vec<String>,
+    /// Specifies the label selector to filter pods or services with.
+    label_selector: Option<String>,
+    /// Optional path to a kubeconfig file readable by Vector. If not set,
+    /// Vector will try to connect to Kubernetes using in-cluster configuration.
+    kube_config_file: Option<PathBuf>,
+    #[derivative(Default(value = "default_refresh_interval_secs()"))]
+    pub(super) refresh_interval_secs: u64,
+}
+
+impl KubernetesSdConfig {
+    pub(super) fn build(&self, proxy: &ProxyConfig) -> crate::Result<KubernetesSd> {
+        let config = match &self.kube_config_file {
+            Some(kc) => k8s::client::config::Config::kubeconfig(kc)?,
+            None => k8s::client::config::Config::in_cluster()?,
+        };
+        Ok(KubernetesSd {
+            client: k8s::client::Client::new(config, proxy)?,
+            role: self.role,
+            namespaces: self.namespaces.clone(),
+            label_selector: self.label_selector.clone(),
+        })
+    }
+}
+
+pub(super) struct KubernetesSd {
+    client: k8s::client::Client,
+    role: Role,
+    namespaces: Vec<String>,
+    label_selector: Option<String>,
+}
+
+type ListRequest<T> = Result<
+    (
+        Request<Vec<u8>>,
+        fn(StatusCode) -> ResponseBody<ListResponse<T>>,
+    ),
+    RequestError,
+>;
+
+impl KubernetesSd {
+    /// Lists the objects of the namespaces, or of all of them.
+    async fn list<T>(
+        &mut self,
+        all: fn(ListOptional<'_>) -> ListRequest<T>,
+        namespaced: fn(&str, ListOptional<'_>) -> ListRequest<T>,
+        label_selector: Option<&str>,
+    ) -> crate::Result<Vec<T>>
+    where
+        T: DeserializeOwned + ListableResource,
+    {
+        let optional = ListOptional {
+            label_selector,
+            ..Default::default()
+        };
+        let requests = if self.namespaces.is_empty() {
+            vec![all(optional)?.0]
+        } else {
+            self.namespaces
+                .iter()
+                .map(|namespace| namespaced(namespace, optional).map(|(request, _)| request))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut items = Vec::new();
+        for request in requests {
+            let response = self.client.send(request).await?;
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            if parts.status != StatusCode::OK {
+                return Err(
+                    format!("Listing {} failed with status {}", T::KIND, parts.status).into(),
+                );
+            }
+            items.extend(serde_json::from_slice::<List<T>>(&body)?.items);
+        }
+        Ok(items)
+    }
+}
+
+#[async_trait]
+impl Discoverer for KubernetesSd {
+    fn mechanism(&self) -> &'static str {
+        "kubernetes_sd"
+    }
+
+    async fn discover(&mut self) -> crate::Result<Vec<Labels>> {
+        let label_selector = self.label_selector.clone();
+        match self.role {
+            Role::Pod => {
+                let pods = self
+                    .list(
+                        Pod::list_pod_for_all_namespaces,
+                        Pod::list_namespaced_pod,
+                        label_selector.as_deref(),
+                    )
+                    .await?;
+                Ok(pods.iter().flat_map(pod_targets).collect())
+            }
+            Role::Endpoints => {
+                // The selector applies to the services, endpoints having the labels of the
+                // service they belong to only when managed by the endpoints controller.
+                let services = self
+                    .list(
+                        Service::list_service_for_all_namespaces,
+                        Service::list_namespaced_service,
+                        label_selector.as_deref(),
+                    )
+                    .await?;
+                let endpoints = self
+                    .list(
+                        Endpoints::list_endpoints_for_all_namespaces,
+                        Endpoints::list_namespaced_endpoints,
+                        None,
+                    )
+                    .await?;
+
+                let services = services
+                    .iter()
+                    .filter_map(|service| {
+                        let metadata = &service.metadata;
+                        Some((
+                            (metadata.namespace.as_ref()?, metadata.name.as_ref()?),
+                            service,
+                        ))
+                    })
+                    .collect::<HashMap<_, _>>();
+                Ok(endpoints
+                    .iter()
+                    .filter_map(|endpoints| {
+                        let metadata = &endpoints.metadata;
+                        let key = (metadata.namespace.as_ref()?, metadata.name.as_ref()?);
+                        services
+                            .get(&key)
+                            .map(|service| endpoints_targets(endpoints, service))
+                    })
+                    .flatten()
+                    .collect())
+            }
+        }
+    }
+}
+
+/// The `prometheus.io/*` annotations of an object to scrape.
+struct ScrapeAnnotations<'a> {
+    port: Option<&'a str>,
+    path: Option<&'a str>,
+    scheme: Option<&'a str>,
+}
+
+impl<'a> ScrapeAnnotations<'a> {
+    /// Returns `None` unless the object is annotated with `prometheus.io/scrape: "true"`.
+    fn new(metadata: &'a ObjectMeta) -> Option<Self> {
+        let annotations = metadata.annotations.as_ref()?;
+        let get = |name| annotations.get(name).map(String::as_str);
+        (get(SCRAPE_ANNOTATION) == Some("true")).then(|| Self {
+            port: get(PORT_ANNOTATION),
+            path: get(PATH_ANNOTATION),
+            scheme: get(SCHEME_ANNOTATION),
+        })
+    }
+
+    fn matches_port(&self, port: i32) -> bool {
+        self.port
+            .map_or(true, |annotated| annotated == port.to_string())
+    }
+
+    fn target(&self, host: &str, port: &str) -> Labels {
+        let mut labels = Labels::new();
+        labels.insert(ADDRESS_LABEL.into(), format!("{}:{}", host, port));
+        if let Some(path) = self.path {
+            labels.insert(METRICS_PATH_LABEL.into(), path.into());
+        }
+        if let Some(scheme) = self.scheme {
+            labels.insert(SCHEME_LABEL.into(), scheme.into());
+        }
+        labels
+    }
+}
+
+fn insert_meta(labels: &mut Labels, name: &str, value: impl Into<String>) {
+    labels.insert(format!("{}{}", META_PREFIX, name), value.into());
+}
+
+fn insert_object_meta(labels: &mut Labels, kind: &str, metadata: &ObjectMeta) {
+    if let Some(name) = &metadata.name {
+        insert_meta(labels, &format!("{}_name", kind), name);
+    }
+    for (map, suffix) in [
+        (&metadata.labels, "label"),
+        (&metadata.annotations, "annotation"),
+    ] {
+        for (name, value) in map.iter().flatten() {
+            let name = format!("{}_{}_{}", kind, suffix, sanitize_label_name(name));
+            insert_meta(labels, &name, value);
+        }
+    }
+}
+
+/// The targets of a running pod, either the annotated port or each port of its containers.
+fn pod_targets(pod: &Pod) -> Vec<Labels> {
+    let annotations = match ScrapeAnnotations::new(&pod.metadata) {
+        Some(annotations) => annotations,
+        None => return Vec::new(),
+    };
+    let status = pod.status.as_ref();
+    let ip = match status.and_then(|status| status.pod_ip.as_ref()) {
+        Some(ip) if status.and_then(|status| status.phase.as_deref()) == Some("Running") => ip,
+        _ => return Vec::new(),
+    };
+
+    let mut common = Labels::new();
+    if let Some(namespace) = &pod.metadata.namespace {
+        insert_meta(&mut common, "namespace", namespace);
+    }
+    insert_object_meta(&mut common, "pod", &pod.metadata);
+    insert_meta(&mut common, "pod_ip", ip);
+    let spec = pod.spec.as_ref();
+    if let Some(node_name) = spec.and_then(|spec| spec.node_name.as_ref()) {
+        insert_meta(&mut common, "pod_node_name", node_name);
+    }
+
+    let mut targets = Vec::new();
+    for container in spec.iter().flat_map(|spec| &spec.containers) {
+        for port in container.ports.iter().flatten() {
+            if !annotations.matches_port(port.container_port) {
+                continue;
+            }
+            let mut labels = annotations.target(ip, &port.container_port.to_string());
+            labels.extend(common.clone());
+            insert_meta(&mut labels, "pod_container_name", &container.name);
+            insert_meta(
+                &mut labels,
+                "pod_container_port_number",
+                port.container_port.to_string(),
+            );
+            if let Some(name) = &port.name {
+                insert_meta(&mut labels, "pod_container_port_name", name);
+            }
+            targets.push(labels);
+        }
+    }
+
+    // The annotated port may not be declared by any container.
+    if let (Some(port), true) = (annotations.port, targets.is_empty()) {
+        let mut labels = annotations.target(ip, port);
+        labels.extend(common);
+        targets.push(labels);
+    }
+    targets
+}
+
+/// The targets of the ready addresses of the endpoints of an annotated service.
+fn endpoints_targets(endpoints: &Endpoints, service: &Service) -> Vec<Labels> {
+    let annotations = match ScrapeAnnotations::new(&service.metadata) {
+        Some(annotations) => annotations,
+        None => return Vec::new(),
+    };
+
+    let mut common = Labels::new();
+    if let Some(namespace) = &endpoints.metadata.namespace {
+        insert_meta(&mut common, "namespace", namespace);
+    }
+    if let Some(name) = &endpoints.metadata.name {
+        insert_meta(&mut common, "endpoints_name", name);
+    }
+    insert_object_meta(&mut common, "service", &service.metadata);
+
+    let mut targets = Vec::new();
+    for subset in endpoints.subsets.iter().flatten() {
+        for address in subset.addresses.iter().flatten() {
+            for port in subset.ports.iter().flatten() {
+                if !annotations.matches_port(port.port) {
+                    continue;
+                }
+                let mut labels = annotations.target(&address.ip, &port.port.to_string());
+                labels.extend(common.clone());
+                if let Some(name) = &port.name {
+                    insert_meta(&mut labels, "endpoint_port_name", name);
+                }
+                if let Some(node_name) = &address.node_name {
+                    insert_meta(&mut labels, "endpoint_node_name", node_name);
+                }
+                if let Some(target) = &address.target_ref {
+                    if let (Some(kind), Some(name)) = (&target.kind, &target.name) {
+                        insert_meta(&mut labels, "endpoint_address_target_kind", kind);
+                        insert_meta(&mut labels, "endpoint_address_target_name", name);
+                    }
+                }
+                targets.push(labels);
+            }
+        }
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(annotations: serde_json::Value, phase: &str) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": "api-0",
+                "namespace": "prod",
+                "labels": {"app.kubernetes.io/name": "api"},
+                "annotations": annotations,
+            },
+            "spec": {
+                "nodeName": "node-1",
+                "containers": [
+                    {"name": "api", "ports": [
+                        {"containerPort": 8080, "name": "http"},
+                        {"containerPort": 9102, "name": "metrics"},
+                    ]},
+                    {"name": "sidecar"},
+                ],
+            },
+            "status": {"phase": phase, "podIP": "10.0.0.1"},
+        }))
+        .unwrap()
+    }
+
+    fn addresses(targets: &[Labels]) -> Vec<&str> {
+        targets
+            .iter()
+            .map(|labels| labels[ADDRESS_LABEL].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn discovers_annotated_pods() {
+        let targets = pod_targets(&pod(
+            serde_json::json!({
+                "prometheus.io/scrape": "true",
+                "prometheus.io/port": "9102",
+                "prometheus.io/path": "/stats",
+            }),
+            "Running",
+        ));
+        assert_eq!(addresses(&targets), vec!["10.0.0.1:9102"]);
+
+        let labels = &targets[0];
+        let get = |name: &str| labels.get(name).map(String::as_str);
+        assert_eq!(get(METRICS_PATH_LABEL), Some("/stats"));
+        assert_eq!(get(SCHEME_LABEL), None);
+        assert_eq!(get("__meta_kubernetes_namespace"), Some("prod"));
+        assert_eq!(get("__meta_kubernetes_pod_name"), Some("api-0"));
+        assert_eq!(get("__meta_kubernetes_pod_node_name"), Some("node-1"));
+        assert_eq!(
+            get("__meta_kubernetes_pod_label_app_kubernetes_io_name"),
+            Some("api")
+        );
+        assert_eq!(
+            get("__meta_kubernetes_pod_annotation_prometheus_io_port"),
+            Some("9102")
+        );
+        assert_eq!(get("__meta_kubernetes_pod_container_name"), Some("api"));
+        assert_eq!(
+            get("__meta_kubernetes_pod_container_port_name"),
+            Some("metrics")
+        );
+    }
+
+    #[test]
+    fn discovers_all_ports_without_port_annotation() {
+        let targets = pod_targets(&pod(
+            serde_json::json!({"prometheus.io/scrape": "true"}),
+            "Running",
+        ));
+        assert_eq!(addresses(&targets), vec!["10.0.0.1:8080", "10.0.0.1:9102"]);
+    }
+
+    #[test]
+    fn discovers_undeclared_annotated_port() {
+        let targets = pod_targets(&pod(
+            serde_json::json!({"prometheus.io/scrape": "true", "prometheus.io/port": "9999"}),
+            "Running",
+        ));
+        assert_eq!(addresses(&targets), vec!["10.0.0.1:9999"]);
+        assert_eq!(targets[0].get("__meta_kubernetes_pod_container_name"), None);
+    }
+
+    #[test]
+    fn ignores_pods_not_to_scrape() {
+        assert!(pod_targets(&pod(serde_json::json!({}), "Running")).is_empty());
+        assert!(pod_targets(&pod(
+            serde_json::json!({"prometheus.io/scrape": "false"}),
+            "Running"
+        ))
+        .is_empty());
+        assert!(pod_targets(&pod(
+            serde_json::json!({"prometheus.io/scrape": "true"}),
+            "Succeeded"
+        ))
+        .is_empty());
+    }
+
+    #[test]
+    fn discovers_endpoints_of_annotated_services() {
+        let service: Service = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": "api",
+                "namespace": "prod",
+                "annotations": {
+                    "prometheus.io/scrape": "true",
+                    "prometheus.io/port": "9102",
+                    "prometheus.io/scheme": "https",
+                },
+            },
+        }))
+        .unwrap();
+        let endpoints: Endpoints = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "api", "namespace": "prod"},
+            "subsets": [{
+                "addresses": [
+                    {"ip": "10.0.0.1", "nodeName": "node-1", "targetRef": {"kind": "Pod", "name": "api-0"}},
+                    {"ip": "10.0.0.2"},
+                ],
+                "notReadyAddresses": [{"ip": "10.0.0.3"}],
+                "ports": [{"port": 8080, "name": "http"}, {"port": 9102, "name": "metrics"}],
+            }],
+        }))
+        .unwrap();
+
+        let targets = endpoints_targets(&endpoints, &service);
+        assert_eq!(addresses(&targets), vec!["10.0.0.1:9102", "10.0.0.2:9102"]);
+
+        let labels = &targets[0];
+        let get = |name: &str| labels.get(name).map(String::as_str);
+        assert_eq!(get(SCHEME_LABEL), Some("https"));
+        assert_eq!(get("__meta_kubernetes_service_name"), Some("api"));
+        assert_eq!(get("__meta_kubernetes_endpoints_name"), Some("api"));
+        assert_eq!(get("__meta_kubernetes_endpoint_port_name"), Some("metrics"));
+        assert_eq!(
+            get("__meta_kubernetes_endpoint_address_target_name"),
+            Some("api-0")
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Discovery of the targets to scrape. As with Prometheus, each mechanism
+//! discovers targets as sets of labels, `__address__` being the address to
+//! scrape and `__meta_*` ones describing where the target was discovered.
+//! The labels are then relabeled, the remaining labels not starting with `__`
+//! being added as tags to the metrics scraped from the target.
+
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use url::form_urlencoded;
+
+use super::relabel::{relabel, Labels, RelabelConfig, Relabeler};
+use crate::{config::ProxyConfig, internal_events::PrometheusDiscoveryError};
+
+mod file_sd;
+mod kubernetes;
+
+pub use file_sd::FileSdConfig;
+pub use kubernetes::KubernetesSdConfig;
+
+const ADDRESS_LABEL: &str = "__address__";
+const SCHEME_LABEL: &str = "__scheme__";
+const METRICS_PATH_LABEL: &str = "__metrics_path__";
+const PARAM_LABEL_PREFIX: &str = "__param_";
+
+const fn default_refresh_interval_secs() -> u64 {
+    30
+}
+
+/// Replaces the characters not allowed in label names by underscores.
+fn sanitize_label_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// A target to scrape, and the tags to add to the metrics scraped from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScrapeTarget {
+    pub url: http::Uri,
+    pub tags: Vec<(String, String)>,
+}
+
+impl ScrapeTarget {
+    pub const fn new(url: http::Uri) -> Self {
+        Self {
+            url,
+            tags: Vec::new(),
+        }
+    }
+
+    fn from_labels(labels: &Labels) -> crate::Result<Self> {
+        let label = |name, default| labels.get(name).map(String::as_str).unwrap_or(default);
+
+        let address = labels
+            .get(ADDRESS_LABEL)
+            .filter(|address| !address.is_empty())
+            .ok_or("Target has no `__address__` label")?;
+        let mut url = format!(
+            "{}://{}{}",
+            label(SCHEME_LABEL, "http"),
+            address,
+            label(METRICS_PATH_LABEL, "/metrics")
+        );
+
+        let params = labels
+            .iter()
+            .filter_map(|(name, value)| {
+                name.strip_prefix(PARAM_LABEL_PREFIX)
+                    .map(|name| (name, value))
+            })
+            .collect::<Vec<_>>();
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(
+                &form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(params)
+                    .finish(),
+            );
+        }
+
+        Ok(Self {
+            url: url.parse()?,
+            tags: labels
+                .iter()
+                .filter(|(name, _)| !name.starts_with("__"))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        })
+    }
+}
+
+#[async_trait]
+trait Discoverer: Send {
+    fn mechanism(&self) -> &'static str;
+
+    async fn discover(&mut self) -> crate::Result<Vec<Labels>>;
+}
+
+/// Refreshes the targets of a discoverer at most once per interval, keeping
+/// the previous ones when refreshing fails.
+struct Refreshed {
+    discoverer: Box<dyn Discoverer>,
+    interval: Duration,
+    refreshed: Option<Instant>,
+    targets: Vec<Labels>,
+}
+
+impl Refreshed {
+    fn new(discoverer: Box<dyn Discoverer>, refresh_interval_secs: u64) -> Self {
+        Self {
+            discoverer,
+            interval: Duration::from_secs(refresh_interval_secs),
+            refreshed: None,
+            targets: Vec::new(),
+        }
+    }
+
+    async fn targets(&mut self) -> &[Labels] {
+        let due = self
+            .refreshed
+            .map_or(true, |refreshed| refreshed.elapsed() >= self.interval);
+        if due {
+            self.refreshed = Some(Instant::now());
+            match self.discoverer.discover().await {
+                Ok(targets) => self.targets = targets,
+                Err(error) => emit!(&PrometheusDiscoveryError {
+                    mechanism: self.discoverer.mechanism(),
+                    error,
+                }),
+            }
+        }
+        &self.targets
+    }
+}
+
+pub struct Discovery {
+    discoverers: Vec<Refreshed>,
+    relabelers: Vec<Relabeler>,
+}
+
+impl Discovery {
+    /// Returns `None` if no discovery mechanism is configured.
+    pub fn new(
+        file_sd: Option<&FileSdConfig>,
+        kubernetes_sd: Option<&KubernetesSdConfig>,
+        relabel_configs: &[RelabelConfig],
+        proxy: &ProxyConfig,
+    ) -> crate::Result<Option<Self>> {
+        let mut discoverers = Vec::new();
+        if let Some(config) = file_sd {
+            discoverers.push(Refreshed::new(
+                Box::new(config.build()?),
+                config.refresh_interval_secs,
+            ));
+        }
+        if let Some(config) = kubernetes_sd {
+            discoverers.push(Refreshed::new(
+                Box::new(config.build(proxy)?),
+                config.refresh_interval_secs,
+            ));
+        }
+
+        let relabelers = relabel_configs
+            .iter()
+            .map(RelabelConfig::build)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((!discoverers.is_empty()).then(|| Self {
+            discoverers,
+            relabelers,
+        }))
+    }
+
+    /// The targets currently discovered, refreshing those which are due.
+    pub async fn targets(&mut self) -> Vec<ScrapeTarget> {
+        let mut seen = BTreeSet::new();
+        let mut targets = Vec::new();
+        for discoverer in &mut self.discoverers {
+            let mechanism = discoverer.discoverer.mechanism();
+            for labels in discoverer.targets().await {
+                let target = match relabel(with_defaults(labels.clone()), &self.relabelers) {
+                    Some(labels) => ScrapeTarget::from_labels(&labels),
+                    None => continue,
+                };
+                match target {
+                    // Several discovered targets may end up being the same once relabeled.
+                    Ok(target) => {
+                        if seen.insert((target.url.to_string(), target.tags.clone())) {
+                            targets.push(target);
+                        }
+                    }
+                    Err(error) => emit!(&PrometheusDiscoveryError { mechanism, error }),
+                }
+            }
+        }
+        targets
+    }
+}
+
+fn with_defaults(mut labels: Labels) -> Labels {
+    labels
+        .entry(SCHEME_LABEL.into())
+        .or_insert_with(|| "http".into());
+    labels
+        .entry(METRICS_PATH_LABEL.into())
+        .or_insert_with(|| "/metrics".into());
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn builds_targets_from_labels() {
+        let target = ScrapeTarget::from_labels(&with_defaults(labels(&[
+            ("__address__", "10.0.0.1:9102"),
+            ("__meta_filepath", "/etc/targets.json"),
+            ("job", "api"),
+        ])))
+        .unwrap();
+        assert_eq!(target.url, "http://10.0.0.1:9102/metrics");
+        assert_eq!(target.tags, vec![("job".to_string(), "api".to_string())]);
+
+        let target = ScrapeTarget::from_labels(&labels(&[
+            ("__address__", "example.com"),
+            ("__scheme__", "https"),
+            ("__metrics_path__", "/federate"),
+            ("__param_match[]", "{job=\"api\"}"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            target.url,
+            "https://example.com/federate?match%5B%5D=%7Bjob%3D%22api%22%7D"
+        );
+        assert!(target.tags.is_empty());
+
+        assert!(ScrapeTarget::from_labels(&labels(&[("job", "api")])).is_err());
+    }
+
+    #[test]
+    fn sanitizes_label_names() {
+        assert_eq!(
+            sanitize_label_name("prometheus.io/scrape"),
+            "prometheus_io_scrape"
+        );
+        assert_eq!(
+            sanitize_label_name("app.kubernetes.io/name"),
+            "app_kubernetes_io_name"
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use std::{cmp::Ordering, collections::BTreeMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 use chrono::{DateTime, TimeZone, Utc};
 use prometheus_parser::{proto, GroupKind, MetricGroup, ParserError};
@@ -30,7 +34,33 @@ pub(super) fn parse_text(packet: &str) -> Result<Vec<Event>, ParserError> {
 }
 
 pub(super) fn parse_request(request: proto::WriteRequest) -> Result<Vec<Event>, ParserError> {
-    prometheus_parser::parse_request(request).map(reparse_groups)
+    let descriptions = request
+        .metadata
+        .iter()
+        .map(|metadata| {
+            (
+                metadata.metric_family_name.clone(),
+                (non_empty(&metadata.help), non_empty(&metadata.unit)),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    prometheus_parser::parse_request(request).map(|groups| {
+        let mut events = reparse_groups(groups);
+        for event in &mut events {
+            let metric = event.as_mut_metric();
+            if let Some((description, unit)) = descriptions.get(metric.name()) {
+                let metadata = metric.metadata_mut();
+                metadata.set_metric_description(description.clone());
+                metadata.set_metric_unit(unit.clone());
+            }
+        }
+        events
+    })
+}
+
+fn non_empty(value: &str) -> Option<Arc<str>> {
+    (!value.is_empty()).then(|| value.into())
 }
 
 fn reparse_groups(groups: Vec<MetricGroup>) -> Vec<Event> {
@@ -0,0 +1,324 @@
+//! Relabeling of discovered targets, following the semantics of Prometheus'
+//! `relabel_configs`: the rules are applied in order to the labels of a
+//! target, which is dropped if a rule says so.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+pub type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Snafu)]
+pub enum RelabelError {
+    #[snafu(display("Invalid relabeling regex {:?}: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+    #[snafu(display("Relabeling action `{}` requires `target_label`", action))]
+    MissingTargetLabel { action: &'static str },
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Eq, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    #[derivative(Default)]
+    Replace,
+    Keep,
+    Drop,
+    LabelMap,
+    LabelDrop,
+    LabelKeep,
+}
+
+impl RelabelAction {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Replace => "replace",
+            Self::Keep => "keep",
+            Self::Drop => "drop",
+            Self::LabelMap => "labelmap",
+            Self::LabelDrop => "labeldrop",
+            Self::LabelKeep => "labelkeep",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct RelabelConfig {
+    /// The labels whose values, joined with `separator`, are matched against `regex`.
+    source_labels: Vec<String>,
+    #[derivative(Default(value = "default_separator()"))]
+    separator: String,
+    /// Anchored at both ends.
+    #[derivative(Default(value = "default_regex()"))]
+    regex: String,
+    target_label: Option<String>,
+    #[derivative(Default(value = "default_replacement()"))]
+    replacement: String,
+    action: RelabelAction,
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+impl RelabelConfig {
+    pub fn build(&self) -> Result<Relabeler, RelabelError> {
+        let regex = Regex::new(&format!("^(?:{})$", self.regex)).context(InvalidRegexSnafu {
+            regex: self.regex.clone(),
+        })?;
+        if self.action == RelabelAction::Replace && self.target_label.is_none() {
+            return Err(RelabelError::MissingTargetLabel {
+                action: self.action.name(),
+            });
+        }
+        Ok(Relabeler {
+            config: self.clone(),
+            regex,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Relabeler {
+    config: RelabelConfig,
+    regex: Regex,
+}
+
+impl Relabeler {
+    /// Applies the rule to `labels`, returning `false` if the target is to be dropped.
+    fn apply(&self, labels: &mut Labels) -> bool {
+        let config = &self.config;
+        match config.action {
+            RelabelAction::Replace => {
+                let value = self.source_value(labels);
+                if let Some(captures) = self.regex.captures(&value) {
+                    let mut target = String::new();
+                    // Checked when built.
+                    let target_label = config.target_label.as_ref().unwrap();
+                    captures.expand(target_label, &mut target);
+                    let mut replacement = String::new();
+                    captures.expand(&config.replacement, &mut replacement);
+                    if replacement.is_empty() {
+                        labels.remove(&target);
+                    } else if !target.is_empty() {
+                        labels.insert(target, replacement);
+                    }
+                }
+                true
+            }
+            RelabelAction::Keep => self.regex.is_match(&self.source_value(labels)),
+            RelabelAction::Drop => !self.regex.is_match(&self.source_value(labels)),
+            RelabelAction::LabelMap => {
+                let mapped = labels
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        self.regex.captures(name).map(|captures| {
+                            let mut target = String::new();
+                            captures.expand(&config.replacement, &mut target);
+                            (target, value.clone())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                labels.extend(mapped);
+                true
+            }
+            RelabelAction::LabelDrop => {
+                labels.retain(|name, _| !self.regex.is_match(name));
+                true
+            }
+            RelabelAction::LabelKeep => {
+                labels.retain(|name, _| self.regex.is_match(name));
+                true
+            }
+        }
+    }
+
+    fn source_value(&self, labels: &Labels) -> String {
+        self.config
+            .source_labels
+            .iter()
+            .map(|name| labels.get(name).map(String::as_str).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(&self.config.separator)
+    }
+}
+
+/// Applies the rules in order, returning `None` if one of them drops the target.
+pub fn relabel(mut labels: Labels, relabelers: &[Relabeler]) -> Option<Labels> {
+    relabelers
+        .iter()
+        .all(|relabeler| relabeler.apply(&mut labels))
+        .then(|| labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn relabeler(config: &str) -> Relabeler {
+        toml::from_str::<RelabelConfig>(config)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn replaces() {
+        let relabelers = [relabeler(
+            r#"
+            source_labels = ["__address__", "__meta_kubernetes_pod_annotation_prometheus_io_port"]
+            regex = '([^:]+)(?::\d+)?;(\d+)'
+            target_label = "__address__"
+            replacement = "$1:$2"
+            "#,
+        )];
+        assert_eq!(
+            relabel(
+                labels(&[
+                    ("__address__", "10.0.0.1:80"),
+                    (
+                        "__meta_kubernetes_pod_annotation_prometheus_io_port",
+                        "9102"
+                    ),
+                ]),
+                &relabelers
+            ),
+            Some(labels(&[
+                ("__address__", "10.0.0.1:9102"),
+                (
+                    "__meta_kubernetes_pod_annotation_prometheus_io_port",
+                    "9102"
+                ),
+            ]))
+        );
+
+        // Unchanged when the regex doesn't match.
+        assert_eq!(
+            relabel(labels(&[("__address__", "10.0.0.1:80")]), &relabelers),
+            Some(labels(&[("__address__", "10.0.0.1:80")]))
+        );
+    }
+
+    #[test]
+    fn removes_empty_replacements() {
+        let relabelers = [relabeler(
+            r#"
+            source_labels = ["missing"]
+            target_label = "job"
+            "#,
+        )];
+        assert_eq!(
+            relabel(labels(&[("job", "api")]), &relabelers),
+            Some(labels(&[]))
+        );
+    }
+
+    #[test]
+    fn keeps_and_drops() {
+        let keep = [relabeler(
+            r#"
+            source_labels = ["__meta_kubernetes_namespace"]
+            regex = "prod|staging"
+            action = "keep"
+            "#,
+        )];
+        assert!(relabel(labels(&[("__meta_kubernetes_namespace", "prod")]), &keep).is_some());
+        // Anchored, so partial matches don't count.
+        assert!(relabel(labels(&[("__meta_kubernetes_namespace", "preprod")]), &keep).is_none());
+        assert!(relabel(labels(&[]), &keep).is_none());
+
+        let drop = [relabeler(
+            r#"
+            source_labels = ["__meta_kubernetes_namespace"]
+            regex = "kube-system"
+            action = "drop"
+            "#,
+        )];
+        assert!(relabel(
+            labels(&[("__meta_kubernetes_namespace", "kube-system")]),
+            &drop
+        )
+        .is_none());
+        assert!(relabel(labels(&[("__meta_kubernetes_namespace", "prod")]), &drop).is_some());
+    }
+
+    #[test]
+    fn maps_and_filters_labels() {
+        let relabelers = [
+            relabeler(
+                r#"
+                regex = "__meta_kubernetes_pod_label_(.+)"
+                action = "labelmap"
+                "#,
+            ),
+            relabeler(
+                r#"
+                regex = "pod_template_hash"
+                action = "labeldrop"
+                "#,
+            ),
+        ];
+        assert_eq!(
+            relabel(
+                labels(&[
+                    ("__meta_kubernetes_pod_label_app", "api"),
+                    ("__meta_kubernetes_pod_label_pod_template_hash", "abc"),
+                ]),
+                &relabelers
+            ),
+            Some(labels(&[
+                ("__meta_kubernetes_pod_label_app", "api"),
+                ("__meta_kubernetes_pod_label_pod_template_hash", "abc"),
+                ("app", "api"),
+            ]))
+        );
+
+        let keep = [relabeler(
+            r#"
+            regex = "__.+|app"
+            action = "labelkeep"
+            "#,
+        )];
+        assert_eq!(
+            relabel(
+                labels(&[("__address__", "host:80"), ("app", "api"), ("env", "prod")]),
+                &keep
+            ),
+            Some(labels(&[("__address__", "host:80"), ("app", "api")]))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let config = toml::from_str::<RelabelConfig>(r#"regex = "(""#).unwrap();
+        assert!(matches!(
+            config.build(),
+            Err(RelabelError::InvalidRegex { .. })
+        ));
+
+        let config = toml::from_str::<RelabelConfig>(r#"source_labels = ["a"]"#).unwrap();
+        assert!(matches!(
+            config.build(),
+            Err(RelabelError::MissingTargetLabel { .. })
+        ));
+    }
+}
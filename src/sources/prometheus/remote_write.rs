@@ -1,7 +1,11 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 
 use bytes::Bytes;
-use prometheus_parser::proto;
+use prometheus_parser::{proto, METRIC_NAME_LABEL};
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use warp::http::{HeaderMap, StatusCode};
@@ -56,7 +60,7 @@ impl GenerateConfig for PrometheusRemoteWriteConfig {
 #[typetag::serde(name = "prometheus_remote_write")]
 impl SourceConfig for PrometheusRemoteWriteConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
-        let source = RemoteWriteSource;
+        let source = RemoteWriteSource::default();
         source.run(
             self.address,
             "",
@@ -79,14 +83,55 @@ impl SourceConfig for PrometheusRemoteWriteConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
 }
 
-#[derive(Clone)]
-struct RemoteWriteSource;
+#[derive(Clone, Default)]
+struct RemoteWriteSource {
+    /// The metadata of the metric families by their name. Prometheus sends it apart from the
+    /// samples, so it is kept to type the samples of the later requests.
+    metadata: Arc<Mutex<HashMap<String, proto::MetricMetadata>>>,
+}
 
 impl RemoteWriteSource {
+    /// Remembers the metadata of the request, and adds the remembered metadata of the families of
+    /// its samples that it lacks.
+    fn complete_metadata(&self, request: &mut proto::WriteRequest) {
+        let proto::WriteRequest {
+            timeseries,
+            metadata,
+        } = request;
+        let mut known = self.metadata.lock().unwrap();
+        for family in metadata.iter() {
+            known.insert(family.metric_family_name.clone(), family.clone());
+        }
+
+        let mut included = metadata
+            .iter()
+            .map(|family| family.metric_family_name.clone())
+            .collect::<HashSet<_>>();
+        let names = timeseries
+            .iter()
+            .flat_map(|timeseries| timeseries.labels.iter())
+            .filter(|label| label.name == METRIC_NAME_LABEL)
+            .map(|label| label.value.as_str());
+        for name in names {
+            for family in family_names(name) {
+                if !included.contains(family) {
+                    if let Some(cached) = known.get(family) {
+                        included.insert(family.to_owned());
+                        metadata.push(cached.clone());
+                    }
+                }
+            }
+        }
+    }
+
     fn decode_body(&self, body: Bytes) -> Result<Vec<Event>, ErrorMessage> {
-        let request = proto::WriteRequest::decode(body).map_err(|error| {
+        let mut request = proto::WriteRequest::decode(body).map_err(|error| {
             emit!(&PrometheusRemoteWriteParseError {
                 error: error.clone()
             });
@@ -95,6 +140,7 @@ impl RemoteWriteSource {
                 format!("Could not decode write request: {}", error),
             )
         })?;
+        self.complete_metadata(&mut request);
         parser::parse_request(request).map_err(|error| {
             ErrorMessage::new(
                 StatusCode::BAD_REQUEST,
@@ -104,6 +150,16 @@ impl RemoteWriteSource {
     }
 }
 
+/// The names of the families the series may belong to, as the series of histograms and summaries
+/// are named after their family with a suffix.
+fn family_names(name: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(name).chain(
+        ["_bucket", "_sum", "_count"]
+            .into_iter()
+            .filter_map(move |suffix| name.strip_suffix(suffix)),
+    )
+}
+
 impl HttpSource for RemoteWriteSource {
     fn build_events(
         &self,
@@ -128,6 +184,7 @@ impl HttpSource for RemoteWriteSource {
 
 #[cfg(test)]
 mod test {
+    use bytes::BytesMut;
     use chrono::{SubsecRound as _, Utc};
     use vector_core::event::{EventStatus, Metric, MetricKind, MetricValue};
 
@@ -204,6 +261,92 @@ mod test {
         vector_common::assert_event_data_eq!(events, output);
     }
 
+    fn decode(source: &RemoteWriteSource, request: proto::WriteRequest) -> Vec<Event> {
+        let mut body = BytesMut::new();
+        request.encode(&mut body).unwrap();
+        source.decode_body(body.freeze()).unwrap()
+    }
+
+    fn timeseries(name: &str, value: f64) -> proto::TimeSeries {
+        proto::TimeSeries {
+            labels: vec![proto::Label {
+                name: METRIC_NAME_LABEL.into(),
+                value: name.into(),
+            }],
+            samples: vec![proto::Sample {
+                value,
+                timestamp: 1612325106789,
+            }],
+        }
+    }
+
+    fn metadata(name: &str, r#type: proto::MetricType) -> proto::MetricMetadata {
+        proto::MetricMetadata {
+            r#type: r#type as i32,
+            metric_family_name: name.into(),
+            help: format!("The {}.", name),
+            unit: "seconds".into(),
+        }
+    }
+
+    #[test]
+    fn remembers_metadata_across_requests() {
+        let source = RemoteWriteSource::default();
+        let events = decode(
+            &source,
+            proto::WriteRequest {
+                timeseries: vec![],
+                metadata: vec![
+                    metadata("uptime_total", proto::MetricType::Counter),
+                    metadata("latency", proto::MetricType::Histogram),
+                ],
+            },
+        );
+        assert!(events.is_empty());
+
+        let events = decode(
+            &source,
+            proto::WriteRequest {
+                timeseries: vec![
+                    timeseries("uptime_total", 42.0),
+                    timeseries("latency_count", 3.0),
+                    timeseries("latency_sum", 1.5),
+                ],
+                metadata: vec![],
+            },
+        );
+        assert_eq!(events.len(), 2);
+        let uptime = events[0].as_metric();
+        assert_eq!(uptime.value(), &MetricValue::Counter { value: 42.0 });
+        assert_eq!(
+            uptime.metadata().metric_description().as_deref(),
+            Some("The uptime_total.")
+        );
+        assert_eq!(uptime.metadata().metric_unit().as_deref(), Some("seconds"));
+        let latency = events[1].as_metric();
+        assert!(matches!(
+            latency.value(),
+            MetricValue::AggregatedHistogram { count: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn keeps_staleness_markers() {
+        let source = RemoteWriteSource::default();
+        let events = decode(
+            &source,
+            proto::WriteRequest {
+                timeseries: vec![timeseries("temperature", prometheus_parser::stale_marker())],
+                metadata: vec![metadata("temperature", proto::MetricType::Gauge)],
+            },
+        );
+        assert_eq!(events.len(), 1);
+        match events[0].as_metric().value() {
+            MetricValue::Gauge { value } => assert!(prometheus_parser::is_stale_marker(*value)),
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
     fn make_events() -> Vec<Event> {
         let timestamp = || Utc::now().trunc_subsecs(3);
         vec![
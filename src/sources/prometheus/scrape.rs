@@ -1,5 +1,7 @@
 use std::{
+    collections::BTreeMap,
     future::ready,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -7,14 +9,20 @@ use futures::{stream, FutureExt, StreamExt, TryFutureExt};
 use hyper::{Body, Request};
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
+use tokio::sync::Mutex;
 use tokio_stream::wrappers::IntervalStream;
 use vector_core::ByteSizeOf;
 
-use super::parser;
+use super::{
+    discovery::{Discovery, FileSdConfig, KubernetesSdConfig, ScrapeTarget},
+    parser,
+    relabel::RelabelConfig,
+};
 use crate::{
     config::{
         self, GenerateConfig, Output, ProxyConfig, SourceConfig, SourceContext, SourceDescription,
     },
+    event::Metric,
     http::{Auth, HttpClient},
     internal_events::{
         BytesReceived, PrometheusEventsReceived, PrometheusHttpError, PrometheusHttpResponseError,
@@ -38,12 +46,14 @@ static NOT_FOUND_NO_PATH: &str = "No path is set on the endpoint and we got a 40
 enum ConfigError {
     #[snafu(display("Cannot set both `endpoints` and `hosts`"))]
     BothEndpointsAndHosts,
+    #[snafu(display("One of `endpoints`, `file_sd` or `kubernetes_sd` must be set"))]
+    NoTargets,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct PrometheusScrapeConfig {
     // Deprecated name
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
     #[serde(default = "default_scrape_interval_secs")]
     scrape_interval_secs: u64,
@@ -53,6 +63,10 @@ struct PrometheusScrapeConfig {
     honor_labels: bool,
     tls: Option<TlsOptions>,
     auth: Option<Auth>,
+    file_sd: Option<FileSdConfig>,
+    kubernetes_sd: Option<KubernetesSdConfig>,
+    #[serde(default)]
+    relabel_configs: Vec<RelabelConfig>,
 }
 
 pub(crate) const fn default_scrape_interval_secs() -> u64 {
@@ -77,6 +91,9 @@ impl GenerateConfig for PrometheusScrapeConfig {
             honor_labels: false,
             tls: None,
             auth: None,
+            file_sd: None,
+            kubernetes_sd: None,
+            relabel_configs: Vec::new(),
         })
         .unwrap()
     }
@@ -86,14 +103,28 @@ impl GenerateConfig for PrometheusScrapeConfig {
 #[typetag::serde(name = "prometheus_scrape")]
 impl SourceConfig for PrometheusScrapeConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
-        let urls = self
+        let endpoints = self
             .endpoints
             .iter()
-            .map(|s| s.parse::<http::Uri>().context(sources::UriParseSnafu))
-            .collect::<Result<Vec<http::Uri>, sources::BuildError>>()?;
+            .map(|s| {
+                s.parse::<http::Uri>()
+                    .map(ScrapeTarget::new)
+                    .context(sources::UriParseSnafu)
+            })
+            .collect::<Result<Vec<ScrapeTarget>, sources::BuildError>>()?;
+        let discovery = Discovery::new(
+            self.file_sd.as_ref(),
+            self.kubernetes_sd.as_ref(),
+            &self.relabel_configs,
+            &cx.proxy,
+        )?;
+        if endpoints.is_empty() && discovery.is_none() {
+            return Err(ConfigError::NoTargets.into());
+        }
         let tls = TlsSettings::from_options(&self.tls)?;
         Ok(prometheus(
-            urls,
+            endpoints,
+            discovery,
             self.instance_tag.clone(),
             self.endpoint_tag.clone(),
             self.honor_labels,
@@ -125,7 +156,7 @@ impl SourceConfig for PrometheusScrapeConfig {
 struct PrometheusCompatConfig {
     // Clone of PrometheusScrapeConfig to work around serde bug
     // https://github.com/serde-rs/serde/issues/1504
-    #[serde(alias = "hosts")]
+    #[serde(alias = "hosts", default)]
     endpoints: Vec<String>,
     instance_tag: Option<String>,
     endpoint_tag: Option<String>,
@@ -135,6 +166,10 @@ struct PrometheusCompatConfig {
     scrape_interval_secs: u64,
     tls: Option<TlsOptions>,
     auth: Option<Auth>,
+    file_sd: Option<FileSdConfig>,
+    kubernetes_sd: Option<KubernetesSdConfig>,
+    #[serde(default)]
+    relabel_configs: Vec<RelabelConfig>,
 }
 
 #[async_trait::async_trait]
@@ -151,6 +186,9 @@ impl SourceConfig for PrometheusCompatConfig {
             scrape_interval_secs: self.scrape_interval_secs,
             tls: self.tls.clone(),
             auth: self.auth.clone(),
+            file_sd: self.file_sd.clone(),
+            kubernetes_sd: self.kubernetes_sd.clone(),
+            relabel_configs: self.relabel_configs.clone(),
         };
         config.build(cx).await
     }
@@ -168,28 +206,24 @@ impl SourceConfig for PrometheusCompatConfig {
     }
 }
 
-// InstanceInfo stores the scraped instance info and the tag to insert into the log event with. It
-// is used to join these two pieces of info to avoid storing the instance if instance_tag is not
-// configured
-#[derive(Clone)]
-struct InstanceInfo {
-    tag: String,
-    instance: String,
-    honor_label: bool,
-}
-
-// EndpointInfo stores the scraped endpoint info and the tag to insert into the log event with. It
-// is used to join these two pieces of info to avoid storing the endpoint if endpoint_tag is not
-// configured
-#[derive(Clone)]
-struct EndpointInfo {
-    tag: String,
-    endpoint: String,
-    honor_label: bool,
+/// Inserts a tag of the target into a scraped metric. If the metric already has the tag, it is
+/// kept when honoring labels, and otherwise renamed to `exported_<tag>`.
+fn insert_target_tag(metric: &mut Metric, tag: &str, value: &str, honor_labels: bool) {
+    match (honor_labels, metric.tag_value(tag)) {
+        (false, Some(old_value)) => {
+            metric.insert_tag(format!("exported_{}", tag), old_value);
+            metric.insert_tag(tag.to_string(), value.to_string());
+        }
+        (true, Some(_)) => {}
+        (_, None) => {
+            metric.insert_tag(tag.to_string(), value.to_string());
+        }
+    }
 }
 
 fn prometheus(
-    urls: Vec<http::Uri>,
+    endpoints: Vec<ScrapeTarget>,
+    discovery: Option<Discovery>,
     instance_tag: Option<String>,
     endpoint_tag: Option<String>,
     honor_labels: bool,
@@ -200,179 +234,147 @@ fn prometheus(
     shutdown: ShutdownSignal,
     mut out: SourceSender,
 ) -> sources::Source {
+    let discovery = discovery.map(|discovery| Arc::new(Mutex::new(discovery)));
     Box::pin(async move {
         let mut stream = IntervalStream::new(tokio::time::interval(Duration::from_secs(interval)))
             .take_until(shutdown)
-            .map(move |_| stream::iter(urls.clone()))
-            .flatten()
-            .map(move |url| {
-                let client =
-                    HttpClient::new(tls.clone(), &proxy).expect("Building HTTP client failed");
-
-                let mut request = Request::get(&url)
-                    .body(Body::empty())
-                    .expect("error creating request");
-                if let Some(auth) = &auth {
-                    auth.apply(&mut request);
+            .then(move |_| {
+                let mut targets = endpoints.clone();
+                let discovery = discovery.clone();
+                async move {
+                    if let Some(discovery) = discovery {
+                        targets.extend(discovery.lock().await.targets().await);
+                    }
+                    stream::iter(targets)
                 }
+            })
+            .flatten()
+            .map(
+                move |ScrapeTarget {
+                          url,
+                          tags: target_tags,
+                      }| {
+                    let client =
+                        HttpClient::new(tls.clone(), &proxy).expect("Building HTTP client failed");
+
+                    let mut request = Request::get(&url)
+                        .body(Body::empty())
+                        .expect("error creating request");
+                    if let Some(auth) = &auth {
+                        auth.apply(&mut request);
+                    }
 
-                let instance_info = instance_tag.as_ref().map(|tag| {
-                    let instance = format!(
-                        "{}:{}",
-                        url.host().unwrap_or_default(),
-                        url.port_u16().unwrap_or_else(|| match url.scheme() {
-                            Some(scheme) if scheme == &http::uri::Scheme::HTTP => 80,
-                            Some(scheme) if scheme == &http::uri::Scheme::HTTPS => 443,
-                            _ => 0,
-                        })
-                    );
-                    InstanceInfo {
-                        tag: tag.to_string(),
-                        instance,
-                        honor_label: honor_labels,
+                    // The tags of discovered targets take precedence over the instance and endpoint
+                    // ones.
+                    let mut tags = target_tags.into_iter().collect::<BTreeMap<_, _>>();
+                    if let Some(tag) = &instance_tag {
+                        let instance = format!(
+                            "{}:{}",
+                            url.host().unwrap_or_default(),
+                            url.port_u16().unwrap_or_else(|| match url.scheme() {
+                                Some(scheme) if scheme == &http::uri::Scheme::HTTP => 80,
+                                Some(scheme) if scheme == &http::uri::Scheme::HTTPS => 443,
+                                _ => 0,
+                            })
+                        );
+                        tags.entry(tag.clone()).or_insert(instance);
                     }
-                });
-                let endpoint_info = endpoint_tag.as_ref().map(|tag| EndpointInfo {
-                    tag: tag.to_string(),
-                    endpoint: url.to_string(),
-                    honor_label: honor_labels,
-                });
-
-                let start = Instant::now();
-                client
-                    .send(request)
-                    .map_err(crate::Error::from)
-                    .and_then(|response| async move {
-                        let (header, body) = response.into_parts();
-                        let body = hyper::body::to_bytes(body).await?;
-                        emit!(&BytesReceived {
-                            byte_size: body.len(),
-                            protocol: "http"
-                        });
-                        Ok((header, body))
-                    })
-                    .into_stream()
-                    .filter_map(move |response| {
-                        let instance_info = instance_info.clone();
-                        let endpoint_info = endpoint_info.clone();
-
-                        ready(match response {
-                            Ok((header, body)) if header.status == hyper::StatusCode::OK => {
-                                emit!(&PrometheusRequestCompleted {
-                                    start,
-                                    end: Instant::now()
-                                });
-
-                                let body = String::from_utf8_lossy(&body);
-
-                                match parser::parse_text(&body) {
-                                    Ok(events) => {
-                                        emit!(&PrometheusEventsReceived {
-                                            byte_size: events.size_of(),
-                                            count: events.len(),
-                                            uri: url.clone()
-                                        });
-                                        Some(stream::iter(events).map(move |mut event| {
-                                            let metric = event.as_mut_metric();
-                                            if let Some(InstanceInfo {
-                                                tag,
-                                                instance,
-                                                honor_label,
-                                            }) = &instance_info
-                                            {
-                                                match (honor_label, metric.tag_value(tag)) {
-                                                    (false, Some(old_instance)) => {
-                                                        metric.insert_tag(
-                                                            format!("exported_{}", tag),
-                                                            old_instance,
-                                                        );
-                                                        metric.insert_tag(
-                                                            tag.clone(),
-                                                            instance.clone(),
-                                                        );
-                                                    }
-                                                    (true, Some(_)) => {}
-                                                    (_, None) => {
-                                                        metric.insert_tag(
-                                                            tag.clone(),
-                                                            instance.clone(),
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                            if let Some(EndpointInfo {
-                                                tag,
-                                                endpoint,
-                                                honor_label,
-                                            }) = &endpoint_info
-                                            {
-                                                match (honor_label, metric.tag_value(tag)) {
-                                                    (false, Some(old_endpoint)) => {
-                                                        metric.insert_tag(
-                                                            format!("exported_{}", tag),
-                                                            old_endpoint,
-                                                        );
-                                                        metric.insert_tag(
-                                                            tag.clone(),
-                                                            endpoint.clone(),
-                                                        );
-                                                    }
-                                                    (true, Some(_)) => {}
-                                                    (_, None) => {
-                                                        metric.insert_tag(
-                                                            tag.clone(),
-                                                            endpoint.clone(),
-                                                        );
-                                                    }
+                    if let Some(tag) = &endpoint_tag {
+                        tags.entry(tag.clone()).or_insert_with(|| url.to_string());
+                    }
+                    let tags = Arc::new(tags);
+
+                    let start = Instant::now();
+                    client
+                        .send(request)
+                        .map_err(crate::Error::from)
+                        .and_then(|response| async move {
+                            let (header, body) = response.into_parts();
+                            let body = hyper::body::to_bytes(body).await?;
+                            emit!(&BytesReceived {
+                                byte_size: body.len(),
+                                protocol: "http"
+                            });
+                            Ok((header, body))
+                        })
+                        .into_stream()
+                        .filter_map(move |response| {
+                            let tags = Arc::clone(&tags);
+
+                            ready(match response {
+                                Ok((header, body)) if header.status == hyper::StatusCode::OK => {
+                                    emit!(&PrometheusRequestCompleted {
+                                        start,
+                                        end: Instant::now()
+                                    });
+
+                                    let body = String::from_utf8_lossy(&body);
+
+                                    match parser::parse_text(&body) {
+                                        Ok(events) => {
+                                            emit!(&PrometheusEventsReceived {
+                                                byte_size: events.size_of(),
+                                                count: events.len(),
+                                                uri: url.clone()
+                                            });
+                                            Some(stream::iter(events).map(move |mut event| {
+                                                let metric = event.as_mut_metric();
+                                                for (tag, value) in tags.iter() {
+                                                    insert_target_tag(
+                                                        metric,
+                                                        tag,
+                                                        value,
+                                                        honor_labels,
+                                                    );
                                                 }
+                                                event
+                                            }))
+                                        }
+                                        Err(error) => {
+                                            if url.path() == "/" {
+                                                // https://github.com/vectordotdev/vector/pull/3801#issuecomment-700723178
+                                                warn!(
+                                                    message = PARSE_ERROR_NO_PATH,
+                                                    endpoint = %url,
+                                                );
                                             }
-                                            event
-                                        }))
-                                    }
-                                    Err(error) => {
-                                        if url.path() == "/" {
-                                            // https://github.com/vectordotdev/vector/pull/3801#issuecomment-700723178
-                                            warn!(
-                                                message = PARSE_ERROR_NO_PATH,
-                                                endpoint = %url,
-                                            );
+                                            emit!(&PrometheusParseError {
+                                                error,
+                                                url: url.clone(),
+                                                body,
+                                            });
+                                            None
                                         }
-                                        emit!(&PrometheusParseError {
-                                            error,
-                                            url: url.clone(),
-                                            body,
-                                        });
-                                        None
                                     }
                                 }
-                            }
-                            Ok((header, _)) => {
-                                if header.status == hyper::StatusCode::NOT_FOUND
-                                    && url.path() == "/"
-                                {
-                                    // https://github.com/vectordotdev/vector/pull/3801#issuecomment-700723178
-                                    warn!(
-                                        message = NOT_FOUND_NO_PATH,
-                                        endpoint = %url,
-                                    );
+                                Ok((header, _)) => {
+                                    if header.status == hyper::StatusCode::NOT_FOUND
+                                        && url.path() == "/"
+                                    {
+                                        // https://github.com/vectordotdev/vector/pull/3801#issuecomment-700723178
+                                        warn!(
+                                            message = NOT_FOUND_NO_PATH,
+                                            endpoint = %url,
+                                        );
+                                    }
+                                    emit!(&PrometheusHttpResponseError {
+                                        code: header.status,
+                                        url: url.clone(),
+                                    });
+                                    None
                                 }
-                                emit!(&PrometheusHttpResponseError {
-                                    code: header.status,
-                                    url: url.clone(),
-                                });
-                                None
-                            }
-                            Err(error) => {
-                                emit!(&PrometheusHttpError {
-                                    error,
-                                    url: url.clone(),
-                                });
-                                None
-                            }
+                                Err(error) => {
+                                    emit!(&PrometheusHttpError {
+                                        error,
+                                        url: url.clone(),
+                                    });
+                                    None
+                                }
+                            })
                         })
-                    })
-                    .flatten()
-            })
+                        .flatten()
+                },
+            )
             .flatten()
             .boxed();
 
@@ -433,6 +435,9 @@ mod test {
             honor_labels: true,
             auth: None,
             tls: None,
+            file_sd: None,
+            kubernetes_sd: None,
+            relabel_configs: Vec::new(),
         };
 
         let (tx, rx) = SourceSender::new_test();
@@ -486,6 +491,9 @@ mod test {
             honor_labels: false,
             auth: None,
             tls: None,
+            file_sd: None,
+            kubernetes_sd: None,
+            relabel_configs: Vec::new(),
         };
 
         let (tx, rx) = SourceSender::new_test();
@@ -529,6 +537,81 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_prometheus_file_sd() {
+        let in_addr = next_addr();
+
+        let dummy_endpoint = warp::path!("stats").map(|| {
+            r#"
+                promhttp_metric_handler_requests_total{job="exported", code="200"} 100 1612411516789
+            "#
+        });
+
+        tokio::spawn(warp::serve(dummy_endpoint).run(in_addr));
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("targets.json"),
+            format!(
+                r#"[{{"targets": ["{}"], "labels": {{"job": "api", "__metrics_path__": "/stats"}}}}]"#,
+                in_addr
+            ),
+        )
+        .unwrap();
+
+        let config: PrometheusScrapeConfig = toml::from_str(&format!(
+            r#"
+            scrape_interval_secs = 1
+            instance_tag = "instance"
+
+            [file_sd]
+            files = ["{}/*.json"]
+
+            [[relabel_configs]]
+            source_labels = ["job"]
+            target_label = "team"
+            regex = "api|web"
+            replacement = "platform"
+            "#,
+            dir.path().display()
+        ))
+        .unwrap();
+
+        let (tx, rx) = SourceSender::new_test();
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+
+        tokio::spawn(source);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let events = test_util::collect_ready(rx).await;
+        assert!(!events.is_empty());
+
+        for event in events {
+            let metric = event.into_metric();
+            assert_eq!(metric.tag_value("job"), Some(String::from("api")));
+            assert_eq!(
+                metric.tag_value("exported_job"),
+                Some(String::from("exported"))
+            );
+            assert_eq!(metric.tag_value("team"), Some(String::from("platform")));
+            assert_eq!(metric.tag_value("instance"), Some(in_addr.to_string()));
+            assert_eq!(metric.tag_value("__metrics_path__"), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_requires_targets() {
+        let config: PrometheusScrapeConfig = toml::from_str("scrape_interval_secs = 1").unwrap();
+        let (tx, _rx) = SourceSender::new_test();
+        assert!(config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_prometheus_routing() {
         let in_addr = next_addr();
@@ -586,6 +669,9 @@ mod test {
                 scrape_interval_secs: 1,
                 tls: None,
                 auth: None,
+                file_sd: None,
+                kubernetes_sd: None,
+                relabel_configs: Vec::new(),
             },
         );
         config.add_sink(
@@ -672,6 +758,9 @@ mod integration_tests {
             honor_labels: false,
             auth: None,
             tls: None,
+            file_sd: None,
+            kubernetes_sd: None,
+            relabel_configs: Vec::new(),
         };
 
         let (tx, rx) = SourceSender::new_test();
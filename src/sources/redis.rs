@@ -0,0 +1,625 @@
+//! The `redis` source reads the entries of a Redis stream as a member of a consumer group.
+//! Entries are acknowledged once their events are delivered. The entries left pending, as when
+//! Vector stops before their events are delivered, are read again on start, and the ones left
+//! pending by other consumers are claimed once they are idle for long enough.
+
+use std::{collections::HashMap, time::Duration};
+
+use bytes::{Bytes, BytesMut};
+use chrono::{TimeZone, Utc};
+use redis::{aio::ConnectionManager, RedisResult};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tokio_util::codec::Decoder as _;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{
+        self,
+        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+    },
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event},
+    internal_events::{
+        BytesReceived, RedisAckError, RedisEventsReceived, RedisReceiveEventError,
+        StreamClosedError,
+    },
+    serde::{bool_or_struct, default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    sources::util::AcknowledgementTimeout,
+    SourceSender,
+};
+
+/// How long the reads wait for new entries, in milliseconds.
+const BLOCK_MILLIS: u64 = 1000;
+
+/// How long to wait before reading again after a failed read.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct RedisSourceConfig {
+    #[serde(default = "default_url")]
+    #[derivative(Default(value = "default_url()"))]
+    url: String,
+    #[serde(default = "default_key")]
+    #[derivative(Default(value = "default_key()"))]
+    key: String,
+    #[serde(default = "default_group")]
+    #[derivative(Default(value = "default_group()"))]
+    group: String,
+    #[serde(default = "default_consumer")]
+    #[derivative(Default(value = "default_consumer()"))]
+    consumer: String,
+    /// The ID of the entry after which the group starts reading when it is created, `$` reading
+    /// only the entries added afterwards.
+    #[serde(default = "default_start_id")]
+    #[derivative(Default(value = "default_start_id()"))]
+    start_id: String,
+    /// The number of entries read at once.
+    #[serde(default = "default_batch_size")]
+    #[derivative(Default(value = "default_batch_size()"))]
+    batch_size: usize,
+    /// The entries pending for longer than this are claimed, none if not set.
+    #[serde(default)]
+    claim_min_idle_secs: Option<u64>,
+    #[serde(default = "default_field")]
+    #[derivative(Default(value = "default_field()"))]
+    field: String,
+    #[serde(default = "default_id_key")]
+    #[derivative(Default(value = "default_id_key()"))]
+    id_key: String,
+    #[serde(default = "default_framing_message_based")]
+    #[derivative(Default(value = "default_framing_message_based()"))]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    #[derivative(Default(value = "default_decoding()"))]
+    decoding: DeserializerConfig,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_url() -> String {
+    "redis://127.0.0.1:6379/0".into()
+}
+
+fn default_key() -> String {
+    "vector".into()
+}
+
+fn default_group() -> String {
+    "vector".into()
+}
+
+fn default_consumer() -> String {
+    "vector".into()
+}
+
+fn default_start_id() -> String {
+    "$".into()
+}
+
+const fn default_batch_size() -> usize {
+    100
+}
+
+fn default_field() -> String {
+    "message".into()
+}
+
+fn default_id_key() -> String {
+    "id".into()
+}
+
+inventory::submit! {
+    SourceDescription::new::<RedisSourceConfig>("redis")
+}
+
+impl_generate_config_from_default!(RedisSourceConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "redis")]
+impl SourceConfig for RedisSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.key.is_empty() {
+            return Err("`key` cannot be empty.".into());
+        }
+        let client = redis::Client::open(self.url.as_str())?;
+        // The blocking reads hold their connection, so the entries are acknowledged on another.
+        let mut reads = client.get_tokio_connection_manager().await?;
+        let acks = client.get_tokio_connection_manager().await?;
+        create_group(&mut reads, &self.key, &self.group, &self.start_id).await?;
+
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build()?;
+        let source = RedisSource {
+            reads,
+            acker: EntryAcker {
+                conn: acks,
+                key: self.key.clone(),
+                group: self.group.clone(),
+            },
+            consumer: self.consumer.clone(),
+            batch_size: self.batch_size,
+            claim_min_idle: self.claim_min_idle_secs.map(Duration::from_secs),
+            field: self.field.clone(),
+            id_key: self.id_key.clone(),
+            decoder,
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+            acknowledgement_timeout: cx.acknowledgement_timeout,
+        };
+
+        Ok(Box::pin(source.run(cx.shutdown, cx.out)))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "redis"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+
+    fn can_redeliver(&self) -> bool {
+        true
+    }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        true
+    }
+}
+
+async fn create_group(
+    conn: &mut ConnectionManager,
+    key: &str,
+    group: &str,
+    start_id: &str,
+) -> RedisResult<()> {
+    let result = redis::cmd("XGROUP")
+        .arg("CREATE")
+        .arg(key)
+        .arg(group)
+        .arg(start_id)
+        .arg("MKSTREAM")
+        .query_async(conn)
+        .await;
+    match result {
+        // The group was created before, possibly by another consumer.
+        Err(error) if error.code() == Some("BUSYGROUP") => Ok(()),
+        result => result,
+    }
+}
+
+/// The ID and the fields of an entry, which are missing if the entry was deleted while pending.
+type Entry = (String, Option<HashMap<String, Vec<u8>>>);
+
+/// What is read next.
+enum Position {
+    /// The entries delivered to this consumer before it was started, after the given ID.
+    Pending(String),
+    /// The idle entries pending for any consumer, from the given cursor.
+    Claim(String),
+    /// The entries never delivered to any consumer of the group.
+    New,
+}
+
+struct RedisSource {
+    reads: ConnectionManager,
+    acker: EntryAcker,
+    consumer: String,
+    batch_size: usize,
+    claim_min_idle: Option<Duration>,
+    field: String,
+    id_key: String,
+    decoder: codecs::Decoder,
+    acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
+}
+
+impl RedisSource {
+    async fn run(self, mut shutdown: ShutdownSignal, mut out: SourceSender) -> Result<(), ()> {
+        let mut position = Position::Pending("0".into());
+        let mut claim_at = Instant::now();
+
+        loop {
+            if let (Position::New, Some(min_idle)) = (&position, self.claim_min_idle) {
+                if claim_at <= Instant::now() {
+                    position = Position::Claim("0-0".into());
+                    claim_at = Instant::now() + min_idle;
+                }
+            }
+
+            let read = async {
+                match &position {
+                    Position::Pending(id) => self.read(id).await.map(|entries| {
+                        let next = match entries.last() {
+                            Some((id, _)) => Position::Pending(id.clone()),
+                            None => Position::New,
+                        };
+                        (next, entries)
+                    }),
+                    Position::Claim(cursor) => {
+                        let min_idle = self.claim_min_idle.unwrap_or_default();
+                        self.claim(cursor, min_idle).await.map(|(cursor, entries)| {
+                            let next = if cursor == "0-0" {
+                                Position::New
+                            } else {
+                                Position::Claim(cursor)
+                            };
+                            (next, entries)
+                        })
+                    }
+                    Position::New => self.read(">").await.map(|entries| (Position::New, entries)),
+                }
+            };
+            let result = tokio::select! {
+                result = read => result,
+                _ = &mut shutdown => break,
+            };
+
+            match result {
+                Ok((next, entries)) => {
+                    position = next;
+                    for entry in entries {
+                        self.handle_entry(entry, &mut out).await?;
+                    }
+                }
+                Err(error) => {
+                    emit!(&RedisReceiveEventError { error });
+                    tokio::select! {
+                        _ = tokio::time::sleep(RETRY_DELAY) => {},
+                        _ = &mut shutdown => break,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn read(&self, id: &str) -> RedisResult<Vec<Entry>> {
+        let mut conn = self.reads.clone();
+        let reply: Option<Vec<(String, Vec<Entry>)>> = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(&self.acker.group)
+            .arg(&self.consumer)
+            .arg("COUNT")
+            .arg(self.batch_size)
+            .arg("BLOCK")
+            .arg(BLOCK_MILLIS)
+            .arg("STREAMS")
+            .arg(&self.acker.key)
+            .arg(id)
+            .query_async(&mut conn)
+            .await?;
+        Ok(reply
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, entries)| entries)
+            .collect())
+    }
+
+    /// Claims the entries pending for longer than `min_idle`, returning the cursor to continue
+    /// from, `0-0` once all the pending entries were scanned.
+    async fn claim(&self, cursor: &str, min_idle: Duration) -> RedisResult<(String, Vec<Entry>)> {
+        let mut conn = self.reads.clone();
+        let reply: Vec<redis::Value> = redis::cmd("XAUTOCLAIM")
+            .arg(&self.acker.key)
+            .arg(&self.acker.group)
+            .arg(&self.consumer)
+            .arg(min_idle.as_millis() as u64)
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(self.batch_size)
+            .query_async(&mut conn)
+            .await?;
+        // Redis 7 replies with the IDs of the deleted entries as well, which are of no use.
+        let mut reply = reply.into_iter();
+        let cursor = redis::from_redis_value(&reply.next().unwrap_or(redis::Value::Nil))?;
+        let entries = redis::from_redis_value(&reply.next().unwrap_or(redis::Value::Nil))?;
+        Ok((cursor, entries))
+    }
+
+    async fn handle_entry(&self, (id, fields): Entry, out: &mut SourceSender) -> Result<(), ()> {
+        let events =
+            match decode_entry(self.decoder.clone(), &id, fields, &self.field, &self.id_key) {
+                Some(events) => events,
+                None => {
+                    // The entry won't become decodable by reading it again.
+                    self.acker.finalize(id, BatchStatus::Rejected).await;
+                    return Ok(());
+                }
+            };
+        let count = events.len();
+        emit!(&RedisEventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        if self.acknowledgements {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            let events = events
+                .into_iter()
+                .map(|event| event.with_batch_notifier(&batch));
+            match out.send_batch(events).await {
+                Ok(()) => {
+                    drop(batch);
+                    let acker = self.acker.clone();
+                    let timeout = self.acknowledgement_timeout;
+                    tokio::spawn(
+                        async move { acker.finalize(id, timeout.wait(receiver).await).await },
+                    );
+                    Ok(())
+                }
+                Err(error) => {
+                    emit!(&StreamClosedError { error, count });
+                    Err(())
+                }
+            }
+        } else {
+            match out.send_batch(events).await {
+                Ok(()) => {
+                    self.acker.finalize(id, BatchStatus::Delivered).await;
+                    Ok(())
+                }
+                Err(error) => {
+                    emit!(&StreamClosedError { error, count });
+                    Err(())
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct EntryAcker {
+    conn: ConnectionManager,
+    key: String,
+    group: String,
+}
+
+impl EntryAcker {
+    /// Acknowledges an entry once its events were delivered, or rejected, as they would be
+    /// rejected again. Errored entries are left pending, to be read again once Vector is
+    /// restarted or they are claimed.
+    async fn finalize(&self, id: String, status: BatchStatus) {
+        if status == BatchStatus::Errored {
+            return;
+        }
+        let mut conn = self.conn.clone();
+        let result: RedisResult<u64> = redis::cmd("XACK")
+            .arg(&self.key)
+            .arg(&self.group)
+            .arg(&id)
+            .query_async(&mut conn)
+            .await;
+        if let Err(error) = result {
+            emit!(&RedisAckError { error, status });
+        }
+    }
+}
+
+/// Decodes the payload field of an entry into log events annotated with its ID. Returns `None` if
+/// the entry has no payload, or it can't be decoded.
+fn decode_entry(
+    mut decoder: codecs::Decoder,
+    id: &str,
+    fields: Option<HashMap<String, Vec<u8>>>,
+    field: &str,
+    id_key: &str,
+) -> Option<Vec<Event>> {
+    let payload = fields?.remove(field)?;
+    emit!(&BytesReceived {
+        byte_size: payload.len(),
+        protocol: "redis",
+    });
+
+    let mut events = Vec::new();
+    let mut bytes = BytesMut::from(payload.as_slice());
+    loop {
+        match decoder.decode_eof(&mut bytes) {
+            Ok(Some((next, _))) => events.extend(next),
+            Ok(None) => break,
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further handling
+                // is needed here.
+                if !error.can_continue() {
+                    return None;
+                }
+            }
+        }
+    }
+
+    // The IDs start with the time the entries were added at, in milliseconds.
+    let timestamp = id
+        .split('-')
+        .next()
+        .and_then(|millis| millis.parse().ok())
+        .and_then(|millis| Utc.timestamp_millis_opt(millis).latest())
+        .unwrap_or_else(Utc::now);
+
+    for event in &mut events {
+        if let Event::Log(log) = event {
+            log.insert(log_schema().source_type_key(), Bytes::from("redis"));
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.insert(id_key, id.to_owned());
+        }
+    }
+
+    Some(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisSourceConfig>();
+    }
+
+    fn decoder() -> codecs::Decoder {
+        DecodingConfig::new(default_framing_message_based(), default_decoding())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn decodes_entries() {
+        let mut fields = HashMap::new();
+        fields.insert("message".to_owned(), b"hello".to_vec());
+        fields.insert("other".to_owned(), b"ignored".to_vec());
+
+        let events =
+            decode_entry(decoder(), "1656581400123-0", Some(fields), "message", "id").unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "hello".into());
+        assert_eq!(log["id"], "1656581400123-0".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp_millis(1656581400123).into()
+        );
+        assert!(!log.contains("other"));
+    }
+
+    #[test]
+    fn rejects_entries_without_payload() {
+        let mut fields = HashMap::new();
+        fields.insert("other".to_owned(), b"hello".to_vec());
+
+        assert!(decode_entry(decoder(), "1-0", Some(fields), "message", "id").is_none());
+        assert!(decode_entry(decoder(), "1-0", None, "message", "id").is_none());
+    }
+}
+
+#[cfg(all(test, feature = "redis-integration-tests"))]
+mod integration_tests {
+    use redis::AsyncCommands;
+
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{collect_n, random_string, trace_init},
+    };
+
+    fn redis_server() -> String {
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379/0".to_owned())
+    }
+
+    fn config(key: &str) -> RedisSourceConfig {
+        RedisSourceConfig {
+            url: redis_server(),
+            key: key.to_owned(),
+            start_id: "0".into(),
+            ..Default::default()
+        }
+    }
+
+    async fn add_entries(conn: &mut ConnectionManager, key: &str, count: usize) {
+        for i in 0..count {
+            let _: String = conn
+                .xadd(key, "*", &[("message", format!("line {}", i))])
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn pending_count(conn: &mut ConnectionManager, key: &str) -> u64 {
+        let summary: (
+            u64,
+            Option<String>,
+            Option<String>,
+            Option<Vec<(String, String)>>,
+        ) = redis::cmd("XPENDING")
+            .arg(key)
+            .arg("vector")
+            .query_async(conn)
+            .await
+            .unwrap();
+        summary.0
+    }
+
+    #[tokio::test]
+    async fn reads_and_acknowledges_entries() {
+        trace_init();
+        let key = format!("test-{}", random_string(10));
+        let config = config(&key);
+        let mut conn = redis::Client::open(redis_server())
+            .unwrap()
+            .get_tokio_connection_manager()
+            .await
+            .unwrap();
+        add_entries(&mut conn, &key, 10).await;
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        let events = collect_n(rx, 10).await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "line 0".into()
+        );
+        assert_eq!(
+            events[9].as_log()[log_schema().message_key()],
+            "line 9".into()
+        );
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(pending_count(&mut conn, &key).await, 0);
+    }
+
+    #[tokio::test]
+    async fn reads_pending_entries_on_start() {
+        trace_init();
+        let key = format!("test-{}", random_string(10));
+        let config = config(&key);
+        let mut conn = redis::Client::open(redis_server())
+            .unwrap()
+            .get_tokio_connection_manager()
+            .await
+            .unwrap();
+        create_group(&mut conn, &key, "vector", "0").await.unwrap();
+        add_entries(&mut conn, &key, 5).await;
+
+        // Deliver the entries to the consumer without acknowledging them, as if Vector stopped.
+        let _: redis::Value = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg("vector")
+            .arg("vector")
+            .arg("STREAMS")
+            .arg(&key)
+            .arg(">")
+            .query_async(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(pending_count(&mut conn, &key).await, 5);
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        let events = collect_n(rx, 5).await;
+        assert_eq!(
+            events[0].as_log()[log_schema().message_key()],
+            "line 0".into()
+        );
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(pending_count(&mut conn, &key).await, 0);
+    }
+}
@@ -0,0 +1,231 @@
+//! The subset of the Basic Encoding Rules needed to read SNMP messages, and to write the
+//! responses to informs.
+
+use snafu::Snafu;
+
+pub const INTEGER: u8 = 0x02;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+pub const IP_ADDRESS: u8 = 0x40;
+pub const COUNTER32: u8 = 0x41;
+pub const GAUGE32: u8 = 0x42;
+pub const TIME_TICKS: u8 = 0x43;
+pub const OPAQUE: u8 = 0x44;
+pub const COUNTER64: u8 = 0x46;
+pub const NO_SUCH_OBJECT: u8 = 0x80;
+pub const NO_SUCH_INSTANCE: u8 = 0x81;
+pub const END_OF_MIB_VIEW: u8 = 0x82;
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum BerError {
+    #[snafu(display("Unexpected end of data"))]
+    Truncated,
+    #[snafu(display("Expected tag {:#04x}, found {:#04x}", expected, found))]
+    UnexpectedTag { expected: u8, found: u8 },
+    #[snafu(display("Invalid length"))]
+    InvalidLength,
+    #[snafu(display("Integer out of range"))]
+    IntegerOverflow,
+}
+
+/// Reads consecutive TLVs from a buffer.
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Reads the next TLV, returning its tag and contents.
+    pub fn read(&mut self) -> Result<(u8, &'a [u8]), BerError> {
+        let (&tag, rest) = self.data.split_first().ok_or(BerError::Truncated)?;
+        let (&first, mut rest) = rest.split_first().ok_or(BerError::Truncated)?;
+
+        let length = if first & 0x80 == 0 {
+            first as usize
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return Err(BerError::InvalidLength);
+            }
+            let (bytes, remainder) = rest.split_at(count);
+            rest = remainder;
+            bytes
+                .iter()
+                .fold(0usize, |length, &byte| (length << 8) | byte as usize)
+        };
+
+        if rest.len() < length {
+            return Err(BerError::Truncated);
+        }
+        let (contents, rest) = rest.split_at(length);
+        self.data = rest;
+        Ok((tag, contents))
+    }
+
+    /// Reads the next TLV, which must have the given tag.
+    pub fn expect(&mut self, expected: u8) -> Result<&'a [u8], BerError> {
+        match self.read()? {
+            (tag, contents) if tag == expected => Ok(contents),
+            (found, _) => Err(BerError::UnexpectedTag { expected, found }),
+        }
+    }
+
+    pub fn integer(&mut self) -> Result<i64, BerError> {
+        decode_integer(self.expect(INTEGER)?)
+    }
+
+    pub fn octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        self.expect(OCTET_STRING)
+    }
+
+    pub fn object_identifier(&mut self) -> Result<Vec<u32>, BerError> {
+        decode_object_identifier(self.expect(OBJECT_IDENTIFIER)?)
+    }
+
+    /// Reads a constructed TLV with the given tag, returning a reader over its contents.
+    pub fn constructed(&mut self, tag: u8) -> Result<Reader<'a>, BerError> {
+        self.expect(tag).map(Reader::new)
+    }
+}
+
+pub fn decode_integer(contents: &[u8]) -> Result<i64, BerError> {
+    if contents.is_empty() || contents.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    let initial = if contents[0] & 0x80 != 0 { -1i64 } else { 0 };
+    Ok(contents
+        .iter()
+        .fold(initial, |value, &byte| (value << 8) | byte as i64))
+}
+
+/// Decodes the unsigned integers of the SNMP application types, which may have a leading
+/// zero byte to keep them positive.
+pub fn decode_unsigned(contents: &[u8]) -> Result<u64, BerError> {
+    let contents = match contents {
+        [0, rest @ ..] => rest,
+        contents => contents,
+    };
+    if contents.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    Ok(contents
+        .iter()
+        .fold(0u64, |value, &byte| (value << 8) | byte as u64))
+}
+
+pub fn decode_object_identifier(contents: &[u8]) -> Result<Vec<u32>, BerError> {
+    let mut oid = Vec::with_capacity(contents.len() + 1);
+    let mut value = 0u32;
+    for (index, &byte) in contents.iter().enumerate() {
+        value = value.checked_mul(128).ok_or(BerError::IntegerOverflow)? | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            if oid.is_empty() {
+                // The first two arcs are packed into the first sub-identifier.
+                let first = (value / 40).min(2);
+                oid.push(first);
+                oid.push(value - first * 40);
+            } else {
+                oid.push(value);
+            }
+            value = 0;
+        } else if index == contents.len() - 1 {
+            return Err(BerError::Truncated);
+        }
+    }
+    Ok(oid)
+}
+
+/// Appends a TLV to `out`.
+pub fn write(out: &mut Vec<u8>, tag: u8, contents: &[u8]) {
+    out.push(tag);
+    let length = contents.len();
+    if length < 0x80 {
+        out.push(length as u8);
+    } else {
+        let bytes = (length as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|&&byte| byte == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(contents);
+}
+
+pub fn write_integer(out: &mut Vec<u8>, value: i64) {
+    let bytes = value.to_be_bytes();
+    // Strip redundant leading bytes, keeping the sign bit.
+    let mut skip = 0;
+    while skip < bytes.len() - 1 {
+        let (byte, next) = (bytes[skip], bytes[skip + 1]);
+        if (byte == 0 && next & 0x80 == 0) || (byte == 0xff && next & 0x80 != 0) {
+            skip += 1;
+        } else {
+            break;
+        }
+    }
+    write(out, INTEGER, &bytes[skip..]);
+}
+
+#[cfg(test)]
+pub fn write_object_identifier(out: &mut Vec<u8>, oid: &[u32]) {
+    let mut contents = Vec::new();
+    let mut arcs = oid.iter().copied();
+    let first = arcs.next().unwrap_or(0) * 40 + arcs.next().unwrap_or(0);
+    for arc in std::iter::once(first).chain(arcs) {
+        let mut bytes = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            bytes.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        contents.extend(bytes.iter().rev());
+    }
+    write(out, OBJECT_IDENTIFIER, &contents);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_roundtrip() {
+        for value in [0, 1, 127, 128, 255, 256, -1, -128, -129, i32::MAX as i64] {
+            let mut out = Vec::new();
+            write_integer(&mut out, value);
+            assert_eq!(Reader::new(&out).integer(), Ok(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn object_identifiers_roundtrip() {
+        let oid = vec![1, 3, 6, 1, 4, 1, 8072, 2, 3, 0, 1];
+        let mut out = Vec::new();
+        write_object_identifier(&mut out, &oid);
+        assert_eq!(Reader::new(&out).object_identifier(), Ok(oid));
+    }
+
+    #[test]
+    fn long_lengths() {
+        let contents = vec![0x61; 300];
+        let mut out = Vec::new();
+        write(&mut out, OCTET_STRING, &contents);
+        assert_eq!(&out[..4], &[OCTET_STRING, 0x82, 0x01, 0x2c]);
+        assert_eq!(Reader::new(&out).octet_string(), Ok(contents.as_slice()));
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        assert_eq!(
+            Reader::new(&[OCTET_STRING, 0x05, 0x61]).read(),
+            Err(BerError::Truncated)
+        );
+    }
+}
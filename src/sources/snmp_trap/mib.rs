@@ -0,0 +1,502 @@
+//! A lenient reader for SMIv1/SMIv2 MIB modules. It only extracts what is needed to name the
+//! object identifiers of traps and their varbinds: the OID assignments and the enumerations of
+//! integer syntaxes, including the ones of textual conventions.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use snafu::{ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+pub enum MibError {
+    #[snafu(display("Could not read MIB file {:?}: {}", path, source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Macros whose invocations end with an OID assignment.
+const OID_MACROS: &[&str] = &[
+    "OBJECT-TYPE",
+    "NOTIFICATION-TYPE",
+    "TRAP-TYPE",
+    "MODULE-IDENTITY",
+    "OBJECT-IDENTITY",
+    "OBJECT-GROUP",
+    "NOTIFICATION-GROUP",
+    "MODULE-COMPLIANCE",
+    "AGENT-CAPABILITIES",
+];
+
+/// Nodes defined by SNMPv2-SMI and RFC1155-SMI, so that MIBs can be loaded without them.
+const WELL_KNOWN: &[(&str, &[u32])] = &[
+    ("ccitt", &[0]),
+    ("zeroDotZero", &[0, 0]),
+    ("iso", &[1]),
+    ("org", &[1, 3]),
+    ("dod", &[1, 3, 6]),
+    ("internet", &[1, 3, 6, 1]),
+    ("directory", &[1, 3, 6, 1, 1]),
+    ("mgmt", &[1, 3, 6, 1, 2]),
+    ("mib-2", &[1, 3, 6, 1, 2, 1]),
+    ("transmission", &[1, 3, 6, 1, 2, 1, 10]),
+    ("experimental", &[1, 3, 6, 1, 3]),
+    ("private", &[1, 3, 6, 1, 4]),
+    ("enterprises", &[1, 3, 6, 1, 4, 1]),
+    ("security", &[1, 3, 6, 1, 5]),
+    ("snmpV2", &[1, 3, 6, 1, 6]),
+    ("snmpDomains", &[1, 3, 6, 1, 6, 1]),
+    ("snmpProxys", &[1, 3, 6, 1, 6, 2]),
+    ("snmpModules", &[1, 3, 6, 1, 6, 3]),
+    ("joint-iso-ccitt", &[2]),
+];
+
+#[derive(Debug, PartialEq)]
+struct Node {
+    module: String,
+    name: String,
+    enums: Option<BTreeMap<i64, String>>,
+}
+
+/// An assignment whose parent may only be defined in a module loaded later.
+struct Assignment {
+    module: String,
+    name: String,
+    parent: String,
+    arcs: Vec<u32>,
+    enums: Option<BTreeMap<i64, String>>,
+    /// The type of the object, which may be a textual convention with enumerations.
+    syntax: Option<String>,
+}
+
+/// Names resolved for an OID.
+#[derive(Debug, PartialEq)]
+pub struct Resolved<'a> {
+    /// The name of the closest defined node, such as `IF-MIB::ifOperStatus`, followed by the
+    /// remaining arcs, such as `.3` for the instance of a table column.
+    pub name: String,
+    /// The enumerations of the node's syntax, if any.
+    pub enums: Option<&'a BTreeMap<i64, String>>,
+}
+
+#[derive(Debug, Default)]
+pub struct Mib {
+    nodes: BTreeMap<Vec<u32>, Node>,
+}
+
+impl Mib {
+    /// Loads the MIB modules in the given files, and in the files of the given directories.
+    pub fn load(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut sources = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let entries = fs::read_dir(path).context(ReadSnafu { path })?;
+                let mut files = entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_file())
+                    .collect::<Vec<_>>();
+                files.sort();
+                for file in files {
+                    sources.push(read(&file)?);
+                }
+            } else {
+                sources.push(read(path)?);
+            }
+        }
+        Ok(Self::parse(sources.iter().map(String::as_str)))
+    }
+
+    pub fn parse<'a>(sources: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut assignments = Vec::new();
+        let mut textual_conventions = HashMap::new();
+        for source in sources {
+            parse_module(source, &mut assignments, &mut textual_conventions);
+        }
+
+        let mut oids = HashMap::new();
+        let mut nodes = BTreeMap::new();
+        for (name, oid) in WELL_KNOWN {
+            oids.insert(name.to_string(), oid.to_vec());
+            nodes.insert(
+                oid.to_vec(),
+                Node {
+                    module: "SNMPv2-SMI".to_owned(),
+                    name: name.to_string(),
+                    enums: None,
+                },
+            );
+        }
+
+        // Resolve assignments until no more parents can be found, as modules may be loaded in
+        // any order.
+        loop {
+            let before = assignments.len();
+            assignments.retain(
+                |assignment: &Assignment| match oids.get(&assignment.parent) {
+                    Some(parent) => {
+                        let mut oid = parent.clone();
+                        oid.extend(&assignment.arcs);
+                        oids.insert(assignment.name.clone(), oid.clone());
+                        nodes.insert(
+                            oid,
+                            Node {
+                                module: assignment.module.clone(),
+                                name: assignment.name.clone(),
+                                enums: assignment.enums.clone().or_else(|| {
+                                    assignment
+                                        .syntax
+                                        .as_ref()
+                                        .and_then(|syntax| textual_conventions.get(syntax).cloned())
+                                }),
+                            },
+                        );
+                        false
+                    }
+                    None => true,
+                },
+            );
+            if assignments.is_empty() || assignments.len() == before {
+                break;
+            }
+        }
+
+        for assignment in assignments {
+            debug!(
+                message = "Skipping MIB node with unknown parent.",
+                module = %assignment.module,
+                name = %assignment.name,
+                parent = %assignment.parent,
+            );
+        }
+
+        Self { nodes }
+    }
+
+    /// Names an OID after its closest defined ancestor.
+    pub fn resolve(&self, oid: &[u32]) -> Option<Resolved<'_>> {
+        (1..=oid.len()).rev().find_map(|len| {
+            self.nodes.get(&oid[..len]).map(|node| {
+                let mut name = format!("{}::{}", node.module, node.name);
+                for arc in &oid[len..] {
+                    name.push('.');
+                    name.push_str(&arc.to_string());
+                }
+                Resolved {
+                    name,
+                    enums: node.enums.as_ref(),
+                }
+            })
+        })
+    }
+}
+
+fn read(path: &Path) -> Result<String, MibError> {
+    let bytes = fs::read(path).context(ReadSnafu { path })?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Splits a module into tokens, dropping comments and quoted strings.
+fn tokenize(source: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("--") {
+            // Comments end at the end of the line, or at the next `--`.
+            let newline = comment.find('\n').unwrap_or(comment.len());
+            let end = match comment[..newline].find("--") {
+                Some(index) => index + 2,
+                None => newline,
+            };
+            rest = &comment[end..];
+        } else if let Some(quoted) = rest.strip_prefix('"') {
+            rest = quoted.find('"').map_or("", |end| &quoted[end + 1..]);
+        } else if rest.starts_with("::=") {
+            tokens.push(&rest[..3]);
+            rest = &rest[3..];
+        } else if rest.starts_with(|c: char| "{}(),;|".contains(c)) {
+            tokens.push(&rest[..1]);
+            rest = &rest[1..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "{}(),;|\"".contains(c) || c == ':')
+                .unwrap_or(rest.len());
+            // Stray colons outside of `::=`, such as in ranges, are skipped.
+            let end = end.max(1);
+            tokens.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+fn parse_module(
+    source: &str,
+    assignments: &mut Vec<Assignment>,
+    textual_conventions: &mut HashMap<String, BTreeMap<i64, String>>,
+) {
+    let tokens = tokenize(source);
+    let module = match tokens.iter().position(|token| *token == "DEFINITIONS") {
+        Some(index) if index > 0 => tokens[index - 1].to_owned(),
+        _ => return,
+    };
+
+    let mut index = 0;
+    while index + 1 < tokens.len() {
+        let (name, next) = (tokens[index], tokens[index + 1]);
+
+        // Imported names and macro definitions look like assignments, skip them.
+        if name == "IMPORTS" {
+            index = find(&tokens, index, ";").unwrap_or(tokens.len());
+            continue;
+        }
+        if next == "MACRO" {
+            index = find(&tokens, index, "END").unwrap_or(tokens.len());
+            continue;
+        }
+
+        if next == "::=" && tokens.get(index + 2) == Some(&"TEXTUAL-CONVENTION") {
+            let end = find(&tokens, index + 3, "SYNTAX").unwrap_or(tokens.len());
+            if let Some(enums) = enumerations(&tokens, end + 1) {
+                textual_conventions.insert(name.to_owned(), enums);
+            }
+            index = end;
+            continue;
+        }
+
+        let object_identifier = next == "OBJECT" && tokens.get(index + 2) == Some(&"IDENTIFIER");
+        if (object_identifier || OID_MACROS.contains(&next)) && starts_lowercase(name) {
+            let assign = match find(&tokens, index + 2, "::=") {
+                Some(assign) => assign,
+                None => break,
+            };
+            let body = &tokens[index + 2..assign];
+            let syntax = find(body, 0, "SYNTAX").map(|syntax| syntax + 1);
+
+            if let Some((end, parent, arcs)) = parse_oid(&tokens, assign + 1, &module, assignments)
+            {
+                assignments.push(Assignment {
+                    module: module.clone(),
+                    name: name.to_owned(),
+                    parent,
+                    arcs,
+                    enums: syntax.and_then(|syntax| enumerations(body, syntax)),
+                    syntax: syntax
+                        .and_then(|syntax| body.get(syntax))
+                        .map(|syntax| syntax.to_string()),
+                });
+                index = end;
+                continue;
+            }
+        }
+
+        index += 1;
+    }
+}
+
+fn starts_lowercase(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+}
+
+fn find(tokens: &[&str], from: usize, token: &str) -> Option<usize> {
+    tokens
+        .get(from..)?
+        .iter()
+        .position(|candidate| *candidate == token)
+        .map(|offset| from + offset)
+}
+
+/// Parses `INTEGER { up(1), down(2) }` at `index`.
+fn enumerations(tokens: &[&str], index: usize) -> Option<BTreeMap<i64, String>> {
+    if tokens.get(index) != Some(&"INTEGER") || tokens.get(index + 1) != Some(&"{") {
+        return None;
+    }
+    let mut enums = BTreeMap::new();
+    let mut index = index + 2;
+    while let [name, "(", value, ")", ..] = tokens.get(index..)? {
+        enums.insert(value.parse().ok()?, name.to_string());
+        index += 4;
+        match tokens.get(index) {
+            Some(&",") => index += 1,
+            _ => break,
+        }
+    }
+    Some(enums)
+}
+
+/// Parses an OID value such as `{ ifEntry 8 }` or `{ iso org(3) dod(6) 1 }` at `index`,
+/// returning the index after it along with the parent and the arcs below it. Named arcs, such
+/// as `org(3)`, are assigned along the way.
+fn parse_oid(
+    tokens: &[&str],
+    index: usize,
+    module: &str,
+    assignments: &mut Vec<Assignment>,
+) -> Option<(usize, String, Vec<u32>)> {
+    if tokens.get(index) != Some(&"{") {
+        return None;
+    }
+    let end = find(tokens, index, "}")?;
+    let components = &tokens[index + 1..end];
+    let (parent, mut rest) = components.split_first()?;
+    let mut parent = parent.to_string();
+    let mut arcs = Vec::new();
+
+    while let Some((component, remainder)) = rest.split_first() {
+        match remainder {
+            ["(", number, ")", remainder @ ..] => {
+                arcs.push(number.parse().ok()?);
+                assignments.push(Assignment {
+                    module: module.to_owned(),
+                    name: component.to_string(),
+                    parent: parent.clone(),
+                    arcs: std::mem::take(&mut arcs),
+                    enums: None,
+                    syntax: None,
+                });
+                parent = component.to_string();
+                rest = remainder;
+            }
+            _ => {
+                arcs.push(component.parse().ok()?);
+                rest = remainder;
+            }
+        }
+    }
+
+    Some((end + 1, parent, arcs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IF_MIB: &str = r#"
+IF-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Integer32, mib-2 FROM SNMPv2-SMI
+    TruthValue FROM SNMPv2-TC;
+
+ifMIB MODULE-IDENTITY
+    LAST-UPDATED "200006140000Z"
+    DESCRIPTION
+            "The MIB module to describe generic objects for network
+            interface sub-layers. -- not a comment"
+    ::= { mib-2 31 }
+
+interfaces   OBJECT IDENTIFIER ::= { mib-2 2 }
+
+ifTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF IfEntry
+    ::= { interfaces 2 }
+
+ifEntry OBJECT-TYPE
+    SYNTAX      IfEntry
+    INDEX   { ifIndex }
+    ::= { ifTable 1 }
+
+ifIndex OBJECT-TYPE
+    SYNTAX      InterfaceIndex -- (1..2147483647)
+    ::= { ifEntry 1 }
+
+ifOperStatus OBJECT-TYPE
+    SYNTAX  INTEGER {
+                up(1),        -- ready to pass packets
+                down(2),
+                testing(3)
+            }
+    MAX-ACCESS  read-only
+    ::= { ifEntry 8 }
+
+ifPromiscuousMode  OBJECT-TYPE
+    SYNTAX      TruthValue
+    ::= { ifXEntry 16 }
+
+linkDown NOTIFICATION-TYPE
+    OBJECTS { ifIndex, ifAdminStatus, ifOperStatus }
+    ::= { snmpTraps 3 }
+
+END
+"#;
+
+    const SNMPV2_MIB: &str = r#"
+SNMPv2-MIB DEFINITIONS ::= BEGIN
+snmpMIB MODULE-IDENTITY
+    ::= { snmpModules 1 }
+snmpMIBObjects OBJECT IDENTIFIER ::= { snmpMIB 1 }
+snmpTraps OBJECT IDENTIFIER ::= { snmpMIBObjects 5 }
+END
+"#;
+
+    const TC: &str = r#"
+SNMPv2-TC DEFINITIONS ::= BEGIN
+TruthValue ::= TEXTUAL-CONVENTION
+    STATUS       current
+    DESCRIPTION "Represents a boolean value."
+    SYNTAX       INTEGER { true(1), false(2) }
+END
+"#;
+
+    #[test]
+    fn resolves_across_modules() {
+        // Modules are loaded before the ones they import from.
+        let mib = Mib::parse(vec![IF_MIB, SNMPV2_MIB]);
+
+        let resolved = mib.resolve(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 8, 3]).unwrap();
+        assert_eq!(resolved.name, "IF-MIB::ifOperStatus.3");
+        let enums = resolved.enums.unwrap();
+        assert_eq!(enums[&1], "up");
+        assert_eq!(enums[&3], "testing");
+
+        assert_eq!(
+            mib.resolve(&[1, 3, 6, 1, 6, 3, 1, 1, 5, 3]).unwrap().name,
+            "IF-MIB::linkDown"
+        );
+        assert_eq!(
+            mib.resolve(&[1, 3, 6, 1, 2, 1, 31]).unwrap().name,
+            "IF-MIB::ifMIB"
+        );
+        assert_eq!(
+            mib.resolve(&[1, 3, 6, 1, 4, 1, 9]).unwrap().name,
+            "SNMPv2-SMI::enterprises.9"
+        );
+    }
+
+    #[test]
+    fn textual_convention_enums() {
+        let mib = Mib::parse(vec![
+            IF_MIB,
+            "VECTOR-MIB DEFINITIONS ::= BEGIN\nvectorEnabled OBJECT-TYPE\n    SYNTAX TruthValue\n    ::= { enterprises 32473 }\nEND",
+            TC,
+        ]);
+        let enums = mib.resolve(&[1, 3, 6, 1, 4, 1, 32473, 0]).unwrap().enums;
+        assert_eq!(enums.unwrap()[&2], "false");
+
+        // `ifXEntry` isn't defined, so `ifPromiscuousMode` can't be placed.
+        assert!(mib
+            .nodes
+            .values()
+            .all(|node| node.name != "ifPromiscuousMode"));
+    }
+
+    #[test]
+    fn named_arcs() {
+        let mib = Mib::parse(vec![
+            "TEST-MIB DEFINITIONS ::= BEGIN\nexample OBJECT IDENTIFIER ::= { enterprises vector(32473) 1 }\nEND",
+        ]);
+        assert_eq!(
+            mib.resolve(&[1, 3, 6, 1, 4, 1, 32473, 1, 2]).unwrap().name,
+            "TEST-MIB::example.2"
+        );
+        assert_eq!(
+            mib.resolve(&[1, 3, 6, 1, 4, 1, 32473, 2]).unwrap().name,
+            "TEST-MIB::vector.2"
+        );
+    }
+}
@@ -0,0 +1,748 @@
+//! The `snmp_trap` source listens for SNMPv2c and SNMPv3 notifications. Traps and informs are
+//! turned into one event each, with their variable bindings named after the objects of the
+//! configured MIB modules. SNMPv2c informs are acknowledged once they are parsed.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use tokio::net::UdpSocket;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
+    event::{Event, LogEvent, Value},
+    internal_events::{
+        BytesReceived, SnmpTrapEventsReceived, SnmpTrapParseError, SnmpTrapResponseError,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    udp, SourceSender,
+};
+
+mod ber;
+mod mib;
+mod usm;
+
+use self::{
+    ber::{BerError, Reader},
+    mib::Mib,
+    usm::{User, UserConfig, UsmError},
+};
+
+const VERSION_2C: i64 = 1;
+const VERSION_3: i64 = 3;
+
+const USM_SECURITY_MODEL: i64 = 3;
+const AUTH_FLAG: u8 = 0x01;
+const PRIV_FLAG: u8 = 0x02;
+
+const RESPONSE: u8 = 0xa2;
+const INFORM_REQUEST: u8 = 0xa6;
+const TRAP: u8 = 0xa7;
+
+/// `SNMPv2-MIB::sysUpTime.0`
+const SYS_UP_TIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+/// `SNMPv2-MIB::snmpTrapOID.0`
+const SNMP_TRAP_OID: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+
+/// Large enough for any notification, UDP datagrams can't exceed it.
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpTrapConfig {
+    #[serde(default = "default_address")]
+    #[derivative(Default(value = "default_address()"))]
+    address: SocketAddr,
+    /// The SNMPv2c communities traps are accepted from. Any community is accepted when empty.
+    #[serde(default)]
+    communities: Vec<String>,
+    /// MIB files, or directories of MIB files, used to name the OIDs of traps and varbinds.
+    #[serde(default)]
+    mib_paths: Vec<PathBuf>,
+    /// The SNMPv3 users traps are accepted from.
+    #[serde(default)]
+    users: Vec<UserConfig>,
+    host_key: Option<String>,
+    receive_buffer_bytes: Option<usize>,
+}
+
+fn default_address() -> SocketAddr {
+    SocketAddr::new([0, 0, 0, 0].into(), 162)
+}
+
+inventory::submit! {
+    SourceDescription::new::<SnmpTrapConfig>("snmp_trap")
+}
+
+impl_generate_config_from_default!(SnmpTrapConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_trap")]
+impl SourceConfig for SnmpTrapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let mib = Mib::load(&self.mib_paths)?;
+        let users = self
+            .users
+            .iter()
+            .map(|config| User::new(config).map(|user| (config.name.clone(), user)))
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        let socket = UdpSocket::bind(self.address).await?;
+        if let Some(receive_buffer_bytes) = self.receive_buffer_bytes {
+            if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+                warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+            }
+        }
+
+        let context = Context {
+            mib,
+            communities: self.communities.clone(),
+            users,
+            host_key: self
+                .host_key
+                .clone()
+                .unwrap_or_else(|| log_schema().host_key().to_owned()),
+        };
+
+        Ok(Box::pin(snmp_trap_source(
+            socket,
+            Arc::new(context),
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "snmp_trap"
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum TrapError {
+    #[snafu(display("Malformed message: {}", source))]
+    Malformed { source: BerError },
+    #[snafu(display("Unsupported SNMP version {}", version))]
+    UnsupportedVersion { version: i64 },
+    #[snafu(display("Unsupported security model {}", model))]
+    UnsupportedSecurityModel { model: i64 },
+    #[snafu(display("Community {:?} is not allowed", community))]
+    CommunityNotAllowed { community: String },
+    #[snafu(display("Unexpected PDU type {:#04x}", tag))]
+    UnexpectedPdu { tag: u8 },
+    #[snafu(display("Unknown value type {:#04x}", tag))]
+    UnknownValueType { tag: u8 },
+    #[snafu(display("{}", source))]
+    Security { source: UsmError },
+}
+
+impl From<BerError> for TrapError {
+    fn from(source: BerError) -> Self {
+        Self::Malformed { source }
+    }
+}
+
+impl From<UsmError> for TrapError {
+    fn from(source: UsmError) -> Self {
+        Self::Security { source }
+    }
+}
+
+struct Context {
+    mib: Mib,
+    communities: Vec<String>,
+    users: HashMap<String, User>,
+    host_key: String,
+}
+
+async fn snmp_trap_source(
+    socket: UdpSocket,
+    context: Arc<Context>,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    info!(message = "Listening.", address = ?socket.local_addr().ok());
+
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (byte_size, peer) = tokio::select! {
+            recv = socket.recv_from(&mut buf) => match recv {
+                Ok(recv) => recv,
+                Err(error) => {
+                    error!(message = "Error receiving SNMP message.", %error);
+                    return Err(());
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+
+        emit!(&BytesReceived {
+            byte_size,
+            protocol: "udp",
+        });
+
+        let message = match parse_message(&buf[..byte_size], &context.communities, &context.users) {
+            Ok(message) => message,
+            Err(error) => {
+                emit!(&SnmpTrapParseError {
+                    error: &error,
+                    peer
+                });
+                continue;
+            }
+        };
+
+        if let Some(response) = message.response() {
+            if let Err(error) = socket.send_to(&response, peer).await {
+                emit!(&SnmpTrapResponseError { error, peer });
+            }
+        }
+
+        let event = message.into_event(&context, peer);
+        emit!(&SnmpTrapEventsReceived {
+            byte_size: event.size_of(),
+            count: 1,
+        });
+
+        if let Err(error) = out.send_event(event).await {
+            emit!(&StreamClosedError { error, count: 1 });
+            return Ok(());
+        }
+    }
+}
+
+enum Security {
+    Community(String),
+    User {
+        name: String,
+        engine_id: Vec<u8>,
+        context_name: String,
+    },
+}
+
+struct Message {
+    security: Security,
+    pdu: Pdu,
+}
+
+struct Pdu {
+    tag: u8,
+    request_id: i64,
+    varbinds: Vec<VarBind>,
+    /// The encoded varbinds, echoed back in the response to an inform.
+    raw_varbinds: Vec<u8>,
+}
+
+struct VarBind {
+    oid: Vec<u32>,
+    value: SnmpValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum SnmpValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectIdentifier(Vec<u32>),
+    IpAddress(Ipv4Addr),
+    Counter32(u64),
+    Gauge32(u64),
+    TimeTicks(u64),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+fn parse_message(
+    data: &[u8],
+    communities: &[String],
+    users: &HashMap<String, User>,
+) -> Result<Message, TrapError> {
+    let mut message = Reader::new(data).constructed(ber::SEQUENCE)?;
+    match message.integer()? {
+        VERSION_2C => {
+            let community = String::from_utf8_lossy(message.octet_string()?).into_owned();
+            if !communities.is_empty() && !communities.contains(&community) {
+                return Err(TrapError::CommunityNotAllowed { community });
+            }
+            Ok(Message {
+                security: Security::Community(community),
+                pdu: parse_pdu(&mut message)?,
+            })
+        }
+        VERSION_3 => parse_v3_message(data, message, users),
+        version => Err(TrapError::UnsupportedVersion { version }),
+    }
+}
+
+/// Parses the rest of an SNMPv3 message, after its version. The whole message is needed to
+/// check its digest.
+fn parse_v3_message(
+    data: &[u8],
+    mut message: Reader<'_>,
+    users: &HashMap<String, User>,
+) -> Result<Message, TrapError> {
+    let mut global_data = message.constructed(ber::SEQUENCE)?;
+    let _id = global_data.integer()?;
+    let _max_size = global_data.integer()?;
+    let flags = global_data.octet_string()?.first().copied().unwrap_or(0);
+    let model = global_data.integer()?;
+    if model != USM_SECURITY_MODEL {
+        return Err(TrapError::UnsupportedSecurityModel { model });
+    }
+
+    let mut parameters = Reader::new(message.octet_string()?).constructed(ber::SEQUENCE)?;
+    let engine_id = parameters.octet_string()?;
+    let engine_boots = parameters.integer()?;
+    let engine_time = parameters.integer()?;
+    let name = String::from_utf8_lossy(parameters.octet_string()?).into_owned();
+    let auth_parameters = parameters.octet_string()?;
+    let priv_parameters = parameters.octet_string()?;
+
+    let user = users
+        .get(&name)
+        .ok_or_else(|| UsmError::UnknownUser { user: name.clone() })?;
+    user.check_security_level(flags & AUTH_FLAG != 0, flags & PRIV_FLAG != 0)?;
+
+    if flags & AUTH_FLAG != 0 {
+        // The digest is computed over the message with its own placeholder zeroed out.
+        let offset = auth_parameters.as_ptr() as usize - data.as_ptr() as usize;
+        let mut zeroed = data.to_vec();
+        zeroed[offset..offset + auth_parameters.len()]
+            .iter_mut()
+            .for_each(|byte| *byte = 0);
+        user.authenticate(engine_id, &zeroed, auth_parameters)?;
+    }
+
+    let decrypted;
+    let mut scoped_pdu = if flags & PRIV_FLAG != 0 {
+        decrypted = user.decrypt(
+            engine_id,
+            engine_boots as u32,
+            engine_time as u32,
+            priv_parameters,
+            message.octet_string()?,
+        )?;
+        Reader::new(&decrypted).constructed(ber::SEQUENCE)?
+    } else {
+        message.constructed(ber::SEQUENCE)?
+    };
+    let _context_engine_id = scoped_pdu.octet_string()?;
+    let context_name = String::from_utf8_lossy(scoped_pdu.octet_string()?).into_owned();
+
+    Ok(Message {
+        security: Security::User {
+            name,
+            engine_id: engine_id.to_vec(),
+            context_name,
+        },
+        pdu: parse_pdu(&mut scoped_pdu)?,
+    })
+}
+
+fn parse_pdu(reader: &mut Reader<'_>) -> Result<Pdu, TrapError> {
+    let (tag, contents) = reader.read()?;
+    if tag != TRAP && tag != INFORM_REQUEST {
+        return Err(TrapError::UnexpectedPdu { tag });
+    }
+
+    let mut pdu = Reader::new(contents);
+    let request_id = pdu.integer()?;
+    let _error_status = pdu.integer()?;
+    let _error_index = pdu.integer()?;
+    let raw_varbinds = pdu.expect(ber::SEQUENCE)?;
+
+    let mut list = Reader::new(raw_varbinds);
+    let mut varbinds = Vec::new();
+    while !list.is_empty() {
+        let mut varbind = list.constructed(ber::SEQUENCE)?;
+        let oid = varbind.object_identifier()?;
+        let (tag, contents) = varbind.read()?;
+        varbinds.push(VarBind {
+            oid,
+            value: SnmpValue::decode(tag, contents)?,
+        });
+    }
+
+    Ok(Pdu {
+        tag,
+        request_id,
+        varbinds,
+        raw_varbinds: raw_varbinds.to_vec(),
+    })
+}
+
+impl SnmpValue {
+    fn decode(tag: u8, contents: &[u8]) -> Result<Self, TrapError> {
+        Ok(match tag {
+            ber::INTEGER => Self::Integer(ber::decode_integer(contents)?),
+            ber::OCTET_STRING => Self::OctetString(contents.to_vec()),
+            ber::NULL => Self::Null,
+            ber::OBJECT_IDENTIFIER => {
+                Self::ObjectIdentifier(ber::decode_object_identifier(contents)?)
+            }
+            ber::IP_ADDRESS => match contents {
+                [a, b, c, d] => Self::IpAddress(Ipv4Addr::new(*a, *b, *c, *d)),
+                _ => return Err(BerError::InvalidLength.into()),
+            },
+            ber::COUNTER32 => Self::Counter32(ber::decode_unsigned(contents)?),
+            ber::GAUGE32 => Self::Gauge32(ber::decode_unsigned(contents)?),
+            ber::TIME_TICKS => Self::TimeTicks(ber::decode_unsigned(contents)?),
+            ber::OPAQUE => Self::Opaque(contents.to_vec()),
+            ber::COUNTER64 => Self::Counter64(ber::decode_unsigned(contents)?),
+            ber::NO_SUCH_OBJECT => Self::NoSuchObject,
+            ber::NO_SUCH_INSTANCE => Self::NoSuchInstance,
+            ber::END_OF_MIB_VIEW => Self::EndOfMibView,
+            tag => return Err(TrapError::UnknownValueType { tag }),
+        })
+    }
+
+    const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::OctetString(_) => "octet_string",
+            Self::Null => "null",
+            Self::ObjectIdentifier(_) => "object_identifier",
+            Self::IpAddress(_) => "ip_address",
+            Self::Counter32(_) => "counter32",
+            Self::Gauge32(_) => "gauge32",
+            Self::TimeTicks(_) => "time_ticks",
+            Self::Opaque(_) => "opaque",
+            Self::Counter64(_) => "counter64",
+            Self::NoSuchObject => "no_such_object",
+            Self::NoSuchInstance => "no_such_instance",
+            Self::EndOfMibView => "end_of_mib_view",
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Integer(value) => Value::from(*value),
+            Self::OctetString(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text)
+                    if !text
+                        .chars()
+                        .any(|c| c.is_control() && !c.is_ascii_whitespace()) =>
+                {
+                    Value::from(text)
+                }
+                _ => Value::from(hex(bytes)),
+            },
+            Self::ObjectIdentifier(oid) => Value::from(format_oid(oid)),
+            Self::IpAddress(address) => Value::from(address.to_string()),
+            Self::Counter32(value)
+            | Self::Gauge32(value)
+            | Self::TimeTicks(value)
+            | Self::Counter64(value) => Value::from(*value),
+            Self::Opaque(bytes) => Value::from(hex(bytes)),
+            Self::Null | Self::NoSuchObject | Self::NoSuchInstance | Self::EndOfMibView => {
+                Value::Null
+            }
+        }
+    }
+}
+
+fn format_oid(oid: &[u32]) -> String {
+    oid.iter()
+        .map(|arc| arc.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+impl Message {
+    /// The response acknowledging an SNMPv2c inform. Acknowledging SNMPv3 informs would
+    /// require this source to act as an authoritative SNMP engine, which it doesn't.
+    fn response(&self) -> Option<Vec<u8>> {
+        let community = match &self.security {
+            Security::Community(community) if self.pdu.tag == INFORM_REQUEST => community,
+            _ => return None,
+        };
+
+        let mut pdu = Vec::new();
+        ber::write_integer(&mut pdu, self.pdu.request_id);
+        ber::write_integer(&mut pdu, 0);
+        ber::write_integer(&mut pdu, 0);
+        ber::write(&mut pdu, ber::SEQUENCE, &self.pdu.raw_varbinds);
+
+        let mut message = Vec::new();
+        ber::write_integer(&mut message, VERSION_2C);
+        ber::write(&mut message, ber::OCTET_STRING, community.as_bytes());
+        ber::write(&mut message, RESPONSE, &pdu);
+
+        let mut response = Vec::new();
+        ber::write(&mut response, ber::SEQUENCE, &message);
+        Some(response)
+    }
+
+    fn into_event(self, context: &Context, peer: SocketAddr) -> Event {
+        let mut log = LogEvent::default();
+        log.insert(log_schema().source_type_key(), Bytes::from("snmp_trap"));
+        log.insert(log_schema().timestamp_key(), Utc::now());
+        log.insert(context.host_key.as_str(), peer.ip().to_string());
+
+        match self.security {
+            Security::Community(community) => {
+                log.insert("version", "2c");
+                log.insert("community", community);
+            }
+            Security::User {
+                name,
+                engine_id,
+                context_name,
+            } => {
+                log.insert("version", "3");
+                log.insert("user", name);
+                log.insert("engine_id", hex(&engine_id));
+                log.insert("context_name", context_name);
+            }
+        }
+
+        log.insert(
+            "pdu_type",
+            if self.pdu.tag == INFORM_REQUEST {
+                "inform"
+            } else {
+                "trap"
+            },
+        );
+        log.insert("request_id", self.pdu.request_id);
+
+        let mut message = None;
+        let mut varbinds = Vec::new();
+        for varbind in self.pdu.varbinds {
+            match (varbind.oid.as_slice(), &varbind.value) {
+                (SYS_UP_TIME, SnmpValue::TimeTicks(uptime)) => {
+                    log.insert("uptime", *uptime);
+                }
+                (SNMP_TRAP_OID, SnmpValue::ObjectIdentifier(oid)) => {
+                    let trap_oid = format_oid(oid);
+                    let trap_name = context.mib.resolve(oid).map(|resolved| resolved.name);
+                    message = Some(trap_name.clone().unwrap_or_else(|| trap_oid.clone()));
+                    log.insert("trap_oid", trap_oid);
+                    if let Some(trap_name) = trap_name {
+                        log.insert("trap_name", trap_name);
+                    }
+                }
+                _ => varbinds.push(varbind_value(&varbind, &context.mib)),
+            }
+        }
+        log.insert("varbinds", varbinds);
+        if let Some(message) = message {
+            log.insert(log_schema().message_key(), message);
+        }
+
+        Event::Log(log)
+    }
+}
+
+fn varbind_value(varbind: &VarBind, mib: &Mib) -> Value {
+    let mut fields = BTreeMap::new();
+    fields.insert("oid".to_owned(), Value::from(format_oid(&varbind.oid)));
+    fields.insert("type".to_owned(), Value::from(varbind.value.type_name()));
+    fields.insert("value".to_owned(), varbind.value.to_value());
+
+    if let Some(resolved) = mib.resolve(&varbind.oid) {
+        match (&varbind.value, resolved.enums) {
+            (SnmpValue::Integer(value), Some(enums)) => {
+                if let Some(label) = enums.get(value) {
+                    fields.insert("label".to_owned(), Value::from(label.as_str()));
+                }
+            }
+            (SnmpValue::ObjectIdentifier(oid), _) => {
+                if let Some(label) = mib.resolve(oid) {
+                    fields.insert("label".to_owned(), Value::from(label.name));
+                }
+            }
+            _ => (),
+        }
+        fields.insert("name".to_owned(), Value::from(resolved.name));
+    }
+
+    Value::from(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpTrapConfig>();
+    }
+
+    const IF_MIB: &str = r#"
+IF-MIB DEFINITIONS ::= BEGIN
+interfaces OBJECT IDENTIFIER ::= { mib-2 2 }
+ifTable OBJECT-TYPE ::= { interfaces 2 }
+ifEntry OBJECT-TYPE ::= { ifTable 1 }
+ifIndex OBJECT-TYPE ::= { ifEntry 1 }
+ifOperStatus OBJECT-TYPE
+    SYNTAX INTEGER { up(1), down(2), testing(3) }
+    ::= { ifEntry 8 }
+linkDown NOTIFICATION-TYPE
+    OBJECTS { ifIndex, ifOperStatus }
+    ::= { snmpModules 1 1 5 3 }
+END
+"#;
+
+    fn varbind(out: &mut Vec<u8>, oid: &[u32], tag: u8, value: &[u8]) {
+        let mut value_tlv = Vec::new();
+        ber::write(&mut value_tlv, tag, value);
+        varbind_tlv(out, oid, &value_tlv);
+    }
+
+    fn varbind_tlv(out: &mut Vec<u8>, oid: &[u32], value_tlv: &[u8]) {
+        let mut contents = Vec::new();
+        ber::write_object_identifier(&mut contents, oid);
+        contents.extend_from_slice(value_tlv);
+        ber::write(out, ber::SEQUENCE, &contents);
+    }
+
+    fn v2c_message(community: &str, tag: u8) -> Vec<u8> {
+        let mut varbinds = Vec::new();
+        varbind(&mut varbinds, SYS_UP_TIME, ber::TIME_TICKS, &[0x01, 0x00]);
+        let mut trap_oid = Vec::new();
+        ber::write_object_identifier(&mut trap_oid, &[1, 3, 6, 1, 6, 3, 1, 1, 5, 3]);
+        varbind_tlv(&mut varbinds, SNMP_TRAP_OID, &trap_oid);
+        varbind(
+            &mut varbinds,
+            &[1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 3],
+            ber::INTEGER,
+            &[3],
+        );
+        varbind(
+            &mut varbinds,
+            &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8, 3],
+            ber::INTEGER,
+            &[2],
+        );
+        varbind(
+            &mut varbinds,
+            &[1, 3, 6, 1, 4, 1, 32473, 1],
+            ber::OCTET_STRING,
+            b"eth0",
+        );
+        varbind(
+            &mut varbinds,
+            &[1, 3, 6, 1, 4, 1, 32473, 2],
+            ber::OCTET_STRING,
+            &[0xde, 0xad],
+        );
+        varbind(
+            &mut varbinds,
+            &[1, 3, 6, 1, 4, 1, 32473, 3],
+            ber::IP_ADDRESS,
+            &[10, 0, 0, 1],
+        );
+
+        let mut pdu = Vec::new();
+        ber::write_integer(&mut pdu, 1234);
+        ber::write_integer(&mut pdu, 0);
+        ber::write_integer(&mut pdu, 0);
+        ber::write(&mut pdu, ber::SEQUENCE, &varbinds);
+
+        let mut message = Vec::new();
+        ber::write_integer(&mut message, VERSION_2C);
+        ber::write(&mut message, ber::OCTET_STRING, community.as_bytes());
+        ber::write(&mut message, tag, &pdu);
+
+        let mut out = Vec::new();
+        ber::write(&mut out, ber::SEQUENCE, &message);
+        out
+    }
+
+    fn context() -> Context {
+        Context {
+            mib: Mib::parse(vec![IF_MIB]),
+            communities: vec!["public".to_owned()],
+            users: HashMap::new(),
+            host_key: "host".to_owned(),
+        }
+    }
+
+    fn peer() -> SocketAddr {
+        "192.0.2.1:1162".parse().unwrap()
+    }
+
+    #[test]
+    fn decodes_v2c_trap() {
+        let context = context();
+        let data = v2c_message("public", TRAP);
+        let message = parse_message(&data, &context.communities, &context.users).unwrap();
+        assert!(message.response().is_none());
+
+        let event = message.into_event(&context, peer());
+        let log = event.as_log();
+        assert_eq!(log["message"], "IF-MIB::linkDown".into());
+        assert_eq!(log["trap_oid"], "1.3.6.1.6.3.1.1.5.3".into());
+        assert_eq!(log["version"], "2c".into());
+        assert_eq!(log["community"], "public".into());
+        assert_eq!(log["host"], "192.0.2.1".into());
+        assert_eq!(log["uptime"], 256.into());
+        assert_eq!(log["request_id"], 1234.into());
+        assert_eq!(log["pdu_type"], "trap".into());
+
+        assert_eq!(log["varbinds[0].name"], "IF-MIB::ifIndex.3".into());
+        assert_eq!(log["varbinds[0].value"], 3.into());
+        assert_eq!(log["varbinds[1].name"], "IF-MIB::ifOperStatus.3".into());
+        assert_eq!(log["varbinds[1].label"], "down".into());
+        assert_eq!(log["varbinds[2].type"], "octet_string".into());
+        assert_eq!(log["varbinds[2].value"], "eth0".into());
+        assert!(!log.contains("varbinds[2].name"));
+        assert_eq!(log["varbinds[3].value"], "de:ad".into());
+        assert_eq!(log["varbinds[4].value"], "10.0.0.1".into());
+    }
+
+    #[test]
+    fn acknowledges_v2c_inform() {
+        let context = context();
+        let data = v2c_message("public", INFORM_REQUEST);
+        let message = parse_message(&data, &context.communities, &context.users).unwrap();
+
+        let response = message.response().unwrap();
+        let mut reader = Reader::new(&response).constructed(ber::SEQUENCE).unwrap();
+        assert_eq!(reader.integer(), Ok(VERSION_2C));
+        assert_eq!(reader.octet_string(), Ok(&b"public"[..]));
+        let mut pdu = reader.constructed(RESPONSE).unwrap();
+        assert_eq!(pdu.integer(), Ok(1234));
+        assert_eq!(pdu.integer(), Ok(0));
+        assert_eq!(pdu.integer(), Ok(0));
+        assert_eq!(
+            pdu.expect(ber::SEQUENCE).unwrap(),
+            message.pdu.raw_varbinds.as_slice()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_community() {
+        let context = context();
+        let data = v2c_message("private", TRAP);
+        assert!(matches!(
+            parse_message(&data, &context.communities, &context.users),
+            Err(TrapError::CommunityNotAllowed { .. })
+        ));
+    }
+}
@@ -0,0 +1,273 @@
+//! The User-based Security Model of SNMPv3 (RFC 3414), with the AES privacy protocol of
+//! RFC 3826.
+
+use openssl::{
+    hash::{Hasher, MessageDigest},
+    memcmp,
+    pkey::PKey,
+    sign::Signer,
+    symm::{Cipher, Crypter, Mode},
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+/// Length of the truncated digest carried in `msgAuthenticationParameters`.
+pub const AUTH_PARAMETERS_LEN: usize = 12;
+
+#[derive(Debug, Snafu)]
+pub enum UsmError {
+    #[snafu(display("Unknown user {:?}", user))]
+    UnknownUser { user: String },
+    #[snafu(display(
+        "Message is authenticated, but user {:?} has no authentication key",
+        user
+    ))]
+    NoAuthKey { user: String },
+    #[snafu(display("Message is encrypted, but user {:?} has no privacy key", user))]
+    NoPrivKey { user: String },
+    #[snafu(display("Authentication failed for user {:?}", user))]
+    AuthenticationFailed { user: String },
+    #[snafu(display("Message from user {:?} is less secure than configured", user))]
+    SecurityLevel { user: String },
+    #[snafu(display("Invalid privacy parameters"))]
+    InvalidPrivParameters,
+    #[snafu(display("Cryptographic operation failed: {}", source))]
+    Crypto { source: openssl::error::ErrorStack },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthProtocol {
+    Md5,
+    Sha,
+}
+
+impl AuthProtocol {
+    fn digest(self) -> MessageDigest {
+        match self {
+            Self::Md5 => MessageDigest::md5(),
+            Self::Sha => MessageDigest::sha1(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivProtocol {
+    Des,
+    Aes,
+}
+
+/// An SNMPv3 user traps are accepted from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    pub name: String,
+    pub auth_protocol: Option<AuthProtocol>,
+    pub auth_password: Option<String>,
+    pub priv_protocol: Option<PrivProtocol>,
+    pub priv_password: Option<String>,
+}
+
+/// A user along with the keys derived from its passwords. The keys still have to be
+/// localized to the engine ID of each message, which is cheap compared to deriving them.
+pub struct User {
+    name: String,
+    auth: Option<(AuthProtocol, Vec<u8>)>,
+    privacy: Option<(PrivProtocol, Vec<u8>)>,
+}
+
+impl User {
+    pub fn new(config: &UserConfig) -> Result<Self, UsmError> {
+        let auth = match (config.auth_protocol, &config.auth_password) {
+            (Some(protocol), Some(password)) => {
+                Some((protocol, password_to_key(protocol, password)?))
+            }
+            _ => None,
+        };
+        // The privacy key is derived with the hash function of the authentication protocol.
+        let privacy = match (&auth, config.priv_protocol, &config.priv_password) {
+            (Some((auth_protocol, _)), Some(protocol), Some(password)) => {
+                Some((protocol, password_to_key(*auth_protocol, password)?))
+            }
+            _ => None,
+        };
+        Ok(Self {
+            name: config.name.clone(),
+            auth,
+            privacy,
+        })
+    }
+
+    /// Rejects messages from users with keys that aren't authenticated or encrypted with them.
+    pub fn check_security_level(
+        &self,
+        authenticated: bool,
+        encrypted: bool,
+    ) -> Result<(), UsmError> {
+        if (self.auth.is_some() && !authenticated) || (self.privacy.is_some() && !encrypted) {
+            Err(UsmError::SecurityLevel {
+                user: self.name.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the digest of a message, given the message with its authentication parameters
+    /// zeroed out.
+    pub fn authenticate(
+        &self,
+        engine_id: &[u8],
+        zeroed_message: &[u8],
+        auth_parameters: &[u8],
+    ) -> Result<(), UsmError> {
+        let (protocol, key) = self.auth.as_ref().ok_or_else(|| UsmError::NoAuthKey {
+            user: self.name.clone(),
+        })?;
+        let key = localize_key(*protocol, key, engine_id)?;
+        let pkey = PKey::hmac(&key).context(CryptoSnafu)?;
+        let mut signer = Signer::new(protocol.digest(), &pkey).context(CryptoSnafu)?;
+        signer.update(zeroed_message).context(CryptoSnafu)?;
+        let digest = signer.sign_to_vec().context(CryptoSnafu)?;
+
+        if auth_parameters.len() == AUTH_PARAMETERS_LEN
+            && memcmp::eq(&digest[..AUTH_PARAMETERS_LEN], auth_parameters)
+        {
+            Ok(())
+        } else {
+            Err(UsmError::AuthenticationFailed {
+                user: self.name.clone(),
+            })
+        }
+    }
+
+    /// Decrypts the scoped PDU of a message.
+    pub fn decrypt(
+        &self,
+        engine_id: &[u8],
+        engine_boots: u32,
+        engine_time: u32,
+        priv_parameters: &[u8],
+        encrypted: &[u8],
+    ) -> Result<Vec<u8>, UsmError> {
+        let (auth_protocol, _) = self.auth.as_ref().ok_or_else(|| UsmError::NoAuthKey {
+            user: self.name.clone(),
+        })?;
+        let (protocol, key) = self.privacy.as_ref().ok_or_else(|| UsmError::NoPrivKey {
+            user: self.name.clone(),
+        })?;
+        if priv_parameters.len() != 8 {
+            return Err(UsmError::InvalidPrivParameters);
+        }
+        let key = localize_key(*auth_protocol, key, engine_id)?;
+
+        let (cipher, key, iv) = match protocol {
+            PrivProtocol::Des => {
+                if key.len() < 16 || encrypted.len() % 8 != 0 {
+                    return Err(UsmError::InvalidPrivParameters);
+                }
+                // The last 8 bytes of the key are the pre-IV, salted with the parameters.
+                let iv = key[8..16]
+                    .iter()
+                    .zip(priv_parameters)
+                    .map(|(a, b)| a ^ b)
+                    .collect::<Vec<_>>();
+                (Cipher::des_cbc(), key[..8].to_vec(), iv)
+            }
+            PrivProtocol::Aes => {
+                let mut iv = Vec::with_capacity(16);
+                iv.extend_from_slice(&engine_boots.to_be_bytes());
+                iv.extend_from_slice(&engine_time.to_be_bytes());
+                iv.extend_from_slice(priv_parameters);
+                (Cipher::aes_128_cfb128(), key[..16].to_vec(), iv)
+            }
+        };
+
+        let mut crypter =
+            Crypter::new(cipher, Mode::Decrypt, &key, Some(&iv)).context(CryptoSnafu)?;
+        crypter.pad(false);
+        let mut decrypted = vec![0; encrypted.len() + cipher.block_size()];
+        let mut count = crypter
+            .update(encrypted, &mut decrypted)
+            .context(CryptoSnafu)?;
+        count += crypter
+            .finalize(&mut decrypted[count..])
+            .context(CryptoSnafu)?;
+        decrypted.truncate(count);
+        Ok(decrypted)
+    }
+}
+
+/// Derives a key from a password by hashing a megabyte of the repeated password (RFC 3414,
+/// A.2).
+fn password_to_key(protocol: AuthProtocol, password: &str) -> Result<Vec<u8>, UsmError> {
+    const TOTAL: usize = 1024 * 1024;
+    let password = password.as_bytes();
+    let mut hasher = Hasher::new(protocol.digest()).context(CryptoSnafu)?;
+    if !password.is_empty() {
+        let mut block = [0u8; 64];
+        let mut index = 0;
+        for _ in 0..TOTAL / block.len() {
+            for byte in block.iter_mut() {
+                *byte = password[index % password.len()];
+                index += 1;
+            }
+            hasher.update(&block).context(CryptoSnafu)?;
+        }
+    }
+    Ok(hasher.finish().context(CryptoSnafu)?.to_vec())
+}
+
+/// Localizes a key to an SNMP engine.
+fn localize_key(protocol: AuthProtocol, key: &[u8], engine_id: &[u8]) -> Result<Vec<u8>, UsmError> {
+    let mut hasher = Hasher::new(protocol.digest()).context(CryptoSnafu)?;
+    hasher.update(key).context(CryptoSnafu)?;
+    hasher.update(engine_id).context(CryptoSnafu)?;
+    hasher.update(key).context(CryptoSnafu)?;
+    Ok(hasher.finish().context(CryptoSnafu)?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The sample keys of RFC 3414, A.3.
+    const ENGINE_ID: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn md5_keys() {
+        let key = password_to_key(AuthProtocol::Md5, "maplesyrup").unwrap();
+        assert_eq!(hex(&key), "9faf3283884e92834ebc9847d8edd963");
+        let key = localize_key(AuthProtocol::Md5, &key, &ENGINE_ID).unwrap();
+        assert_eq!(hex(&key), "526f5eed9fcce26f8964c2930787d82b");
+    }
+
+    #[test]
+    fn sha_keys() {
+        let key = password_to_key(AuthProtocol::Sha, "maplesyrup").unwrap();
+        assert_eq!(hex(&key), "9fb5cc0381497b3793528939ff788d5d79145211");
+        let key = localize_key(AuthProtocol::Sha, &key, &ENGINE_ID).unwrap();
+        assert_eq!(hex(&key), "6695febc9288e36282235fc7151f128497b38f3f");
+    }
+
+    #[test]
+    fn rejects_wrong_digest() {
+        let user = User::new(&UserConfig {
+            name: "vector".to_owned(),
+            auth_protocol: Some(AuthProtocol::Sha),
+            auth_password: Some("maplesyrup".to_owned()),
+            priv_protocol: None,
+            priv_password: None,
+        })
+        .unwrap();
+        assert!(matches!(
+            user.authenticate(&ENGINE_ID, b"message", &[0; AUTH_PARAMETERS_LEN]),
+            Err(UsmError::AuthenticationFailed { .. })
+        ));
+    }
+}
@@ -98,7 +98,7 @@ impl SourceConfig for SocketConfig {
                     None => NewlineDelimitedDecoderConfig::new_with_max_length(max_length).into(),
                 };
 
-                let decoder = DecodingConfig::new(framing, config.decoding().clone()).build();
+                let decoder = DecodingConfig::new(framing, config.decoding().clone()).build()?;
 
                 let tcp = tcp::RawTcpSource::new(config.clone(), decoder);
                 let tls = MaybeTlsSettings::from_config(config.tls(), true)?;
@@ -120,7 +120,7 @@ impl SourceConfig for SocketConfig {
                     .unwrap_or_else(|| log_schema().host_key().to_string());
                 let decoder =
                     DecodingConfig::new(config.framing().clone(), config.decoding().clone())
-                        .build();
+                        .build()?;
                 Ok(udp::udp(
                     config.address(),
                     config.max_length(),
@@ -140,7 +140,7 @@ impl SourceConfig for SocketConfig {
                     config.framing.unwrap_or_else(default_framing_message_based),
                     config.decoding.clone(),
                 )
-                .build();
+                .build()?;
                 Ok(unix::unix_datagram(
                     config.path,
                     config
@@ -167,7 +167,7 @@ impl SourceConfig for SocketConfig {
                     None => NewlineDelimitedDecoderConfig::new_with_max_length(max_length).into(),
                 };
 
-                let decoder = DecodingConfig::new(framing, config.decoding.clone()).build();
+                let decoder = DecodingConfig::new(framing, config.decoding.clone()).build()?;
 
                 let host_key = config
                     .host_key
@@ -620,6 +620,7 @@ mod test {
                 out: sender,
                 proxy: Default::default(),
                 acknowledgements: false,
+                acknowledgement_timeout: Default::default(),
                 schema_definitions: HashMap::default(),
             })
             .await
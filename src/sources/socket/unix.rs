@@ -89,7 +89,10 @@ pub(super) fn unix_stream(
     build_unix_stream_source(
         path,
         decoder,
-        move |events, received_from| handle_events(events, &host_key, received_from),
+        move |events, received_from| {
+            handle_events(events, &host_key, received_from);
+            Vec::new()
+        },
         shutdown,
         out,
     )
@@ -0,0 +1,106 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use mysql_async::{prelude::Queryable, Conn, Opts};
+use ordered_float::NotNan;
+use snafu::ResultExt;
+
+use super::{MysqlConnectSnafu, MysqlQuerySnafu, QueryError, Row};
+use crate::event::Value;
+
+/// Connects with the session time zone set to UTC, so that cursors on timestamp columns compare
+/// as they were read.
+pub async fn connect(opts: &Opts) -> Result<Conn, QueryError> {
+    let mut conn = Conn::new(opts.clone()).await.context(MysqlConnectSnafu)?;
+    conn.query_drop("SET time_zone = '+00:00'")
+        .await
+        .context(MysqlQuerySnafu)?;
+    Ok(conn)
+}
+
+/// Runs the query as a prepared statement, whose results are typed.
+pub async fn query(conn: &mut Conn, query: &str) -> Result<Vec<Row>, QueryError> {
+    let rows: Vec<mysql_async::Row> = conn.exec(query, ()).await.context(MysqlQuerySnafu)?;
+    Ok(rows
+        .into_iter()
+        .map(|mut row| {
+            let names = row
+                .columns_ref()
+                .iter()
+                .map(|column| column.name_str().into_owned())
+                .collect::<Vec<_>>();
+            names
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let value = row
+                        .take::<mysql_async::Value, _>(index)
+                        .map_or(Value::Null, to_value);
+                    (name, value)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+fn to_value(value: mysql_async::Value) -> Value {
+    use mysql_async::Value as MysqlValue;
+
+    match value {
+        MysqlValue::NULL => Value::Null,
+        MysqlValue::Bytes(bytes) => Value::from(String::from_utf8_lossy(&bytes).into_owned()),
+        MysqlValue::Int(value) => Value::from(value),
+        MysqlValue::UInt(value) => Value::from(value),
+        MysqlValue::Float(value) => NotNan::new(value as f64).map_or(Value::Null, Value::from),
+        MysqlValue::Double(value) => NotNan::new(value).map_or(Value::Null, Value::from),
+        MysqlValue::Date(year, month, day, hour, minute, second, micros) => {
+            // Zero dates, which MySQL allows, have no equivalent.
+            NaiveDate::from_ymd_opt(year.into(), month.into(), day.into())
+                .and_then(|date| {
+                    date.and_hms_micro_opt(hour.into(), minute.into(), second.into(), micros)
+                })
+                .map_or(Value::Null, |datetime| {
+                    Value::from(DateTime::<Utc>::from_utc(datetime, Utc))
+                })
+        }
+        MysqlValue::Time(negative, days, hours, minutes, seconds, micros) => {
+            let mut time = format!(
+                "{}{:02}:{:02}:{:02}",
+                if negative { "-" } else { "" },
+                days * 24 + u32::from(hours),
+                minutes,
+                seconds
+            );
+            if micros > 0 {
+                time.push_str(&format!(".{:06}", micros));
+            }
+            Value::from(time)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_values() {
+        use mysql_async::Value as MysqlValue;
+
+        assert_eq!(to_value(MysqlValue::Int(-3)), Value::from(-3));
+        assert_eq!(
+            to_value(MysqlValue::Bytes(b"alice".to_vec())),
+            Value::from("alice")
+        );
+        assert_eq!(
+            to_value(MysqlValue::Date(2022, 3, 1, 10, 0, 0, 500)),
+            Value::from(DateTime::<Utc>::from_utc(
+                NaiveDate::from_ymd(2022, 3, 1).and_hms_micro(10, 0, 0, 500),
+                Utc
+            ))
+        );
+        assert_eq!(to_value(MysqlValue::Date(0, 0, 0, 0, 0, 0, 0)), Value::Null);
+        assert_eq!(
+            to_value(MysqlValue::Time(true, 1, 2, 3, 4, 0)),
+            Value::from("-26:03:04")
+        );
+    }
+}
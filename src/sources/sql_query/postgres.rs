@@ -0,0 +1,94 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use openssl::ssl::{SslConnector, SslMethod};
+use ordered_float::NotNan;
+use postgres_openssl::MakeTlsConnector;
+use snafu::ResultExt;
+use tokio_postgres::{types::Type, Client, Config};
+
+use super::{PostgresConnectSnafu, PostgresQuerySnafu, QueryError, Row, TlsSnafu};
+use crate::event::Value;
+
+/// Connects with TLS if the server supports it, unless `sslmode` says otherwise. The session time
+/// zone is set to UTC, so that cursors on timestamp columns compare as they were read.
+pub async fn connect(config: &Config) -> Result<Client, QueryError> {
+    let connector = MakeTlsConnector::new(
+        SslConnector::builder(SslMethod::tls())
+            .context(TlsSnafu)?
+            .build(),
+    );
+    let (client, connection) = config
+        .connect(connector)
+        .await
+        .context(PostgresConnectSnafu)?;
+    tokio::spawn(async move {
+        if let Err(error) = connection.await {
+            debug!(message = "PostgreSQL connection closed.", %error);
+        }
+    });
+
+    client
+        .batch_execute("SET TIME ZONE 'UTC'")
+        .await
+        .context(PostgresQuerySnafu)?;
+    Ok(client)
+}
+
+pub async fn query(client: &Client, query: &str) -> Result<Vec<Row>, QueryError> {
+    let rows = client.query(query, &[]).await.context(PostgresQuerySnafu)?;
+    rows.iter()
+        .map(|row| {
+            row.columns()
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    let value = to_value(row, index, column.type_()).context(PostgresQuerySnafu)?;
+                    Ok((column.name().to_owned(), value))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Converts a column of a row into a `Value`. Columns of other types are null, and need to be
+/// cast in the query, for example to `text`.
+fn to_value(
+    row: &tokio_postgres::Row,
+    index: usize,
+    type_: &Type,
+) -> Result<Value, tokio_postgres::Error> {
+    Ok(match *type_ {
+        Type::BOOL => row.try_get::<_, Option<bool>>(index)?.map(Value::from),
+        Type::INT2 => row
+            .try_get::<_, Option<i16>>(index)?
+            .map(|value| Value::from(value as i64)),
+        Type::INT4 => row
+            .try_get::<_, Option<i32>>(index)?
+            .map(|value| Value::from(value as i64)),
+        Type::INT8 => row.try_get::<_, Option<i64>>(index)?.map(Value::from),
+        Type::OID => row
+            .try_get::<_, Option<u32>>(index)?
+            .map(|value| Value::from(value as i64)),
+        Type::FLOAT4 => row
+            .try_get::<_, Option<f32>>(index)?
+            .and_then(|value| NotNan::new(value as f64).ok())
+            .map(Value::from),
+        Type::FLOAT8 => row
+            .try_get::<_, Option<f64>>(index)?
+            .and_then(|value| NotNan::new(value).ok())
+            .map(Value::from),
+        Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => {
+            row.try_get::<_, Option<String>>(index)?.map(Value::from)
+        }
+        Type::TIMESTAMP => row
+            .try_get::<_, Option<NaiveDateTime>>(index)?
+            .map(|value| Value::from(DateTime::<Utc>::from_utc(value, Utc))),
+        Type::TIMESTAMPTZ => row
+            .try_get::<_, Option<DateTime<Utc>>>(index)?
+            .map(Value::from),
+        Type::DATE => row
+            .try_get::<_, Option<NaiveDate>>(index)?
+            .map(|value| Value::from(value.format("%Y-%m-%d").to_string())),
+        _ => None,
+    }
+    .unwrap_or(Value::Null))
+}
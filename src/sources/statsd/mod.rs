@@ -17,7 +17,8 @@ use crate::{
         NewlineDelimitedDecoder,
     },
     config::{
-        self, GenerateConfig, Output, Resource, SourceConfig, SourceContext, SourceDescription,
+        self, log_schema, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
     },
     event::Event,
     internal_events::{
@@ -34,7 +35,7 @@ pub mod parser;
 #[cfg(unix)]
 mod unix;
 
-use parser::parse;
+use parser::parse_event;
 #[cfg(unix)]
 use unix::{statsd_unix, UnixConfig};
 
@@ -133,7 +134,9 @@ impl SourceConfig for StatsdConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(config::DataType::Metric)]
+        vec![Output::default(
+            config::DataType::Metric | config::DataType::Log,
+        )]
     }
 
     fn source_type(&self) -> &'static str {
@@ -165,10 +168,12 @@ impl decoding::format::Deserializer for StatsdDeserializer {
         });
         match std::str::from_utf8(&bytes)
             .map_err(ParseError::InvalidUtf8)
-            .and_then(parse)
+            .and_then(parse_event)
         {
-            Ok(metric) => {
-                let event = Event::Metric(metric);
+            Ok(mut event) => {
+                if let Event::Log(log) = &mut event {
+                    log.insert(log_schema().source_type_key(), Bytes::from("statsd"));
+                }
                 emit!(&EventsReceived {
                     count: 1,
                     byte_size: event.size_of(),
@@ -5,14 +5,32 @@ use std::{
     str::Utf8Error,
 };
 
+use chrono::{DateTime, TimeZone, Utc};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Event, LogEvent, Value,
+    },
+};
 
 static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
 static NONALPHANUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^a-zA-Z_\-0-9\.]").unwrap());
 
+/// Parses the DogStatsD service checks and events into logs, and the other packets into metrics.
+pub fn parse_event(packet: &str) -> Result<Event, ParseError> {
+    if let Some(body) = packet.strip_prefix("_sc|") {
+        parse_service_check(body).map(Event::Log)
+    } else if let Some(body) = packet.strip_prefix("_e{") {
+        parse_datadog_event(body).map(Event::Log)
+    } else {
+        parse(packet).map(Event::Metric)
+    }
+}
+
 pub fn parse(packet: &str) -> Result<Metric, ParseError> {
     // https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/#datagram-format
     let key_and_body = packet.splitn(2, ':').collect::<Vec<_>>();
@@ -33,26 +51,27 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
     let name = sanitize_key(key);
     let metric_type = parts[1];
 
-    // sampling part is optional and comes after metric type part
-    let sampling = parts.get(2).filter(|s| s.starts_with('@'));
-    let sample_rate = if let Some(s) = sampling {
-        1.0 / sanitize_sampling(parse_sampling(s)?)
-    } else {
-        1.0
-    };
-
-    // tags are optional and could be found either after sampling of after metric type part
-    let tags = if sampling.is_none() {
-        parts.get(2)
-    } else {
-        parts.get(3)
-    };
-    let tags = tags.filter(|s| s.starts_with('#'));
-    let tags = if let Some(t) = tags {
-        Some(parse_tags(t)?)
-    } else {
-        None
-    };
+    // the sampling, tags, container ID and timestamp parts are optional and come after the metric
+    // type part, the parts of the later DogStatsD extensions being ignored
+    let mut sample_rate = 1.0;
+    let mut tags = None;
+    let mut container_id = None;
+    let mut timestamp = None;
+    for part in &parts[2..] {
+        if part.starts_with('@') {
+            sample_rate = 1.0 / sanitize_sampling(parse_sampling(part)?);
+        } else if part.starts_with('#') {
+            tags = Some(parse_tags(part)?);
+        } else if let Some(id) = part.strip_prefix("c:") {
+            container_id = Some(id);
+        } else if let Some(seconds) = part.strip_prefix('T') {
+            timestamp = Some(parse_timestamp(seconds)?);
+        }
+    }
+    if let Some(id) = container_id {
+        tags.get_or_insert_with(BTreeMap::new)
+            .insert("container_id".to_owned(), id.to_owned());
+    }
 
     let metric = match metric_type {
         "c" => {
@@ -110,7 +129,116 @@ pub fn parse(packet: &str) -> Result<Metric, ParseError> {
         .with_tags(tags),
         other => return Err(ParseError::UnknownMetricType(other.into())),
     };
-    Ok(metric)
+    Ok(metric.with_timestamp(timestamp))
+}
+
+fn parse_service_check(body: &str) -> Result<LogEvent, ParseError> {
+    // https://docs.datadoghq.com/developers/service_checks/dogstatsd_service_checks_submission/#datagram-format
+    let mut parts = body.split('|');
+    let name = parts
+        .next()
+        .filter(|name| !name.is_empty())
+        .ok_or(ParseError::Malformed("service check should have a name"))?;
+    let status = match parts.next() {
+        Some("0") => "ok",
+        Some("1") => "warning",
+        Some("2") => "critical",
+        Some("3") => "unknown",
+        _ => {
+            return Err(ParseError::Malformed(
+                "service check status should be one of 0, 1, 2 or 3",
+            ))
+        }
+    };
+
+    let mut log = LogEvent::default();
+    log.insert("service_check", name.to_owned());
+    log.insert("status", status.to_owned());
+    insert_fields(&mut log, parts)?;
+    Ok(log)
+}
+
+fn parse_datadog_event(body: &str) -> Result<LogEvent, ParseError> {
+    // https://docs.datadoghq.com/events/guides/dogstatsd/#datagram-format
+    let (lengths, rest) = body.split_once("}:").ok_or(ParseError::Malformed(
+        "event should have lengths and a body",
+    ))?;
+    let (title_length, text_length) = lengths.split_once(',').ok_or(ParseError::Malformed(
+        "event lengths should be comma separated",
+    ))?;
+    let (title_length, text_length) = (title_length.parse::<usize>()?, text_length.parse()?);
+
+    // the title and text may contain pipes, so they are found by their length
+    let title = rest.get(..title_length).ok_or(ParseError::Malformed(
+        "event title is shorter than its length",
+    ))?;
+    let text = rest
+        .get(title_length..)
+        .and_then(|rest| rest.strip_prefix('|'))
+        .and_then(|rest| rest.get(..text_length))
+        .ok_or(ParseError::Malformed(
+            "event text is shorter than its length",
+        ))?;
+    let fields = &rest[title_length + 1 + text_length..];
+    if !fields.is_empty() && !fields.starts_with('|') {
+        return Err(ParseError::Malformed(
+            "event text is longer than its length",
+        ));
+    }
+
+    let mut log = LogEvent::default();
+    log.insert("title", title.to_owned());
+    log.insert(log_schema().message_key(), text.replace("\\n", "\n"));
+    insert_fields(&mut log, fields.split('|').skip(1))?;
+    Ok(log)
+}
+
+/// Inserts the optional fields of the service checks and events, ignoring the unknown ones.
+fn insert_fields<'a>(
+    log: &mut LogEvent,
+    fields: impl Iterator<Item = &'a str>,
+) -> Result<(), ParseError> {
+    let mut timestamp = None;
+    for field in fields {
+        if field.starts_with('#') {
+            let tags = parse_tags(field)?
+                .into_iter()
+                .map(|(key, value)| (key, Value::from(value)))
+                .collect::<BTreeMap<_, _>>();
+            log.insert("tags", Value::Object(tags));
+            continue;
+        }
+        let (key, value) = match field.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let name = match key {
+            "d" => {
+                timestamp = Some(parse_timestamp(value)?);
+                continue;
+            }
+            "h" => log_schema().host_key(),
+            "m" => log_schema().message_key(),
+            "c" => "container_id",
+            "p" => "priority",
+            "t" => "alert_type",
+            "k" => "aggregation_key",
+            "s" => "source_type_name",
+            _ => continue,
+        };
+        log.insert(name, value.to_owned());
+    }
+    log.insert(
+        log_schema().timestamp_key(),
+        timestamp.unwrap_or_else(Utc::now),
+    );
+    Ok(())
+}
+
+fn parse_timestamp(input: &str) -> Result<DateTime<Utc>, ParseError> {
+    Utc.timestamp_opt(input.parse()?, 0)
+        .single()
+        .ok_or(ParseError::Malformed("timestamp is out of range"))
 }
 
 fn parse_sampling(input: &str) -> Result<f64, ParseError> {
@@ -139,13 +267,11 @@ fn parse_tags(input: &str) -> Result<BTreeMap<String, String>, ParseError> {
 
     let chunks = input[1..].split(',').collect::<Vec<_>>();
     for chunk in chunks {
-        let pair: Vec<_> = chunk.split(':').collect();
-        let key = &pair[0];
         // same as in telegraf plugin:
         // if tag value is not provided, use "true"
         // https://github.com/influxdata/telegraf/blob/master/plugins/inputs/statsd/datadog.go#L152
-        let value = pair.get(1).unwrap_or(&"true");
-        result.insert((*key).to_owned(), (*value).to_owned());
+        let (key, value) = chunk.split_once(':').unwrap_or((chunk, "true"));
+        result.insert(key.to_owned(), value.to_owned());
     }
 
     Ok(result)
@@ -228,8 +354,13 @@ impl From<ParseFloatError> for ParseError {
 mod test {
     use vector_common::assert_event_data_eq;
 
-    use super::{parse, sanitize_key, sanitize_sampling};
-    use crate::event::metric::{Metric, MetricKind, MetricValue, StatisticKind};
+    use chrono::{TimeZone, Utc};
+
+    use super::{parse, parse_event, sanitize_key, sanitize_sampling, ParseError};
+    use crate::{
+        config::log_schema,
+        event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    };
 
     #[test]
     fn basic_counter() {
@@ -396,6 +527,67 @@ mod test {
         );
     }
 
+    #[test]
+    fn dogstatsd_extensions() {
+        assert_event_data_eq!(
+            parse("page.views:1|c|#url:http://example.com|c:83c0a99c0a54|T1656581400|e:x"),
+            Ok(Metric::new(
+                "page.views",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(
+                vec![
+                    ("url".to_owned(), "http://example.com".to_owned()),
+                    ("container_id".to_owned(), "83c0a99c0a54".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ))
+            .with_timestamp(Some(Utc.timestamp(1656581400, 0)))),
+        );
+    }
+
+    #[test]
+    fn service_check() {
+        let event = parse_event("_sc|redis.can_connect|2|d:1656581400|h:db1|#env:prod|m:timed out")
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(log["service_check"], "redis.can_connect".into());
+        assert_eq!(log["status"], "critical".into());
+        assert_eq!(log["tags.env"], "prod".into());
+        assert_eq!(log[log_schema().host_key()], "db1".into());
+        assert_eq!(log[log_schema().message_key()], "timed out".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()],
+            Utc.timestamp(1656581400, 0).into()
+        );
+
+        assert_eq!(
+            parse_event("_sc|redis.can_connect|4"),
+            Err(ParseError::Malformed(
+                "service check status should be one of 0, 1, 2 or 3"
+            ))
+        );
+    }
+
+    #[test]
+    fn datadog_event() {
+        let event =
+            parse_event("_e{9,16}:Deploy|ed|version 2\\n|done|p:low|t:success|c:83c0a99c0a54")
+                .unwrap();
+        let log = event.as_log();
+        assert_eq!(log["title"], "Deploy|ed".into());
+        assert_eq!(log[log_schema().message_key()], "version 2\n|done".into());
+        assert_eq!(log["priority"], "low".into());
+        assert_eq!(log["alert_type"], "success".into());
+        assert_eq!(log["container_id"], "83c0a99c0a54".into());
+        assert!(log.contains(log_schema().timestamp_key()));
+
+        assert!(parse_event("_e{9,20}:Deploy|ed|version 2").is_err());
+        assert!(parse_event("_e{3,4}:abc|defgh").is_err());
+    }
+
     #[test]
     fn sanitizing_keys() {
         assert_eq!("foo-bar-baz", sanitize_key("foo/bar/baz"));
@@ -24,5 +24,11 @@ pub fn statsd_unix(config: UnixConfig, shutdown: ShutdownSignal, out: SourceSend
         Deserializer::Boxed(Box::new(StatsdDeserializer)),
     );
 
-    build_unix_stream_source(config.path, decoder, |_events, _host| {}, shutdown, out)
+    build_unix_stream_source(
+        config.path,
+        decoder,
+        |_events, _host| Vec::new(),
+        shutdown,
+        out,
+    )
 }
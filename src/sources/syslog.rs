@@ -17,15 +17,16 @@ use crate::sources::util::build_unix_stream_source;
 use crate::{
     codecs::{
         self,
-        decoding::{Deserializer, Framer},
-        BytesDecoder, OctetCountingDecoder, SyslogDeserializer,
+        decoding::{format::Deserializer as _, Deserializer, Framer},
+        BytesDecoder, BytesDeserializer, OctetCountingDecoder, OctetCountingDetection,
+        SyslogDeserializer,
     },
     config::{
         log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
         SourceDescription,
     },
     event::Event,
-    internal_events::SyslogUdpReadError,
+    internal_events::{StreamClosedError, SyslogParseError, SyslogUdpReadError},
     shutdown::ShutdownSignal,
     sources::util::{SocketListenAddr, TcpNullAcker, TcpSource},
     tcp::TcpKeepaliveConfig,
@@ -33,6 +34,9 @@ use crate::{
     udp, SourceSender,
 };
 
+/// The output the messages that can't be parsed are sent to, if `route_parse_failures` is set.
+const PARSE_FAILURES: &str = "parse_failures";
+
 #[derive(Deserialize, Serialize, Debug)]
 // TODO: add back when serde-rs/serde#1358 is addressed
 // #[serde(deny_unknown_fields)]
@@ -43,6 +47,35 @@ pub struct SyslogConfig {
     max_length: usize,
     /// The host key of the log. (This differs from `hostname`)
     host_key: Option<String>,
+    /// How the messages received over TCP and Unix sockets are framed.
+    #[serde(default)]
+    framing: Framing,
+    /// Whether the messages that can't be parsed are sent to the `parse_failures` output, rather
+    /// than dropped.
+    #[serde(default)]
+    route_parse_failures: bool,
+}
+
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// Detected from the first message of each connection, octet counted if it starts with a
+    /// digit.
+    #[derivative(Default)]
+    Auto,
+    OctetCounting,
+    NonTransparent,
+}
+
+impl From<Framing> for OctetCountingDetection {
+    fn from(framing: Framing) -> Self {
+        match framing {
+            Framing::Auto => OctetCountingDetection::PerStream,
+            Framing::OctetCounting => OctetCountingDetection::OctetCounting,
+            Framing::NonTransparent => OctetCountingDetection::NonTransparent,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -69,6 +102,8 @@ impl SyslogConfig {
             mode,
             host_key: None,
             max_length: crate::serde::default_max_length(),
+            framing: Framing::default(),
+            route_parse_failures: false,
         }
     }
 }
@@ -89,6 +124,8 @@ impl GenerateConfig for SyslogConfig {
             },
             host_key: None,
             max_length: crate::serde::default_max_length(),
+            framing: Framing::default(),
+            route_parse_failures: false,
         })
         .unwrap()
     }
@@ -102,6 +139,10 @@ impl SourceConfig for SyslogConfig {
             .host_key
             .clone()
             .unwrap_or_else(|| log_schema().host_key().to_string());
+        let handler = EventHandler {
+            host_key,
+            route_parse_failures: self.route_parse_failures,
+        };
 
         match self.mode.clone() {
             Mode::Tcp {
@@ -113,7 +154,8 @@ impl SourceConfig for SyslogConfig {
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
-                    host_key,
+                    framing: self.framing,
+                    handler,
                 };
                 let shutdown_secs = 30;
                 let tls = MaybeTlsSettings::from_config(&tls, true)?;
@@ -134,7 +176,7 @@ impl SourceConfig for SyslogConfig {
             } => Ok(udp(
                 address,
                 self.max_length,
-                host_key,
+                handler,
                 receive_buffer_bytes,
                 cx.shutdown,
                 cx.out,
@@ -142,16 +184,17 @@ impl SourceConfig for SyslogConfig {
             #[cfg(unix)]
             Mode::Unix { path } => {
                 let decoder = Decoder::new(
-                    Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(
-                        self.max_length,
-                    )),
-                    Deserializer::Syslog(SyslogDeserializer),
+                    Framer::OctetCounting(
+                        OctetCountingDecoder::new_with_max_length(self.max_length)
+                            .with_detection(self.framing.into()),
+                    ),
+                    Deserializer::Bytes(BytesDeserializer::new()),
                 );
 
                 Ok(build_unix_stream_source(
                     path,
                     decoder,
-                    move |events, host| handle_events(events, &host_key, host),
+                    move |events, host| handler.handle(events, host),
                     cx.shutdown,
                     cx.out,
                 ))
@@ -160,7 +203,11 @@ impl SourceConfig for SyslogConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(DataType::Log)]
+        let mut outputs = vec![Output::default(DataType::Log)];
+        if self.route_parse_failures {
+            outputs.push(Output::from((PARSE_FAILURES, DataType::Log)));
+        }
+        outputs
     }
 
     fn source_type(&self) -> &'static str {
@@ -184,7 +231,8 @@ impl SourceConfig for SyslogConfig {
 #[derive(Debug, Clone)]
 struct SyslogTcpSource {
     max_length: usize,
-    host_key: String,
+    framing: Framing,
+    handler: EventHandler,
 }
 
 impl TcpSource for SyslogTcpSource {
@@ -195,13 +243,16 @@ impl TcpSource for SyslogTcpSource {
 
     fn decoder(&self) -> Self::Decoder {
         codecs::Decoder::new(
-            Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(self.max_length)),
-            Deserializer::Syslog(SyslogDeserializer),
+            Framer::OctetCounting(
+                OctetCountingDecoder::new_with_max_length(self.max_length)
+                    .with_detection(self.framing.into()),
+            ),
+            Deserializer::Bytes(BytesDeserializer::new()),
         )
     }
 
-    fn handle_events(&self, events: &mut [Event], host: Bytes) {
-        handle_events(events, &self.host_key, Some(host));
+    fn route_events(&self, events: &mut Vec<Event>, host: Bytes) -> Vec<(&'static str, Event)> {
+        self.handler.handle(events, Some(host))
     }
 
     fn build_acker(&self, _: &[Self::Item]) -> Self::Acker {
@@ -209,10 +260,10 @@ impl TcpSource for SyslogTcpSource {
     }
 }
 
-pub fn udp(
+fn udp(
     addr: SocketAddr,
     _max_length: usize,
-    host_key: String,
+    handler: EventHandler,
     receive_buffer_bytes: Option<usize>,
     shutdown: ShutdownSignal,
     mut out: SourceSender,
@@ -238,39 +289,93 @@ pub fn udp(
             socket,
             codecs::Decoder::new(
                 Framer::Bytes(BytesDecoder::new()),
-                Deserializer::Syslog(SyslogDeserializer),
+                Deserializer::Bytes(BytesDeserializer::new()),
             ),
         )
-        .take_until(shutdown)
-        .filter_map(|frame| {
-            let host_key = host_key.clone();
-            async move {
-                match frame {
-                    Ok(((mut events, _byte_size), received_from)) => {
-                        let received_from = received_from.ip().to_string().into();
-                        handle_events(&mut events, &host_key, Some(received_from));
-                        Some(events.remove(0))
+        .take_until(shutdown);
+
+        while let Some(frame) = stream.next().await {
+            match frame {
+                Ok(((events, _byte_size), received_from)) => {
+                    let count = events.len();
+                    let mut events = events.into_vec();
+                    let received_from = received_from.ip().to_string().into();
+                    let named = handler.handle(&mut events, Some(received_from));
+                    for (output, event) in named {
+                        if let Err(error) = out.send_batch_named(output, Some(event)).await {
+                            emit!(&StreamClosedError { error, count });
+                            return Err(());
+                        }
                     }
-                    Err(error) => {
-                        emit!(&SyslogUdpReadError { error });
-                        None
+                    if let Err(error) = out.send_batch(events).await {
+                        emit!(&StreamClosedError { error, count });
+                        return Err(());
                     }
                 }
+                Err(error) => emit!(&SyslogUdpReadError { error }),
             }
-        })
-        .boxed();
+        }
+
+        info!("Finished sending.");
+        Ok(())
+    })
+}
+
+/// Parses and enriches the events of the raw messages.
+#[derive(Debug, Clone)]
+struct EventHandler {
+    host_key: String,
+    route_parse_failures: bool,
+}
 
-        match out.send_event_stream(&mut stream).await {
-            Ok(()) => {
-                info!("Finished sending.");
-                Ok(())
+impl EventHandler {
+    /// Replaces the events of the raw messages with their parsed events. The events of the
+    /// messages that can't be parsed are returned to be sent to the `parse_failures` output if
+    /// routed there, or dropped otherwise.
+    fn handle(
+        &self,
+        events: &mut Vec<Event>,
+        default_host: Option<Bytes>,
+    ) -> Vec<(&'static str, Event)> {
+        let mut failures = Vec::new();
+        for event in std::mem::take(events) {
+            match parse_event(&event) {
+                Some(parsed) => events.extend(parsed),
+                None if self.route_parse_failures => failures.push(event),
+                None => {}
             }
-            Err(error) => {
-                error!(message = "Error sending line.", %error);
-                Err(())
+        }
+
+        handle_events(events, &self.host_key, default_host.clone());
+        handle_events(&mut failures, &self.host_key, default_host);
+        failures
+            .into_iter()
+            .map(|event| (PARSE_FAILURES, event))
+            .collect()
+    }
+}
+
+/// Parses the raw message of the event, the parsed events keeping its metadata.
+fn parse_event(event: &Event) -> Option<SmallVec<[Event; 1]>> {
+    let message = event
+        .as_log()
+        .get(log_schema().message_key())
+        .map(|message| message.coerce_to_bytes())
+        .unwrap_or_default();
+    match SyslogDeserializer.parse(message) {
+        Ok(mut parsed) => {
+            for parsed in &mut parsed {
+                *parsed.metadata_mut() = event.metadata().clone();
             }
+            Some(parsed)
         }
-    })
+        Err(error) => {
+            emit!(&SyslogParseError {
+                error: error.to_string()
+            });
+            None
+        }
+    }
 }
 
 fn handle_events(events: &mut [Event], host_key: &str, default_host: Option<Bytes>) {
@@ -453,6 +558,52 @@ mod test {
         assert!(matches!(config.mode, Mode::Unix { .. }));
     }
 
+    #[test]
+    fn config_framing_and_parse_failures() {
+        let config: SyslogConfig = toml::from_str(
+            r#"
+            mode = "tcp"
+            address = "127.0.0.1:1235"
+            framing = "non_transparent"
+            route_parse_failures = true
+          "#,
+        )
+        .unwrap();
+        assert_eq!(config.framing, Framing::NonTransparent);
+
+        let outputs = config.outputs();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[1].port, Some(PARSE_FAILURES.to_owned()));
+    }
+
+    #[test]
+    fn routes_parse_failures() {
+        let valid = r#"<13>1 2019-02-13T19:48:34+00:00 74794bfb6795 root 8449 - - i am foobar"#;
+        let invalid = "not much of a syslog message";
+        let handler = |route_parse_failures| EventHandler {
+            host_key: "host".into(),
+            route_parse_failures,
+        };
+
+        let mut events = vec![Event::from(valid), Event::from(invalid)];
+        let failures = handler(true).handle(&mut events, Some("127.0.0.1".into()));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()["appname"], "root".into());
+        assert_eq!(failures.len(), 1);
+        let (output, failure) = &failures[0];
+        assert_eq!(*output, PARSE_FAILURES);
+        assert_eq!(failure.as_log()[log_schema().message_key()], invalid.into());
+        assert_eq!(failure.as_log()["source_ip"], "127.0.0.1".into());
+        assert_eq!(
+            failure.as_log()[log_schema().source_type_key()],
+            "syslog".into()
+        );
+
+        let mut events = vec![Event::from(valid), Event::from(invalid)];
+        assert!(handler(false).handle(&mut events, None).is_empty());
+        assert_eq!(events.len(), 1);
+    }
+
     #[test]
     fn syslog_ng_network_syslog_protocol() {
         // this should also match rsyslog omfwd with template=RSYSLOG_SyslogProtocol23Format
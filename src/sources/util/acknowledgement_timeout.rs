@@ -0,0 +1,156 @@
+//! The timeout of the acknowledgements of the events sent by the sources, so that sinks unable to
+//! deliver them no longer stall the sources invisibly.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event::{BatchStatus, BatchStatusReceiver},
+    internal_events::{AcknowledgementReceived, AcknowledgementTimedOut},
+};
+
+/// What the sources do with the events not acknowledged in time.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AcknowledgementTimeoutAction {
+    /// Gives up on the events, as if they were delivered.
+    Drop,
+    /// Handles the events as if their delivery errored, so that the sources able to, like those
+    /// consuming from Kafka or SQS, receive them again.
+    Redeliver,
+}
+
+impl AcknowledgementTimeoutAction {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Drop => "drop",
+            Self::Redeliver => "redeliver",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AcknowledgementTimeoutConfig {
+    /// The acknowledgements are waited for indefinitely, if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Defaults to redelivering with the sources able to, and to dropping with the others.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_timeout: Option<AcknowledgementTimeoutAction>,
+}
+
+impl AcknowledgementTimeoutConfig {
+    pub fn build(&self, can_redeliver: bool) -> AcknowledgementTimeout {
+        let action = self.on_timeout.unwrap_or(if can_redeliver {
+            AcknowledgementTimeoutAction::Redeliver
+        } else {
+            AcknowledgementTimeoutAction::Drop
+        });
+        AcknowledgementTimeout {
+            timeout: self
+                .timeout_secs
+                .map(|secs| (Duration::from_secs(secs), action)),
+        }
+    }
+}
+
+/// How the wait for the acknowledgement of the events ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcknowledgementOutcome {
+    /// The events were acknowledged in time, with this status.
+    Acknowledged(BatchStatus),
+    /// The events were not acknowledged in time, and are handled with this action.
+    TimedOut(AcknowledgementTimeoutAction),
+}
+
+impl AcknowledgementOutcome {
+    /// The status of the events, those timed out being handled as delivered when dropped, and as
+    /// errored when redelivered.
+    pub const fn status(self) -> BatchStatus {
+        match self {
+            Self::Acknowledged(status) => status,
+            Self::TimedOut(AcknowledgementTimeoutAction::Drop) => BatchStatus::Delivered,
+            Self::TimedOut(AcknowledgementTimeoutAction::Redeliver) => BatchStatus::Errored,
+        }
+    }
+}
+
+/// Waits for the acknowledgements of the events, recording their latency.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AcknowledgementTimeout {
+    timeout: Option<(Duration, AcknowledgementTimeoutAction)>,
+}
+
+impl AcknowledgementTimeout {
+    /// The status of the events, or the one of the action on timeout if not acknowledged in time.
+    pub async fn wait(self, receiver: BatchStatusReceiver) -> BatchStatus {
+        self.wait_outcome(receiver).await.status()
+    }
+
+    /// Whether the events were acknowledged in time, for the sources handling the timeouts apart
+    /// from the errors.
+    pub async fn wait_outcome(self, receiver: BatchStatusReceiver) -> AcknowledgementOutcome {
+        let start = Instant::now();
+        let status = match self.timeout {
+            Some((timeout, action)) => match tokio::time::timeout(timeout, receiver).await {
+                Ok(status) => status,
+                Err(_) => {
+                    emit!(&AcknowledgementTimedOut {
+                        timeout,
+                        action: action.as_str(),
+                    });
+                    return AcknowledgementOutcome::TimedOut(action);
+                }
+            },
+            None => receiver.await,
+        };
+        emit!(&AcknowledgementReceived {
+            latency: start.elapsed(),
+            status,
+        });
+        AcknowledgementOutcome::Acknowledged(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::BatchNotifier;
+
+    fn timeout(on_timeout: Option<AcknowledgementTimeoutAction>) -> AcknowledgementTimeoutConfig {
+        AcknowledgementTimeoutConfig {
+            timeout_secs: Some(1),
+            on_timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn applies_the_action_on_timeout() {
+        tokio::time::pause();
+
+        let (_batch, receiver) = BatchNotifier::new_with_receiver();
+        let status = timeout(None).build(true).wait(receiver).await;
+        assert_eq!(status, BatchStatus::Errored);
+
+        let (_batch, receiver) = BatchNotifier::new_with_receiver();
+        let status = timeout(None).build(false).wait(receiver).await;
+        assert_eq!(status, BatchStatus::Delivered);
+
+        let (_batch, receiver) = BatchNotifier::new_with_receiver();
+        let status = timeout(Some(AcknowledgementTimeoutAction::Redeliver))
+            .build(false)
+            .wait(receiver)
+            .await;
+        assert_eq!(status, BatchStatus::Errored);
+    }
+
+    #[tokio::test]
+    async fn returns_the_status_in_time() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        drop(batch);
+        let status = timeout(None).build(true).wait(receiver).await;
+        assert_eq!(status, BatchStatus::Delivered);
+    }
+}
@@ -1,8 +1,13 @@
 use std::{future::Future, pin::Pin, task::Poll};
 
-use futures::{future::Shared, stream::FuturesOrdered, FutureExt, StreamExt};
+use futures::{
+    future::{BoxFuture, Shared},
+    stream::FuturesOrdered,
+    FutureExt, StreamExt,
+};
 use tokio::sync::mpsc;
 
+use super::{AcknowledgementOutcome, AcknowledgementTimeout};
 use crate::{event::BatchStatusReceiver, shutdown::ShutdownSignal};
 
 /// The `OrderedFinalizer` framework here is a mechanism for marking
@@ -18,9 +23,28 @@ impl<T: Send + 'static> OrderedFinalizer<T> {
     pub(crate) fn new(
         shutdown: Shared<ShutdownSignal>,
         apply_done: impl Fn(T) + Send + 'static,
+    ) -> Self {
+        Self::with_timeout(
+            shutdown,
+            AcknowledgementTimeout::default(),
+            move |_outcome, entry| apply_done(entry),
+        )
+    }
+
+    /// Finalizes the entries with their status, or with the timeout action if they are not
+    /// acknowledged in time.
+    pub(crate) fn with_timeout(
+        shutdown: Shared<ShutdownSignal>,
+        acknowledgement_timeout: AcknowledgementTimeout,
+        apply_done: impl FnMut(AcknowledgementOutcome, T) + Send + 'static,
     ) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
-        tokio::spawn(run_finalizer(shutdown, receiver, apply_done));
+        tokio::spawn(run_finalizer(
+            shutdown,
+            receiver,
+            acknowledgement_timeout,
+            apply_done,
+        ));
         Self {
             sender: Some(sender),
         }
@@ -38,7 +62,8 @@ impl<T: Send + 'static> OrderedFinalizer<T> {
 async fn run_finalizer<T>(
     shutdown: Shared<ShutdownSignal>,
     mut new_entries: mpsc::UnboundedReceiver<(BatchStatusReceiver, T)>,
-    apply_done: impl Fn(T),
+    acknowledgement_timeout: AcknowledgementTimeout,
+    mut apply_done: impl FnMut(AcknowledgementOutcome, T),
 ) {
     let mut status_receivers = FuturesOrdered::default();
 
@@ -48,14 +73,14 @@ async fn run_finalizer<T>(
             new_entry = new_entries.recv() => match new_entry {
                 Some((receiver, entry)) => {
                     status_receivers.push(FinalizerFuture {
-                        receiver,
+                        outcome: acknowledgement_timeout.wait_outcome(receiver).boxed(),
                         entry: Some(entry),
                     });
                 }
                 None => break,
             },
             finished = status_receivers.next(), if !status_receivers.is_empty() => match finished {
-                Some((_status, entry)) => apply_done(entry),
+                Some((outcome, entry)) => apply_done(outcome, entry),
                 // The is_empty guard above prevents this from being reachable.
                 None => unreachable!(),
             },
@@ -64,24 +89,24 @@ async fn run_finalizer<T>(
     // We've either seen a shutdown signal or the new entry sender was
     // closed. Wait for the last statuses to come in before indicating
     // we are done.
-    while let Some((_status, entry)) = status_receivers.next().await {
-        apply_done(entry);
+    while let Some((outcome, entry)) = status_receivers.next().await {
+        apply_done(outcome, entry);
     }
     drop(shutdown);
 }
 
 #[pin_project::pin_project]
 struct FinalizerFuture<T> {
-    receiver: BatchStatusReceiver,
+    outcome: BoxFuture<'static, AcknowledgementOutcome>,
     entry: Option<T>,
 }
 
 impl<T> Future for FinalizerFuture<T> {
-    type Output = (<BatchStatusReceiver as Future>::Output, T);
+    type Output = (AcknowledgementOutcome, T);
     fn poll(mut self: Pin<&mut Self>, ctx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
-        let status = futures::ready!(self.receiver.poll_unpin(ctx));
+        let outcome = futures::ready!(self.outcome.poll_unpin(ctx));
         // The use of this above in a `FuturesOrdered` will only take
         // this once before dropping the future.
-        Poll::Ready((status, self.entry.take().unwrap_or_else(|| unreachable!())))
+        Poll::Ready((outcome, self.entry.take().unwrap_or_else(|| unreachable!())))
     }
 }
@@ -2,35 +2,45 @@ use std::io::Read;
 
 use bytes::{Buf, Bytes};
 use flate2::read::{MultiGzDecoder, ZlibDecoder};
-use snap::raw::Decoder as SnappyDecoder;
+use snap::raw::{decompress_len, Decoder as SnappyDecoder};
 use warp::http::StatusCode;
 
 use super::error::ErrorMessage;
 use crate::internal_events::HttpDecompressError;
 
-pub fn decode(header: &Option<String>, mut body: Bytes) -> Result<Bytes, ErrorMessage> {
+pub fn decode(header: &Option<String>, body: Bytes) -> Result<Bytes, ErrorMessage> {
+    decode_limited(header, body, None)
+}
+
+/// Decodes the body as `decode` does, failing if the body decompressed by any of the encodings is
+/// larger than `max_size` bytes.
+pub fn decode_limited(
+    header: &Option<String>,
+    mut body: Bytes,
+    max_size: Option<usize>,
+) -> Result<Bytes, ErrorMessage> {
     if let Some(encodings) = header {
         for encoding in encodings.rsplit(',').map(str::trim) {
             body = match encoding {
                 "identity" => body,
-                "gzip" => {
-                    let mut decoded = Vec::new();
-                    MultiGzDecoder::new(body.reader())
-                        .read_to_end(&mut decoded)
+                "gzip" => read_limited(MultiGzDecoder::new(body.reader()), encoding, max_size)?,
+                "deflate" => read_limited(ZlibDecoder::new(body.reader()), encoding, max_size)?,
+                "zstd" => {
+                    let decoder = zstd::stream::read::Decoder::new(body.reader())
                         .map_err(|error| handle_decode_error(encoding, error))?;
-                    decoded.into()
+                    read_limited(decoder, encoding, max_size)?
                 }
-                "deflate" => {
-                    let mut decoded = Vec::new();
-                    ZlibDecoder::new(body.reader())
-                        .read_to_end(&mut decoded)
+                "snappy" => {
+                    // The decompressed length is in the header of the payload, so payloads
+                    // too large are rejected before decompressing them.
+                    let len = decompress_len(&body)
                         .map_err(|error| handle_decode_error(encoding, error))?;
-                    decoded.into()
+                    check_size(len, max_size)?;
+                    SnappyDecoder::new()
+                        .decompress_vec(&body)
+                        .map_err(|error| handle_decode_error(encoding, error))?
+                        .into()
                 }
-                "snappy" => SnappyDecoder::new()
-                    .decompress_vec(&body)
-                    .map_err(|error| handle_decode_error(encoding, error))?
-                    .into(),
                 encoding => {
                     return Err(ErrorMessage::new(
                         StatusCode::UNSUPPORTED_MEDIA_TYPE,
@@ -44,6 +54,36 @@ pub fn decode(header: &Option<String>, mut body: Bytes) -> Result<Bytes, ErrorMe
     Ok(body)
 }
 
+fn read_limited(
+    mut reader: impl Read,
+    encoding: &str,
+    max_size: Option<usize>,
+) -> Result<Bytes, ErrorMessage> {
+    let mut decoded = Vec::new();
+    match max_size {
+        // Reading one more byte than allowed tells payloads of exactly `max_size` bytes from
+        // larger ones, without decompressing the rest of them.
+        Some(max_size) => reader.take(max_size as u64 + 1).read_to_end(&mut decoded),
+        None => reader.read_to_end(&mut decoded),
+    }
+    .map_err(|error| handle_decode_error(encoding, error))?;
+    check_size(decoded.len(), max_size)?;
+    Ok(decoded.into())
+}
+
+fn check_size(size: usize, max_size: Option<usize>) -> Result<(), ErrorMessage> {
+    match max_size {
+        Some(max_size) if size > max_size => Err(ErrorMessage::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Decompressed payload is larger than the limit of {} bytes.",
+                max_size
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn handle_decode_error(encoding: &str, error: impl std::error::Error) -> ErrorMessage {
     emit!(&HttpDecompressError {
         encoding,
@@ -54,3 +94,54 @@ fn handle_decode_error(encoding: &str, error: impl std::error::Error) -> ErrorMe
         format!("Failed decompressing payload with {} decoder.", encoding),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    const BODY: &[u8] = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+
+    fn header(encodings: &str) -> Option<String> {
+        Some(encodings.to_owned())
+    }
+
+    #[test]
+    fn decodes_zstd() {
+        let body = zstd::stream::encode_all(BODY, 3).unwrap();
+        assert_eq!(decode(&header("zstd"), body.into()).unwrap(), BODY);
+    }
+
+    #[test]
+    fn limits_decompressed_size() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(BODY).unwrap();
+        let gzip = Bytes::from(encoder.finish().unwrap());
+        let zstd = Bytes::from(zstd::stream::encode_all(BODY, 3).unwrap());
+        let snappy = Bytes::from(snap::raw::Encoder::new().compress_vec(BODY).unwrap());
+
+        for (encoding, body) in [("gzip", gzip), ("zstd", zstd), ("snappy", snappy)] {
+            assert_eq!(
+                decode_limited(&header(encoding), body.clone(), Some(BODY.len())).unwrap(),
+                BODY
+            );
+            let error = decode_limited(&header(encoding), body, Some(BODY.len() - 1)).unwrap_err();
+            assert_eq!(error.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+    }
+
+    #[test]
+    fn does_not_limit_uncompressed_body() {
+        // Only decompressed payloads are limited, the size of the request being limited by the
+        // server.
+        let body = Bytes::from_static(BODY);
+        assert_eq!(
+            decode_limited(&header("identity"), body.clone(), Some(1)).unwrap(),
+            body
+        );
+        assert_eq!(decode_limited(&None, body.clone(), Some(1)).unwrap(), body);
+    }
+}
@@ -19,12 +19,13 @@ use warp::{
 
 use super::{
     auth::{HttpSourceAuth, HttpSourceAuthConfig},
-    encoding::decode,
+    encoding::decode_limited,
     error::ErrorMessage,
 };
 use crate::{
     config::{AcknowledgementsConfig, SourceContext},
     internal_events::{HttpBadRequest, HttpBytesReceived, HttpEventsReceived},
+    sources::util::AcknowledgementTimeout,
     tls::{MaybeTlsSettings, TlsConfig},
     SourceSender,
 };
@@ -39,6 +40,11 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         path: &str,
     ) -> Result<Vec<Event>, ErrorMessage>;
 
+    /// The largest size in bytes of the request bodies once decompressed, if limited.
+    fn max_decompressed_size(&self) -> Option<usize> {
+        None
+    }
+
     fn run(
         self,
         address: SocketAddr,
@@ -54,6 +60,7 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
         let auth = HttpSourceAuth::try_from(auth.as_ref())?;
         let path = path.to_owned();
         let acknowledgements = cx.do_acknowledgements(&acknowledgements);
+        let acknowledgement_timeout = cx.acknowledgement_timeout;
         Ok(Box::pin(async move {
             let span = crate::trace::current_span();
             let mut filter: BoxedFilter<()> = warp::post().boxed();
@@ -97,7 +104,9 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
 
                         let events = auth
                             .is_valid(&auth_header)
-                            .and_then(|()| decode(&encoding_header, body))
+                            .and_then(|()| {
+                                decode_limited(&encoding_header, body, self.max_decompressed_size())
+                            })
                             .and_then(|body| {
                                 self.build_events(body, headers, query_parameters, path.as_str())
                             })
@@ -111,7 +120,12 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                                 events
                             });
 
-                        handle_request(events, acknowledgements, cx.out.clone())
+                        handle_request(
+                            events,
+                            acknowledgements,
+                            acknowledgement_timeout,
+                            cx.out.clone(),
+                        )
                     },
                 )
                 .with(warp::trace(move |_info| span.clone()));
@@ -154,6 +168,7 @@ impl warp::reject::Reject for RejectShuttingDown {}
 async fn handle_request(
     events: Result<Vec<Event>, ErrorMessage>,
     acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
     mut out: SourceSender,
 ) -> Result<impl warp::Reply, Rejection> {
     match events {
@@ -168,7 +183,7 @@ async fn handle_request(
                     error!(message = "Tried to send the following event.", %error);
                     warp::reject::custom(RejectShuttingDown)
                 })
-                .and_then(|_| handle_batch_status(receiver))
+                .and_then(|_| handle_batch_status(receiver, acknowledgement_timeout))
                 .await
         }
         Err(error) => {
@@ -183,10 +198,11 @@ async fn handle_request(
 
 async fn handle_batch_status(
     receiver: Option<BatchStatusReceiver>,
+    acknowledgement_timeout: AcknowledgementTimeout,
 ) -> Result<impl warp::Reply, Rejection> {
     match receiver {
         None => Ok(warp::reply()),
-        Some(receiver) => match receiver.await {
+        Some(receiver) => match acknowledgement_timeout.wait(receiver).await {
             BatchStatus::Delivered => Ok(warp::reply()),
             BatchStatus::Errored => Err(warp::reject::custom(ErrorMessage::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -1,3 +1,4 @@
+mod acknowledgement_timeout;
 #[cfg(any(feature = "sources-http"))]
 mod body_decoding;
 #[cfg(any(
@@ -7,6 +8,7 @@ mod body_decoding;
 mod codecs;
 mod encoding_config;
 #[cfg(any(
+    feature = "sources-azure_event_hubs",
     feature = "sources-file",
     feature = "sources-kafka",
     feature = "sources-splunk_hec"
@@ -32,6 +34,10 @@ mod unix_stream;
 #[cfg(any(feature = "sources-utils-tls", feature = "sources-vector"))]
 mod wrappers;
 
+pub use acknowledgement_timeout::{
+    AcknowledgementOutcome, AcknowledgementTimeout, AcknowledgementTimeoutAction,
+    AcknowledgementTimeoutConfig,
+};
 #[cfg(any(
     all(feature = "sources-utils-tls", feature = "listenfd"),
     feature = "codecs",
@@ -1,5 +1,6 @@
 use std::{convert::TryFrom, time::Duration};
 
+use bytes::Bytes;
 use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -62,3 +63,164 @@ pub enum Error {
         source: regex::Error,
     },
 }
+
+/// Built-in aggregations of the multiline messages of common formats, so that their patterns
+/// don't have to be written for each application.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultilinePreset {
+    /// Java stack traces, along with the line of the message they were logged with.
+    Java,
+    /// Python tracebacks.
+    Python,
+    /// Lines written by Docker's `json-file` logging driver, which splits the messages of more
+    /// than 16KiB into partial ones.
+    DockerJsonFile,
+}
+
+impl MultilinePreset {
+    pub fn config(self, timeout_ms: u64) -> line_agg::Config {
+        let (start_pattern, condition_pattern, mode) = match self {
+            // Lines following a message are part of its stack trace if they are frames, chained
+            // causes, or the exception itself, for example `java.lang.IllegalStateException: ..`.
+            Self::Java => (
+                r"^[^\s]",
+                r"^(?:\s+at\s|\s+\.\.\.\s+\d+\s+(?:more|common frames omitted)|\s*Caused by:|\s*Suppressed:|(?:[\w$]+\.)+[\w$]*(?:Exception|Error|Throwable)(?::|$))",
+                line_agg::Mode::ContinueThrough,
+            ),
+            // The frames are indented, and followed by the exception.
+            Self::Python => (
+                r"^Traceback \(most recent call last\):",
+                r"^\s",
+                line_agg::Mode::ContinuePast,
+            ),
+            // Partial messages are those whose `log` doesn't end with a newline.
+            Self::DockerJsonFile => (
+                r#""log":"(?:(?:[^"\\]|\\.)*(?:[^"\\]|\\[^n]))?""#,
+                r#""log":"(?:[^"\\]|\\.)*\\n""#,
+                line_agg::Mode::HaltWith,
+            ),
+        };
+
+        line_agg::Config {
+            start_pattern: Regex::new(start_pattern).expect("preset patterns are valid"),
+            condition_pattern: Regex::new(condition_pattern).expect("preset patterns are valid"),
+            mode,
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    /// Merges the lines aggregated by the preset into a single message.
+    pub fn merge(self, text: Bytes) -> Bytes {
+        match self {
+            Self::Java | Self::Python => text,
+            Self::DockerJsonFile => merge_docker_partials(text),
+        }
+    }
+}
+
+/// Concatenates the `log` of partial messages into the last one, which keeps its other fields,
+/// so that the merged message can be parsed like the complete ones. Lines that aren't written by
+/// the `json-file` driver are left as they are.
+fn merge_docker_partials(text: Bytes) -> Bytes {
+    if !text.contains(&b'\n') {
+        return text;
+    }
+
+    let mut log = String::new();
+    let mut last = None;
+    for line in text.split(|&byte| byte == b'\n') {
+        match serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(line) {
+            Ok(object) => {
+                if let Some(serde_json::Value::String(part)) = object.get("log") {
+                    log.push_str(part);
+                }
+                last = Some(object);
+            }
+            Err(_) => return text.clone(),
+        }
+    }
+
+    match last {
+        Some(mut object) => {
+            // Replaced in place, to keep the order of the fields.
+            object.insert("log".to_owned(), log.into());
+            serde_json::to_vec(&object).map_or(text, Bytes::from)
+        }
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::line_agg::{LineAgg, Logic};
+
+    async fn aggregate(preset: MultilinePreset, lines: &[&'static str]) -> Vec<String> {
+        let stream = futures::stream::iter(
+            lines
+                .iter()
+                .map(|line| ("test.log", Bytes::from_static(line.as_bytes()), ())),
+        );
+        LineAgg::new(stream, Logic::new(preset.config(10)))
+            .map(|(_, text, _)| String::from_utf8(preset.merge(text).to_vec()).unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn java_preset() {
+        let lines = [
+            "2022-03-01 10:00:00 ERROR Request failed",
+            "java.lang.IllegalStateException: boom",
+            "\tat com.example.Foo.bar(Foo.java:10)",
+            "Caused by: java.io.IOException: closed",
+            "\tat com.example.Foo.baz(Foo.java:20)",
+            "\t... 3 more",
+            "2022-03-01 10:00:01 INFO Next request",
+        ];
+        assert_eq!(
+            aggregate(MultilinePreset::Java, &lines).await,
+            vec![lines[..6].join("\n"), lines[6].to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn python_preset() {
+        let lines = [
+            "ERROR:root:Request failed",
+            "Traceback (most recent call last):",
+            "  File \"app.py\", line 3, in <module>",
+            "    1 / 0",
+            "ZeroDivisionError: division by zero",
+            "INFO:root:Next request",
+        ];
+        assert_eq!(
+            aggregate(MultilinePreset::Python, &lines).await,
+            vec![
+                lines[0].to_owned(),
+                lines[1..5].join("\n"),
+                lines[5].to_owned()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn docker_json_file_preset() {
+        let lines = [
+            r#"{"log":"first \"quoted\" ","stream":"stdout","time":"2022-03-01T10:00:00.1Z"}"#,
+            r#"{"log":"second \\","stream":"stdout","time":"2022-03-01T10:00:00.2Z"}"#,
+            r#"{"log":"last\n","stream":"stdout","time":"2022-03-01T10:00:00.3Z"}"#,
+            r#"{"log":"whole\n","stream":"stdout","time":"2022-03-01T10:00:00.4Z"}"#,
+        ];
+        assert_eq!(
+            aggregate(MultilinePreset::DockerJsonFile, &lines).await,
+            vec![
+                r#"{"log":"first \"quoted\" second \\last\n","stream":"stdout","time":"2022-03-01T10:00:00.3Z"}"#,
+                lines[3],
+            ]
+        );
+    }
+}
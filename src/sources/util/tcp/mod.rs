@@ -31,6 +31,7 @@ use crate::{
         TcpBytesReceived, TcpSendAckError, TcpSocketTlsConnectionError,
     },
     shutdown::ShutdownSignal,
+    source_sender::ClosedError,
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
     SourceSender,
@@ -112,6 +113,13 @@ where
 
     fn handle_events(&self, _events: &mut [Event], _host: Bytes) {}
 
+    /// Handles the events, returning the ones to send to the named outputs of the source with
+    /// the names of their outputs. The others are sent to the default output.
+    fn route_events(&self, events: &mut Vec<Event>, host: Bytes) -> Vec<(&'static str, Event)> {
+        self.handle_events(events, host);
+        Vec::new()
+    }
+
     fn build_acker(&self, item: &[Self::Item]) -> Self::Acker;
 
     fn run(
@@ -334,8 +342,8 @@ async fn handle_stream<T>(
                             }
                         }
 
-                        source.handle_events(&mut events, host.clone());
-                        match out.send_batch(events).await {
+                        let named = source.route_events(&mut events, host.clone());
+                        match send_events(&mut out, named, events).await {
                             Ok(_) => {
                                 let ack = match receiver {
                                     None => TcpSourceAck::Ack,
@@ -455,6 +463,18 @@ where
     }
 }
 
+/// Sends the events to their named outputs, then the others to the default output.
+async fn send_events(
+    out: &mut SourceSender,
+    named: Vec<(&'static str, Event)>,
+    events: Vec<Event>,
+) -> Result<(), ClosedError> {
+    for (output, event) in named {
+        out.send_batch_named(output, Some(event)).await?;
+    }
+    out.send_batch(events).await
+}
+
 #[cfg(test)]
 mod test {
     use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
@@ -30,11 +30,16 @@ use crate::{
 /// Returns a `Source` object corresponding to a Unix domain stream socket.
 /// Passing in different functions for `decoder` and `handle_events` can allow
 /// for different source-specific logic (such as decoding syslog messages in the
-/// syslog source).
+/// syslog source). The events returned by `handle_events` are sent to the named
+/// outputs they are returned with, rather than to the default output.
 pub fn build_unix_stream_source(
     listen_path: PathBuf,
     decoder: codecs::Decoder,
-    handle_events: impl Fn(&mut [Event], Option<Bytes>) + Clone + Send + Sync + 'static,
+    handle_events: impl Fn(&mut Vec<Event>, Option<Bytes>) -> Vec<(&'static str, Event)>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> Source {
@@ -91,16 +96,23 @@ pub fn build_unix_stream_source(
 
                     while let Some(result) = stream.next().await {
                         match result {
-                            Ok((mut events, _byte_size)) => {
+                            Ok((events, _byte_size)) => {
                                 emit!(&SocketEventsReceived {
                                     mode: SocketMode::Unix,
                                     byte_size: events.size_of(),
                                     count: events.len(),
                                 });
 
-                                handle_events(&mut events, received_from.clone());
-
                                 let count = events.len();
+                                let mut events = events.into_vec();
+                                let named = handle_events(&mut events, received_from.clone());
+                                for (output, event) in named {
+                                    if let Err(error) =
+                                        out.send_batch_named(output, Some(event)).await
+                                    {
+                                        emit!(&StreamClosedError { error, count });
+                                    }
+                                }
                                 if let Err(error) = out.send_batch(events).await {
                                     emit!(&StreamClosedError { error, count });
                                 }
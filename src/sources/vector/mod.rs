@@ -92,6 +92,10 @@ impl SourceConfig for VectorConfig {
     fn can_acknowledge(&self) -> bool {
         true
     }
+
+    fn can_time_out_acknowledgements(&self) -> bool {
+        matches!(self, VectorConfig::V2(_))
+    }
 }
 
 #[cfg(test)]
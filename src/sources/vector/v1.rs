@@ -262,6 +262,7 @@ mod test {
                 out: tx,
                 proxy: Default::default(),
                 acknowledgements: false,
+                acknowledgement_timeout: Default::default(),
                 schema_definitions: HashMap::default(),
             })
             .await
@@ -301,6 +302,7 @@ mod test {
                 out: tx,
                 proxy: Default::default(),
                 acknowledgements: false,
+                acknowledgement_timeout: Default::default(),
                 schema_definitions: HashMap::default(),
             })
             .await
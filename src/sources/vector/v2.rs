@@ -2,9 +2,8 @@ use std::net::SocketAddr;
 
 use futures::{FutureExt, StreamExt, TryFutureExt};
 use serde::{Deserialize, Serialize};
-use tokio::net::TcpStream;
 use tonic::{
-    transport::{server::Connected, Certificate, Server},
+    transport::{server::Connected, Server},
     Request, Response, Status,
 };
 use tracing_futures::Instrument;
@@ -19,8 +18,11 @@ use crate::{
     proto::vector as proto,
     serde::bool_or_struct,
     shutdown::ShutdownSignalToken,
-    sources::{util::AfterReadExt as _, Source},
-    tls::{MaybeTlsIncomingStream, MaybeTlsSettings, TlsConfig},
+    sources::{
+        util::{AcknowledgementTimeout, AfterReadExt as _},
+        Source,
+    },
+    tls::{MaybeTlsSettings, TlsConfig},
     SourceSender,
 };
 
@@ -28,20 +30,17 @@ use crate::{
 pub struct Service {
     pipeline: SourceSender,
     acknowledgements: bool,
+    acknowledgement_timeout: AcknowledgementTimeout,
+    /// The maximum size of the compressed events once decompressed.
+    max_decompressed_size: usize,
 }
 
-#[tonic::async_trait]
-impl proto::Service for Service {
-    async fn push_events(
+impl Service {
+    async fn handle_events(
         &self,
-        request: Request<proto::PushEventsRequest>,
+        request: proto::PushEventsRequest,
     ) -> Result<Response<proto::PushEventsResponse>, Status> {
-        let mut events: Vec<Event> = request
-            .into_inner()
-            .events
-            .into_iter()
-            .map(Event::from)
-            .collect();
+        let mut events: Vec<Event> = request.events.into_iter().map(Event::from).collect();
 
         let count = events.len();
         let byte_size = events.size_of();
@@ -58,11 +57,50 @@ impl proto::Service for Service {
                 emit!(&StreamClosedError { error, count });
                 Status::unavailable(message)
             })
-            .and_then(|_| handle_batch_status(receiver))
+            .and_then(|_| handle_batch_status(receiver, self.acknowledgement_timeout))
             .await?;
 
         Ok(Response::new(proto::PushEventsResponse {}))
     }
+}
+
+#[tonic::async_trait]
+impl proto::Service for Service {
+    async fn push_events(
+        &self,
+        request: Request<proto::PushEventsRequest>,
+    ) -> Result<Response<proto::PushEventsResponse>, Status> {
+        self.handle_events(request.into_inner()).await
+    }
+
+    async fn push_compressed_events(
+        &self,
+        request: Request<proto::PushCompressedEventsRequest>,
+    ) -> Result<Response<proto::PushEventsResponse>, Status> {
+        let request = request
+            .into_inner()
+            .decompress(self.max_decompressed_size)
+            .map_err(Status::invalid_argument)?;
+        self.handle_events(request).await
+    }
+
+    async fn negotiate(
+        &self,
+        request: Request<proto::NegotiateRequest>,
+    ) -> Result<Response<proto::NegotiateResponse>, Status> {
+        let request = request.into_inner();
+        // All the known compressions are supported, so the preferred one of the sink is used.
+        let compression = request
+            .compressions
+            .into_iter()
+            .find_map(proto::Compression::from_i32)
+            .unwrap_or(proto::Compression::None);
+
+        Ok(Response::new(proto::NegotiateResponse {
+            protocol_version: request.protocol_version.min(proto::PROTOCOL_VERSION),
+            compression: compression.into(),
+        }))
+    }
 
     // TODO: figure out a way to determine if the current Vector instance is "healthy".
     async fn health_check(
@@ -77,9 +115,12 @@ impl proto::Service for Service {
     }
 }
 
-async fn handle_batch_status(receiver: Option<BatchStatusReceiver>) -> Result<(), Status> {
+async fn handle_batch_status(
+    receiver: Option<BatchStatusReceiver>,
+    acknowledgement_timeout: AcknowledgementTimeout,
+) -> Result<(), Status> {
     let status = match receiver {
-        Some(receiver) => receiver.await,
+        Some(receiver) => acknowledgement_timeout.wait(receiver).await,
         None => BatchStatus::Delivered,
     };
 
@@ -100,12 +141,20 @@ pub struct VectorConfig {
     tls: Option<TlsConfig>,
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: AcknowledgementsConfig,
+    /// The maximum size of the compressed events of a request once decompressed, the larger
+    /// requests being rejected.
+    #[serde(default = "default_max_decompressed_size_bytes")]
+    max_decompressed_size_bytes: usize,
 }
 
 const fn default_shutdown_timeout_secs() -> u64 {
     30
 }
 
+const fn default_max_decompressed_size_bytes() -> usize {
+    100 * 1024 * 1024
+}
+
 impl GenerateConfig for VectorConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
@@ -113,6 +162,7 @@ impl GenerateConfig for VectorConfig {
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             tls: None,
             acknowledgements: Default::default(),
+            max_decompressed_size_bytes: default_max_decompressed_size_bytes(),
         })
         .unwrap()
     }
@@ -123,7 +173,14 @@ impl VectorConfig {
         let tls_settings = MaybeTlsSettings::from_config(&self.tls, true)?;
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
-        let source = run(self.address, tls_settings, cx, acknowledgements).map_err(|error| {
+        let source = run(
+            self.address,
+            tls_settings,
+            cx,
+            acknowledgements,
+            self.max_decompressed_size_bytes,
+        )
+        .map_err(|error| {
             error!(message = "Source future failed.", %error);
         });
 
@@ -148,12 +205,15 @@ async fn run(
     tls_settings: MaybeTlsSettings,
     cx: SourceContext,
     acknowledgements: bool,
+    max_decompressed_size: usize,
 ) -> crate::Result<()> {
     let span = crate::trace::current_span();
 
     let service = proto::Server::new(Service {
         pipeline: cx.out,
         acknowledgements,
+        acknowledgement_timeout: cx.acknowledgement_timeout,
+        max_decompressed_size,
     });
     let (tx, rx) = tokio::sync::oneshot::channel::<ShutdownSignalToken>();
 
@@ -182,35 +242,16 @@ async fn run(
     Ok(())
 }
 
-#[derive(Clone)]
-pub struct MaybeTlsConnectInfo {
-    pub remote_addr: SocketAddr,
-    pub peer_certs: Option<Vec<Certificate>>,
-}
-
-impl Connected for MaybeTlsIncomingStream<TcpStream> {
-    type ConnectInfo = MaybeTlsConnectInfo;
-
-    fn connect_info(&self) -> Self::ConnectInfo {
-        MaybeTlsConnectInfo {
-            remote_addr: self.peer_addr(),
-            peer_certs: self
-                .ssl_stream()
-                .and_then(|s| s.ssl().peer_cert_chain())
-                .map(|s| {
-                    s.into_iter()
-                        .filter_map(|c| c.to_pem().ok())
-                        .map(Certificate::from_pem)
-                        .collect()
-                }),
-        }
-    }
-}
-
 #[cfg(feature = "sinks-vector")]
 #[cfg(test)]
 mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
     use vector_common::assert_event_data_eq;
+    use vector_core::event::EventArray;
 
     use super::*;
     use crate::{
@@ -220,8 +261,7 @@ mod tests {
         SourceSender,
     };
 
-    #[tokio::test]
-    async fn receive_message() {
+    async fn run_test(sink_config: &str) {
         let addr = test_util::next_addr();
         let config = format!(r#"address = "{}""#, addr);
         let source: VectorConfig = toml::from_str(&config).unwrap();
@@ -235,19 +275,172 @@ mod tests {
         tokio::spawn(server);
         test_util::wait_for_tcp(addr).await;
 
+        let (events, stream) = test_util::random_events_with_stream(100, 100, None);
+        run_sink(addr, sink_config, stream).await;
+        components::SOURCE_TESTS.assert(&components::TCP_SOURCE_TAGS);
+
+        let output = test_util::collect_ready(rx).await;
+        assert_event_data_eq!(events, output);
+    }
+
+    async fn run_sink(
+        addr: SocketAddr,
+        sink_config: &str,
+        stream: impl futures::Stream<Item = EventArray> + Send,
+    ) {
         // Ideally, this would be a fully custom agent to send the data,
         // but the sink side already does such a test and this is good
         // to ensure interoperability.
-        let config = format!(r#"address = "{}""#, addr);
+        let config = format!(
+            r#"address = "{}"
+            {}"#,
+            addr, sink_config
+        );
         let sink: SinkConfig = toml::from_str(&config).unwrap();
         let cx = SinkContext::new_test();
         let (sink, _) = sink.build(cx).await.unwrap();
+        sink.run(stream).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn receive_message() {
+        run_test("").await;
+    }
+
+    #[tokio::test]
+    async fn receive_compressed_message() {
+        run_test(
+            r#"compression = true
+            connections = 2"#,
+        )
+        .await;
+    }
+
+    /// A source not decompressing the events. It still negotiates the compression, as a source
+    /// behind a load balancer can when another source replies to the negotiation, unless it's a
+    /// source of the first version of the protocol.
+    struct WithoutDecompression {
+        service: Service,
+        negotiates: bool,
+        compressed_requests: Arc<AtomicUsize>,
+    }
+
+    #[tonic::async_trait]
+    impl proto::Service for WithoutDecompression {
+        async fn push_events(
+            &self,
+            request: Request<proto::PushEventsRequest>,
+        ) -> Result<Response<proto::PushEventsResponse>, Status> {
+            proto::Service::push_events(&self.service, request).await
+        }
+
+        async fn push_compressed_events(
+            &self,
+            _: Request<proto::PushCompressedEventsRequest>,
+        ) -> Result<Response<proto::PushEventsResponse>, Status> {
+            self.compressed_requests.fetch_add(1, Ordering::Relaxed);
+            Err(Status::unimplemented("not implemented"))
+        }
+
+        async fn negotiate(
+            &self,
+            request: Request<proto::NegotiateRequest>,
+        ) -> Result<Response<proto::NegotiateResponse>, Status> {
+            if self.negotiates {
+                proto::Service::negotiate(&self.service, request).await
+            } else {
+                Err(Status::unimplemented("not implemented"))
+            }
+        }
+
+        async fn health_check(
+            &self,
+            request: Request<proto::HealthCheckRequest>,
+        ) -> Result<Response<proto::HealthCheckResponse>, Status> {
+            proto::Service::health_check(&self.service, request).await
+        }
+    }
+
+    /// Sends compressed events to a source not decompressing them, returning how many compressed
+    /// requests it rejected.
+    async fn run_without_decompression(negotiates: bool) -> usize {
+        let addr = test_util::next_addr();
+        let (tx, rx) = SourceSender::new_test();
+        let compressed_requests = Arc::new(AtomicUsize::new(0));
+        let service = proto::Server::new(WithoutDecompression {
+            service: Service {
+                pipeline: tx,
+                acknowledgements: false,
+                acknowledgement_timeout: AcknowledgementTimeout::default(),
+                max_decompressed_size: default_max_decompressed_size_bytes(),
+            },
+            negotiates,
+            compressed_requests: Arc::clone(&compressed_requests),
+        });
+        tokio::spawn(Server::builder().add_service(service).serve(addr));
+        test_util::wait_for_tcp(addr).await;
 
         let (events, stream) = test_util::random_events_with_stream(100, 100, None);
-        sink.run(stream).await.unwrap();
-        components::SOURCE_TESTS.assert(&components::TCP_SOURCE_TAGS);
+        run_sink(addr, "compression = true", stream).await;
 
         let output = test_util::collect_ready(rx).await;
         assert_event_data_eq!(events, output);
+        compressed_requests.load(Ordering::Relaxed)
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_uncompressed_events() {
+        assert!(run_without_decompression(true).await > 0);
+    }
+
+    #[tokio::test]
+    async fn sends_uncompressed_events_to_first_version_sources() {
+        assert_eq!(run_without_decompression(false).await, 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_too_large_decompressed_events() {
+        let (tx, _rx) = SourceSender::new_test();
+        let service = Service {
+            pipeline: tx,
+            acknowledgements: false,
+            acknowledgement_timeout: AcknowledgementTimeout::default(),
+            max_decompressed_size: 1024,
+        };
+        let (events, _) = test_util::random_events_with_stream(2048, 1, None);
+        let request = proto::PushEventsRequest {
+            events: events.into_iter().map(Into::into).collect(),
+        };
+        let request =
+            proto::PushCompressedEventsRequest::new(&request, proto::Compression::Zstd).unwrap();
+
+        let status = proto::Service::push_compressed_events(&service, Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn negotiates_protocol() {
+        let (tx, _rx) = SourceSender::new_test();
+        let service = Service {
+            pipeline: tx,
+            acknowledgements: false,
+            acknowledgement_timeout: AcknowledgementTimeout::default(),
+            max_decompressed_size: default_max_decompressed_size_bytes(),
+        };
+        let response = proto::Service::negotiate(
+            &service,
+            Request::new(proto::NegotiateRequest {
+                protocol_version: proto::PROTOCOL_VERSION + 1,
+                compressions: vec![42, proto::Compression::Zstd.into()],
+            }),
+        )
+        .await
+        .unwrap()
+        .into_inner();
+
+        assert_eq!(response.protocol_version, proto::PROTOCOL_VERSION);
+        assert_eq!(response.compression, i32::from(proto::Compression::Zstd));
     }
 }
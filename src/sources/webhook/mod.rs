@@ -0,0 +1,539 @@
+//! The `webhook` source receives the webhooks of a provider, rejecting deliveries whose signature
+//! doesn't match the shared secret. Unlike the `http` source, it answers the way each provider
+//! expects, for example replying to the challenge Slack sends when an endpoint is registered.
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use vector_core::ByteSizeOf;
+use warp::{
+    filters::{path::FullPath, BoxedFilter},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    reply::Response,
+    Filter, Reply,
+};
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, GenerateConfig, Output, Resource,
+        SourceConfig, SourceContext, SourceDescription,
+    },
+    event::{BatchNotifier, BatchStatus, Event, LogEvent, Value},
+    internal_events::{
+        HttpBadRequest, HttpBytesReceived, HttpEventsReceived, StreamClosedError,
+        WebhookSignatureError,
+    },
+    serde::bool_or_struct,
+    tls::{MaybeTlsSettings, TlsConfig},
+    SourceSender,
+};
+
+mod provider;
+
+pub use self::provider::SignatureError;
+use self::provider::{Provider, Verifier};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    address: SocketAddr,
+    provider: Provider,
+    secret: String,
+    #[serde(default = "default_path")]
+    path: String,
+    #[serde(default = "default_timestamp_tolerance_secs")]
+    timestamp_tolerance_secs: u64,
+    #[serde(default)]
+    unwrap_batches: bool,
+    #[serde(default)]
+    headers: Vec<String>,
+    tls: Option<TlsConfig>,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_path() -> String {
+    "/".to_owned()
+}
+
+const fn default_timestamp_tolerance_secs() -> u64 {
+    300
+}
+
+inventory::submit! {
+    SourceDescription::new::<WebhookConfig>("webhook")
+}
+
+impl GenerateConfig for WebhookConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"address = "0.0.0.0:8080"
+            provider = "github"
+            secret = "${WEBHOOK_SECRET}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "webhook")]
+impl SourceConfig for WebhookConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, true)?;
+        let source = Arc::new(WebhookSource {
+            provider: self.provider,
+            verifier: Verifier::new(self.provider, &self.secret, self.timestamp_tolerance_secs)?,
+            unwrap_batches: self.unwrap_batches,
+            headers: self.headers.clone(),
+            protocol: tls.http_protocol_name(),
+            acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+        });
+
+        let mut filter: BoxedFilter<()> = warp::post().boxed();
+        for segment in self.path.split('/').filter(|segment| !segment.is_empty()) {
+            filter = filter.and(warp::path(segment.to_owned())).boxed();
+        }
+        let out = cx.out;
+        let routes = filter
+            .and(warp::path::end())
+            .and(warp::path::full())
+            .and(warp::header::headers_cloned())
+            .and(warp::body::bytes())
+            .and_then(move |path: FullPath, headers: HeaderMap, body: Bytes| {
+                let source = Arc::clone(&source);
+                let out = out.clone();
+                async move {
+                    Ok::<_, Infallible>(source.handle(path.as_str(), &headers, body, out).await)
+                }
+            });
+
+        let listener = tls.bind(&self.address).await?;
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let span = crate::trace::current_span();
+            warp::serve(routes.with(warp::trace(move |_info| span.clone())))
+                .serve_incoming_with_graceful_shutdown(
+                    listener.accept_stream(),
+                    shutdown.map(|_| ()),
+                )
+                .await;
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+struct WebhookSource {
+    provider: Provider,
+    verifier: Verifier,
+    unwrap_batches: bool,
+    headers: Vec<String>,
+    protocol: &'static str,
+    acknowledgements: bool,
+}
+
+impl WebhookSource {
+    async fn handle(
+        &self,
+        http_path: &str,
+        headers: &HeaderMap,
+        body: Bytes,
+        mut out: SourceSender,
+    ) -> Response {
+        emit!(&HttpBytesReceived {
+            byte_size: body.len(),
+            http_path,
+            protocol: self.protocol,
+        });
+
+        if let Err(error) = self.verifier.verify(headers, &body, Utc::now().timestamp()) {
+            emit!(&WebhookSignatureError {
+                error: &error,
+                provider: self.provider.name(),
+            });
+            return match error {
+                SignatureError::Crypto { .. } => error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Could not verify signature",
+                ),
+                _ => error_response(StatusCode::UNAUTHORIZED, &error.to_string()),
+            };
+        }
+
+        let payload = match parse_payload(headers, &body) {
+            Ok(payload) => payload,
+            Err(message) => {
+                emit!(&HttpBadRequest {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    message: &message,
+                });
+                return error_response(StatusCode::BAD_REQUEST, &message);
+            }
+        };
+
+        // Slack verifies endpoints by having them echo a challenge, which isn't an event.
+        if self.provider == Provider::Slack {
+            if let Some(challenge) = slack_challenge(&payload) {
+                return warp::reply::json(&serde_json::json!({ "challenge": challenge }))
+                    .into_response();
+            }
+        }
+
+        let mut events = self.build_events(payload, headers);
+        let count = events.len();
+        emit!(&HttpEventsReceived {
+            count,
+            byte_size: events.size_of(),
+            http_path,
+            protocol: self.protocol,
+        });
+
+        let receiver = BatchNotifier::maybe_apply_to_events(self.acknowledgements, &mut events);
+        if let Err(error) = out.send_batch(events).await {
+            emit!(&StreamClosedError { error, count });
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, "Shutting down");
+        }
+
+        match receiver {
+            None => StatusCode::OK.into_response(),
+            Some(receiver) => match receiver.await {
+                BatchStatus::Delivered => StatusCode::OK.into_response(),
+                // Providers redeliver on server errors, but not on client ones.
+                BatchStatus::Errored => error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Error delivering contents to sink",
+                ),
+                BatchStatus::Rejected => error_response(
+                    StatusCode::BAD_REQUEST,
+                    "Contents failed to deliver to sink",
+                ),
+            },
+        }
+    }
+
+    fn build_events(&self, payload: Value, headers: &HeaderMap) -> Vec<Event> {
+        let payloads = if self.unwrap_batches {
+            unwrap_batch(payload, self.provider.batch_field())
+        } else {
+            vec![payload]
+        };
+
+        let now = Utc::now();
+        payloads
+            .into_iter()
+            .map(|payload| {
+                let mut log = match payload {
+                    Value::Object(fields) => LogEvent::from(fields),
+                    value => {
+                        let mut log = LogEvent::default();
+                        log.insert(log_schema().message_key(), value);
+                        log
+                    }
+                };
+                for name in &self.headers {
+                    let value = headers.get(name).map(HeaderValue::as_bytes);
+                    log.try_insert_flat(name, Value::from(value.map(Bytes::copy_from_slice)));
+                }
+                log.try_insert(log_schema().source_type_key(), Bytes::from("webhook"));
+                log.try_insert(log_schema().timestamp_key(), now);
+                Event::from(log)
+            })
+            .collect()
+    }
+}
+
+/// Parses a JSON payload, or a form as sent by Slack for slash commands and interactions, the
+/// latter wrapping a JSON payload in its `payload` field.
+fn parse_payload(headers: &HeaderMap, body: &[u8]) -> Result<Value, String> {
+    let is_form = headers
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+            value.starts_with("application/x-www-form-urlencoded")
+        });
+    if !is_form {
+        return serde_json::from_slice::<serde_json::Value>(body)
+            .map(Value::from)
+            .map_err(|error| format!("Invalid JSON payload: {}", error));
+    }
+
+    let fields = url::form_urlencoded::parse(body)
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+    match fields.as_slice() {
+        [(name, payload)] if name == "payload" => {
+            serde_json::from_str::<serde_json::Value>(payload)
+                .map(Value::from)
+                .map_err(|error| format!("Invalid JSON payload: {}", error))
+        }
+        _ => Ok(Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, Value::from(value)))
+                .collect(),
+        )),
+    }
+}
+
+/// Splits a batch into its items, the batch being either an array or an array in the field of
+/// the provider.
+fn unwrap_batch(mut payload: Value, field: Option<&str>) -> Vec<Value> {
+    if let (Value::Object(fields), Some(field)) = (&mut payload, field) {
+        if let Some(Value::Array(items)) = fields.get_mut(field) {
+            return std::mem::take(items);
+        }
+    }
+    match payload {
+        Value::Array(items) => items,
+        payload => vec![payload],
+    }
+}
+
+fn slack_challenge(payload: &Value) -> Option<String> {
+    match payload {
+        Value::Object(fields) => match (fields.get("type"), fields.get("challenge")) {
+            (Some(Value::Bytes(kind)), Some(Value::Bytes(challenge)))
+                if kind.as_ref() == b"url_verification" =>
+            {
+                Some(String::from_utf8_lossy(challenge).into_owned())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn error_response(code: StatusCode, message: &str) -> Response {
+    let body = serde_json::json!({ "code": code.as_u16(), "message": message });
+    warp::reply::with_status(warp::reply::json(&body), code).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::Stream;
+    use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+
+    use super::*;
+    use crate::{
+        event::EventStatus,
+        test_util::{
+            collect_ready,
+            components::{self, HTTP_PUSH_SOURCE_TAGS},
+            next_addr, wait_for_tcp,
+        },
+    };
+
+    const SECRET: &str = "secret";
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebhookConfig>();
+    }
+
+    async fn source(
+        provider: Provider,
+        unwrap_batches: bool,
+        status: EventStatus,
+    ) -> (impl Stream<Item = Event> + Unpin, SocketAddr) {
+        components::init_test();
+        let (sender, recv) = SourceSender::new_test_finalize(status);
+        let address = next_addr();
+        let config = WebhookConfig {
+            address,
+            provider,
+            secret: SECRET.to_owned(),
+            path: "/hooks".to_owned(),
+            timestamp_tolerance_secs: default_timestamp_tolerance_secs(),
+            unwrap_batches,
+            headers: vec!["X-GitHub-Event".to_owned()],
+            tls: None,
+            acknowledgements: true.into(),
+        };
+        let source = config
+            .build(SourceContext::new_test(sender, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    fn sign(message: &str) -> String {
+        let key = PKey::hmac(SECRET.as_bytes()).unwrap();
+        let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+        signer.update(message.as_bytes()).unwrap();
+        hex::encode(signer.sign_to_vec().unwrap())
+    }
+
+    async fn send(
+        address: SocketAddr,
+        headers: &[(&str, String)],
+        body: &str,
+    ) -> reqwest::Response {
+        let mut request = reqwest::Client::new()
+            .post(&format!("http://{}/hooks", address))
+            .body(body.to_owned());
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        request.send().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn receives_signed_deliveries() {
+        let (mut recv, address) = source(Provider::Github, false, EventStatus::Delivered).await;
+        let body = r#"{"action":"opened","number":1}"#;
+        let response = send(
+            address,
+            &[
+                ("X-Hub-Signature-256", format!("sha256={}", sign(body))),
+                ("X-GitHub-Event", "pull_request".to_owned()),
+            ],
+            body,
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+
+        let events = collect_ready(&mut recv).await;
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["action"], "opened".into());
+        assert_eq!(log["number"], 1.into());
+        assert_eq!(log["X-GitHub-Event"], "pull_request".into());
+        assert_eq!(log[log_schema().source_type_key()], "webhook".into());
+        assert!(log.get(log_schema().timestamp_key()).is_some());
+
+        components::SOURCE_TESTS.assert(&HTTP_PUSH_SOURCE_TAGS);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_signatures() {
+        let (mut recv, address) = source(Provider::Github, false, EventStatus::Delivered).await;
+        let body = r#"{"action":"opened"}"#;
+
+        let response = send(address, &[], body).await;
+        assert_eq!(response.status(), 401);
+        let response = send(
+            address,
+            &[("X-Hub-Signature-256", format!("sha256={}", sign("{}")))],
+            body,
+        )
+        .await;
+        assert_eq!(response.status(), 401);
+
+        assert!(collect_ready(&mut recv).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_payloads() {
+        let (_recv, address) = source(Provider::Github, false, EventStatus::Delivered).await;
+        let body = "{";
+        let response = send(
+            address,
+            &[("X-Hub-Signature-256", format!("sha256={}", sign(body)))],
+            body,
+        )
+        .await;
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn answers_slack_challenges() {
+        let (mut recv, address) = source(Provider::Slack, false, EventStatus::Delivered).await;
+        let body = r#"{"token":"x","challenge":"3eZbrw1aB","type":"url_verification"}"#;
+        let timestamp = Utc::now().timestamp().to_string();
+        let response = send(
+            address,
+            &[
+                ("X-Slack-Request-Timestamp", timestamp.clone()),
+                (
+                    "X-Slack-Signature",
+                    format!("v0={}", sign(&format!("v0:{}:{}", timestamp, body))),
+                ),
+            ],
+            body,
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.text().await.unwrap(),
+            r#"{"challenge":"3eZbrw1aB"}"#
+        );
+
+        assert!(collect_ready(&mut recv).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unwraps_batches() {
+        let (mut recv, address) = source(Provider::Pagerduty, true, EventStatus::Delivered).await;
+        let body = r#"{"messages":[{"event":"incident.trigger"},{"event":"incident.resolve"}]}"#;
+        let response = send(
+            address,
+            &[("X-PagerDuty-Signature", format!("v1={}", sign(body)))],
+            body,
+        )
+        .await;
+        assert_eq!(response.status(), 200);
+
+        let events = collect_ready(&mut recv).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].as_log()["event"], "incident.trigger".into());
+        assert_eq!(events[1].as_log()["event"], "incident.resolve".into());
+    }
+
+    #[tokio::test]
+    async fn reports_delivery_errors() {
+        let (_recv, address) = source(Provider::Github, false, EventStatus::Errored).await;
+        let body = r#"{"action":"opened"}"#;
+        let response = send(
+            address,
+            &[("X-Hub-Signature-256", format!("sha256={}", sign(body)))],
+            body,
+        )
+        .await;
+        assert_eq!(response.status(), 500);
+    }
+
+    #[test]
+    fn parses_forms() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+
+        let payload = parse_payload(&headers, b"command=%2Fweather&text=94070").unwrap();
+        assert_eq!(
+            payload,
+            Value::Object(btreemap! {
+                "command" => "/weather",
+                "text" => "94070",
+            })
+        );
+
+        let payload =
+            parse_payload(&headers, b"payload=%7B%22type%22%3A%22block_actions%22%7D").unwrap();
+        assert_eq!(
+            payload,
+            Value::Object(btreemap! { "type" => "block_actions" })
+        );
+    }
+}
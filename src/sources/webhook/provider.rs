@@ -0,0 +1,336 @@
+//! The signature schemes of the supported providers. All of them sign with HMAC-SHA256 keyed by
+//! the shared secret, and differ in the headers carrying the signature and in what is signed.
+
+use openssl::{
+    error::ErrorStack,
+    hash::MessageDigest,
+    memcmp,
+    pkey::{PKey, Private},
+    sign::Signer,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use warp::http::HeaderMap;
+
+#[derive(Debug, Snafu)]
+pub enum SignatureError {
+    #[snafu(display("Missing header {:?}", name))]
+    MissingHeader { name: &'static str },
+    #[snafu(display("Malformed header {:?}", name))]
+    MalformedHeader { name: &'static str },
+    #[snafu(display("Timestamp {} is outside of the tolerance", timestamp))]
+    Expired { timestamp: i64 },
+    #[snafu(display("Signature doesn't match the payload"))]
+    Mismatch,
+    #[snafu(display("Could not compute signature: {}", source))]
+    Crypto { source: ErrorStack },
+}
+
+impl SignatureError {
+    pub const fn error_code(&self) -> &'static str {
+        match self {
+            Self::MissingHeader { .. } => "missing_signature",
+            Self::MalformedHeader { .. } => "malformed_signature",
+            Self::Expired { .. } => "expired_signature",
+            Self::Mismatch => "invalid_signature",
+            Self::Crypto { .. } => "signing_failed",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    Github,
+    Stripe,
+    Slack,
+    Pagerduty,
+}
+
+impl Provider {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Stripe => "stripe",
+            Self::Slack => "slack",
+            Self::Pagerduty => "pagerduty",
+        }
+    }
+
+    /// The field of payloads holding a batch of events, for providers delivering them that way.
+    pub const fn batch_field(self) -> Option<&'static str> {
+        match self {
+            Self::Pagerduty => Some("messages"),
+            Self::Github | Self::Stripe | Self::Slack => None,
+        }
+    }
+}
+
+pub struct Verifier {
+    provider: Provider,
+    key: PKey<Private>,
+    /// The largest difference in seconds allowed between the signed timestamp and now, for
+    /// providers signing one. Zero disables the check.
+    tolerance_secs: u64,
+}
+
+impl Verifier {
+    pub fn new(provider: Provider, secret: &str, tolerance_secs: u64) -> Result<Self, ErrorStack> {
+        Ok(Self {
+            provider,
+            key: PKey::hmac(secret.as_bytes())?,
+            tolerance_secs,
+        })
+    }
+
+    /// Verifies that the request was signed with the secret, `now` being the current UNIX time.
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8], now: i64) -> Result<(), SignatureError> {
+        match self.provider {
+            Provider::Github => {
+                let name = "x-hub-signature-256";
+                let signature = header(headers, name)?
+                    .strip_prefix("sha256=")
+                    .ok_or(SignatureError::MalformedHeader { name })?;
+                self.check(&[decode(signature, name)?], &[body])
+            }
+            Provider::Stripe => {
+                // For example `t=1492774577,v1=5257a869...,v0=6ffbb59b...`, there being several
+                // `v1` signatures while the secret is rolled.
+                let name = "stripe-signature";
+                let mut timestamp = None;
+                let mut signatures = Vec::new();
+                for pair in header(headers, name)?.split(',') {
+                    match pair.trim().split_once('=') {
+                        Some(("t", value)) => timestamp = Some(value),
+                        Some(("v1", value)) => signatures.push(decode(value, name)?),
+                        Some(_) => (),
+                        None => return Err(SignatureError::MalformedHeader { name }),
+                    }
+                }
+                let timestamp = timestamp.ok_or(SignatureError::MalformedHeader { name })?;
+                self.check_timestamp(timestamp, name, now)?;
+                self.check(&signatures, &[timestamp.as_bytes(), b".", body])
+            }
+            Provider::Slack => {
+                let timestamp_name = "x-slack-request-timestamp";
+                let timestamp = header(headers, timestamp_name)?;
+                self.check_timestamp(timestamp, timestamp_name, now)?;
+
+                let name = "x-slack-signature";
+                let signature = header(headers, name)?
+                    .strip_prefix("v0=")
+                    .ok_or(SignatureError::MalformedHeader { name })?;
+                self.check(
+                    &[decode(signature, name)?],
+                    &[b"v0:", timestamp.as_bytes(), b":", body],
+                )
+            }
+            Provider::Pagerduty => {
+                // Several signatures while the secret is rolled, for example `v1=abc,v1=def`.
+                let name = "x-pagerduty-signature";
+                let signatures = header(headers, name)?
+                    .split(',')
+                    .filter_map(|signature| signature.trim().strip_prefix("v1="))
+                    .map(|signature| decode(signature, name))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.check(&signatures, &[body])
+            }
+        }
+    }
+
+    fn check_timestamp(
+        &self,
+        timestamp: &str,
+        name: &'static str,
+        now: i64,
+    ) -> Result<(), SignatureError> {
+        let timestamp = timestamp
+            .parse::<i64>()
+            .map_err(|_| SignatureError::MalformedHeader { name })?;
+        if self.tolerance_secs > 0 && (now - timestamp).unsigned_abs() > self.tolerance_secs {
+            Err(SignatureError::Expired { timestamp })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that one of the signatures is the one of the concatenated parts.
+    fn check(&self, signatures: &[Vec<u8>], parts: &[&[u8]]) -> Result<(), SignatureError> {
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key).context(CryptoSnafu)?;
+        for part in parts {
+            signer.update(part).context(CryptoSnafu)?;
+        }
+        let expected = signer.sign_to_vec().context(CryptoSnafu)?;
+
+        // Compared in constant time, to not leak how much of a signature matches.
+        if signatures
+            .iter()
+            .any(|signature| signature.len() == expected.len() && memcmp::eq(signature, &expected))
+        {
+            Ok(())
+        } else {
+            Err(SignatureError::Mismatch)
+        }
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a str, SignatureError> {
+    headers
+        .get(name)
+        .ok_or(SignatureError::MissingHeader { name })?
+        .to_str()
+        .map_err(|_| SignatureError::MalformedHeader { name })
+}
+
+fn decode(signature: &str, name: &'static str) -> Result<Vec<u8>, SignatureError> {
+    hex::decode(signature.trim()).map_err(|_| SignatureError::MalformedHeader { name })
+}
+
+#[cfg(test)]
+mod tests {
+    use warp::http::HeaderValue;
+
+    use super::*;
+
+    const SECRET: &str = "It's a Secret to Everybody";
+    const BODY: &[u8] = b"Hello, World!";
+    const NOW: i64 = 1_650_000_000;
+
+    fn sign(parts: &[&[u8]]) -> String {
+        let key = PKey::hmac(SECRET.as_bytes()).unwrap();
+        let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+        for part in parts {
+            signer.update(part).unwrap();
+        }
+        hex::encode(signer.sign_to_vec().unwrap())
+    }
+
+    fn verify(
+        provider: Provider,
+        headers: &[(&'static str, String)],
+    ) -> Result<(), SignatureError> {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        Verifier::new(provider, SECRET, 300)
+            .unwrap()
+            .verify(&map, BODY, NOW)
+    }
+
+    #[test]
+    fn verifies_github() {
+        // The example of GitHub's documentation.
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        assert_eq!(format!("sha256={}", sign(&[BODY])), signature);
+        assert!(verify(
+            Provider::Github,
+            &[("x-hub-signature-256", signature.into())]
+        )
+        .is_ok());
+
+        assert!(matches!(
+            verify(
+                Provider::Github,
+                &[(
+                    "x-hub-signature-256",
+                    format!("sha256={}", sign(&[b"other"]))
+                )]
+            ),
+            Err(SignatureError::Mismatch)
+        ));
+        assert!(matches!(
+            verify(Provider::Github, &[("x-hub-signature-256", sign(&[BODY]))]),
+            Err(SignatureError::MalformedHeader { .. })
+        ));
+        assert!(matches!(
+            verify(Provider::Github, &[]),
+            Err(SignatureError::MissingHeader { .. })
+        ));
+    }
+
+    #[test]
+    fn verifies_stripe() {
+        let timestamp = NOW.to_string();
+        let signature = sign(&[timestamp.as_bytes(), b".", BODY]);
+        assert!(verify(
+            Provider::Stripe,
+            &[(
+                "stripe-signature",
+                format!(
+                    "t={},v1={},v1={},v0=00",
+                    timestamp,
+                    sign(&[b"old"]),
+                    signature
+                )
+            )]
+        )
+        .is_ok());
+
+        assert!(matches!(
+            verify(
+                Provider::Stripe,
+                &[(
+                    "stripe-signature",
+                    format!("t={},v1={}", NOW + 1, signature)
+                )]
+            ),
+            Err(SignatureError::Mismatch)
+        ));
+
+        let timestamp = (NOW - 301).to_string();
+        let signature = sign(&[timestamp.as_bytes(), b".", BODY]);
+        assert!(matches!(
+            verify(
+                Provider::Stripe,
+                &[(
+                    "stripe-signature",
+                    format!("t={},v1={}", timestamp, signature)
+                )]
+            ),
+            Err(SignatureError::Expired { .. })
+        ));
+    }
+
+    #[test]
+    fn verifies_slack() {
+        let timestamp = (NOW - 10).to_string();
+        let signature = format!("v0={}", sign(&[b"v0:", timestamp.as_bytes(), b":", BODY]));
+        assert!(verify(
+            Provider::Slack,
+            &[
+                ("x-slack-request-timestamp", timestamp.clone()),
+                ("x-slack-signature", signature.clone())
+            ]
+        )
+        .is_ok());
+
+        assert!(matches!(
+            verify(Provider::Slack, &[("x-slack-signature", signature)]),
+            Err(SignatureError::MissingHeader {
+                name: "x-slack-request-timestamp"
+            })
+        ));
+    }
+
+    #[test]
+    fn verifies_pagerduty() {
+        let signature = sign(&[BODY]);
+        assert!(verify(
+            Provider::Pagerduty,
+            &[(
+                "x-pagerduty-signature",
+                format!("v1={}, v1={}", sign(&[b"old"]), signature)
+            )]
+        )
+        .is_ok());
+
+        assert!(matches!(
+            verify(
+                Provider::Pagerduty,
+                &[("x-pagerduty-signature", format!("v2={}", signature))]
+            ),
+            Err(SignatureError::Mismatch)
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+//! The `windows_event_log` source subscribes to channels of the Windows Event Log, turning the
+//! XML rendering of each event into structured fields. Its position in the channels is kept as a
+//! bookmark in the data directory, so that restarts resume after the last processed event.
+
+use std::{io, path::PathBuf, time::Duration};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, AcknowledgementsConfig, DataType, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{BatchNotifier, Event, LogEvent, Value},
+    internal_events::{
+        BytesReceived, StreamClosedError, WindowsEventLogEventsReceived, WindowsEventLogParseError,
+        WindowsEventLogReadError,
+    },
+    serde::bool_or_struct,
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+mod render;
+mod subscription;
+
+use self::subscription::{Bookmark, EvtHandle, Publishers, Subscription};
+
+const BOOKMARK_FILENAME: &str = "bookmark.xml";
+const WAIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one channel must be configured"))]
+    NoChannels,
+    #[snafu(display("Could not read bookmark {:?}: {}", path, source))]
+    ReadBookmark { source: io::Error, path: PathBuf },
+    #[snafu(display("Could not subscribe to channels {:?}: {}", channels, source))]
+    Subscribe {
+        source: io::Error,
+        channels: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct WindowsEventLogConfig {
+    #[serde(default = "default_channels")]
+    #[derivative(Default(value = "default_channels()"))]
+    channels: Vec<String>,
+    #[serde(default)]
+    levels: Vec<Level>,
+    #[serde(default)]
+    read_existing_events: bool,
+    data_dir: Option<PathBuf>,
+    #[serde(default = "default_batch_size")]
+    #[derivative(Default(value = "default_batch_size()"))]
+    batch_size: usize,
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+fn default_channels() -> Vec<String> {
+    vec!["Application".to_owned(), "System".to_owned()]
+}
+
+const fn default_batch_size() -> usize {
+    100
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Level {
+    Critical,
+    Error,
+    Warning,
+    Information,
+    Verbose,
+}
+
+impl Level {
+    /// The values of `System/Level` the level matches. Events logged regardless of the level they
+    /// are collected at have a level of 0, and are treated as information.
+    const fn values(self) -> &'static [u8] {
+        match self {
+            Self::Critical => &[1],
+            Self::Error => &[2],
+            Self::Warning => &[3],
+            Self::Information => &[0, 4],
+            Self::Verbose => &[5],
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<WindowsEventLogConfig>("windows_event_log")
+}
+
+impl_generate_config_from_default!(WindowsEventLogConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "windows_event_log")]
+impl SourceConfig for WindowsEventLogConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        if self.channels.is_empty() {
+            return Err(BuildError::NoChannels.into());
+        }
+
+        let data_dir = cx
+            .globals
+            .resolve_and_make_data_subdir(self.data_dir.as_ref(), cx.key.id())?;
+        let bookmark_path = data_dir.join(BOOKMARK_FILENAME);
+
+        let saved = match std::fs::read_to_string(&bookmark_path) {
+            Ok(xml) => Some(xml),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(source) => {
+                return Err(BuildError::ReadBookmark {
+                    source,
+                    path: bookmark_path,
+                }
+                .into())
+            }
+        };
+        let saved = saved.and_then(|xml| match Bookmark::new(Some(&xml)) {
+            Ok(bookmark) => Some(bookmark),
+            Err(error) => {
+                warn!(
+                    message = "Invalid bookmark, ignoring it.",
+                    path = ?bookmark_path,
+                    %error,
+                );
+                None
+            }
+        });
+
+        let query = build_query(&self.channels, &self.levels);
+        let subscription = Subscription::new(&query, saved.as_ref(), self.read_existing_events)
+            .context(SubscribeSnafu {
+                channels: self.channels.clone(),
+            })?;
+        let bookmark = match saved {
+            Some(bookmark) => bookmark,
+            None => Bookmark::new(None)?,
+        };
+
+        Ok(Box::pin(
+            WindowsEventLogSource {
+                subscription,
+                bookmark,
+                bookmark_path,
+                batch_size: self.batch_size.max(1),
+                acknowledgements: cx.do_acknowledgements(&self.acknowledgements),
+                out: cx.out,
+            }
+            .run(cx.shutdown),
+        ))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "windows_event_log"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// Builds a structured query selecting the events of the given levels, or all of them, in each
+/// of the channels.
+fn build_query(channels: &[String], levels: &[Level]) -> String {
+    let selector = if levels.is_empty() {
+        "*".to_owned()
+    } else {
+        let conditions = levels
+            .iter()
+            .flat_map(|level| level.values())
+            .map(|value| format!("Level={}", value))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        format!("*[System[({})]]", conditions)
+    };
+
+    let mut query = String::from("<QueryList><Query Id=\"0\">");
+    for channel in channels {
+        query.push_str(&format!(
+            "<Select Path=\"{}\">{}</Select>",
+            escape_attribute(channel),
+            selector
+        ));
+    }
+    query.push_str("</Query></QueryList>");
+    query
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+struct WindowsEventLogSource {
+    subscription: Subscription,
+    bookmark: Bookmark,
+    bookmark_path: PathBuf,
+    batch_size: usize,
+    acknowledgements: bool,
+    out: SourceSender,
+}
+
+impl WindowsEventLogSource {
+    async fn run(mut self, mut shutdown: ShutdownSignal) -> Result<(), ()> {
+        let mut publishers = Publishers::default();
+
+        loop {
+            let events = match self.subscription.next(self.batch_size) {
+                Ok(events) => events,
+                Err(error) => {
+                    emit!(&WindowsEventLogReadError { error });
+                    return Err(());
+                }
+            };
+            if events.is_empty() {
+                tokio::select! {
+                    _ = self.subscription.wait(WAIT_TIMEOUT) => continue,
+                    _ = &mut shutdown => return Ok(()),
+                }
+            }
+
+            let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(self.acknowledgements);
+            let mut byte_size = 0;
+            let mut logs = Vec::with_capacity(events.len());
+            for event in &events {
+                let xml = match subscription::render_event(event) {
+                    Ok(xml) => xml,
+                    Err(error) => {
+                        emit!(&WindowsEventLogReadError { error });
+                        continue;
+                    }
+                };
+                byte_size += xml.len();
+
+                match render::parse_event(&xml) {
+                    Ok(mut log) => {
+                        if let Some(message) = format_message(&mut publishers, &log, event) {
+                            log.insert(log_schema().message_key(), message);
+                        }
+                        log.insert(
+                            log_schema().source_type_key(),
+                            Bytes::from("windows_event_log"),
+                        );
+                        logs.push(Event::from(log.with_batch_notifier_option(&batch)));
+                    }
+                    Err(error) => emit!(&WindowsEventLogParseError { error }),
+                }
+            }
+            drop(batch);
+
+            let last = events.last().expect("events aren't empty");
+            if let Err(error) = self.bookmark.update(last) {
+                emit!(&WindowsEventLogReadError { error });
+            }
+            drop(events);
+
+            emit!(&BytesReceived {
+                byte_size,
+                protocol: "windows_event_log",
+            });
+
+            if !logs.is_empty() {
+                let count = logs.len();
+                emit!(&WindowsEventLogEventsReceived {
+                    count,
+                    byte_size: logs.size_of(),
+                });
+
+                if let Err(error) = self.out.send_batch(logs).await {
+                    emit!(&StreamClosedError { error, count });
+                    return Ok(());
+                }
+                if let Some(receiver) = receiver {
+                    // Ignore the received status, we can't do anything with failures here.
+                    receiver.await;
+                }
+            }
+
+            self.save_bookmark().await;
+        }
+    }
+
+    async fn save_bookmark(&self) {
+        let result = match self.bookmark.to_xml() {
+            Ok(xml) => {
+                // Written to a temporary file first, so that a crash can't leave a partial
+                // bookmark behind.
+                let temporary = self.bookmark_path.with_extension("xml.tmp");
+                match tokio::fs::write(&temporary, xml).await {
+                    Ok(()) => tokio::fs::rename(&temporary, &self.bookmark_path).await,
+                    Err(error) => Err(error),
+                }
+            }
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            error!(
+                message = "Could not save Windows Event Log bookmark.",
+                %error,
+                path = ?self.bookmark_path,
+            );
+        }
+    }
+}
+
+fn format_message(
+    publishers: &mut Publishers,
+    log: &LogEvent,
+    event: &EvtHandle,
+) -> Option<String> {
+    match log.get("provider_name") {
+        Some(Value::Bytes(provider)) => {
+            publishers.format_message(&String::from_utf8_lossy(provider), event)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowsEventLogConfig>();
+    }
+
+    #[test]
+    fn builds_query_of_all_events() {
+        assert_eq!(
+            build_query(&default_channels(), &[]),
+            "<QueryList><Query Id=\"0\"><Select Path=\"Application\">*</Select>\
+             <Select Path=\"System\">*</Select></Query></QueryList>"
+        );
+    }
+
+    #[test]
+    fn builds_query_of_levels() {
+        assert_eq!(
+            build_query(
+                &["Microsoft-Windows-Sysmon/Operational".to_owned()],
+                &[Level::Error, Level::Information]
+            ),
+            "<QueryList><Query Id=\"0\"><Select Path=\"Microsoft-Windows-Sysmon/Operational\">\
+             *[System[(Level=2 or Level=0 or Level=4)]]</Select></Query></QueryList>"
+        );
+    }
+
+    #[test]
+    fn escapes_channels() {
+        assert_eq!(
+            build_query(&["A&\"B\"".to_owned()], &[]),
+            "<QueryList><Query Id=\"0\"><Select Path=\"A&amp;&quot;B&quot;\">*</Select>\
+             </Query></QueryList>"
+        );
+    }
+}
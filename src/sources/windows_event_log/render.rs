@@ -0,0 +1,263 @@
+//! Turns the XML rendering of an event into structured fields.
+
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use chrono::{DateTime, Utc};
+use roxmltree::{Document, Node};
+
+use crate::{
+    config::log_schema,
+    event::{LogEvent, Value},
+};
+
+pub fn parse_event(xml: &str) -> Result<LogEvent, roxmltree::Error> {
+    let document = Document::parse(xml)?;
+    let mut log = LogEvent::default();
+
+    for section in document.root_element().children().filter(Node::is_element) {
+        match section.tag_name().name() {
+            "System" => parse_system(section, &mut log),
+            "EventData" => {
+                if let Some(data) = parse_event_data(section) {
+                    log.insert("event_data", data);
+                }
+            }
+            "UserData" if section.children().any(|node| node.is_element()) => {
+                log.insert("user_data", parse_element(section));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(log)
+}
+
+fn parse_system(system: Node, log: &mut LogEvent) {
+    for node in system.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "Provider" => {
+                insert_text(log, "provider_name", node.attribute("Name"));
+                insert_text(log, "provider_guid", node.attribute("Guid"));
+            }
+            "EventID" => {
+                insert_integer(log, "event_id", node.text());
+                insert_integer(log, "event_qualifiers", node.attribute("Qualifiers"));
+            }
+            "Version" => insert_integer(log, "version", node.text()),
+            "Level" => {
+                if let Some(level) = node.text().and_then(|text| text.parse().ok()) {
+                    log.insert("level_value", level);
+                    if let Some(name) = level_name(level) {
+                        log.insert("level", name);
+                    }
+                }
+            }
+            "Task" => insert_integer(log, "task", node.text()),
+            "Opcode" => insert_integer(log, "opcode", node.text()),
+            "Keywords" => insert_text(log, "keywords", node.text()),
+            "TimeCreated" => {
+                if let Some(timestamp) = node
+                    .attribute("SystemTime")
+                    .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                {
+                    log.insert(log_schema().timestamp_key(), timestamp.with_timezone(&Utc));
+                }
+            }
+            "EventRecordID" => insert_integer(log, "record_id", node.text()),
+            "Correlation" => {
+                insert_text(log, "activity_id", node.attribute("ActivityID"));
+                insert_text(
+                    log,
+                    "related_activity_id",
+                    node.attribute("RelatedActivityID"),
+                );
+            }
+            "Execution" => {
+                insert_integer(log, "process_id", node.attribute("ProcessID"));
+                insert_integer(log, "thread_id", node.attribute("ThreadID"));
+            }
+            "Channel" => insert_text(log, "channel", node.text()),
+            "Computer" => insert_text(log, log_schema().host_key(), node.text()),
+            "Security" => insert_text(log, "user_id", node.attribute("UserID")),
+            _ => (),
+        }
+    }
+}
+
+/// The names of the standard levels. Level 0 is used by events logged regardless of the level
+/// they are collected at, and is displayed as information.
+const fn level_name(level: i64) -> Option<&'static str> {
+    match level {
+        0 | 4 => Some("information"),
+        1 => Some("critical"),
+        2 => Some("error"),
+        3 => Some("warning"),
+        5 => Some("verbose"),
+        _ => None,
+    }
+}
+
+/// Event data is an object keyed by the names of its items, or an array if any of them has no
+/// name, as is the case for events of classic event sources.
+fn parse_event_data(section: Node) -> Option<Value> {
+    let items = section
+        .children()
+        .filter(|node| node.is_element() && node.tag_name().name() == "Data")
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        return None;
+    }
+
+    let text = |item: &Node| Value::from(item.text().unwrap_or_default().to_owned());
+    if items.iter().all(|item| item.has_attribute("Name")) {
+        Some(Value::from(
+            items
+                .iter()
+                .map(|item| (item.attribute("Name").unwrap().to_owned(), text(item)))
+                .collect::<BTreeMap<_, _>>(),
+        ))
+    } else {
+        Some(Value::from(items.iter().map(text).collect::<Vec<_>>()))
+    }
+}
+
+/// Converts an element into an object keyed by the names of its children, or into its text if it
+/// has none. Repeated children are collected into arrays.
+fn parse_element(element: Node) -> Value {
+    let mut fields = BTreeMap::new();
+    for child in element.children().filter(Node::is_element) {
+        let value = parse_element(child);
+        match fields.entry(child.tag_name().name().to_owned()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                Value::Array(values) => values.push(value),
+                existing => {
+                    let first = std::mem::replace(existing, Value::Null);
+                    *existing = Value::Array(vec![first, value]);
+                }
+            },
+        }
+    }
+
+    if fields.is_empty() {
+        Value::from(element.text().unwrap_or_default().to_owned())
+    } else {
+        Value::from(fields)
+    }
+}
+
+fn insert_text(log: &mut LogEvent, key: &str, text: Option<&str>) {
+    if let Some(text) = text {
+        log.insert(key, text.to_owned());
+    }
+}
+
+fn insert_integer(log: &mut LogEvent, key: &str, text: Option<&str>) {
+    if let Some(value) = text.and_then(|text| text.parse::<i64>().ok()) {
+        log.insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = r#"<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+  <System>
+    <Provider Name="Service Control Manager" Guid="{555908d1-a6d7-4695-8e1e-26931d2012f4}" EventSourceName="Service Control Manager"/>
+    <EventID Qualifiers="16384">7036</EventID>
+    <Version>0</Version>
+    <Level>4</Level>
+    <Task>0</Task>
+    <Opcode>0</Opcode>
+    <Keywords>0x8080000000000000</Keywords>
+    <TimeCreated SystemTime="2022-03-01T10:00:00.1234567Z"/>
+    <EventRecordID>12345</EventRecordID>
+    <Correlation/>
+    <Execution ProcessID="640" ThreadID="700"/>
+    <Channel>System</Channel>
+    <Computer>WIN-HOST</Computer>
+    <Security/>
+  </System>
+  <EventData>
+    <Data Name="param1">Windows Update</Data>
+    <Data Name="param2">running</Data>
+    <Binary>770075006100750073007600</Binary>
+  </EventData>
+</Event>"#;
+
+    #[test]
+    fn parses_system_fields() {
+        let log = parse_event(EVENT).unwrap();
+        assert_eq!(log["provider_name"], "Service Control Manager".into());
+        assert_eq!(log["event_id"], 7036.into());
+        assert_eq!(log["event_qualifiers"], 16384.into());
+        assert_eq!(log["level"], "information".into());
+        assert_eq!(log["level_value"], 4.into());
+        assert_eq!(log["keywords"], "0x8080000000000000".into());
+        assert_eq!(log["record_id"], 12345.into());
+        assert_eq!(log["process_id"], 640.into());
+        assert_eq!(log["channel"], "System".into());
+        assert_eq!(log[log_schema().host_key()], "WIN-HOST".into());
+        assert_eq!(
+            log[log_schema().timestamp_key()]
+                .as_timestamp()
+                .unwrap()
+                .timestamp_nanos(),
+            1_646_128_800_123_456_700
+        );
+        assert!(log.get("activity_id").is_none());
+        assert!(log.get("user_id").is_none());
+    }
+
+    #[test]
+    fn parses_named_event_data() {
+        let log = parse_event(EVENT).unwrap();
+        assert_eq!(log["event_data.param1"], "Windows Update".into());
+        assert_eq!(log["event_data.param2"], "running".into());
+        assert!(log.get("event_data.Binary").is_none());
+    }
+
+    #[test]
+    fn parses_unnamed_event_data() {
+        let log = parse_event(
+            r#"<Event><System><Level>2</Level></System>
+            <EventData><Data>first</Data><Data></Data></EventData></Event>"#,
+        )
+        .unwrap();
+        assert_eq!(log["level"], "error".into());
+        assert_eq!(
+            log["event_data"],
+            Value::from(vec![Value::from("first"), Value::from("")])
+        );
+    }
+
+    #[test]
+    fn parses_user_data() {
+        let log = parse_event(
+            r#"<Event><System/><UserData>
+            <LogFileCleared xmlns="http://manifests.microsoft.com/win/2004/08/windows/eventlog">
+              <SubjectUserName>admin</SubjectUserName>
+              <Item>a</Item>
+              <Item>b</Item>
+            </LogFileCleared></UserData></Event>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            log["user_data.LogFileCleared.SubjectUserName"],
+            "admin".into()
+        );
+        assert_eq!(
+            log["user_data.LogFileCleared.Item"],
+            Value::from(vec![Value::from("a"), Value::from("b")])
+        );
+        assert!(log.get("event_data").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_xml() {
+        assert!(parse_event("<Event><System></Event>").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Safe wrappers around the parts of the Windows Event Log API used by the source.
+
+use std::{
+    collections::HashMap, ffi::OsStr, io, os::windows::ffi::OsStrExt, ptr, sync::Arc,
+    time::Duration,
+};
+
+use winapi::{
+    shared::{
+        minwindef::{DWORD, FALSE, TRUE},
+        winerror::{ERROR_INSUFFICIENT_BUFFER, ERROR_NO_MORE_ITEMS},
+    },
+    um::{
+        handleapi::CloseHandle,
+        synchapi::{CreateEventW, ResetEvent, WaitForSingleObject},
+        winevt::{
+            EvtClose, EvtCreateBookmark, EvtFormatMessage, EvtFormatMessageEvent, EvtNext,
+            EvtOpenPublisherMetadata, EvtRender, EvtRenderBookmark, EvtRenderEventXml,
+            EvtSubscribe, EvtSubscribeStartAfterBookmark, EvtSubscribeStartAtOldestRecord,
+            EvtSubscribeToFutureEvents, EvtUpdateBookmark, EVT_HANDLE,
+        },
+        winnt::HANDLE,
+    },
+};
+
+/// An event log object, closed when dropped.
+pub struct EvtHandle(EVT_HANDLE);
+
+// Event log handles aren't tied to the thread that opened them.
+unsafe impl Send for EvtHandle {}
+unsafe impl Sync for EvtHandle {}
+
+impl EvtHandle {
+    fn new(handle: EVT_HANDLE) -> io::Result<Self> {
+        if handle.is_null() {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Self(handle))
+        }
+    }
+}
+
+impl Drop for EvtHandle {
+    fn drop(&mut self) {
+        unsafe { EvtClose(self.0) };
+    }
+}
+
+/// The event the service sets when new events match a subscription.
+struct Signal(HANDLE);
+
+unsafe impl Send for Signal {}
+unsafe impl Sync for Signal {}
+
+impl Drop for Signal {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.0) };
+    }
+}
+
+pub struct Subscription {
+    handle: EvtHandle,
+    signal: Arc<Signal>,
+}
+
+impl Subscription {
+    /// Subscribes to the events matching a structured query, starting after the bookmark if one
+    /// is given, and otherwise at the oldest or at the next event.
+    pub fn new(query: &str, bookmark: Option<&Bookmark>, read_existing: bool) -> io::Result<Self> {
+        let signal = unsafe { CreateEventW(ptr::null_mut(), TRUE, TRUE, ptr::null()) };
+        if signal.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let signal = Arc::new(Signal(signal));
+
+        let flags = match (bookmark, read_existing) {
+            (Some(_), _) => EvtSubscribeStartAfterBookmark,
+            (None, true) => EvtSubscribeStartAtOldestRecord,
+            (None, false) => EvtSubscribeToFutureEvents,
+        };
+        let query = to_wide(query);
+        let handle = unsafe {
+            EvtSubscribe(
+                ptr::null_mut(),
+                signal.0,
+                ptr::null(),
+                query.as_ptr(),
+                bookmark.map_or(ptr::null_mut(), |bookmark| bookmark.0 .0),
+                ptr::null_mut(),
+                None,
+                flags,
+            )
+        };
+
+        Ok(Self {
+            handle: EvtHandle::new(handle)?,
+            signal,
+        })
+    }
+
+    /// Returns up to `count` of the pending events, without waiting for more.
+    pub fn next(&self, count: usize) -> io::Result<Vec<EvtHandle>> {
+        let mut handles = vec![ptr::null_mut(); count];
+        let mut returned: DWORD = 0;
+        let ok = unsafe {
+            EvtNext(
+                self.handle.0,
+                count as DWORD,
+                handles.as_mut_ptr(),
+                0,
+                0,
+                &mut returned,
+            )
+        };
+        if ok == FALSE {
+            let error = io::Error::last_os_error();
+            return if error.raw_os_error() == Some(ERROR_NO_MORE_ITEMS as i32) {
+                unsafe { ResetEvent(self.signal.0) };
+                Ok(Vec::new())
+            } else {
+                Err(error)
+            };
+        }
+
+        handles.truncate(returned as usize);
+        Ok(handles.into_iter().map(EvtHandle).collect())
+    }
+
+    /// Waits for new events for at most `timeout`.
+    pub async fn wait(&self, timeout: Duration) {
+        let signal = Arc::clone(&self.signal);
+        let timeout = timeout.as_millis() as DWORD;
+        let _ =
+            tokio::task::spawn_blocking(move || unsafe { WaitForSingleObject(signal.0, timeout) })
+                .await;
+    }
+}
+
+/// The position of the last processed event in each of the channels.
+pub struct Bookmark(EvtHandle);
+
+impl Bookmark {
+    /// Creates a bookmark from its XML rendering, or an empty one.
+    pub fn new(xml: Option<&str>) -> io::Result<Self> {
+        let handle = match xml {
+            Some(xml) => unsafe { EvtCreateBookmark(to_wide(xml).as_ptr()) },
+            None => unsafe { EvtCreateBookmark(ptr::null()) },
+        };
+        EvtHandle::new(handle).map(Self)
+    }
+
+    pub fn update(&mut self, event: &EvtHandle) -> io::Result<()> {
+        if unsafe { EvtUpdateBookmark(self.0 .0, event.0) } == FALSE {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn to_xml(&self) -> io::Result<String> {
+        render(&self.0, EvtRenderBookmark)
+    }
+}
+
+pub fn render_event(event: &EvtHandle) -> io::Result<String> {
+    render(event, EvtRenderEventXml)
+}
+
+fn render(fragment: &EvtHandle, flags: DWORD) -> io::Result<String> {
+    let mut buffer: Vec<u16> = Vec::new();
+    loop {
+        // Sizes are in bytes.
+        let mut used: DWORD = 0;
+        let mut property_count: DWORD = 0;
+        let ok = unsafe {
+            EvtRender(
+                ptr::null_mut(),
+                fragment.0,
+                flags,
+                (buffer.len() * 2) as DWORD,
+                buffer.as_mut_ptr().cast(),
+                &mut used,
+                &mut property_count,
+            )
+        };
+        if ok == TRUE {
+            return Ok(from_wide(&buffer[..used as usize / 2]));
+        }
+
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER as i32) {
+            return Err(error);
+        }
+        buffer.resize((used as usize + 1) / 2, 0);
+    }
+}
+
+/// Formats the messages of events with the metadata of their publishers, which are opened once.
+#[derive(Default)]
+pub struct Publishers {
+    metadata: HashMap<String, Option<EvtHandle>>,
+}
+
+impl Publishers {
+    /// Returns `None` if the publisher isn't installed on this machine, or doesn't define a
+    /// message for the event.
+    pub fn format_message(&mut self, publisher: &str, event: &EvtHandle) -> Option<String> {
+        let metadata = self
+            .metadata
+            .entry(publisher.to_owned())
+            .or_insert_with(|| {
+                let publisher = to_wide(publisher);
+                let handle = unsafe {
+                    EvtOpenPublisherMetadata(ptr::null_mut(), publisher.as_ptr(), ptr::null(), 0, 0)
+                };
+                EvtHandle::new(handle).ok()
+            })
+            .as_ref()?;
+
+        let mut buffer: Vec<u16> = Vec::new();
+        loop {
+            // Sizes are in characters.
+            let mut used: DWORD = 0;
+            let ok = unsafe {
+                EvtFormatMessage(
+                    metadata.0,
+                    event.0,
+                    0,
+                    0,
+                    ptr::null_mut(),
+                    EvtFormatMessageEvent,
+                    buffer.len() as DWORD,
+                    buffer.as_mut_ptr(),
+                    &mut used,
+                )
+            };
+            if ok == TRUE {
+                let message = from_wide(&buffer[..used as usize]);
+                return Some(message.trim_end().to_owned());
+            }
+            if io::Error::last_os_error().raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER as i32) {
+                return None;
+            }
+            buffer.resize(used as usize, 0);
+        }
+    }
+}
+
+fn to_wide(text: &str) -> Vec<u16> {
+    OsStr::new(text).encode_wide().chain(Some(0)).collect()
+}
+
+fn from_wide(text: &[u16]) -> String {
+    let len = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+    String::from_utf16_lossy(&text[..len])
+}
@@ -1,17 +1,21 @@
 use std::{borrow::Cow, collections::BTreeMap, time::Duration};
 
 use colored::{ColoredString, Colorize};
+use serde_json::Value;
 use tokio_stream::StreamExt;
 use url::Url;
 use vector_api_client::{
-    connect_subscription_client,
+    connect_subscription_client_with_options,
     gql::{
         output_events_by_component_id_patterns_subscription::OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns,
         TapEncodingFormat, TapSubscriptionExt,
     },
-    Client,
+    Client, ConnectOptions,
 };
+use vector_common::{encode_logfmt, TimeZone};
+use vrl::{diagnostic::Formatter, Program, Runtime};
 
+use super::OutputFormat;
 use crate::{
     config,
     signal::{SignalRx, SignalTo},
@@ -23,6 +27,24 @@ const RECONNECT_DELAY: u64 = 5000;
 /// CLI command func for issuing 'tap' queries, and communicating with a local/remote
 /// Vector API server via HTTP/WebSockets.
 pub(crate) async fn cmd(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode::ExitCode {
+    // Compile the VRL program first, so that an invalid program is reported before connecting.
+    let program = match opts.vrl.as_ref() {
+        Some(source) => match vrl::compile(source, &vrl_stdlib::all()) {
+            Ok(program) => Some(program),
+            Err(diagnostics) => {
+                #[allow(clippy::print_stderr)]
+                {
+                    eprintln!(
+                        "[tap] Invalid VRL program:\n{}",
+                        Formatter::new(source, diagnostics).colored()
+                    );
+                }
+                return exitcode::CONFIG;
+            }
+        },
+        None => None,
+    };
+
     // Use the provided URL as the Vector GraphQL API server, or default to the local port
     // provided by the API config. This will work despite `api` and `api-client` being distinct
     // features; the config is available even if `api` is disabled.
@@ -32,9 +54,23 @@ pub(crate) async fn cmd(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode
             .expect("Couldn't parse default API URL. Please report this.")
     });
 
+    let connect_options = match opts.api.connect_options() {
+        Ok(options) => options,
+        Err(error) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("[tap] {}", error);
+            }
+            return exitcode::USAGE;
+        }
+    };
+
     // Return early with instructions for enabling the API if the endpoint isn't reachable
     // via a healthcheck.
-    if Client::new_with_healthcheck(url.clone()).await.is_none() {
+    if Client::new_with_healthcheck(url.clone(), &connect_options)
+        .await
+        .is_none()
+    {
         return exitcode::UNAVAILABLE;
     }
 
@@ -59,13 +95,13 @@ pub(crate) async fn cmd(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode
             .collect()
     };
 
-    let formatter = EventFormatter::new(opts.meta, opts.format);
+    let formatter = EventFormatter::new(opts.meta, opts.format, opts.fields.clone(), program);
 
     loop {
         tokio::select! {
             biased;
             Some(SignalTo::Shutdown | SignalTo::Quit) = signal_rx.recv() => break,
-            status = run(url.clone(), opts, outputs_patterns.clone(), formatter.clone()) => {
+            status = run(url.clone(), &connect_options, opts, outputs_patterns.clone(), formatter.clone()) => {
                 if status == exitcode::UNAVAILABLE || status == exitcode::TEMPFAIL && !opts.no_reconnect {
                     eprintln!("[tap] Connection failed. Reconnecting in {:?} seconds.", RECONNECT_DELAY / 1000);
                     tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY)).await;
@@ -81,26 +117,28 @@ pub(crate) async fn cmd(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode
 
 async fn run(
     url: Url,
+    connect_options: &ConnectOptions,
     opts: &super::Opts,
     outputs_patterns: Vec<String>,
-    formatter: EventFormatter,
+    mut formatter: EventFormatter,
 ) -> exitcode::ExitCode {
-    let subscription_client = match connect_subscription_client(url).await {
-        Ok(c) => c,
-        Err(e) => {
-            #[allow(clippy::print_stderr)]
-            {
-                eprintln!("[tap] Couldn't connect to Vector API via WebSockets: {}", e);
+    let subscription_client =
+        match connect_subscription_client_with_options(url, connect_options).await {
+            Ok(c) => c,
+            Err(e) => {
+                #[allow(clippy::print_stderr)]
+                {
+                    eprintln!("[tap] Couldn't connect to Vector API via WebSockets: {}", e);
+                }
+                return exitcode::UNAVAILABLE;
             }
-            return exitcode::UNAVAILABLE;
-        }
-    };
+        };
 
     tokio::pin! {
         let stream = subscription_client.output_events_by_component_id_patterns_subscription(
             outputs_patterns,
             opts.inputs_of.clone(),
-            opts.format,
+            formatter.request_encoding(),
             opts.limit as i64,
             opts.interval as i64,
         );
@@ -116,13 +154,19 @@ async fn run(
                 for tap_event in d.output_events_by_component_id_patterns.iter() {
                     match tap_event {
                         OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns::Log(ev) => {
-                            println!("{}", formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()));
+                            if let Some(event) = formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()) {
+                                println!("{}", event);
+                            }
                         },
                         OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns::Metric(ev) => {
-                            println!("{}", formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()));
+                            if let Some(event) = formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()) {
+                                println!("{}", event);
+                            }
                         },
                         OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns::Trace(ev) => {
-                            println!("{}", formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()));
+                            if let Some(event) = formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()) {
+                                println!("{}", event);
+                            }
                         },
                         OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns::EventNotification(ev) => {
                             if !opts.quiet {
@@ -138,35 +182,195 @@ async fn run(
     }
 }
 
+/// Columns holding event metadata, shown first in `table` output when `--meta` is set
+const META_COLUMNS: [&str; 3] = ["component_id", "component_kind", "component_type"];
+
+/// Maximum width of a `table` column, beyond which values are truncated
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Parses an event received as JSON. Anything else is kept as a plain string.
+fn parse_event(event: &str) -> Value {
+    serde_json::from_str(event).unwrap_or_else(|_| Value::String(event.to_string()))
+}
+
+/// Returns the value at `path` within `event`, where `path` is a `.`-separated list of keys
+fn lookup<'a>(event: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(event, |value, key| value.get(key))
+}
+
+/// Returns an object of the given `fields` of `event`, keyed by their path. Fields that don't
+/// exist in the event are omitted.
+fn project(event: &Value, fields: &[String]) -> Value {
+    Value::Object(
+        fields
+            .iter()
+            .filter_map(|field| Some((field.clone(), lookup(event, field)?.clone())))
+            .collect(),
+    )
+}
+
+/// Runs the `--vrl` program on `event`, returning the event as modified by the program, or
+/// `None` if the program aborted or failed.
+fn remap(program: &Program, event: Value) -> Option<Value> {
+    let mut target = vrl::Value::from(event);
+    Runtime::default()
+        .resolve(&mut target, program, &TimeZone::default())
+        .ok()?;
+
+    target.try_into().ok()
+}
+
+/// Encodes `event` the way the API would have encoded the original event
+fn encode(event: &Value, encoding: TapEncodingFormat) -> String {
+    match encoding {
+        TapEncodingFormat::Json => event.to_string(),
+        TapEncodingFormat::Yaml => serde_yaml::to_string(event)
+            .expect("YAML serialization of event failed. Please report."),
+        TapEncodingFormat::Logfmt => match event {
+            Value::Object(map) => encode_logfmt::to_string(
+                &map.iter()
+                    .map(|(key, value)| (key.clone(), value))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+            .expect("logfmt serialization of event failed. Please report."),
+            _ => event.to_string(),
+        },
+    }
+}
+
+/// Renders a value as a single-line `table` cell. Strings are shown without quotes.
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.replace('\n', " "),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// Column layout of `table` output, fixed by the first event printed
+#[derive(Clone)]
+struct TableLayout {
+    columns: Vec<String>,
+    widths: Vec<usize>,
+}
+
+impl TableLayout {
+    fn new(columns: Vec<String>, first_row: &[String]) -> Self {
+        let widths = columns
+            .iter()
+            .zip(first_row)
+            .map(|(column, value)| {
+                column
+                    .chars()
+                    .count()
+                    .max(value.chars().count())
+                    .min(MAX_COLUMN_WIDTH)
+            })
+            .collect();
+
+        Self { columns, widths }
+    }
+
+    /// Pads each cell to the width of its column, truncating cells that don't fit. Columns of
+    /// zero width, such as an empty field whose first value was empty, are skipped.
+    fn render(&self, cells: &[String]) -> String {
+        let row = cells
+            .iter()
+            .zip(&self.widths)
+            .filter(|(_, width)| **width > 0)
+            .map(|(cell, &width)| {
+                if cell.chars().count() > width {
+                    let truncated = cell
+                        .chars()
+                        .take(width.saturating_sub(1))
+                        .collect::<String>();
+                    format!("{}…", truncated)
+                } else {
+                    format!("{:width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        row.trim_end().to_string()
+    }
+}
+
 #[derive(Clone)]
 struct EventFormatter {
     meta: bool,
-    format: TapEncodingFormat,
+    format: OutputFormat,
+    fields: Vec<String>,
+    program: Option<Program>,
+    table: Option<TableLayout>,
     component_id_label: ColoredString,
     component_kind_label: ColoredString,
     component_type_label: ColoredString,
 }
 
 impl EventFormatter {
-    fn new(meta: bool, format: TapEncodingFormat) -> Self {
+    fn new(
+        meta: bool,
+        format: OutputFormat,
+        fields: Vec<String>,
+        program: Option<Program>,
+    ) -> Self {
         Self {
             meta,
             format,
+            fields,
+            program,
+            table: None,
             component_id_label: "component_id".green(),
             component_kind_label: "component_kind".green(),
             component_type_label: "component_type".green(),
         }
     }
 
+    /// Returns the encoding to request events in. Remapped events, projected fields and tables
+    /// are rendered here, from events requested as JSON.
+    fn request_encoding(&self) -> TapEncodingFormat {
+        if self.fields.is_empty() && self.program.is_none() {
+            self.format.encoding()
+        } else {
+            TapEncodingFormat::Json
+        }
+    }
+
+    /// Formats an event for printing, or returns `None` if the `--vrl` program dropped it
     fn format<'a>(
-        &self,
+        &mut self,
         component_id: &str,
         component_kind: &str,
         component_type: &str,
         event: &'a str,
-    ) -> Cow<'a, str> {
-        if self.meta {
-            match self.format {
+    ) -> Option<Cow<'a, str>> {
+        let remapped = match &self.program {
+            Some(program) => Some(remap(program, parse_event(event))?),
+            None => None,
+        };
+
+        if self.format == OutputFormat::Table {
+            let event = remapped.unwrap_or_else(|| parse_event(event));
+            return Some(
+                self.table_row(component_id, component_kind, component_type, &event)
+                    .into(),
+            );
+        }
+
+        let event: Cow<'a, str> = if remapped.is_none() && self.fields.is_empty() {
+            event.into()
+        } else {
+            let event = remapped.unwrap_or_else(|| parse_event(event));
+            if self.fields.is_empty() {
+                encode(&event, self.format.encoding()).into()
+            } else {
+                encode(&project(&event, &self.fields), self.format.encoding()).into()
+            }
+        };
+
+        Some(if self.meta {
+            match self.format.encoding() {
                 TapEncodingFormat::Json => format!(
                     r#"{{"{}":"{}","{}":"{}","{}":"{}","event":{}}}"#,
                     self.component_id_label,
@@ -180,7 +384,7 @@ impl EventFormatter {
                 .into(),
                 TapEncodingFormat::Yaml => {
                     let mut value: BTreeMap<String, serde_yaml::Value> = BTreeMap::new();
-                    value.insert("event".to_string(), serde_yaml::from_str(event).unwrap());
+                    value.insert("event".to_string(), serde_yaml::from_str(&event).unwrap());
                     // We interpolate to include component_id rather than
                     // include it in the map to correctly preserve color
                     // formatting
@@ -209,7 +413,148 @@ impl EventFormatter {
                 .into(),
             }
         } else {
-            event.into()
+            event
+        })
+    }
+
+    /// Returns the `table` row for an event, preceded by the table header for the first event.
+    /// Columns are the projected fields if any, or else the top-level fields of the first event.
+    fn table_row(
+        &mut self,
+        component_id: &str,
+        component_kind: &str,
+        component_type: &str,
+        event: &Value,
+    ) -> String {
+        let cells = |columns: &[String]| {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| match (self.meta, i) {
+                    (true, 0) => component_id.to_string(),
+                    (true, 1) => component_kind.to_string(),
+                    (true, 2) => component_type.to_string(),
+                    _ => cell(lookup(event, column)),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut output = String::new();
+        if self.table.is_none() {
+            let mut columns = Vec::new();
+            if self.meta {
+                columns.extend(META_COLUMNS.iter().map(|c| c.to_string()));
+            }
+            if self.fields.is_empty() {
+                if let Value::Object(map) = event {
+                    columns.extend(map.keys().cloned());
+                }
+            } else {
+                columns.extend(self.fields.iter().cloned());
+            }
+
+            let first_row = cells(&columns);
+            let layout = TableLayout::new(columns, &first_row);
+            output.push_str(&layout.render(&layout.columns).bold().to_string());
+            output.push('\n');
+            self.table = Some(layout);
         }
+
+        let layout = self
+            .table
+            .as_ref()
+            .expect("Table layout is set from the first event");
+        output.push_str(&layout.render(&cells(&layout.columns)));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_nested_fields() {
+        let event = parse_event(r#"{"message":"hi","host":"a","tags":{"env":"prod"}}"#);
+        let fields = vec![
+            "message".to_string(),
+            "tags.env".to_string(),
+            "missing".to_string(),
+        ];
+
+        assert_eq!(
+            encode(&project(&event, &fields), TapEncodingFormat::Json),
+            r#"{"message":"hi","tags.env":"prod"}"#
+        );
+        assert_eq!(
+            encode(&project(&event, &fields), TapEncodingFormat::Logfmt),
+            r#"message=hi tags.env=prod"#
+        );
+    }
+
+    #[test]
+    fn table_rows() {
+        let mut formatter = EventFormatter::new(
+            false,
+            OutputFormat::Table,
+            vec!["message".to_string(), "count".to_string()],
+            None,
+        );
+
+        assert_eq!(
+            formatter.format(
+                "in",
+                "source",
+                "demo_logs",
+                r#"{"message":"hello","count":1}"#
+            ),
+            Some(format!("{}\nhello    1", "message  count".bold()).into())
+        );
+        assert_eq!(
+            formatter.format(
+                "in",
+                "source",
+                "demo_logs",
+                r#"{"message":"a much longer message","count":20}"#
+            ),
+            Some("a much…  20".into())
+        );
+    }
+
+    #[test]
+    fn table_skips_zero_width_columns() {
+        let layout = TableLayout::new(
+            vec![String::new(), "count".to_string()],
+            &[String::new(), "1".to_string()],
+        );
+
+        assert_eq!(
+            layout.render(&["not empty".to_string(), "20".to_string()]),
+            "20"
+        );
+    }
+
+    #[test]
+    fn vrl_remaps_and_drops_events() {
+        let program = vrl::compile(
+            r#"if .status == 200 { abort }
+            .status_text = "error""#,
+            &vrl_stdlib::all(),
+        )
+        .unwrap();
+        let mut formatter = EventFormatter::new(false, OutputFormat::Json, vec![], Some(program));
+
+        assert!(matches!(
+            formatter.request_encoding(),
+            TapEncodingFormat::Json
+        ));
+        assert_eq!(
+            formatter.format("in", "source", "demo_logs", r#"{"status":200}"#),
+            None
+        );
+        assert_eq!(
+            formatter.format("in", "source", "demo_logs", r#"{"status":500}"#),
+            Some(r#"{"status":500,"status_text":"error"}"#.into())
+        );
     }
 }
@@ -5,6 +5,41 @@ pub(crate) use cmd::cmd;
 use url::Url;
 use vector_api_client::gql::TapEncodingFormat;
 
+/// Format of events printed to screen. `table` is rendered by `vector tap` itself, from
+/// events requested as JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Logfmt,
+    Table,
+}
+
+impl OutputFormat {
+    /// Returns the format to encode events in when printing them as-is
+    const fn encoding(self) -> TapEncodingFormat {
+        match self {
+            Self::Json | Self::Table => TapEncodingFormat::Json,
+            Self::Yaml => TapEncodingFormat::Yaml,
+            Self::Logfmt => TapEncodingFormat::Logfmt,
+        }
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "yaml" => Ok(Self::Yaml),
+            "logfmt" => Ok(Self::Logfmt),
+            "table" => Ok(Self::Table),
+            _ => Err("Invalid output format".to_string()),
+        }
+    }
+}
+
 #[derive(Parser, Debug, Clone)]
 #[clap(rename_all = "kebab-case")]
 pub struct Opts {
@@ -21,8 +56,16 @@ pub struct Opts {
     limit: u32,
 
     /// Encoding format for events printed to screen
-    #[clap(default_value = "json", possible_values = &["json", "yaml", "logfmt"], short = 'f', long)]
-    format: TapEncodingFormat,
+    #[clap(default_value = "json", possible_values = &["json", "yaml", "logfmt", "table"], short = 'f', long)]
+    format: OutputFormat,
+
+    /// Fields of events to print (comma-separated; nested fields are separated by `.`, e.g. `host,tags.env`). Prints whole events if omitted
+    #[clap(use_value_delimiter(true), long)]
+    fields: Vec<String>,
+
+    /// VRL program to run on each event before printing it, e.g. `del(.host)`. Events for which the program aborts or fails aren't printed, so `if .status == 200 { abort }` filters events
+    #[clap(long)]
+    vrl: Option<String>,
 
     /// Components IDs to observe (comma-separated; accepts glob patterns)
     #[clap(use_value_delimiter(true))]
@@ -47,4 +90,7 @@ pub struct Opts {
     /// Whether to reconnect if the underlying Vector API connection drops. By default, tap will attempt to reconnect if the connection drops.
     #[clap(short, long)]
     no_reconnect: bool,
+
+    #[clap(flatten)]
+    api: crate::cli::ApiClientOpts,
 }
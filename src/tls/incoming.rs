@@ -20,6 +20,7 @@ use super::{
     CreateAcceptorSnafu, HandshakeSnafu, IncomingListenerSnafu, MaybeTlsSettings, MaybeTlsStream,
     SslBuildSnafu, TcpBindSnafu, TlsError, TlsSettings,
 };
+use crate::internal_events::TlsCertificatesReloadError;
 #[cfg(feature = "sources-utils-tcp-socket")]
 use crate::tcp;
 #[cfg(feature = "sources-utils-tcp-keepalive")]
@@ -43,22 +44,31 @@ impl MaybeTlsSettings {
     pub(crate) async fn bind(&self, addr: &SocketAddr) -> crate::tls::Result<MaybeTlsListener> {
         let listener = TcpListener::bind(addr).await.context(TcpBindSnafu)?;
 
-        let acceptor = match self {
-            Self::Tls(tls) => Some(tls.acceptor()?),
-            Self::Raw(()) => None,
+        let (acceptor, generation) = match self {
+            Self::Tls(tls) => (Some(tls.acceptor()?), tls.generation()),
+            Self::Raw(()) => (None, 0),
         };
 
-        Ok(MaybeTlsListener { listener, acceptor })
+        Ok(MaybeTlsListener {
+            listener,
+            acceptor,
+            settings: self.tls().cloned(),
+            generation,
+        })
     }
 }
 
 pub struct MaybeTlsListener {
     listener: TcpListener,
     acceptor: Option<SslAcceptor>,
+    settings: Option<TlsSettings>,
+    /// The generation of the certificates of the acceptor.
+    generation: u64,
 }
 
 impl MaybeTlsListener {
     pub(crate) async fn accept(&mut self) -> crate::tls::Result<MaybeTlsIncomingStream<TcpStream>> {
+        self.reload_acceptor();
         self.listener
             .accept()
             .await
@@ -68,6 +78,23 @@ impl MaybeTlsListener {
             .context(IncomingListenerSnafu)
     }
 
+    /// Builds the acceptor again if the certificates were reloaded, for the new connections.
+    fn reload_acceptor(&mut self) {
+        if let Some(settings) = &self.settings {
+            let generation = settings.generation();
+            if generation != self.generation {
+                self.generation = generation;
+                match settings.acceptor() {
+                    Ok(acceptor) => self.acceptor = Some(acceptor),
+                    Err(error) => emit!(&TlsCertificatesReloadError {
+                        source: "acceptor",
+                        error: error.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+
     async fn into_accept(
         mut self,
     ) -> (crate::tls::Result<MaybeTlsIncomingStream<TcpStream>>, Self) {
@@ -137,6 +164,8 @@ impl From<TcpListener> for MaybeTlsListener {
         Self {
             listener,
             acceptor: None,
+            settings: None,
+            generation: 0,
         }
     }
 }
@@ -357,3 +386,41 @@ impl AsyncWrite for MaybeTlsIncomingStream<TcpStream> {
         }
     }
 }
+
+#[cfg(feature = "tonic")]
+mod tonic {
+    use std::net::SocketAddr;
+
+    use tokio::net::TcpStream;
+    use tonic::transport::{server::Connected, Certificate};
+
+    use super::MaybeTlsIncomingStream;
+
+    #[derive(Clone)]
+    pub struct MaybeTlsConnectInfo {
+        pub remote_addr: SocketAddr,
+        pub peer_certs: Option<Vec<Certificate>>,
+    }
+
+    impl Connected for MaybeTlsIncomingStream<TcpStream> {
+        type ConnectInfo = MaybeTlsConnectInfo;
+
+        fn connect_info(&self) -> Self::ConnectInfo {
+            MaybeTlsConnectInfo {
+                remote_addr: self.peer_addr(),
+                peer_certs: self
+                    .ssl_stream()
+                    .and_then(|s| s.ssl().peer_cert_chain())
+                    .map(|s| {
+                        s.into_iter()
+                            .filter_map(|c| c.to_pem().ok())
+                            .map(Certificate::from_pem)
+                            .collect()
+                    }),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tonic")]
+pub use self::tonic::MaybeTlsConnectInfo;
@@ -14,12 +14,17 @@ use crate::tcp::{self, TcpKeepaliveConfig};
 mod incoming;
 mod maybe_tls;
 mod outgoing;
+mod reload;
 mod settings;
+#[cfg(all(unix, feature = "tls-spiffe"))]
+mod spiffe;
 
+#[cfg(all(feature = "sources-utils-tls", feature = "tonic"))]
+pub use incoming::MaybeTlsConnectInfo;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 pub(crate) use incoming::{MaybeTlsIncomingStream, MaybeTlsListener};
 pub(crate) use maybe_tls::MaybeTls;
-pub use settings::{MaybeTlsSettings, TlsConfig, TlsOptions, TlsSettings};
+pub use settings::{MaybeTlsSettings, SpiffeOptions, TlsConfig, TlsOptions, TlsSettings};
 #[cfg(test)]
 pub use settings::{TEST_PEM_CA_PATH, TEST_PEM_CRT_PATH, TEST_PEM_KEY_PATH};
 
@@ -120,6 +125,8 @@ pub enum TlsError {
     NewCaStack { source: ErrorStack },
     #[snafu(display("Could not push intermediate certificate onto stack"))]
     CaStackPush { source: ErrorStack },
+    #[snafu(display("Could not fetch the SVID from the SPIFFE Workload API: {}", message))]
+    SpiffeWorkloadApi { message: String },
 }
 
 impl MaybeTlsStream<TcpStream> {
@@ -18,20 +18,28 @@ impl MaybeTlsSettings {
 
         match self {
             MaybeTlsSettings::Raw(()) => Ok(MaybeTlsStream::Raw(stream)),
-            MaybeTlsSettings::Tls(_) => {
-                let config = tls_connector(self)?;
-                let ssl = config.into_ssl(host).context(SslBuildSnafu)?;
+            MaybeTlsSettings::Tls(_) => self.upgrade(host, stream).await.map(MaybeTlsStream::Tls),
+        }
+    }
+
+    /// Negotiates TLS over an established connection, such as one switching to TLS after a
+    /// `STARTTLS` command.
+    pub(crate) async fn upgrade(
+        &self,
+        host: &str,
+        stream: TcpStream,
+    ) -> crate::tls::Result<SslStream<TcpStream>> {
+        let config = tls_connector(self)?;
+        let ssl = config.into_ssl(host).context(SslBuildSnafu)?;
 
-                let mut stream = SslStream::new(ssl, stream).context(SslBuildSnafu)?;
-                Pin::new(&mut stream)
-                    .connect()
-                    .await
-                    .context(HandshakeSnafu)?;
+        let mut stream = SslStream::new(ssl, stream).context(SslBuildSnafu)?;
+        Pin::new(&mut stream)
+            .connect()
+            .await
+            .context(HandshakeSnafu)?;
 
-                debug!(message = "Negotiated TLS.");
+        debug!(message = "Negotiated TLS.");
 
-                Ok(MaybeTlsStream::Tls(stream))
-            }
-        }
+        Ok(stream)
     }
 }
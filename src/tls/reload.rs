@@ -0,0 +1,257 @@
+//! Reloading of the certificates and keys, when their files change or when the SPIFFE Workload
+//! API rotates them, without restarting the components using them.
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use openssl::{
+    ssl::{SslConnector, SslContext, SslMethod},
+    x509::X509,
+};
+use snafu::ResultExt;
+
+use super::{
+    settings::{IdentityStore, PEM_START_MARKER},
+    TlsBuildConnectorSnafu, TlsOptions, TlsSettings,
+};
+use crate::internal_events::{TlsCertificatesReloadError, TlsCertificatesReloaded};
+
+/// How often the files are checked for changes, at most.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The certificate authorities and identity of the TLS settings.
+#[derive(Clone, Default)]
+pub(super) struct TlsMaterial {
+    pub(super) authorities: Vec<X509>,
+    pub(super) identity: Option<IdentityStore>,
+}
+
+pub(super) struct Reloader {
+    /// The files to load the material from again when they are modified, if not updated by the
+    /// SPIFFE Workload API.
+    files: Option<WatchedFiles>,
+    state: Mutex<State>,
+}
+
+struct WatchedFiles {
+    options: TlsOptions,
+    paths: Vec<PathBuf>,
+}
+
+struct State {
+    material: TlsMaterial,
+    generation: u64,
+    checked_at: Instant,
+    modified: Vec<Option<SystemTime>>,
+    /// The context for the connectors built before the reload, with its generation.
+    connector_context: Option<(u64, SslContext)>,
+}
+
+impl State {
+    fn new(material: TlsMaterial, modified: Vec<Option<SystemTime>>) -> Self {
+        Self {
+            material,
+            generation: 0,
+            checked_at: Instant::now(),
+            modified,
+            connector_context: None,
+        }
+    }
+}
+
+impl Reloader {
+    /// Watches the files of the options, unless the certificates and keys are all inline.
+    pub(super) fn for_files(options: &TlsOptions, material: TlsMaterial) -> Option<Self> {
+        let paths = [&options.ca_file, &options.crt_file, &options.key_file]
+            .iter()
+            .filter_map(|path| path.as_ref())
+            .filter(|path| {
+                !path
+                    .to_str()
+                    .map_or(false, |path| path.contains(PEM_START_MARKER))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return None;
+        }
+
+        let modified = modified_times(&paths);
+        Some(Self {
+            files: Some(WatchedFiles {
+                options: options.clone(),
+                paths,
+            }),
+            state: Mutex::new(State::new(material, modified)),
+        })
+    }
+
+    /// Holds the material, until replaced with `update`.
+    #[cfg(all(unix, feature = "tls-spiffe"))]
+    pub(super) fn for_updates(material: TlsMaterial) -> Self {
+        Self {
+            files: None,
+            state: Mutex::new(State::new(material, Vec::new())),
+        }
+    }
+
+    #[cfg(all(unix, feature = "tls-spiffe"))]
+    pub(super) fn update(&self, material: TlsMaterial, source: &'static str) {
+        let mut state = self.state.lock().unwrap();
+        state.material = material;
+        state.generation += 1;
+        emit!(&TlsCertificatesReloaded { source });
+    }
+
+    pub(super) fn material(&self) -> TlsMaterial {
+        self.refresh();
+        self.state.lock().unwrap().material.clone()
+    }
+
+    pub(super) fn generation(&self) -> u64 {
+        self.refresh();
+        self.state.lock().unwrap().generation
+    }
+
+    /// Loads the files again if they were modified. While they can't be loaded, as when they are
+    /// only partly replaced yet, the previous certificates and keys remain in use.
+    fn refresh(&self) {
+        let files = match &self.files {
+            Some(files) => files,
+            None => return,
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.checked_at.elapsed() < CHECK_INTERVAL {
+            return;
+        }
+        state.checked_at = Instant::now();
+
+        let modified = modified_times(&files.paths);
+        if modified == state.modified {
+            return;
+        }
+        match files.options.load_material() {
+            Ok(material) => {
+                state.material = material;
+                state.generation += 1;
+                state.modified = modified;
+                emit!(&TlsCertificatesReloaded { source: "files" });
+            }
+            Err(error) => emit!(&TlsCertificatesReloadError {
+                source: "files",
+                error: error.to_string(),
+            }),
+        }
+    }
+
+    /// The context with the reloaded certificates and keys for the connections of the connectors
+    /// built once, or `None` if they were never reloaded.
+    pub(super) fn connector_context(&self, settings: &TlsSettings) -> Option<SslContext> {
+        let generation = self.generation();
+        if generation == 0 {
+            return None;
+        }
+        if let Some((built, context)) = &self.state.lock().unwrap().connector_context {
+            if *built == generation {
+                return Some(context.clone());
+            }
+        }
+
+        let built = SslConnector::builder(SslMethod::tls())
+            .context(TlsBuildConnectorSnafu)
+            .and_then(|mut builder| {
+                settings.apply_context(&mut builder)?;
+                Ok(builder.build().into_context())
+            });
+        match built {
+            Ok(context) => {
+                self.state.lock().unwrap().connector_context = Some((generation, context.clone()));
+                Some(context)
+            }
+            Err(error) => {
+                emit!(&TlsCertificatesReloadError {
+                    source: "connector",
+                    error: error.to_string(),
+                });
+                None
+            }
+        }
+    }
+}
+
+fn modified_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tls::{TEST_PEM_CA_PATH, TEST_PEM_CRT_PATH, TEST_PEM_KEY_PATH};
+
+    fn options(dir: &std::path::Path) -> TlsOptions {
+        for (source, name) in [
+            (TEST_PEM_CA_PATH, "ca.crt"),
+            (TEST_PEM_CRT_PATH, "localhost.crt"),
+            (TEST_PEM_KEY_PATH, "localhost.key"),
+        ] {
+            fs::copy(source, dir.join(name)).unwrap();
+        }
+        TlsOptions {
+            ca_file: Some(dir.join("ca.crt")),
+            crt_file: Some(dir.join("localhost.crt")),
+            key_file: Some(dir.join("localhost.key")),
+            ..TlsOptions::default()
+        }
+    }
+
+    fn expire_check(reloader: &Reloader) {
+        let mut state = reloader.state.lock().unwrap();
+        state.checked_at -= CHECK_INTERVAL;
+        // Detect the change even though the modification times may be too coarse.
+        state.modified.clear();
+    }
+
+    #[test]
+    fn reloads_modified_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = options(dir.path());
+        let reloader = Reloader::for_files(&options, options.load_material().unwrap()).unwrap();
+        assert_eq!(reloader.generation(), 0);
+
+        expire_check(&reloader);
+        assert_eq!(reloader.generation(), 1);
+        assert_eq!(reloader.material().authorities.len(), 1);
+    }
+
+    #[test]
+    fn keeps_material_while_files_are_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = options(dir.path());
+        let reloader = Reloader::for_files(&options, options.load_material().unwrap()).unwrap();
+
+        fs::write(dir.path().join("localhost.key"), "").unwrap();
+        expire_check(&reloader);
+        assert_eq!(reloader.generation(), 0);
+        assert!(reloader.material().identity.is_some());
+    }
+
+    #[test]
+    fn ignores_inline_certificates() {
+        let options = TlsOptions {
+            ca_file: Some(fs::read_to_string(TEST_PEM_CA_PATH).unwrap().into()),
+            ..TlsOptions::default()
+        };
+        assert!(Reloader::for_files(&options, TlsMaterial::default()).is_none());
+    }
+}
@@ -3,26 +3,30 @@ use std::{
     fs::File,
     io::Read,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use openssl::{
     pkcs12::{ParsedPkcs12, Pkcs12},
-    pkey::{PKey, Private},
+    pkey::{PKey, PKeyRef, Private},
     ssl::{ConnectConfiguration, SslContextBuilder, SslVerifyMode},
     stack::Stack,
-    x509::{store::X509StoreBuilder, X509},
+    x509::{store::X509StoreBuilder, X509Ref, X509},
 };
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
+#[cfg(all(unix, feature = "tls-spiffe"))]
+use super::spiffe;
 use super::{
+    reload::{Reloader, TlsMaterial},
     AddCertToStoreSnafu, AddExtraChainCertSnafu, CaStackPushSnafu, DerExportSnafu,
     FileOpenFailedSnafu, FileReadFailedSnafu, MaybeTls, NewCaStackSnafu, NewStoreBuilderSnafu,
     ParsePkcs12Snafu, Pkcs12Snafu, PrivateKeyParseSnafu, Result, SetCertificateSnafu,
     SetPrivateKeySnafu, SetVerifyCertSnafu, TlsError, TlsIdentitySnafu, X509ParseSnafu,
 };
 
-const PEM_START_MARKER: &str = "-----BEGIN ";
+pub(super) const PEM_START_MARKER: &str = "-----BEGIN ";
 
 #[cfg(test)]
 pub const TEST_PEM_CA_PATH: &str = "tests/data/Vector_CA.crt";
@@ -31,7 +35,7 @@ pub const TEST_PEM_CRT_PATH: &str = "tests/data/localhost.crt";
 #[cfg(test)]
 pub const TEST_PEM_KEY_PATH: &str = "tests/data/localhost.key";
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct TlsConfig {
     pub enabled: Option<bool>,
     #[serde(flatten)]
@@ -56,7 +60,7 @@ impl TlsConfig {
 }
 
 /// Standard TLS options
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct TlsOptions {
     pub verify_certificate: Option<bool>,
     pub verify_hostname: Option<bool>,
@@ -67,6 +71,22 @@ pub struct TlsOptions {
     #[serde(alias = "key_path")]
     pub key_file: Option<PathBuf>,
     pub key_pass: Option<String>,
+    /// Whether the certificates and keys are reloaded when their files change, the new
+    /// connections then using them.
+    pub reload_certificates: Option<bool>,
+    pub spiffe: Option<SpiffeOptions>,
+}
+
+/// Fetches the certificate and key, as an SVID, and the certificate authorities from the SPIFFE
+/// Workload API, which rotates them.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SpiffeOptions {
+    /// The endpoint of the Workload API, as a `unix:` URI, defaulting to the
+    /// `SPIFFE_ENDPOINT_SOCKET` environment variable.
+    pub endpoint_socket: Option<String>,
+    /// The SPIFFE ID of the SVID to use, when the workload has several, defaulting to the first.
+    pub spiffe_id: Option<String>,
 }
 
 impl TlsOptions {
@@ -88,11 +108,51 @@ pub struct TlsSettings {
     pub(super) verify_hostname: bool,
     authorities: Vec<X509>,
     pub(super) identity: Option<IdentityStore>, // openssl::pkcs12::ParsedPkcs12 doesn't impl Clone yet
+    /// Holds the certificates and keys replacing the ones above, once reloaded.
+    reloader: Option<Arc<Reloader>>,
 }
 
 #[derive(Clone)]
 pub struct IdentityStore(Vec<u8>, String);
 
+impl IdentityStore {
+    /// Archives the certificate with its key and its chain of intermediate certificates.
+    pub(super) fn build(
+        name: &str,
+        key: &PKeyRef<Private>,
+        crt: &X509Ref,
+        chain: impl IntoIterator<Item = X509>,
+    ) -> Result<Self> {
+        let mut ca_stack = Stack::new().context(NewCaStackSnafu)?;
+        for intermediate in chain {
+            ca_stack.push(intermediate).context(CaStackPushSnafu)?;
+        }
+
+        let mut builder = Pkcs12::builder();
+        builder.ca(ca_stack);
+        let pkcs12 = builder.build("", name, key, crt).context(Pkcs12Snafu)?;
+        let identity = pkcs12.to_der().context(DerExportSnafu)?;
+
+        // Build the resulting parsed PKCS#12 archive,
+        // but don't store it, as it cannot be cloned.
+        // This is just for error checking.
+        pkcs12.parse("").context(TlsIdentitySnafu)?;
+
+        Ok(Self(identity, "".into()))
+    }
+
+    fn parse(&self) -> ParsedPkcs12 {
+        // This data was test-built previously, so we can just use it
+        // here and expect the results will not fail. This can all be
+        // reworked when `openssl::pkcs12::ParsedPkcs12` gains the Clone
+        // impl.
+        Pkcs12::from_der(&self.0)
+            .expect("Could not build PKCS#12 archive from parsed data")
+            .parse(&self.1)
+            .expect("Could not parse stored PKCS#12 archive")
+    }
+}
+
 impl TlsSettings {
     /// Generate a filled out settings struct from the given optional
     /// option set, interpreted as client options. If `options` is
@@ -119,25 +179,49 @@ impl TlsSettings {
             }
         }
 
+        let (material, reloader) = match &options.spiffe {
+            Some(spiffe) => {
+                let reloader = load_spiffe(spiffe, options.load_authorities()?)?;
+                (reloader.material(), Some(reloader))
+            }
+            None => {
+                let material = options.load_material()?;
+                let reloader = if options.reload_certificates.unwrap_or(true) {
+                    Reloader::for_files(options, material.clone()).map(Arc::new)
+                } else {
+                    None
+                };
+                (material, reloader)
+            }
+        };
+
         Ok(Self {
             verify_certificate: options.verify_certificate.unwrap_or(!for_server),
             verify_hostname: options.verify_hostname.unwrap_or(!for_server),
-            authorities: options.load_authorities()?,
-            identity: options.load_identity()?,
+            authorities: material.authorities,
+            identity: material.identity,
+            reloader,
         })
     }
 
-    fn identity(&self) -> Option<ParsedPkcs12> {
-        // This data was test-built previously, so we can just use it
-        // here and expect the results will not fail. This can all be
-        // reworked when `openssl::pkcs12::ParsedPkcs12` gains the Clone
-        // impl.
-        self.identity.as_ref().map(|identity| {
-            Pkcs12::from_der(&identity.0)
-                .expect("Could not build PKCS#12 archive from parsed data")
-                .parse(&identity.1)
-                .expect("Could not parse stored PKCS#12 archive")
-        })
+    /// The certificate authorities and identity in use, reloaded if they changed.
+    fn material(&self) -> TlsMaterial {
+        match &self.reloader {
+            Some(reloader) => reloader.material(),
+            None => TlsMaterial {
+                authorities: self.authorities.clone(),
+                identity: self.identity.clone(),
+            },
+        }
+    }
+
+    /// The generation of the certificates and keys in use, which is incremented every time they
+    /// are reloaded.
+    #[cfg(feature = "sources-utils-tls")]
+    pub(super) fn generation(&self) -> u64 {
+        self.reloader
+            .as_ref()
+            .map_or(0, |reloader| reloader.generation())
     }
 
     pub(super) fn apply_context(&self, context: &mut SslContextBuilder) -> Result<()> {
@@ -146,7 +230,8 @@ impl TlsSettings {
         } else {
             SslVerifyMode::NONE
         });
-        if let Some(identity) = self.identity() {
+        let material = self.material();
+        if let Some(identity) = material.identity.as_ref().map(IdentityStore::parse) {
             context
                 .set_certificate(&identity.cert)
                 .context(SetCertificateSnafu)?;
@@ -161,9 +246,9 @@ impl TlsSettings {
                 }
             }
         }
-        if !self.authorities.is_empty() {
+        if !material.authorities.is_empty() {
             let mut store = X509StoreBuilder::new().context(NewStoreBuilderSnafu)?;
-            for authority in &self.authorities {
+            for authority in &material.authorities {
                 store
                     .add_cert(authority.clone())
                     .context(AddCertToStoreSnafu)?;
@@ -186,10 +271,47 @@ impl TlsSettings {
 
     pub fn apply_connect_configuration(&self, connection: &mut ConnectConfiguration) {
         connection.set_verify_hostname(self.verify_hostname);
+        // The connectors are built once with the certificates loaded at the time, so the
+        // connections are switched to the reloaded ones.
+        if let Some(context) = self
+            .reloader
+            .as_ref()
+            .and_then(|reloader| reloader.connector_context(self))
+        {
+            if let Err(error) = connection.set_ssl_context(&context) {
+                warn!(message = "Failed to use the reloaded TLS certificates.", %error);
+            }
+        }
+    }
+    /// The client identity as a DER-encoded PKCS#12 archive and its password, for clients
+    /// that set up their TLS connector themselves.
+    pub fn identity_pkcs12_der(&self) -> Option<(Vec<u8>, String)> {
+        self.material()
+            .identity
+            .map(|identity| (identity.0, identity.1))
     }
 }
 
+#[cfg(all(unix, feature = "tls-spiffe"))]
+fn load_spiffe(options: &SpiffeOptions, authorities: Vec<X509>) -> Result<Arc<Reloader>> {
+    spiffe::watch(options, authorities)
+}
+
+#[cfg(not(all(unix, feature = "tls-spiffe")))]
+fn load_spiffe(_: &SpiffeOptions, _: Vec<X509>) -> Result<Arc<Reloader>> {
+    Err(TlsError::SpiffeWorkloadApi {
+        message: "SPIFFE is not supported by this build of Vector".into(),
+    })
+}
+
 impl TlsOptions {
+    pub(super) fn load_material(&self) -> Result<TlsMaterial> {
+        Ok(TlsMaterial {
+            authorities: self.load_authorities()?,
+            identity: self.load_identity()?,
+        })
+    }
+
     fn load_authorities(&self) -> Result<Vec<X509>> {
         match &self.ca_file {
             None => Ok(vec![]),
@@ -237,22 +359,7 @@ impl TlsOptions {
                 let crt = crt_stack.next().ok_or(TlsError::MissingCertificate)?;
                 let key = load_key(key_file, &self.key_pass)?;
 
-                let mut ca_stack = Stack::new().context(NewCaStackSnafu)?;
-                for intermediate in crt_stack {
-                    ca_stack.push(intermediate).context(CaStackPushSnafu)?;
-                }
-
-                let mut builder = Pkcs12::builder();
-                builder.ca(ca_stack);
-                let pkcs12 = builder.build("", &name, &key, &crt).context(Pkcs12Snafu)?;
-                let identity = pkcs12.to_der().context(DerExportSnafu)?;
-
-                // Build the resulting parsed PKCS#12 archive,
-                // but don't store it, as it cannot be cloned.
-                // This is just for error checking.
-                pkcs12.parse("").context(TlsIdentitySnafu)?;
-
-                Ok(Some(IdentityStore(identity, "".into())))
+                IdentityStore::build(&name, &key, &crt, crt_stack).map(Some)
             }
         }
     }
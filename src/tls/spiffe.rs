@@ -0,0 +1,273 @@
+//! Fetching of the X.509 SVIDs, the certificates identifying the workloads, and of the CA
+//! certificates of their trust domain from the SPIFFE Workload API.
+
+use std::{
+    path::PathBuf,
+    sync::{mpsc, Arc, Weak},
+    thread,
+    time::Duration,
+};
+
+use futures::StreamExt;
+use openssl::{pkey::PKey, x509::X509};
+use tokio::net::UnixStream;
+use tonic::{
+    metadata::MetadataValue,
+    transport::{Endpoint, Uri},
+    Request,
+};
+use tower::service_fn;
+
+use super::{
+    reload::{Reloader, TlsMaterial},
+    settings::IdentityStore,
+    Result, SpiffeOptions, TlsError,
+};
+use crate::{
+    internal_events::TlsCertificatesReloadError,
+    proto::spiffe::{Client, X509svidRequest, X509svidResponse},
+};
+
+const ENDPOINT_SOCKET_ENV: &str = "SPIFFE_ENDPOINT_SOCKET";
+
+/// How long to wait for the first SVID before failing.
+const FIRST_SVID_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait before fetching the SVIDs again, after the connection to the Workload API is
+/// lost.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Fetches the first SVID, then keeps receiving the rotated ones, until the reloader is dropped.
+/// The authorities are trusted in addition to those of the trust domain.
+pub(super) fn watch(options: &SpiffeOptions, authorities: Vec<X509>) -> Result<Arc<Reloader>> {
+    let path = socket_path(options).map_err(|message| TlsError::SpiffeWorkloadApi { message })?;
+    let spiffe_id = options.spiffe_id.clone();
+
+    // The settings are loaded synchronously, and the SVIDs keep being received for as long as they
+    // are used, so they are fetched on their own thread.
+    let (first, received) = mpsc::channel();
+    thread::Builder::new()
+        .name("spiffe-workload-api".into())
+        .spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Unable to create the SPIFFE Workload API runtime.");
+            runtime.block_on(receive(path, spiffe_id, authorities, first));
+        })
+        .map_err(|error| TlsError::SpiffeWorkloadApi {
+            message: error.to_string(),
+        })?;
+
+    match received.recv_timeout(FIRST_SVID_TIMEOUT) {
+        Ok(result) => result.map_err(|message| TlsError::SpiffeWorkloadApi { message }),
+        Err(_) => Err(TlsError::SpiffeWorkloadApi {
+            message: "timed out waiting for the first SVID".into(),
+        }),
+    }
+}
+
+async fn receive(
+    path: PathBuf,
+    spiffe_id: Option<String>,
+    authorities: Vec<X509>,
+    first: mpsc::Sender<std::result::Result<Arc<Reloader>, String>>,
+) {
+    let mut first = Some(first);
+    let mut reloader = Weak::new();
+    loop {
+        let mut responses = match fetch(path.clone()).await {
+            Ok(responses) => responses,
+            Err(error) => match first.take() {
+                Some(first) => {
+                    let _ = first.send(Err(error));
+                    return;
+                }
+                None => {
+                    emit!(&TlsCertificatesReloadError {
+                        source: "spiffe",
+                        error,
+                    });
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            },
+        };
+
+        while let Some(response) = responses.next().await {
+            let material = response
+                .map_err(|status| status.to_string())
+                .and_then(|response| material(response, spiffe_id.as_deref(), &authorities));
+            match (material, first.take()) {
+                (Ok(material), Some(first)) => {
+                    let created = Arc::new(Reloader::for_updates(material));
+                    reloader = Arc::downgrade(&created);
+                    if first.send(Ok(created)).is_err() {
+                        return;
+                    }
+                }
+                (Err(error), Some(first)) => {
+                    let _ = first.send(Err(error));
+                    return;
+                }
+                (Ok(material), None) => match reloader.upgrade() {
+                    Some(reloader) => reloader.update(material, "spiffe"),
+                    // The settings are no longer used.
+                    None => return,
+                },
+                (Err(error), None) => emit!(&TlsCertificatesReloadError {
+                    source: "spiffe",
+                    error,
+                }),
+            }
+        }
+
+        if reloader.strong_count() == 0 {
+            return;
+        }
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+async fn fetch(path: PathBuf) -> std::result::Result<tonic::Streaming<X509svidResponse>, String> {
+    // The URI is ignored, as the connections are made to the socket.
+    let channel = Endpoint::from_static("http://localhost")
+        .connect_with_connector(service_fn(move |_: Uri| UnixStream::connect(path.clone())))
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let mut request = Request::new(X509svidRequest {});
+    // Required by the standard, to prevent server-side request forgery.
+    request
+        .metadata_mut()
+        .insert("workload.spiffe.io", MetadataValue::from_static("true"));
+    Client::new(channel)
+        .fetch_x509svid(request)
+        .await
+        .map(tonic::Response::into_inner)
+        .map_err(|status| status.to_string())
+}
+
+fn material(
+    response: X509svidResponse,
+    spiffe_id: Option<&str>,
+    authorities: &[X509],
+) -> std::result::Result<TlsMaterial, String> {
+    let svid = response
+        .svids
+        .into_iter()
+        .find(|svid| spiffe_id.map_or(true, |spiffe_id| svid.spiffe_id == spiffe_id))
+        .ok_or_else(|| match spiffe_id {
+            Some(spiffe_id) => format!("the workload has no SVID for {:?}", spiffe_id),
+            None => "the workload has no SVID".into(),
+        })?;
+
+    let mut certificates = split_der(&svid.x509_svid)?.into_iter();
+    let certificate = certificates
+        .next()
+        .ok_or_else(|| "the SVID has no certificate".to_owned())?;
+    let key = PKey::private_key_from_pkcs8(&svid.x509_svid_key)
+        .map_err(|error| format!("invalid SVID key: {}", error))?;
+    let identity = IdentityStore::build(&svid.spiffe_id, &key, &certificate, certificates)
+        .map_err(|error| error.to_string())?;
+
+    let mut bundle = split_der(&svid.bundle)?;
+    bundle.extend(authorities.iter().cloned());
+    Ok(TlsMaterial {
+        authorities: bundle,
+        identity: Some(identity),
+    })
+}
+
+/// Splits the concatenated DER encoded certificates.
+fn split_der(mut data: &[u8]) -> std::result::Result<Vec<X509>, String> {
+    let mut certificates = Vec::new();
+    while !data.is_empty() {
+        let length = der_length(data).ok_or_else(|| "invalid DER encoding".to_owned())?;
+        let (certificate, rest) = data.split_at(length);
+        certificates.push(
+            X509::from_der(certificate)
+                .map_err(|error| format!("invalid certificate: {}", error))?,
+        );
+        data = rest;
+    }
+    Ok(certificates)
+}
+
+/// The length of the DER encoded value at the start of the data, header included.
+fn der_length(data: &[u8]) -> Option<usize> {
+    let first = *data.get(1)?;
+    let (header, length) = if first & 0x80 == 0 {
+        (2, usize::from(first))
+    } else {
+        let count = usize::from(first & 0x7f);
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let bytes = data.get(2..2 + count)?;
+        let length = bytes
+            .iter()
+            .fold(0, |length, byte| length << 8 | usize::from(*byte));
+        (2 + count, length)
+    };
+    let total = header + length;
+    (total <= data.len()).then(|| total)
+}
+
+fn socket_path(options: &SpiffeOptions) -> std::result::Result<PathBuf, String> {
+    let endpoint = options
+        .endpoint_socket
+        .clone()
+        .or_else(|| std::env::var(ENDPOINT_SOCKET_ENV).ok())
+        .ok_or_else(|| {
+            format!(
+                "the endpoint is set neither by `endpoint_socket` nor by `{}`",
+                ENDPOINT_SOCKET_ENV
+            )
+        })?;
+    endpoint
+        .strip_prefix("unix://")
+        .or_else(|| endpoint.strip_prefix("unix:"))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            format!(
+                "unsupported endpoint {:?}, expected a `unix:` URI",
+                endpoint
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tls::TEST_PEM_CRT_PATH;
+
+    #[test]
+    fn parses_endpoints() {
+        let path = |endpoint: &str| {
+            socket_path(&SpiffeOptions {
+                endpoint_socket: Some(endpoint.into()),
+                spiffe_id: None,
+            })
+        };
+        assert_eq!(
+            path("unix:///run/spire/agent.sock"),
+            Ok("/run/spire/agent.sock".into())
+        );
+        assert_eq!(path("unix:/tmp/agent.sock"), Ok("/tmp/agent.sock".into()));
+        assert!(path("tcp://127.0.0.1:8081").is_err());
+        assert!(path("unix:").is_err());
+    }
+
+    #[test]
+    fn splits_concatenated_certificates() {
+        let pem = std::fs::read(TEST_PEM_CRT_PATH).unwrap();
+        let der = X509::from_pem(&pem).unwrap().to_der().unwrap();
+        let bundle = [der.clone(), der].concat();
+
+        assert_eq!(split_der(&bundle).unwrap().len(), 2);
+        assert_eq!(split_der(&[]).unwrap().len(), 0);
+        assert!(split_der(&bundle[..bundle.len() - 1]).is_err());
+    }
+}
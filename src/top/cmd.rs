@@ -3,7 +3,7 @@ use std::time::Duration;
 use futures_util::future::join_all;
 use tokio::sync::oneshot;
 use url::Url;
-use vector_api_client::{connect_subscription_client, Client};
+use vector_api_client::{connect_subscription_client_with_options, Client};
 
 use super::{
     dashboard::{init_dashboard, is_tty},
@@ -36,8 +36,19 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
             .expect("Couldn't parse default API URL. Please report this.")
     });
 
+    let connect_options = match opts.api.connect_options() {
+        Ok(options) => options,
+        Err(error) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("{}", error);
+            }
+            return exitcode::USAGE;
+        }
+    };
+
     // Create a new API client for connecting to the local/remote Vector instance.
-    let client = match Client::new_with_healthcheck(url.clone()).await {
+    let client = match Client::new_with_healthcheck(url.clone(), &connect_options).await {
         Some(client) => client,
         None => return exitcode::UNAVAILABLE,
     };
@@ -73,13 +84,16 @@ pub async fn cmd(opts: &super::Opts) -> exitcode::ExitCode {
             };
             let _ = tx.send(EventType::InitializeState(state)).await;
 
-            let subscription_client = match connect_subscription_client(ws_url.clone()).await {
-                Ok(c) => c,
-                Err(_) => {
-                    tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY)).await;
-                    continue;
-                }
-            };
+            let subscription_client =
+                match connect_subscription_client_with_options(ws_url.clone(), &connect_options)
+                    .await
+                {
+                    Ok(c) => c,
+                    Err(_) => {
+                        tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY)).await;
+                        continue;
+                    }
+                };
 
             // Subscribe to updated metrics
             let finished =
@@ -24,6 +24,7 @@ use super::{
     events::capture_key_press,
     state::{self, ConnectionStatus},
 };
+use crate::config::ComponentKey;
 
 /// Format metrics, with thousands separation
 trait ThousandsFormatter {
@@ -104,7 +105,14 @@ fn format_metric(total: i64, throughput: i64, human_metrics: bool) -> String {
     }
 }
 
-const NUM_COLUMNS: usize = 8;
+fn format_utilization(utilization: Option<f64>) -> String {
+    match utilization {
+        Some(u) => format!("{:.1}%", u),
+        None => "--".to_string(),
+    }
+}
+
+const NUM_COLUMNS: usize = 9;
 static HEADER: [&str; NUM_COLUMNS] = [
     "ID",
     "Output",
@@ -114,8 +122,41 @@ static HEADER: [&str; NUM_COLUMNS] = [
     "Events Out",
     "Bytes",
     "Errors",
+    "Buffer",
 ];
 
+/// Interactive state of the dashboard, driven by key presses
+#[derive(Debug, Default)]
+struct View {
+    /// Index of the selected component, in display order
+    selected: usize,
+    /// Whether to show the detail view of the selected component, in place of the table
+    detail: bool,
+}
+
+impl View {
+    /// Returns the key of the selected component, if any components exist
+    fn selected_key<'a>(&self, state: &'a state::State) -> Option<&'a ComponentKey> {
+        state.components.keys().nth(self.selected)
+    }
+
+    /// Updates the view according to a key press. Returns `true` if the dashboard should exit.
+    fn handle_key(&mut self, key: KeyCode, num_components: usize) -> bool {
+        match key {
+            KeyCode::Char('q') => return true,
+            KeyCode::Esc if !self.detail => return true,
+            KeyCode::Esc => self.detail = false,
+            KeyCode::Enter => self.detail = num_components > 0,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(num_components.saturating_sub(1))
+            }
+            _ => {}
+        }
+        false
+    }
+}
+
 struct Widgets<'a> {
     constraints: Vec<Constraint>,
     url_string: &'a str,
@@ -168,7 +209,13 @@ impl<'a> Widgets<'a> {
 
     /// Renders a components table, showing sources, transforms and sinks in tabular form, with
     /// statistics pulled from `ComponentsState`,
-    fn components_table<B: Backend>(&self, f: &mut Frame<B>, state: &state::State, area: Rect) {
+    fn components_table<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        state: &state::State,
+        view: &View,
+        area: Rect,
+    ) {
         // Header columns
         let header = HEADER
             .iter()
@@ -177,7 +224,7 @@ impl<'a> Widgets<'a> {
 
         // Data columns
         let mut items = Vec::new();
-        for (_, r) in state.components.iter() {
+        for (i, (_, r)) in state.components.iter().enumerate() {
             let mut data = vec![
                 r.key.id().to_string(),
                 (!r.has_displayable_outputs())
@@ -204,15 +251,20 @@ impl<'a> Widgets<'a> {
                     r.processed_bytes_throughput_sec,
                     self.opts.human_metrics,
                 ),
-                if self.opts.human_metrics {
-                    r.errors.human_format()
-                } else {
-                    r.errors.thousands_format()
+                match r.errors {
+                    0 => "--".to_string(),
+                    _ => format_metric(r.errors, r.errors_throughput_sec, self.opts.human_metrics),
                 },
+                format_utilization(r.buffer_utilization),
             ];
 
             data.extend_from_slice(&formatted_metrics);
-            items.push(Row::new(data).style(Style::default()));
+            let style = if i == view.selected {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            items.push(Row::new(data).style(style));
 
             // Add output rows
             if r.has_displayable_outputs() {
@@ -238,19 +290,66 @@ impl<'a> Widgets<'a> {
             .block(Block::default().borders(Borders::ALL).title("Components"))
             .column_spacing(2)
             .widths(&[
-                Constraint::Percentage(15), // ID
-                Constraint::Percentage(15), // Output
-                Constraint::Percentage(10), // Kind
+                Constraint::Percentage(14), // ID
+                Constraint::Percentage(10), // Output
+                Constraint::Percentage(8),  // Kind
                 Constraint::Percentage(10), // Type
-                Constraint::Percentage(10), // Events In
-                Constraint::Percentage(10), // Events Out
-                Constraint::Percentage(10), // Bytes
-                Constraint::Percentage(10), // Errors
+                Constraint::Percentage(12), // Events In
+                Constraint::Percentage(12), // Events Out
+                Constraint::Percentage(12), // Bytes
+                Constraint::Percentage(12), // Errors
+                Constraint::Percentage(10), // Buffer
             ]);
 
         f.render_widget(w, area);
     }
 
+    /// Renders the detail view of a component, showing its error rate, buffer utilization and
+    /// most recent error messages
+    fn component_detail<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        state: &state::State,
+        key: &ComponentKey,
+        area: Rect,
+    ) {
+        let mut text = Vec::new();
+
+        if let Some(r) = state.components.get(key) {
+            text.push(Spans::from(vec![
+                Span::styled("Errors: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::from(match r.errors {
+                    0 => "--".to_string(),
+                    _ => format_metric(r.errors, r.errors_throughput_sec, self.opts.human_metrics),
+                }),
+                Span::styled(" | Buffer: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::from(format_utilization(r.buffer_utilization)),
+            ]));
+            text.push(Spans::from(""));
+        }
+
+        match state.error_messages.get(key) {
+            Some(messages) if !messages.is_empty() => {
+                text.extend(messages.iter().rev().map(|m| {
+                    Spans::from(Span::styled(m.as_str(), Style::default().fg(Color::Red)))
+                }));
+            }
+            _ => text.push(Spans::from(Span::styled(
+                "No errors logged since connecting",
+                Style::default().fg(Color::Gray),
+            ))),
+        }
+
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "{} (last {} errors, newest first)",
+            key.id(),
+            state::ERROR_MESSAGES_LIMIT
+        ));
+        let w = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+
+        f.render_widget(w, area);
+    }
+
     /// Alerts the user to resize the window to view columns
     fn components_resize_window<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
         let block = Block::default().borders(Borders::ALL).title("Components");
@@ -261,9 +360,13 @@ impl<'a> Widgets<'a> {
         f.render_widget(w, area);
     }
 
-    /// Renders a box showing instructions on how to exit from `vector top`.
-    fn quit_box<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let text = vec![Spans::from("To quit, press ESC or 'q'")];
+    /// Renders a box showing instructions on how to navigate and exit from `vector top`.
+    fn quit_box<B: Backend>(&self, f: &mut Frame<B>, view: &View, area: Rect) {
+        let text = vec![Spans::from(if view.detail {
+            "To return to components, press ESC. To quit, press 'q'"
+        } else {
+            "To quit, press ESC or 'q'. Select a component with up/down, and press Enter for details"
+        })];
 
         let block = Block::default()
             .borders(Borders::ALL)
@@ -277,7 +380,7 @@ impl<'a> Widgets<'a> {
     }
 
     /// Draw a single frame. Creates a layout and renders widgets into it.
-    fn draw<B: Backend>(&self, f: &mut Frame<B>, state: state::State) {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, state: &state::State, view: &View) {
         let size = f.size();
         let rects = Layout::default()
             .constraints(self.constraints.as_ref())
@@ -286,13 +389,13 @@ impl<'a> Widgets<'a> {
         self.title(f, rects[0], &state.connection_status);
 
         // Require a minimum of 80 chars of line width to display the table
-        if size.width >= 80 {
-            self.components_table(f, &state, rects[1]);
-        } else {
-            self.components_resize_window(f, rects[1]);
+        match view.selected_key(state) {
+            Some(key) if view.detail => self.component_detail(f, state, key, rects[1]),
+            _ if size.width >= 80 => self.components_table(f, state, view, rects[1]),
+            _ => self.components_resize_window(f, rects[1]),
         }
 
-        self.quit_box(f, rects[2]);
+        self.quit_box(f, view, rects[2]);
     }
 }
 
@@ -331,17 +434,23 @@ pub async fn init_dashboard<'a>(
     terminal.clear()?;
 
     let widgets = Widgets::new(url, opts);
+    let mut view = View::default();
+    let mut state = state::State::new(Default::default());
 
     loop {
         tokio::select! {
-            Some(state) = state_rx.recv() => {
-                terminal.draw(|f| widgets.draw(f, state))?;
+            Some(new_state) = state_rx.recv() => {
+                state = new_state;
+                // Keep the selection in range as components are removed
+                view.selected = view.selected.min(state.components.len().saturating_sub(1));
+                terminal.draw(|f| widgets.draw(f, &state, &view))?;
             },
             k = key_press_rx.recv() => {
-                if let KeyCode::Esc | KeyCode::Char('q') = k.unwrap() {
+                if view.handle_key(k.unwrap(), state.components.len()) {
                     let _ = key_press_kill_tx.send(());
                     break
                 }
+                terminal.draw(|f| widgets.draw(f, &state, &view))?;
             }
             _ = &mut shutdown_rx => {
                 let _ = key_press_kill_tx.send(());
@@ -445,6 +554,26 @@ mod tests {
         assert_eq!(N.human_format(), "1.10 T");
     }
 
+    #[test]
+    /// Selection should stay within the component list, and ESC should leave the detail
+    /// view before quitting
+    fn view_navigation() {
+        let mut view = View::default();
+
+        assert!(!view.handle_key(KeyCode::Up, 3));
+        assert_eq!(view.selected, 0);
+        for _ in 0..5 {
+            view.handle_key(KeyCode::Down, 3);
+        }
+        assert_eq!(view.selected, 2);
+
+        assert!(!view.handle_key(KeyCode::Enter, 3));
+        assert!(view.detail);
+        assert!(!view.handle_key(KeyCode::Esc, 3));
+        assert!(!view.detail);
+        assert!(view.handle_key(KeyCode::Esc, 3));
+    }
+
     #[test]
     /// Should format bytes
     fn format_bytes() {
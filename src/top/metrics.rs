@@ -36,6 +36,8 @@ async fn component_added(client: Arc<SubscriptionClient>, tx: state::EventTx) {
                     processed_bytes_total: 0,
                     processed_bytes_throughput_sec: 0,
                     errors: 0,
+                    errors_throughput_sec: 0,
+                    buffer_utilization: None,
                 }))
                 .await;
         }
@@ -212,6 +214,88 @@ async fn processed_bytes_throughputs(
     }
 }
 
+async fn errors_totals(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_errors_totals_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors_totals;
+            let _ = tx
+                .send(state::EventType::ErrorsTotals(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(c.component_id.as_str()),
+                                c.metric.errors_total as i64,
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn errors_throughputs(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_errors_throughputs_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors_throughputs;
+            let _ = tx
+                .send(state::EventType::ErrorsThroughputs(
+                    interval,
+                    c.into_iter()
+                        .map(|c| (ComponentKey::from(c.component_id.as_str()), c.throughput))
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+async fn buffer_utilizations(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_buffer_usages_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_buffer_usages;
+            let _ = tx
+                .send(state::EventType::BufferUtilizations(
+                    c.into_iter()
+                        .map(|c| (ComponentKey::from(c.component_id.as_str()), c.utilization))
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
+/// Error messages logged by components
+async fn component_errors(client: Arc<SubscriptionClient>, tx: state::EventTx) {
+    tokio::pin! {
+        let stream = client.component_errors_subscription();
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_errors;
+            let _ = tx
+                .send(state::EventType::ComponentError(
+                    ComponentKey::from(c.component_id.as_str()),
+                    c.message,
+                ))
+                .await;
+        }
+    }
+}
+
 /// Subscribe to each metrics channel through a separate client. This is a temporary workaround
 /// until client multiplexing is fixed. In future, we should be able to use a single client
 pub fn subscribe(
@@ -251,9 +335,21 @@ pub fn subscribe(
         )),
         tokio::spawn(processed_bytes_throughputs(
             Arc::clone(&client),
-            tx,
+            tx.clone(),
             interval,
         )),
+        tokio::spawn(errors_totals(Arc::clone(&client), tx.clone(), interval)),
+        tokio::spawn(errors_throughputs(
+            Arc::clone(&client),
+            tx.clone(),
+            interval,
+        )),
+        tokio::spawn(buffer_utilizations(
+            Arc::clone(&client),
+            tx.clone(),
+            interval,
+        )),
+        tokio::spawn(component_errors(Arc::clone(&client), tx)),
     ]
 }
 
@@ -296,8 +392,9 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
                         sent_events_throughput_sec: 0,
                         processed_bytes_total: d.on.processed_bytes_total(),
                         processed_bytes_throughput_sec: 0,
-
                         errors: 0,
+                        errors_throughput_sec: 0,
+                        buffer_utilization: None,
                     },
                 ))
             })
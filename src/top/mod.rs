@@ -26,4 +26,7 @@ pub struct Opts {
     /// Whether to reconnect if the underlying Vector API connection drops. By default, top will attempt to reconnect if the connection drops.
     #[clap(short, long)]
     no_reconnect: bool,
+
+    #[clap(flatten)]
+    api: crate::cli::ApiClientOpts,
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, VecDeque},
     fmt::Display,
 };
 
@@ -11,6 +11,9 @@ use crate::config::ComponentKey;
 
 type IdentifiedMetric = (ComponentKey, i64);
 
+/// Number of error messages kept for each component, for display in the detail view
+pub const ERROR_MESSAGES_LIMIT: usize = 10;
+
 #[derive(Debug)]
 pub struct SentEventsMetric {
     pub key: ComponentKey,
@@ -31,6 +34,13 @@ pub enum EventType {
     ProcessedBytesTotals(Vec<IdentifiedMetric>),
     /// Interval + identified metric
     ProcessedBytesThroughputs(i64, Vec<IdentifiedMetric>),
+    ErrorsTotals(Vec<IdentifiedMetric>),
+    /// Interval + identified metric
+    ErrorsThroughputs(i64, Vec<IdentifiedMetric>),
+    /// Buffer utilization percentage of each component with a buffer
+    BufferUtilizations(Vec<(ComponentKey, Option<f64>)>),
+    /// An error message logged by a component
+    ComponentError(ComponentKey, String),
     ComponentAdded(ComponentRow),
     ComponentRemoved(ComponentKey),
     ConnectionUpdated(ConnectionStatus),
@@ -76,6 +86,8 @@ impl Display for ConnectionStatus {
 pub struct State {
     pub connection_status: ConnectionStatus,
     pub components: BTreeMap<ComponentKey, ComponentRow>,
+    /// The most recent error messages of each component, oldest first
+    pub error_messages: BTreeMap<ComponentKey, VecDeque<String>>,
 }
 
 impl State {
@@ -83,6 +95,7 @@ impl State {
         Self {
             connection_status: ConnectionStatus::Pending,
             components,
+            error_messages: BTreeMap::new(),
         }
     }
 }
@@ -118,6 +131,8 @@ pub struct ComponentRow {
     pub sent_events_total: i64,
     pub sent_events_throughput_sec: i64,
     pub errors: i64,
+    pub errors_throughput_sec: i64,
+    pub buffer_utilization: Option<f64>,
 }
 
 impl ComponentRow {
@@ -140,7 +155,14 @@ pub async fn updater(mut event_rx: EventRx) -> StateRx {
         while let Some(event_type) = event_rx.recv().await {
             match event_type {
                 EventType::InitializeState(new_state) => {
+                    // Error messages aren't part of the initial query, so keep those already
+                    // received for components that still exist
+                    let error_messages = std::mem::take(&mut state.error_messages);
                     state = new_state;
+                    state.error_messages = error_messages
+                        .into_iter()
+                        .filter(|(key, _)| state.components.contains_key(key))
+                        .collect();
                 }
                 EventType::ReceivedEventsTotals(rows) => {
                     for (key, v) in rows {
@@ -200,11 +222,41 @@ pub async fn updater(mut event_rx: EventRx) -> StateRx {
                         }
                     }
                 }
+                EventType::ErrorsTotals(rows) => {
+                    for (key, v) in rows {
+                        if let Some(r) = state.components.get_mut(&key) {
+                            r.errors = v;
+                        }
+                    }
+                }
+                EventType::ErrorsThroughputs(interval, rows) => {
+                    for (key, v) in rows {
+                        if let Some(r) = state.components.get_mut(&key) {
+                            r.errors_throughput_sec =
+                                (v as f64 * (1000.0 / interval as f64)) as i64;
+                        }
+                    }
+                }
+                EventType::BufferUtilizations(rows) => {
+                    for (key, v) in rows {
+                        if let Some(r) = state.components.get_mut(&key) {
+                            r.buffer_utilization = v;
+                        }
+                    }
+                }
+                EventType::ComponentError(key, message) => {
+                    let messages = state.error_messages.entry(key).or_default();
+                    if messages.len() == ERROR_MESSAGES_LIMIT {
+                        messages.pop_front();
+                    }
+                    messages.push_back(message);
+                }
                 EventType::ComponentAdded(c) => {
                     let _ = state.components.insert(c.key.clone(), c);
                 }
                 EventType::ComponentRemoved(key) => {
                     let _ = state.components.remove(&key);
+                    let _ = state.error_messages.remove(&key);
                 }
                 EventType::ConnectionUpdated(status) => {
                     state.connection_status = status;
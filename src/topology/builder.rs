@@ -5,7 +5,7 @@ use std::{
     time::Instant,
 };
 
-use futures::{stream::FuturesOrdered, FutureExt, StreamExt, TryFutureExt};
+use futures::{future::join, stream::FuturesOrdered, FutureExt, StreamExt, TryFutureExt};
 use once_cell::sync::Lazy;
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
 use tokio::{
@@ -17,24 +17,27 @@ use vector_core::{
     buffers::{
         topology::{
             builder::TopologyBuilder,
-            channel::{BufferReceiver, BufferSender},
+            channel::{BufferReceiver, BufferSender, PriorityClassifier},
         },
-        BufferType, WhenFull,
+        WhenFull,
     },
     internal_event::EventsSent,
     ByteSizeOf,
 };
 
 use super::{
+    dead_letter, event_tracing,
     fanout::{self, Fanout},
+    limits::{self, Budgeted, InFlight},
+    priority::LowPriority,
     schema,
     task::{Task, TaskOutput},
     BuiltBuffer, ConfigDiff,
 };
 use crate::{
     config::{
-        ComponentKey, DataType, Input, Output, OutputId, ProxyConfig, SinkContext, SourceContext,
-        TransformContext,
+        ComponentKey, ComponentLimits, DataType, Input, Output, OutputId, ProxyConfig, SinkContext,
+        SourceContext, TransformContext, DEAD_LETTER_OUTPUT,
     },
     event::{EventArray, EventContainer},
     internal_events::EventsReceived,
@@ -70,7 +73,10 @@ pub(self) async fn load_enrichment_tables<'a>(
             let indexes = if !diff.enrichment_tables.contains_new(name) {
                 // If this is an existing enrichment table, we need to store the indexes to reapply
                 // them again post load.
-                Some(ENRICHMENT_TABLES.index_fields(&table_name))
+                Some((
+                    ENRICHMENT_TABLES.index_fields(&table_name),
+                    ENRICHMENT_TABLES.lookup_indexes(&table_name),
+                ))
             } else {
                 None
             };
@@ -83,7 +89,7 @@ pub(self) async fn load_enrichment_tables<'a>(
                 }
             };
 
-            if let Some(indexes) = indexes {
+            if let Some((indexes, lookup_indexes)) = indexes {
                 for (case, index) in indexes {
                     match table
                         .add_index(case, &index.iter().map(|s| s.as_ref()).collect::<Vec<_>>())
@@ -100,6 +106,15 @@ pub(self) async fn load_enrichment_tables<'a>(
                         }
                     }
                 }
+
+                for index in lookup_indexes {
+                    if let Err(error) = table.add_lookup_index(&index) {
+                        error!(message = "Unable to add index to reloaded enrichment table.",
+                                table = ?name.to_string(),
+                                %error);
+                        continue 'tables;
+                    }
+                }
             }
 
             enrichment_tables.insert(table_name, table);
@@ -158,8 +173,15 @@ pub async fn build_pieces(
             let rx = builder.add_output(output.clone());
 
             let (fanout, control) = Fanout::new();
+            let source_id = key.id().to_owned();
             let pump = async move {
-                rx.map(EventArray::from).map(Ok).forward(fanout).await?;
+                rx.map(EventArray::from)
+                    .map(move |mut events| {
+                        event_tracing::start(&mut events, &source_id);
+                        Ok(events)
+                    })
+                    .forward(fanout)
+                    .await?;
                 Ok(TaskOutput::Source)
             };
 
@@ -202,6 +224,9 @@ pub async fn build_pieces(
             out: pipeline,
             proxy: ProxyConfig::merge_with_env(&config.global.proxy, &source.proxy),
             acknowledgements: source.sink_acknowledgements,
+            acknowledgement_timeout: source
+                .acknowledgement_timeout
+                .build(source.inner.can_redeliver()),
             schema_definitions,
         };
         let server = match source.inner.build(context).await {
@@ -281,6 +306,7 @@ pub async fn build_pieces(
             input_details: transform.inner.input(),
             outputs: transform.inner.outputs(&merged_definition),
             enable_concurrency: transform.inner.enable_concurrency(),
+            limits: transform.limits,
         };
 
         let transform = match transform.inner.build(&context).await {
@@ -290,8 +316,14 @@ pub async fn build_pieces(
             }
             Ok(transform) => transform,
         };
+        if let Err(error) = limits::check(&node.limits, &transform) {
+            errors.push(format!("Transform \"{}\": {}", key, error));
+            continue;
+        }
 
-        let (input_tx, input_rx) = TopologyBuilder::standalone_memory(100, WhenFull::Block).await;
+        let input_buffer_size = limits::input_buffer_size(&node.limits);
+        let (input_tx, input_rx) =
+            TopologyBuilder::standalone_memory(input_buffer_size, WhenFull::Block).await;
 
         inputs.insert(key.clone(), (input_tx, node.inputs.clone()));
 
@@ -308,26 +340,37 @@ pub async fn build_pieces(
         .filter(|(key, _)| diff.sinks.contains_new(key))
     {
         let sink_inputs = &sink.inputs;
+        let has_dead_letter = sink.dead_letter.is_some();
         let healthcheck = sink.healthcheck();
         let enable_healthcheck = healthcheck.enabled && config.healthchecks.enabled;
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input().data_type();
 
-        let (tx, rx, acker) = if let Some(buffer) = buffers.remove(key) {
+        let low_priority = match sink
+            .low_priority
+            .as_ref()
+            .map(|condition| condition.build(enrichment_tables))
+            .transpose()
+        {
+            Ok(condition) => condition.map(|condition| {
+                Arc::new(LowPriority::new(condition)) as Arc<dyn PriorityClassifier<EventArray>>
+            }),
+            Err(error) => {
+                errors.push(format!("Sink \"{}\": low_priority: {}", key, error));
+                continue;
+            }
+        };
+
+        let (mut tx, rx, acker) = if let Some(buffer) = buffers.remove(key) {
             buffer
         } else {
-            let buffer_type = match sink.buffer.stages().first().expect("cant ever be empty") {
-                BufferType::Memory { .. } => "memory",
-                BufferType::DiskV1 { .. } | BufferType::DiskV2 { .. } => "disk",
-            };
             let buffer_span = error_span!(
                 "sink",
                 component_kind = "sink",
                 component_id = %key.id(),
                 component_type = typetag,
                 component_name = %key.id(),
-                buffer_type = buffer_type,
             );
             let buffer = sink
                 .buffer
@@ -341,6 +384,7 @@ pub async fn build_pieces(
                 Ok((tx, rx, acker)) => (tx, Arc::new(Mutex::new(Some(rx))), acker),
             }
         };
+        tx.set_priority_lanes(low_priority);
 
         let cx = SinkContext {
             acker: acker.clone(),
@@ -359,6 +403,17 @@ pub async fn build_pieces(
 
         let (trigger, tripwire) = Tripwire::new();
 
+        let dead_letter_output = has_dead_letter.then(|| {
+            let (fanout, control) = Fanout::new();
+            outputs.insert(
+                OutputId::from((key, DEAD_LETTER_OUTPUT.to_owned())),
+                control,
+            );
+            fanout
+        });
+        let component_key = key.clone();
+        let sink_id = key.id().to_owned();
+
         let sink = async move {
             // Why is this Arc<Mutex<Option<_>>> needed you ask.
             // In case when this function build_pieces errors
@@ -374,19 +429,28 @@ pub async fn build_pieces(
 
             let mut rx = crate::utilization::wrap(rx);
 
-            sink.run(
-                rx.by_ref()
-                    .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
-                    .inspect(|events| {
-                        emit!(&EventsReceived {
-                            count: events.len(),
-                            byte_size: events.size_of(),
-                        })
+            let events = rx
+                .by_ref()
+                .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
+                .map(move |mut events| {
+                    event_tracing::enter(&mut events, &sink_id, "sink");
+                    events
+                })
+                .inspect(|events| {
+                    emit!(&EventsReceived {
+                        count: events.len(),
+                        byte_size: events.size_of(),
                     })
-                    .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+                })
+                .take_until_if(tripwire);
+            let result = match dead_letter_output {
+                Some(output) => {
+                    let (events, forwarder) = dead_letter::track(events, component_key, output);
+                    join(sink.run(events), forwarder).await.0
+                }
+                None => sink.run(events).await,
+            };
+            result.map(|_| {
                 debug!("Finished.");
                 TaskOutput::Sink(rx, acker)
             })
@@ -488,6 +552,7 @@ struct TransformNode {
     input_details: Input,
     outputs: Vec<Output>,
     enable_concurrency: bool,
+    limits: ComponentLimits,
 }
 
 fn build_transform(
@@ -505,6 +570,7 @@ fn build_transform(
             node.input_details.data_type(),
             node.typetag,
             &node.key,
+            &node.limits,
         ),
     }
 }
@@ -516,7 +582,14 @@ fn build_sync_transform(
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     let (outputs, controls) = TransformOutputs::new(node.outputs);
 
-    let runner = Runner::new(t, input_rx, node.input_details.data_type(), outputs);
+    let runner = Runner::new(
+        t,
+        node.key.id().to_owned(),
+        input_rx,
+        node.input_details.data_type(),
+        outputs,
+        node.limits,
+    );
     let transform = if node.enable_concurrency {
         runner.run_concurrently().boxed()
     } else {
@@ -538,9 +611,11 @@ fn build_sync_transform(
 
 struct Runner {
     transform: Box<dyn SyncTransform>,
+    id: Arc<str>,
     input_rx: Option<BufferReceiver<EventArray>>,
     input_type: DataType,
     outputs: TransformOutputs,
+    limits: ComponentLimits,
     timer: crate::utilization::Timer,
     last_report: Instant,
 }
@@ -548,21 +623,27 @@ struct Runner {
 impl Runner {
     fn new(
         transform: Box<dyn SyncTransform>,
+        id: String,
         input_rx: BufferReceiver<EventArray>,
         input_type: DataType,
         outputs: TransformOutputs,
+        limits: ComponentLimits,
     ) -> Self {
         Self {
             transform,
+            id: id.into(),
             input_rx: Some(input_rx),
             input_type,
             outputs,
+            limits,
             timer: crate::utilization::Timer::new(),
             last_report: Instant::now(),
         }
     }
 
-    fn on_events_received(&mut self, events: &EventArray) {
+    fn on_events_received(&mut self, events: &mut EventArray) {
+        event_tracing::enter(events, &self.id, "transform");
+
         let stopped = self.timer.stop_wait();
         if stopped.duration_since(self.last_report).as_secs() >= 5 {
             self.timer.report();
@@ -576,6 +657,11 @@ impl Runner {
     }
 
     async fn send_outputs(&mut self, outputs_buf: &mut TransformOutputsBuf) {
+        if event_tracing::enabled() {
+            let id = &self.id;
+            outputs_buf.for_each_event(|event| event_tracing::exit(event, id));
+        }
+
         self.timer.start_wait();
         self.outputs.send(outputs_buf).await;
     }
@@ -586,15 +672,19 @@ impl Runner {
 
         let mut outputs_buf = self.outputs.new_buf_with_capacity(INLINE_BATCH_SIZE);
 
-        let mut input_rx = self
+        let input_rx = self
             .input_rx
             .take()
             .expect("can't run runner twice")
             .filter(move |events| ready(filter_events_type(events, self.input_type)));
+        let mut input_rx = Budgeted::new(Box::pin(input_rx), &self.limits);
 
+        // The events are processed one batch at a time, the next one being taken only once the
+        // previous one is sent on, which is never more than `InFlight` would let through, so the
+        // in-flight limits hold as is.
         self.timer.start_wait();
-        while let Some(events) = input_rx.next().await {
-            self.on_events_received(&events);
+        while let Some(mut events) = input_rx.next().await {
+            self.on_events_received(&mut events);
             self.transform.transform_all(events, &mut outputs_buf);
             self.send_outputs(&mut outputs_buf).await;
         }
@@ -604,13 +694,15 @@ impl Runner {
     }
 
     async fn run_concurrently(mut self) -> Result<TaskOutput, ()> {
-        let mut input_rx = self
+        let input_rx = self
             .input_rx
             .take()
             .expect("can't run runner twice")
             .filter(move |events| ready(filter_events_type(events, self.input_type)));
+        let mut input_rx = Budgeted::new(Box::pin(input_rx), &self.limits);
 
         let mut in_flight = FuturesOrdered::new();
+        let mut in_flight_size = InFlight::new(&self.limits);
         let mut shutting_down = false;
 
         self.timer.start_wait();
@@ -620,24 +712,26 @@ impl Runner {
 
                 result = in_flight.next(), if !in_flight.is_empty() => {
                     match result {
-                        Some(Ok(outputs_buf)) => {
+                        Some(Ok((outputs_buf, size))) => {
                             let mut outputs_buf: TransformOutputsBuf = outputs_buf;
                             self.send_outputs(&mut outputs_buf).await;
+                            in_flight_size.finish(size);
                         }
                         _ => unreachable!("join error or bad poll"),
                     }
                 }
 
-                input_events = input_rx.next(), if in_flight.len() < *TRANSFORM_CONCURRENCY_LIMIT && !shutting_down => {
+                input_events = input_rx.next(), if in_flight.len() < *TRANSFORM_CONCURRENCY_LIMIT && in_flight_size.has_capacity() && !shutting_down => {
                     match input_events {
-                        Some(events) => {
-                            self.on_events_received(&events);
+                        Some(mut events) => {
+                            self.on_events_received(&mut events);
 
+                            let size = in_flight_size.start(&events);
                             let mut t = self.transform.clone();
                             let mut outputs_buf = self.outputs.new_buf_with_capacity(events.len());
                             let task = tokio::spawn(async move {
                                 t.transform_all(events, &mut outputs_buf);
-                                outputs_buf
+                                (outputs_buf, size)
                             }.in_current_span());
                             in_flight.push(task);
                         }
@@ -667,21 +761,33 @@ fn build_task_transform(
     input_type: DataType,
     typetag: &str,
     key: &ComponentKey,
+    limits: &ComponentLimits,
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     let (fanout, control) = Fanout::new();
 
     let input_rx = crate::utilization::wrap(input_rx);
 
+    let input_id = key.id().to_owned();
+    let output_id = key.id().to_owned();
     let filtered = input_rx
         .filter(move |events| ready(filter_events_type(events, input_type)))
+        .map(move |mut events| {
+            event_tracing::enter(&mut events, &input_id, "transform");
+            events
+        })
         .inspect(|events| {
             emit!(&EventsReceived {
                 count: events.len(),
                 byte_size: events.size_of(),
             })
         });
+    let filtered = Budgeted::new(Box::pin(filtered), limits);
     let transform = t
         .transform(Box::pin(filtered))
+        .map(move |mut events| {
+            event_tracing::exit_all(&mut events, &output_id);
+            events
+        })
         .inspect(|events: &EventArray| {
             emit!(&EventsSent {
                 count: events.len(),
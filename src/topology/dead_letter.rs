@@ -0,0 +1,157 @@
+//! Routing of the events a sink failed to deliver to its dead letter output.
+
+use std::sync::Arc;
+
+use futures::{Future, SinkExt, Stream, StreamExt};
+use tokio::sync::{mpsc, Semaphore};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use vector_core::event::{
+    BatchNotifier, BatchStatus, BatchStatusReceiver, Event, EventArray, EventContainer,
+    EventFinalizer, EventFinalizers, EventMutRef, EventStatus,
+};
+
+use super::fanout::Fanout;
+use crate::{config::ComponentKey, internal_events::DeadLetterEventsSent};
+
+/// Maximum number of events of a sink whose delivery is tracked at once. Once reached, the sink
+/// only receives more events as earlier ones are delivered or sent to the dead letter output.
+const MAX_PENDING_EVENTS: usize = 10_000;
+
+/// An event sent to the sink, waiting for the status of its delivery.
+struct Pending {
+    event: Event,
+    finalizers: EventFinalizers,
+    receiver: BatchStatusReceiver,
+    /// Whether the event holds one of the `MAX_PENDING_EVENTS` permits.
+    permit: bool,
+}
+
+/// Tracks the delivery of each event the sink receives, routing the events it fails to deliver,
+/// once retried as configured, to the dead letter output.
+///
+/// The finalizers of the events are held back until their delivery completes, so that the
+/// sources only acknowledge the failed events once the dead letter component has handled them.
+/// A copy of each event is kept until then, up to `MAX_PENDING_EVENTS` events at once.
+///
+/// Returns the stream the sink is to consume, and the future forwarding the failed events, which
+/// completes once the stream has been dropped and all its events have been delivered.
+pub(super) fn track<S>(
+    events: S,
+    key: ComponentKey,
+    mut output: Fanout,
+) -> (
+    impl Stream<Item = EventArray> + Send,
+    impl Future<Output = ()> + Send,
+)
+where
+    S: Stream<Item = EventArray> + Send,
+{
+    let (pending_tx, pending_rx) = mpsc::unbounded_channel::<Pending>();
+    let semaphore = Arc::new(Semaphore::new(MAX_PENDING_EVENTS));
+
+    let events = {
+        let semaphore = Arc::clone(&semaphore);
+        events.then(move |mut events: EventArray| {
+            let semaphore = Arc::clone(&semaphore);
+            let pending_tx = pending_tx.clone();
+            async move {
+                // Arrays larger than the limit go through once all the other events are done.
+                let mut permits = events.len().min(MAX_PENDING_EVENTS);
+                semaphore
+                    .acquire_many(permits as u32)
+                    .await
+                    .expect("The semaphore is never closed")
+                    .forget();
+
+                events.for_each_event(|mut event| {
+                    let finalizers = event.metadata_mut().take_finalizers();
+                    let (batch, receiver) = BatchNotifier::new_with_receiver();
+                    let copy = to_event(&event);
+                    event
+                        .metadata_mut()
+                        .add_finalizer(EventFinalizer::new(batch));
+                    let permit = permits > 0;
+                    permits = permits.saturating_sub(1);
+                    // The forwarder only stops once this stream is dropped.
+                    let _ = pending_tx.send(Pending {
+                        event: copy,
+                        finalizers,
+                        receiver,
+                        permit,
+                    });
+                });
+                events
+            }
+        })
+    };
+
+    let forwarder = async move {
+        let mut pending = UnboundedReceiverStream::new(pending_rx)
+            .map(
+                |Pending {
+                     event,
+                     finalizers,
+                     receiver,
+                     permit,
+                 }| async move { (receiver.await, event, finalizers, permit) },
+            )
+            .buffer_unordered(MAX_PENDING_EVENTS);
+
+        while let Some((status, event, finalizers, permit)) = pending.next().await {
+            let status = match status {
+                BatchStatus::Delivered => None,
+                BatchStatus::Errored => Some("errored"),
+                BatchStatus::Rejected => Some("rejected"),
+            };
+            match status {
+                None => finalizers.update_status(EventStatus::Delivered),
+                Some(status) => {
+                    let event = annotate(event, &key, status, finalizers);
+                    emit!(&DeadLetterEventsSent { count: 1, status });
+                    if output.send(EventArray::from(event)).await.is_err() {
+                        error!(message = "Failed to send the event to the dead letter output.");
+                    }
+                }
+            }
+            if permit {
+                semaphore.add_permits(1);
+            }
+        }
+    };
+
+    (events, forwarder)
+}
+
+fn to_event(event: &EventMutRef<'_>) -> Event {
+    match event {
+        EventMutRef::Log(log) => Event::Log((**log).clone()),
+        EventMutRef::Metric(metric) => Event::Metric((**metric).clone()),
+        EventMutRef::Trace(trace) => Event::Trace((**trace).clone()),
+    }
+}
+
+/// Records the sink which failed to deliver the event and the status of the delivery, in the
+/// `dead_letter` field of logs and traces, and in the `dead_letter_*` tags of metrics.
+fn annotate(
+    mut event: Event,
+    key: &ComponentKey,
+    status: &'static str,
+    finalizers: EventFinalizers,
+) -> Event {
+    match &mut event {
+        Event::Log(log) => {
+            log.insert("dead_letter.sink", key.id().to_owned());
+            log.insert("dead_letter.status", status);
+        }
+        Event::Metric(metric) => {
+            metric.insert_tag("dead_letter_sink".to_owned(), key.id().to_owned());
+            metric.insert_tag("dead_letter_status".to_owned(), status.to_owned());
+        }
+        Event::Trace(trace) => {
+            trace.insert("dead_letter.sink", key.id().to_owned());
+            trace.insert("dead_letter.status", status);
+        }
+    }
+    event.metadata_mut().merge_finalizers(finalizers);
+    event
+}
@@ -0,0 +1,89 @@
+//! The event tracing mode, in which events sampled from the sources record the components they
+//! traverse along with when they entered and left each of them. The traces are published once
+//! the events reach a sink, to the `eventTrace` subscription of the API.
+
+use std::{
+    num::NonZeroU64,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
+
+use crate::event::{EventArray, EventMutRef, EventTrace};
+
+/// How many traces are kept for the slower subscribers, before they start missing some.
+const TRACES_CAPACITY: usize = 1000;
+
+/// One in how many events are traced, 0 when the event tracing mode is disabled.
+static SAMPLE_RATE: AtomicU64 = AtomicU64::new(0);
+
+static EMITTED: AtomicU64 = AtomicU64::new(0);
+
+static TRACES: Lazy<broadcast::Sender<EventTrace>> =
+    Lazy::new(|| broadcast::channel(TRACES_CAPACITY).0);
+
+/// Enables the event tracing mode, tracing one in `sample_rate` events, or disables it.
+pub fn set_sample_rate(sample_rate: Option<NonZeroU64>) {
+    SAMPLE_RATE.store(sample_rate.map_or(0, NonZeroU64::get), Ordering::Relaxed);
+}
+
+/// Whether the event tracing mode is enabled, checked before going through the events.
+pub(super) fn enabled() -> bool {
+    SAMPLE_RATE.load(Ordering::Relaxed) > 0
+}
+
+/// Subscribes to the traces of the events reaching the sinks.
+pub fn subscribe() -> broadcast::Receiver<EventTrace> {
+    TRACES.subscribe()
+}
+
+/// Samples the events emitted by the given source, starting their traces.
+pub(super) fn start(events: &mut EventArray, source_id: &str) {
+    let sample_rate = SAMPLE_RATE.load(Ordering::Relaxed);
+    if sample_rate == 0 {
+        return;
+    }
+
+    events.for_each_event(|mut event| {
+        let emitted = EMITTED.fetch_add(1, Ordering::Relaxed);
+        if emitted % sample_rate == 0 {
+            event
+                .metadata_mut()
+                .set_trace(EventTrace::new(emitted, source_id));
+        }
+    });
+}
+
+/// Records the traced events being received by the given transform or sink. The traces of the
+/// events received by a sink are complete, so they are published.
+pub(super) fn enter(events: &mut EventArray, component_id: &str, component_kind: &'static str) {
+    if !enabled() {
+        return;
+    }
+
+    events.for_each_event(|mut event| {
+        if let Some(trace) = event.metadata_mut().trace_mut() {
+            trace.enter(component_id, component_kind);
+            if component_kind == "sink" {
+                // There are no subscribers when nobody is watching, which is fine.
+                let _ = TRACES.send(trace.clone());
+            }
+        }
+    });
+}
+
+/// Records the traced event being sent by the given transform.
+pub(super) fn exit(mut event: EventMutRef<'_>, component_id: &str) {
+    if let Some(trace) = event.metadata_mut().trace_mut() {
+        trace.exit(component_id);
+    }
+}
+
+/// Records the traced events being sent by the given transform, for the transforms which don't
+/// send their events through output buffers.
+pub(super) fn exit_all(events: &mut EventArray, component_id: &str) {
+    if enabled() {
+        events.for_each_event(|event| exit(event, component_id));
+    }
+}
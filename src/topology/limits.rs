@@ -0,0 +1,249 @@
+//! Enforcement of the resource limits of transforms.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{ready, Stream};
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::ComponentLimits,
+    event::{EventArray, EventContainer},
+    transforms::Transform,
+};
+
+/// The size of the input buffer of transforms not limiting their in-flight events.
+pub(super) const DEFAULT_INPUT_BUFFER_SIZE: usize = 100;
+
+/// Gets the size of the input buffer of a transform with the given limits.
+pub(super) fn input_buffer_size(limits: &ComponentLimits) -> usize {
+    limits
+        .max_in_flight_events
+        .unwrap_or(DEFAULT_INPUT_BUFFER_SIZE)
+        .max(1)
+}
+
+/// Checks that the limits can be enforced for the transform.
+///
+/// Task transforms consume their input as a stream, holding on to, merging or dropping events as
+/// they see fit, so the events they have in flight can't be told apart from those they are done
+/// with, and capping them isn't supported.
+pub(super) fn check(limits: &ComponentLimits, transform: &Transform) -> Result<(), &'static str> {
+    match transform {
+        Transform::Task(_)
+            if limits.max_in_flight_events.is_some() || limits.max_memory_bytes.is_some() =>
+        {
+            Err("`max_in_flight_events` and `max_memory_bytes` limits aren't supported by this transform.")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Keeps track of the events a transform is processing concurrently, so that no more of them are
+/// taken from its input while over its limits.
+#[derive(Debug)]
+pub(super) struct InFlight {
+    max_events: Option<usize>,
+    max_bytes: Option<usize>,
+    events: usize,
+    bytes: usize,
+}
+
+impl InFlight {
+    pub(super) const fn new(limits: &ComponentLimits) -> Self {
+        Self {
+            max_events: limits.max_in_flight_events,
+            max_bytes: limits.max_memory_bytes,
+            events: 0,
+            bytes: 0,
+        }
+    }
+
+    /// Returns whether more events can be taken from the input.
+    ///
+    /// Nothing being in flight, the next events can always be taken, however large they are, as the
+    /// transform would otherwise never make progress.
+    pub(super) fn has_capacity(&self) -> bool {
+        self.events == 0
+            || (self.max_events.map_or(true, |max| self.events < max)
+                && self.max_bytes.map_or(true, |max| self.bytes < max))
+    }
+
+    pub(super) fn start(&mut self, events: &EventArray) -> (usize, usize) {
+        let size = (events.len(), events.size_of());
+        self.events += size.0;
+        self.bytes += size.1;
+        size
+    }
+
+    pub(super) fn finish(&mut self, (events, bytes): (usize, usize)) {
+        self.events -= events;
+        self.bytes -= bytes;
+    }
+}
+
+/// An input stream which yields to the runtime every time its events exhaust the task budget of
+/// the transform, so that a busy transform doesn't hog the thread it runs on.
+#[derive(Debug)]
+pub(super) struct Budgeted<S> {
+    inner: S,
+    budget: Option<usize>,
+    remaining: usize,
+}
+
+impl<S> Budgeted<S> {
+    pub(super) fn new(inner: S, limits: &ComponentLimits) -> Self {
+        let budget = limits.task_budget.map(|budget| budget.max(1));
+        Self {
+            inner,
+            budget,
+            remaining: budget.unwrap_or(0),
+        }
+    }
+}
+
+impl<S> Stream for Budgeted<S>
+where
+    S: Stream<Item = EventArray> + Unpin,
+{
+    type Item = EventArray;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(budget) = self.budget {
+            if self.remaining == 0 {
+                // Same as `tokio::task::yield_now`, letting the other tasks run before we do again.
+                self.remaining = budget;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+        }
+
+        let events = ready!(Pin::new(&mut self.inner).poll_next(cx));
+        if let Some(events) = &events {
+            self.remaining = self.remaining.saturating_sub(events.len());
+        }
+        Poll::Ready(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, FutureExt, StreamExt};
+
+    use super::*;
+    use crate::{event::LogEvent, transforms::TaskTransform};
+
+    fn events(count: usize) -> EventArray {
+        EventArray::from(vec![LogEvent::from("event"); count])
+    }
+
+    #[test]
+    fn in_flight_events_are_limited() {
+        let mut in_flight = InFlight::new(&ComponentLimits {
+            max_in_flight_events: Some(3),
+            ..ComponentLimits::default()
+        });
+
+        // Whatever its size, the first batch can always be processed.
+        let first = in_flight.start(&events(5));
+        assert!(!in_flight.has_capacity());
+        in_flight.finish(first);
+        assert!(in_flight.has_capacity());
+
+        let first = in_flight.start(&events(2));
+        assert!(in_flight.has_capacity());
+        let second = in_flight.start(&events(1));
+        assert!(!in_flight.has_capacity());
+        in_flight.finish(second);
+        assert!(in_flight.has_capacity());
+        in_flight.finish(first);
+    }
+
+    #[test]
+    fn in_flight_bytes_are_limited() {
+        let batch = events(2);
+        let mut in_flight = InFlight::new(&ComponentLimits {
+            max_memory_bytes: Some(batch.size_of() + 1),
+            ..ComponentLimits::default()
+        });
+
+        let first = in_flight.start(&batch);
+        assert!(in_flight.has_capacity());
+        let second = in_flight.start(&batch);
+        assert!(!in_flight.has_capacity());
+        in_flight.finish(first);
+        assert!(in_flight.has_capacity());
+        in_flight.finish(second);
+    }
+
+    #[test]
+    fn task_transforms_reject_in_flight_limits() {
+        struct Passthrough;
+
+        impl TaskTransform<EventArray> for Passthrough {
+            fn transform(
+                self: Box<Self>,
+                task: Pin<Box<dyn Stream<Item = EventArray> + Send>>,
+            ) -> Pin<Box<dyn Stream<Item = EventArray> + Send>> {
+                task
+            }
+        }
+
+        let transform = Transform::task(Passthrough);
+        assert!(check(&ComponentLimits::default(), &transform).is_ok());
+        assert!(check(
+            &ComponentLimits {
+                task_budget: Some(10),
+                ..ComponentLimits::default()
+            },
+            &transform
+        )
+        .is_ok());
+        assert!(check(
+            &ComponentLimits {
+                max_in_flight_events: Some(10),
+                ..ComponentLimits::default()
+            },
+            &transform
+        )
+        .is_err());
+        assert!(check(
+            &ComponentLimits {
+                max_memory_bytes: Some(1024),
+                ..ComponentLimits::default()
+            },
+            &transform
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn budgeted_input_yields_once_exhausted() {
+        let limits = ComponentLimits {
+            task_budget: Some(3),
+            ..ComponentLimits::default()
+        };
+        let mut input = Budgeted::new(stream::iter(vec![events(2), events(2), events(1)]), &limits);
+
+        assert_eq!(input.next().now_or_never().flatten(), Some(events(2)));
+        assert_eq!(input.next().now_or_never().flatten(), Some(events(2)));
+        // The budget is exhausted, so the input yields before going on.
+        assert_eq!(input.next().now_or_never(), None);
+        assert_eq!(input.next().now_or_never().flatten(), Some(events(1)));
+        assert_eq!(input.next().now_or_never(), Some(None));
+    }
+
+    #[test]
+    fn unbudgeted_input_never_yields() {
+        let mut input = Budgeted::new(
+            stream::iter(vec![events(200), events(200)]),
+            &ComponentLimits::default(),
+        );
+
+        assert_eq!(input.next().now_or_never().flatten(), Some(events(200)));
+        assert_eq!(input.next().now_or_never().flatten(), Some(events(200)));
+        assert_eq!(input.next().now_or_never(), Some(None));
+    }
+}
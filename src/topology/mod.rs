@@ -9,6 +9,11 @@
 pub(super) use vector_core::fanout;
 
 pub mod builder;
+mod dead_letter;
+pub mod event_tracing;
+mod limits;
+mod priority;
+mod reload;
 mod running;
 mod schema;
 mod task;
@@ -23,6 +28,7 @@ use std::{
 };
 
 use futures::{Future, FutureExt};
+pub use reload::{reload_status, ReloadState, ReloadStatus, ReloadStrategy};
 pub(super) use running::RunningTopology;
 use tokio::sync::{mpsc, watch};
 use vector_buffers::{
@@ -0,0 +1,65 @@
+//! Priority lanes for the buffers of sinks, dropping their low priority events under pressure.
+
+use vector_buffers::topology::channel::PriorityClassifier;
+use vector_core::event::{array::events_into_arrays, EventArray, EventContainer};
+
+use crate::conditions::Condition;
+
+/// Classifies the events matching a condition as low priority, so that they're dropped whenever
+/// the buffer of the sink would otherwise block.
+#[derive(Debug)]
+pub(super) struct LowPriority {
+    condition: Condition,
+}
+
+impl LowPriority {
+    pub(super) const fn new(condition: Condition) -> Self {
+        Self { condition }
+    }
+}
+
+impl PriorityClassifier<EventArray> for LowPriority {
+    fn retain_high_priority(&self, events: EventArray) -> Option<EventArray> {
+        let high_priority = events
+            .into_events()
+            .filter(|event| !self.condition.check(event));
+
+        // The events of an array are all of the same type, so they fit in a single array again.
+        events_into_arrays(high_priority, None).next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conditions::{ConditionConfig, VrlConfig},
+        event::{Event, LogEvent},
+    };
+
+    #[test]
+    fn retains_high_priority_events() {
+        let condition = VrlConfig {
+            source: r#".level == "debug""#.to_string(),
+        }
+        .build(&Default::default())
+        .unwrap();
+        let classifier = LowPriority::new(condition);
+
+        let mut debug = LogEvent::from("chatter");
+        debug.insert("level", "debug");
+        let mut audit = LogEvent::from("login");
+        audit.insert("level", "audit");
+        let events = EventArray::from(vec![debug.clone(), audit.clone(), debug.clone()]);
+
+        let retained = classifier
+            .retain_high_priority(events)
+            .expect("high priority events should be retained")
+            .into_events()
+            .collect::<Vec<_>>();
+        assert_eq!(retained, vec![Event::from(audit)]);
+
+        let events = EventArray::from(vec![debug]);
+        assert!(classifier.retain_high_priority(events).is_none());
+    }
+}
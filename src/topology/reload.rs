@@ -0,0 +1,173 @@
+//! The status of the last reload of the topology, as reported by the API.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+
+use crate::config::ComponentKey;
+
+static RELOADS: Lazy<RwLock<Reloads>> = Lazy::new(RwLock::default);
+
+const INVARIANT: &str = "Couldn't acquire lock on the reload status. Please report this.";
+
+/// How a reload replaces the components of the topology.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReloadStrategy {
+    /// The new components are built while the old ones keep running, the traffic being cut over
+    /// to them once they're all built and healthy.
+    BlueGreen,
+    /// The old components are shut down before the new ones are built, as they claim the same
+    /// resources, such as ports or disk buffers.
+    InPlace,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReloadState {
+    /// The new components are being built, and healthchecked.
+    Building,
+    /// The traffic was cut over to the new components, while the old ones drain their in-flight
+    /// and buffered events.
+    Draining,
+    /// The new components are running, and the old ones are gone.
+    Completed,
+    /// The new components couldn't be built, or weren't healthy, so the old config is still
+    /// running.
+    Failed,
+}
+
+/// The status of a reload of the topology.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReloadStatus {
+    pub strategy: ReloadStrategy,
+    pub state: ReloadState,
+    /// The old components still draining their events.
+    pub draining: Vec<ComponentKey>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// The status of the last reload, and how many reloads there were.
+#[derive(Debug, Default)]
+struct Reloads {
+    generation: u64,
+    status: Option<ReloadStatus>,
+}
+
+/// Returns the status of the last reload, if any.
+pub fn reload_status() -> Option<ReloadStatus> {
+    RELOADS.read().expect(INVARIANT).status.clone()
+}
+
+/// Reports on the progress of an ongoing reload.
+///
+/// Once a newer reload started, the reports on the previous ones are ignored.
+#[derive(Debug)]
+pub(super) struct ReloadHandle {
+    reloads: &'static RwLock<Reloads>,
+    generation: u64,
+}
+
+impl ReloadHandle {
+    pub(super) fn start(strategy: ReloadStrategy) -> Self {
+        Self::start_in(&RELOADS, strategy)
+    }
+
+    fn start_in(reloads: &'static RwLock<Reloads>, strategy: ReloadStrategy) -> Self {
+        let mut current = reloads.write().expect(INVARIANT);
+        current.generation += 1;
+        current.status = Some(ReloadStatus {
+            strategy,
+            state: ReloadState::Building,
+            draining: Vec::new(),
+            started_at: Utc::now(),
+            finished_at: None,
+        });
+        Self {
+            reloads,
+            generation: current.generation,
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut ReloadStatus)) {
+        let mut current = self.reloads.write().expect(INVARIANT);
+        if current.generation == self.generation {
+            if let Some(status) = current.status.as_mut() {
+                f(status);
+            }
+        }
+    }
+
+    pub(super) fn draining(&self, components: Vec<ComponentKey>) {
+        self.update(|status| {
+            status.state = ReloadState::Draining;
+            status.draining = components;
+        });
+    }
+
+    pub(super) fn drained(&self, component: &ComponentKey) {
+        self.update(|status| status.draining.retain(|key| key != component));
+    }
+
+    pub(super) fn finish(self, state: ReloadState) {
+        self.update(|status| {
+            status.state = state;
+            status.draining.clear();
+            status.finished_at = Some(Utc::now());
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reloads() -> &'static RwLock<Reloads> {
+        Box::leak(Box::new(RwLock::default()))
+    }
+
+    fn status(reloads: &RwLock<Reloads>) -> ReloadStatus {
+        reloads.read().unwrap().status.clone().unwrap()
+    }
+
+    #[test]
+    fn reports_progress() {
+        let reloads = reloads();
+        let (source, sink) = (ComponentKey::from("in"), ComponentKey::from("out"));
+
+        let reload = ReloadHandle::start_in(reloads, ReloadStrategy::BlueGreen);
+        let started = status(reloads);
+        assert_eq!(started.strategy, ReloadStrategy::BlueGreen);
+        assert_eq!(started.state, ReloadState::Building);
+        assert_eq!(started.finished_at, None);
+
+        reload.draining(vec![source.clone(), sink.clone()]);
+        assert_eq!(status(reloads).state, ReloadState::Draining);
+        reload.drained(&source);
+        assert_eq!(status(reloads).draining, vec![sink]);
+
+        reload.finish(ReloadState::Completed);
+        let finished = status(reloads);
+        assert_eq!(finished.state, ReloadState::Completed);
+        assert!(finished.draining.is_empty());
+        assert_eq!(finished.started_at, started.started_at);
+        assert!(finished.finished_at.is_some());
+    }
+
+    #[test]
+    fn ignores_previous_reloads() {
+        let reloads = reloads();
+
+        let previous = ReloadHandle::start_in(reloads, ReloadStrategy::BlueGreen);
+        previous.draining(vec![ComponentKey::from("out")]);
+        let reload = ReloadHandle::start_in(reloads, ReloadStrategy::InPlace);
+
+        previous.finish(ReloadState::Completed);
+        let current = status(reloads);
+        assert_eq!(current.strategy, ReloadStrategy::InPlace);
+        assert_eq!(current.state, ReloadState::Building);
+
+        reload.finish(ReloadState::Failed);
+        assert_eq!(status(reloads).state, ReloadState::Failed);
+    }
+}
@@ -9,10 +9,13 @@ use std::{
 use futures::{future, Future, FutureExt};
 use tokio::{
     sync::{mpsc, watch},
-    time::{interval, sleep_until, Duration, Instant},
+    time::{interval, sleep_until, timeout_at, Duration, Instant},
 };
 use tracing::Instrument;
-use vector_buffers::topology::channel::BufferSender;
+use vector_buffers::{
+    topology::channel::{BufferReceiver, BufferSender},
+    Acker,
+};
 
 use crate::{
     config::{ComponentKey, Config, ConfigDiff, HealthcheckOptions, OutputId, Resource},
@@ -22,7 +25,9 @@ use crate::{
         build_or_log_errors, builder,
         builder::Pieces,
         fanout::{ControlChannel, ControlMessage},
-        handle_errors, retain, take_healthchecks,
+        handle_errors,
+        reload::{ReloadHandle, ReloadState, ReloadStrategy},
+        retain, take_healthchecks,
         task::TaskOutput,
         BuiltBuffer, TaskHandle, WatchRx, WatchTx,
     },
@@ -31,6 +36,9 @@ use crate::{
 
 use super::{TapOutput, TapResource};
 
+/// How long the components replaced by a reload are given to drain their events by default.
+const DEFAULT_RELOAD_DRAIN_TIMEOUT_SECS: u64 = 60;
+
 #[allow(dead_code)]
 pub struct RunningTopology {
     inputs: HashMap<ComponentKey, BufferSender<EventArray>>,
@@ -187,6 +195,12 @@ impl RunningTopology {
 
         let diff = ConfigDiff::new(&self.config, &new_config);
 
+        if self.can_build_ahead(&diff, &new_config) {
+            return Ok(self.reload_blue_green(&diff, new_config).await);
+        }
+
+        let reload = ReloadHandle::start(ReloadStrategy::InPlace);
+
         // Checks passed so let's shutdown the difference.
         let buffers = self.shutdown_diff(&diff, &new_config).await;
 
@@ -208,6 +222,7 @@ impl RunningTopology {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
                 self.config = new_config;
+                reload.finish(ReloadState::Completed);
                 // We have successfully changed to new config.
                 return Ok(true);
             }
@@ -223,6 +238,7 @@ impl RunningTopology {
             {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
+                reload.finish(ReloadState::Failed);
                 // We have successfully returned to old config.
                 return Ok(false);
             }
@@ -230,10 +246,180 @@ impl RunningTopology {
 
         // We failed in rebuilding the old state.
         error!("Failed in rebuilding the old configuration.");
+        reload.finish(ReloadState::Failed);
 
         Err(())
     }
 
+    /// Returns whether the new components of the diff can be built while the components they
+    /// replace are still running, which isn't the case when they claim the same resources, such as
+    /// ports or disk buffers.
+    fn can_build_ahead(&self, diff: &ConfigDiff, new_config: &Config) -> bool {
+        let remove_source = diff
+            .sources
+            .removed_and_changed()
+            .map(|key| (key, self.config.sources[key].inner.resources()));
+        let remove_sink = diff
+            .sinks
+            .removed_and_changed()
+            .map(|key| (key, self.config.sinks[key].resources(key)));
+        let add_source = diff
+            .sources
+            .changed_and_added()
+            .map(|key| (key, new_config.sources[key].inner.resources()));
+        let add_sink = diff
+            .sinks
+            .changed_and_added()
+            .map(|key| (key, new_config.sinks[key].resources(key)));
+
+        Resource::conflicts(
+            remove_source
+                .chain(remove_sink)
+                .map(|(key, value)| ((true, key), value))
+                .chain(
+                    add_source
+                        .chain(add_sink)
+                        .map(|(key, value)| ((false, key), value)),
+                ),
+        )
+        .is_empty()
+    }
+
+    /// Builds, and healthchecks, the new components of the diff while the old ones keep running,
+    /// only then cutting the traffic over to them. The old components drain their in-flight and
+    /// buffered events in the background.
+    ///
+    /// If the new components can't be built, or aren't healthy, the topology is left untouched.
+    async fn reload_blue_green(&mut self, diff: &ConfigDiff, new_config: Config) -> bool {
+        let reload = ReloadHandle::start(ReloadStrategy::BlueGreen);
+
+        // The changed sinks take the buffers over from the sinks they replace, once those stop
+        // reading from them. As disk buffers can't be claimed by two sinks at once, these are
+        // memory buffers, which don't acknowledge the events read.
+        let buffers = self
+            .reusable_buffers(diff, &new_config)
+            .into_iter()
+            .map(|key| {
+                let tx = self.inputs[&key].clone();
+                let buffer: BuiltBuffer = (tx, Arc::new(Mutex::new(None)), Acker::passthrough());
+                (key, buffer)
+            })
+            .collect::<HashMap<_, _>>();
+        let receivers = buffers
+            .iter()
+            .map(|(key, (_, rx, _))| (key.clone(), Arc::clone(rx)))
+            .collect();
+
+        info!("Building new components alongside the running ones.");
+        let mut new_pieces = match build_or_log_errors(&new_config, diff, buffers).await {
+            Some(new_pieces) => new_pieces,
+            None => {
+                reload.finish(ReloadState::Failed);
+                return false;
+            }
+        };
+        if !self
+            .run_healthchecks(diff, &mut new_pieces, new_config.healthchecks)
+            .await
+        {
+            reload.finish(ReloadState::Failed);
+            return false;
+        }
+
+        let draining = self.cut_over_diff(diff, receivers).await;
+        self.connect_diff(diff, &mut new_pieces).await;
+        self.spawn_diff(diff, new_pieces);
+        let drain_timeout = Duration::from_secs(
+            new_config
+                .global
+                .reload_drain_timeout_secs
+                .unwrap_or(DEFAULT_RELOAD_DRAIN_TIMEOUT_SECS),
+        );
+        self.config = new_config;
+
+        reload.draining(draining.iter().map(|(key, _)| key.clone()).collect());
+        tokio::spawn(drain(reload, draining, drain_timeout));
+
+        true
+    }
+
+    /// Detaches the removed and replaced pieces of topology, so that they drain their events
+    /// while the new ones take over. Sources are shut down right away, as they'd otherwise keep
+    /// on producing events.
+    ///
+    /// The replaced sinks whose buffer is reused stop reading from it instead, their receiver being
+    /// handed over to the new sinks.
+    ///
+    /// Returns the tasks of the detached pieces.
+    async fn cut_over_diff(
+        &mut self,
+        diff: &ConfigDiff,
+        receivers: HashMap<ComponentKey, Arc<Mutex<Option<BufferReceiver<EventArray>>>>>,
+    ) -> Vec<(ComponentKey, TaskHandle)> {
+        let mut draining = Vec::new();
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut source_shutdown_complete_futures = Vec::new();
+        for key in diff.sources.removed_and_changed() {
+            info!(message = "Shutting down source.", key = %key);
+            draining.extend(self.tasks.remove(key).map(|task| (key.clone(), task)));
+
+            self.remove_outputs(key);
+            source_shutdown_complete_futures
+                .push(self.shutdown_coordinator.shutdown_source(key, deadline));
+        }
+        futures::future::join_all(source_shutdown_complete_futures).await;
+
+        for key in diff.sources.removed_and_changed() {
+            if let Some(task) = self.source_tasks.remove(key) {
+                task.await.unwrap().unwrap();
+            }
+        }
+
+        for key in &diff.transforms.to_remove {
+            info!(message = "Removing transform.", key = %key);
+            draining.extend(self.tasks.remove(key).map(|task| (key.clone(), task)));
+
+            self.remove_inputs(key).await;
+            self.remove_outputs(key);
+        }
+
+        for key in &diff.sinks.to_remove {
+            info!(message = "Removing sink.", key = %key);
+            draining.extend(self.tasks.remove(key).map(|task| (key.clone(), task)));
+
+            self.remove_inputs(key).await;
+            self.remove_outputs(key);
+        }
+
+        // Replaced transforms and sinks lose their inputs once the new ones are connected.
+        for key in &diff.transforms.to_change {
+            draining.extend(self.tasks.remove(key).map(|task| (key.clone(), task)));
+        }
+        for key in &diff.sinks.to_change {
+            self.remove_outputs(key);
+            let previous = self.tasks.remove(key);
+            match receivers.get(key) {
+                Some(receiver) => {
+                    self.detach_triggers
+                        .remove(key)
+                        .unwrap()
+                        .into_inner()
+                        .cancel();
+                    debug!(message = "Waiting for sink to hand its buffer over.", %key);
+                    let rx = match previous.unwrap().await.unwrap().unwrap() {
+                        TaskOutput::Sink(rx, _) => rx.into_inner(),
+                        _ => unreachable!(),
+                    };
+                    *receiver.lock().unwrap() = Some(rx);
+                }
+                None => draining.extend(previous.map(|task| (key.clone(), task))),
+            }
+        }
+
+        draining
+    }
+
     pub(crate) async fn run_healthchecks(
         &mut self,
         diff: &ConfigDiff,
@@ -266,6 +452,16 @@ impl RunningTopology {
         }
     }
 
+    /// Returns the changed sinks which can reuse their buffer, as its configuration wasn't changed.
+    fn reusable_buffers(&self, diff: &ConfigDiff, new_config: &Config) -> HashSet<ComponentKey> {
+        diff.sinks
+            .to_change
+            .iter()
+            .filter(|&key| self.config.sinks[key].buffer == new_config.sinks[key].buffer)
+            .cloned()
+            .collect()
+    }
+
     /// Shutdowns removed and replaced pieces of topology.
     /// Returns buffers to be reused.
     async fn shutdown_diff(
@@ -369,15 +565,7 @@ impl RunningTopology {
             .filter(|&(existing_sink, _)| existing_sink)
             .map(|(_, key)| key.clone());
 
-        // Buffer reuse
-        // We can reuse buffers whose configuration wasn't changed.
-        let reuse_buffers = diff
-            .sinks
-            .to_change
-            .iter()
-            .filter(|&key| self.config.sinks[key].buffer == new_config.sinks[key].buffer)
-            .cloned()
-            .collect::<HashSet<_>>();
+        let reuse_buffers = self.reusable_buffers(diff, new_config);
 
         let wait_for_sinks = conflicting_sinks
             .chain(reuse_buffers.iter().cloned())
@@ -389,6 +577,7 @@ impl RunningTopology {
         for key in &diff.sinks.to_remove {
             info!(message = "Removing sink.", key = %key);
             self.remove_inputs(key).await;
+            self.remove_outputs(key);
         }
 
         // Detach changed sinks
@@ -420,6 +609,7 @@ impl RunningTopology {
         // Cleanup changed and collect buffers to be reused
         let mut buffers = HashMap::<ComponentKey, BuiltBuffer>::new();
         for key in &diff.sinks.to_change {
+            self.remove_outputs(key);
             if wait_for_sinks.contains(key) {
                 let previous = self.tasks.remove(key).unwrap();
                 debug!(message = "Waiting for sink to shutdown.", %key);
@@ -477,6 +667,13 @@ impl RunningTopology {
             self.setup_outputs(key, new_pieces).await;
         }
 
+        // Sinks with a dead letter component have an output too
+        for key in diff.sinks.changed_and_added() {
+            if new_pieces.outputs.contains_key(key) {
+                self.setup_outputs(key, new_pieces).await;
+            }
+        }
+
         for key in &diff.transforms.to_change {
             self.replace_inputs(key, new_pieces).await;
         }
@@ -795,3 +992,19 @@ impl RunningTopology {
         self.watch.1.clone()
     }
 }
+
+/// Waits for the detached pieces of topology to drain their events, aborting those which don't
+/// finish in time.
+async fn drain(reload: ReloadHandle, draining: Vec<(ComponentKey, TaskHandle)>, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    for (key, mut task) in draining {
+        if timeout_at(deadline, &mut task).await.is_err() {
+            warn!(message = "Failed to drain in time. Killing component.", key = %key);
+            task.abort();
+        } else {
+            debug!(message = "Component drained.", key = %key);
+        }
+        reload.drained(&key);
+    }
+    reload.finish(ReloadState::Completed);
+}
@@ -105,6 +105,12 @@ pub(super) fn merged_definition(
             };
 
             definition = definition.merge(transform_definition);
+
+        // If the input is the dead letter output of a sink, the events are those of the inputs of
+        // the sink.
+        } else if let Some(sink) = config.sinks.get(key) {
+            let sink_definition = merged_definition(&sink.inputs, config, cache);
+            definition = definition.merge(sink_definition);
         }
     }
 
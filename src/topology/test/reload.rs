@@ -123,6 +123,9 @@ async fn topology_reuse_old_port_sink() {
             namespace: None,
             tags: None,
         })],
+        tags: None,
+        all_metrics: false,
+        host_tag: None,
     };
 
     let mut old_config = Config::builder();
@@ -173,6 +176,9 @@ async fn topology_reuse_old_port_cross_dependency() {
             namespace: None,
             tags: None,
         })],
+        tags: None,
+        all_metrics: false,
+        host_tag: None,
     };
 
     let mut old_config = Config::builder();
@@ -236,6 +242,9 @@ async fn topology_disk_buffer_conflict() {
                 namespace: None,
                 tags: None,
             })],
+            tags: None,
+            all_metrics: false,
+            host_tag: None,
         },
     );
     old_config.add_sink(
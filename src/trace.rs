@@ -1,4 +1,7 @@
-use std::sync::{Mutex, MutexGuard};
+use std::{
+    fmt::Debug,
+    sync::{Mutex, MutexGuard},
+};
 
 use metrics_tracing_context::MetricsLayer;
 use once_cell::sync::OnceCell;
@@ -13,7 +16,7 @@ use tracing_core::span;
 pub use tracing_futures::Instrument;
 use tracing_limit::RateLimitedLayer;
 use tracing_log::LogTracer;
-use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{layer::SubscriberExt, registry::LookupSpan};
 pub use tracing_tower::{InstrumentableService, InstrumentedService};
 
 use crate::event::LogEvent;
@@ -28,6 +31,11 @@ static BUFFER: OnceCell<Mutex<Option<Vec<LogEvent>>>> = OnceCell::new();
 /// initialized.
 static SENDER: OnceCell<Sender<LogEvent>> = OnceCell::new();
 
+/// COMPONENT_SENDER holds the sender handle of the per-component log
+/// stream, which only carries internal log events emitted within the span
+/// of a component, along with the id of that component.
+static COMPONENT_SENDER: OnceCell<Sender<ComponentLogEvent>> = OnceCell::new();
+
 fn metrics_layer_enabled() -> bool {
     !matches!(std::env::var("DISABLE_INTERNAL_METRICS_TRACING_INTEGRATION"), Ok(x) if x == "true")
 }
@@ -137,11 +145,82 @@ pub fn subscribe() -> TraceSubscription {
     TraceSubscription { buffer, receiver }
 }
 
+/// An internal log event emitted within the span of a component
+#[derive(Clone, Debug)]
+pub struct ComponentLogEvent {
+    pub component_id: String,
+    pub log: LogEvent,
+}
+
+/// Returns a receiver for internal log events emitted by components from now on. Unlike
+/// `subscribe`, this leaves the early buffer untouched, and events are only attributed to
+/// components while there is at least one receiver.
+pub fn subscribe_components() -> Receiver<ComponentLogEvent> {
+    COMPONENT_SENDER
+        .get_or_init(|| broadcast::channel(99).0)
+        .subscribe()
+}
+
+/// Id of the component a span was created for, kept in the span's extensions so that
+/// internal log events can be attributed to the component that emitted them.
+struct SpanComponentId(String);
+
+#[derive(Default)]
+struct ComponentIdVisitor(Option<String>);
+
+impl tracing::field::Visit for ComponentIdVisitor {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "component_id" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) {
+        if field.name() == "component_id" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
 struct BroadcastSubscriber<S> {
     subscriber: S,
 }
 
-impl<S: Subscriber + 'static> Subscriber for BroadcastSubscriber<S> {
+impl<S> BroadcastSubscriber<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    /// Returns the id of the component whose span the event was emitted in, if any
+    fn component_id(&self, event: &tracing::Event<'_>) -> Option<String> {
+        let current = self.subscriber.current_span();
+        let id = event.parent().or_else(|| current.id())?;
+
+        self.subscriber.span(id)?.scope().find_map(|span| {
+            let extensions = span.extensions();
+            extensions
+                .get::<SpanComponentId>()
+                .map(|component_id| component_id.0.clone())
+        })
+    }
+
+    fn send_component_log_event(&self, event: &tracing::Event<'_>) {
+        if let Some(sender) = COMPONENT_SENDER.get() {
+            if sender.receiver_count() > 0 {
+                if let Some(component_id) = self.component_id(event) {
+                    let _ = sender.send(ComponentLogEvent {
+                        component_id,
+                        log: event.into(),
+                    }); // Ignore errors
+                }
+            }
+        }
+    }
+}
+
+impl<S> Subscriber for BroadcastSubscriber<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+{
     #[inline]
     fn enabled(&self, metadata: &Metadata<'_>) -> bool {
         self.subscriber.enabled(metadata)
@@ -149,7 +228,17 @@ impl<S: Subscriber + 'static> Subscriber for BroadcastSubscriber<S> {
 
     #[inline]
     fn new_span(&self, span: &tracing::span::Attributes<'_>) -> Id {
-        self.subscriber.new_span(span)
+        let id = self.subscriber.new_span(span);
+
+        let mut visitor = ComponentIdVisitor::default();
+        span.record(&mut visitor);
+        if let (Some(component_id), Some(span_ref)) = (visitor.0, self.subscriber.span(&id)) {
+            span_ref
+                .extensions_mut()
+                .insert(SpanComponentId(component_id));
+        }
+
+        id
     }
 
     #[inline]
@@ -170,6 +259,7 @@ impl<S: Subscriber + 'static> Subscriber for BroadcastSubscriber<S> {
         if let Some(sender) = SENDER.get() {
             let _ = sender.send(event.into()); // Ignore errors
         }
+        self.send_component_log_event(event);
         self.subscriber.event(event)
     }
 
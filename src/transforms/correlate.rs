@@ -0,0 +1,402 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use async_stream::stream;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::{CorrelateUnmatchedEventDiscarded, TemplateRenderingError},
+    schema,
+    template::Template,
+    transforms::{TaskTransform, Transform},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CorrelateConfig {
+    /// The condition matching the events of the left stream.
+    pub left: AnyCondition,
+    /// The condition matching the events of the right stream.
+    pub right: AnyCondition,
+    /// The key pairing a left event with a right one.
+    pub key: Template,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: f64,
+    #[serde(default)]
+    pub unmatched_left: UnmatchedPolicy,
+    #[serde(default)]
+    pub unmatched_right: UnmatchedPolicy,
+    #[serde(default = "default_left_field")]
+    pub left_field: String,
+    #[serde(default = "default_right_field")]
+    pub right_field: String,
+}
+
+/// What becomes of the events not paired before their window ends.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmatchedPolicy {
+    /// The events are forwarded as is.
+    Emit,
+    Drop,
+}
+
+impl Default for UnmatchedPolicy {
+    fn default() -> Self {
+        Self::Emit
+    }
+}
+
+const fn default_window_secs() -> f64 {
+    30.0
+}
+
+fn default_left_field() -> String {
+    "left".to_owned()
+}
+
+fn default_right_field() -> String {
+    "right".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<CorrelateConfig>("correlate")
+}
+
+impl GenerateConfig for CorrelateConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            left: AnyCondition::String(r#".type == "request""#.to_owned()),
+            right: AnyCondition::String(r#".type == "response""#.to_owned()),
+            key: Template::try_from("{{ request_id }}").unwrap(),
+            window_secs: default_window_secs(),
+            unmatched_left: UnmatchedPolicy::Emit,
+            unmatched_right: UnmatchedPolicy::Emit,
+            left_field: default_left_field(),
+            right_field: default_right_field(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "correlate")]
+impl TransformConfig for CorrelateConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Correlate::new(self, &context.enrichment_tables).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "correlate"
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    event: Event,
+    expires_at: Instant,
+}
+
+/// The events of a key waiting for an event of the other side.
+#[derive(Debug, Default)]
+struct Pendings {
+    left: VecDeque<Pending>,
+    right: VecDeque<Pending>,
+}
+
+impl Pendings {
+    fn side_mut(&mut self, side: Side) -> &mut VecDeque<Pending> {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+}
+
+pub struct Correlate {
+    left: Condition,
+    right: Condition,
+    key: Template,
+    window: Duration,
+    unmatched_left: UnmatchedPolicy,
+    unmatched_right: UnmatchedPolicy,
+    left_field: String,
+    right_field: String,
+    pending: HashMap<String, Pendings>,
+}
+
+impl Correlate {
+    pub fn new(
+        config: &CorrelateConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        if !config.window_secs.is_finite() || config.window_secs <= 0.0 {
+            return Err("`window_secs` must be positive".into());
+        }
+
+        Ok(Self {
+            left: config.left.build(enrichment_tables)?,
+            right: config.right.build(enrichment_tables)?,
+            key: config.key.clone(),
+            window: Duration::from_secs_f64(config.window_secs),
+            unmatched_left: config.unmatched_left,
+            unmatched_right: config.unmatched_right,
+            left_field: config.left_field.clone(),
+            right_field: config.right_field.clone(),
+            pending: HashMap::new(),
+        })
+    }
+
+    /// Pairs the event with the oldest pending event of the other side with the same key, or
+    /// keeps it until its window ends. The events of neither side, or without a key, are
+    /// forwarded as is.
+    fn record(&mut self, event: Event, now: Instant, output: &mut Vec<Event>) {
+        let side = if self.left.check(&event) {
+            Side::Left
+        } else if self.right.check(&event) {
+            Side::Right
+        } else {
+            output.push(event);
+            return;
+        };
+
+        let key = match self.key.render_string(&event) {
+            Ok(key) => key,
+            Err(error) => {
+                emit!(&TemplateRenderingError {
+                    error,
+                    field: Some("key"),
+                    drop_event: false,
+                });
+                output.push(event);
+                return;
+            }
+        };
+
+        let pendings = self.pending.entry(key).or_default();
+        let other = match side {
+            Side::Left => &mut pendings.right,
+            Side::Right => &mut pendings.left,
+        };
+        match other.pop_front() {
+            Some(pending) => {
+                let (left, right) = match side {
+                    Side::Left => (event, pending.event),
+                    Side::Right => (pending.event, event),
+                };
+                output.push(merge(&self.left_field, &self.right_field, left, right));
+            }
+            None => pendings.side_mut(side).push_back(Pending {
+                event,
+                expires_at: now + self.window,
+            }),
+        }
+    }
+
+    /// Applies the unmatched policies to the events the windows of which ended by `now`, or to
+    /// all the pending events if `now` is `None`.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: Option<Instant>) {
+        let (unmatched_left, unmatched_right) = (self.unmatched_left, self.unmatched_right);
+        self.pending.retain(|_, pendings| {
+            for (side, policy) in [(Side::Left, unmatched_left), (Side::Right, unmatched_right)] {
+                let queue = pendings.side_mut(side);
+                while let Some(pending) = queue.front() {
+                    if now.map_or(false, |now| pending.expires_at > now) {
+                        break;
+                    }
+                    let event = queue.pop_front().expect("Queue is not empty").event;
+                    match policy {
+                        UnmatchedPolicy::Emit => output.push(event),
+                        UnmatchedPolicy::Drop => emit!(&CorrelateUnmatchedEventDiscarded {
+                            side: side.as_str()
+                        }),
+                    }
+                }
+            }
+            !pendings.is_empty()
+        });
+    }
+}
+
+/// Nests the fields of the paired events, and merges their metadata so the merged event is
+/// only delivered once both are.
+fn merge(left_field: &str, right_field: &str, left: Event, right: Event) -> Event {
+    let (left, mut metadata) = left.into_log().into_parts();
+    let (right, right_metadata) = right.into_log().into_parts();
+    metadata.merge(right_metadata);
+
+    let timestamp = [&left, &right]
+        .iter()
+        .filter_map(|fields| {
+            fields
+                .get(log_schema().timestamp_key())
+                .and_then(Value::as_timestamp)
+                .cloned()
+        })
+        .max();
+
+    let mut log = LogEvent::new_with_metadata(metadata);
+    log.insert(left_field, left);
+    log.insert(right_field, right);
+    if let Some(timestamp) = timestamp {
+        log.insert(log_schema().timestamp_key(), timestamp);
+    }
+    Event::Log(log)
+}
+
+impl TaskTransform<Event> for Correlate {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(1));
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(&mut output, Some(Instant::now()));
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_into(&mut output, None);
+                                done = true;
+                            }
+                            Some(event) => self.record(event, Instant::now(), &mut output),
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<CorrelateConfig>();
+    }
+
+    fn make_transform(
+        unmatched_left: UnmatchedPolicy,
+        unmatched_right: UnmatchedPolicy,
+    ) -> Correlate {
+        let config = toml::from_str::<CorrelateConfig>(
+            r#"
+            left = '.type == "request"'
+            right = '.type == "response"'
+            key = "{{ id }}"
+            window_secs = 10
+            "#,
+        )
+        .unwrap();
+        Correlate::new(
+            &CorrelateConfig {
+                unmatched_left,
+                unmatched_right,
+                ..config
+            },
+            &Default::default(),
+        )
+        .unwrap()
+    }
+
+    fn event(kind: &str, id: &str) -> Event {
+        let mut event = Event::from(format!("{} {}", kind, id));
+        event.as_mut_log().insert("type", kind);
+        event.as_mut_log().insert("id", id);
+        event
+    }
+
+    #[test]
+    fn pairs_events_by_key() {
+        let mut transform = make_transform(UnmatchedPolicy::Emit, UnmatchedPolicy::Emit);
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        transform.record(event("request", "1"), now, &mut output);
+        transform.record(event("request", "2"), now, &mut output);
+        transform.record(event("response", "2"), now, &mut output);
+        assert_eq!(output.len(), 1);
+
+        let merged = output.pop().unwrap().into_log();
+        assert_eq!(merged["left.message"], "request 2".into());
+        assert_eq!(merged["right.message"], "response 2".into());
+
+        // Events of neither side are forwarded as is.
+        transform.record(Event::from("other"), now, &mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output.pop().unwrap().into_log()["message"], "other".into());
+
+        // The pending request is forwarded once its window ends.
+        transform.flush_into(&mut output, Some(now + Duration::from_secs(9)));
+        assert!(output.is_empty());
+        transform.flush_into(&mut output, Some(now + Duration::from_secs(10)));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["message"], "request 1".into());
+        assert!(transform.pending.is_empty());
+    }
+
+    #[test]
+    fn drops_unmatched_events() {
+        let mut transform = make_transform(UnmatchedPolicy::Emit, UnmatchedPolicy::Drop);
+        let now = Instant::now();
+        let mut output = Vec::new();
+
+        transform.record(event("request", "1"), now, &mut output);
+        transform.record(event("response", "2"), now, &mut output);
+        transform.flush_into(&mut output, None);
+
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["message"], "request 1".into());
+    }
+}
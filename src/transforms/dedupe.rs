@@ -1,21 +1,35 @@
-use std::{future::ready, pin::Pin};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::Duration,
+};
 
+use async_stream::stream;
 use bytes::Bytes;
-use futures::{Stream, StreamExt};
+use futures::{stream, Stream, StreamExt};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use vector_common::TimeZone;
+use vrl::{diagnostic::Formatter, Program, Runtime};
 
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
         TransformDescription,
     },
-    event::{Event, Value},
-    internal_events::DedupeEventDiscarded,
+    event::{Event, Value, VrlTarget},
+    internal_events::{DedupeCacheError, DedupeEventDiscarded, DedupeFieldsError},
     schema,
     transforms::{TaskTransform, Transform},
 };
 
+const CACHE_FILENAME: &str = "cache.bin";
+
+/// The length of the content hashes kept in the cache.
+const HASH_LEN: usize = 16;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub enum FieldMatchConfig {
@@ -23,12 +37,21 @@ pub enum FieldMatchConfig {
     MatchFields(Vec<String>),
     #[serde(rename = "ignore")]
     IgnoreFields(Vec<String>),
+    /// A VRL expression computing the value that identifies the duplicates.
+    #[serde(rename = "vrl")]
+    Vrl(String),
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CacheConfig {
     pub num_events: usize,
+    /// Whether to persist the cache in the data directory, so the duplicates of the events seen
+    /// before a restart are caught too. A persisted cache always holds content hashes.
+    #[serde(default)]
+    pub persist: bool,
+    #[serde(default = "default_persist_interval_secs")]
+    pub persist_interval_secs: u64,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -38,10 +61,23 @@ pub struct DedupeConfig {
     pub fields: Option<FieldMatchConfig>,
     #[serde(default = "default_cache_config")]
     pub cache: CacheConfig,
+    /// Whether to keep a hash of the matched fields in the cache instead of their values, which
+    /// bounds the memory used by each cached event.
+    #[serde(default)]
+    pub hash: bool,
+    pub data_dir: Option<PathBuf>,
 }
 
 const fn default_cache_config() -> CacheConfig {
-    CacheConfig { num_events: 5000 }
+    CacheConfig {
+        num_events: 5000,
+        persist: false,
+        persist_interval_secs: default_persist_interval_secs(),
+    }
+}
+
+const fn default_persist_interval_secs() -> u64 {
+    10
 }
 
 impl DedupeConfig {
@@ -50,8 +86,7 @@ impl DedupeConfig {
     /// after we've already parsed the config.
     pub fn fill_default_fields_match(&self) -> FieldMatchConfig {
         match &self.fields {
-            Some(FieldMatchConfig::MatchFields(x)) => FieldMatchConfig::MatchFields(x.clone()),
-            Some(FieldMatchConfig::IgnoreFields(y)) => FieldMatchConfig::IgnoreFields(y.clone()),
+            Some(fields) => fields.clone(),
             None => FieldMatchConfig::MatchFields(vec![
                 log_schema().timestamp_key().into(),
                 log_schema().host_key().into(),
@@ -63,7 +98,19 @@ impl DedupeConfig {
 
 pub struct Dedupe {
     fields: FieldMatchConfig,
+    /// The compiled `fields.vrl` expression.
+    program: Option<Program>,
+    hash: bool,
     cache: LruCache<CacheEntry, bool>,
+    persistence: Option<Persistence>,
+}
+
+/// Where and how often the cache is persisted.
+struct Persistence {
+    path: PathBuf,
+    interval: Duration,
+    /// Whether the cache changed since it was last written.
+    dirty: bool,
 }
 
 inventory::submit! {
@@ -75,6 +122,8 @@ impl GenerateConfig for DedupeConfig {
         toml::Value::try_from(Self {
             fields: None,
             cache: default_cache_config(),
+            hash: false,
+            data_dir: None,
         })
         .unwrap()
     }
@@ -83,8 +132,24 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::event_task(Dedupe::new(self.clone())))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let mut dedupe = Dedupe::new(self.clone(), &context.enrichment_tables)?;
+
+        if self.cache.persist {
+            let subdir = context.key.as_ref().map_or("dedupe", |key| key.id());
+            let path = context
+                .globals
+                .resolve_and_make_data_subdir(self.data_dir.as_ref(), subdir)?
+                .join(CACHE_FILENAME);
+            dedupe
+                .restore(
+                    path,
+                    Duration::from_secs(self.cache.persist_interval_secs.max(1)),
+                )
+                .await?;
+        }
+
+        Ok(Transform::event_task(dedupe))
     }
 
     fn input(&self) -> Input {
@@ -113,7 +178,8 @@ type TypeId = u8;
 /// "fields.match", so there is never any ambiguity about what field is being
 /// referred to. If a field from "fields.match" does not show up in an incoming
 /// Event, the CacheEntry will have None in the correspond location in the
-/// vector.
+/// vector. The value computed by "fields.vrl" is stored the same way, as the
+/// single element of the vector.
 ///
 /// When ignoring fields, a CacheEntry contains a vector of 3-tuples. Each
 /// element in the vector represents one field in the corresponding LogEvent.
@@ -125,10 +191,53 @@ type TypeId = u8;
 /// iterating over the fields of the incoming Events, we know that the
 /// CacheEntries for 2 equivalent events will always contain the fields in the
 /// same order.
+///
+/// When hashing, a CacheEntry only contains a hash of one of the above.
 #[derive(PartialEq, Eq, Hash)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
     Ignore(Vec<(String, TypeId, Bytes)>),
+    Hash([u8; HASH_LEN]),
+}
+
+impl CacheEntry {
+    /// Hashes the entry with SHA-256, which is stable across versions, so persisted hashes can
+    /// be compared with the ones of later events.
+    fn hashed(self) -> Self {
+        fn update(hasher: &mut Sha256, bytes: &[u8]) {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        }
+
+        let mut hasher = Sha256::new();
+        match &self {
+            CacheEntry::Match(fields) => {
+                hasher.update([0]);
+                for field in fields {
+                    match field {
+                        Some((type_id, value)) => {
+                            hasher.update([1, *type_id]);
+                            update(&mut hasher, value);
+                        }
+                        None => hasher.update([0]),
+                    }
+                }
+            }
+            CacheEntry::Ignore(fields) => {
+                hasher.update([1]);
+                for (name, type_id, value) in fields {
+                    update(&mut hasher, name.as_bytes());
+                    hasher.update([*type_id]);
+                    update(&mut hasher, value);
+                }
+            }
+            CacheEntry::Hash(_) => return self,
+        }
+
+        let mut hash = [0; HASH_LEN];
+        hash.copy_from_slice(&hasher.finalize()[..HASH_LEN]);
+        CacheEntry::Hash(hash)
+    }
 }
 
 /// Assigns a unique number to each of the types supported by Event::Value.
@@ -147,26 +256,136 @@ const fn type_id_for_value(val: &Value) -> TypeId {
 }
 
 impl Dedupe {
-    pub fn new(config: DedupeConfig) -> Self {
+    pub fn new(
+        config: DedupeConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
         let num_entries = config.cache.num_events;
         let fields = config.fill_default_fields_match();
-        Self {
+
+        let program = match &fields {
+            FieldMatchConfig::Vrl(source) => {
+                let functions = vrl_stdlib::all()
+                    .into_iter()
+                    .chain(enrichment::vrl_functions().into_iter())
+                    .chain(vector_vrl_functions::vrl_functions())
+                    .collect::<Vec<_>>();
+
+                let mut state = vrl::state::Compiler::new();
+                state.set_external_context(enrichment_tables.clone());
+
+                Some(
+                    vrl::compile_with_state(source, &functions, &mut state).map_err(
+                        |diagnostics| Formatter::new(source, diagnostics).colored().to_string(),
+                    )?,
+                )
+            }
+            _ => None,
+        };
+
+        Ok(Self {
             fields,
+            program,
+            hash: config.hash || config.cache.persist,
             cache: LruCache::new(num_entries),
+            persistence: None,
+        })
+    }
+
+    /// Loads the cache persisted at `path`, if any, and persists it there from now on.
+    async fn restore(&mut self, path: PathBuf, interval: Duration) -> io::Result<()> {
+        match tokio::fs::read(&path).await {
+            // The hashes are stored from the least to the most recently seen.
+            Ok(contents) => {
+                for hash in contents.chunks_exact(HASH_LEN) {
+                    let mut entry = [0; HASH_LEN];
+                    entry.copy_from_slice(hash);
+                    self.cache.put(CacheEntry::Hash(entry), true);
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        self.persistence = Some(Persistence {
+            path,
+            interval,
+            dirty: false,
+        });
+        Ok(())
+    }
+
+    /// Writes the cache, if it changed since it was last written.
+    async fn persist(&mut self) {
+        let contents = match &self.persistence {
+            Some(persistence) if persistence.dirty => self
+                .cache
+                .iter()
+                .rev()
+                .filter_map(|(entry, _)| match entry {
+                    CacheEntry::Hash(hash) => Some(hash.as_slice()),
+                    _ => None,
+                })
+                .flatten()
+                .copied()
+                .collect::<Vec<u8>>(),
+            _ => return,
+        };
+
+        if let Some(persistence) = self.persistence.as_mut() {
+            match write_atomic(&persistence.path, &contents).await {
+                Ok(()) => persistence.dirty = false,
+                Err(error) => emit!(&DedupeCacheError { error }),
+            }
         }
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
-        let cache_entry = build_cache_entry(&event, &self.fields);
+        let cache_entry = match &self.program {
+            Some(program) => match run_vrl(program, &event) {
+                Ok(value) => CacheEntry::Match(vec![Some((
+                    type_id_for_value(&value),
+                    value.coerce_to_bytes(),
+                ))]),
+                Err(error) => {
+                    emit!(&DedupeFieldsError { error });
+                    return Some(event);
+                }
+            },
+            None => build_cache_entry(&event, &self.fields),
+        };
+        let cache_entry = if self.hash {
+            cache_entry.hashed()
+        } else {
+            cache_entry
+        };
+
         if self.cache.put(cache_entry, true).is_some() {
             emit!(&DedupeEventDiscarded { event });
             None
         } else {
+            if let Some(persistence) = self.persistence.as_mut() {
+                persistence.dirty = true;
+            }
             Some(event)
         }
     }
 }
 
+fn run_vrl(program: &Program, event: &Event) -> Result<Value, String> {
+    let mut target = VrlTarget::new(event.clone());
+    Runtime::default()
+        .resolve(&mut target, program, &TimeZone::default())
+        .map_err(|error| error.to_string())
+}
+
+/// Writes to a temporary file first, so a crash never leaves a truncated cache.
+async fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, path).await
+}
+
 /// Takes in an Event and returns a CacheEntry to place into the LRU cache
 /// containing all relevant information for the fields that need matching
 /// against according to the specified FieldMatchConfig.
@@ -198,19 +417,55 @@ fn build_cache_entry(event: &Event, fields: &FieldMatchConfig) -> CacheEntry {
 
             CacheEntry::Ignore(entry)
         }
+        // The VRL expression is run by the transform itself.
+        FieldMatchConfig::Vrl(_) => CacheEntry::Match(Vec::new()),
     }
 }
 
 impl TaskTransform<Event> for Dedupe {
     fn transform(
         self: Box<Self>,
-        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
     where
         Self: 'static,
     {
-        let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        let mut me = self;
+
+        let persist_period = me.persistence.as_ref().map_or(
+            Duration::from_secs(default_persist_interval_secs()),
+            |persistence| persistence.interval,
+        );
+        let mut persist_stream = tokio::time::interval(persist_period);
+
+        Box::pin(
+            stream! {
+              loop {
+                let mut output = None;
+                let done = tokio::select! {
+                    _ = persist_stream.tick() => {
+                      me.persist().await;
+                      false
+                    }
+                    maybe_event = input_rx.next() => {
+                      match maybe_event {
+                        None => {
+                          me.persist().await;
+                          true
+                        }
+                        Some(event) => {
+                          output = me.transform_one(event);
+                          false
+                        }
+                      }
+                    }
+                };
+                yield stream::iter(output.into_iter());
+                if done { break }
+              }
+            }
+            .flatten(),
+        )
     }
 }
 
@@ -229,11 +484,25 @@ mod tests {
         crate::test_util::test_generate_config::<DedupeConfig>();
     }
 
+    fn make_transform(num_events: usize, fields: FieldMatchConfig, hash: bool) -> Dedupe {
+        Dedupe::new(
+            DedupeConfig {
+                cache: CacheConfig {
+                    num_events,
+                    persist: false,
+                    persist_interval_secs: default_persist_interval_secs(),
+                },
+                fields: Some(fields),
+                hash,
+                data_dir: None,
+            },
+            &Default::default(),
+        )
+        .unwrap()
+    }
+
     fn make_match_transform(num_events: usize, fields: Vec<String>) -> Dedupe {
-        Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
-            fields: Some(FieldMatchConfig::MatchFields(fields)),
-        })
+        make_transform(num_events, FieldMatchConfig::MatchFields(fields), false)
     }
 
     fn make_ignore_transform(num_events: usize, given_fields: Vec<String>) -> Dedupe {
@@ -241,10 +510,7 @@ mod tests {
         let mut fields = vec!["message".into(), "timestamp".into()];
         fields.extend(given_fields);
 
-        Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
-            fields: Some(FieldMatchConfig::IgnoreFields(fields)),
-        })
+        make_transform(num_events, FieldMatchConfig::IgnoreFields(fields), false)
     }
 
     #[test]
@@ -483,4 +749,86 @@ mod tests {
         let new_event = transform.transform_one(event2.clone()).unwrap();
         assert_eq!(new_event, event2);
     }
+
+    #[test]
+    fn dedupe_hash_basic() {
+        let transform = make_transform(
+            5,
+            FieldMatchConfig::MatchFields(vec!["matched".into()]),
+            true,
+        );
+        basic(transform);
+    }
+
+    #[test]
+    fn dedupe_hash_type_matching() {
+        let transform = make_transform(
+            5,
+            FieldMatchConfig::IgnoreFields(vec!["message".into(), "timestamp".into()]),
+            true,
+        );
+        type_matching(transform);
+    }
+
+    #[test]
+    fn dedupe_vrl_basic() {
+        let transform = make_transform(5, FieldMatchConfig::Vrl(".matched".into()), false);
+        basic(transform);
+    }
+
+    #[test]
+    fn dedupe_vrl_error_passes_event() {
+        let mut transform =
+            make_transform(5, FieldMatchConfig::Vrl("to_int!(.matched)".into()), false);
+
+        let mut event = Event::from("message");
+        event.as_mut_log().insert("matched", "not a number");
+
+        // Events are never discarded when their identity can't be computed.
+        assert!(transform.transform_one(event.clone()).is_some());
+        assert!(transform.transform_one(event).is_some());
+    }
+
+    #[tokio::test]
+    async fn dedupe_persisted_cache() {
+        let data_dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let path = data_dir.join(CACHE_FILENAME);
+        let config = DedupeConfig {
+            cache: CacheConfig {
+                num_events: 2,
+                persist: true,
+                persist_interval_secs: 1,
+            },
+            fields: Some(FieldMatchConfig::MatchFields(vec!["matched".into()])),
+            hash: false,
+            data_dir: None,
+        };
+
+        let event = |value: &str| {
+            let mut event = Event::from("message");
+            event.as_mut_log().insert("matched", value.to_owned());
+            event
+        };
+
+        let mut transform = Dedupe::new(config.clone(), &Default::default()).unwrap();
+        transform
+            .restore(path.clone(), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(transform.transform_one(event("a")).is_some());
+        assert!(transform.transform_one(event("b")).is_some());
+        assert!(transform.transform_one(event("c")).is_some());
+        transform.persist().await;
+
+        let mut transform = Dedupe::new(config, &Default::default()).unwrap();
+        transform
+            .restore(path, Duration::from_secs(1))
+            .await
+            .unwrap();
+        // The least recently seen event was evicted before the cache was persisted.
+        assert!(transform.transform_one(event("c")).is_none());
+        assert!(transform.transform_one(event("b")).is_none());
+        assert!(transform.transform_one(event("a")).is_some());
+    }
 }
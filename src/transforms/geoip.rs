@@ -1,4 +1,11 @@
-use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::IpAddr,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -8,41 +15,42 @@ use crate::{
         TransformDescription,
     },
     event::Event,
-    internal_events::{GeoipIpAddressParseError, ParserMissingFieldError},
+    internal_events::{
+        GeoipDatabaseReloadError, GeoipDatabaseReloaded, GeoipIpAddressParseError,
+        ParserMissingFieldError,
+    },
     schema,
     transforms::{FunctionTransform, OutputBuffer, Transform},
     Result,
 };
 
+/// How often the database files are checked for updates, at most.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct GeoipConfig {
     pub source: String,
-    pub database: String,
+    /// A database looked up in addition to `databases`, into `target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub databases: Vec<GeoipDatabaseConfig>,
     #[serde(default = "default_geoip_target_field")]
     pub target: String,
 }
 
-#[derive(Derivative)]
-#[derivative(Debug)]
-pub struct Geoip {
-    #[derivative(Debug = "ignore")]
-    pub dbreader: maxminddb::Reader<Vec<u8>>,
-    pub database: String,
-    pub source: String,
-    pub target: String,
-}
-
-impl Clone for Geoip {
-    fn clone(&self) -> Self {
-        Self {
-            dbreader: maxminddb::Reader::open_readfile(self.database.clone())
-                .expect("Panicked while cloning GeoIP lookup database. Did you move the GeoIP database on disk during runtime?"),
-            database: self.database.clone(),
-            source: self.source.clone(),
-            target: self.target.clone()
-        }
-    }
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GeoipDatabaseConfig {
+    pub path: String,
+    /// The field the results are inserted into, the `target` of the transform if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// The fields of the results to insert, each into its own field of the event rather than
+    /// into the target.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
 }
 
 fn default_geoip_target_field() -> String {
@@ -56,7 +64,8 @@ inventory::submit! {
 impl GenerateConfig for GeoipConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
-            database: "/path/to/GeoLite2-City.mmdb".to_string(),
+            database: Some("/path/to/GeoLite2-City.mmdb".to_string()),
+            databases: Vec::new(),
             source: "ip address".to_owned(),
             target: default_geoip_target_field(),
         })
@@ -68,11 +77,7 @@ impl GenerateConfig for GeoipConfig {
 #[typetag::serde(name = "geoip")]
 impl TransformConfig for GeoipConfig {
     async fn build(&self, _context: &TransformContext) -> Result<Transform> {
-        Ok(Transform::function(Geoip::new(
-            self.database.clone(),
-            self.source.clone(),
-            self.target.clone(),
-        )?))
+        Ok(Transform::function(Geoip::new(self)?))
     }
 
     fn input(&self) -> Input {
@@ -89,24 +94,197 @@ impl TransformConfig for GeoipConfig {
 }
 
 // MaxMind GeoIP database files have a type field we can use to recognize specific
-// products. If we encounter one of these two types, we look for ASN/ISP information;
-// otherwise we expect to be working with a City database.
+// products. If we encounter one of these types, we look for ASN/ISP, connection type or
+// anonymizer information; otherwise we expect to be working with a City database.
 const ASN_DATABASE_TYPE: &str = "GeoLite2-ASN";
 const ISP_DATABASE_TYPE: &str = "GeoIP2-ISP";
+const CONNECTION_TYPE_DATABASE_TYPE: &str = "GeoIP2-Connection-Type";
+const ANONYMOUS_IP_DATABASE_TYPE: &str = "GeoIP2-Anonymous-IP";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DatabaseKind {
+    Isp,
+    ConnectionType,
+    AnonymousIp,
+    City,
+}
 
-impl Geoip {
-    pub fn new(database: String, source: String, target: String) -> crate::Result<Self> {
-        Ok(Geoip {
-            dbreader: maxminddb::Reader::open_readfile(database.clone())?,
-            database,
-            source,
+impl DatabaseKind {
+    fn of(reader: &maxminddb::Reader<Vec<u8>>) -> Self {
+        match reader.metadata.database_type.as_str() {
+            ASN_DATABASE_TYPE | ISP_DATABASE_TYPE => Self::Isp,
+            CONNECTION_TYPE_DATABASE_TYPE => Self::ConnectionType,
+            ANONYMOUS_IP_DATABASE_TYPE => Self::AnonymousIp,
+            _ => Self::City,
+        }
+    }
+}
+
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+struct Database {
+    path: String,
+    #[derivative(Debug = "ignore")]
+    reader: Arc<maxminddb::Reader<Vec<u8>>>,
+    kind: DatabaseKind,
+    target: String,
+    fields: BTreeMap<String, String>,
+    modified: Option<SystemTime>,
+    checked_at: Instant,
+}
+
+impl Database {
+    fn open(path: String, target: String, fields: BTreeMap<String, String>) -> Result<Self> {
+        let modified = modified_time(&path);
+        let reader = maxminddb::Reader::open_readfile(&path)?;
+        Ok(Self {
+            path,
+            kind: DatabaseKind::of(&reader),
+            reader: Arc::new(reader),
             target,
+            fields,
+            modified,
+            checked_at: Instant::now(),
         })
     }
 
-    fn has_isp_db(&self) -> bool {
-        self.dbreader.metadata.database_type == ASN_DATABASE_TYPE
-            || self.dbreader.metadata.database_type == ISP_DATABASE_TYPE
+    /// Opens the database file again if it was updated. While it can't be opened, as when it is
+    /// only partly replaced yet, the previous database remains in use.
+    fn refresh(&mut self) {
+        if self.checked_at.elapsed() < CHECK_INTERVAL {
+            return;
+        }
+        self.checked_at = Instant::now();
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return;
+        }
+        match maxminddb::Reader::open_readfile(&self.path) {
+            Ok(reader) => {
+                self.kind = DatabaseKind::of(&reader);
+                self.reader = Arc::new(reader);
+                self.modified = modified;
+                emit!(&GeoipDatabaseReloaded { path: &self.path });
+            }
+            Err(error) => emit!(&GeoipDatabaseReloadError {
+                path: &self.path,
+                error,
+            }),
+        }
+    }
+
+    fn lookup(&self, ip: Option<IpAddr>) -> serde_json::Result<serde_json::Value> {
+        match self.kind {
+            DatabaseKind::Isp => {
+                let mut isp: Isp = Default::default();
+                if let Some(data) =
+                    ip.and_then(|ip| self.reader.lookup::<maxminddb::geoip2::Isp>(ip).ok())
+                {
+                    if let Some(as_number) = data.autonomous_system_number {
+                        isp.autonomous_system_number = as_number as i64;
+                    }
+                    if let Some(as_organization) = data.autonomous_system_organization {
+                        isp.autonomous_system_organization = as_organization;
+                    }
+                    if let Some(isp_name) = data.isp {
+                        isp.isp = isp_name;
+                    }
+                    if let Some(organization) = data.organization {
+                        isp.organization = organization;
+                    }
+                }
+                serde_json::to_value(isp)
+            }
+            DatabaseKind::ConnectionType => {
+                let connection_type = ip
+                    .and_then(|ip| self.reader.lookup::<ConnectionType>(ip).ok())
+                    .unwrap_or_default();
+                serde_json::to_value(connection_type)
+            }
+            DatabaseKind::AnonymousIp => {
+                let anonymous_ip = ip
+                    .and_then(|ip| self.reader.lookup::<AnonymousIp>(ip).ok())
+                    .unwrap_or_default();
+                serde_json::to_value(anonymous_ip)
+            }
+            DatabaseKind::City => {
+                let mut city: City = Default::default();
+                if let Some(data) =
+                    ip.and_then(|ip| self.reader.lookup::<maxminddb::geoip2::City>(ip).ok())
+                {
+                    if let Some(city_names) = data.city.and_then(|c| c.names) {
+                        if let Some(city_name) = city_names.get("en") {
+                            city.city_name = city_name;
+                        }
+                    }
+
+                    if let Some(continent_code) = data.continent.and_then(|c| c.code) {
+                        city.continent_code = continent_code;
+                    }
+
+                    if let Some(country_code) = data.country.and_then(|cy| cy.iso_code) {
+                        city.country_code = country_code;
+                    };
+
+                    if let Some(time_zone) = data.location.clone().and_then(|loc| loc.time_zone) {
+                        city.timezone = time_zone;
+                    }
+
+                    if let Some(latitude) = data.location.clone().and_then(|loc| loc.latitude) {
+                        city.latitude = latitude.to_string();
+                    }
+
+                    if let Some(longitude) = data.location.clone().and_then(|loc| loc.longitude) {
+                        city.longitude = longitude.to_string();
+                    }
+
+                    if let Some(postal_code) = data.postal.clone().and_then(|p| p.code) {
+                        city.postal_code = postal_code;
+                    }
+                }
+                serde_json::to_value(city)
+            }
+        }
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[derive(Clone, Debug)]
+pub struct Geoip {
+    databases: Vec<Database>,
+    source: String,
+}
+
+impl Geoip {
+    pub fn new(config: &GeoipConfig) -> crate::Result<Self> {
+        let databases = config
+            .database
+            .iter()
+            .map(|path| Database::open(path.clone(), config.target.clone(), BTreeMap::new()))
+            .chain(config.databases.iter().map(|database| {
+                Database::open(
+                    database.path.clone(),
+                    database
+                        .target
+                        .clone()
+                        .unwrap_or_else(|| config.target.clone()),
+                    database.fields.clone(),
+                )
+            }))
+            .collect::<crate::Result<Vec<_>>>()?;
+        if databases.is_empty() {
+            return Err("At least one of `database` or `databases` must be set.".into());
+        }
+        Ok(Geoip {
+            databases,
+            source: config.source.clone(),
+        })
     }
 }
 
@@ -118,6 +296,23 @@ struct Isp<'a> {
     organization: &'a str,
 }
 
+#[derive(Default, Deserialize, Serialize)]
+struct ConnectionType<'a> {
+    #[serde(borrow, default)]
+    connection_type: &'a str,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+struct AnonymousIp {
+    is_anonymous: bool,
+    is_anonymous_vpn: bool,
+    is_hosting_provider: bool,
+    is_public_proxy: bool,
+    is_residential_proxy: bool,
+    is_tor_exit_node: bool,
+}
+
 #[derive(Default, Serialize)]
 struct City<'a> {
     city_name: &'a str,
@@ -131,85 +326,57 @@ struct City<'a> {
 
 impl FunctionTransform for Geoip {
     fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
-        let mut isp: Isp = Default::default();
-        let mut city: City = Default::default();
-        let target_field = self.target.clone();
+        for database in &mut self.databases {
+            database.refresh();
+        }
+
         let ipaddress = event
             .as_log()
             .get(self.source.as_str())
             .map(|s| s.to_string_lossy());
-        if let Some(ipaddress) = &ipaddress {
-            match FromStr::from_str(ipaddress) {
-                Ok(ip) => {
-                    if self.has_isp_db() {
-                        if let Ok(data) = self.dbreader.lookup::<maxminddb::geoip2::Isp>(ip) {
-                            if let Some(as_number) = data.autonomous_system_number {
-                                isp.autonomous_system_number = as_number as i64;
-                            }
-                            if let Some(as_organization) = data.autonomous_system_organization {
-                                isp.autonomous_system_organization = as_organization;
-                            }
-                            if let Some(isp_name) = data.isp {
-                                isp.isp = isp_name;
-                            }
-                            if let Some(organization) = data.organization {
-                                isp.organization = organization;
-                            }
-                        }
-                    } else if let Ok(data) = self.dbreader.lookup::<maxminddb::geoip2::City>(ip) {
-                        if let Some(city_names) = data.city.and_then(|c| c.names) {
-                            if let Some(city_name) = city_names.get("en") {
-                                city.city_name = city_name;
-                            }
-                        }
-
-                        if let Some(continent_code) = data.continent.and_then(|c| c.code) {
-                            city.continent_code = continent_code;
-                        }
-
-                        if let Some(country_code) = data.country.and_then(|cy| cy.iso_code) {
-                            city.country_code = country_code;
-                        };
-
-                        if let Some(time_zone) = data.location.clone().and_then(|loc| loc.time_zone)
-                        {
-                            city.timezone = time_zone;
-                        }
-
-                        if let Some(latitude) = data.location.clone().and_then(|loc| loc.latitude) {
-                            city.latitude = latitude.to_string();
-                        }
-
-                        if let Some(longitude) = data.location.clone().and_then(|loc| loc.longitude)
-                        {
-                            city.longitude = longitude.to_string();
-                        }
-
-                        if let Some(postal_code) = data.postal.clone().and_then(|p| p.code) {
-                            city.postal_code = postal_code;
-                        }
-                    }
-                }
+        let ip = match &ipaddress {
+            Some(ipaddress) => match FromStr::from_str(ipaddress) {
+                Ok(ip) => Some(ip),
                 Err(error) => {
                     emit!(&GeoipIpAddressParseError {
                         error,
                         address: ipaddress
                     });
+                    None
                 }
+            },
+            None => {
+                emit!(&ParserMissingFieldError {
+                    field: &self.source
+                });
+                None
             }
-        } else {
-            emit!(&ParserMissingFieldError {
-                field: &self.source
-            });
         };
 
-        let json_value = if self.has_isp_db() {
-            serde_json::to_value(isp)
-        } else {
-            serde_json::to_value(city)
-        };
-        if let Ok(json_value) = json_value {
-            event.as_mut_log().insert(target_field.as_str(), json_value);
+        // The results of the databases with the same target are merged.
+        let mut targets = BTreeMap::<&str, serde_json::Map<String, serde_json::Value>>::new();
+        for database in &self.databases {
+            let results = match database.lookup(ip) {
+                Ok(serde_json::Value::Object(results)) => results,
+                _ => continue,
+            };
+            if database.fields.is_empty() {
+                targets
+                    .entry(database.target.as_str())
+                    .or_default()
+                    .extend(results);
+            } else {
+                for (field, path) in &database.fields {
+                    if let Some(value) = results.get(field) {
+                        event.as_mut_log().insert(path.as_str(), value.clone());
+                    }
+                }
+            }
+        }
+        for (target, results) in targets {
+            event
+                .as_mut_log()
+                .insert(target, serde_json::Value::Object(results));
         }
 
         output.push(event);
@@ -391,20 +558,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn geoip_lookup_multiple_databases() {
+        let mut augment = Geoip::new(&GeoipConfig {
+            source: "remote_addr".to_string(),
+            database: Some("tests/data/GeoIP2-City-Test.mmdb".to_string()),
+            databases: vec![
+                GeoipDatabaseConfig {
+                    path: "tests/data/GeoIP2-ISP-Test.mmdb".to_string(),
+                    target: None,
+                    fields: BTreeMap::new(),
+                },
+                GeoipDatabaseConfig {
+                    path: "tests/data/GeoLite2-ASN-Test.mmdb".to_string(),
+                    target: Some("network".to_string()),
+                    fields: BTreeMap::new(),
+                },
+            ],
+            target: "geo".to_string(),
+        })
+        .unwrap();
+        let new_event = parse_with(r#"{"remote_addr": "2.125.160.216"}"#, &mut augment);
+
+        let log = new_event.as_log();
+        assert_eq!(log["geo.city_name"], "Boxford".into());
+        assert!(log.get("geo.autonomous_system_number").is_some());
+        assert!(log.get("network.autonomous_system_number").is_some());
+    }
+
+    #[test]
+    fn geoip_lookup_mapped_fields() {
+        let mut augment = Geoip::new(&GeoipConfig {
+            source: "remote_addr".to_string(),
+            database: None,
+            databases: vec![GeoipDatabaseConfig {
+                path: "tests/data/GeoIP2-ISP-Test.mmdb".to_string(),
+                target: None,
+                fields: vec![
+                    ("autonomous_system_number".to_string(), "asn".to_string()),
+                    ("isp".to_string(), "network.isp".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+            }],
+            target: "geo".to_string(),
+        })
+        .unwrap();
+        let new_event = parse_with(r#"{"remote_addr": "208.192.1.2"}"#, &mut augment);
+
+        let log = new_event.as_log();
+        assert_eq!(log["asn"], 701.into());
+        assert_eq!(log["network.isp"], "Verizon Business".into());
+        assert!(log.get("geo").is_none());
+        assert!(log.get("organization").is_none());
+    }
+
+    #[test]
+    fn geoip_reloads_updated_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("database.mmdb");
+        std::fs::copy("tests/data/GeoIP2-City-Test.mmdb", &path).unwrap();
+        let mut augment = Geoip::new(&GeoipConfig {
+            source: "remote_addr".to_string(),
+            database: Some(path.to_str().unwrap().to_string()),
+            databases: Vec::new(),
+            target: "geo".to_string(),
+        })
+        .unwrap();
+
+        std::fs::copy("tests/data/GeoIP2-ISP-Test.mmdb", &path).unwrap();
+        let new_event = parse_with(r#"{"remote_addr": "208.192.1.2"}"#, &mut augment);
+        assert!(new_event.as_log().get("geo.isp").is_none());
+
+        let database = &mut augment.databases[0];
+        database.checked_at -= CHECK_INTERVAL;
+        // Detect the update even though the modification times may be too coarse.
+        database.modified = None;
+        let new_event = parse_with(r#"{"remote_addr": "208.192.1.2"}"#, &mut augment);
+        assert_eq!(new_event.as_log()["geo.isp"], "Verizon Business".into());
+    }
+
+    #[test]
+    fn geoip_requires_a_database() {
+        assert!(Geoip::new(&GeoipConfig {
+            source: "remote_addr".to_string(),
+            database: None,
+            databases: Vec::new(),
+            target: "geo".to_string(),
+        })
+        .is_err());
+    }
+
     fn parse_one(text: &str, database: &str) -> Event {
+        let mut augment = Geoip::new(&GeoipConfig {
+            source: "remote_addr".to_string(),
+            database: Some(database.to_string()),
+            databases: Vec::new(),
+            target: "geo".to_string(),
+        })
+        .unwrap();
+        parse_with(text, &mut augment)
+    }
+
+    fn parse_with(text: &str, augment: &mut Geoip) -> Event {
         let mut parser = JsonParser::from(JsonParserConfig::default());
         let event = Event::from(text);
         let metadata = event.metadata().clone();
         let event = transform_one(&mut parser, event).unwrap();
         assert_eq!(event.metadata(), &metadata);
 
-        let mut augment = Geoip::new(
-            database.to_string(),
-            "remote_addr".to_string(),
-            "geo".to_string(),
-        )
-        .unwrap();
-        let result = transform_one(&mut augment, event).unwrap();
+        let result = transform_one(augment, event).unwrap();
         assert_eq!(result.metadata(), &metadata);
         result
     }
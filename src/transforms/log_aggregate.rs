@@ -0,0 +1,389 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    time::Duration,
+};
+
+use async_stream::stream;
+use chrono::{TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, EventMetadata, LogEvent, Value},
+    internal_events::TemplateRenderingError,
+    schema,
+    template::Template,
+    transforms::{TaskTransform, Transform},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LogAggregateConfig {
+    pub group_by: Option<Template>,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    /// The interval between the starts of the windows, which defaults to `window_secs` for
+    /// tumbling windows.
+    pub slide_secs: Option<u64>,
+    /// The numeric field to compute the sum, the minimum and the maximum of.
+    pub field: Option<String>,
+    #[serde(default = "default_sample_size")]
+    pub sample_size: usize,
+}
+
+const fn default_window_secs() -> u64 {
+    60
+}
+
+const fn default_sample_size() -> usize {
+    1
+}
+
+inventory::submit! {
+    TransformDescription::new::<LogAggregateConfig>("log_aggregate")
+}
+
+impl GenerateConfig for LogAggregateConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            group_by: Some(Template::try_from("{{ message }}").unwrap()),
+            window_secs: default_window_secs(),
+            slide_secs: None,
+            field: None,
+            sample_size: default_sample_size(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "log_aggregate")]
+impl TransformConfig for LogAggregateConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        LogAggregate::new(self).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "log_aggregate"
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ConfigError {
+    #[snafu(display("`window_secs` must be positive"))]
+    ZeroWindow,
+    #[snafu(display("`slide_secs` must be positive and divide `window_secs`"))]
+    InvalidSlide,
+}
+
+/// The events of one key during one window.
+#[derive(Debug, Default)]
+struct Rollup {
+    count: u64,
+    /// The sum, the minimum and the maximum of the numeric values of `field`.
+    stats: Option<(f64, f64, f64)>,
+    samples: Vec<Value>,
+    metadata: EventMetadata,
+}
+
+impl Rollup {
+    fn into_event(
+        self,
+        key: Option<String>,
+        window_start: i64,
+        window_end: i64,
+        has_field: bool,
+    ) -> Event {
+        let mut log = LogEvent::new_with_metadata(self.metadata);
+        if let Some(key) = key {
+            log.insert("key", key);
+        }
+        log.insert("count", self.count);
+        if has_field {
+            let (sum, min, max) = self.stats.map_or((None, None, None), |(sum, min, max)| {
+                (Some(sum), Some(min), Some(max))
+            });
+            log.insert("sum", sum);
+            log.insert("min", min);
+            log.insert("max", max);
+        }
+        if !self.samples.is_empty() {
+            log.insert("samples", self.samples);
+        }
+        log.insert("window_start", Utc.timestamp_millis(window_start));
+        log.insert("window_end", Utc.timestamp_millis(window_end));
+        log.insert(
+            log_schema().timestamp_key(),
+            Utc.timestamp_millis(window_end),
+        );
+        Event::Log(log)
+    }
+}
+
+pub struct LogAggregate {
+    group_by: Option<Template>,
+    window_ms: i64,
+    slide_ms: i64,
+    field: Option<String>,
+    sample_size: usize,
+    /// The rollups of each key, by start of their window in milliseconds since the epoch.
+    windows: BTreeMap<i64, HashMap<Option<String>, Rollup>>,
+}
+
+impl LogAggregate {
+    pub fn new(config: &LogAggregateConfig) -> crate::Result<Self> {
+        let window_secs = config.window_secs;
+        let slide_secs = config.slide_secs.unwrap_or(window_secs);
+        if window_secs == 0 {
+            return Err(Box::new(ConfigError::ZeroWindow));
+        }
+        if slide_secs == 0 || window_secs % slide_secs != 0 {
+            return Err(Box::new(ConfigError::InvalidSlide));
+        }
+
+        Ok(Self {
+            group_by: config.group_by.clone(),
+            window_ms: window_secs as i64 * 1000,
+            slide_ms: slide_secs as i64 * 1000,
+            field: config.field.clone(),
+            sample_size: config.sample_size,
+            windows: BTreeMap::new(),
+        })
+    }
+
+    /// Adds the event to the rollup of its key in each of the windows `now` falls into.
+    fn record(&mut self, event: Event, now: i64) {
+        let (log, metadata) = event.into_log().into_parts();
+        let log = LogEvent::from(log);
+
+        let key = self.group_by.as_ref().and_then(|template| {
+            template
+                .render_string(&log)
+                .map_err(|error| {
+                    emit!(&TemplateRenderingError {
+                        error,
+                        field: Some("group_by"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+
+        let value = self
+            .field
+            .as_ref()
+            .and_then(|field| match log.get(field.as_str()) {
+                Some(Value::Integer(value)) => Some(*value as f64),
+                Some(Value::Float(value)) => Some(value.into_inner()),
+                Some(value) => value.to_string_lossy().parse().ok(),
+                None => None,
+            });
+
+        let latest_start = now - now.rem_euclid(self.slide_ms);
+        let mut start = latest_start;
+        while start > now - self.window_ms {
+            let rollup = self
+                .windows
+                .entry(start)
+                .or_default()
+                .entry(key.clone())
+                .or_default();
+
+            rollup.count += 1;
+            if let Some(value) = value {
+                rollup.stats = Some(match rollup.stats {
+                    Some((sum, min, max)) => (sum + value, min.min(value), max.max(value)),
+                    None => (value, value, value),
+                });
+            }
+            if rollup.samples.len() < self.sample_size {
+                rollup.samples.push(Value::from(log.as_map().clone()));
+            }
+            // The rollups are only delivered once all the events they hold are.
+            rollup.metadata.merge(metadata.clone());
+
+            start -= self.slide_ms;
+        }
+    }
+
+    /// Emits the rollups of the windows that ended by `now`, or of all of them if `now` is
+    /// `None`.
+    fn flush_into(&mut self, output: &mut Vec<Event>, now: Option<i64>) {
+        let expired = match now {
+            Some(now) => {
+                let remaining = self.windows.split_off(&(now - self.window_ms + 1));
+                std::mem::replace(&mut self.windows, remaining)
+            }
+            None => std::mem::take(&mut self.windows),
+        };
+
+        for (start, rollups) in expired {
+            for (key, rollup) in rollups {
+                output.push(rollup.into_event(
+                    key,
+                    start,
+                    start + self.window_ms,
+                    self.field.is_some(),
+                ));
+            }
+        }
+    }
+}
+
+impl TaskTransform<Event> for LogAggregate {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(Duration::from_secs(1));
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(&mut output, Some(Utc::now().timestamp_millis()));
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_into(&mut output, None);
+                                done = true;
+                            }
+                            Some(event) => self.record(event, Utc::now().timestamp_millis()),
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LogAggregateConfig>();
+    }
+
+    fn make_transform(window_secs: u64, slide_secs: Option<u64>) -> LogAggregate {
+        LogAggregate::new(&LogAggregateConfig {
+            group_by: Some(Template::try_from("{{ message }}").unwrap()),
+            window_secs,
+            slide_secs,
+            field: Some("duration".to_owned()),
+            sample_size: 2,
+        })
+        .unwrap()
+    }
+
+    fn event(message: &str, duration: impl Into<Value>) -> Event {
+        let mut event = Event::from(message);
+        event.as_mut_log().insert("duration", duration.into());
+        event
+    }
+
+    fn flush(transform: &mut LogAggregate, now: Option<i64>) -> Vec<Event> {
+        let mut output = Vec::new();
+        transform.flush_into(&mut output, now);
+        output.sort_by_key(|event| {
+            (
+                event.as_log()["window_start"].to_string_lossy(),
+                event.as_log()["key"].to_string_lossy(),
+            )
+        });
+        output
+    }
+
+    #[test]
+    fn tumbling_windows() {
+        let mut transform = make_transform(10, None);
+
+        transform.record(event("timeout", 3), 10_000);
+        transform.record(event("timeout", "1.5"), 12_000);
+        transform.record(event("timeout", "slow"), 19_999);
+        transform.record(event("refused", 1), 15_000);
+        transform.record(event("timeout", 4), 20_000);
+
+        assert!(flush(&mut transform, Some(19_999)).is_empty());
+
+        let output = flush(&mut transform, Some(20_000));
+        assert_eq!(output.len(), 2);
+
+        let refused = output[0].as_log();
+        assert_eq!(refused["key"], "refused".into());
+        assert_eq!(refused["count"], 1.into());
+        assert_eq!(refused["sum"], 1.0.into());
+
+        let timeout = output[1].as_log();
+        assert_eq!(timeout["key"], "timeout".into());
+        assert_eq!(timeout["count"], 3.into());
+        assert_eq!(timeout["sum"], 4.5.into());
+        assert_eq!(timeout["min"], 1.5.into());
+        assert_eq!(timeout["max"], 3.0.into());
+        assert_eq!(timeout["window_start"], Utc.timestamp_millis(10_000).into());
+        assert_eq!(timeout["window_end"], Utc.timestamp_millis(20_000).into());
+        assert_eq!(timeout["samples"].as_array().unwrap().len(), 2);
+
+        let output = flush(&mut transform, None);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["count"], 1.into());
+    }
+
+    #[test]
+    fn sliding_windows() {
+        let mut transform = make_transform(10, Some(5));
+
+        transform.record(event("timeout", 1), 12_000);
+        transform.record(event("timeout", 2), 17_000);
+
+        // The first event falls into the windows starting at 5s and 10s.
+        let output = flush(&mut transform, Some(15_000));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["count"], 1.into());
+
+        let output = flush(&mut transform, Some(20_000));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["count"], 2.into());
+        assert_eq!(output[0].as_log()["sum"], 3.0.into());
+
+        let output = flush(&mut transform, Some(25_000));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].as_log()["count"], 1.into());
+    }
+
+    #[test]
+    fn invalid_slide() {
+        assert!(LogAggregate::new(&LogAggregateConfig {
+            group_by: None,
+            window_secs: 10,
+            slide_secs: Some(3),
+            field: None,
+            sample_size: 0,
+        })
+        .is_err());
+    }
+}
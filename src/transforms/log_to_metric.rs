@@ -2,6 +2,9 @@ use std::{collections::BTreeMap, convert::TryFrom, num::ParseFloatError};
 
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use vector_common::TimeZone;
+use vrl::{diagnostic::Formatter, Program, Runtime};
 
 use crate::{
     config::{
@@ -9,12 +12,14 @@ use crate::{
         TransformDescription,
     },
     event::{
-        metric::{Metric, MetricKind, MetricValue, StatisticKind},
-        Event, Value,
+        metric::{
+            Bucket, Metric, MetricKind, MetricSketch, MetricValue, Quantile, Sample, StatisticKind,
+        },
+        Event, LogEvent, Value, VrlTarget,
     },
     internal_events::{
         LogToMetricFieldNullError, LogToMetricParseFloatError, LogToMetricTemplateParseError,
-        ParserMissingFieldError,
+        LogToMetricValueError, ParserMissingFieldError,
     },
     schema,
     template::{Template, TemplateParseError, TemplateRenderingError},
@@ -24,7 +29,18 @@ use crate::{
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct LogToMetricConfig {
+    #[serde(default)]
     pub metrics: Vec<MetricConfig>,
+    /// The tags of all the metrics, overridden by the tags of the same name of each metric.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<IndexMap<String, String>>,
+    /// Converts the log events produced by `metric_to_log` back into the metrics they were
+    /// produced from, instead of computing `metrics`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_metrics: bool,
+    /// The tag the host field is moved back into, as set for `metric_to_log`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_tag: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -57,7 +73,11 @@ pub struct SetConfig {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct HistogramConfig {
-    field: String,
+    field: Option<String>,
+    /// A VRL expression computing the samples, or the buckets, of the histogram.
+    value: Option<String>,
+    /// The upper limits of the buckets to aggregate the samples into.
+    buckets: Option<Vec<f64>>,
     name: Option<String>,
     namespace: Option<String>,
     tags: Option<IndexMap<String, String>>,
@@ -65,7 +85,9 @@ pub struct HistogramConfig {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SummaryConfig {
-    field: String,
+    field: Option<String>,
+    /// A VRL expression computing the samples, or the quantiles, of the summary.
+    value: Option<String>,
     name: Option<String>,
     namespace: Option<String>,
     tags: Option<IndexMap<String, String>>,
@@ -82,13 +104,31 @@ pub enum MetricConfig {
 }
 
 impl MetricConfig {
-    fn field(&self) -> &str {
+    fn field(&self) -> Option<&str> {
+        match self {
+            MetricConfig::Counter(CounterConfig { field, .. }) => Some(field),
+            MetricConfig::Histogram(HistogramConfig { field, .. }) => field.as_deref(),
+            MetricConfig::Gauge(GaugeConfig { field, .. }) => Some(field),
+            MetricConfig::Set(SetConfig { field, .. }) => Some(field),
+            MetricConfig::Summary(SummaryConfig { field, .. }) => field.as_deref(),
+        }
+    }
+
+    fn value(&self) -> Option<&str> {
         match self {
-            MetricConfig::Counter(CounterConfig { field, .. }) => field,
-            MetricConfig::Histogram(HistogramConfig { field, .. }) => field,
-            MetricConfig::Gauge(GaugeConfig { field, .. }) => field,
-            MetricConfig::Set(SetConfig { field, .. }) => field,
-            MetricConfig::Summary(SummaryConfig { field, .. }) => field,
+            MetricConfig::Histogram(HistogramConfig { value, .. })
+            | MetricConfig::Summary(SummaryConfig { value, .. }) => value.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        match self {
+            MetricConfig::Counter(CounterConfig { name, .. })
+            | MetricConfig::Histogram(HistogramConfig { name, .. })
+            | MetricConfig::Gauge(GaugeConfig { name, .. })
+            | MetricConfig::Set(SetConfig { name, .. })
+            | MetricConfig::Summary(SummaryConfig { name, .. }) => name.as_deref(),
         }
     }
 }
@@ -101,9 +141,24 @@ const fn default_kind() -> MetricKind {
     MetricKind::Incremental
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display(
+        "metric #{} must have either a `field` or a `value`, but not both",
+        index
+    ))]
+    FieldOrValue { index: usize },
+    #[snafu(display("metric #{} must have a `name`, as it has no `field`", index))]
+    MissingName { index: usize },
+    #[snafu(display("invalid `value` of metric #{}: {}", index, diagnostics))]
+    InvalidValue { index: usize, diagnostics: String },
+}
+
 #[derive(Debug, Clone)]
 pub struct LogToMetric {
     config: LogToMetricConfig,
+    /// The compiled `value` of each metric, if it has one.
+    values: Vec<Option<Program>>,
 }
 
 inventory::submit! {
@@ -121,6 +176,9 @@ impl GenerateConfig for LogToMetricConfig {
                 kind: MetricKind::Incremental,
                 tags: None,
             })],
+            tags: None,
+            all_metrics: false,
+            host_tag: None,
         })
         .unwrap()
     }
@@ -129,8 +187,11 @@ impl GenerateConfig for LogToMetricConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(LogToMetric::new(self.clone())))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(LogToMetric::new(
+            self.clone(),
+            &context.enrichment_tables,
+        )?))
     }
 
     fn input(&self) -> Input {
@@ -151,8 +212,46 @@ impl TransformConfig for LogToMetricConfig {
 }
 
 impl LogToMetric {
-    pub const fn new(config: LogToMetricConfig) -> Self {
-        LogToMetric { config }
+    pub fn new(
+        config: LogToMetricConfig,
+        enrichment_tables: &enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        let functions = vrl_stdlib::all()
+            .into_iter()
+            .chain(enrichment::vrl_functions().into_iter())
+            .chain(vector_vrl_functions::vrl_functions())
+            .collect::<Vec<_>>();
+
+        let values = config
+            .metrics
+            .iter()
+            .enumerate()
+            .map(|(index, metric)| {
+                if metric.field().is_some() == metric.value().is_some() {
+                    return Err(BuildError::FieldOrValue { index });
+                }
+                if metric.field().is_none() && metric.name().is_none() {
+                    return Err(BuildError::MissingName { index });
+                }
+
+                metric
+                    .value()
+                    .map(|source| {
+                        let mut state = vrl::state::Compiler::new();
+                        state.set_external_context(enrichment_tables.clone());
+
+                        vrl::compile_with_state(source, &functions, &mut state).map_err(
+                            |diagnostics| BuildError::InvalidValue {
+                                index,
+                                diagnostics: Formatter::new(source, diagnostics).to_string(),
+                            },
+                        )
+                    })
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(LogToMetric { config, values })
     }
 }
 
@@ -169,6 +268,10 @@ enum TransformError {
         field: String,
         error: ParseFloatError,
     },
+    ValueError {
+        name: String,
+        error: String,
+    },
 }
 
 fn render_template(s: &str, event: &Event) -> Result<String, TransformError> {
@@ -178,50 +281,33 @@ fn render_template(s: &str, event: &Event) -> Result<String, TransformError> {
         .map_err(TransformError::TemplateRenderingError)
 }
 
+/// Renders the tags shared by all the metrics, then the tags of the metric, which override them.
 fn render_tags(
+    shared: &Option<IndexMap<String, String>>,
     tags: &Option<IndexMap<String, String>>,
     event: &Event,
 ) -> Result<Option<BTreeMap<String, String>>, TransformError> {
-    Ok(match tags {
-        None => None,
-        Some(tags) => {
-            let mut map = BTreeMap::new();
-            for (name, value) in tags {
-                match render_template(value, event) {
-                    Ok(tag) => {
-                        map.insert(name.to_string(), tag);
-                    }
-                    Err(TransformError::TemplateRenderingError(error)) => {
-                        emit!(&crate::internal_events::TemplateRenderingError {
-                            error,
-                            drop_event: false,
-                            field: Some(name.as_str()),
-                        });
-                    }
-                    Err(other) => return Err(other),
-                }
+    let mut map = BTreeMap::new();
+    for (name, value) in shared.iter().chain(tags.iter()).flatten() {
+        match render_template(value, event) {
+            Ok(tag) => {
+                map.insert(name.to_string(), tag);
             }
-            if !map.is_empty() {
-                Some(map)
-            } else {
-                None
+            Err(TransformError::TemplateRenderingError(error)) => {
+                emit!(&crate::internal_events::TemplateRenderingError {
+                    error,
+                    drop_event: false,
+                    field: Some(name.as_str()),
+                });
             }
+            Err(other) => return Err(other),
         }
-    })
+    }
+    Ok(if !map.is_empty() { Some(map) } else { None })
 }
 
-fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformError> {
-    let log = event.as_log();
-
-    let timestamp = log
-        .get(log_schema().timestamp_key())
-        .and_then(Value::as_timestamp)
-        .cloned();
-    let metadata = event.metadata().clone();
-
-    let field = config.field();
-
-    let value = match log.get(field) {
+fn field_value<'a>(log: &'a LogEvent, field: &str) -> Result<&'a Value, TransformError> {
+    match log.get(field) {
         None => Err(TransformError::FieldNotFound {
             field: field.to_string(),
         }),
@@ -229,17 +315,171 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             field: field.to_string(),
         }),
         Some(value) => Ok(value),
-    }?;
+    }
+}
+
+fn parse_float(value: &Value, field: &str) -> Result<f64, TransformError> {
+    value
+        .to_string_lossy()
+        .parse()
+        .map_err(|error| TransformError::ParseFloatError {
+            field: field.to_string(),
+            error,
+        })
+}
+
+/// Computes the value of a histogram or a summary, either from a single sample in a field or
+/// from the result of its VRL `value`.
+fn distribution(
+    field: Option<&str>,
+    program: Option<&Program>,
+    name: &str,
+    statistic: StatisticKind,
+    event: &Event,
+) -> Result<MetricValue, TransformError> {
+    match (field, program) {
+        (Some(field), _) => {
+            let value = parse_float(field_value(event.as_log(), field)?, field)?;
+            Ok(MetricValue::Distribution {
+                samples: vector_core::samples![value => 1],
+                statistic,
+            })
+        }
+        (None, program) => {
+            let result = match program {
+                Some(program) => {
+                    let mut target = VrlTarget::new(event.clone());
+                    Runtime::default()
+                        .resolve(&mut target, program, &TimeZone::default())
+                        .map_err(|error| error.to_string())
+                }
+                None => Err("the metric has neither a `field` nor a `value`".to_owned()),
+            };
+            result
+                .and_then(|value| vrl_distribution(value, statistic))
+                .map_err(|error| TransformError::ValueError {
+                    name: name.to_owned(),
+                    error,
+                })
+        }
+    }
+}
+
+/// Converts the result of a VRL `value`: a number is a single sample, an array holds many
+/// samples, and an object holds the `buckets` or the `quantiles` already aggregated, along with
+/// their `count` and `sum`.
+fn vrl_distribution(value: Value, statistic: StatisticKind) -> Result<MetricValue, String> {
+    match value {
+        Value::Integer(_) | Value::Float(_) => Ok(MetricValue::Distribution {
+            samples: vec![Sample {
+                value: number(Some(&value), "value")?,
+                rate: 1,
+            }],
+            statistic,
+        }),
+        Value::Array(values) => Ok(MetricValue::Distribution {
+            samples: values
+                .iter()
+                .map(|value| number(Some(value), "sample").map(|value| Sample { value, rate: 1 }))
+                .collect::<Result<_, _>>()?,
+            statistic,
+        }),
+        Value::Object(fields) => {
+            let total = count(fields.get("count"), "count")?;
+            let sum = number(fields.get("sum"), "sum")?;
+            match statistic {
+                StatisticKind::Histogram => Ok(MetricValue::AggregatedHistogram {
+                    buckets: elements(fields.get("buckets"), "buckets")?
+                        .iter()
+                        .map(|bucket| {
+                            Ok(Bucket {
+                                upper_limit: number(bucket.get("upper_limit"), "upper_limit")?,
+                                count: count(bucket.get("count"), "count")?,
+                            })
+                        })
+                        .collect::<Result<_, String>>()?,
+                    count: total,
+                    sum,
+                }),
+                StatisticKind::Summary => Ok(MetricValue::AggregatedSummary {
+                    quantiles: elements(fields.get("quantiles"), "quantiles")?
+                        .iter()
+                        .map(|quantile| {
+                            Ok(Quantile {
+                                quantile: number(quantile.get("quantile"), "quantile")?,
+                                value: number(quantile.get("value"), "value")?,
+                            })
+                        })
+                        .collect::<Result<_, String>>()?,
+                    count: total,
+                    sum,
+                }),
+            }
+        }
+        value => Err(format!(
+            "expected a number, an array or an object, got {}",
+            value.kind_str()
+        )),
+    }
+}
+
+fn number(value: Option<&Value>, field: &str) -> Result<f64, String> {
+    match value {
+        Some(Value::Integer(value)) => Ok(*value as f64),
+        Some(Value::Float(value)) => Ok(value.into_inner()),
+        Some(value) => Err(format!(
+            "expected `{}` to be a number, got {}",
+            field,
+            value.kind_str()
+        )),
+        None => Err(format!("missing `{}`", field)),
+    }
+}
+
+fn count(value: Option<&Value>, field: &str) -> Result<u32, String> {
+    match value {
+        Some(Value::Integer(value)) => u32::try_from(*value)
+            .map_err(|_| format!("expected `{}` to be a count, got {}", field, value)),
+        Some(value) => Err(format!(
+            "expected `{}` to be an integer, got {}",
+            field,
+            value.kind_str()
+        )),
+        None => Err(format!("missing `{}`", field)),
+    }
+}
+
+fn elements<'a>(value: Option<&'a Value>, field: &str) -> Result<&'a [Value], String> {
+    match value {
+        Some(Value::Array(values)) => Ok(values),
+        Some(value) => Err(format!(
+            "expected `{}` to be an array, got {}",
+            field,
+            value.kind_str()
+        )),
+        None => Err(format!("missing `{}`", field)),
+    }
+}
+
+fn to_metric(
+    config: &MetricConfig,
+    program: Option<&Program>,
+    shared_tags: &Option<IndexMap<String, String>>,
+    event: &Event,
+) -> Result<Metric, TransformError> {
+    let log = event.as_log();
+
+    let timestamp = log
+        .get(log_schema().timestamp_key())
+        .and_then(Value::as_timestamp)
+        .cloned();
+    let metadata = event.metadata().clone();
 
     match config {
         MetricConfig::Counter(counter) => {
+            let value = field_value(log, &counter.field)?;
             let value = if counter.increment_by_value {
-                value.to_string_lossy().parse().map_err(|error| {
-                    TransformError::ParseFloatError {
-                        field: counter.field.clone(),
-                        error,
-                    }
-                })?
+                parse_float(value, &counter.field)?
             } else {
                 1.0
             };
@@ -252,7 +492,7 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
                 .map(|namespace| render_template(namespace, event))
                 .transpose()?;
 
-            let tags = render_tags(&counter.tags, event)?;
+            let tags = render_tags(shared_tags, &counter.tags, event)?;
 
             Ok(Metric::new_with_metadata(
                 name,
@@ -265,74 +505,65 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_timestamp(timestamp))
         }
         MetricConfig::Histogram(hist) => {
-            let value = value.to_string_lossy().parse().map_err(|error| {
-                TransformError::ParseFloatError {
-                    field: field.to_string(),
-                    error,
-                }
-            })?;
-
-            let name = hist.name.as_ref().unwrap_or(&hist.field);
-            let name = render_template(name, event)?;
+            let name = hist.name.as_ref().or_else(|| hist.field.as_ref());
+            let name = render_template(name.map_or("", String::as_str), event)?;
+
+            let value = distribution(
+                hist.field.as_deref(),
+                program,
+                &name,
+                StatisticKind::Histogram,
+                event,
+            )?;
+            let value = match &hist.buckets {
+                Some(buckets) => value
+                    .distribution_to_agg_histogram(buckets)
+                    .unwrap_or(value),
+                None => value,
+            };
 
             let namespace = hist.namespace.as_ref();
             let namespace = namespace
                 .map(|namespace| render_template(namespace, event))
                 .transpose()?;
 
-            let tags = render_tags(&hist.tags, event)?;
+            let tags = render_tags(shared_tags, &hist.tags, event)?;
 
-            Ok(Metric::new_with_metadata(
-                name,
-                MetricKind::Incremental,
-                MetricValue::Distribution {
-                    samples: vector_core::samples![value => 1],
-                    statistic: StatisticKind::Histogram,
-                },
-                metadata,
+            Ok(
+                Metric::new_with_metadata(name, MetricKind::Incremental, value, metadata)
+                    .with_namespace(namespace)
+                    .with_tags(tags)
+                    .with_timestamp(timestamp),
             )
-            .with_namespace(namespace)
-            .with_tags(tags)
-            .with_timestamp(timestamp))
         }
         MetricConfig::Summary(summary) => {
-            let value = value.to_string_lossy().parse().map_err(|error| {
-                TransformError::ParseFloatError {
-                    field: field.to_string(),
-                    error,
-                }
-            })?;
+            let name = summary.name.as_ref().or_else(|| summary.field.as_ref());
+            let name = render_template(name.map_or("", String::as_str), event)?;
 
-            let name = summary.name.as_ref().unwrap_or(&summary.field);
-            let name = render_template(name, event)?;
+            let value = distribution(
+                summary.field.as_deref(),
+                program,
+                &name,
+                StatisticKind::Summary,
+                event,
+            )?;
 
             let namespace = summary.namespace.as_ref();
             let namespace = namespace
                 .map(|namespace| render_template(namespace, event))
                 .transpose()?;
 
-            let tags = render_tags(&summary.tags, event)?;
+            let tags = render_tags(shared_tags, &summary.tags, event)?;
 
-            Ok(Metric::new_with_metadata(
-                name,
-                MetricKind::Incremental,
-                MetricValue::Distribution {
-                    samples: vector_core::samples![value => 1],
-                    statistic: StatisticKind::Summary,
-                },
-                metadata,
+            Ok(
+                Metric::new_with_metadata(name, MetricKind::Incremental, value, metadata)
+                    .with_namespace(namespace)
+                    .with_tags(tags)
+                    .with_timestamp(timestamp),
             )
-            .with_namespace(namespace)
-            .with_tags(tags)
-            .with_timestamp(timestamp))
         }
         MetricConfig::Gauge(gauge) => {
-            let value = value.to_string_lossy().parse().map_err(|error| {
-                TransformError::ParseFloatError {
-                    field: field.to_string(),
-                    error,
-                }
-            })?;
+            let value = parse_float(field_value(log, &gauge.field)?, &gauge.field)?;
 
             let name = gauge.name.as_ref().unwrap_or(&gauge.field);
             let name = render_template(name, event)?;
@@ -342,7 +573,7 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
                 .map(|namespace| render_template(namespace, event))
                 .transpose()?;
 
-            let tags = render_tags(&gauge.tags, event)?;
+            let tags = render_tags(shared_tags, &gauge.tags, event)?;
 
             Ok(Metric::new_with_metadata(
                 name,
@@ -355,7 +586,7 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_timestamp(timestamp))
         }
         MetricConfig::Set(set) => {
-            let value = value.to_string_lossy();
+            let value = field_value(log, &set.field)?.to_string_lossy();
 
             let name = set.name.as_ref().unwrap_or(&set.field);
             let name = render_template(name, event)?;
@@ -365,7 +596,7 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
                 .map(|namespace| render_template(namespace, event))
                 .transpose()?;
 
-            let tags = render_tags(&set.tags, event)?;
+            let tags = render_tags(shared_tags, &set.tags, event)?;
 
             Ok(Metric::new_with_metadata(
                 name,
@@ -382,35 +613,172 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
     }
 }
 
+/// Converts a log event produced by `metric_to_log` back into the metric it was produced from.
+fn decode_metric(event: &Event, host_tag: &str) -> Result<Metric, TransformError> {
+    let log = event.as_log();
+    let name = match log.get("name") {
+        Some(Value::Bytes(name)) => String::from_utf8_lossy(name).into_owned(),
+        _ => {
+            return Err(TransformError::FieldNotFound {
+                field: "name".to_owned(),
+            })
+        }
+    };
+    let value_error = |error| TransformError::ValueError {
+        name: name.clone(),
+        error,
+    };
+
+    let kind = match log.get("kind").map(Value::to_string_lossy).as_deref() {
+        Some("absolute") => MetricKind::Absolute,
+        Some("incremental") => MetricKind::Incremental,
+        _ => {
+            return Err(value_error(
+                "expected `kind` to be `absolute` or `incremental`".to_owned(),
+            ))
+        }
+    };
+    let namespace = log.get("namespace").map(Value::to_string_lossy);
+
+    let mut tags = match log.get("tags") {
+        Some(Value::Object(tags)) => tags
+            .iter()
+            .map(|(tag, value)| (tag.clone(), value.to_string_lossy()))
+            .collect(),
+        Some(value) => {
+            return Err(value_error(format!(
+                "expected `tags` to be an object, got {}",
+                value.kind_str()
+            )))
+        }
+        None => BTreeMap::new(),
+    };
+    if let Some(host) = log.get(log_schema().host_key()) {
+        tags.insert(host_tag.to_owned(), host.to_string_lossy());
+    }
+
+    let timestamp = log
+        .get(log_schema().timestamp_key())
+        .and_then(Value::as_timestamp)
+        .cloned();
+    let value = decode_value(log).map_err(value_error)?;
+
+    Ok(
+        Metric::new_with_metadata(name, kind, value, event.metadata().clone())
+            .with_namespace(namespace)
+            .with_tags((!tags.is_empty()).then(|| tags))
+            .with_timestamp(timestamp),
+    )
+}
+
+fn decode_value(log: &LogEvent) -> Result<MetricValue, String> {
+    if let Some(counter) = log.get("counter") {
+        Ok(MetricValue::Counter {
+            value: number(counter.get("value"), "counter.value")?,
+        })
+    } else if let Some(gauge) = log.get("gauge") {
+        Ok(MetricValue::Gauge {
+            value: number(gauge.get("value"), "gauge.value")?,
+        })
+    } else if let Some(set) = log.get("set") {
+        Ok(MetricValue::Set {
+            values: elements(set.get("values"), "set.values")?
+                .iter()
+                .map(Value::to_string_lossy)
+                .collect(),
+        })
+    } else if let Some(distribution) = log.get("distribution") {
+        let statistic = match distribution
+            .get("statistic")
+            .map(Value::to_string_lossy)
+            .as_deref()
+        {
+            Some("histogram") => StatisticKind::Histogram,
+            Some("summary") => StatisticKind::Summary,
+            _ => {
+                return Err(
+                    "expected `distribution.statistic` to be `histogram` or `summary`".to_owned(),
+                )
+            }
+        };
+        Ok(MetricValue::Distribution {
+            samples: elements(distribution.get("samples"), "distribution.samples")?
+                .iter()
+                .map(|sample| {
+                    Ok(Sample {
+                        value: number(sample.get("value"), "value")?,
+                        rate: count(sample.get("rate"), "rate")?,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+            statistic,
+        })
+    } else if let Some(histogram) = log.get("aggregated_histogram") {
+        vrl_distribution(histogram.clone(), StatisticKind::Histogram)
+    } else if let Some(summary) = log.get("aggregated_summary") {
+        vrl_distribution(summary.clone(), StatisticKind::Summary)
+    } else if let Some(sketch) = log.get("sketch") {
+        serde_json::to_value(sketch)
+            .and_then(serde_json::from_value::<MetricSketch>)
+            .map(|sketch| MetricValue::Sketch { sketch })
+            .map_err(|error| format!("invalid `sketch`: {}", error))
+    } else {
+        Err("missing the value of the metric".to_owned())
+    }
+}
+
+fn emit_error(error: TransformError) {
+    match error {
+        TransformError::FieldNull { field } => emit!(&LogToMetricFieldNullError {
+            field: field.as_ref()
+        }),
+        TransformError::FieldNotFound { field } => emit!(&ParserMissingFieldError {
+            field: field.as_ref()
+        }),
+        TransformError::ParseFloatError { field, error } => {
+            emit!(&LogToMetricParseFloatError {
+                field: field.as_ref(),
+                error
+            })
+        }
+        TransformError::ValueError { name, error } => {
+            emit!(&LogToMetricValueError {
+                name: name.as_ref(),
+                error: error.as_ref(),
+            })
+        }
+        TransformError::TemplateRenderingError(error) => {
+            emit!(&crate::internal_events::TemplateRenderingError {
+                error,
+                drop_event: false,
+                field: None,
+            })
+        }
+        TransformError::TemplateParseError(error) => {
+            emit!(&LogToMetricTemplateParseError { error })
+        }
+    }
+}
+
 impl FunctionTransform for LogToMetric {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
-        for config in self.config.metrics.iter() {
-            match to_metric(config, &event) {
-                Ok(metric) => {
-                    output.push(Event::Metric(metric));
-                }
-                Err(TransformError::FieldNull { field }) => emit!(&LogToMetricFieldNullError {
-                    field: field.as_ref()
-                }),
-                Err(TransformError::FieldNotFound { field }) => emit!(&ParserMissingFieldError {
-                    field: field.as_ref()
-                }),
-                Err(TransformError::ParseFloatError { field, error }) => {
-                    emit!(&LogToMetricParseFloatError {
-                        field: field.as_ref(),
-                        error
-                    })
-                }
-                Err(TransformError::TemplateRenderingError(error)) => {
-                    emit!(&crate::internal_events::TemplateRenderingError {
-                        error,
-                        drop_event: false,
-                        field: None,
-                    })
-                }
-                Err(TransformError::TemplateParseError(error)) => {
-                    emit!(&LogToMetricTemplateParseError { error })
-                }
+        if self.config.all_metrics {
+            let host_tag = self
+                .config
+                .host_tag
+                .as_deref()
+                .unwrap_or_else(|| log_schema().host_key());
+            match decode_metric(&event, host_tag) {
+                Ok(metric) => output.push(Event::Metric(metric)),
+                Err(error) => emit_error(error),
+            }
+            return;
+        }
+
+        for (config, program) in self.config.metrics.iter().zip(self.values.iter()) {
+            match to_metric(config, program.as_ref(), &self.config.tags, &event) {
+                Ok(metric) => output.push(Event::Metric(metric)),
+                Err(error) => emit_error(error),
             }
         }
     }
@@ -462,7 +830,7 @@ mod tests {
 
         let event = create_event("status", "42");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -495,7 +863,7 @@ mod tests {
         event.as_mut_log().insert("code", "200");
         let metadata = event.metadata().clone();
 
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -533,7 +901,7 @@ mod tests {
 
         let event = create_event("backtrace", "message");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -560,7 +928,7 @@ mod tests {
         );
 
         let event = create_event("success", "42");
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -579,7 +947,7 @@ mod tests {
 
         let event = create_event("amount", "33.99");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -609,7 +977,7 @@ mod tests {
 
         let event = create_event("amount", "33.99");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -637,7 +1005,7 @@ mod tests {
 
         let event = create_event("memory_rss", "123");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -665,7 +1033,7 @@ mod tests {
         );
 
         let event = create_event("status", "not a number");
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -682,7 +1050,7 @@ mod tests {
         );
 
         let event = create_event("not foo", "not a number");
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -699,7 +1067,7 @@ mod tests {
         );
 
         let event = create_event("status", Value::Null);
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
 
         assert_eq!(transform_one(&mut transform, event), None);
     }
@@ -727,7 +1095,7 @@ mod tests {
         event.as_mut_log().insert("backtrace", "message");
         let metadata = event.metadata().clone();
 
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
 
         let mut output = OutputBuffer::default();
         transform.transform(&mut output, event);
@@ -783,7 +1151,7 @@ mod tests {
         event.as_mut_log().insert("service", "xyz");
         let metadata = event.metadata().clone();
 
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
 
         let mut output = OutputBuffer::default();
         transform.transform(&mut output, event);
@@ -827,7 +1195,7 @@ mod tests {
 
         let event = create_event("user_ip", "1.2.3.4");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -856,7 +1224,7 @@ mod tests {
 
         let event = create_event("response_time", "2.5");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -886,7 +1254,7 @@ mod tests {
 
         let event = create_event("response_time", "2.5");
         let metadata = event.metadata().clone();
-        let mut transform = LogToMetric::new(config);
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
         let metric = transform_one(&mut transform, event).unwrap();
 
         assert_eq!(
@@ -903,4 +1271,198 @@ mod tests {
             .with_timestamp(Some(ts()))
         );
     }
+
+    #[test]
+    fn response_time_histogram_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            value = "[.fast, .slow]"
+            name = "response_time"
+            buckets = [1.0, 5.0]
+            "#,
+        );
+
+        let mut event = create_event("fast", 0.5);
+        event.as_mut_log().insert("slow", 2.5);
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::AggregatedHistogram {
+                    buckets: vector_core::buckets![1.0 => 1, 5.0 => 1],
+                    count: 2,
+                    sum: 3.0,
+                },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn response_time_summary_quantiles() {
+        let config = parse_config(
+            r#"
+            tags = {host = "{{host}}", service = "api"}
+
+            [[metrics]]
+            type = "summary"
+            value = """
+            {
+                "quantiles": [{"quantile": 0.5, "value": .p50}, {"quantile": 0.99, "value": .p99}],
+                "count": .count,
+                "sum": .sum
+            }
+            """
+            name = "response_time"
+            tags = {service = "{{service}}"}
+            "#,
+        );
+
+        let mut event = create_event("p50", 1.5);
+        event.as_mut_log().insert("p99", 4);
+        event.as_mut_log().insert("count", 10);
+        event.as_mut_log().insert("sum", 20.0);
+        event.as_mut_log().insert("host", "localhost");
+        event.as_mut_log().insert("service", "web");
+        let metadata = event.metadata().clone();
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::AggregatedSummary {
+                    quantiles: vector_core::quantiles![0.5 => 1.5, 0.99 => 4.0],
+                    count: 10,
+                    sum: 20.0,
+                },
+                metadata
+            )
+            .with_tags(Some(
+                vec![
+                    ("host".to_owned(), "localhost".to_owned()),
+                    ("service".to_owned(), "web".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ))
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn invalid_value() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            value = ".response_time"
+            name = "response_time"
+            "#,
+        );
+
+        let event = create_event("response_time", "fast");
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
+        assert!(transform_one(&mut transform, event).is_none());
+    }
+
+    #[test]
+    fn field_or_value_required() {
+        let both = parse_config(
+            r#"
+            [[metrics]]
+            type = "summary"
+            field = "response_time"
+            value = ".response_time"
+            "#,
+        );
+        assert!(LogToMetric::new(both, &Default::default()).is_err());
+
+        let unnamed = parse_config(
+            r#"
+            [[metrics]]
+            type = "summary"
+            value = ".response_time"
+            "#,
+        );
+        assert!(LogToMetric::new(unnamed, &Default::default()).is_err());
+    }
+
+    #[cfg(feature = "transforms-metric_to_log")]
+    #[test]
+    fn round_trips_metric_to_log() {
+        let config = parse_config("all_metrics = true");
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
+        let to_log = crate::transforms::metric_to_log::MetricToLog::new(None, Default::default());
+
+        let metrics = vec![
+            Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 12.0 },
+            ),
+            Metric::new(
+                "latency",
+                MetricKind::Absolute,
+                MetricValue::AggregatedHistogram {
+                    buckets: vec![
+                        Bucket {
+                            upper_limit: 0.5,
+                            count: 3,
+                        },
+                        Bucket {
+                            upper_limit: f64::INFINITY,
+                            count: 4,
+                        },
+                    ],
+                    count: 7,
+                    sum: 8.5,
+                },
+            ),
+            Metric::new(
+                "users",
+                MetricKind::Incremental,
+                MetricValue::Set {
+                    values: vec!["alice".to_owned()].into_iter().collect(),
+                },
+            ),
+        ];
+
+        for metric in metrics {
+            let metric = metric
+                .with_namespace(Some("app"))
+                .with_tags(Some(
+                    vec![
+                        ("host".to_owned(), "localhost".to_owned()),
+                        ("region".to_owned(), "eu".to_owned()),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ))
+                .with_timestamp(Some(ts()));
+
+            let log = to_log.transform_one(metric.clone()).unwrap();
+            let decoded = transform_one(&mut transform, Event::Log(log)).unwrap();
+            assert_eq!(decoded.into_metric(), metric);
+        }
+    }
+
+    #[test]
+    fn all_metrics_invalid_event() {
+        let config = parse_config("all_metrics = true");
+        let mut transform = LogToMetric::new(config, &Default::default()).unwrap();
+
+        let event = create_event("name", "requests");
+        assert!(transform_one(&mut transform, event).is_none());
+    }
 }
@@ -0,0 +1,561 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    pin::Pin,
+    time::Duration,
+};
+
+use async_stream::stream;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{
+        metric::{Metric, MetricData, MetricKind, MetricSeries, MetricValue},
+        Event,
+    },
+    internal_events::{MetricTemporalityResetDetected, MetricTemporalitySeriesExpired},
+    schema,
+    transforms::{TaskTransform, Transform},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MetricTemporalityConfig {
+    /// The kind the metrics are converted to.
+    pub target_kind: TargetKind,
+
+    /// How long the state of a series is kept after it was last seen, in seconds.
+    #[serde(default = "default_expire_after_secs")]
+    pub expire_after_secs: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    /// Converts the incremental metrics, the deltas, into cumulative ones.
+    Absolute,
+    /// Converts the absolute metrics, the cumulative values, into deltas.
+    Incremental,
+}
+
+const fn default_expire_after_secs() -> u64 {
+    300
+}
+
+inventory::submit! {
+    TransformDescription::new::<MetricTemporalityConfig>("metric_temporality")
+}
+
+impl GenerateConfig for MetricTemporalityConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            target_kind: TargetKind::Absolute,
+            expire_after_secs: default_expire_after_secs(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "metric_temporality")]
+impl TransformConfig for MetricTemporalityConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        if self.expire_after_secs == 0 {
+            return Err("`expire_after_secs` must be greater than zero".into());
+        }
+        Ok(Transform::event_task(MetricTemporality::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "metric_temporality"
+    }
+}
+
+/// The last known value of a series: the running total when converting to absolute, the
+/// reference value the deltas are computed from when converting to incremental.
+#[derive(Debug)]
+struct SeriesState {
+    data: MetricData,
+    seen_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct MetricTemporality {
+    target_kind: TargetKind,
+    expire_after: Duration,
+    state: HashMap<MetricSeries, SeriesState>,
+}
+
+impl MetricTemporality {
+    pub fn new(config: &MetricTemporalityConfig) -> Self {
+        Self {
+            target_kind: config.target_kind,
+            expire_after: Duration::from_secs(config.expire_after_secs),
+            state: HashMap::new(),
+        }
+    }
+
+    /// Converts the metric, or returns `None` if it only sets the reference value of its series.
+    fn record(&mut self, metric: Metric, now: Instant) -> Option<Metric> {
+        // Summaries, sets, distributions and sketches have no meaningful running total, so they
+        // are passed through as they are.
+        if !matches!(
+            metric.value(),
+            MetricValue::Counter { .. }
+                | MetricValue::Gauge { .. }
+                | MetricValue::AggregatedHistogram { .. }
+        ) {
+            return Some(metric);
+        }
+
+        match (self.target_kind, metric.kind()) {
+            (TargetKind::Absolute, MetricKind::Incremental) => {
+                Some(self.make_absolute(metric, now))
+            }
+            (TargetKind::Incremental, MetricKind::Absolute) => self.make_incremental(metric, now),
+            // Already of the target kind, the metric also becomes the new state of its series.
+            (TargetKind::Absolute, MetricKind::Absolute) => {
+                self.state.insert(
+                    metric.series().clone(),
+                    SeriesState {
+                        data: metric.data().clone(),
+                        seen_at: now,
+                    },
+                );
+                Some(metric)
+            }
+            (TargetKind::Incremental, MetricKind::Incremental) => {
+                if let Some(state) = self.state.get_mut(metric.series()) {
+                    if state.data.value.add(metric.value()) {
+                        state.seen_at = now;
+                    }
+                }
+                Some(metric)
+            }
+        }
+    }
+
+    fn make_absolute(&mut self, metric: Metric, now: Instant) -> Metric {
+        match self.state.entry(metric.series().clone()) {
+            Entry::Occupied(mut entry) => {
+                let state = entry.get_mut();
+                state.seen_at = now;
+                if state.data.value.add(metric.value()) {
+                    let total = state.data.value.clone();
+                    return metric.with_value(total).into_absolute();
+                }
+                // The series changed type, the deltas now add up from this value.
+                state.data = metric.data().clone();
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(SeriesState {
+                    data: metric.data().clone(),
+                    seen_at: now,
+                });
+            }
+        }
+        metric.into_absolute()
+    }
+
+    fn make_incremental(&mut self, metric: Metric, now: Instant) -> Option<Metric> {
+        // The first value of a series is only kept as the reference for the next ones: it is the
+        // total since the series started, often long before, and not something that just happened.
+        let state = match self.state.entry(metric.series().clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(SeriesState {
+                    data: metric.data().clone(),
+                    seen_at: now,
+                });
+                return None;
+            }
+        };
+        let previous = std::mem::replace(&mut state.data, metric.data().clone());
+        state.seen_at = now;
+
+        let mut delta = metric.data().clone();
+        if delta.subtract(&previous) {
+            return Some(metric.with_value(delta.value).into_incremental());
+        }
+        if is_reset(&previous.value, metric.value()) {
+            // The source restarted and counts from zero again, so the whole value is new.
+            emit!(&MetricTemporalityResetDetected {
+                series: metric.series(),
+            });
+            return Some(metric.into_incremental());
+        }
+        // The series changed type, this value is the new reference.
+        None
+    }
+
+    /// Forgets the series that weren't seen for a while, so that their state doesn't grow
+    /// without bounds as series come and go.
+    fn expire(&mut self, now: Instant) {
+        let expire_after = self.expire_after;
+        let count = self.state.len();
+        self.state
+            .retain(|_, state| now.saturating_duration_since(state.seen_at) < expire_after);
+        let count = count - self.state.len();
+        if count > 0 {
+            emit!(&MetricTemporalitySeriesExpired { count });
+        }
+    }
+}
+
+/// Whether the cumulative value went down, as it only does when its source restarted.
+fn is_reset(previous: &MetricValue, current: &MetricValue) -> bool {
+    match (previous, current) {
+        (MetricValue::Counter { value: previous }, MetricValue::Counter { value }) => {
+            value < previous
+        }
+        (
+            MetricValue::AggregatedHistogram {
+                count: previous, ..
+            },
+            MetricValue::AggregatedHistogram { count, .. },
+        ) => count < previous,
+        _ => false,
+    }
+}
+
+impl TaskTransform<Event> for MetricTemporality {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut expire_stream = tokio::time::interval(self.expire_after);
+
+        Box::pin(stream! {
+            loop {
+                tokio::select! {
+                    _ = expire_stream.tick() => self.expire(Instant::now()),
+                    maybe_event = input_rx.next() => match maybe_event {
+                        None => break,
+                        Some(event) => {
+                            if let Some(metric) = self.record(event.into_metric(), Instant::now()) {
+                                yield Event::Metric(metric);
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, SinkExt};
+
+    use super::*;
+    use crate::event::metric::Bucket;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricTemporalityConfig>();
+    }
+
+    fn transform(target_kind: TargetKind) -> MetricTemporality {
+        MetricTemporality::new(&MetricTemporalityConfig {
+            target_kind,
+            expire_after_secs: 60,
+        })
+    }
+
+    fn counter(kind: MetricKind, value: f64) -> Metric {
+        Metric::new("requests", kind, MetricValue::Counter { value })
+    }
+
+    fn histogram(kind: MetricKind, counts: [u32; 2]) -> Metric {
+        Metric::new(
+            "latency",
+            kind,
+            MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: 1.0,
+                        count: counts[0],
+                    },
+                    Bucket {
+                        upper_limit: 2.0,
+                        count: counts[1],
+                    },
+                ],
+                count: counts[0] + counts[1],
+                sum: f64::from(counts[0] + counts[1]),
+            },
+        )
+    }
+
+    #[test]
+    fn incremental_to_absolute() {
+        let mut temporality = transform(TargetKind::Absolute);
+        let now = Instant::now();
+
+        let outputs = [1.0, 2.5, 3.0]
+            .iter()
+            .map(|value| temporality.record(counter(MetricKind::Incremental, *value), now))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            vec![
+                Some(counter(MetricKind::Absolute, 1.0)),
+                Some(counter(MetricKind::Absolute, 3.5)),
+                Some(counter(MetricKind::Absolute, 6.5)),
+            ]
+        );
+
+        // An absolute value becomes the total the next deltas are added to.
+        assert_eq!(
+            temporality.record(counter(MetricKind::Absolute, 10.0), now),
+            Some(counter(MetricKind::Absolute, 10.0))
+        );
+        assert_eq!(
+            temporality.record(counter(MetricKind::Incremental, 1.0), now),
+            Some(counter(MetricKind::Absolute, 11.0))
+        );
+    }
+
+    #[test]
+    fn absolute_to_incremental() {
+        let mut temporality = transform(TargetKind::Incremental);
+        let now = Instant::now();
+
+        let outputs = [10.0, 12.0, 12.0, 15.5]
+            .iter()
+            .map(|value| temporality.record(counter(MetricKind::Absolute, *value), now))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            vec![
+                None,
+                Some(counter(MetricKind::Incremental, 2.0)),
+                Some(counter(MetricKind::Incremental, 0.0)),
+                Some(counter(MetricKind::Incremental, 3.5)),
+            ]
+        );
+
+        // Deltas are passed through as they are.
+        assert_eq!(
+            temporality.record(counter(MetricKind::Incremental, 1.0), now),
+            Some(counter(MetricKind::Incremental, 1.0))
+        );
+        assert_eq!(
+            temporality.record(counter(MetricKind::Absolute, 17.5), now),
+            Some(counter(MetricKind::Incremental, 1.0))
+        );
+    }
+
+    #[test]
+    fn absolute_to_incremental_resets() {
+        let mut temporality = transform(TargetKind::Incremental);
+        let now = Instant::now();
+
+        assert_eq!(
+            temporality.record(counter(MetricKind::Absolute, 100.0), now),
+            None
+        );
+        // The counter restarted from zero, so all of its value is new.
+        assert_eq!(
+            temporality.record(counter(MetricKind::Absolute, 4.0), now),
+            Some(counter(MetricKind::Incremental, 4.0))
+        );
+        assert_eq!(
+            temporality.record(counter(MetricKind::Absolute, 6.0), now),
+            Some(counter(MetricKind::Incremental, 2.0))
+        );
+
+        assert_eq!(
+            temporality.record(histogram(MetricKind::Absolute, [5, 5]), now),
+            None
+        );
+        assert_eq!(
+            temporality.record(histogram(MetricKind::Absolute, [7, 6]), now),
+            Some(histogram(MetricKind::Incremental, [2, 1]))
+        );
+        assert_eq!(
+            temporality.record(histogram(MetricKind::Absolute, [1, 0]), now),
+            Some(histogram(MetricKind::Incremental, [1, 0]))
+        );
+    }
+
+    #[test]
+    fn changed_type_restarts_series() {
+        let mut temporality = transform(TargetKind::Incremental);
+        let now = Instant::now();
+        let gauge = |value| {
+            Metric::new(
+                "requests",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value },
+            )
+        };
+
+        assert_eq!(
+            temporality.record(counter(MetricKind::Absolute, 10.0), now),
+            None
+        );
+        assert_eq!(temporality.record(gauge(5.0), now), None);
+        assert_eq!(
+            temporality.record(gauge(3.0), now),
+            Some(Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Gauge { value: -2.0 }
+            ))
+        );
+    }
+
+    #[test]
+    fn passes_through_other_types() {
+        let mut temporality = transform(TargetKind::Incremental);
+        let summary = Metric::new(
+            "latency",
+            MetricKind::Absolute,
+            MetricValue::AggregatedSummary {
+                quantiles: Vec::new(),
+                count: 3,
+                sum: 1.5,
+            },
+        );
+
+        assert_eq!(
+            temporality.record(summary.clone(), Instant::now()),
+            Some(summary)
+        );
+    }
+
+    #[test]
+    fn expires_stale_series() {
+        let mut temporality = transform(TargetKind::Incremental);
+        let start = Instant::now();
+
+        temporality.record(counter(MetricKind::Absolute, 10.0), start);
+        temporality.record(
+            counter(MetricKind::Absolute, 5.0).with_name("errors"),
+            start + Duration::from_secs(30),
+        );
+
+        temporality.expire(start + Duration::from_secs(61));
+        assert_eq!(temporality.state.len(), 1);
+
+        // Once forgotten, the series starts over with a new reference value.
+        assert_eq!(
+            temporality.record(
+                counter(MetricKind::Absolute, 12.0),
+                start + Duration::from_secs(62)
+            ),
+            None
+        );
+        assert_eq!(
+            temporality.record(
+                counter(MetricKind::Absolute, 7.0).with_name("errors"),
+                start + Duration::from_secs(62)
+            ),
+            Some(counter(MetricKind::Incremental, 2.0).with_name("errors"))
+        );
+    }
+
+    #[tokio::test]
+    async fn transform_stream() {
+        let transform = toml::from_str::<MetricTemporalityConfig>(
+            r#"
+target_kind = "absolute"
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap()
+        .into_task();
+
+        let inputs = [1.0, 2.0, 3.0]
+            .iter()
+            .map(|value| Event::Metric(counter(MetricKind::Incremental, *value)))
+            .collect::<Vec<_>>();
+        let outputs = transform
+            .transform_events(Box::pin(stream::iter(inputs)))
+            .map(Event::into_metric)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            outputs,
+            vec![
+                counter(MetricKind::Absolute, 1.0),
+                counter(MetricKind::Absolute, 3.0),
+                counter(MetricKind::Absolute, 6.0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn transform_expires_series() {
+        let transform = toml::from_str::<MetricTemporalityConfig>(
+            r#"
+target_kind = "absolute"
+expire_after_secs = 60
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap()
+        .into_task();
+
+        let (mut tx, rx) = futures::channel::mpsc::channel(10);
+        let mut out_stream = transform.transform_events(Box::pin(rx));
+
+        tokio::time::pause();
+
+        tx.send(Event::Metric(counter(MetricKind::Incremental, 4.0)))
+            .await
+            .unwrap();
+        assert_eq!(
+            out_stream.next().await.unwrap().into_metric(),
+            counter(MetricKind::Absolute, 4.0)
+        );
+
+        // Once the series wasn't seen for a while, its total starts over.
+        tokio::time::advance(Duration::from_secs(121)).await;
+        assert_eq!(
+            std::task::Poll::Pending,
+            futures::poll!(out_stream.next()).map(|event| event.is_some())
+        );
+        tx.send(Event::Metric(counter(MetricKind::Incremental, 1.0)))
+            .await
+            .unwrap();
+        assert_eq!(
+            out_stream.next().await.unwrap().into_metric(),
+            counter(MetricKind::Absolute, 1.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_zero_expiration() {
+        let config = toml::from_str::<MetricTemporalityConfig>(
+            r#"
+target_kind = "incremental"
+expire_after_secs = 0
+"#,
+        )
+        .unwrap();
+        assert!(config.build(&TransformContext::default()).await.is_err());
+    }
+}
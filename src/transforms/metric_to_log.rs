@@ -8,7 +8,7 @@ use crate::{
         log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
         TransformDescription,
     },
-    event::{self, Event, LogEvent, Metric},
+    event::{self, metric::MetricValue, Event, LogEvent, Metric},
     internal_events::MetricToLogSerializeError,
     schema,
     transforms::{FunctionTransform, OutputBuffer, Transform},
@@ -94,6 +94,9 @@ impl MetricToLog {
                     for (key, value) in object {
                         log.insert_flat(key, value);
                     }
+                    for (path, value) in infinite_values(metric.value()) {
+                        log.insert(path.as_str(), value);
+                    }
 
                     let timestamp = log
                         .remove(self.timestamp_key.as_str())
@@ -116,6 +119,51 @@ impl MetricToLog {
     }
 }
 
+/// JSON can't represent infinite numbers, such as the upper limit of the last bucket of
+/// histograms, so they are serialized as nulls and restored afterwards.
+fn infinite_values(value: &MetricValue) -> Vec<(String, f64)> {
+    let mut values = Vec::new();
+    match value {
+        MetricValue::Counter { value } => values.push(("counter.value".to_owned(), *value)),
+        MetricValue::Gauge { value } => values.push(("gauge.value".to_owned(), *value)),
+        MetricValue::Distribution { samples, .. } => {
+            for (index, sample) in samples.iter().enumerate() {
+                if sample.value.is_infinite() {
+                    values.push((
+                        format!("distribution.samples[{}].value", index),
+                        sample.value,
+                    ));
+                }
+            }
+        }
+        MetricValue::AggregatedHistogram { buckets, sum, .. } => {
+            for (index, bucket) in buckets.iter().enumerate() {
+                if bucket.upper_limit.is_infinite() {
+                    values.push((
+                        format!("aggregated_histogram.buckets[{}].upper_limit", index),
+                        bucket.upper_limit,
+                    ));
+                }
+            }
+            values.push(("aggregated_histogram.sum".to_owned(), *sum));
+        }
+        MetricValue::AggregatedSummary { quantiles, sum, .. } => {
+            for (index, quantile) in quantiles.iter().enumerate() {
+                if quantile.value.is_infinite() {
+                    values.push((
+                        format!("aggregated_summary.quantiles[{}].value", index),
+                        quantile.value,
+                    ));
+                }
+            }
+            values.push(("aggregated_summary.sum".to_owned(), *sum));
+        }
+        MetricValue::Set { .. } | MetricValue::Sketch { .. } => {}
+    }
+    values.retain(|(_, value)| value.is_infinite());
+    values
+}
+
 impl FunctionTransform for MetricToLog {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
         let retval: Option<Event> = self
@@ -18,6 +18,8 @@ pub mod coercer;
 pub mod compound;
 #[cfg(feature = "transforms-concat")]
 pub mod concat;
+#[cfg(feature = "transforms-correlate")]
+pub mod correlate;
 #[cfg(feature = "transforms-dedupe")]
 pub mod dedupe;
 #[cfg(feature = "transforms-field_filter")]
@@ -32,6 +34,8 @@ pub mod grok_parser;
 pub mod json_parser;
 #[cfg(feature = "transforms-key_value_parser")]
 pub mod key_value_parser;
+#[cfg(feature = "transforms-log_aggregate")]
+pub mod log_aggregate;
 #[cfg(feature = "transforms-log_to_metric")]
 pub mod log_to_metric;
 #[cfg(feature = "transforms-logfmt_parser")]
@@ -40,9 +44,13 @@ pub mod logfmt_parser;
 pub mod lua;
 #[cfg(feature = "transforms-merge")]
 pub mod merge;
+#[cfg(feature = "transforms-metric_temporality")]
+pub mod metric_temporality;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
 pub mod noop;
+#[cfg(feature = "transforms-pii_scrubber")]
+pub mod pii_scrubber;
 #[cfg(feature = "transforms-pipelines")]
 pub mod pipelines;
 #[cfg(feature = "transforms-reduce")]
@@ -61,6 +69,10 @@ pub mod rename_fields;
 pub mod route;
 #[cfg(feature = "transforms-sample")]
 pub mod sample;
+#[cfg(feature = "transforms-sample_adaptive")]
+pub mod sample_adaptive;
+#[cfg(feature = "transforms-schema_enforce")]
+pub mod schema_enforce;
 #[cfg(feature = "transforms-split")]
 pub mod split;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
@@ -0,0 +1,537 @@
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snafu::{ResultExt, Snafu};
+use vector_core::transform::SyncTransform;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::PiiScrubbed,
+    schema,
+    transforms::Transform,
+};
+
+const AUDIT: &str = "audit";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PiiScrubberConfig {
+    #[serde(default = "default_detectors")]
+    pub detectors: Vec<BuiltinDetector>,
+    /// Custom regular expressions by name, the matches of which are scrubbed.
+    #[serde(default)]
+    pub patterns: IndexMap<String, String>,
+    /// Words scrubbed wherever they appear, regardless of case.
+    #[serde(default)]
+    pub dictionary: Vec<String>,
+    #[serde(default)]
+    pub mode: ScrubMode,
+    #[serde(default = "default_mask")]
+    pub mask: String,
+    /// The salt prepended to the matches before hashing them.
+    pub salt: Option<String>,
+    /// The confidence below which the matches of the detectors are left as is.
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinDetector {
+    CreditCard,
+    Email,
+    Ipv4,
+    Phone,
+    UsSsn,
+}
+
+/// How the detected PII is scrubbed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrubMode {
+    /// The matches are replaced by the mask.
+    Mask,
+    /// The matches are replaced by their salted SHA-256 hashes, so they can still be correlated.
+    Hash,
+    /// The fields holding matches are removed.
+    Remove,
+}
+
+impl Default for ScrubMode {
+    fn default() -> Self {
+        Self::Mask
+    }
+}
+
+fn default_detectors() -> Vec<BuiltinDetector> {
+    vec![
+        BuiltinDetector::CreditCard,
+        BuiltinDetector::Email,
+        BuiltinDetector::Ipv4,
+        BuiltinDetector::Phone,
+        BuiltinDetector::UsSsn,
+    ]
+}
+
+fn default_mask() -> String {
+    "[REDACTED]".to_owned()
+}
+
+const fn default_min_confidence() -> f64 {
+    0.5
+}
+
+inventory::submit! {
+    TransformDescription::new::<PiiScrubberConfig>("pii_scrubber")
+}
+
+impl GenerateConfig for PiiScrubberConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            detectors: default_detectors(),
+            patterns: IndexMap::new(),
+            dictionary: Vec::new(),
+            mode: ScrubMode::Mask,
+            mask: default_mask(),
+            salt: None,
+            min_confidence: default_min_confidence(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pii_scrubber")]
+impl TransformConfig for PiiScrubberConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(PiiScrubber::new(self)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![
+            Output::default(DataType::Log),
+            Output::from((AUDIT, DataType::Log)),
+        ]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "pii_scrubber"
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum BuildError {
+    #[snafu(display("Invalid pattern {:?}: {}", name, source))]
+    InvalidPattern { name: String, source: regex::Error },
+}
+
+/// Finds candidate matches, and rates how likely each is to be PII.
+#[derive(Clone, Debug)]
+struct Detector {
+    name: String,
+    regex: Regex,
+    confidence: fn(&str) -> f64,
+}
+
+impl Detector {
+    fn builtin(detector: BuiltinDetector) -> Self {
+        let (name, pattern, confidence): (&str, &str, fn(&str) -> f64) = match detector {
+            BuiltinDetector::CreditCard => {
+                ("credit_card", r"\b(?:\d[ -]?){12,18}\d\b", luhn_confidence)
+            }
+            BuiltinDetector::Email => (
+                "email",
+                r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b",
+                |_| 0.9,
+            ),
+            BuiltinDetector::Ipv4 => ("ipv4", r"\b(?:\d{1,3}\.){3}\d{1,3}\b", ipv4_confidence),
+            BuiltinDetector::Phone => (
+                "phone",
+                r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]\d{4}\b",
+                |_| 0.5,
+            ),
+            BuiltinDetector::UsSsn => ("us_ssn", r"\b\d{3}-\d{2}-\d{4}\b", ssn_confidence),
+        };
+
+        Self {
+            name: name.to_owned(),
+            regex: Regex::new(pattern).expect("builtin patterns are valid"),
+            confidence,
+        }
+    }
+}
+
+/// The digits of credit card numbers end with a Luhn checksum, which random numbers only pass
+/// one time out of ten.
+fn luhn_confidence(candidate: &str) -> f64 {
+    let digits = candidate
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|digit| u32::from(digit - b'0'))
+        .collect::<Vec<_>>();
+    if !(13..=19).contains(&digits.len()) {
+        return 0.0;
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| match (index % 2, digit * 2) {
+            (0, _) => *digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    if sum % 10 == 0 {
+        0.95
+    } else {
+        0.2
+    }
+}
+
+fn ipv4_confidence(candidate: &str) -> f64 {
+    if candidate
+        .split('.')
+        .all(|octet| octet.parse::<u8>().is_ok())
+    {
+        0.7
+    } else {
+        0.1
+    }
+}
+
+/// Social security numbers never have the areas 000, 666 or 900 to 999, the group 00 nor the
+/// serial 0000.
+fn ssn_confidence(candidate: &str) -> f64 {
+    let mut parts = candidate.split('-');
+    let area = parts.next().unwrap_or_default();
+    let group = parts.next().unwrap_or_default();
+    let serial = parts.next().unwrap_or_default();
+    if area == "000" || area == "666" || area.starts_with('9') || group == "00" || serial == "0000"
+    {
+        0.3
+    } else {
+        0.8
+    }
+}
+
+/// A scrubbed match, as reported by the audit records.
+#[derive(Debug)]
+struct Finding {
+    field: String,
+    detector: String,
+    confidence: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct PiiScrubber {
+    detectors: Vec<Detector>,
+    mode: ScrubMode,
+    mask: String,
+    salt: String,
+    min_confidence: f64,
+}
+
+impl PiiScrubber {
+    pub fn new(config: &PiiScrubberConfig) -> crate::Result<Self> {
+        let mut detectors = config
+            .detectors
+            .iter()
+            .map(|detector| Detector::builtin(*detector))
+            .collect::<Vec<_>>();
+        for (name, pattern) in &config.patterns {
+            detectors.push(Detector {
+                name: name.clone(),
+                regex: Regex::new(pattern).context(InvalidPatternSnafu { name })?,
+                confidence: |_| 1.0,
+            });
+        }
+        if !config.dictionary.is_empty() {
+            let words = config
+                .dictionary
+                .iter()
+                .map(|word| regex::escape(word))
+                .collect::<Vec<_>>();
+            let pattern = format!(r"(?i)\b(?:{})\b", words.join("|"));
+            detectors.push(Detector {
+                name: "dictionary".to_owned(),
+                regex: Regex::new(&pattern).context(InvalidPatternSnafu { name: "dictionary" })?,
+                confidence: |_| 1.0,
+            });
+        }
+
+        Ok(Self {
+            detectors,
+            mode: config.mode,
+            mask: config.mask.clone(),
+            salt: config.salt.clone().unwrap_or_default(),
+            min_confidence: config.min_confidence,
+        })
+    }
+
+    /// Finds the matches to scrub, keeping the most confident of the overlapping ones, in order.
+    fn detect<'a>(&'a self, text: &str) -> Vec<(usize, usize, &'a Detector, f64)> {
+        let mut candidates = self
+            .detectors
+            .iter()
+            .flat_map(|detector| {
+                detector.regex.find_iter(text).filter_map(move |found| {
+                    let confidence = (detector.confidence)(found.as_str());
+                    (confidence >= self.min_confidence)
+                        .then(|| (found.start(), found.end(), detector, confidence))
+                })
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| {
+            b.3.partial_cmp(&a.3)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then((b.1 - b.0).cmp(&(a.1 - a.0)))
+        });
+
+        let mut accepted: Vec<(usize, usize, &Detector, f64)> = Vec::new();
+        for candidate in candidates {
+            if accepted
+                .iter()
+                .all(|other| candidate.1 <= other.0 || other.1 <= candidate.0)
+            {
+                accepted.push(candidate);
+            }
+        }
+        accepted.sort_by_key(|accepted| accepted.0);
+        accepted
+    }
+
+    fn hash(&self, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update(text.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Scrubs the strings of the value recursively, and returns whether the value is to be
+    /// removed.
+    fn scrub(&self, path: &str, value: &mut Value, findings: &mut Vec<Finding>) -> bool {
+        match value {
+            Value::Bytes(bytes) => {
+                let text = String::from_utf8_lossy(bytes).into_owned();
+                let matches = self.detect(&text);
+                if matches.is_empty() {
+                    return false;
+                }
+
+                let mut scrubbed = String::with_capacity(text.len());
+                let mut end = 0;
+                for (start, stop, detector, confidence) in matches {
+                    findings.push(Finding {
+                        field: path.to_owned(),
+                        detector: detector.name.clone(),
+                        confidence,
+                    });
+                    scrubbed.push_str(&text[end..start]);
+                    match self.mode {
+                        ScrubMode::Hash => scrubbed.push_str(&self.hash(&text[start..stop])),
+                        ScrubMode::Mask | ScrubMode::Remove => scrubbed.push_str(&self.mask),
+                    }
+                    end = stop;
+                }
+                scrubbed.push_str(&text[end..]);
+
+                *value = Value::from(scrubbed);
+                self.mode == ScrubMode::Remove
+            }
+            Value::Object(fields) => {
+                fields.retain(|key, value| {
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    !self.scrub(&path, value, findings)
+                });
+                false
+            }
+            Value::Array(values) => {
+                *values = std::mem::take(values)
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(index, mut value)| {
+                        let path = format!("{}[{}]", path, index);
+                        (!self.scrub(&path, &mut value, findings)).then(|| value)
+                    })
+                    .collect();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Scrubs the event, and returns the audit record of what was scrubbed, if anything.
+    fn scrub_event(&self, log: &mut LogEvent) -> Option<Event> {
+        let mut findings = Vec::new();
+        let fields = log.as_map_mut();
+        fields.retain(|key, value| !self.scrub(key, value, &mut findings));
+        if findings.is_empty() {
+            return None;
+        }
+
+        emit!(&PiiScrubbed {
+            count: findings.len()
+        });
+
+        let mut audit = LogEvent::default();
+        audit.insert(
+            "findings",
+            findings
+                .into_iter()
+                .map(|finding| {
+                    let mut fields = BTreeMap::new();
+                    fields.insert("field".to_owned(), Value::from(finding.field));
+                    fields.insert("detector".to_owned(), Value::from(finding.detector));
+                    fields.insert("confidence".to_owned(), Value::from(finding.confidence));
+                    Value::from(fields)
+                })
+                .collect::<Vec<_>>(),
+        );
+        audit.insert(
+            "mode",
+            match self.mode {
+                ScrubMode::Mask => "mask",
+                ScrubMode::Hash => "hash",
+                ScrubMode::Remove => "remove",
+            },
+        );
+        if let Some(timestamp) = log.get(log_schema().timestamp_key()).cloned() {
+            audit.insert(log_schema().timestamp_key(), timestamp);
+        }
+        Some(Event::from(audit))
+    }
+}
+
+impl SyncTransform for PiiScrubber {
+    fn transform(
+        &mut self,
+        mut event: Event,
+        output: &mut vector_core::transform::TransformOutputsBuf,
+    ) {
+        if let Some(audit) = self.scrub_event(event.as_mut_log()) {
+            output.push_named(AUDIT, audit);
+        }
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::transform::TransformOutputsBuf;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PiiScrubberConfig>();
+    }
+
+    fn run(config: &str, event: Event) -> (LogEvent, Vec<Event>) {
+        let config = toml::from_str::<PiiScrubberConfig>(config).unwrap();
+        let mut transform = PiiScrubber::new(&config).unwrap();
+        let mut outputs =
+            TransformOutputsBuf::new_with_capacity(config.outputs(&schema::Definition::empty()), 1);
+
+        transform.transform(event, &mut outputs);
+        let audit = outputs.drain_named(AUDIT).collect();
+        (outputs.drain().next().unwrap().into_log(), audit)
+    }
+
+    #[test]
+    fn luhn() {
+        assert!(luhn_confidence("4111 1111 1111 1111") > 0.9);
+        assert!(luhn_confidence("4111 1111 1111 1112") < 0.5);
+        assert!(luhn_confidence("1234") < 0.5);
+    }
+
+    #[test]
+    fn masks_nested_fields() {
+        let mut event = Event::from("paid with 4111-1111-1111-1111, receipt to jane@example.com");
+        event.as_mut_log().insert("user.ssn", "123-45-6789");
+        event
+            .as_mut_log()
+            .insert("user.ids[0]", "4111 1111 1111 1112");
+
+        let (log, audit) = run("", event);
+        assert_eq!(
+            log["message"],
+            "paid with [REDACTED], receipt to [REDACTED]".into()
+        );
+        assert_eq!(log["user.ssn"], "[REDACTED]".into());
+        // Numbers failing the checksum are left as is.
+        assert_eq!(log["user.ids[0]"], "4111 1111 1111 1112".into());
+
+        assert_eq!(audit.len(), 1);
+        let audit = audit[0].as_log();
+        assert_eq!(audit["mode"], "mask".into());
+        assert_eq!(audit["findings[0].field"], "message".into());
+        assert_eq!(audit["findings[0].detector"], "credit_card".into());
+        assert_eq!(audit["findings[1].detector"], "email".into());
+        assert_eq!(audit["findings[2].field"], "user.ssn".into());
+        assert_eq!(audit["findings[2].detector"], "us_ssn".into());
+    }
+
+    #[test]
+    fn hashes_dictionary_words() {
+        let config = r#"
+            detectors = []
+            dictionary = ["Project Falcon"]
+            mode = "hash"
+            salt = "pepper"
+        "#;
+        let (log, audit) = run(config, Event::from("status of project falcon"));
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"pepperproject falcon");
+        assert_eq!(
+            log["message"],
+            format!("status of {}", hex::encode(hasher.finalize())).into()
+        );
+        assert_eq!(
+            audit[0].as_log()["findings[0].detector"],
+            "dictionary".into()
+        );
+    }
+
+    #[test]
+    fn removes_fields() {
+        let config = r#"
+            mode = "remove"
+            patterns.api_key = "sk_[a-z0-9]{8}"
+        "#;
+        let mut event = Event::from("hello");
+        event
+            .as_mut_log()
+            .insert("headers.auth", "Bearer sk_abcd1234");
+        event.as_mut_log().insert("headers.host", "example");
+
+        let (log, audit) = run(config, event);
+        assert!(log.get("headers.auth").is_none());
+        assert_eq!(log["headers.host"], "example".into());
+        assert_eq!(audit[0].as_log()["findings[0].detector"], "api_key".into());
+    }
+
+    #[test]
+    fn forwards_clean_events() {
+        let (log, audit) = run("", Event::from("nothing to see here"));
+        assert_eq!(log["message"], "nothing to see here".into());
+        assert!(audit.is_empty());
+    }
+}
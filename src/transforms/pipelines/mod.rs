@@ -330,6 +330,7 @@ mod tests {
         let config: PipelinesConfig = config.try_into().unwrap();
         let outer = TransformOuter {
             inputs: Vec::<String>::new(),
+            limits: Default::default(),
             inner: Box::new(config),
         };
         let name = ComponentKey::from("foo");
@@ -1,11 +1,16 @@
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 
 use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, Utc};
 use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
+use vector_common::TimeZone;
+use vrl::{Program, Runtime};
 
-use crate::event::{LogEvent, Value};
+use crate::event::{Event, LogEvent, Value, VrlTarget};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -21,6 +26,9 @@ pub enum MergeStrategy {
     ShortestArray,
     LongestArray,
     FlatUnique,
+    /// A VRL expression computing the merged value from the value merged so far, at
+    /// `.accumulated`, and the value of the new event, at `.value`.
+    Vrl(String),
 }
 
 //------------------------------------------------------------------------------
@@ -508,11 +516,47 @@ impl ReduceValueMerger for MinNumberMerger {
 
 //------------------------------------------------------------------------------
 
-pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync {
+#[derive(Debug, Clone)]
+struct VrlMerger {
+    v: Value,
+    program: Arc<Program>,
+}
+
+impl VrlMerger {
+    #[allow(clippy::missing_const_for_fn)] // const cannot run destructor
+    fn new(v: Value, program: Arc<Program>) -> Self {
+        Self { v, program }
+    }
+}
+
+impl ReduceValueMerger for VrlMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let mut fields = BTreeMap::new();
+        fields.insert("accumulated".to_owned(), self.v.clone());
+        fields.insert("value".to_owned(), v);
+
+        let mut target = VrlTarget::new(Event::from(fields));
+        self.v = Runtime::default()
+            .resolve(&mut target, &self.program, &TimeZone::default())
+            .map_err(|error| error.to_string())?;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(k.as_str(), self.v);
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync + dyn_clone::DynClone {
     fn add(&mut self, v: Value) -> Result<(), String>;
     fn insert_into(self: Box<Self>, k: String, v: &mut LogEvent) -> Result<(), String>;
 }
 
+dyn_clone::clone_trait_object!(ReduceValueMerger);
+
 impl From<Value> for Box<dyn ReduceValueMerger> {
     fn from(v: Value) -> Self {
         match v {
@@ -591,6 +635,23 @@ pub(crate) fn get_value_merger(
         MergeStrategy::Discard => Ok(Box::new(DiscardMerger::new(v))),
         MergeStrategy::Retain => Ok(Box::new(RetainMerger::new(v))),
         MergeStrategy::FlatUnique => Ok(Box::new(FlatUniqueMerger::new(v))),
+        MergeStrategy::Vrl(_) => Err("VRL merge strategies must be compiled first".to_owned()),
+    }
+}
+
+/// A merge strategy with its VRL expression, if any, compiled.
+#[derive(Debug, Clone)]
+pub(crate) enum CompiledMergeStrategy {
+    Builtin(MergeStrategy),
+    Vrl(Arc<Program>),
+}
+
+impl CompiledMergeStrategy {
+    pub(crate) fn merger(&self, v: Value) -> Result<Box<dyn ReduceValueMerger>, String> {
+        match self {
+            Self::Builtin(strategy) => get_value_merger(v, strategy),
+            Self::Vrl(program) => Ok(Box::new(VrlMerger::new(v, Arc::clone(program)))),
+        }
     }
 }
 
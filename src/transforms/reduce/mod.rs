@@ -1,6 +1,7 @@
 use std::{
     collections::{hash_map, HashMap},
     pin::Pin,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -8,11 +9,13 @@ use async_stream::stream;
 use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use vector_core::ByteSizeOf;
+use vrl::diagnostic::Formatter;
 
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
-    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
+    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent, Value},
     internal_events::ReduceStaleEventFlushed,
     schema,
     transforms::{TaskTransform, Transform},
@@ -43,6 +46,14 @@ pub struct ReduceConfig {
     /// reduce.
     pub ends_when: Option<AnyCondition>,
     pub starts_when: Option<AnyCondition>,
+
+    /// The field under which `ends_when` and `starts_when` see the state
+    /// accumulated so far for the group of the event.
+    pub state_field: Option<String>,
+
+    /// The size in bytes of the accumulated events above which a reduce is
+    /// flushed.
+    pub max_bytes: Option<usize>,
 }
 
 inventory::submit! {
@@ -76,10 +87,12 @@ struct ReduceState {
     fields: HashMap<String, Box<dyn ReduceValueMerger>>,
     stale_since: Instant,
     metadata: EventMetadata,
+    byte_size: usize,
 }
 
 impl ReduceState {
-    fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) -> Self {
+    fn new(e: LogEvent, strategies: &IndexMap<String, CompiledMergeStrategy>) -> Self {
+        let byte_size = e.size_of();
         let (fields, metadata) = e.into_parts();
         Self {
             stale_since: Instant::now(),
@@ -87,7 +100,7 @@ impl ReduceState {
                 .into_iter()
                 .filter_map(|(k, v)| {
                     if let Some(strat) = strategies.get(&k) {
-                        match get_value_merger(v, strat) {
+                        match strat.merger(v) {
                             Ok(m) => Some((k, m)),
                             Err(error) => {
                                 warn!(message = "Failed to create merger.", field = ?k, %error);
@@ -100,10 +113,12 @@ impl ReduceState {
                 })
                 .collect(),
             metadata,
+            byte_size,
         }
     }
 
-    fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) {
+    fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<String, CompiledMergeStrategy>) {
+        self.byte_size += e.size_of();
         let (fields, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
@@ -112,7 +127,7 @@ impl ReduceState {
             match self.fields.entry(k) {
                 hash_map::Entry::Vacant(entry) => {
                     if let Some(strat) = strategy {
-                        match get_value_merger(v, strat) {
+                        match strat.merger(v) {
                             Ok(m) => {
                                 entry.insert(m);
                             }
@@ -134,6 +149,21 @@ impl ReduceState {
         self.stale_since = Instant::now();
     }
 
+    fn is_full(&self, max_bytes: Option<usize>) -> bool {
+        max_bytes.map_or(false, |max_bytes| self.byte_size >= max_bytes)
+    }
+
+    /// The fields the state would be flushed into, leaving it as is.
+    fn preview(&self) -> Value {
+        let mut event = LogEvent::default();
+        for (k, v) in &self.fields {
+            if let Err(error) = dyn_clone::clone_box(&**v).insert_into(k.clone(), &mut event) {
+                warn!(message = "Failed to merge values for field.", %error);
+            }
+        }
+        Value::from(event.into_parts().0)
+    }
+
     fn flush(mut self) -> LogEvent {
         let mut event = LogEvent::new_with_metadata(self.metadata);
         for (k, v) in self.fields.drain() {
@@ -151,10 +181,12 @@ pub struct Reduce {
     expire_after: Duration,
     flush_period: Duration,
     group_by: Vec<String>,
-    merge_strategies: IndexMap<String, MergeStrategy>,
+    merge_strategies: IndexMap<String, CompiledMergeStrategy>,
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
     ends_when: Option<Condition>,
     starts_when: Option<Condition>,
+    state_field: Option<String>,
+    max_bytes: Option<usize>,
 }
 
 impl Reduce {
@@ -178,14 +210,46 @@ impl Reduce {
             .transpose()?;
         let group_by = config.group_by.clone().into_iter().collect();
 
+        let functions = vrl_stdlib::all()
+            .into_iter()
+            .chain(enrichment::vrl_functions().into_iter())
+            .chain(vector_vrl_functions::vrl_functions())
+            .collect::<Vec<_>>();
+        let merge_strategies = config
+            .merge_strategies
+            .iter()
+            .map(|(field, strategy)| {
+                let strategy = match strategy {
+                    MergeStrategy::Vrl(source) => {
+                        let mut state = vrl::state::Compiler::new();
+                        state.set_external_context(enrichment_tables.clone());
+
+                        let program = vrl::compile_with_state(source, &functions, &mut state)
+                            .map_err(|diagnostics| {
+                                format!(
+                                    "invalid merge strategy for field {:?}:\n{}",
+                                    field,
+                                    Formatter::new(source, diagnostics)
+                                )
+                            })?;
+                        CompiledMergeStrategy::Vrl(Arc::new(program))
+                    }
+                    strategy => CompiledMergeStrategy::Builtin(strategy.clone()),
+                };
+                Ok((field.clone(), strategy))
+            })
+            .collect::<crate::Result<_>>()?;
+
         Ok(Reduce {
             expire_after: Duration::from_millis(config.expire_after_ms.unwrap_or(30000)),
             flush_period: Duration::from_millis(config.flush_period_ms.unwrap_or(1000)),
             group_by,
-            merge_strategies: config.merge_strategies.clone(),
+            merge_strategies,
             reduce_merge_states: HashMap::new(),
             ends_when,
             starts_when,
+            state_field: config.state_field.clone(),
+            max_bytes: config.max_bytes,
         })
     }
 
@@ -210,38 +274,66 @@ impl Reduce {
             .for_each(|(_, s)| output.push(Event::from(s.flush())));
     }
 
-    fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
+    /// Adds the event to the state of its group, flushing the state once it
+    /// holds `max_bytes`.
+    fn push_or_new_reduce_state(
+        &mut self,
+        output: &mut Vec<Event>,
+        event: LogEvent,
+        discriminant: Discriminant,
+    ) {
         match self.reduce_merge_states.entry(discriminant) {
             hash_map::Entry::Vacant(entry) => {
-                entry.insert(ReduceState::new(event, &self.merge_strategies));
+                let state = ReduceState::new(event, &self.merge_strategies);
+                if state.is_full(self.max_bytes) {
+                    output.push(state.flush().into());
+                } else {
+                    entry.insert(state);
+                }
             }
             hash_map::Entry::Occupied(mut entry) => {
                 entry.get_mut().add_event(event, &self.merge_strategies);
+                if entry.get().is_full(self.max_bytes) {
+                    output.push(entry.remove().flush().into());
+                }
             }
         }
     }
 
+    fn check(condition: &Option<Condition>, event: &Event) -> bool {
+        condition.as_ref().map(|c| c.check(event)).unwrap_or(false)
+    }
+
     fn transform_one(&mut self, output: &mut Vec<Event>, event: Event) {
-        let starts_here = self
-            .starts_when
-            .as_ref()
-            .map(|c| c.check(&event))
-            .unwrap_or(false);
-        let ends_here = self
-            .ends_when
-            .as_ref()
-            .map(|c| c.check(&event))
-            .unwrap_or(false);
+        let discriminant = Discriminant::from_log_event(event.as_log(), &self.group_by);
+
+        let (starts_here, ends_here) = match &self.state_field {
+            Some(field) if self.starts_when.is_some() || self.ends_when.is_some() => {
+                let state = self
+                    .reduce_merge_states
+                    .get(&discriminant)
+                    .map_or(Value::Null, ReduceState::preview);
+                let mut event = event.clone();
+                event.as_mut_log().insert(field.as_str(), state);
+                (
+                    Self::check(&self.starts_when, &event),
+                    Self::check(&self.ends_when, &event),
+                )
+            }
+            _ => (
+                Self::check(&self.starts_when, &event),
+                Self::check(&self.ends_when, &event),
+            ),
+        };
 
         let event = event.into_log();
-        let discriminant = Discriminant::from_log_event(&event, &self.group_by);
 
         if starts_here {
             if let Some(state) = self.reduce_merge_states.remove(&discriminant) {
                 output.push(state.flush().into());
             }
 
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(output, event, discriminant)
         } else if ends_here {
             output.push(match self.reduce_merge_states.remove(&discriminant) {
                 Some(mut state) => {
@@ -253,7 +345,7 @@ impl Reduce {
                     .into(),
             })
         } else {
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(output, event, discriminant)
         }
 
         self.flush_into(output);
@@ -565,4 +657,86 @@ merge_strategies.bar = "concat"
         assert_eq!(output_2["bar"], json!([2, 4, 6, 8, "done"]).into());
         assert_eq!(output_2.metadata(), &metadata_2);
     }
+
+    #[tokio::test]
+    async fn vrl_merge_strategies_and_state_conditions() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+state_field = "_reduce"
+merge_strategies.product = { vrl = "int!(.accumulated) * int!(.value)" }
+ends_when = "(int(._reduce.product) ?? 0) >= 6"
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap();
+        let reduce = reduce.into_task();
+
+        let inputs = (1..=5)
+            .map(|product| {
+                let mut e = LogEvent::from(format!("test message {}", product));
+                e.insert("product", product);
+                e.insert("request_id", "1");
+                e.into()
+            })
+            .collect::<Vec<Event>>();
+        let in_stream = Box::pin(stream::iter(inputs));
+        let mut out_stream = reduce.transform_events(in_stream);
+
+        // The fourth event ends the reduce, as the state it sees holds 1 * 2 * 3.
+        let output_1 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_1["message"], "test message 1".into());
+        assert_eq!(output_1["product"], 24.into());
+        assert!(output_1.get("_reduce").is_none());
+
+        let output_2 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_2["product"], 5.into());
+    }
+
+    #[tokio::test]
+    async fn invalid_vrl_merge_strategy() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+merge_strategies.foo = { vrl = "nope(" }
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await;
+        assert!(reduce.is_err());
+    }
+
+    #[tokio::test]
+    async fn max_bytes() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+max_bytes = 2500
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap();
+        let reduce = reduce.into_task();
+
+        let inputs = (1..=4)
+            .map(|counter| {
+                let mut e = LogEvent::from("x".repeat(1000));
+                e.insert("counter", counter);
+                e.insert("request_id", "1");
+                e.into()
+            })
+            .collect::<Vec<Event>>();
+        let in_stream = Box::pin(stream::iter(inputs));
+        let mut out_stream = reduce.transform_events(in_stream);
+
+        let output_1 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_1["counter"], Value::from(6));
+
+        let output_2 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_2["counter"], Value::from(4));
+    }
 }
@@ -9,8 +9,9 @@ use crate::{
         TransformDescription,
     },
     event::Event,
-    internal_events::RouteEventDiscarded,
+    internal_events::{RouteEventDiscarded, TemplateRenderingError},
     schema,
+    template::Template,
     transforms::Transform,
 };
 
@@ -19,6 +20,7 @@ use crate::{
 #[derive(Clone)]
 pub struct Route {
     conditions: Vec<(String, Condition)>,
+    weighted: Option<Weighted>,
 }
 
 impl Route {
@@ -28,7 +30,91 @@ impl Route {
             let condition = condition.build(&context.enrichment_tables)?;
             conditions.push((output_name.clone(), condition));
         }
-        Ok(Self { conditions })
+
+        if let Some(output_name) = config
+            .weights
+            .keys()
+            .find(|k| config.route.contains_key(*k))
+        {
+            return Err(format!(
+                "output {:?} can't be both in `route` and in `weights`",
+                output_name
+            )
+            .into());
+        }
+        let weighted = if config.weights.is_empty() {
+            if config.weight_key.is_some() {
+                return Err("`weight_key` requires `weights`".into());
+            }
+            None
+        } else {
+            Some(Weighted::new(&config.weights, config.weight_key.clone())?)
+        };
+
+        Ok(Self {
+            conditions,
+            weighted,
+        })
+    }
+}
+
+/// Sends each event to one of the weighted outputs, with a probability proportional to its
+/// weight. The events with the same key always go to the same output, and the events without
+/// a key are spread in turn.
+#[derive(Clone)]
+struct Weighted {
+    /// The outputs, with the sum of the weights up to and including theirs.
+    outputs: Vec<(String, u64)>,
+    total: u64,
+    key: Option<Template>,
+    counter: u64,
+}
+
+impl Weighted {
+    fn new(weights: &IndexMap<String, u64>, key: Option<Template>) -> crate::Result<Self> {
+        let mut total = 0u64;
+        let mut outputs = Vec::with_capacity(weights.len());
+        for (output_name, weight) in weights {
+            total = total
+                .checked_add(*weight)
+                .ok_or("the sum of `weights` overflows")?;
+            outputs.push((output_name.clone(), total));
+        }
+        if total == 0 {
+            return Err("at least one of `weights` must be positive".into());
+        }
+
+        Ok(Self {
+            outputs,
+            total,
+            key,
+            counter: 0,
+        })
+    }
+
+    fn pick(&mut self, event: &Event) -> &str {
+        let key = self.key.as_ref().and_then(|key| {
+            key.render_string(event)
+                .map_err(|error| {
+                    emit!(&TemplateRenderingError {
+                        error,
+                        field: Some("weight_key"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+        let point = match key {
+            Some(key) => seahash::hash(key.as_bytes()) % self.total,
+            None => {
+                let point = self.counter % self.total;
+                self.counter = self.counter.wrapping_add(1);
+                point
+            }
+        };
+
+        let index = self.outputs.partition_point(|(_, end)| *end <= point);
+        &self.outputs[index].0
     }
 }
 
@@ -47,6 +133,10 @@ impl SyncTransform for Route {
                 })
             }
         }
+
+        if let Some(weighted) = self.weighted.as_mut() {
+            output.push_named(weighted.pick(&event), event);
+        }
     }
 }
 
@@ -56,8 +146,16 @@ impl SyncTransform for Route {
 #[serde(deny_unknown_fields)]
 pub struct RouteConfig {
     // Deprecated name
-    #[serde(alias = "lanes")]
+    #[serde(alias = "lanes", default)]
     route: IndexMap<String, AnyCondition>,
+
+    /// Outputs each receiving a share of the events proportional to its weight.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    weights: IndexMap<String, u64>,
+
+    /// The key by which the events are assigned to the weighted outputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    weight_key: Option<Template>,
 }
 
 inventory::submit! {
@@ -72,6 +170,8 @@ impl GenerateConfig for RouteConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             route: IndexMap::new(),
+            weights: IndexMap::new(),
+            weight_key: None,
         })
         .unwrap()
     }
@@ -92,6 +192,7 @@ impl TransformConfig for RouteConfig {
     fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
         self.route
             .keys()
+            .chain(self.weights.keys())
             .map(|output_name| Output::from((output_name, DataType::all())))
             .collect()
     }
@@ -267,6 +368,79 @@ mod test {
         }
     }
 
+    fn weighted_outputs(config: &str, events: impl Iterator<Item = Event>) -> Vec<Vec<Event>> {
+        let config = toml::from_str::<RouteConfig>(config).unwrap();
+        let mut transform = Route::new(&config, &Default::default()).unwrap();
+        let mut outputs =
+            TransformOutputsBuf::new_with_capacity(config.outputs(&schema::Definition::empty()), 1);
+
+        for event in events {
+            transform.transform(event, &mut outputs);
+        }
+        config
+            .weights
+            .keys()
+            .map(|output_name| outputs.drain_named(output_name).collect())
+            .collect()
+    }
+
+    #[test]
+    fn route_weighted_in_turn() {
+        let outputs = weighted_outputs(
+            r#"
+            weights.canary = 1
+            weights.production = 3
+        "#,
+            (0..8).map(|_| Event::from("hello world")),
+        );
+
+        assert_eq!(outputs[0].len(), 2);
+        assert_eq!(outputs[1].len(), 6);
+    }
+
+    #[test]
+    fn route_weighted_by_key() {
+        let config = r#"
+            weights.canary = 5
+            weights.production = 95
+            weight_key = "{{ user_id }}"
+        "#;
+        let events = || {
+            (0..1000).map(|id| {
+                let mut event = Event::from("hello world");
+                event.as_mut_log().insert("user_id", id);
+                event
+            })
+        };
+
+        let outputs = weighted_outputs(config, events());
+        assert!((20..=80).contains(&outputs[0].len()));
+        assert_eq!(outputs[0].len() + outputs[1].len(), 1000);
+
+        // The events with the same key always go to the same output.
+        let user_ids = |events: &[Event]| {
+            events
+                .iter()
+                .map(|event| event.as_log()["user_id"].clone())
+                .collect::<Vec<_>>()
+        };
+        let again = weighted_outputs(config, events());
+        assert_eq!(user_ids(&again[0]), user_ids(&outputs[0]));
+    }
+
+    #[test]
+    fn route_weighted_output_names_conflict() {
+        let config = toml::from_str::<RouteConfig>(
+            r#"
+            route.canary = '.message == "hello world"'
+            weights.canary = 1
+        "#,
+        )
+        .unwrap();
+
+        assert!(Route::new(&config, &Default::default()).is_err());
+    }
+
     #[tokio::test]
     async fn route_metrics_with_output_tag() {
         init_test();
@@ -0,0 +1,349 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::Event,
+    internal_events::{AdaptiveSampleEventDiscarded, TemplateRenderingError},
+    schema,
+    template::Template,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SampleAdaptiveConfig {
+    /// The number of events per second to forward, across all the keys.
+    pub target_rate: f64,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: f64,
+    pub key_field: Option<Template>,
+    #[serde(default = "default_rate_field")]
+    pub rate_field: String,
+    pub exclude: Option<AnyCondition>,
+}
+
+const fn default_window_secs() -> f64 {
+    10.0
+}
+
+fn default_rate_field() -> String {
+    "sample_rate".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<SampleAdaptiveConfig>("sample_adaptive")
+}
+
+impl GenerateConfig for SampleAdaptiveConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            target_rate: 100.0,
+            window_secs: default_window_secs(),
+            key_field: None,
+            rate_field: default_rate_field(),
+            exclude: None::<AnyCondition>,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sample_adaptive")]
+impl TransformConfig for SampleAdaptiveConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        if !(self.target_rate > 0.0 && self.window_secs > 0.0) {
+            return Err(Box::new(ConfigError::NonPositive));
+        }
+        if !self.window_secs.is_finite() {
+            return Err(Box::new(ConfigError::InfiniteWindow));
+        }
+
+        Ok(Transform::function(SampleAdaptive::new(
+            self.target_rate * self.window_secs,
+            Duration::from_secs_f64(self.window_secs),
+            self.key_field.clone(),
+            self.rate_field.clone(),
+            self.exclude
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()?,
+        )))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "sample_adaptive"
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ConfigError {
+    #[snafu(display("`target_rate` and `window_secs` must be positive"))]
+    NonPositive,
+    #[snafu(display("`window_secs` must be finite"))]
+    InfiniteWindow,
+}
+
+#[derive(Clone, Debug)]
+struct KeyState {
+    /// The number of events with the key during the current window.
+    count: u64,
+    /// The probability to forward the events with the key, as of the previous window.
+    probability: f64,
+    /// For the keys without history, the number of events forwarded until the next window.
+    allowance: Option<f64>,
+    /// The accumulated probabilities not yet spent on forwarding an event, which spreads the
+    /// forwarded events evenly instead of drawing them at random.
+    credit: f64,
+}
+
+impl KeyState {
+    /// The keys without history are forwarded up to `share` events, their share of the budget,
+    /// until the next window.
+    const fn new(share: f64) -> Self {
+        Self {
+            count: 0,
+            probability: 1.0,
+            allowance: Some(share),
+            credit: 0.0,
+        }
+    }
+}
+
+/// Forwards about `budget` events per window, sharing it evenly between the keys: the keys with
+/// fewer events than their share are forwarded whole, and the others are sampled down to it, by
+/// the probabilities computed from the counts of the previous window.
+#[derive(Clone)]
+pub struct SampleAdaptive {
+    budget: f64,
+    window: Duration,
+    key_field: Option<Template>,
+    rate_field: String,
+    exclude: Option<Condition>,
+    keys: HashMap<Option<String>, KeyState>,
+    window_end: Option<Instant>,
+}
+
+impl SampleAdaptive {
+    pub fn new(
+        budget: f64,
+        window: Duration,
+        key_field: Option<Template>,
+        rate_field: String,
+        exclude: Option<Condition>,
+    ) -> Self {
+        Self {
+            budget,
+            window,
+            key_field,
+            rate_field,
+            exclude,
+            keys: HashMap::new(),
+            window_end: None,
+        }
+    }
+
+    fn sample(&mut self, output: &mut OutputBuffer, mut event: Event, now: Instant) {
+        if let Some(condition) = self.exclude.as_ref() {
+            if condition.check(&event) {
+                output.push(event);
+                return;
+            }
+        }
+
+        match self.window_end {
+            Some(window_end) if now < window_end => {}
+            Some(_) => {
+                self.rebalance();
+                self.window_end = Some(now + self.window);
+            }
+            None => self.window_end = Some(now + self.window),
+        }
+
+        let key = self.key_field.as_ref().and_then(|template| {
+            template
+                .render_string(&event)
+                .map_err(|error| {
+                    emit!(&TemplateRenderingError {
+                        error,
+                        field: Some("key_field"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+
+        let share = self.budget / (self.keys.len() + 1) as f64;
+        let state = self.keys.entry(key).or_insert_with(|| KeyState::new(share));
+        state.count += 1;
+        let allowed = state
+            .allowance
+            .map_or(true, |allowance| state.count as f64 <= allowance);
+        if allowed {
+            state.credit += state.probability;
+        }
+
+        if allowed && state.credit >= 1.0 {
+            state.credit -= 1.0;
+            let rate = 1.0 / state.probability;
+            event.as_mut_log().insert(self.rate_field.as_str(), rate);
+            output.push(event);
+        } else {
+            emit!(&AdaptiveSampleEventDiscarded);
+        }
+    }
+
+    /// Computes the probabilities of the keys for the next window from their counts, forgetting
+    /// the keys without events.
+    fn rebalance(&mut self) {
+        self.keys.retain(|_, state| state.count > 0);
+
+        let mut keys = self.keys.values_mut().collect::<Vec<_>>();
+        keys.sort_unstable_by_key(|state| state.count);
+
+        let mut remaining = self.budget;
+        let total = keys.len();
+        for (index, state) in keys.into_iter().enumerate() {
+            let share = remaining / (total - index) as f64;
+            let count = state.count as f64;
+            if count <= share {
+                state.probability = 1.0;
+                remaining -= count;
+            } else {
+                state.probability = share / count;
+                remaining -= share;
+            }
+            state.count = 0;
+            state.allowance = None;
+        }
+    }
+}
+
+impl FunctionTransform for SampleAdaptive {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        self.sample(output, event, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{LogEvent, Value};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SampleAdaptiveConfig>();
+    }
+
+    fn event(key: &str) -> Event {
+        let mut log = LogEvent::from("message");
+        log.insert("service", key);
+        log.into()
+    }
+
+    fn run(
+        sampler: &mut SampleAdaptive,
+        counts: &[(&str, usize)],
+        now: Instant,
+    ) -> HashMap<String, Vec<Event>> {
+        let mut forwarded = HashMap::<String, Vec<Event>>::new();
+        for (key, count) in counts {
+            for _ in 0..*count {
+                let mut buf = OutputBuffer::with_capacity(1);
+                sampler.sample(&mut buf, event(key), now);
+                forwarded
+                    .entry(key.to_string())
+                    .or_default()
+                    .extend(buf.into_events());
+            }
+        }
+        forwarded
+    }
+
+    #[test]
+    fn shares_the_budget_between_keys() {
+        let mut sampler = SampleAdaptive::new(
+            80.0,
+            Duration::from_secs(10),
+            Some(Template::try_from("{{ service }}").unwrap()),
+            "sample_rate".to_owned(),
+            None,
+        );
+        let start = Instant::now();
+        let counts = [("quiet", 16), ("loud", 1024), ("louder", 2048)];
+
+        // Until the first window ends, the keys are forwarded up to their share as they come.
+        let forwarded = run(&mut sampler, &counts, start);
+        assert_eq!(forwarded["quiet"].len(), 16);
+        assert_eq!(forwarded["loud"].len(), 40);
+        assert_eq!(forwarded["louder"].len(), 26);
+
+        let forwarded = run(&mut sampler, &counts, start + Duration::from_secs(10));
+        assert_eq!(forwarded["quiet"].len(), 16);
+        assert_eq!(forwarded["loud"].len(), 32);
+        assert_eq!(forwarded["louder"].len(), 32);
+
+        assert_eq!(
+            forwarded["quiet"][0].as_log().get("sample_rate"),
+            Some(&Value::from(1.0))
+        );
+        assert_eq!(
+            forwarded["loud"][0].as_log().get("sample_rate"),
+            Some(&Value::from(32.0))
+        );
+        assert_eq!(
+            forwarded["louder"][0].as_log().get("sample_rate"),
+            Some(&Value::from(64.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_infinite_windows() {
+        let config = SampleAdaptiveConfig {
+            target_rate: 100.0,
+            window_secs: f64::INFINITY,
+            key_field: None,
+            rate_field: default_rate_field(),
+            exclude: None,
+        };
+        assert!(config.build(&TransformContext::default()).await.is_err());
+    }
+
+    #[test]
+    fn forgets_keys_without_events() {
+        let mut sampler = SampleAdaptive::new(
+            16.0,
+            Duration::from_secs(1),
+            Some(Template::try_from("{{ service }}").unwrap()),
+            "sample_rate".to_owned(),
+            None,
+        );
+        let start = Instant::now();
+
+        run(&mut sampler, &[("a", 128), ("b", 128)], start);
+        let forwarded = run(&mut sampler, &[("a", 128)], start + Duration::from_secs(1));
+        assert_eq!(forwarded["a"].len(), 8);
+
+        // Once `b` is forgotten, `a` gets the whole budget.
+        let forwarded = run(&mut sampler, &[("a", 128)], start + Duration::from_secs(2));
+        assert_eq!(sampler.keys.len(), 1);
+        assert_eq!(forwarded["a"].len(), 16);
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use value::Kind;
+use vector_common::TimeZone;
+use vector_core::transform::SyncTransform;
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, LogEvent, Value},
+    internal_events::SchemaEnforceViolation,
+    schema,
+    transforms::Transform,
+    types::Conversion,
+};
+
+const VIOLATIONS: &str = "violations";
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SchemaEnforceConfig {
+    /// The schema of the fields by path.
+    pub fields: IndexMap<String, FieldSchema>,
+    #[serde(default)]
+    pub action: Action,
+    /// The field holding the violations of the events sent to the `violations` output.
+    #[serde(default = "default_violations_field")]
+    pub violations_field: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSchema {
+    #[serde(default)]
+    pub required: bool,
+    /// The kinds the value can have, any kind if empty.
+    #[serde(default)]
+    pub kind: Vec<FieldKind>,
+    /// The values allowed.
+    #[serde(rename = "enum")]
+    pub values: Option<Vec<Value>>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// The value set when repairing a field that is missing or can't be coerced.
+    pub default: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    Null,
+    Array,
+    Object,
+}
+
+impl FieldKind {
+    fn add_to(self, kind: Kind) -> Kind {
+        match self {
+            FieldKind::Bytes => kind.or_bytes(),
+            FieldKind::Integer => kind.or_integer(),
+            FieldKind::Float => kind.or_float(),
+            FieldKind::Boolean => kind.or_boolean(),
+            FieldKind::Timestamp => kind.or_timestamp(),
+            FieldKind::Null => kind.or_null(),
+            FieldKind::Array => kind.or_array(value::kind::Collection::any()),
+            FieldKind::Object => kind.or_object(value::kind::Collection::any()),
+        }
+    }
+}
+
+/// What becomes of the events violating the schema.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// The events are discarded.
+    Drop,
+    /// The violations are repaired where possible, and the events that still violate the
+    /// schema are sent to the `violations` output.
+    Repair,
+    /// The events are sent to the `violations` output.
+    Route,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Self::Route
+    }
+}
+
+fn default_violations_field() -> String {
+    "schema_violations".to_owned()
+}
+
+inventory::submit! {
+    TransformDescription::new::<SchemaEnforceConfig>("schema_enforce")
+}
+
+impl GenerateConfig for SchemaEnforceConfig {
+    fn generate_config() -> toml::Value {
+        let mut fields = IndexMap::new();
+        fields.insert(
+            "status".to_owned(),
+            FieldSchema {
+                required: true,
+                kind: vec![FieldKind::Integer],
+                min: Some(100.0),
+                max: Some(599.0),
+                ..Default::default()
+            },
+        );
+
+        toml::Value::try_from(Self {
+            fields,
+            action: Action::Route,
+            violations_field: default_violations_field(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "schema_enforce")]
+impl TransformConfig for SchemaEnforceConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(SchemaEnforce::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        let mut outputs = vec![Output::default(DataType::Log)];
+        if self.action != Action::Drop {
+            outputs.push(Output::from((VIOLATIONS, DataType::Log)));
+        }
+        outputs
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "schema_enforce"
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FieldRule {
+    path: String,
+    required: bool,
+    kind: Option<Kind>,
+    values: Option<Vec<Value>>,
+    min: Option<f64>,
+    max: Option<f64>,
+    default: Option<Value>,
+}
+
+impl FieldRule {
+    fn new(path: &str, schema: &FieldSchema) -> Self {
+        let kind = (!schema.kind.is_empty()).then(|| {
+            schema
+                .kind
+                .iter()
+                .fold(Kind::empty(), |kind, field_kind| field_kind.add_to(kind))
+        });
+
+        Self {
+            path: path.to_owned(),
+            required: schema.required,
+            kind,
+            values: schema.values.clone(),
+            min: schema.min,
+            max: schema.max,
+            default: schema.default.clone(),
+        }
+    }
+
+    /// Describes how the value violates the schema, if it does.
+    fn violation(&self, value: Option<&Value>) -> Option<String> {
+        let value = match value {
+            Some(value) => value,
+            None if self.required => return Some("missing required field".to_owned()),
+            None => return None,
+        };
+
+        if let Some(kind) = &self.kind {
+            if !kind.is_superset(&Kind::from(value)) {
+                return Some(format!("expected {}, found {}", kind, value.kind_str()));
+            }
+        }
+        if let Some(values) = &self.values {
+            if !values.contains(value) {
+                return Some(format!(
+                    "{:?} is not one of the allowed values",
+                    value.to_string_lossy()
+                ));
+            }
+        }
+        let number = match value {
+            Value::Integer(number) => Some(*number as f64),
+            Value::Float(number) => Some(number.into_inner()),
+            _ => None,
+        };
+        if let Some(number) = number {
+            if self.min.map_or(false, |min| number < min)
+                || self.max.map_or(false, |max| number > max)
+            {
+                return Some(format!("{} is out of range", number));
+            }
+        }
+        None
+    }
+
+    /// Finds the value satisfying the schema closest to the value: the value coerced to one of
+    /// the kinds and clamped into the range, or else the default.
+    fn repair(&self, value: Option<&Value>) -> Option<Value> {
+        let coerced = value.and_then(|value| match &self.kind {
+            Some(kind) if !kind.is_superset(&Kind::from(value)) => coerce(value, kind),
+            _ => Some(value.clone()),
+        });
+
+        coerced
+            .map(|value| self.clamp(value))
+            .into_iter()
+            .chain(self.default.clone())
+            .find(|value| self.violation(Some(value)).is_none())
+    }
+
+    fn clamp(&self, value: Value) -> Value {
+        let number = match &value {
+            Value::Integer(number) => *number as f64,
+            Value::Float(number) => number.into_inner(),
+            _ => return value,
+        };
+        let (bound, round): (f64, fn(f64) -> f64) = match (self.min, self.max) {
+            (Some(min), _) if number < min => (min, f64::ceil),
+            (_, Some(max)) if number > max => (max, f64::floor),
+            _ => return value,
+        };
+        match value {
+            Value::Integer(_) => Value::Integer(round(bound) as i64),
+            _ => Value::from(bound),
+        }
+    }
+}
+
+/// Parses the string form of a scalar value into the first of the scalar kinds that succeeds.
+fn coerce(value: &Value, kind: &Kind) -> Option<Value> {
+    let bytes = match value {
+        Value::Bytes(bytes) => bytes.clone(),
+        Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Timestamp(_) => {
+            Bytes::from(value.to_string_lossy())
+        }
+        _ => return None,
+    };
+
+    [
+        (kind.contains_integer(), Conversion::Integer),
+        (kind.contains_float(), Conversion::Float),
+        (kind.contains_boolean(), Conversion::Boolean),
+        (
+            kind.contains_timestamp(),
+            Conversion::Timestamp(TimeZone::default()),
+        ),
+        (kind.contains_bytes(), Conversion::Bytes),
+    ]
+    .iter()
+    .filter(|(allowed, _)| *allowed)
+    .find_map(|(_, conversion)| conversion.convert(bytes.clone()).ok())
+}
+
+#[derive(Clone, Debug)]
+pub struct SchemaEnforce {
+    rules: Vec<FieldRule>,
+    action: Action,
+    violations_field: String,
+}
+
+impl SchemaEnforce {
+    pub fn new(config: &SchemaEnforceConfig) -> Self {
+        Self {
+            rules: config
+                .fields
+                .iter()
+                .map(|(path, schema)| FieldRule::new(path, schema))
+                .collect(),
+            action: config.action,
+            violations_field: config.violations_field.clone(),
+        }
+    }
+
+    /// Checks the fields of the event, repairing them if configured to, and returns the
+    /// violations left.
+    fn enforce(&self, log: &mut LogEvent) -> Vec<Value> {
+        let mut violations = Vec::new();
+        for rule in &self.rules {
+            let value = log.get(rule.path.as_str());
+            let error = match rule.violation(value) {
+                Some(error) => error,
+                None => {
+                    if self.action == Action::Repair && value.is_none() {
+                        if let Some(default) = rule.default.clone() {
+                            log.insert(rule.path.as_str(), default);
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            if self.action == Action::Repair {
+                if let Some(repaired) = rule.repair(value) {
+                    log.insert(rule.path.as_str(), repaired);
+                    continue;
+                }
+            }
+
+            let mut violation = BTreeMap::new();
+            violation.insert("field".to_owned(), Value::from(rule.path.clone()));
+            violation.insert("error".to_owned(), Value::from(error));
+            violations.push(Value::from(violation));
+        }
+        violations
+    }
+}
+
+impl SyncTransform for SchemaEnforce {
+    fn transform(
+        &mut self,
+        mut event: Event,
+        output: &mut vector_core::transform::TransformOutputsBuf,
+    ) {
+        let violations = self.enforce(event.as_mut_log());
+        if violations.is_empty() {
+            output.push(event);
+            return;
+        }
+
+        emit!(&SchemaEnforceViolation {
+            count: violations.len(),
+            dropped: self.action == Action::Drop,
+        });
+        if self.action != Action::Drop {
+            event
+                .as_mut_log()
+                .insert(self.violations_field.as_str(), violations);
+            output.push_named(VIOLATIONS, event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::transform::TransformOutputsBuf;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SchemaEnforceConfig>();
+    }
+
+    fn run(action: &str, event: Event) -> (Vec<Event>, Vec<Event>) {
+        let config = toml::from_str::<SchemaEnforceConfig>(&format!(
+            r#"
+            action = "{}"
+
+            [fields.status]
+            required = true
+            kind = ["integer"]
+            min = 100.0
+            max = 599.0
+
+            [fields.level]
+            kind = ["bytes"]
+            enum = ["info", "warn", "error"]
+            default = "info"
+            "#,
+            action
+        ))
+        .unwrap();
+        let mut transform = SchemaEnforce::new(&config);
+        let mut outputs =
+            TransformOutputsBuf::new_with_capacity(config.outputs(&schema::Definition::empty()), 1);
+
+        transform.transform(event, &mut outputs);
+        let violations = if config.action == Action::Drop {
+            Vec::new()
+        } else {
+            outputs.drain_named(VIOLATIONS).collect()
+        };
+        (outputs.drain().collect(), violations)
+    }
+
+    fn event(status: impl Into<Value>, level: Option<&str>) -> Event {
+        let mut event = Event::from("hello");
+        event.as_mut_log().insert("status", status.into());
+        if let Some(level) = level {
+            event.as_mut_log().insert("level", level);
+        }
+        event
+    }
+
+    #[test]
+    fn forwards_valid_events() {
+        let (output, violations) = run("drop", event(200, Some("warn")));
+        assert_eq!(output.len(), 1);
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn drops_invalid_events() {
+        let (output, _) = run("drop", event("200", None));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn routes_invalid_events() {
+        let (output, violations) = run("route", event(700, Some("debug")));
+        assert!(output.is_empty());
+        assert_eq!(violations.len(), 1);
+
+        let log = violations[0].as_log();
+        assert_eq!(log["status"], 700.into());
+        assert_eq!(log["schema_violations[0].field"], "status".into());
+        assert_eq!(
+            log["schema_violations[0].error"],
+            "700 is out of range".into()
+        );
+        assert_eq!(log["schema_violations[1].field"], "level".into());
+    }
+
+    #[test]
+    fn repairs_invalid_events() {
+        let (output, violations) = run("repair", event("200", None));
+        assert!(violations.is_empty());
+        assert_eq!(output[0].as_log()["status"], 200.into());
+        assert_eq!(output[0].as_log()["level"], "info".into());
+
+        let (output, _) = run("repair", event(700, Some("debug")));
+        assert_eq!(output[0].as_log()["status"], 599.into());
+        assert_eq!(output[0].as_log()["level"], "info".into());
+
+        // The events that can't be repaired are routed with their violations.
+        let (output, violations) = run("repair", event("ok", Some("warn")));
+        assert!(output.is_empty());
+        assert_eq!(
+            violations[0].as_log()["schema_violations[0].error"],
+            "expected integer, found string".into()
+        );
+    }
+}
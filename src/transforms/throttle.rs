@@ -1,8 +1,16 @@
-use std::{num::NonZeroU32, pin::Pin, time::Duration};
+use std::{
+    collections::HashMap,
+    mem,
+    num::NonZeroU32,
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use async_stream::stream;
 use futures::{stream, Stream, StreamExt};
 use governor::{clock, Quota, RateLimiter};
+use redis::aio::ConnectionManager;
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 
@@ -10,7 +18,7 @@ use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, Output, TransformConfig, TransformContext, TransformDescription},
     event::Event,
-    internal_events::{TemplateRenderingError, ThrottleEventDiscarded},
+    internal_events::{TemplateRenderingError, ThrottleBackendError, ThrottleEventDiscarded},
     schema,
     template::Template,
     transforms::{TaskTransform, Transform},
@@ -23,6 +31,46 @@ pub struct ThrottleConfig {
     window_secs: f64,
     key_field: Option<Template>,
     exclude: Option<AnyCondition>,
+    /// Shares the counters of the keys between the instances using the same backend.
+    backend: Option<BackendConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackendConfig {
+    Redis(RedisBackendConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RedisBackendConfig {
+    url: String,
+    #[serde(default = "default_key_prefix")]
+    key_prefix: String,
+    /// How long to wait for the backend before falling back to the local rate limiter.
+    #[serde(default = "default_timeout_ms")]
+    timeout_ms: u64,
+    /// How long to wait before connecting again to an unreachable backend.
+    #[serde(default = "default_retry_secs")]
+    retry_secs: f64,
+    /// How often the counts are synced with the backend.
+    #[serde(default = "default_sync_interval_ms")]
+    sync_interval_ms: u64,
+}
+
+fn default_key_prefix() -> String {
+    "vector:throttle:".to_owned()
+}
+
+const fn default_timeout_ms() -> u64 {
+    100
+}
+
+const fn default_retry_secs() -> f64 {
+    5.0
+}
+
+const fn default_sync_interval_ms() -> u64 {
+    100
 }
 
 inventory::submit! {
@@ -58,6 +106,7 @@ pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
     key_field: Option<Template>,
     exclude: Option<Condition>,
     clock: C,
+    shared: Option<SharedCounters>,
 }
 
 impl<C, I> Throttle<C, I>
@@ -88,6 +137,11 @@ where
             .as_ref()
             .map(|condition| condition.build(&context.enrichment_tables))
             .transpose()?;
+        let shared = config
+            .backend
+            .as_ref()
+            .map(|backend| SharedCounters::new(backend, config.window_secs, threshold))
+            .transpose()?;
 
         Ok(Self {
             quota,
@@ -95,10 +149,242 @@ where
             flush_keys_interval,
             key_field: config.key_field.clone(),
             exclude,
+            shared,
         })
     }
 }
 
+/// Counts the events of each key in the backend, over windows aligned on the epoch so that all
+/// the instances sharing the backend agree on them. The events are let through or discarded right
+/// away, from the counts last synced with the backend and those counted since, the counts being
+/// synced in the background every `sync_interval_ms`.
+#[derive(Clone)]
+struct SharedCounters {
+    client: redis::Client,
+    key_prefix: String,
+    window: Duration,
+    threshold: NonZeroU32,
+    timeout: Duration,
+    retry: Duration,
+    sync_interval: Duration,
+}
+
+impl SharedCounters {
+    fn new(
+        backend: &BackendConfig,
+        window_secs: f64,
+        threshold: NonZeroU32,
+    ) -> crate::Result<Self> {
+        let BackendConfig::Redis(config) = backend;
+        if !config.retry_secs.is_finite() || config.retry_secs < 0.0 {
+            return Err("`backend.retry_secs` must not be negative".into());
+        }
+        if config.sync_interval_ms == 0 {
+            return Err("`backend.sync_interval_ms` must be non-zero".into());
+        }
+
+        Ok(Self {
+            client: redis::Client::open(config.url.as_str())?,
+            key_prefix: config.key_prefix.clone(),
+            window: Duration::from_secs_f64(window_secs),
+            threshold,
+            timeout: Duration::from_millis(config.timeout_ms),
+            retry: Duration::from_secs_f64(config.retry_secs),
+            sync_interval: Duration::from_millis(config.sync_interval_ms),
+        })
+    }
+
+    fn window_ms(&self) -> u128 {
+        self.window.as_millis().max(1)
+    }
+
+    /// The index of the current window since the epoch.
+    fn current_window(&self) -> u128 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        now.as_millis() / self.window_ms()
+    }
+
+    /// Starts syncing the counts with the backend, until the returned counts are dropped.
+    fn start(&self) -> SharedCounts {
+        let state = Arc::new(Mutex::new(SharedState::default()));
+        tokio::spawn(self.clone().sync(Arc::downgrade(&state)));
+        SharedCounts {
+            counters: self.clone(),
+            state,
+        }
+    }
+
+    async fn sync(self, state: Weak<Mutex<SharedState>>) {
+        let mut connection = None;
+        let mut retry_at: Option<Instant> = None;
+        let mut interval = tokio::time::interval(self.sync_interval);
+        loop {
+            interval.tick().await;
+            let window = self.current_window();
+            let batch = match state.upgrade() {
+                Some(state) => state.lock().expect("throttle state poisoned").take(window),
+                None => break,
+            };
+            if batch.is_empty() {
+                continue;
+            }
+
+            // The backend isn't tried again until the retry delay elapsed since it last failed.
+            let result = if retry_at.map_or(false, |at| Instant::now() < at) {
+                None
+            } else {
+                match self.send(&mut connection, window, &batch).await {
+                    Ok(totals) => Some(totals),
+                    Err(error) => {
+                        emit!(&ThrottleBackendError { error });
+                        connection = None;
+                        retry_at = Some(Instant::now() + self.retry);
+                        None
+                    }
+                }
+            };
+            match state.upgrade() {
+                Some(state) => {
+                    let mut state = state.lock().expect("throttle state poisoned");
+                    match result {
+                        Some(totals) => state.synced(window, &batch, &totals),
+                        None => state.unavailable(),
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Adds the counts to those of the backend, returning the totals of the keys.
+    async fn send(
+        &self,
+        connection: &mut Option<ConnectionManager>,
+        window: u128,
+        batch: &[(Option<String>, u64)],
+    ) -> Result<Vec<u64>, String> {
+        let window_ms = self.window_ms();
+        let sending = async {
+            if connection.is_none() {
+                let connected = ConnectionManager::new(self.client.clone())
+                    .await
+                    .map_err(|error| error.to_string())?;
+                *connection = Some(connected);
+            }
+
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+            for (key, count) in batch {
+                let counter = format!(
+                    "{}{}:{}",
+                    self.key_prefix,
+                    key.as_deref().unwrap_or_default(),
+                    window
+                );
+                pipe.incr(&counter, *count)
+                    .pexpire(&counter, window_ms as usize)
+                    .ignore();
+            }
+            pipe.query_async::<_, Vec<u64>>(connection.as_mut().expect("connected"))
+                .await
+                .map_err(|error| error.to_string())
+        };
+        tokio::time::timeout(self.timeout, sending)
+            .await
+            .unwrap_or_else(|_| Err("Timed out.".to_owned()))
+    }
+}
+
+/// The counts of the keys, checked by the transform and synced by the background task.
+struct SharedCounts {
+    counters: SharedCounters,
+    state: Arc<Mutex<SharedState>>,
+}
+
+impl SharedCounts {
+    /// Counts the event of the key, returning whether it is within the threshold, or `None` while
+    /// the backend is unavailable.
+    fn check(&self, key: &Option<String>) -> Option<bool> {
+        self.state.lock().expect("throttle state poisoned").check(
+            key,
+            self.counters.current_window(),
+            u64::from(self.counters.threshold.get()),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct SharedState {
+    /// Whether the last sync succeeded, the events being rate limited locally otherwise.
+    available: bool,
+    counts: HashMap<Option<String>, KeyCounts>,
+}
+
+#[derive(Debug, Default)]
+struct KeyCounts {
+    window: u128,
+    /// The count of the key in the backend, as of the last sync.
+    synced: u64,
+    /// The events counted since, being synced.
+    syncing: u64,
+    /// The events counted since, to be synced.
+    pending: u64,
+}
+
+impl SharedState {
+    fn check(&mut self, key: &Option<String>, window: u128, threshold: u64) -> Option<bool> {
+        if !self.counts.contains_key(key) {
+            self.counts.insert(key.clone(), KeyCounts::default());
+        }
+        let counts = self.counts.get_mut(key).expect("counts were inserted");
+        if counts.window != window {
+            *counts = KeyCounts {
+                window,
+                ..Default::default()
+            };
+        }
+        // The events are counted while the backend is unavailable too, for the sync to try it
+        // again.
+        counts.pending += 1;
+        self.available
+            .then(|| counts.synced + counts.syncing + counts.pending <= threshold)
+    }
+
+    /// Takes the counts to sync, forgetting those of the past windows.
+    fn take(&mut self, window: u128) -> Vec<(Option<String>, u64)> {
+        self.counts.retain(|_, counts| counts.window == window);
+        self.counts
+            .iter_mut()
+            .filter(|(_, counts)| counts.pending > 0)
+            .map(|(key, counts)| {
+                counts.syncing = mem::take(&mut counts.pending);
+                (key.clone(), counts.syncing)
+            })
+            .collect()
+    }
+
+    fn synced(&mut self, window: u128, batch: &[(Option<String>, u64)], totals: &[u64]) {
+        self.available = true;
+        for ((key, _), total) in batch.iter().zip(totals) {
+            if let Some(counts) = self.counts.get_mut(key) {
+                if counts.window == window {
+                    counts.synced = *total;
+                    counts.syncing = 0;
+                }
+            }
+        }
+    }
+
+    fn unavailable(&mut self) {
+        self.available = false;
+        for counts in self.counts.values_mut() {
+            counts.synced += mem::take(&mut counts.syncing);
+        }
+    }
+}
+
 impl<C, I> TaskTransform<Event> for Throttle<C, I>
 where
     C: clock::Clock<Instant = I> + Send + 'static,
@@ -117,6 +403,8 @@ where
 
         let limiter = RateLimiter::dashmap_with_clock(self.quota, &self.clock);
 
+        let shared = self.shared.as_ref().map(SharedCounters::start);
+
         Box::pin(
             stream! {
               loop {
@@ -143,17 +431,14 @@ where
                                                 .ok()
                                         });
 
-                                        match limiter.check_key(&key) {
-                                            Ok(()) => {
-                                                output.push(event);
-                                            }
-                                            _ => {
-                                                if let Some(key) = key {
-                                                  emit!(&ThrottleEventDiscarded{key})
-                                                } else {
-                                                  emit!(&ThrottleEventDiscarded{key: "None".to_string()})
-                                                }
-                                            }
+                                        let shared_check = shared.as_ref().and_then(|shared| shared.check(&key));
+
+                                        if shared_check.unwrap_or_else(|| limiter.check_key(&key).is_ok()) {
+                                            output.push(event);
+                                        } else if let Some(key) = key {
+                                            emit!(&ThrottleEventDiscarded{key})
+                                        } else {
+                                            emit!(&ThrottleEventDiscarded{key: "None".to_string()})
                                         }
                                     }
                                 }
@@ -386,4 +671,138 @@ key_field = "{{ bucket }}"
         // And still nothing there
         assert_eq!(Poll::Ready(None), futures::poll!(out_stream.next()));
     }
+
+    #[tokio::test]
+    async fn throttle_unreachable_backend() {
+        let clock = clock::FakeRelativeClock::default();
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+
+[backend]
+type = "redis"
+url = "redis://127.0.0.1:1"
+"#,
+        )
+        .unwrap();
+
+        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
+            .map(Transform::event_task)
+            .unwrap();
+
+        let throttle = throttle.into_task();
+
+        let (mut tx, rx) = futures::channel::mpsc::channel(10);
+        let mut out_stream = throttle.transform_events(Box::pin(rx));
+
+        // tokio interval is always immediately ready, so we poll once to make sure
+        // we trip it/set the interval in the future
+        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+
+        for _ in 0..3 {
+            tx.send(Event::new_empty_log()).await.unwrap();
+        }
+        tx.disconnect();
+
+        // The events are rate limited locally while the backend is unreachable
+        let mut count = 0_u8;
+        while let Some(_event) = out_stream.next().await {
+            count += 1;
+        }
+        assert_eq!(2, count);
+    }
+
+    #[tokio::test]
+    async fn throttle_unresponsive_backend() {
+        // Accepts connections, but never answers.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = toml::from_str::<ThrottleConfig>(&format!(
+            r#"
+threshold = 2
+window_secs = 5
+
+[backend]
+type = "redis"
+url = "redis://{}"
+timeout_ms = 60000
+"#,
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+
+        let throttle = Throttle::new(
+            &config,
+            &TransformContext::default(),
+            clock::FakeRelativeClock::default(),
+        )
+        .map(Transform::event_task)
+        .unwrap();
+
+        let throttle = throttle.into_task();
+
+        let (mut tx, rx) = futures::channel::mpsc::channel(10);
+        let mut out_stream = throttle.transform_events(Box::pin(rx));
+
+        for _ in 0..3 {
+            tx.send(Event::new_empty_log()).await.unwrap();
+        }
+        tx.disconnect();
+
+        // The events don't wait for the backend, being rate limited locally meanwhile
+        let count = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut count = 0_u8;
+            while let Some(_event) = out_stream.next().await {
+                count += 1;
+            }
+            count
+        })
+        .await
+        .unwrap();
+        assert_eq!(2, count);
+    }
+
+    #[test]
+    fn shared_counts_apply_the_threshold() {
+        let key = Some("a".to_owned());
+        let mut state = SharedState::default();
+
+        // Rate limited locally until the counts are synced
+        assert_eq!(state.check(&key, 1, 3), None);
+        let batch = state.take(1);
+        assert_eq!(batch, vec![(key.clone(), 1)]);
+        // Other instances counted 1 more event
+        state.synced(1, &batch, &[2]);
+
+        assert_eq!(state.check(&key, 1, 3), Some(true));
+        assert_eq!(state.check(&key, 1, 3), Some(false));
+        // The counts start over with each window
+        assert_eq!(state.check(&key, 2, 3), Some(true));
+        assert_eq!(state.take(2), vec![(key.clone(), 1)]);
+
+        state.unavailable();
+        assert_eq!(state.check(&key, 2, 3), None);
+    }
+
+    #[test]
+    fn invalid_backend_url() {
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+
+[backend]
+type = "redis"
+url = "not a url"
+"#,
+        )
+        .unwrap();
+
+        assert!(Throttle::new(
+            &config,
+            &TransformContext::default(),
+            clock::FakeRelativeClock::default()
+        )
+        .is_err());
+    }
 }
@@ -36,6 +36,11 @@ pub struct Opts {
         use_value_delimiter(true)
     )]
     pub config_dirs: Vec<PathBuf>,
+
+    /// Record the output events of the tests with a snapshot into their golden
+    /// files, instead of checking them against it.
+    #[clap(long)]
+    update: bool,
 }
 
 impl Opts {
@@ -77,7 +82,8 @@ pub async fn cmd(opts: &Opts) -> exitcode::ExitCode {
                     println!("{}", "No tests found.".yellow());
                 }
             } else {
-                for test in tests {
+                for mut test in tests {
+                    test.set_update_snapshot(opts.update);
                     let name = test.name.clone();
                     let UnitTestResult { errors } = test.run().await;
                     if !errors.is_empty() {
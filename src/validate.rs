@@ -95,7 +95,7 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
 
     let mut validated = true;
 
-    let mut config = match validate_config(opts, &mut fmt) {
+    let mut config = match validate_config(opts, &mut fmt).await {
         Some(config) => config,
         None => return exitcode::CONFIG,
     };
@@ -117,7 +117,7 @@ pub async fn validate(opts: &Opts, color: bool) -> ExitCode {
     }
 }
 
-fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
+async fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
     // Prepare paths
     let paths = opts.paths_with_formats();
     let paths = if let Some(paths) = config::process_paths(&paths) {
@@ -137,7 +137,8 @@ fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
     config::init_log_schema(&paths, true)
         .map_err(&mut report_error)
         .ok()?;
-    let (builder, load_warnings) = config::load_builder_from_paths(&paths)
+    let (builder, load_warnings) = config::load_builder_and_secrets_from_paths(&paths)
+        .await
         .map_err(&mut report_error)
         .ok()?;
 
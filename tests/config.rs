@@ -462,6 +462,34 @@ async fn bad_s3_region() {
     )
 }
 
+#[cfg(all(feature = "sources-file", feature = "sinks-socket"))]
+#[tokio::test]
+async fn bad_acknowledgement_timeout() {
+    let err = load(
+        r#"
+        [sources.in]
+        type = "file"
+        include = ["/var/log/messages"]
+        acknowledgement_timeout.timeout_secs = 10
+
+        [sinks.out]
+        type = "socket"
+        mode = "tcp"
+        inputs = ["in"]
+        encoding = "text"
+        address = "127.0.0.1:9999"
+        "#,
+        Format::Toml,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        err,
+        vec!["Source \"in\" of type \"file\" does not support `acknowledgement_timeout`"]
+    )
+}
+
 #[cfg(all(
     feature = "sources-socket",
     feature = "transforms-sample",
@@ -579,6 +579,134 @@ async fn topology_rebuild_connected_transform() {
     assert_eq!(vec![event1, event2], res);
 }
 
+/// Sends the numbered events to the source, pacing them so that they keep on coming while the
+/// topology reloads.
+fn send_numbered_events(
+    mut input: vector::SourceSender,
+    numbers: std::ops::Range<usize>,
+) -> tokio::task::JoinHandle<vector::SourceSender> {
+    tokio::spawn(async move {
+        for number in numbers {
+            input
+                .send_event(Event::from(number.to_string()))
+                .await
+                .unwrap();
+            sleep(Duration::from_millis(1)).await;
+        }
+        input
+    })
+}
+
+fn numbered_messages(numbers: std::ops::Range<usize>) -> Vec<String> {
+    numbers.map(|number| number.to_string()).collect()
+}
+
+#[tokio::test]
+async fn topology_reload_changed_source_loses_no_events() {
+    let (in1v1, source1v1) = source_with_data("v1");
+    let (out1, sink1) = sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1v1);
+    config.add_sink("out1", &["in1"], sink1);
+
+    let (mut topology, _crash) = start_topology(config.build().unwrap(), false).await;
+    let h_out1 = tokio::spawn(out1.flat_map(into_message_stream).collect::<Vec<_>>());
+    let mut in1v1 = send_numbered_events(in1v1, 0..100).await.unwrap();
+
+    let (in1v2, source1v2) = source_with_data("v2");
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1v2);
+    config.add_sink("out1", &["in1"], sink(10).1);
+
+    assert!(topology
+        .reload_config_and_respawn(config.build().unwrap())
+        .await
+        .unwrap());
+    in1v1.send_event(Event::from("100")).await.unwrap_err();
+    send_numbered_events(in1v2, 100..200).await.unwrap();
+    topology.stop().await;
+
+    assert_eq!(h_out1.await.unwrap(), numbered_messages(0..200));
+}
+
+#[tokio::test]
+async fn topology_reload_changed_transform_loses_no_events() {
+    let (in1, source1) = source();
+    let (out1, sink1) = sink(10);
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_transform("t1", &["in1"], transform(" v1", 0.0));
+    config.add_sink("out1", &["t1"], sink1);
+
+    let (mut topology, _crash) = start_topology(config.build().unwrap(), false).await;
+    let h_out1 = tokio::spawn(out1.flat_map(into_message_stream).collect::<Vec<_>>());
+    let h_in1 = send_numbered_events(in1, 0..200);
+    sleep(Duration::from_millis(50)).await;
+
+    let mut config = Config::builder();
+    config.add_source("in1", source().1);
+    config.add_transform("t1", &["in1"], transform(" v2", 0.0));
+    config.add_sink("out1", &["t1"], sink(10).1);
+
+    assert!(topology
+        .reload_config_and_respawn(config.build().unwrap())
+        .await
+        .unwrap());
+    h_in1.await.unwrap();
+    topology.stop().await;
+
+    // The old transform drains its events while the new one takes over, so they can interleave.
+    let mut numbers = h_out1
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|message| {
+            let (number, version) = message.split_once(' ').unwrap();
+            assert!(version == "v1" || version == "v2", "{}", message);
+            number.parse().unwrap()
+        })
+        .collect::<Vec<usize>>();
+    numbers.sort_unstable();
+    assert_eq!(numbers, (0..200).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn topology_reload_changed_sink_loses_no_events() {
+    let (in1, source1) = source();
+    let (out1v1, sink1v1) = sink_with_data(10, "v1");
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink("out1", &["in1"], sink1v1);
+
+    let (mut topology, _crash) = start_topology(config.build().unwrap(), false).await;
+    let h_out1v1 = tokio::spawn(out1v1.flat_map(into_message_stream).collect::<Vec<_>>());
+    let h_in1 = send_numbered_events(in1, 0..200);
+    sleep(Duration::from_millis(50)).await;
+
+    let (out1v2, sink1v2) = sink_with_data(10, "v2");
+
+    let mut config = Config::builder();
+    config.add_source("in1", source().1);
+    config.add_sink("out1", &["in1"], sink1v2);
+
+    let h_out1v2 = tokio::spawn(out1v2.flat_map(into_message_stream).collect::<Vec<_>>());
+    assert!(topology
+        .reload_config_and_respawn(config.build().unwrap())
+        .await
+        .unwrap());
+    h_in1.await.unwrap();
+    topology.stop().await;
+
+    // The new sink takes the buffer over once the old one stopped reading from it.
+    let mut output = h_out1v1.await.unwrap();
+    output.extend(h_out1v2.await.unwrap());
+    assert_eq!(output, numbered_messages(0..200));
+}
+
 #[tokio::test]
 async fn topology_required_healthcheck_fails_start() {
     let mut config = basic_config_with_sink_failing_healthcheck();
@@ -666,6 +794,7 @@ async fn topology_disk_buffer_flushes_on_idle() {
         stages: vec![BufferType::DiskV1 {
             max_size: 1024,
             when_full: WhenFull::DropNewest,
+            encryption: None,
         }],
     };
     config.add_sink_outer("out1", sink1_outer);
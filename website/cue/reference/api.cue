@@ -47,6 +47,123 @@ api: {
 				of the address set using the `bind` parameter.
 				"""
 		}
+		tls: {
+			common:      false
+			required:    false
+			description: """
+				Configures TLS for the API server. When enabled, the `/graphql` endpoint,
+				including WebSocket subscriptions, is only reachable over HTTPS. `crt_file`
+				and `key_file` are required.
+				"""
+			type: object: options: {
+				enabled: {
+					common:      true
+					required:    false
+					description: "Whether to serve the API over TLS."
+					type: bool: default: false
+				}
+				crt_file: {
+					common:      true
+					required:    false
+					description: "Absolute path to the certificate file used to identify the API server."
+					type: string: {
+						default: null
+						examples: ["/path/to/host_certificate.crt"]
+					}
+				}
+				key_file: {
+					common:      true
+					required:    false
+					description: "Absolute path to the private key file matching `crt_file`."
+					type: string: {
+						default: null
+						examples: ["/path/to/host_certificate.key"]
+					}
+				}
+				key_pass: {
+					common:      false
+					required:    false
+					description: "Passphrase used to unlock the encrypted key file."
+					type: string: {
+						default: null
+						examples: ["${KEY_PASS_ENV_VAR}", "PassWord1"]
+					}
+				}
+				ca_file: {
+					common:      false
+					required:    false
+					description: "Absolute path to an additional CA certificate file, in PEM format."
+					type: string: {
+						default: null
+						examples: ["/path/to/certificate_authority.crt"]
+					}
+				}
+				verify_certificate: {
+					common:      false
+					required:    false
+					description: "If `true`, clients must present a valid certificate signed by `ca_file`."
+					type: bool: default: false
+				}
+			}
+		}
+		auth: {
+			common:      false
+			required:    false
+			description: """
+				Credentials that every request to `/graphql` must present in its
+				`Authorization` header. This also applies to the HTTP upgrade request
+				that opens WebSocket subscriptions, such as those used by `vector top`
+				and `vector tap`. The `/health` endpoint stays unauthenticated.
+				"""
+			type: object: options: {
+				strategy: {
+					required:    true
+					description: "The authentication strategy to use."
+					type: string: enum: {
+						basic:  "The [basic authentication strategy](\(urls.basic_auth))."
+						bearer: "The bearer token authentication strategy."
+					}
+				}
+				user: {
+					required:      true
+					relevant_when: "strategy = \"basic\""
+					description:   "The basic authentication user name."
+					type: string: examples: ["${API_USERNAME}", "username"]
+				}
+				password: {
+					required:      true
+					relevant_when: "strategy = \"basic\""
+					description:   "The basic authentication password."
+					type: string: examples: ["${API_PASSWORD}", "password"]
+				}
+				token: {
+					required:      true
+					relevant_when: "strategy = \"bearer\""
+					description:   "The token to use for bearer authentication."
+					type: string: examples: ["${API_TOKEN}", "my-token"]
+				}
+			}
+		}
+		event_tracing_sample_rate: {
+			common:      false
+			required:    false
+			description: """
+				Enables the event tracing mode, in which one in this many events
+				emitted by the sources record the components they traverse, with
+				when they entered and left each of them. The traces of the events
+				reaching a sink are streamed by the `eventTrace` GraphQL
+				subscription, breaking the latency of the pipeline down into the
+				time each component spent processing the event, and the time the
+				event waited in its input buffer. Tracing adds some overhead to
+				every component, so it's meant for debugging rather than to be
+				left on.
+				"""
+			type: uint: {
+				default: null
+				examples: [100, 1000]
+				unit: "events"
+			}
+		}
 	}
 
 	endpoints: {
@@ -66,6 +183,110 @@ api: {
 							there were any errors in your query.
 							"""
 					}
+					"401": {
+						description: """
+							The request didn't carry the credentials configured
+							in `api.auth`.
+							"""
+					}
+				}
+			}
+		}
+		"/components": {
+			GET: {
+				description: """
+					Lists the configured components as JSON, including their
+					kind, type and inputs. Requires the same credentials as
+					`/graphql` when `auth` is configured.
+					"""
+				responses: {
+					"200": {
+						description: "The list of configured components."
+					}
+				}
+			}
+		}
+		"/components/:id/metrics": {
+			GET: {
+				description: """
+					Returns a JSON summary of the received, sent and errored
+					event totals of a component.
+					"""
+				responses: {
+					"200": {
+						description: "The metrics summary of the component."
+					}
+					"404": {
+						description: "No component exists with the given ID."
+					}
+				}
+			}
+		}
+		"/tap": {
+			GET: {
+				description: """
+					Streams sampled events as [server-sent events](\(urls.sse)).
+					Accepts the comma-separated `outputs_of` and `inputs_of`
+					component ID patterns, plus `interval` and `limit`, with the
+					same meaning as for `vector tap`. Each SSE event is named
+					`log`, `metric`, `trace` or `notification` and carries a
+					JSON body.
+					"""
+				responses: {
+					"200": {
+						description: "The event stream was opened."
+					}
+					"400": {
+						description: "`interval` or `limit` is out of range."
+					}
+				}
+			}
+		}
+		"/buffers": {
+			GET: {
+				description: """
+					Lists the disk buffers of running sinks as JSON, with their
+					number of segments, bytes on disk and oldest record age.
+					Requires the same credentials as `/graphql` when `auth` is
+					configured.
+					"""
+				responses: {
+					"200": {
+						description: "The list of disk buffers."
+					}
+				}
+			}
+		}
+		"/buffers/:id": {
+			GET: {
+				description: """
+					Returns the number of segments, bytes on disk and oldest
+					record age of the disk buffer of a sink.
+					"""
+				responses: {
+					"200": {
+						description: "The usage of the disk buffer."
+					}
+					"404": {
+						description: "No running sink with the given ID has a disk buffer."
+					}
+				}
+			}
+		}
+		"/buffers/:id/compact": {
+			POST: {
+				description: """
+					Asks the disk buffer of a sink to reclaim the space held by
+					acknowledged records, in the background and without
+					restarting the sink.
+					"""
+				responses: {
+					"202": {
+						description: "The compaction was requested."
+					}
+					"404": {
+						description: "No running sink with the given ID has a disk buffer."
+					}
 				}
 			}
 		}
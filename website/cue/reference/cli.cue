@@ -13,6 +13,29 @@ _default_flags: {
 	}
 }
 
+_api_client_options: {
+	"api-token": {
+		description: "Bearer token matching the `api.auth` settings of the Vector API server"
+		type:        "string"
+		env_var:     "VECTOR_API_TOKEN"
+	}
+	"api-user": {
+		description: "User matching the basic `api.auth` settings of the Vector API server"
+		type:        "string"
+		env_var:     "VECTOR_API_USER"
+	}
+	"api-password": {
+		description: "Password matching the basic `api.auth` settings of the Vector API server"
+		type:        "string"
+		env_var:     "VECTOR_API_PASSWORD"
+	}
+	"api-ca-file": {
+		description: "PEM file of a certificate authority to verify the TLS certificate of the Vector API server with, in addition to the system's ones"
+		type:        "string"
+		env_var:     "VECTOR_API_CA_FILE"
+	}
+}
+
 cli: {
 	#Args: [Arg=string]: {
 		description: !=""
@@ -164,6 +187,102 @@ cli: {
 	options: _core_options
 
 	commands: {
+		"buffer": {
+			description: """
+				Inspect the disk buffers of sinks, or reclaim the space held by
+				acknowledged records, for a local or remote Vector instance
+				without restarting it. `vector buffer inspect` prints the
+				segments, on-disk bytes, and oldest record age of the disk buffers
+				of all running sinks, or of the given sinks. `vector buffer compact`
+				asks the disk buffer of the given sink to reclaim space in the
+				background.
+				"""
+
+			example: "vector buffer compact my_sink"
+
+			flags: _default_flags
+
+			options: _api_client_options & {
+				"url": {
+					_short:      "u"
+					description: "Vector API server endpoint"
+					type:        "string"
+				}
+			}
+
+			args: {
+				command: {
+					type:        "string"
+					required:    true
+					description: "Either `inspect` or `compact`."
+				}
+				components: {
+					type:        "list"
+					description: "Sinks whose disk buffer to inspect, or the single sink whose disk buffer to compact. The default is all sinks when inspecting."
+				}
+			}
+		}
+
+		"convert-config": {
+			description: """
+				Convert a Vector configuration file between the TOML, YAML, and JSON
+				formats. The config is validated first, failing with the same errors
+				as when loading it. Keys keep their order, but comments are lost.
+				"""
+
+			example: "vector convert-config /etc/vector/vector.toml --to yaml --output /etc/vector/vector.yaml"
+
+			flags: _default_flags & {
+				"interpolate-env": {
+					description: """
+						Replace the environment variables referenced in the config with
+						their values, instead of keeping them as they are
+						"""
+				}
+				"fragment": {
+					description: """
+						Only validate the components of the config, not that they make up
+						a whole topology, for configs split across several files
+						"""
+				}
+			}
+
+			options: {
+				"from": {
+					description: "The format of the input file, detected from its name by default"
+					type:        "enum"
+					enum: {
+						toml: "TOML"
+						yaml: "YAML"
+						json: "JSON"
+					}
+				}
+				"to": {
+					description: "The format to convert the config to, detected from the name of the output file by default"
+					type:        "enum"
+					enum: {
+						toml: "TOML"
+						yaml: "YAML"
+						json: "JSON"
+					}
+				}
+				"output": {
+					_short:      "o"
+					description: "Write the converted config to this file instead of stdout"
+					type:        "string"
+					example:     "/etc/vector/vector.yaml"
+				}
+			}
+
+			args: {
+				input: {
+					description: "The Vector config file to convert"
+					type:        "string"
+					required:    true
+				}
+			}
+		}
+
 		"graph": {
 			description: """
 				Generate a visual representation of topologies. The output is in the [DOT format](\(urls.dot_format)),
@@ -231,6 +350,15 @@ cli: {
 				out the [unit testing documentation](\(urls.vector_unit_tests)).
 				"""
 
+			flags: _default_flags & {
+				"update": {
+					description: """
+						Record the output events of the tests with a `snapshot` into their
+						golden files, instead of checking them against it
+						"""
+				}
+			}
+
 			options: {
 				"config-toml": {
 					description: env_vars.VECTOR_CONFIG_TOML.description
@@ -259,6 +387,48 @@ cli: {
 			}
 		}
 
+		"replay": {
+			description: """
+				Feed archived events through the pipeline of a config, then exit
+				once they have all been processed, such as to backfill data, or to
+				test changes to transforms against real data. The events are fed
+				into the given source, which they replace, or transform, which they
+				are an extra input of. The other sources are left out, along with
+				the components only they feed.
+				"""
+
+			example: "vector replay --config /etc/vector/vector.toml --into parse_logs s3://archives/2022/05/01.ndjson.gz"
+
+			options: _core_options & {
+				"into": {
+					description: "The source or transform the events are fed into"
+					type:        "string"
+					required:    true
+				}
+				"format": {
+					description: "The format of the inputs"
+					type:        "enum"
+					default:     "ndjson"
+					enum: {
+						ndjson:        "Files of JSON log events, one per line, gzipped when their name ends with `.gz`"
+						"disk-buffer": "The directories of the `disk` buffers of sinks, which must not be in use. Their records are left in place."
+					}
+				}
+				"region": {
+					description: "The AWS region of the S3 objects replayed, the region of the environment by default"
+					type:        "string"
+				}
+			}
+
+			args: {
+				inputs: {
+					description: "The files, `s3://<bucket>/<key>` objects, or disk buffer directories to replay, in order"
+					type:        "list"
+					required:    true
+				}
+			}
+		}
+
 		"tap": {
 			description: """
 				Observe events flowing into components (transforms, sinks) and
@@ -281,7 +451,7 @@ cli: {
 				}
 			}
 
-			options: {
+			options: _api_client_options & {
 				"interval": {
 					_short:      "i"
 					description: "Interval to sample events at, in milliseconds"
@@ -308,8 +478,17 @@ cli: {
 						json:   "Output events as JSON"
 						yaml:   "Output events as YAML"
 						logfmt: "Output events as logfmt"
+						table:  "Output events as a table, with a column per field. Columns are taken from `--fields`, or from the first event"
 					}
 				}
+				"fields": {
+					description: "Fields of events to print (comma-separated; nested fields are separated by `.`, e.g. `host,tags.env`). Prints whole events if omitted"
+					type:        "list"
+				}
+				"vrl": {
+					description: "VRL program to run on each event before printing it, e.g. `del(.host)`. Events for which the program aborts or fails aren't printed, so `if .status == 200 { abort }` filters events"
+					type:        "string"
+				}
 				"inputs-of": {
 					description: "Components (transforms, sinks) to observe for their inputs (comma-separated; accepts glob patterns)"
 					type:        "list"
@@ -355,7 +534,7 @@ cli: {
 				}
 			}
 
-			options: {
+			options: _api_client_options & {
 				"refresh-interval": {
 					_short:      "i"
 					description: "How often the screen refreshes (in milliseconds)"
@@ -434,6 +613,13 @@ cli: {
 						The same result can be achieved by using `.` as the final expression.
 						"""
 				}
+				"print-types": {
+					description: """
+						Type-check the program without running it, and print the kind of the
+						object it emits as JSON, so that the schema of the events a program
+						emits can be verified ahead of deployment.
+						"""
+				}
 			}
 
 			options: {
@@ -452,6 +638,16 @@ cli: {
 						"""
 					type: "string"
 				}
+
+				"input-kind": {
+					description: """
+						The kind of the objects handled by the program as JSON, when printing
+						types. For example, `{"object": {"known": {"message": {"bytes": true}}}}`
+						is an object with a single `message` string field. Defaults to an
+						object of any fields.
+						"""
+					type: "string"
+				}
 			}
 
 			args: {
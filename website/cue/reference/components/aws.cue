@@ -39,6 +39,108 @@ components: _aws: {
 							examples: ["arn:aws:iam::123456789098:role/my_role"]
 						}
 					}
+					external_id: {
+						category:      "Auth"
+						common:        false
+						description:   "The [external ID](\(urls.aws_external_id)) to present when assuming the `assume_role` role, as required by the trust policies of some cross-account roles."
+						relevant_when: "assume_role != null"
+						required:      false
+						type: string: {
+							default: null
+							examples: ["5e9b0d8f-2a1c-4d3e-9f5a-7b6c8d9e0f1a"]
+						}
+					}
+					session_name: {
+						category:    "Auth"
+						common:      false
+						description: "The name of the sessions of the assumed roles, which appears in CloudTrail logs."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["vector-session"]
+						}
+					}
+					role_chain: {
+						category:      "Auth"
+						common:        false
+						description:   "Roles to assume in order before assuming the `assume_role` role, each with the credentials of the previous one, starting from the default credentials. This grants access to roles that can only be assumed from another role."
+						relevant_when: "assume_role != null"
+						required:      false
+						type: array: {
+							default: []
+							items: type: object: {
+								examples: []
+								options: {
+									role_arn: {
+										description: "The ARN of the role."
+										required:    true
+										type: string: examples: ["arn:aws:iam::123456789098:role/intermediate_role"]
+									}
+									external_id: {
+										common:      false
+										description: "The external ID to present when assuming the role."
+										required:    false
+										type: string: {
+											default: null
+											examples: ["5e9b0d8f-2a1c-4d3e-9f5a-7b6c8d9e0f1a"]
+										}
+									}
+								}
+							}
+						}
+					}
+					web_identity_token_file: {
+						category:    "Auth"
+						common:      false
+						description: "The path to a web identity token file, with which to assume the `role_arn` role. This is the file that [IAM roles for service accounts](\(urls.aws_irsa)) mount in the pods of EKS clusters, which is read again each time the credentials are refreshed as it is rotated."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["/var/run/secrets/eks.amazonaws.com/serviceaccount/token"]
+						}
+					}
+					role_arn: {
+						category:      "Auth"
+						common:        false
+						description:   "The ARN of the role to assume with the web identity token."
+						relevant_when: "web_identity_token_file != null"
+						required:      false
+						type: string: {
+							default: null
+							examples: ["arn:aws:iam::123456789098:role/my_role"]
+						}
+					}
+					sso_profile: {
+						category:    "Auth"
+						common:      false
+						description: "The name of an [SSO profile](\(urls.aws_sso)) of the AWS config file, the credentials of which are fetched with the token cached by `aws sso login`. Both the legacy profiles and those referring to an `sso-session` are supported. Once the token expires, `aws sso login` must be run again."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["my-sso-profile"]
+						}
+					}
+					config_file: {
+						category:      "Auth"
+						common:        false
+						description:   "The path to the AWS config file defining `sso_profile`. Defaults to the `AWS_CONFIG_FILE` environment variable, or `~/.aws/config`."
+						relevant_when: "sso_profile != null"
+						required:      false
+						type: string: {
+							default: null
+							examples: ["/path/to/aws/config"]
+						}
+					}
+					credential_process: {
+						category:    "Auth"
+						common:      false
+						description: "A command printing the credentials as JSON, as for the [`credential_process`](\(urls.aws_credential_process)) setting of the AWS config file. It's run by the shell, again each time the credentials expire."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["/usr/local/bin/aws-credentials --role vector"]
+						}
+					}
 					credentials_file: {
 						category:    "Auth"
 						common:      false
@@ -192,6 +294,20 @@ components: _aws: {
 						account access.
 						"""
 				},
+				{
+					title: "Other credentials sources"
+					body: """
+						Each component can use its own source of credentials, rather than those found
+						in the places described above: the web identity token file of
+						[IAM roles for service accounts](\(urls.aws_irsa)) with the
+						[`web_identity_token_file`](#auth.web_identity_token_file) option, the cached
+						credentials of an SSO profile with the [`sso_profile`](#auth.sso_profile) option,
+						or a command with the [`credential_process`](#auth.credential_process) option.
+						The credentials are refreshed before they expire, which the
+						`aws_credentials_refreshes_total` and `aws_credentials_refresh_errors_total`
+						internal metrics count.
+						"""
+				},
 			]
 		}
 	}
@@ -236,6 +352,14 @@ components: _aws_new_sdk: {
 							examples: ["arn:aws:iam::123456789098:role/my_role"]
 						}
 					}
+					external_id: components._aws.configuration.auth.type.object.options.external_id
+					session_name: components._aws.configuration.auth.type.object.options.session_name
+					role_chain: components._aws.configuration.auth.type.object.options.role_chain
+					web_identity_token_file: components._aws.configuration.auth.type.object.options.web_identity_token_file
+					role_arn: components._aws.configuration.auth.type.object.options.role_arn
+					sso_profile: components._aws.configuration.auth.type.object.options.sso_profile
+					config_file: components._aws.configuration.auth.type.object.options.config_file
+					credential_process: components._aws.configuration.auth.type.object.options.credential_process
 					profile: {
 						category:    "Auth"
 						common:      false
@@ -0,0 +1,83 @@
+package metadata
+
+components: _parquet: {
+	configuration: {
+		parquet: {
+			common:      false
+			description: "Writes the objects as [Parquet](\(urls.apache_parquet)) files rather than with the `encoding.codec`. The `compression` option is ignored, the pages of the columns being compressed instead."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					compression: {
+						common:      false
+						description: "The compression of the pages of the columns."
+						required:    false
+						type: string: {
+							default: "snappy"
+							enum: {
+								none:   "No compression."
+								snappy: "[Snappy](\(urls.snappy)) compression."
+								gzip:   "[Gzip](\(urls.gzip)) compression."
+								zstd:   "[Zstandard](\(urls.zstd)) compression."
+							}
+						}
+					}
+					row_group_size: {
+						common:      false
+						description: "The maximum number of rows of each row group. When not set, the events of a batch are written in a single row group."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [10000]
+							unit: "events"
+						}
+					}
+					schema: {
+						common:      true
+						description: "The columns of the files and their types, by the paths of the fields of the events they are read from. When not set, the columns are inferred from the top level fields of the events of each batch."
+						required:    false
+						type: object: {
+							examples: [{"message": "string", "http.status": "integer", "timestamp": "timestamp"}]
+							options: {
+								"*": {
+									common:      false
+									description: "The type of the column."
+									required:    true
+									type: string: {
+										enum: {
+											boolean:   "Booleans."
+											integer:   "64-bit integers."
+											float:     "64-bit floats, integers being converted to floats."
+											string:    "UTF-8 strings, values of other types being converted to strings."
+											timestamp: "Timestamps with a precision of microseconds."
+											json:      "Objects and arrays, written as JSON strings."
+										}
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		parquet: {
+			title: "Parquet files"
+			body:  """
+				When the `parquet` option is set, each batch is written as a single Parquet file,
+				named with the `parquet` extension by default. The `only_fields` and
+				`except_fields` options of the `encoding` are applied first.
+
+				The columns of each file are either declared with `parquet.schema`, or inferred
+				from the top level fields of the events of the batch: the type of a column is that
+				of the values of its field, integers and floats being written as floats, and
+				fields holding values of other conflicting types as strings. All the columns are
+				optional, events without a field, or whose field can't be converted to the type of
+				its column, being written with a null value.
+				"""
+		}
+	}
+}
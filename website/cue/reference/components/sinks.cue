@@ -69,11 +69,48 @@ components: sinks: [Name=string]: {
 
 		buffer: {
 			common:      false
-			description: "Configures the sink specific buffer behavior."
+			description: """
+				Configures the sink specific buffer behavior.
+
+				A list of buffers can be given instead of a single one to chain them: when a buffer
+				becomes full, events overflow into the next buffer of the list rather than blocking
+				or being dropped. For instance, a `memory` buffer followed by a `disk` buffer absorbs
+				bursts on disk without paying the disk latency in the steady state. At most one
+				buffer of the list can be a `disk` buffer.
+				"""
 			required:    false
 			type: object: {
 				examples: []
 				options: {
+					encryption: {
+						common: false
+						description: """
+							Encrypts the records of the buffer at rest. Each record is encrypted with
+							AES-256-GCM, with its own random nonce, before being written to disk.
+							Records which can't be decrypted, because they were tampered with or because
+							the key changed, are dropped as corrupted records. Encryption can only be
+							turned on, or off, while the buffer is empty: Vector refuses to start
+							otherwise, as the records already in the buffer couldn't be read back.
+							"""
+						required:      false
+						relevant_when: "type = \"disk\""
+						type: object: {
+							examples: []
+							options: {
+								key: {
+									description: """
+										The 256-bit encryption key, encoded in base64. It should be supplied through
+										an environment variable rather than written in the configuration file.
+										"""
+									required: true
+									type: string: {
+										examples: ["${VECTOR_BUFFER_KEY}"]
+										syntax: "literal"
+									}
+								}
+							}
+						}
+					}
 					max_events: {
 						common:        true
 						description:   "The maximum number of [events](\(urls.vector_data_model)) allowed in the buffer."
@@ -120,6 +157,7 @@ components: sinks: [Name=string]: {
 							enum: {
 								block:       "Applies back pressure when the buffer is full. This prevents data loss, but will cause data to pile up on the edge."
 								drop_newest: "Drops new data as it's received. This data is lost. This should be used when performance is the highest priority."
+								overflow:    "Sends new data to the next buffer of the list. This is the default for every buffer but the last one, which can't overflow."
 							}
 						}
 					}
@@ -207,6 +245,17 @@ components: sinks: [Name=string]: {
 											if codec == "ndjson" {
 												ndjson: "Newline delimited list of JSON encoded events."
 											}
+											if codec == "otlp" {
+												otlp: "[OTLP](\(urls.opentelemetry_otlp)) export request encoded as protobuf, holding the events as log records."
+											}
+											if codec == "msgpack" {
+												if batched {
+													msgpack: "Concatenated [MessagePack](\(urls.msgpack)) maps, each map representing one event."
+												}
+												if !batched {
+													msgpack: "[MessagePack](\(urls.msgpack)) encoded event."
+												}
+											}
 										}
 									}
 								}
@@ -239,6 +288,43 @@ components: sinks: [Name=string]: {
 							}
 						}
 
+						rename_fields: {
+							common: false
+							description: """
+								Renames the specified fields before encoding them, mapping their paths
+								to their new paths. The fields are matched by `only_fields` and
+								`except_fields` by their original names, and renamed simultaneously, so
+								that two fields can be swapped.
+								"""
+							required: false
+							type: object: {
+								examples: [
+									{
+										msg:         "message"
+										"host.name": "hostname"
+									},
+								]
+								options: {}
+							}
+						}
+
+						field_order: {
+							common: false
+							description: """
+								The top-level fields written first, in this order, by the `json` codec,
+								after they are renamed. The other fields follow in lexicographic order,
+								so that the output matches downstream column contracts. Only supported
+								along with the `codec` options of the shared encoding configuration.
+								"""
+							required: false
+							type: array: {
+								default: null
+								items: type: string: {
+									examples: ["timestamp", "level", "message"]
+								}
+							}
+						}
+
 						timestamp_format: {
 							common:      false
 							description: "How to format event timestamps."
@@ -256,6 +342,27 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		dead_letter: {
+			common: false
+			description: """
+				The ID of a transform or sink receiving the events this sink fails to deliver, once their requests exhausted their retries or were rejected. The events are routed unchanged, with the ID of this sink and the delivery status in the `dead_letter.sink` and `dead_letter.status` fields of logs and traces, and the `dead_letter_sink` and `dead_letter_status` tags of metrics.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["archive"]
+			}
+		}
+
+		low_priority: {
+			common: false
+			description: """
+				The condition classifying the events sent to this sink as low priority. Whenever the buffer is full, low priority events are dropped right away, and counted as discarded by the buffer, while the other events keep waiting for room as with `when_full = "block"`. This lets audit logs, for example, survive an overload at the expense of debug logs.
+				"""
+			required: false
+			type: condition: {}
+		}
+
 		if features.healthcheck != _|_ {
 			if features.healthcheck.enabled {
 				healthcheck: {
@@ -314,6 +421,12 @@ components: sinks: [Name=string]: {
 											required:    false
 											type: float: default: 0.7
 										}
+										per_partition: {
+											common:      false
+											description: "Tracks the concurrency limit of each partition of the requests separately, such as those of each partition key of the batches, so that a slow partition doesn't throttle the requests of the others. The `concurrency` limit then applies to each partition. As the partition of a request is only known once it is built, the sink then builds up to 200 requests ahead of their limits, instead of waiting for the limit to build each one. Only the `aws_cloudwatch_metrics`, `gcp_chronicle`, `logdna` and `prometheus_remote_write` sinks partition their requests; the other sinks ignore this option, logging a warning."
+											required:    false
+											type: bool: default: false
+										}
 										rtt_deviation_scale: {
 											common: false
 											description: """
@@ -366,6 +479,21 @@ components: sinks: [Name=string]: {
 									unit:    null
 								}
 							}
+							retry_after: {
+								common:      false
+								description: "Whether to wait for the delay the responses ask for, in their `Retry-After` or `RateLimit-Reset` headers, before retrying the requests, up to `retry_max_duration_secs`, instead of the back-off."
+								required:    false
+								type: bool: default: false
+							}
+							retry_budget_percent: {
+								common:      false
+								description: "The percentage of the requests that may be retried, in addition to 10 retries per second, over the last 10 seconds. Once the budget is exhausted, failed requests aren't retried. By default, the retries are unlimited."
+								required:    false
+								type: float: {
+									default: null
+									examples: [20.0]
+								}
+							}
 							retry_initial_backoff_secs: {
 								common:      false
 								description: "The amount of time to wait before attempting the first retry for a failed request. Once, the first retry has failed the fibonacci sequence will be used to select future backoffs."
@@ -375,6 +503,18 @@ components: sinks: [Name=string]: {
 									unit:    "seconds"
 								}
 							}
+							retry_jitter_mode: {
+								common:      false
+								description: "How the delays between the retries are randomized, so that the requests failing together aren't retried together."
+								required:    false
+								type: string: {
+									default: "none"
+									enum: {
+										none: "The delays follow the back-off exactly."
+										full: "The delays are drawn uniformly between zero and the back-off."
+									}
+								}
+							}
 							retry_max_duration_secs: {
 								common:      false
 								description: "The maximum amount of time, in seconds, to wait between retries."
@@ -410,6 +550,39 @@ components: sinks: [Name=string]: {
 									}
 								}
 							}
+
+							if features.send.request.compression {
+								compression: {
+									common:      false
+									description: "Compresses the bodies of the requests with the first algorithm the endpoint accepts. Once the endpoint rejects an algorithm with a `415 Unsupported Media Type` response, the request is sent again with the next one, which is used from then on. Can't be used along with `compression` or `signing`."
+									required:    false
+									type: object: {
+										examples: []
+										options: {
+											algorithms: {
+												description: "The compression algorithms, in order of preference."
+												required:    true
+												type: array: items: type: string: {
+													enum: {
+														gzip:   "[Gzip](\(urls.gzip)) standard DEFLATE compression."
+														zstd:   "[zstd](\(urls.zstd)) compression."
+														snappy: "[Snappy](\(urls.snappy)) compression, in its block format."
+													}
+												}
+											}
+											level: {
+												common:      false
+												description: "The compression level, from 0 to 9 for `gzip` and from 1 to 22 for `zstd`. Ignored by `snappy`."
+												required:    false
+												type: uint: {
+													default: null
+													unit:    null
+												}
+											}
+										}
+									}
+								}
+							}
 						}
 					}
 				}
@@ -649,5 +822,8 @@ components: sinks: [Name=string]: {
 		buffer_sent_events_total:             components.sources.internal_metrics.output.metrics.buffer_sent_events_total
 		buffer_sent_event_bytes_total:        components.sources.internal_metrics.output.metrics.buffer_sent_event_bytes_total
 		buffer_discarded_events_total:        components.sources.internal_metrics.output.metrics.buffer_discarded_events_total
+		buffer_disk_bytes:                    components.sources.internal_metrics.output.metrics.buffer_disk_bytes
+		buffer_oldest_record_age_seconds:     components.sources.internal_metrics.output.metrics.buffer_oldest_record_age_seconds
+		buffer_segments:                      components.sources.internal_metrics.output.metrics.buffer_segments
 	}
 }
@@ -33,7 +33,7 @@ components: sinks: aws_s3: components._aws & {
 				codec: {
 					enabled: true
 					batched: true
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "text", "msgpack"]
 				}
 			}
 			proxy: enabled: true
@@ -245,6 +245,7 @@ components: sinks: aws_s3: components._aws & {
 				options: {}
 			}
 		}
+		parquet: components._parquet.configuration.parquet
 	}
 
 	input: {
@@ -253,6 +254,8 @@ components: sinks: aws_s3: components._aws & {
 	}
 
 	how_it_works: {
+		parquet: components._parquet.how_it_works.parquet
+
 		cross_account: {
 			title: "Cross account object writing"
 			body:  """
@@ -33,7 +33,7 @@ components: sinks: azure_blob: {
 				codec: {
 					enabled: true
 					batched: true
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "text", "msgpack"]
 				}
 			}
 			request: {
@@ -109,6 +109,7 @@ components: sinks: azure_blob: {
 				syntax:  "strftime"
 			}
 		}
+		parquet: components._parquet.configuration.parquet
 	}
 
 	input: {
@@ -117,6 +118,8 @@ components: sinks: azure_blob: {
 	}
 
 	how_it_works: {
+		parquet: components._parquet.how_it_works.parquet
+
 		object_naming: {
 			title: "Object naming"
 			body:  """
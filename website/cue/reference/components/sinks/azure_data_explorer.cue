@@ -0,0 +1,185 @@
+package metadata
+
+components: sinks: azure_data_explorer: {
+	title: "Azure Data Explorer"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Azure"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    100_000_000
+				max_events:   null
+				timeout_secs: 30
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.azure_data_explorer
+
+				interface: {
+					socket: {
+						api: {
+							title: "Azure Data Explorer queued ingestion"
+							url:   urls.azure_data_explorer_queued_ingestion
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: {
+			common:      false
+			description: "Options for the authentication to the cluster. Defaults to the managed identity of the Azure resource Vector runs on."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					authority_host: {
+						common:        false
+						description:   "The Azure Active Directory endpoint issuing the tokens, for national clouds."
+						relevant_when: "strategy = \"client_secret\""
+						required:      false
+						type: string: {
+							default: "https://login.microsoftonline.com"
+						}
+					}
+					client_id: {
+						common:      true
+						description: "The client ID of the application, or of the user-assigned managed identity to use."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["84b865dc-6e26-4f3b-9e0b-3e1c6aee1066"]
+						}
+					}
+					client_secret: {
+						description:   "The client secret of the application."
+						relevant_when: "strategy = \"client_secret\""
+						required:      true
+						type: string: {
+							examples: ["${AZURE_CLIENT_SECRET}"]
+						}
+					}
+					strategy: {
+						description: "The authentication strategy to use."
+						required:    true
+						type: string: {
+							enum: {
+								client_secret:    "Authenticates as an application registered in Azure Active Directory, with a client secret."
+								managed_identity: "Authenticates with a [managed identity](\(urls.azure_data_explorer_managed_identities)), through the instance metadata service."
+							}
+						}
+					}
+					tenant_id: {
+						description:   "The ID of the Azure Active Directory tenant of the application."
+						relevant_when: "strategy = \"client_secret\""
+						required:      true
+						type: string: {
+							examples: ["72f988bf-86f1-41af-91ab-2d7cd011db47"]
+						}
+					}
+				}
+			}
+		}
+		database: {
+			description: "The database containing the table."
+			required:    true
+			type: string: {
+				examples: ["logs"]
+			}
+		}
+		flush_immediately: {
+			common:      false
+			description: "Asks the cluster to ingest each batch without aggregating it with others, which lowers the latency of the ingestion at the expense of its efficiency."
+			required:    false
+			type: bool: default: false
+		}
+		ingestion_endpoint: {
+			description: "The data ingestion URI of the cluster."
+			required:    true
+			type: string: {
+				examples: ["https://ingest-mycluster.westeurope.kusto.windows.net"]
+			}
+		}
+		mapping_reference: {
+			common:      true
+			description: "The name of a JSON [ingestion mapping](\(urls.azure_data_explorer_ingestion_mappings)) of the table, mapping the fields of the events to its columns. Without one, the fields are mapped to the columns of the same name."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector_mapping"]
+			}
+		}
+		table: {
+			description: "The table to ingest the events into. Events are batched separately for each table."
+			required:    true
+			type: string: {
+				examples: ["Logs", "{{ table }}"]
+				syntax: "template"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		queued_ingestion: {
+			title: "Queued ingestion"
+			body:  """
+				Each batch is uploaded as a compressed blob of JSON lines to the temporary storage of the
+				cluster, and an ingestion message referencing it is then posted to one of its ingestion
+				queues. The events are acknowledged once the message is queued: the cluster then ingests
+				the blob on its own schedule, usually within minutes, and reports the failures of the
+				ingestion with the `.show ingestion failures` command.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
@@ -0,0 +1,159 @@
+package metadata
+
+components: sinks: clickhouse_native: {
+	title: "Clickhouse Native"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["Yandex"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.clickhouse
+
+				interface: {
+					socket: {
+						api: {
+							title: "Clickhouse native interface"
+							url:   urls.clickhouse_native
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				[Clickhouse](\(urls.clickhouse)) version `>= 20.1` is required, and `>= 21.11` for asynchronous inserts.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address of the native interface of the [Clickhouse](\(urls.clickhouse)) server, the port defaulting to `9000`, or `9440` with TLS."
+			required:    true
+			type: string: {
+				examples: ["127.0.0.1:9000", "clickhouse.example.com:9440"]
+			}
+		}
+		async_insert: {
+			common:      false
+			description: "Sets `async_insert`, letting Clickhouse buffer the inserted rows to write them along with those of other inserts."
+			required:    false
+			type: bool: default: false
+		}
+		auth: {
+			common:      false
+			description: "The credentials used to connect to the server, the `default` user being used otherwise."
+			required:    false
+			type: object: options: {
+				password: {
+					common:      true
+					description: "The password of the user."
+					required:    false
+					type: string: {
+						default: ""
+						examples: ["${CLICKHOUSE_PASSWORD}"]
+					}
+				}
+				user: {
+					description: "The name of the user."
+					required:    true
+					type: string: {
+						examples: ["${CLICKHOUSE_USERNAME}"]
+					}
+				}
+			}
+		}
+		database: {
+			common:      true
+			description: "The database that contains the table that data will be inserted into."
+			required:    false
+			type: string: {
+				default: "default"
+				examples: ["mydatabase"]
+			}
+		}
+		table: {
+			description: "The table that data will be inserted into."
+			required:    true
+			type: string: {
+				examples: ["mytable"]
+			}
+		}
+		wait_for_async_insert: {
+			common:        false
+			description:   "Sets `wait_for_async_insert`, only acknowledging asynchronous inserts once their rows are written to the table."
+			relevant_when: "async_insert = true"
+			required:      false
+			type: bool: default: true
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		columnar_blocks: {
+			title: "Columnar blocks"
+			body:  """
+				Events are inserted over the native protocol of Clickhouse, each batch being sent as a
+				block holding the values of each column. The columns of the table are looked up when
+				connecting, and each insert only holds those of the columns present in at least one
+				event of the batch, the others being filled with their default values by Clickhouse.
+
+				Values are converted to the types of their columns reported by the server. Strings can
+				be inserted into numeric, date and `UUID` columns, and integers into time columns as
+				seconds since the Unix epoch. Arrays are inserted into `Array` columns, and missing
+				values into `Nullable` columns as `NULL`. The `String`, `FixedString`, integer,
+				`Float32`, `Float64`, `Bool`, `Date`, `Date32`, `DateTime`, `DateTime64` and `UUID`
+				types are supported, along with their `Nullable`, `Array` and `LowCardinality`
+				variants. Batches holding values which can't be converted are rejected.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
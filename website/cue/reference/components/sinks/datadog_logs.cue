@@ -69,11 +69,77 @@ components: sinks: datadog_logs: {
 		}
 		endpoint: sinks._datadog.configuration.endpoint
 		region:   sinks._datadog.configuration.region
-		site:     sinks._datadog.configuration.site
+		reserved_attributes: {
+			common:      false
+			description: "Templates rendered into the [reserved attributes](\(urls.datadog_reserved_attributes)) of the logs, overwriting the fields of the events with the same names."
+			required:    false
+			type: object: options: {
+				ddsource: {
+					common:      true
+					description: "The technology the logs originate from."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["nginx", "{{ source_type }}"]
+						syntax: "template"
+					}
+				}
+				ddtags: {
+					common:      true
+					description: "The comma separated tags of the logs. Tags which don't start with a letter are discarded."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["env:prod,team:{{ team }}"]
+						syntax: "template"
+					}
+				}
+				hostname: {
+					common:      true
+					description: "The host the logs originate from."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["{{ kubernetes.pod_node_name }}"]
+						syntax: "template"
+					}
+				}
+				service: {
+					common:      true
+					description: "The service the logs originate from."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["checkout", "{{ kubernetes.pod_labels.app }}"]
+						syntax: "template"
+					}
+				}
+			}
+		}
+		site: sinks._datadog.configuration.site
 	}
 
 	input: {
 		logs:    true
 		metrics: null
 	}
+
+	how_it_works: {
+		reserved_attributes: {
+			title: "Reserved attributes"
+			body:  """
+				The `reserved_attributes` templates are rendered for each event, replacing the need
+				for a `remap` transform setting the attributes Datadog treats specially. The
+				rendered values are discarded, leaving the fields of the event unchanged, when they
+				are empty, longer than 200 characters or hold whitespace, and the tags of `ddtags`
+				which are invalid are discarded one by one. Templates which can't be rendered leave
+				the fields unchanged as well.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		datadog_logs_delivery_latency_seconds:          components.sources.internal_metrics.output.metrics.datadog_logs_delivery_latency_seconds
+		datadog_logs_invalid_reserved_attributes_total: components.sources.internal_metrics.output.metrics.datadog_logs_invalid_reserved_attributes_total
+	}
 }
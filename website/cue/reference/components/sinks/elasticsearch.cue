@@ -139,6 +139,8 @@ components: sinks: elasticsearch: {
 						description: """
 							Action to use when making requests to the [Elasticsearch Bulk API](\(urls.elasticsearch_bulk)).
 							Currently, Vector only supports `index` and `create`. `update` and `delete` actions are not supported.
+							In data stream mode, documents are always sent with the `create` action, and setting any other
+							action is a configuration error.
 							"""
 						required:    false
 						type: string: {
@@ -271,11 +273,12 @@ components: sinks: elasticsearch: {
 		}
 		pipeline: {
 			common:      true
-			description: "Name of the pipeline to apply."
+			description: "Name of the [ingest pipeline](\(urls.elasticsearch_ingest_pipelines)) to apply. Static names are applied to whole requests, while names depending on the events are applied to each document, events the name can't be rendered for being dropped."
 			required:    false
 			type: string: {
 				default: null
-				examples: ["pipeline-name"]
+				examples: ["pipeline-name", "{{ service }}-pipeline"]
+				syntax: "template"
 			}
 		}
 		query: {
@@ -323,7 +326,12 @@ components: sinks: elasticsearch: {
 				By default, Vector uses the `index` action with Elasticsearch's Bulk API.
 				To use [Data streams](\(urls.elasticsearch_data_streams)), set the `mode` to
 				`data_stream`. Use the combination of `data_stream.type`, `data_stream.dataset` and
-				`data_stream.namespace` instead of `index`.
+				`data_stream.namespace` instead of `index`. Each of them is a template, so events
+				can be routed to different data streams, and with `data_stream.auto_routing` the
+				`data_stream` fields of the events take precedence.
+
+				Data streams are append only, so Vector always sends their documents with the
+				`create` action.
 				"""
 		}
 
@@ -334,6 +342,9 @@ components: sinks: elasticsearch: {
 				due to Elasticsearch index mapping errors, where data keys aren't consistently
 				typed. To change this behavior, refer to the Elasticsearch [`ignore_malformed`
 				setting](\(urls.elasticsearch_ignore_malformed)).
+
+				Vector logs the index, status, error type and reason of each rejected document,
+				and counts them in the `elasticsearch_rejected_documents_total` metric.
 				"""
 		}
 
@@ -341,11 +352,12 @@ components: sinks: elasticsearch: {
 	}
 
 	telemetry: metrics: {
-		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
-		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
-		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
-		events_discarded_total:           components.sources.internal_metrics.output.metrics.events_discarded_total
-		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
-		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+		component_sent_bytes_total:             components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:            components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		elasticsearch_rejected_documents_total: components.sources.internal_metrics.output.metrics.elasticsearch_rejected_documents_total
+		events_discarded_total:                 components.sources.internal_metrics.output.metrics.events_discarded_total
+		events_out_total:                       components.sources.internal_metrics.output.metrics.events_out_total
+		processing_errors_total:                components.sources.internal_metrics.output.metrics.processing_errors_total
 	}
 }
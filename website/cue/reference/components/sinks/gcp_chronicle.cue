@@ -0,0 +1,157 @@
+package metadata
+
+components: sinks: gcp_chronicle: {
+	title: "GCP Chronicle"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: ["GCP"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    1_000_000
+				max_events:   null
+				timeout_secs: 15
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					batched: true
+					enum: ["json", "text"]
+				}
+			}
+			proxy: enabled: true
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.gcp_chronicle
+
+				interface: {
+					socket: {
+						api: {
+							title: "Chronicle Ingestion API"
+							url:   urls.gcp_chronicle_ingestion_api
+						}
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "required"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		api: {
+			common:      true
+			description: "The ingestion API to send the events to."
+			required:    false
+			type: string: {
+				default: "unstructured"
+				enum: {
+					unstructured: "Sends each event as a raw log line, parsed by Chronicle according to its `log_type`."
+					udm:          "Sends each event as is, which must already be a [UDM event](\(urls.gcp_chronicle_udm))."
+				}
+			}
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the Chronicle ingestion API. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		customer_id: {
+			description: "The unique identifier of the Chronicle instance to send the events to."
+			required:    true
+			type: string: {
+				examples: ["c8c65bfa-5f2c-42d4-9189-64bb7b939f2c"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint to which to send data, overriding the one of the `region`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["https://malachiteingestion-pa.googleapis.com"]
+			}
+		}
+		log_type: {
+			common:      true
+			description: "The [log type](\(urls.gcp_chronicle_log_types)) of the unstructured entries, which selects the parser applied to them. Events are sent in separate requests for each log type. Required with the `unstructured` API and ignored otherwise."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["WINDOWS_DNS", "{{ log_type }}"]
+				syntax: "template"
+			}
+		}
+		region: {
+			common:      true
+			description: "The region of the Chronicle instance, which selects the endpoint of the ingestion API."
+			required:    false
+			type: string: {
+				default: "us"
+				enum: {
+					us:   "United States"
+					eu:   "Europe"
+					uk:   "United Kingdom"
+					asia: "Asia"
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		unstructured_entries: {
+			title: "Unstructured entries"
+			body:  """
+				With the `unstructured` API, the `text` codec sends the `message` of the events as the log
+				lines, whereas the `json` codec sends the whole events encoded as JSON. The timestamp of the
+				events is sent along with the entries, so that Chronicle doesn't use the time of ingestion.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
@@ -33,7 +33,7 @@ components: sinks: gcp_cloud_storage: {
 				codec: {
 					enabled: true
 					batched: true
-					enum: ["ndjson", "text"]
+					enum: ["ndjson", "text", "msgpack"]
 				}
 			}
 			proxy: enabled: true
@@ -168,6 +168,7 @@ components: sinks: gcp_cloud_storage: {
 				}
 			}
 		}
+		parquet: components._parquet.configuration.parquet
 	}
 
 	input: {
@@ -176,6 +177,8 @@ components: sinks: gcp_cloud_storage: {
 	}
 
 	how_it_works: {
+		parquet: components._parquet.how_it_works.parquet
+
 		object_access_control_list: {
 			title: "Object access control list (ACL)"
 			body:  """
@@ -0,0 +1,163 @@
+package metadata
+
+components: sinks: grpc: {
+	title: "gRPC"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_events:   1000
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.grpc
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address of the gRPC server, the scheme defaulting to `http`, or `https` with TLS."
+			required:    true
+			type: string: {
+				examples: ["http://127.0.0.1:50051", "logs.example.com:443"]
+			}
+		}
+		descriptor_set_file: {
+			description: "The path of a [file descriptor set](\(urls.protobuf_self_description)) holding the service of the method and the types of its messages, as written by `protoc --include_imports --descriptor_set_out`."
+			required:    true
+			type: string: {
+				examples: ["/etc/vector/service.desc"]
+			}
+		}
+		fields: {
+			common:      true
+			description: "The fields of the messages, by their paths, and the fields of the events they are read from. When not set, each field of the messages is read from the field of the events with the same name."
+			required:    false
+			type: object: {
+				examples: [{"message": "message", "host.name": "host", "labels": "tags"}]
+				options: {
+					"*": {
+						common:      false
+						description: "The field of the events the field of the messages is read from."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["host"]
+						}
+					}
+				}
+			}
+		}
+		headers: {
+			common:      false
+			description: "The metadata sent along with each request."
+			required:    false
+			type: object: {
+				examples: [{"authorization": "Bearer ${GRPC_TOKEN}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "A metadata entry."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["Bearer ${GRPC_TOKEN}"]
+						}
+					}
+				}
+			}
+		}
+		method: {
+			description: "The method called with the events. Unary and client streaming methods are supported."
+			required:    true
+			type: string: {
+				examples: ["logs.v1.LogService/Push"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		messages: {
+			title: "Messages"
+			body:  """
+				Each event is encoded as a message of the input type of the method, as found in the
+				descriptor set. Nested messages are read from objects, repeated fields from arrays,
+				and maps from objects. Strings are parsed into numeric and boolean fields, enum fields
+				take either the names or the numbers of their values, and `google.protobuf.Timestamp`
+				fields take timestamps or RFC 3339 strings. Fields of the events which aren't fields of
+				the message are left out, and events with values which can't be encoded into their
+				fields are rejected.
+				"""
+		}
+		methods: {
+			title: "Methods"
+			body:  """
+				Unary methods are called once per event, concurrently as configured by the `request`
+				options. Client streaming methods are called once per batch, the messages of the batch
+				being streamed in a single call. The responses are discarded. Calls failing with the
+				`NOT_FOUND`, `INVALID_ARGUMENT`, `ALREADY_EXISTS`, `PERMISSION_DENIED`,
+				`OUT_OF_RANGE`, `UNIMPLEMENTED` or `UNAUTHENTICATED` codes aren't retried.
+				"""
+		}
+		healthcheck: {
+			title: "Health checks"
+			body:  """
+				The health check uses the standard [health checking protocol](\(urls.grpc_health_checking))
+				for the service of the method, and passes for servers which don't implement it.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
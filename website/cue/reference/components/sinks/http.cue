@@ -33,13 +33,14 @@ components: sinks: http: {
 				codec: {
 					enabled: true
 					batched: true
-					enum: ["json", "ndjson", "text"]
+					enum: ["json", "ndjson", "otlp", "text"]
 				}
 			}
 			proxy: enabled: true
 			request: {
-				enabled: true
-				headers: true
+				enabled:     true
+				headers:     true
+				compression: true
 			}
 			tls: {
 				enabled:                true
@@ -88,6 +89,110 @@ components: sinks: http: {
 				examples: ["https://10.22.212.22:9000/endpoint"]
 			}
 		}
+		signing: {
+			common:      false
+			description: "Signs the requests, for endpoints authenticating them by their signatures. The health check requests are signed too."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					algorithm: {
+						common:      false
+						description: "The hash function of the HMAC. Only used with the `hmac` strategy."
+						required:    false
+						type: string: {
+							default: "sha256"
+							enum: {
+								sha256: "HMAC-SHA256."
+								sha512: "HMAC-SHA512."
+							}
+						}
+					}
+					auth: {
+						common:      false
+						description: "The AWS credentials the requests are signed with. Only used with the `aws` strategy, the default credentials chain being used when not set."
+						required:    false
+						type: object: {
+							examples: []
+							options: components._aws.configuration.auth.type.object.options
+						}
+					}
+					header: {
+						common:      false
+						description: "The header carrying the signature. Only used with the `hmac` strategy."
+						required:    false
+						type: string: {
+							default: "X-Signature"
+							examples: ["X-Hub-Signature-256"]
+						}
+					}
+					prefix: {
+						common:      false
+						description: "Prepended to the signature in its header. Only used with the `hmac` strategy."
+						required:    false
+						type: string: {
+							default: ""
+							examples: ["sha256="]
+						}
+					}
+					region: {
+						description:   "The [AWS region](\(urls.aws_regions)) of the target service."
+						relevant_when: "strategy = \"aws\""
+						required:      true
+						type: string: {
+							examples: ["us-east-1"]
+						}
+					}
+					secret: {
+						description:   "The shared secret the HMAC is keyed by."
+						relevant_when: "strategy = \"hmac\""
+						required:      true
+						type: string: {
+							examples: ["${HTTP_SIGNING_SECRET}"]
+						}
+					}
+					service: {
+						description:   "The name of the AWS service the requests are signed for, such as `aoss` for OpenSearch Serverless or `execute-api` for API Gateway."
+						relevant_when: "strategy = \"aws\""
+						required:      true
+						type: string: {
+							examples: ["aoss", "execute-api"]
+						}
+					}
+					signature_encoding: {
+						common:      false
+						description: "How the signature is encoded in its header. Only used with the `hmac` strategy."
+						required:    false
+						type: string: {
+							default: "hex"
+							enum: {
+								hex:    "Lowercase hexadecimal."
+								base64: "Standard Base64."
+							}
+						}
+					}
+					strategy: {
+						description: "The signing strategy to use."
+						required:    true
+						type: string: {
+							enum: {
+								aws:  "Signs the requests with [AWS Signature Version 4](\(urls.aws_sigv4)). Can't be used along with `auth` or an `Authorization` header."
+								hmac: "Signs the bodies of the requests, once compressed, with an HMAC keyed by `secret`."
+							}
+						}
+					}
+					timestamp_header: {
+						common:      false
+						description: "When set, the current UNIX timestamp is sent in this header and signed along with the body, as `<timestamp>.<body>`. Only used with the `hmac` strategy."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["X-Timestamp"]
+						}
+					}
+				}
+			}
+		}
 		healthcheck: type: object: options: uri: {
 			common: false
 			description: """
@@ -33,7 +33,7 @@ components: sinks: kafka: {
 				enabled: true
 				codec: {
 					enabled: true
-					enum: ["json", "text", "ndjson"]
+					enum: ["json", "text", "ndjson", "msgpack"]
 				}
 			}
 			request: enabled: false
@@ -133,6 +133,101 @@ components: sinks: kafka: {
 				examples: ["headers"]
 			}
 		}
+		idempotence: {
+			common:      false
+			description: "Makes the producer idempotent, sets `enable.idempotence`, so that retried messages are not duplicated, and are written in the order they were sent."
+			required:    false
+			type: bool: default: false
+		}
+		schema_validation: {
+			common:      false
+			description: "Validates the encoded payloads against the latest schema of their subject in a [Schema Registry](\(urls.confluent_schema_registry)) before producing them. The events whose payload doesn't match are rejected, and routed to the `dead_letter` output of the sink when configured. Avro and JSON schemas are checked, the payload being parsed as JSON, so the `json` or `ndjson` encoding codec is required. Protobuf schemas aren't checked. While the schema of a subject can't be fetched, the schema fetched last is used, and the events are produced unchecked if there is none."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					auth: configuration._http_auth & {_args: {
+						password_example: "${SCHEMA_REGISTRY_PASSWORD}"
+						username_example: "${SCHEMA_REGISTRY_USERNAME}"
+					}}
+					refresh_secs: {
+						common:      false
+						description: "How often the latest schema of a subject is fetched again."
+						required:    false
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+					}
+					subject: {
+						common:      false
+						description: "The subject of the schema the payloads are validated against. Defaults to the `<topic>-value` subject of the topic of each event."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["events-value"]
+						}
+					}
+					tls: configuration._tls_connect & {_args: {
+						can_enable:             false
+						can_verify_certificate: true
+						can_verify_hostname:    true
+						enabled_default:        false
+					}}
+					url: {
+						description: "The base URL of the Schema Registry."
+						required:    true
+						type: string: {
+							examples: ["http://localhost:8081"]
+						}
+					}
+				}
+			}
+		}
+		transaction: {
+			common:      false
+			description: "Sends the events in transactions, the producer being idempotent. The events of a transaction are only acknowledged once it is committed, and those of aborted transactions are marked as errored, letting sources supporting end-to-end acknowledgements deliver them again. Consumers reading with `isolation.level` set to `read_committed` don't see the messages of aborted transactions, avoiding duplicates in Kafka-to-Kafka pipelines."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					commit_interval_ms: {
+						common:      false
+						description: "The maximum time a transaction is kept open before being committed, which must be lower than `timeout_ms`."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    "milliseconds"
+						}
+					}
+					max_events: {
+						common:      false
+						description: "The maximum number of events sent in a transaction."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    "events"
+						}
+					}
+					timeout_ms: {
+						common:      false
+						description: "The time after which the brokers abort an open transaction, sets `transaction.timeout.ms`."
+						required:    false
+						type: uint: {
+							default: 60000
+							unit:    "milliseconds"
+						}
+					}
+					transactional_id: {
+						description: "Identifies the producer across restarts, sets `transactional.id`, letting the brokers abort the transactions left open by a previous instance. It must be unique to each instance of the sink."
+						required:    true
+						type: string: {
+							examples: ["vector-kafka-sink"]
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
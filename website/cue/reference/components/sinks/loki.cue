@@ -125,6 +125,7 @@ components: sinks: loki: {
 				enum: {
 					"drop":              "Drop the event."
 					"rewrite_timestamp": "Rewrite timestamp of the event to the latest timestamp that was pushed."
+					"accept":            "Send the event as it is, for Loki to ingest it within its [out-of-order window](\(urls.loki_out_of_order_writes)). Requires Loki 2.4 or later."
 				}
 			}
 		}
@@ -134,18 +135,53 @@ components: sinks: loki: {
 			required:    false
 			type: bool: default: false
 		}
-
+		remove_structured_metadata_fields: {
+			common:      false
+			description: "If this is set to `true` then when structured metadata is collected from events those fields will also get removed from the event."
+			required:    false
+			type: bool: default: false
+		}
 		remove_timestamp: {
 			common:      false
 			description: "If this is set to `true` then the timestamp will be removed from the event payload. Note the event timestamp will still be sent as metadata to Loki for indexing."
 			required:    false
 			type: bool: default: true
 		}
+		structured_metadata: {
+			common:      false
+			description: """
+				A set of [structured metadata](\(urls.loki_structured_metadata)) attached to each line. Both keys and
+				values are templatable. Unlike labels, structured metadata doesn't define the streams of the lines, and
+				can hold values of high cardinality such as trace ids. Requires Loki 3.0 or later.
+				"""
+			required: false
+			type: object: {
+				examples: [
+					{
+						"trace_id":              "{{ trace_id }}"
+						"\"{{ event_field }}\"": "{{ another_event_field }}"
+					},
+				]
+				options: {
+					"*": {
+						common:      false
+						description: "Any structured metadata, templatable"
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{{ trace_id }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 		tenant_id: {
 			common:      false
 			description: """
 				The tenant id that's sent with every request, by default this is not required since a proxy should set
-				this header. When running Loki locally a tenant id is not required either.
+				this header. When running Loki locally a tenant id is not required either. The events are batched by
+				tenant, each request holding the streams of a single tenant.
 
 				You can read more about tenant id's [here](\(urls.loki_multi_tenancy)).
 				"""
@@ -173,7 +209,10 @@ components: sinks: loki: {
 				races between Vector instances. To avoid this we suggest
 				either assigning each Vector instance with a unique label
 				or deploying a centralized Vector which will ensure no logs
-				will get sent out-of-order.
+				will get sent out-of-order. Since Loki 2.4, the
+				`out_of_order_action` can alternatively be set to `accept`
+				for Loki to ingest such logs within its out-of-order
+				window.
 				"""
 		}
 
@@ -185,6 +224,12 @@ components: sinks: loki: {
 				accepted by Loki. If no timestamp is supplied with events
 				then the Loki sink will supply its own monotonically
 				increasing timestamp.
+
+				Since Loki 2.4, out-of-order writes are accepted within a
+				window of the latest line of each stream. Setting
+				`out_of_order_action` to `accept` leaves the late events
+				as they are rather than dropping them or rewriting their
+				timestamps.
 				"""
 		}
 	}
@@ -13,7 +13,7 @@ components: sinks: nats: {
 	}
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		healthcheck: enabled: true
 		send: {
 			compression: enabled: false
@@ -52,14 +52,80 @@ components: sinks: nats: {
 		notices: []
 	}
 
-	configuration: components._nats.configuration & {}
+	configuration: components._nats.configuration & {
+		jetstream: {
+			common:      false
+			description: "Publishes the messages to [JetStream](\(urls.nats_jetstream)), each of them being acknowledged by the stream storing it."
+			required:    false
+			type: object: options: {
+				ack_timeout_secs: {
+					common:      false
+					description: "How long to wait for the acknowledgement of each message, the events of the messages which aren't acknowledged in time being reported as errored."
+					required:    false
+					type: uint: {
+						default: 5
+						unit:    "seconds"
+					}
+				}
+				enabled: {
+					common:      true
+					description: "Publishes the messages to JetStream."
+					required:    false
+					type: bool: default: false
+				}
+				expected_stream: {
+					common:      false
+					description: "The stream expected to store the messages, sent as the `Nats-Expected-Stream` header. The messages stored by other streams are rejected."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["logs"]
+					}
+				}
+				message_id: {
+					common:      true
+					description: "The identifier of the messages, sent as the `Nats-Msg-Id` header, by which the streams deduplicate the messages published more than once within their duplicate windows."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["{{ event_id }}"]
+						syntax: "template"
+					}
+				}
+			}
+		}
+	}
 
 	input: {
 		logs:    true
 		metrics: null
 	}
 
-	how_it_works: components._nats.how_it_works
+	how_it_works: components._nats.how_it_works & {
+		jetstream: {
+			title: "JetStream"
+			body:  """
+				When `jetstream.enabled` is set, the sink waits for each message to be acknowledged
+				by the stream storing it before publishing the next one, the events being delivered
+				once acknowledged, including messages the stream deduplicated. Messages refused by
+				the streams reject their events, while those which can't be published, or aren't
+				acknowledged within `jetstream.ack_timeout_secs`, report their events as errored.
+
+				With `jetstream.message_id`, events sent again, such as after being redelivered by
+				their sources, are only stored once by the streams, as long as they are published
+				within the duplicate windows of the streams.
+				"""
+		}
+		subject_sanitization: {
+			title: "Subject sanitization"
+			body:  """
+				When the `subject` is a template, the characters which aren't allowed in the
+				subjects messages are published to, such as whitespace and the `*` and `>`
+				wildcards, are replaced by underscores in the rendered subjects, as are their empty
+				tokens. The dots of the rendered values of fields still separate tokens.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
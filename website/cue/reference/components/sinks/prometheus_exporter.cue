@@ -125,6 +125,39 @@ components: sinks: prometheus_exporter: {
 			required:    false
 			type: bool: default: false
 		}
+		exemplar_tags: {
+			common: false
+			description: """
+				The tags of histogram [distributions](\(urls.vector_data_model)/metric#distribution), typically
+				holding trace IDs, that are exposed as the labels of the [exemplars](\(urls.prometheus_exemplars))
+				of their buckets rather than as the labels of their series. Exemplars are only served in the
+				protobuf exposition format.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: examples: ["trace_id", "span_id"]
+			}
+		}
+		native_histograms: {
+			common: false
+			description: """
+				Whether or not to also expose histogram [distributions](\(urls.vector_data_model)/metric#distribution)
+				as [native histograms](\(urls.prometheus_native_histograms)), which are only served in the protobuf
+				exposition format.
+				"""
+			required: false
+			type: bool: default: false
+		}
+		native_histogram_schema: {
+			common: false
+			description: """
+				The resolution of the native histograms, from -4 to 8. Each power of two is divided into
+				`2^native_histogram_schema` buckets.
+				"""
+			required: false
+			type: float: default: 3.0
+		}
 	}
 
 	input: {
@@ -359,6 +392,20 @@ components: sinks: prometheus_exporter: {
 			]
 		}
 
+		native_histograms_and_exemplars: {
+			title: "Native Histograms and Exemplars"
+			body: """
+				When `native_histograms` is enabled or `exemplar_tags` are set, the metrics are served in the
+				protobuf exposition format to the clients asking for it in their `Accept` header, as
+				Prometheus does when scraping native histograms. The other clients still receive the text
+				format, which holds neither.
+
+				Native histograms are computed from the samples of the distributions, and are served
+				alongside the buckets configured with `buckets`. The exemplar of each bucket is the last
+				sample that fell into it with any of the `exemplar_tags`.
+				"""
+		}
+
 		memory_usage: {
 			title: "Memory Usage"
 			body: """
@@ -143,6 +143,27 @@ components: sinks: prometheus_remote_write: {
 		}
 	}
 
+	how_it_works: {
+		metadata: {
+			title: "Metric metadata"
+			body: """
+				The type of each metric family is sent as metadata along
+				with its samples. Its help is the description of the metrics,
+				as received by the `prometheus_remote_write` source, or else
+				their name, and its unit is the unit of the metrics, if known.
+				"""
+		}
+		staleness_markers: {
+			title: "Staleness markers"
+			body: """
+				The staleness markers received by the
+				`prometheus_remote_write` source are sent as is. The series
+				of histograms and summaries whose sum is a staleness marker
+				are all sent as stale.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
@@ -77,15 +77,32 @@ components: sinks: redis: {
 				syntax: "template"
 			}
 		}
+		key_hash_tag: {
+			common:      false
+			description: "The [hash tag](\(urls.redis_cluster_hash_tags)) the keys are prefixed with, as `{tag}`. The keys with the same hash tag are stored on the same node in cluster mode."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ app }}"]
+				syntax: "template"
+			}
+		}
+		cluster: {
+			common:      false
+			description: "Whether `url` is one of the nodes of a Redis cluster, the other nodes being discovered from it. Each command is sent to the node holding its key."
+			required:    false
+			type: bool: default: false
+		}
 		data_type: {
 			common:      false
-			description: "The Redis data type (`list` or `channel`) to use."
+			description: "The Redis data type (`list`, `channel` or `stream`) to use."
 			required:    false
 			type: string: {
 				default: "list"
 				enum: {
 					list:    "Use the Redis `list` data type."
 					channel: "Use the Redis `channel` data type."
+					stream:  "Use the Redis `stream` data type, adding entries with `XADD`."
 				}
 			}
 		}
@@ -111,6 +128,54 @@ components: sinks: redis: {
 				}
 			}
 		}
+		stream: {
+			common:      false
+			description: "Options for the Redis `stream` data type."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					field: {
+						common:      false
+						description: "The field of the entries holding the encoded events."
+						required:    false
+						type: string: default: "message"
+					}
+					maxlen: {
+						common:      false
+						description: "The length the streams are trimmed to as entries are added. The streams aren't trimmed if not set."
+						required:    false
+						type: uint: {
+							default: null
+							examples: [100000]
+							unit: null
+						}
+					}
+					approximate: {
+						common:      false
+						description: "Whether the streams may be trimmed to slightly more entries than `maxlen`, which is much more efficient."
+						required:    false
+						type: bool: default: true
+					}
+				}
+			}
+		}
+		pipeline: {
+			common:      false
+			description: "Options for the pipelines the events of a batch are sent in."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					atomic: {
+						common:      false
+						description: "Whether the commands of a batch are run in a `MULTI`/`EXEC` transaction. Defaults to `true`, unless `cluster` is enabled, which doesn't support transactions."
+						required:    false
+						type: bool: default: null
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -127,6 +192,16 @@ components: sinks: redis: {
 				API.
 				"""
 		}
+		pipelining: {
+			title: "Pipelining"
+			body:  """
+				The events of a batch, up to `batch.max_events`, are sent in a single pipeline,
+				which is run as a transaction if `pipeline.atomic` is enabled. Up to
+				`request.concurrency` pipelines are in flight at any time. In cluster mode, the
+				pipelines are split by the nodes holding the keys of the events, which
+				`key_hash_tag` can keep on the same node.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
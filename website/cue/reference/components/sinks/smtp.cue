@@ -0,0 +1,214 @@
+package metadata
+
+components: sinks: smtp: {
+	title: "SMTP"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       true
+				max_events:   1
+				timeout_secs: 1
+			}
+			compression: enabled: false
+			encoding: enabled:    false
+			request: {
+				enabled:                  true
+				adaptive_concurrency:     false
+				concurrency:              1
+				rate_limit_duration_secs: 60
+				rate_limit_num:           10
+				headers:                  false
+			}
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.smtp
+
+				interface: {
+					socket: {
+						api: {
+							title: "SMTP"
+							url:   urls.rfc_5321
+						}
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		auth: {
+			common:      true
+			description: "The credentials of the sender, authenticated with the `PLAIN` or `LOGIN` mechanisms."
+			required:    false
+			type: object: options: {
+				password: {
+					description: "The password of the sender."
+					required:    true
+					type: string: {
+						examples: ["${SMTP_PASSWORD}"]
+					}
+				}
+				user: {
+					description: "The user name of the sender."
+					required:    true
+					type: string: {
+						examples: ["vector@example.com"]
+					}
+				}
+			}
+		}
+		body: {
+			common:      true
+			description: "The body of the emails, rendered for each of their events. The bodies of the events of digests are separated by line breaks."
+			required:    false
+			type: string: {
+				default: "{{ message }}"
+				examples: ["{{ timestamp }} [{{ level }}] {{ message }}"]
+				syntax: "template"
+			}
+		}
+		digest_key: {
+			common:      false
+			description: "Partitions the batches, so that each digest only holds the events with the same rendered key. Events for which the key can't be rendered are digested together."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ service }}", "{{ host }}-{{ level }}"]
+				syntax: "template"
+			}
+		}
+		from: {
+			description: "The sender of the emails, with or without a display name."
+			required:    true
+			type: string: {
+				examples: ["vector@example.com", "Vector <vector@example.com>"]
+			}
+		}
+		helo_name: {
+			common:      false
+			description: "The name the sink introduces itself with to the server. Defaults to the hostname."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["vector.example.com"]
+			}
+		}
+		host: {
+			description: "The host of the SMTP server."
+			required:    true
+			type: string: {
+				examples: ["smtp.example.com", "127.0.0.1"]
+			}
+		}
+		port: {
+			common:      true
+			description: "The port of the SMTP server. Defaults to `465` when `tls.enabled` is set, and to `587` otherwise."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [25, 465, 587]
+				unit: null
+			}
+		}
+		starttls: {
+			common:      true
+			description: "Whether the connections are upgraded to TLS with [`STARTTLS`](\(urls.rfc_3207)). Ignored when `tls.enabled` is set, TLS being negotiated on connect."
+			required:    false
+			type: string: {
+				default: "required"
+				enum: {
+					required:      "Upgrades the connections, failing to send the emails when the server doesn't support `STARTTLS`."
+					opportunistic: "Upgrades the connections when the server supports `STARTTLS`, and sends the emails in plain text otherwise."
+					disabled:      "Sends the emails in plain text."
+				}
+			}
+		}
+		subject: {
+			description: "The subject of the emails, rendered from the first event of digests."
+			required:    true
+			type: string: {
+				examples: ["Alert from {{ host }}", "{{ service }} errors"]
+				syntax: "template"
+			}
+		}
+		to: {
+			description: "The recipients of the emails, with or without display names."
+			required:    true
+			type: array: items: type: string: {
+				examples: ["oncall@example.com", "On call <oncall@example.com>"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		digests: {
+			title: "Digests"
+			body:  """
+				Each batch is sent as a single email, and each event in its own email by default,
+				`batch.max_events` being `1`. Raising `batch.max_events` and `batch.timeout_secs`
+				sends digests instead, holding the rendered bodies of the events of a batch, one
+				after the other. With `digest_key`, the batches are partitioned by the rendered key,
+				so that unrelated events are sent in separate digests. Events whose subject or body
+				can't be rendered are dropped.
+				"""
+		}
+		rate_limits: {
+			title: "Rate limits"
+			body:  """
+				The sink is meant for low volume alerts. Emails are sent one at a time, over a new
+				connection each, and at most ten per minute by default, as mail servers often limit
+				the rate of their senders. Replies in the 4xx range, such as those of servers
+				throttling the sink, are retried, while those in the 5xx range reject the events.
+				"""
+		}
+		tls: {
+			title: "TLS"
+			body:  """
+				By default, the connections are upgraded to TLS with `STARTTLS`, as expected by mail
+				submission servers on port `587`, and the emails aren't sent to servers which don't
+				support it. Setting `tls.enabled` negotiates TLS on connect instead, as expected on
+				port `465`. The other `tls` options apply to both.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		events_out_total:                 components.sources.internal_metrics.output.metrics.events_out_total
+	}
+}
@@ -31,6 +31,15 @@ components: sinks: _splunk_hec: {
 							unit:    null
 						}
 					}
+					resend_limit: {
+						common:      false
+						description: "The maximum number of times a request will be resent once its ack id has been queried `retry_limit` times without being acknowledged, which happens when Splunk loses the ack ids, such as on restarts. Set to `0` to consider the events rejected right away instead."
+						required:    false
+						type: uint: {
+							default: 3
+							unit:    null
+						}
+					}
 					max_pending_acks: {
 						common:      false
 						description: "The maximum number of ack ids pending query. Once reached, the sink will begin applying backpressure."
@@ -65,6 +74,11 @@ components: sinks: _splunk_hec: {
 				The Splunk channel required for indexer acknowledgements is created using a randomly generated UUID. By default, this sink uses the
 				recommended Splunk indexer acknowledgements client behavior: querying for ack statuses every 10 seconds for a maximum of 30 attempts
 				(5 minutes) per `ackID`.
+
+				Splunk doesn't persist the statuses of the `ackID`'s, so those of the requests still pending when it restarts are never
+				acknowledged. Rather than considering their events rejected, this sink resends the requests whose `ackID`'s weren't
+				acknowledged within `retry_limit` queries, up to `resend_limit` times, and only confirms delivery once Splunk acknowledges
+				a resent request. This may deliver the same events more than once to Splunk.
 				"""
 		}
 		splunk_channel: {
@@ -0,0 +1,219 @@
+package metadata
+
+components: sinks: syslog: {
+	title: "Syslog"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: false
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			send_buffer_bytes: enabled: true
+			keepalive: enabled:         true
+			request: enabled:           false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.syslog
+
+				interface: {
+					socket: {
+						api: {
+							title: "Syslog"
+							url:   urls.syslog
+						}
+						direction: "outgoing"
+						protocols: ["tcp", "udp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		address: {
+			description: "The address to connect to. The address _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["92.12.333.224:514"]
+			}
+		}
+		app_name: {
+			common:      true
+			description: "The application name of the messages, truncated to 48 characters, or 32 for RFC 3164."
+			required:    false
+			type: string: {
+				default: "vector"
+				examples: ["{{ appname }}", "my-app"]
+				syntax: "template"
+			}
+		}
+		facility: {
+			common:      true
+			description: "The facility of the messages, either its name, such as `local0`, or its code. If it can't be rendered to a valid facility, the default one is used."
+			required:    false
+			type: string: {
+				default: "user"
+				examples: ["{{ facility }}", "local0", "16"]
+				syntax: "template"
+			}
+		}
+		format: {
+			common:      true
+			description: "The format of the messages."
+			required:    false
+			type: string: {
+				default: "rfc5424"
+				enum: {
+					rfc5424: "Formatted as specified in [RFC 5424](\(urls.syslog_5424))."
+					rfc3164: "Formatted as specified in [RFC 3164](\(urls.syslog_3164)), as expected by legacy collectors."
+				}
+			}
+		}
+		framing: {
+			common:        false
+			description:   "How the messages are framed, as specified in [RFC 6587](\(urls.syslog_6587))."
+			relevant_when: "mode = `tcp`"
+			required:      false
+			type: string: {
+				default: "octet_counting"
+				enum: {
+					octet_counting:  "Each message starts with its length."
+					non_transparent: "Each message ends with a newline."
+				}
+			}
+		}
+		mode: {
+			description: "The type of socket to use."
+			required:    true
+			type: string: {
+				enum: {
+					tcp: "TCP socket"
+					udp: "UDP socket"
+				}
+			}
+		}
+		msg_id: {
+			common:      false
+			description: "The type of the messages, truncated to 32 characters. Not part of the RFC 3164 format."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ msgid }}", "ID47"]
+				syntax: "template"
+			}
+		}
+		proc_id: {
+			common:      false
+			description: "The ID of the process sending the messages, truncated to 128 characters."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ procid }}"]
+				syntax: "template"
+			}
+		}
+		severity: {
+			common:      true
+			description: "The severity of the messages, either its name, such as `notice`, or its code. If it can't be rendered to a valid severity, the default one is used."
+			required:    false
+			type: string: {
+				default: "info"
+				examples: ["{{ severity }}", "notice", "5"]
+				syntax: "template"
+			}
+		}
+		structured_data: {
+			common:      false
+			description: "The root level fields holding the structured data elements of the messages, each an object of the parameters, named after the field. Not part of the RFC 3164 format."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["exampleSDID@32473"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		formatting: {
+			title: "Formatting"
+			body: """
+				The header of each message is made of its priority, computed from its facility and
+				severity, its timestamp, taken from the `timestamp` field or the current time, and
+				its hostname, taken from the `host` field or the hostname of the machine Vector
+				runs on. Then come the application name and the process ID, as well as the message
+				type and the structured data for RFC 5424. The fields that are missing are
+				replaced with `-`.
+
+				The timestamps are in UTC, with milliseconds for RFC 5424.
+
+				The `text` codec uses the `message` field as the content of the message, while the
+				`json` codec uses the whole event.
+				"""
+		}
+
+		structured_data: {
+			title: "Structured Data"
+			body: """
+				Each of the `structured_data` fields is formatted as an element with the ID of the
+				field name and a parameter for each of its keys. The events received by the
+				`syslog` source keep their structured data elements in such fields, so that they
+				can be forwarded unchanged. The `"`, `\\\\` and `]` characters of the parameter
+				values are escaped.
+				"""
+		}
+
+		framing: {
+			title: "Framing"
+			body: """
+				Over TCP, the messages are octet counted by default, each starting with its length,
+				so that they can contain newlines. Legacy collectors that expect one message per
+				line can be sent non-transparently framed messages instead, with
+				`framing = "non_transparent"`. Over UDP, each datagram holds a single message.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:  components.sources.internal_metrics.output.metrics.component_errors_total
+		connection_errors_total: components.sources.internal_metrics.output.metrics.connection_errors_total
+		processed_bytes_total:   components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:  components.sources.internal_metrics.output.metrics.processed_events_total
+	}
+}
@@ -84,6 +84,23 @@ components: sinks: vector: {
 				examples: ["92.12.333.224:\(_port)"]
 			}
 		}
+		compression: {
+			common:        false
+			description:   "Compresses the events with zstd, if the source negotiates it. The compression is negotiated on each connection, again after a failed request, and the events are sent uncompressed to the sources not able to decompress them, such as the sources older than the version 2 of the protocol."
+			required:      false
+			relevant_when: "version = \"2\""
+			type: bool: default: false
+		}
+		connections: {
+			common:        false
+			description:   "The number of HTTP/2 connections to the source, the requests being sent over them round-robin. This is a pool of connections, so that a slow connection doesn't delay all the requests. The requests aren't multiplexed per upstream component: the events of all the components feeding the sink are batched together and share these connections, so a slow request still delays the events batched after it."
+			required:      false
+			relevant_when: "version = \"2\""
+			type: uint: {
+				default: 1
+				unit:    null
+			}
+		}
 		version: {
 			description: "Sink API version. Specifying this version ensures that Vector does not break backward compatibility."
 			common:      true
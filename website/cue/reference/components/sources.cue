@@ -89,6 +89,7 @@ components: sources: [Name=string]: {
 									length_delimited:    "Byte frames whose length is encoded in a header."
 									newline_delimited:   "Byte frames which are delimited by a newline character."
 									octet_counting:      "Byte frames according to the [octet counting](\(urls.rfc_6587_3_4_1)) format."
+									pattern_delimited:   "Byte frames which are delimited by the matches of a regular expression, which can span several lines."
 								}
 							}
 						}
@@ -152,6 +153,46 @@ components: sources: [Name=string]: {
 								}
 							}
 						}
+						pattern_delimited: {
+							description:   "Options for `pattern_delimited` framing."
+							required:      true
+							relevant_when: "method = `pattern_delimited`"
+							type: object: options: {
+								boundary: {
+									description: "What the matches of `pattern` are to the frames."
+									required:    false
+									common:      true
+									type: string: {
+										default: "delimiter"
+										enum: {
+											delimiter: "The matches separate the frames, and are discarded."
+											start:     "The matches start the frames, such as the timestamps starting multi-line log records, and are kept. The line break preceding a match is discarded."
+										}
+									}
+								}
+								max_length: {
+									description: "The maximum frame length limit. Any frames longer than `max_length` bytes will be discarded entirely."
+									required:    false
+									common:      false
+									type: uint: {
+										default: null
+										examples: [65535, 102400]
+										unit: "bytes"
+									}
+								}
+								pattern: {
+									description: """
+										The regular expression matching the boundaries of the frames. It must not match empty
+										input, and `(?m)` makes `^` and `$` match at the start and end of lines.
+										"""
+									required: true
+									type: string: {
+										examples: ["(?m)^\\d{4}-\\d{2}-\\d{2} ", "\\n---\\n"]
+										syntax: "regex"
+									}
+								}
+							}
+						}
 					}
 				}
 				decoding: {
@@ -166,9 +207,134 @@ components: sources: [Name=string]: {
 							type: string: {
 								default: "bytes"
 								enum: {
-									bytes:  "Events containing the byte frame as-is."
-									json:   "Events being parsed from a JSON string."
-									syslog: "Events being parsed from a Syslog message."
+									bytes:    "Events containing the byte frame as-is."
+									csv:      "Events being parsed from CSV rows, one event per row."
+									json:     "Events being parsed from a JSON string."
+									msgpack:  "Events being parsed from MessagePack maps, one event per map."
+									protobuf: "Events being parsed from a protobuf message."
+									syslog:   "Events being parsed from a Syslog message."
+								}
+							}
+						}
+						csv: {
+							description:   "Options for the CSV codec."
+							required:      false
+							common:        false
+							relevant_when: "codec = `csv`"
+							type: object: options: {
+								delimiter: {
+									description: "The character separating the columns, such as a tab for TSV."
+									required:    false
+									common:      true
+									type: string: {
+										default: ","
+										examples: [";", "\t"]
+									}
+								}
+								has_headers: {
+									description: """
+										Whether the first row read names the columns, even when the following rows come in
+										separate frames, as with `newline_delimited` framing. Without headers, the columns are
+										named `column_1`, `column_2`, and so on.
+										"""
+									required: false
+									common:   true
+									type: bool: default: true
+								}
+								headers: {
+									description: "The names of the columns, overriding those of the header row."
+									required:    false
+									common:      false
+									type: array: {
+										default: []
+										items: type: string: examples: ["timestamp", "path", "status"]
+									}
+								}
+								infer_types: {
+									description: "Whether the columns without a type in `types` are converted to integers, floats or booleans when they look like ones."
+									required:    false
+									common:      false
+									type: bool: default: false
+								}
+								malformed: {
+									description: "What becomes of the rows that don't have as many columns as there are headers."
+									required:    false
+									common:      false
+									type: string: {
+										default: "error"
+										enum: {
+											error:   "Fail to decode the whole frame."
+											partial: "Decode the columns that have a header, ignoring the extra ones."
+											skip:    "Drop the row."
+										}
+									}
+								}
+								quote: {
+									description: "The character quoting the columns holding delimiters or line breaks."
+									required:    false
+									common:      false
+									type: string: default: "\""
+								}
+								types: {
+									description: "The types to convert the columns to, by column name, as for the `types` of the `grok_parser` transform."
+									required:    false
+									common:      true
+									type: object: {
+										examples: [{status: "int", duration: "float", timestamp: "timestamp|%F %T"}]
+										options: {}
+									}
+								}
+							}
+						}
+						protobuf: {
+							description:   "Options for the protobuf codec."
+							required:      true
+							relevant_when: "codec = `protobuf`"
+							type: object: options: {
+								bytes: {
+									description: "How the `bytes` fields of the messages are represented."
+									required:    false
+									common:      false
+									type: string: {
+										default: "raw"
+										enum: {
+											raw:    "The bytes as they are."
+											base64: "A base64 encoded string."
+										}
+									}
+								}
+								desc_file: {
+									description: """
+										The path to the descriptor set of the message type, as compiled by
+										`protoc --include_imports --descriptor_set_out=<desc_file> <proto files>`.
+										"""
+									required: true
+									type: string: {
+										examples: ["/etc/vector/protos/app.desc"]
+									}
+								}
+								enums: {
+									description: "How the enum fields of the messages are represented."
+									required:    false
+									common:      false
+									type: string: {
+										default: "name"
+										enum: {
+											name:   "The name of the value, or its number if it isn't in the descriptor set."
+											number: "The number of the value."
+										}
+									}
+								}
+								message_type: {
+									description: """
+										The fully qualified name of the message type. The fields missing from a message are
+										left out of its event rather than set to their default value, and unknown fields are
+										ignored.
+										"""
+									required: true
+									type: string: {
+										examples: ["package.Message"]
+									}
 								}
 							}
 						}
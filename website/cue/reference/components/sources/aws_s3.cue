@@ -39,7 +39,8 @@ components: sources: aws_s3: components._aws & {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
 		strategy: {
 			common:      false
 			description: "The strategy to use to consume objects from AWS S3."
@@ -97,13 +98,51 @@ components: sources: aws_s3: components._aws & {
 						required:    false
 						type: bool: default: true
 					}
+					key_pattern: {
+						common:      false
+						description: "If set, only the objects whose keys match this regular expression are fetched. The other notifications are deleted without fetching their objects."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["^logs/[0-9]{4}/"]
+						}
+					}
+					key_prefixes: {
+						common:      false
+						description: "If not empty, only the objects whose keys start with one of these prefixes are fetched. The other notifications are deleted without fetching their objects."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: examples: ["AWSLogs/", "elb/"]
+						}
+					}
+					key_suffixes: {
+						common:      false
+						description: "If not empty, only the objects whose keys end with one of these suffixes are fetched. The other notifications are deleted without fetching their objects."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: examples: [".log.gz", ".json"]
+						}
+					}
 					queue_url: {
-						description: "The URL of the SQS queue to receive bucket notifications from."
-						required:    true
+						common:      true
+						description: "The URL of the SQS queue to receive bucket notifications from. Required unless `queue_urls` is set."
+						required:    false
 						type: string: {
+							default: null
 							examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
 						}
 					}
+					queue_urls: {
+						common:      false
+						description: "The URLs of more SQS queues to receive bucket notifications from, along with `queue_url`. Each queue is polled by `client_concurrency` tasks."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/OtherQueue"]
+						}
+					}
 				}
 			}
 		}
@@ -147,6 +186,15 @@ components: sources: aws_s3: components._aws & {
 	}
 
 	how_it_works: {
+		streaming: {
+			title: "Streaming objects"
+			body: """
+				Objects are decompressed and split into lines as they are downloaded, rather than
+				being read whole beforehand, so that objects larger than the memory available to
+				Vector can be processed. Use the `key_prefixes`, `key_suffixes`, and `key_pattern`
+				options to skip the objects that shouldn't be processed without downloading them.
+				"""
+		}
 		events: {
 			title: "Handling events from the `aws_s3` source"
 			body:  """
@@ -275,5 +323,7 @@ components: sources: aws_s3: components._aws & {
 		sqs_message_receive_succeeded_total:    components.sources.internal_metrics.output.metrics.sqs_message_receive_succeeded_total
 		sqs_message_received_messages_total:    components.sources.internal_metrics.output.metrics.sqs_message_received_messages_total
 		sqs_s3_event_record_ignored_total:      components.sources.internal_metrics.output.metrics.sqs_s3_event_record_ignored_total
+		source_acknowledgement_latency_seconds: components.sources.internal_metrics.output.metrics.source_acknowledgement_latency_seconds
+		source_acknowledgement_timeouts_total:  components.sources.internal_metrics.output.metrics.source_acknowledgement_timeouts_total
 	}
 }
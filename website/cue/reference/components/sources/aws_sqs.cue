@@ -52,7 +52,8 @@ components: sources: aws_sqs: components._aws_new_sdk & {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
 		poll_secs: {
 			common:      true
 			description: "How long to wait when polling SQS for new messages. 0-20 seconds"
@@ -102,10 +103,12 @@ components: sources: aws_sqs: components._aws_new_sdk & {
 	}
 
 	telemetry: metrics: {
-		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
-		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
-		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
-		sqs_message_delete_failed_total:      components.sources.internal_metrics.output.metrics.sqs_message_delete_failed_total
+		component_received_event_bytes_total:   components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:        components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_bytes_total:         components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		sqs_message_delete_failed_total:        components.sources.internal_metrics.output.metrics.sqs_message_delete_failed_total
+		source_acknowledgement_latency_seconds: components.sources.internal_metrics.output.metrics.source_acknowledgement_latency_seconds
+		source_acknowledgement_timeouts_total:  components.sources.internal_metrics.output.metrics.source_acknowledgement_timeouts_total
 	}
 
 	how_it_works: {
@@ -0,0 +1,248 @@
+package metadata
+
+components: sources: azure_event_hubs: {
+	title: "Azure Event Hubs"
+
+	description: """
+		Consumes events from an [Azure Event Hub](\(urls.azure_event_hubs)) through its
+		[Kafka-compatible endpoint](\(urls.azure_event_hubs_kafka)).
+		"""
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["Azure"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			from: {
+				service: services.azure_event_hubs
+				interface: socket: {
+					api: {
+						title: "Kafka protocol"
+						url:   urls.azure_event_hubs_kafka
+					}
+					direction: "outgoing"
+					port:      9093
+					protocols: ["tcp"]
+					ssl: "required"
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The Kafka endpoint is available in the standard, premium and dedicated tiers
+				of Event Hubs.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		connection_string: {
+			description: "The [connection string](\(urls.azure_event_hubs_connection_string)) of the Event Hubs namespace or event hub. It must grant the `Listen` claim."
+			required:    true
+			type: string: {
+				examples: ["Endpoint=sb://example.servicebus.windows.net/;SharedAccessKeyName=listen;SharedAccessKey=${EVENT_HUBS_KEY}"]
+			}
+		}
+		event_hub: {
+			common:      true
+			description: "The name of the event hub to consume from. Defaults to the `EntityPath` of the connection string."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["logs"]
+			}
+		}
+		consumer_group: {
+			common:      true
+			description: "The consumer group to read with. The partitions of the event hub are balanced across all Vector instances reading with the same consumer group."
+			required:    false
+			type: string: {
+				default: "$Default"
+				examples: ["vector"]
+			}
+		}
+		start_position: {
+			common:      false
+			description: "Where to start reading partitions that don't have a checkpoint yet."
+			required:    false
+			type: string: {
+				default: "latest"
+				enum: {
+					earliest: "Start from the oldest event retained by the partition."
+					latest:   "Start from events enqueued after the partition is assigned."
+				}
+			}
+		}
+		checkpoint_store: {
+			common:      false
+			description: "Keep partition checkpoints in an Azure Blob Storage container instead of committing them to the consumer group."
+			required:    false
+			type: object: options: {
+				connection_string: {
+					description: "The Azure Blob Storage account connection string."
+					required:    true
+					type: string: {
+						examples: ["DefaultEndpointsProtocol=https;AccountName=mylogstorage;AccountKey=storageaccountkeybase64encoded;EndpointSuffix=core.windows.net"]
+					}
+				}
+				container_name: {
+					description: "The name of the container to keep checkpoints in."
+					required:    true
+					type: string: {
+						examples: ["checkpoints"]
+					}
+				}
+			}
+		}
+		checkpoint_interval_secs: {
+			common:      false
+			description: "How often checkpoints are written to the checkpoint store."
+			required:    false
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		librdkafka_options: {
+			common:      false
+			description: "Advanced options passed to librdkafka. See the [librdkafka documentation](\(urls.librdkafka_config)) for details."
+			required:    false
+			type: object: {
+				examples: [
+					{
+						"ssl.ca.location": "/etc/ssl/certs/ca-certificates.crt"
+					},
+				]
+				options: {}
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Event Hubs event"
+		fields: {
+			message: {
+				description: "The body of the event."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: {
+				description: "The time the event was enqueued."
+				required:    true
+				type: timestamp: {}
+			}
+			event_hub: {
+				description: "The event hub the event was read from."
+				required:    true
+				type: string: {
+					examples: ["logs"]
+				}
+			}
+			partition: {
+				description: "The partition the event was read from."
+				required:    true
+				type: uint: {
+					examples: [0]
+					unit: null
+				}
+			}
+			offset: {
+				description: "The offset of the event within its partition."
+				required:    true
+				type: uint: {
+					examples: [100]
+					unit: null
+				}
+			}
+			partition_key: {
+				description: "The partition key the event was published with, if any."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["device-1"]
+				}
+			}
+			properties: {
+				description: "The application properties of the event."
+				required:    true
+				type: object: {
+					examples: [{"region": "westeurope"}]
+					options: {}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		partition_balancing: {
+			title: "Partition Balancing"
+			body:  """
+				Vector instances reading with the same `consumer_group` share the partitions of
+				the event hub, each partition being owned by a single instance at a time. When
+				instances join or leave, the partitions are rebalanced across the remaining ones.
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body:  """
+				The offset of the last event delivered from each partition is checkpointed, so
+				a partition that moves to another instance, or Vector restarting, resumes where it
+				left off. By default, checkpoints are committed to the consumer group. If a
+				`checkpoint_store` is set, they are kept in a blob for each partition instead,
+				under `<namespace>/<event hub>/<consumer group>/checkpoint/<partition>`, and the
+				checkpoints of revoked partitions are written out before they are reassigned.
+				Each blob holds the offset as a JSON body, such as `{"offset":1234}`, rather than
+				as the blob metadata the Event Hubs SDKs use, so the container can't be shared
+				with consumers built on those SDKs.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		kafka_queue_messages:                 components.sources.internal_metrics.output.metrics.kafka_queue_messages
+		kafka_queue_messages_bytes:           components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
+		kafka_requests_total:                 components.sources.internal_metrics.output.metrics.kafka_requests_total
+		kafka_requests_bytes_total:           components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
+		kafka_responses_total:                components.sources.internal_metrics.output.metrics.kafka_responses_total
+		kafka_responses_bytes_total:          components.sources.internal_metrics.output.metrics.kafka_responses_bytes_total
+		kafka_consumed_messages_total:        components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
+		kafka_consumed_messages_bytes_total:  components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
+	}
+}
@@ -161,6 +161,16 @@ components: sources: docker_logs: {
 			required: false
 			type: bool: default: true
 		}
+		container_events: {
+			common: false
+			description: """
+				Setting this to `true` will emit the start, stop, death, and out
+				of memory events of the included containers as log events,
+				alongside their logs.
+				"""
+			required: false
+			type: bool: default: false
+		}
 		exclude_containers: {
 			common: false
 			description: """
@@ -256,11 +266,42 @@ components: sources: docker_logs: {
 		log: {
 			description: "A Docker log event"
 			fields: {
+				compose_project: {
+					description: "The Docker Compose project of the container, from its `com.docker.compose.project` label."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						examples: ["shop"]
+					}
+				}
+				compose_service: {
+					description: "The Docker Compose service of the container, from its `com.docker.compose.service` label."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						examples: ["web"]
+					}
+				}
 				container_created_at: {
 					description: "A UTC timestamp representing when the container was created."
 					required:    true
 					type: timestamp: {}
 				}
+				container_health: {
+					description: "The health status of the container, for containers with a healthcheck."
+					required:    false
+					common:      true
+					type: string: {
+						default: null
+						enum: {
+							starting:  "The container is starting, its healthcheck has not yet succeeded."
+							healthy:   "The healthcheck of the container succeeds."
+							unhealthy: "The healthcheck of the container fails."
+						}
+					}
+				}
 				container_id: {
 					description: "The Docker container ID that the log was collected from."
 					required:    true
@@ -314,6 +355,51 @@ components: sources: docker_logs: {
 				}
 			}
 		}
+		container_event: {
+			description: "A container lifecycle event, emitted when `container_events` is enabled"
+			fields: {
+				compose_project: log.fields.compose_project
+				compose_service: log.fields.compose_service
+				container_event: {
+					description: "The action of the container."
+					required:    true
+					type: string: {
+						enum: {
+							start: "The container started."
+							stop:  "The container was stopped."
+							die:   "The container exited."
+							oom:   "The container ran out of memory."
+						}
+					}
+				}
+				container_id:   log.fields.container_id
+				container_name: log.fields.container_name
+				exit_code: {
+					description: "The exit code of the container, for `die` events."
+					required:    false
+					common:      true
+					type: uint: {
+						default: null
+						examples: [0, 137]
+						unit: null
+					}
+				}
+				host:  fields._local_host
+				image: log.fields.image
+				message: {
+					description: "A description of the event."
+					required:    true
+					type: string: {
+						examples: ["Container evil_ptolemy died."]
+					}
+				}
+				timestamp: {
+					description: "The UTC timestamp of the event."
+					required:    true
+					type: timestamp: {}
+				}
+			}
+		}
 	}
 
 	examples: [
@@ -69,6 +69,24 @@ components: sources: exec: {
 			required:    false
 			type: bool: default: true
 		}
+		separate_stderr: {
+			common:      false
+			description: """
+				Send the events generated from the output of stderr to the `stderr` output instead of the
+				default one. Requires `include_stderr` to be enabled.
+				"""
+			required:    false
+			type: bool: default: false
+		}
+		include_exit_events: {
+			common:      false
+			description: """
+				Generate an event each time the command exits, holding its exit code, or the signal
+				which terminated it.
+				"""
+			required:    false
+			type: bool: default: false
+		}
 		maximum_buffer_size_bytes: {
 			common:      false
 			description: "The maximum buffer size allowed before a log event will be generated."
@@ -122,42 +140,110 @@ components: sources: exec: {
 							unit:    "seconds"
 						}
 					}
+					max_respawn_interval_secs: {
+						common:        false
+						description:   """
+							When set, the interval between restarts doubles each time the command exits, starting
+							from `respawn_interval_secs`, up to this maximum. The interval is reset once the
+							command ran for longer than this maximum.
+							"""
+						relevant_when: "mode = `streaming`"
+						required:      false
+						type: uint: {
+							default: null
+							examples: [300]
+							unit: "seconds"
+						}
+					}
+					graceful_shutdown_timeout_secs: {
+						common:        false
+						description:   """
+							When set, the command is sent a `SIGTERM` on shutdown and given this long to exit
+							before being killed. Otherwise it is killed as soon as shutdown starts. The command
+							is always killed on Windows.
+							"""
+						relevant_when: "mode = `streaming`"
+						required:      false
+						type: uint: {
+							default: null
+							examples: [10]
+							unit: "seconds"
+						}
+					}
 				}
 			}
 		}
 	}
 
-	output: logs: line: {
-		description: "An individual event from exec."
-		fields: {
-			host:      fields._local_host
-			message:   fields._raw_line
-			timestamp: fields._current_timestamp
-			data_stream: {
-				common:      true
-				description: "The data stream from which the event originated."
-				required:    false
-				type: string: {
-					default: null
-					examples: ["stdout", "stderr"]
+	output: logs: {
+		line: {
+			description: "An individual event from exec."
+			fields: {
+				host:      fields._local_host
+				message:   fields._raw_line
+				timestamp: fields._current_timestamp
+				data_stream: {
+					common:      true
+					description: "The data stream from which the event originated."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["stdout", "stderr"]
+					}
 				}
-			}
-			pid: {
-				description: "The process ID of the command."
-				required:    true
-				type: uint: {
-					examples: [60085, 668]
-					unit: null
+				pid: {
+					description: "The process ID of the command."
+					required:    true
+					type: uint: {
+						examples: [60085, 668]
+						unit: null
+					}
+				}
+				command: {
+					required:    true
+					description: "The command that was run to generate this event."
+					type: array: {
+						items: type: string: {
+							examples: ["echo", "Hello World!", "ls", "-la"]
+						}
+					}
 				}
 			}
-			command: {
-				required:    true
-				description: "The command that was run to generate this event."
-				type: array: {
-					items: type: string: {
-						examples: ["echo", "Hello World!", "ls", "-la"]
+		}
+		exit: {
+			description: "An event generated when the command exits, if `include_exit_events` is enabled."
+			fields: {
+				host:      fields._local_host
+				timestamp: fields._current_timestamp
+				message: {
+					description: "A description of the exit status of the command."
+					required:    true
+					type: string: {
+						examples: ["Command exited with code 1.", "Command was terminated by signal 9."]
+					}
+				}
+				exit_code: {
+					description: "The exit code of the command, unless it was terminated by a signal."
+					required:    false
+					common:      true
+					type: uint: {
+						default: null
+						examples: [0, 1]
+						unit: null
+					}
+				}
+				signal: {
+					description: "The signal which terminated the command, on Unix."
+					required:    false
+					common:      true
+					type: uint: {
+						default: null
+						examples: [9, 15]
+						unit: null
 					}
 				}
+				pid:     line.fields.pid
+				command: line.fields.command
 			}
 		}
 	}
@@ -190,6 +190,43 @@ components: sources: file: {
 				unit: "bytes"
 			}
 		}
+		multiline_presets: {
+			common:      false
+			description: "Built-in multiline aggregations, applied to the files matching their `include` patterns instead of `multiline`. The first matching preset is used. See [Multiline presets](#multiline-presets) for more details."
+			required:    false
+			type: array: {
+				default: null
+				items: type: object: options: {
+					include: {
+						description: "Array of file patterns the preset is applied to. [Globbing](#globbing) is supported."
+						required:    true
+						type: array: items: type: string: {
+							examples: ["\(_directory)/billing/*.log"]
+						}
+					}
+					preset: {
+						description: "The format of the multiline messages."
+						required:    true
+						type: string: {
+							enum: {
+								docker_json_file: "Messages of Docker's `json-file` logging driver, split into partial ones when longer than 16KiB."
+								java:             "Java stack traces, along with the line of the message they were logged with."
+								python:           "Python tracebacks."
+							}
+						}
+					}
+					timeout_ms: {
+						common:      false
+						description: "The maximum time to wait for the continuation of a message."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    "milliseconds"
+						}
+					}
+				}
+			}
+		}
 		oldest_first: {
 			category:    "Reading"
 			common:      false
@@ -532,6 +569,37 @@ components: sources: file: {
 			]
 		}
 
+		multiline_presets: {
+			title: "Multiline presets"
+			body: #"""
+				Instead of writing the `multiline` patterns of common formats, a preset can be
+				applied to the files matching some of the `include` patterns, while other files
+				still use `multiline`, if set:
+
+				```toml
+				[sources.my_file_source]
+				type = "file"
+				include = ["/var/log/billing/*.log", "/var/lib/docker/containers/*/*-json.log"]
+
+				[[sources.my_file_source.multiline_presets]]
+				include = ["/var/log/billing/*.log"]
+				preset = "java"
+
+				[[sources.my_file_source.multiline_presets]]
+				include = ["/var/lib/docker/containers/*/*-json.log"]
+				preset = "docker_json_file"
+				```
+
+				* `java` aggregates stack traces with the line of the message logged with them,
+					continuing through the lines of frames, causes, and exceptions.
+				* `python` aggregates tracebacks, from `Traceback (most recent call last):` to the
+					line of the exception. Chained exceptions are aggregated separately.
+				* `docker_json_file` aggregates the partial messages of Docker's `json-file`
+					logging driver, whose `log` doesn't end with a newline, into a single line, in
+					which the `log` of the partial messages are concatenated.
+				"""#
+		}
+
 		permissions: {
 			title: "File permissions"
 			body:  """
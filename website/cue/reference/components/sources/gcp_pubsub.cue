@@ -0,0 +1,220 @@
+package metadata
+
+components: sources: gcp_pubsub: {
+	title: "GCP PubSub"
+
+	description: """
+		Pulls messages from a [GCP Pub/Sub](\(urls.gcp_pubsub)) subscription over a
+		streaming pull.
+		"""
+
+	classes: {
+		commonly_used: false
+		deployment_roles: ["aggregator"]
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: ["GCP"]
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             false
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        true
+			}
+			from: {
+				service: services.gcp_pubsub
+				interface: socket: {
+					api: {
+						title: "GCP Pub/Sub gRPC API"
+						url:   urls.gcp_pubsub_grpc
+					}
+					direction: "outgoing"
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+		}
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "bytes"
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		project: {
+			description: "The project name from which to pull logs."
+			required:    true
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		subscription: {
+			description: "The subscription within the project which is configured to receive logs."
+			required:    true
+			type: string: {
+				examples: ["vector-123456"]
+			}
+		}
+		endpoint: {
+			common:      false
+			description: "The endpoint from which to pull data. Endpoints using the `http` scheme, such as an emulator, are connected to without TLS."
+			required:    false
+			type: string: {
+				default: "https://pubsub.googleapis.com"
+				examples: ["https://us-central1-pubsub.googleapis.com", "http://localhost:8085"]
+			}
+		}
+		api_key: {
+			common:      false
+			description: "A [Google Cloud API key](\(urls.gcp_authentication_api_key)) used to authenticate access the pubsub project and subscription. Either this or `credentials_path` must be set."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["${GCP_API_KEY}", "ef8d5de700e7989468166c40fc8a0ccd"]
+			}
+		}
+		credentials_path: {
+			common:      true
+			description: "The filename for a Google Cloud service account credentials JSON file used to authenticate access to the pubsub project and subscription. If this is unset, Vector checks the `GOOGLE_APPLICATION_CREDENTIALS` environment variable for a filename.\n\nIf no filename is named, Vector will attempt to fetch an instance service account for the compute instance the program is running on. If Vector is not running on a GCE instance, you must define a credentials file as above."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/path/to/credentials.json"]
+			}
+		}
+		skip_authentication: {
+			common:      false
+			description: "Skip all authentication handling. For use with integration tests and emulators only."
+			required:    false
+			type: bool: default: false
+		}
+		ack_deadline_secs: {
+			common:      false
+			description: "The acknowledgement deadline to use for the stream, between 10 and 600 seconds. Messages that are not acknowledged within this time are redelivered, and the deadline of messages whose events are still being delivered is extended at half this interval."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+		retry_delay_secs: {
+			common:      false
+			description: "The amount of time to wait before retrying after the stream fails."
+			required:    false
+			type: uint: {
+				default: 1
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: record: {
+		description: "An individual Pub/Sub message"
+		fields: {
+			message: {
+				description: "The data of the message."
+				required:    true
+				type: string: {
+					examples: ["53.126.150.246 - - [01/Oct/2020:11:25:58 -0400] \"GET /disintermediate HTTP/2.0\" 401 20308"]
+				}
+			}
+			timestamp: {
+				description: "The time the message was published."
+				required:    true
+				type: timestamp: {}
+			}
+			message_id: {
+				description: "The ID of the message, assigned by the server."
+				required:    true
+				type: string: {
+					examples: ["2345678901234567"]
+				}
+			}
+			attributes: {
+				description: "The attributes of the message."
+				required:    true
+				type: object: {
+					examples: [{"service": "api"}]
+					options: {}
+				}
+			}
+			ordering_key: {
+				description: "The ordering key of the message, if it has one."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["user-1234"]
+				}
+			}
+		}
+	}
+
+	permissions: iam: [
+		{
+			platform: "gcp"
+			_service: "pubsub"
+
+			policies: [
+				{
+					_action: "subscriptions.consume"
+					required_for: ["operation"]
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		acknowledgements: {
+			title: "Acknowledgements"
+			body:  """
+				Messages are acknowledged once their events are processed, or delivered to
+				the sinks if `acknowledgements` are enabled. While events are being delivered,
+				the acknowledgement deadline of their messages is extended so they aren't
+				redelivered. Messages that are still unacknowledged when Vector shuts down are
+				redelivered by Pub/Sub once their deadline expires.
+				"""
+		}
+		ordering: {
+			title: "Message Ordering"
+			body:  """
+				On subscriptions with [message ordering](\(urls.gcp_pubsub_ordering)) enabled,
+				messages sharing an ordering key are sent on in the order they were published.
+				Messages whose events are rejected by a sink, or whose delivery errored, are
+				made available for redelivery immediately, along with the messages following
+				them with the same ordering key. Subscriptions with a
+				[dead letter topic](\(urls.gcp_pubsub_dead_letter)) forward them there once the
+				maximum number of delivery attempts is reached.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                        components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:                 components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:         components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:        components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total:   components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		source_acknowledgement_latency_seconds: components.sources.internal_metrics.output.metrics.source_acknowledgement_latency_seconds
+		source_acknowledgement_timeouts_total:  components.sources.internal_metrics.output.metrics.source_acknowledgement_timeouts_total
+	}
+}
@@ -63,7 +63,8 @@ components: sources: heroku_logs: {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
 		address:          sources.http.configuration.address
 		auth:             sources.http.configuration.auth
 		query_parameters: sources.http.configuration.query_parameters
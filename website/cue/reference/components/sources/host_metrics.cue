@@ -122,6 +122,17 @@ components: sources: host_metrics: {
 						examples: ["/", "system.slice/snapd.service"]
 					}
 				}
+				container_limits: {
+					common:      false
+					required:    false
+					description: """
+						Whether the limits of the cgroup Vector runs in, as when it runs in a container, are taken into
+						account by the other collectors, lowered by the limits of its ancestors. The total and available
+						memory reported by the `memory` collector are then limited by its memory limit, and the `cpu`
+						collector reports its CPU limit as `cpu_limit_cores`.
+						"""
+					type: bool: default: false
+				}
 				groups: {
 					common:      false
 					required:    false
@@ -173,6 +184,52 @@ components: sources: host_metrics: {
 						examples: [1, 3]
 					}
 				}
+				units: {
+					common:      false
+					required:    false
+					description: """
+						Lists of systemd unit name patterns to include or exclude. The cgroups created by systemd for its
+						units, such as `nginx.service`, are tagged with the unit name. Unlike `groups`, these lists don't
+						prevent the descendants of the excluded cgroups from being reported.
+						"""
+					type: object: options: {
+						includes: {
+							required: false
+							common:   false
+							description: """
+								The list of unit name patterns for which to gather metrics. The cgroups that were not
+								created for a unit are excluded when set.
+
+								Defaults to including all cgroups.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: ["*"]
+								items: type: string: {
+									examples: ["*.service", "docker-*.scope"]
+								}
+							}
+						}
+						excludes: {
+							required: false
+							common:   false
+							description: """
+								The list of unit name patterns for which not to gather metrics.
+
+								Defaults to excluding no cgroups.
+
+								The patterns are matched using globbing.
+								"""
+							type: array: {
+								default: []
+								items: type: string: {
+									examples: ["user@*.service", "*.mount"]
+								}
+							}
+						}
+					}
+				}
 			}
 		}
 		disk: {
@@ -435,13 +492,39 @@ components: sources: host_metrics: {
 			}
 		}
 
+		// Host CPU limit
+		cpu_limit_cores: _host & {
+			description:   "The number of CPU cores available to the cgroup Vector runs in, as allowed by its CPU quota."
+			relevant_when: "`cgroups.container_limits` is enabled and the cgroup has a CPU quota"
+			type:          "gauge"
+			tags:          _host_metrics_tags & {
+				collector: examples: ["cpu"]
+			}
+		}
+
 		// Host cgroups
-		cgroup_cpu_usage_seconds_total:  _host & _cgroup_cpu & {description:    "The total amount CPU time used by this cgroup and its descendants, in seconds."}
-		cgroup_cpu_user_seconds_total:   _host & _cgroup_cpu & {description:    "The total amount of CPU time spent by this cgroup in user space, in seconds."}
-		cgroup_cpu_system_seconds_total: _host & _cgroup_cpu & {description:    "The total amount of CPU time spent by this cgroup in system tasks, in seconds."}
-		cgroup_memory_current_bytes:     _host & _cgroup_memory & {description: "The total amount of memory currently being used by this cgroup and its descendants, in bytes."}
-		cgroup_memory_anon_bytes:        _host & _cgroup_memory & {description: "The total amount of memory used by this cgroup in anonymous mappings (normal program allocation), in bytes."}
-		cgroup_memory_file_bytes:        _host & _cgroup_memory & {description: "The total amount of memory used by this cgroup to cache filesystem data, including tmpfs and shared memory, in bytes."}
+		cgroup_cpu_usage_seconds_total:     _host & _cgroup_cpu & {description:      "The total amount CPU time used by this cgroup and its descendants, in seconds."}
+		cgroup_cpu_user_seconds_total:      _host & _cgroup_cpu & {description:      "The total amount of CPU time spent by this cgroup in user space, in seconds."}
+		cgroup_cpu_system_seconds_total:    _host & _cgroup_cpu & {description:      "The total amount of CPU time spent by this cgroup in system tasks, in seconds."}
+		cgroup_cpu_throttled_periods_total: _host & _cgroup_cpu_quota & {description: "The number of periods in which this cgroup was throttled for using its entire CPU quota."}
+		cgroup_cpu_throttled_seconds_total: _host & _cgroup_cpu_quota & {description: "The total amount of time this cgroup was throttled for, in seconds."}
+		cgroup_cpu_limit_cores:             _host & {
+			description:   "The number of CPU cores available to this cgroup, as allowed by its CPU quota."
+			relevant_when: "The cgroup has a CPU quota"
+			type:          "gauge"
+			tags:          _cgroup_memory.tags
+		}
+		cgroup_io_read_bytes_total:    _host & _cgroup_io & {description:     "The total number of bytes read by this cgroup from the device."}
+		cgroup_io_reads_total:         _host & _cgroup_io & {description:     "The total number of read operations of this cgroup on the device."}
+		cgroup_io_written_bytes_total: _host & _cgroup_io & {description:     "The total number of bytes written by this cgroup to the device."}
+		cgroup_io_writes_total:        _host & _cgroup_io & {description:     "The total number of write operations of this cgroup on the device."}
+		cgroup_memory_current_bytes:   _host & _cgroup_memory & {description: "The total amount of memory currently being used by this cgroup and its descendants, in bytes."}
+		cgroup_memory_anon_bytes:      _host & _cgroup_memory & {description: "The total amount of memory used by this cgroup in anonymous mappings (normal program allocation), in bytes."}
+		cgroup_memory_file_bytes:      _host & _cgroup_memory & {description: "The total amount of memory used by this cgroup to cache filesystem data, including tmpfs and shared memory, in bytes."}
+		cgroup_memory_limit_bytes:     _host & _cgroup_memory & {
+			description:   "The amount of memory this cgroup and its descendants are limited to, in bytes."
+			relevant_when: "The cgroup has a memory limit"
+		}
 
 		// Host disk
 		disk_read_bytes_total:       _host & _disk_counter & {description: "The accumulated number of bytes read in."}
@@ -466,7 +549,7 @@ components: sources: host_metrics: {
 
 		// Host memory
 		memory_active_bytes:           _host & _memory_gauge & _memory_nowin & {description: "The number of bytes of active main memory."}
-		memory_available_bytes:        _host & _memory_gauge & {description:                 "The number of bytes of main memory available."}
+		memory_available_bytes:        _host & _memory_gauge & {description:                 "The number of bytes of main memory available, limited by the memory left to the cgroup Vector runs in if `cgroups.container_limits` is enabled."}
 		memory_buffers_bytes:          _host & _memory_linux & {description:                 "The number of bytes of main memory used by buffers."}
 		memory_cached_bytes:           _host & _memory_linux & {description:                 "The number of bytes of main memory used by cached blocks."}
 		memory_free_bytes:             _host & _memory_gauge & {description:                 "The number of bytes of main memory not used."}
@@ -481,7 +564,7 @@ components: sources: host_metrics: {
 		}
 		memory_swap_total_bytes: _host & _memory_gauge & {description: "The total number of bytes of swap space."}
 		memory_swap_used_bytes:  _host & _memory_gauge & {description: "The number of used bytes of swap space."}
-		memory_total_bytes:      _host & _memory_gauge & {description: "The total number of bytes of main memory."}
+		memory_total_bytes:      _host & _memory_gauge & {description: "The total number of bytes of main memory, limited by the memory limit of the cgroup Vector runs in if `cgroups.container_limits` is enabled."}
 		memory_used_bytes:       _host & _memory_linux & {description: "The number of bytes of main memory used by programs or caches."}
 		memory_wired_bytes:      _host & _memory_macos & {description: "The number of wired bytes of main memory."}
 
@@ -504,6 +587,17 @@ components: sources: host_metrics: {
 			tags: _host_metrics_tags & {
 				collector: examples: ["cgroups"]
 				cgroup: _cgroup_name
+				unit:   _cgroup_unit
+			}
+		}
+		_cgroup_cpu_quota: _cgroup_cpu & {relevant_when: "The cgroup has a CPU quota"}
+		_cgroup_io: {
+			type: "counter"
+			tags: _host_metrics_tags & {
+				collector: examples: ["cgroups"]
+				cgroup: _cgroup_name
+				unit:   _cgroup_unit
+				device: _disk_device
 			}
 		}
 		_cgroup_memory: {
@@ -511,6 +605,7 @@ components: sources: host_metrics: {
 			tags: _host_metrics_tags & {
 				collector: examples: ["cgroups"]
 				cgroup: _cgroup_name
+				unit:   _cgroup_unit
 			}
 		}
 		_cgroup_name: {
@@ -518,6 +613,11 @@ components: sources: host_metrics: {
 			required:    true
 			examples: ["/", "user.slice", "system.slice/snapd.service"]
 		}
+		_cgroup_unit: {
+			description: "The name of the systemd unit the control group was created for."
+			required:    false
+			examples: ["snapd.service", "session-2.scope"]
+		}
 
 		_disk_device: {
 			description: "The disk device name."
@@ -55,7 +55,8 @@ components: sources: http: {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
 		address: {
 			description: "The address to accept connections on. The address _must_ include a port."
 			required:    true
@@ -77,6 +78,21 @@ components: sources: http: {
 				}
 			}
 		}
+		decompression: {
+			common:      false
+			description: "The compression of the request bodies, undone after the `Content-Encoding` header is applied and before they are framed and decoded. This supports clients compressing the payloads themselves without setting the header."
+			required:    false
+			type: string: {
+				default: "none"
+				enum: {
+					none: "The bodies aren't compressed."
+					auto: "The compression is detected from the magic bytes of each body, which is kept as is if it doesn't start with those of a supported format."
+					gzip: "The bodies are [gzip](\(urls.gzip)) compressed."
+					zstd: "The bodies are [zstd](\(urls.zstd)) compressed."
+					lz4:  "The bodies are [lz4](\(urls.lz4)) frames."
+				}
+			}
+		}
 		headers: {
 			common:      false
 			description: "A list of HTTP headers to include in the log event. These will override any values included in the JSON payload with conflicting names."
@@ -100,6 +116,16 @@ components: sources: http: {
 				}
 			}
 		}
+		max_decompressed_size: {
+			common:      false
+			description: "The largest size of the request bodies once decompressed as per their `Content-Encoding` header. Larger requests are rejected with a `413 Payload Too Large` response. If not set, the size isn't limited."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [10485760]
+				unit: "bytes"
+			}
+		}
 		path: {
 			common:      false
 			description: "The URL path on which log event POST requests shall be sent."
@@ -242,14 +268,16 @@ components: sources: http: {
 	]
 
 	telemetry: metrics: {
-		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
-		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
-		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
-		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
-		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
-		http_bad_requests_total:              components.sources.internal_metrics.output.metrics.http_bad_requests_total
-		parse_errors_total:                   components.sources.internal_metrics.output.metrics.parse_errors_total
-		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_errors_total:                 components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:         components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:        components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total:   components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		events_in_total:                        components.sources.internal_metrics.output.metrics.events_in_total
+		http_bad_requests_total:                components.sources.internal_metrics.output.metrics.http_bad_requests_total
+		parse_errors_total:                     components.sources.internal_metrics.output.metrics.parse_errors_total
+		component_received_events_total:        components.sources.internal_metrics.output.metrics.component_received_events_total
+		source_acknowledgement_latency_seconds: components.sources.internal_metrics.output.metrics.source_acknowledgement_latency_seconds
+		source_acknowledgement_timeouts_total:  components.sources.internal_metrics.output.metrics.source_acknowledgement_timeouts_total
 	}
 
 	how_it_works: {
@@ -257,7 +285,13 @@ components: sources: http: {
 			title: "Decompression"
 			body: """
 				Received body is decompressed according to `Content-Encoding` header.
-				Supported algorithms are `gzip`, `deflate`, and `snappy`.
+				Supported algorithms are `gzip`, `deflate`, `snappy`, and `zstd`. The size of
+				the decompressed body can be limited with `max_decompressed_size`, to protect
+				against payloads inflating to more than what Vector can hold in memory.
+
+				Payloads compressed without a `Content-Encoding` header can be decompressed
+				with the `decompression` option, which also supports `lz4` frames and can
+				detect the compression from the magic bytes of the bodies.
 				"""
 		}
 	}
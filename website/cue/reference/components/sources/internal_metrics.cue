@@ -37,6 +37,49 @@ components: sources: internal_metrics: {
 	}
 
 	configuration: {
+		cardinality: {
+			common:      false
+			description: """
+				Caps the number of distinct values of each tag of each metric, such
+				as file paths or peer addresses, so that Vector's own telemetry
+				doesn't overwhelm the metrics backend it's sent to. The limits, like
+				`tag_filters`, are applied as the metrics are recorded, so they hold
+				for all of Vector's internal metrics, including those reported by the
+				API, for as long as the source runs. Only one `internal_metrics`
+				source can set them, or `tag_filters`, the configuration being
+				rejected otherwise.
+				"""
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					max_tag_values: {
+						common:      true
+						description: """
+							The number of distinct values kept for each tag of a metric.
+							The values seen after them are replaced by `overflow_value`,
+							and the series bucketed together are recorded as one: counters
+							and histograms add up, while gauges keep the last value set. The values
+							seen first keep their own series for as long as Vector runs.
+							"""
+						required:    false
+						type: uint: {
+							default: null
+							examples: [100, 1000]
+							unit: null
+						}
+					}
+					overflow_value: {
+						common:      false
+						description: "The value replacing the values of a tag over `max_tag_values`."
+						required:    false
+						type: string: {
+							default: "overflow"
+						}
+					}
+				}
+			}
+		}
 		namespace: {
 			description: "The namespace of the metric."
 			common:      false
@@ -54,6 +97,46 @@ components: sources: internal_metrics: {
 				unit:    "seconds"
 			}
 		}
+		tag_filters: {
+			common:      false
+			description: """
+				The tags kept for the metrics whose name matches one of the patterns
+				of a filter. All the filters matching a metric are applied, in order,
+				before the cardinality of its tags is capped.
+				"""
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						metrics: {
+							description: "The names of the metrics the filter applies to, as glob patterns."
+							required:    true
+							type: array: items: type: string: examples: ["component_*", "files_*"]
+						}
+						allow: {
+							common:      false
+							description: "The only tags kept, if given."
+							required:    false
+							type: array: {
+								default: null
+								items: type: string: examples: ["component_id", "component_kind"]
+							}
+						}
+						deny: {
+							common:      false
+							description: "The tags removed."
+							required:    false
+							type: array: {
+								default: []
+								items: type: string: examples: ["peer_addr", "file"]
+							}
+						}
+					}
+				}
+			}
+		}
 		tags: {
 			common:      false
 			description: "Metric tag options."
@@ -176,6 +259,30 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		enrichment_table_reload_duration_seconds: {
+			description:       "The time taken to reload an enrichment table from its file, including rebuilding its indexes."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				file: _enrichment_table_file
+			}
+		}
+		enrichment_table_reloads_total: {
+			description:       "The total number of times an enrichment table has been reloaded from its file."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				file: _enrichment_table_file
+			}
+		}
+		enrichment_table_rows: {
+			description:       "The number of rows currently loaded into an enrichment table."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				file: _enrichment_table_file
+			}
+		}
 		quit_total: {
 			description:       "The total number of times the Vector instance has quit."
 			type:              "counter"
@@ -239,6 +346,28 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		aws_credentials_refreshes_total: {
+			description:       "The total number of times a component fetched new AWS credentials, once the previous ones expired."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				provider: {
+					description: "The source of the credentials, such as `default`, `role`, `web_identity`, `sso` or `process`."
+					required:    true
+				}
+			}
+		}
+		aws_credentials_refresh_errors_total: {
+			description:       "The total number of failures fetching new AWS credentials."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				provider: {
+					description: "The source of the credentials, such as `default`, `role`, `web_identity`, `sso` or `process`."
+					required:    true
+				}
+			}
+		}
 		checkpoint_write_errors_total: {
 			description:       "The total number of errors writing checkpoints. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -416,6 +545,17 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		elasticsearch_rejected_documents_total: {
+			description:       "The total number of documents rejected by Elasticsearch in partially failed bulk requests."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				rejection_type: {
+					description: "The type of the error of the document, such as `mapper_parsing_exception`."
+					required:    true
+				}
+			}
+		}
 		encode_errors_total: {
 			description:       "The total number of errors encountered when encoding an event."
 			type:              "counter"
@@ -472,43 +612,61 @@ components: sources: internal_metrics: {
 			description:       "The number of bytes current in the buffer."
 			type:              "gauge"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		buffer_events: {
 			description:       "The number of events currently in the buffer."
 			type:              "gauge"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
+		}
+		buffer_disk_bytes: {
+			description:       "The number of bytes taken on disk by this disk buffer, including acknowledged records that haven't been reclaimed yet."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags & {buffer_type: _buffer_type}
+		}
+		buffer_oldest_record_age_seconds: {
+			description:       "The age of the oldest unacknowledged record in this disk buffer. Only reported by `disk_v2` buffers."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags & {buffer_type: _buffer_type}
+		}
+		buffer_segments: {
+			description:       "The number of segments of this disk buffer: data files for `disk_v2` buffers, tables for `disk` buffers."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		buffer_discarded_events_total: {
 			description:       "The number of events dropped by this non-blocking buffer."
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		buffer_received_event_bytes_total: {
 			description:       "The number of bytes received by this buffer."
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		buffer_received_events_total: {
 			description:       "The number of events received by this buffer."
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		buffer_sent_event_bytes_total: {
 			description:       "The number of bytes sent by this buffer."
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		buffer_sent_events_total: {
 			description:       "The number of events sent by this buffer."
 			type:              "counter"
 			default_namespace: "vector"
-			tags:              _component_tags
+			tags:              _component_tags & {buffer_type: _buffer_type}
 		}
 		component_discarded_events_total: {
 			description:       "The number of events dropped by this component."
@@ -601,6 +759,28 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags & {output: _output}
 		}
+		datadog_logs_delivery_latency_seconds: {
+			description:       "The time between the building of the Datadog logs requests and their responses, including their retries, tagged with the response code."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				status_code: {
+					description: "The HTTP status code of the response."
+					required:    true
+				}
+			}
+		}
+		datadog_logs_invalid_reserved_attributes_total: {
+			description:       "The total number of values discarded because they aren't valid for their Datadog reserved attribute."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				attribute: {
+					description: "The reserved attribute."
+					required:    true
+				}
+			}
+		}
 		datadog_logs_received_in_total: {
 			description:       "Number of Datadog logs received."
 			type:              "counter"
@@ -613,6 +793,17 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		dead_letter_events_total: {
+			description:       "The total number of events a sink failed to deliver and routed to its dead letter component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				status: {
+					description: "The status of the failed delivery, `errored` or `rejected`."
+					required:    true
+				}
+			}
+		}
 		kafka_queue_messages: {
 			description:       "Current number of messages in producer queues."
 			type:              "gauge"
@@ -673,6 +864,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kafka_redeliveries_exhausted_total: {
+			description:       "The total number of messages whose offset was committed after being consumed again `max_redeliveries` times."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		file_delete_errors_total: {
 			description:       "The total number of failures to delete a file. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -822,6 +1019,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		metric_temporality_expired_series_total: {
+			description:       "The number of series whose state was dropped by the metric_temporality transform, as they weren't seen for a while."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		metric_temporality_resets_total: {
+			description:       "The number of resets of cumulative values detected by the metric_temporality transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		open_connections: {
 			description:       "The number of current open connections to Vector."
 			type:              "gauge"
@@ -834,6 +1043,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		pii_findings_total: {
+			description:       "The number of PII matches scrubbed from events."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		processed_bytes_total: {
 			description:       "The number of bytes processed by the component."
 			type:              "counter"
@@ -925,18 +1140,62 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		schema_violations_total: {
+			description:       "The number of schema violations found in events."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		source_acknowledgement_latency_seconds: {
+			description:       "The time sources wait for the acknowledgement of the events they sent."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				status: {
+					description: "The delivery status of the events."
+					required:    true
+					enum: {
+						delivered: "The events were delivered."
+						errored:   "The delivery of the events errored."
+						rejected:  "The events were rejected."
+						timed_out: "The events were not acknowledged in time."
+					}
+				}
+			}
+		}
+		source_acknowledgement_timeouts_total: {
+			description:       "The total number of batches of events not acknowledged in time."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _component_tags & {
+				action: {
+					description: "The action taken on the events."
+					required:    true
+					enum: {
+						drop:      "The events were given up on."
+						redeliver: "The events are received again."
+					}
+				}
+			}
+		}
 		splunk_pending_acks: {
 			description:       "The number of outstanding Splunk HEC indexer acknowledgement acks."
 			type:              "gauge"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		splunk_resent_requests_total: {
+			description:       "The total number of Splunk HEC requests resent after their indexer acknowledgement ack ids expired."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		streams_total: {
 			description:       "The total number of streams."
 			type:              "counter"
@@ -1021,12 +1280,42 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		tap_events_skipped_total: {
+			description:       "The total number of events `vector tap` clients missed because they couldn't keep up with the output of the component."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				component_id: _component_id
+			}
+		}
 		timestamp_parse_errors_total: {
 			description:       "The total number of errors encountered parsing [RFC 3339](\(urls.rfc_3339)) timestamps."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		tls_certificate_reloads_total: {
+			description:       "The total number of times the TLS certificates and keys were reloaded."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				source: {
+					description: "What the certificates were reloaded from, either `files` or `spiffe`."
+					required:    true
+				}
+			}
+		}
+		tls_certificate_reload_errors_total: {
+			description:       "The total number of failures reloading the TLS certificates and keys, the previous ones remaining in use."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				source: {
+					description: "What the certificates were reloaded from, such as `files` or `spiffe`, or what failed using them, such as `acceptor`."
+					required:    true
+				}
+			}
+		}
 		uptime_seconds: {
 			description:       "The total number of seconds the Vector instance has been up."
 			type:              "gauge"
@@ -1185,6 +1474,10 @@ components: sources: internal_metrics: {
 				"write_failed":                "The file write operation failed."
 			}
 		}
+		_enrichment_table_file: {
+			description: "The file the enrichment table is loaded from."
+			required:    true
+		}
 		_file: {
 			description: "The file that produced the error"
 			required:    false
@@ -1207,6 +1500,15 @@ components: sources: internal_metrics: {
 			description: "The specific output of the component."
 			required:    false
 		}
+		_buffer_type: {
+			description: "The type of the buffer stage."
+			required:    true
+			enum: {
+				memory:  "An in-memory buffer stage."
+				disk:    "A disk buffer stage."
+				disk_v2: "A `disk_v2` buffer stage."
+			}
+		}
 		_stage: {
 			description: "The stage within the component at which the error occurred."
 			required:    true
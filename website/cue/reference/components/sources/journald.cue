@@ -54,12 +54,32 @@ components: sources: journald: {
 				unit:    null
 			}
 		}
+		boot_id: {
+			common:      false
+			description: "Include only entries from the boot with this ID, instead of the current one. Can't be used with `current_boot_only` set to `true`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["4ba1e7d4ab1f4b8e9f1e0f4c2f8a6e3b", "-1"]
+			}
+		}
 		current_boot_only: {
 			common:      true
 			description: "Include only entries from the current boot."
 			required:    false
 			type: bool: default: true
 		}
+		exclude_fields: {
+			common:      false
+			description: "Journal fields matching any of these patterns are dropped from the events. Patterns are matched against the journal field names, and support the `*`, `?` and `[...]` wildcards."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["_SOURCE_MONOTONIC_TIMESTAMP", "__*", "_SYSTEMD_*"]
+				}
+			}
+		}
 		exclude_units: {
 			common:      true
 			description: "The list of unit names to exclude from monitoring. Unit names lacking a `\".\"` will have `\".service\"` appended to make them a valid service unit name."
@@ -97,6 +117,17 @@ components: sources: journald: {
 				}
 			}
 		}
+		include_fields: {
+			common:      false
+			description: "If not empty, only the journal fields matching any of these patterns are kept in the events. `exclude_fields` applies on top. The timestamp of the event is set even if the fields it is read from aren't kept."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["MESSAGE", "_SYSTEMD_*", "PRIORITY"]
+				}
+			}
+		}
 		include_units: {
 			common:      true
 			description: "The list of unit names to monitor. If empty or not present, all units are accepted. Unit names lacking a `\".\"` will have `\".service\"` appended to make them a valid service unit name."
@@ -152,6 +183,15 @@ components: sources: journald: {
 				examples: ["/run/log/journal"]
 			}
 		}
+		since: {
+			common:      false
+			description: "Where to start reading the journal if there is no checkpoint, in any format accepted by the `--since` option of `journalctl`, such as an absolute time or a time relative to now. If not set, the whole journal is read."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["2022-03-01 12:00:00", "-2h", "yesterday"]
+			}
+		}
 	}
 
 	output: logs: {
@@ -241,6 +281,15 @@ components: sources: journald: {
 				are replaced with the Unicode replacement character, `�`.
 				"""
 		}
+		starting_point: {
+			title: "Starting Point"
+			body: """
+				Vector resumes reading the journal after the entry saved in its checkpoint. Without a
+				checkpoint, it starts at the time set by `since`, or at the beginning of the journal. Set
+				`boot_id` to backfill the entries of a past boot rather than the current one, the IDs of
+				the boots being listed by `journalctl --list-boots`.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
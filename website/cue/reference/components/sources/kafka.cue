@@ -39,7 +39,44 @@ components: sources: kafka: {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
+		assignments: {
+			common:      false
+			description: """
+				Specific partitions to consume from, instead of subscribing to `topics`. This is useful to replay
+				a partition from a given offset.
+				"""
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					topic: {
+						description: "The topic of the partition."
+						required:    true
+						type: string: examples: ["topic-1"]
+					}
+					partition: {
+						description: "The partition to consume from."
+						required:    true
+						type: uint: {
+							examples: [0, 1]
+							unit: null
+						}
+					}
+					offset: {
+						common:      false
+						description: """
+							The offset to start consuming from, such as `1234`. Negative offsets, such as `-100`, are
+							relative to the end of the partition. If not set, consuming starts at the offset committed
+							for the consumer group, or as per `auto_offset_reset` if there is none.
+							"""
+						required:    false
+						type: "*": {}
+					}
+				}
+			}
+		}
 		auto_offset_reset: {
 			common:      false
 			description: """
@@ -64,6 +101,21 @@ components: sources: kafka: {
 				unit: "milliseconds"
 			}
 		}
+		decompression: {
+			common:      false
+			description: "The compression of the message payloads, undone before they are framed and decoded. Each payload is decompressed once it's completely received."
+			required:    false
+			type: string: {
+				default: "none"
+				enum: {
+					none: "The payloads aren't compressed."
+					auto: "The compression is detected from the magic bytes of each payload, which is kept as is if it doesn't start with those of a supported format."
+					gzip: "The payloads are [gzip](\(urls.gzip)) compressed."
+					zstd: "The payloads are [zstd](\(urls.zstd)) compressed."
+					lz4:  "The payloads are [lz4](\(urls.lz4)) frames."
+				}
+			}
+		}
 		fetch_wait_max_ms: {
 			common:      false
 			description: "Maximum time the broker may wait to fill the response."
@@ -99,6 +151,22 @@ components: sources: kafka: {
 				examples: ["topic"]
 			}
 		}
+		key_decoding: {
+			common:      false
+			description: "Configures how the Kafka message key is decoded."
+			required:    false
+			type: object: options: codec: {
+				description: "The codec to decode the message key with."
+				required:    false
+				type: string: {
+					default: "bytes"
+					enum: {
+						bytes: "Keeps the key as a string, invalid UTF-8 sequences being replaced."
+						json:  "Parses the key as JSON. Keys which aren't valid JSON are kept as strings."
+					}
+				}
+			}
+		}
 		partition_key: {
 			common:      false
 			description: "The log field name to use for the Kafka partition name."
@@ -119,7 +187,10 @@ components: sources: kafka: {
 		}
 		headers_key: {
 			common:      false
-			description: "The log field name to use for the Kafka headers."
+			description: """
+				The log field name to use for the Kafka headers, as a map of header names to their values. The values
+				of a header repeated in a message are collected into an array.
+				"""
 			required:    false
 			type: string: {
 				default: "headers"
@@ -127,6 +198,21 @@ components: sources: kafka: {
 			}
 		}
 		librdkafka_options: components._kafka.configuration.librdkafka_options
+		max_redeliveries: {
+			common:      false
+			description: "How many times a message is consumed again, after its delivery errored with `redeliver_errored` enabled or its acknowledgement timed out, before its offset is committed anyway."
+			required:    false
+			type: uint: {
+				default: 3
+				unit:    null
+			}
+		}
+		redeliver_errored: {
+			common:      false
+			description: "Whether the messages whose delivery errored are consumed again, up to `max_redeliveries` times, instead of having their offset committed. Only applies with acknowledgements enabled."
+			required:    false
+			type: bool: default: false
+		}
 		sasl: {
 			common:      false
 			description: "Options for SASL/SCRAM authentication support."
@@ -182,10 +268,17 @@ components: sources: kafka: {
 		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
 		topics: {
-			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`."
-			required:    true
-			type: array: items: type: string: {
-				examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+			description: """
+				The Kafka topics names to read events from. Regex is supported if the topic begins with `^`.
+				Required unless `assignments` is set.
+				"""
+			common:      true
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+				}
 			}
 		}
 	}
@@ -229,27 +322,40 @@ components: sources: kafka: {
 	}
 
 	telemetry: metrics: {
-		events_failed_total:                  components.sources.internal_metrics.output.metrics.events_failed_total
-		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
-		consumer_offset_updates_failed_total: components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
-		kafka_queue_messages:                 components.sources.internal_metrics.output.metrics.kafka_queue_messages
-		kafka_queue_messages_bytes:           components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
-		kafka_requests_total:                 components.sources.internal_metrics.output.metrics.kafka_requests_total
-		kafka_requests_bytes_total:           components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
-		kafka_responses_total:                components.sources.internal_metrics.output.metrics.kafka_responses_total
-		kafka_responses_bytes_total:          components.sources.internal_metrics.output.metrics.kafka_responses_bytes_total
-		kafka_produced_messages_total:        components.sources.internal_metrics.output.metrics.kafka_produced_messages_total
-		kafka_produced_messages_bytes_total:  components.sources.internal_metrics.output.metrics.kafka_produced_messages_bytes_total
-		kafka_consumed_messages_total:        components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
-		kafka_consumed_messages_bytes_total:  components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
-		processed_bytes_total:                components.sources.internal_metrics.output.metrics.processed_bytes_total
-		processed_events_total:               components.sources.internal_metrics.output.metrics.processed_events_total
-		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
-		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
-		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
-		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
-		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		events_failed_total:                    components.sources.internal_metrics.output.metrics.events_failed_total
+		events_in_total:                        components.sources.internal_metrics.output.metrics.events_in_total
+		consumer_offset_updates_failed_total:   components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
+		kafka_queue_messages:                   components.sources.internal_metrics.output.metrics.kafka_queue_messages
+		kafka_queue_messages_bytes:             components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
+		kafka_requests_total:                   components.sources.internal_metrics.output.metrics.kafka_requests_total
+		kafka_requests_bytes_total:             components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
+		kafka_responses_total:                  components.sources.internal_metrics.output.metrics.kafka_responses_total
+		kafka_responses_bytes_total:            components.sources.internal_metrics.output.metrics.kafka_responses_bytes_total
+		kafka_produced_messages_total:          components.sources.internal_metrics.output.metrics.kafka_produced_messages_total
+		kafka_produced_messages_bytes_total:    components.sources.internal_metrics.output.metrics.kafka_produced_messages_bytes_total
+		kafka_consumed_messages_total:          components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
+		kafka_consumed_messages_bytes_total:    components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
+		kafka_redeliveries_exhausted_total:     components.sources.internal_metrics.output.metrics.kafka_redeliveries_exhausted_total
+		processed_bytes_total:                  components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:                 components.sources.internal_metrics.output.metrics.processed_events_total
+		component_discarded_events_total:       components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:                 components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:         components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:        components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total:   components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		source_acknowledgement_latency_seconds: components.sources.internal_metrics.output.metrics.source_acknowledgement_latency_seconds
+		source_acknowledgement_timeouts_total:  components.sources.internal_metrics.output.metrics.source_acknowledgement_timeouts_total
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		partition_assignment: {
+			title: "Partition assignment"
+			body: """
+				By default, the source subscribes to `topics` and the partitions are balanced across the members
+				of the consumer group. With `assignments`, the source consumes from the listed partitions instead,
+				starting at their configured offsets, and doesn't take part in the group rebalancing. Offsets are
+				still committed for the consumer group.
+				"""
+		}
+	}
 }
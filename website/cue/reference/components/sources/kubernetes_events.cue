@@ -0,0 +1,203 @@
+package metadata
+
+components: sources: kubernetes_events: {
+	title: "Kubernetes Events"
+
+	description: """
+		Collects Kubernetes `Event`s from the Kubernetes API, enriched with
+		metadata of the object each `Event` is about.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		collect: {
+			checkpoint: enabled: true
+			from: {
+				service: services.kubernetes
+
+				interface: socket: {
+					api: {
+						title: "Kubernetes API"
+						url:   urls.kubernetes
+					}
+					direction: "outgoing"
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: [
+			"""
+				[Kubernetes](\(urls.kubernetes)) version `\(services.kubernetes.versions)` is required.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: "kubernetes"
+	}
+
+	configuration: {
+		namespace: {
+			common:      true
+			description: "The namespace to watch `Event`s in. `Event`s of all namespaces are watched if not set."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["default", "kube-system"]
+			}
+		}
+		label_selector: {
+			common:      false
+			description: "Specifies the label selector to filter `Event`s with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["my_custom_label=my_label_value"]
+			}
+		}
+		field_selector: {
+			common:      true
+			description: "Specifies the field selector to filter `Event`s with."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["type=Warning", "involvedObject.kind=Pod"]
+			}
+		}
+		kube_config_file: {
+			common:      false
+			description: "Optional path to a kubeconfig file readable by Vector. If not set, Vector will try to connect to Kubernetes using in-cluster configuration."
+			required:    false
+			type: string: default: null
+		}
+		checkpoint_interval_secs: {
+			common:      false
+			description: "How often to checkpoint the resource version of the last `Event` seen."
+			required:    false
+			type: uint: {
+				default: 5
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: event: {
+		description: "A Kubernetes `Event`."
+		fields: {
+			message: {
+				description: "The human-readable description of the `Event`."
+				required:    true
+				type: string: {
+					examples: ["Back-off restarting failed container"]
+				}
+			}
+			timestamp: {
+				description: "The time the `Event` was last seen, falling back to the time it was first seen."
+				required:    true
+				type: timestamp: {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["kubernetes_events"]
+				}
+			}
+			reason: {
+				description: "The machine-readable reason of the `Event`."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["BackOff", "FailedScheduling"]
+				}
+			}
+			type: {
+				description: "The type of the `Event`."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["Normal", "Warning"]
+				}
+			}
+			count: {
+				description: "The number of times the `Event` occurred."
+				required:    true
+				type: uint: {
+					examples: [1, 12]
+					unit: null
+				}
+			}
+			involved_object: {
+				description: "The object the `Event` is about."
+				required:    true
+				type: object: {
+					examples: [{
+						kind:      "Pod"
+						namespace: "default"
+						name:      "my-app-6d5bf8c4b-x7k2p"
+						uid:       "3b4e3f7a-2c1d-4e5f-9a8b-7c6d5e4f3a2b"
+					}]
+					options: {}
+				}
+			}
+			metadata: {
+				description: "The `name`, `namespace`, `uid` and `resource_version` of the `Event` object itself."
+				required:    true
+				type: object: {
+					examples: []
+					options: {}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		deduplication: {
+			title: "Deduplication"
+			body:  """
+				Kubernetes updates an existing `Event` instead of creating a new one when the
+				same event occurs again, incrementing its `count`. Vector forwards an `Event`
+				once for each distinct `count` it observes, so re-listing the `Event`s after a
+				watch is restarted does not produce duplicates.
+				"""
+		}
+		checkpointing: {
+			title: "Checkpointing"
+			body:  """
+				Vector periodically writes the resource version of the last `Event` seen to its
+				data directory, and resumes watching from it on restart. If the resource version
+				is too old for the API server to resume from, Vector starts over from the
+				current state.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		k8s_watch_requests_invoked_total:     components.sources.internal_metrics.output.metrics.k8s_watch_requests_invoked_total
+		k8s_watch_requests_failed_total:      components.sources.internal_metrics.output.metrics.k8s_watch_requests_failed_total
+		k8s_watch_stream_failed_total:        components.sources.internal_metrics.output.metrics.k8s_watch_stream_failed_total
+		k8s_watch_stream_items_obtained_total: components.sources.internal_metrics.output.metrics.k8s_watch_stream_items_obtained_total
+	}
+}
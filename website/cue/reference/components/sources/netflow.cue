@@ -0,0 +1,172 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	description: """
+		Collects flow records exported over NetFlow v5, [NetFlow v9](\(urls.netflow_v9)),
+		[IPFIX](\(urls.ipfix)) and [sFlow v5](\(urls.sflow)), turning each of them into an
+		event.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.netflow
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled:            false
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			common:      true
+			description: "The UDP address to listen for flow datagrams on. All supported protocols are accepted on the same address."
+			required:    false
+			type: string: {
+				default: "0.0.0.0:\(_port)"
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:4739", "0.0.0.0:6343"]
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the exporter the
+				datagram came from. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+	}
+
+	output: logs: flow: {
+		description: "A flow record, or a counters sample of sFlow."
+		fields: {
+			timestamp: {
+				description: "The export time of the datagram, or the time it was received at if its protocol has none."
+				required:    true
+				type: timestamp: {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["netflow"]
+				}
+			}
+			host: {
+				description: "The IP address of the exporter the datagram came from."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+				}
+			}
+			flow_type: {
+				description: "The protocol the record was exported with."
+				required:    true
+				type: string: {
+					enum: {
+						netflow_v5: "NetFlow v5"
+						netflow_v9: "NetFlow v9"
+						ipfix:      "IPFIX"
+						sflow:      "sFlow v5"
+					}
+				}
+			}
+			record_type: {
+				description: "The kind of record."
+				required:    true
+				type: string: {
+					enum: {
+						flow:     "A flow record, or a flow sample of sFlow."
+						options:  "An options record of NetFlow v9 or IPFIX, describing the exporter itself."
+						counters: "A counters sample of sFlow."
+					}
+				}
+			}
+			"*": {
+				description: """
+					The fields of the record and of the header of its datagram. Fields are named
+					after their [information elements](\(urls.ipfix_information_elements)) in
+					snake case, such as `source_ipv4_address` or `octet_delta_count`, including
+					those of NetFlow v5 records. Unknown elements are named `field_<id>`, or
+					`enterprise_<enterprise number>_<id>` for enterprise-specific ones.
+					"""
+				required:    false
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		templates: {
+			title: "Templates"
+			body:  """
+				The records of NetFlow v9 and IPFIX can only be decoded once the template they
+				refer to was received. Templates are kept per exporter and observation domain
+				for as long as Vector runs, and IPFIX template withdrawals are honored. Data sets
+				whose template is unknown are dropped, and counted with the
+				`component_errors_total` metric.
+				"""
+		}
+		values: {
+			title: "Values"
+			body:  """
+				Integer elements are emitted as integers, addresses in their usual notation, MAC
+				addresses and other octet strings as colon-separated hexadecimal bytes.
+				"""
+		}
+		sflow: {
+			title: "sFlow"
+			body:  """
+				Flow samples are emitted with the fields of their sampled packet headers, decoded
+				down to the transport layer, and of their extended switch data. Counters samples
+				are emitted with their generic interface counters. Other record formats are
+				skipped.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -0,0 +1,263 @@
+package metadata
+
+components: sources: opentelemetry: {
+	_grpc_port: 4317
+	_http_port: 4318
+
+	title: "OpenTelemetry"
+
+	description: """
+		Receives logs, metrics and traces from OpenTelemetry SDKs and collectors with the
+		OpenTelemetry protocol (OTLP), over gRPC or over HTTP with protobuf payloads.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator", "sidecar"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.opentelemetry
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _grpc_port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: enabled: false
+			keepalive: enabled:            false
+			// Each receiver has its own TLS options.
+			tls: enabled: false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		grpc: {
+			common:      true
+			description: "Configures the gRPC receiver of OTLP requests."
+			required:    false
+			type: object: options: {
+				address: {
+					description: "The address to listen for gRPC connections on. It _must_ include a port."
+					required:    false
+					common:      true
+					type: string: {
+						default: "0.0.0.0:\(_grpc_port)"
+						examples: ["0.0.0.0:\(_grpc_port)", "localhost:\(_grpc_port)"]
+					}
+				}
+				tls: configuration._tls_accept & {_args: {
+					can_enable:      true
+					enabled_default: false
+				}}
+			}
+		}
+		http: {
+			common:      true
+			description: "Configures the HTTP receiver of OTLP requests, which accepts protobuf payloads on `/v1/logs`, `/v1/metrics` and `/v1/traces`."
+			required:    false
+			type: object: options: {
+				address: {
+					description: "The address to listen for HTTP connections on. It _must_ include a port."
+					required:    false
+					common:      true
+					type: string: {
+						default: "0.0.0.0:\(_http_port)"
+						examples: ["0.0.0.0:\(_http_port)", "localhost:\(_http_port)"]
+					}
+				}
+				tls: configuration._tls_accept & {_args: {
+					can_enable:      true
+					enabled_default: false
+				}}
+			}
+		}
+	}
+
+	outputs: [
+		{
+			name: "logs"
+			description: """
+				Received log records. Use `<component_id>.logs` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "metrics"
+			description: """
+				Received metrics. Use `<component_id>.metrics` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "traces"
+			description: """
+				Received spans. Use `<component_id>.traces` as an input to downstream transforms and sinks.
+				"""
+		},
+	]
+
+	output: {
+		logs: record: {
+			description: "A log record received from an OpenTelemetry SDK or collector."
+			fields: {
+				message: {
+					description: "The body of the log record."
+					required:    false
+					type: "*": {}
+				}
+				timestamp: {
+					description: "The time of the log record, or the time it was observed if unknown, or the time it was received by Vector if neither is known."
+					required:    true
+					type: timestamp: {}
+				}
+				observed_timestamp: {
+					description: "The time the log record was observed by the collection system."
+					required:    false
+					type: timestamp: {}
+				}
+				severity_text: {
+					description: "The severity of the log record, as known by its source."
+					required:    false
+					type: string: {
+						examples: ["INFO", "ERROR"]
+					}
+				}
+				severity_number: {
+					description: "The severity of the log record, normalized by OpenTelemetry from 1 (`TRACE`) to 24 (`FATAL4`)."
+					required:    false
+					type: uint: {
+						examples: [9, 17]
+						unit: null
+					}
+				}
+				attributes: {
+					description: "The attributes of the log record."
+					required:    false
+					type: object: {
+						examples: [{"http.method": "GET"}]
+						options: {}
+					}
+				}
+				resources: {
+					description: "The attributes of the resource, such as the service, that produced the log record."
+					required:    false
+					type: object: {
+						examples: [{"service.name": "checkout"}]
+						options: {}
+					}
+				}
+				scope: {
+					description: "The `name`, `version` and `attributes` of the instrumentation scope that produced the log record."
+					required:    false
+					type: object: {
+						examples: [{"name": "io.opentelemetry.http", "version": "1.2.0"}]
+						options: {}
+					}
+				}
+				trace_id: {
+					description: "The ID of the trace the log record is part of, in hexadecimal."
+					required:    false
+					type: string: {
+						examples: ["4bf92f3577b34da6a3ce929d0e0e4736"]
+					}
+				}
+				span_id: {
+					description: "The ID of the span the log record is part of, in hexadecimal."
+					required:    false
+					type: string: {
+						examples: ["00f067aa0ba902b7"]
+					}
+				}
+				flags: {
+					description: "The trace flags of the log record."
+					required:    false
+					type: uint: {
+						examples: [1]
+						unit: null
+					}
+				}
+				dropped_attributes_count: {
+					description: "The number of attributes dropped by the source of the log record."
+					required:    false
+					type: uint: {
+						examples: [2]
+						unit: null
+					}
+				}
+			}
+		}
+		metrics: {
+			counter:   output._passthrough_counter
+			gauge:     output._passthrough_gauge
+			histogram: output._passthrough_histogram
+			summary:   output._passthrough_summary
+		}
+	}
+
+	how_it_works: {
+		receivers: {
+			title: "Receivers"
+			body:  """
+				The source implements both receivers of the [OpenTelemetry protocol](\(urls.opentelemetry_otlp)): the
+				gRPC services on the `grpc.address`, and the endpoints of each signal on the `http.address`, on which
+				requests must be `POST`s of protobuf payloads, compressed or not as set by their `Content-Encoding`.
+				JSON payloads are not supported.
+
+				Each signal is sent to its own output, so that logs, metrics and traces can be routed separately.
+				"""
+		}
+		metrics: {
+			title: "Metrics"
+			body:  """
+				Each data point is converted into a metric named after its OpenTelemetry metric, tagged with the
+				attributes of the data point, the attributes of its resource prefixed with `resource.`, and the
+				`scope.name` and `scope.version` of its instrumentation scope:
+
+				* Gauges become gauges.
+				* Monotonic sums become counters, and other sums gauges.
+				* Histograms become aggregated histograms. Exponential histograms are converted to explicit buckets,
+				  the first bucket holding the zero bucket and the negative values.
+				* Summaries become aggregated summaries.
+
+				Metrics with a delta aggregation temporality are incremental, and cumulative ones absolute.
+				"""
+		}
+		traces: {
+			title: "Traces"
+			body:  """
+				Each span is converted into a trace event with its `trace_id`, `span_id` and `parent_span_id` in
+				hexadecimal, `name`, `kind`, `start_timestamp`, `end_timestamp`, `attributes`, `events`, `links` and
+				`status`, along with the `resources` and `scope` it was produced by.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+	}
+}
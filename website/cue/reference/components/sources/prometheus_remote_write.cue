@@ -50,7 +50,8 @@ components: sources: prometheus_remote_write: {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
 		address: {
 			description: "The address to accept connections on. The address _must_ include a port."
 			required:    true
@@ -70,16 +71,30 @@ components: sources: prometheus_remote_write: {
 		metric_types: {
 			title: "Metric type interpretation"
 			body: """
-				The remote_write protocol used by this source transmits
-				only the metric tags, timestamp, and numerical value. No
-				explicit information about the original type of the
-				metric (i.e. counter, histogram, etc) is included. As
-				such, this source makes a guess as to what the original
-				metric type was.
+				The samples of the remote_write protocol carry only the
+				metric tags, timestamp, and numerical value. The type of
+				the metric families (i.e. counter, histogram, etc), their
+				help and unit are sent as metadata, which Prometheus sends
+				periodically in requests of their own.
 
-				For metrics named with a suffix of `_total`, this source
-				emits the value as a counter metric. All other metrics
-				are emitted as gauges.
+				This source remembers the metadata of each metric family
+				it receives, and uses it to interpret the samples of the
+				later requests, so that counters, histograms and summaries
+				are emitted as such. The help and unit are kept along with
+				the metrics, for sinks such as the `prometheus_remote_write`
+				sink to send them on. The samples of the families whose
+				metadata isn't known yet are emitted as gauges.
+				"""
+		}
+		staleness_markers: {
+			title: "Staleness markers"
+			body: """
+				Prometheus marks the series that have gone stale with a
+				sample of a special NaN value. This source keeps these
+				markers as the values of the metrics, distinct from other
+				NaN values. As the counts of histograms and summaries are
+				whole numbers, these are emitted with counts of zero and
+				with the marker as their sum.
 				"""
 		}
 	}
@@ -54,15 +54,171 @@ components: sources: prometheus_scrape: {
 
 	configuration: {
 		endpoints: {
-			description: "Endpoints to scrape metrics from."
-			required:    true
+			common:      true
+			description: "Endpoints to scrape metrics from. Required unless `file_sd` or `kubernetes_sd` is set."
+			required:    false
 			warnings: ["You must explicitly add the path to your endpoints. Vector will _not_ automatically add `/metrics`."]
 			type: array: {
+				default: []
 				items: type: string: {
 					examples: ["http://localhost:9090/metrics"]
 				}
 			}
 		}
+		file_sd: {
+			common:      false
+			description: """
+				Discovers the targets to scrape from JSON files in the format of Prometheus' `file_sd`, for example
+				`[{"targets": ["10.0.0.1:9100"], "labels": {"job": "node"}}]`. See
+				[target discovery](#target-discovery).
+				"""
+			required:    false
+			type: object: options: {
+				files: {
+					description: "Glob patterns of the files to read the targets from."
+					required:    true
+					type: array: items: type: string: examples: ["/etc/vector/targets/*.json"]
+				}
+				refresh_interval_secs: {
+					common:      false
+					description: "The interval between reads of the files, in seconds."
+					required:    false
+					type: uint: {
+						default: 30
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+		kubernetes_sd: {
+			common:      false
+			description: """
+				Discovers the targets to scrape from the `prometheus.io/*` annotations of Kubernetes pods, or of
+				services for their endpoints. See [target discovery](#target-discovery).
+				"""
+			required:    false
+			type: object: options: {
+				role: {
+					common:      true
+					description: "The kind of objects to discover targets from."
+					required:    false
+					type: string: {
+						default: "pod"
+						enum: {
+							pod:       "Scrapes the running pods annotated with `prometheus.io/scrape: \"true\"`."
+							endpoints: "Scrapes the ready endpoints of the services annotated with `prometheus.io/scrape: \"true\"`."
+						}
+					}
+				}
+				namespaces: {
+					common:      true
+					description: "The namespaces to discover targets in. Targets are discovered in all namespaces if empty."
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: examples: ["default"]
+					}
+				}
+				label_selector: {
+					common:      false
+					description: "Specifies the label selector to filter the pods, or services, with."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["app.kubernetes.io/part-of=shop"]
+					}
+				}
+				kube_config_file: {
+					common:      false
+					description: """
+						Optional path to a kubeconfig file readable by Vector. If not set, Vector will try to connect
+						to Kubernetes using in-cluster configuration.
+						"""
+					required:    false
+					type: string: {
+						default: null
+						examples: ["/path/to/.kube/config"]
+					}
+				}
+				refresh_interval_secs: {
+					common:      false
+					description: "The interval between listings of the pods, or endpoints, in seconds."
+					required:    false
+					type: uint: {
+						default: 30
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+		relabel_configs: {
+			common:      false
+			description: """
+				Rules relabeling the discovered targets, applied in order, as Prometheus' `relabel_configs` do. They
+				don't apply to `endpoints`. See [target discovery](#target-discovery).
+				"""
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					action: {
+						common:      true
+						description: "The action to perform."
+						required:    false
+						type: string: {
+							default: "replace"
+							enum: {
+								replace:   "Sets `target_label` to `replacement` if `regex` matches the source labels."
+								keep:      "Drops the targets whose source labels don't match `regex`."
+								drop:      "Drops the targets whose source labels match `regex`."
+								labelmap:  "Copies the labels whose name matches `regex` to the labels named by `replacement`."
+								labeldrop: "Removes the labels whose name matches `regex`."
+								labelkeep: "Removes the labels whose name doesn't match `regex`."
+							}
+						}
+					}
+					source_labels: {
+						common:      true
+						description: "The labels whose values, joined with `separator`, are matched against `regex`."
+						required:    false
+						type: array: {
+							default: []
+							items: type: string: examples: ["__meta_kubernetes_namespace"]
+						}
+					}
+					separator: {
+						common:      false
+						description: "The separator between the values of the source labels."
+						required:    false
+						type: string: default: ";"
+					}
+					regex: {
+						common:      true
+						description: "The regular expression to match, anchored at both ends."
+						required:    false
+						type: string: {
+							default: "(.*)"
+							examples: ["prod|staging"]
+						}
+					}
+					target_label: {
+						common:      true
+						description: "The label to set with the `replace` action, in which capture groups can be referenced."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["namespace"]
+						}
+					}
+					replacement: {
+						common:      false
+						description: "The value to set, in which capture groups can be referenced as `$1`. The label is removed if empty."
+						required:    false
+						type: string: default: "$1"
+					}
+				}
+			}
+		}
 		scrape_interval_secs: {
 			common:      true
 			description: "The interval between scrapes, in seconds."
@@ -116,6 +272,31 @@ components: sources: prometheus_scrape: {
 		}}
 	}
 
+	how_it_works: {
+		target_discovery: {
+			title: "Target discovery"
+			body: """
+				Besides the static `endpoints`, targets can be discovered from files with `file_sd`, and from
+				Kubernetes with `kubernetes_sd`. As with Prometheus, discovered targets are sets of labels:
+				`__address__` is the `host:port` to scrape, `__scheme__` and `__metrics_path__` default to `http`
+				and `/metrics`, and `__param_<name>` labels are added to the query. Targets are rediscovered every
+				`refresh_interval_secs`, the previous targets being kept if that fails.
+
+				With `kubernetes_sd`, the `prometheus.io/port`, `prometheus.io/path` and `prometheus.io/scheme`
+				annotations set the port, path and scheme of the targets. Without a port annotation, every
+				declared port of the pod's containers, or of the endpoints, is scraped. Targets also have
+				`__meta_kubernetes_*` labels, such as `__meta_kubernetes_namespace`, `__meta_kubernetes_pod_name`,
+				`__meta_kubernetes_pod_label_<name>` or `__meta_kubernetes_service_annotation_<name>`, with the
+				characters of names other than letters and digits replaced by underscores. Targets of files
+				have the `__meta_filepath` label, and the labels of their group.
+
+				Once the targets are relabeled with `relabel_configs`, their labels not starting with `__` are
+				added as tags to the metrics scraped from them, conflicting tags being handled as per
+				`honor_labels`.
+				"""
+		}
+	}
+
 	output: metrics: {
 		_extra_tags: {
 			"instance": {
@@ -0,0 +1,310 @@
+package metadata
+
+components: sources: snmp_trap: {
+	_port: 162
+
+	title: "SNMP Trap"
+
+	description: """
+		Listens for [SNMP](\(urls.snmp)) v2c and v3 traps and informs, naming their
+		objects after the definitions of the configured [MIB modules](\(urls.snmp_mib)).
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.snmp
+				interface: socket: {
+					api: {
+						title: "SNMP"
+						url:   urls.snmp
+					}
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled:            false
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			common:      true
+			description: "The UDP address to listen for notifications on. Binding to ports below 1024 usually requires elevated privileges."
+			required:    false
+			type: string: {
+				default: "0.0.0.0:\(_port)"
+				examples: ["0.0.0.0:\(_port)", "127.0.0.1:1162"]
+			}
+		}
+		communities: {
+			common:      true
+			description: "The SNMPv2c communities notifications are accepted from. Notifications from any community are accepted when empty."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["public"]
+				}
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the agent the
+				notification came from. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+		mib_paths: {
+			common:      true
+			description: "MIB files, or directories of MIB files, defining the objects that notifications refer to. Modules may be given in any order; objects whose parents aren't defined by any of them are skipped."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["/usr/share/snmp/mibs", "/etc/vector/mibs/IF-MIB.txt"]
+				}
+			}
+		}
+		users: {
+			common:      false
+			description: "The SNMPv3 users notifications are accepted from, following the [User-based Security Model](\(urls.snmp_usm)). Notifications must be authenticated and encrypted if the user has an authentication or privacy password configured."
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					name: {
+						description: "The name of the user."
+						required:    true
+						type: string: {
+							examples: ["vector"]
+						}
+					}
+					auth_protocol: {
+						description: "The authentication protocol of the user."
+						required:    false
+						type: string: {
+							default: null
+							enum: {
+								md5: "HMAC-MD5-96"
+								sha: "HMAC-SHA-96"
+							}
+						}
+					}
+					auth_password: {
+						description: "The authentication password of the user."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["${SNMP_AUTH_PASSWORD}"]
+						}
+					}
+					priv_protocol: {
+						description: "The privacy protocol of the user. Requires an authentication protocol, whose hash function derives the privacy key."
+						required:    false
+						type: string: {
+							default: null
+							enum: {
+								des: "CBC-DES"
+								aes: "CFB128-AES-128"
+							}
+						}
+					}
+					priv_password: {
+						description: "The privacy password of the user."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["${SNMP_PRIV_PASSWORD}"]
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: notification: {
+		description: "An SNMP trap or inform."
+		fields: {
+			message: {
+				description: "The name of the notification, or its OID if no MIB module defines it."
+				required:    false
+				type: string: {
+					examples: ["IF-MIB::linkDown"]
+				}
+			}
+			timestamp:   fields._current_timestamp
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["snmp_trap"]
+				}
+			}
+			host: {
+				description: "The IP address of the agent the notification came from."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+				}
+			}
+			version: {
+				description: "The SNMP version of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						"2c": "SNMPv2c"
+						"3":  "SNMPv3"
+					}
+				}
+			}
+			community: {
+				description: "The community of an SNMPv2c notification."
+				required:    false
+				type: string: {
+					examples: ["public"]
+				}
+			}
+			user: {
+				description: "The user of an SNMPv3 notification."
+				required:    false
+				type: string: {
+					examples: ["vector"]
+				}
+			}
+			engine_id: {
+				description: "The engine ID of the agent that sent an SNMPv3 notification, in hexadecimal."
+				required:    false
+				type: string: {
+					examples: ["80:00:1f:88:80:5e:2c:3f:61"]
+				}
+			}
+			context_name: {
+				description: "The context name of an SNMPv3 notification."
+				required:    false
+				type: string: {
+					examples: [""]
+				}
+			}
+			pdu_type: {
+				description: "The type of the notification."
+				required:    true
+				type: string: {
+					enum: {
+						trap:   "An unacknowledged notification."
+						inform: "A notification acknowledged by the receiver."
+					}
+				}
+			}
+			request_id: {
+				description: "The request ID of the notification."
+				required:    true
+				type: uint: {
+					examples: [1234]
+					unit: null
+				}
+			}
+			uptime: {
+				description: "The uptime of the agent in hundredths of a second, from `sysUpTime.0`."
+				required:    false
+				type: uint: {
+					examples: [4253]
+					unit: null
+				}
+			}
+			trap_oid: {
+				description: "The OID of the notification, from `snmpTrapOID.0`."
+				required:    false
+				type: string: {
+					examples: ["1.3.6.1.6.3.1.1.5.3"]
+				}
+			}
+			trap_name: {
+				description: "The name of the notification, if a MIB module defines it."
+				required:    false
+				type: string: {
+					examples: ["IF-MIB::linkDown"]
+				}
+			}
+			varbinds: {
+				description: "The remaining variable bindings of the notification, each with its `oid`, `type` and `value`, the `name` of the object if a MIB module defines it, and a `label` for enumerated integers and OIDs."
+				required:    true
+				type: array: {
+					items: type: object: {
+						examples: [{"oid": "1.3.6.1.2.1.2.2.1.8.3", "name": "IF-MIB::ifOperStatus.3", "type": "integer", "value": 2, "label": "down"}]
+						options: {}
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		mibs: {
+			title: "MIB Modules"
+			body:  """
+				OIDs are named after the closest object defined by the loaded MIB modules,
+				followed by the remaining arcs, so a column of a table resolves to names such as
+				`IF-MIB::ifOperStatus.3`. Objects of the SMI itself, such as `enterprises`, are
+				always known. Only OID assignments and the enumerations of integer syntaxes,
+				including those of textual conventions, are read from the modules.
+				"""
+		}
+		values: {
+			title: "Values"
+			body:  """
+				Octet strings that are valid UTF-8 without control characters are emitted as
+				strings, other octet strings and opaque values as colon-separated hexadecimal
+				bytes. Counters, gauges and time ticks are emitted as integers, IP addresses in
+				dotted notation.
+				"""
+		}
+		informs: {
+			title: "Informs"
+			body:  """
+				SNMPv2c informs are acknowledged with a response once they are parsed. SNMPv3
+				informs are turned into events, but not acknowledged, as this would require
+				Vector to act as the authoritative engine of the exchange.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -108,6 +108,125 @@ components: sources: statsd: {
 		set:          output._passthrough_set
 	}
 
+	output: logs: {
+		service_check: {
+			description: "A DogStatsD service check."
+			fields: {
+				service_check: {
+					description: "The name of the service check."
+					required:    true
+					type: string: examples: ["redis.can_connect"]
+				}
+				status: {
+					description: "The status of the service check."
+					required:    true
+					type: string: enum: {
+						ok:       "The service is up."
+						warning:  "The service is degraded."
+						critical: "The service is down."
+						unknown:  "The status of the service is unknown."
+					}
+				}
+				message: {
+					description: "The message describing the status, if any."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["Connection timed out."]
+					}
+				}
+				host:         _dogstatsd_host
+				container_id: _dogstatsd_container_id
+				tags:         _dogstatsd_tags
+				timestamp:    _dogstatsd_timestamp
+			}
+		}
+		event: {
+			description: "A DogStatsD event."
+			fields: {
+				title: {
+					description: "The title of the event."
+					required:    true
+					type: string: examples: ["Deployment"]
+				}
+				message: {
+					description: "The text of the event."
+					required:    true
+					type: string: examples: ["Version 2 is deployed."]
+				}
+				priority: {
+					description: "The priority of the event, if any."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["normal", "low"]
+					}
+				}
+				alert_type: {
+					description: "The alert type of the event, if any."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["error", "warning", "info", "success"]
+					}
+				}
+				aggregation_key: {
+					description: "The key grouping the event with others, if any."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["deployments"]
+					}
+				}
+				source_type_name: {
+					description: "The name of the source of the event, if any."
+					required:    false
+					type: string: {
+						default: null
+						examples: ["jenkins"]
+					}
+				}
+				host:         _dogstatsd_host
+				container_id: _dogstatsd_container_id
+				tags:         _dogstatsd_tags
+				timestamp:    _dogstatsd_timestamp
+			}
+		}
+	}
+
+	_dogstatsd_host: {
+		description: "The host name, if any."
+		required:    false
+		type: string: {
+			default: null
+			examples: ["my-host.local"]
+		}
+	}
+
+	_dogstatsd_container_id: {
+		description: "The ID of the container sending the datagram, if any."
+		required:    false
+		type: string: {
+			default: null
+			examples: ["83c0a99c0a54"]
+		}
+	}
+
+	_dogstatsd_tags: {
+		description: "The tags, if any."
+		required:    false
+		type: object: {
+			examples: [{"env": "prod"}]
+			options: {}
+		}
+	}
+
+	_dogstatsd_timestamp: {
+		description: "The timestamp given by the datagram, or else the time the datagram was received at."
+		required:    true
+		type: timestamp: {}
+	}
+
 	how_it_works: {
 		timestamps: {
 			title: "Timestamps"
@@ -117,7 +236,17 @@ components: sources: statsd: {
 				value indicating a realtime metric (i.e. not a historical metric). Normally, such
 				`null` timestamps are substituted with the current time by downstream sinks or
 				third-party services during sending/ingestion. See the
-				[metric data model](\(urls.vector_metric)) page for more info.
+				[metric data model](\(urls.vector_metric)) page for more info. The metrics with a
+				DogStatsD timestamp field are assigned its timestamp.
+				"""
+		}
+		dogstatsd: {
+			title: "DogStatsD extensions"
+			body: """
+				The DogStatsD extensions of the datagrams sent by Datadog agents and clients are
+				supported. The tags of the metrics are parsed into their tags, and their container
+				ID field into a `container_id` tag. The service checks and events are emitted as
+				logs. The fields of later extensions are ignored.
 				"""
 		}
 	}
@@ -57,6 +57,20 @@ components: sources: syslog: {
 				examples: ["0.0.0.0:\(_port)", "systemd", "systemd#3"]
 			}
 		}
+		framing: {
+			common:        false
+			description:   "How the messages are framed, either octet counted as specified in [RFC 6587](\(urls.syslog_6587)) or delimited by newlines."
+			relevant_when: "mode = `tcp` or `unix`"
+			required:      false
+			type: string: {
+				default: "auto"
+				enum: {
+					auto:            "Detected from the first message of each connection, octet counted if it starts with a digit."
+					octet_counting:  "The messages are octet counted, each starting with its length."
+					non_transparent: "The messages are delimited by newlines."
+				}
+			}
+		}
 		host_key: {
 			category:    "Context"
 			common:      false
@@ -97,6 +111,12 @@ components: sources: syslog: {
 				examples: ["/path/to/socket"]
 			}
 		}
+		route_parse_failures: {
+			common:      false
+			description: "Whether the messages that can't be parsed are sent to the `parse_failures` output, rather than dropped."
+			required:    false
+			type: bool: default: false
+		}
 		connection_limit: {
 			common:        false
 			description:   "The max number of TCP connections that will be processed."
@@ -183,10 +203,11 @@ components: sources: syslog: {
 				}
 			}
 			"*": {
-				description: "In addition to the defined fields, any Syslog 5424 structured fields are parsed and inserted as root level fields."
+				description: "In addition to the defined fields, each element of the Syslog 5424 structured data is inserted as a root level field named after its ID, holding an object of its parameters."
 				required:    true
-				type: string: {
-					examples: ["hello world"]
+				type: object: {
+					examples: [{"iut": "3", "eventSource": "Application"}]
+					options: {}
 				}
 			}
 		}
@@ -218,19 +239,48 @@ components: sources: syslog: {
 				appname:     _app_name
 				procid:      _procid
 				msgid:       _msgid
-				iut:         _iut
-				eventSource: _event_source
-				eventID:     _event_id
-				message:     _message
+				"exampleSDID@32473": {
+					iut:         _iut
+					eventSource: _event_source
+					eventID:     _event_id
+				}
+				message: _message
 			}
 		},
 	]
 
+	outputs: [
+		components._default_output,
+		{
+			name: "parse_failures"
+			description: """
+				The messages that can't be parsed, if `route_parse_failures` is enabled, with the
+				entire line in the `message` field. For a source component named `foo`, this output
+				can be accessed by specifying `foo.parse_failures` as the input to another component.
+				"""
+		},
+	]
+
 	how_it_works: {
 		line_delimiters: {
 			title: "Line Delimiters"
 			body: """
-				Each line is read until a new line delimiter, the `0xA` byte, is found.
+				Each line is read until a new line delimiter, the `0xA` byte, is found. Over TCP
+				and Unix sockets, the messages may be octet counted instead, each starting with its
+				length as specified in [RFC 6587](\(urls.syslog_6587)). By default, the framing
+				of each connection is detected from its first message, so that messages starting
+				with digits aren't mistaken for octet counted ones.
+				"""
+		}
+
+		structured_data: {
+			title: "Structured Data"
+			body: """
+				The elements of the [RFC 5424](\(urls.syslog_5424)) structured data are
+				inserted as root level fields named after their IDs, such as
+				`exampleSDID@32473`, each holding an object of the element's parameters. The
+				escaped `"`, `\\` and `]` characters of the parameter values are unescaped.
+				Elements without parameters are ignored.
 				"""
 		}
 
@@ -243,8 +293,10 @@ components: sources: syslog: {
 				Syslog style). It's unfortunate that the Syslog specification isn't more
 				accurately followed, but we hope that Vector insulates you from these deviations.
 
-				If parsing fails, Vector includes the entire Syslog line in the `message`
-				key. If you find this happening often, we recommend using the
+				If parsing fails, the message is dropped, unless `route_parse_failures` is
+				enabled, in which case its event, with the entire Syslog line in the `message`
+				field, is sent to the `parse_failures` output. If you find this happening often,
+				we recommend using the
 				[`socket` source](\(urls.vector_socket_source)) combined with
 				[regex parsing](\(urls.vrl_functions)/#parse_regex) to implement your own custom
 				ingestion and parsing scheme. Alternatively, you can [open an
@@ -255,6 +307,7 @@ components: sources: syslog: {
 
 	telemetry: metrics: {
 		events_in_total:                 components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:          components.sources.internal_metrics.output.metrics.component_errors_total
 		connection_read_errors_total:    components.sources.internal_metrics.output.metrics.connection_read_errors_total
 		processed_bytes_total:           components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total:          components.sources.internal_metrics.output.metrics.processed_events_total
@@ -54,7 +54,8 @@ components: sources: vector: {
 	}
 
 	configuration: {
-		acknowledgements: configuration._source_acknowledgements
+		acknowledgement_timeout: configuration._source_acknowledgement_timeout
+		acknowledgements:        configuration._source_acknowledgements
 		address: {
 			description: """
 				The HTTP address to listen for connections on. It _must_ include a port.
@@ -64,6 +65,16 @@ components: sources: vector: {
 				examples: ["0.0.0.0:\(_port)"]
 			}
 		}
+		max_decompressed_size_bytes: {
+			common:        false
+			description:   "The maximum size of the events of a request once decompressed. The compressed requests exceeding it are rejected, protecting the source from decompression bombs."
+			required:      false
+			relevant_when: "version = \"2\""
+			type: uint: {
+				default: 104857600
+				unit:    "bytes"
+			}
+		}
 		shutdown_timeout_secs: {
 			common:      false
 			description: "The timeout before a connection is forcefully closed during shutdown."
@@ -88,6 +99,19 @@ components: sources: vector: {
 		}
 	}
 
+	how_it_works: {
+		protocol_negotiation: {
+			title: "Protocol negotiation"
+			body: """
+				With the version 2 of the API, the `vector` sink negotiates the version of the protocol and the
+				compression of the events with this source on each of its connections. The events are compressed
+				with zstd when the `compression` option of the sink is enabled, reducing the bandwidth used between
+				agents and aggregators. The compressed events are sent with a separate method, which the sources
+				of older versions reject rather than ignore, and the sink then sends them uncompressed.
+				"""
+		}
+	}
+
 	output: {
 		logs: event: {
 			description: "A Vector event"
@@ -109,12 +133,14 @@ components: sources: vector: {
 	}
 
 	telemetry: metrics: {
-		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
-		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
-		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
-		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
-		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
-		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
-		protobuf_decode_errors_total:         components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
+		component_discarded_events_total:       components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:                 components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:         components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:        components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total:   components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		events_in_total:                        components.sources.internal_metrics.output.metrics.events_in_total
+		protobuf_decode_errors_total:           components.sources.internal_metrics.output.metrics.protobuf_decode_errors_total
+		source_acknowledgement_latency_seconds: components.sources.internal_metrics.output.metrics.source_acknowledgement_latency_seconds
+		source_acknowledgement_timeouts_total:  components.sources.internal_metrics.output.metrics.source_acknowledgement_timeouts_total
 	}
 }
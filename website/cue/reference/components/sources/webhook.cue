@@ -0,0 +1,172 @@
+package metadata
+
+components: sources: webhook: {
+	_port: 8080
+
+	title: "Webhook"
+
+	description: """
+		Receives the webhooks of GitHub, Stripe, Slack, or PagerDuty, rejecting the deliveries
+		that aren't signed with the shared secret.
+		"""
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.http
+
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["http"]
+					ssl: "optional"
+				}
+			}
+
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: [
+			"""
+				Slack redelivers events it didn't receive a response for within 3 seconds. With
+				acknowledgements enabled, responses wait for the events to be delivered to the sinks,
+				which should then be fast enough.
+				""",
+		]
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		address:          sources.http.configuration.address
+		headers:          sources.http.configuration.headers
+		path: {
+			common:      false
+			description: "The URL path on which the webhooks are delivered."
+			required:    false
+			type: string: {
+				default: "/"
+				examples: ["/webhooks/github"]
+			}
+		}
+		provider: {
+			description: "The provider delivering the webhooks, which determines how their signature is verified."
+			required:    true
+			type: string: {
+				enum: {
+					github:    "Deliveries signed in the `X-Hub-Signature-256` header."
+					pagerduty: "Deliveries signed in the `X-PagerDuty-Signature` header."
+					slack:     "Requests signed in the `X-Slack-Signature` and `X-Slack-Request-Timestamp` headers."
+					stripe:    "Events signed in the `Stripe-Signature` header."
+				}
+			}
+		}
+		secret: {
+			description: "The secret shared with the provider, that deliveries are signed with."
+			required:    true
+			type: string: {
+				examples: ["${WEBHOOK_SECRET}"]
+			}
+		}
+		timestamp_tolerance_secs: {
+			common:      false
+			description: "The largest difference allowed between the time Stripe or Slack signed a delivery and the time it's received, protecting against replays. `0` disables the check."
+			required:    false
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+		unwrap_batches: {
+			common:      false
+			description: "Whether a delivery carrying several events is split into one event each. Batches are JSON arrays, or the `messages` array of PagerDuty's deliveries."
+			required:    false
+			type: bool: default: false
+		}
+	}
+
+	output: logs: event: {
+		description: "A webhook delivery, or an item of a batch of them."
+		fields: {
+			"*": {
+				description: "The fields of the JSON payload, or of the submitted form."
+				required:    true
+				type: "*": {}
+			}
+			message: {
+				description: "The payload, if it isn't a JSON object."
+				required:    false
+				type: "*": {}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["webhook"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		signatures: {
+			title: "Signatures"
+			body: """
+				Every delivery is verified with the scheme of the provider, all of them being an
+				HMAC-SHA256 of the payload keyed by the shared secret:
+				[GitHub](\(urls.github_webhook_signatures)),
+				[Stripe](\(urls.stripe_webhook_signatures)),
+				[Slack](\(urls.slack_request_signing)), and
+				[PagerDuty](\(urls.pagerduty)). Several signatures are accepted
+				while a secret is rolled, for the providers sending them. Deliveries without a valid
+				signature are answered with `401 Unauthorized`, and those that aren't JSON, or forms
+				for Slack's slash commands and interactions, with `400 Bad Request`.
+				"""
+		}
+		responses: {
+			title: "Responses"
+			body: """
+				Deliveries are answered with `200 OK` once their events are forwarded, or delivered
+				to the sinks when acknowledgements are enabled. Events failing to be delivered are
+				answered with `500 Internal Server Error`, so that the provider redelivers them.
+
+				Slack verifies an endpoint by sending it a
+				[`url_verification`](\(urls.slack_url_verification)) request, which is answered with
+				its challenge instead of being forwarded.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		processed_bytes_total:                components.sources.internal_metrics.output.metrics.processed_bytes_total
+	}
+}
@@ -0,0 +1,253 @@
+package metadata
+
+components: sources: windows_event_log: {
+	title: "Windows Event Log"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: true
+		collect: {
+			checkpoint: enabled: true
+			from: service:       services.windows_event_log
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      false
+			"aarch64-unknown-linux-musl":     false
+			"armv7-unknown-linux-gnueabihf":  false
+			"armv7-unknown-linux-musleabihf": false
+			"x86_64-apple-darwin":            false
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       false
+			"x86_64-unknown-linux-musl":      false
+		}
+
+		requirements: [
+			"""
+				Reading the `Security` channel requires Vector to run as an administrator, or
+				as a member of the `Event Log Readers` group.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		acknowledgements: configuration._source_acknowledgements
+		batch_size: {
+			common:      false
+			description: "The maximum number of events read at once. The bookmark is saved at the end of each batch."
+			required:    false
+			type: uint: {
+				default: 100
+				unit:    "events"
+			}
+		}
+		channels: {
+			common:      true
+			description: "The channels to subscribe to."
+			required:    false
+			type: array: {
+				default: ["Application", "System"]
+				items: type: string: {
+					examples: ["Security", "Microsoft-Windows-Sysmon/Operational"]
+				}
+			}
+		}
+		levels: {
+			common:      true
+			description: "The levels of the events to collect. Events of all levels are collected when empty."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					enum: {
+						critical:    "Critical events."
+						error:       "Errors."
+						warning:     "Warnings."
+						information: "Informational events, including those logged regardless of the level."
+						verbose:     "Verbose events."
+					}
+				}
+			}
+		}
+		read_existing_events: {
+			common:      false
+			description: "Whether to collect the events already in the channels when no bookmark has been saved yet, rather than only new ones."
+			required:    false
+			type: bool: default: false
+		}
+	}
+
+	output: logs: event: {
+		description: "An event of the Windows Event Log."
+		fields: {
+			message: {
+				description: "The message of the event, formatted by its provider. Absent if the provider isn't installed or defines no message for the event."
+				required:    false
+				type: string: {
+					examples: ["The Windows Update service entered the running state."]
+				}
+			}
+			timestamp: {
+				description: "The time the event was created at."
+				required:    true
+				type: timestamp: {}
+			}
+			host: {
+				description: "The computer the event was logged on."
+				required:    true
+				type: string: {
+					examples: ["WIN-HOST"]
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["windows_event_log"]
+				}
+			}
+			channel: {
+				description: "The channel the event was logged to."
+				required:    true
+				type: string: {
+					examples: ["System"]
+				}
+			}
+			provider_name: {
+				description: "The provider that logged the event."
+				required:    true
+				type: string: {
+					examples: ["Service Control Manager"]
+				}
+			}
+			provider_guid: {
+				description: "The GUID of the provider that logged the event, for manifest-based providers."
+				required:    false
+				type: string: {
+					examples: ["{555908d1-a6d7-4695-8e1e-26931d2012f4}"]
+				}
+			}
+			event_id: {
+				description: "The identifier of the event, unique for its provider."
+				required:    true
+				type: uint: {
+					examples: [7036]
+					unit: null
+				}
+			}
+			event_qualifiers: {
+				description: "The qualifiers of the event identifier, for events of classic event sources."
+				required:    false
+				type: uint: {
+					examples: [16384]
+					unit: null
+				}
+			}
+			level: {
+				description: "The name of the level of the event, for the standard levels."
+				required:    false
+				type: string: {
+					enum: {
+						critical:    "A critical event."
+						error:       "An error."
+						warning:     "A warning."
+						information: "An informational event."
+						verbose:     "A verbose event."
+					}
+				}
+			}
+			level_value: {
+				description: "The level of the event."
+				required:    true
+				type: uint: {
+					examples: [4]
+					unit: null
+				}
+			}
+			record_id: {
+				description: "The number of the event in its channel."
+				required:    true
+				type: uint: {
+					examples: [12345]
+					unit: null
+				}
+			}
+			keywords: {
+				description: "The keywords of the event, as a hexadecimal mask."
+				required:    false
+				type: string: {
+					examples: ["0x8080000000000000"]
+				}
+			}
+			event_data: {
+				description: "The data of the event, keyed by name. An array of values for events whose data has no names, as logged by classic event sources."
+				required:    false
+				type: object: {
+					examples: [{"param1": "Windows Update", "param2": "running"}]
+					options: {}
+				}
+			}
+			user_data: {
+				description: "The user data of the event, with its elements converted into nested objects."
+				required:    false
+				type: object: {
+					examples: [{"LogFileCleared": {"SubjectUserName": "admin"}}]
+					options: {}
+				}
+			}
+			"*": {
+				description: """
+					The version, task, opcode, process_id, thread_id, activity_id,
+					related_activity_id and user_id of the event, if present in its system
+					properties.
+					"""
+				required:    false
+				type: "*": {}
+			}
+		}
+	}
+
+	how_it_works: {
+		bookmarks: {
+			title: "Bookmarks"
+			body:  """
+				The position of the last processed event in each of the channels is saved as
+				a bookmark in the data directory at the end of each batch, after the events are
+				acknowledged if acknowledgements are enabled. Vector resumes after the bookmark
+				when restarted. An unreadable bookmark is ignored, starting as if none was saved.
+				"""
+		}
+		filtering: {
+			title: "Filtering"
+			body:  """
+				The channels and levels are turned into a [structured query](\(urls.windows_event_log_query)),
+				so that events are filtered by the Event Log service rather than by Vector.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
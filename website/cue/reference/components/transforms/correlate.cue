@@ -0,0 +1,120 @@
+package metadata
+
+components: transforms: correlate: {
+	title: "Correlate"
+
+	description: """
+		Pairs the events of two streams sharing a key within a time window into merged events, such as
+		requests with their responses, without an external stream processor.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		key: {
+			description: "The key pairing an event of the left stream with an event of the right stream."
+			required:    true
+			type: string: {
+				examples: ["{{ request_id }}", "{{ host }}-{{ trace_id }}"]
+				syntax: "template"
+			}
+		}
+		left: {
+			description: "The condition matching the events of the left stream, such as the events of one of the inputs."
+			required:    true
+			type: condition: {}
+		}
+		left_field: {
+			common:      false
+			description: "The field of the merged events holding the fields of the left event."
+			required:    false
+			type: string: {
+				default: "left"
+				syntax:  "literal"
+			}
+		}
+		right: {
+			description: "The condition matching the events of the right stream, which aren't matched by `left`."
+			required:    true
+			type: condition: {}
+		}
+		right_field: {
+			common:      false
+			description: "The field of the merged events holding the fields of the right event."
+			required:    false
+			type: string: {
+				default: "right"
+				syntax:  "literal"
+			}
+		}
+		unmatched_left: {
+			common:      false
+			description: "What becomes of the left events not paired within their window."
+			required:    false
+			type: string: {
+				default: "emit"
+				enum: {
+					emit: "Forward the events as is."
+					drop: "Discard the events."
+				}
+			}
+		}
+		unmatched_right: {
+			common:      false
+			description: "What becomes of the right events not paired within their window."
+			required:    false
+			type: string: {
+				default: "emit"
+				enum: {
+					emit: "Forward the events as is."
+					drop: "Discard the events."
+				}
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "How long an event waits for an event of the other stream with the same key."
+			required:    false
+			type: float: {
+				default: 30.0
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		pairing: {
+			title: "Pairing"
+			body: """
+				Each event of a stream is paired with the oldest waiting event of the other stream with the
+				same key, and the merged event nests the fields of both under `left_field` and `right_field`,
+				with the latest of their timestamps. The events matching neither condition, or for which the
+				key can't be rendered, are forwarded as is.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
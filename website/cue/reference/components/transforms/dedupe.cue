@@ -40,9 +40,35 @@ components: transforms: dedupe: {
 							unit:    null
 						}
 					}
+					persist: {
+						common:      false
+						description: "Whether to persist the cache in the data directory, so the duplicates of the Events seen before a restart are still caught. A persisted cache always holds hashes, as with `hash`."
+						required:    false
+						type: bool: default: false
+					}
+					persist_interval_secs: {
+						common:        false
+						description:   "How often to write the persisted cache, which is also written on shutdown."
+						required:      false
+						relevant_when: "persist = true"
+						type: uint: {
+							default: 10
+							unit:    "seconds"
+						}
+					}
 				}
 			}
 		}
+		data_dir: {
+			common:      false
+			description: "The directory used to persist the cache when `cache.persist` is set. By default, the global `data_dir` option is used. Please make sure the Vector project has write permissions to this dir."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+				syntax: "file_system_path"
+			}
+		}
 		fields: {
 			description: "Options controlling what fields to match against."
 			required:    true
@@ -70,9 +96,25 @@ components: transforms: dedupe: {
 							}
 						}
 					}
+					vrl: {
+						common:      false
+						description: "A [VRL](\(urls.vrl_reference)) expression computing the value considered when deciding if an Event is a duplicate. Events for which it fails are never considered duplicates. Incompatible with the `fields.match` and `fields.ignore` options."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["[.user_id, .request_id]", "parse_json!(.message).id"]
+							syntax: "remap_program"
+						}
+					}
 				}
 			}
 		}
+		hash: {
+			common:      false
+			description: "Whether to cache a 128 bit hash of the fields considered for matching instead of their values, which bounds the memory used by each cached Event. Set `fields.ignore` to `[]` to match the full content of the Events."
+			required:    false
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -131,6 +173,18 @@ components: transforms: dedupe: {
 				"""
 		}
 
+		persistence: {
+			title: "Persistence"
+			body: """
+				With `cache.persist`, the hashes of the cache are written to the data
+				directory every `cache.persist_interval_secs` seconds and on
+				shutdown, and loaded back on startup, so duplicates are caught across
+				restarts. Since each entry only takes 16 bytes, large values of
+				`cache.num_events` can be used to catch duplicates across very large
+				windows.
+				"""
+		}
+
 		missing_fields: {
 			title: "Missing Fields"
 			body: """
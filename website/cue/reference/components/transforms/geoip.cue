@@ -5,7 +5,8 @@ components: transforms: geoip: {
 
 	description: """
 		Enrich events with geolocation data from the MaxMind GeoIP2-City,
-		GeoLite2-City, GeoIP2-ISP and GeoLite2-ASN databases.
+		GeoLite2-City, GeoIP2-ISP, GeoLite2-ASN, GeoIP2-Connection-Type and
+		GeoIP2-Anonymous-IP databases.
 		"""
 
 	classes: {
@@ -36,13 +37,52 @@ components: transforms: geoip: {
 			description: """
 				Path to the [MaxMind GeoIP2](\(urls.maxmind_geoip2)) or [GeoLite2 binary city
 				database](\(urls.maxmind_geolite2_city)) file (`GeoLite2-City.mmdb`). Other
-				databases, such as the the country database, are not supported.
+				databases, such as the the country database, are not supported. Either this
+				option or `databases` must be set.
 				"""
-			required:    true
+			common:      true
+			required:    false
 			type: string: {
+				default: null
 				examples: ["/path/to/GeoLite2-City.mmdb", "/path/to/GeoLite2-ISP.mmdb"]
 			}
 		}
+		databases: {
+			description: """
+				The databases to look the IP address up in, in addition to `database`, in a
+				single lookup. The databases are opened again when their files are updated.
+				"""
+			common:      false
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					path: {
+						description: "Path to the database file, of any of the [supported databases](#supported_databases)."
+						required:    true
+						type: string: {
+							examples: ["/path/to/GeoLite2-ASN.mmdb", "/path/to/GeoIP2-Anonymous-IP.mmdb"]
+						}
+					}
+					target: {
+						description: "The field to insert the results of this database into, `target` by default. The results of the databases with the same target are merged."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["network", "parent.child"]
+						}
+					}
+					fields: {
+						description: "The fields of the results to insert, each into the field of the event it is mapped to rather than into the target. The other fields are not inserted."
+						required:    false
+						type: object: {
+							examples: [{autonomous_system_number: "asn", is_anonymous: "network.anonymous"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
 		source: {
 			description: "The field name that contains the IP address. This field should contain a valid IPv4 or IPv6 address."
 			required:    true
@@ -84,11 +124,26 @@ components: transforms: geoip: {
 				* [GeoIP2-ISP.mmdb](\(urls.maxmind_geoip2_isp)) (paid) — Determine the Internet
 					Service Provider (ISP), organization name, and autonomous system organization
 					and number associated with an IP address.
+				* [GeoIP2-Connection-Type.mmdb](\(urls.maxmind_geoip2_connection_type)) (paid) —
+					Determine the connection type, such as cable or cellular, associated with an
+					IP address.
+				* [GeoIP2-Anonymous-IP.mmdb](\(urls.maxmind_geoip2_anonymous_ip)) (paid) —
+					Determine whether an IP address belongs to an anonymizer, such as a VPN, a
+					proxy or a Tor exit node.
 
 				The database files should be in the [MaxMind DB file
 				format](\(urls.maxmind_db_file_format)).
 				"""
 		}
+		database_reload: {
+			title: "Database updates"
+			body:  """
+				The database files are checked for updates every minute, and opened again when
+				modified, so that updated databases are used without restarting Vector. While an
+				updated file can't be opened, as when it is only partly written, the previous
+				database remains in use.
+				"""
+		}
 	}
 
 	output: logs: line: {
@@ -96,13 +151,18 @@ components: transforms: geoip: {
 			Available with the [GeoIP2-City](\(urls.maxmind_geoip2_city)) or
 			[GeoLite2-City](\(urls.maxmind_geolite2_city)) database.
 			"""
+		_anonymous_ip_db_blurb: """
+			Available with the [GeoIP2-Anonymous-IP](\(urls.maxmind_geoip2_anonymous_ip))
+			database
+			"""
 
 		description: "Geo-enriched log event"
 		fields: {
 			geoip: {
 				description: """
 					The root field containing all geolocation data as subfields. Depending on the
-					database used, either the city or the ISP field is populated.
+					databases used, the city, ISP, connection type or anonymizer fields are
+					populated.
 					"""
 				required: true
 				type: object: {
@@ -152,6 +212,20 @@ components: transforms: geoip: {
 							}
 							groups: ["City"]
 						}
+						connection_type: {
+							description: """
+								The connection type associated with the IP address. Available with
+								the [GeoIP2-Connection-Type](\(urls.maxmind_geoip2_connection_type))
+								database.
+								"""
+							required:    false
+							common:      false
+							type: string: {
+								default: null
+								examples: ["Cable/DSL", "Cellular", "Corporate", "Satellite"]
+							}
+							groups: ["Connection Type"]
+						}
 						continent_code: {
 							description: """
 								The continent code associated with the IP address.
@@ -182,6 +256,60 @@ components: transforms: geoip: {
 							}
 							groups: ["City"]
 						}
+						is_anonymous: {
+							description: """
+								Whether the IP address belongs to any anonymizer. \(_anonymous_ip_db_blurb).
+								"""
+							required:    false
+							common:      false
+							type: bool: default: null
+							groups: ["Anonymous IP"]
+						}
+						is_anonymous_vpn: {
+							description: """
+								Whether the IP address belongs to an anonymous VPN provider. \(_anonymous_ip_db_blurb).
+								"""
+							required:    false
+							common:      false
+							type: bool: default: null
+							groups: ["Anonymous IP"]
+						}
+						is_hosting_provider: {
+							description: """
+								Whether the IP address belongs to a hosting or VPN provider. \(_anonymous_ip_db_blurb).
+								"""
+							required:    false
+							common:      false
+							type: bool: default: null
+							groups: ["Anonymous IP"]
+						}
+						is_public_proxy: {
+							description: """
+								Whether the IP address belongs to a public proxy. \(_anonymous_ip_db_blurb).
+								"""
+							required:    false
+							common:      false
+							type: bool: default: null
+							groups: ["Anonymous IP"]
+						}
+						is_residential_proxy: {
+							description: """
+								Whether the IP address belongs to a residential ISP on a suspected anonymizing network. \(_anonymous_ip_db_blurb).
+								"""
+							required:    false
+							common:      false
+							type: bool: default: null
+							groups: ["Anonymous IP"]
+						}
+						is_tor_exit_node: {
+							description: """
+								Whether the IP address is a Tor exit node. \(_anonymous_ip_db_blurb).
+								"""
+							required:    false
+							common:      false
+							type: bool: default: null
+							groups: ["Anonymous IP"]
+						}
 						isp: {
 							description: """
 								The name of the Internet Service Provider (ISP) associated with the
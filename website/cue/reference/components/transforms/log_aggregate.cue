@@ -0,0 +1,162 @@
+package metadata
+
+components: transforms: log_aggregate: {
+	title: "Log Aggregate"
+
+	description: """
+		Aggregates log events sharing a key over time windows into rollup events, which hold their count,
+		statistics of a numeric field, and a sample of the original events, collapsing noisy repeated
+		lines before they reach expensive sinks.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      true
+	}
+
+	features: {
+		reduce: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		field: {
+			common:      true
+			description: "The numeric field to compute the `sum`, the `min` and the `max` of. Values that aren't numbers are ignored."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["duration", "response.bytes"]
+			}
+		}
+		group_by: {
+			common:      true
+			description: "The key grouping the events into rollups. If left unspecified, all the events of a window are grouped together."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["{{ message }}", "{{ host }}-{{ status }}"]
+				syntax: "template"
+			}
+		}
+		sample_size: {
+			common:      false
+			description: "The number of original events kept in the `samples` of each rollup."
+			required:    false
+			type: uint: {
+				default: 1
+				unit:    "events"
+			}
+		}
+		slide_secs: {
+			common: false
+			description: """
+				The interval between the starts of the windows. Defaults to `window_secs`, for tumbling
+				windows. Shorter intervals make sliding windows, which overlap so that each event is counted
+				in each of the windows it falls into. It must divide `window_secs`.
+				"""
+			required: false
+			type: uint: {
+				default: null
+				unit:    "seconds"
+			}
+		}
+		window_secs: {
+			common:      true
+			description: "The length of the windows, which are aligned on the epoch."
+			required:    false
+			type: uint: {
+				default: 60
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	output: logs: rollup: {
+		description: "The rollup of the events of a key over a window."
+		fields: {
+			count: {
+				description: "The number of events."
+				required:    true
+				type: uint: {
+					examples: [42]
+					unit: "events"
+				}
+			}
+			key: {
+				description: "The rendered `group_by` key."
+				required:    false
+				common:      true
+				type: string: {
+					default: null
+					examples: ["connection refused"]
+				}
+			}
+			max: {
+				description: "The maximum value of `field`, when `field` is set."
+				required:    false
+				common:      true
+				type: float: default: null
+			}
+			min: {
+				description: "The minimum value of `field`, when `field` is set."
+				required:    false
+				common:      true
+				type: float: default: null
+			}
+			samples: {
+				description: "The first events of the window, up to `sample_size`."
+				required:    false
+				common:      true
+				type: array: {
+					default: null
+					items: type: object: {}
+				}
+			}
+			sum: {
+				description: "The sum of the values of `field`, when `field` is set."
+				required:    false
+				common:      true
+				type: float: default: null
+			}
+			timestamp: {
+				description: "The end of the window."
+				required:    true
+				type: timestamp: {}
+			}
+			window_end: {
+				description: "The end of the window."
+				required:    true
+				type: timestamp: {}
+			}
+			window_start: {
+				description: "The start of the window."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	how_it_works: {
+		flushing: {
+			title: "Flushing"
+			body: """
+				The rollups of a window are emitted within a second of the end of the window, and the
+				rollups of all the open windows are emitted on shutdown. The windows are kept in memory,
+				so the memory used grows with the number of keys and with `window_secs / slide_secs`.
+				"""
+		}
+	}
+}
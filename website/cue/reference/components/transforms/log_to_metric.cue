@@ -25,106 +25,179 @@ components: transforms: log_to_metric: {
 	}
 
 	configuration: {
+		all_metrics: {
+			common: false
+			description: """
+				Converts the log events made by the `metric_to_log` transform back into the metrics they were
+				made from, with their kind, namespace, tags, timestamp and value, instead of deriving the
+				`metrics`.
+				"""
+			required: false
+			type: bool: default: false
+		}
+		host_tag: {
+			common:        false
+			description:   "The tag the host field is moved back into, as for the `host_tag` of `metric_to_log`."
+			required:      false
+			relevant_when: "all_metrics = true"
+			type: string: {
+				default: "host"
+				examples: ["host", "hostname"]
+			}
+		}
 		metrics: {
 			description: "A table of key/value pairs representing the keys to be added to the event."
-			required:    true
-			type: array: items: type: object: {
-				examples: []
-				options: {
-					field: {
-						description: "The log field to use as the metric."
-						required:    true
-						type: string: {
-							examples: ["duration", "parent.child"]
+			required:    false
+			common:      true
+			type: array: {
+				default: []
+				items: type: object: {
+					examples: []
+					options: {
+						buckets: {
+							description: """
+								The upper limits of the buckets to aggregate the samples of the histogram into,
+								emitting an aggregated histogram instead of a distribution.
+								"""
+							required:      false
+							common:        false
+							relevant_when: #"type = "histogram""#
+							type: array: {
+								default: null
+								items: type: float: examples: [0.1, 0.5, 1.0, 5.0]
+							}
 						}
-					}
-					increment_by_value: {
-						description: """
-							If `true` the metric will be incremented by the `field` value.
-							If `false` the metric will be incremented by 1 regardless of the `field` value.
-							"""
-						required:      false
-						common:        false
-						relevant_when: #"type = "counter""#
-						type: bool: {
-							default: false
+						field: {
+							description: """
+								The log field to use as the metric. Histograms and summaries can compute
+								their value with `value` instead.
+								"""
+							required:    true
+							type: string: {
+								examples: ["duration", "parent.child"]
+							}
 						}
-					}
-					kind: {
-						description: """
-							The kind of the metric.
-							"""
-						required:      false
-						common:        false
-						relevant_when: #"type = "counter""#
-						type: string: {
-							enum: {
-								absolute:    "An absolute counter value."
-								incremental: "In incremental counter value."
+						increment_by_value: {
+							description: """
+								If `true` the metric will be incremented by the `field` value.
+								If `false` the metric will be incremented by 1 regardless of the `field` value.
+								"""
+							required:      false
+							common:        false
+							relevant_when: #"type = "counter""#
+							type: bool: {
+								default: false
 							}
-							default: "incremental"
 						}
-					}
-					name: {
-						description: "The name of the metric. Defaults to `<field>_total` for `counter` and `<field>` for `gauge`."
-						required:    false
-						common:      true
-						type: string: {
-							default: null
-							examples: ["duration_total"]
-							syntax: "template"
+						kind: {
+							description: """
+								The kind of the metric.
+								"""
+							required:      false
+							common:        false
+							relevant_when: #"type = "counter""#
+							type: string: {
+								enum: {
+									absolute:    "An absolute counter value."
+									incremental: "In incremental counter value."
+								}
+								default: "incremental"
+							}
 						}
-					}
-					namespace: {
-						description: "The namespace of the metric."
-						required:    false
-						common:      true
-						type: string: {
-							default: null
-							examples: ["service"]
-							syntax: "template"
+						name: {
+							description: "The name of the metric. Defaults to `<field>_total` for `counter` and `<field>` for `gauge`."
+							required:    false
+							common:      true
+							type: string: {
+								default: null
+								examples: ["duration_total"]
+								syntax: "template"
+							}
 						}
-					}
-					tags: {
-						description: "Key/value pairs representing [metric tags](\(urls.vector_metric)#tags)."
-						required:    false
-						common:      true
-						type: object: {
-							examples: [
-								{
-									host:   "${HOSTNAME}"
-									region: "us-east-1"
-									status: "{{status}}"
-								},
-							]
-							options: {
-								"*": {
-									description: """
-	                      Key/value pairs representing [metric tags](\(urls.vector_metric)#tags).
-	                      Environment variables and field interpolation is allowed.
-	                      """
-									required:    true
-									type: "*": {}
+						namespace: {
+							description: "The namespace of the metric."
+							required:    false
+							common:      true
+							type: string: {
+								default: null
+								examples: ["service"]
+								syntax: "template"
+							}
+						}
+						tags: {
+							description: "Key/value pairs representing [metric tags](\(urls.vector_metric)#tags)."
+							required:    false
+							common:      true
+							type: object: {
+								examples: [
+									{
+										host:   "${HOSTNAME}"
+										region: "us-east-1"
+										status: "{{status}}"
+									},
+								]
+								options: {
+									"*": {
+										description: """
+		                      Key/value pairs representing [metric tags](\(urls.vector_metric)#tags).
+		                      Environment variables and field interpolation is allowed.
+		                      """
+										required:    true
+										type: "*": {}
+									}
 								}
 							}
 						}
-					}
-					type: {
-						description: "The metric type."
-						required:    true
-						type: string: {
-							enum: {
-								counter:   "A [counter metric type](\(urls.vector_metric)#counter)."
-								gauge:     "A [gauge metric type](\(urls.vector_metric)#gauge)."
-								histogram: "A [distribution metric type](\(urls.vector_metric)#histogram) with histogram statistic."
-								set:       "A [set metric type](\(urls.vector_metric)#set)."
-								summary:   "A [distribution metric type](\(urls.vector_metric)#distribution) with summary statistic."
+						value: {
+							description: """
+								A [VRL](\(urls.vrl_reference)) expression computing the value of the metric
+								instead of `field`, which then requires a `name`. It can return a number, for a
+								single sample, an array of numbers, for many samples, or an object holding the
+								`count`, the `sum` and the `buckets` (of `upper_limit` and `count`) of a
+								histogram or the `quantiles` (of `quantile` and `value`) of a summary.
+								"""
+							required:      false
+							common:        false
+							relevant_when: #"type = "histogram" or type = "summary""#
+							type: string: {
+								default: null
+								examples: ["[.request_time, .upstream_time]", "to_float!(.duration) * 1000"]
+								syntax: "remap_program"
+							}
+						}
+						type: {
+							description: "The metric type."
+							required:    true
+							type: string: {
+								enum: {
+									counter:   "A [counter metric type](\(urls.vector_metric)#counter)."
+									gauge:     "A [gauge metric type](\(urls.vector_metric)#gauge)."
+									histogram: "A [distribution metric type](\(urls.vector_metric)#histogram) with histogram statistic."
+									set:       "A [set metric type](\(urls.vector_metric)#set)."
+									summary:   "A [distribution metric type](\(urls.vector_metric)#distribution) with summary statistic."
+								}
 							}
 						}
 					}
 				}
 			}
 		}
+		tags: {
+			description: """
+				Key/value pairs representing [metric tags](\(urls.vector_metric)#tags) added to all the
+				metrics, unless they have a tag of the same name.
+				"""
+			required: false
+			common:   false
+			type: object: {
+				examples: [
+					{
+						host: "{{host}}"
+					},
+				]
+				options: {}
+			}
+		}
 	}
 
 	input: {
@@ -136,6 +209,8 @@ components: transforms: log_to_metric: {
 		counter:      output._passthrough_counter
 		distribution: output._passthrough_distribution
 		gauge:        output._passthrough_gauge
+		histogram:    output._passthrough_histogram
+		summary:      output._passthrough_summary
 		set:          output._passthrough_set
 	}
 
@@ -0,0 +1,169 @@
+package metadata
+
+components: transforms: metric_temporality: {
+	title: "Metric Temporality"
+
+	description: """
+		Converts incremental metrics, which hold the change since the previous
+		value, into absolute metrics, which hold the running total, or the reverse.
+		This bridges sources and sinks expecting a different kind, as StatsD or
+		OpenTelemetry delta metrics sent to Prometheus.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		expire_after_secs: {
+			common: false
+			description: """
+				How long the state of a series is kept after it was last seen. Expired series
+				start over: their running total from zero when converting to `absolute`, and
+				from a new reference value when converting to `incremental`.
+				"""
+			required: false
+			type: uint: {
+				default: 300
+				unit:    "seconds"
+			}
+		}
+		target_kind: {
+			description: "The kind the metrics are converted to."
+			required:    true
+			type: string: {
+				enum: {
+					absolute:    "Adds up the `incremental` metrics of each series into a running total."
+					incremental: "Emits the change of the `absolute` metrics of each series since their previous value."
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	examples: [
+		{
+			title: "Cumulative counter to deltas"
+			configuration: {
+				target_kind: "incremental"
+			}
+			input: [
+				{
+					metric: {
+						kind: "absolute"
+						name: "requests_total"
+						counter: {
+							value: 10.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "absolute"
+						name: "requests_total"
+						counter: {
+							value: 13.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "absolute"
+						name: "requests_total"
+						counter: {
+							value: 2.0
+						}
+					}
+				},
+			]
+			output: [
+				{
+					metric: {
+						kind: "incremental"
+						name: "requests_total"
+						counter: {
+							value: 3.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "incremental"
+						name: "requests_total"
+						counter: {
+							value: 2.0
+						}
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		conversion: {
+			title: "Conversion"
+			body: """
+				The last value of each series, identified by its name, namespace, and tags, is
+				kept. When converting to `absolute`, the `incremental` metrics are added to it
+				and the running total is emitted. When converting to `incremental`, the change
+				since it is emitted for the `absolute` metrics. The first `absolute` metric of a
+				series is only kept as the reference for the next ones, as its value usually
+				covers much more than what just happened. Metrics that are already of the target
+				kind are passed through.
+
+				Only `counter`, `gauge`, and aggregated `histogram` metrics are converted. Other
+				metrics, like `summary` ones whose quantiles can't be added up, are passed through
+				as they are.
+				"""
+		}
+
+		resets: {
+			title: "Resets"
+			body: """
+				A `counter` or `histogram` whose cumulative value goes down was reset, as when the
+				process counting it restarted. When converting to `incremental`, its new value is
+				then emitted as the change, since counting started again from zero, rather than
+				a negative change.
+				"""
+		}
+
+		state_expiration: {
+			title: "State Expiration"
+			body: """
+				The state of a series that wasn't seen for `expire_after_secs` is dropped, so that
+				memory doesn't grow without bounds as series come and go. The state is checked
+				for expired series every `expire_after_secs`, so a series may be kept up to
+				twice that long.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		metric_temporality_expired_series_total: components.sources.internal_metrics.output.metrics.metric_temporality_expired_series_total
+		metric_temporality_resets_total:         components.sources.internal_metrics.output.metrics.metric_temporality_resets_total
+	}
+}
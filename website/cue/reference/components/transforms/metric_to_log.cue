@@ -101,7 +101,16 @@ components: transforms: metric_to_log: {
 		},
 	]
 
-	how_it_works: {}
+	how_it_works: {
+		round_trip: {
+			title: "Round-tripping"
+			body: """
+				The log events hold all of the metric, including infinite values such as the upper limit of
+				the last bucket of histograms, so the `log_to_metric` transform with `all_metrics` set turns
+				them back into identical metrics.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
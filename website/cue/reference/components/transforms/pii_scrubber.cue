@@ -0,0 +1,123 @@
+package metadata
+
+components: transforms: pii_scrubber: {
+	title: "PII Scrubber"
+
+	description: """
+		Scrubs personally identifiable information from all the string fields of log events, nested or
+		not, by detecting it with regular expressions, checksums and dictionaries, and reports what it
+		scrubbed where on a separate audit output.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		sanitize: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		detectors: {
+			common:      true
+			description: "The builtin detectors to run."
+			required:    false
+			type: array: {
+				default: ["credit_card", "email", "ipv4", "phone", "us_ssn"]
+				items: type: string: enum: {
+					credit_card: "Card numbers of 13 to 19 digits, with a confidence of 0.95 when their Luhn checksum is valid and 0.2 otherwise."
+					email:       "Email addresses, with a confidence of 0.9."
+					ipv4:        "IPv4 addresses, with a confidence of 0.7 when their octets are valid and 0.1 otherwise."
+					phone:       "North American phone numbers, with a confidence of 0.5."
+					us_ssn:      "US social security numbers, with a confidence of 0.8 when their parts are valid and 0.3 otherwise."
+				}
+			}
+		}
+		dictionary: {
+			common:      false
+			description: "Words or phrases scrubbed wherever they appear as whole words, regardless of case, with a confidence of 1."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["Project Falcon"]
+			}
+		}
+		mask: {
+			common:      false
+			description: "The string replacing the matches in the `mask` mode."
+			required:    false
+			type: string: default: "[REDACTED]"
+		}
+		min_confidence: {
+			common:      false
+			description: "The confidence below which the matches of the detectors are left as is."
+			required:    false
+			type: float: default: 0.5
+		}
+		mode: {
+			common:      true
+			description: "How the detected PII is scrubbed."
+			required:    false
+			type: string: {
+				default: "mask"
+				enum: {
+					mask:   "Replace the matches with `mask`."
+					hash:   "Replace the matches with the hex-encoded SHA-256 hashes of `salt` followed by the matches, so they can still be correlated."
+					remove: "Remove the fields holding matches."
+				}
+			}
+		}
+		patterns: {
+			common:      false
+			description: "A table of names to custom regular expressions, the matches of which are scrubbed with a confidence of 1."
+			required:    false
+			type: object: {
+				examples: [{api_key: "sk_[A-Za-z0-9]{32}"}]
+				options: {
+					"*": {
+						description: "The regular expression."
+						required:    true
+						type: string: syntax: "regex"
+					}
+				}
+			}
+		}
+		salt: {
+			common:      false
+			description: "The salt prepended to the matches before hashing them in the `hash` mode."
+			required:    false
+			type: string: default: null
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "audit"
+			description: """
+				An audit record for each scrubbed event, holding the `mode` and the `findings`, each with
+				the `field`, the `detector` and the `confidence` of a scrubbed match, but never the match
+				itself. For a transform component named `foo`, this output can be accessed by specifying
+				`foo.audit` as the input to another component.
+				"""
+		},
+	]
+
+	telemetry: metrics: {
+		pii_findings_total: components.sources.internal_metrics.output.metrics.pii_findings_total
+	}
+}
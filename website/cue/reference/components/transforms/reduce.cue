@@ -58,6 +58,15 @@ components: transforms: reduce: {
 				unit:    "milliseconds"
 			}
 		}
+		max_bytes: {
+			common:      false
+			description: "The size of the events combined into a transaction above which the transaction is flushed, regardless of `ends_when`."
+			required:    false
+			type: uint: {
+				default: null
+				unit:    "bytes"
+			}
+		}
 		group_by: {
 			common:      true
 			description: "An ordered list of fields by which to group events. Each group is combined independently, allowing you to keep independent events separate. When no fields are specified, all events will be combined in a single group. Events missing a specified field will be combined in their own group."
@@ -84,6 +93,10 @@ components: transforms: reduce: {
 				   `[field-name]_end` is added with the last received
 				   timestamp value.
 				3. Numeric values are summed.
+
+				A strategy can also be a VRL expression, given as `{ vrl = "..." }`, computing
+				the combined value from the value combined so far, at `.accumulated`, and the
+				value of the new event, at `.value`.
 				"""
 			required: false
 			type: object: {
@@ -93,6 +106,7 @@ components: transforms: reduce: {
 						path:        "discard"
 						duration_ms: "sum"
 						query:       "array"
+						bytes: vrl: "int!(.accumulated) + int!(.value)"
 					},
 				]
 				options: {
@@ -112,6 +126,7 @@ components: transforms: reduce: {
 								max:            "The maximum of all numeric values."
 								min:            "The minimum of all numeric values."
 								flat_unique:    "Create a flattened array of all the unique values."
+								vrl:            "Compute the combined value with a VRL expression."
 							}
 						}
 					}
@@ -127,6 +142,19 @@ components: transforms: reduce: {
 			required: false
 			type: condition: {}
 		}
+		state_field: {
+			common: false
+			description: """
+				The field under which `ends_when` and `starts_when` see the transaction combined so far for
+				the group of the event, such as `._reduce.message`, so the conditions can depend on the
+				accumulated state. The field is only added to the event the conditions are checked against.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["_reduce"]
+			}
+		}
 	}
 
 	input: {
@@ -34,7 +34,7 @@ components: transforms: route: {
 				can then be referenced as an input by other components with the name `<transform_name>.<route_id>`.
 				Note, `_default` is a reserved output name and cannot be used as a route name.
 				"""
-			required: true
+			required: false
 			type: object: {
 				options: {
 					"*": {
@@ -48,6 +48,38 @@ components: transforms: route: {
 				}
 			}
 		}
+		weight_key: {
+			common: false
+			description: """
+				The key by which the events are assigned to the `weights` outputs: the events with the same key
+				always go to the same output. Without a key, the events are spread between the outputs in turn.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["{{ user_id }}", "{{ host }}"]
+				syntax: "template"
+			}
+		}
+		weights: {
+			common: false
+			description: """
+				A table of route identifiers to weights. Each event goes to exactly one of these routes, with a
+				probability proportional to its weight, such as to send 5% of the events to a canary sink. These
+				routes are referenced like the routes of `route`, the names of which they can't reuse.
+				"""
+			required: false
+			type: object: {
+				examples: [{canary: 5, production: 95}]
+				options: {
+					"*": {
+						description: "The weight of the route."
+						required:    true
+						type: uint: unit: null
+					}
+				}
+			}
+		}
 	}
 
 	input: {
@@ -0,0 +1,93 @@
+package metadata
+
+components: transforms: sample_adaptive: {
+	title: "Adaptive Sample"
+
+	description: """
+		Samples events down to a target rate, sharing it evenly between keys so that the quiet keys are kept
+		whole while the loud ones are sampled down, and records the effective sample rate of each event for
+		later upweighting.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		exclude: {
+			common: true
+			description: """
+				The set of logical conditions to exclude events from sampling. The excluded events aren't
+				counted towards the target rate.
+				"""
+			required: false
+			type: condition: {}
+		}
+		key_field: {
+			common: false
+			description: """
+				The key the target rate is shared between. If left unspecified, or if the event doesn't
+				have `key_field`, all the events share the same key.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["{{ service }}", "{{ host }}-{{ level }}"]
+				syntax: "template"
+			}
+		}
+		rate_field: {
+			common:      false
+			description: "The field recording the effective sample rate of the forwarded events, expressed as N for 1 out of N events."
+			required:    false
+			type: string: {
+				default: "sample_rate"
+				syntax:  "literal"
+			}
+		}
+		target_rate: {
+			description: """
+				The number of events per second to forward, across all the keys. Over each window, the keys
+				with fewer events than their share of the target are forwarded whole, and the others are
+				sampled down to their share, as computed from the previous window. The keys seen for the
+				first time are forwarded up to their share of the target, as of their first event, until
+				the window ends.
+				"""
+			required: true
+			type: float: {
+				examples: [100.0, 1000.0]
+			}
+		}
+		window_secs: {
+			common:      false
+			description: "How often the sample rates of the keys are computed. Must be finite."
+			required:    false
+			type: float: {
+				default: 10.0
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
@@ -0,0 +1,153 @@
+package metadata
+
+components: transforms: schema_enforce: {
+	title: "Schema Enforce"
+
+	description: """
+		Validates log events against a declared schema, checking the presence, the kinds, the allowed
+		values and the ranges of their fields, and drops the events violating it, repairs them, or
+		routes them to a separate output along with the details of their violations.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		action: {
+			common:      true
+			description: "What becomes of the events violating the schema."
+			required:    false
+			type: string: {
+				default: "route"
+				enum: {
+					drop:   "Discard the events."
+					repair: "Coerce the values to one of the kinds of their field, clamp them into its range, or else replace them, or fill the missing fields, with the `default` of their field. The events that can't be repaired are sent to the `violations` output."
+					route:  "Send the events to the `violations` output."
+				}
+			}
+		}
+		fields: {
+			description: "A table of field paths to the schema of their values."
+			required:    true
+			type: object: {
+				examples: [
+					{
+						status: {
+							required: true
+							kind: ["integer"]
+							min: 100.0
+							max: 599.0
+						}
+						level: {
+							enum: ["info", "warn", "error"]
+							default: "info"
+						}
+					},
+				]
+				options: {
+					"*": {
+						description: "The schema of the field."
+						required:    true
+						type: object: options: {
+							default: {
+								common:      false
+								description: "The value of the field set when repairing, if the field is missing or its value can't be repaired."
+								required:    false
+								type: "*": {}
+							}
+							enum: {
+								common:      false
+								description: "The values the field can have."
+								required:    false
+								type: array: {
+									default: null
+									items: type: "*": {}
+								}
+							}
+							kind: {
+								common:      true
+								description: "The kinds the value of the field can have. Any kind is allowed if left unspecified."
+								required:    false
+								type: array: {
+									default: []
+									items: type: string: enum: {
+										bytes:     "A string."
+										integer:   "An integer."
+										float:     "A floating point number."
+										boolean:   "A boolean."
+										timestamp: "A timestamp."
+										null:      "A null value."
+										array:     "An array."
+										object:    "An object."
+									}
+								}
+							}
+							max: {
+								common:      false
+								description: "The maximum numeric value of the field."
+								required:    false
+								type: float: default: null
+							}
+							min: {
+								common:      false
+								description: "The minimum numeric value of the field."
+								required:    false
+								type: float: default: null
+							}
+							required: {
+								common:      true
+								description: "Whether the field must be present."
+								required:    false
+								type: bool: default: false
+							}
+						}
+					}
+				}
+			}
+		}
+		violations_field: {
+			common:      false
+			description: "The field holding the violations of the events sent to the `violations` output, as an array of objects with the `field` and the `error` of each violation."
+			required:    false
+			type: string: {
+				default: "schema_violations"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "violations"
+			description: """
+				The events violating the schema, unless `action` is `drop`. For a transform component named
+				`foo`, this output can be accessed by specifying `foo.violations` as the input to another
+				component.
+				"""
+		},
+	]
+
+	telemetry: metrics: {
+		events_discarded_total:  components.sources.internal_metrics.output.metrics.events_discarded_total
+		schema_violations_total: components.sources.internal_metrics.output.metrics.schema_violations_total
+	}
+}
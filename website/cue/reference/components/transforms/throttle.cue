@@ -25,6 +25,70 @@ components: transforms: throttle: {
 	}
 
 	configuration: {
+		backend: {
+			common: false
+			description: """
+				A backend holding the counters of the keys, so that the `threshold` applies across all the
+				instances sharing it rather than to each instance. The events are then counted over windows of
+				`window_secs` aligned on the epoch. Each instance lets the events through or discards them right
+				away, from the counts last synced with the backend and those it counted since, so the threshold
+				may be exceeded by the events counted by the other instances between two syncs. Until the
+				counts are first synced, and while the backend is unreachable, the events are rate limited by
+				each instance.
+				"""
+			required: false
+			type: object: options: {
+				key_prefix: {
+					common:      false
+					description: "The prefix of the keys of the counters in the backend."
+					required:    false
+					type: string: {
+						default: "vector:throttle:"
+					}
+				}
+				retry_secs: {
+					common:      false
+					description: "How long to wait before syncing the counts again with an unreachable backend."
+					required:    false
+					type: float: {
+						default: 5.0
+						unit:    "seconds"
+					}
+				}
+				sync_interval_ms: {
+					common:      false
+					description: "How often the counts are synced with the backend, in the background."
+					required:    false
+					type: uint: {
+						default: 100
+						unit:    "milliseconds"
+					}
+				}
+				timeout_ms: {
+					common:      false
+					description: "How long to wait for the backend to sync the counts, before rate limiting the events locally."
+					required:    false
+					type: uint: {
+						default: 100
+						unit:    "milliseconds"
+					}
+				}
+				type: {
+					description: "The type of the backend."
+					required:    true
+					type: string: {
+						enum: redis: "Count the events in [Redis](\(urls.redis))."
+					}
+				}
+				url: {
+					description: "The URL of the backend."
+					required:    true
+					type: string: {
+						examples: ["redis://127.0.0.1:6379/0"]
+					}
+				}
+			}
+		}
 		exclude: {
 			common: true
 			description: """
@@ -78,6 +142,7 @@ components: transforms: throttle: {
 	}
 
 	telemetry: metrics: {
+		component_errors_total: components.sources.internal_metrics.output.metrics.component_errors_total
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
 
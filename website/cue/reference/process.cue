@@ -55,7 +55,16 @@ process: {
 
 		signals: {
 			SIGHUP: {
-				description: "Reloads configuration on the fly."
+				description: """
+					Reloads configuration on the fly. The new and changed components are built, and healthchecked,
+					while the old ones keep running, the traffic being cut over to them only once they're all
+					healthy. The old components then drain their in-flight and buffered events, for up to
+					`reload_drain_timeout_secs`, before being torn down, while the changed sinks keeping the
+					same buffer take it over from the sinks they replace. Components claiming the same resources,
+					such as ports or disk buffers, as the ones they replace require the old ones to be shut down
+					first instead. The status of the last reload is available through the `reloadStatus` query of
+					the GraphQL API.
+					"""
 			}
 
 			SIGTERM: {
@@ -63,7 +63,7 @@ remap: {
 		the provided condition(s). _All_ fields need to match for rows to be returned; if any fields
 		don't match, no rows are returned.
 
-		There are currently two forms of search criteria:
+		There are currently four forms of search criteria:
 
 		1. **Exact match search**. The given field must match the value exactly. Case sensitivity
 		   can be specified using the `case_sensitive` argument. An exact match search can use an
@@ -77,6 +77,18 @@ remap: {
 		   match criteria. We recommend using date ranges as the _only_ criteria when the enrichment
 		   data set is very small.
 
+		3. **Numeric range search**. The number in the given field must be less than or equal to
+		   the `contains` number, and the number in the field named by `end` greater than or equal
+		   to it, as in `{"port_start": {"contains": .port, "end": "port_end"}}`. The `end` field
+		   must be a string literal. The ranges are indexed, so that only the rows whose range
+		   may contain the number are scanned.
+
+		4. **CIDR search**. The network in the given field, such as `10.0.0.0/8`, must contain the
+		   `contains` IP address, as in `{"network": {"contains": .client_ip}}`. The networks are
+		   indexed by prefix length. When several networks contain the address, only the most
+		   specific ones (the longest-prefix match) are returned by `get_enrichment_table_record`,
+		   while `find_enrichment_table_records` returns them all, from the most specific.
+
 		To use this function, you need to update your Vector configuration to
 		include an
 		[`enrichment_tables`](\(urls.vector_configuration_global)/#enrichment_tables)
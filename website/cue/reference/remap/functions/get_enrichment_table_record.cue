@@ -76,5 +76,17 @@ remap: functions: get_enrichment_table_record: {
 				"""#
 			return: {"id": 1, "firstname": "Bob", "surname": "Smith"}
 		},
+		{
+			title: "CIDR search"
+			source: #"""
+				get_enrichment_table_record!("networks",
+				  {
+				    "network": {
+				      "contains": "10.1.2.3"
+				    }
+				  })
+				"""#
+			return: {"network": "10.1.0.0/16", "owner": "payments"}
+		},
 	]
 }
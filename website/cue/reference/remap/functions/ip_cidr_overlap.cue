@@ -0,0 +1,46 @@
+package metadata
+
+remap: functions: ip_cidr_overlap: {
+	category: "IP"
+	description: """
+		Determines whether the blocks referenced by `cidr_1` and `cidr_2` share any address. A plain
+		IP address is the block made of that single address. Blocks of different IP versions never
+		overlap.
+		"""
+
+	arguments: [
+		{
+			name:        "cidr_1"
+			description: "The first CIDR block (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "cidr_2"
+			description: "The second CIDR block (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`cidr_1` or `cidr_2` isn't a valid CIDR block or IP address",
+	]
+	return: types: ["boolean"]
+
+	examples: [
+		{
+			title: "Overlapping blocks"
+			source: #"""
+				ip_cidr_overlap!("10.0.0.0/8", "10.20.0.0/16")
+				"""#
+			return: true
+		},
+		{
+			title: "Disjoint blocks"
+			source: #"""
+				ip_cidr_overlap!("10.0.0.0/16", "10.1.0.0/16")
+				"""#
+			return: false
+		},
+	]
+}
@@ -0,0 +1,40 @@
+package metadata
+
+remap: functions: ip_subnet_aggregate: {
+	category: "IP"
+	description: """
+		Summarizes the CIDR blocks and IP addresses in `value` into the fewest CIDR blocks covering
+		exactly the same addresses, merging the overlapping and adjacent ones. The IPv4 blocks are
+		returned first, each version in order of address.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The CIDR blocks and IP addresses (v4 or v6) to summarize."
+			required:    true
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"an item of `value` isn't a valid CIDR block or IP address",
+	]
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Merge adjacent blocks"
+			source: #"""
+				ip_subnet_aggregate!(["10.0.0.0/24", "10.0.1.0/24", "10.0.1.7"])
+				"""#
+			return: ["10.0.0.0/23"]
+		},
+		{
+			title: "Summarize addresses"
+			source: #"""
+				ip_subnet_aggregate!(["192.168.0.3", "192.168.0.2", "2001:db8::1"])
+				"""#
+			return: ["192.168.0.2/31", "2001:db8::1/128"]
+		},
+	]
+}
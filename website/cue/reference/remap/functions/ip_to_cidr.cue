@@ -0,0 +1,46 @@
+package metadata
+
+remap: functions: ip_to_cidr: {
+	category: "IP"
+	description: """
+		Converts the `value` to the CIDR block of the given `prefix_length` containing it, clearing
+		its host bits.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "prefix_length"
+			description: "The length of the prefix of the block, up to 32 for IPv4 addresses and 128 for IPv6 addresses."
+			required:    true
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+		"`prefix_length` is out of range for the address",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "IPv4 address to CIDR"
+			source: #"""
+				ip_to_cidr!("192.168.10.32", 24)
+				"""#
+			return: "192.168.10.0/24"
+		},
+		{
+			title: "IPv6 address to CIDR"
+			source: #"""
+				ip_to_cidr!("2001:4f8:3:ba:2e0:81ff:fe22:d1f1", 64)
+				"""#
+			return: "2001:4f8:3:ba::/64"
+		},
+	]
+}
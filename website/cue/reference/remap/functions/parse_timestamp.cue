@@ -19,10 +19,41 @@ remap: functions: parse_timestamp: {
 			required:    true
 			type: ["string"]
 		},
-
+		{
+			name:        "timezone"
+			description: """
+				The time zone of the timestamps whose `format` lacks an offset, such as
+				`America/New_York` or `local`. Defaults to the time zone of Vector.
+				"""
+			required:    false
+			type: ["string"]
+		},
+		{
+			name:        "locale"
+			description: """
+				The language of the month and day names in `value`, such as `fr` or `de_DE`.
+				The supported languages are `de`, `en`, `es`, `fr`, `it` and `nl`. Each name
+				matches the `%a`, `%A`, `%b` or `%B` specifier at the same position in `format`,
+				in either its full or abbreviated form.
+				"""
+			required:    false
+			type: ["string"]
+		},
+		{
+			name:        "year_pivot"
+			description: """
+				The two-digit years (`%y`) below the pivot are in the 2000s, the others in the
+				1900s. Defaults to 70.
+				"""
+			required:    false
+			type: ["integer"]
+		},
 	]
 	internal_failure_reasons: [
 		"`value` fails to parse using the provided `format`",
+		"`timezone` isn't a known time zone",
+		"`locale` isn't a supported language",
+		"`year_pivot` isn't between 0 and 100",
 	]
 	return: types: ["timestamp"]
 
@@ -34,5 +65,19 @@ remap: functions: parse_timestamp: {
 				"""#
 			return: "2020-10-10T16:00:00Z"
 		},
+		{
+			title: "Parse French timestamp"
+			source: #"""
+				parse_timestamp!("11 février 2021 16:00", format: "%d %B %Y %R", locale: "fr", timezone: "Europe/Paris")
+				"""#
+			return: "2021-02-11T15:00:00Z"
+		},
+		{
+			title: "Parse two-digit year"
+			source: #"""
+				parse_timestamp!("11/02/65 16:00 +00:00", format: "%d/%m/%y %R %z", year_pivot: 50)
+				"""#
+			return: "1965-02-11T16:00:00Z"
+		},
 	]
 }
@@ -0,0 +1,10 @@
+package metadata
+
+services: azure_data_explorer: {
+	name:     "Azure Data Explorer"
+	thing:    "an \(name) cluster"
+	url:      urls.azure_data_explorer
+	versions: null
+
+	description: "[Azure Data Explorer](\(urls.azure_data_explorer)) is a fully managed data analytics service in Azure, storing logs and telemetry in tables queried with the Kusto Query Language."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: azure_event_hubs: {
+	name:     "Azure Event Hubs"
+	thing:    "an \(name) event hub"
+	url:      urls.azure_event_hubs
+	versions: null
+
+	description: "[Azure Event Hubs][urls.azure_event_hubs] is a fully managed, real-time data ingestion service. Events are published to partitioned event hubs, which are read by consumer groups."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: gcp_chronicle: {
+	name:     "GCP Chronicle"
+	thing:    "a \(name) instance"
+	url:      urls.gcp_chronicle
+	versions: null
+
+	description: "[Chronicle](\(urls.gcp_chronicle)) is Google Cloud's security operations platform, which normalizes, indexes and correlates security telemetry to detect and investigate threats."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: grpc: {
+	name:     "gRPC"
+	thing:    "a \(name) server"
+	url:      urls.grpc
+	versions: null
+
+	description: "[gRPC](\(urls.grpc)) is a high performance, open source universal RPC framework, in which services and the types of their messages are defined with Protocol Buffers."
+}
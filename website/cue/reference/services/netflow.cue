@@ -0,0 +1,8 @@
+package metadata
+
+services: netflow: {
+	name:     "NetFlow"
+	thing:    "a \(name) exporter"
+	url:      urls.netflow_v9
+	versions: "v5, v9, IPFIX, sFlow v5"
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: opentelemetry: {
+	name:     "OpenTelemetry"
+	thing:    "an \(name) SDK or collector"
+	url:      urls.opentelemetry
+	versions: null
+
+	description: "[OpenTelemetry](\(urls.opentelemetry)) is a collection of APIs, SDKs and tools to instrument applications and export their logs, metrics and traces with the OpenTelemetry protocol (OTLP)."
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: windows_event_log: {
+	name:     "Windows Event Log"
+	thing:    "the \(name)"
+	url:      urls.windows_event_log
+	versions: null
+
+	description: "The [Windows Event Log](\(urls.windows_event_log)) is where Windows, its services and applications record their events, organized in channels such as `Application`, `System` or `Security`."
+}
@@ -5,6 +5,7 @@ urls: {
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
+	amqp_0_9_1:                                               "https://www.rabbitmq.com/amqp-0-9-1-reference.html"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
 	apache_common:                                            "\(apache)/docs/current/logs.html#common"
@@ -13,6 +14,7 @@ urls: {
 	apache_extended_status:                                   "\(apache)/docs/current/mod/core.html#extendedstatus"
 	apache_install:                                           "\(apache)/docs/current/install.html"
 	apache_mod_status:                                        "http://httpd.apache.org/docs/current/mod/mod_status.html"
+	apache_parquet:                                           "https://parquet.apache.org/"
 	apt:                                                      "\(wikipedia)/wiki/APT_(software)"
 	arm:                                                      "\(wikipedia)/wiki/ARM_architecture"
 	aws_access_keys:                                          "\(aws_docs)/IAM/latest/UserGuide/id_credentials_access-keys.html"
@@ -39,6 +41,7 @@ urls: {
 	aws_credential_process:                                   "\(aws_docs)/cli/latest/userguide/cli-configure-sourcing-external.html"
 	aws_credentials_file:                                     "\(aws_docs)/cli/latest/userguide/cli-configure-files.html"
 	aws_docs:                                                 "https://docs.aws.amazon.com"
+	aws_external_id:                                          "\(aws_docs)/IAM/latest/UserGuide/id_roles_create_for-user_externalid.html"
 	aws_elasticsearch:                                        "https://aws.amazon.com/elasticsearch-service/"
 	aws_elasticsearch_regions:                                "\(aws_docs)/general/latest/gr/rande.html#elasticsearch-service-regions"
 	aws_ec2_instance_metadata:                                "\(aws_docs)/AWSEC2/latest/UserGuide/ec2-instance-metadata.html"
@@ -49,6 +52,7 @@ urls: {
 	aws_elb_https:                                            "\(aws_docs)/elasticloadbalancing/latest/classic/elb-create-https-ssl-load-balancer.html"
 	aws_iam:                                                  "\(aws_docs)/IAM/latest/UserGuide/introduction.html"
 	aws_iam_role:                                             "\(aws_docs)/IAM/latest/UserGuide/id_roles.html"
+	aws_irsa:                                                 "\(aws_docs)/eks/latest/userguide/iam-roles-for-service-accounts.html"
 	aws_imds_v1_security_problems:                            "https://aws.amazon.com/blogs/security/defense-in-depth-open-firewalls-reverse-proxies-ssrf-vulnerabilities-ec2-instance-metadata-service/"
 	aws_kinesis_firehose:                                     "https://aws.amazon.com/kinesis/data-firehose/"
 	aws_kinesis_firehose_http_protocol:                       "\(aws_docs)/firehose/latest/dev/create-destination.html#create-destination-http"
@@ -76,6 +80,8 @@ urls: {
 	aws_s3_sse:                                               "\(aws_docs)/AmazonS3/latest/dev/UsingServerSideEncryption.html"
 	aws_s3_storage_classes:                                   "https://aws.amazon.com/s3/storage-classes/"
 	aws_s3_tags:                                              "\(aws_docs)/AmazonS3/latest/user-guide/add-object-tags.html"
+	aws_sigv4:                                                "\(aws_docs)/general/latest/gr/signature-version-4.html"
+	aws_sso:                                                  "\(aws_docs)/cli/latest/userguide/cli-configure-sso.html"
 	aws_sqs:                                                  "https://aws.amazon.com/sqs/"
 	aws_sqs_api:                                              "\(aws_docs)/AWSSimpleQueueService/latest/APIReference/Welcome.html"
 	aws_sqs_create:                                           "\(aws_docs)/AWSSimpleQueueService/latest/SQSDeveloperGuide/sqs-configure-create-queue.html"
@@ -83,6 +89,13 @@ urls: {
 	aws_vpc_flow_logs:                                        "\(aws_docs)/vpc/latest/userguide/flow-logs.html"
 	azure_blob:                                               "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	azure_blob_endpoints:                                     "https://docs.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api"
+	azure_data_explorer:                                      "https://azure.microsoft.com/en-us/services/data-explorer/"
+	azure_data_explorer_ingestion_mappings:                   "https://docs.microsoft.com/en-us/azure/data-explorer/kusto/management/mappings"
+	azure_data_explorer_managed_identities:                   "https://docs.microsoft.com/en-us/azure/active-directory/managed-identities-azure-resources/overview"
+	azure_data_explorer_queued_ingestion:                     "https://docs.microsoft.com/en-us/azure/data-explorer/kusto/api/netfx/kusto-ingest-client-reference"
+	azure_event_hubs:                                         "https://azure.microsoft.com/en-us/services/event-hubs/"
+	azure_event_hubs_connection_string:                       "https://docs.microsoft.com/en-us/azure/event-hubs/event-hubs-get-connection-string"
+	azure_event_hubs_kafka:                                   "https://docs.microsoft.com/en-us/azure/event-hubs/event-hubs-for-kafka-ecosystem-overview"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
 	base64:                                                   "\(wikipedia)/wiki/Base64"
@@ -99,9 +112,11 @@ urls: {
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
 	clickhouse:                                               "https://clickhouse.yandex/"
 	clickhouse_http:                                          "https://clickhouse.yandex/docs/en/interfaces/http/"
+	clickhouse_native:                                        "https://clickhouse.com/docs/en/interfaces/tcp/"
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	confluent_schema_registry:                                "https://docs.confluent.io/platform/current/schema-registry/index.html"
 	console:                                                  "\(wikipedia)/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
@@ -124,6 +139,7 @@ urls: {
 	datadog_metrics:                                          "\(datadog_docs)/metrics/"
 	datadog_events:                                           "\(datadog_docs)/events/"
 	datadog_metrics_endpoints:                                "\(datadog_docs)/api/v1/metrics/"
+	datadog_reserved_attributes:                              "\(datadog_docs)/logs/log_configuration/attributes_naming_convention/#reserved-attributes"
 	datadog_search_syntax:                                    "\(datadog_docs)/logs/explorer/search_syntax/"
 	date:                                                     "https://man7.org/linux/man-pages/man1/date.1.html"
 	debian:                                                   "https://www.debian.org/"
@@ -166,6 +182,7 @@ urls: {
 	elasticsearch_id_field:                                   "https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-id-field.html"
 	elasticsearch_id_performance:                             "https://www.elastic.co/guide/en/elasticsearch/reference/master/tune-for-indexing-speed.html#_use_auto_generated_ids"
 	elasticsearch_ignore_malformed:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ignore-malformed.html"
+	elasticsearch_ingest_pipelines:                           "https://www.elastic.co/guide/en/elasticsearch/reference/current/ingest.html"
 	encoding_charset_labels:                                  "https://encoding.spec.whatwg.org/#concept-encoding-get"
 	encoding_standard:                                        "https://encoding.spec.whatwg.org/"
 	endler_dev:                                               "https://endler.dev/"
@@ -190,9 +207,16 @@ urls: {
 	gcp_authentication_api_key:                               "\(gcp)/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:                      "\(gcp)/docs/authentication/production"
 	gcp_authentication_service_account:                       "\(gcp)/docs/authentication/production#obtaining_and_providing_service_account_credentials_manually"
+	gcp_chronicle:                                            "\(gcp)/chronicle/docs"
+	gcp_chronicle_ingestion_api:                              "\(gcp)/chronicle/docs/reference/ingestion-api"
+	gcp_chronicle_log_types:                                  "\(gcp)/chronicle/docs/ingestion/parser-list/supported-default-parsers"
+	gcp_chronicle_udm:                                        "\(gcp)/chronicle/docs/reference/udm-field-list"
 	gcp_cloud_storage:                                        "\(gcp)/storage"
 	gcp_folders:                                              "\(gcp)/resource-manager/docs/creating-managing-folders"
 	gcp_pubsub:                                               "\(gcp)/pubsub/"
+	gcp_pubsub_dead_letter:                                   "\(gcp)/pubsub/docs/handling-failures"
+	gcp_pubsub_grpc:                                          "\(gcp)/pubsub/docs/reference/rpc/google.pubsub.v1"
+	gcp_pubsub_ordering:                                      "\(gcp)/pubsub/docs/ordering"
 	gcp_pubsub_rest:                                          "\(gcp)/pubsub/docs/reference/rest/"
 	gcp_projects:                                             "\(gcp)/resource-manager/docs/creating-managing-projects"
 	gcp_resources:                                            "\(gcp)/monitoring/api/resources"
@@ -212,6 +236,7 @@ urls: {
 	github:                                                   "https://github.com"
 	github_protected_branches:                                "https://help.github.com/en/github/administering-a-repository/about-protected-branches"
 	github_sign_commits:                                      "https://help.github.com/en/github/authenticating-to-github/signing-commits"
+	github_webhook_signatures:                                "https://docs.github.com/en/developers/webhooks-and-events/webhooks/securing-your-webhooks"
 	globbing:                                                 "\(wikipedia)/wiki/Glob_(programming)"
 	glog:                                                     "\(github)/google/glog"
 	graphql:                                                  "https://graphql.org"
@@ -220,6 +245,8 @@ urls: {
 	grok:                                                     "https://grokdebug.herokuapp.com/"
 	grok_debugger:                                            "https://grokdebug.herokuapp.com/"
 	grok_patterns:                                            "\(github)/daschl/grok/tree/master/patterns"
+	grpc:                                                     "https://grpc.io/"
+	grpc_health_checking:                                     "https://github.com/grpc/grpc/blob/master/doc/health-checking.md"
 	gzip:                                                     "https://www.gzip.org/"
 	haproxy:                                                  "https://www.haproxy.org/"
 	helm:                                                     "https://helm.sh/"
@@ -252,6 +279,8 @@ urls: {
 	inode:                                                    "\(wikipedia)/wiki/Inode"
 	ip_aton:                                                  "https://linux.die.net/man/3/inet_aton"
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
+	ipfix:                                                    "https://datatracker.ietf.org/doc/html/rfc7011"
+	ipfix_information_elements:                               "https://www.iana.org/assignments/ipfix/ipfix.xhtml"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
@@ -291,6 +320,8 @@ urls: {
 	logstash_protocol:                                        "https://github.com/elastic/logstash-forwarder/blob/master/PROTOCOL.md"
 	loki:                                                     "https://grafana.com/oss/loki/"
 	loki_multi_tenancy:                                       "\(github)/grafana/loki/blob/master/docs/operations/multi-tenancy.md"
+	loki_out_of_order_writes:                                 "https://grafana.com/docs/loki/latest/configure/#accept-out-of-order-writes"
+	loki_structured_metadata:                                 "https://grafana.com/docs/loki/latest/get-started/labels/structured-metadata/"
 	log_event_source:                                         "\(vector_repo)/blob/master/src/event/"
 	logplex:                                                  "https://devcenter.heroku.com/articles/logplex"
 	logplex_protocol:                                         "\(github)/heroku/logplex/blob/master/doc/README.http_drains.md"
@@ -320,7 +351,9 @@ urls: {
 	maxmind:                                                  "https://www.maxmind.com/en/home"
 	maxmind_db_file_format:                                   "https://maxmind.github.io/MaxMind-DB/"
 	maxmind_geoip2:                                           "https://dev.maxmind.com/geoip/geoip2/downloadable"
+	maxmind_geoip2_anonymous_ip:                              "https://www.maxmind.com/en/geoip2-anonymous-ip-database"
 	maxmind_geoip2_city:                                      "https://www.maxmind.com/en/geoip2-city"
+	maxmind_geoip2_connection_type:                           "https://www.maxmind.com/en/geoip2-connection-type-database"
 	maxmind_geoip2_isp:                                       "https://www.maxmind.com/en/geoip2-isp-database"
 	maxmind_geolite2_asn:                                     "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
 	maxmind_geolite2_city:                                    "https://dev.maxmind.com/geoip/geoip2/geolite2/#Download_Access"
@@ -332,7 +365,10 @@ urls: {
 	mongodb_command_server_status:                            "https://docs.mongodb.com/manual/reference/command/serverStatus/"
 	mongodb_connection_string_uri_format:                     "https://docs.mongodb.com/manual/reference/connection-string/"
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
+	msgpack:                                                  "https://msgpack.org"
+	mysql:                                                    "https://www.mysql.com/"
 	nats:                                                     "https://nats.io/"
+	nats_jetstream:                                           "https://docs.nats.io/nats-concepts/jetstream"
 	nats_rs:                                                  "\(github)/nats-io/nats.rs"
 	new_bug_report:                                           "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "\(vector_repo)/issues/new?labels=type%3A+new+feature"
@@ -344,6 +380,7 @@ urls: {
 	new_source:                                               "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_target:                                               "\(vector_repo)/issues/new?labels=type%3A+task&labels=domain%3A+operations"
 	new_transform:                                            "\(vector_repo)/issues/new?labels=type%3A+new+feature"
+	netflow_v9:                                               "https://datatracker.ietf.org/doc/html/rfc3954"
 	nginx:                                                    "https://www.nginx.com/"
 	nginx_combined:                                           "https://nginx.org/en/docs/http/ngx_http_log_module.html"
 	nginx_error:                                              "https://github.com/nginx/nginx/blob/branches/stable-1.18/src/core/ngx_log.c#L102"
@@ -352,7 +389,10 @@ urls: {
 	nixos:                                                    "https://nixos.org/"
 	nixpkgs_9682:                                             "\(github)/NixOS/nixpkgs/issues/9682"
 	openssl:                                                  "https://www.openssl.org/"
+	opentelemetry:                                            "https://opentelemetry.io/"
+	opentelemetry_otlp:                                       "https://opentelemetry.io/docs/reference/specification/protocol/otlp/"
 	order_of_ops:                                             "\(wikipedia)/wiki/Order_of_operations"
+	pagerduty:                                                "https://developer.pagerduty.com"
 	papertrail:                                               "https://www.papertrail.com/"
 	papertrail_syslog:                                        "https://help.papertrailapp.com/kb/how-it-works/http-api/#submitting-log-messages"
 	perl_windows:                                             "https://www.perl.org/get.html#win32"
@@ -367,6 +407,8 @@ urls: {
 	percent_encoding_www_form_urlencoded:                     "https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set"
 	posix_acls:                                               "https://www.usenix.org/legacy/publications/library/proceedings/usenix03/tech/freenix03/full_papers/gruenbacher/gruenbacher_html/main.html"
 	postgresql:                                               "https://www.postgresql.org/"
+	postgresql_connection_string:                             "https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNSTRING"
+	postgresql_copy:                                          "https://www.postgresql.org/docs/current/sql-copy.html"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
 	postgresql_matching:                                      "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
 	procfs:                                                   "https://en.wikipedia.org/wiki/Procfs#:~:text=The%20proc%20filesystem%20(procfs)%20is,in%20the%20kernel%20than%20traditional"
@@ -374,6 +416,7 @@ urls: {
 	prometheus_client:                                        "https://prometheus.io/docs/instrumenting/clientlibs/"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_counter:                                       "https://prometheus.io/docs/concepts/metric_types/#counter"
+	prometheus_exemplars:                                     "https://prometheus.io/docs/prometheus/latest/feature_flags/#exemplars-storage"
 	prometheus_gauge:                                         "https://prometheus.io/docs/concepts/metric_types/#gauge"
 	prometheus_high_cardinality:                              "https://prometheus.io/docs/practices/naming/#labels"
 	prometheus_histogram:                                     "https://prometheus.io/docs/concepts/metric_types/#histogram"
@@ -381,12 +424,16 @@ urls: {
 	prometheus_summary:                                       "https://prometheus.io/docs/concepts/metric_types/#summary"
 	prometheus_text_based_exposition_format:                  "\(github)/prometheus/docs/blob/master/content/docs/instrumenting/exposition_formats.md#text-based-format"
 	prometheus_metric_naming:                                 "https://prometheus.io/docs/practices/naming/#metric-names"
+	prometheus_native_histograms:                             "https://prometheus.io/docs/prometheus/latest/feature_flags/#native-histograms"
 	prometheus_remote_integrations:                           "https://prometheus.io/docs/operating/integrations/#remote-endpoints-and-storage"
 	prometheus_remote_write:                                  "https://prometheus.io/docs/prometheus/latest/configuration/configuration/#remote_write"
 	prometheus_remote_write_protocol:                         "https://docs.google.com/document/d/1LPhVRSFkGNSuU1fBd81ulhsCPR4hkSZyyBj1SZ8fWOM/edit#heading=h.n0d0vphea3fe"
 	protobuf:                                                 "https://developers.google.com/protocol-buffers"
+	protobuf_self_description:                                "https://developers.google.com/protocol-buffers/docs/techniques#self-description"
 	pulsar:                                                   "https://pulsar.apache.org/"
 	pulsar_protocol:                                          "https://pulsar.apache.org/docs/en/develop-binary-protocol/"
+	rabbitmq:                                                 "https://www.rabbitmq.com/"
+	rabbitmq_dead_letter:                                     "https://www.rabbitmq.com/dlx.html"
 	raspbian:                                                 "https://www.raspbian.org/"
 	rdkafka:                                                  "\(github)/edenhill/librdkafka"
 	regex:                                                    "\(wikipedia)/wiki/Regular_expression"
@@ -401,8 +448,10 @@ urls: {
 	rfc_2136:                                                 "https://tools.ietf.org/html/rfc2136"
 	rfc_2460:                                                 "https://tools.ietf.org/html/rfc2460"
 	rfc_2822:                                                 "https://tools.ietf.org/html/rfc2822#section-3.3"
+	rfc_3207:                                                 "https://tools.ietf.org/html/rfc3207"
 	rfc_3339:                                                 "https://tools.ietf.org/html/rfc3339"
 	rfc_4180:                                                 "https://tools.ietf.org/html/rfc4180"
+	rfc_5321:                                                 "https://tools.ietf.org/html/rfc5321"
 	rfc_6587_3_4_1:                                           "https://tools.ietf.org/html/rfc6587#section-3.4.1"
 	rfc_6891:                                                 "https://tools.ietf.org/html/rfc6891"
 	rhel:                                                     "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
@@ -420,7 +469,9 @@ urls: {
 	rust_tokio:                                               "\(github)/tokio-rs/tokio"
 	rustup:                                                   "https://rustup.rs"
 	redis:                                                    "https://redis.io"
+	redis_cluster_hash_tags:                                  "https://redis.io/docs/reference/cluster-spec/#hash-tags"
 	redis_rs:                                                 "https://github.com/mitsuhiko/redis-rs"
+	redis_streams:                                            "https://redis.io/docs/data-types/streams/"
 	sematext:                                                 "https://sematext.com"
 	sematext_create_logs_app:                                 "https://apps.sematext.com/ui/integrations"
 	sematext_es:                                              "https://sematext.com/docs/logs/index-events-via-elasticsearch-api/"
@@ -428,13 +479,20 @@ urls: {
 	sematext_monitoring:                                      "https://sematext.com/docs/monitoring/"
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
+	sflow:                                                    "https://sflow.org/sflow_version_5.txt"
 	sha1:                                                     "\(wikipedia)/wiki/SHA-1"
 	sha2:                                                     "\(wikipedia)/wiki/SHA-2"
 	sha3:                                                     "\(wikipedia)/wiki/SHA-3"
 	signal:                                                   "\(wikipedia)/wiki/Signal_(IPC)"
+	slack_request_signing:                                    "https://api.slack.com/authentication/verifying-requests-from-slack"
+	slack_url_verification:                                   "https://api.slack.com/events/url_verification"
 	snake_case:                                               "\(wikipedia)/wiki/Snake_case"
 	snappy:                                                   "https://google.github.io/snappy/"
+	snmp:                                                     "https://datatracker.ietf.org/doc/html/rfc3416"
+	snmp_mib:                                                 "https://datatracker.ietf.org/doc/html/rfc2578"
+	snmp_usm:                                                 "https://datatracker.ietf.org/doc/html/rfc3414"
 	socket:                                                   "\(wikipedia)/wiki/Network_socket"
+	spiffe_workload_api:                                      "https://github.com/spiffe/spiffe/blob/main/standards/SPIFFE_Workload_API.md"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
 	splunk_hec_channel_header:                                "https://docs.splunk.com/Documentation/Splunk/8.2.4/Data/FormateventsforHTTPEventCollector#Channel_identifier_header"
@@ -445,6 +503,7 @@ urls: {
 	splunk_hec_raw_endpoint:                                  "https://docs.splunk.com/Documentation/Splunk/8.0.0/RESTREF/RESTinput#services.2Fcollector.2Fraw"
 	splunk_hec_setup:                                         "https://docs.splunk.com/Documentation/Splunk/latest/Data/UsetheHTTPEventCollector"
 	specs_instrumentation:                                    "\(vector_repo)/blob/master/docs/specs/instrumentation.md)"
+	sse:                                                      "https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events"
 	standard_streams:                                         "\(wikipedia)/wiki/Standard_streams"
 	statsd:                                                   "\(github)/statsd/statsd"
 	statsd_multi:                                             "\(github)/statsd/statsd/blob/master/docs/metric_types.md#multi-metric-packets"
@@ -455,6 +514,7 @@ urls: {
 	exec:                                                     "\(wikipedia)/wiki/Exec_(system_call)"
 	stdout:                                                   "\(wikipedia)/wiki/Standard_streams#Standard_output_(stdout)"
 	stripe_blog_canonical_log_lines:                          "https://stripe.com/blog/canonical-log-lines"
+	stripe_webhook_signatures:                                "https://stripe.com/docs/webhooks/signatures"
 	strptime_specifiers:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	sysfs:                                                    "https://www.kernel.org/doc/html/latest/filesystems/sysfs.html"
 	syslog:                                                   "\(wikipedia)/wiki/Syslog"
@@ -615,6 +675,8 @@ urls: {
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
 	wikipedia:                                                "https://en.wikipedia.org"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
+	windows_event_log:                                        "https://docs.microsoft.com/en-us/windows/win32/wes/windows-event-log"
+	windows_event_log_query:                                  "https://docs.microsoft.com/en-us/windows/win32/wes/consuming-events"
 	windows_installer:                                        "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                                  "https://github.com/woothee/woothee"